@@ -0,0 +1,155 @@
+//! MISO LIMS administration CLI.
+//!
+//! Usage:
+//!   miso-admin verify                 - Audit cross-table invariants and print a JSON report
+//!   miso-admin replay-events [--check] - Replay the event store, checking its hash chain,
+//!                                        and rebuild its read model
+//!   miso-admin rebuild-sample-list     - Rebuild the sample list read model and its counts
+//!   miso-admin restore-archive <id>    - Put an archive's log rows back into their table
+//!
+//! `verify` exits with status 1 if any violation is found, so it can gate
+//! deployments and scheduled health checks. Checks whose tables have no
+//! persistence support yet are reported as skipped.
+//!
+//! `replay-events` exits with status 1 if the hash chain is broken, in
+//! which case the read model is left alone. With `--check` it only checks
+//! the chain.
+
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use miso_application::{
+    EventStoreService, IntegrityAuditService, RetentionService, SampleListService,
+};
+use miso_infrastructure::persistence::{
+    database::Database,
+    repositories::{
+        SeaOrmEventStoreRepository, SeaOrmLogArchiveRepository, SeaOrmProjectRepository,
+        SeaOrmSampleListRepository, SeaOrmSampleRepository,
+    },
+};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Load .env file if present
+    let _ = dotenvy::dotenv();
+
+    // Logs go to stderr so stdout carries only the report
+    tracing_subscriber::fmt()
+        .with_env_filter("warn")
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("verify") => verify().await,
+        Some("replay-events") => replay_events(args[1..].iter().any(|a| a == "--check")).await,
+        Some("rebuild-sample-list") => rebuild_sample_list().await,
+        Some("restore-archive") => match args.get(1).map(|id| id.parse::<i32>()) {
+            Some(Ok(id)) => restore_archive(id).await,
+            _ => Err(anyhow::anyhow!("Usage: miso-admin restore-archive <id>")),
+        },
+        Some(other) => Err(anyhow::anyhow!("Unknown command: {}", other)),
+        None => Err(anyhow::anyhow!(
+            "Usage: miso-admin verify | miso-admin replay-events [--check] | \
+             miso-admin rebuild-sample-list | miso-admin restore-archive <id>"
+        )),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the integrity audit and prints the report to stdout.
+async fn verify() -> Result<ExitCode> {
+    let db = connect().await?;
+
+    let service = IntegrityAuditService::new().with_samples(
+        Arc::new(SeaOrmProjectRepository::new(db.connection().clone())),
+        Arc::new(SeaOrmSampleRepository::new(db.connection().clone())),
+    );
+    let report = service.run().await.context("Integrity audit failed")?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Replays the event store, rebuilding its read model unless only
+/// checking, and prints the report to stdout.
+async fn replay_events(check_only: bool) -> Result<ExitCode> {
+    let db = connect().await?;
+
+    let service = EventStoreService::new(Arc::new(SeaOrmEventStoreRepository::new(
+        db.connection().clone(),
+    )));
+    let report = service
+        .replay(!check_only)
+        .await
+        .context("Event store replay failed")?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(if report.is_intact() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Rebuilds the sample list read model and prints the report to stdout.
+async fn rebuild_sample_list() -> Result<ExitCode> {
+    let db = connect().await?;
+
+    let service = SampleListService::new(Arc::new(SeaOrmSampleListRepository::new(
+        db.connection().clone(),
+    )));
+    let report = service
+        .rebuild()
+        .await
+        .context("Sample list rebuild failed")?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Restores an archive of log rows and prints it to stdout.
+async fn restore_archive(id: i32) -> Result<ExitCode> {
+    let db = connect().await?;
+
+    // Archives record their own file, so the directory is only a default
+    let directory =
+        std::env::var("RETENTION__DIRECTORY").unwrap_or_else(|_| "archive".to_string());
+    let service = RetentionService::new(
+        Arc::new(SeaOrmLogArchiveRepository::new(db.connection().clone())),
+        directory,
+    );
+    let archive = service
+        .restore(id)
+        .await
+        .context("Archive restore failed")?;
+
+    println!("{}", serde_json::to_string_pretty(&archive)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn connect() -> Result<Database> {
+    if std::env::var("DATABASE_URL").is_err() {
+        bail!("DATABASE_URL must be set");
+    }
+    Database::from_env()
+        .await
+        .context("Failed to connect to database")
+}
@@ -1,6 +1,23 @@
 //! Server configuration.
 
-use serde::Deserialize;
+use std::collections::HashMap;
+
+use miso_application::jobs::{DigestTemplate, Schedule};
+use miso_application::RetentionPolicy;
+use miso_domain::entities::{ArchivedLog, ControlType, LibraryDesign, QcTarget, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::services::{
+    ControlPolicy, KitCompatibility, PlexityLimits, QcEvaluator, QcThreshold, YieldTargets,
+};
+use miso_domain::value_objects::QcTestType;
+use miso_infrastructure::external::basespace::BaseSpaceConfig;
+use miso_infrastructure::external::ldap::LdapConfig;
+use miso_infrastructure::external::oidc::OidcConfig;
+use miso_infrastructure::external::run_planning::RunPlanningConfig;
+use miso_infrastructure::external::shipment_tracking::ShipmentTrackingConfig;
+use miso_infrastructure::notifications::email::EmailConfig;
+use miso_infrastructure::storage::s3::S3Config;
+use serde::{Deserialize, Deserializer};
 
 /// Server configuration.
 #[derive(Debug, Clone, Deserialize)]
@@ -30,6 +47,1026 @@ pub struct Config {
     /// Log level (default: info)
     #[serde(default = "default_log_level")]
     pub log_level: String,
+
+    /// Also record every change in the hash-chained event store
+    /// (default: false)
+    #[serde(default)]
+    pub event_store: bool,
+
+    /// Only let members of a project, and admins, see and change its
+    /// samples, libraries and pools (default: false)
+    #[serde(default)]
+    pub project_membership: bool,
+
+    /// Queries at or above this many milliseconds are logged as slow;
+    /// 0 disables slow-query logging (default: 500)
+    #[serde(default = "default_slow_query_ms")]
+    pub slow_query_ms: u64,
+
+    /// SMTP settings; notifications are only logged if unset
+    #[serde(default)]
+    pub email: Option<EmailSettings>,
+
+    /// Scheduled digest settings; no digest is sent if unset
+    #[serde(default)]
+    pub digest: Option<DigestSettings>,
+
+    /// Pool plexity limits; pools are unlimited if unset
+    #[serde(default)]
+    pub pool_limits: Option<PoolLimitSettings>,
+
+    /// Smallest Hamming distance allowed between any two indices in a pool
+    /// (default: 3)
+    #[serde(default = "default_min_index_distance")]
+    pub min_index_distance: u32,
+
+    /// Read targets pool proportions are proposed from; none are proposed
+    /// if unset
+    #[serde(default)]
+    pub pool_targets: Option<PoolTargetSettings>,
+
+    /// Library kit compatibility; kits aren't checked if unset
+    #[serde(default)]
+    pub library_kits: Option<LibraryKitSettings>,
+
+    /// QC thresholds measurements are judged against; measurements'
+    /// statuses are taken as recorded if unset
+    #[serde(default)]
+    pub qc_thresholds: Option<QcThresholdSettings>,
+
+    /// Controls prep batches and runs must include; nothing is required if
+    /// unset
+    #[serde(default)]
+    pub controls: Option<ControlSettings>,
+
+    /// LDAP directory settings; only internal users can log in if unset
+    #[serde(default)]
+    pub ldap: Option<LdapSettings>,
+
+    /// OpenID Connect single sign-on settings; disabled if unset
+    #[serde(default)]
+    pub oidc: Option<OidcSettings>,
+
+    /// Where to send sample sheets when runs are ready to load; runs are
+    /// only marked if unset
+    #[serde(default)]
+    pub run_planning: Option<RunPlanningSettings>,
+
+    /// BaseSpace Sequence Hub settings; runs aren't imported if unset
+    #[serde(default)]
+    pub basespace: Option<BaseSpaceSettings>,
+
+    /// Log retention settings; logs are kept for good if unset
+    #[serde(default)]
+    pub retention: Option<RetentionSettings>,
+
+    /// Label printing settings; any label can be reprinted if unset
+    #[serde(default)]
+    pub labels: Option<LabelSettings>,
+
+    /// Reagent alert settings; lab managers aren't alerted if unset
+    #[serde(default)]
+    pub reagents: Option<ReagentSettings>,
+
+    /// Where uploaded attachments are kept; a local "attachments"
+    /// directory if unset
+    #[serde(default)]
+    pub attachments: Option<AttachmentSettings>,
+
+    /// Courier tracking service settings; shipments are only updated by
+    /// webhook if unset
+    #[serde(default)]
+    pub shipment_tracking: Option<ShipmentTrackingSettings>,
+
+    /// API usage sampling and quota settings; one call in 10 is recorded
+    /// and there is no quota if unset
+    #[serde(default)]
+    pub api_usage: Option<ApiUsageSettings>,
+
+    /// When archiving rules are evaluated; daily at 03:00 UTC if unset
+    #[serde(default)]
+    pub auto_archive: Option<AutoArchiveSettings>,
+
+    /// When box contents are reconciled against item locations; daily at
+    /// 01:00 UTC if unset
+    #[serde(default)]
+    pub reconciliation: Option<ReconciliationSettings>,
+
+    /// How often running runs are checked for overruns; hourly if unset
+    #[serde(default)]
+    pub stale_runs: Option<StaleRunSettings>,
+}
+
+/// SMTP relay settings (`EMAIL__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailSettings {
+    /// Relay host name
+    pub smtp_host: String,
+
+    /// Relay port (default: 587)
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+
+    /// Upgrade the connection with STARTTLS (default: true)
+    #[serde(default = "default_starttls")]
+    pub starttls: bool,
+
+    /// Relay login
+    pub username: Option<String>,
+
+    /// Relay password
+    pub password: Option<String>,
+
+    /// Sender address
+    pub from: String,
+
+    /// Comma-separated addresses to notify, keyed by role, e.g.
+    /// `EMAIL__RECIPIENTS__LAB_MANAGER=lab-managers@example.org`
+    #[serde(default)]
+    pub recipients: HashMap<String, String>,
+}
+
+impl EmailSettings {
+    /// Converts the settings into notifier configuration.
+    pub fn to_email_config(&self) -> Result<EmailConfig, DomainError> {
+        let recipients = self
+            .recipients
+            .iter()
+            .map(|(role, addresses)| {
+                Ok((
+                    role.parse::<Role>()?,
+                    addresses
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|a| !a.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, DomainError>>()?;
+
+        Ok(EmailConfig {
+            host: self.smtp_host.clone(),
+            port: self.smtp_port,
+            starttls: self.starttls,
+            credentials: self.username.clone().zip(self.password.clone()),
+            from: self.from.clone(),
+            recipients,
+        })
+    }
+}
+
+/// LDAP directory settings (`LDAP__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct LdapSettings {
+    /// Server URL, e.g. "ldaps://ldap.example.org"
+    pub url: String,
+
+    /// Upgrade an `ldap://` connection with StartTLS (default: false)
+    #[serde(default)]
+    pub starttls: bool,
+
+    /// Service account to search with; the search is anonymous if unset
+    pub bind_dn: Option<String>,
+
+    /// Service account password
+    pub bind_password: Option<String>,
+
+    /// Where to search for users, e.g. "ou=people,dc=example,dc=org"
+    pub base_dn: String,
+
+    /// User search filter (default: "(uid={username})")
+    #[serde(default = "default_ldap_user_filter")]
+    pub user_filter: String,
+
+    /// Comma-separated common names of the groups whose members get each
+    /// role, keyed by role, e.g. `LDAP__GROUP_ROLES__TECHNICIAN=lab-staff`
+    #[serde(default)]
+    pub group_roles: HashMap<String, String>,
+}
+
+impl LdapSettings {
+    /// Converts the settings into directory client configuration.
+    pub fn to_ldap_config(&self) -> Result<LdapConfig, DomainError> {
+        let group_roles = parse_group_roles(&self.group_roles, "LDAP")?;
+
+        Ok(LdapConfig {
+            url: self.url.clone(),
+            starttls: self.starttls,
+            bind_credentials: self.bind_dn.clone().zip(self.bind_password.clone()),
+            base_dn: self.base_dn.clone(),
+            user_filter: self.user_filter.clone(),
+            group_roles,
+        })
+    }
+}
+
+/// OpenID Connect single sign-on settings (`OIDC__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcSettings {
+    /// Issuer URL, e.g. "https://login.example.org/realms/lab"
+    pub issuer_url: String,
+
+    /// Client ID registered with the identity provider
+    pub client_id: String,
+
+    /// Client secret registered with the identity provider
+    pub client_secret: String,
+
+    /// This server's callback URL, e.g.
+    /// "https://miso.example.org/api/v1/auth/oidc/callback"
+    pub redirect_url: String,
+
+    /// Space-separated scopes to request (default: "openid profile email")
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: String,
+
+    /// Claim holding the username (default: "preferred_username")
+    #[serde(default = "default_oidc_username_claim")]
+    pub username_claim: String,
+
+    /// Claim listing the user's groups (default: "groups")
+    #[serde(default = "default_oidc_groups_claim")]
+    pub groups_claim: String,
+
+    /// Comma-separated groups whose members get each role, keyed by role,
+    /// e.g. `OIDC__GROUP_ROLES__ADMIN=lims-admins`
+    #[serde(default)]
+    pub group_roles: HashMap<String, String>,
+}
+
+impl OidcSettings {
+    /// Converts the settings into identity provider client configuration.
+    pub fn to_oidc_config(&self) -> Result<OidcConfig, DomainError> {
+        let group_roles = parse_group_roles(&self.group_roles, "OIDC")?;
+
+        Ok(OidcConfig {
+            issuer_url: self.issuer_url.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            redirect_url: self.redirect_url.clone(),
+            scopes: self.scopes.split_whitespace().map(str::to_string).collect(),
+            username_claim: self.username_claim.clone(),
+            groups_claim: self.groups_claim.clone(),
+            group_roles,
+        })
+    }
+}
+
+/// Sample sheet upload settings (`RUN_PLANNING__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunPlanningSettings {
+    /// Upload URL, in which `{ip_address}`, `{serial_number}` and `{name}`
+    /// are replaced with the sequencer's, e.g.
+    /// "http://{ip_address}/api/v1/samplesheets"
+    pub url: String,
+
+    /// Bearer token to upload with, e.g. a BaseSpace access token
+    pub token: Option<String>,
+}
+
+impl RunPlanningSettings {
+    /// Converts the settings into run planner configuration.
+    pub fn to_run_planning_config(&self) -> RunPlanningConfig {
+        RunPlanningConfig {
+            url: self.url.clone(),
+            token: self.token.clone(),
+        }
+    }
+}
+
+/// BaseSpace Sequence Hub run import settings (`BASESPACE__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct BaseSpaceSettings {
+    /// API server (default: "https://api.basespace.illumina.com")
+    #[serde(default = "default_basespace_api_url")]
+    pub api_url: String,
+
+    /// Access token
+    pub access_token: String,
+
+    /// App client ID, to refresh the access token with
+    pub client_id: Option<String>,
+
+    /// App client secret, to refresh the access token with
+    pub client_secret: Option<String>,
+
+    /// Refresh token; an expired access token isn't refreshed without one
+    pub refresh_token: Option<String>,
+
+    /// Minutes between imports (default: 60)
+    #[serde(default = "default_basespace_poll_minutes")]
+    pub poll_minutes: u64,
+}
+
+impl BaseSpaceSettings {
+    /// Converts the settings into BaseSpace client configuration.
+    pub fn to_basespace_config(&self) -> BaseSpaceConfig {
+        BaseSpaceConfig {
+            api_url: self.api_url.clone(),
+            access_token: self.access_token.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            refresh_token: self.refresh_token.clone(),
+        }
+    }
+
+    /// Returns how often runs are imported.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        if self.poll_minutes == 0 {
+            return Err(DomainError::Validation(
+                "BaseSpace poll interval must be at least a minute".to_string(),
+            ));
+        }
+        Ok(Schedule::Every(std::time::Duration::from_secs(
+            self.poll_minutes * 60,
+        )))
+    }
+}
+
+/// Courier tracking service settings (`SHIPMENT_TRACKING__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShipmentTrackingSettings {
+    /// Lookup URL, in which `{courier}` and `{tracking_number}` are
+    /// replaced with the shipment's, e.g.
+    /// "https://tracking.example.org/v1/{courier}/{tracking_number}"
+    pub url: String,
+
+    /// Bearer token to send, if the service needs one
+    pub token: Option<String>,
+
+    /// Minutes between checks (default: 30)
+    #[serde(default = "default_shipment_poll_minutes")]
+    pub poll_minutes: u64,
+}
+
+impl ShipmentTrackingSettings {
+    /// Converts the settings into tracking client configuration.
+    pub fn to_shipment_tracking_config(&self) -> ShipmentTrackingConfig {
+        ShipmentTrackingConfig {
+            url: self.url.clone(),
+            token: self.token.clone(),
+        }
+    }
+
+    /// Returns how often shipments are checked.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        if self.poll_minutes == 0 {
+            return Err(DomainError::Validation(
+                "Shipment tracking poll interval must be at least a minute".to_string(),
+            ));
+        }
+        Ok(Schedule::Every(std::time::Duration::from_secs(
+            self.poll_minutes * 60,
+        )))
+    }
+}
+
+/// API usage settings (`API_USAGE__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiUsageSettings {
+    /// Record one call in this many, counting it for all of them
+    /// (default: 10)
+    #[serde(default = "default_api_usage_sample_every")]
+    pub sample_every: u64,
+
+    /// Seconds between writes of the recorded counts (default: 60)
+    #[serde(default = "default_api_usage_flush_seconds")]
+    pub flush_seconds: u64,
+
+    /// Calls each API key, or each user calling without one, may make per
+    /// hour; unlimited if unset
+    pub requests_per_hour: Option<u32>,
+}
+
+impl Default for ApiUsageSettings {
+    fn default() -> Self {
+        Self {
+            sample_every: default_api_usage_sample_every(),
+            flush_seconds: default_api_usage_flush_seconds(),
+            requests_per_hour: None,
+        }
+    }
+}
+
+impl ApiUsageSettings {
+    /// Returns how often the recorded counts are written.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        if self.flush_seconds == 0 {
+            return Err(DomainError::Validation(
+                "API usage flush interval must be at least a second".to_string(),
+            ));
+        }
+        Ok(Schedule::Every(std::time::Duration::from_secs(
+            self.flush_seconds,
+        )))
+    }
+}
+
+/// Automatic archiving settings (`AUTO_ARCHIVE__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutoArchiveSettings {
+    /// UTC time of day to evaluate the archiving rules, as "HH:MM"
+    /// (default: "03:00")
+    #[serde(default = "default_auto_archive_at")]
+    pub at: String,
+}
+
+impl Default for AutoArchiveSettings {
+    fn default() -> Self {
+        Self {
+            at: default_auto_archive_at(),
+        }
+    }
+}
+
+impl AutoArchiveSettings {
+    /// Returns when the archiving rules are evaluated.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        daily_at(&self.at, "auto-archive")
+    }
+}
+
+/// Storage reconciliation settings (`RECONCILIATION__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconciliationSettings {
+    /// UTC time of day to reconcile box contents, as "HH:MM"
+    /// (default: "01:00")
+    #[serde(default = "default_reconciliation_at")]
+    pub at: String,
+}
+
+impl Default for ReconciliationSettings {
+    fn default() -> Self {
+        Self {
+            at: default_reconciliation_at(),
+        }
+    }
+}
+
+impl ReconciliationSettings {
+    /// Returns when box contents are reconciled.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        daily_at(&self.at, "reconciliation")
+    }
+}
+
+/// Stale run check settings (`STALE_RUNS__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaleRunSettings {
+    /// Minutes between checks (default: 60)
+    #[serde(default = "default_stale_run_check_minutes")]
+    pub check_minutes: u64,
+}
+
+impl Default for StaleRunSettings {
+    fn default() -> Self {
+        Self {
+            check_minutes: default_stale_run_check_minutes(),
+        }
+    }
+}
+
+impl StaleRunSettings {
+    /// Returns how often running runs are checked.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        if self.check_minutes == 0 {
+            return Err(DomainError::Validation(
+                "Stale run check interval must be at least a minute".to_string(),
+            ));
+        }
+        Ok(Schedule::Every(std::time::Duration::from_secs(
+            self.check_minutes * 60,
+        )))
+    }
+}
+
+/// Log retention settings (`RETENTION__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetentionSettings {
+    /// Days to keep audit log entries; kept for good if unset
+    pub audit_log_days: Option<u32>,
+
+    /// Days to keep change log entries; kept for good if unset
+    pub change_log_days: Option<u32>,
+
+    /// Days to keep closed storage audits; kept for good if unset
+    pub storage_audit_days: Option<u32>,
+
+    /// Directory archive files are written to (default: "archive")
+    #[serde(default = "default_retention_directory")]
+    pub directory: String,
+
+    /// UTC time of day to archive, as "HH:MM" (default: "02:00")
+    #[serde(default = "default_retention_at")]
+    pub at: String,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            audit_log_days: None,
+            change_log_days: None,
+            storage_audit_days: None,
+            directory: default_retention_directory(),
+            at: default_retention_at(),
+        }
+    }
+}
+
+impl RetentionSettings {
+    /// Returns how long each log is kept.
+    pub fn policy(&self) -> Result<RetentionPolicy, DomainError> {
+        [
+            (ArchivedLog::AuditLog, self.audit_log_days),
+            (ArchivedLog::ChangeLog, self.change_log_days),
+            (ArchivedLog::StorageAudit, self.storage_audit_days),
+        ]
+        .into_iter()
+        .filter_map(|(log, days)| days.map(|days| (log, days)))
+        .try_fold(RetentionPolicy::new(), |policy, (log, days)| {
+            if days == 0 {
+                return Err(DomainError::Validation(format!(
+                    "{} retention must be at least a day",
+                    log
+                )));
+            }
+            Ok(policy.keep(log, days))
+        })
+    }
+
+    /// Returns when logs are archived.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        daily_at(&self.at, "archival")
+    }
+}
+
+/// Label printing settings (`LABELS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LabelSettings {
+    /// Comma-separated templates printed once per entity, e.g. "sample"
+    #[serde(default)]
+    pub unique_templates: String,
+}
+
+impl LabelSettings {
+    /// Returns the templates printed once per entity.
+    pub fn unique_templates(&self) -> Vec<String> {
+        self.unique_templates
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Reagent alert settings (`REAGENTS__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReagentSettings {
+    /// UTC time of day to send the alert, as "HH:MM" (default: "07:00")
+    #[serde(default = "default_reagents_at")]
+    pub at: String,
+
+    /// Days ahead to warn of expiring lots (default: 30)
+    #[serde(default = "default_reagent_expiry_days")]
+    pub expiry_days: u32,
+}
+
+impl ReagentSettings {
+    /// Returns when the alert is sent.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        daily_at(&self.at, "reagent alert")
+    }
+}
+
+/// Attachment storage settings (`ATTACHMENTS__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentSettings {
+    /// Local directory to keep files in (default: "attachments"); unused
+    /// if an S3 bucket is configured
+    #[serde(default = "default_attachment_directory")]
+    pub directory: String,
+
+    /// S3 bucket to keep files in instead (`ATTACHMENTS__S3__*`)
+    #[serde(default)]
+    pub s3: Option<S3Settings>,
+}
+
+/// S3 bucket settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Settings {
+    /// Service endpoint, for S3-compatible servers; AWS if unset
+    pub endpoint: Option<String>,
+
+    /// Region (default: "us-east-1")
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+
+    /// Bucket name
+    pub bucket: String,
+
+    /// Prefix put before every object key
+    pub prefix: Option<String>,
+
+    /// Access key ID
+    pub access_key_id: String,
+
+    /// Secret access key
+    pub secret_access_key: String,
+}
+
+impl S3Settings {
+    /// Converts the settings into S3 client configuration.
+    pub fn to_s3_config(&self) -> S3Config {
+        S3Config {
+            endpoint: self.endpoint.clone(),
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            prefix: self.prefix.clone(),
+            access_key_id: self.access_key_id.clone(),
+            secret_access_key: self.secret_access_key.clone(),
+        }
+    }
+}
+
+/// Parses a daily "HH:MM" UTC time for the job named `what`.
+fn daily_at(at: &str, what: &str) -> Result<Schedule, DomainError> {
+    let invalid = || DomainError::Validation(format!("Invalid {} time: {}", what, at));
+    let (hour, minute) = at.trim().split_once(':').ok_or_else(invalid)?;
+    Schedule::daily_at(
+        hour.parse().map_err(|_| invalid())?,
+        minute.parse().map_err(|_| invalid())?,
+    )
+}
+
+/// Parses comma-separated group names keyed by role, requiring at least one
+/// group so that someone can log in.
+fn parse_group_roles(
+    group_roles: &HashMap<String, String>,
+    source: &str,
+) -> Result<HashMap<Role, Vec<String>>, DomainError> {
+    let group_roles = group_roles
+        .iter()
+        .map(|(role, groups)| {
+            Ok((
+                role.parse::<Role>()?,
+                groups
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|g| !g.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>(),
+            ))
+        })
+        .collect::<Result<HashMap<_, _>, DomainError>>()?;
+    if group_roles.values().all(Vec::is_empty) {
+        return Err(DomainError::Validation(format!(
+            "{} group roles must map at least one group, or no user can log in through it",
+            source
+        )));
+    }
+    Ok(group_roles)
+}
+
+/// Scheduled digest settings (`DIGEST__*` variables).
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigestSettings {
+    /// UTC time of day to send the digest, as "HH:MM"
+    pub at: String,
+
+    /// Comma-separated roles to send the digest to (default: lab_manager)
+    #[serde(default = "default_digest_roles")]
+    pub roles: String,
+
+    /// Subject template; the default is used if unset
+    pub subject: Option<String>,
+
+    /// Path of a body template file; the default is used if unset
+    pub template: Option<String>,
+
+    /// How full a box must be, in percent, to be listed as nearly full
+    #[serde(default = "default_box_fill_percent")]
+    pub box_fill_percent: f64,
+}
+
+impl DigestSettings {
+    /// Returns when the digest is sent.
+    pub fn schedule(&self) -> Result<Schedule, DomainError> {
+        daily_at(&self.at, "digest")
+    }
+
+    /// Returns the roles the digest is sent to.
+    pub fn roles(&self) -> Result<Vec<Role>, DomainError> {
+        self.roles
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Loads the digest template.
+    pub fn template(&self) -> Result<DigestTemplate, DomainError> {
+        let default = DigestTemplate::default();
+        let subject = match &self.subject {
+            Some(subject) => subject.clone(),
+            None => default.subject().to_string(),
+        };
+        let body = match &self.template {
+            Some(path) => std::fs::read_to_string(path).map_err(|e| {
+                DomainError::Validation(format!("Failed to read digest template {}: {}", path, e))
+            })?,
+            None => default.body().to_string(),
+        };
+        DigestTemplate::new(subject, body)
+    }
+}
+
+/// Pool plexity limits (`POOL_LIMITS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PoolLimitSettings {
+    /// Comma-separated `platform=limit` pairs, e.g. "illumina=384"
+    #[serde(default, deserialize_with = "limit_list")]
+    pub platforms: Vec<(String, usize)>,
+
+    /// Comma-separated `container model=limit` pairs, e.g.
+    /// "Flongle Flow Cell=12"; these override the platform's limit
+    #[serde(default, deserialize_with = "limit_list")]
+    pub containers: Vec<(String, usize)>,
+}
+
+impl PoolLimitSettings {
+    /// Returns the configured limits.
+    pub fn limits(&self) -> PlexityLimits {
+        let limits = self
+            .platforms
+            .iter()
+            .fold(PlexityLimits::new(), |limits, (platform, max)| {
+                limits.with_platform(platform, *max)
+            });
+        self.containers
+            .iter()
+            .fold(limits, |limits, (container, max)| {
+                limits.with_container(container, *max)
+            })
+    }
+}
+
+fn limit_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, usize)>, D::Error> {
+    parse_limit_list(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Parses "name=limit" pairs separated by commas.
+fn parse_limit_list(list: &str) -> Result<Vec<(String, usize)>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, max) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| format!("Invalid pool limit, expected name=limit: {}", pair))?;
+            let max = max
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|max| *max > 0)
+                .ok_or_else(|| format!("Invalid pool limit: {}", pair))?;
+            Ok((name.trim().to_string(), max))
+        })
+        .collect()
+}
+
+/// Read targets for balancing pools (`POOL_TARGETS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PoolTargetSettings {
+    /// Comma-separated `design=reads` pairs giving the reads each library
+    /// of a design needs, e.g. "wgs=400000000,rna_seq=30000000"
+    #[serde(default, deserialize_with = "read_list")]
+    pub designs: Vec<(String, u64)>,
+
+    /// Comma-separated `platform=reads` pairs giving the reads a lane
+    /// gives, e.g. "illumina=400000000"
+    #[serde(default, deserialize_with = "read_list")]
+    pub platforms: Vec<(String, u64)>,
+
+    /// Comma-separated `container model=reads` pairs, e.g.
+    /// "S4 Flow Cell=2500000000"; these override the platform's
+    #[serde(default, deserialize_with = "read_list")]
+    pub containers: Vec<(String, u64)>,
+}
+
+impl PoolTargetSettings {
+    /// Returns the configured targets.
+    pub fn targets(&self) -> YieldTargets {
+        let targets = self
+            .designs
+            .iter()
+            .fold(YieldTargets::new(), |targets, (design, reads)| {
+                targets.with_design(LibraryDesign::from_code(design), *reads)
+            });
+        let targets = self
+            .platforms
+            .iter()
+            .fold(targets, |targets, (platform, reads)| {
+                targets.with_platform(platform, *reads)
+            });
+        self.containers
+            .iter()
+            .fold(targets, |targets, (container, reads)| {
+                targets.with_container(container, *reads)
+            })
+    }
+}
+
+fn read_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(String, u64)>, D::Error> {
+    parse_read_list(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Parses "name=reads" pairs separated by commas.
+fn parse_read_list(list: &str) -> Result<Vec<(String, u64)>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, reads) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| format!("Invalid read target, expected name=reads: {}", pair))?;
+            let reads = reads
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|reads| *reads > 0)
+                .ok_or_else(|| format!("Invalid read target: {}", pair))?;
+            Ok((name.trim().to_string(), reads))
+        })
+        .collect()
+}
+
+/// QC thresholds (`QC_THRESHOLDS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct QcThresholdSettings {
+    /// Comma-separated `test=min..max` ranges for samples, e.g.
+    /// "qubit=10..,nanodrop=1.8..2.2"; either end may be left open
+    #[serde(default, deserialize_with = "threshold_list")]
+    pub samples: Vec<(String, QcThreshold)>,
+
+    /// Ranges for libraries, e.g. "qubit=2..,tapestation=200..800"
+    #[serde(default, deserialize_with = "threshold_list")]
+    pub libraries: Vec<(String, QcThreshold)>,
+
+    /// Ranges for pools
+    #[serde(default, deserialize_with = "threshold_list")]
+    pub pools: Vec<(String, QcThreshold)>,
+}
+
+impl QcThresholdSettings {
+    /// Returns an evaluator judging measurements against the thresholds.
+    pub fn evaluator(&self) -> QcEvaluator {
+        [
+            (QcTarget::Sample, &self.samples),
+            (QcTarget::Library, &self.libraries),
+            (QcTarget::Pool, &self.pools),
+        ]
+        .into_iter()
+        .flat_map(|(target, thresholds)| {
+            thresholds
+                .iter()
+                .map(move |(test, threshold)| (target, test, *threshold))
+        })
+        .fold(QcEvaluator::new(), |evaluator, (target, test, threshold)| {
+            let test_type: QcTestType = test.parse().unwrap_or_else(|never| match never {});
+            evaluator.with_threshold(target, &test_type, threshold)
+        })
+    }
+}
+
+fn threshold_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, QcThreshold)>, D::Error> {
+    parse_threshold_list(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Parses "test=min..max" ranges separated by commas.
+fn parse_threshold_list(list: &str) -> Result<Vec<(String, QcThreshold)>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || format!("Invalid QC threshold, expected test=min..max: {}", entry);
+            let (test, range) = entry.rsplit_once('=').ok_or_else(invalid)?;
+            let (min, max) = range.split_once("..").ok_or_else(invalid)?;
+            let bound = |value: &str| -> Result<Option<f64>, String> {
+                match value.trim() {
+                    "" => Ok(None),
+                    value => value.parse::<f64>().map(Some).map_err(|_| invalid()),
+                }
+            };
+            let threshold = QcThreshold {
+                min: bound(min)?,
+                max: bound(max)?,
+            };
+            if test.trim().is_empty() || (threshold.min.is_none() && threshold.max.is_none()) {
+                return Err(invalid());
+            }
+            Ok((test.trim().to_string(), threshold))
+        })
+        .collect()
+}
+
+/// Library kit compatibility (`LIBRARY_KITS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LibraryKitSettings {
+    /// Comma-separated `kit=design@platform` entries, e.g.
+    /// "TruSeq Stranded mRNA=rna_seq@illumina"; a kit supporting several
+    /// combinations is listed once for each
+    #[serde(default, deserialize_with = "kit_list")]
+    pub combinations: Vec<(String, String, String)>,
+}
+
+impl LibraryKitSettings {
+    /// Returns the configured compatibility table.
+    pub fn compatibility(&self) -> KitCompatibility {
+        self.combinations
+            .iter()
+            .fold(KitCompatibility::new(), |kits, (kit, design, platform)| {
+                kits.with_combination(kit, LibraryDesign::from_code(design), platform)
+            })
+    }
+}
+
+fn kit_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, String, String)>, D::Error> {
+    parse_kit_list(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Parses "kit=design@platform" entries separated by commas.
+fn parse_kit_list(list: &str) -> Result<Vec<(String, String, String)>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || {
+                format!(
+                    "Invalid library kit, expected kit=design@platform: {}",
+                    entry
+                )
+            };
+            let (kit, combination) = entry.rsplit_once('=').ok_or_else(invalid)?;
+            let (design, platform) = combination.split_once('@').ok_or_else(invalid)?;
+            let fields = [kit, design, platform].map(str::trim);
+            if fields.iter().any(|field| field.is_empty()) {
+                return Err(invalid());
+            }
+            let [kit, design, platform] = fields.map(str::to_string);
+            Ok((kit, design, platform))
+        })
+        .collect()
+}
+
+/// Required controls (`CONTROLS__*` variables).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ControlSettings {
+    /// Comma-separated control types every workset must hold before its
+    /// samples are updated together, e.g. "positive,negative"
+    #[serde(default, deserialize_with = "control_list")]
+    pub batches: Vec<ControlType>,
+
+    /// Comma-separated control types a run's pools must hold before it
+    /// starts, e.g. "ntc"
+    #[serde(default, deserialize_with = "control_list")]
+    pub runs: Vec<ControlType>,
+}
+
+impl ControlSettings {
+    /// Returns the configured policy.
+    pub fn policy(&self) -> ControlPolicy {
+        let policy = self
+            .batches
+            .iter()
+            .fold(ControlPolicy::new(), |policy, control| {
+                policy.with_batch_control(*control)
+            });
+        self.runs
+            .iter()
+            .fold(policy, |policy, control| policy.with_run_control(*control))
+    }
+}
+
+fn control_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ControlType>, D::Error> {
+    parse_control_list(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Parses control type codes separated by commas.
+fn parse_control_list(list: &str) -> Result<Vec<ControlType>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::parse)
+        .collect()
 }
 
 fn default_host() -> String {
@@ -48,6 +1085,102 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_slow_query_ms() -> u64 {
+    500
+}
+
+fn default_min_index_distance() -> u32 {
+    3
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_starttls() -> bool {
+    true
+}
+
+fn default_ldap_user_filter() -> String {
+    "(uid={username})".to_string()
+}
+
+fn default_oidc_scopes() -> String {
+    "openid profile email".to_string()
+}
+
+fn default_oidc_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+fn default_oidc_groups_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_digest_roles() -> String {
+    "lab_manager".to_string()
+}
+
+fn default_box_fill_percent() -> f64 {
+    90.0
+}
+
+fn default_basespace_api_url() -> String {
+    "https://api.basespace.illumina.com".to_string()
+}
+
+fn default_basespace_poll_minutes() -> u64 {
+    60
+}
+
+fn default_shipment_poll_minutes() -> u64 {
+    30
+}
+
+fn default_api_usage_sample_every() -> u64 {
+    10
+}
+
+fn default_api_usage_flush_seconds() -> u64 {
+    60
+}
+
+fn default_auto_archive_at() -> String {
+    "03:00".to_string()
+}
+
+fn default_reconciliation_at() -> String {
+    "01:00".to_string()
+}
+
+fn default_stale_run_check_minutes() -> u64 {
+    60
+}
+
+fn default_retention_directory() -> String {
+    "archive".to_string()
+}
+
+fn default_retention_at() -> String {
+    "02:00".to_string()
+}
+
+fn default_reagents_at() -> String {
+    "07:00".to_string()
+}
+
+fn default_reagent_expiry_days() -> u32 {
+    miso_application::DEFAULT_EXPIRY_DAYS
+}
+
+fn default_attachment_directory() -> String {
+    "attachments".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
 impl Config {
     /// Loads configuration from environment variables.
     pub fn from_env() -> Result<Self, config::ConfigError> {
@@ -60,14 +1193,243 @@ impl Config {
             .set_default("port", 8080)?
             .set_default("jwt_expiration_hours", 24)?
             .set_default("cors_enabled", false)?
+            .set_default("event_store", false)?
             .set_default("log_level", "info")?
+            .set_default("slow_query_ms", 500)?
+            .set_default("min_index_distance", 3)?
             .build()?
             .try_deserialize()
     }
 
+    /// Returns the slow-query threshold, if slow-query logging is enabled.
+    pub fn slow_query_threshold(&self) -> Option<std::time::Duration> {
+        (self.slow_query_ms > 0).then(|| std::time::Duration::from_millis(self.slow_query_ms))
+    }
+
     /// Returns the server address.
     pub fn address(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_settings() {
+        let mut digest = DigestSettings {
+            at: "07:00".to_string(),
+            roles: "lab_manager, technician".to_string(),
+            subject: None,
+            template: None,
+            box_fill_percent: default_box_fill_percent(),
+        };
+        assert_eq!(digest.schedule().unwrap(), Schedule::daily_at(7, 0).unwrap());
+        assert_eq!(
+            digest.roles().unwrap(),
+            vec![Role::LabManager, Role::Technician]
+        );
+        assert_eq!(digest.template().unwrap(), DigestTemplate::default());
+
+        digest.at = "7am".to_string();
+        assert!(digest.schedule().is_err());
+        digest.roles = "lab_manager,janitor".to_string();
+        assert!(digest.roles().is_err());
+        digest.subject = Some("Summary for {{day}}".to_string());
+        assert!(digest.template().is_err());
+    }
+
+    #[test]
+    fn test_pool_limit_lists() {
+        let settings = PoolLimitSettings {
+            platforms: parse_limit_list("illumina=384, oxford_nanopore = 96").unwrap(),
+            containers: parse_limit_list("Flongle Flow Cell=12,").unwrap(),
+        };
+        let limits = settings.limits();
+        assert_eq!(limits.max_size("illumina", None), Some(384));
+        assert_eq!(
+            limits.max_size("oxford_nanopore", Some("Flongle Flow Cell")),
+            Some(12)
+        );
+
+        assert!(parse_limit_list("illumina").is_err());
+        assert!(parse_limit_list("illumina=0").is_err());
+        assert!(parse_limit_list("illumina=many").is_err());
+    }
+
+    #[test]
+    fn test_pool_target_lists() {
+        let settings = PoolTargetSettings {
+            designs: parse_read_list("wgs=400000000, rna_seq = 30000000").unwrap(),
+            platforms: parse_read_list("illumina=400000000").unwrap(),
+            containers: parse_read_list("S4 Flow Cell=2500000000,").unwrap(),
+        };
+        let targets = settings.targets();
+        assert_eq!(
+            targets.required_reads(&LibraryDesign::RnaSeq),
+            Some(30_000_000)
+        );
+        assert_eq!(
+            targets.lane_reads("illumina", Some("S4 Flow Cell")),
+            Some(2_500_000_000)
+        );
+
+        assert!(parse_read_list("wgs").is_err());
+        assert!(parse_read_list("wgs=0").is_err());
+        assert!(parse_read_list("wgs=30M").is_err());
+    }
+
+    #[test]
+    fn test_qc_threshold_lists() {
+        let settings = QcThresholdSettings {
+            libraries: parse_threshold_list("Qubit = 2.., tapestation=200..800,").unwrap(),
+            ..Default::default()
+        };
+        let evaluator = settings.evaluator();
+        assert_eq!(
+            evaluator.threshold(QcTarget::Library, &QcTestType::Qubit),
+            Some(QcThreshold {
+                min: Some(2.0),
+                max: None
+            })
+        );
+        assert_eq!(
+            evaluator.threshold(QcTarget::Library, &QcTestType::TapeStation),
+            Some(QcThreshold {
+                min: Some(200.0),
+                max: Some(800.0)
+            })
+        );
+        assert_eq!(evaluator.threshold(QcTarget::Sample, &QcTestType::Qubit), None);
+
+        assert!(parse_threshold_list("qubit").is_err());
+        assert!(parse_threshold_list("qubit=2").is_err());
+        assert!(parse_threshold_list("qubit=..").is_err());
+        assert!(parse_threshold_list("qubit=low..").is_err());
+    }
+
+    #[test]
+    fn test_library_kit_lists() {
+        let settings = LibraryKitSettings {
+            combinations: parse_kit_list(
+                "TruSeq Stranded mRNA = rna_seq@illumina, SMRTbell Prep Kit 3.0=wgs@pac_bio,",
+            )
+            .unwrap(),
+        };
+        let kits = settings.compatibility();
+        assert!(kits
+            .check("TruSeq Stranded mRNA", &LibraryDesign::RnaSeq, "illumina")
+            .is_ok());
+        assert!(kits
+            .check("SMRTbell Prep Kit 3.0", &LibraryDesign::RnaSeq, "pac_bio")
+            .is_err());
+
+        assert!(parse_kit_list("TruSeq").is_err());
+        assert!(parse_kit_list("TruSeq=rna_seq").is_err());
+        assert!(parse_kit_list("TruSeq=@illumina").is_err());
+    }
+
+    #[test]
+    fn test_control_lists() {
+        let settings = ControlSettings {
+            batches: parse_control_list("Positive, negative,").unwrap(),
+            runs: parse_control_list("ntc").unwrap(),
+        };
+        let policy = settings.policy();
+        assert_eq!(
+            policy.missing_from_batch(&[ControlType::Negative]),
+            vec![ControlType::Positive]
+        );
+        assert_eq!(policy.missing_from_run(&[]), vec![ControlType::Ntc]);
+
+        assert!(parse_control_list("").unwrap().is_empty());
+        assert!(parse_control_list("positive,blank").is_err());
+    }
+
+    #[test]
+    fn test_ldap_group_roles() {
+        let mut settings = LdapSettings {
+            url: "ldap://ldap.example.org".to_string(),
+            starttls: true,
+            bind_dn: Some("cn=miso,dc=example,dc=org".to_string()),
+            bind_password: Some("secret".to_string()),
+            base_dn: "ou=people,dc=example,dc=org".to_string(),
+            user_filter: default_ldap_user_filter(),
+            group_roles: HashMap::from([
+                ("admin".to_string(), "lims-admins".to_string()),
+                (
+                    "technician".to_string(),
+                    "lab-staff, sequencing".to_string(),
+                ),
+            ]),
+        };
+        let config = settings.to_ldap_config().unwrap();
+        assert_eq!(
+            config.group_roles[&Role::Technician],
+            vec!["lab-staff".to_string(), "sequencing".to_string()]
+        );
+        assert!(config.bind_credentials.is_some());
+
+        settings
+            .group_roles
+            .insert("janitor".to_string(), "cleaners".to_string());
+        assert!(settings.to_ldap_config().is_err());
+        settings.group_roles = HashMap::from([("admin".to_string(), " ,".to_string())]);
+        assert!(settings.to_ldap_config().is_err());
+    }
+
+    #[test]
+    fn test_oidc_settings() {
+        let mut settings = OidcSettings {
+            issuer_url: "https://login.example.org/realms/lab".to_string(),
+            client_id: "miso".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_url: "https://miso.example.org/api/v1/auth/oidc/callback".to_string(),
+            scopes: default_oidc_scopes(),
+            username_claim: default_oidc_username_claim(),
+            groups_claim: default_oidc_groups_claim(),
+            group_roles: HashMap::from([("lab_manager".to_string(), "lab-leads".to_string())]),
+        };
+        let config = settings.to_oidc_config().unwrap();
+        assert_eq!(config.scopes, vec!["openid", "profile", "email"]);
+        assert_eq!(
+            config.role_for(&["Lab-Leads".to_string()]),
+            Some(Role::LabManager)
+        );
+
+        settings.group_roles.clear();
+        assert!(settings.to_oidc_config().is_err());
+    }
+
+    #[test]
+    fn test_retention_settings() {
+        let mut settings = RetentionSettings {
+            audit_log_days: Some(365),
+            storage_audit_days: Some(90),
+            ..RetentionSettings::default()
+        };
+        let policy = settings.policy().unwrap();
+        assert_eq!(
+            policy.period(ArchivedLog::AuditLog),
+            Some(chrono::Duration::days(365))
+        );
+        assert_eq!(policy.period(ArchivedLog::ChangeLog), None);
+        assert_eq!(settings.schedule().unwrap(), Schedule::daily_at(2, 0).unwrap());
+
+        settings.change_log_days = Some(0);
+        assert!(settings.policy().is_err());
+        settings.at = "25:00".to_string();
+        assert!(settings.schedule().is_err());
+    }
+
+    #[test]
+    fn test_label_settings() {
+        let settings = LabelSettings {
+            unique_templates: " sample, ,tissue ".to_string(),
+        };
+        assert_eq!(settings.unique_templates(), ["sample", "tissue"]);
+        assert!(LabelSettings::default().unique_templates().is_empty());
+    }
+}
@@ -5,6 +5,13 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use miso_application::dto::{
+    codes, IndexCollisionResponse, KitCompatibilityResponse, LoadMismatchResponse,
+    PoolCapacityResponse,
+    ProjectLockedResponse, RoleRequiredResponse,
+};
+use miso_domain::entities::Role;
+use miso_domain::errors::{DomainError, LibraryError, PoolError, RunError, StorageError};
 use serde::Serialize;
 use thiserror::Error;
 
@@ -35,9 +42,15 @@ pub enum ApiError {
     #[error("Forbidden")]
     Forbidden,
 
+    #[error("Requires the {0} role")]
+    RoleRequired(Role),
+
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Internal server error")]
     Internal(#[from] anyhow::Error),
 
@@ -53,7 +66,9 @@ impl IntoResponse for ApiError {
             ApiError::Validation(msg) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error", msg.clone()),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized", "Authentication required".to_string()),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "forbidden", "Permission denied".to_string()),
+            ApiError::RoleRequired(role) => (StatusCode::FORBIDDEN, "role_required", format!("Requires the {} role or higher", role)),
             ApiError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg.clone()),
+            ApiError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, "too_many_requests", msg.clone()),
             ApiError::Internal(e) => {
                 tracing::error!("Internal error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "An unexpected error occurred".to_string())
@@ -62,7 +77,15 @@ impl IntoResponse for ApiError {
                 let (status, error_type) = match e {
                     miso_domain::errors::DomainError::NotFound { .. } => (StatusCode::NOT_FOUND, "not_found"),
                     miso_domain::errors::DomainError::Duplicate { .. } => (StatusCode::CONFLICT, "duplicate"),
+                    miso_domain::errors::DomainError::ConcurrentModification { .. } => (StatusCode::CONFLICT, "conflict"),
+                    miso_domain::errors::DomainError::ProjectLocked { .. } => (StatusCode::LOCKED, "project_locked"),
+                    miso_domain::errors::DomainError::PermissionDenied(_) => (StatusCode::FORBIDDEN, "forbidden"),
                     miso_domain::errors::DomainError::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error"),
+                    miso_domain::errors::DomainError::Library(LibraryError::InvalidKitType { .. }) => (StatusCode::UNPROCESSABLE_ENTITY, "invalid_kit_type"),
+                    miso_domain::errors::DomainError::Pool(PoolError::IndexCollision { .. }) => (StatusCode::CONFLICT, "index_collision"),
+                    miso_domain::errors::DomainError::Pool(PoolError::CapacityExceeded(..)) => (StatusCode::CONFLICT, "pool_capacity_exceeded"),
+                    miso_domain::errors::DomainError::Storage(StorageError::PositionOccupied { .. }) => (StatusCode::CONFLICT, "position_occupied"),
+                    miso_domain::errors::DomainError::Run(RunError::LoadMismatch { .. }) => (StatusCode::CONFLICT, "load_mismatch"),
                     _ => (StatusCode::BAD_REQUEST, "domain_error"),
                 };
                 (status, error_type, e.to_string())
@@ -72,13 +95,71 @@ impl IntoResponse for ApiError {
         let body = ErrorResponse {
             error: error_type.to_string(),
             message,
-            details: None,
+            details: self.details(),
         };
 
         (status, Json(body)).into_response()
     }
 }
 
+impl ApiError {
+    /// Structured detail for errors a client may want to act on.
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::RoleRequired(role) => serde_json::to_value(RoleRequiredResponse {
+                required_role: codes::role(*role).to_string(),
+            })
+            .ok(),
+            ApiError::Domain(DomainError::Library(LibraryError::InvalidKitType {
+                kit,
+                design,
+                platform,
+                allowed,
+            })) => serde_json::to_value(KitCompatibilityResponse::new(
+                kit, design, platform, allowed,
+            ))
+            .ok(),
+            ApiError::Domain(DomainError::Pool(PoolError::IndexCollision {
+                lib1,
+                lib2,
+                distance,
+                required,
+            })) => serde_json::to_value(IndexCollisionResponse {
+                library1: lib1.clone(),
+                library2: lib2.clone(),
+                distance: *distance,
+                required_distance: *required,
+            })
+            .ok(),
+            ApiError::Domain(DomainError::Pool(PoolError::CapacityExceeded(pool, max_size))) => {
+                serde_json::to_value(PoolCapacityResponse {
+                    pool: pool.clone(),
+                    max_size: *max_size,
+                })
+                .ok()
+            }
+            ApiError::Domain(DomainError::Run(RunError::LoadMismatch { run, mismatches })) => {
+                serde_json::to_value(LoadMismatchResponse {
+                    run: run.clone(),
+                    mismatches: mismatches.clone(),
+                })
+                .ok()
+            }
+            ApiError::Domain(DomainError::ProjectLocked {
+                project,
+                reason,
+                unlock_at,
+            }) => serde_json::to_value(ProjectLockedResponse {
+                project: project.clone(),
+                reason: reason.clone(),
+                unlock_at: *unlock_at,
+            })
+            .ok(),
+            _ => None,
+        }
+    }
+}
+
 impl From<validator::ValidationErrors> for ApiError {
     fn from(errors: validator::ValidationErrors) -> Self {
         ApiError::Validation(format!("Validation failed: {}", errors))
@@ -7,12 +7,14 @@
 //! - **Routes**: HTTP endpoint handlers
 //! - **Middleware**: Authentication, logging, CORS
 //! - **State**: Shared application state (services, config)
+//! - **Server**: Startup, with site plugins installed
 //! - **Error Handling**: Consistent API error responses
 
 pub mod config;
 pub mod error;
 pub mod middleware;
 pub mod routes;
+pub mod server;
 pub mod state;
 
 pub use config::Config;
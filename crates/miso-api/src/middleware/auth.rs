@@ -1,13 +1,29 @@
 //! Authentication middleware.
+//!
+//! [`authenticate`] runs on every API request. A request with a bearer
+//! token, or with an API key in [`API_KEY_HEADER`], has the token or key
+//! checked and its user loaded, and is rejected if either fails; the user
+//! is then available to handlers as [`AuthUser`] (and as the domain `User`
+//! extension). A request with neither passes through anonymously, and
+//! handlers that need a user reject it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
 
 use axum::{
-    extract::FromRequestParts,
-    http::{header, request::Parts, StatusCode},
+    extract::{FromRequestParts, OptionalFromRequestParts, Request, State},
+    http::{header, request::Parts},
+    middleware::Next,
+    response::Response,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use miso_application::access::Requester;
+use miso_application::dto::codes;
+use miso_domain::entities::{EntityId, Role, User};
 use serde::{Deserialize, Serialize};
 
-use crate::ApiError;
+use crate::{ApiError, AppState};
 
 /// JWT claims.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,14 +38,167 @@ pub struct Claims {
     pub exp: usize,
     /// Issued at timestamp
     pub iat: usize,
+    /// Token ID, by which the token is revoked on logout
+    pub jti: String,
+}
+
+impl Claims {
+    /// Creates claims for a user, expiring after `expiration_hours`.
+    pub fn new(user_id: &str, username: &str, role: &str, expiration_hours: u64) -> Self {
+        let now = Utc::now();
+        Self {
+            sub: user_id.to_string(),
+            username: username.to_string(),
+            role: role.to_string(),
+            exp: (now + Duration::hours(expiration_hours as i64)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Creates claims for a domain user.
+    pub fn for_user(user: &User, expiration_hours: u64) -> Self {
+        Self::new(
+            &user.id.to_string(),
+            &user.username,
+            codes::role(user.role),
+            expiration_hours,
+        )
+    }
+
+    /// Signs the claims into a token.
+    pub fn encode(&self, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        encode(
+            &Header::default(),
+            self,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+    }
+
+    /// Checks a token's signature and expiry and returns its claims.
+    pub fn decode(token: &str, secret: &str) -> Result<Self, ApiError> {
+        decode::<Self>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map(|data| data.claims)
+        .map_err(|_| ApiError::Unauthorized)
+    }
+
+    /// Returns when the token expires.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp as i64, 0).unwrap_or_default()
+    }
+}
+
+/// Tokens revoked before they expire, by token ID.
+///
+/// Revocations are held in memory: they are lost on restart and not shared
+/// between servers, so a logged-out token is refused only by the server
+/// that logged it out, and only until it restarts. Tokens are short-lived,
+/// which bounds the exposure.
+#[derive(Debug, Default)]
+pub struct RevokedTokens {
+    tokens: Mutex<HashMap<String, usize>>,
+}
+
+impl RevokedTokens {
+    /// Creates an empty revocation list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revokes a token until it expires, forgetting tokens that have
+    /// expired anyway.
+    pub fn revoke(&self, claims: &Claims) {
+        let now = Utc::now().timestamp() as usize;
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|_, exp| *exp > now);
+        tokens.insert(claims.jti.clone(), claims.exp);
+    }
+
+    /// Returns true if a token has been revoked.
+    pub fn is_revoked(&self, claims: &Claims) -> bool {
+        self.tokens.lock().unwrap().contains_key(&claims.jti)
+    }
+}
+
+/// Header carrying an API key, for instruments and scripts that can't log
+/// in.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Authenticates requests carrying a bearer token or an API key.
+///
+/// The user is loaded on every request, so deactivating a user or changing
+/// their role takes effect without waiting for their tokens to expire. A
+/// request made with an API key acts for the key's user, with the key's
+/// role if that is lower than theirs.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user, auth_user) = if let Some(auth_header) = request.headers().get(header::AUTHORIZATION)
+    {
+        let token = auth_header
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(ApiError::Unauthorized)?;
+
+        let claims = Claims::decode(token, &state.config.jwt_secret)?;
+        if state.revoked_tokens.is_revoked(&claims) {
+            return Err(ApiError::Unauthorized);
+        }
+
+        let id: EntityId = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+        let user = find_active_user(&state, id).await?;
+        let auth_user = AuthUser::new(&user, Some(claims));
+        (user, auth_user)
+    } else if let Some(key) = request.headers().get(API_KEY_HEADER) {
+        let key = key.to_str().map_err(|_| ApiError::Unauthorized)?;
+        let api_key = state
+            .api_key_service
+            .authenticate(key)
+            .await?
+            .ok_or(ApiError::Unauthorized)?;
+
+        let mut user = find_active_user(&state, api_key.user_id).await?;
+        user.role = api_key.effective_role(user.role);
+        let mut auth_user = AuthUser::new(&user, None);
+        auth_user.api_key_id = Some(api_key.id);
+        (user, auth_user)
+    } else {
+        return Ok(next.run(request).await);
+    };
+
+    request.extensions_mut().insert(auth_user);
+    request.extensions_mut().insert(user);
+
+    Ok(next.run(request).await)
+}
+
+async fn find_active_user(state: &AppState, id: EntityId) -> Result<User, ApiError> {
+    state
+        .users
+        .find_by_id(id)
+        .await?
+        .filter(|user| user.active)
+        .ok_or(ApiError::Unauthorized)
 }
 
-/// Authenticated user extracted from JWT.
+/// Authenticated user, as loaded by [`authenticate`].
 #[derive(Debug, Clone)]
 pub struct AuthUser {
-    pub id: String,
+    pub id: EntityId,
     pub username: String,
     pub role: String,
+    /// Claims of the token the request was made with; `None` if it was
+    /// made with an API key
+    pub token: Option<Claims>,
+    /// API key the request was made with, if any
+    pub api_key_id: Option<EntityId>,
 }
 
 impl<S> FromRequestParts<S> for AuthUser
@@ -39,40 +208,41 @@ where
     type Rejection = ApiError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        // Get the Authorization header
-        let auth_header = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|value| value.to_str().ok())
-            .ok_or(ApiError::Unauthorized)?;
-
-        // Check for Bearer token
-        if !auth_header.starts_with("Bearer ") {
-            return Err(ApiError::Unauthorized);
-        }
-
-        let token = &auth_header[7..];
-
-        // Get the JWT secret from environment (in production, this should come from state)
-        let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string());
+        parts
+            .extensions
+            .get::<AuthUser>()
+            .cloned()
+            .ok_or(ApiError::Unauthorized)
+    }
+}
 
-        // Decode and validate the token
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| ApiError::Unauthorized)?;
+/// Handlers taking `Option<AuthUser>` serve anonymous requests too.
+impl<S> OptionalFromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
 
-        Ok(AuthUser {
-            id: token_data.claims.sub,
-            username: token_data.claims.username,
-            role: token_data.claims.role,
-        })
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<AuthUser>().cloned())
     }
 }
 
 impl AuthUser {
+    /// Creates the authenticated user for a token's or API key's user.
+    pub fn new(user: &User, token: Option<Claims>) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            role: codes::role(user.role).to_string(),
+            token,
+            api_key_id: None,
+        }
+    }
+
     /// Returns true if the user has admin role.
     pub fn is_admin(&self) -> bool {
         self.role == "admin" || self.role == "super_admin"
@@ -90,6 +260,22 @@ impl AuthUser {
     pub fn can_delete(&self) -> bool {
         matches!(self.role.as_str(), "lab_manager" | "admin" | "super_admin")
     }
+
+    /// Returns the user as a requester of project data.
+    pub fn requester(&self) -> Requester<'_> {
+        Requester::new(&self.username, self.as_role())
+    }
+
+    /// Returns the user's domain role; unrecognised roles are read-only.
+    pub fn as_role(&self) -> Role {
+        match self.role.as_str() {
+            "technician" => Role::Technician,
+            "lab_manager" => Role::LabManager,
+            "admin" => Role::Admin,
+            "super_admin" => Role::SuperAdmin,
+            _ => Role::Viewer,
+        }
+    }
 }
 
 /// Creates a JWT token for a user.
@@ -100,25 +286,36 @@ pub fn create_token(
     secret: &str,
     expiration_hours: u64,
 ) -> Result<String, jsonwebtoken::errors::Error> {
-    use chrono::{Duration, Utc};
-    use jsonwebtoken::{encode, EncodingKey, Header};
-
-    let now = Utc::now();
-    let exp = (now + Duration::hours(expiration_hours as i64)).timestamp() as usize;
-    let iat = now.timestamp() as usize;
-
-    let claims = Claims {
-        sub: user_id.to_string(),
-        username: username.to_string(),
-        role: role.to_string(),
-        exp,
-        iat,
-    };
-
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+    Claims::new(user_id, username, role, expiration_hours).encode(secret)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_round_trip_and_revoke() {
+        let user = User::new_internal(
+            7,
+            "alice".to_string(),
+            "Alice".to_string(),
+            "alice@example.org".to_string(),
+            Role::LabManager,
+        );
+        let claims = Claims::for_user(&user, 1);
+        let token = claims.encode("secret").unwrap();
+
+        let decoded = Claims::decode(&token, "secret").unwrap();
+        assert_eq!(decoded.sub, "7");
+        assert_eq!(decoded.role, "lab_manager");
+        assert_eq!(decoded.jti, claims.jti);
+        assert!(decoded.expires_at() > Utc::now());
+        assert!(Claims::decode(&token, "other secret").is_err());
+
+        let revoked = RevokedTokens::new();
+        assert!(!revoked.is_revoked(&decoded));
+        revoked.revoke(&decoded);
+        assert!(revoked.is_revoked(&decoded));
+        assert!(!revoked.is_revoked(&Claims::for_user(&user, 1)));
+    }
+}
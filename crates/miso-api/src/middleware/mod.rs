@@ -1,6 +1,9 @@
 //! API middleware.
 
 mod auth;
+mod role;
+mod usage;
 
 pub use auth::*;
-
+pub use role::*;
+pub use usage::*;
@@ -0,0 +1,131 @@
+//! Role-based authorization.
+//!
+//! A handler declares the least role it needs by taking a
+//! [`RequireRole`] argument, e.g. `user: RequireRole<LabManager>`. Requests
+//! without a user are refused with 401, and those whose user's role is
+//! lower with 403 naming the role required.
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use miso_domain::entities::Role;
+
+use super::AuthUser;
+use crate::ApiError;
+
+/// A role a handler can require.
+pub trait MinimumRole: Send + Sync {
+    /// The least role allowed.
+    const ROLE: Role;
+}
+
+macro_rules! minimum_role {
+    ($($(#[$doc:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            impl MinimumRole for $name {
+                const ROLE: Role = Role::$name;
+            }
+        )*
+    };
+}
+
+minimum_role!(
+    /// Any user.
+    Viewer,
+    /// Users who can change lab data.
+    Technician,
+    /// Users who can delete lab data.
+    LabManager,
+    /// Users who can change system configuration.
+    Admin,
+    /// Users with full access.
+    SuperAdmin,
+);
+
+/// An authenticated user with at least the role `R`.
+///
+/// Dereferences to the [`AuthUser`].
+#[derive(Debug, Clone)]
+pub struct RequireRole<R: MinimumRole> {
+    user: AuthUser,
+    role: PhantomData<R>,
+}
+
+impl<R: MinimumRole> RequireRole<R> {
+    /// Returns the user.
+    pub fn into_inner(self) -> AuthUser {
+        self.user
+    }
+}
+
+impl<R: MinimumRole> Deref for RequireRole<R> {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.user
+    }
+}
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: MinimumRole,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if !user.as_role().has_at_least(&R::ROLE) {
+            return Err(ApiError::RoleRequired(R::ROLE));
+        }
+
+        Ok(Self {
+            user,
+            role: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+
+    use super::*;
+    use crate::middleware::Claims;
+
+    async fn extract<R: MinimumRole>(role: &str) -> Result<RequireRole<R>, ApiError> {
+        let (mut parts, _) = Request::new(()).into_parts();
+        parts.extensions.insert(AuthUser {
+            id: 1,
+            username: "alice".to_string(),
+            role: role.to_string(),
+            token: Some(Claims::new("1", "alice", role, 1)),
+            api_key_id: None,
+        });
+        RequireRole::<R>::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_requires_at_least_the_role() {
+        assert!(extract::<LabManager>("lab_manager").await.is_ok());
+        assert_eq!(
+            extract::<LabManager>("super_admin").await.unwrap().username,
+            "alice"
+        );
+        assert!(matches!(
+            extract::<LabManager>("technician").await,
+            Err(ApiError::RoleRequired(Role::LabManager))
+        ));
+
+        let (mut parts, _) = Request::new(()).into_parts();
+        assert!(matches!(
+            RequireRole::<Viewer>::from_request_parts(&mut parts, &()).await,
+            Err(ApiError::Unauthorized)
+        ));
+    }
+}
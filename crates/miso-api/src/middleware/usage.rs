@@ -0,0 +1,188 @@
+//! API usage tracking and quotas.
+//!
+//! [`track_usage`] runs on every API request after [`authenticate`]. Calls
+//! by a user or API key are sampled for the usage report and, if a quota
+//! is configured, counted against it. Every response to a caller under a
+//! quota says how much of it is left, so integrations can slow down before
+//! they are refused with 429.
+//!
+//! [`authenticate`]: super::authenticate
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use miso_application::ApiCall;
+use miso_domain::entities::EntityId;
+
+use super::AuthUser;
+use crate::{ApiError, AppState};
+
+/// Header giving the calls allowed per quota window.
+pub const QUOTA_LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Header giving the calls left in the current quota window.
+pub const QUOTA_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Header giving the seconds until the quota window starts over.
+pub const QUOTA_RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Who a quota is counted for: an API key, or a user calling with tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Caller {
+    ApiKey(EntityId),
+    User(EntityId),
+}
+
+impl Caller {
+    /// Returns the caller a request is made as.
+    pub fn of(user: &AuthUser) -> Self {
+        match user.api_key_id {
+            Some(id) => Self::ApiKey(id),
+            None => Self::User(user.id),
+        }
+    }
+}
+
+/// How much of a caller's quota is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaUsage {
+    pub limit: u32,
+    pub remaining: u32,
+    /// When the window starts over
+    pub resets_at: DateTime<Utc>,
+    /// True if the call was refused
+    pub exceeded: bool,
+}
+
+impl QuotaUsage {
+    /// Adds the quota headers to a response.
+    fn apply(&self, response: &mut Response, now: DateTime<Utc>) {
+        let reset = (self.resets_at - now).num_seconds().max(0);
+        let headers = response.headers_mut();
+        headers.insert(QUOTA_LIMIT_HEADER, HeaderValue::from(self.limit));
+        headers.insert(QUOTA_REMAINING_HEADER, HeaderValue::from(self.remaining));
+        headers.insert(QUOTA_RESET_HEADER, HeaderValue::from(reset));
+        if self.exceeded {
+            headers.insert(axum::http::header::RETRY_AFTER, HeaderValue::from(reset));
+        }
+    }
+}
+
+/// Calls each caller may make per hour.
+///
+/// Like [`RevokedTokens`](super::RevokedTokens), counts are held in
+/// memory: each server counts the calls it answers, and counts start over
+/// on restart.
+#[derive(Debug)]
+pub struct ApiQuota {
+    limit: u32,
+    windows: Mutex<HashMap<Caller, (DateTime<Utc>, u32)>>,
+}
+
+impl ApiQuota {
+    /// Creates a quota of `limit` calls per caller per hour.
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a call against the caller's quota, unless it is used up.
+    pub fn take(&self, caller: Caller, now: DateTime<Utc>) -> QuotaUsage {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows.retain(|_, (resets_at, _)| *resets_at > now);
+
+        let (resets_at, used) = windows
+            .entry(caller)
+            .or_insert((now + TimeDelta::hours(1), 0));
+        let exceeded = *used >= self.limit;
+        if !exceeded {
+            *used += 1;
+        }
+
+        QuotaUsage {
+            limit: self.limit,
+            remaining: self.limit - *used,
+            resets_at: *resets_at,
+            exceeded,
+        }
+    }
+}
+
+/// Samples authenticated calls for the usage report and enforces the
+/// quota, if any.
+pub async fn track_usage(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(user) = request.extensions().get::<AuthUser>().cloned() else {
+        return next.run(request).await;
+    };
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |p| p.as_str().to_string());
+
+    let started = Instant::now();
+    let now = Utc::now();
+    let quota = state
+        .api_quota
+        .as_ref()
+        .map(|quota| quota.take(Caller::of(&user), now));
+
+    let mut response = match quota {
+        Some(usage) if usage.exceeded => ApiError::TooManyRequests(format!(
+            "API quota of {} calls an hour used up",
+            usage.limit
+        ))
+        .into_response(),
+        _ => next.run(request).await,
+    };
+
+    state.api_usage_service.record(&ApiCall {
+        user_id: user.id,
+        api_key_id: user.api_key_id,
+        method,
+        route,
+        status: response.status().as_u16(),
+        elapsed: started.elapsed(),
+        at: now,
+    });
+    if let Some(usage) = quota {
+        usage.apply(&mut response, now);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_per_caller_and_hour() {
+        let quota = ApiQuota::new(2);
+        let now = Utc::now();
+        let key = Caller::ApiKey(1);
+
+        assert_eq!(quota.take(key, now).remaining, 1);
+        let used = quota.take(key, now + TimeDelta::minutes(1));
+        assert_eq!((used.remaining, used.exceeded), (0, false));
+        let refused = quota.take(key, now + TimeDelta::minutes(2));
+        assert!(refused.exceeded);
+        assert_eq!(refused.resets_at, now + TimeDelta::hours(1));
+
+        // The key's user has a quota of their own, and the window starts over
+        assert!(!quota.take(Caller::User(1), now).exceeded);
+        assert_eq!(quota.take(key, now + TimeDelta::hours(1)).remaining, 1);
+    }
+}
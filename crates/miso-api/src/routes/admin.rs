@@ -0,0 +1,110 @@
+//! Administration route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+
+use chrono::Utc;
+use miso_application::dto::{
+    ApiUsageFilter, ApiUsageReportRow, ArchivalReport, AuditChainVerification, LogArchiveResponse, LogTableSizeResponse,
+    SampleListRebuildReport,
+};
+use miso_domain::entities::ArchivedLog;
+use serde::Deserialize;
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates administration routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/audit/verify", get(verify_audit_chains))
+        .route("/sample-list/rebuild", post(rebuild_sample_list))
+        .route("/archives", get(list_archives))
+        .route("/archives/run", post(archive_logs))
+        .route("/archives/sizes", get(log_sizes))
+        .route("/archives/{id}/restore", post(restore_archive))
+        .route("/api-usage", get(api_usage))
+}
+
+/// Query parameters for listing log archives.
+#[derive(Debug, Deserialize)]
+struct ArchiveListQuery {
+    /// "audit_log", "change_log" or "storage_audit"
+    log: ArchivedLog,
+}
+
+/// Walk the audit log and change log hash chains and report the first
+/// entry of each that doesn't fit, for compliance audits.
+async fn verify_audit_chains(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<AuditChainVerification>, ApiError> {
+    let verification = state.audit_service.verify_chains().await?;
+    Ok(Json(verification))
+}
+
+/// Rebuild the sample list read model and its counts from the samples,
+/// projects, QC results and boxes they are drawn from.
+async fn rebuild_sample_list(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<SampleListRebuildReport>, ApiError> {
+    let report = state.sample_list_service.rebuild().await?;
+    Ok(Json(report))
+}
+
+/// List the archives of a log, newest first.
+async fn list_archives(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Query(query): Query<ArchiveListQuery>,
+) -> Result<Json<Vec<LogArchiveResponse>>, ApiError> {
+    let archives = state.retention_service.list_archives(query.log).await?;
+    Ok(Json(archives))
+}
+
+/// Archive every log row past its retention period now, rather than
+/// waiting for the nightly run.
+async fn archive_logs(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<ArchivalReport>, ApiError> {
+    let report = state.retention_service.archive_expired().await?;
+    Ok(Json(report))
+}
+
+/// Report how many rows each log holds and how many are archived.
+async fn log_sizes(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<Vec<LogTableSizeResponse>>, ApiError> {
+    let sizes = state.retention_service.sizes().await?;
+    Ok(Json(sizes))
+}
+
+/// Put an archive's rows back into their table.
+async fn restore_archive(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Path(id): Path<i32>,
+) -> Result<Json<LogArchiveResponse>, ApiError> {
+    let archive = state.retention_service.restore(id).await?;
+    Ok(Json(archive))
+}
+
+/// Report API calls per user, key and endpoint, busiest first; the last
+/// day's unless a period is given.
+async fn api_usage(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Query(filter): Query<ApiUsageFilter>,
+) -> Result<Json<Vec<ApiUsageReportRow>>, ApiError> {
+    let report = state.api_usage_service.report(filter, Utc::now()).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,58 @@
+//! API key management route handlers.
+
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use miso_domain::entities::User;
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates API key routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_keys).post(create_key))
+        .route("/{id}", delete(revoke_key))
+}
+
+/// List all API keys, newest first.
+async fn list_keys(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<Vec<ApiKeyResponse>>, ApiError> {
+    let keys = state.api_key_service.list_keys().await?;
+    Ok(Json(keys))
+}
+
+/// Create an API key acting for the current user. The key is only shown
+/// in this response.
+async fn create_key(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Extension(account): Extension<User>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreatedApiKeyResponse>, ApiError> {
+    request.validate()?;
+
+    let key = state.api_key_service.create_key(request, &account).await?;
+
+    Ok(Json(key))
+}
+
+/// Revoke an API key.
+async fn revoke_key(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<ApiKeyResponse>, ApiError> {
+    let key = state.api_key_service.revoke_key(id).await?;
+    Ok(Json(key))
+}
@@ -0,0 +1,106 @@
+//! Automatic archiving rule route handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    ArchivePlanResponse, ArchiveRuleResponse, AutoArchivalResponse, CreateArchiveRuleRequest,
+    UpdateArchiveRuleRequest,
+};
+use miso_application::ArchiveRuleService;
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates archiving rule routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_rules).post(create_rule))
+        .route("/{id}", put(update_rule))
+        .route("/{id}/preview", get(preview_rule))
+        .route("/{id}/archivals", get(list_archivals))
+        .route("/archivals/{id}/restore", post(restore_archival))
+}
+
+fn rules(state: &AppState) -> Result<&Arc<ArchiveRuleService>, ApiError> {
+    state
+        .archive_rule_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Runs are not available".to_string()))
+}
+
+/// List archiving rules.
+async fn list_rules(
+    State(state): State<AppState>,
+    _user: RequireRole<LabManager>,
+) -> Result<Json<Vec<ArchiveRuleResponse>>, ApiError> {
+    Ok(Json(rules(&state)?.list().await?))
+}
+
+/// Create an archiving rule.
+async fn create_rule(
+    State(state): State<AppState>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreateArchiveRuleRequest>,
+) -> Result<(StatusCode, Json<ArchiveRuleResponse>), ApiError> {
+    request.validate()?;
+
+    let rule = rules(&state)?.create(request, &user.username).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Change, pause or resume an archiving rule.
+async fn update_rule(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<UpdateArchiveRuleRequest>,
+) -> Result<Json<ArchiveRuleResponse>, ApiError> {
+    request.validate()?;
+
+    let rule = rules(&state)?.update(id, request, &user.username).await?;
+
+    Ok(Json(rule))
+}
+
+/// List what evaluating a rule now would archive and announce.
+async fn preview_rule(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<LabManager>,
+) -> Result<Json<ArchivePlanResponse>, ApiError> {
+    Ok(Json(rules(&state)?.preview(id).await?))
+}
+
+/// List the items a rule archived, most recent first.
+async fn list_archivals(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Technician>,
+) -> Result<Json<Vec<AutoArchivalResponse>>, ApiError> {
+    Ok(Json(rules(&state)?.archivals(id).await?))
+}
+
+/// Take an item a rule archived back out of the archive.
+async fn restore_archival(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<AutoArchivalResponse>, ApiError> {
+    let archival = rules(&state)?
+        .restore(id, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(archival))
+}
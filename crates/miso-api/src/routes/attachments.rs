@@ -0,0 +1,155 @@
+//! Attachment route handlers, shared by samples, libraries and runs.
+
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, MethodRouter},
+    Json, Router,
+};
+
+use miso_application::dto::AttachmentResponse;
+use miso_domain::entities::AttachmentTarget;
+
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, LabManager, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Largest file that can be uploaded, in bytes.
+const MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+
+/// Creates attachment routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/{id}", get(get_attachment).delete(delete_attachment))
+        .route("/{id}/content", get(download_attachment))
+}
+
+/// Creates the `/{id}/attachments` route for one kind of entity.
+pub fn target_routes(target: AttachmentTarget) -> MethodRouter<AppState> {
+    get(move |state: State<AppState>, user: AuthUser, id: Path<i32>| {
+        list_attachments(state, target, user, id)
+    })
+        .post(
+            move |state: State<AppState>,
+                  user: RequireRole<Technician>,
+                  id: Path<i32>,
+                  multipart: Multipart| upload_attachment(state, target, user, id, multipart),
+        )
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+}
+
+/// List the files attached to an entity, newest first.
+async fn list_attachments(
+    State(state): State<AppState>,
+    target: AttachmentTarget,
+    user: AuthUser,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<AttachmentResponse>>, ApiError> {
+    let attachments = state
+        .attachment_service
+        .list(target, id, Some(user.requester()))
+        .await?;
+    Ok(Json(attachments))
+}
+
+/// Upload a file to an entity as the multipart field "file".
+async fn upload_attachment(
+    State(state): State<AppState>,
+    target: AttachmentTarget,
+    user: RequireRole<Technician>,
+    Path(id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<AttachmentResponse>), ApiError> {
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+    {
+        if field.name() == Some("file") {
+            let filename = field.file_name().unwrap_or_default().to_string();
+            let content_type = field.content_type().unwrap_or_default().to_string();
+            let contents = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            file = Some((filename, content_type, contents));
+        }
+    }
+    let (filename, content_type, contents) =
+        file.ok_or_else(|| ApiError::BadRequest("No file was uploaded".to_string()))?;
+
+    let attachment = state
+        .attachment_service
+        .upload(
+            target,
+            id,
+            &filename,
+            &content_type,
+            &contents,
+            &user.username,
+            user.as_role(),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+/// Get an attachment's metadata.
+async fn get_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: AuthUser,
+) -> Result<Json<AttachmentResponse>, ApiError> {
+    let attachment = state
+        .attachment_service
+        .get(id, Some(user.requester()))
+        .await?;
+    Ok(Json(attachment))
+}
+
+/// Download an attachment.
+async fn download_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let file = state
+        .attachment_service
+        .download(id, Some(user.requester()))
+        .await?;
+    let filename: String = file
+        .attachment
+        .filename
+        .chars()
+        .map(|c| if c.is_control() || c == '"' || c == '\\' { '_' } else { c })
+        .collect();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        file.contents,
+    ))
+}
+
+/// Delete an attachment and its stored file.
+async fn delete_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state
+        .attachment_service
+        .delete(id, &user.username, user.as_role())
+        .await?;
+
+    Ok(())
+}
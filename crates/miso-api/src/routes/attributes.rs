@@ -0,0 +1,123 @@
+//! Custom attribute route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use miso_domain::entities::AttributeTarget;
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    AttributeDefinitionResponse, AttributeSchemaResponse, CreateAttributeDefinitionRequest,
+    UpdateAttributeDefinitionRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates custom attribute routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_definitions).post(create_definition))
+        .route(
+            "/{id}",
+            get(get_definition)
+                .put(update_definition)
+                .delete(delete_definition),
+        )
+        .route("/schema/{target}", get(get_schema))
+}
+
+/// Query parameters for listing custom attributes.
+#[derive(Debug, Deserialize)]
+pub struct ListDefinitionsQuery {
+    /// Only list attributes of this kind of entity
+    pub target: Option<AttributeTarget>,
+}
+
+/// Query parameters for a form schema.
+#[derive(Debug, Deserialize)]
+pub struct SchemaQuery {
+    /// Include the attributes of this project
+    pub project_id: Option<i32>,
+}
+
+/// List custom attributes.
+async fn list_definitions(
+    State(state): State<AppState>,
+    Query(query): Query<ListDefinitionsQuery>,
+) -> Result<Json<Vec<AttributeDefinitionResponse>>, ApiError> {
+    let definitions = state
+        .attribute_service
+        .list_definitions(query.target)
+        .await?;
+    Ok(Json(definitions))
+}
+
+/// Get a custom attribute by ID.
+async fn get_definition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<AttributeDefinitionResponse>, ApiError> {
+    let definition = state.attribute_service.get_definition(id).await?;
+    Ok(Json(definition))
+}
+
+/// Get the custom attributes a form should show, in order.
+async fn get_schema(
+    State(state): State<AppState>,
+    Path(target): Path<AttributeTarget>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<AttributeSchemaResponse>, ApiError> {
+    let schema = state
+        .attribute_service
+        .schema(target, query.project_id)
+        .await?;
+    Ok(Json(schema))
+}
+
+/// Define a custom attribute.
+async fn create_definition(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<CreateAttributeDefinitionRequest>,
+) -> Result<Json<AttributeDefinitionResponse>, ApiError> {
+    request.validate()?;
+
+    let definition = state.attribute_service.create_definition(request).await?;
+
+    Ok(Json(definition))
+}
+
+/// Update a custom attribute.
+async fn update_definition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<UpdateAttributeDefinitionRequest>,
+) -> Result<Json<AttributeDefinitionResponse>, ApiError> {
+    request.validate()?;
+
+    let definition = state
+        .attribute_service
+        .update_definition(id, request)
+        .await?;
+
+    Ok(Json(definition))
+}
+
+/// Remove a custom attribute.
+async fn delete_definition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+) -> Result<(), ApiError> {
+    state.attribute_service.delete_definition(id).await?;
+
+    Ok(())
+}
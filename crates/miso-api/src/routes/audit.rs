@@ -0,0 +1,71 @@
+//! Audit log route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use miso_application::dto::{AuditEntryResponse, EntitySnapshotResponse};
+use miso_domain::entities::AuditQuery;
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole},
+    state::AppState,
+};
+
+/// Creates audit log routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_audit_entries))
+        .route("/snapshots/{entity_type}/{entity_id}", get(get_entity_snapshot))
+}
+
+/// Query parameters for reading the audit log.
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    /// Entity type, e.g. "Sample"
+    pub entity_type: Option<String>,
+    /// Entity ID; needs `entity_type`
+    pub entity_id: Option<i32>,
+    /// Username of whoever made the changes
+    pub user: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List audit log entries, newest first.
+async fn list_audit_entries(
+    State(state): State<AppState>,
+    _user: RequireRole<LabManager>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<Vec<AuditEntryResponse>>, ApiError> {
+    let entries = state
+        .audit_service
+        .list_entries(AuditQuery {
+            entity_type: query.entity_type,
+            entity_id: query.entity_id,
+            changed_by: query.user,
+            limit: query.limit,
+            offset: query.offset,
+        })
+        .await?;
+
+    Ok(Json(entries))
+}
+
+/// Get an entity's current state from the event store's read model.
+async fn get_entity_snapshot(
+    State(state): State<AppState>,
+    _user: RequireRole<LabManager>,
+    Path((entity_type, entity_id)): Path<(String, i32)>,
+) -> Result<Json<EntitySnapshotResponse>, ApiError> {
+    let snapshot = state
+        .event_store_service
+        .get_snapshot(&entity_type, entity_id)
+        .await?;
+
+    Ok(Json(snapshot))
+}
@@ -0,0 +1,136 @@
+//! Authentication route handlers: login, single sign-on, logout and token
+//! refresh.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Redirect,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use miso_infrastructure::external::oidc::OidcAuthProvider;
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{CurrentUser, LoginRequest, TokenResponse};
+use miso_domain::entities::User;
+
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, Claims},
+    state::AppState,
+};
+
+/// Creates authentication routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/login", post(login))
+        .route("/oidc/login", get(oidc_login))
+        .route("/oidc/callback", get(oidc_callback))
+        .route("/logout", post(logout))
+        .route("/refresh", post(refresh))
+}
+
+/// Issues a token for a user.
+fn issue_token(state: &AppState, user: &User) -> Result<TokenResponse, ApiError> {
+    let claims = Claims::for_user(user, state.config.jwt_expiration_hours);
+    let token = claims
+        .encode(&state.config.jwt_secret)
+        .map_err(|e| ApiError::Internal(e.into()))?;
+
+    Ok(TokenResponse {
+        token,
+        token_type: "Bearer".to_string(),
+        expires_at: claims.expires_at(),
+        user: CurrentUser::from(user),
+    })
+}
+
+/// Log in with a username and password, checked against each login
+/// provider in turn.
+async fn login(
+    State(state): State<AppState>,
+    Json(request): Json<LoginRequest>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    request.validate()?;
+
+    let mut authenticated = None;
+    for provider in &state.auth_providers {
+        authenticated = provider
+            .authenticate(&request.username, &request.password)
+            .await?;
+        if authenticated.is_some() {
+            break;
+        }
+    }
+    let user = authenticated.ok_or(ApiError::Unauthorized)?;
+
+    tracing::info!("User {} logged in", user.username);
+    Ok(Json(issue_token(&state, &user)?))
+}
+
+/// Where the identity provider sends a user back to.
+#[derive(Debug, Deserialize)]
+struct OidcCallback {
+    code: String,
+    state: String,
+}
+
+fn oidc(state: &AppState) -> Result<&Arc<OidcAuthProvider>, ApiError> {
+    state
+        .oidc
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Single sign-on is not configured".to_string()))
+}
+
+/// Start a single sign-on login, sending the user to the identity provider.
+async fn oidc_login(State(state): State<AppState>) -> Result<Redirect, ApiError> {
+    let url = oidc(&state)?.start_login().await?;
+    Ok(Redirect::to(&url))
+}
+
+/// Finish a single sign-on login the identity provider has sent the user
+/// back from.
+async fn oidc_callback(
+    State(state): State<AppState>,
+    Query(callback): Query<OidcCallback>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let user = oidc(&state)?
+        .finish_login(&callback.code, &callback.state)
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+
+    tracing::info!("User {} logged in through single sign-on", user.username);
+    Ok(Json(issue_token(&state, &user)?))
+}
+
+/// Revoke the token the request was made with.
+async fn logout(State(state): State<AppState>, user: AuthUser) -> Result<StatusCode, ApiError> {
+    state.revoked_tokens.revoke(token(&user)?);
+
+    tracing::info!("User {} logged out", user.username);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Exchange the token the request was made with for a new one.
+async fn refresh(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Extension(account): Extension<User>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let old_token = token(&user)?;
+    let response = issue_token(&state, &account)?;
+    state.revoked_tokens.revoke(old_token);
+
+    Ok(Json(response))
+}
+
+/// Returns the token a request was made with; API keys have none to
+/// revoke or refresh.
+fn token(user: &AuthUser) -> Result<&Claims, ApiError> {
+    user.token.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("Requests made with an API key have no token".to_string())
+    })
+}
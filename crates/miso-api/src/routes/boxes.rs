@@ -0,0 +1,125 @@
+//! Storage box route handlers: creating boxes, placing, removing and
+//! moving items, and plate maps.
+
+use axum::{
+    extract::{Path, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    AssignBoxRequest, BoxImportResponse, BoxSummary, CreateBoxRequest, ImportBoxesRequest, ItemMoved,
+    MoveItemRequest, MoveToRequest, PlaceItemRequest, PlateMap, StoredItem,
+};
+
+use super::storage::{browser, units};
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates box routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_box))
+        .route("/import", post(import_boxes))
+        .route("/{id}", get(plate_map))
+        .route("/{id}/rack", put(assign_rack))
+        .route(
+            "/{id}/positions/{position}",
+            put(place_item).delete(remove_item),
+        )
+        .route("/{id}/positions/{position}/move", post(move_item))
+}
+
+/// Create an empty box.
+async fn create_box(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Json(request): Json<CreateBoxRequest>,
+) -> Result<Json<BoxSummary>, ApiError> {
+    request.validate()?;
+
+    let storage_box = browser(&state)?.create_box(request).await?;
+    Ok(Json(storage_box))
+}
+
+/// Put a box in a rack, or take it out of storage.
+async fn assign_rack(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Technician>,
+    Json(request): Json<AssignBoxRequest>,
+) -> Result<Json<BoxSummary>, ApiError> {
+    let storage_box = units(&state)?.assign_box(id, request).await?;
+    Ok(Json(storage_box))
+}
+
+/// Fill boxes from a `Box,Position,Barcode` CSV, creating those that
+/// don't exist yet.
+async fn import_boxes(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ImportBoxesRequest>,
+) -> Result<Json<BoxImportResponse>, ApiError> {
+    request.validate()?;
+
+    let importer = state
+        .box_import_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Box storage is not available".to_string()))?;
+    let imported = importer.import(request, &user.username).await?;
+    Ok(Json(imported))
+}
+
+/// Get a box as a dense grid of positions.
+async fn plate_map(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<PlateMap>, ApiError> {
+    let map = browser(&state)?.plate_map(id).await?;
+    Ok(Json(map))
+}
+
+/// Place an item at an empty position.
+async fn place_item(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Path((id, position)): Path<(i32, String)>,
+    Json(request): Json<PlaceItemRequest>,
+) -> Result<Json<StoredItem>, ApiError> {
+    let placed = browser(&state)?.place_item(id, &position, request).await?;
+    Ok(Json(placed))
+}
+
+/// Remove the item at a position.
+async fn remove_item(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Path((id, position)): Path<(i32, String)>,
+) -> Result<Json<StoredItem>, ApiError> {
+    let removed = browser(&state)?.remove_item(id, &position).await?;
+    Ok(Json(removed))
+}
+
+/// Move the item at a position, within the box or to another.
+async fn move_item(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Path((id, position)): Path<(i32, String)>,
+    Json(request): Json<MoveToRequest>,
+) -> Result<Json<ItemMoved>, ApiError> {
+    request.validate()?;
+
+    let moved = browser(&state)?
+        .move_item(MoveItemRequest {
+            from_box_id: id,
+            from_position: position,
+            to_box_id: request.to_box_id.unwrap_or(id),
+            to_position: request.to_position,
+        })
+        .await?;
+    Ok(Json(moved))
+}
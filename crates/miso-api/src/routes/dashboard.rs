@@ -0,0 +1,92 @@
+//! Dashboard statistics route handlers.
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+use miso_application::dto::DashboardStats;
+
+use crate::{error::ApiError, state::AppState};
+
+/// Days of history covered when none are requested.
+const DEFAULT_DAYS: u32 = 30;
+
+/// Most days of history a client may request.
+const MAX_DAYS: u32 = 365;
+
+/// How often the stream pushes fresh statistics.
+const STREAM_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Creates dashboard routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/stats", get(stats))
+        .route("/stream", get(stream_stats))
+}
+
+/// Query parameters for dashboard statistics.
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Days of history to cover, up to 365 (default 30)
+    pub days: Option<u32>,
+}
+
+impl StatsQuery {
+    fn days(&self) -> u32 {
+        self.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS)
+    }
+}
+
+/// Get the current dashboard statistics.
+async fn stats(
+    State(state): State<AppState>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<DashboardStats>, ApiError> {
+    let stats = state
+        .dashboard_service
+        .stats(Utc::now(), query.days())
+        .await?;
+    Ok(Json(stats))
+}
+
+/// Stream dashboard statistics as server-sent events.
+///
+/// A `stats` event is sent on connection and every 30 seconds after. If
+/// gathering fails, an `error` event carries the message and the stream
+/// carries on.
+async fn stream_stats(
+    State(state): State<AppState>,
+    Query(query): Query<StatsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let days = query.days();
+    let mut ticks = interval(STREAM_INTERVAL);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let updates = stream::unfold((state, ticks), move |(state, mut ticks)| async move {
+        ticks.tick().await;
+        let event = match state.dashboard_service.stats(Utc::now(), days).await {
+            Ok(stats) => Event::default()
+                .event("stats")
+                .json_data(&stats)
+                .unwrap_or_else(|e| Event::default().event("error").data(e.to_string())),
+            Err(e) => {
+                warn!("Failed to gather dashboard statistics: {}", e);
+                Event::default().event("error").data(e.to_string())
+            }
+        };
+        Some((Ok(event), (state, ticks)))
+    });
+
+    Sse::new(updates).keep_alive(KeepAlive::default())
+}
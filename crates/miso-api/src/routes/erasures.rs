@@ -0,0 +1,43 @@
+//! Withdrawn participant erasure route handlers.
+
+use axum::{extract::State, routing::get, Json, Router};
+use validator::Validate;
+
+use miso_application::dto::{EraseIdentityRequest, ErasureResponse};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates erasure routes.
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(list_erasures).post(erase_identity))
+}
+
+/// List erasures, newest first.
+async fn list_erasures(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<Vec<ErasureResponse>>, ApiError> {
+    let erasures = state.erasure_service.list_erasures().await?;
+    Ok(Json(erasures))
+}
+
+/// Erase an identity's identifying details, and those of the samples
+/// derived from it. This can't be undone.
+async fn erase_identity(
+    State(state): State<AppState>,
+    user: RequireRole<Admin>,
+    Json(request): Json<EraseIdentityRequest>,
+) -> Result<Json<ErasureResponse>, ApiError> {
+    request.validate()?;
+
+    let erasure = state
+        .erasure_service
+        .erase_identity(request, &user.username)
+        .await?;
+
+    Ok(Json(erasure))
+}
@@ -0,0 +1,113 @@
+//! Export template route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateExportTemplateRequest, ExportFilter, ExportTemplateResponse, UpdateExportTemplateRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, AuthUser, RequireRole},
+    state::AppState,
+};
+
+/// Creates export template routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_templates).post(create_template))
+        .route(
+            "/{id}",
+            get(get_template).put(update_template).delete(delete_template),
+        )
+        .route("/{id}/export", get(render_export))
+}
+
+/// List all export templates.
+async fn list_templates(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ExportTemplateResponse>>, ApiError> {
+    let templates = state.export_service.list_templates().await?;
+    Ok(Json(templates))
+}
+
+/// Get an export template by ID.
+async fn get_template(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ExportTemplateResponse>, ApiError> {
+    let template = state.export_service.get_template(id).await?;
+    Ok(Json(template))
+}
+
+/// Create an export template.
+async fn create_template(
+    State(state): State<AppState>,
+    user: RequireRole<Admin>,
+    Json(request): Json<CreateExportTemplateRequest>,
+) -> Result<Json<ExportTemplateResponse>, ApiError> {
+    request.validate()?;
+
+    let template = state
+        .export_service
+        .create_template(request, &user.username)
+        .await?;
+
+    Ok(Json(template))
+}
+
+/// Update an export template.
+async fn update_template(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<UpdateExportTemplateRequest>,
+) -> Result<Json<ExportTemplateResponse>, ApiError> {
+    request.validate()?;
+
+    let template = state.export_service.update_template(id, request).await?;
+
+    Ok(Json(template))
+}
+
+/// Delete an export template.
+async fn delete_template(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+) -> Result<(), ApiError> {
+    state.export_service.delete_template(id).await?;
+
+    Ok(())
+}
+
+/// Render an export file using a saved template.
+async fn render_export(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: AuthUser,
+    Query(filter): Query<ExportFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let file = state
+        .export_service
+        .render(id, filter, Some(user.requester()))
+        .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name),
+            ),
+        ],
+        file.body,
+    ))
+}
@@ -0,0 +1,143 @@
+//! Extraction batch route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateExtractionBatchRequest, ExtractionBatchFilter, ExtractionBatchResponse,
+    ExtractionBatchSamplesRequest, ExtractionYieldFilter, ExtractionYieldReport,
+    UpdateExtractionBatchRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates extraction batch routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_batches).post(create_batch))
+        .route("/yields", get(yield_report))
+        .route(
+            "/{id}",
+            get(get_batch).put(update_batch).delete(delete_batch),
+        )
+        .route("/{id}/samples", post(add_samples))
+        .route("/{id}/samples/remove", post(remove_samples))
+}
+
+/// List extraction batches, most recently extracted first, or the batch of
+/// a sample.
+async fn list_batches(
+    State(state): State<AppState>,
+    Query(filter): Query<ExtractionBatchFilter>,
+) -> Result<Json<Vec<ExtractionBatchResponse>>, ApiError> {
+    let batches = state.extraction_batch_service.list_batches(filter).await?;
+    Ok(Json(batches))
+}
+
+/// Record an extraction batch with its samples and reagent blanks.
+async fn create_batch(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateExtractionBatchRequest>,
+) -> Result<(StatusCode, Json<ExtractionBatchResponse>), ApiError> {
+    request.validate()?;
+
+    let batch = state
+        .extraction_batch_service
+        .create_batch(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(batch)))
+}
+
+/// Yield distributions by extraction batch and kit lot.
+async fn yield_report(
+    State(state): State<AppState>,
+    Query(filter): Query<ExtractionYieldFilter>,
+) -> Result<Json<ExtractionYieldReport>, ApiError> {
+    let report = state.extraction_batch_service.yield_report(filter).await?;
+    Ok(Json(report))
+}
+
+/// Get an extraction batch.
+async fn get_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ExtractionBatchResponse>, ApiError> {
+    let batch = state.extraction_batch_service.get_batch(id).await?;
+    Ok(Json(batch))
+}
+
+/// Correct an extraction batch's details.
+async fn update_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateExtractionBatchRequest>,
+) -> Result<Json<ExtractionBatchResponse>, ApiError> {
+    request.validate()?;
+
+    let batch = state
+        .extraction_batch_service
+        .update_batch(id, request, &user.username)
+        .await?;
+
+    Ok(Json(batch))
+}
+
+/// Delete an extraction batch, releasing its samples.
+async fn delete_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<(), ApiError> {
+    state
+        .extraction_batch_service
+        .delete_batch(id, &user.username)
+        .await?;
+
+    Ok(())
+}
+
+/// Add samples and reagent blanks to an extraction batch.
+async fn add_samples(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ExtractionBatchSamplesRequest>,
+) -> Result<Json<ExtractionBatchResponse>, ApiError> {
+    request.validate()?;
+
+    let batch = state
+        .extraction_batch_service
+        .add_samples(id, request, &user.username)
+        .await?;
+
+    Ok(Json(batch))
+}
+
+/// Remove samples and reagent blanks from an extraction batch.
+async fn remove_samples(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ExtractionBatchSamplesRequest>,
+) -> Result<Json<ExtractionBatchResponse>, ApiError> {
+    request.validate()?;
+
+    let batch = state
+        .extraction_batch_service
+        .remove_samples(id, request, &user.username)
+        .await?;
+
+    Ok(Json(batch))
+}
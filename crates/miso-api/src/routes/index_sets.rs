@@ -0,0 +1,56 @@
+//! Index catalog route handlers.
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{CreateIndexSetRequest, IndexSetResponse, IndexSetSummary};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates index catalog routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_index_sets).post(create_index_set))
+        .route("/{id}", get(get_index_set))
+}
+
+/// List all index sets.
+async fn list_index_sets(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<IndexSetSummary>>, ApiError> {
+    let sets = state.index_set_service.list_index_sets().await?;
+    Ok(Json(sets))
+}
+
+/// Get an index set and its indices by ID.
+async fn get_index_set(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<IndexSetResponse>, ApiError> {
+    let set = state.index_set_service.get_index_set(id).await?;
+    Ok(Json(set))
+}
+
+/// Register an index kit or plate.
+async fn create_index_set(
+    State(state): State<AppState>,
+    user: RequireRole<Admin>,
+    Json(request): Json<CreateIndexSetRequest>,
+) -> Result<Json<IndexSetResponse>, ApiError> {
+    request.validate()?;
+
+    let set = state
+        .index_set_service
+        .create_index_set(request, &user.username)
+        .await?;
+
+    Ok(Json(set))
+}
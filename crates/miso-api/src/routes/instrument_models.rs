@@ -0,0 +1,118 @@
+//! Instrument model catalog route handlers.
+
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateInstrumentModelRequest, InstrumentModelResponse, UpdateInstrumentModelRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates instrument model routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_models).post(create_model))
+        .route(
+            "/{id}",
+            get(get_model).put(update_model).delete(delete_model),
+        )
+        .route(
+            "/{id}/container-models/{container_model}",
+            put(link_container_model).delete(unlink_container_model),
+        )
+}
+
+/// List the instrument model catalog.
+async fn list_models(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<InstrumentModelResponse>>, ApiError> {
+    let models = state.instrument_model_service.list_models().await?;
+    Ok(Json(models))
+}
+
+/// Get an instrument model by ID.
+async fn get_model(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<InstrumentModelResponse>, ApiError> {
+    let model = state.instrument_model_service.get_model(id).await?;
+    Ok(Json(model))
+}
+
+/// Add an instrument model to the catalog.
+async fn create_model(
+    State(state): State<AppState>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<CreateInstrumentModelRequest>,
+) -> Result<Json<InstrumentModelResponse>, ApiError> {
+    request.validate()?;
+
+    let model = state.instrument_model_service.create_model(request).await?;
+
+    Ok(Json(model))
+}
+
+/// Update a catalogued instrument model.
+async fn update_model(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<UpdateInstrumentModelRequest>,
+) -> Result<Json<InstrumentModelResponse>, ApiError> {
+    request.validate()?;
+
+    let model = state
+        .instrument_model_service
+        .update_model(id, request)
+        .await?;
+
+    Ok(Json(model))
+}
+
+/// Mark a container model as compatible with an instrument model.
+async fn link_container_model(
+    State(state): State<AppState>,
+    Path((id, container_model)): Path<(i32, String)>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<InstrumentModelResponse>, ApiError> {
+    let model = state
+        .instrument_model_service
+        .link_container_model(id, &container_model)
+        .await?;
+
+    Ok(Json(model))
+}
+
+/// Mark a container model as no longer compatible with an instrument model.
+async fn unlink_container_model(
+    State(state): State<AppState>,
+    Path((id, container_model)): Path<(i32, String)>,
+    _user: RequireRole<Admin>,
+) -> Result<Json<InstrumentModelResponse>, ApiError> {
+    let model = state
+        .instrument_model_service
+        .unlink_container_model(id, &container_model)
+        .await?;
+
+    Ok(Json(model))
+}
+
+/// Remove an instrument model from the catalog.
+async fn delete_model(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+) -> Result<(), ApiError> {
+    state.instrument_model_service.delete_model(id).await?;
+
+    Ok(())
+}
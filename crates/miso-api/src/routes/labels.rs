@@ -0,0 +1,41 @@
+//! Label route handlers.
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use miso_application::dto::LabelUsageResponse;
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole},
+    state::AppState,
+};
+
+/// Creates label routes.
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/usage", get(list_label_usage))
+}
+
+/// Query parameters for label usage.
+#[derive(Debug, Deserialize)]
+pub struct LabelUsageQuery {
+    /// Start of the period; defaults to 30 days ago
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// List the print jobs of a period and the labels they used, for reordering
+/// label stock.
+async fn list_label_usage(
+    State(state): State<AppState>,
+    _user: RequireRole<LabManager>,
+    Query(query): Query<LabelUsageQuery>,
+) -> Result<Json<Vec<LabelUsageResponse>>, ApiError> {
+    let since = query.since.unwrap_or_else(|| Utc::now() - Duration::days(30));
+    let usage = state.label_print_service.usage(since).await?;
+    Ok(Json(usage))
+}
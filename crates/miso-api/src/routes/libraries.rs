@@ -0,0 +1,289 @@
+//! Library route handlers.
+
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateLibraryAliquotRequest, CreateLibraryRequest, ImportLibrariesRequest, LibraryAliquotResponse, LibraryImportResponse, LibraryReferenceGenome,
+    LibraryResponse, LibrarySummary, SetCatalogIndexRequest, SetLibraryIndexRequest,
+    UpdateLibraryRequest,
+};
+use miso_domain::entities::{AttachmentTarget, QcTarget};
+
+use super::{attachments, qcs};
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates library routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_library))
+        .route("/import", post(import_libraries))
+        .route("/aliquots/{id}", get(get_aliquot))
+        .route("/{id}", get(get_library).put(update_library))
+        .route("/{id}/index", put(set_library_index))
+        .route("/{id}/index/catalog", put(set_catalog_index))
+        .route("/{id}/archive", post(archive_library))
+        .route(
+            "/{id}/attachments",
+            attachments::target_routes(AttachmentTarget::Library),
+        )
+        .route("/{id}/aliquots", get(list_aliquots).post(create_aliquot))
+        .route("/{id}/qcs", qcs::routes(QcTarget::Library))
+        .route("/{id}/reference-genome", get(get_reference_genome))
+        .route("/barcode/{barcode}", get(get_library_by_barcode))
+        .route("/project/{project_id}", get(list_libraries_by_project))
+        .route("/sample/{sample_id}", get(list_libraries_by_sample))
+}
+
+/// Query parameters for listing libraries.
+#[derive(Debug, Deserialize)]
+pub struct ListLibrariesQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List libraries by project.
+async fn list_libraries_by_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<i32>,
+    user: Option<AuthUser>,
+    Query(query): Query<ListLibrariesQuery>,
+) -> Result<Json<Vec<LibrarySummary>>, ApiError> {
+    let libraries = state
+        .library_service
+        .list_libraries_by_project(project_id, query.limit, query.offset, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(libraries))
+}
+
+/// List the libraries made from a sample.
+async fn list_libraries_by_sample(
+    State(state): State<AppState>,
+    Path(sample_id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<Vec<LibrarySummary>>, ApiError> {
+    let libraries = state
+        .library_service
+        .list_libraries_by_sample(sample_id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(libraries))
+}
+
+/// List a library's aliquots.
+async fn list_aliquots(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<LibraryAliquotResponse>>, ApiError> {
+    let aliquots = state.library_aliquot_service.list_aliquots(id).await?;
+    Ok(Json(aliquots))
+}
+
+/// Take an aliquot of a library, withdrawing its volume.
+async fn create_aliquot(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateLibraryAliquotRequest>,
+) -> Result<(StatusCode, Json<LibraryAliquotResponse>), ApiError> {
+    request.validate()?;
+
+    let aliquot = state
+        .library_aliquot_service
+        .create_aliquot(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(aliquot)))
+}
+
+/// Get a library aliquot by ID.
+async fn get_aliquot(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<LibraryAliquotResponse>, ApiError> {
+    let aliquot = state.library_aliquot_service.get_aliquot(id).await?;
+    Ok(Json(aliquot))
+}
+
+/// Get a library by ID.
+async fn get_library(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    let library = state
+        .library_service
+        .get_library(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(library))
+}
+
+/// Get the reference genome a library is analysed against, its own or
+/// else its project's.
+async fn get_reference_genome(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<LibraryReferenceGenome>, ApiError> {
+    let genome = state.reference_genome_service.for_library(id).await?;
+    Ok(Json(genome))
+}
+
+/// Get a library by barcode.
+async fn get_library_by_barcode(
+    State(state): State<AppState>,
+    Path(barcode): Path<String>,
+    user: Option<AuthUser>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    let library = state
+        .library_service
+        .get_library_by_barcode(&barcode, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(library))
+}
+
+/// Prepare a new library from a sample.
+async fn create_library(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateLibraryRequest>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    request.validate()?;
+
+    let library = state
+        .library_service
+        .create_library(request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(library))
+}
+
+/// Query parameters for importing libraries.
+#[derive(Debug, Deserialize)]
+pub struct ImportLibrariesQuery {
+    /// Check the file without creating anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Create libraries from a CSV or TSV library sheet uploaded as the `file`
+/// field of a multipart form.
+///
+/// Responds 201 once every row has been created, 200 if a dry run found
+/// nothing wrong, and 422 with per-row errors otherwise, in which case no
+/// library has been created.
+async fn import_libraries(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Query(query): Query<ImportLibrariesQuery>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<LibraryImportResponse>), ApiError> {
+    let mut contents = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+    {
+        if field.name() == Some("file") {
+            contents = Some(
+                field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+            );
+        }
+    }
+    let contents =
+        contents.ok_or_else(|| ApiError::BadRequest("No file was uploaded".to_string()))?;
+
+    let request = ImportLibrariesRequest {
+        contents,
+        dry_run: query.dry_run,
+    };
+    let response = state
+        .library_service
+        .import_libraries(request, &user.username, user.as_role())
+        .await?;
+    let status = if response.applied {
+        StatusCode::CREATED
+    } else if response.results.iter().all(|r| r.errors.is_empty()) {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(response)))
+}
+
+/// Update a library.
+async fn update_library(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateLibraryRequest>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    request.validate()?;
+
+    let library = state
+        .library_service
+        .update_library(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(library))
+}
+
+/// Assign a library's index.
+async fn set_library_index(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<SetLibraryIndexRequest>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    request.validate()?;
+
+    let library = state
+        .library_service
+        .set_index(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(library))
+}
+
+/// Assign a library the index at a position of a catalog index set.
+async fn set_catalog_index(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<SetCatalogIndexRequest>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    request.validate()?;
+
+    let library = state
+        .library_service
+        .set_catalog_index(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(library))
+}
+
+/// Archive a library.
+async fn archive_library(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<LibraryResponse>, ApiError> {
+    let library = state
+        .library_service
+        .archive_library(id, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(library))
+}
@@ -0,0 +1,66 @@
+//! Maintenance window route handlers.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use miso_application::dto::{CreateMaintenanceRequest, MaintenanceWindowResponse};
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole},
+    state::AppState,
+};
+
+/// Creates maintenance window routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_maintenance).post(schedule_maintenance))
+        .route("/{id}", delete(cancel_maintenance))
+}
+
+/// List maintenance under way or planned, soonest first.
+async fn list_maintenance(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MaintenanceWindowResponse>>, ApiError> {
+    let windows = state
+        .facility_status_service
+        .list_maintenance(Utc::now())
+        .await?;
+    Ok(Json(windows))
+}
+
+/// Announce maintenance of a sequencer or the whole facility.
+async fn schedule_maintenance(
+    State(state): State<AppState>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreateMaintenanceRequest>,
+) -> Result<(StatusCode, Json<MaintenanceWindowResponse>), ApiError> {
+    request.validate()?;
+
+    let window = state
+        .facility_status_service
+        .schedule_maintenance(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(window)))
+}
+
+/// Cancel a maintenance window.
+async fn cancel_maintenance(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state
+        .facility_status_service
+        .cancel_maintenance(id, &user.username)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Metrics endpoint in the Prometheus text exposition format.
+
+use std::fmt::Write;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+use miso_application::dto::LogTableSizeResponse;
+use tracing::warn;
+
+use crate::state::AppState;
+
+/// Metrics endpoint.
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let mut body = String::new();
+
+    if let Some(database) = &state.database {
+        let queries = database.query_stats();
+        metric(
+            &mut body,
+            "miso_db_queries_total",
+            "counter",
+            "Queries executed",
+            queries.queries as f64,
+        );
+        metric(
+            &mut body,
+            "miso_db_query_failures_total",
+            "counter",
+            "Queries that returned an error",
+            queries.failures as f64,
+        );
+        metric(
+            &mut body,
+            "miso_db_slow_queries_total",
+            "counter",
+            "Queries at or above the slow-query threshold",
+            queries.slow as f64,
+        );
+        metric(
+            &mut body,
+            "miso_db_query_seconds_total",
+            "counter",
+            "Total query execution time",
+            queries.total_time.as_secs_f64(),
+        );
+
+        match database.pool_status().await {
+            Ok(pool) => {
+                metric(
+                    &mut body,
+                    "miso_db_pool_max_connections",
+                    "gauge",
+                    "Configured maximum pool size",
+                    pool.max_connections as f64,
+                );
+                let _ = writeln!(
+                    body,
+                    "# HELP miso_db_pool_connections Open pool connections by state\n\
+                     # TYPE miso_db_pool_connections gauge\n\
+                     miso_db_pool_connections{{state=\"idle\"}} {}\n\
+                     miso_db_pool_connections{{state=\"in_use\"}} {}",
+                    pool.idle, pool.in_use
+                );
+                metric(
+                    &mut body,
+                    "miso_db_pool_acquire_wait_seconds",
+                    "gauge",
+                    "Time taken to check out a connection at scrape time",
+                    pool.acquire_wait.as_secs_f64(),
+                );
+            }
+            Err(e) => warn!("Could not read connection pool status: {}", e),
+        }
+
+        match state.retention_service.sizes().await {
+            Ok(sizes) => {
+                let by_table = |value: fn(&LogTableSizeResponse) -> u64| {
+                    sizes
+                        .iter()
+                        .map(|size| (size.log.as_str(), value(size)))
+                        .collect::<Vec<_>>()
+                };
+                table_gauge(
+                    &mut body,
+                    "miso_log_table_rows",
+                    "Rows in each log table",
+                    &by_table(|size| size.rows),
+                );
+                table_gauge(
+                    &mut body,
+                    "miso_log_archived_rows",
+                    "Rows of each log moved to archive files",
+                    &by_table(|size| size.archived_rows),
+                );
+                table_gauge(
+                    &mut body,
+                    "miso_log_archives",
+                    "Archive files of each log",
+                    &by_table(|size| size.archives),
+                );
+            }
+            Err(e) => warn!("Could not read log table sizes: {}", e),
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+fn metric(body: &mut String, name: &str, kind: &str, help: &str, value: f64) {
+    let _ = writeln!(
+        body,
+        "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}"
+    );
+}
+
+fn table_gauge(body: &mut String, name: &str, help: &str, values: &[(&str, u64)]) {
+    let _ = writeln!(body, "# HELP {name} {help}\n# TYPE {name} gauge");
+    for (table, value) in values {
+        let _ = writeln!(body, "{name}{{table=\"{table}\"}} {value}");
+    }
+}
@@ -1,49 +1,145 @@
 //! API route handlers.
 
+pub mod admin;
+pub mod api_keys;
+pub mod archive_rules;
+pub mod attachments;
+pub mod attributes;
+pub mod audit;
+pub mod auth;
+pub mod boxes;
+pub mod dashboard;
+pub mod erasures;
+pub mod exports;
+pub mod extraction_batches;
 pub mod health;
+pub mod index_sets;
+pub mod instrument_models;
+pub mod labels;
+pub mod libraries;
+pub mod maintenance;
+pub mod metrics;
+#[cfg(feature = "ssr")]
+pub mod pages;
+pub mod panels;
+pub mod pools;
 pub mod projects;
+pub mod qcs;
+pub mod reagents;
+pub mod reference_genomes;
+pub mod requisitions;
+pub mod runs;
 pub mod samples;
 pub mod scanner;
+pub mod search;
+pub mod sequencers;
+pub mod status;
+pub mod storage;
+pub mod transfers;
+pub mod worksets;
 
-use axum::{routing::get, Router};
+use axum::{body::Body, http::Request, middleware, routing::get, Router};
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
 
 use crate::AppState;
-use miso_domain::repositories::{ProjectRepository, SampleRepository};
 
 /// Creates the API router.
-pub fn create_router<PR, SR>(state: AppState<PR, SR>) -> Router
-where
-    PR: ProjectRepository + 'static,
-    SR: SampleRepository + 'static,
+pub fn create_router(state: AppState) -> Router
 {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    Router::new()
+    let router = Router::new()
         // Health check
         .route("/health", get(health::health_check))
         .route("/ready", get(health::readiness_check))
+        .route("/metrics", get(metrics::metrics))
+        // Public facility status
+        .route("/status", get(status::status))
+        .route("/status.html", get(status::status_page))
         // API v1 routes
-        .nest("/api/v1", api_v1_routes())
+        .nest(
+            "/api/v1",
+            api_v1_routes()
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    crate::middleware::track_usage,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    crate::middleware::authenticate,
+                )),
+        );
+
+    // Everything else is a page, when the server renders them
+    #[cfg(feature = "ssr")]
+    let router = router.fallback_service(pages::routes(&state));
+
+    router
         // Middleware
-        .layer(TraceLayer::new_for_http())
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(cors)
         .with_state(state)
 }
 
+/// Creates the tracing span for a request, tagged with its request ID so
+/// that everything logged while handling it (including slow queries) can be
+/// correlated.
+fn request_span(request: &Request<Body>) -> tracing::Span {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id,
+    )
+}
+
 /// API v1 routes.
-fn api_v1_routes<PR, SR>() -> Router<AppState<PR, SR>>
-where
-    PR: ProjectRepository + 'static,
-    SR: SampleRepository + 'static,
+fn api_v1_routes() -> Router<AppState>
 {
     Router::new()
+        .nest("/auth", auth::routes())
+        .nest("/api-keys", api_keys::routes())
         .nest("/projects", projects::routes())
         .nest("/samples", samples::routes())
+        .nest("/libraries", libraries::routes())
+        .nest("/index-sets", index_sets::routes())
+        .nest("/panels", panels::routes())
+        .nest("/reference-genomes", reference_genomes::routes())
+        .nest("/pools", pools::routes())
+        .nest("/runs", runs::routes())
         .nest("/scanner", scanner::routes())
+        .nest("/export-templates", exports::routes())
+        .nest("/instrument-models", instrument_models::routes())
+        .nest("/sequencers", sequencers::routes())
+        .nest("/labels", labels::routes())
+        .nest("/storage", storage::routes())
+        .nest("/boxes", boxes::routes())
+        .nest("/worksets", worksets::routes())
+        .nest("/transfers", transfers::routes())
+        .nest("/requisitions", requisitions::routes())
+        .nest("/extraction-batches", extraction_batches::routes())
+        .nest("/reagents", reagents::routes())
+        .nest("/maintenance", maintenance::routes())
+        .nest("/archive-rules", archive_rules::routes())
+        .nest("/attachments", attachments::routes())
+        .nest("/dashboard", dashboard::routes())
+        .nest("/search", search::routes())
+        .nest("/attributes", attributes::routes())
+        .nest("/audit", audit::routes())
+        .nest("/erasures", erasures::routes())
+        .nest("/admin", admin::routes())
 }
 
@@ -0,0 +1,37 @@
+//! Server-rendered pages of the web frontend (`ssr` feature).
+
+use axum::Router;
+use leptos::config::get_configuration;
+use leptos::prelude::*;
+use leptos_axum::{file_and_error_handler, generate_route_list, LeptosRoutes};
+use miso_frontend::page_data::PageServices;
+use miso_frontend::{shell, App};
+
+use crate::AppState;
+
+/// Creates the router rendering the frontend's pages and answering their
+/// server functions. Anything else is served from the compiled site, as
+/// laid out by cargo-leptos.
+pub fn routes(state: &AppState) -> Router {
+    let options = get_configuration(None)
+        .expect("Failed to load Leptos configuration")
+        .leptos_options;
+    let services = PageServices {
+        dashboard: state.dashboard_service.clone(),
+        storage: state.storage_browser_service.clone(),
+        runs: state.run_monitor_service.clone(),
+    };
+
+    Router::new()
+        .leptos_routes_with_context(
+            &options,
+            generate_route_list(App),
+            move || provide_context(services.clone()),
+            {
+                let options = options.clone();
+                move || shell(options.clone())
+            },
+        )
+        .fallback(file_and_error_handler(shell))
+        .with_state(options)
+}
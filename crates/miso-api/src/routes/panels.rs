@@ -0,0 +1,94 @@
+//! Targeted panel route handlers.
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{CreatePanelRequest, PanelResponse, PanelVersionRequest};
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole},
+    state::AppState,
+};
+
+/// Creates panel routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_panels).post(create_panel))
+        .route("/{id}", get(get_panel))
+        .route("/{id}/versions", post(create_version))
+        .route("/{id}/bed", get(download_bed))
+}
+
+/// List every version of every panel.
+async fn list_panels(State(state): State<AppState>) -> Result<Json<Vec<PanelResponse>>, ApiError> {
+    let panels = state.panel_service.list_panels().await?;
+    Ok(Json(panels))
+}
+
+/// Get a panel version by ID.
+async fn get_panel(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<PanelResponse>, ApiError> {
+    let panel = state.panel_service.get_panel(id).await?;
+    Ok(Json(panel))
+}
+
+/// Create the first version of a panel.
+async fn create_panel(
+    State(state): State<AppState>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreatePanelRequest>,
+) -> Result<Json<PanelResponse>, ApiError> {
+    request.validate()?;
+
+    let panel = state
+        .panel_service
+        .create_panel(request, &user.username)
+        .await?;
+
+    Ok(Json(panel))
+}
+
+/// Record a new version of a panel with revised target regions.
+async fn create_version(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<PanelVersionRequest>,
+) -> Result<Json<PanelResponse>, ApiError> {
+    request.validate()?;
+
+    let panel = state
+        .panel_service
+        .create_version(id, request, &user.username)
+        .await?;
+
+    Ok(Json(panel))
+}
+
+/// Download a panel version's BED file.
+async fn download_bed(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, ApiError> {
+    let file = state.panel_service.bed_file(id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name),
+            ),
+        ],
+        file.body,
+    ))
+}
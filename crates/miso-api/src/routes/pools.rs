@@ -0,0 +1,206 @@
+//! Pool route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    AddPoolElementRequest, BalancePoolRequest, CreatePoolRequest, IndexAssignmentResponse,
+    PoolBalanceResponse, PoolResponse, PoolSummary, PoolValidationResponse,
+    PoolingWorksheetRequest, PoolingWorksheetResponse, SuggestIndicesRequest,
+};
+use miso_domain::entities::QcTarget;
+
+use super::qcs;
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates pool routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_pools).post(create_pool))
+        .route("/index-suggestions", post(suggest_indices))
+        .route("/worksheet", post(pooling_worksheet))
+        .route("/{id}", get(get_pool))
+        .route("/{id}/elements", post(add_pool_element))
+        .route(
+            "/{id}/elements/{library_aliquot_id}",
+            delete(remove_pool_element),
+        )
+        .route("/{id}/validation", get(validate_pool))
+        .route("/{id}/qcs", qcs::routes(QcTarget::Pool))
+        .route("/{id}/proportions", get(balance_pool).put(apply_balance))
+}
+
+/// Query parameters for listing pools.
+#[derive(Debug, Deserialize)]
+pub struct ListPoolsQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List pools, newest first.
+async fn list_pools(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Query(query): Query<ListPoolsQuery>,
+) -> Result<Json<Vec<PoolSummary>>, ApiError> {
+    let pools = state
+        .pool_service
+        .list_pools(query.limit, query.offset, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(pools))
+}
+
+/// Get a pool by ID.
+async fn get_pool(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<PoolResponse>, ApiError> {
+    let pool = state
+        .pool_service
+        .get_pool(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(pool))
+}
+
+/// Create an empty pool.
+async fn create_pool(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreatePoolRequest>,
+) -> Result<Json<PoolResponse>, ApiError> {
+    request.validate()?;
+
+    let pool = state
+        .pool_service
+        .create_pool(request, &user.username)
+        .await?;
+
+    Ok(Json(pool))
+}
+
+/// Add a library aliquot to a pool.
+///
+/// Responds 409 with the colliding libraries if the library's index is too
+/// close to one already in the pool.
+async fn add_pool_element(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<AddPoolElementRequest>,
+) -> Result<Json<PoolResponse>, ApiError> {
+    request.validate()?;
+
+    let pool = state
+        .pool_service
+        .add_element(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(pool))
+}
+
+/// Remove a library aliquot from a pool.
+async fn remove_pool_element(
+    State(state): State<AppState>,
+    Path((id, library_aliquot_id)): Path<(i32, i32)>,
+    user: RequireRole<Technician>,
+) -> Result<Json<PoolResponse>, ApiError> {
+    let pool = state
+        .pool_service
+        .remove_element(id, library_aliquot_id, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(pool))
+}
+
+/// Check whether a pool's libraries can be sequenced together.
+async fn validate_pool(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<PoolValidationResponse>, ApiError> {
+    let validation = state
+        .pool_service
+        .validate_pool(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(validation))
+}
+
+/// Suggest catalog indices for libraries about to be pooled, keeping any
+/// they have, so that none collide.
+///
+/// Responds 409 if the libraries' own indices already collide, and 422 if
+/// the sets hold no assignment far enough apart.
+async fn suggest_indices(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Json(request): Json<SuggestIndicesRequest>,
+) -> Result<Json<IndexAssignmentResponse>, ApiError> {
+    request.validate()?;
+
+    let assignment = state
+        .pool_service
+        .suggest_indices(request, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(assignment))
+}
+
+/// Work out the volume of each library to pipette to pool them equimolar
+/// at a target molarity and volume, prediluting any too concentrated to
+/// pipette.
+async fn pooling_worksheet(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Json(request): Json<PoolingWorksheetRequest>,
+) -> Result<Json<PoolingWorksheetResponse>, ApiError> {
+    request.validate()?;
+
+    let worksheet = state
+        .pool_service
+        .pooling_worksheet(request, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(worksheet))
+}
+
+/// Propose each library's share of a pool from the reads its design needs
+/// and the reads a lane gives, with volumes if a target molarity is given.
+async fn balance_pool(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+    Query(request): Query<BalancePoolRequest>,
+) -> Result<Json<PoolBalanceResponse>, ApiError> {
+    request.validate()?;
+
+    let balance = state
+        .pool_service
+        .balance_pool(id, request, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(balance))
+}
+
+/// Set each library's share of a pool, and its volume, to those proposed.
+async fn apply_balance(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<BalancePoolRequest>,
+) -> Result<Json<PoolResponse>, ApiError> {
+    request.validate()?;
+
+    let pool = state
+        .pool_service
+        .apply_balance(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(pool))
+}
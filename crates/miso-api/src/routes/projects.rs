@@ -9,21 +9,47 @@ use serde::Deserialize;
 use validator::Validate;
 
 use miso_application::dto::{
-    CreateProjectRequest, ProjectResponse, ProjectSummary, UpdateProjectRequest,
+    ActivityFeedResponse, AddProjectMemberRequest, CreateDeliveryTargetRequest,
+    CreateProjectRequest, CreateSubprojectRequest, DeliveryFilter, DeliveryResponse,
+    DeliveryTargetResponse, LockProjectRequest, ProjectMemberResponse, ProjectResponse,
+    ProjectSummary, RecordDeliveryRequest, SubprojectResponse, UpdateDeliveryTargetRequest,
+    UpdateProjectRequest, UpdateSubprojectRequest, VerifyDeliveryRequest,
 };
-use miso_domain::repositories::{ProjectRepository, SampleRepository};
 
-use crate::{error::ApiError, middleware::AuthUser, state::AppState};
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, LabManager, RequireRole, Technician},
+    state::AppState,
+};
 
 /// Creates project routes.
-pub fn routes<PR, SR>() -> Router<AppState<PR, SR>>
-where
-    PR: ProjectRepository + 'static,
-    SR: SampleRepository + 'static,
+pub fn routes() -> Router<AppState>
 {
     Router::new()
         .route("/", get(list_projects).post(create_project))
-        .route("/:id", get(get_project).put(update_project).delete(delete_project))
+        .route("/{id}", get(get_project).put(update_project).delete(delete_project))
+        .route("/{id}/activity", get(get_project_activity))
+        .route("/{id}/lock", put(lock_project).delete(unlock_project))
+        .route("/{id}/members", get(list_members).post(add_member))
+        .route("/{id}/members/{user_id}", delete(remove_member))
+        .route("/{id}/subprojects", get(list_subprojects).post(create_subproject))
+        .route(
+            "/{id}/subprojects/{subproject_id}",
+            put(update_subproject).delete(delete_subproject),
+        )
+        .route(
+            "/{id}/delivery-targets",
+            get(list_delivery_targets).post(create_delivery_target),
+        )
+        .route(
+            "/{id}/delivery-targets/{target_id}",
+            put(update_delivery_target),
+        )
+        .route("/{id}/deliveries", get(list_deliveries).post(record_delivery))
+        .route(
+            "/{id}/deliveries/{delivery_id}/verification",
+            post(verify_delivery),
+        )
 }
 
 /// Query parameters for listing projects.
@@ -33,9 +59,17 @@ pub struct ListProjectsQuery {
     pub offset: Option<u64>,
 }
 
+/// Query parameters for a project's activity feed.
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub limit: Option<u64>,
+    /// Cursor from the previous page's `next_cursor`
+    pub before: Option<String>,
+}
+
 /// List all projects.
-async fn list_projects<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn list_projects(
+    State(state): State<AppState>,
     Query(query): Query<ListProjectsQuery>,
 ) -> Result<Json<Vec<ProjectSummary>>, ApiError> {
     let projects = state
@@ -47,17 +81,31 @@ async fn list_projects<PR: ProjectRepository, SR: SampleRepository>(
 }
 
 /// Get a project by ID.
-async fn get_project<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn get_project(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<Json<ProjectResponse>, ApiError> {
     let project = state.project_service.get_project(id).await?;
     Ok(Json(project))
 }
 
+/// Get a project's recent activity, newest first.
+async fn get_project_activity(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: AuthUser,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<ActivityFeedResponse>, ApiError> {
+    let feed = state
+        .activity_service
+        .project_activity(id, query.before.as_deref(), query.limit, Some(user.requester()))
+        .await?;
+    Ok(Json(feed))
+}
+
 /// Create a new project.
-async fn create_project<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn create_project(
+    State(state): State<AppState>,
     user: AuthUser,
     Json(request): Json<CreateProjectRequest>,
 ) -> Result<Json<ProjectResponse>, ApiError> {
@@ -72,35 +120,247 @@ async fn create_project<PR: ProjectRepository, SR: SampleRepository>(
 }
 
 /// Update a project.
-async fn update_project<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn update_project(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
-    user: AuthUser,
+    user: RequireRole<Technician>,
     Json(request): Json<UpdateProjectRequest>,
 ) -> Result<Json<ProjectResponse>, ApiError> {
-    if !user.can_edit() {
-        return Err(ApiError::Forbidden);
-    }
-
     request.validate()?;
 
-    let project = state.project_service.update_project(id, request).await?;
+    let project = state
+        .project_service
+        .update_project(id, request, &user.username, user.as_role())
+        .await?;
 
     Ok(Json(project))
 }
 
 /// Delete a project.
-async fn delete_project<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn delete_project(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
-    user: AuthUser,
+    user: RequireRole<LabManager>,
 ) -> Result<(), ApiError> {
-    if !user.can_delete() {
-        return Err(ApiError::Forbidden);
-    }
+    state
+        .project_service
+        .delete_project(id, &user.username, user.as_role())
+        .await?;
+
+    Ok(())
+}
 
-    state.project_service.delete_project(id).await?;
+
+/// Lock a project's data against changes by anyone but admins.
+async fn lock_project(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<LockProjectRequest>,
+) -> Result<Json<ProjectResponse>, ApiError> {
+    request.validate()?;
+
+    let project = state
+        .project_service
+        .lock_project(id, request, &user.username)
+        .await?;
+
+    Ok(Json(project))
+}
+
+/// Lift a project's lock.
+async fn unlock_project(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<Json<ProjectResponse>, ApiError> {
+    let project = state
+        .project_service
+        .unlock_project(id, &user.username)
+        .await?;
+
+    Ok(Json(project))
+}
+
+/// List a project's members.
+async fn list_members(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: AuthUser,
+) -> Result<Json<Vec<ProjectMemberResponse>>, ApiError> {
+    let members = state.project_member_service.list_members(id).await?;
+    Ok(Json(members))
+}
+
+/// Add a user to a project.
+async fn add_member(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<AddProjectMemberRequest>,
+) -> Result<Json<ProjectMemberResponse>, ApiError> {
+    let member = state
+        .project_member_service
+        .add_member(id, request, &user.username)
+        .await?;
+
+    Ok(Json(member))
+}
+
+/// Remove a user from a project.
+async fn remove_member(
+    State(state): State<AppState>,
+    Path((id, user_id)): Path<(i32, i32)>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state
+        .project_member_service
+        .remove_member(id, user_id, &user.username)
+        .await?;
 
     Ok(())
 }
 
+/// List a project's subprojects.
+async fn list_subprojects(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<SubprojectResponse>>, ApiError> {
+    let subprojects = state.subproject_service.list(id).await?;
+    Ok(Json(subprojects))
+}
+
+/// Create a subproject of a project.
+async fn create_subproject(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreateSubprojectRequest>,
+) -> Result<Json<SubprojectResponse>, ApiError> {
+    request.validate()?;
+
+    let subproject = state
+        .subproject_service
+        .create(id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(subproject))
+}
+
+/// Rename a subproject or change its description.
+async fn update_subproject(
+    State(state): State<AppState>,
+    Path((id, subproject_id)): Path<(i32, i32)>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<UpdateSubprojectRequest>,
+) -> Result<Json<SubprojectResponse>, ApiError> {
+    request.validate()?;
+
+    let subproject = state
+        .subproject_service
+        .update(id, subproject_id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(subproject))
+}
+
+/// Delete a subproject, leaving its samples in the project.
+async fn delete_subproject(
+    State(state): State<AppState>,
+    Path((id, subproject_id)): Path<(i32, i32)>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state
+        .subproject_service
+        .delete(id, subproject_id, &user.username, user.as_role())
+        .await?;
+
+    Ok(())
+}
+
+/// List the places a project's data is released to.
+async fn list_delivery_targets(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<DeliveryTargetResponse>>, ApiError> {
+    let targets = state.delivery_service.list_targets(id).await?;
+    Ok(Json(targets))
+}
+
+/// Register a place a project's data is released to.
+async fn create_delivery_target(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreateDeliveryTargetRequest>,
+) -> Result<Json<DeliveryTargetResponse>, ApiError> {
+    request.validate()?;
+
+    let target = state
+        .delivery_service
+        .create_target(id, request, &user.username)
+        .await?;
+
+    Ok(Json(target))
+}
+
+/// Change or retire a delivery target.
+async fn update_delivery_target(
+    State(state): State<AppState>,
+    Path((id, target_id)): Path<(i32, i32)>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<UpdateDeliveryTargetRequest>,
+) -> Result<Json<DeliveryTargetResponse>, ApiError> {
+    request.validate()?;
+
+    let target = state
+        .delivery_service
+        .update_target(id, target_id, request, &user.username)
+        .await?;
+
+    Ok(Json(target))
+}
+
+/// List a project's deliveries, newest first.
+async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(filter): Query<DeliveryFilter>,
+) -> Result<Json<Vec<DeliveryResponse>>, ApiError> {
+    let deliveries = state.delivery_service.list_deliveries(id, filter).await?;
+    Ok(Json(deliveries))
+}
+
+/// Record that a run's data was copied to one of the project's targets.
+async fn record_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RecordDeliveryRequest>,
+) -> Result<Json<DeliveryResponse>, ApiError> {
+    request.validate()?;
+
+    let delivery = state
+        .delivery_service
+        .record_delivery(id, request, &user.username)
+        .await?;
+
+    Ok(Json(delivery))
+}
+
+/// Record whether a delivered copy matched its checksum manifest.
+async fn verify_delivery(
+    State(state): State<AppState>,
+    Path((id, delivery_id)): Path<(i32, i32)>,
+    user: RequireRole<Technician>,
+    Json(request): Json<VerifyDeliveryRequest>,
+) -> Result<Json<DeliveryResponse>, ApiError> {
+    request.validate()?;
+
+    let delivery = state
+        .delivery_service
+        .verify_delivery(id, delivery_id, request, &user.username)
+        .await?;
+
+    Ok(Json(delivery))
+}
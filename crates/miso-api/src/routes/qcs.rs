@@ -0,0 +1,62 @@
+//! QC measurement route handlers, shared by samples, libraries and pools.
+
+use axum::{
+    extract::{Path, State},
+    routing::{get, MethodRouter},
+    Json,
+};
+use validator::Validate;
+
+use miso_application::dto::{CreateQcRequest, QcResponse};
+use miso_domain::entities::QcTarget;
+
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates the `/{id}/qcs` route for one kind of entity.
+pub fn routes(target: QcTarget) -> MethodRouter<AppState> {
+    get(move |state: State<AppState>, user: Option<AuthUser>, id: Path<i32>| {
+        list_qcs(state, target, user, id)
+    })
+    .post(
+        move |state: State<AppState>,
+              user: RequireRole<Technician>,
+              id: Path<i32>,
+              request: Json<CreateQcRequest>| add_qc(state, target, user, id, request),
+    )
+}
+
+/// List an entity's QC measurements, oldest first.
+async fn list_qcs(
+    State(state): State<AppState>,
+    target: QcTarget,
+    user: Option<AuthUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<QcResponse>>, ApiError> {
+    let qcs = state
+        .qc_service
+        .list_qcs(target, id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(qcs))
+}
+
+/// Record a QC measurement of an entity.
+async fn add_qc(
+    State(state): State<AppState>,
+    target: QcTarget,
+    user: RequireRole<Technician>,
+    Path(id): Path<i32>,
+    Json(request): Json<CreateQcRequest>,
+) -> Result<Json<QcResponse>, ApiError> {
+    request.validate()?;
+
+    let qc = state
+        .qc_service
+        .add_qc(target, id, request, &user.username, user.as_role())
+        .await?;
+
+    Ok(Json(qc))
+}
@@ -0,0 +1,127 @@
+//! Reagent inventory route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateReagentLotRequest, ExpiringLotResponse, LowStockResponse, ReagentExpiryFilter,
+    ReagentLotFilter, ReagentLotResponse, UpdateReagentLotRequest,
+};
+use miso_application::DEFAULT_EXPIRY_DAYS;
+
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates reagent inventory routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_lots).post(create_lot))
+        .route("/expiring", get(expiring_lots))
+        .route("/low-stock", get(low_stock))
+        .route(
+            "/{id}",
+            get(get_lot).put(update_lot).delete(delete_lot),
+        )
+}
+
+/// List reagent lots by kit, soonest expiring first.
+async fn list_lots(
+    State(state): State<AppState>,
+    Query(filter): Query<ReagentLotFilter>,
+) -> Result<Json<Vec<ReagentLotResponse>>, ApiError> {
+    let lots = state.reagent_inventory_service.list_lots(filter).await?;
+    Ok(Json(lots))
+}
+
+/// Record a lot received into stock.
+async fn create_lot(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateReagentLotRequest>,
+) -> Result<(StatusCode, Json<ReagentLotResponse>), ApiError> {
+    request.validate()?;
+
+    let lot = state
+        .reagent_inventory_service
+        .create_lot(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(lot)))
+}
+
+/// Lots with units left that have expired or expire soon.
+async fn expiring_lots(
+    State(state): State<AppState>,
+    Query(filter): Query<ReagentExpiryFilter>,
+) -> Result<Json<Vec<ExpiringLotResponse>>, ApiError> {
+    let lots = state
+        .reagent_inventory_service
+        .expiring_soon(
+            filter.days.unwrap_or(DEFAULT_EXPIRY_DAYS),
+            Utc::now().date_naive(),
+        )
+        .await?;
+
+    Ok(Json(lots))
+}
+
+/// Kits at or below their reorder level.
+async fn low_stock(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<LowStockResponse>>, ApiError> {
+    let kits = state
+        .reagent_inventory_service
+        .low_stock(Utc::now().date_naive())
+        .await?;
+
+    Ok(Json(kits))
+}
+
+/// Get a reagent lot.
+async fn get_lot(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ReagentLotResponse>, ApiError> {
+    let lot = state.reagent_inventory_service.get_lot(id).await?;
+    Ok(Json(lot))
+}
+
+/// Correct a lot or record the units left.
+async fn update_lot(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateReagentLotRequest>,
+) -> Result<Json<ReagentLotResponse>, ApiError> {
+    request.validate()?;
+
+    let lot = state
+        .reagent_inventory_service
+        .update_lot(id, request, &user.username)
+        .await?;
+
+    Ok(Json(lot))
+}
+
+/// Delete a reagent lot.
+async fn delete_lot(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<(), ApiError> {
+    state
+        .reagent_inventory_service
+        .delete_lot(id, &user.username)
+        .await?;
+
+    Ok(())
+}
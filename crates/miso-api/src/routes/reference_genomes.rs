@@ -0,0 +1,75 @@
+//! Reference genome registry route handlers.
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateReferenceGenomeRequest, ReferenceGenomeResponse, UpdateReferenceGenomeRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{Admin, RequireRole},
+    state::AppState,
+};
+
+/// Creates reference genome routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_genomes).post(create_genome))
+        .route("/{id}", get(get_genome).put(update_genome))
+}
+
+/// List all reference genomes.
+async fn list_genomes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ReferenceGenomeResponse>>, ApiError> {
+    let genomes = state.reference_genome_service.list_genomes().await?;
+    Ok(Json(genomes))
+}
+
+/// Get a reference genome by ID.
+async fn get_genome(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ReferenceGenomeResponse>, ApiError> {
+    let genome = state.reference_genome_service.get_genome(id).await?;
+    Ok(Json(genome))
+}
+
+/// Register a reference genome.
+async fn create_genome(
+    State(state): State<AppState>,
+    user: RequireRole<Admin>,
+    Json(request): Json<CreateReferenceGenomeRequest>,
+) -> Result<Json<ReferenceGenomeResponse>, ApiError> {
+    request.validate()?;
+
+    let genome = state
+        .reference_genome_service
+        .create_genome(request, &user.username)
+        .await?;
+
+    Ok(Json(genome))
+}
+
+/// Update a reference genome.
+async fn update_genome(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Admin>,
+    Json(request): Json<UpdateReferenceGenomeRequest>,
+) -> Result<Json<ReferenceGenomeResponse>, ApiError> {
+    request.validate()?;
+
+    let genome = state
+        .reference_genome_service
+        .update_genome(id, request)
+        .await?;
+
+    Ok(Json(genome))
+}
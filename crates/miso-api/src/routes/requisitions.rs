@@ -0,0 +1,184 @@
+//! Requisition route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateRequisitionRequest, RequisitionFilter, RequisitionHoldRequest, RequisitionResponse,
+    RequisitionSamplesRequest, UpdateRequisitionRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates requisition routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_requisitions).post(create_requisition))
+        .route(
+            "/{id}",
+            get(get_requisition)
+                .put(update_requisition)
+                .delete(delete_requisition),
+        )
+        .route("/{id}/samples", post(add_samples))
+        .route("/{id}/samples/remove", post(remove_samples))
+        .route("/{id}/pause", post(pause_requisition))
+        .route("/{id}/resume", post(resume_requisition))
+        .route("/{id}/stop", post(stop_requisition))
+}
+
+/// List requisitions, newest first, or the requisition of a sample.
+async fn list_requisitions(
+    State(state): State<AppState>,
+    Query(filter): Query<RequisitionFilter>,
+) -> Result<Json<Vec<RequisitionResponse>>, ApiError> {
+    let requisitions = state.requisition_service.list_requisitions(filter).await?;
+    Ok(Json(requisitions))
+}
+
+/// Record an external order and the samples already received for it.
+async fn create_requisition(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateRequisitionRequest>,
+) -> Result<(StatusCode, Json<RequisitionResponse>), ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .create_requisition(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(requisition)))
+}
+
+/// Get a requisition.
+async fn get_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    let requisition = state.requisition_service.get_requisition(id).await?;
+    Ok(Json(requisition))
+}
+
+/// Correct a requisition's order ID or assay.
+async fn update_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateRequisitionRequest>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .update_requisition(id, request, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
+
+/// Delete a requisition, detaching its samples.
+async fn delete_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state
+        .requisition_service
+        .delete_requisition(id, &user.username)
+        .await?;
+
+    Ok(())
+}
+
+/// Attach samples to a requisition.
+async fn add_samples(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RequisitionSamplesRequest>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .add_samples(id, request, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
+
+/// Detach samples from a requisition.
+async fn remove_samples(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RequisitionSamplesRequest>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .remove_samples(id, request, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
+
+/// Put work on a requisition on hold.
+async fn pause_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RequisitionHoldRequest>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .pause(id, request, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
+
+/// Take work on a requisition off hold.
+async fn resume_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    let requisition = state
+        .requisition_service
+        .resume(id, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
+
+/// Stop work on a requisition for good.
+async fn stop_requisition(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RequisitionHoldRequest>,
+) -> Result<Json<RequisitionResponse>, ApiError> {
+    request.validate()?;
+
+    let requisition = state
+        .requisition_service
+        .stop(id, request, &user.username)
+        .await?;
+
+    Ok(Json(requisition))
+}
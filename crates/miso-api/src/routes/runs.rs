@@ -0,0 +1,551 @@
+//! Sequencing run route handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    AssignPoolRequest, AttachQcReportRequest, CreateRunRequest, DataLocationFilter,
+    DataLocationResponse, DeliveryResponse, IngestInstrumentLogRequest, LoadCheckRequest, LoadCheckResponse,
+    ImportSampleSheetRequest, MarkReadyToLoadRequest, PauseRunRequest, RegisterDataLocationRequest, ResumeRunRequest,
+    RunInstrumentEventsResponse, RunMetricsResponse, RunOverviewResponse, RunProgressRequest,
+    RunCarryoverResponse, RunQcReportResponse, RunResponse, RunSummary, SampleSheetFilter, SampleSheetImportResponse, SampleSheetFormat, SignOffRunQcRequest, SubmitRunMetricsRequest,
+    UpdateDataLocationRequest, UpdateRunStatusRequest,
+};
+use miso_application::RunMonitorService;
+use miso_domain::entities::AttachmentTarget;
+
+use super::attachments;
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole, Technician},
+    state::{AppState, RunLifecycleService},
+};
+
+/// Creates run routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_runs).post(create_run))
+        .route("/import", post(import_sample_sheet))
+        .route("/{id}", get(get_run))
+        .route(
+            "/{id}/attachments",
+            attachments::target_routes(AttachmentTarget::Run),
+        )
+        .route("/{id}/partitions/{partition}", put(assign_pool))
+        .route("/{id}/status", put(update_run_status))
+        .route("/{id}/pause", post(pause_run))
+        .route("/{id}/resume", post(resume_run))
+        .route("/{id}/progress", put(record_run_progress))
+        .route("/{id}/overview", get(get_run_overview))
+        .route("/{id}/samplesheet", get(get_sample_sheet))
+        .route("/{id}/ready", post(mark_ready_to_load))
+        .route("/{id}/carryover", get(check_carryover))
+        .route("/{id}/load-check", post(check_load))
+        .route("/{id}/qc", put(sign_off_qc))
+        .route(
+            "/{id}/metrics",
+            get(get_run_metrics).post(submit_run_metrics),
+        )
+        .route("/{id}/multiqc", get(get_qc_report).put(attach_qc_report))
+        .route(
+            "/{id}/instrument-events",
+            get(list_instrument_events).post(ingest_instrument_log),
+        )
+        .route(
+            "/{id}/data-locations",
+            get(list_data_locations).post(register_data_location),
+        )
+        .route(
+            "/{id}/data-locations/{location_id}",
+            put(update_data_location).delete(delete_data_location),
+        )
+        .route(
+            "/{id}/data-locations/{location_id}/purge",
+            post(request_purge).delete(cancel_purge),
+        )
+        .route(
+            "/{id}/data-locations/{location_id}/purge/confirm",
+            post(confirm_purge),
+        )
+        .route("/{id}/deliveries", get(list_run_deliveries))
+}
+
+fn monitor(state: &AppState) -> Result<&Arc<RunMonitorService>, ApiError> {
+    state
+        .run_monitor_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run monitoring is not available".to_string()))
+}
+
+fn lifecycle(state: &AppState) -> Result<&Arc<RunLifecycleService>, ApiError> {
+    state
+        .run_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run tracking is not available".to_string()))
+}
+
+/// Query parameters for listing runs.
+#[derive(Debug, Deserialize)]
+pub struct ListRunsQuery {
+    /// Status code, e.g. "running"
+    pub status: Option<String>,
+    pub sequencer_id: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List runs, newest first, optionally filtered by status and sequencer.
+async fn list_runs(
+    State(state): State<AppState>,
+    Query(query): Query<ListRunsQuery>,
+) -> Result<Json<Vec<RunSummary>>, ApiError> {
+    let runs = lifecycle(&state)?
+        .list_runs(
+            query.status.as_deref(),
+            query.sequencer_id,
+            query.limit,
+            query.offset,
+        )
+        .await?;
+    Ok(Json(runs))
+}
+
+/// Get a run by ID.
+async fn get_run(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let run = lifecycle(&state)?.get_run(id).await?;
+    Ok(Json(run))
+}
+
+/// Query parameters for generating a sample sheet.
+#[derive(Debug, Deserialize)]
+pub struct SampleSheetQuery {
+    /// Only include this lane
+    pub lane: Option<u8>,
+    /// Only include libraries from this project
+    pub project_id: Option<i32>,
+    /// Layout, "v1" (the default), "v2" or "minknow"
+    #[serde(default)]
+    pub format: SampleSheetFormat,
+}
+
+/// Response header carrying each lane's index hopping warnings.
+const INDEX_HOPPING_WARNING_HEADER: &str = "x-index-hopping-warning";
+
+/// Download a run's Illumina or MinKNOW sample sheet.
+///
+/// Lanes at risk of index hopping are reported in
+/// `x-index-hopping-warning` headers, one per warning.
+async fn get_sample_sheet(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(query): Query<SampleSheetQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sample_sheets = state
+        .sample_sheet_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run tracking is not available".to_string()))?;
+    let filter = SampleSheetFilter {
+        lane: query.lane,
+        project_id: query.project_id,
+    };
+    let file = sample_sheets.generate(id, filter, query.format).await?;
+
+    let mut warnings = HeaderMap::new();
+    for warning in &file.warnings {
+        if let Ok(value) = HeaderValue::from_str(warning) {
+            warnings.append(INDEX_HOPPING_WARNING_HEADER, value);
+        }
+    }
+
+    Ok((
+        warnings,
+        [
+            (header::CONTENT_TYPE, file.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name),
+            ),
+        ],
+        file.body,
+    ))
+}
+
+/// Mark a run ready to load, sending its sample sheet to its sequencer if
+/// run planning is configured.
+async fn mark_ready_to_load(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<MarkReadyToLoadRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let sample_sheets = state
+        .sample_sheet_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run tracking is not available".to_string()))?;
+    let run = sample_sheets
+        .mark_ready_to_load(id, request, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// List libraries whose index pair was sequenced in an earlier lane of the
+/// run or in the previous run on its sequencer.
+async fn check_carryover(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunCarryoverResponse>, ApiError> {
+    let sample_sheets = state
+        .sample_sheet_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run tracking is not available".to_string()))?;
+
+    Ok(Json(sample_sheets.check_carryover(id).await?))
+}
+
+/// Set up a run on an available sequencer.
+async fn create_run(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateRunRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    request.validate()?;
+
+    let run = lifecycle(&state)?
+        .create_run(request, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// Create a run, with a pool per lane, from a sample sheet planned
+/// outside the LIMS.
+async fn import_sample_sheet(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ImportSampleSheetRequest>,
+) -> Result<Json<SampleSheetImportResponse>, ApiError> {
+    request.validate()?;
+
+    let importer = state
+        .sample_sheet_import_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Run tracking is not available".to_string()))?;
+    let imported = importer.import(request, &user.username).await?;
+
+    Ok(Json(imported))
+}
+
+/// Load a pool on one of a run's partitions.
+async fn assign_pool(
+    State(state): State<AppState>,
+    Path((id, partition)): Path<(i32, u8)>,
+    user: RequireRole<Technician>,
+    Json(request): Json<AssignPoolRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    request.validate()?;
+
+    let run = lifecycle(&state)?
+        .assign_pool(id, partition, request, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// Check the flow cell and pool tubes scanned at the instrument against the
+/// run's plan; a mismatch is refused with 409 listing every mismatch.
+async fn check_load(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<LoadCheckRequest>,
+) -> Result<Json<LoadCheckResponse>, ApiError> {
+    request.validate()?;
+
+    let checked = lifecycle(&state)?
+        .check_load(id, request, &user.username)
+        .await?;
+
+    Ok(Json(checked))
+}
+
+/// Start, complete or fail a run.
+///
+/// Completing a run marks the pools loaded on it sequenced.
+async fn update_run_status(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateRunStatusRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let run = lifecycle(&state)?
+        .update_status(id, &request.status, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// Pause a running run, recording what interrupted it and any lanes
+/// needing extra QC review.
+async fn pause_run(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<PauseRunRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    request.validate()?;
+
+    let run = lifecycle(&state)?
+        .pause_run(id, request, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// Resume a paused run, flagging any further lanes affected.
+async fn resume_run(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ResumeRunRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let run = lifecycle(&state)?
+        .resume_run(id, request, &user.username)
+        .await?;
+
+    Ok(Json(run))
+}
+
+/// Record the cycles a running run has completed, refining its estimated
+/// completion time.
+async fn record_run_progress(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Technician>,
+    Json(request): Json<RunProgressRequest>,
+) -> Result<Json<RunResponse>, ApiError> {
+    let run = lifecycle(&state)?.record_progress(id, request).await?;
+    Ok(Json(run))
+}
+
+/// Get a run with its lane metrics, loaded pools and QC decision.
+async fn get_run_overview(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunOverviewResponse>, ApiError> {
+    let overview = monitor(&state)?.overview(id).await?;
+    Ok(Json(overview))
+}
+
+/// Record a reviewer's QC pass or fail decision on a run.
+async fn sign_off_qc(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<SignOffRunQcRequest>,
+) -> Result<Json<RunOverviewResponse>, ApiError> {
+    request.validate()?;
+
+    let overview = monitor(&state)?
+        .sign_off_qc(id, request, &user.username, Utc::now())
+        .await?;
+
+    Ok(Json(overview))
+}
+
+/// Get demultiplexing metrics and assay completion for a run.
+async fn get_run_metrics(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunMetricsResponse>, ApiError> {
+    let metrics = state.run_metrics_service.get_metrics(id).await?;
+    Ok(Json(metrics))
+}
+
+/// Submit per-lane, per-library metrics from a demultiplexing pipeline.
+async fn submit_run_metrics(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<SubmitRunMetricsRequest>,
+) -> Result<Json<RunMetricsResponse>, ApiError> {
+    request.validate()?;
+
+    let metrics = state
+        .run_metrics_service
+        .submit_metrics(id, request, &user.username)
+        .await?;
+
+    Ok(Json(metrics))
+}
+
+/// Get the MultiQC report attached to a run.
+async fn get_qc_report(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunQcReportResponse>, ApiError> {
+    let report = state.qc_report_service.get_report(id).await?;
+    Ok(Json(report))
+}
+
+/// Attach a MultiQC report (link and optional summary JSON) to a run.
+async fn attach_qc_report(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<AttachQcReportRequest>,
+) -> Result<Json<RunQcReportResponse>, ApiError> {
+    request.validate()?;
+
+    let report = state
+        .qc_report_service
+        .attach_report(id, request, &user.username)
+        .await?;
+
+    Ok(Json(report))
+}
+
+/// List the errors and warnings the instrument reported for a run.
+async fn list_instrument_events(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<RunInstrumentEventsResponse>, ApiError> {
+    let events = state.instrument_event_service.list_for_run(id).await?;
+    Ok(Json(events))
+}
+
+/// Ingest an instrument log or run report from the run folder.
+async fn ingest_instrument_log(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<IngestInstrumentLogRequest>,
+) -> Result<Json<RunInstrumentEventsResponse>, ApiError> {
+    request.validate()?;
+
+    let events = state
+        .instrument_event_service
+        .ingest_log(id, request, &user.username)
+        .await?;
+
+    Ok(Json(events))
+}
+
+/// List where a run's data is stored, optionally for one lane.
+async fn list_data_locations(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(filter): Query<DataLocationFilter>,
+) -> Result<Json<Vec<DataLocationResponse>>, ApiError> {
+    let locations = state.data_location_service.list_for_run(id, filter).await?;
+    Ok(Json(locations))
+}
+
+/// Register a location holding a run's data.
+async fn register_data_location(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<RegisterDataLocationRequest>,
+) -> Result<Json<DataLocationResponse>, ApiError> {
+    request.validate()?;
+
+    let location = state
+        .data_location_service
+        .register(id, request, &user.username)
+        .await?;
+
+    Ok(Json(location))
+}
+
+/// Update a registered data location.
+async fn update_data_location(
+    State(state): State<AppState>,
+    Path((id, location_id)): Path<(i32, i32)>,
+    _user: RequireRole<Technician>,
+    Json(request): Json<UpdateDataLocationRequest>,
+) -> Result<Json<DataLocationResponse>, ApiError> {
+    request.validate()?;
+
+    let location = state
+        .data_location_service
+        .update(id, location_id, request)
+        .await?;
+
+    Ok(Json(location))
+}
+
+/// Remove a data location from the registry.
+async fn delete_data_location(
+    State(state): State<AppState>,
+    Path((id, location_id)): Path<(i32, i32)>,
+    _user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    state.data_location_service.delete(id, location_id).await?;
+
+    Ok(())
+}
+
+/// Request that a data location past its retention date be purged.
+async fn request_purge(
+    State(state): State<AppState>,
+    Path((id, location_id)): Path<(i32, i32)>,
+    user: RequireRole<Technician>,
+) -> Result<Json<DataLocationResponse>, ApiError> {
+    let location = state
+        .data_location_service
+        .request_purge(id, location_id, &user.username)
+        .await?;
+
+    Ok(Json(location))
+}
+
+/// Withdraw a pending purge request.
+async fn cancel_purge(
+    State(state): State<AppState>,
+    Path((id, location_id)): Path<(i32, i32)>,
+    _user: RequireRole<Technician>,
+) -> Result<Json<DataLocationResponse>, ApiError> {
+    let location = state
+        .data_location_service
+        .cancel_purge(id, location_id)
+        .await?;
+
+    Ok(Json(location))
+}
+
+/// Confirm that a data location's data has been deleted.
+async fn confirm_purge(
+    State(state): State<AppState>,
+    Path((id, location_id)): Path<(i32, i32)>,
+    user: RequireRole<LabManager>,
+) -> Result<Json<DataLocationResponse>, ApiError> {
+    let location = state
+        .data_location_service
+        .confirm_purge(id, location_id, &user.username)
+        .await?;
+
+    Ok(Json(location))
+}
+
+/// List where a run's data has been delivered, newest first.
+async fn list_run_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<DeliveryResponse>>, ApiError> {
+    let deliveries = state.delivery_service.list_for_run(id).await?;
+    Ok(Json(deliveries))
+}
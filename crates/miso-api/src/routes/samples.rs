@@ -1,31 +1,59 @@
 //! Sample route handlers.
 
 use axum::{
-    extract::{Path, Query, State},
-    routing::{delete, get, post, put},
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch, post, put},
     Json, Router,
 };
 use serde::Deserialize;
 use validator::Validate;
 
 use miso_application::dto::{
-    CreatePlainSampleRequest, SampleResponse, SampleSummary, UpdateSampleRequest,
+    BulkUpdateSamplesRequest, BulkUpdateSamplesResponse, ChangeLogEntryResponse, ConsentResponse,
+    CreateIdentityRequest, CreateIdentityResponse, CreatePlainSampleRequest,
+    CreatePooledSampleRequest, ImportSamplesRequest, LabelJobResponse, LabelPrintResponse,
+    PossibleDuplicateResponse, ResolveDuplicateRequest, SampleImportResponse, SampleLineageResponse,
+    SampleListCountResponse, SampleListItem, SampleListQuery, SampleResponse, SampleSummary, SetConsentRequest,
+    UpdateSampleRequest,
 };
-use miso_domain::repositories::{ProjectRepository, SampleRepository};
+use miso_domain::entities::{AttachmentTarget, QcTarget};
+use miso_domain::labels::Label;
 
-use crate::{error::ApiError, middleware::AuthUser, state::AppState};
+use super::{attachments, qcs};
+use crate::{
+    error::ApiError,
+    middleware::{AuthUser, LabManager, RequireRole, Technician},
+    state::AppState,
+};
 
 /// Creates sample routes.
-pub fn routes<PR, SR>() -> Router<AppState<PR, SR>>
-where
-    PR: ProjectRepository + 'static,
-    SR: SampleRepository + 'static,
+pub fn routes() -> Router<AppState>
 {
     Router::new()
         .route("/", get(list_samples).post(create_sample))
-        .route("/:id", get(get_sample).put(update_sample).delete(delete_sample))
-        .route("/barcode/:barcode", get(get_sample_by_barcode))
-        .route("/project/:project_id", get(list_samples_by_project))
+        .route("/bulk", patch(bulk_update_samples))
+        .route("/import", post(import_samples))
+        .route("/labels", post(print_sample_labels))
+        .route("/overview", get(list_sample_overview))
+        .route("/overview/counts", get(count_sample_overview))
+        .route("/pooled", post(create_pooled_sample))
+        .route("/identities", post(create_identity))
+        .route("/identities/duplicates", get(list_possible_duplicates))
+        .route("/identities/duplicates/{id}", put(resolve_possible_duplicate))
+        .route("/identities/{id}/consent", get(get_consent).put(set_consent))
+        .route("/{id}", get(get_sample).put(update_sample).delete(delete_sample))
+        .route(
+            "/{id}/attachments",
+            attachments::target_routes(AttachmentTarget::Sample),
+        )
+        .route("/{id}/changelog", get(get_sample_change_log))
+        .route("/{id}/label", post(print_sample_label))
+        .route("/{id}/labels", get(list_sample_labels))
+        .route("/{id}/lineage", get(get_sample_lineage))
+        .route("/{id}/qcs", qcs::routes(QcTarget::Sample))
+        .route("/barcode/{barcode}", get(get_sample_by_barcode))
+        .route("/project/{project_id}", get(list_samples_by_project))
 }
 
 /// Query parameters for listing samples.
@@ -34,104 +62,484 @@ pub struct ListSamplesQuery {
     pub limit: Option<u64>,
     pub offset: Option<u64>,
     pub project_id: Option<i32>,
+    /// Only the samples assigned to this subproject
+    pub subproject_id: Option<i32>,
 }
 
 /// List samples.
-async fn list_samples<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn list_samples(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
     Query(query): Query<ListSamplesQuery>,
 ) -> Result<Json<Vec<SampleSummary>>, ApiError> {
-    if let Some(project_id) = query.project_id {
+    if let Some(subproject_id) = query.subproject_id {
+        let samples = state
+            .sample_service
+            .list_samples_by_subproject(
+                subproject_id,
+                query.project_id,
+                query.limit,
+                query.offset,
+                user.as_ref().map(AuthUser::requester),
+            )
+            .await?;
+        Ok(Json(samples))
+    } else if let Some(project_id) = query.project_id {
         let samples = state
             .sample_service
-            .list_samples_by_project(project_id, query.limit, query.offset)
+            .list_samples_by_project(project_id, query.limit, query.offset, user.as_ref().map(AuthUser::requester))
             .await?;
         Ok(Json(samples))
     } else {
-        Err(ApiError::BadRequest("project_id is required".to_string()))
+        Err(ApiError::BadRequest(
+            "project_id or subproject_id is required".to_string(),
+        ))
     }
 }
 
+/// Query parameters for the sample overview counts.
+#[derive(Debug, Deserialize)]
+pub struct SampleCountsQuery {
+    pub project_id: Option<i32>,
+}
+
+/// List samples with their project, latest QC and box position, from the
+/// sample list read model.
+async fn list_sample_overview(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Query(query): Query<SampleListQuery>,
+) -> Result<Json<Vec<SampleListItem>>, ApiError> {
+    let samples = state
+        .sample_list_service
+        .list(query, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(samples))
+}
+
+/// Count unarchived samples by project and QC status.
+async fn count_sample_overview(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Query(query): Query<SampleCountsQuery>,
+) -> Result<Json<Vec<SampleListCountResponse>>, ApiError> {
+    let counts = state
+        .sample_list_service
+        .counts(query.project_id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(counts))
+}
+
 /// List samples by project.
-async fn list_samples_by_project<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn list_samples_by_project(
+    State(state): State<AppState>,
     Path(project_id): Path<i32>,
+    user: Option<AuthUser>,
     Query(query): Query<ListSamplesQuery>,
 ) -> Result<Json<Vec<SampleSummary>>, ApiError> {
-    let samples = state
-        .sample_service
-        .list_samples_by_project(project_id, query.limit, query.offset)
-        .await?;
+    let requester = user.as_ref().map(AuthUser::requester);
+    let samples = match query.subproject_id {
+        Some(subproject_id) => {
+            state
+                .sample_service
+                .list_samples_by_subproject(
+                    subproject_id,
+                    Some(project_id),
+                    query.limit,
+                    query.offset,
+                    requester,
+                )
+                .await?
+        }
+        None => {
+            state
+                .sample_service
+                .list_samples_by_project(project_id, query.limit, query.offset, requester)
+                .await?
+        }
+    };
     Ok(Json(samples))
 }
 
 /// Get a sample by ID.
-async fn get_sample<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn get_sample(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
+    user: Option<AuthUser>,
 ) -> Result<Json<SampleResponse>, ApiError> {
-    let sample = state.sample_service.get_sample(id).await?;
+    let sample = state.sample_service.get_sample(id, user.as_ref().map(AuthUser::requester)).await?;
     Ok(Json(sample))
 }
 
+/// Get the identities a sample comes from and the samples derived from it.
+async fn get_sample_lineage(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<SampleLineageResponse>, ApiError> {
+    let lineage = state
+        .sample_service
+        .get_sample_lineage(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(lineage))
+}
+
+/// Get a sample's change log.
+async fn get_sample_change_log(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<Vec<ChangeLogEntryResponse>>, ApiError> {
+    let entries = state
+        .sample_service
+        .get_sample_change_log(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(entries))
+}
+
+/// Query parameters for printing a label.
+#[derive(Debug, Deserialize)]
+pub struct PrintLabelQuery {
+    /// Why a unique label is being reprinted; lab managers only
+    pub override_reason: Option<String>,
+}
+
+/// Print a sample's barcode label on the configured printer.
+async fn print_sample_label(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(query): Query<PrintLabelQuery>,
+    user: RequireRole<Technician>,
+) -> Result<Json<LabelPrintResponse>, ApiError> {
+    let printer = state.printer.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("No printer configured".to_string())
+    })?;
+
+    let sample = state
+        .sample_service
+        .get_sample(id, Some(user.requester()))
+        .await?;
+    let project = state.project_service.get_project(sample.project_id).await?;
+    let label = Label::sample(sample.id, &sample.barcode, &sample.name, &project.code);
+
+    let print = state
+        .label_print_service
+        .print(
+            printer.as_ref(),
+            &label,
+            &user.username,
+            user.as_role(),
+            query.override_reason.as_deref(),
+        )
+        .await?;
+
+    Ok(Json(print))
+}
+
+/// Lays out the labels of samples, in order, refusing any the user may not
+/// see.
+pub(super) async fn sample_labels(
+    state: &AppState,
+    ids: &[i32],
+    user: &AuthUser,
+) -> Result<Vec<Label>, ApiError> {
+    let mut labels = Vec::with_capacity(ids.len());
+    for &id in ids {
+        let sample = state
+            .sample_service
+            .get_sample(id, Some(user.requester()))
+            .await?;
+        let project = state.project_service.get_project(sample.project_id).await?;
+        labels.push(Label::sample(sample.id, &sample.barcode, &sample.name, &project.code));
+    }
+    Ok(labels)
+}
+
+/// Request body for printing a batch of sample labels.
+#[derive(Debug, Deserialize)]
+pub struct PrintSampleLabelsRequest {
+    pub sample_ids: Vec<i32>,
+    /// Why unique labels are being reprinted; lab managers only
+    pub override_reason: Option<String>,
+}
+
+/// Print the labels of several samples as one job.
+///
+/// Large jobs are refused if the printer has the wrong labels loaded; the
+/// labels used are recorded either way.
+async fn print_sample_labels(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(req): Json<PrintSampleLabelsRequest>,
+) -> Result<Json<LabelJobResponse>, ApiError> {
+    let printer = state.printer.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("No printer configured".to_string())
+    })?;
+
+    let labels = sample_labels(&state, &req.sample_ids, &user).await?;
+    let job = state
+        .label_print_service
+        .print_all(
+            printer.as_ref(),
+            &labels,
+            &user.username,
+            user.as_role(),
+            req.override_reason.as_deref(),
+        )
+        .await?;
+
+    Ok(Json(job))
+}
+
+/// List the labels printed for a sample.
+async fn list_sample_labels(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: Option<AuthUser>,
+) -> Result<Json<Vec<LabelPrintResponse>>, ApiError> {
+    // Fetching the sample refuses anyone who may not see it
+    state
+        .sample_service
+        .get_sample(id, user.as_ref().map(AuthUser::requester))
+        .await?;
+    let prints = state.label_print_service.history("Sample", id).await?;
+    Ok(Json(prints))
+}
+
 /// Get a sample by barcode.
-async fn get_sample_by_barcode<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn get_sample_by_barcode(
+    State(state): State<AppState>,
     Path(barcode): Path<String>,
+    user: Option<AuthUser>,
 ) -> Result<Json<SampleResponse>, ApiError> {
-    let sample = state.sample_service.get_sample_by_barcode(&barcode).await?;
+    let sample = state
+        .sample_service
+        .get_sample_by_barcode(&barcode, user.as_ref().map(AuthUser::requester))
+        .await?;
     Ok(Json(sample))
 }
 
 /// Create a new sample.
-async fn create_sample<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
-    user: AuthUser,
+async fn create_sample(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
     Json(request): Json<CreatePlainSampleRequest>,
 ) -> Result<Json<SampleResponse>, ApiError> {
-    if !user.can_edit() {
-        return Err(ApiError::Forbidden);
-    }
-
     request.validate()?;
 
     let sample = state
         .sample_service
-        .create_plain_sample(request, &user.username)
+        .create_plain_sample(request, &user.username, user.as_role())
         .await?;
 
     Ok(Json(sample))
 }
 
+/// Create a tissue pooled from several identities.
+async fn create_pooled_sample(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreatePooledSampleRequest>,
+) -> Result<(StatusCode, Json<SampleResponse>), ApiError> {
+    request.validate()?;
+
+    let sample = state
+        .sample_service
+        .create_pooled_sample(request, &user.username, user.as_role())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(sample)))
+}
+
+/// Create an identity.
+///
+/// Responds 409 with the similar identities, creating nothing, if existing
+/// identities have similar external names and the request doesn't say to
+/// create it anyway.
+async fn create_identity(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateIdentityRequest>,
+) -> Result<(StatusCode, Json<CreateIdentityResponse>), ApiError> {
+    request.validate()?;
+
+    let response = state
+        .sample_service
+        .create_identity(request, &user.username, user.as_role())
+        .await?;
+    let status = if response.identity.is_some() {
+        StatusCode::CREATED
+    } else {
+        StatusCode::CONFLICT
+    };
+
+    Ok((status, Json(response)))
+}
+
+/// List identities created despite looking like existing ones, awaiting
+/// review.
+async fn list_possible_duplicates(
+    State(state): State<AppState>,
+    _user: RequireRole<LabManager>,
+) -> Result<Json<Vec<PossibleDuplicateResponse>>, ApiError> {
+    let duplicates = state.sample_service.list_possible_duplicates().await?;
+
+    Ok(Json(duplicates))
+}
+
+/// Record whether a possible duplicate is a different person or should be
+/// merged.
+async fn resolve_possible_duplicate(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<ResolveDuplicateRequest>,
+) -> Result<Json<PossibleDuplicateResponse>, ApiError> {
+    request.validate()?;
+
+    let duplicate = state
+        .sample_service
+        .resolve_possible_duplicate(id, request, &user.username)
+        .await?;
+
+    Ok(Json(duplicate))
+}
+
+/// Get the consent recorded for an identity.
+async fn get_consent(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<LabManager>,
+) -> Result<Json<ConsentResponse>, ApiError> {
+    let consent = state.consent_service.get_consent(id).await?;
+    Ok(Json(consent))
+}
+
+/// Record what of an identity's samples exports may share.
+async fn set_consent(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<SetConsentRequest>,
+) -> Result<Json<ConsentResponse>, ApiError> {
+    request.validate()?;
+
+    let consent = state
+        .consent_service
+        .set_consent(id, request, &user.username)
+        .await?;
+
+    Ok(Json(consent))
+}
+
 /// Update a sample.
-async fn update_sample<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn update_sample(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
-    user: AuthUser,
+    user: RequireRole<Technician>,
     Json(request): Json<UpdateSampleRequest>,
 ) -> Result<Json<SampleResponse>, ApiError> {
-    if !user.can_edit() {
-        return Err(ApiError::Forbidden);
-    }
-
     request.validate()?;
 
-    let sample = state.sample_service.update_sample(id, request).await?;
+    let sample = state
+        .sample_service
+        .update_sample(id, request, &user.username, user.as_role())
+        .await?;
 
     Ok(Json(sample))
 }
 
+/// Update many samples at once.
+///
+/// Responds 409 with per-row results if any row was rejected, in which
+/// case no row has been written.
+async fn bulk_update_samples(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<BulkUpdateSamplesRequest>,
+) -> Result<(StatusCode, Json<BulkUpdateSamplesResponse>), ApiError> {
+    request.validate()?;
+
+    let response = state
+        .sample_service
+        .bulk_update_samples(request, &user.username, user.as_role())
+        .await?;
+    let status = if response.applied {
+        StatusCode::OK
+    } else {
+        StatusCode::CONFLICT
+    };
+
+    Ok((status, Json(response)))
+}
+
+/// Query parameters for importing samples.
+#[derive(Debug, Deserialize)]
+pub struct ImportSamplesQuery {
+    /// Check the file without creating anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Create samples from a CSV or TSV manifest uploaded as the `file` field
+/// of a multipart form.
+///
+/// Responds 201 once every row has been created, 200 if a dry run found
+/// nothing wrong, and 422 with per-row errors otherwise, in which case no
+/// sample has been created.
+async fn import_samples(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Query(query): Query<ImportSamplesQuery>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<SampleImportResponse>), ApiError> {
+    let mut contents = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+    {
+        if field.name() == Some("file") {
+            contents = Some(
+                field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+            );
+        }
+    }
+    let contents =
+        contents.ok_or_else(|| ApiError::BadRequest("No file was uploaded".to_string()))?;
+
+    let request = ImportSamplesRequest {
+        contents,
+        dry_run: query.dry_run,
+    };
+    let response = state
+        .sample_import_service
+        .import(request, &user.username, user.as_role())
+        .await?;
+    let status = if response.applied {
+        StatusCode::CREATED
+    } else if response.results.iter().all(|r| r.errors.is_empty()) {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(response)))
+}
+
 /// Delete a sample.
-async fn delete_sample<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn delete_sample(
+    State(state): State<AppState>,
     Path(id): Path<i32>,
-    user: AuthUser,
+    user: RequireRole<LabManager>,
 ) -> Result<(), ApiError> {
-    if !user.can_delete() {
-        return Err(ApiError::Forbidden);
-    }
-
-    state.sample_service.delete_sample(id).await?;
+    state
+        .sample_service
+        .delete_sample(id, &user.username, user.as_role())
+        .await?;
 
     Ok(())
 }
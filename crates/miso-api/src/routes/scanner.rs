@@ -1,5 +1,7 @@
 //! VisionMate scanner route handlers.
 
+use std::collections::HashMap;
+
 use axum::{
     extract::State,
     routing::{get, post},
@@ -7,16 +9,16 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use miso_application::dto::{RackScanResult, TubeScanResult};
-use miso_domain::repositories::{ProjectRepository, SampleRepository};
+use miso_application::dto::{RackScanResult, SampleSummary, TubeScanResult};
 
-use crate::{error::ApiError, middleware::AuthUser, state::AppState};
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
 
 /// Creates scanner routes.
-pub fn routes<PR, SR>() -> Router<AppState<PR, SR>>
-where
-    PR: ProjectRepository + 'static,
-    SR: SampleRepository + 'static,
+pub fn routes() -> Router<AppState>
 {
     Router::new()
         .route("/status", get(scanner_status))
@@ -32,15 +34,15 @@ pub struct ScannerStatusResponse {
 }
 
 /// Get scanner status.
-async fn scanner_status<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
+async fn scanner_status(
+    State(state): State<AppState>,
 ) -> Json<ScannerStatusResponse> {
     match &state.scanner {
         Some(scanner) => {
             let connected = scanner.ping().await;
             Json(ScannerStatusResponse {
                 connected,
-                ip: Some(format!("configured")),
+                ip: Some("configured".to_string()),
                 message: if connected {
                     "Scanner is ready".to_string()
                 } else {
@@ -64,15 +66,11 @@ pub struct ScanRequest {
 }
 
 /// Trigger a rack scan.
-async fn scan_rack<PR: ProjectRepository, SR: SampleRepository>(
-    State(state): State<AppState<PR, SR>>,
-    user: AuthUser,
+async fn scan_rack(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
     Json(_request): Json<ScanRequest>,
 ) -> Result<Json<RackScanResult>, ApiError> {
-    if !user.can_edit() {
-        return Err(ApiError::Forbidden);
-    }
-
     let scanner = state.scanner.as_ref().ok_or_else(|| {
         ApiError::BadRequest("No scanner configured".to_string())
     })?;
@@ -81,15 +79,28 @@ async fn scan_rack<PR: ProjectRepository, SR: SampleRepository>(
         ApiError::BadRequest(format!("Scan failed: {}", e))
     })?;
 
+    // Look up all scanned tubes in one query
+    let barcodes: Vec<String> = result.positions.values().cloned().collect();
+    let samples: HashMap<String, SampleSummary> = state
+        .sample_service
+        .get_samples_by_barcodes(&barcodes, Some(user.requester()))
+        .await?
+        .into_iter()
+        .map(|s| (s.barcode.clone(), s))
+        .collect();
+
     // Convert scanner result to API response
     let tubes: Vec<TubeScanResult> = result
         .positions
         .into_iter()
-        .map(|(position, barcode)| TubeScanResult {
-            position,
-            barcode,
-            sample_id: None, // TODO: Look up sample by barcode
-            sample_name: None,
+        .map(|(position, barcode)| {
+            let sample = samples.get(&barcode);
+            TubeScanResult {
+                position,
+                sample_id: sample.map(|s| s.id),
+                sample_name: sample.map(|s| s.name.clone()),
+                barcode,
+            }
         })
         .collect();
 
@@ -0,0 +1,40 @@
+//! Quick search route handler.
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use miso_application::dto::SearchResponse;
+
+use crate::{error::ApiError, middleware::AuthUser, state::AppState};
+
+/// Creates search routes.
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(search))
+}
+
+/// Query parameters for a search.
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Text to find in project codes and names, and sample names and
+    /// barcodes
+    pub q: String,
+    /// Most hits of each kind to return
+    pub limit: Option<u64>,
+}
+
+/// Search projects and samples.
+async fn search(
+    State(state): State<AppState>,
+    user: Option<AuthUser>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    let results = state
+        .search_service
+        .search(&query.q, query.limit, user.as_ref().map(AuthUser::requester))
+        .await?;
+    Ok(Json(results))
+}
@@ -0,0 +1,159 @@
+//! Sequencer registry route handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use miso_domain::entities::SequencerStatus;
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{CreateSequencerRequest, SequencerResponse, UpdateSequencerRequest};
+use miso_application::SequencerService;
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates sequencer routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_sequencers).post(create_sequencer))
+        .route(
+            "/{id}",
+            get(get_sequencer)
+                .put(update_sequencer)
+                .delete(delete_sequencer),
+        )
+        .route("/{id}/maintenance", post(start_maintenance))
+        .route("/{id}/out-of-service", post(take_out_of_service))
+        .route("/{id}/return-to-service", post(return_to_service))
+        .route("/{id}/retire", post(retire_sequencer))
+}
+
+/// Query parameters for listing sequencers.
+#[derive(Debug, Deserialize)]
+pub struct ListSequencersQuery {
+    /// Only list sequencers with this status
+    pub status: Option<SequencerStatus>,
+}
+
+fn sequencers(state: &AppState) -> Result<&Arc<SequencerService>, ApiError> {
+    state
+        .sequencer_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Sequencers are not available".to_string()))
+}
+
+/// List sequencers.
+async fn list_sequencers(
+    State(state): State<AppState>,
+    Query(query): Query<ListSequencersQuery>,
+    _user: RequireRole<Technician>,
+) -> Result<Json<Vec<SequencerResponse>>, ApiError> {
+    Ok(Json(sequencers(&state)?.list(query.status).await?))
+}
+
+/// Get a sequencer by ID.
+async fn get_sequencer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    _user: RequireRole<Technician>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    Ok(Json(sequencers(&state)?.get(id).await?))
+}
+
+/// Register a sequencer.
+async fn create_sequencer(
+    State(state): State<AppState>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<CreateSequencerRequest>,
+) -> Result<(StatusCode, Json<SequencerResponse>), ApiError> {
+    request.validate()?;
+
+    let sequencer = sequencers(&state)?.create(request, &user.username).await?;
+
+    Ok((StatusCode::CREATED, Json(sequencer)))
+}
+
+/// Update a sequencer's details.
+async fn update_sequencer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+    Json(request): Json<UpdateSequencerRequest>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    request.validate()?;
+
+    let sequencer = sequencers(&state)?
+        .update(id, request, &user.username)
+        .await?;
+
+    Ok(Json(sequencer))
+}
+
+/// Delete a sequencer that has had no runs.
+async fn delete_sequencer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<StatusCode, ApiError> {
+    sequencers(&state)?.delete(id, &user.username).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Put a sequencer under maintenance.
+async fn start_maintenance(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    change_status(&state, id, SequencerStatus::Maintenance, &user.username).await
+}
+
+/// Take a sequencer out of service.
+async fn take_out_of_service(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    change_status(&state, id, SequencerStatus::OutOfService, &user.username).await
+}
+
+/// Make a sequencer available for runs again.
+async fn return_to_service(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    change_status(&state, id, SequencerStatus::Available, &user.username).await
+}
+
+/// Retire a sequencer for good.
+async fn retire_sequencer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    change_status(&state, id, SequencerStatus::Retired, &user.username).await
+}
+
+async fn change_status(
+    state: &AppState,
+    id: i32,
+    status: SequencerStatus,
+    changed_by: &str,
+) -> Result<Json<SequencerResponse>, ApiError> {
+    let sequencer = sequencers(state)?
+        .change_status(id, status, changed_by)
+        .await?;
+
+    Ok(Json(sequencer))
+}
@@ -0,0 +1,160 @@
+//! Public facility status endpoints.
+//!
+//! `/status` and `/status.html` need no login, so collaborators can check
+//! whether runs are being taken. Responses may be cached for a minute and
+//! carry an ETag, so polling clients and proxies mostly get 304s.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+
+use miso_application::dto::FacilityStatusResponse;
+
+use crate::{error::ApiError, state::AppState};
+
+/// How long clients and proxies may reuse a status response.
+const CACHE_CONTROL: &str = "public, max-age=60";
+
+/// Instrument availability and planned maintenance, as JSON.
+pub async fn status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let status = state.facility_status_service.status(Utc::now()).await?;
+    Ok(cached(&headers, etag(&status, "json"), || Json(status).into_response()))
+}
+
+/// Instrument availability and planned maintenance, as a page.
+pub async fn status_page(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let status = state.facility_status_service.status(Utc::now()).await?;
+    Ok(cached(&headers, etag(&status, "html"), || {
+        Html(render(&status)).into_response()
+    }))
+}
+
+/// Answers 304 if the client already has this version, and otherwise the
+/// rendered response, both with caching headers.
+fn cached(headers: &HeaderMap, etag: String, render: impl FnOnce() -> Response) -> Response {
+    let fresh = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|t| t.trim() == etag || t.trim() == "*"));
+
+    let caching = [
+        (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        (header::ETAG, etag),
+    ];
+    if fresh {
+        (StatusCode::NOT_MODIFIED, caching).into_response()
+    } else {
+        (caching, render()).into_response()
+    }
+}
+
+/// Tags a version of the status in one representation.
+fn etag(status: &FacilityStatusResponse, representation: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    status.hash(&mut hasher);
+    representation.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// Renders the status page.
+fn render(status: &FacilityStatusResponse) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Sequencing facility status</title>\n\
+         <style>body{font-family:sans-serif;max-width:48em;margin:2em auto;padding:0 1em}\
+         table{border-collapse:collapse;width:100%}td,th{text-align:left;padding:.3em;\
+         border-bottom:1px solid #ddd}.up{color:#1a7f37}.down{color:#cf222e}</style>\n\
+         </head>\n<body>\n<h1>Sequencing facility status</h1>\n",
+    );
+
+    let _ = writeln!(
+        html,
+        "<p class=\"{}\"><strong>{}</strong></p>",
+        if status.accepting_runs { "up" } else { "down" },
+        if status.accepting_runs {
+            "Accepting runs"
+        } else {
+            "Not accepting runs at the moment"
+        }
+    );
+
+    html.push_str("<h2>Instruments</h2>\n");
+    if status.instruments.is_empty() {
+        html.push_str("<p>No instruments listed.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Instrument</th><th>Model</th><th>Status</th></tr>\n");
+        for instrument in &status.instruments {
+            let state = match instrument.back_at {
+                Some(back_at) => format!("{} until {}", instrument.status, format_time(back_at)),
+                None => instrument.status.to_string(),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{} {}</td><td class=\"{}\">{}</td></tr>",
+                escape(&instrument.name),
+                escape(&instrument.platform),
+                escape(&instrument.model),
+                if instrument.available { "up" } else { "down" },
+                escape(&state)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Planned maintenance</h2>\n");
+    if status.planned_maintenance.is_empty() {
+        html.push_str("<p>None planned.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for maintenance in &status.planned_maintenance {
+            let _ = writeln!(
+                html,
+                "<li>{}: {} to {}{} &ndash; {}</li>",
+                escape(maintenance.instrument.as_deref().unwrap_or("Whole facility")),
+                format_time(maintenance.starts_at),
+                format_time(maintenance.ends_at),
+                if maintenance.in_progress { " (under way)" } else { "" },
+                escape(&maintenance.reason)
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// Escapes text for an HTML element or attribute.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,262 @@
+//! Storage route handlers: box browsing, freezers, shelves and racks,
+//! freezer audits, and run data usage and retention.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    AcceptCorrectionsRequest, BoxContents, CreateStorageUnitRequest, DataLocationResponse,
+    ItemLocation, ItemMoved, MoveItemRequest, RecordBoxScanRequest, StartStorageAuditRequest,
+    StorageAuditResponse, StorageNode, StorageUnitFilter, StorageUnitMap, StorageUnitResponse,
+    StorageUsageReport, UpdateStorageUnitRequest,
+};
+use miso_application::{StorageAuditService, StorageBrowserService, StorageUnitService};
+use miso_domain::repositories::{SampleRepository, StorageBoxRepository, StorageUnitRepository};
+
+use crate::{
+    error::ApiError,
+    middleware::{LabManager, RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates storage routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/freezers", get(freezers))
+        .route("/units", get(list_units).post(create_unit))
+        .route(
+            "/units/{id}",
+            get(get_unit).put(update_unit).delete(delete_unit),
+        )
+        .route("/units/{id}/map", get(unit_map))
+        .route("/boxes/{id}", get(box_contents))
+        .route("/locate", get(locate_item))
+        .route("/moves", post(move_item))
+        .route("/audits", get(list_audits).post(start_audit))
+        .route("/audits/{id}", get(get_audit))
+        .route("/audits/{id}/scans", post(record_scan))
+        .route("/audits/{id}/corrections", post(accept_corrections))
+        .route("/usage", get(storage_usage))
+        .route("/purge-candidates", get(purge_candidates))
+}
+
+type Browser = StorageBrowserService<dyn StorageBoxRepository, dyn SampleRepository>;
+
+pub(super) fn browser(state: &AppState) -> Result<&Arc<Browser>, ApiError> {
+    state
+        .storage_browser_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Box storage is not available".to_string()))
+}
+
+type Units = StorageUnitService<dyn StorageUnitRepository, dyn StorageBoxRepository>;
+
+pub(super) fn units(state: &AppState) -> Result<&Arc<Units>, ApiError> {
+    state
+        .storage_unit_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Box storage is not available".to_string()))
+}
+
+fn audits(state: &AppState) -> Result<&Arc<StorageAuditService>, ApiError> {
+    state
+        .storage_audit_service
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("Box storage is not available".to_string()))
+}
+
+/// Query parameters for locating an item.
+#[derive(Debug, Deserialize)]
+pub struct LocateQuery {
+    /// Barcode of the item to find
+    pub barcode: String,
+}
+
+/// List freezers, shelves and racks with their boxes and fill levels.
+async fn freezers(State(state): State<AppState>) -> Result<Json<Vec<StorageNode>>, ApiError> {
+    let hierarchy = browser(&state)?.hierarchy().await?;
+    Ok(Json(hierarchy))
+}
+
+/// List the freezers, or the shelves or racks in a unit.
+async fn list_units(
+    State(state): State<AppState>,
+    Query(filter): Query<StorageUnitFilter>,
+) -> Result<Json<Vec<StorageUnitResponse>>, ApiError> {
+    let list = units(&state)?.list_units(filter).await?;
+    Ok(Json(list))
+}
+
+/// Add a freezer, a shelf to a freezer or a rack to a shelf.
+async fn create_unit(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateStorageUnitRequest>,
+) -> Result<(StatusCode, Json<StorageUnitResponse>), ApiError> {
+    request.validate()?;
+
+    let unit = units(&state)?.create_unit(request, &user.username).await?;
+    Ok((StatusCode::CREATED, Json(unit)))
+}
+
+/// Get a freezer, shelf or rack.
+async fn get_unit(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<StorageUnitResponse>, ApiError> {
+    let unit = units(&state)?.get_unit(id).await?;
+    Ok(Json(unit))
+}
+
+/// Rename, relabel or resize a freezer, shelf or rack.
+async fn update_unit(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateStorageUnitRequest>,
+) -> Result<Json<StorageUnitResponse>, ApiError> {
+    request.validate()?;
+
+    let unit = units(&state)?
+        .update_unit(id, request, &user.username)
+        .await?;
+    Ok(Json(unit))
+}
+
+/// Delete an empty freezer, shelf or rack.
+async fn delete_unit(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<LabManager>,
+) -> Result<(), ApiError> {
+    units(&state)?.delete_unit(id, &user.username).await?;
+    Ok(())
+}
+
+/// Map a freezer, shelf or rack down to the boxes in each rack.
+async fn unit_map(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<StorageUnitMap>, ApiError> {
+    let map = units(&state)?.unit_map(id).await?;
+    Ok(Json(map))
+}
+
+/// Get a box and its contents.
+async fn box_contents(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<BoxContents>, ApiError> {
+    let contents = browser(&state)?.box_contents(id).await?;
+    Ok(Json(contents))
+}
+
+/// Find the box and position holding an item.
+async fn locate_item(
+    State(state): State<AppState>,
+    Query(query): Query<LocateQuery>,
+) -> Result<Json<ItemLocation>, ApiError> {
+    let location = browser(&state)?.locate(query.barcode.trim()).await?;
+    Ok(Json(location))
+}
+
+/// Move an item to another position.
+async fn move_item(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Json(request): Json<MoveItemRequest>,
+) -> Result<Json<ItemMoved>, ApiError> {
+    request.validate()?;
+
+    let moved = browser(&state)?.move_item(request).await?;
+    Ok(Json(moved))
+}
+
+/// Query parameters for listing storage audits.
+#[derive(Debug, Deserialize)]
+pub struct ListAuditsQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List freezer audits, newest first.
+async fn list_audits(
+    State(state): State<AppState>,
+    Query(query): Query<ListAuditsQuery>,
+) -> Result<Json<Vec<StorageAuditResponse>>, ApiError> {
+    let list = audits(&state)?
+        .list_audits(query.limit, query.offset)
+        .await?;
+    Ok(Json(list))
+}
+
+/// Start auditing a freezer's boxes against what is on the shelf.
+async fn start_audit(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<StartStorageAuditRequest>,
+) -> Result<Json<StorageAuditResponse>, ApiError> {
+    request.validate()?;
+
+    let audit = audits(&state)?
+        .start_audit(request, &user.username)
+        .await?;
+    Ok(Json(audit))
+}
+
+/// Get an audit, its discrepancies and the box to scan next.
+async fn get_audit(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<StorageAuditResponse>, ApiError> {
+    let audit = audits(&state)?.get_audit(id).await?;
+    Ok(Json(audit))
+}
+
+/// Record a scan of one of the audit's boxes.
+async fn record_scan(
+    State(state): State<AppState>,
+    _user: RequireRole<Technician>,
+    Path(id): Path<i32>,
+    Json(request): Json<RecordBoxScanRequest>,
+) -> Result<Json<StorageAuditResponse>, ApiError> {
+    let audit = audits(&state)?.record_scan(id, request).await?;
+    Ok(Json(audit))
+}
+
+/// Correct records to match the chosen discrepancies, closing the audit.
+async fn accept_corrections(
+    State(state): State<AppState>,
+    user: RequireRole<LabManager>,
+    Path(id): Path<i32>,
+    Json(request): Json<AcceptCorrectionsRequest>,
+) -> Result<Json<StorageAuditResponse>, ApiError> {
+    let audit = audits(&state)?
+        .accept_corrections(id, request, &user.username)
+        .await?;
+    Ok(Json(audit))
+}
+
+/// Report bytes stored per project and per platform.
+async fn storage_usage(
+    State(state): State<AppState>,
+) -> Result<Json<StorageUsageReport>, ApiError> {
+    let report = state.data_location_service.storage_usage().await?;
+    Ok(Json(report))
+}
+
+/// List data locations past their retention date.
+async fn purge_candidates(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DataLocationResponse>>, ApiError> {
+    let candidates = state.data_location_service.purge_candidates().await?;
+    Ok(Json(candidates))
+}
@@ -0,0 +1,116 @@
+//! Transfer (chain of custody) route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use validator::Validate;
+
+use miso_application::dto::{
+    CreateTransferRequest, ReceiveTransferRequest, TrackingUpdateRequest, TransferFilter,
+    TransferResponse, UpdateShipmentRequest,
+};
+
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates transfer routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_transfers).post(create_transfer))
+        .route("/tracking", post(record_tracking))
+        .route("/{id}", get(get_transfer))
+        .route("/{id}/receive", post(receive_transfer))
+        .route("/{id}/shipment", put(update_shipment))
+}
+
+/// List transfers, most recently sent first, or the chain of custody of a
+/// sample or library (`?sample_id=` or `?library_id=`), oldest first.
+async fn list_transfers(
+    State(state): State<AppState>,
+    Query(filter): Query<TransferFilter>,
+) -> Result<Json<Vec<TransferResponse>>, ApiError> {
+    let transfers = state.transfer_service.list_transfers(filter).await?;
+    Ok(Json(transfers))
+}
+
+/// Record samples and libraries sent to another lab or person.
+async fn create_transfer(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateTransferRequest>,
+) -> Result<(StatusCode, Json<TransferResponse>), ApiError> {
+    request.validate()?;
+
+    let transfer = state
+        .transfer_service
+        .create_transfer(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+/// Get a transfer.
+async fn get_transfer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    let transfer = state.transfer_service.get_transfer(id).await?;
+    Ok(Json(transfer))
+}
+
+/// Record whether items arrived and passed QC.
+async fn receive_transfer(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<ReceiveTransferRequest>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    request.validate()?;
+
+    let transfer = state
+        .transfer_service
+        .receive(id, request, &user.username)
+        .await?;
+
+    Ok(Json(transfer))
+}
+
+/// Set or correct the courier, tracking number and recipient address.
+async fn update_shipment(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<UpdateShipmentRequest>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    request.validate()?;
+
+    let transfer = state
+        .transfer_service
+        .update_shipment(id, request, &user.username)
+        .await?;
+
+    Ok(Json(transfer))
+}
+
+/// Record a status pushed by a courier or tracking service for the
+/// transfers sent under a tracking number.
+async fn record_tracking(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<TrackingUpdateRequest>,
+) -> Result<Json<Vec<TransferResponse>>, ApiError> {
+    request.validate()?;
+
+    let transfers = state
+        .transfer_service
+        .record_tracking(request, &user.username)
+        .await?;
+
+    Ok(Json(transfers))
+}
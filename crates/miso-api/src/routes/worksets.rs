@@ -0,0 +1,179 @@
+//! Workset route handlers.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+use miso_application::dto::{
+    BulkUpdateSamplesResponse, CreateWorksetRequest, LabelJobResponse, UpdateSampleRequest,
+    WorksetItemsRequest, WorksetResponse,
+};
+
+use super::samples::{sample_labels, PrintLabelQuery};
+use crate::{
+    error::ApiError,
+    middleware::{RequireRole, Technician},
+    state::AppState,
+};
+
+/// Creates workset routes.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_worksets).post(create_workset))
+        .route("/{id}", get(get_workset).delete(delete_workset))
+        .route("/{id}/items", post(add_items))
+        .route("/{id}/items/remove", post(remove_items))
+        .route("/{id}/samples", patch(update_samples))
+        .route("/{id}/labels", post(print_labels))
+}
+
+/// Query parameters for listing worksets.
+#[derive(Debug, Deserialize)]
+pub struct ListWorksetsQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// List worksets, newest first.
+async fn list_worksets(
+    State(state): State<AppState>,
+    Query(query): Query<ListWorksetsQuery>,
+) -> Result<Json<Vec<WorksetResponse>>, ApiError> {
+    let worksets = state
+        .workset_service
+        .list_worksets(query.limit, query.offset)
+        .await?;
+    Ok(Json(worksets))
+}
+
+/// Create a workset, e.g. from the barcodes of a rack scan.
+async fn create_workset(
+    State(state): State<AppState>,
+    user: RequireRole<Technician>,
+    Json(request): Json<CreateWorksetRequest>,
+) -> Result<(StatusCode, Json<WorksetResponse>), ApiError> {
+    request.validate()?;
+
+    let workset = state
+        .workset_service
+        .create_workset(request, &user.username)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(workset)))
+}
+
+/// Get a workset.
+async fn get_workset(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<WorksetResponse>, ApiError> {
+    let workset = state.workset_service.get_workset(id).await?;
+    Ok(Json(workset))
+}
+
+/// Delete a workset, leaving its members as they are.
+async fn delete_workset(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+) -> Result<(), ApiError> {
+    state
+        .workset_service
+        .delete_workset(id, &user.username)
+        .await?;
+
+    Ok(())
+}
+
+/// Add samples and libraries to a workset.
+async fn add_items(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<WorksetItemsRequest>,
+) -> Result<Json<WorksetResponse>, ApiError> {
+    request.validate()?;
+
+    let workset = state
+        .workset_service
+        .add_items(id, request, &user.username)
+        .await?;
+
+    Ok(Json(workset))
+}
+
+/// Remove samples and libraries from a workset.
+async fn remove_items(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(request): Json<WorksetItemsRequest>,
+) -> Result<Json<WorksetResponse>, ApiError> {
+    request.validate()?;
+
+    let workset = state
+        .workset_service
+        .remove_items(id, request, &user.username)
+        .await?;
+
+    Ok(Json(workset))
+}
+
+/// Make the same changes to every sample in a workset, as one bulk update.
+///
+/// Like a bulk update, nothing is written unless every sample can be, and
+/// the response is then 409 with the rows that need attention.
+async fn update_samples(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    user: RequireRole<Technician>,
+    Json(changes): Json<UpdateSampleRequest>,
+) -> Result<(StatusCode, Json<BulkUpdateSamplesResponse>), ApiError> {
+    changes.validate()?;
+
+    let request = state.workset_service.sample_updates(id, changes).await?;
+    let response = state
+        .sample_service
+        .bulk_update_samples(request, &user.username, user.as_role())
+        .await?;
+    let status = if response.applied {
+        StatusCode::OK
+    } else {
+        StatusCode::CONFLICT
+    };
+
+    Ok((status, Json(response)))
+}
+
+/// Print the labels of every sample in a workset as one job.
+async fn print_labels(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(query): Query<PrintLabelQuery>,
+    user: RequireRole<Technician>,
+) -> Result<Json<LabelJobResponse>, ApiError> {
+    let printer = state.printer.as_ref().ok_or_else(|| {
+        ApiError::BadRequest("No printer configured".to_string())
+    })?;
+
+    let workset = state.workset_service.get_workset(id).await?;
+    let labels = sample_labels(&state, &workset.sample_ids, &user).await?;
+
+    let job = state
+        .label_print_service
+        .print_all(
+            printer.as_ref(),
+            &labels,
+            &user.username,
+            user.as_role(),
+            query.override_reason.as_deref(),
+        )
+        .await?;
+
+    Ok(Json(job))
+}
@@ -0,0 +1,286 @@
+//! Server startup.
+//!
+//! [`run`] is the whole of the `miso-server` binary. Sites with plugins
+//! build their own binary that installs them and calls it:
+//!
+//! ```no_run
+//! use miso_application::plugins::PluginRegistry;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let plugins = PluginRegistry::new(); // .install(&SiteRules)
+//!     miso_api::server::run(plugins).await
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use tracing::info;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use miso_application::jobs::{
+    ApiUsageFlushJob, AutoArchiveJob, DigestJob, LocationReconciliationJob, LogArchivalJob, ReagentAlertJob, RunImportJob, Scheduler, ShipmentTrackingJob, StaleRunJob,
+};
+use miso_application::{ReagentInventoryService, RetentionService, TransferService};
+use miso_application::plugins::PluginRegistry;
+use miso_domain::notifications::Notifier;
+use miso_infrastructure::external::basespace::BaseSpaceClient;
+use miso_infrastructure::external::ldap::{LdapAuthProvider, LdapClient};
+use miso_infrastructure::external::oidc::{OidcAuthProvider, OidcClient};
+use miso_infrastructure::external::shipment_tracking::HttpShipmentTracker;
+use miso_infrastructure::notifications::{email::EmailNotifier, log::LogNotifier};
+use miso_infrastructure::persistence::{
+    database::{Database, DatabaseConfig},
+    repositories::{
+        SeaOrmApiKeyRepository, SeaOrmApiUsageRepository, SeaOrmArchiveRuleRepository, SeaOrmAutoArchivalRepository, SeaOrmAttributeDefinitionRepository, SeaOrmAuditLogRepository, SeaOrmChangeLogRepository, SeaOrmConsentRepository,
+        SeaOrmDataLocationRepository, SeaOrmDeliveryRepository, SeaOrmDeliveryTargetRepository, SeaOrmErasureRepository, SeaOrmEventStoreRepository, SeaOrmExportTemplateRepository, SeaOrmIndexSetRepository,
+        SeaOrmInstrumentEventRepository, SeaOrmInstrumentModelRepository, SeaOrmItemLocationRepository, SeaOrmLibraryAliquotRepository, SeaOrmLibraryRepository, SeaOrmLabelPrintRepository, SeaOrmLogArchiveRepository, SeaOrmMaintenanceWindowRepository, SeaOrmPanelRepository, SeaOrmPoolRepository, SeaOrmPossibleDuplicateRepository, SeaOrmReferenceGenomeRepository,
+        SeaOrmProjectActivityRepository, SeaOrmProjectMemberRepository, SeaOrmProjectRepository, SeaOrmQcRecordRepository, SeaOrmQcReportRepository, SeaOrmReconciliationReportRepository,
+        SeaOrmExtractionBatchRepository, SeaOrmAttachmentRepository, SeaOrmReagentLotRepository, SeaOrmRequisitionRepository,
+        SeaOrmRunMetricsRepository, SeaOrmRunRepository, SeaOrmSampleCompositionRepository, SeaOrmSampleListRepository, SeaOrmSampleRepository,
+        SeaOrmSequencerRepository, SeaOrmStorageAuditRepository, SeaOrmStorageBoxRepository, SeaOrmStorageUnitRepository, SeaOrmSubprojectRepository,
+        SeaOrmTransferRepository, SeaOrmUserRepository, SeaOrmWorksetRepository,
+    },
+};
+
+use crate::{routes, state::Repositories, AppState, Config};
+
+/// Configures logging, connects to the database, starts background jobs
+/// and serves the API until the process is stopped.
+///
+/// Configuration is read from the environment, as [`Config::from_env`]
+/// describes.
+pub async fn run(plugins: PluginRegistry) -> Result<()> {
+    // Load configuration
+    let config = Config::from_env().expect("Failed to load configuration");
+
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| config.log_level.clone().into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    info!(
+        "Starting MISO LIMS API Server v{}",
+        env!("CARGO_PKG_VERSION")
+    );
+    for plugin in plugins.installed() {
+        info!("Plugin installed: {}", plugin);
+    }
+
+    // Connect to database
+    let db = Database::connect(
+        DatabaseConfig::new(&config.database_url)
+            .slow_query_threshold(config.slow_query_threshold()),
+    )
+    .await
+    .expect("Failed to connect to database");
+
+    // Create repositories
+    let repositories = Repositories {
+        projects: Arc::new(SeaOrmProjectRepository::new(db.connection().clone())),
+        samples: Arc::new(SeaOrmSampleRepository::new(db.connection().clone())),
+        libraries: Arc::new(SeaOrmLibraryRepository::new(db.connection().clone())),
+        index_sets: Arc::new(SeaOrmIndexSetRepository::new(db.connection().clone())),
+        pools: Arc::new(SeaOrmPoolRepository::new(db.connection().clone())),
+        run_metrics: Arc::new(SeaOrmRunMetricsRepository::new(db.connection().clone())),
+        qc_reports: Arc::new(SeaOrmQcReportRepository::new(db.connection().clone())),
+        export_templates: Arc::new(SeaOrmExportTemplateRepository::new(db.connection().clone())),
+        change_logs: Arc::new(SeaOrmChangeLogRepository::new(db.connection().clone())),
+        project_activity: Arc::new(SeaOrmProjectActivityRepository::new(
+            db.connection().clone(),
+        )),
+        instrument_models: Arc::new(SeaOrmInstrumentModelRepository::new(
+            db.connection().clone(),
+        )),
+        data_locations: Arc::new(SeaOrmDataLocationRepository::new(db.connection().clone())),
+        instrument_events: Arc::new(SeaOrmInstrumentEventRepository::new(
+            db.connection().clone(),
+        )),
+        attribute_definitions: Arc::new(SeaOrmAttributeDefinitionRepository::new(
+            db.connection().clone(),
+        )),
+        users: Arc::new(SeaOrmUserRepository::new(db.connection().clone())),
+        panels: Arc::new(SeaOrmPanelRepository::new(db.connection().clone())),
+        reference_genomes: Arc::new(SeaOrmReferenceGenomeRepository::new(
+            db.connection().clone(),
+        )),
+        api_keys: Arc::new(SeaOrmApiKeyRepository::new(db.connection().clone())),
+        possible_duplicates: Arc::new(SeaOrmPossibleDuplicateRepository::new(
+            db.connection().clone(),
+        )),
+        audit_log: Arc::new(SeaOrmAuditLogRepository::new(db.connection().clone())),
+        event_store: Arc::new(SeaOrmEventStoreRepository::new(db.connection().clone())),
+        erasures: Arc::new(SeaOrmErasureRepository::new(db.connection().clone())),
+        qc_records: Arc::new(SeaOrmQcRecordRepository::new(db.connection().clone())),
+        consents: Arc::new(SeaOrmConsentRepository::new(db.connection().clone())),
+        storage_audits: Arc::new(SeaOrmStorageAuditRepository::new(db.connection().clone())),
+        log_archives: Arc::new(SeaOrmLogArchiveRepository::new(db.connection().clone())),
+        label_prints: Arc::new(SeaOrmLabelPrintRepository::new(db.connection().clone())),
+        library_aliquots: Arc::new(SeaOrmLibraryAliquotRepository::new(db.connection().clone())),
+        worksets: Arc::new(SeaOrmWorksetRepository::new(db.connection().clone())),
+        transfers: Arc::new(SeaOrmTransferRepository::new(db.connection().clone())),
+        requisitions: Arc::new(SeaOrmRequisitionRepository::new(db.connection().clone())),
+        extraction_batches: Arc::new(SeaOrmExtractionBatchRepository::new(
+            db.connection().clone(),
+        )),
+        reagent_lots: Arc::new(SeaOrmReagentLotRepository::new(db.connection().clone())),
+        attachments: Arc::new(SeaOrmAttachmentRepository::new(db.connection().clone())),
+        project_members: Arc::new(SeaOrmProjectMemberRepository::new(db.connection().clone())),
+        delivery_targets: Arc::new(SeaOrmDeliveryTargetRepository::new(db.connection().clone())),
+        deliveries: Arc::new(SeaOrmDeliveryRepository::new(db.connection().clone())),
+        subprojects: Arc::new(SeaOrmSubprojectRepository::new(db.connection().clone())),
+        archive_rules: Arc::new(SeaOrmArchiveRuleRepository::new(db.connection().clone())),
+        auto_archivals: Arc::new(SeaOrmAutoArchivalRepository::new(db.connection().clone())),
+        maintenance_windows: Arc::new(SeaOrmMaintenanceWindowRepository::new(
+            db.connection().clone(),
+        )),
+        api_usage: Arc::new(SeaOrmApiUsageRepository::new(db.connection().clone())),
+        storage_units: Arc::new(SeaOrmStorageUnitRepository::new(db.connection().clone())),
+        sample_compositions: Arc::new(SeaOrmSampleCompositionRepository::new(
+            db.connection().clone(),
+        )),
+        sample_list: Arc::new(SeaOrmSampleListRepository::new(db.connection().clone())),
+        boxes: Some(Arc::new(SeaOrmStorageBoxRepository::new(
+            db.connection().clone(),
+        ))),
+        runs: Some(Arc::new(SeaOrmRunRepository::new(db.connection().clone()))),
+        sequencers: Some(Arc::new(SeaOrmSequencerRepository::new(
+            db.connection().clone(),
+        ))),
+    };
+
+    // Notifications are emailed if an SMTP relay is configured
+    let notifier: Arc<dyn Notifier> = match &config.email {
+        Some(email) => Arc::new(EmailNotifier::new(email.to_email_config()?)?),
+        None => Arc::new(LogNotifier::new()),
+    };
+
+    // Start background jobs
+    let mut scheduler = Scheduler::new();
+    if let Some(digest) = &config.digest {
+        let mut job = DigestJob::new(digest.roles()?, notifier.clone())
+            .with_template(digest.template()?)
+            .with_samples(repositories.samples.clone());
+        if let Some(runs) = &repositories.runs {
+            job = job.with_runs(runs.clone());
+        }
+        if let Some(boxes) = &repositories.boxes {
+            job = job.with_boxes(boxes.clone(), digest.box_fill_percent);
+        }
+        scheduler = scheduler.register(digest.schedule()?, Arc::new(job));
+    }
+    if let (Some(basespace), Some(runs), Some(sequencers)) =
+        (&config.basespace, &repositories.runs, &repositories.sequencers)
+    {
+        let job = RunImportJob::new(
+            runs.clone(),
+            sequencers.clone(),
+            Arc::new(BaseSpaceClient::new(basespace.to_basespace_config())),
+        );
+        scheduler = scheduler.register(basespace.schedule()?, Arc::new(job));
+    }
+    if let (Some(runs), Some(sequencers)) = (&repositories.runs, &repositories.sequencers) {
+        let stale_runs = config.stale_runs.clone().unwrap_or_default();
+        let job = StaleRunJob::new(
+            runs.clone(),
+            sequencers.clone(),
+            repositories.instrument_models.clone(),
+            notifier.clone(),
+        );
+        scheduler = scheduler.register(stale_runs.schedule()?, Arc::new(job));
+    }
+    if let Some(retention) = &config.retention {
+        let service = RetentionService::new(repositories.log_archives.clone(), &retention.directory)
+            .with_policy(retention.policy()?);
+        let job = LogArchivalJob::new(Arc::new(service));
+        scheduler = scheduler.register(retention.schedule()?, Arc::new(job));
+    }
+    if let Some(reagents) = &config.reagents {
+        let inventory = ReagentInventoryService::new(repositories.reagent_lots.clone());
+        let job = ReagentAlertJob::new(Arc::new(inventory), notifier.clone(), reagents.expiry_days);
+        scheduler = scheduler.register(reagents.schedule()?, Arc::new(job));
+    }
+    if let Some(boxes) = &repositories.boxes {
+        let reconciliation = config.reconciliation.clone().unwrap_or_default();
+        let job = LocationReconciliationJob::new(
+            boxes.clone(),
+            Arc::new(SeaOrmItemLocationRepository::new(db.connection().clone())),
+            Arc::new(SeaOrmReconciliationReportRepository::new(
+                db.connection().clone(),
+            )),
+            notifier.clone(),
+        );
+        scheduler = scheduler.register(reconciliation.schedule()?, Arc::new(job));
+    }
+    if let Some(tracking) = &config.shipment_tracking {
+        let transfers = TransferService::new(
+            repositories.transfers.clone(),
+            repositories.samples.clone(),
+            repositories.libraries.clone(),
+        )
+        .with_notifier(notifier.clone());
+        let job = ShipmentTrackingJob::new(
+            Arc::new(transfers),
+            Arc::new(HttpShipmentTracker::new(tracking.to_shipment_tracking_config())),
+        );
+        scheduler = scheduler.register(tracking.schedule()?, Arc::new(job));
+    }
+
+    // Directory users can log in too if an LDAP server is configured
+    let ldap = match &config.ldap {
+        Some(ldap) => Some(Arc::new(LdapAuthProvider::new(
+            LdapClient::new(ldap.to_ldap_config()?),
+            repositories.users.clone(),
+        ))),
+        None => None,
+    };
+
+    // Single sign-on users log in at the identity provider if one is configured
+    let oidc = match &config.oidc {
+        Some(oidc) => Some(OidcAuthProvider::new(
+            OidcClient::new(oidc.to_oidc_config()?)?,
+            repositories.users.clone(),
+        )),
+        None => None,
+    };
+
+    // Create application state
+    let mut state = AppState::with_plugins(config.clone(), repositories, plugins, notifier.clone()).with_database(db);
+    if let Some(ldap) = ldap {
+        state = state.with_auth_provider(ldap);
+    }
+    if let Some(oidc) = oidc {
+        state = state.with_oidc(oidc);
+    }
+
+    // API usage is sampled by the handlers' state, so its counts are
+    // written out from there
+    let api_usage = config.api_usage.clone().unwrap_or_default();
+    let job = ApiUsageFlushJob::new(state.api_usage_service.clone());
+    scheduler = scheduler.register(api_usage.schedule()?, Arc::new(job));
+
+    // Archiving rules are evaluated by the same service that previews them
+    if let Some(rules) = &state.archive_rule_service {
+        let auto_archive = config.auto_archive.clone().unwrap_or_default();
+        let job = AutoArchiveJob::new(rules.clone(), notifier);
+        scheduler = scheduler.register(auto_archive.schedule()?, Arc::new(job));
+    }
+    let _jobs = scheduler.start();
+
+    // Create router
+    let app = routes::create_router(state);
+
+    // Start server
+    let addr = config.address();
+    info!("Server listening on http://{}", addr);
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -2,44 +2,739 @@
 
 use std::sync::Arc;
 
-use miso_application::{ProjectService, SampleService};
-use miso_domain::repositories::{ProjectRepository, SampleRepository};
-use miso_infrastructure::hardware::scanner::VisionMateClient;
+use miso_application::access::ProjectAccess;
+use miso_application::audit::AuditTrail;
+use miso_application::locks::ProjectLocks;
+use miso_application::plugins::PluginRegistry;
+use miso_application::redaction::Redaction;
+use miso_application::{
+    ActivityService, ApiKeyService, ApiUsageService, ArchiveRuleService, AttachmentService, AttributeDefinitionService, AuditService, FacilityStatusService, BoxImportService, ConsentService, DashboardService, DataLocationService, DeliveryService, ErasureService, EventStoreService, ExportService,
+    IndexSetService, InstrumentEventService, InstrumentModelService, LabelPrintService, LibraryAliquotService, LibraryService, PanelService, PoolService, ProjectMemberService, ProjectService, QcReportService, QcService, ReagentInventoryService, ReferenceGenomeService, RetentionService,
+    RunMetricsService, RunMonitorService, RunService, SampleImportService, SampleListProjection, SampleListService, SampleService, SampleSheetImportService, SampleSheetService, SearchService, SequencerService, StorageAuditService, StorageBrowserService, SubprojectService,
+    ExtractionBatchService, RequisitionService, StorageUnitService, TransferService, WorksetService,
+};
+use miso_domain::repositories::{
+    ApiKeyRepository, ApiUsageRepository, ArchiveRuleRepository, AttachmentRepository, AttributeDefinitionRepository, AuditLogRepository, AutoArchivalRepository, ChangeLogRepository, ConsentRepository, DataLocationRepository, DeliveryRepository, DeliveryTargetRepository, ErasureRepository, EventStoreRepository, ExportTemplateRepository, IndexSetRepository,
+    InstrumentEventRepository, InstrumentModelRepository, LabelPrintRepository, LibraryAliquotRepository, LibraryRepository, LogArchiveRepository, MaintenanceWindowRepository, PanelRepository, PoolRepository, PossibleDuplicateRepository,
+    ProjectActivityRepository, ProjectMemberRepository,
+    ProjectRepository, QcRecordRepository, QcReportRepository, ReagentLotRepository, ReferenceGenomeRepository, RunMetricsRepository, RunRepository,
+    SampleListRepository, SampleRepository, SequencerRepository, StorageAuditRepository, StorageBoxRepository, UserRepository,
+    ExtractionBatchRepository, RequisitionRepository, SampleCompositionRepository, StorageUnitRepository, SubprojectRepository, TransferRepository,
+    WorksetRepository,
+};
+use miso_domain::attachments::AttachmentStore;
+use miso_domain::notifications::Notifier;
+use miso_domain::services::CollisionCheckConfig;
+use miso_infrastructure::auth::{local::LocalAuthProvider, AuthProvider};
+use miso_infrastructure::external::oidc::OidcAuthProvider;
+use miso_infrastructure::external::run_planning::HttpRunPlanner;
 use miso_infrastructure::hardware::printer::ZebraPrinter;
+use miso_infrastructure::hardware::scanner::VisionMateClient;
+use miso_infrastructure::notifications::log::LogNotifier;
+use miso_infrastructure::persistence::Database;
+use miso_infrastructure::storage::local::LocalAttachmentStore;
+use miso_infrastructure::storage::s3::S3AttachmentStore;
 
+use crate::config::{
+    ControlSettings, LibraryKitSettings, PoolLimitSettings, PoolTargetSettings, QcThresholdSettings,
+};
+use crate::middleware::{ApiQuota, RevokedTokens};
 use crate::Config;
 
+/// Repository implementations the application state is built from.
+///
+/// Repositories are held as trait objects so that handlers need not be
+/// generic over every backing store.
+#[derive(Clone)]
+pub struct Repositories {
+    pub projects: Arc<dyn ProjectRepository>,
+    pub samples: Arc<dyn SampleRepository>,
+    pub libraries: Arc<dyn LibraryRepository>,
+    pub index_sets: Arc<dyn IndexSetRepository>,
+    pub pools: Arc<dyn PoolRepository>,
+    pub run_metrics: Arc<dyn RunMetricsRepository>,
+    pub qc_reports: Arc<dyn QcReportRepository>,
+    pub export_templates: Arc<dyn ExportTemplateRepository>,
+    pub change_logs: Arc<dyn ChangeLogRepository>,
+    pub project_activity: Arc<dyn ProjectActivityRepository>,
+    pub instrument_models: Arc<dyn InstrumentModelRepository>,
+    pub data_locations: Arc<dyn DataLocationRepository>,
+    pub instrument_events: Arc<dyn InstrumentEventRepository>,
+    pub attribute_definitions: Arc<dyn AttributeDefinitionRepository>,
+    pub users: Arc<dyn UserRepository>,
+    pub panels: Arc<dyn PanelRepository>,
+    pub reference_genomes: Arc<dyn ReferenceGenomeRepository>,
+    pub api_keys: Arc<dyn ApiKeyRepository>,
+    pub possible_duplicates: Arc<dyn PossibleDuplicateRepository>,
+    pub audit_log: Arc<dyn AuditLogRepository>,
+    pub event_store: Arc<dyn EventStoreRepository>,
+    pub erasures: Arc<dyn ErasureRepository>,
+    pub qc_records: Arc<dyn QcRecordRepository>,
+    pub consents: Arc<dyn ConsentRepository>,
+    pub storage_audits: Arc<dyn StorageAuditRepository>,
+    /// Archives of old audit log, change log and storage audit rows
+    pub log_archives: Arc<dyn LogArchiveRepository>,
+    /// Record of the labels printed
+    pub label_prints: Arc<dyn LabelPrintRepository>,
+    /// Portions of libraries taken out for pooling
+    pub library_aliquots: Arc<dyn LibraryAliquotRepository>,
+    /// Batches of samples and libraries taken through a workflow step
+    pub worksets: Arc<dyn WorksetRepository>,
+    /// Samples and libraries handed between labs or people
+    pub transfers: Arc<dyn TransferRepository>,
+    /// External orders samples are received for
+    pub requisitions: Arc<dyn RequisitionRepository>,
+    /// Samples extracted together, with their kit lots and reagent blanks
+    pub extraction_batches: Arc<dyn ExtractionBatchRepository>,
+    /// Kit lots held in stock
+    pub reagent_lots: Arc<dyn ReagentLotRepository>,
+    /// Metadata of files attached to samples, libraries and runs
+    pub attachments: Arc<dyn AttachmentRepository>,
+    /// Which users belong to which projects
+    pub project_members: Arc<dyn ProjectMemberRepository>,
+    /// Places projects release their data to
+    pub delivery_targets: Arc<dyn DeliveryTargetRepository>,
+    /// Runs' data delivered to projects
+    pub deliveries: Arc<dyn DeliveryRepository>,
+    /// Partitions of projects' samples
+    pub subprojects: Arc<dyn SubprojectRepository>,
+    /// Rules archiving libraries and samples after their last run
+    pub archive_rules: Arc<dyn ArchiveRuleRepository>,
+    /// Items archived by the rules
+    pub auto_archivals: Arc<dyn AutoArchivalRepository>,
+    /// Planned sequencer and facility maintenance
+    pub maintenance_windows: Arc<dyn MaintenanceWindowRepository>,
+    /// Hourly API call counts per caller and endpoint
+    pub api_usage: Arc<dyn ApiUsageRepository>,
+    /// Freezers, shelves and racks boxes are assigned to
+    pub storage_units: Arc<dyn StorageUnitRepository>,
+    /// Identities pooled samples were made from
+    pub sample_compositions: Arc<dyn SampleCompositionRepository>,
+    /// Sample list read model, kept up to date from domain events
+    pub sample_list: Arc<dyn SampleListRepository>,
+    /// Storage boxes; box browsing is unavailable without them
+    pub boxes: Option<Arc<dyn StorageBoxRepository>>,
+    /// Sequencing runs; the run detail page is unavailable without them
+    pub runs: Option<Arc<dyn RunRepository>>,
+    /// Sequencers; runs can't be set up or started without them
+    pub sequencers: Option<Arc<dyn SequencerRepository>>,
+}
+
+/// Box layout import service over the repository trait objects.
+pub type BoxImporter = BoxImportService<
+    dyn StorageBoxRepository,
+    dyn SampleRepository,
+    dyn LibraryRepository,
+    dyn PoolRepository,
+    dyn ProjectRepository,
+>;
+
+/// Run sample sheet service over the repository trait objects.
+pub type RunSampleSheets = SampleSheetService<
+    dyn RunRepository,
+    dyn PoolRepository,
+    dyn LibraryRepository,
+    dyn ProjectRepository,
+>;
+
+/// Sample sheet import service over the repository trait objects.
+pub type SampleSheetImporter = SampleSheetImportService<
+    dyn LibraryRepository,
+    dyn PoolRepository,
+    dyn RunRepository,
+    dyn SequencerRepository,
+    dyn InstrumentModelRepository,
+>;
+
+/// Run lifecycle service over the repository trait objects.
+pub type RunLifecycleService =
+    RunService<dyn RunRepository, dyn SequencerRepository, dyn PoolRepository>;
+
 /// Shared application state.
 #[derive(Clone)]
-pub struct AppState<PR: ProjectRepository, SR: SampleRepository> {
+pub struct AppState {
     /// Application configuration
     pub config: Arc<Config>,
+    /// Users, loaded for each authenticated request
+    pub users: Arc<dyn UserRepository>,
+    /// Tokens revoked by logging out or refreshing
+    pub revoked_tokens: Arc<RevokedTokens>,
+    /// Calls each caller has left this hour; unlimited if unset
+    pub api_quota: Option<Arc<ApiQuota>>,
+    /// Where logins are checked, in order; internal users first
+    pub auth_providers: Vec<Arc<dyn AuthProvider>>,
+    /// API keys for instruments and scripts
+    pub api_key_service: Arc<ApiKeyService<dyn ApiKeyRepository>>,
+    /// Single sign-on through an OpenID Connect provider (optional)
+    pub oidc: Option<Arc<OidcAuthProvider>>,
     /// Project service
-    pub project_service: Arc<ProjectService<PR>>,
+    pub project_service: Arc<ProjectService<dyn ProjectRepository>>,
+    /// Project membership service
+    pub project_member_service: Arc<ProjectMemberService>,
     /// Sample service
-    pub sample_service: Arc<SampleService<SR>>,
+    pub sample_service: Arc<SampleService<dyn SampleRepository, dyn ChangeLogRepository>>,
+    /// Sample manifest import service
+    pub sample_import_service:
+        Arc<SampleImportService<dyn SampleRepository, dyn ProjectRepository>>,
+    /// Sample list read model service
+    pub sample_list_service: Arc<SampleListService<dyn SampleListRepository>>,
+    /// Library service
+    pub library_service: Arc<LibraryService<dyn LibraryRepository, dyn SampleRepository>>,
+    /// Library aliquot service
+    pub library_aliquot_service:
+        Arc<LibraryAliquotService<dyn LibraryAliquotRepository, dyn LibraryRepository>>,
+    /// Workset service
+    pub workset_service:
+        Arc<WorksetService<dyn WorksetRepository, dyn SampleRepository, dyn LibraryRepository>>,
+    /// Transfer service
+    pub transfer_service:
+        Arc<TransferService<dyn TransferRepository, dyn SampleRepository, dyn LibraryRepository>>,
+    /// Requisition service
+    pub requisition_service:
+        Arc<RequisitionService<dyn RequisitionRepository, dyn SampleRepository>>,
+    /// Extraction batch service
+    pub extraction_batch_service:
+        Arc<ExtractionBatchService<dyn ExtractionBatchRepository, dyn SampleRepository>>,
+    /// Reagent inventory service
+    pub reagent_inventory_service: Arc<ReagentInventoryService<dyn ReagentLotRepository>>,
+    /// Sample, library and run attachment service
+    pub attachment_service: Arc<AttachmentService>,
+    /// Project data delivery service
+    pub delivery_service: Arc<DeliveryService>,
+    /// Subproject service
+    pub subproject_service: Arc<SubprojectService>,
+    /// Maintenance window and public status service
+    pub facility_status_service: Arc<FacilityStatusService>,
+    /// API usage sampling and report service
+    pub api_usage_service: Arc<ApiUsageService>,
+    /// Index catalog service
+    pub index_set_service: Arc<IndexSetService<dyn IndexSetRepository>>,
+    /// Targeted panel service
+    pub panel_service: Arc<PanelService<dyn PanelRepository>>,
+    /// Reference genome registry service
+    pub reference_genome_service: Arc<
+        ReferenceGenomeService<dyn ReferenceGenomeRepository, dyn LibraryRepository, dyn ProjectRepository>,
+    >,
+    /// Pool service
+    pub pool_service: Arc<PoolService<dyn PoolRepository, dyn LibraryRepository>>,
+    /// Run metrics service
+    pub run_metrics_service: Arc<RunMetricsService<dyn RunMetricsRepository>>,
+    /// QC measurement service
+    pub qc_service: Arc<QcService>,
+    /// QC report service
+    pub qc_report_service: Arc<QcReportService<dyn QcReportRepository>>,
+    /// Run data location registry service
+    pub data_location_service: Arc<DataLocationService<dyn DataLocationRepository>>,
+    /// Instrument-reported run event service
+    pub instrument_event_service: Arc<InstrumentEventService<dyn InstrumentEventRepository>>,
+    /// Project activity feed service
+    pub activity_service:
+        Arc<ActivityService<dyn ProjectActivityRepository, dyn ProjectRepository>>,
+    /// Instrument model catalog service
+    pub instrument_model_service: Arc<InstrumentModelService<dyn InstrumentModelRepository>>,
+    /// Custom attribute service
+    pub attribute_service: Arc<AttributeDefinitionService<dyn AttributeDefinitionRepository>>,
+    /// Audit log service
+    pub audit_service: Arc<AuditService<dyn AuditLogRepository>>,
+    /// Log retention and archive service
+    pub retention_service: Arc<RetentionService<dyn LogArchiveRepository>>,
+    /// Label printing service, enforcing unique labels
+    pub label_print_service: Arc<LabelPrintService<dyn LabelPrintRepository>>,
+    /// Event store read model and replay service
+    pub event_store_service: Arc<EventStoreService<dyn EventStoreRepository>>,
+    /// Participant consent service
+    pub consent_service: Arc<ConsentService>,
+    /// Withdrawn participant erasure service
+    pub erasure_service: Arc<ErasureService>,
+    /// Dashboard statistics service
+    pub dashboard_service: Arc<DashboardService>,
+    /// Box storage browsing service, if boxes are persisted
+    pub storage_browser_service:
+        Option<Arc<StorageBrowserService<dyn StorageBoxRepository, dyn SampleRepository>>>,
+    /// Box layout import service, if boxes are persisted
+    pub box_import_service: Option<Arc<BoxImporter>>,
+    /// Freezer inventory audit service, if boxes are persisted
+    pub storage_audit_service: Option<Arc<StorageAuditService>>,
+    /// Freezer, shelf and rack service, if boxes are persisted
+    pub storage_unit_service:
+        Option<Arc<StorageUnitService<dyn StorageUnitRepository, dyn StorageBoxRepository>>>,
+    /// Run monitoring service, if runs are persisted
+    pub run_monitor_service: Option<Arc<RunMonitorService>>,
+    /// Run lifecycle service, if runs and sequencers are persisted
+    pub run_service: Option<Arc<RunLifecycleService>>,
+    /// Run sample sheet service, if runs are persisted
+    pub sample_sheet_service: Option<Arc<RunSampleSheets>>,
+    /// Sample sheet import service, if runs and sequencers are persisted
+    pub sample_sheet_import_service: Option<Arc<SampleSheetImporter>>,
+    /// Automatic archiving rule service, if runs are persisted
+    pub archive_rule_service: Option<Arc<ArchiveRuleService>>,
+    /// Sequencer registry service, if sequencers are persisted
+    pub sequencer_service: Option<Arc<SequencerService>>,
+    /// Project and sample search service
+    pub search_service: Arc<SearchService<dyn ProjectRepository, dyn SampleRepository>>,
+    /// Export template service
+    pub export_service: Arc<
+        ExportService<dyn ExportTemplateRepository, dyn ProjectRepository, dyn SampleRepository>,
+    >,
+    /// Database handle for pool instrumentation (optional)
+    pub database: Option<Database>,
     /// VisionMate scanner client (optional)
     pub scanner: Option<Arc<VisionMateClient>>,
     /// Zebra printer client (optional)
     pub printer: Option<Arc<ZebraPrinter>>,
 }
 
-impl<PR: ProjectRepository, SR: SampleRepository> AppState<PR, SR> {
+impl AppState {
     /// Creates a new application state.
-    pub fn new(
+    pub fn new(config: Config, repositories: Repositories) -> Self {
+        Self::with_plugins(
+            config,
+            repositories,
+            PluginRegistry::new(),
+            Arc::new(LogNotifier::new()),
+        )
+    }
+
+    /// Creates a new application state whose services run site plugins'
+    /// hooks and send notifications through `notifier`.
+    pub fn with_plugins(
         config: Config,
-        project_repo: Arc<PR>,
-        sample_repo: Arc<SR>,
+        repositories: Repositories,
+        mut plugins: PluginRegistry,
+        notifier: Arc<dyn Notifier>,
     ) -> Self {
+        plugins.add_subscriber(Arc::new(SampleListProjection::new(
+            repositories.sample_list.clone(),
+            repositories.projects.clone(),
+        )));
+        let plugins = Arc::new(plugins);
+        let mut audit = AuditTrail::new(repositories.audit_log.clone());
+        if config.event_store {
+            audit = audit.with_event_store(repositories.event_store.clone());
+        }
+        let project_locks = ProjectLocks::new(repositories.projects.clone());
+        let project_access = if config.project_membership {
+            ProjectAccess::new(repositories.project_members.clone(), repositories.users.clone())
+        } else {
+            ProjectAccess::default()
+        };
+        let project_member_service = ProjectMemberService::new(
+            repositories.project_members,
+            repositories.projects.clone(),
+            repositories.users.clone(),
+        )
+        .with_audit(audit.clone());
+        let pool_limits = config
+            .pool_limits
+            .as_ref()
+            .map(PoolLimitSettings::limits)
+            .unwrap_or_default();
+        let collision_config = CollisionCheckConfig {
+            min_distance: config.min_index_distance,
+            ..Default::default()
+        };
+        let pool_targets = config
+            .pool_targets
+            .as_ref()
+            .map(PoolTargetSettings::targets)
+            .unwrap_or_default();
+        let library_kits = config
+            .library_kits
+            .as_ref()
+            .map(LibraryKitSettings::compatibility)
+            .unwrap_or_default();
+        let qc_evaluator = config
+            .qc_thresholds
+            .as_ref()
+            .map(QcThresholdSettings::evaluator)
+            .unwrap_or_default();
+        let controls = config
+            .controls
+            .as_ref()
+            .map(ControlSettings::policy)
+            .unwrap_or_default();
+        // An invalid policy is refused at startup, so is never left out here
+        let retention = config.retention.clone().unwrap_or_default();
+        let retention_service =
+            RetentionService::new(repositories.log_archives.clone(), &retention.directory)
+                .with_policy(retention.policy().unwrap_or_default());
+        let label_print_service = LabelPrintService::new(repositories.label_prints.clone())
+            .with_unique_templates(
+                config
+                    .labels
+                    .as_ref()
+                    .map(|labels| labels.unique_templates())
+                    .unwrap_or_default(),
+            );
+        let mut dashboard_service = DashboardService::new(repositories.samples.clone());
+        if let Some(boxes) = &repositories.boxes {
+            dashboard_service = dashboard_service.with_boxes(boxes.clone());
+        }
+        if let (Some(runs), Some(sequencers)) = (&repositories.runs, &repositories.sequencers) {
+            dashboard_service = dashboard_service.with_runs(runs.clone(), sequencers.clone());
+        }
+        let sample_sheet_service = repositories.runs.clone().map(|runs| {
+            let mut sample_sheets = SampleSheetService::new(
+                runs,
+                repositories.pools.clone(),
+                repositories.libraries.clone(),
+                repositories.projects.clone(),
+            )
+            .with_panels(repositories.panels.clone())
+            .with_reference_genomes(repositories.reference_genomes.clone())
+            .with_audit(audit.clone());
+            if let Some(sequencers) = &repositories.sequencers {
+                sample_sheets = sample_sheets.with_sequencers(sequencers.clone());
+            }
+            if let Some(run_planning) = &config.run_planning {
+                sample_sheets = sample_sheets.with_run_planner(Arc::new(HttpRunPlanner::new(
+                    run_planning.to_run_planning_config(),
+                )));
+            }
+            Arc::new(sample_sheets)
+        });
+        let attachment_store: Arc<dyn AttachmentStore> =
+            match config.attachments.as_ref().and_then(|a| a.s3.as_ref()) {
+                Some(s3) => Arc::new(S3AttachmentStore::new(s3.to_s3_config())),
+                None => Arc::new(LocalAttachmentStore::new(
+                    config
+                        .attachments
+                        .as_ref()
+                        .map_or("attachments", |a| a.directory.as_str()),
+                )),
+            };
+        let mut attachment_service = AttachmentService::new(
+            repositories.attachments,
+            attachment_store,
+            repositories.samples.clone(),
+            repositories.libraries.clone(),
+        )
+        .with_audit(audit.clone())
+        .with_project_access(project_access.clone())
+        .with_project_locks(project_locks.clone());
+        if let Some(runs) = &repositories.runs {
+            attachment_service = attachment_service.with_runs(runs.clone());
+        }
+        let mut delivery_service = DeliveryService::new(
+            repositories.delivery_targets,
+            repositories.deliveries,
+            repositories.projects.clone(),
+            repositories.data_locations.clone(),
+        )
+        .with_audit(audit.clone());
+        if let Some(runs) = &repositories.runs {
+            delivery_service = delivery_service.with_runs(runs.clone());
+        }
+        let subproject_service =
+            SubprojectService::new(repositories.subprojects.clone(), repositories.projects.clone())
+                .with_audit(audit.clone())
+                .with_project_locks(project_locks.clone());
+        let mut facility_status_service =
+            FacilityStatusService::new(repositories.maintenance_windows).with_audit(audit.clone());
+        if let Some(sequencers) = &repositories.sequencers {
+            facility_status_service = facility_status_service.with_sequencers(sequencers.clone());
+        }
+        let api_usage = config.api_usage.clone().unwrap_or_default();
+        let api_usage_service = ApiUsageService::new(repositories.api_usage, api_usage.sample_every);
+        let sequencer_service = repositories.sequencers.clone().map(|sequencers| {
+            let mut sequencer_service =
+                SequencerService::new(sequencers, repositories.instrument_models.clone())
+                    .with_audit(audit.clone());
+            if let Some(runs) = &repositories.runs {
+                sequencer_service = sequencer_service.with_runs(runs.clone());
+            }
+            Arc::new(sequencer_service)
+        });
+        let sample_sheet_import_service = match (&repositories.runs, &repositories.sequencers) {
+            (Some(runs), Some(sequencers)) => Some(Arc::new(SampleSheetImportService::new(
+                repositories.libraries.clone(),
+                repositories.pools.clone(),
+                runs.clone(),
+                sequencers.clone(),
+                repositories.instrument_models.clone(),
+            ))),
+            _ => None,
+        };
+        let run_service = match (&repositories.runs, repositories.sequencers) {
+            (Some(runs), Some(sequencers)) => Some(Arc::new(
+                RunService::new(runs.clone(), sequencers, repositories.pools.clone())
+                    .with_controls(controls.clone(), repositories.libraries.clone())
+                    .with_audit(audit.clone()),
+            )),
+            _ => None,
+        };
+
+        let qc_service = QcService::new(
+            repositories.qc_records,
+            repositories.samples.clone(),
+            repositories.libraries.clone(),
+            repositories.pools.clone(),
+            repositories.change_logs.clone(),
+        )
+        .with_evaluator(qc_evaluator)
+        .with_plugins(plugins.clone())
+        .with_audit(audit.clone())
+        .with_project_access(project_access.clone())
+        .with_project_locks(project_locks.clone());
+        let consent_service = ConsentService::new(
+            repositories.consents.clone(),
+            repositories.samples.clone(),
+        )
+        .with_audit(audit.clone());
+        let box_import_service = repositories.boxes.clone().map(|boxes| {
+            Arc::new(
+                BoxImportService::new(
+                    boxes,
+                    repositories.samples.clone(),
+                    repositories.libraries.clone(),
+                    repositories.pools.clone(),
+                    repositories.projects.clone(),
+                )
+                .with_audit(audit.clone()),
+            )
+        });
+        let storage_audit_service = repositories.boxes.clone().map(|boxes| {
+            Arc::new(StorageAuditService::new(
+                repositories.storage_audits,
+                boxes,
+                repositories.samples.clone(),
+                repositories.libraries.clone(),
+                repositories.pools.clone(),
+            ))
+        });
+        let archive_rule_service = repositories.runs.clone().map(|runs| {
+            Arc::new(
+                ArchiveRuleService::new(
+                    repositories.archive_rules,
+                    repositories.auto_archivals,
+                    runs,
+                    repositories.pools.clone(),
+                    repositories.libraries.clone(),
+                    repositories.samples.clone(),
+                )
+                .with_audit(audit.clone())
+                .with_project_locks(project_locks.clone()),
+            )
+        });
+        let storage_unit_service = repositories.boxes.clone().map(|boxes| {
+            Arc::new(
+                StorageUnitService::new(repositories.storage_units, boxes)
+                    .with_audit(audit.clone()),
+            )
+        });
+        let redaction = Redaction::new(repositories.consents, repositories.samples.clone())
+            .with_compositions(repositories.sample_compositions.clone());
+        let erasure_service = ErasureService::new(
+            repositories.samples.clone(),
+            repositories.change_logs.clone(),
+            repositories.audit_log.clone(),
+            repositories.possible_duplicates.clone(),
+            repositories.erasures.clone(),
+        )
+        .with_compositions(repositories.sample_compositions.clone())
+        // Events recorded while the store was enabled are erased even if
+        // it since was turned off
+        .with_event_store(repositories.event_store.clone());
+
         Self {
             config: Arc::new(config),
-            project_service: Arc::new(ProjectService::new(project_repo)),
-            sample_service: Arc::new(SampleService::new(sample_repo)),
+            users: repositories.users.clone(),
+            revoked_tokens: Arc::new(RevokedTokens::new()),
+            api_quota: api_usage
+                .requests_per_hour
+                .map(|limit| Arc::new(ApiQuota::new(limit))),
+            auth_providers: vec![Arc::new(LocalAuthProvider::new(repositories.users))],
+            oidc: None,
+            api_key_service: Arc::new(ApiKeyService::new(repositories.api_keys)),
+            project_service: Arc::new(
+                ProjectService::new(repositories.projects.clone())
+                    .with_reference_genomes(repositories.reference_genomes.clone())
+                    .with_plugins(plugins.clone())
+                    .with_audit(audit.clone()),
+            ),
+            project_member_service: Arc::new(project_member_service),
+            sample_service: Arc::new(
+                SampleService::new(repositories.samples.clone(), repositories.change_logs.clone())
+                    .with_attribute_definitions(repositories.attribute_definitions.clone())
+                    .with_possible_duplicates(repositories.possible_duplicates)
+                    .with_erasures(repositories.erasures)
+                    .with_compositions(repositories.sample_compositions)
+                    .with_subprojects(repositories.subprojects)
+                    .with_plugins(plugins.clone())
+                    .with_audit(audit.clone())
+                    .with_project_locks(project_locks.clone())
+                    .with_project_access(project_access.clone()),
+            ),
+            sample_import_service: Arc::new(
+                SampleImportService::new(
+                    repositories.samples.clone(),
+                    repositories.projects.clone(),
+                )
+                .with_plugins(plugins.clone())
+                .with_audit(audit.clone())
+                .with_project_access(project_access.clone()),
+            ),
+            sample_list_service: Arc::new(
+                SampleListService::new(repositories.sample_list)
+                    .with_project_access(project_access.clone()),
+            ),
+            library_service: Arc::new(
+                LibraryService::new(repositories.libraries.clone(), repositories.samples.clone())
+                    .with_kits(library_kits)
+                    .with_panels(repositories.panels.clone())
+                    .with_reference_genomes(repositories.reference_genomes.clone())
+                    .with_index_sets(repositories.index_sets.clone())
+                    .with_plugins(plugins.clone())
+                    .with_audit(audit.clone())
+                    .with_project_locks(project_locks.clone())
+                    .with_project_access(project_access.clone()),
+            ),
+            library_aliquot_service: Arc::new(
+                LibraryAliquotService::new(
+                    repositories.library_aliquots,
+                    repositories.libraries.clone(),
+                )
+                .with_audit(audit.clone())
+                .with_project_locks(project_locks.clone()),
+            ),
+            workset_service: Arc::new(
+                WorksetService::new(
+                    repositories.worksets,
+                    repositories.samples.clone(),
+                    repositories.libraries.clone(),
+                )
+                .with_controls(controls)
+                .with_audit(audit.clone()),
+            ),
+            transfer_service: Arc::new(
+                TransferService::new(
+                    repositories.transfers,
+                    repositories.samples.clone(),
+                    repositories.libraries.clone(),
+                )
+                .with_notifier(notifier)
+                .with_audit(audit.clone()),
+            ),
+            requisition_service: Arc::new(
+                RequisitionService::new(repositories.requisitions, repositories.samples.clone())
+                    .with_audit(audit.clone()),
+            ),
+            extraction_batch_service: Arc::new(
+                ExtractionBatchService::new(
+                    repositories.extraction_batches,
+                    repositories.samples.clone(),
+                )
+                .with_audit(audit.clone()),
+            ),
+            reagent_inventory_service: Arc::new(
+                ReagentInventoryService::new(repositories.reagent_lots).with_audit(audit.clone()),
+            ),
+            attachment_service: Arc::new(attachment_service),
+            delivery_service: Arc::new(delivery_service),
+            subproject_service: Arc::new(subproject_service),
+            facility_status_service: Arc::new(facility_status_service),
+            api_usage_service: Arc::new(api_usage_service),
+            index_set_service: Arc::new(IndexSetService::new(repositories.index_sets.clone())),
+            panel_service: Arc::new(PanelService::new(repositories.panels)),
+            reference_genome_service: Arc::new(ReferenceGenomeService::new(
+                repositories.reference_genomes,
+                repositories.libraries.clone(),
+                repositories.projects.clone(),
+            )),
+            pool_service: Arc::new(
+                PoolService::new(repositories.pools, repositories.libraries)
+                    .with_collision_config(collision_config)
+                    .with_index_sets(repositories.index_sets)
+                    .with_instrument_models(repositories.instrument_models.clone())
+                    .with_limits(pool_limits)
+                    .with_yield_targets(pool_targets)
+                    .with_audit(audit.clone())
+                    .with_project_access(project_access.clone())
+                    .with_project_locks(project_locks),
+            ),
+            run_metrics_service: Arc::new(RunMetricsService::new(repositories.run_metrics)),
+            qc_service: Arc::new(qc_service),
+            qc_report_service: Arc::new(QcReportService::new(repositories.qc_reports)),
+            data_location_service: Arc::new(DataLocationService::new(
+                repositories.data_locations,
+            )),
+            instrument_event_service: Arc::new(InstrumentEventService::new(
+                repositories.instrument_events,
+            )),
+            activity_service: Arc::new(
+                ActivityService::new(repositories.project_activity, repositories.projects.clone())
+                    .with_project_access(project_access.clone()),
+            ),
+            instrument_model_service: Arc::new(InstrumentModelService::new(
+                repositories.instrument_models,
+            )),
+            attribute_service: Arc::new(AttributeDefinitionService::new(
+                repositories.attribute_definitions,
+            )),
+            audit_service: Arc::new(
+                AuditService::new(repositories.audit_log, repositories.change_logs)
+                    .with_archives(repositories.log_archives.clone()),
+            ),
+            retention_service: Arc::new(retention_service),
+            label_print_service: Arc::new(label_print_service),
+            event_store_service: Arc::new(EventStoreService::new(repositories.event_store)),
+            consent_service: Arc::new(consent_service),
+            erasure_service: Arc::new(erasure_service),
+            dashboard_service: Arc::new(dashboard_service),
+            box_import_service,
+            storage_audit_service,
+            storage_unit_service,
+            storage_browser_service: repositories.boxes.map(|boxes| {
+                Arc::new(
+                    StorageBrowserService::new(boxes, repositories.samples.clone())
+                        .with_plugins(plugins),
+                )
+            }),
+            run_monitor_service: repositories
+                .runs
+                .map(|runs| Arc::new(RunMonitorService::new(runs))),
+            run_service,
+            sample_sheet_service,
+            sample_sheet_import_service,
+            archive_rule_service,
+            sequencer_service,
+            search_service: Arc::new(
+                SearchService::new(repositories.projects.clone(), repositories.samples.clone())
+                    .with_project_access(project_access.clone()),
+            ),
+            export_service: Arc::new(
+                ExportService::new(
+                    repositories.export_templates,
+                    repositories.projects,
+                    repositories.samples,
+                )
+                .with_redaction(redaction)
+                .with_project_access(project_access),
+            ),
+            database: None,
             scanner: None,
             printer: None,
         }
     }
 
+    /// Sets the database handle reported on by the metrics endpoint.
+    pub fn with_database(mut self, database: Database) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Adds a login provider, tried after those already added.
+    pub fn with_auth_provider(mut self, provider: Arc<dyn AuthProvider>) -> Self {
+        self.auth_providers.push(provider);
+        self
+    }
+
+    /// Enables single sign-on through an OpenID Connect provider.
+    pub fn with_oidc(mut self, oidc: OidcAuthProvider) -> Self {
+        self.oidc = Some(Arc::new(oidc));
+        self
+    }
+
     /// Sets the VisionMate scanner client.
     pub fn with_scanner(mut self, scanner: VisionMateClient) -> Self {
         self.scanner = Some(Arc::new(scanner));
@@ -52,4 +747,3 @@ impl<PR: ProjectRepository, SR: SampleRepository> AppState<PR, SR> {
         self
     }
 }
-
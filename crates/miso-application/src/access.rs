@@ -0,0 +1,118 @@
+//! Project access.
+//!
+//! A [`ProjectAccess`] guard is handed to the services that read and change
+//! the samples, libraries and pools of projects, and refuses anyone who
+//! isn't a member of the project. Admins reach every project.
+//!
+//! A guard without memberships checks nothing, so services use one by
+//! default and sites that don't enforce membership see no change.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectMemberRepository, UserRepository};
+
+/// The user a read or change is made for.
+#[derive(Debug, Clone, Copy)]
+pub struct Requester<'a> {
+    pub username: &'a str,
+    pub role: Role,
+}
+
+impl<'a> Requester<'a> {
+    /// Creates a requester.
+    pub fn new(username: &'a str, role: Role) -> Self {
+        Self { username, role }
+    }
+}
+
+/// Limits project data to the projects' members.
+#[derive(Clone, Default)]
+pub struct ProjectAccess {
+    members: Option<(Arc<dyn ProjectMemberRepository>, Arc<dyn UserRepository>)>,
+}
+
+impl ProjectAccess {
+    /// Creates a guard checking memberships, finding requesters among
+    /// `users` by username.
+    pub fn new(members: Arc<dyn ProjectMemberRepository>, users: Arc<dyn UserRepository>) -> Self {
+        Self {
+            members: Some((members, users)),
+        }
+    }
+
+    /// Returns true if memberships are checked.
+    pub fn is_enforced(&self) -> bool {
+        self.members.is_some()
+    }
+
+    /// Returns the projects a requester may reach, or `None` if they may
+    /// reach every project. Anonymous requesters reach none.
+    pub async fn projects(
+        &self,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Option<HashSet<EntityId>>, DomainError> {
+        let Some((members, users)) = &self.members else {
+            return Ok(None);
+        };
+        let Some(requester) = requester else {
+            return Ok(Some(HashSet::new()));
+        };
+        if requester.role.has_at_least(&Role::Admin) {
+            return Ok(None);
+        }
+
+        let projects = match users.find_by_username(requester.username).await? {
+            Some(user) => members.find_projects(user.id).await?.into_iter().collect(),
+            None => HashSet::new(),
+        };
+        Ok(Some(projects))
+    }
+
+    /// Checks that a requester may reach a project's data.
+    pub async fn check(
+        &self,
+        project_id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<(), DomainError> {
+        match self.projects(requester).await? {
+            Some(projects) if !projects.contains(&project_id) => {
+                Err(denied(project_id, requester))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a requester may reach the data of any of the given
+    /// projects; data drawing on no project is open to all.
+    pub async fn check_any(
+        &self,
+        project_ids: &HashSet<EntityId>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<(), DomainError> {
+        match self.projects(requester).await? {
+            Some(projects) if projects.is_disjoint(project_ids) => {
+                match project_ids.iter().min() {
+                    Some(&project_id) => Err(denied(project_id, requester)),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn denied(project_id: EntityId, requester: Option<Requester<'_>>) -> DomainError {
+    match requester {
+        Some(requester) => DomainError::PermissionDenied(format!(
+            "{} is not a member of project {}",
+            requester.username, project_id
+        )),
+        None => DomainError::PermissionDenied(format!(
+            "Log in as a member of project {} to see its data",
+            project_id
+        )),
+    }
+}
@@ -0,0 +1,465 @@
+//! Audit trail.
+//!
+//! An [`AuditTrail`] is handed to the services that create, update and
+//! delete lab records, and writes an audit log entry for each change. The
+//! fields of an entry are worked out by serializing the entity before and
+//! after the change and comparing the two, so any field an entity gains is
+//! audited without further work. Bookkeeping fields that change on every
+//! write, such as `updated_at`, are left out.
+//!
+//! In event store mode the trail also appends each change, as the entity's
+//! whole state, to the hash-chained event store, before writing the audit
+//! log entry.
+//!
+//! A trail without a log records nothing, so services use one by default.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use miso_domain::entities::{AuditAction, AuditEntry, EntityId, FieldChange, StoredEvent};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{AuditLogRepository, EventStoreRepository};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Fields that change on every write and say nothing about what changed.
+const IGNORED_FIELDS: &[&str] = &["updated_at", "version"];
+
+/// Times appending events is tried when other writers keep extending the
+/// chain first.
+const APPEND_ATTEMPTS: usize = 5;
+
+/// Records creates, updates and deletes in the audit log.
+#[derive(Clone, Default)]
+pub struct AuditTrail {
+    log: Option<Arc<dyn AuditLogRepository>>,
+    events: Option<Arc<dyn EventStoreRepository>>,
+}
+
+impl AuditTrail {
+    /// Creates a trail writing to the given log.
+    pub fn new(log: Arc<dyn AuditLogRepository>) -> Self {
+        Self {
+            log: Some(log),
+            events: None,
+        }
+    }
+
+    /// Also appends every change to the given event store.
+    pub fn with_event_store(mut self, events: Arc<dyn EventStoreRepository>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Records that `by` created an entity.
+    pub async fn created<T: Serialize>(
+        &self,
+        entity_type: &str,
+        id: EntityId,
+        entity: &T,
+        by: &str,
+    ) -> Result<(), DomainError> {
+        let value = to_value(entity)?;
+        let event = StoredEvent::new(
+            entity_type,
+            id,
+            AuditAction::Create,
+            Some(value.to_string()),
+            by,
+        );
+        self.append(vec![event]).await?;
+        let changes = diff(None, Some(&value));
+        self.record(entity_type, id, AuditAction::Create, changes, by)
+            .await
+    }
+
+    /// Records that `by` changed an entity. Nothing is recorded if no
+    /// field changed.
+    pub async fn updated<T: Serialize>(
+        &self,
+        entity_type: &str,
+        id: EntityId,
+        before: &T,
+        after: &T,
+        by: &str,
+    ) -> Result<(), DomainError> {
+        self.updated_all(entity_type, &[(id, before, after)], by)
+            .await
+    }
+
+    /// Records that `by` changed several entities of a type at once.
+    pub async fn updated_all<T: Serialize>(
+        &self,
+        entity_type: &str,
+        changes: &[(EntityId, &T, &T)],
+        by: &str,
+    ) -> Result<(), DomainError> {
+        if self.log.is_none() && self.events.is_none() {
+            return Ok(());
+        }
+
+        let mut entries = Vec::new();
+        let mut events = Vec::new();
+        for (id, before, after) in changes {
+            let after = to_value(after)?;
+            let changes = diff(Some(&to_value(before)?), Some(&after));
+            if !changes.is_empty() {
+                entries.push(AuditEntry::new(
+                    entity_type,
+                    *id,
+                    AuditAction::Update,
+                    changes,
+                    by,
+                ));
+                events.push(StoredEvent::new(
+                    entity_type,
+                    *id,
+                    AuditAction::Update,
+                    Some(after.to_string()),
+                    by,
+                ));
+            }
+        }
+        self.append(events).await?;
+        match &self.log {
+            Some(log) if !entries.is_empty() => log.save_all(&entries).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Records that `by` deleted an entity, keeping its last values.
+    pub async fn deleted<T: Serialize>(
+        &self,
+        entity_type: &str,
+        id: EntityId,
+        entity: &T,
+        by: &str,
+    ) -> Result<(), DomainError> {
+        let event = StoredEvent::new(entity_type, id, AuditAction::Delete, None, by);
+        self.append(vec![event]).await?;
+        let changes = diff(Some(&to_value(entity)?), None);
+        self.record(entity_type, id, AuditAction::Delete, changes, by)
+            .await
+    }
+
+    /// Chains events onto the newest stored event and appends them, trying
+    /// again if another writer appended first.
+    async fn append(&self, events: Vec<StoredEvent>) -> Result<(), DomainError> {
+        let Some(store) = &self.events else {
+            return Ok(());
+        };
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut attempt = 1;
+        loop {
+            let mut previous_hash = store.last().await?.map(|e| e.hash).unwrap_or_default();
+            let mut chained = events.clone();
+            for event in &mut chained {
+                event.previous_hash = previous_hash;
+                event.hash = event_hash(event);
+                previous_hash = event.hash.clone();
+            }
+            match store.append(&chained).await {
+                Err(DomainError::ConcurrentModification { .. }) if attempt < APPEND_ATTEMPTS => {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn record(
+        &self,
+        entity_type: &str,
+        id: EntityId,
+        action: AuditAction,
+        changes: Vec<FieldChange>,
+        by: &str,
+    ) -> Result<(), DomainError> {
+        let Some(log) = &self.log else {
+            return Ok(());
+        };
+        log.save_all(&[AuditEntry::new(entity_type, id, action, changes, by)])
+            .await
+    }
+}
+
+/// Returns the hex SHA-256 hash a stored event should have, given its
+/// contents and previous hash.
+pub fn event_hash(event: &StoredEvent) -> String {
+    Sha256::digest(event.hash_input().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn to_value<T: Serialize>(entity: &T) -> Result<Value, DomainError> {
+    serde_json::to_value(entity).map_err(|e| DomainError::Validation(e.to_string()))
+}
+
+/// Returns the fields that differ between two serialized entities, in
+/// order of their paths.
+fn diff(before: Option<&Value>, after: Option<&Value>) -> Vec<FieldChange> {
+    let mut old = BTreeMap::new();
+    let mut new = BTreeMap::new();
+    if let Some(before) = before {
+        flatten("", before, &mut old);
+    }
+    if let Some(after) = after {
+        flatten("", after, &mut new);
+    }
+
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(|field| {
+            FieldChange::new(
+                field.clone(),
+                old.get(field).map(|v| v.to_string()),
+                new.get(field).map(|v| v.to_string()),
+            )
+        })
+        .collect()
+}
+
+/// Collects the leaf values of a serialized entity by path. Objects are
+/// descended into; lists are compared whole.
+fn flatten<'a>(path: &str, value: &'a Value, fields: &mut BTreeMap<String, &'a Value>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if path.is_empty() && IGNORED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten(&path, value, fields);
+            }
+        }
+        _ => {
+            fields.insert(path.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{AuditQuery, EntitySnapshot, Sample};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryLog {
+        entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditLogRepository for InMemoryLog {
+        async fn find(&self, _: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+        async fn save_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+            self.entries.lock().unwrap().extend_from_slice(entries);
+            Ok(())
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_entity(&self, _: &str, _: EntityId) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[AuditEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryEvents {
+        events: Mutex<Vec<StoredEvent>>,
+    }
+
+    #[async_trait]
+    impl EventStoreRepository for InMemoryEvents {
+        async fn last(&self) -> Result<Option<StoredEvent>, DomainError> {
+            Ok(self.events.lock().unwrap().last().cloned())
+        }
+        async fn append(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+            let mut stored = self.events.lock().unwrap();
+            let last_hash = stored.last().map(|e| e.hash.clone()).unwrap_or_default();
+            if events[0].previous_hash != last_hash {
+                return Err(DomainError::ConcurrentModification {
+                    entity_type: "StoredEvent".to_string(),
+                    id: last_hash,
+                });
+            }
+            for event in events {
+                let mut event = event.clone();
+                event.sequence = stored.len() as i64 + 1;
+                stored.push(event);
+            }
+            Ok(())
+        }
+        async fn list_after(&self, _: i64, _: u64) -> Result<Vec<StoredEvent>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_entity(
+            &self,
+            _: &str,
+            _: EntityId,
+        ) -> Result<Vec<StoredEvent>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[StoredEvent]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn find_snapshot(
+            &self,
+            _: &str,
+            _: EntityId,
+        ) -> Result<Option<EntitySnapshot>, DomainError> {
+            unimplemented!()
+        }
+        async fn replace_snapshots(&self, _: &[EntitySnapshot]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn identity() -> Sample {
+        Sample::new_identity(
+            1,
+            "PAT_0001".to_string(),
+            Barcode::new_unchecked("SAM1".to_string()),
+            1,
+            "MRN-1".to_string(),
+            "tech".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_updates_record_changed_fields_only() {
+        let log = Arc::new(InMemoryLog::default());
+        let audit = AuditTrail::new(log.clone());
+
+        let before = identity();
+        let mut after = before.clone();
+        after.set_qc_status(QcStatus::Ready);
+        after.version += 1;
+        if let miso_domain::entities::SampleDetails::Detailed(details) = &mut after.details {
+            details.external_name = Some("MRN-2".to_string());
+        }
+
+        audit
+            .updated("Sample", 1, &before, &after, "manager")
+            .await
+            .unwrap();
+        audit
+            .updated("Sample", 1, &after, &after, "manager")
+            .await
+            .unwrap();
+
+        let entries = log.entries.lock().unwrap().clone();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Update);
+        assert_eq!(entries[0].changed_by, "manager");
+        assert_eq!(
+            entries[0].changes,
+            vec![
+                FieldChange::new(
+                    "details.external_name",
+                    Some("\"MRN-1\"".to_string()),
+                    Some("\"MRN-2\"".to_string()),
+                ),
+                FieldChange::new(
+                    "qc_status",
+                    Some("\"not_ready\"".to_string()),
+                    Some("\"ready\"".to_string()),
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_creates_and_deletes_record_every_field() {
+        let log = Arc::new(InMemoryLog::default());
+        let audit = AuditTrail::new(log.clone());
+        let sample = identity();
+
+        audit.created("Sample", 1, &sample, "tech").await.unwrap();
+        audit
+            .deleted("Sample", 1, &sample, "manager")
+            .await
+            .unwrap();
+        AuditTrail::default()
+            .created("Sample", 1, &sample, "tech")
+            .await
+            .unwrap();
+
+        let entries = log.entries.lock().unwrap().clone();
+        assert_eq!(entries.len(), 2);
+        let name = |entry: &AuditEntry| {
+            entry
+                .changes
+                .iter()
+                .find(|c| c.field == "name")
+                .cloned()
+                .unwrap()
+        };
+        assert_eq!(name(&entries[0]).old, None);
+        assert_eq!(name(&entries[0]).new.as_deref(), Some("\"PAT_0001\""));
+        assert_eq!(name(&entries[1]).old.as_deref(), Some("\"PAT_0001\""));
+        assert_eq!(name(&entries[1]).new, None);
+        assert!(entries[0].changes.iter().all(|c| c.field != "updated_at"));
+    }
+
+    #[tokio::test]
+    async fn test_event_store_chains_whole_states() {
+        let log = Arc::new(InMemoryLog::default());
+        let events = Arc::new(InMemoryEvents::default());
+        let audit = AuditTrail::new(log.clone()).with_event_store(events.clone());
+
+        let sample = identity();
+        let mut renamed = sample.clone();
+        renamed.name = "PAT_0002".to_string();
+        audit.created("Sample", 1, &sample, "tech").await.unwrap();
+        audit
+            .updated("Sample", 1, &renamed, &renamed, "tech")
+            .await
+            .unwrap();
+        audit
+            .updated("Sample", 1, &sample, &renamed, "tech")
+            .await
+            .unwrap();
+        audit
+            .deleted("Sample", 1, &renamed, "manager")
+            .await
+            .unwrap();
+
+        let stored = events.events.lock().unwrap().clone();
+        assert_eq!(log.entries.lock().unwrap().len(), 3);
+        assert_eq!(stored.len(), 3);
+        assert_eq!(stored[0].previous_hash, "");
+        for pair in stored.windows(2) {
+            assert_eq!(pair[1].previous_hash, pair[0].hash);
+        }
+        assert!(stored.iter().all(|e| e.hash == event_hash(e)));
+
+        let state: Value = serde_json::from_str(stored[1].state.as_ref().unwrap()).unwrap();
+        assert_eq!(state["name"], "PAT_0002");
+        assert_eq!(state["barcode"], "SAM1");
+        assert_eq!(stored[2].action, AuditAction::Delete);
+        assert_eq!(stored[2].state, None);
+    }
+}
@@ -0,0 +1,39 @@
+//! Activity feed Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{ActivityItem, ActivityKind};
+use serde::{Deserialize, Serialize};
+
+/// One event in a project's activity feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityItemResponse {
+    pub kind: ActivityKind,
+    pub occurred_at: DateTime<Utc>,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub summary: String,
+    pub detail: Option<String>,
+    pub actor: String,
+}
+
+impl From<ActivityItem> for ActivityItemResponse {
+    fn from(item: ActivityItem) -> Self {
+        Self {
+            kind: item.kind,
+            occurred_at: item.occurred_at,
+            entity_type: item.entity_type,
+            entity_id: item.entity_id,
+            summary: item.summary,
+            detail: item.detail,
+            actor: item.actor,
+        }
+    }
+}
+
+/// A page of a project's activity feed, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityFeedResponse {
+    pub items: Vec<ActivityItemResponse>,
+    /// Pass as `before` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
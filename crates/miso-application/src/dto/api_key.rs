@@ -0,0 +1,68 @@
+//! API key Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::ApiKey;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::codes;
+
+/// Request to create an API key.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    /// What the key is for, e.g. "novaseq-01"
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Role code of the most the key may do, e.g. "technician"; no more
+    /// than the creator's
+    pub role: String,
+
+    /// When the key stops working; never if unset
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response containing API key details. The key itself is never shown
+/// again after it is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyResponse {
+    pub id: i32,
+    pub name: String,
+    /// The start of the key, to tell keys apart
+    pub prefix: String,
+    pub role: String,
+    /// The user the key acts for
+    pub user_id: i32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// True if the key is neither revoked nor expired
+    pub active: bool,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            active: key.is_active_at(Utc::now()),
+            role: codes::role(key.role).to_string(),
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            user_id: key.user_id,
+            created_at: key.created_at,
+            expires_at: key.expires_at,
+            last_used_at: key.last_used_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+/// A newly created API key, with the key to send as `X-Api-Key`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiKeyResponse {
+    /// The key; it can't be recovered later
+    pub key: String,
+    #[serde(flatten)]
+    pub details: ApiKeyResponse,
+}
@@ -0,0 +1,36 @@
+//! API usage Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for the API usage report.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApiUsageFilter {
+    /// Start of the report (default: a day ago); counts are hourly
+    pub since: Option<DateTime<Utc>>,
+    /// End of the report (default: now)
+    pub until: Option<DateTime<Utc>>,
+    /// Only this user's calls, by any means
+    pub user_id: Option<i32>,
+    /// Only calls made with this API key
+    pub api_key_id: Option<i32>,
+}
+
+/// How much one caller used one endpoint over the report's period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsageReportRow {
+    pub user_id: i32,
+    /// Key the calls were made with; unset for calls made after logging in
+    pub api_key_id: Option<i32>,
+    pub method: String,
+    /// Route template, e.g. "/api/v1/samples/{id}"
+    pub route: String,
+    /// Estimated calls, from the sampled calls
+    pub calls: i64,
+    /// Estimated calls answered with a 4xx or 5xx status
+    pub errors: i64,
+    /// Mean time of the sampled calls, in milliseconds
+    pub mean_ms: Option<f64>,
+    /// Slowest sampled call, in milliseconds
+    pub max_ms: i64,
+}
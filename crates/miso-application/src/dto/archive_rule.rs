@@ -0,0 +1,134 @@
+//! Automatic archiving Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{ArchiveRule, ArchiveTarget, AutoArchival};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create an archiving rule.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateArchiveRuleRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    pub target: ArchiveTarget,
+
+    /// Days after the last completed run that items are archived
+    #[validate(range(min = 1))]
+    pub after_days: u32,
+
+    /// Days ahead that lab managers are told; defaults to 7, or to
+    /// `after_days` for shorter rules
+    #[validate(range(min = 1))]
+    pub notice_days: Option<u32>,
+}
+
+/// Request to change an archiving rule. Changing `after_days` or
+/// `notice_days` announces items again before anything is archived.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateArchiveRuleRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(range(min = 1))]
+    pub after_days: Option<u32>,
+
+    #[validate(range(min = 1))]
+    pub notice_days: Option<u32>,
+
+    /// False stops the rule being evaluated
+    pub active: Option<bool>,
+}
+
+/// Response containing an archiving rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveRuleResponse {
+    pub id: i32,
+    pub name: String,
+    pub target: ArchiveTarget,
+    pub after_days: u32,
+    pub notice_days: u32,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub last_evaluated_at: Option<DateTime<Utc>>,
+}
+
+impl From<ArchiveRule> for ArchiveRuleResponse {
+    fn from(rule: ArchiveRule) -> Self {
+        Self {
+            id: rule.id,
+            name: rule.name,
+            target: rule.target,
+            after_days: rule.after_days,
+            notice_days: rule.notice_days,
+            active: rule.active,
+            created_by: rule.created_by,
+            created_at: rule.created_at,
+            last_evaluated_at: rule.last_evaluated_at,
+        }
+    }
+}
+
+/// A library or sample a rule applies to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveCandidate {
+    pub entity_id: i32,
+    pub name: String,
+    pub project_id: i32,
+    /// When the last run the item was sequenced on completed
+    pub last_run_at: DateTime<Utc>,
+    /// When the rule archives the item
+    pub due_at: DateTime<Utc>,
+}
+
+/// What evaluating a rule does: the items it archives and the items it
+/// announces for archiving. A preview lists what an evaluation now would
+/// do without doing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivePlanResponse {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub target: ArchiveTarget,
+    /// Items due that were announced by an earlier evaluation
+    pub archive: Vec<ArchiveCandidate>,
+    /// Items whose notice period has started
+    pub announce: Vec<ArchiveCandidate>,
+}
+
+impl ArchivePlanResponse {
+    /// Returns true if the evaluation does nothing.
+    pub fn is_empty(&self) -> bool {
+        self.archive.is_empty() && self.announce.is_empty()
+    }
+}
+
+/// Response containing an item archived by a rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoArchivalResponse {
+    pub id: i32,
+    pub rule_id: i32,
+    pub target: ArchiveTarget,
+    pub entity_id: i32,
+    pub entity_name: String,
+    pub last_run_at: DateTime<Utc>,
+    pub archived_at: DateTime<Utc>,
+    pub restored_by: Option<String>,
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+impl From<AutoArchival> for AutoArchivalResponse {
+    fn from(archival: AutoArchival) -> Self {
+        Self {
+            id: archival.id,
+            rule_id: archival.rule_id,
+            target: archival.target,
+            entity_id: archival.entity_id,
+            entity_name: archival.entity_name,
+            last_run_at: archival.last_run_at,
+            archived_at: archival.archived_at,
+            restored_by: archival.restored_by,
+            restored_at: archival.restored_at,
+        }
+    }
+}
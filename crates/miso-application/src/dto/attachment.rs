@@ -0,0 +1,44 @@
+//! Attachment Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Attachment, AttachmentTarget};
+use serde::{Deserialize, Serialize};
+
+/// Attachment metadata returned from the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentResponse {
+    pub id: i32,
+    pub target: AttachmentTarget,
+    pub target_id: i32,
+    pub filename: String,
+    pub content_type: String,
+    /// Size in bytes
+    pub size: i64,
+    /// Hex-encoded SHA-256 of the contents
+    pub checksum: String,
+    pub uploaded_by: String,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+impl From<Attachment> for AttachmentResponse {
+    fn from(attachment: Attachment) -> Self {
+        Self {
+            id: attachment.id,
+            target: attachment.target,
+            target_id: attachment.target_id,
+            filename: attachment.filename,
+            content_type: attachment.content_type,
+            size: attachment.size,
+            checksum: attachment.checksum,
+            uploaded_by: attachment.uploaded_by,
+            uploaded_at: attachment.uploaded_at,
+        }
+    }
+}
+
+/// An attachment's metadata with its contents, for download.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentContent {
+    pub attachment: AttachmentResponse,
+    pub contents: Vec<u8>,
+}
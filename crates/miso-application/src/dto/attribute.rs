@@ -0,0 +1,65 @@
+//! Custom attribute Data Transfer Objects.
+
+use miso_domain::entities::{AttributeTarget, AttributeType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to define a custom attribute.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateAttributeDefinitionRequest {
+    pub target: AttributeTarget,
+
+    /// Limits the attribute to one project; omit for every project
+    pub project_id: Option<i32>,
+
+    #[validate(length(min = 1, max = 100))]
+    pub key: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub label: String,
+
+    pub value_type: AttributeType,
+
+    #[serde(default)]
+    pub required: bool,
+
+    pub options: Option<Vec<String>>,
+
+    pub min: Option<f64>,
+
+    pub max: Option<f64>,
+
+    #[validate(range(min = 1))]
+    pub max_length: Option<u32>,
+
+    #[validate(length(max = 2000))]
+    pub help_text: Option<String>,
+
+    pub position: Option<i32>,
+}
+
+/// Request to update a custom attribute.
+///
+/// The target, project and key are fixed once values have been recorded
+/// under them.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateAttributeDefinitionRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub label: Option<String>,
+
+    pub required: Option<bool>,
+
+    pub options: Option<Vec<String>>,
+
+    pub min: Option<f64>,
+
+    pub max: Option<f64>,
+
+    #[validate(range(min = 1))]
+    pub max_length: Option<u32>,
+
+    #[validate(length(max = 2000))]
+    pub help_text: Option<String>,
+
+    pub position: Option<i32>,
+}
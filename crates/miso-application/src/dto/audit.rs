@@ -0,0 +1,88 @@
+//! Audit log Data Transfer Objects.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{AuditAction, AuditEntry};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A field's value before and after a change; absent on a side where the
+/// field didn't exist, as before a create or after a delete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChangeResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// One recorded create, update or delete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntryResponse {
+    pub id: i32,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub action: AuditAction,
+    /// Changed fields by path, e.g. "details.external_name"
+    pub changes: BTreeMap<String, FieldChangeResponse>,
+    pub changed_by: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        let value = |json: Option<String>| json.and_then(|json| serde_json::from_str(&json).ok());
+        Self {
+            id: entry.id,
+            entity_type: entry.entity_type,
+            entity_id: entry.entity_id,
+            action: entry.action,
+            changes: entry
+                .changes
+                .into_iter()
+                .map(|change| {
+                    (
+                        change.field,
+                        FieldChangeResponse {
+                            old: value(change.old),
+                            new: value(change.new),
+                        },
+                    )
+                })
+                .collect(),
+            changed_by: entry.changed_by,
+            changed_at: entry.changed_at,
+        }
+    }
+}
+
+/// The first entry of a log that doesn't fit the hash chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreakResponse {
+    pub id: i32,
+    /// What doesn't match
+    pub reason: String,
+}
+
+/// Result of walking one log's hash chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogChainReport {
+    /// Number of entries checked, up to the first break
+    pub entries_checked: u64,
+    /// Entries written before chaining began, which have no hash
+    pub unchained: u64,
+    /// Where the chain breaks, if it does
+    pub first_break: Option<ChainBreakResponse>,
+}
+
+/// Machine-readable result of verifying the audit and change log chains.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChainVerification {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Whether both chains are unbroken
+    pub intact: bool,
+    pub audit_log: LogChainReport,
+    pub change_log: LogChainReport,
+}
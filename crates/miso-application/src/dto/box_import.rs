@@ -0,0 +1,59 @@
+//! Box layout import Data Transfer Objects.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to import box layouts from a `Box,Position,Barcode` CSV.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ImportBoxesRequest {
+    /// Raw CSV contents
+    #[validate(length(min = 1))]
+    pub contents: String,
+
+    /// Rows of boxes the import creates; defaults to 9
+    #[validate(range(min = 1, max = 26))]
+    pub rows: Option<u8>,
+
+    /// Columns of boxes the import creates; defaults to 9
+    #[validate(range(min = 1, max = 48))]
+    pub cols: Option<u8>,
+
+    pub freezer: Option<String>,
+    pub shelf: Option<String>,
+    pub rack: Option<String>,
+
+    /// Project to create placeholder samples in for barcodes nothing has;
+    /// without it such rows are left unresolved
+    pub placeholder_project_id: Option<i32>,
+}
+
+/// A box filled by the import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedBox {
+    pub box_id: i32,
+    pub name: String,
+    /// False if an existing box with this label as its barcode was filled
+    pub created: bool,
+    /// Number of items placed in it
+    pub placed: usize,
+}
+
+/// A box CSV row that could not be placed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedBoxRow {
+    pub line: usize,
+    pub box_label: String,
+    pub position: String,
+    pub barcode: String,
+    pub reason: String,
+}
+
+/// Result of importing box layouts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoxImportResponse {
+    pub boxes: Vec<ImportedBox>,
+    pub placed_rows: usize,
+    /// Placeholder samples created for unknown barcodes
+    pub placeholders_created: usize,
+    pub unresolved: Vec<UnresolvedBoxRow>,
+}
@@ -0,0 +1,40 @@
+//! Consent Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Consent, ConsentSharing};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record an identity's consent.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct SetConsentRequest {
+    pub sharing: ConsentSharing,
+
+    /// Reference to the signed form, or other notes
+    #[validate(length(max = 1000))]
+    pub notes: Option<String>,
+}
+
+/// Response containing an identity's consent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentResponse {
+    pub id: i32,
+    pub identity_id: i32,
+    pub sharing: ConsentSharing,
+    pub notes: Option<String>,
+    pub recorded_by: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<Consent> for ConsentResponse {
+    fn from(consent: Consent) -> Self {
+        Self {
+            id: consent.id,
+            identity_id: consent.identity_id,
+            sharing: consent.sharing,
+            notes: consent.notes,
+            recorded_by: consent.recorded_by,
+            recorded_at: consent.recorded_at,
+        }
+    }
+}
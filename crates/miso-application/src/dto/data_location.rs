@@ -0,0 +1,161 @@
+//! Run data location Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{DataLocation, EntityId, Platform, RetentionClass};
+use miso_domain::services::{StorageTotal, StorageUsage};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to register where a run's (or a lane's) data is stored.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RegisterDataLocationRequest {
+    /// Lane the data covers; omit for whole-run data
+    #[validate(range(min = 1))]
+    pub lane: Option<u8>,
+
+    /// Absolute filesystem path or `s3://bucket/key` URI
+    #[validate(length(min = 1, max = 4096))]
+    pub uri: String,
+
+    #[validate(length(min = 1, max = 4096))]
+    pub checksum_manifest: Option<String>,
+
+    /// Defaults to `standard`
+    pub retention_class: Option<RetentionClass>,
+
+    pub size_bytes: Option<u64>,
+
+    /// Platform the run was sequenced on, for usage reporting
+    pub platform: Option<Platform>,
+
+    /// Projects with data at this location, for usage reporting
+    pub project_ids: Option<Vec<i32>>,
+}
+
+/// Request to update a registered data location.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateDataLocationRequest {
+    #[validate(length(min = 1, max = 4096))]
+    pub checksum_manifest: Option<String>,
+
+    pub retention_class: Option<RetentionClass>,
+
+    pub size_bytes: Option<u64>,
+
+    pub platform: Option<Platform>,
+
+    pub project_ids: Option<Vec<i32>>,
+}
+
+/// Filters for listing a run's data locations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataLocationFilter {
+    /// Only include data for this lane (whole-run data is always included)
+    pub lane: Option<u8>,
+}
+
+/// Response containing a registered data location.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataLocationResponse {
+    pub id: i32,
+    pub run_id: i32,
+    pub lane: Option<u8>,
+    pub uri: String,
+    pub checksum_manifest: Option<String>,
+    pub retention_class: RetentionClass,
+    /// When the data may be purged; absent if it is kept forever
+    pub retain_until: Option<DateTime<Utc>>,
+    pub size_bytes: Option<u64>,
+    pub platform: Option<Platform>,
+    pub project_ids: Vec<i32>,
+    pub registered_by: String,
+    pub registered_at: DateTime<Utc>,
+    pub purge_requested_by: Option<String>,
+    pub purge_requested_at: Option<DateTime<Utc>>,
+    pub purged_by: Option<String>,
+    pub purged_at: Option<DateTime<Utc>>,
+}
+
+impl From<DataLocation> for DataLocationResponse {
+    fn from(location: DataLocation) -> Self {
+        Self {
+            retain_until: location.retain_until(),
+            id: location.id,
+            run_id: location.run_id,
+            lane: location.lane,
+            uri: location.uri,
+            checksum_manifest: location.checksum_manifest,
+            retention_class: location.retention_class,
+            size_bytes: location.size_bytes,
+            platform: location.platform,
+            project_ids: location.project_ids,
+            registered_by: location.registered_by,
+            registered_at: location.registered_at,
+            purge_requested_by: location.purge_requested_by,
+            purge_requested_at: location.purge_requested_at,
+            purged_by: location.purged_by,
+            purged_at: location.purged_at,
+        }
+    }
+}
+
+/// Storage held by one project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStorageUsage {
+    pub project_id: i32,
+    pub bytes: u64,
+    pub locations: usize,
+}
+
+/// Storage held on one platform's output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformStorageUsage {
+    pub platform: Platform,
+    pub bytes: u64,
+    pub locations: usize,
+}
+
+/// Report of bytes stored across unpurged data locations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsageReport {
+    pub generated_at: DateTime<Utc>,
+    pub total_bytes: u64,
+    /// Largest first
+    pub by_project: Vec<ProjectStorageUsage>,
+    /// Largest first
+    pub by_platform: Vec<PlatformStorageUsage>,
+    /// Bytes at locations not attributed to any project
+    pub unattributed_bytes: u64,
+    /// Locations whose size is unknown and so are not counted
+    pub unsized_locations: usize,
+}
+
+impl From<StorageUsage> for StorageUsageReport {
+    fn from(usage: StorageUsage) -> Self {
+        let project = |(project_id, total): (EntityId, StorageTotal)| ProjectStorageUsage {
+            project_id,
+            bytes: total.bytes,
+            locations: total.locations,
+        };
+
+        let mut by_project: Vec<_> = usage.by_project.into_iter().map(project).collect();
+        by_project.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+
+        Self {
+            generated_at: Utc::now(),
+            total_bytes: usage.total_bytes,
+            by_project,
+            by_platform: usage
+                .by_platform
+                .into_iter()
+                .map(|(platform, total)| PlatformStorageUsage {
+                    platform,
+                    bytes: total.bytes,
+                    locations: total.locations,
+                })
+                .collect(),
+            unattributed_bytes: usage.unattributed.bytes,
+            unsized_locations: usage.unsized_locations,
+        }
+    }
+}
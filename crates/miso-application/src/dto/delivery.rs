@@ -0,0 +1,134 @@
+//! Data delivery Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Delivery, DeliveryDestination, DeliveryStatus, DeliveryTarget};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to register a place a project's data is released to.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateDeliveryTargetRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    /// `{"protocol": "s3", "bucket": ..., "prefix": ...}` or
+    /// `{"protocol": "sftp", "host": ..., "port": 22, "path": ...}`
+    pub destination: DeliveryDestination,
+}
+
+/// Request to change a delivery target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateDeliveryTargetRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    pub destination: Option<DeliveryDestination>,
+
+    /// False retires the target
+    pub active: Option<bool>,
+}
+
+/// Response containing a delivery target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryTargetResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub destination: DeliveryDestination,
+    /// The destination as a URI, e.g. `s3://bucket/prefix`
+    pub uri: String,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DeliveryTarget> for DeliveryTargetResponse {
+    fn from(target: DeliveryTarget) -> Self {
+        Self {
+            uri: target.destination.to_string(),
+            id: target.id,
+            project_id: target.project_id,
+            name: target.name,
+            destination: target.destination,
+            active: target.active,
+            created_by: target.created_by,
+            created_at: target.created_at,
+        }
+    }
+}
+
+/// Request to record that a run's data was copied to a delivery target.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RecordDeliveryRequest {
+    pub target_id: i32,
+
+    pub run_id: i32,
+
+    /// Where the data was copied to; defaults to a directory named after
+    /// the run under the target
+    #[validate(length(min = 1, max = 4096))]
+    pub uri: Option<String>,
+
+    /// Defaults to the manifest registered with the run's data
+    #[validate(length(min = 1, max = 4096))]
+    pub checksum_manifest: Option<String>,
+
+    pub size_bytes: Option<u64>,
+}
+
+/// Outcome of checking a delivered copy against its checksum manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct VerifyDeliveryRequest {
+    /// True if every file was found and matched
+    pub complete: bool,
+
+    /// What the check found, e.g. the files that didn't match
+    #[validate(length(max = 4096))]
+    pub note: Option<String>,
+}
+
+/// Filters for listing a project's deliveries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryFilter {
+    pub run_id: Option<i32>,
+    pub target_id: Option<i32>,
+    pub status: Option<DeliveryStatus>,
+}
+
+/// Response containing a delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryResponse {
+    pub id: i32,
+    pub target_id: i32,
+    pub project_id: i32,
+    pub run_id: i32,
+    pub uri: String,
+    pub checksum_manifest: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub status: DeliveryStatus,
+    pub delivered_by: String,
+    pub delivered_at: DateTime<Utc>,
+    pub verified_by: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+}
+
+impl From<Delivery> for DeliveryResponse {
+    fn from(delivery: Delivery) -> Self {
+        Self {
+            id: delivery.id,
+            target_id: delivery.target_id,
+            project_id: delivery.project_id,
+            run_id: delivery.run_id,
+            uri: delivery.uri,
+            checksum_manifest: delivery.checksum_manifest,
+            size_bytes: delivery.size_bytes,
+            status: delivery.status,
+            delivered_by: delivery.delivered_by,
+            delivered_at: delivery.delivered_at,
+            verified_by: delivery.verified_by,
+            verified_at: delivery.verified_at,
+            note: delivery.note,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Erasure Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::Erasure;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to erase a withdrawn participant's identifying details.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct EraseIdentityRequest {
+    /// The identity the participant's samples descend from
+    pub identity_id: i32,
+
+    /// Why, e.g. the withdrawal request's reference
+    #[validate(length(min = 1, max = 1000))]
+    pub reason: String,
+}
+
+/// Response containing erasure details. The erased names are never shown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureResponse {
+    pub id: i32,
+    pub identity_id: i32,
+    /// Samples scrubbed, the identity included
+    pub samples_erased: u32,
+    pub reason: String,
+    pub erased_by: String,
+    pub erased_at: DateTime<Utc>,
+}
+
+impl From<Erasure> for ErasureResponse {
+    fn from(erasure: Erasure) -> Self {
+        Self {
+            id: erasure.id,
+            identity_id: erasure.identity_id,
+            samples_erased: erasure.samples_erased,
+            reason: erasure.reason,
+            erased_by: erasure.erased_by,
+            erased_at: erasure.erased_at,
+        }
+    }
+}
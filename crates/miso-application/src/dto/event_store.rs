@@ -0,0 +1,59 @@
+//! Event store Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::EntitySnapshot;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The first stored event that doesn't fit the hash chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub sequence: i64,
+    /// What doesn't match
+    pub reason: String,
+}
+
+/// Machine-readable result of replaying the event store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Number of events replayed
+    pub events: u64,
+    /// Number of entities in the resulting read model
+    pub entities: usize,
+    pub last_sequence: Option<i64>,
+    /// Where the chain breaks, if it does; events from there on aren't
+    /// replayed
+    pub broken_link: Option<BrokenLink>,
+    /// Whether the read model was rebuilt from the events
+    pub rebuilt: bool,
+}
+
+impl ReplayReport {
+    /// Returns true if every event fits the hash chain.
+    pub fn is_intact(&self) -> bool {
+        self.broken_link.is_none()
+    }
+}
+
+/// An entity's current state from the event store's read model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshotResponse {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub state: Value,
+    /// Sequence number of the event that gave this state
+    pub sequence: i64,
+}
+
+impl From<EntitySnapshot> for EntitySnapshotResponse {
+    fn from(snapshot: EntitySnapshot) -> Self {
+        Self {
+            state: serde_json::from_str(&snapshot.state).unwrap_or(Value::Null),
+            entity_type: snapshot.entity_type,
+            entity_id: snapshot.entity_id,
+            sequence: snapshot.sequence,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Export template Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Delimiter, ExportColumn, ExportEntityType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create an export template.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateExportTemplateRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    pub entity_type: ExportEntityType,
+
+    #[validate(length(min = 1))]
+    pub columns: Vec<ExportColumn>,
+
+    pub delimiter: Option<Delimiter>,
+
+    pub include_header: Option<bool>,
+}
+
+/// Request to update an export template.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateExportTemplateRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 1))]
+    pub columns: Option<Vec<ExportColumn>>,
+
+    pub delimiter: Option<Delimiter>,
+
+    pub include_header: Option<bool>,
+}
+
+/// Response containing an export template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTemplateResponse {
+    pub id: i32,
+    pub name: String,
+    pub entity_type: ExportEntityType,
+    pub columns: Vec<ExportColumn>,
+    pub delimiter: Delimiter,
+    pub include_header: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<miso_domain::entities::ExportTemplate> for ExportTemplateResponse {
+    fn from(template: miso_domain::entities::ExportTemplate) -> Self {
+        Self {
+            id: template.id,
+            name: template.name,
+            entity_type: template.entity_type,
+            columns: template.columns,
+            delimiter: template.delimiter,
+            include_header: template.include_header,
+            created_by: template.created_by,
+            created_at: template.created_at,
+            updated_at: template.updated_at,
+        }
+    }
+}
+
+/// Filters applied when rendering an export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportFilter {
+    /// Restrict sample exports to one project
+    pub project_id: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// A rendered export file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub body: String,
+    /// Problems found while rendering that don't stop the file being used
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,169 @@
+//! Extraction batch Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::ExtractionBatch;
+use miso_domain::services::YieldStatistics;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record an extraction batch.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateExtractionBatchRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub instrument: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub kit: String,
+
+    #[validate(length(min = 1, max = 100))]
+    pub kit_lot: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub operator: String,
+
+    /// When the extraction was run; defaults to now
+    pub extracted_at: Option<DateTime<Utc>>,
+
+    /// Stock samples the extraction produced
+    #[serde(default)]
+    pub sample_ids: Vec<i32>,
+
+    /// Reagent blanks taken through the extraction
+    #[serde(default)]
+    pub blank_ids: Vec<i32>,
+}
+
+/// Request to correct an extraction batch's details.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateExtractionBatchRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub instrument: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub kit: Option<String>,
+
+    #[validate(length(min = 1, max = 100))]
+    pub kit_lot: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub operator: Option<String>,
+
+    pub extracted_at: Option<DateTime<Utc>>,
+}
+
+/// Samples and blanks to add to or remove from an extraction batch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct ExtractionBatchSamplesRequest {
+    #[serde(default)]
+    pub sample_ids: Vec<i32>,
+
+    #[serde(default)]
+    pub blank_ids: Vec<i32>,
+}
+
+/// Query parameters for listing extraction batches.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtractionBatchFilter {
+    /// Only the batch this sample was extracted in
+    pub sample_id: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// An extraction batch and its samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionBatchResponse {
+    pub id: i32,
+    pub name: String,
+    pub instrument: Option<String>,
+    pub kit: String,
+    pub kit_lot: String,
+    pub operator: String,
+    pub extracted_at: DateTime<Utc>,
+    pub sample_ids: Vec<i32>,
+    pub blank_ids: Vec<i32>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ExtractionBatch> for ExtractionBatchResponse {
+    fn from(batch: ExtractionBatch) -> Self {
+        Self {
+            id: batch.id,
+            name: batch.name,
+            instrument: batch.instrument,
+            kit: batch.kit,
+            kit_lot: batch.kit_lot,
+            operator: batch.operator,
+            extracted_at: batch.extracted_at,
+            sample_ids: batch.sample_ids,
+            blank_ids: batch.blank_ids,
+            created_by: batch.created_by,
+            created_at: batch.created_at,
+            updated_at: batch.updated_at,
+        }
+    }
+}
+
+/// Query parameters for the extraction yield report.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtractionYieldFilter {
+    /// Only batches using this kit
+    pub kit: Option<String>,
+    /// Only batches using this kit lot
+    pub kit_lot: Option<String>,
+    /// How many of the most recent batches to cover
+    pub limit: Option<u64>,
+}
+
+/// Yield of a reagent blank. Any yield means the batch's reagents were
+/// contaminated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlankYieldResponse {
+    pub sample_id: i32,
+    pub name: String,
+    /// None if the blank hasn't been quantified
+    pub yield_ng: Option<f64>,
+}
+
+/// Yields of the samples of one extraction batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchYieldResponse {
+    pub batch_id: i32,
+    pub name: String,
+    pub kit: String,
+    pub kit_lot: String,
+    pub extracted_at: DateTime<Utc>,
+    /// Number of samples in the batch
+    pub samples: usize,
+    /// Distribution of the yields of the samples that have been quantified
+    pub yields: Option<YieldStatistics>,
+    pub blanks: Vec<BlankYieldResponse>,
+    /// Whether any blank has a yield
+    pub contaminated: bool,
+}
+
+/// Yields of every sample extracted with one kit lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KitLotYieldResponse {
+    pub kit: String,
+    pub kit_lot: String,
+    pub batches: usize,
+    pub yields: Option<YieldStatistics>,
+}
+
+/// Yields by extraction batch and by kit lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionYieldReport {
+    /// Most recently extracted first
+    pub batches: Vec<BatchYieldResponse>,
+    /// Ordered by kit, then lot
+    pub lots: Vec<KitLotYieldResponse>,
+}
@@ -0,0 +1,86 @@
+//! Facility status and maintenance Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{MaintenanceWindow, SequencerStatus};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to announce planned maintenance.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateMaintenanceRequest {
+    /// Sequencer being serviced; the whole facility if unset
+    pub sequencer_id: Option<i32>,
+
+    pub starts_at: DateTime<Utc>,
+
+    pub ends_at: DateTime<Utc>,
+
+    /// Shown on the public status page
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
+
+/// A maintenance window, as seen by staff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindowResponse {
+    pub id: i32,
+    pub sequencer_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<MaintenanceWindow> for MaintenanceWindowResponse {
+    fn from(window: MaintenanceWindow) -> Self {
+        Self {
+            id: window.id,
+            sequencer_id: window.sequencer_id,
+            starts_at: window.starts_at,
+            ends_at: window.ends_at,
+            reason: window.reason,
+            created_by: window.created_by,
+            created_at: window.created_at,
+        }
+    }
+}
+
+/// Whether the facility is taking runs, as published on the public status
+/// page. Only what a collaborator needs is included: no serial numbers,
+/// addresses or staff names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FacilityStatusResponse {
+    /// True unless the whole facility is down or no sequencer is usable
+    pub accepting_runs: bool,
+    /// Sequencers in service, by name
+    pub instruments: Vec<InstrumentAvailability>,
+    /// Maintenance under way or announced, soonest first
+    pub planned_maintenance: Vec<PlannedMaintenance>,
+}
+
+/// Whether one sequencer can take runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct InstrumentAvailability {
+    pub name: String,
+    /// Instrument model, e.g. "NovaSeq 6000"
+    pub model: String,
+    pub platform: String,
+    /// Maintenance while a window is under way, whatever was recorded
+    pub status: SequencerStatus,
+    /// True if the sequencer is available or running
+    pub available: bool,
+    /// When maintenance under way ends
+    pub back_at: Option<DateTime<Utc>>,
+}
+
+/// Maintenance under way or announced.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlannedMaintenance {
+    /// Sequencer being serviced; the whole facility if unset
+    pub instrument: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: String,
+    pub in_progress: bool,
+}
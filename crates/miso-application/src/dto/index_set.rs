@@ -0,0 +1,113 @@
+//! Index catalog Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::IndexSet;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::codes;
+
+/// One index of a set being registered.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct IndexSetEntryRequest {
+    /// Index name, e.g. "UDP0001"
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    #[validate(length(min = 1, max = 50))]
+    pub i7: String,
+
+    /// Second index, for dual-index kits
+    #[validate(length(min = 1, max = 50))]
+    pub i5: Option<String>,
+}
+
+/// Request to register an index kit or plate in the catalog.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateIndexSetRequest {
+    /// Kit or plate name, e.g. "IDT UDI Plate 1"
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    /// Family code: "tru_seq", "nextera", "idt_udi", "ten_x" or "custom"
+    pub family: String,
+
+    /// The indices in the order the kit lists them, e.g. by well
+    #[validate(length(min = 1, max = 384), nested)]
+    pub indices: Vec<IndexSetEntryRequest>,
+}
+
+/// An index of a set, with its position in the kit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSetEntryResponse {
+    /// 1-based position in the kit's order
+    pub position: usize,
+    pub name: String,
+    pub i7: String,
+    pub i5: Option<String>,
+}
+
+/// Response containing an index set and its indices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSetResponse {
+    pub id: i32,
+    pub name: String,
+    /// Family code, e.g. "idt_udi"
+    pub family: String,
+    pub indices: Vec<IndexSetEntryResponse>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<IndexSet> for IndexSetResponse {
+    fn from(set: IndexSet) -> Self {
+        Self {
+            id: set.id,
+            name: set.name,
+            family: codes::index_family(set.family).to_string(),
+            indices: set
+                .indices
+                .iter()
+                .enumerate()
+                .map(|(i, index)| IndexSetEntryResponse {
+                    position: i + 1,
+                    name: index.name().to_string(),
+                    i7: index.i7().to_string(),
+                    i5: index.i5().map(str::to_string),
+                })
+                .collect(),
+            created_by: set.created_by,
+            created_at: set.created_at,
+        }
+    }
+}
+
+/// Summary of an index set for listings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSetSummary {
+    pub id: i32,
+    pub name: String,
+    pub family: String,
+    pub index_count: usize,
+}
+
+impl From<IndexSet> for IndexSetSummary {
+    fn from(set: IndexSet) -> Self {
+        Self {
+            id: set.id,
+            family: codes::index_family(set.family).to_string(),
+            index_count: set.indices.len(),
+            name: set.name,
+        }
+    }
+}
+
+/// Request to give a library the index at a position of a catalog set.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct SetCatalogIndexRequest {
+    pub index_set_id: i32,
+
+    /// 1-based position in the set, e.g. 5 for the fifth well
+    #[validate(range(min = 1))]
+    pub position: usize,
+}
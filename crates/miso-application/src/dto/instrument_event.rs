@@ -0,0 +1,79 @@
+//! Instrument event Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{EventSeverity, InstrumentEvent};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Format of an instrument log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstrumentLogFormat {
+    /// Illumina control software or RTA text log
+    IlluminaLog,
+    /// Oxford Nanopore MinKNOW run report JSON
+    OntReport,
+}
+
+/// Request to ingest an instrument log file from a run folder.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct IngestInstrumentLogRequest {
+    pub format: InstrumentLogFormat,
+
+    /// Path of the file within the run folder, e.g. "Logs/NovaSeq.log"
+    #[validate(length(min = 1, max = 255))]
+    pub file_name: String,
+
+    /// File contents
+    pub contents: String,
+}
+
+/// Response containing an instrument event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentEventResponse {
+    pub id: i32,
+    pub severity: EventSeverity,
+    pub source: String,
+    pub code: Option<String>,
+    pub message: String,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub ingested_by: String,
+    pub ingested_at: DateTime<Utc>,
+}
+
+impl From<InstrumentEvent> for InstrumentEventResponse {
+    fn from(event: InstrumentEvent) -> Self {
+        Self {
+            id: event.id,
+            severity: event.severity,
+            source: event.source,
+            code: event.code,
+            message: event.message,
+            occurred_at: event.occurred_at,
+            ingested_by: event.ingested_by,
+            ingested_at: event.ingested_at,
+        }
+    }
+}
+
+/// Response containing the instrument events reported for a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunInstrumentEventsResponse {
+    pub run_id: i32,
+    pub errors: usize,
+    pub warnings: usize,
+    pub events: Vec<InstrumentEventResponse>,
+}
+
+impl RunInstrumentEventsResponse {
+    /// Builds the response for a run's events.
+    pub fn new(run_id: i32, events: Vec<InstrumentEvent>) -> Self {
+        let errors = events.iter().filter(|e| e.is_error()).count();
+        Self {
+            run_id,
+            errors,
+            warnings: events.len() - errors,
+            events: events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
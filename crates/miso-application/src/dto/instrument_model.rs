@@ -0,0 +1,97 @@
+//! Instrument model catalog Data Transfer Objects.
+
+use miso_domain::entities::{InstrumentModel, Platform};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to add an instrument model to the catalog.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateInstrumentModelRequest {
+    pub platform: Platform,
+
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    #[validate(range(min = 1))]
+    pub partitions: u8,
+
+    pub description: Option<String>,
+
+    pub container_models: Option<Vec<String>>,
+
+    pub patterned_containers: Option<Vec<String>>,
+
+    pub chemistries: Option<Vec<String>>,
+
+    #[validate(range(min = 1))]
+    pub max_read_length: Option<u16>,
+
+    #[validate(range(min = 1))]
+    pub expected_run_hours: Option<u32>,
+
+    #[validate(range(min = 1))]
+    pub seconds_per_cycle: Option<u32>,
+}
+
+/// Request to update a catalogued instrument model.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateInstrumentModelRequest {
+    pub platform: Option<Platform>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(range(min = 1))]
+    pub partitions: Option<u8>,
+
+    pub description: Option<String>,
+
+    pub container_models: Option<Vec<String>>,
+
+    pub patterned_containers: Option<Vec<String>>,
+
+    pub chemistries: Option<Vec<String>>,
+
+    #[validate(range(min = 1))]
+    pub max_read_length: Option<u16>,
+
+    #[validate(range(min = 1))]
+    pub expected_run_hours: Option<u32>,
+
+    #[validate(range(min = 1))]
+    pub seconds_per_cycle: Option<u32>,
+}
+
+/// Response containing a catalogued instrument model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentModelResponse {
+    pub id: i32,
+    pub platform: Platform,
+    pub name: String,
+    pub partitions: u8,
+    pub description: Option<String>,
+    pub container_models: Vec<String>,
+    pub patterned_containers: Vec<String>,
+    pub chemistries: Vec<String>,
+    pub max_read_length: Option<u16>,
+    pub expected_run_hours: Option<u32>,
+    pub seconds_per_cycle: Option<u32>,
+}
+
+impl From<InstrumentModel> for InstrumentModelResponse {
+    fn from(model: InstrumentModel) -> Self {
+        Self {
+            id: model.id,
+            platform: model.platform,
+            name: model.name,
+            partitions: model.partitions,
+            description: model.description,
+            container_models: model.container_models,
+            patterned_containers: model.patterned_containers,
+            chemistries: model.chemistries,
+            max_read_length: model.max_read_length,
+            expected_run_hours: model.expected_run_hours,
+            seconds_per_cycle: model.seconds_per_cycle,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Integrity audit Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::services::{IntegrityCheck, IntegrityViolation};
+use serde::{Deserialize, Serialize};
+
+/// Whether a check was run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// The check ran to completion
+    Ran,
+    /// The data the check needs isn't available
+    Skipped,
+}
+
+/// Summary of one check within an audit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckOutcome {
+    pub check: IntegrityCheck,
+    pub status: CheckStatus,
+    /// Number of entities inspected
+    pub entities_checked: usize,
+    pub violation_count: usize,
+}
+
+/// Machine-readable result of an integrity audit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub checks: Vec<CheckOutcome>,
+    pub violations: Vec<IntegrityViolation>,
+}
+
+impl IntegrityReport {
+    /// Returns true if no check found a violation.
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
@@ -0,0 +1,70 @@
+//! Label print Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{LabelPrint, LabelUsage};
+use serde::{Deserialize, Serialize};
+
+/// A label printed for an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelPrintResponse {
+    pub id: i32,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub template: String,
+    /// Which print of the label it was, from 1
+    pub copy: u32,
+    pub printed_by: String,
+    pub printed_at: DateTime<Utc>,
+    /// Why a manager allowed a reprint of a unique label
+    pub override_reason: Option<String>,
+}
+
+impl From<LabelPrint> for LabelPrintResponse {
+    fn from(print: LabelPrint) -> Self {
+        Self {
+            id: print.id,
+            entity_type: print.entity_type,
+            entity_id: print.entity_id,
+            template: print.template,
+            copy: print.copy,
+            printed_by: print.printed_by,
+            printed_at: print.printed_at,
+            override_reason: print.override_reason,
+        }
+    }
+}
+
+/// The labels a print job used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelUsageResponse {
+    pub id: i32,
+    pub printer: String,
+    pub template: String,
+    /// Labels sent to the printer
+    pub labels: u32,
+    /// The printer's lifetime label count as the job started, if read
+    pub odometer: Option<u64>,
+    pub printed_by: String,
+    pub printed_at: DateTime<Utc>,
+}
+
+impl From<LabelUsage> for LabelUsageResponse {
+    fn from(usage: LabelUsage) -> Self {
+        Self {
+            id: usage.id,
+            printer: usage.printer,
+            template: usage.template,
+            labels: usage.labels,
+            odometer: usage.odometer,
+            printed_by: usage.printed_by,
+            printed_at: usage.printed_at,
+        }
+    }
+}
+
+/// A print job: the labels printed and the stock they used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelJobResponse {
+    pub prints: Vec<LabelPrintResponse>,
+    pub usage: LabelUsageResponse,
+}
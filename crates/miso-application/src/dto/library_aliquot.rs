@@ -0,0 +1,48 @@
+//! Library aliquot Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::LibraryAliquot;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to take an aliquot of a library.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateLibraryAliquotRequest {
+    /// Volume to take, withdrawn from the library
+    #[validate(range(min = 0.0))]
+    pub volume_ul: f64,
+
+    /// Concentration of the aliquot, if diluted; defaults to the library's
+    #[validate(range(min = 0.0))]
+    pub concentration_ng_ul: Option<f64>,
+
+    /// Barcode of the aliquot's tube, if it has its own
+    #[validate(length(min = 1, max = 50))]
+    pub barcode: Option<String>,
+}
+
+/// A portion of a library taken out for pooling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryAliquotResponse {
+    pub id: i32,
+    pub library_id: i32,
+    pub barcode: Option<String>,
+    pub volume_ul: Option<f64>,
+    pub concentration_ng_ul: Option<f64>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<LibraryAliquot> for LibraryAliquotResponse {
+    fn from(aliquot: LibraryAliquot) -> Self {
+        Self {
+            id: aliquot.id,
+            library_id: aliquot.library_id,
+            barcode: aliquot.barcode.map(|b| b.to_string()),
+            volume_ul: aliquot.volume.map(|v| v.as_microliters()),
+            concentration_ng_ul: aliquot.concentration.map(|c| c.value()),
+            created_by: aliquot.created_by,
+            created_at: aliquot.created_at,
+        }
+    }
+}
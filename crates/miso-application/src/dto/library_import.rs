@@ -0,0 +1,42 @@
+//! Library sheet import Data Transfer Objects.
+
+use serde::{Deserialize, Serialize};
+
+use super::LibraryResponse;
+
+/// Request to create libraries from a library sheet CSV or TSV.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportLibrariesRequest {
+    /// Raw file contents
+    pub contents: String,
+    /// Check every row without creating anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result for one row of a library sheet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryImportRowResult {
+    pub line: usize,
+    pub name: String,
+    /// Barcode of the sample the library is made from
+    pub sample: String,
+    /// Name of the index resolved from the catalog, if any
+    pub index: Option<String>,
+    /// What is wrong with the row; empty if it can be imported
+    pub errors: Vec<String>,
+}
+
+/// Response to a library sheet import.
+///
+/// Like sample manifests, library sheets are imported all-or-nothing: if
+/// any row has errors, or on a dry run, `applied` is false and no library
+/// has been created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryImportResponse {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub results: Vec<LibraryImportRowResult>,
+    /// The created libraries, empty unless applied
+    pub libraries: Vec<LibraryResponse>,
+}
@@ -1,8 +1,87 @@
 //! Data Transfer Objects for API boundaries.
+//!
+//! Those the frontend and client library also use live in miso-dto and
+//! are re-exported here; the rest are only seen by API clients.
 
-mod project;
-mod sample;
-
-pub use project::*;
-pub use sample::*;
+mod activity;
+mod api_key;
+mod api_usage;
+mod archive_rule;
+mod attachment;
+mod attribute;
+mod audit;
+mod box_import;
+mod consent;
+mod data_location;
+mod delivery;
+mod erasure;
+mod event_store;
+mod export;
+mod extraction_batch;
+mod facility_status;
+mod instrument_event;
+mod instrument_model;
+mod index_set;
+mod integrity;
+mod label_print;
+mod library_aliquot;
+mod library_import;
+mod panel;
+mod project_member;
+mod qc;
+mod qc_report;
+mod reagent;
+mod reference_genome;
+mod requisition;
+mod retention;
+mod sample_import;
+mod sample_list;
+mod sample_sheet;
+mod sequencer;
+mod storage_audit;
+mod storage_unit;
+mod subproject;
+mod transfer;
+mod workset;
 
+pub use activity::*;
+pub use api_key::*;
+pub use api_usage::*;
+pub use archive_rule::*;
+pub use attachment::*;
+pub use attribute::*;
+pub use audit::*;
+pub use box_import::*;
+pub use consent::*;
+pub use data_location::*;
+pub use delivery::*;
+pub use erasure::*;
+pub use event_store::*;
+pub use export::*;
+pub use extraction_batch::*;
+pub use facility_status::*;
+pub use instrument_event::*;
+pub use instrument_model::*;
+pub use index_set::*;
+pub use integrity::*;
+pub use label_print::*;
+pub use library_aliquot::*;
+pub use library_import::*;
+pub use miso_dto::*;
+pub use panel::*;
+pub use project_member::*;
+pub use qc::*;
+pub use qc_report::*;
+pub use reagent::*;
+pub use reference_genome::*;
+pub use requisition::*;
+pub use retention::*;
+pub use sample_import::*;
+pub use sample_list::*;
+pub use sample_sheet::*;
+pub use sequencer::*;
+pub use storage_audit::*;
+pub use storage_unit::*;
+pub use subproject::*;
+pub use transfer::*;
+pub use workset::*;
@@ -0,0 +1,79 @@
+//! Targeted panel Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::Panel;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create the first version of a targeted panel.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreatePanelRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    /// Genome build the intervals are on, e.g. "GRCh38"
+    #[validate(length(min = 1, max = 50))]
+    pub genome_build: String,
+
+    pub description: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub bed_file_name: String,
+
+    /// Contents of the BED file listing the target regions
+    pub bed_contents: String,
+}
+
+/// Request to record a new version of a panel with revised target regions.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct PanelVersionRequest {
+    /// Genome build of the new version; defaults to the previous version's
+    #[validate(length(min = 1, max = 50))]
+    pub genome_build: Option<String>,
+
+    /// Description of the new version; defaults to the previous version's
+    pub description: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub bed_file_name: String,
+
+    /// Contents of the BED file listing the target regions
+    pub bed_contents: String,
+}
+
+/// Response containing a panel version's details, without its BED file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanelResponse {
+    pub id: i32,
+    pub name: String,
+    pub version: u32,
+    /// Name and version as given to pipelines, e.g. "CancerHotspot_v2"
+    pub identifier: String,
+    pub genome_build: String,
+    pub description: Option<String>,
+    pub bed_file_name: String,
+    /// Number of target intervals
+    pub intervals: u32,
+    /// Total bases covered by the intervals
+    pub target_bases: u64,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Panel> for PanelResponse {
+    fn from(panel: Panel) -> Self {
+        Self {
+            id: panel.id,
+            identifier: panel.identifier(),
+            name: panel.name,
+            version: panel.version,
+            genome_build: panel.genome_build,
+            description: panel.description,
+            bed_file_name: panel.bed_file.file_name,
+            intervals: panel.bed_file.intervals,
+            target_bases: panel.bed_file.target_bases,
+            created_by: panel.created_by,
+            created_at: panel.created_at,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Project membership Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request to add a user to a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddProjectMemberRequest {
+    pub user_id: i32,
+}
+
+/// A member of a project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectMemberResponse {
+    pub project_id: i32,
+    pub user_id: i32,
+    pub username: String,
+    pub display_name: String,
+    pub added_by: String,
+    pub added_at: DateTime<Utc>,
+}
@@ -0,0 +1,65 @@
+//! QC measurement Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{QcRecord, QcTarget};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record a QC measurement.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateQcRequest {
+    /// Test performed, e.g. "qubit", "tapestation" or a custom name
+    #[validate(length(min = 1, max = 100))]
+    pub test_type: String,
+
+    pub value: Option<f64>,
+
+    #[validate(length(max = 50))]
+    pub unit: Option<String>,
+
+    /// "not_ready", "ready", "passed", "failed" or "needs_review"
+    pub status: String,
+
+    pub notes: Option<String>,
+
+    /// When the test was performed; now if not given
+    pub performed_at: Option<DateTime<Utc>>,
+}
+
+/// Response containing a QC measurement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QcResponse {
+    pub id: i32,
+    pub entity_type: QcTarget,
+    pub entity_id: i32,
+    pub test_type: String,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub status: String,
+    pub notes: Option<String>,
+    pub performed_by: String,
+    pub performed_at: DateTime<Utc>,
+    /// Whether the entity's measurements now disagree with its final QC
+    /// status, which was kept for review
+    #[serde(default)]
+    pub status_needs_review: bool,
+}
+
+impl From<QcRecord> for QcResponse {
+    fn from(record: QcRecord) -> Self {
+        let result = record.result;
+        Self {
+            id: record.id,
+            entity_type: record.target,
+            entity_id: record.entity_id,
+            test_type: result.test_type.to_string(),
+            value: result.value,
+            unit: result.unit,
+            status: result.status.to_string(),
+            notes: result.notes,
+            performed_by: result.performed_by,
+            performed_at: result.performed_at,
+            status_needs_review: false,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! QC report Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::SampleQcSummary;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to attach a MultiQC report to a run.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct AttachQcReportRequest {
+    #[validate(url)]
+    pub report_url: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub file_name: Option<String>,
+
+    /// Contents of `multiqc_data.json`, if available
+    pub summary: Option<serde_json::Value>,
+}
+
+/// Response containing a run's QC report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunQcReportResponse {
+    pub id: i32,
+    pub run_id: i32,
+    pub report_url: Option<String>,
+    pub file_name: Option<String>,
+    pub multiqc_version: Option<String>,
+    pub samples: Vec<SampleQcSummary>,
+    pub attached_by: String,
+    pub attached_at: DateTime<Utc>,
+}
+
+impl From<miso_domain::entities::RunQcReport> for RunQcReportResponse {
+    fn from(report: miso_domain::entities::RunQcReport) -> Self {
+        Self {
+            id: report.id,
+            run_id: report.run_id,
+            report_url: report.report_url,
+            file_name: report.file_name,
+            multiqc_version: report.multiqc_version,
+            samples: report.samples,
+            attached_by: report.attached_by,
+            attached_at: report.attached_at,
+        }
+    }
+}
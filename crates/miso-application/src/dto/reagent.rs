@@ -0,0 +1,124 @@
+//! Reagent inventory Data Transfer Objects.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use miso_domain::entities::ReagentLot;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record a lot received into stock.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateReagentLotRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub kit: String,
+
+    #[validate(length(min = 1, max = 100))]
+    pub lot_number: String,
+
+    pub expires_on: NaiveDate,
+
+    /// Units received
+    #[validate(range(min = 0))]
+    pub quantity: i32,
+
+    /// Units at or below which the kit should be reordered
+    #[serde(default)]
+    #[validate(range(min = 0))]
+    pub reorder_level: i32,
+}
+
+/// Request to correct a lot or record units used.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateReagentLotRequest {
+    pub expires_on: Option<NaiveDate>,
+
+    /// Units left
+    #[validate(range(min = 0))]
+    pub quantity: Option<i32>,
+
+    #[validate(range(min = 0))]
+    pub reorder_level: Option<i32>,
+}
+
+/// Query parameters for listing reagent lots.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReagentLotFilter {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// Query parameters for the expiring-soon report.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReagentExpiryFilter {
+    /// How many days ahead to look (default: 30)
+    pub days: Option<u32>,
+}
+
+/// A reagent lot in stock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReagentLotResponse {
+    pub id: i32,
+    pub kit: String,
+    pub lot_number: String,
+    pub expires_on: NaiveDate,
+    pub quantity: i32,
+    pub reorder_level: i32,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ReagentLot> for ReagentLotResponse {
+    fn from(lot: ReagentLot) -> Self {
+        Self {
+            id: lot.id,
+            kit: lot.kit,
+            lot_number: lot.lot_number,
+            expires_on: lot.expires_on,
+            quantity: lot.quantity,
+            reorder_level: lot.reorder_level,
+            created_by: lot.created_by,
+            created_at: lot.created_at,
+            updated_at: lot.updated_at,
+        }
+    }
+}
+
+/// A lot with units left that has expired or expires soon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpiringLotResponse {
+    #[serde(flatten)]
+    pub lot: ReagentLotResponse,
+    /// Days until the lot expires, negative once it has
+    pub days_left: i64,
+    pub expired: bool,
+}
+
+/// A kit with few enough usable units left that it should be reordered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LowStockResponse {
+    pub kit: String,
+    /// Units left across the kit's unexpired lots
+    pub on_hand: i32,
+    /// Reorder level of the kit's most recently received lot
+    pub reorder_level: i32,
+    /// The kit's unexpired lots with units left
+    pub lot_numbers: Vec<String>,
+}
+
+/// Reagent lots expiring soon and kits running low.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReagentAlertReport {
+    /// Days ahead the expiry check looked
+    pub days: u32,
+    /// Soonest expiring first
+    pub expiring: Vec<ExpiringLotResponse>,
+    /// By kit
+    pub low_stock: Vec<LowStockResponse>,
+}
+
+impl ReagentAlertReport {
+    /// Returns true if there is nothing to act on.
+    pub fn is_empty(&self) -> bool {
+        self.expiring.is_empty() && self.low_stock.is_empty()
+    }
+}
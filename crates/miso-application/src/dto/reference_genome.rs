@@ -0,0 +1,74 @@
+//! Reference genome Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::ReferenceGenome;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to register a reference genome.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateReferenceGenomeRequest {
+    /// Build name pipelines know it by, e.g. "GRCh38"
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub species: String,
+
+    /// Where the FASTA is, for custom builds, e.g. "s3://refs/custom.fa"
+    #[validate(length(min = 1, max = 1024))]
+    pub fasta_uri: Option<String>,
+
+    pub description: Option<String>,
+}
+
+/// Request to update a reference genome. Its name can't be changed, since
+/// pipelines know it by name.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateReferenceGenomeRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub species: Option<String>,
+
+    #[validate(length(min = 1, max = 1024))]
+    pub fasta_uri: Option<String>,
+
+    pub description: Option<String>,
+}
+
+/// Response containing reference genome details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceGenomeResponse {
+    pub id: i32,
+    pub name: String,
+    pub species: String,
+    pub fasta_uri: Option<String>,
+    pub description: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ReferenceGenome> for ReferenceGenomeResponse {
+    fn from(genome: ReferenceGenome) -> Self {
+        Self {
+            id: genome.id,
+            name: genome.name,
+            species: genome.species,
+            fasta_uri: genome.fasta_uri,
+            description: genome.description,
+            created_by: genome.created_by,
+            created_at: genome.created_at,
+            updated_at: genome.updated_at,
+        }
+    }
+}
+
+/// The reference genome a library's data is analysed against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryReferenceGenome {
+    pub library_id: i32,
+    pub project_id: i32,
+    /// True if the genome is the project's, rather than set on the library
+    pub inherited: bool,
+    pub reference_genome: ReferenceGenomeResponse,
+}
@@ -0,0 +1,95 @@
+//! Requisition Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::Requisition;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record an external order.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateRequisitionRequest {
+    /// The order's ID in the ordering system
+    #[validate(length(min = 1, max = 255))]
+    pub alias: String,
+
+    /// Assay the order asks for
+    #[validate(length(min = 1, max = 255))]
+    pub assay: String,
+
+    /// Samples already received for the order
+    #[serde(default)]
+    pub sample_ids: Vec<i32>,
+}
+
+/// Request to correct a requisition's order ID or assay.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateRequisitionRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub alias: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub assay: Option<String>,
+}
+
+/// Samples to attach to or detach from a requisition.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RequisitionSamplesRequest {
+    #[validate(length(min = 1))]
+    pub sample_ids: Vec<i32>,
+}
+
+/// Request to pause or stop work on a requisition.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RequisitionHoldRequest {
+    /// Why work is being paused or stopped
+    #[validate(length(min = 1, max = 2000))]
+    pub reason: String,
+}
+
+/// Query parameters for listing requisitions.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequisitionFilter {
+    /// Only the requisition this sample is attached to
+    pub sample_id: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// A requisition and its samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequisitionResponse {
+    pub id: i32,
+    pub alias: String,
+    pub assay: String,
+    pub sample_ids: Vec<i32>,
+    pub stopped: bool,
+    pub stop_reason: Option<String>,
+    pub paused: bool,
+    pub pause_reason: Option<String>,
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Whether work on the order can go ahead
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Requisition> for RequisitionResponse {
+    fn from(requisition: Requisition) -> Self {
+        Self {
+            active: requisition.is_active(),
+            id: requisition.id,
+            alias: requisition.alias,
+            assay: requisition.assay,
+            sample_ids: requisition.sample_ids,
+            stopped: requisition.stopped,
+            stop_reason: requisition.stop_reason,
+            paused: requisition.paused,
+            pause_reason: requisition.pause_reason,
+            paused_at: requisition.paused_at,
+            created_by: requisition.created_by,
+            created_at: requisition.created_at,
+            updated_at: requisition.updated_at,
+        }
+    }
+}
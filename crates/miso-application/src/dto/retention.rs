@@ -0,0 +1,69 @@
+//! Log retention and archival Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{ArchivedLog, LogArchive, LogTableSize};
+use serde::{Deserialize, Serialize};
+
+/// An archive file of old log rows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogArchiveResponse {
+    pub id: i32,
+    pub log: ArchivedLog,
+    /// Path of the compressed archive file on the server
+    pub file: String,
+    pub first_id: i32,
+    pub last_id: i32,
+    pub rows: u64,
+    pub archived_at: DateTime<Utc>,
+    /// When the rows were put back, if they have been
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+impl From<LogArchive> for LogArchiveResponse {
+    fn from(archive: LogArchive) -> Self {
+        Self {
+            id: archive.id,
+            log: archive.log,
+            file: archive.file,
+            first_id: archive.first_id,
+            last_id: archive.last_id,
+            rows: archive.rows,
+            archived_at: archive.archived_at,
+            restored_at: archive.restored_at,
+        }
+    }
+}
+
+/// What one archival pass moved out of the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivalReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Rows archived, over every log
+    pub rows: u64,
+    /// Archives written, oldest first
+    pub archives: Vec<LogArchiveResponse>,
+}
+
+/// How big a log has grown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogTableSizeResponse {
+    pub log: ArchivedLog,
+    /// Rows in the database
+    pub rows: u64,
+    /// Rows in archives that haven't been restored
+    pub archived_rows: u64,
+    /// Archives that haven't been restored
+    pub archives: u64,
+}
+
+impl From<LogTableSize> for LogTableSizeResponse {
+    fn from(size: LogTableSize) -> Self {
+        Self {
+            log: size.log,
+            rows: size.rows,
+            archived_rows: size.archived_rows,
+            archives: size.archives,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Sample manifest import Data Transfer Objects.
+
+use serde::{Deserialize, Serialize};
+
+use super::SampleResponse;
+
+/// Request to create samples from a manifest CSV or TSV.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSamplesRequest {
+    /// Raw file contents
+    pub contents: String,
+    /// Check every row without creating anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result for one row of a sample manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleImportRowResult {
+    pub line: usize,
+    pub name: String,
+    /// Barcode the sample is or would be given
+    pub barcode: String,
+    /// What is wrong with the row; empty if it can be imported
+    pub errors: Vec<String>,
+}
+
+/// Response to a sample manifest import.
+///
+/// Imports are all-or-nothing: if any row has errors, or on a dry run,
+/// `applied` is false and no sample has been created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleImportResponse {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub results: Vec<SampleImportRowResult>,
+    /// The created samples, empty unless applied
+    pub samples: Vec<SampleResponse>,
+}
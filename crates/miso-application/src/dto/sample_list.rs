@@ -0,0 +1,116 @@
+//! Sample list Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{SampleListCount, SampleListRow};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for the sample list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SampleListQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub project_id: Option<i32>,
+    /// "not_ready", "ready", "passed", "failed" or "needs_review"
+    pub qc_status: Option<String>,
+    /// Text the name or barcode contains
+    pub search: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+    /// "name" (the default), "barcode", "project", "qc_status" or
+    /// "updated_at"
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// A sample's latest QC measurement, as listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatestQcSummary {
+    pub id: i32,
+    pub test_type: String,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub status: String,
+    pub performed_at: DateTime<Utc>,
+}
+
+/// A sample in the sample list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleListItem {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub project_id: i32,
+    pub project_code: String,
+    pub project_name: String,
+    pub sample_class: String,
+    pub qc_status: String,
+    pub latest_qc: Option<LatestQcSummary>,
+    pub box_id: Option<i32>,
+    pub box_name: Option<String>,
+    /// Position in the box, e.g. "B12"
+    pub position: Option<String>,
+    pub archived: bool,
+    pub received_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SampleListRow> for SampleListItem {
+    fn from(row: SampleListRow) -> Self {
+        let (box_id, box_name, position) = match row.location {
+            Some(l) => (Some(l.box_id), Some(l.box_name), Some(l.position)),
+            None => (None, None, None),
+        };
+        Self {
+            id: row.sample_id,
+            name: row.name,
+            barcode: row.barcode,
+            project_id: row.project_id,
+            project_code: row.project_code,
+            project_name: row.project_name,
+            sample_class: row.sample_class,
+            qc_status: row.qc_status.to_string(),
+            latest_qc: row.latest_qc.map(|qc| LatestQcSummary {
+                id: qc.qc_id,
+                test_type: qc.test_type,
+                value: qc.value,
+                unit: qc.unit,
+                status: qc.status.to_string(),
+                performed_at: qc.performed_at,
+            }),
+            box_id,
+            box_name,
+            position,
+            archived: row.archived,
+            received_at: row.received_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// How many unarchived samples of a project have a QC status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleListCountResponse {
+    pub project_id: i32,
+    pub qc_status: String,
+    pub count: u64,
+}
+
+impl From<SampleListCount> for SampleListCountResponse {
+    fn from(count: SampleListCount) -> Self {
+        Self {
+            project_id: count.project_id,
+            qc_status: count.qc_status.to_string(),
+            count: count.count,
+        }
+    }
+}
+
+/// Result of rebuilding the sample list from the tables it is drawn from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleListRebuildReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Number of rows rebuilt
+    pub rows: u64,
+}
@@ -0,0 +1,93 @@
+//! Sample sheet import Data Transfer Objects.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create a run from an externally planned sample sheet.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ImportSampleSheetRequest {
+    /// Raw sample sheet contents
+    #[validate(length(min = 1))]
+    pub contents: String,
+
+    /// Name for the new run
+    #[validate(length(min = 1, max = 255))]
+    pub run_name: String,
+
+    pub sequencer_id: i32,
+
+    /// Number of lanes on the flow cell; defaults to the highest lane in the sheet
+    #[validate(range(min = 1))]
+    pub partitions: Option<u8>,
+
+    pub container_barcode: Option<String>,
+
+    /// Model of the container, e.g. "S4 Flow Cell"; required with a barcode
+    #[validate(length(min = 1, max = 255))]
+    pub container_model: Option<String>,
+}
+
+/// A pool attached to a lane of the imported run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedPool {
+    pub lane: u8,
+    pub pool_id: i32,
+    pub pool_name: String,
+    /// False if an existing pool with exactly these libraries was reused
+    pub created: bool,
+    pub library_ids: Vec<i32>,
+}
+
+/// A sample sheet row that could not be matched to a library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmatchedRow {
+    pub line: usize,
+    pub lane: Option<u8>,
+    pub sample_id: String,
+    pub index: Option<String>,
+    pub index2: Option<String>,
+    pub reason: String,
+}
+
+/// Result of importing a sample sheet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleSheetImportResponse {
+    pub run_id: i32,
+    pub run_name: String,
+    pub pools: Vec<ImportedPool>,
+    pub matched_rows: usize,
+    pub unmatched: Vec<UnmatchedRow>,
+}
+
+/// Restricts a generated sample sheet to part of a run.
+///
+/// On flow cells shared between projects, each bioinformatics group is
+/// given only the rows for its own lanes or project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SampleSheetFilter {
+    /// Only include this lane
+    pub lane: Option<u8>,
+    /// Only include libraries from this project
+    pub project_id: Option<i32>,
+}
+
+/// Layout of a generated sample sheet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleSheetFormat {
+    /// IEM layout, for bcl2fastq
+    #[default]
+    V1,
+    /// BCL Convert layout
+    V2,
+    /// Oxford Nanopore MinKNOW sample sheet
+    MinKnow,
+}
+
+/// Request to mark a run ready to load on its sequencer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkReadyToLoadRequest {
+    /// Layout of the sample sheet sent to the sequencer; MinKNOW for
+    /// Oxford Nanopore sequencers and v1 otherwise if not given
+    pub format: Option<SampleSheetFormat>,
+}
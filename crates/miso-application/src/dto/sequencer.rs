@@ -0,0 +1,92 @@
+//! Sequencer registry Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Platform, Sequencer, SequencerStatus};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to register a sequencer.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateSequencerRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    /// Catalogued instrument model of the sequencer
+    pub instrument_model_id: i32,
+
+    #[validate(length(min = 1, max = 255))]
+    pub serial_number: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub location: Option<String>,
+
+    /// Address Run Scanner monitors the instrument at
+    #[validate(length(min = 1, max = 255))]
+    pub ip_address: Option<String>,
+
+    pub date_commissioned: Option<DateTime<Utc>>,
+}
+
+/// Request to change a sequencer's details. Its status is changed through
+/// the status transitions instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateSequencerRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    pub instrument_model_id: Option<i32>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub serial_number: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub location: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub ip_address: Option<String>,
+
+    pub date_commissioned: Option<DateTime<Utc>>,
+}
+
+/// Response containing a sequencer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencerResponse {
+    pub id: i32,
+    pub name: String,
+    pub serial_number: Option<String>,
+    pub instrument_model_id: i32,
+    pub model: String,
+    pub platform: Platform,
+    pub partitions: u8,
+    pub status: SequencerStatus,
+    /// True if runs can be started on the sequencer
+    pub can_run: bool,
+    pub location: Option<String>,
+    pub ip_address: Option<String>,
+    pub date_commissioned: Option<DateTime<Utc>>,
+    pub last_service_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Sequencer> for SequencerResponse {
+    fn from(sequencer: Sequencer) -> Self {
+        Self {
+            can_run: sequencer.can_run(),
+            platform: sequencer.platform(),
+            partitions: sequencer.num_partitions(),
+            id: sequencer.id,
+            name: sequencer.name,
+            serial_number: sequencer.serial_number,
+            instrument_model_id: sequencer.model.id,
+            model: sequencer.model.name,
+            status: sequencer.status,
+            location: sequencer.location,
+            ip_address: sequencer.ip_address,
+            date_commissioned: sequencer.date_commissioned,
+            last_service_date: sequencer.last_service_date,
+            created_at: sequencer.created_at,
+            updated_at: sequencer.updated_at,
+        }
+    }
+}
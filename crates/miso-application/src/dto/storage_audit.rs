@@ -0,0 +1,66 @@
+//! Storage audit Data Transfer Objects.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{ScanDiscrepancy, StorageAuditStatus};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to start a physical inventory of a freezer.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct StartStorageAuditRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub freezer: String,
+}
+
+/// A scan of one of an audit's boxes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordBoxScanRequest {
+    pub box_id: i32,
+    /// Barcodes read, by position, e.g. "A1" or "A01"; empty positions are
+    /// left out
+    pub positions: BTreeMap<String, String>,
+}
+
+/// Request to correct records to match what an audit found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptCorrectionsRequest {
+    /// Indexes of the discrepancies to correct; the rest are left as found
+    pub discrepancies: Vec<usize>,
+}
+
+/// The box an audit asks to be scanned next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditBoxPrompt {
+    pub box_id: i32,
+    pub name: String,
+    pub barcode: Option<String>,
+    /// Where to find it, e.g. "Freezer 1 / Shelf 2 / Rack A"
+    pub location: String,
+}
+
+/// A discrepancy found by an audit, with the index to accept it by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDiscrepancyResponse {
+    pub index: usize,
+    #[serde(flatten)]
+    pub discrepancy: ScanDiscrepancy,
+}
+
+/// Response containing a storage audit and what to scan next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageAuditResponse {
+    pub id: i32,
+    pub freezer: String,
+    pub status: StorageAuditStatus,
+    pub boxes_total: usize,
+    pub boxes_scanned: usize,
+    /// Null once every box is scanned
+    pub next_box: Option<AuditBoxPrompt>,
+    pub discrepancies: Vec<ScanDiscrepancyResponse>,
+    pub started_by: String,
+    pub started_at: DateTime<Utc>,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,136 @@
+//! Freezer, shelf and rack Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{StorageUnit, StorageUnitKind};
+use miso_dto::BoxSummary;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to add a freezer, a shelf to a freezer or a rack to a shelf.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateStorageUnitRequest {
+    /// "freezer", "shelf" or "rack"
+    pub kind: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    /// Freezer a shelf goes in or shelf a rack goes on
+    pub parent_id: Option<i32>,
+
+    #[validate(length(min = 1, max = 50))]
+    pub barcode: Option<String>,
+
+    /// Most shelves, racks or boxes the unit holds
+    #[validate(range(min = 1))]
+    pub capacity: Option<u32>,
+
+    /// Temperature (°C)
+    pub temperature: Option<i8>,
+}
+
+/// Request to update a freezer, shelf or rack. Unset fields are left as
+/// they are.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateStorageUnitRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 1, max = 50))]
+    pub barcode: Option<String>,
+
+    #[validate(range(min = 1))]
+    pub capacity: Option<u32>,
+
+    pub temperature: Option<i8>,
+}
+
+/// Query parameters for listing storage units.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StorageUnitFilter {
+    /// Only the units in this unit; freezers if unset
+    pub parent_id: Option<i32>,
+}
+
+/// Request to assign a box to a rack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignBoxRequest {
+    /// Rack to put the box in; `None` takes the box out of storage
+    pub rack_id: Option<i32>,
+}
+
+/// A freezer, shelf or rack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageUnitResponse {
+    pub id: i32,
+    /// "freezer", "shelf" or "rack"
+    pub kind: String,
+    pub name: String,
+    pub parent_id: Option<i32>,
+    pub barcode: Option<String>,
+    pub capacity: Option<u32>,
+    pub temperature: Option<i8>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<StorageUnit> for StorageUnitResponse {
+    fn from(unit: StorageUnit) -> Self {
+        Self {
+            id: unit.id,
+            kind: unit.kind.to_string(),
+            name: unit.name,
+            parent_id: unit.parent_id,
+            barcode: unit.barcode,
+            capacity: unit.capacity,
+            temperature: unit.temperature,
+            created_by: unit.created_by,
+            created_at: unit.created_at,
+            updated_at: unit.updated_at,
+        }
+    }
+}
+
+/// A freezer, shelf or rack with everything in it, for drawing a freezer
+/// map.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageUnitMap {
+    pub id: i32,
+    /// "freezer", "shelf" or "rack"
+    pub kind: String,
+    pub name: String,
+    pub barcode: Option<String>,
+    pub capacity: Option<u32>,
+    pub temperature: Option<i8>,
+    /// Shelves, racks or boxes the unit holds
+    pub used: usize,
+    /// Whether the unit is at capacity
+    pub full: bool,
+    /// Shelves of a freezer or racks of a shelf, by name; empty for racks
+    pub children: Vec<StorageUnitMap>,
+    /// Boxes in a rack, by name; empty for freezers and shelves
+    pub boxes: Vec<BoxSummary>,
+}
+
+impl StorageUnitMap {
+    /// Maps a unit holding `children`, or, for a rack, `boxes`.
+    pub fn new(unit: &StorageUnit, children: Vec<Self>, boxes: Vec<BoxSummary>) -> Self {
+        let used = match unit.kind {
+            StorageUnitKind::Rack => boxes.len(),
+            _ => children.len(),
+        };
+        Self {
+            id: unit.id,
+            kind: unit.kind.to_string(),
+            name: unit.name.clone(),
+            barcode: unit.barcode.clone(),
+            capacity: unit.capacity,
+            temperature: unit.temperature,
+            used,
+            full: unit.check_room(used).is_err(),
+            children,
+            boxes,
+        }
+    }
+}
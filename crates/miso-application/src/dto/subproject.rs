@@ -0,0 +1,52 @@
+//! Subproject Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::Subproject;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create a subproject of a project.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateSubprojectRequest {
+    /// Short name, unique within the project (e.g. "COHORT-A")
+    #[validate(length(min = 1, max = 100))]
+    pub alias: String,
+
+    #[validate(length(max = 4096))]
+    pub description: Option<String>,
+}
+
+/// Request to change a subproject.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateSubprojectRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub alias: Option<String>,
+
+    /// An empty description clears it
+    #[validate(length(max = 4096))]
+    pub description: Option<String>,
+}
+
+/// Response containing a subproject.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubprojectResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub alias: String,
+    pub description: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Subproject> for SubprojectResponse {
+    fn from(subproject: Subproject) -> Self {
+        Self {
+            id: subproject.id,
+            project_id: subproject.project_id,
+            alias: subproject.alias,
+            description: subproject.description,
+            created_by: subproject.created_by,
+            created_at: subproject.created_at,
+        }
+    }
+}
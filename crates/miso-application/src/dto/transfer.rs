@@ -0,0 +1,199 @@
+//! Transfer Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{
+    ShipmentStatus, Transfer, TransferItem, TransferItemType, TransferReceipt,
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to record samples and libraries handed from one lab or person
+/// to another.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateTransferRequest {
+    /// Lab or user sending the items
+    #[validate(length(min = 1, max = 255))]
+    pub sender: String,
+
+    /// Lab or user receiving the items
+    #[validate(length(min = 1, max = 255))]
+    pub recipient: String,
+
+    /// When the items were sent; now if not given
+    pub sent_at: Option<DateTime<Utc>>,
+
+    pub notes: Option<String>,
+
+    /// Courier carrying the items
+    #[validate(length(max = 100))]
+    pub courier: Option<String>,
+
+    /// Courier's tracking number; needs a courier
+    #[validate(length(max = 100))]
+    pub tracking_number: Option<String>,
+
+    /// Address to tell when the shipment is out for delivery
+    #[validate(email)]
+    pub recipient_email: Option<String>,
+
+    #[serde(default)]
+    pub sample_ids: Vec<i32>,
+
+    #[serde(default)]
+    pub library_ids: Vec<i32>,
+}
+
+/// Request to set or correct how a transfer was shipped. Fields left out
+/// are cleared.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateShipmentRequest {
+    #[validate(length(max = 100))]
+    pub courier: Option<String>,
+
+    /// Needs a courier; changing it forgets the status reported so far
+    #[validate(length(max = 100))]
+    pub tracking_number: Option<String>,
+
+    #[validate(email)]
+    pub recipient_email: Option<String>,
+}
+
+/// A status update pushed by a courier or tracking service.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct TrackingUpdateRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub tracking_number: String,
+
+    /// "pending", "in_transit", "out_for_delivery", "delivered" or
+    /// "exception"
+    pub status: ShipmentStatus,
+}
+
+/// What one pass over the shipments in transit found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackingSummary {
+    /// Shipments looked up
+    pub checked: usize,
+    /// Shipments whose status changed
+    pub updated: usize,
+    /// Shipments that couldn't be looked up
+    pub failed: usize,
+}
+
+/// What the recipient found for some of a transfer's items.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ReceiveTransferRequest {
+    #[validate(length(min = 1), nested)]
+    pub items: Vec<TransferReceiptRequest>,
+}
+
+/// What the recipient found for one item.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct TransferReceiptRequest {
+    /// "sample" or "library"
+    pub item_type: TransferItemType,
+
+    pub entity_id: i32,
+
+    /// Whether the item arrived
+    pub received: bool,
+
+    /// Whether the item passed QC on arrival, if it was checked
+    pub qc_passed: Option<bool>,
+
+    /// Required when the item fails QC
+    #[validate(length(max = 2000))]
+    pub qc_note: Option<String>,
+}
+
+impl From<TransferReceiptRequest> for TransferReceipt {
+    fn from(request: TransferReceiptRequest) -> Self {
+        Self {
+            item_type: request.item_type,
+            entity_id: request.entity_id,
+            received: request.received,
+            qc_passed: request.qc_passed,
+            qc_note: request.qc_note,
+        }
+    }
+}
+
+/// Query parameters for listing transfers.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferFilter {
+    /// Only transfers of this sample, oldest first
+    pub sample_id: Option<i32>,
+    /// Only transfers of this library, oldest first
+    pub library_id: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// An item of a transfer and what the recipient found.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferItemResponse {
+    /// "sample" or "library"
+    pub item_type: String,
+    pub entity_id: i32,
+    pub received: Option<bool>,
+    pub qc_passed: Option<bool>,
+    pub qc_note: Option<String>,
+}
+
+impl From<TransferItem> for TransferItemResponse {
+    fn from(item: TransferItem) -> Self {
+        Self {
+            item_type: item.item_type.as_str().to_string(),
+            entity_id: item.entity_id,
+            received: item.received,
+            qc_passed: item.qc_passed,
+            qc_note: item.qc_note,
+        }
+    }
+}
+
+/// A transfer and its items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferResponse {
+    pub id: i32,
+    pub sender: String,
+    pub recipient: String,
+    pub items: Vec<TransferItemResponse>,
+    pub notes: Option<String>,
+    pub courier: Option<String>,
+    pub tracking_number: Option<String>,
+    pub recipient_email: Option<String>,
+    /// Last status reported by the courier
+    pub shipment_status: Option<ShipmentStatus>,
+    pub shipment_updated_at: Option<DateTime<Utc>>,
+    pub sent_at: DateTime<Utc>,
+    /// Who checked every item in; `None` until then
+    pub received_by: Option<String>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Transfer> for TransferResponse {
+    fn from(transfer: Transfer) -> Self {
+        Self {
+            id: transfer.id,
+            sender: transfer.sender,
+            recipient: transfer.recipient,
+            items: transfer.items.into_iter().map(Into::into).collect(),
+            notes: transfer.notes,
+            courier: transfer.courier,
+            tracking_number: transfer.tracking_number,
+            recipient_email: transfer.recipient_email,
+            shipment_status: transfer.shipment_status,
+            shipment_updated_at: transfer.shipment_updated_at,
+            sent_at: transfer.sent_at,
+            received_by: transfer.received_by,
+            received_at: transfer.received_at,
+            created_by: transfer.created_by,
+            created_at: transfer.created_at,
+            updated_at: transfer.updated_at,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Workset Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Workset, WorksetItemType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request to create a workset.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateWorksetRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+
+    pub description: Option<String>,
+
+    /// Initial members
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub items: WorksetItemsRequest,
+}
+
+/// Samples and libraries to add to or remove from a workset, by ID or by
+/// barcode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct WorksetItemsRequest {
+    #[serde(default)]
+    pub sample_ids: Vec<i32>,
+
+    #[serde(default)]
+    pub library_ids: Vec<i32>,
+
+    /// Barcodes of samples or libraries, e.g. the tubes of a rack scan
+    #[serde(default)]
+    #[validate(length(max = 1000))]
+    pub barcodes: Vec<String>,
+}
+
+/// A workset and its members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorksetResponse {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    /// Sample members, in the order they were added
+    pub sample_ids: Vec<i32>,
+    /// Library members, in the order they were added
+    pub library_ids: Vec<i32>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Workset> for WorksetResponse {
+    fn from(workset: Workset) -> Self {
+        Self {
+            sample_ids: workset.ids(WorksetItemType::Sample),
+            library_ids: workset.ids(WorksetItemType::Library),
+            id: workset.id,
+            name: workset.name,
+            description: workset.description,
+            created_by: workset.created_by,
+            created_at: workset.created_at,
+            updated_at: workset.updated_at,
+        }
+    }
+}
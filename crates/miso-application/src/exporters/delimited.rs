@@ -0,0 +1,117 @@
+//! Delimited (CSV/TSV) rendering driven by export templates.
+//!
+//! Rows are the serialized API representations of entities, so any field a
+//! client can see can be exported by naming its dotted path.
+
+use miso_domain::entities::ExportTemplate;
+use serde_json::Value;
+
+/// Renders rows according to a template.
+///
+/// Missing fields render as empty cells. Cells containing the delimiter,
+/// quotes or line breaks are quoted.
+pub fn render_delimited(template: &ExportTemplate, rows: &[Value]) -> String {
+    let delimiter = template.delimiter.as_char();
+    let mut out = String::new();
+
+    if template.include_header {
+        let headers: Vec<String> = template
+            .columns
+            .iter()
+            .map(|c| escape(c.header_text(), delimiter))
+            .collect();
+        push_line(&mut out, &headers, delimiter);
+    }
+
+    for row in rows {
+        let cells: Vec<String> = template
+            .columns
+            .iter()
+            .map(|c| escape(&cell_text(lookup(row, &c.field)), delimiter))
+            .collect();
+        push_line(&mut out, &cells, delimiter);
+    }
+
+    out
+}
+
+fn push_line(out: &mut String, cells: &[String], delimiter: char) {
+    out.push_str(&cells.join(&delimiter.to_string()));
+    out.push('\n');
+}
+
+fn lookup<'a>(row: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(row, |value, key| value.get(key))
+}
+
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn escape(cell: &str, delimiter: char) -> String {
+    if cell.contains(delimiter) || cell.contains('"') || cell.contains('\n') || cell.contains('\r')
+    {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{Delimiter, ExportColumn, ExportEntityType};
+    use serde_json::json;
+
+    fn template(delimiter: Delimiter) -> ExportTemplate {
+        ExportTemplate::new(
+            1,
+            "Collaborator".to_string(),
+            ExportEntityType::Sample,
+            vec![
+                ExportColumn {
+                    field: "barcode".to_string(),
+                    header: Some("Tube ID".to_string()),
+                },
+                ExportColumn {
+                    field: "attributes.donor".to_string(),
+                    header: Some("Donor".to_string()),
+                },
+                ExportColumn {
+                    field: "volume_ul".to_string(),
+                    header: None,
+                },
+            ],
+            delimiter,
+            "admin".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_renders_nested_fields_and_escapes() {
+        let rows = vec![
+            json!({ "barcode": "S-001", "attributes": { "donor": "D, 12" }, "volume_ul": 20.5 }),
+            json!({ "barcode": "S-002", "volume_ul": null }),
+        ];
+
+        let output = render_delimited(&template(Delimiter::Comma), &rows);
+        assert_eq!(
+            output,
+            "Tube ID,Donor,volume_ul\nS-001,\"D, 12\",20.5\nS-002,,\n"
+        );
+    }
+
+    #[test]
+    fn test_tab_delimiter_without_header() {
+        let mut template = template(Delimiter::Tab);
+        template.include_header = false;
+
+        let output = render_delimited(&template, &[json!({ "barcode": "S-001" })]);
+        assert_eq!(output, "S-001\t\t\n");
+    }
+}
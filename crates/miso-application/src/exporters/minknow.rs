@@ -0,0 +1,114 @@
+//! Oxford Nanopore MinKNOW sample sheet writer.
+//!
+//! MinKNOW reads a CSV naming the flow cell, the sequencing kit and, for
+//! barcoded runs, which barcode each sample was ligated to, so that runs
+//! can be started and demultiplexed without retyping metadata at the
+//! sequencer. Barcode and alias columns are only written when a row has a
+//! barcode; MinKNOW rejects them on unbarcoded runs.
+
+/// A row of a MinKNOW sample sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinKnowRow {
+    pub flow_cell_id: String,
+    pub kit: String,
+    pub experiment_id: String,
+    pub sample_id: String,
+    /// MinKNOW's barcode name, e.g. "barcode01"
+    pub barcode: Option<String>,
+    /// What to name the barcode's reads by instead of the barcode
+    pub alias: Option<String>,
+}
+
+/// Renders a MinKNOW sample sheet.
+pub fn render_minknow_sample_sheet(rows: &[MinKnowRow]) -> String {
+    let barcoded = rows.iter().any(|r| r.barcode.is_some());
+    let mut out = String::new();
+
+    let mut headers = vec!["flow_cell_id", "kit", "experiment_id", "sample_id"];
+    if barcoded {
+        headers.extend(["barcode", "alias"]);
+    }
+    push_line(&mut out, &headers);
+
+    for row in rows {
+        let mut cells = vec![
+            row.flow_cell_id.as_str(),
+            row.kit.as_str(),
+            row.experiment_id.as_str(),
+            row.sample_id.as_str(),
+        ];
+        if barcoded {
+            cells.push(row.barcode.as_deref().unwrap_or_default());
+            cells.push(row.alias.as_deref().unwrap_or_default());
+        }
+        push_line(&mut out, &cells);
+    }
+
+    out
+}
+
+/// Turns an ONT barcode's index name ("NB01", "BC12", "RB05",
+/// "barcode07") into the name MinKNOW knows it by, e.g. "barcode01".
+///
+/// Returns `None` if the name doesn't end in a barcode number.
+pub fn minknow_barcode(index_name: &str) -> Option<String> {
+    let digits = index_name.len()
+        - index_name
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .len();
+    let number: u16 = index_name[index_name.len() - digits..].parse().ok()?;
+    (number > 0).then(|| format!("barcode{:02}", number))
+}
+
+/// MinKNOW sample sheets have no quoting, so commas are dropped from values.
+fn push_line(out: &mut String, cells: &[&str]) {
+    let cells: Vec<String> = cells.iter().map(|c| c.replace(',', "")).collect();
+    out.push_str(&cells.join(","));
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(barcode: Option<&str>, alias: Option<&str>) -> MinKnowRow {
+        MinKnowRow {
+            flow_cell_id: "PAM12345".to_string(),
+            kit: "SQK-NBD114-24".to_string(),
+            experiment_id: "RUN42".to_string(),
+            sample_id: "POOL1".to_string(),
+            barcode: barcode.map(str::to_string),
+            alias: alias.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_barcode_columns_only_for_barcoded_runs() {
+        let rendered = render_minknow_sample_sheet(&[
+            row(Some("barcode01"), Some("LIB001")),
+            row(Some("barcode02"), Some("LIB002")),
+        ]);
+        assert_eq!(
+            rendered,
+            "flow_cell_id,kit,experiment_id,sample_id,barcode,alias\n\
+             PAM12345,SQK-NBD114-24,RUN42,POOL1,barcode01,LIB001\n\
+             PAM12345,SQK-NBD114-24,RUN42,POOL1,barcode02,LIB002\n"
+        );
+
+        let rendered = render_minknow_sample_sheet(&[row(None, None)]);
+        assert_eq!(
+            rendered,
+            "flow_cell_id,kit,experiment_id,sample_id\nPAM12345,SQK-NBD114-24,RUN42,POOL1\n"
+        );
+    }
+
+    #[test]
+    fn test_minknow_barcode_names() {
+        assert_eq!(minknow_barcode("NB01").as_deref(), Some("barcode01"));
+        assert_eq!(minknow_barcode("BC12").as_deref(), Some("barcode12"));
+        assert_eq!(minknow_barcode("barcode7").as_deref(), Some("barcode07"));
+        assert_eq!(minknow_barcode("RB096").as_deref(), Some("barcode96"));
+        assert_eq!(minknow_barcode("NB00"), None);
+        assert_eq!(minknow_barcode("A01B"), None);
+    }
+}
@@ -0,0 +1,9 @@
+//! Exporters for writing entities to files for external tools.
+
+mod delimited;
+mod minknow;
+mod sample_sheet;
+
+pub use delimited::render_delimited;
+pub use minknow::{minknow_barcode, render_minknow_sample_sheet, MinKnowRow};
+pub use sample_sheet::{render_sample_sheet, render_sample_sheet_v2, sample_sheet_reads};
@@ -0,0 +1,218 @@
+//! Illumina sample sheet writer.
+//!
+//! Writes the v1 (IEM) and v2 (BCL Convert) layouts read by
+//! [`parse_sample_sheet`], always lane-split so that a sheet derived for
+//! part of a flow cell keeps its lane numbers. In v1, the Description
+//! column carries the targeted panel a library captured, for pipelines to
+//! pick their target regions by, and the Reference_Genome column the genome
+//! build to align it to; BCL Convert doesn't accept either.
+//!
+//! [`parse_sample_sheet`]: crate::importers::parse_sample_sheet
+
+use crate::importers::{SampleSheet, SampleSheetRow};
+
+/// Data section columns, in order.
+const COLUMNS: &[&str] = &[
+    "Lane",
+    "Sample_ID",
+    "Sample_Name",
+    "index",
+    "index2",
+    "Sample_Project",
+    "Description",
+    "Reference_Genome",
+];
+
+/// v2 `[BCLConvert_Data]` columns, in order.
+const V2_COLUMNS: &[&str] = &["Lane", "Sample_ID", "Index", "Index2", "Sample_Project"];
+
+/// Renders a sample sheet in the v1 layout.
+pub fn render_sample_sheet(sheet: &SampleSheet) -> String {
+    let mut out = String::from("[Header]\n");
+    for (key, value) in &sheet.header {
+        push_line(&mut out, &[key.as_str(), value.as_str()]);
+    }
+
+    out.push_str("\n[Reads]\n");
+    for read in &sheet.reads {
+        out.push_str(&read.to_string());
+        out.push('\n');
+    }
+
+    out.push_str("\n[Data]\n");
+    push_line(&mut out, COLUMNS);
+    for row in &sheet.rows {
+        let lane = row.lane.map(|l| l.to_string()).unwrap_or_default();
+        push_line(
+            &mut out,
+            &[
+                lane.as_str(),
+                row.sample_id.as_str(),
+                row.sample_name.as_deref().unwrap_or_default(),
+                row.index.as_deref().unwrap_or_default(),
+                row.index2.as_deref().unwrap_or_default(),
+                row.project.as_deref().unwrap_or_default(),
+                row.description.as_deref().unwrap_or_default(),
+                row.reference_genome.as_deref().unwrap_or_default(),
+            ],
+        );
+    }
+
+    out
+}
+
+/// Renders a sample sheet in the v2 layout.
+///
+/// The header is written as given, so it should carry `FileFormatVersion`.
+/// The first read is Read 1 and any later one Read 2; index cycles are the
+/// longest index of each kind in the rows.
+pub fn render_sample_sheet_v2(sheet: &SampleSheet) -> String {
+    let mut out = String::from("[Header]\n");
+    for (key, value) in &sheet.header {
+        push_line(&mut out, &[key.as_str(), value.as_str()]);
+    }
+
+    out.push_str("\n[Reads]\n");
+    let index_cycles = |index: fn(&SampleSheetRow) -> Option<&String>| {
+        sheet.rows.iter().filter_map(index).map(String::len).max()
+    };
+    let reads = [
+        ("Read1Cycles", sheet.reads.first().copied()),
+        ("Read2Cycles", sheet.reads.get(1..).and_then(<[u32]>::last).copied()),
+        ("Index1Cycles", index_cycles(|r| r.index.as_ref()).map(|c| c as u32)),
+        ("Index2Cycles", index_cycles(|r| r.index2.as_ref()).map(|c| c as u32)),
+    ];
+    for (key, cycles) in reads {
+        if let Some(cycles) = cycles {
+            push_line(&mut out, &[key, cycles.to_string().as_str()]);
+        }
+    }
+
+    out.push_str("\n[BCLConvert_Data]\n");
+    push_line(&mut out, V2_COLUMNS);
+    for row in &sheet.rows {
+        let lane = row.lane.map(|l| l.to_string()).unwrap_or_default();
+        push_line(
+            &mut out,
+            &[
+                lane.as_str(),
+                row.sample_id.as_str(),
+                row.index.as_deref().unwrap_or_default(),
+                row.index2.as_deref().unwrap_or_default(),
+                row.project.as_deref().unwrap_or_default(),
+            ],
+        );
+    }
+
+    out
+}
+
+/// Parses a run's read structure ("2x151" or "151+8+151") into the read
+/// lengths listed in a sample sheet's `[Reads]` section.
+pub fn sample_sheet_reads(read_length: &str) -> Vec<u32> {
+    match read_length.split_once('x') {
+        Some((count, length)) => match (count.trim().parse(), length.trim().parse()) {
+            (Ok(count), Ok(length)) => vec![length; count],
+            _ => Vec::new(),
+        },
+        None => read_length
+            .split('+')
+            .map(|r| r.trim().parse())
+            .collect::<Result<_, _>>()
+            .unwrap_or_default(),
+    }
+}
+
+/// Sample sheets have no quoting, so commas are dropped from values.
+fn push_line(out: &mut String, cells: &[&str]) {
+    let cells: Vec<String> = cells.iter().map(|c| c.replace(',', "")).collect();
+    out.push_str(&cells.join(","));
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::parse_sample_sheet;
+
+    #[test]
+    fn test_round_trips_through_parser() {
+        let mut sheet = SampleSheet {
+            reads: sample_sheet_reads("2x151"),
+            ..Default::default()
+        };
+        sheet
+            .header
+            .insert("Experiment Name".to_string(), "RUN42".to_string());
+        sheet.rows.push(SampleSheetRow {
+            line: 0,
+            lane: Some(2),
+            sample_id: "LIB001".to_string(),
+            sample_name: Some("Liver, left".to_string()),
+            index: Some("ATTACTCG".to_string()),
+            index2: None,
+            project: Some("PRJ1".to_string()),
+            description: Some("CancerHotspot_v2".to_string()),
+            reference_genome: Some("GRCh38".to_string()),
+        });
+
+        let parsed = parse_sample_sheet(&render_sample_sheet(&sheet)).unwrap();
+        assert_eq!(parsed.header, sheet.header);
+        assert_eq!(parsed.read_length().as_deref(), Some("2x151"));
+
+        let row = &parsed.rows[0];
+        assert_eq!(row.lane, Some(2));
+        assert_eq!(row.sample_id, "LIB001");
+        assert_eq!(row.sample_name.as_deref(), Some("Liver left"));
+        assert_eq!(row.index2, None);
+        assert_eq!(row.project.as_deref(), Some("PRJ1"));
+        assert_eq!(row.description.as_deref(), Some("CancerHotspot_v2"));
+        assert_eq!(row.reference_genome.as_deref(), Some("GRCh38"));
+    }
+
+    #[test]
+    fn test_v2_round_trips_through_parser() {
+        let mut sheet = SampleSheet {
+            reads: sample_sheet_reads("2x151"),
+            ..Default::default()
+        };
+        sheet
+            .header
+            .insert("FileFormatVersion".to_string(), "2".to_string());
+        sheet
+            .header
+            .insert("RunName".to_string(), "RUN42".to_string());
+        sheet.rows.push(SampleSheetRow {
+            line: 0,
+            lane: Some(1),
+            sample_id: "LIB001".to_string(),
+            sample_name: Some("Liver".to_string()),
+            index: Some("ATTACTCG".to_string()),
+            index2: Some("AGGCTATA".to_string()),
+            project: Some("PRJ1".to_string()),
+            description: Some("CancerHotspot_v2".to_string()),
+            reference_genome: None,
+        });
+
+        let rendered = render_sample_sheet_v2(&sheet);
+        assert!(rendered.contains("Read2Cycles,151\nIndex1Cycles,8\nIndex2Cycles,8\n"));
+        assert!(!rendered.contains("CancerHotspot_v2"));
+
+        let parsed = parse_sample_sheet(&rendered).unwrap();
+        assert_eq!(parsed.header, sheet.header);
+        assert_eq!(parsed.read_length().as_deref(), Some("2x151"));
+        let row = &parsed.rows[0];
+        assert_eq!(row.lane, Some(1));
+        assert_eq!(row.sample_id, "LIB001");
+        assert_eq!(row.index.as_deref(), Some("ATTACTCG"));
+        assert_eq!(row.index2.as_deref(), Some("AGGCTATA"));
+        assert_eq!(row.project.as_deref(), Some("PRJ1"));
+    }
+
+    #[test]
+    fn test_sample_sheet_reads() {
+        assert_eq!(sample_sheet_reads("2x151"), vec![151, 151]);
+        assert_eq!(sample_sheet_reads("151+8+151"), vec![151, 8, 151]);
+        assert!(sample_sheet_reads("long").is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+//! Box layout CSV parser.
+//!
+//! Freezer inventories kept in spreadsheets list one stored tube per row
+//! under a `Box,Position,Barcode` header. Columns are matched by name,
+//! ignoring case, so they may come in any order and alongside others.
+//! Rows without a barcode are empty positions and are skipped.
+
+use miso_domain::errors::DomainError;
+
+/// A stored item from a box layout CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxCsvRow {
+    /// 1-based line number in the file, for error reporting
+    pub line: usize,
+    /// Box column: the box's label
+    pub box_label: String,
+    /// Position column, e.g. "A1"
+    pub position: String,
+    /// Barcode column
+    pub barcode: String,
+}
+
+/// Parses the contents of a box layout CSV.
+pub fn parse_box_csv(contents: &str) -> Result<Vec<BoxCsvRow>, DomainError> {
+    // Spreadsheets often save CSV with a byte order mark
+    let mut lines = contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| DomainError::Validation("The box CSV is empty".to_string()))?;
+    let header = split_line(header);
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                DomainError::Validation(format!("The box CSV has no {} column", name))
+            })
+    };
+    let (box_col, position_col, barcode_col) =
+        (column("Box")?, column("Position")?, column("Barcode")?);
+
+    let mut rows = Vec::new();
+    for (i, raw) in lines {
+        let fields = split_line(raw);
+        let field = |col: usize| fields.get(col).cloned().unwrap_or_default();
+        let barcode = field(barcode_col);
+        if barcode.is_empty() {
+            continue;
+        }
+        rows.push(BoxCsvRow {
+            line: i + 1,
+            box_label: field(box_col),
+            position: field(position_col),
+            barcode,
+        });
+    }
+
+    Ok(rows)
+}
+
+fn split_line(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|f| f.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_box_csv() {
+        let contents = "\u{feff}Barcode,box,POSITION,Notes\r\n\
+            SAM001,Freezer 1 Box 3,A1,\r\n\
+            \r\n\
+            ,Freezer 1 Box 3,A2,empty\r\n\
+            \"SAM002\",Freezer 1 Box 3,b12\r\n";
+
+        let rows = parse_box_csv(contents).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                BoxCsvRow {
+                    line: 2,
+                    box_label: "Freezer 1 Box 3".to_string(),
+                    position: "A1".to_string(),
+                    barcode: "SAM001".to_string(),
+                },
+                BoxCsvRow {
+                    line: 5,
+                    box_label: "Freezer 1 Box 3".to_string(),
+                    position: "b12".to_string(),
+                    barcode: "SAM002".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_box_csv_needs_its_columns() {
+        assert!(parse_box_csv("").is_err());
+        assert!(parse_box_csv("Box,Barcode\nB1,SAM001\n").is_err());
+    }
+}
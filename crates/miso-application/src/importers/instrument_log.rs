@@ -0,0 +1,241 @@
+//! Instrument error and warning log parsers.
+//!
+//! Illumina control software and RTA write plain-text logs to the run
+//! folder, one entry per line, with a timestamp followed by the entry's
+//! level. Oxford Nanopore's MinKNOW writes a JSON run report whose
+//! `messages` array holds the run messages shown in its UI. Only warnings
+//! and errors are kept; informational entries are skipped.
+//!
+//! Neither format records a time zone, so timestamps are read as UTC.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use miso_domain::entities::EventSeverity;
+use miso_domain::errors::DomainError;
+use serde_json::Value;
+
+/// Timestamp layouts found at the start of Illumina log lines.
+const LOG_TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+];
+
+/// A warning or error read from an instrument log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportedEvent {
+    /// Severity
+    pub severity: EventSeverity,
+    /// Instrument-specific event code, if reported
+    pub code: Option<String>,
+    /// Message text
+    pub message: String,
+    /// When the event was logged, if recorded
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// Parses an Illumina control software or RTA log.
+///
+/// A line is an event if one of its first words, once stripped of brackets
+/// and colons, names a warning or error level, e.g. `[Warning]` or
+/// `ERROR:`. The rest of the line is the message.
+pub fn parse_illumina_log(contents: &str) -> Vec<ReportedEvent> {
+    contents.lines().filter_map(parse_log_line).collect()
+}
+
+fn parse_log_line(line: &str) -> Option<ReportedEvent> {
+    let (occurred_at, mut rest) = split_timestamp(line.trim());
+
+    for _ in 0..3 {
+        let (word, after) = next_word(rest)?;
+        if let Some(severity) = level(word) {
+            let message = after.trim_start_matches([':', '-', ' ', '\t']).trim();
+            if message.is_empty() {
+                return None;
+            }
+            return Some(ReportedEvent {
+                severity,
+                code: None,
+                message: message.to_string(),
+                occurred_at,
+            });
+        }
+        rest = after;
+    }
+
+    None
+}
+
+/// Splits a leading timestamp, one or two words long, off a log line.
+fn split_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    let Some((first, after_first)) = next_word(line) else {
+        return (None, line);
+    };
+    if let Some((_, after_second)) = next_word(after_first) {
+        let candidate = &line[..line.len() - after_second.len()];
+        if let Some(at) = parse_timestamp(candidate) {
+            return (Some(at), after_second);
+        }
+    }
+    match parse_timestamp(first) {
+        Some(at) => (Some(at), after_first),
+        None => (None, line),
+    }
+}
+
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    LOG_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s.trim(), format).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// Returns the next whitespace-delimited word and what follows it.
+fn next_word(s: &str) -> Option<(&str, &str)> {
+    let s = s.trim_start();
+    if s.is_empty() {
+        return None;
+    }
+    Some(match s.find(char::is_whitespace) {
+        Some(end) => (&s[..end], &s[end..]),
+        None => (s, ""),
+    })
+}
+
+fn level(word: &str) -> Option<EventSeverity> {
+    let word = word.trim_matches(|c| matches!(c, '[' | ']' | '(' | ')' | ':' | '<' | '>'));
+    match word.to_ascii_lowercase().as_str() {
+        "warn" | "warning" => Some(EventSeverity::Warning),
+        "err" | "error" | "fatal" | "critical" => Some(EventSeverity::Error),
+        _ => None,
+    }
+}
+
+/// Parses the contents of a MinKNOW run report (`report_*.json`).
+///
+/// Each entry of `messages` carries a `severity`, either MinKNOW's numeric
+/// level (2 for warnings, 3 and above for errors) or its name, a
+/// `user_message` or `message`, and optionally an `identifier` and an
+/// RFC 3339 `timestamp`.
+pub fn parse_ont_report(data: &Value) -> Result<Vec<ReportedEvent>, DomainError> {
+    let messages = data
+        .get("messages")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            DomainError::Validation("MinKNOW report has no messages section".to_string())
+        })?;
+
+    let mut events = Vec::new();
+    for entry in messages {
+        let severity = match entry.get("severity") {
+            Some(Value::Number(n)) => match n.as_u64() {
+                Some(2) => EventSeverity::Warning,
+                Some(level) if level >= 3 => EventSeverity::Error,
+                _ => continue,
+            },
+            Some(Value::String(s)) => match level(s) {
+                Some(severity) => severity,
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let Some(message) = entry
+            .get("user_message")
+            .or_else(|| entry.get("message"))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+        else {
+            continue;
+        };
+
+        events.push(ReportedEvent {
+            severity,
+            code: entry
+                .get("identifier")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            message: message.to_string(),
+            occurred_at: entry
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc)),
+        });
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_illumina_log_levels() {
+        let log = "\
+2024-12-15 08:31:02.345 [Info] Run started
+2024-12-15 09:12:44.001 [Warning] Chiller temperature 24.1C above set point
+12/15/2024 10:05:13.250 1 ERROR: Fluidics pressure check failed
+    at Instrument.Fluidics.Check()
+WARN: Low free space on output drive
+2024-12-15 11:00:00 [Error]
+";
+
+        let events = parse_illumina_log(log);
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0].severity, EventSeverity::Warning);
+        assert_eq!(
+            events[0].message,
+            "Chiller temperature 24.1C above set point"
+        );
+        assert_eq!(
+            events[0].occurred_at,
+            Some(
+                Utc.with_ymd_and_hms(2024, 12, 15, 9, 12, 44).unwrap()
+                    + chrono::Duration::milliseconds(1)
+            )
+        );
+
+        assert_eq!(events[1].severity, EventSeverity::Error);
+        assert_eq!(events[1].message, "Fluidics pressure check failed");
+        assert!(events[1].occurred_at.is_some());
+
+        assert_eq!(events[2].severity, EventSeverity::Warning);
+        assert_eq!(events[2].message, "Low free space on output drive");
+        assert_eq!(events[2].occurred_at, None);
+    }
+
+    #[test]
+    fn test_ont_report_messages() {
+        let data = json!({
+            "messages": [
+                { "severity": 1, "user_message": "Starting sequencing" },
+                {
+                    "severity": 2,
+                    "identifier": "flowcell_temperature",
+                    "user_message": "Flow cell temperature is high",
+                    "timestamp": "2024-12-15T09:00:00+01:00"
+                },
+                { "severity": "error", "message": "Pore scan failed" },
+                { "severity": 3, "user_message": " " }
+            ]
+        });
+
+        let events = parse_ont_report(&data).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].severity, EventSeverity::Warning);
+        assert_eq!(events[0].code.as_deref(), Some("flowcell_temperature"));
+        assert_eq!(
+            events[0].occurred_at,
+            Some(Utc.with_ymd_and_hms(2024, 12, 15, 8, 0, 0).unwrap())
+        );
+        assert_eq!(events[1].severity, EventSeverity::Error);
+        assert_eq!(events[1].message, "Pore scan failed");
+
+        assert!(parse_ont_report(&json!({})).is_err());
+    }
+}
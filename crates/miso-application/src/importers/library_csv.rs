@@ -0,0 +1,124 @@
+//! Library spreadsheet CSV/TSV parser.
+//!
+//! Library sheets list one new library per row under a header naming at
+//! least `Sample`, `Name`, `Design` and `Platform` columns; the sample is
+//! given by barcode. Columns are matched the same way as in sample
+//! manifests, ignoring case, spaces and underscores, and files whose
+//! header contains a tab are read as tab-separated. Values are returned as
+//! written for the caller to check.
+
+use miso_domain::errors::DomainError;
+
+use super::sample_csv::{column_key, split_line};
+
+/// A library from a library sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LibraryCsvRow {
+    /// 1-based line number in the file, for error reporting
+    pub line: usize,
+    /// Sample column: barcode of the sample the library is made from
+    pub sample: String,
+    pub name: String,
+    /// Design code, e.g. "wgs" or "rna_seq"
+    pub design: String,
+    pub platform: String,
+    /// Library type code; paired end if blank
+    pub library_type: Option<String>,
+    pub kit: Option<String>,
+    /// Index column: name of an index in the catalog
+    pub index: Option<String>,
+    /// Index Set column: the set the index is from, if its name alone is
+    /// ambiguous
+    pub index_set: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Parses the contents of a library sheet.
+pub fn parse_library_csv(contents: &str) -> Result<Vec<LibraryCsvRow>, DomainError> {
+    // Spreadsheets often save CSV with a byte order mark
+    let mut lines = contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| DomainError::Validation("The library file is empty".to_string()))?;
+    let separator = if header.contains('\t') { '\t' } else { ',' };
+    let header: Vec<String> = split_line(header, separator)
+        .iter()
+        .map(|h| column_key(h))
+        .collect();
+    let column = |name: &str| header.iter().position(|h| *h == column_key(name));
+    let required = |name: &str| {
+        column(name).ok_or_else(|| {
+            DomainError::Validation(format!("The library file has no {} column", name))
+        })
+    };
+    let [sample_col, name_col, design_col, platform_col] =
+        ["Sample", "Name", "Design", "Platform"].map(required);
+    let (sample_col, name_col, design_col, platform_col) =
+        (sample_col?, name_col?, design_col?, platform_col?);
+    let optional = ["Library Type", "Kit", "Index", "Index Set", "Description"].map(column);
+
+    let mut rows = Vec::new();
+    for (i, raw) in lines {
+        let fields = split_line(raw, separator);
+        let field = |col: usize| fields.get(col).cloned().unwrap_or_default();
+        let value = |col: Option<usize>| col.map(field).filter(|v| !v.is_empty());
+        let [library_type, kit, index, index_set, description] = optional.map(value);
+        rows.push(LibraryCsvRow {
+            line: i + 1,
+            sample: field(sample_col),
+            name: field(name_col),
+            design: field(design_col),
+            platform: field(platform_col),
+            library_type,
+            kit,
+            index,
+            index_set,
+            description,
+        });
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_library_csv() {
+        let contents = "Sample,Name,Design,Platform,Kit,Index,Index_Set\r\n\
+            SAM-1,LIB_1,wgs,ILLUMINA,TruSeq DNA PCR-Free,UDP0001,\r\n\
+            \r\n\
+            SAM-2,LIB_2,rna_seq,ILLUMINA,,UDP0002,IDT UDI Plate 1\r\n";
+        let rows = parse_library_csv(contents).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].sample, "SAM-1");
+        assert_eq!(rows[0].design, "wgs");
+        assert_eq!(rows[0].kit.as_deref(), Some("TruSeq DNA PCR-Free"));
+        assert_eq!(rows[0].index_set, None);
+        assert_eq!(rows[1].line, 4);
+        assert_eq!(rows[1].kit, None);
+        assert_eq!(rows[1].index_set.as_deref(), Some("IDT UDI Plate 1"));
+    }
+
+    #[test]
+    fn test_parse_library_tsv() {
+        let contents = "sample\tname\tdesign\tplatform\tlibrary type\n\
+            SAM-1\tLIB_1\twgs\tONT\tsingle_end\n";
+        let rows = parse_library_csv(contents).unwrap();
+
+        assert_eq!(rows[0].platform, "ONT");
+        assert_eq!(rows[0].library_type.as_deref(), Some("single_end"));
+        assert_eq!(rows[0].index, None);
+
+        assert!(parse_library_csv("").is_err());
+        assert!(parse_library_csv("Sample,Name,Design\nSAM-1,LIB_1,wgs\n").is_err());
+    }
+}
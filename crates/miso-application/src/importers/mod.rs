@@ -0,0 +1,18 @@
+//! Importers for files produced by external tools.
+//!
+//! Importers turn third-party file formats into domain values. They do no
+//! I/O themselves; callers hand over already-read contents.
+
+mod box_csv;
+mod instrument_log;
+mod library_csv;
+mod multiqc;
+mod sample_csv;
+mod sample_sheet;
+
+pub use box_csv::{parse_box_csv, BoxCsvRow};
+pub use instrument_log::{parse_illumina_log, parse_ont_report, ReportedEvent};
+pub use library_csv::{parse_library_csv, LibraryCsvRow};
+pub use multiqc::{parse_multiqc_summary, MultiQcSummary};
+pub use sample_csv::{parse_sample_csv, SampleCsvRow};
+pub use sample_sheet::{parse_sample_sheet, SampleSheet, SampleSheetRow};
@@ -0,0 +1,133 @@
+//! MultiQC summary (`multiqc_data.json`) parser.
+//!
+//! Only the General Statistics table is read. Each MultiQC module contributes
+//! one entry to `report_general_stats_data`, keyed by sample name, so values
+//! for a sample are merged across modules.
+
+use std::collections::BTreeMap;
+
+use miso_domain::entities::SampleQcSummary;
+use miso_domain::errors::DomainError;
+use serde_json::Value;
+
+/// Metrics extracted from a MultiQC summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiQcSummary {
+    /// MultiQC version that produced the file, if recorded
+    pub version: Option<String>,
+    /// Per-sample metrics, ordered by sample name
+    pub samples: Vec<SampleQcSummary>,
+}
+
+/// Parses the contents of a `multiqc_data.json` file.
+pub fn parse_multiqc_summary(data: &Value) -> Result<MultiQcSummary, DomainError> {
+    let sections = data
+        .get("report_general_stats_data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            DomainError::Validation(
+                "MultiQC summary has no report_general_stats_data section".to_string(),
+            )
+        })?;
+
+    let mut samples: BTreeMap<String, SampleQcSummary> = BTreeMap::new();
+
+    for section in sections {
+        let Some(section) = section.as_object() else {
+            continue;
+        };
+
+        for (sample_name, metrics) in section {
+            let Some(metrics) = metrics.as_object() else {
+                continue;
+            };
+
+            let summary = samples
+                .entry(sample_name.clone())
+                .or_insert_with(|| SampleQcSummary::new(sample_name.clone()));
+
+            for (key, value) in metrics {
+                let Some(value) = value.as_f64() else {
+                    continue;
+                };
+                apply_metric(summary, key, value);
+            }
+        }
+    }
+
+    let version = data
+        .get("config_version")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    Ok(MultiQcSummary {
+        version,
+        samples: samples.into_values().filter(|s| !s.is_empty()).collect(),
+    })
+}
+
+/// Maps a General Statistics key onto the summary, first value wins.
+fn apply_metric(summary: &mut SampleQcSummary, key: &str, value: f64) {
+    let slot = match key {
+        "total_sequences" => &mut summary.total_sequences,
+        "percent_gc" => &mut summary.percent_gc,
+        "percent_duplicates" => &mut summary.percent_duplicates,
+        // Picard reports duplication as a fraction
+        "PERCENT_DUPLICATION" => {
+            if summary.percent_duplicates.is_none() {
+                summary.percent_duplicates = Some(value * 100.0);
+            }
+            return;
+        }
+        "avg_sequence_length" => &mut summary.avg_sequence_length,
+        "percent_aligned" | "overall_alignment_rate" | "uniquely_mapped_percent" => {
+            &mut summary.percent_aligned
+        }
+        _ => return,
+    };
+
+    if slot.is_none() {
+        *slot = Some(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merges_modules_per_sample() {
+        let data = json!({
+            "config_version": "1.21",
+            "report_general_stats_data": [
+                {
+                    "LIB001": { "total_sequences": 1200000.0, "percent_gc": 48.0 },
+                    "LIB002": { "total_sequences": 900000.0, "percent_duplicates": 12.5 }
+                },
+                {
+                    "LIB001": { "PERCENT_DUPLICATION": 0.1, "unknown_metric": 3.0 },
+                    "LIB002": { "overall_alignment_rate": 97.2 }
+                }
+            ]
+        });
+
+        let summary = parse_multiqc_summary(&data).unwrap();
+        assert_eq!(summary.version.as_deref(), Some("1.21"));
+        assert_eq!(summary.samples.len(), 2);
+
+        let lib1 = &summary.samples[0];
+        assert_eq!(lib1.sample_name, "LIB001");
+        assert_eq!(lib1.total_sequences, Some(1200000.0));
+        assert!((lib1.percent_duplicates.unwrap() - 10.0).abs() < 1e-9);
+
+        let lib2 = &summary.samples[1];
+        assert_eq!(lib2.percent_duplicates, Some(12.5));
+        assert_eq!(lib2.percent_aligned, Some(97.2));
+    }
+
+    #[test]
+    fn test_rejects_missing_general_stats() {
+        assert!(parse_multiqc_summary(&json!({ "config_version": "1.21" })).is_err());
+    }
+}
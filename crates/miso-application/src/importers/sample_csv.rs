@@ -0,0 +1,149 @@
+//! Sample manifest CSV/TSV parser.
+//!
+//! Sample manifests list one new sample per row under a header naming
+//! at least `Name` and `Project` columns. Columns are matched by name,
+//! ignoring case, spaces and underscores, so "Scientific Name" and
+//! `scientific_name` are the same column. Files whose header contains a
+//! tab are read as tab-separated. Values are returned as written; checking
+//! them is left to the caller, so that every row's problems can be
+//! reported together.
+
+use miso_domain::errors::DomainError;
+
+/// A sample from a manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleCsvRow {
+    /// 1-based line number in the file, for error reporting
+    pub line: usize,
+    pub name: String,
+    /// Project column: the project's code
+    pub project: String,
+    /// Barcode to give the sample; one is generated if blank
+    pub barcode: Option<String>,
+    /// Class column, e.g. "tissue" or "Tissue Processing"; plain if blank
+    pub class: Option<String>,
+    /// Parent column: barcode of an existing sample or of an earlier row
+    pub parent: Option<String>,
+    pub scientific_name: Option<String>,
+    pub external_name: Option<String>,
+    pub tissue_origin: Option<String>,
+    pub tissue_type: Option<String>,
+    pub analyte_type: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Parses the contents of a sample manifest.
+pub fn parse_sample_csv(contents: &str) -> Result<Vec<SampleCsvRow>, DomainError> {
+    // Spreadsheets often save CSV with a byte order mark
+    let mut lines = contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| DomainError::Validation("The sample file is empty".to_string()))?;
+    let separator = if header.contains('\t') { '\t' } else { ',' };
+    let header: Vec<String> = split_line(header, separator)
+        .iter()
+        .map(|h| column_key(h))
+        .collect();
+    let column = |name: &str| header.iter().position(|h| *h == column_key(name));
+    let required = |name: &str| {
+        column(name).ok_or_else(|| {
+            DomainError::Validation(format!("The sample file has no {} column", name))
+        })
+    };
+    let (name_col, project_col) = (required("Name")?, required("Project")?);
+    let optional = [
+        "Barcode",
+        "Class",
+        "Parent",
+        "Scientific Name",
+        "External Name",
+        "Tissue Origin",
+        "Tissue Type",
+        "Analyte Type",
+        "Description",
+    ]
+    .map(column);
+
+    let mut rows = Vec::new();
+    for (i, raw) in lines {
+        let fields = split_line(raw, separator);
+        let field = |col: usize| fields.get(col).cloned().unwrap_or_default();
+        let value = |col: Option<usize>| col.map(field).filter(|v| !v.is_empty());
+        let [barcode, class, parent, scientific_name, external_name, tissue_origin, tissue_type, analyte_type, description] =
+            optional.map(value);
+        rows.push(SampleCsvRow {
+            line: i + 1,
+            name: field(name_col),
+            project: field(project_col),
+            barcode,
+            class,
+            parent,
+            scientific_name,
+            external_name,
+            tissue_origin,
+            tissue_type,
+            analyte_type,
+            description,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// A header cell as matched against column names.
+pub(super) fn column_key(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub(super) fn split_line(line: &str, separator: char) -> Vec<String> {
+    line.split(separator)
+        .map(|f| f.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sample_csv() {
+        let contents = "\u{feff}project,NAME,Class,Parent,External_Name,Notes\r\n\
+            PROJ1,Patient 7,identity,,P-0007,\r\n\
+            \r\n\
+            PROJ1,Patient 7 biopsy,Tissue,SAM-P7,,\"from clinic\"\r\n";
+        let rows = parse_sample_csv(contents).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].name, "Patient 7");
+        assert_eq!(rows[0].project, "PROJ1");
+        assert_eq!(rows[0].class.as_deref(), Some("identity"));
+        assert_eq!(rows[0].parent, None);
+        assert_eq!(rows[0].external_name.as_deref(), Some("P-0007"));
+        assert_eq!(rows[1].line, 4);
+        assert_eq!(rows[1].parent.as_deref(), Some("SAM-P7"));
+        assert_eq!(rows[1].barcode, None);
+    }
+
+    #[test]
+    fn test_parse_sample_tsv() {
+        let contents = "Name\tProject\tScientific Name\tBarcode\n\
+            Soil A\tENV\tSoil metagenome\tSAM100\n";
+        let rows = parse_sample_csv(contents).unwrap();
+
+        assert_eq!(rows[0].scientific_name.as_deref(), Some("Soil metagenome"));
+        assert_eq!(rows[0].barcode.as_deref(), Some("SAM100"));
+
+        assert!(parse_sample_csv("").is_err());
+        assert!(parse_sample_csv("Name,Barcode\nSoil A,SAM100\n").is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Illumina sample sheet parser.
+//!
+//! Supports both the v1 (IEM) layout with a `[Data]` section and the v2
+//! layout with a `[BCLConvert_Data]` section. Column names are matched
+//! case-insensitively, so `index`/`Index` and `index2`/`Index2` are both
+//! accepted.
+
+use std::collections::BTreeMap;
+
+use miso_domain::errors::DomainError;
+
+/// A parsed Illumina sample sheet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SampleSheet {
+    /// Key/value pairs from the `[Header]` section
+    pub header: BTreeMap<String, String>,
+    /// Read lengths from the `[Reads]` section, in order
+    pub reads: Vec<u32>,
+    /// Rows from the data section
+    pub rows: Vec<SampleSheetRow>,
+}
+
+impl SampleSheet {
+    /// Returns the highest lane number referenced, if any rows specify lanes.
+    pub fn max_lane(&self) -> Option<u8> {
+        self.rows.iter().filter_map(|r| r.lane).max()
+    }
+
+    /// Formats the read structure as stored on runs (e.g. "2x151").
+    pub fn read_length(&self) -> Option<String> {
+        match self.reads.as_slice() {
+            [] => None,
+            [first, rest @ ..] if rest.iter().all(|r| r == first) => {
+                Some(format!("{}x{}", self.reads.len(), first))
+            }
+            reads => Some(
+                reads
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>()
+                    .join("+"),
+            ),
+        }
+    }
+}
+
+/// A single row from the data section of a sample sheet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SampleSheetRow {
+    /// 1-based line number in the file, for error reporting
+    pub line: usize,
+    /// Lane, if the sheet is lane-split
+    pub lane: Option<u8>,
+    /// Sample_ID column
+    pub sample_id: String,
+    /// Sample_Name column
+    pub sample_name: Option<String>,
+    /// i7 index sequence
+    pub index: Option<String>,
+    /// i5 index sequence
+    pub index2: Option<String>,
+    /// Sample_Project column
+    pub project: Option<String>,
+    /// Description column
+    pub description: Option<String>,
+    /// Reference_Genome column
+    pub reference_genome: Option<String>,
+}
+
+/// Parses the contents of an Illumina sample sheet.
+pub fn parse_sample_sheet(contents: &str) -> Result<SampleSheet, DomainError> {
+    let mut sheet = SampleSheet::default();
+    let mut section = String::new();
+    let mut columns: Option<Vec<String>> = None;
+
+    for (i, raw) in contents.lines().enumerate() {
+        let line_number = i + 1;
+        let fields = split_line(raw);
+
+        if fields.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+
+        let first = fields[0].as_str();
+        if first.starts_with('[') && first.ends_with(']') {
+            section = first[1..first.len() - 1].to_ascii_lowercase();
+            columns = None;
+            continue;
+        }
+
+        match section.as_str() {
+            "header" => {
+                let value = fields.get(1).cloned().unwrap_or_default();
+                sheet.header.insert(first.to_string(), value);
+            }
+            "reads" => {
+                // v1 lists bare lengths; v2 uses "Read1Cycles,151" pairs
+                let value = if fields.len() > 1 && !fields[1].is_empty() {
+                    if first.to_ascii_lowercase().starts_with("index") {
+                        continue;
+                    }
+                    &fields[1]
+                } else {
+                    first
+                };
+                let length = value.parse().map_err(|_| {
+                    DomainError::Validation(format!(
+                        "Line {}: invalid read length '{}'",
+                        line_number, value
+                    ))
+                })?;
+                sheet.reads.push(length);
+            }
+            "data" | "bclconvert_data" => match &columns {
+                None => {
+                    columns = Some(fields.iter().map(|c| c.to_ascii_lowercase()).collect());
+                }
+                Some(cols) => {
+                    sheet.rows.push(parse_row(cols, &fields, line_number)?);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    if sheet.rows.is_empty() {
+        return Err(DomainError::Validation(
+            "Sample sheet has no data rows".to_string(),
+        ));
+    }
+
+    Ok(sheet)
+}
+
+fn parse_row(
+    columns: &[String],
+    fields: &[String],
+    line_number: usize,
+) -> Result<SampleSheetRow, DomainError> {
+    let get = |name: &str| {
+        columns
+            .iter()
+            .position(|c| c == name)
+            .and_then(|i| fields.get(i))
+            .filter(|v| !v.is_empty())
+            .cloned()
+    };
+
+    let sample_id = get("sample_id").ok_or_else(|| {
+        DomainError::Validation(format!("Line {}: missing Sample_ID", line_number))
+    })?;
+
+    let lane = get("lane")
+        .map(|l| {
+            l.parse::<u8>().ok().filter(|&n| n > 0).ok_or_else(|| {
+                DomainError::Validation(format!("Line {}: invalid lane '{}'", line_number, l))
+            })
+        })
+        .transpose()?;
+
+    Ok(SampleSheetRow {
+        line: line_number,
+        lane,
+        sample_id,
+        sample_name: get("sample_name"),
+        index: get("index").map(|s| s.to_uppercase()),
+        index2: get("index2").map(|s| s.to_uppercase()),
+        project: get("sample_project"),
+        description: get("description"),
+        reference_genome: get("reference_genome"),
+    })
+}
+
+fn split_line(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|f| f.trim().trim_matches('"').to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_v1_sheet() {
+        let contents = "[Header]\r\n\
+            IEMFileVersion,5\r\n\
+            Experiment Name,RUN42\r\n\
+            \r\n\
+            [Reads]\r\n\
+            151\r\n\
+            151\r\n\
+            \r\n\
+            [Data]\r\n\
+            Lane,Sample_ID,Sample_Name,I7_Index_ID,index,I5_Index_ID,index2,Sample_Project\r\n\
+            1,LIB001,Liver,D701,attactcg,D501,TATAGCCT,PRJ1\r\n\
+            2,LIB002,,D702,TCCGGAGA,D502,ATAGAGGC,PRJ1,,\r\n";
+
+        let sheet = parse_sample_sheet(contents).unwrap();
+        assert_eq!(sheet.header.get("Experiment Name").unwrap(), "RUN42");
+        assert_eq!(sheet.read_length().as_deref(), Some("2x151"));
+        assert_eq!(sheet.rows.len(), 2);
+        assert_eq!(sheet.max_lane(), Some(2));
+
+        let row = &sheet.rows[0];
+        assert_eq!(row.sample_id, "LIB001");
+        assert_eq!(row.index.as_deref(), Some("ATTACTCG"));
+        assert_eq!(row.line, 11);
+        assert!(sheet.rows[1].sample_name.is_none());
+    }
+
+    #[test]
+    fn test_parse_v2_sheet() {
+        let contents = "[Header]\n\
+            FileFormatVersion,2\n\
+            [Reads]\n\
+            Read1Cycles,101\n\
+            Read2Cycles,101\n\
+            Index1Cycles,10\n\
+            [BCLConvert_Data]\n\
+            Sample_ID,Index,Index2\n\
+            LIB003,GAACTGAGCG,TCGTGGAGCG\n";
+
+        let sheet = parse_sample_sheet(contents).unwrap();
+        assert_eq!(sheet.reads, vec![101, 101]);
+        assert_eq!(sheet.rows[0].lane, None);
+        assert_eq!(sheet.rows[0].index2.as_deref(), Some("TCGTGGAGCG"));
+    }
+
+    #[test]
+    fn test_rejects_sheet_without_rows() {
+        assert!(parse_sample_sheet("[Header]\nIEMFileVersion,5\n").is_err());
+        assert!(parse_sample_sheet("[Data]\nLane,Sample_ID\n0,LIB001\n").is_err());
+    }
+}
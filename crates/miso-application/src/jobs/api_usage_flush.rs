@@ -0,0 +1,32 @@
+//! Periodic write-out of sampled API usage counts.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use miso_domain::errors::DomainError;
+
+use super::ScheduledJob;
+use crate::services::ApiUsageService;
+
+/// Job that writes out the API usage counts gathered since it last ran.
+pub struct ApiUsageFlushJob {
+    usage: Arc<ApiUsageService>,
+}
+
+impl ApiUsageFlushJob {
+    /// Creates a new flush job.
+    pub fn new(usage: Arc<ApiUsageService>) -> Self {
+        Self { usage }
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for ApiUsageFlushJob {
+    fn name(&self) -> &str {
+        "api-usage-flush"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.usage.flush().await.map(|_| ())
+    }
+}
@@ -0,0 +1,105 @@
+//! Daily evaluation of the automatic archiving rules.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use tracing::{info, instrument};
+
+use super::ScheduledJob;
+use crate::dto::ArchivePlanResponse;
+use crate::services::ArchiveRuleService;
+
+/// Job that evaluates the active archiving rules and tells lab managers
+/// what was archived and what will be archived once its notice runs out.
+/// Nothing is sent if no rule did anything.
+pub struct AutoArchiveJob {
+    rules: Arc<ArchiveRuleService>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl AutoArchiveJob {
+    /// Creates a new archiving job.
+    pub fn new(rules: Arc<ArchiveRuleService>, notifier: Arc<dyn Notifier>) -> Self {
+        Self { rules, notifier }
+    }
+
+    /// Evaluates the rules and sends the outcome if any rule did anything.
+    #[instrument(skip(self))]
+    pub async fn evaluate(&self) -> Result<Vec<ArchivePlanResponse>, DomainError> {
+        let plans: Vec<ArchivePlanResponse> = self
+            .rules
+            .evaluate_all(Utc::now())
+            .await?
+            .into_iter()
+            .filter(|plan| !plan.is_empty())
+            .collect();
+
+        let archived: usize = plans.iter().map(|p| p.archive.len()).sum();
+        let announced: usize = plans.iter().map(|p| p.announce.len()).sum();
+        info!("{} items archived, {} announced for archiving", archived, announced);
+
+        if !plans.is_empty() {
+            self.notifier
+                .notify(&notification_for(&plans, archived, announced))
+                .await?;
+        }
+
+        Ok(plans)
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for AutoArchiveJob {
+    fn name(&self) -> &str {
+        "auto-archive"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.evaluate().await.map(|_| ())
+    }
+}
+
+/// Builds the lab manager notice for the rules that did something.
+fn notification_for(
+    plans: &[ArchivePlanResponse],
+    archived: usize,
+    announced: usize,
+) -> Notification {
+    let mut body = String::new();
+
+    for plan in plans {
+        let _ = writeln!(body, "{}:\n", plan.rule_name);
+        if !plan.archive.is_empty() {
+            let _ = writeln!(body, "  Archived:");
+            for item in &plan.archive {
+                let _ = writeln!(body, "    {} {}", plan.target, item.name);
+            }
+        }
+        if !plan.announce.is_empty() {
+            let _ = writeln!(body, "  To be archived:");
+            for item in &plan.announce {
+                let _ = writeln!(
+                    body,
+                    "    {} {} on {}",
+                    plan.target,
+                    item.name,
+                    item.due_at.date_naive()
+                );
+            }
+        }
+        body.push('\n');
+    }
+    body.push_str("Archived items can be restored from the rule's archive log.\n");
+
+    Notification::lab_managers(
+        format!(
+            "Auto-archive: {} archived, {} to be archived",
+            archived, announced
+        ),
+        body,
+    )
+}
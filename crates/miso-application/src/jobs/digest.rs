@@ -0,0 +1,371 @@
+//! Scheduled digest summarising recent lab activity.
+//!
+//! The digest is rendered from a template whose `{{name}}` placeholders are
+//! replaced by the sections below, and sent once per subscribed role.
+//! Sections whose repositories haven't been supplied read "Not available".
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use miso_domain::entities::{Role, RunStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use miso_domain::repositories::{
+    QueryOptions, RunRepository, SampleRepository, StorageBoxRepository,
+};
+use tracing::{info, instrument};
+
+use super::ScheduledJob;
+
+/// Placeholders a digest template may use.
+pub const DIGEST_PLACEHOLDERS: &[&str] = &[
+    "date",
+    "since",
+    "runs_completed",
+    "qc_failures",
+    "boxes_nearly_full",
+];
+
+const DEFAULT_SUBJECT: &str = "MISO lab summary for {{date}}";
+
+const DEFAULT_BODY: &str = "\
+Activity since {{since}}.
+
+Runs finished:
+{{runs_completed}}
+
+Samples failing QC:
+{{qc_failures}}
+
+Boxes nearly full:
+{{boxes_nearly_full}}
+";
+
+/// Subject and body templates for a digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestTemplate {
+    subject: String,
+    body: String,
+}
+
+impl DigestTemplate {
+    /// Creates a template, checking that it only uses known placeholders.
+    pub fn new(subject: impl Into<String>, body: impl Into<String>) -> Result<Self, DomainError> {
+        let template = Self {
+            subject: subject.into(),
+            body: body.into(),
+        };
+        for text in [&template.subject, &template.body] {
+            for name in placeholders(text)? {
+                if !DIGEST_PLACEHOLDERS.contains(&name) {
+                    return Err(DomainError::Validation(format!(
+                        "Unknown digest placeholder: {{{{{}}}}}",
+                        name
+                    )));
+                }
+            }
+        }
+        Ok(template)
+    }
+
+    /// Returns the subject template.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the body template.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Renders the subject and body.
+    pub fn render(&self, values: &HashMap<&str, String>) -> (String, String) {
+        (
+            substitute(&self.subject, values),
+            substitute(&self.body, values),
+        )
+    }
+}
+
+impl Default for DigestTemplate {
+    fn default() -> Self {
+        Self {
+            subject: DEFAULT_SUBJECT.to_string(),
+            body: DEFAULT_BODY.to_string(),
+        }
+    }
+}
+
+/// Returns the names of the placeholders in `text`, in order.
+fn placeholders(text: &str) -> Result<Vec<&str>, DomainError> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            DomainError::Validation("Unclosed placeholder in digest template".to_string())
+        })?;
+        names.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+fn substitute(text: &str, values: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        if let Some(value) = values.get(after[..end].trim()) {
+            out.push_str(value);
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Job that sends a summary of the last period's activity to subscribed
+/// roles.
+pub struct DigestJob {
+    template: DigestTemplate,
+    roles: Vec<Role>,
+    period: Duration,
+    box_fill_percent: f64,
+    runs: Option<Arc<dyn RunRepository>>,
+    samples: Option<Arc<dyn SampleRepository>>,
+    boxes: Option<Arc<dyn StorageBoxRepository>>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl DigestJob {
+    /// Creates a daily digest for `roles` with the default template and no
+    /// sections enabled.
+    pub fn new(roles: Vec<Role>, notifier: Arc<dyn Notifier>) -> Self {
+        Self {
+            template: DigestTemplate::default(),
+            roles,
+            period: Duration::days(1),
+            box_fill_percent: 90.0,
+            runs: None,
+            samples: None,
+            boxes: None,
+            notifier,
+        }
+    }
+
+    /// Sets the template.
+    pub fn with_template(mut self, template: DigestTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Sets how far back the digest looks.
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.period = period;
+        self
+    }
+
+    /// Enables the finished runs section.
+    pub fn with_runs(mut self, runs: Arc<dyn RunRepository>) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Enables the QC failures section.
+    pub fn with_samples(mut self, samples: Arc<dyn SampleRepository>) -> Self {
+        self.samples = Some(samples);
+        self
+    }
+
+    /// Enables the nearly full boxes section, listing boxes at or above
+    /// `fill_percent` full.
+    pub fn with_boxes(mut self, boxes: Arc<dyn StorageBoxRepository>, fill_percent: f64) -> Self {
+        self.boxes = Some(boxes);
+        self.box_fill_percent = fill_percent;
+        self
+    }
+
+    /// Renders the digest for the period ending at `now`.
+    #[instrument(skip(self))]
+    pub async fn compose(&self, now: DateTime<Utc>) -> Result<(String, String), DomainError> {
+        let since = now - self.period;
+
+        let mut values = HashMap::new();
+        values.insert("date", now.format("%Y-%m-%d").to_string());
+        values.insert("since", since.format("%Y-%m-%d %H:%M UTC").to_string());
+        values.insert("runs_completed", self.runs_completed(since).await?);
+        values.insert("qc_failures", self.qc_failures(since).await?);
+        values.insert("boxes_nearly_full", self.boxes_nearly_full().await?);
+
+        Ok(self.template.render(&values))
+    }
+
+    /// Renders the digest and sends it to each subscribed role.
+    #[instrument(skip(self))]
+    pub async fn send(&self) -> Result<(), DomainError> {
+        let (subject, body) = self.compose(Utc::now()).await?;
+
+        for role in &self.roles {
+            self.notifier
+                .notify(&Notification::new(*role, subject.clone(), body.clone()))
+                .await?;
+        }
+
+        info!("Sent digest to {} roles", self.roles.len());
+        Ok(())
+    }
+
+    async fn runs_completed(&self, since: DateTime<Utc>) -> Result<String, DomainError> {
+        let Some(runs) = &self.runs else {
+            return Ok(NOT_AVAILABLE.to_string());
+        };
+
+        let mut lines = Vec::new();
+        for status in [RunStatus::Completed, RunStatus::Failed] {
+            for run in runs.find_by_status(status).await? {
+                if let Some(at) = run.completed_at.filter(|at| *at >= since) {
+                    lines.push((at, format!("{} ({})", run.name, status)));
+                }
+            }
+        }
+        lines.sort();
+        Ok(list(lines.into_iter().map(|(_, line)| line)))
+    }
+
+    async fn qc_failures(&self, since: DateTime<Utc>) -> Result<String, DomainError> {
+        let Some(samples) = &self.samples else {
+            return Ok(NOT_AVAILABLE.to_string());
+        };
+
+        let failed = samples.find_qc_failed_since(since).await?;
+        Ok(list(
+            failed
+                .into_iter()
+                .map(|s| format!("{} ({})", s.name, s.barcode)),
+        ))
+    }
+
+    async fn boxes_nearly_full(&self) -> Result<String, DomainError> {
+        let Some(boxes) = &self.boxes else {
+            return Ok(NOT_AVAILABLE.to_string());
+        };
+
+        let mut full: Vec<_> = boxes
+            .list(QueryOptions::new())
+            .await?
+            .into_iter()
+            .filter(|b| b.fill_percent() >= self.box_fill_percent)
+            .collect();
+        full.sort_by(|a, b| b.fill_percent().total_cmp(&a.fill_percent()));
+
+        Ok(list(full.into_iter().map(|b| {
+            let path = b.location.path();
+            format!(
+                "{}{} {}/{} ({:.0}%)",
+                b.name,
+                if path.is_empty() {
+                    String::new()
+                } else {
+                    format!(" in {}", path)
+                },
+                b.item_count(),
+                b.capacity(),
+                b.fill_percent()
+            )
+        })))
+    }
+}
+
+const NOT_AVAILABLE: &str = "  Not available";
+
+/// Formats items as an indented list, or "None".
+fn list(items: impl Iterator<Item = String>) -> String {
+    let mut out = String::new();
+    for item in items {
+        let _ = writeln!(out, "  - {}", item);
+    }
+    if out.is_empty() {
+        "  None".to_string()
+    } else {
+        out.truncate(out.trim_end().len());
+        out
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for DigestJob {
+    fn name(&self) -> &str {
+        "digest"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.send().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Outbox {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Outbox {
+        async fn notify(&self, notification: &Notification) -> Result<(), DomainError> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_template_placeholders() {
+        assert!(DigestTemplate::new("Summary {{date}}", "{{ qc_failures }}").is_ok());
+        assert!(DigestTemplate::new("Summary {{date}}", "{{expiring_kits}}").is_err());
+        assert!(DigestTemplate::new("Summary {{date", "").is_err());
+
+        let template =
+            DigestTemplate::new("Summary {{date}}", "Failed:\n{{ qc_failures }}!").unwrap();
+        let values = HashMap::from([
+            ("date", "2024-12-15".to_string()),
+            ("qc_failures", "  None".to_string()),
+        ]);
+        assert_eq!(
+            template.render(&values),
+            (
+                "Summary 2024-12-15".to_string(),
+                "Failed:\n  None!".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_digest_sent_to_each_role() {
+        let outbox = Arc::new(Outbox::default());
+        let job = DigestJob::new(vec![Role::LabManager, Role::Technician], outbox.clone());
+
+        let now = Utc.with_ymd_and_hms(2024, 12, 15, 7, 0, 0).unwrap();
+        let (subject, body) = job.compose(now).await.unwrap();
+        assert_eq!(subject, "MISO lab summary for 2024-12-15");
+        assert!(body.starts_with("Activity since 2024-12-14 07:00 UTC."));
+        assert!(body.contains("Samples failing QC:\n  Not available\n"));
+
+        job.run().await.unwrap();
+        let sent = outbox.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].audience, Role::LabManager);
+        assert_eq!(sent[1].audience, Role::Technician);
+    }
+}
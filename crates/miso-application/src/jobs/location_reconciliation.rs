@@ -0,0 +1,177 @@
+//! Nightly reconciliation of box contents against item locations.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use miso_domain::entities::{
+    DiscrepancyKind, LocationDiscrepancy, ReconciliationReport, StorableType,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use miso_domain::repositories::{
+    ItemLocationRepository, QueryOptions, ReconciliationReportRepository, StorageBoxRepository,
+};
+use miso_domain::services::LocationReconciler;
+use tracing::{info, instrument};
+
+use super::ScheduledJob;
+
+/// Maximum number of discrepancies listed individually in a notification.
+const MAX_LISTED_DISCREPANCIES: usize = 20;
+
+/// Job that cross-checks every box against the locations items record,
+/// stores a discrepancy report and alerts lab managers to any drift.
+pub struct LocationReconciliationJob<B, L, R>
+where
+    B: StorageBoxRepository + ?Sized,
+    L: ItemLocationRepository + ?Sized,
+    R: ReconciliationReportRepository + ?Sized,
+{
+    boxes: Arc<B>,
+    locations: Arc<L>,
+    reports: Arc<R>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl<B, L, R> LocationReconciliationJob<B, L, R>
+where
+    B: StorageBoxRepository + ?Sized,
+    L: ItemLocationRepository + ?Sized,
+    R: ReconciliationReportRepository + ?Sized,
+{
+    /// Creates a new reconciliation job.
+    pub fn new(
+        boxes: Arc<B>,
+        locations: Arc<L>,
+        reports: Arc<R>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
+        Self {
+            boxes,
+            locations,
+            reports,
+            notifier,
+        }
+    }
+
+    /// Runs one reconciliation pass and returns the saved report.
+    #[instrument(skip(self))]
+    pub async fn reconcile(&self) -> Result<ReconciliationReport, DomainError> {
+        let started_at = Utc::now();
+
+        let boxes = self.boxes.list(QueryOptions::new()).await?;
+
+        let mut recorded = Vec::new();
+        for item_type in [
+            StorableType::Sample,
+            StorableType::Library,
+            StorableType::LibraryAliquot,
+            StorableType::Pool,
+        ] {
+            recorded.extend(self.locations.list_recorded(item_type).await?);
+        }
+
+        let mut report = ReconciliationReport {
+            id: 0,
+            boxes_checked: boxes.len() as u32,
+            items_checked: recorded.len() as u32,
+            discrepancies: LocationReconciler::reconcile(&boxes, &recorded),
+            started_at,
+            completed_at: Utc::now(),
+        };
+        report.id = self.reports.save(&report).await?;
+
+        info!(
+            "Reconciled {} boxes against {} item locations: {} discrepancies (report ID: {})",
+            report.boxes_checked,
+            report.items_checked,
+            report.discrepancies.len(),
+            report.id
+        );
+
+        if !report.is_clean() {
+            self.notifier.notify(&notification_for(&report)).await?;
+        }
+
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl<B, L, R> ScheduledJob for LocationReconciliationJob<B, L, R>
+where
+    B: StorageBoxRepository + ?Sized,
+    L: ItemLocationRepository + ?Sized,
+    R: ReconciliationReportRepository + ?Sized,
+{
+    fn name(&self) -> &str {
+        "location-reconciliation"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.reconcile().await.map(|_| ())
+    }
+}
+
+/// Builds the lab manager alert for a report with discrepancies.
+fn notification_for(report: &ReconciliationReport) -> Notification {
+    let mut body = format!(
+        "Reconciliation report {} found {} discrepancies across {} boxes.\n\n",
+        report.id,
+        report.discrepancies.len(),
+        report.boxes_checked
+    );
+
+    for kind in [
+        DiscrepancyKind::MissingFromBox,
+        DiscrepancyKind::UnrecordedLocation,
+        DiscrepancyKind::WrongBox,
+        DiscrepancyKind::WrongPosition,
+        DiscrepancyKind::MultiplePlacements,
+        DiscrepancyKind::UnknownItem,
+    ] {
+        let count = report.count_of(kind);
+        if count > 0 {
+            let _ = writeln!(body, "  {}: {}", kind, count);
+        }
+    }
+
+    body.push('\n');
+    for discrepancy in report.discrepancies.iter().take(MAX_LISTED_DISCREPANCIES) {
+        let _ = writeln!(body, "  {}", describe(discrepancy));
+    }
+    if report.discrepancies.len() > MAX_LISTED_DISCREPANCIES {
+        let _ = writeln!(
+            body,
+            "  ... and {} more",
+            report.discrepancies.len() - MAX_LISTED_DISCREPANCIES
+        );
+    }
+
+    Notification::lab_managers(
+        format!(
+            "Storage reconciliation: {} discrepancies",
+            report.discrepancies.len()
+        ),
+        body,
+    )
+}
+
+fn describe(d: &LocationDiscrepancy) -> String {
+    let place = |box_id: Option<i32>, position: &Option<String>| match (box_id, position) {
+        (Some(b), Some(p)) => format!("box {} {}", b, p),
+        (Some(b), None) => format!("box {}", b),
+        _ => "nowhere".to_string(),
+    };
+
+    format!(
+        "{} {} ({}): found in {}, recorded in {}",
+        d.item.item_type,
+        d.item.item_id,
+        d.kind,
+        place(d.box_id, &d.position),
+        place(d.recorded_box_id, &d.recorded_position)
+    )
+}
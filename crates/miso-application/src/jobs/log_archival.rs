@@ -0,0 +1,34 @@
+//! Nightly archival of log rows past their retention period.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::LogArchiveRepository;
+
+use super::ScheduledJob;
+use crate::services::RetentionService;
+
+/// Job that moves audit log, change log and storage audit rows past their
+/// retention period into compressed archive files.
+pub struct LogArchivalJob<A: LogArchiveRepository + ?Sized> {
+    retention: Arc<RetentionService<A>>,
+}
+
+impl<A: LogArchiveRepository + ?Sized> LogArchivalJob<A> {
+    /// Creates a new archival job.
+    pub fn new(retention: Arc<RetentionService<A>>) -> Self {
+        Self { retention }
+    }
+}
+
+#[async_trait]
+impl<A: LogArchiveRepository + ?Sized> ScheduledJob for LogArchivalJob<A> {
+    fn name(&self) -> &str {
+        "log-archival"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.retention.archive_expired().await.map(|_| ())
+    }
+}
@@ -0,0 +1,27 @@
+//! Background jobs run on a schedule.
+//!
+//! Jobs implement [`ScheduledJob`] and are registered with a [`Scheduler`]
+//! together with a [`Schedule`]. The scheduler runs each job on its own
+//! task; a failed run is logged and the job runs again at its next slot.
+
+mod api_usage_flush;
+mod auto_archive;
+mod digest;
+mod location_reconciliation;
+mod log_archival;
+mod reagent_alerts;
+mod run_import;
+mod scheduler;
+mod shipment_tracking;
+mod stale_runs;
+
+pub use api_usage_flush::ApiUsageFlushJob;
+pub use auto_archive::AutoArchiveJob;
+pub use digest::{DigestJob, DigestTemplate, DIGEST_PLACEHOLDERS};
+pub use location_reconciliation::LocationReconciliationJob;
+pub use log_archival::LogArchivalJob;
+pub use reagent_alerts::ReagentAlertJob;
+pub use run_import::{RunImportJob, RunImportSummary};
+pub use scheduler::{Schedule, ScheduledJob, Scheduler};
+pub use shipment_tracking::ShipmentTrackingJob;
+pub use stale_runs::StaleRunJob;
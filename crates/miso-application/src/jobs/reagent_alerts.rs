@@ -0,0 +1,121 @@
+//! Daily alert for reagent lots expiring soon and kits running low.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use miso_domain::repositories::ReagentLotRepository;
+use tracing::{info, instrument};
+
+use super::ScheduledJob;
+use crate::dto::ReagentAlertReport;
+use crate::services::ReagentInventoryService;
+
+/// Job that tells lab managers which reagent lots have expired or expire
+/// soon and which kits should be reordered. Nothing is sent if there is
+/// nothing to act on.
+pub struct ReagentAlertJob<R: ReagentLotRepository + ?Sized> {
+    inventory: Arc<ReagentInventoryService<R>>,
+    notifier: Arc<dyn Notifier>,
+    days: u32,
+}
+
+impl<R: ReagentLotRepository + ?Sized> ReagentAlertJob<R> {
+    /// Creates a new alert job warning of lots expiring within `days`.
+    pub fn new(
+        inventory: Arc<ReagentInventoryService<R>>,
+        notifier: Arc<dyn Notifier>,
+        days: u32,
+    ) -> Self {
+        Self {
+            inventory,
+            notifier,
+            days,
+        }
+    }
+
+    /// Builds today's report and sends it if it lists anything.
+    #[instrument(skip(self))]
+    pub async fn check(&self) -> Result<ReagentAlertReport, DomainError> {
+        let report = self
+            .inventory
+            .alerts(self.days, Utc::now().date_naive())
+            .await?;
+
+        info!(
+            "{} reagent lots expiring, {} kits low on stock",
+            report.expiring.len(),
+            report.low_stock.len()
+        );
+
+        if !report.is_empty() {
+            self.notifier.notify(&notification_for(&report)).await?;
+        }
+
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl<R: ReagentLotRepository + ?Sized> ScheduledJob for ReagentAlertJob<R> {
+    fn name(&self) -> &str {
+        "reagent-alerts"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.check().await.map(|_| ())
+    }
+}
+
+/// Builds the lab manager alert for a non-empty report.
+fn notification_for(report: &ReagentAlertReport) -> Notification {
+    let mut body = String::new();
+
+    if !report.expiring.is_empty() {
+        let _ = writeln!(
+            body,
+            "Lots expired or expiring in the next {} days:\n",
+            report.days
+        );
+        for expiring in &report.expiring {
+            let when = if expiring.expired {
+                "expired"
+            } else {
+                "expires"
+            };
+            let _ = writeln!(
+                body,
+                "  {} lot {}: {} {}, {} units left",
+                expiring.lot.kit,
+                expiring.lot.lot_number,
+                when,
+                expiring.lot.expires_on,
+                expiring.lot.quantity
+            );
+        }
+        body.push('\n');
+    }
+
+    if !report.low_stock.is_empty() {
+        body.push_str("Kits to reorder:\n\n");
+        for low in &report.low_stock {
+            let _ = writeln!(
+                body,
+                "  {}: {} units left (reorder at {})",
+                low.kit, low.on_hand, low.reorder_level
+            );
+        }
+    }
+
+    Notification::lab_managers(
+        format!(
+            "Reagents: {} lots expiring, {} kits to reorder",
+            report.expiring.len(),
+            report.low_stock.len()
+        ),
+        body,
+    )
+}
@@ -0,0 +1,157 @@
+//! Periodic import of finished runs from an instrument cloud service.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{Run, Sequencer};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{RunRepository, SequencerRepository};
+use miso_domain::run_import::{ExternalRun, RunSource};
+use tracing::{info, instrument, warn};
+
+use super::ScheduledJob;
+
+/// What one import pass did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunImportSummary {
+    /// Runs recorded for the first time
+    pub created: usize,
+    /// Runs already recorded that were completed or given metrics
+    pub updated: usize,
+    /// Runs left as they were, or that couldn't be matched to a sequencer
+    pub skipped: usize,
+}
+
+/// Job that records runs finished on an instrument cloud service, with
+/// their lane metrics.
+///
+/// Runs are matched to MISO runs by name, and to sequencers by serial
+/// number or else by name; runs on sequencers MISO doesn't know are
+/// skipped. Each pass asks only for runs finished since the last
+/// successful one started.
+pub struct RunImportJob<R, S>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+{
+    runs: Arc<R>,
+    sequencers: Arc<S>,
+    source: Arc<dyn RunSource>,
+    last_import: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl<R, S> RunImportJob<R, S>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+{
+    /// Creates a new run import.
+    pub fn new(runs: Arc<R>, sequencers: Arc<S>, source: Arc<dyn RunSource>) -> Self {
+        Self {
+            runs,
+            sequencers,
+            source,
+            last_import: Mutex::new(None),
+        }
+    }
+
+    /// Imports runs finished since the last successful pass.
+    #[instrument(skip(self), fields(source = self.source.name()))]
+    pub async fn import(&self) -> Result<RunImportSummary, DomainError> {
+        let started = Utc::now();
+        let since = *self.last_import.lock().unwrap_or_else(|e| e.into_inner());
+
+        let external = self.source.finished_runs(since).await?;
+        let sequencers = self.sequencers.list().await?;
+        let mut summary = RunImportSummary::default();
+
+        for external in external {
+            let Some(sequencer) = find_sequencer(&sequencers, &external) else {
+                warn!(
+                    "Run {} is on an unknown sequencer ({})",
+                    external.name,
+                    external
+                        .instrument_serial
+                        .as_deref()
+                        .or(external.instrument_name.as_deref())
+                        .unwrap_or("unnamed")
+                );
+                summary.skipped += 1;
+                continue;
+            };
+
+            let existing = self.runs.find_by_name(&external.name).await?;
+            let created = existing.is_none();
+            let mut run = existing.unwrap_or_else(|| {
+                Run::new(
+                    0,
+                    external.name.clone(),
+                    sequencer.id,
+                    sequencer.num_partitions(),
+                    self.source.name().to_string(),
+                )
+            });
+            if run.sequencer_id != sequencer.id {
+                warn!(
+                    "Run {} is recorded on another sequencer than {}",
+                    run.name, sequencer.name
+                );
+                summary.skipped += 1;
+                continue;
+            }
+
+            if !run.record_external(&external) && !created {
+                summary.skipped += 1;
+                continue;
+            }
+            self.runs.save(&run).await?;
+            if created {
+                summary.created += 1;
+            } else {
+                summary.updated += 1;
+            }
+        }
+
+        *self.last_import.lock().unwrap_or_else(|e| e.into_inner()) = Some(started);
+
+        info!(
+            "Imported runs: {} created, {} updated, {} skipped",
+            summary.created, summary.updated, summary.skipped
+        );
+
+        Ok(summary)
+    }
+}
+
+#[async_trait]
+impl<R, S> ScheduledJob for RunImportJob<R, S>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+{
+    fn name(&self) -> &str {
+        "run-import"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.import().await.map(|_| ())
+    }
+}
+
+/// Finds the sequencer an external run ran on, by serial number or else by
+/// name.
+fn find_sequencer<'a>(sequencers: &'a [Sequencer], run: &ExternalRun) -> Option<&'a Sequencer> {
+    run.instrument_serial
+        .as_deref()
+        .and_then(|serial| {
+            sequencers
+                .iter()
+                .find(|s| s.serial_number.as_deref() == Some(serial))
+        })
+        .or_else(|| {
+            run.instrument_name
+                .as_deref()
+                .and_then(|name| sequencers.iter().find(|s| s.name == name))
+        })
+}
@@ -0,0 +1,127 @@
+//! Minimal in-process job scheduler.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveTime, Utc};
+use miso_domain::errors::DomainError;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// A unit of background work.
+#[async_trait]
+pub trait ScheduledJob: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &str;
+
+    /// Runs the job once.
+    async fn run(&self) -> Result<(), DomainError>;
+}
+
+/// When a job should run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Once a day at the given UTC time
+    Daily(NaiveTime),
+    /// Repeatedly, with the given gap between the start of each run
+    Every(Duration),
+}
+
+impl Schedule {
+    /// Once a day at the given UTC hour and minute.
+    pub fn daily_at(hour: u32, minute: u32) -> Result<Self, DomainError> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+            .map(Self::Daily)
+            .ok_or_else(|| {
+                DomainError::Validation(format!("Invalid time of day: {:02}:{:02}", hour, minute))
+            })
+    }
+
+    /// Returns the next time the job is due strictly after `now`.
+    pub fn next_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Daily(time) => {
+                let today = now.date_naive().and_time(*time).and_utc();
+                if today > now {
+                    today
+                } else {
+                    today + chrono::Duration::days(1)
+                }
+            }
+            Self::Every(interval) => {
+                now + chrono::Duration::from_std(*interval).unwrap_or(chrono::Duration::MAX)
+            }
+        }
+    }
+}
+
+/// Runs registered jobs on their schedules.
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<(Schedule, Arc<dyn ScheduledJob>)>,
+}
+
+impl Scheduler {
+    /// Creates an empty scheduler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a job.
+    pub fn register(mut self, schedule: Schedule, job: Arc<dyn ScheduledJob>) -> Self {
+        self.jobs.push((schedule, job));
+        self
+    }
+
+    /// Returns true if no jobs are registered.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Spawns a task per job. Tasks run until aborted.
+    pub fn start(self) -> Vec<JoinHandle<()>> {
+        self.jobs
+            .into_iter()
+            .map(|(schedule, job)| {
+                tokio::spawn(async move {
+                    loop {
+                        let now = Utc::now();
+                        let next = schedule.next_after(now);
+                        info!("Job {} next runs at {}", job.name(), next);
+                        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+                        if let Err(e) = job.run().await {
+                            error!("Job {} failed: {}", job.name(), e);
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_daily_run() {
+        let schedule = Schedule::daily_at(2, 30).unwrap();
+
+        let before = Utc.with_ymd_and_hms(2024, 12, 15, 1, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(before),
+            Utc.with_ymd_and_hms(2024, 12, 15, 2, 30, 0).unwrap()
+        );
+
+        let at = Utc.with_ymd_and_hms(2024, 12, 15, 2, 30, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(at),
+            Utc.with_ymd_and_hms(2024, 12, 16, 2, 30, 0).unwrap()
+        );
+
+        assert!(Schedule::daily_at(24, 0).is_err());
+    }
+}
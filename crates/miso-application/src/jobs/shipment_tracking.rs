@@ -0,0 +1,58 @@
+//! Periodic check on shipments in transit with their courier.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{LibraryRepository, SampleRepository, TransferRepository};
+use miso_domain::shipments::ShipmentTracker;
+
+use super::ScheduledJob;
+use crate::services::TransferService;
+
+/// Job that asks a tracking service where every tracked transfer not yet
+/// delivered has got to, telling the recipient once it is out for
+/// delivery.
+pub struct ShipmentTrackingJob<T, S, L>
+where
+    T: TransferRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    transfers: Arc<TransferService<T, S, L>>,
+    tracker: Arc<dyn ShipmentTracker>,
+}
+
+impl<T, S, L> ShipmentTrackingJob<T, S, L>
+where
+    T: TransferRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    /// Creates a new tracking job.
+    pub fn new(
+        transfers: Arc<TransferService<T, S, L>>,
+        tracker: Arc<dyn ShipmentTracker>,
+    ) -> Self {
+        Self { transfers, tracker }
+    }
+}
+
+#[async_trait]
+impl<T, S, L> ScheduledJob for ShipmentTrackingJob<T, S, L>
+where
+    T: TransferRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    fn name(&self) -> &str {
+        "shipment-tracking"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.transfers
+            .poll_tracking(self.tracker.as_ref())
+            .await
+            .map(|_| ())
+    }
+}
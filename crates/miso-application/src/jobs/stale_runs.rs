@@ -0,0 +1,153 @@
+//! Periodic check for runs sequencing longer than their instrument allows.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use miso_domain::entities::{EntityId, Run, RunStatus, Sequencer};
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use miso_domain::repositories::{InstrumentModelRepository, RunRepository, SequencerRepository};
+use tracing::{info, instrument, warn};
+
+use super::ScheduledJob;
+
+/// Job that flags runs still Running past their instrument model's expected
+/// duration and alerts lab managers to newly overdue runs.
+///
+/// Expected durations are read from the instrument model catalog, falling
+/// back to the sequencer's own copy of its model. Runs on models with no
+/// expected duration are never flagged.
+pub struct StaleRunJob<R, S, M>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    M: InstrumentModelRepository + ?Sized,
+{
+    runs: Arc<R>,
+    sequencers: Arc<S>,
+    models: Arc<M>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl<R, S, M> StaleRunJob<R, S, M>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    M: InstrumentModelRepository + ?Sized,
+{
+    /// Creates a new stale run check.
+    pub fn new(
+        runs: Arc<R>,
+        sequencers: Arc<S>,
+        models: Arc<M>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
+        Self {
+            runs,
+            sequencers,
+            models,
+            notifier,
+        }
+    }
+
+    /// Flags overdue runs and returns those flagged by this pass.
+    #[instrument(skip(self))]
+    pub async fn check(&self) -> Result<Vec<Run>, DomainError> {
+        let now = Utc::now();
+
+        let expected: HashMap<String, Duration> = self
+            .models
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|m| m.expected_run_duration().map(|d| (m.name, d)))
+            .collect();
+
+        let mut sequencers: HashMap<EntityId, Option<Sequencer>> = HashMap::new();
+        let mut flagged = Vec::new();
+
+        for mut run in self.runs.find_by_status(RunStatus::Running).await? {
+            if run.is_overdue() {
+                continue;
+            }
+
+            let sequencer = match sequencers.get(&run.sequencer_id) {
+                Some(sequencer) => sequencer.clone(),
+                None => {
+                    let sequencer = self.sequencers.find_by_id(run.sequencer_id).await?;
+                    sequencers.insert(run.sequencer_id, sequencer.clone());
+                    sequencer
+                }
+            };
+            let Some(sequencer) = sequencer else {
+                warn!(
+                    "Run {} is on unknown sequencer {}",
+                    run.name, run.sequencer_id
+                );
+                continue;
+            };
+
+            let Some(limit) = expected
+                .get(&sequencer.model.name)
+                .copied()
+                .or_else(|| sequencer.model.expected_run_duration())
+            else {
+                continue;
+            };
+
+            if run.exceeds_duration(limit, now) && run.flag_overdue(now) {
+                self.runs.save(&run).await?;
+                flagged.push((run, sequencer));
+            }
+        }
+
+        info!("Flagged {} overdue runs", flagged.len());
+
+        if !flagged.is_empty() {
+            self.notifier.notify(&notification_for(&flagged)).await?;
+        }
+
+        Ok(flagged.into_iter().map(|(run, _)| run).collect())
+    }
+}
+
+#[async_trait]
+impl<R, S, M> ScheduledJob for StaleRunJob<R, S, M>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    M: InstrumentModelRepository + ?Sized,
+{
+    fn name(&self) -> &str {
+        "stale-runs"
+    }
+
+    async fn run(&self) -> Result<(), DomainError> {
+        self.check().await.map(|_| ())
+    }
+}
+
+/// Builds the lab manager alert for newly overdue runs.
+fn notification_for(flagged: &[(Run, Sequencer)]) -> Notification {
+    let mut body = format!(
+        "{} runs have been sequencing longer than expected:\n\n",
+        flagged.len()
+    );
+
+    for (run, sequencer) in flagged {
+        let started = run
+            .started_at
+            .map(|s| s.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        let _ = writeln!(
+            body,
+            "  {} on {} ({}), started {}",
+            run.name, sequencer.name, sequencer.model.name, started
+        );
+    }
+
+    Notification::lab_managers(format!("Overdue runs: {}", flagged.len()), body)
+}
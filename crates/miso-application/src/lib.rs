@@ -8,11 +8,30 @@
 //! - **Use Cases**: Specific operations (e.g., CreateSample, PoolLibraries)
 //! - **DTOs**: Data Transfer Objects for API boundaries
 //! - **Services**: Application services that coordinate complex workflows
+//! - **Importers**: Parsers for files produced by external tools
+//! - **Exporters**: Renderers for files consumed by external tools
+//! - **Jobs**: Background work run on a schedule
+//! - **Plugins**: Site-specific hooks registered at startup
+//! - **Audit**: The record of every change to lab records
+//! - **Access**: Keeping project data to the projects' members
+//! - **Locks**: Freezing a project's data for review
+//! - **Redaction**: Holding back what participants didn't consent to share
 
+pub mod access;
+pub mod audit;
 pub mod dto;
+pub mod exporters;
+pub mod importers;
+pub mod jobs;
+pub mod locks;
+pub mod plugins;
+pub mod redaction;
 pub mod services;
 pub mod use_cases;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-export commonly used types
 pub use dto::*;
 pub use services::*;
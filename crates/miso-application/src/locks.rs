@@ -0,0 +1,41 @@
+//! Project locks.
+//!
+//! A [`ProjectLocks`] guard is handed to the services that change the
+//! samples and libraries of a project, and refuses changes to a locked
+//! project by anyone but an admin.
+//!
+//! A guard without projects checks nothing, so services use one by default.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ProjectRepository;
+
+/// Refuses changes to the data of locked projects.
+#[derive(Clone, Default)]
+pub struct ProjectLocks {
+    projects: Option<Arc<dyn ProjectRepository>>,
+}
+
+impl ProjectLocks {
+    /// Creates a guard checking the given projects' locks.
+    pub fn new(projects: Arc<dyn ProjectRepository>) -> Self {
+        Self {
+            projects: Some(projects),
+        }
+    }
+
+    /// Checks that someone with `role` may change a project's data.
+    ///
+    /// Projects that don't exist aren't locked; the caller reports them.
+    pub async fn check(&self, project_id: EntityId, role: Role) -> Result<(), DomainError> {
+        let Some(projects) = &self.projects else {
+            return Ok(());
+        };
+        match projects.find_by_id(project_id).await? {
+            Some(project) => project.check_unlocked(role),
+            None => Ok(()),
+        }
+    }
+}
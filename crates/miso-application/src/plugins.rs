@@ -0,0 +1,260 @@
+//! Site plugins.
+//!
+//! A [`PluginRegistry`] collects the validation hooks, naming hooks and
+//! event subscribers a site registers at startup, and is handed to the
+//! services that create and update entities. Sites usually bundle their
+//! hooks in a [`Plugin`]:
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use async_trait::async_trait;
+//! use miso_application::plugins::{Plugin, PluginRegistry};
+//! use miso_domain::entities::Sample;
+//! use miso_domain::errors::DomainError;
+//! use miso_domain::plugins::{Operation, ValidationHook};
+//!
+//! struct DescriptionRequired;
+//!
+//! #[async_trait]
+//! impl ValidationHook for DescriptionRequired {
+//!     async fn validate_sample(&self, _: Operation, sample: &Sample) -> Result<(), DomainError> {
+//!         if sample.description.is_none() {
+//!             return Err(DomainError::Validation("Description is required".into()));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! struct SiteRules;
+//!
+//! impl Plugin for SiteRules {
+//!     fn name(&self) -> &str {
+//!         "site-rules"
+//!     }
+//!
+//!     fn register(&self, registry: &mut PluginRegistry) {
+//!         registry.add_validator(Arc::new(DescriptionRequired));
+//!     }
+//! }
+//!
+//! let plugins = PluginRegistry::new().install(&SiteRules);
+//! assert_eq!(plugins.installed(), ["site-rules"]);
+//! ```
+
+use std::sync::Arc;
+
+use miso_domain::entities::{Library, Project, Sample};
+use miso_domain::errors::DomainError;
+use miso_domain::plugins::{DomainEvent, EventSubscriber, NamingHook, Operation, ValidationHook};
+use tracing::warn;
+
+/// A bundle of hooks that registers itself with a [`PluginRegistry`].
+pub trait Plugin {
+    /// Name logged at startup.
+    fn name(&self) -> &str;
+
+    /// Adds the plugin's hooks to `registry`.
+    fn register(&self, registry: &mut PluginRegistry);
+}
+
+/// The hooks registered by site plugins.
+///
+/// An empty registry changes nothing, so services use one by default.
+#[derive(Default, Clone)]
+pub struct PluginRegistry {
+    installed: Vec<String>,
+    validators: Vec<Arc<dyn ValidationHook>>,
+    naming: Vec<Arc<dyn NamingHook>>,
+    subscribers: Vec<Arc<dyn EventSubscriber>>,
+}
+
+impl PluginRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs a plugin's hooks.
+    pub fn install(mut self, plugin: &dyn Plugin) -> Self {
+        plugin.register(&mut self);
+        self.installed.push(plugin.name().to_string());
+        self
+    }
+
+    /// Names of the installed plugins, in installation order.
+    pub fn installed(&self) -> &[String] {
+        &self.installed
+    }
+
+    /// Adds a validation hook. Every hook must accept an entity for it to
+    /// be saved.
+    pub fn add_validator(&mut self, hook: Arc<dyn ValidationHook>) {
+        self.validators.push(hook);
+    }
+
+    /// Adds a naming hook. The first hook to return a name wins.
+    pub fn add_naming(&mut self, hook: Arc<dyn NamingHook>) {
+        self.naming.push(hook);
+    }
+
+    /// Adds an event subscriber.
+    pub fn add_subscriber(&mut self, subscriber: Arc<dyn EventSubscriber>) {
+        self.subscribers.push(subscriber);
+    }
+
+    /// Runs the validation hooks on a project.
+    pub async fn validate_project(
+        &self,
+        operation: Operation,
+        project: &Project,
+    ) -> Result<(), DomainError> {
+        for hook in &self.validators {
+            hook.validate_project(operation, project).await?;
+        }
+        Ok(())
+    }
+
+    /// Runs the validation hooks on a sample.
+    pub async fn validate_sample(
+        &self,
+        operation: Operation,
+        sample: &Sample,
+    ) -> Result<(), DomainError> {
+        for hook in &self.validators {
+            hook.validate_sample(operation, sample).await?;
+        }
+        Ok(())
+    }
+
+    /// Runs the validation hooks on a library.
+    pub async fn validate_library(
+        &self,
+        operation: Operation,
+        library: &Library,
+    ) -> Result<(), DomainError> {
+        for hook in &self.validators {
+            hook.validate_library(operation, library).await?;
+        }
+        Ok(())
+    }
+
+    /// The site's name for a new sample, if a naming hook gives one.
+    pub async fn sample_name(&self, sample: &Sample) -> Result<Option<String>, DomainError> {
+        for hook in &self.naming {
+            if let Some(name) = hook.sample_name(sample).await? {
+                return Ok(Some(name));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The site's name for a new library, if a naming hook gives one.
+    pub async fn library_name(
+        &self,
+        library: &Library,
+        sample: &Sample,
+    ) -> Result<Option<String>, DomainError> {
+        for hook in &self.naming {
+            if let Some(name) = hook.library_name(library, sample).await? {
+                return Ok(Some(name));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Delivers an event to every subscriber.
+    ///
+    /// The change has already been saved, so subscriber errors are logged
+    /// rather than returned.
+    pub async fn publish(&self, event: DomainEvent) {
+        for subscriber in &self.subscribers {
+            if let Err(e) = subscriber.on_event(&event).await {
+                warn!("Event subscriber failed on {}: {}", event.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::value_objects::Barcode;
+
+    use super::*;
+
+    struct Prefix(&'static str);
+
+    #[async_trait]
+    impl NamingHook for Prefix {
+        async fn sample_name(&self, sample: &Sample) -> Result<Option<String>, DomainError> {
+            Ok(Some(format!("{}_{}", self.0, sample.name)))
+        }
+    }
+
+    struct KeepName;
+
+    #[async_trait]
+    impl NamingHook for KeepName {}
+
+    struct Failing;
+
+    #[async_trait]
+    impl EventSubscriber for Failing {
+        async fn on_event(&self, _event: &DomainEvent) -> Result<(), DomainError> {
+            Err(DomainError::Validation("unreachable".to_string()))
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<&'static str>>);
+
+    #[async_trait]
+    impl EventSubscriber for Recorder {
+        async fn on_event(&self, event: &DomainEvent) -> Result<(), DomainError> {
+            self.0.lock().unwrap().push(event.name());
+            Ok(())
+        }
+    }
+
+    fn sample() -> Sample {
+        Sample::new_plain(
+            1,
+            "S1".to_string(),
+            Barcode::new("SAM1").unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_first_naming_hook_with_a_name_wins() {
+        let mut registry = PluginRegistry::new();
+        registry.add_naming(Arc::new(KeepName));
+        registry.add_naming(Arc::new(Prefix("A")));
+        registry.add_naming(Arc::new(Prefix("B")));
+
+        let name = registry.sample_name(&sample()).await.unwrap();
+
+        assert_eq!(name.as_deref(), Some("A_S1"));
+        assert_eq!(
+            PluginRegistry::new().sample_name(&sample()).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_subscriber_does_not_stop_others() {
+        let recorder = Arc::new(Recorder::default());
+        let mut registry = PluginRegistry::new();
+        registry.add_subscriber(Arc::new(Failing));
+        registry.add_subscriber(recorder.clone());
+
+        registry.publish(DomainEvent::SampleDeleted(1)).await;
+
+        assert_eq!(*recorder.0.lock().unwrap(), ["sample_deleted"]);
+    }
+}
@@ -0,0 +1,409 @@
+//! Consent-driven redaction.
+//!
+//! A [`Redaction`] is handed to the services that send samples out of the
+//! LIMS, and restricts each sample to what the consent recorded for its
+//! identity allows: all of it, it without identifying details, or nothing.
+//! A sample's identity is found by walking up its parents. A sample pooled
+//! from several identities may be shared only as far as every one of them
+//! consented to.
+//!
+//! A redaction without consents restricts nothing, so services use one by
+//! default.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use miso_domain::entities::{Consent, EntityId, Sample, SampleClass};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    ConsentRepository, SampleCompositionRepository, SampleRepository,
+};
+
+/// Restricts outgoing samples to what their participants consented to.
+#[derive(Clone, Default)]
+pub struct Redaction {
+    sources: Option<(Arc<dyn ConsentRepository>, Arc<dyn SampleRepository>)>,
+    compositions: Option<Arc<dyn SampleCompositionRepository>>,
+}
+
+impl Redaction {
+    /// Creates a redaction driven by the given consents, finding samples'
+    /// identities through the given sample repository.
+    pub fn new(consents: Arc<dyn ConsentRepository>, samples: Arc<dyn SampleRepository>) -> Self {
+        Self {
+            sources: Some((consents, samples)),
+            compositions: None,
+        }
+    }
+
+    /// Finds the identities of pooled samples through the given
+    /// compositions.
+    ///
+    /// Without them, samples descended from a pooled sample have no
+    /// identity and are shared in full.
+    pub fn with_compositions(mut self, compositions: Arc<dyn SampleCompositionRepository>) -> Self {
+        self.compositions = Some(compositions);
+        self
+    }
+
+    /// Restricts samples to what may be shared, dropping those that may not
+    /// be shared at all. The order of the rest is kept.
+    pub async fn samples(&self, samples: Vec<Sample>) -> Result<Vec<Sample>, DomainError> {
+        let Some((consents, repository)) = &self.sources else {
+            return Ok(samples);
+        };
+
+        // Each sample's parent, by ID, until every ancestor is known
+        let mut parents: HashMap<EntityId, Option<EntityId>> =
+            samples.iter().map(|s| (s.id, s.parent_id())).collect();
+        let mut identities: HashSet<EntityId> = samples
+            .iter()
+            .filter(|s| s.sample_class() == SampleClass::Identity)
+            .map(|s| s.id)
+            .collect();
+        let mut pooled: Vec<EntityId> = samples
+            .iter()
+            .filter(|s| s.is_pooled())
+            .map(|s| s.id)
+            .collect();
+        loop {
+            let mut missing: Vec<EntityId> = parents
+                .values()
+                .flatten()
+                .filter(|id| !parents.contains_key(id))
+                .copied()
+                .collect();
+            if missing.is_empty() {
+                break;
+            }
+            missing.sort_unstable();
+            missing.dedup();
+
+            // A parent that no longer exists ends the walk
+            for id in &missing {
+                parents.insert(*id, None);
+            }
+            for ancestor in repository.find_by_ids(&missing).await? {
+                if ancestor.sample_class() == SampleClass::Identity {
+                    identities.insert(ancestor.id);
+                }
+                if ancestor.is_pooled() {
+                    pooled.push(ancestor.id);
+                }
+                parents.insert(ancestor.id, ancestor.parent_id());
+            }
+        }
+
+        // The identities each pooled sample was made from
+        let components: HashMap<EntityId, Vec<EntityId>> = match &self.compositions {
+            Some(compositions) if !pooled.is_empty() => compositions
+                .find_by_samples(&pooled)
+                .await?
+                .into_iter()
+                .map(|c| (c.sample_id, c.identity_ids))
+                .collect(),
+            _ => HashMap::new(),
+        };
+
+        let identities_of = |id: EntityId| -> Vec<EntityId> {
+            let mut root = id;
+            for _ in 0..parents.len() {
+                match parents.get(&root) {
+                    Some(Some(parent)) => root = *parent,
+                    _ => break,
+                }
+            }
+            if identities.contains(&root) {
+                vec![root]
+            } else {
+                components.get(&root).cloned().unwrap_or_default()
+            }
+        };
+
+        let mut identity_ids: Vec<EntityId> =
+            samples.iter().flat_map(|s| identities_of(s.id)).collect();
+        identity_ids.sort_unstable();
+        identity_ids.dedup();
+        let consents: HashMap<EntityId, Consent> = consents
+            .find_by_identities(&identity_ids)
+            .await?
+            .into_iter()
+            .map(|c| (c.identity_id, c))
+            .collect();
+
+        Ok(samples
+            .into_iter()
+            .filter_map(|sample| {
+                identities_of(sample.id)
+                    .iter()
+                    .filter_map(|id| consents.get(id))
+                    .try_fold(sample, |sample, consent| consent.restrict(sample))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{ConsentSharing, SampleComposition, SampleDetails};
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryConsents {
+        consents: Vec<Consent>,
+        lookups: Mutex<Vec<Vec<EntityId>>>,
+    }
+
+    #[async_trait]
+    impl ConsentRepository for InMemoryConsents {
+        async fn find_by_identity(&self, _: EntityId) -> Result<Option<Consent>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_identities(
+            &self,
+            identity_ids: &[EntityId],
+        ) -> Result<Vec<Consent>, DomainError> {
+            self.lookups.lock().unwrap().push(identity_ids.to_vec());
+            Ok(self
+                .consents
+                .iter()
+                .filter(|c| identity_ids.contains(&c.identity_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, _: &Consent) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct InMemoryCompositions {
+        compositions: Vec<SampleComposition>,
+    }
+
+    #[async_trait]
+    impl SampleCompositionRepository for InMemoryCompositions {
+        async fn find_by_samples(
+            &self,
+            sample_ids: &[EntityId],
+        ) -> Result<Vec<SampleComposition>, DomainError> {
+            Ok(self
+                .compositions
+                .iter()
+                .filter(|c| sample_ids.contains(&c.sample_id))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_identity(&self, _: EntityId) -> Result<Vec<SampleComposition>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &SampleComposition) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct InMemorySamples {
+        samples: Vec<Sample>,
+    }
+
+    #[async_trait]
+    impl SampleRepository for InMemorySamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(self
+                .samples
+                .iter()
+                .filter(|s| ids.contains(&s.id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn sample(id: EntityId, parent_id: Option<EntityId>) -> Sample {
+        let mut sample = Sample::new_identity(
+            id,
+            format!("PAT_{}", id),
+            Barcode::new_unchecked(format!("SAM{}", id)),
+            1,
+            format!("MRN-{}", id),
+            "tech".to_string(),
+        );
+        sample.description = Some("Second biopsy".to_string());
+        if let (SampleDetails::Detailed(details), Some(parent_id)) =
+            (&mut sample.details, parent_id)
+        {
+            details.sample_class = SampleClass::Tissue;
+            details.parent_id = Some(parent_id);
+        }
+        sample
+    }
+
+    fn consent(identity_id: EntityId, sharing: ConsentSharing) -> Consent {
+        Consent::new(identity_id, sharing, None, "manager".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_samples_are_restricted_by_their_identity_consent() {
+        // 1 <- 2 <- 3 (deidentified), 4 <- 5 (none), 6 (no consent)
+        let stored = Arc::new(InMemorySamples {
+            samples: vec![
+                sample(1, None),
+                sample(2, Some(1)),
+                sample(3, Some(2)),
+                sample(4, None),
+                sample(5, Some(4)),
+                sample(6, None),
+            ],
+        });
+        let consents = Arc::new(InMemoryConsents {
+            consents: vec![
+                consent(1, ConsentSharing::Deidentified),
+                consent(4, ConsentSharing::None),
+            ],
+            ..Default::default()
+        });
+        let redaction = Redaction::new(consents.clone(), stored);
+
+        let shared = redaction
+            .samples(vec![sample(3, Some(2)), sample(5, Some(4)), sample(6, None)])
+            .await
+            .unwrap();
+
+        let ids: Vec<EntityId> = shared.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![3, 6]);
+        assert!(shared[0].description.is_none());
+        assert_eq!(shared[1].description.as_deref(), Some("Second biopsy"));
+        assert_eq!(*consents.lookups.lock().unwrap(), vec![vec![1, 4, 6]]);
+    }
+
+    #[tokio::test]
+    async fn test_pooled_samples_need_every_identity_consent() {
+        // 7 pooled from 1 (deidentified) and 6 (no consent), 8 <- 7,
+        // 9 pooled from 4 (none) and 6
+        let pooled = |id: EntityId| {
+            Sample::new_pooled(
+                id,
+                format!("POOL_{}", id),
+                Barcode::new_unchecked(format!("SAM{}", id)),
+                1,
+                "tech".to_string(),
+            )
+        };
+        let mut child = sample(8, Some(7));
+        child.description = Some("Extraction".to_string());
+        let stored = Arc::new(InMemorySamples {
+            samples: vec![sample(1, None), sample(4, None), sample(6, None), pooled(7)],
+        });
+        let consents = Arc::new(InMemoryConsents {
+            consents: vec![
+                consent(1, ConsentSharing::Deidentified),
+                consent(4, ConsentSharing::None),
+            ],
+            ..Default::default()
+        });
+        let compositions = Arc::new(InMemoryCompositions {
+            compositions: vec![
+                SampleComposition {
+                    sample_id: 7,
+                    identity_ids: vec![1, 6],
+                },
+                SampleComposition {
+                    sample_id: 9,
+                    identity_ids: vec![4, 6],
+                },
+            ],
+        });
+        let redaction = Redaction::new(consents.clone(), stored).with_compositions(compositions);
+
+        let shared = redaction
+            .samples(vec![child, pooled(9)])
+            .await
+            .unwrap();
+
+        let ids: Vec<EntityId> = shared.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![8]);
+        assert!(shared[0].description.is_none());
+        assert_eq!(*consents.lookups.lock().unwrap(), vec![vec![1, 4, 6]]);
+    }
+
+    #[tokio::test]
+    async fn test_default_redaction_shares_everything() {
+        let samples = vec![sample(1, None), sample(2, Some(1))];
+
+        let shared = Redaction::default().samples(samples.clone()).await.unwrap();
+
+        assert_eq!(shared, samples);
+    }
+}
@@ -0,0 +1,129 @@
+//! Activity service for project timelines.
+
+use std::sync::Arc;
+
+use miso_domain::entities::ActivityCursor;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectActivityRepository, ProjectRepository};
+use tracing::instrument;
+
+use crate::access::{ProjectAccess, Requester};
+use crate::dto::ActivityFeedResponse;
+
+/// Default number of items per page.
+const DEFAULT_PAGE_SIZE: u64 = 50;
+
+/// Largest page a client may request.
+const MAX_PAGE_SIZE: u64 = 200;
+
+/// Service for reading project activity feeds.
+pub struct ActivityService<A, P>
+where
+    A: ProjectActivityRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+{
+    activity: Arc<A>,
+    projects: Arc<P>,
+    access: ProjectAccess,
+}
+
+impl<A, P> ActivityService<A, P>
+where
+    A: ProjectActivityRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+{
+    /// Creates a new activity service.
+    pub fn new(activity: Arc<A>, projects: Arc<P>) -> Self {
+        Self {
+            activity,
+            projects,
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Keeps project timelines to the projects' members.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Gets a page of a project's activity, newest first.
+    ///
+    /// `before` is the `next_cursor` of the previous page.
+    #[instrument(skip(self))]
+    pub async fn project_activity(
+        &self,
+        project_id: i32,
+        before: Option<&str>,
+        limit: Option<u64>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<ActivityFeedResponse, DomainError> {
+        self.projects.find_by_id(project_id).await?.ok_or_else(|| {
+            DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: project_id.to_string(),
+            }
+        })?;
+        self.access.check(project_id, requester).await?;
+
+        let cursor = before.map(ActivityCursor::decode).transpose()?;
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        // One extra item tells us whether there is another page
+        let mut items = self
+            .activity
+            .list_for_project(project_id, cursor, limit + 1)
+            .await?;
+        let next_cursor = if items.len() as u64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(|item| item.cursor().encode())
+        } else {
+            None
+        };
+
+        Ok(ActivityFeedResponse {
+            items: items.into_iter().map(Into::into).collect(),
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use miso_domain::entities::{ActivityItem, EntityId, Project, Role};
+
+    use super::*;
+    use crate::test_support::{Members, OneProject};
+
+    /// A project with no activity.
+    struct Quiet;
+
+    #[async_trait]
+    impl ProjectActivityRepository for Quiet {
+        async fn list_for_project(
+            &self,
+            _: EntityId,
+            _: Option<ActivityCursor>,
+            _: u64,
+        ) -> Result<Vec<ActivityItem>, DomainError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timelines_reach_members_only() {
+        let project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        let service = ActivityService::new(Arc::new(Quiet), Arc::new(OneProject::new(project)))
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+
+        let member = Some(Requester::new("member", Role::Technician));
+        assert!(service.project_activity(1, None, None, member).await.unwrap().items.is_empty());
+        for requester in [None, Some(Requester::new("outsider", Role::Technician))] {
+            assert!(matches!(
+                service.project_activity(1, None, None, requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+        }
+    }
+}
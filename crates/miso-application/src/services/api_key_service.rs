@@ -0,0 +1,254 @@
+//! API key service.
+//!
+//! Keys are 64 random hex digits after a `miso_` prefix. Only their
+//! SHA-256 hash is stored: unlike passwords they are long and random, so a
+//! fast, unsalted hash is enough, and it lets a key be looked up by its
+//! hash on every request.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use miso_domain::entities::{ApiKey, EntityId, Role, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ApiKeyRepository;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, instrument};
+
+use crate::dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+
+/// Prefix of every key, so leaked keys are easy to search for.
+const KEY_PREFIX: &str = "miso_";
+
+/// How many characters of a key are kept to tell keys apart.
+const SHOWN_LENGTH: usize = 12;
+
+/// How stale a key's last use may get before it is recorded again, so
+/// that a busy instrument doesn't write on every request.
+const LAST_USED_RESOLUTION: Duration = Duration::minutes(1);
+
+/// Service for issuing, revoking and checking API keys.
+pub struct ApiKeyService<K: ApiKeyRepository + ?Sized> {
+    keys: Arc<K>,
+}
+
+impl<K: ApiKeyRepository + ?Sized> ApiKeyService<K> {
+    /// Creates a new API key service.
+    pub fn new(keys: Arc<K>) -> Self {
+        Self { keys }
+    }
+
+    /// Creates a key acting for `creator`, with a role no higher than
+    /// theirs.
+    #[instrument(skip(self, request, creator), fields(name = %request.name))]
+    pub async fn create_key(
+        &self,
+        request: CreateApiKeyRequest,
+        creator: &User,
+    ) -> Result<CreatedApiKeyResponse, DomainError> {
+        let role: Role = request.role.parse()?;
+        if !creator.role.has_at_least(&role) {
+            return Err(DomainError::Validation(format!(
+                "{} can't create a key with the {} role",
+                creator.username, role
+            )));
+        }
+        if request.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(DomainError::Validation(
+                "API key expiry must be in the future".to_string(),
+            ));
+        }
+
+        let secret = generate_key();
+        let mut key = ApiKey::new(
+            request.name,
+            secret[..SHOWN_LENGTH].to_string(),
+            hash_key(&secret),
+            role,
+            creator.id,
+        )?;
+        key.expires_at = request.expires_at;
+
+        if self.keys.find_by_name(&key.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "ApiKey".to_string(),
+                field: "name".to_string(),
+                value: key.name,
+            });
+        }
+
+        key.id = self.keys.save(&key).await?;
+
+        info!(
+            "Created API key: {} (ID: {}) for {} as {}",
+            key.name, key.id, creator.username, role
+        );
+
+        Ok(CreatedApiKeyResponse {
+            key: secret,
+            details: key.into(),
+        })
+    }
+
+    /// Lists all keys, newest first.
+    #[instrument(skip(self))]
+    pub async fn list_keys(&self) -> Result<Vec<ApiKeyResponse>, DomainError> {
+        let keys = self.keys.list().await?;
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// Revokes a key, which then stops working at once.
+    #[instrument(skip(self))]
+    pub async fn revoke_key(&self, id: EntityId) -> Result<ApiKeyResponse, DomainError> {
+        let mut key = self
+            .keys
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ApiKey".to_string(),
+                id: id.to_string(),
+            })?;
+
+        key.revoke()?;
+        self.keys.save(&key).await?;
+
+        info!("Revoked API key: {} (ID: {})", key.name, id);
+
+        Ok(key.into())
+    }
+
+    /// Returns the key presented with a request, if it is known and
+    /// active, and records its use.
+    #[instrument(skip_all)]
+    pub async fn authenticate(&self, secret: &str) -> Result<Option<ApiKey>, DomainError> {
+        let Some(mut key) = self.keys.find_by_hash(&hash_key(secret)).await? else {
+            debug!("Unknown API key presented");
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        if !key.is_active_at(now) {
+            debug!("Revoked or expired API key {} presented", key.name);
+            return Ok(None);
+        }
+
+        if key
+            .last_used_at
+            .is_none_or(|at| now - at >= LAST_USED_RESOLUTION)
+        {
+            key.last_used_at = Some(now);
+            self.keys.save(&key).await?;
+        }
+
+        Ok(Some(key))
+    }
+}
+
+/// Generates a new key.
+fn generate_key() -> String {
+    format!(
+        "{}{}{}",
+        KEY_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Returns the hex-encoded SHA-256 hash of a key.
+fn hash_key(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryKeys {
+        keys: Mutex<Vec<ApiKey>>,
+    }
+
+    #[async_trait]
+    impl ApiKeyRepository for InMemoryKeys {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ApiKey>, DomainError> {
+            let keys = self.keys.lock().unwrap();
+            Ok(keys.iter().find(|k| k.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<ApiKey>, DomainError> {
+            let keys = self.keys.lock().unwrap();
+            Ok(keys.iter().find(|k| k.name == name).cloned())
+        }
+        async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError> {
+            let keys = self.keys.lock().unwrap();
+            Ok(keys.iter().find(|k| k.key_hash == key_hash).cloned())
+        }
+        async fn list(&self) -> Result<Vec<ApiKey>, DomainError> {
+            Ok(self.keys.lock().unwrap().clone())
+        }
+        async fn save(&self, key: &ApiKey) -> Result<EntityId, DomainError> {
+            let mut keys = self.keys.lock().unwrap();
+            let mut key = key.clone();
+            if key.id == 0 {
+                key.id = keys.len() as EntityId + 1;
+            }
+            keys.retain(|k| k.id != key.id);
+            keys.push(key.clone());
+            Ok(key.id)
+        }
+    }
+
+    fn request(name: &str, role: &str) -> CreateApiKeyRequest {
+        CreateApiKeyRequest {
+            name: name.to_string(),
+            role: role.to_string(),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keys_authenticate_until_revoked() {
+        let service = ApiKeyService::new(Arc::new(InMemoryKeys::default()));
+        let admin = User::new_internal(
+            1,
+            "admin".to_string(),
+            "Admin".to_string(),
+            "admin@example.org".to_string(),
+            Role::Admin,
+        );
+
+        let created = service
+            .create_key(request("novaseq-01", "technician"), &admin)
+            .await
+            .unwrap();
+        assert!(created.key.starts_with(KEY_PREFIX));
+        assert_eq!(created.key.len(), KEY_PREFIX.len() + 64);
+        assert!(created.key.starts_with(&created.details.prefix));
+        assert!(matches!(
+            service
+                .create_key(request("novaseq-01", "viewer"), &admin)
+                .await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(service
+            .create_key(request("root", "super_admin"), &admin)
+            .await
+            .is_err());
+
+        let key = service.authenticate(&created.key).await.unwrap().unwrap();
+        assert_eq!(key.role, Role::Technician);
+        assert_eq!(key.user_id, admin.id);
+        assert!(key.last_used_at.is_some());
+        assert!(service.authenticate("miso_wrong").await.unwrap().is_none());
+
+        let revoked = service.revoke_key(created.details.id).await.unwrap();
+        assert!(!revoked.active);
+        assert!(service.authenticate(&created.key).await.unwrap().is_none());
+        assert!(service.revoke_key(created.details.id).await.is_err());
+    }
+}
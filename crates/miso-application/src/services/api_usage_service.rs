@@ -0,0 +1,270 @@
+//! API usage service.
+//!
+//! Counts calls per caller and endpoint so integration owners and admins
+//! can see who uses the API, how heavily and how fast it answers. Only one
+//! call in every `sample_every` is recorded, standing for the others, so
+//! busy integrations cost little to watch. Counts are gathered in memory
+//! and written out by [`ApiUsageService::flush`]; anything not yet flushed
+//! is lost if the server stops.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use miso_domain::entities::{ApiUsage, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ApiUsageRepository;
+use tracing::{debug, instrument, warn};
+
+use crate::dto::{ApiUsageFilter, ApiUsageReportRow};
+
+/// One API call, as seen by the server.
+#[derive(Debug, Clone)]
+pub struct ApiCall {
+    pub user_id: EntityId,
+    /// Key the call was made with, if any
+    pub api_key_id: Option<EntityId>,
+    pub method: String,
+    /// Route template the call matched
+    pub route: String,
+    /// HTTP status answered
+    pub status: u16,
+    pub elapsed: Duration,
+    pub at: DateTime<Utc>,
+}
+
+/// Service for API usage counts.
+pub struct ApiUsageService {
+    usage: Arc<dyn ApiUsageRepository>,
+    sample_every: u64,
+    /// Calls skipped since the last one recorded
+    skipped: AtomicU64,
+    pending: Mutex<Vec<ApiUsage>>,
+}
+
+impl ApiUsageService {
+    /// Creates a service recording one call in every `sample_every`; every
+    /// call if that is 0 or 1.
+    pub fn new(usage: Arc<dyn ApiUsageRepository>, sample_every: u64) -> Self {
+        Self {
+            usage,
+            sample_every: sample_every.max(1),
+            skipped: AtomicU64::new(0),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Counts a call if it is sampled, returning true if it was.
+    pub fn record(&self, call: &ApiCall) -> bool {
+        let every = self.sample_every;
+        let skipped = self
+            .skipped
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(if n + 1 >= every { 0 } else { n + 1 })
+            })
+            .unwrap_or(0);
+        if skipped > 0 {
+            return false;
+        }
+
+        let mut usage = ApiUsage::new(
+            call.at,
+            call.user_id,
+            call.api_key_id,
+            &call.method,
+            &call.route,
+        );
+        usage.record(
+            self.sample_every as i64,
+            call.status >= 400,
+            i64::try_from(call.elapsed.as_millis()).unwrap_or(i64::MAX),
+        );
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        add(&mut pending, usage);
+        true
+    }
+
+    /// Writes out the counts gathered since the last flush, returning how
+    /// many caller and endpoint counts were written. If they can't be
+    /// written they are kept for the next flush.
+    #[instrument(skip(self))]
+    pub async fn flush(&self) -> Result<usize, DomainError> {
+        let batch = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        match self.usage.add(&batch).await {
+            Ok(()) => {
+                debug!("Flushed {} API usage counts", batch.len());
+                Ok(batch.len())
+            }
+            Err(e) => {
+                warn!("Couldn't write {} API usage counts: {}", batch.len(), e);
+                let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+                for usage in batch {
+                    add(&mut pending, usage);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Reports calls per caller and endpoint over a period, busiest first.
+    pub async fn report(
+        &self,
+        filter: ApiUsageFilter,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ApiUsageReportRow>, DomainError> {
+        let until = filter.until.unwrap_or(now);
+        let since = filter.since.unwrap_or(until - TimeDelta::days(1));
+        if since >= until {
+            return Err(DomainError::Validation(
+                "The report must start before it ends".to_string(),
+            ));
+        }
+
+        let mut totals: BTreeMap<(EntityId, Option<EntityId>, String, String), ApiUsage> =
+            BTreeMap::new();
+        for usage in self.usage.list(since, until).await? {
+            if filter.user_id.is_some_and(|id| id != usage.user_id)
+                || filter.api_key_id.is_some_and(|id| usage.api_key_id != Some(id))
+            {
+                continue;
+            }
+            let key = (
+                usage.user_id,
+                usage.api_key_id,
+                usage.method.clone(),
+                usage.route.clone(),
+            );
+            match totals.get_mut(&key) {
+                Some(total) => total.merge(&usage),
+                None => {
+                    totals.insert(key, usage);
+                }
+            }
+        }
+
+        let mut rows: Vec<ApiUsageReportRow> = totals
+            .into_values()
+            .map(|usage| ApiUsageReportRow {
+                mean_ms: usage.mean_ms(),
+                user_id: usage.user_id,
+                api_key_id: usage.api_key_id,
+                method: usage.method,
+                route: usage.route,
+                calls: usage.calls,
+                errors: usage.errors,
+                max_ms: usage.max_ms,
+            })
+            .collect();
+        rows.sort_by_key(|row| std::cmp::Reverse(row.calls));
+
+        Ok(rows)
+    }
+}
+
+/// Adds a count to the pending counts, merging it into any for the same
+/// caller, endpoint and hour.
+fn add(pending: &mut Vec<ApiUsage>, usage: ApiUsage) {
+    match pending.iter_mut().find(|p| p.same_bucket(&usage)) {
+        Some(existing) => existing.merge(&usage),
+        None => pending.push(usage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryUsage {
+        rows: Mutex<Vec<ApiUsage>>,
+    }
+
+    #[async_trait]
+    impl ApiUsageRepository for InMemoryUsage {
+        async fn add(&self, usage: &[ApiUsage]) -> Result<(), DomainError> {
+            let mut rows = self.rows.lock().unwrap();
+            for usage in usage {
+                add(&mut rows, usage.clone());
+            }
+            Ok(())
+        }
+        async fn list(
+            &self,
+            since: DateTime<Utc>,
+            until: DateTime<Utc>,
+        ) -> Result<Vec<ApiUsage>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|u| u.hour >= since && u.hour < until)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn call(api_key_id: Option<EntityId>, route: &str, status: u16, ms: u64) -> ApiCall {
+        ApiCall {
+            user_id: 4,
+            api_key_id,
+            method: "GET".to_string(),
+            route: route.to_string(),
+            status,
+            elapsed: Duration::from_millis(ms),
+            at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sampled_calls_are_reported_per_key_and_route() {
+        let service = ApiUsageService::new(Arc::new(InMemoryUsage::default()), 2);
+
+        // Every other call is sampled, counting for two
+        let sampled: Vec<bool> = [
+            call(Some(1), "/api/v1/samples", 200, 10),
+            call(Some(1), "/api/v1/samples", 200, 500),
+            call(Some(1), "/api/v1/samples", 404, 30),
+            call(None, "/api/v1/samples", 200, 5),
+            call(Some(1), "/api/v1/runs", 200, 40),
+        ]
+        .iter()
+        .map(|c| service.record(c))
+        .collect();
+        assert_eq!(sampled, vec![true, false, true, false, true]);
+
+        assert_eq!(service.flush().await.unwrap(), 2);
+        assert_eq!(service.flush().await.unwrap(), 0);
+
+        let report = service
+            .report(
+                ApiUsageFilter {
+                    api_key_id: Some(1),
+                    ..Default::default()
+                },
+                Utc::now() + TimeDelta::hours(1),
+            )
+            .await
+            .unwrap();
+        let rows: Vec<_> = report
+            .iter()
+            .map(|r| (r.route.as_str(), r.calls, r.errors, r.mean_ms, r.max_ms))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("/api/v1/samples", 4, 2, Some(20.0), 30),
+                ("/api/v1/runs", 2, 0, Some(40.0), 40),
+            ]
+        );
+    }
+}
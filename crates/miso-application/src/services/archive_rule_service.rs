@@ -0,0 +1,846 @@
+//! Automatic archiving service.
+//!
+//! Lab managers set up rules such as "archive libraries 90 days after their
+//! last run completed". The scheduler evaluates the active rules: items
+//! entering a rule's notice period are announced, and items that are due
+//! and were announced by an earlier evaluation are archived. A preview
+//! lists what an evaluation would do without doing it. Items in locked
+//! projects are left alone, and an archived item can be restored, after
+//! which the rule that archived it leaves it alone.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{
+    ArchiveRule, ArchiveTarget, AutoArchival, EntityId, Role, RunStatus, Sample,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    ArchiveRuleRepository, AutoArchivalRepository, LibraryRepository, PoolRepository,
+    RunRepository, SampleRepository,
+};
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    ArchiveCandidate, ArchivePlanResponse, ArchiveRuleResponse, AutoArchivalResponse,
+    CreateArchiveRuleRequest, UpdateArchiveRuleRequest,
+};
+use crate::locks::ProjectLocks;
+
+/// Who archives items in the audit log.
+const ARCHIVED_BY: &str = "auto-archive";
+
+/// Notice given by rules created without one, in days.
+const DEFAULT_NOTICE_DAYS: u32 = 7;
+
+/// Service for archiving rules and the items they archive.
+pub struct ArchiveRuleService {
+    rules: Arc<dyn ArchiveRuleRepository>,
+    archivals: Arc<dyn AutoArchivalRepository>,
+    runs: Arc<dyn RunRepository>,
+    pools: Arc<dyn PoolRepository>,
+    libraries: Arc<dyn LibraryRepository>,
+    samples: Arc<dyn SampleRepository>,
+    audit: AuditTrail,
+    locks: ProjectLocks,
+}
+
+impl ArchiveRuleService {
+    /// Creates a new archiving rule service.
+    pub fn new(
+        rules: Arc<dyn ArchiveRuleRepository>,
+        archivals: Arc<dyn AutoArchivalRepository>,
+        runs: Arc<dyn RunRepository>,
+        pools: Arc<dyn PoolRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+        samples: Arc<dyn SampleRepository>,
+    ) -> Self {
+        Self {
+            rules,
+            archivals,
+            runs,
+            pools,
+            libraries,
+            samples,
+            audit: AuditTrail::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Records rule changes, archiving and restoring in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Leaves the items of locked projects alone and refuses to restore
+    /// them for anyone but an admin.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Lists all rules by name.
+    pub async fn list(&self) -> Result<Vec<ArchiveRuleResponse>, DomainError> {
+        let rules = self.rules.list().await?;
+        Ok(rules.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates an archiving rule.
+    #[instrument(skip(self, request))]
+    pub async fn create(
+        &self,
+        request: CreateArchiveRuleRequest,
+        created_by: &str,
+    ) -> Result<ArchiveRuleResponse, DomainError> {
+        let notice_days = request
+            .notice_days
+            .unwrap_or(DEFAULT_NOTICE_DAYS.min(request.after_days));
+        let mut rule = ArchiveRule::new(
+            request.name,
+            request.target,
+            request.after_days,
+            notice_days,
+            created_by.to_string(),
+        )?;
+
+        rule.id = self.rules.save(&rule).await?;
+        self.audit
+            .created("ArchiveRule", rule.id, &rule, created_by)
+            .await?;
+
+        info!(
+            "Created archive rule {}: {}s {} days after their last run",
+            rule.name, rule.target, rule.after_days
+        );
+
+        Ok(rule.into())
+    }
+
+    /// Changes an archiving rule.
+    #[instrument(skip(self, request))]
+    pub async fn update(
+        &self,
+        id: EntityId,
+        request: UpdateArchiveRuleRequest,
+        updated_by: &str,
+    ) -> Result<ArchiveRuleResponse, DomainError> {
+        let before = self.find_rule(id).await?;
+        let mut rule = before.clone();
+
+        if let Some(name) = request.name {
+            rule.name = name.trim().to_string();
+        }
+        if let Some(active) = request.active {
+            rule.active = active;
+        }
+        rule.reschedule(
+            request.after_days.unwrap_or(rule.after_days),
+            request.notice_days.unwrap_or(rule.notice_days),
+        )?;
+        rule.validate()?;
+
+        self.rules.save(&rule).await?;
+        self.audit
+            .updated("ArchiveRule", id, &before, &rule, updated_by)
+            .await?;
+
+        info!("Updated archive rule {}", rule.name);
+
+        Ok(rule.into())
+    }
+
+    /// Lists what evaluating a rule now would archive and announce, without
+    /// doing either.
+    pub async fn preview(&self, id: EntityId) -> Result<ArchivePlanResponse, DomainError> {
+        let rule = self.find_rule(id).await?;
+        self.plan(&rule, Utc::now()).await
+    }
+
+    /// Lists the items a rule archived, most recently archived first.
+    pub async fn archivals(&self, id: EntityId) -> Result<Vec<AutoArchivalResponse>, DomainError> {
+        self.find_rule(id).await?;
+        let archivals = self.archivals.find_by_rule(id).await?;
+        Ok(archivals.into_iter().map(Into::into).collect())
+    }
+
+    /// Evaluates every active rule at `now`, archiving the items that are
+    /// due and were announced, and returns what each rule did.
+    #[instrument(skip(self))]
+    pub async fn evaluate_all(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ArchivePlanResponse>, DomainError> {
+        let mut plans = Vec::new();
+        for rule in self.rules.list().await?.into_iter().filter(|r| r.active) {
+            plans.push(self.evaluate(rule, now).await?);
+        }
+        Ok(plans)
+    }
+
+    /// Takes an item a rule archived back out of the archive. The rule
+    /// leaves the item alone from then on.
+    #[instrument(skip(self))]
+    pub async fn restore(
+        &self,
+        archival_id: EntityId,
+        restored_by: &str,
+        role: Role,
+    ) -> Result<AutoArchivalResponse, DomainError> {
+        let mut archival = self
+            .archivals
+            .find_by_id(archival_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "AutoArchival".to_string(),
+                id: archival_id.to_string(),
+            })?;
+        archival.restore(restored_by.to_string(), Utc::now())?;
+
+        match archival.target {
+            ArchiveTarget::Library => {
+                if let Some(before) = self.libraries.find_by_id(archival.entity_id).await? {
+                    self.locks.check(before.project_id, role).await?;
+                    let mut library = before.clone();
+                    library.unarchive();
+                    self.libraries.save(&library).await?;
+                    self.audit
+                        .updated("Library", library.id, &before, &library, restored_by)
+                        .await?;
+                }
+            }
+            ArchiveTarget::Sample => {
+                if let Some(before) = self.samples.find_by_id(archival.entity_id).await? {
+                    self.locks.check(before.project_id, role).await?;
+                    let mut sample = before.clone();
+                    sample.unarchive();
+                    self.save_sample(&before, &mut sample, restored_by).await?;
+                }
+            }
+        }
+
+        self.archivals.save(&archival).await?;
+
+        info!("Restored {} {}", archival.target, archival.entity_name);
+
+        Ok(archival.into())
+    }
+
+    /// Evaluates a rule at `now`.
+    async fn evaluate(
+        &self,
+        mut rule: ArchiveRule,
+        now: DateTime<Utc>,
+    ) -> Result<ArchivePlanResponse, DomainError> {
+        let mut plan = self.plan(&rule, now).await?;
+
+        let mut archived = Vec::with_capacity(plan.archive.len());
+        for candidate in std::mem::take(&mut plan.archive) {
+            if self.archive(&rule, &candidate).await? {
+                archived.push(candidate);
+            }
+        }
+        plan.archive = archived;
+
+        let before = rule.clone();
+        rule.last_evaluated_at = Some(now);
+        self.rules.save(&rule).await?;
+        self.audit
+            .updated("ArchiveRule", rule.id, &before, &rule, ARCHIVED_BY)
+            .await?;
+
+        info!(
+            "Archive rule {}: {} archived, {} announced",
+            rule.name,
+            plan.archive.len(),
+            plan.announce.len()
+        );
+
+        Ok(plan)
+    }
+
+    /// Archives a candidate and records it, returning false if it was
+    /// archived or changed in the meantime.
+    async fn archive(
+        &self,
+        rule: &ArchiveRule,
+        candidate: &ArchiveCandidate,
+    ) -> Result<bool, DomainError> {
+        match rule.target {
+            ArchiveTarget::Library => {
+                let Some(before) = self.libraries.find_by_id(candidate.entity_id).await? else {
+                    return Ok(false);
+                };
+                if before.archived {
+                    return Ok(false);
+                }
+                let mut library = before.clone();
+                library.archive();
+                self.libraries.save(&library).await?;
+                self.audit
+                    .updated("Library", library.id, &before, &library, ARCHIVED_BY)
+                    .await?;
+            }
+            ArchiveTarget::Sample => {
+                let Some(before) = self.samples.find_by_id(candidate.entity_id).await? else {
+                    return Ok(false);
+                };
+                if before.archived {
+                    return Ok(false);
+                }
+                let mut sample = before.clone();
+                sample.archive();
+                if let Err(e) = self.save_sample(&before, &mut sample, ARCHIVED_BY).await {
+                    warn!("Not archiving sample {}: {}", sample.name, e);
+                    return Ok(false);
+                }
+            }
+        }
+
+        let archival = AutoArchival::new(
+            rule,
+            candidate.entity_id,
+            candidate.name.clone(),
+            candidate.last_run_at,
+        );
+        self.archivals.save(&archival).await?;
+        Ok(true)
+    }
+
+    /// Saves a changed sample unless someone else changed it first.
+    async fn save_sample(
+        &self,
+        before: &Sample,
+        sample: &mut Sample,
+        changed_by: &str,
+    ) -> Result<(), DomainError> {
+        let conflicts = self
+            .samples
+            .update_all_versioned(std::slice::from_ref(sample))
+            .await?;
+        if !conflicts.is_empty() {
+            return Err(DomainError::ConcurrentModification {
+                entity_type: "Sample".to_string(),
+                id: sample.id.to_string(),
+            });
+        }
+        sample.version += 1;
+        self.audit
+            .updated("Sample", sample.id, before, sample, changed_by)
+            .await
+    }
+
+    /// Works out what evaluating a rule at `now` would archive and announce.
+    async fn plan(
+        &self,
+        rule: &ArchiveRule,
+        now: DateTime<Utc>,
+    ) -> Result<ArchivePlanResponse, DomainError> {
+        let (archive, rest): (Vec<_>, Vec<_>) = self
+            .candidates(rule)
+            .await?
+            .into_iter()
+            .partition(|c| rule.archives(c.last_run_at, now));
+        let announce = rest
+            .into_iter()
+            .filter(|c| rule.announces(c.last_run_at, now))
+            .collect();
+
+        Ok(ArchivePlanResponse {
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            target: rule.target,
+            archive,
+            announce,
+        })
+    }
+
+    /// Finds the unarchived items a rule applies to, soonest due first:
+    /// those sequenced on a completed run, not restored from the rule, and
+    /// not in a locked project.
+    async fn candidates(&self, rule: &ArchiveRule) -> Result<Vec<ArchiveCandidate>, DomainError> {
+        let library_runs = self.last_library_runs().await?;
+        let library_ids: Vec<EntityId> = library_runs.keys().copied().collect();
+        let libraries = self.libraries.find_by_ids(&library_ids).await?;
+
+        let items: Vec<(EntityId, String, EntityId, DateTime<Utc>)> = match rule.target {
+            ArchiveTarget::Library => libraries
+                .into_iter()
+                .filter(|l| !l.archived)
+                .map(|l| {
+                    let last_run = library_runs[&l.id];
+                    (l.id, l.name, l.project_id, last_run)
+                })
+                .collect(),
+            ArchiveTarget::Sample => {
+                let mut sample_runs: HashMap<EntityId, DateTime<Utc>> = HashMap::new();
+                for library in &libraries {
+                    let last_run = library_runs[&library.id];
+                    let latest = sample_runs.entry(library.sample_id).or_insert(last_run);
+                    *latest = (*latest).max(last_run);
+                }
+                let sample_ids: Vec<EntityId> = sample_runs.keys().copied().collect();
+                self.samples
+                    .find_by_ids(&sample_ids)
+                    .await?
+                    .into_iter()
+                    .filter(|s| !s.archived)
+                    .map(|s| {
+                        let last_run = sample_runs[&s.id];
+                        (s.id, s.name, s.project_id, last_run)
+                    })
+                    .collect()
+            }
+        };
+
+        let restored: HashSet<EntityId> = self
+            .archivals
+            .find_by_rule(rule.id)
+            .await?
+            .into_iter()
+            .filter(AutoArchival::is_restored)
+            .map(|a| a.entity_id)
+            .collect();
+
+        let mut unlocked: HashMap<EntityId, bool> = HashMap::new();
+        let mut candidates = Vec::new();
+        for (entity_id, name, project_id, last_run_at) in items {
+            if restored.contains(&entity_id) {
+                continue;
+            }
+            let open = match unlocked.get(&project_id) {
+                Some(&open) => open,
+                None => {
+                    let open = match self.locks.check(project_id, Role::LabManager).await {
+                        Ok(()) => true,
+                        Err(DomainError::ProjectLocked { .. }) => false,
+                        Err(e) => return Err(e),
+                    };
+                    unlocked.insert(project_id, open);
+                    open
+                }
+            };
+            if open {
+                candidates.push(ArchiveCandidate {
+                    entity_id,
+                    name,
+                    project_id,
+                    last_run_at,
+                    due_at: rule.due_at(last_run_at),
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| a.due_at.cmp(&b.due_at).then_with(|| a.name.cmp(&b.name)));
+        Ok(candidates)
+    }
+
+    /// Finds when each library's last completed run completed.
+    async fn last_library_runs(&self) -> Result<HashMap<EntityId, DateTime<Utc>>, DomainError> {
+        let mut pool_runs: HashMap<EntityId, DateTime<Utc>> = HashMap::new();
+        for run in self.runs.find_by_status(RunStatus::Completed).await? {
+            let Some(completed_at) = run.completed_at else {
+                continue;
+            };
+            for pool_id in run.partitions.iter().filter_map(|p| p.pool_id) {
+                let latest = pool_runs.entry(pool_id).or_insert(completed_at);
+                *latest = (*latest).max(completed_at);
+            }
+        }
+
+        let mut library_runs: HashMap<EntityId, DateTime<Utc>> = HashMap::new();
+        for (pool_id, completed_at) in pool_runs {
+            let Some(pool) = self.pools.find_by_id(pool_id).await? else {
+                continue;
+            };
+            for element in pool.elements {
+                let latest = library_runs.entry(element.library_id).or_insert(completed_at);
+                *latest = (*latest).max(completed_at);
+            }
+        }
+        Ok(library_runs)
+    }
+
+    /// Finds a rule by ID.
+    async fn find_rule(&self, id: EntityId) -> Result<ArchiveRule, DomainError> {
+        self.rules
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ArchiveRule".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use miso_domain::entities::{
+        Library, LibraryDesign, LibraryType, Pool, PoolElement, Run, SampleClass,
+    };
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryRules(Mutex<Vec<ArchiveRule>>);
+
+    #[async_trait]
+    impl ArchiveRuleRepository for InMemoryRules {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ArchiveRule>, DomainError> {
+            Ok(self.0.lock().unwrap().iter().find(|r| r.id == id).cloned())
+        }
+        async fn list(&self) -> Result<Vec<ArchiveRule>, DomainError> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+        async fn save(&self, rule: &ArchiveRule) -> Result<EntityId, DomainError> {
+            let mut rules = self.0.lock().unwrap();
+            let mut rule = rule.clone();
+            if rule.id == 0 {
+                rule.id = rules.len() as EntityId + 1;
+            }
+            rules.retain(|r| r.id != rule.id);
+            rules.push(rule.clone());
+            Ok(rule.id)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryArchivals(Mutex<Vec<AutoArchival>>);
+
+    #[async_trait]
+    impl AutoArchivalRepository for InMemoryArchivals {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<AutoArchival>, DomainError> {
+            Ok(self.0.lock().unwrap().iter().find(|a| a.id == id).cloned())
+        }
+        async fn find_by_rule(&self, rule_id: EntityId) -> Result<Vec<AutoArchival>, DomainError> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|a| a.rule_id == rule_id)
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, archival: &AutoArchival) -> Result<EntityId, DomainError> {
+            let mut archivals = self.0.lock().unwrap();
+            let mut archival = archival.clone();
+            if archival.id == 0 {
+                archival.id = archivals.len() as EntityId + 1;
+                archivals.push(archival.clone());
+            } else if let Some(stored) = archivals.iter_mut().find(|a| a.id == archival.id) {
+                *stored = archival.clone();
+            }
+            Ok(archival.id)
+        }
+    }
+
+    /// Library N was sequenced, alone in pool N, on run N, which completed
+    /// at the given time. Samples are never looked at.
+    struct Lab {
+        completed: Vec<DateTime<Utc>>,
+        libraries: Mutex<Vec<Library>>,
+    }
+
+    impl Lab {
+        fn new(completed: Vec<DateTime<Utc>>) -> Self {
+            let libraries = (1..=completed.len() as EntityId)
+                .map(|id| {
+                    Library::new(
+                        id,
+                        format!("LIB{}", id),
+                        Barcode::new(format!("LIB-{}", id)).unwrap(),
+                        id,
+                        1,
+                        LibraryDesign::Wgs,
+                        LibraryType::PairedEnd,
+                        "ILLUMINA".to_string(),
+                        "tech".to_string(),
+                    )
+                })
+                .collect();
+            Self {
+                completed,
+                libraries: Mutex::new(libraries),
+            }
+        }
+
+        fn archived(&self) -> Vec<EntityId> {
+            let libraries = self.libraries.lock().unwrap();
+            libraries.iter().filter(|l| l.archived).map(|l| l.id).collect()
+        }
+    }
+
+    #[async_trait]
+    impl RunRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sequencer(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_status(&self, status: RunStatus) -> Result<Vec<Run>, DomainError> {
+            assert_eq!(status, RunStatus::Completed);
+            Ok((1..)
+                .zip(&self.completed)
+                .map(|(id, &completed_at)| {
+                    let mut run = Run::new(id, format!("RUN{}", id), 1, 1, "tech".to_string());
+                    run.status = RunStatus::Completed;
+                    run.completed_at = Some(completed_at);
+                    run.partitions[0].pool_id = Some(id);
+                    run
+                })
+                .collect())
+        }
+        async fn find_by_pool(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Run) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl PoolRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Pool>, DomainError> {
+            let mut pool = Pool::new(
+                id,
+                format!("POOL{}", id),
+                Barcode::new(format!("POOL-{}", id)).unwrap(),
+                "ILLUMINA".to_string(),
+                "tech".to_string(),
+            );
+            pool.elements.push(PoolElement {
+                library_aliquot_id: id,
+                library_id: id,
+                volume: None,
+                proportion: None,
+            });
+            Ok(Some(pool))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Pool) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl LibraryRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(self.libraries.lock().unwrap().iter().find(|l| l.id == id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            let libraries = self.libraries.lock().unwrap();
+            Ok(libraries.iter().filter(|l| ids.contains(&l.id)).cloned().collect())
+        }
+        async fn save(&self, library: &Library) -> Result<EntityId, DomainError> {
+            let mut libraries = self.libraries.lock().unwrap();
+            if let Some(stored) = libraries.iter_mut().find(|l| l.id == library.id) {
+                *stored = library.clone();
+            }
+            Ok(library.id)
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: DateTime<Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: DateTime<Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn ids(candidates: &[ArchiveCandidate]) -> Vec<EntityId> {
+        candidates.iter().map(|c| c.entity_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_items_are_announced_archived_and_restored() {
+        let now = Utc::now();
+        let days_ago = |d| now - Duration::days(d);
+        let lab = Arc::new(Lab::new(vec![days_ago(100), days_ago(85), days_ago(10)]));
+        let service = ArchiveRuleService::new(
+            Arc::new(InMemoryRules::default()),
+            Arc::new(InMemoryArchivals::default()),
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+        );
+
+        let rule = service
+            .create(
+                CreateArchiveRuleRequest {
+                    name: "Sequenced libraries".to_string(),
+                    target: ArchiveTarget::Library,
+                    after_days: 90,
+                    notice_days: None,
+                },
+                "lm",
+            )
+            .await
+            .unwrap();
+        assert_eq!(rule.notice_days, 7);
+
+        // Nothing is archived before it has been announced, however overdue
+        let preview = service.preview(rule.id).await.unwrap();
+        assert!(preview.archive.is_empty());
+        assert_eq!(ids(&preview.announce), vec![1, 2]);
+        assert!(lab.archived().is_empty());
+
+        let plans = service.evaluate_all(now).await.unwrap();
+        assert_eq!(ids(&plans[0].announce), vec![1, 2]);
+        assert!(lab.archived().is_empty());
+
+        let plans = service.evaluate_all(now + Duration::hours(1)).await.unwrap();
+        assert_eq!(ids(&plans[0].archive), vec![1]);
+        assert!(plans[0].announce.is_empty());
+        assert_eq!(lab.archived(), vec![1]);
+
+        let archival = &service.archivals(rule.id).await.unwrap()[0];
+        let restored = service.restore(archival.id, "tech", Role::Technician).await.unwrap();
+        assert_eq!(restored.restored_by.as_deref(), Some("tech"));
+        assert!(lab.archived().is_empty());
+        assert!(service.restore(archival.id, "tech", Role::Technician).await.is_err());
+
+        // The restored library is left alone; the announced one is archived
+        // once due
+        let plans = service.evaluate_all(now + Duration::days(6)).await.unwrap();
+        assert_eq!(ids(&plans[0].archive), vec![2]);
+        assert_eq!(lab.archived(), vec![2]);
+        assert_eq!(service.archivals(rule.id).await.unwrap().len(), 2);
+
+        // Inactive rules aren't evaluated
+        let update = UpdateArchiveRuleRequest {
+            active: Some(false),
+            ..Default::default()
+        };
+        service.update(rule.id, update, "lm").await.unwrap();
+        assert!(service.evaluate_all(now + Duration::days(90)).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,740 @@
+//! Attachment service.
+//!
+//! Files are uploaded against a sample, library or run. The contents go to
+//! the configured attachment store under a key of their own and the
+//! metadata - file name, size, SHA-256 checksum and uploader - to the
+//! attachment repository. Downloads are checked against the checksum so a
+//! file damaged in storage isn't handed out as if it were intact.
+//!
+//! Sample and library attachments are only reachable by members of the
+//! item's project; run attachments by everyone.
+
+use std::sync::Arc;
+
+use miso_domain::attachments::AttachmentStore;
+use miso_domain::entities::{Attachment, AttachmentTarget, EntityId, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    AttachmentRepository, LibraryRepository, RunRepository, SampleRepository,
+};
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument, warn};
+
+use crate::access::{ProjectAccess, Requester};
+use crate::audit::AuditTrail;
+use crate::dto::{AttachmentContent, AttachmentResponse};
+use crate::locks::ProjectLocks;
+
+/// Service for attachment operations.
+///
+/// Run attachments are unavailable until the run repository is supplied.
+pub struct AttachmentService {
+    attachments: Arc<dyn AttachmentRepository>,
+    store: Arc<dyn AttachmentStore>,
+    samples: Arc<dyn SampleRepository>,
+    libraries: Arc<dyn LibraryRepository>,
+    runs: Option<Arc<dyn RunRepository>>,
+    audit: AuditTrail,
+    access: ProjectAccess,
+    locks: ProjectLocks,
+}
+
+impl AttachmentService {
+    /// Creates a service attaching files to samples and libraries.
+    pub fn new(
+        attachments: Arc<dyn AttachmentRepository>,
+        store: Arc<dyn AttachmentStore>,
+        samples: Arc<dyn SampleRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+    ) -> Self {
+        Self {
+            attachments,
+            store,
+            samples,
+            libraries,
+            runs: None,
+            audit: AuditTrail::default(),
+            access: ProjectAccess::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Enables attaching files to runs.
+    pub fn with_runs(mut self, runs: Arc<dyn RunRepository>) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Records uploads and deletions in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Limits samples' and libraries' attachments to their projects'
+    /// members.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Refuses uploads to and deletions from locked projects' samples and
+    /// libraries, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Stores a file and records it against the target.
+    ///
+    /// If the metadata can't be saved the stored contents are removed
+    /// again, so nothing is left in the store that no attachment refers to.
+    #[instrument(skip(self, contents), fields(size = contents.len()))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload(
+        &self,
+        target: AttachmentTarget,
+        target_id: EntityId,
+        filename: &str,
+        content_type: &str,
+        contents: &[u8],
+        uploaded_by: &str,
+        role: Role,
+    ) -> Result<AttachmentResponse, DomainError> {
+        if let Some(project_id) = self.target_project(target, target_id).await? {
+            self.access
+                .check(project_id, Some(Requester::new(uploaded_by, role)))
+                .await?;
+            self.locks.check(project_id, role).await?;
+        }
+        if contents.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "{} is empty",
+                filename.trim()
+            )));
+        }
+
+        let mut attachment = Attachment::new(
+            target,
+            target_id,
+            filename,
+            content_type,
+            contents.len() as i64,
+            checksum(contents),
+            uploaded_by.to_string(),
+        )?;
+
+        self.store
+            .put(&attachment.storage_key, &attachment.content_type, contents)
+            .await?;
+        attachment.id = match self.attachments.save(&attachment).await {
+            Ok(id) => id,
+            Err(e) => {
+                if let Err(cleanup) = self.store.delete(&attachment.storage_key).await {
+                    warn!(
+                        "Couldn't remove {} after its metadata failed to save: {}",
+                        attachment.storage_key, cleanup
+                    );
+                }
+                return Err(e);
+            }
+        };
+        self.audit
+            .created("Attachment", attachment.id, &attachment, uploaded_by)
+            .await?;
+
+        info!(
+            "Attached {} ({} bytes) to {} {} (ID: {})",
+            attachment.filename, attachment.size, target, target_id, attachment.id
+        );
+
+        Ok(attachment.into())
+    }
+
+    /// Lists the files attached to a sample, library or run, newest first.
+    pub async fn list(
+        &self,
+        target: AttachmentTarget,
+        target_id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<AttachmentResponse>, DomainError> {
+        if let Some(project_id) = self.target_project(target, target_id).await? {
+            self.access.check(project_id, requester).await?;
+        }
+
+        Ok(self
+            .attachments
+            .find_by_target(target, target_id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Gets an attachment's metadata.
+    pub async fn get(
+        &self,
+        id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<AttachmentResponse, DomainError> {
+        Ok(self.find_reachable(id, requester).await?.into())
+    }
+
+    /// Reads an attachment's contents, checking them against the checksum
+    /// taken on upload.
+    #[instrument(skip(self))]
+    pub async fn download(
+        &self,
+        id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<AttachmentContent, DomainError> {
+        let attachment = self.find_reachable(id, requester).await?;
+        let contents = self.store.get(&attachment.storage_key).await?;
+
+        if checksum(&contents) != attachment.checksum {
+            warn!(
+                "Contents of attachment {} no longer match its checksum",
+                id
+            );
+            return Err(DomainError::Validation(format!(
+                "The stored contents of {} are damaged",
+                attachment.filename
+            )));
+        }
+
+        Ok(AttachmentContent {
+            attachment: attachment.into(),
+            contents,
+        })
+    }
+
+    /// Deletes an attachment and its stored contents.
+    #[instrument(skip(self))]
+    pub async fn delete(
+        &self,
+        id: EntityId,
+        deleted_by: &str,
+        role: Role,
+    ) -> Result<(), DomainError> {
+        let attachment = self.find_attachment(id).await?;
+        if let Some(project_id) = self
+            .target_project(attachment.target, attachment.target_id)
+            .await?
+        {
+            self.access
+                .check(project_id, Some(Requester::new(deleted_by, role)))
+                .await?;
+            self.locks.check(project_id, role).await?;
+        }
+
+        self.attachments.delete(id).await?;
+        if let Err(e) = self.store.delete(&attachment.storage_key).await {
+            warn!(
+                "Couldn't remove {} for deleted attachment {}: {}",
+                attachment.storage_key, id, e
+            );
+        }
+        self.audit
+            .deleted("Attachment", id, &attachment, deleted_by)
+            .await?;
+
+        info!("Deleted attachment {} (ID: {})", attachment.filename, id);
+        Ok(())
+    }
+
+    /// Finds the project of the entity files are attached to, checking it
+    /// exists. Runs belong to no project.
+    async fn target_project(
+        &self,
+        target: AttachmentTarget,
+        target_id: EntityId,
+    ) -> Result<Option<EntityId>, DomainError> {
+        let (entity_type, project) = match target {
+            AttachmentTarget::Sample => (
+                "Sample",
+                self.samples
+                    .find_by_id(target_id)
+                    .await?
+                    .map(|s| Some(s.project_id)),
+            ),
+            AttachmentTarget::Library => (
+                "Library",
+                self.libraries
+                    .find_by_id(target_id)
+                    .await?
+                    .map(|l| Some(l.project_id)),
+            ),
+            AttachmentTarget::Run => {
+                let runs = self.runs.as_ref().ok_or_else(|| {
+                    DomainError::Validation("Run attachments are not configured".to_string())
+                })?;
+                ("Run", runs.find_by_id(target_id).await?.map(|_| None))
+            }
+        };
+
+        project.ok_or_else(|| DomainError::NotFound {
+            entity_type: entity_type.to_string(),
+            id: target_id.to_string(),
+        })
+    }
+
+    /// Finds an attachment, checking the requester may reach its target.
+    async fn find_reachable(
+        &self,
+        id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Attachment, DomainError> {
+        let attachment = self.find_attachment(id).await?;
+        if let Some(project_id) = self
+            .target_project(attachment.target, attachment.target_id)
+            .await?
+        {
+            self.access.check(project_id, requester).await?;
+        }
+        Ok(attachment)
+    }
+
+    async fn find_attachment(&self, id: EntityId) -> Result<Attachment, DomainError> {
+        self.attachments
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Attachment".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+/// Hex-encoded SHA-256 of a file's contents.
+fn checksum(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        Library, Project, Run, RunStatus, Sample, SampleClass,
+    };
+    use miso_domain::repositories::{
+        NewSample, QueryOptions, VersionConflict,
+    };
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::test_support::{Members, OneProject};
+
+    #[derive(Default)]
+    struct InMemoryAttachments {
+        attachments: Mutex<Vec<Attachment>>,
+        fail_saves: bool,
+    }
+
+    #[async_trait]
+    impl AttachmentRepository for InMemoryAttachments {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Attachment>, DomainError> {
+            Ok(self.attachments.lock().unwrap().iter().find(|a| a.id == id).cloned())
+        }
+        async fn find_by_target(
+            &self,
+            target: AttachmentTarget,
+            target_id: EntityId,
+        ) -> Result<Vec<Attachment>, DomainError> {
+            Ok(self
+                .attachments
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|a| a.target == target && a.target_id == target_id)
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, attachment: &Attachment) -> Result<EntityId, DomainError> {
+            if self.fail_saves {
+                return Err(DomainError::Validation("database unavailable".to_string()));
+            }
+            let mut attachments = self.attachments.lock().unwrap();
+            let mut attachment = attachment.clone();
+            attachment.id = attachments.len() as EntityId + 1;
+            let id = attachment.id;
+            attachments.push(attachment);
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.attachments.lock().unwrap().retain(|a| a.id != id);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryStore {
+        files: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl AttachmentStore for InMemoryStore {
+        async fn put(&self, key: &str, _: &str, contents: &[u8]) -> Result<(), DomainError> {
+            self.files.lock().unwrap().insert(key.to_string(), contents.to_vec());
+            Ok(())
+        }
+        async fn get(&self, key: &str) -> Result<Vec<u8>, DomainError> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| DomainError::NotFound {
+                    entity_type: "Attachment".to_string(),
+                    id: key.to_string(),
+                })
+        }
+        async fn delete(&self, key: &str) -> Result<(), DomainError> {
+            self.files.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    /// Only run 1 and sample 1, of project 1, exist; libraries are never
+    /// looked at.
+    struct Entities;
+
+    #[async_trait]
+    impl RunRepository for Entities {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Run>, DomainError> {
+            Ok((id == 1).then(|| Run::new(1, "RUN1".to_string(), 1, 1, "tech".to_string())))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sequencer(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_status(&self, _: RunStatus) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_pool(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Run) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl LibraryRepository for Entities {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for Entities {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok((id == 1).then(|| {
+                Sample::new_plain(
+                    1,
+                    "SAM1".to_string(),
+                    Barcode::new("SAM-1").unwrap(),
+                    1,
+                    "Homo sapiens".to_string(),
+                    "tech".to_string(),
+                )
+            }))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn service(
+        attachments: InMemoryAttachments,
+        store: Arc<InMemoryStore>,
+    ) -> AttachmentService {
+        AttachmentService::new(
+            Arc::new(attachments),
+            store,
+            Arc::new(Entities),
+            Arc::new(Entities),
+        )
+        .with_runs(Arc::new(Entities))
+    }
+
+    #[tokio::test]
+    async fn test_upload_download_and_damage() {
+        let store = Arc::new(InMemoryStore::default());
+        let service = service(InMemoryAttachments::default(), store.clone());
+
+        let uploaded = service
+            .upload(
+                AttachmentTarget::Run,
+                1,
+                "traces/bioanalyzer.pdf",
+                "application/pdf",
+                b"%PDF",
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert_eq!(uploaded.filename, "bioanalyzer.pdf");
+        assert_eq!(uploaded.size, 4);
+        assert_eq!(uploaded.checksum.len(), 64);
+        assert!(matches!(
+            service
+                .upload(AttachmentTarget::Run, 2, "a.txt", "", b"x", "tech", Role::Technician)
+                .await,
+            Err(DomainError::NotFound { .. })
+        ));
+        assert!(service
+            .upload(AttachmentTarget::Run, 1, "empty.txt", "", b"", "tech", Role::Technician)
+            .await
+            .is_err());
+
+        let listed = service.list(AttachmentTarget::Run, 1, None).await.unwrap();
+        assert_eq!(listed, vec![uploaded.clone()]);
+        assert_eq!(service.download(uploaded.id, None).await.unwrap().contents, b"%PDF");
+
+        // Contents changed behind the service's back aren't handed out
+        for contents in store.files.lock().unwrap().values_mut() {
+            contents.push(b'!');
+        }
+        assert!(matches!(
+            service.download(uploaded.id, None).await,
+            Err(DomainError::Validation(_))
+        ));
+
+        service.delete(uploaded.id, "tech", Role::Technician).await.unwrap();
+        assert!(store.files.lock().unwrap().is_empty());
+        assert!(service.list(AttachmentTarget::Run, 1, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_removes_stored_contents() {
+        let store = Arc::new(InMemoryStore::default());
+        let attachments = InMemoryAttachments {
+            fail_saves: true,
+            ..Default::default()
+        };
+        let service = service(attachments, store.clone());
+
+        assert!(service
+            .upload(
+                AttachmentTarget::Run,
+                1,
+                "gel.png",
+                "image/png",
+                b"png",
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .is_err());
+        assert!(store.files.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_locked_projects_attachments_change_only_by_admins() {
+        let store = Arc::new(InMemoryStore::default());
+        let mut project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        project.lock("PI review".to_string(), "lm".to_string(), None);
+        let service = service(InMemoryAttachments::default(), store.clone())
+            .with_project_locks(ProjectLocks::new(Arc::new(OneProject::new(project))));
+
+        let upload = |by, role| {
+            service.upload(AttachmentTarget::Sample, 1, "gel.png", "image/png", b"png", by, role)
+        };
+        assert!(matches!(
+            upload("tech", Role::Technician).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        let uploaded = upload("admin", Role::Admin).await.unwrap();
+
+        assert!(matches!(
+            service.delete(uploaded.id, "tech", Role::Technician).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        assert_eq!(store.files.lock().unwrap().len(), 1);
+        service.delete(uploaded.id, "admin", Role::Admin).await.unwrap();
+
+        // Runs belong to no project, so aren't locked
+        service
+            .upload(
+                AttachmentTarget::Run,
+                1,
+                "run.csv",
+                "text/csv",
+                b"csv",
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_project_attachments_reach_members_only() {
+        let store = Arc::new(InMemoryStore::default());
+        let service = service(InMemoryAttachments::default(), store)
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let member = Some(Requester::new("member", Role::Technician));
+        let outsider = Some(Requester::new("outsider", Role::Technician));
+
+        let uploaded = service
+            .upload(
+                AttachmentTarget::Sample,
+                1,
+                "gel.png",
+                "image/png",
+                b"png",
+                "member",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .upload(
+                    AttachmentTarget::Sample,
+                    1,
+                    "b.png",
+                    "",
+                    b"x",
+                    "outsider",
+                    Role::Technician,
+                )
+                .await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+
+        for requester in [None, outsider] {
+            assert!(matches!(
+                service.get(uploaded.id, requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+            assert!(matches!(
+                service.download(uploaded.id, requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+            assert!(service.list(AttachmentTarget::Sample, 1, requester).await.is_err());
+        }
+        assert!(matches!(
+            service.delete(uploaded.id, "outsider", Role::Technician).await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+        assert_eq!(service.get(uploaded.id, member).await.unwrap(), uploaded);
+        assert_eq!(service.download(uploaded.id, member).await.unwrap().contents, b"png");
+    }
+}
@@ -0,0 +1,332 @@
+//! Custom attribute definition service.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{AttributeDefinition, AttributeTarget};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::AttributeDefinitionRepository;
+use tracing::{info, instrument};
+
+use crate::dto::{
+    codes, AttributeDefinitionResponse, AttributeSchemaResponse, CreateAttributeDefinitionRequest,
+    UpdateAttributeDefinitionRequest,
+};
+
+/// Service for managing custom attributes and resolving the schema forms
+/// are generated from.
+pub struct AttributeDefinitionService<R: AttributeDefinitionRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: AttributeDefinitionRepository + ?Sized> AttributeDefinitionService<R> {
+    /// Creates a new attribute definition service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Defines a custom attribute.
+    #[instrument(skip(self))]
+    pub async fn create_definition(
+        &self,
+        request: CreateAttributeDefinitionRequest,
+    ) -> Result<AttributeDefinitionResponse, DomainError> {
+        let mut definition = AttributeDefinition::new(
+            request.target,
+            request.key,
+            request.label,
+            request.value_type,
+        );
+        definition.project_id = request.project_id;
+        definition.required = request.required;
+        definition.options = request.options.unwrap_or_default();
+        definition.min = request.min;
+        definition.max = request.max;
+        definition.max_length = request.max_length;
+        definition.help_text = request.help_text;
+        definition.position = request.position.unwrap_or_default();
+        definition.validate()?;
+        self.ensure_key_free(&definition).await?;
+
+        definition.id = self.repository.save(&definition).await?;
+
+        info!(
+            "Defined {} attribute: {} (ID: {})",
+            definition.target, definition.key, definition.id
+        );
+
+        Ok(definition.into())
+    }
+
+    /// Gets a custom attribute by ID.
+    #[instrument(skip(self))]
+    pub async fn get_definition(
+        &self,
+        id: i32,
+    ) -> Result<AttributeDefinitionResponse, DomainError> {
+        Ok(self.find_definition(id).await?.into())
+    }
+
+    /// Lists the custom attributes, optionally only those of one target.
+    #[instrument(skip(self))]
+    pub async fn list_definitions(
+        &self,
+        target: Option<AttributeTarget>,
+    ) -> Result<Vec<AttributeDefinitionResponse>, DomainError> {
+        let definitions = self.repository.list(target).await?;
+        Ok(definitions.into_iter().map(Into::into).collect())
+    }
+
+    /// Updates a custom attribute.
+    #[instrument(skip(self))]
+    pub async fn update_definition(
+        &self,
+        id: i32,
+        request: UpdateAttributeDefinitionRequest,
+    ) -> Result<AttributeDefinitionResponse, DomainError> {
+        let mut definition = self.find_definition(id).await?;
+
+        if let Some(label) = request.label {
+            definition.label = label;
+        }
+        if let Some(required) = request.required {
+            definition.required = required;
+        }
+        if let Some(options) = request.options {
+            definition.options = options;
+        }
+        if let Some(min) = request.min {
+            definition.min = Some(min);
+        }
+        if let Some(max) = request.max {
+            definition.max = Some(max);
+        }
+        if let Some(max_length) = request.max_length {
+            definition.max_length = Some(max_length);
+        }
+        if let Some(help_text) = request.help_text {
+            definition.help_text = Some(help_text);
+        }
+        if let Some(position) = request.position {
+            definition.position = position;
+        }
+        definition.validate()?;
+
+        self.repository.save(&definition).await?;
+
+        info!("Updated attribute: {} (ID: {})", definition.key, id);
+
+        Ok(definition.into())
+    }
+
+    /// Removes a custom attribute. Values already recorded under its key
+    /// are kept but no longer shown or accepted.
+    #[instrument(skip(self))]
+    pub async fn delete_definition(&self, id: i32) -> Result<(), DomainError> {
+        self.find_definition(id).await?;
+        self.repository.delete(id).await?;
+
+        info!("Deleted attribute: {}", id);
+
+        Ok(())
+    }
+
+    /// Resolves the attributes a form for the target should show: those
+    /// shared by every project, plus the project's own when one is given.
+    #[instrument(skip(self))]
+    pub async fn schema(
+        &self,
+        target: AttributeTarget,
+        project_id: Option<i32>,
+    ) -> Result<AttributeSchemaResponse, DomainError> {
+        let definitions = match project_id {
+            Some(project_id) => self.repository.find_for_project(target, project_id).await?,
+            None => self
+                .repository
+                .list(Some(target))
+                .await?
+                .into_iter()
+                .filter(|d| d.project_id.is_none())
+                .collect(),
+        };
+
+        Ok(AttributeSchemaResponse {
+            target: codes::attribute_target(target).to_string(),
+            project_id,
+            fields: definitions.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn find_definition(&self, id: i32) -> Result<AttributeDefinition, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "AttributeDefinition".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// A key may be used once per entity: a shared attribute's key can't
+    /// be reused by any project, nor a project's twice.
+    async fn ensure_key_free(&self, definition: &AttributeDefinition) -> Result<(), DomainError> {
+        let clash = self
+            .repository
+            .list(Some(definition.target))
+            .await?
+            .into_iter()
+            .any(|d| {
+                d.key == definition.key
+                    && (d.project_id.is_none()
+                        || definition.project_id.is_none()
+                        || d.project_id == definition.project_id)
+            });
+        if clash {
+            return Err(DomainError::Duplicate {
+                entity_type: "AttributeDefinition".to_string(),
+                field: "key".to_string(),
+                value: definition.key.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{AttributeType, EntityId};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryDefinitions {
+        definitions: Mutex<Vec<AttributeDefinition>>,
+    }
+
+    #[async_trait]
+    impl AttributeDefinitionRepository for InMemoryDefinitions {
+        async fn find_by_id(
+            &self,
+            id: EntityId,
+        ) -> Result<Option<AttributeDefinition>, DomainError> {
+            let definitions = self.definitions.lock().unwrap();
+            Ok(definitions.iter().find(|d| d.id == id).cloned())
+        }
+        async fn list(
+            &self,
+            target: Option<AttributeTarget>,
+        ) -> Result<Vec<AttributeDefinition>, DomainError> {
+            let definitions = self.definitions.lock().unwrap();
+            Ok(definitions
+                .iter()
+                .filter(|d| target.is_none_or(|t| d.target == t))
+                .cloned()
+                .collect())
+        }
+        async fn find_for_project(
+            &self,
+            target: AttributeTarget,
+            project_id: EntityId,
+        ) -> Result<Vec<AttributeDefinition>, DomainError> {
+            let definitions = self.definitions.lock().unwrap();
+            Ok(definitions
+                .iter()
+                .filter(|d| d.target == target && d.applies_to(project_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, definition: &AttributeDefinition) -> Result<EntityId, DomainError> {
+            let mut definitions = self.definitions.lock().unwrap();
+            let mut definition = definition.clone();
+            if definition.id == 0 {
+                definition.id = definitions.len() as EntityId + 1;
+            }
+            definitions.retain(|d| d.id != definition.id);
+            definitions.push(definition.clone());
+            Ok(definition.id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.definitions.lock().unwrap().retain(|d| d.id != id);
+            Ok(())
+        }
+    }
+
+    fn request(key: &str, project_id: Option<i32>) -> CreateAttributeDefinitionRequest {
+        CreateAttributeDefinitionRequest {
+            target: AttributeTarget::Sample,
+            project_id,
+            key: key.to_string(),
+            label: key.to_string(),
+            value_type: AttributeType::Text,
+            required: project_id.is_some(),
+            options: None,
+            min: None,
+            max: None,
+            max_length: None,
+            help_text: None,
+            position: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_includes_shared_and_project_attributes() {
+        let service = AttributeDefinitionService::new(Arc::new(InMemoryDefinitions::default()));
+        service
+            .create_definition(request("donor_id", None))
+            .await
+            .unwrap();
+        service
+            .create_definition(request("consent_form", Some(3)))
+            .await
+            .unwrap();
+
+        let shared = service.schema(AttributeTarget::Sample, None).await.unwrap();
+        assert_eq!(shared.fields.len(), 1);
+
+        let project = service
+            .schema(AttributeTarget::Sample, Some(3))
+            .await
+            .unwrap();
+        let keys: Vec<_> = project.fields.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, vec!["donor_id", "consent_form"]);
+        assert!(project.fields[1].required);
+
+        let other = service
+            .schema(AttributeTarget::Library, Some(3))
+            .await
+            .unwrap();
+        assert!(other.fields.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_unique_per_entity() {
+        let service = AttributeDefinitionService::new(Arc::new(InMemoryDefinitions::default()));
+        service
+            .create_definition(request("donor_id", None))
+            .await
+            .unwrap();
+        service
+            .create_definition(request("consent_form", Some(3)))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .create_definition(request("donor_id", Some(4)))
+                .await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service
+                .create_definition(request("consent_form", None))
+                .await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(service
+            .create_definition(request("consent_form", Some(4)))
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,441 @@
+//! Audit log service.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{ArchivedLog, AuditQuery, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{AuditLogRepository, ChangeLogRepository, LogArchiveRepository};
+use miso_domain::services::LogChainVerifier;
+use tracing::{info, instrument, warn};
+
+use crate::dto::{AuditChainVerification, AuditEntryResponse, ChainBreakResponse, LogChainReport};
+
+/// Default number of entries per page.
+const DEFAULT_PAGE_SIZE: u64 = 100;
+
+/// Largest page a client may request.
+const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Entries read at a time while verifying a chain.
+const VERIFY_PAGE_SIZE: u64 = 500;
+
+/// Service for reading and verifying the audit log.
+pub struct AuditService<A: AuditLogRepository + ?Sized> {
+    log: Arc<A>,
+    change_log: Arc<dyn ChangeLogRepository>,
+    archives: Option<Arc<dyn LogArchiveRepository>>,
+}
+
+impl<A: AuditLogRepository + ?Sized> AuditService<A> {
+    /// Creates a new audit service.
+    pub fn new(log: Arc<A>, change_log: Arc<dyn ChangeLogRepository>) -> Self {
+        Self {
+            log,
+            change_log,
+            archives: None,
+        }
+    }
+
+    /// Checks the logs' chains from where their archives end, once their
+    /// oldest entries have been archived.
+    pub fn with_archives(mut self, archives: Arc<dyn LogArchiveRepository>) -> Self {
+        self.archives = Some(archives);
+        self
+    }
+
+    /// Lists audit log entries matching a query, newest first.
+    #[instrument(skip(self))]
+    pub async fn list_entries(
+        &self,
+        mut query: AuditQuery,
+    ) -> Result<Vec<AuditEntryResponse>, DomainError> {
+        if query.entity_id.is_some() && query.entity_type.is_none() {
+            return Err(DomainError::Validation(
+                "An entity ID must be given with its entity type".to_string(),
+            ));
+        }
+        query.limit = Some(
+            query
+                .limit
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(1, MAX_PAGE_SIZE),
+        );
+
+        let entries = self.log.find(&query).await?;
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// Walks the audit log and change log from their first entries,
+    /// checking each against the hash chain, and reports the first entry of
+    /// each that doesn't fit. Archived entries are not checked; the first
+    /// entry left must follow on from the last one archived.
+    #[instrument(skip(self))]
+    pub async fn verify_chains(&self) -> Result<AuditChainVerification, DomainError> {
+        let started_at = Utc::now();
+
+        let audit_log = verify_log(self.anchor(ArchivedLog::AuditLog).await?, |after| async move {
+            let page = self.log.list_after(after, VERIFY_PAGE_SIZE).await?;
+            Ok(page
+                .into_iter()
+                .map(|e| {
+                    let expected_hash = e.expected_hash();
+                    (e.id, e.previous_hash, e.hash, expected_hash)
+                })
+                .collect())
+        })
+        .await?;
+        let change_log = verify_log(self.anchor(ArchivedLog::ChangeLog).await?, |after| async move {
+            let page = self.change_log.list_after(after, VERIFY_PAGE_SIZE).await?;
+            Ok(page
+                .into_iter()
+                .map(|e| {
+                    let expected_hash = e.expected_hash();
+                    (e.id, e.previous_hash, e.hash, expected_hash)
+                })
+                .collect())
+        })
+        .await?;
+
+        let intact = audit_log.first_break.is_none() && change_log.first_break.is_none();
+        for (name, report) in [("Audit log", &audit_log), ("Change log", &change_log)] {
+            match &report.first_break {
+                Some(broken) => warn!(
+                    "{} chain breaks at entry {}: {}",
+                    name, broken.id, broken.reason
+                ),
+                None => info!("{} chain intact over {} entries", name, report.entries_checked),
+            }
+        }
+
+        Ok(AuditChainVerification {
+            started_at,
+            completed_at: Utc::now(),
+            intact,
+            audit_log,
+            change_log,
+        })
+    }
+
+    /// Returns the hash of the last archived entry of a log, which its
+    /// remaining entries chain onto; empty if none are archived.
+    async fn anchor(&self, log: ArchivedLog) -> Result<String, DomainError> {
+        let Some(archives) = &self.archives else {
+            return Ok(String::new());
+        };
+        let newest = archives
+            .list(log)
+            .await?
+            .into_iter()
+            .find(|archive| !archive.is_restored());
+        Ok(newest.map(|archive| archive.last_hash).unwrap_or_default())
+    }
+}
+
+/// An entry's ID, previous hash, hash, and the hash its contents give.
+type ChainLink = (EntityId, String, String, String);
+
+/// Checks a log page by page, stopping at the first entry that doesn't fit
+/// the chain. `anchor` is the hash of the last archived entry, if any;
+/// `page` lists the entries after an ID, oldest first.
+async fn verify_log<F, Fut>(anchor: String, page: F) -> Result<LogChainReport, DomainError>
+where
+    F: Fn(EntityId) -> Fut,
+    Fut: Future<Output = Result<Vec<ChainLink>, DomainError>>,
+{
+    let mut verifier = LogChainVerifier::anchored(&anchor);
+    let mut last_id = 0;
+    let mut first_break = None;
+    'pages: loop {
+        let links = page(last_id).await?;
+        let full_page = links.len() as u64 == VERIFY_PAGE_SIZE;
+
+        for (id, previous_hash, hash, expected_hash) in links {
+            if let Err(broken) = verifier.check(id, &previous_hash, &hash, expected_hash) {
+                first_break = Some(ChainBreakResponse {
+                    id: broken.id,
+                    reason: broken.reason,
+                });
+                break 'pages;
+            }
+            last_id = id;
+        }
+        if !full_page {
+            break;
+        }
+    }
+
+    Ok(LogChainReport {
+        entries_checked: verifier.checked,
+        unchained: verifier.unchained,
+        first_break,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::DateTime;
+    use miso_domain::entities::{
+        ArchivedRow, AuditAction, AuditEntry, ChangeLogEntry, FieldChange, LogArchive,
+        LogTableSize, ERASED,
+    };
+
+    use super::*;
+
+    /// Keeps entries in memory, chaining them as the database does.
+    #[derive(Default)]
+    struct InMemoryLog {
+        entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditLogRepository for InMemoryLog {
+        async fn find(&self, _query: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn save_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+            let mut stored = self.entries.lock().unwrap();
+            for entry in entries {
+                let mut entry = entry.clone();
+                entry.id = stored.len() as EntityId + 1;
+                entry.chain(stored.last().map(|e| e.hash.clone()).unwrap_or_default());
+                stored.push(entry);
+            }
+            Ok(())
+        }
+        async fn list_after(
+            &self,
+            id: EntityId,
+            limit: u64,
+        ) -> Result<Vec<AuditEntry>, DomainError> {
+            let stored = self.entries.lock().unwrap();
+            Ok(stored
+                .iter()
+                .filter(|e| e.id > id)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_entity(
+            &self,
+            _entity_type: &str,
+            _entity_id: EntityId,
+        ) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _entries: &[AuditEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryChangeLog {
+        entries: Mutex<Vec<ChangeLogEntry>>,
+    }
+
+    #[async_trait]
+    impl ChangeLogRepository for InMemoryChangeLog {
+        async fn find_by_entity(
+            &self,
+            _entity_type: &str,
+            _entity_id: EntityId,
+        ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            let mut stored = self.entries.lock().unwrap();
+            for entry in entries {
+                let mut entry = entry.clone();
+                entry.id = stored.len() as EntityId + 1;
+                entry.chain(stored.last().map(|e| e.hash.clone()).unwrap_or_default());
+                stored.push(entry);
+            }
+            Ok(())
+        }
+        async fn list_after(
+            &self,
+            id: EntityId,
+            limit: u64,
+        ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            let stored = self.entries.lock().unwrap();
+            Ok(stored
+                .iter()
+                .filter(|e| e.id > id)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn update_all(&self, _entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// Archives recorded, with none of their rows kept.
+    struct RecordedArchives(Vec<LogArchive>);
+
+    #[async_trait]
+    impl LogArchiveRepository for RecordedArchives {
+        async fn list_expired(
+            &self,
+            _log: ArchivedLog,
+            _before: DateTime<Utc>,
+            _limit: u64,
+        ) -> Result<Vec<ArchivedRow>, DomainError> {
+            unimplemented!()
+        }
+        async fn archive(
+            &self,
+            _archive: &LogArchive,
+            _ids: &[EntityId],
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn restore(
+            &self,
+            _archive: &LogArchive,
+            _rows: &[String],
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_id(&self, _id: EntityId) -> Result<Option<LogArchive>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, log: ArchivedLog) -> Result<Vec<LogArchive>, DomainError> {
+            Ok(self.0.iter().rev().filter(|a| a.log == log).cloned().collect())
+        }
+        async fn sizes(&self) -> Result<Vec<LogTableSize>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn entry(name: &str) -> AuditEntry {
+        let change = FieldChange::new("external_name", None, Some(format!("\"{}\"", name)));
+        AuditEntry::new("Sample", 1, AuditAction::Create, vec![change], "tech")
+    }
+
+    #[tokio::test]
+    async fn test_verify_chains_finds_first_tampered_entry() {
+        let log = Arc::new(InMemoryLog::default());
+        let change_log = Arc::new(InMemoryChangeLog::default());
+        log.save_all(&[entry("P1"), entry("P2")]).await.unwrap();
+        log.save_all(&[entry("P3")]).await.unwrap();
+        change_log
+            .save_all(&[ChangeLogEntry::new("Sample", 1, "Received", "tech")])
+            .await
+            .unwrap();
+        let service = AuditService::new(log.clone(), change_log.clone());
+
+        let report = service.verify_chains().await.unwrap();
+        assert!(report.intact);
+        assert_eq!(report.audit_log.entries_checked, 3);
+        assert_eq!(report.change_log.entries_checked, 1);
+
+        // Erasing details keeps the chain, and the entry is still checked
+        log.entries.lock().unwrap()[0].erase_fields(&["external_name"]);
+        assert!(service.verify_chains().await.unwrap().intact);
+
+        log.entries.lock().unwrap()[1].changed_by = "someone else".to_string();
+        let report = service.verify_chains().await.unwrap();
+        assert!(!report.intact);
+        let broken = report.audit_log.first_break.unwrap();
+        assert_eq!(broken.id, 2);
+        assert_eq!(broken.reason, "Its hash does not match its contents");
+        assert_eq!(report.audit_log.entries_checked, 1);
+
+        // Removing an entry breaks the link from the one after it
+        change_log
+            .save_all(&[ChangeLogEntry::new("Sample", 1, "Stored", "tech")])
+            .await
+            .unwrap();
+        change_log.entries.lock().unwrap().remove(0);
+        let report = service.verify_chains().await.unwrap();
+        assert_eq!(report.change_log.first_break.unwrap().id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_verify_chains_checks_erased_entries() {
+        let log = Arc::new(InMemoryLog::default());
+        log.save_all(&[entry("P1"), entry("P2")]).await.unwrap();
+        let service = AuditService::new(log.clone(), Arc::new(InMemoryChangeLog::default()));
+
+        // Rewriting the rest of an erased entry is noticed
+        {
+            let mut entries = log.entries.lock().unwrap();
+            entries[0].erase_fields(&["external_name"]);
+            entries[0].changed_by = "someone else".to_string();
+        }
+        let report = service.verify_chains().await.unwrap();
+        assert_eq!(report.audit_log.first_break.unwrap().id, 1);
+
+        // So is planting the erased marker without erasing
+        log.entries.lock().unwrap()[0].changed_by = "tech".to_string();
+        assert!(service.verify_chains().await.unwrap().intact);
+        log.entries.lock().unwrap()[1].changes[0].new = Some(format!("\"{}\"", ERASED));
+        let report = service.verify_chains().await.unwrap();
+        let broken = report.audit_log.first_break.unwrap();
+        assert_eq!(broken.id, 2);
+        assert_eq!(broken.reason, "Its hash does not match its contents");
+    }
+
+    #[tokio::test]
+    async fn test_verify_chains_checks_redacted_change_log_entries() {
+        let change_log = Arc::new(InMemoryChangeLog::default());
+        change_log
+            .save_all(&[
+                ChangeLogEntry::new("Sample", 1, "Received from MRN-1's clinic", "tech")
+                    .with_reason(Some("MRN-1 asked".to_string())),
+                ChangeLogEntry::new("Sample", 1, "Stored", "tech")
+                    .with_reason(Some("Freezer move".to_string())),
+            ])
+            .await
+            .unwrap();
+        let service = AuditService::new(Arc::new(InMemoryLog::default()), change_log.clone());
+
+        // Redacting keeps the chain, and the rest of the entry is checked
+        assert!(change_log.entries.lock().unwrap()[0].redact("MRN-1"));
+        assert!(service.verify_chains().await.unwrap().intact);
+        change_log.entries.lock().unwrap()[0].changed_by = "someone else".to_string();
+        let report = service.verify_chains().await.unwrap();
+        assert_eq!(report.change_log.first_break.unwrap().id, 1);
+
+        // A reason written with the erased marker is still checked
+        change_log.entries.lock().unwrap()[0].changed_by = "tech".to_string();
+        change_log.entries.lock().unwrap()[1].reason = Some(format!("{} by request", ERASED));
+        let report = service.verify_chains().await.unwrap();
+        let broken = report.change_log.first_break.unwrap();
+        assert_eq!(broken.id, 2);
+        assert_eq!(broken.reason, "Its hash does not match its contents");
+    }
+
+    #[tokio::test]
+    async fn test_verify_chains_starts_after_archived_entries() {
+        let log = Arc::new(InMemoryLog::default());
+        log.save_all(&[entry("P1"), entry("P2"), entry("P3")])
+            .await
+            .unwrap();
+        let archived: Vec<AuditEntry> = log.entries.lock().unwrap().drain(..2).collect();
+        let archive_rows: Vec<ArchivedRow> = archived
+            .iter()
+            .map(|e| ArchivedRow {
+                id: e.id,
+                hash: e.hash.clone(),
+                json: String::new(),
+            })
+            .collect();
+        let archive =
+            LogArchive::of_rows(ArchivedLog::AuditLog, "audit.jsonl.gz", &archive_rows).unwrap();
+
+        let service = AuditService::new(log.clone(), Arc::new(InMemoryChangeLog::default()));
+        let report = service.verify_chains().await.unwrap();
+        assert_eq!(report.audit_log.first_break.unwrap().id, 3);
+
+        let service = service.with_archives(Arc::new(RecordedArchives(vec![archive])));
+        let report = service.verify_chains().await.unwrap();
+        assert!(report.intact);
+        assert_eq!(report.audit_log.entries_checked, 1);
+    }
+}
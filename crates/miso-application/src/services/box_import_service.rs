@@ -0,0 +1,308 @@
+//! Box layout import service for freezer inventories kept in spreadsheets.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use miso_domain::entities::{
+    EntityId, Sample, StorableItem, StorableType, StorageBox, StorageLocation,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    LibraryRepository, PoolRepository, ProjectRepository, SampleRepository,
+    StorageBoxRepository,
+};
+use miso_domain::value_objects::{Barcode, BoxPosition, Dimension};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{BoxImportResponse, ImportBoxesRequest, ImportedBox, UnresolvedBoxRow};
+use crate::importers::{parse_box_csv, BoxCsvRow};
+
+/// Service that fills boxes from a `Box,Position,Barcode` CSV.
+pub struct BoxImportService<B, S, L, P, J>
+where
+    B: StorageBoxRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    boxes: Arc<B>,
+    samples: Arc<S>,
+    libraries: Arc<L>,
+    pools: Arc<P>,
+    projects: Arc<J>,
+    audit: AuditTrail,
+}
+
+impl<B, S, L, P, J> BoxImportService<B, S, L, P, J>
+where
+    B: StorageBoxRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    /// Creates a new import service.
+    pub fn new(
+        boxes: Arc<B>,
+        samples: Arc<S>,
+        libraries: Arc<L>,
+        pools: Arc<P>,
+        projects: Arc<J>,
+    ) -> Self {
+        Self {
+            boxes,
+            samples,
+            libraries,
+            pools,
+            projects,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records placeholder samples the import creates in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Imports box layouts, placing each listed barcode in its box.
+    ///
+    /// A box label that is an existing box's barcode fills that box;
+    /// otherwise a box is created named and barcoded after the label, in
+    /// the requested location, holding the kind of item first placed in
+    /// it. Barcodes are looked up as samples, then libraries, then pools.
+    /// With a placeholder project, unknown barcodes become new plain
+    /// samples there. Rows that can't be placed are reported back rather
+    /// than failing the import.
+    #[instrument(skip(self, request))]
+    pub async fn import(
+        &self,
+        request: ImportBoxesRequest,
+        imported_by: &str,
+    ) -> Result<BoxImportResponse, DomainError> {
+        let rows = parse_box_csv(&request.contents)?;
+
+        if let Some(project_id) = request.placeholder_project_id {
+            if self.projects.find_by_id(project_id).await?.is_none() {
+                return Err(DomainError::NotFound {
+                    entity_type: "Project".to_string(),
+                    id: project_id.to_string(),
+                });
+            }
+        }
+
+        // Rows grouped by box, in the order the boxes first appear
+        let mut groups: Vec<(String, Vec<BoxCsvRow>)> = Vec::new();
+        for row in rows {
+            match groups.iter_mut().find(|(label, _)| *label == row.box_label) {
+                Some((_, group)) => group.push(row),
+                None => groups.push((row.box_label.clone(), vec![row])),
+            }
+        }
+
+        let mut import = Import {
+            dimension: Dimension::new(request.rows.unwrap_or(9), request.cols.unwrap_or(9)),
+            location: StorageLocation {
+                freezer: request.freezer,
+                shelf: request.shelf,
+                rack: request.rack,
+                temperature: None,
+            },
+            placeholder_project_id: request.placeholder_project_id,
+            placed: HashSet::new(),
+            placeholders_created: 0,
+        };
+
+        let mut boxes = Vec::new();
+        let mut unresolved = Vec::new();
+        let mut placed_rows = 0;
+
+        for (label, rows) in groups {
+            let unresolve = |row: &BoxCsvRow, reason: String| UnresolvedBoxRow {
+                line: row.line,
+                box_label: row.box_label.clone(),
+                position: row.position.clone(),
+                barcode: row.barcode.clone(),
+                reason,
+            };
+            if label.is_empty() {
+                unresolved.extend(rows.iter().map(|r| unresolve(r, "No box given".to_string())));
+                continue;
+            }
+
+            let existing = self.boxes.find_by_barcode(&label).await?;
+            let created = existing.is_none();
+            let mut storage_box = existing;
+            let mut placed = 0;
+
+            for row in &rows {
+                match self
+                    .place_row(&mut import, &mut storage_box, row, imported_by)
+                    .await?
+                {
+                    Ok(()) => placed += 1,
+                    Err(reason) => unresolved.push(unresolve(row, reason)),
+                }
+            }
+
+            // A box is only created once something could be placed in it
+            if let Some(mut storage_box) = storage_box.filter(|_| placed > 0) {
+                storage_box.id = self.boxes.save(&storage_box).await?;
+                boxes.push(ImportedBox {
+                    box_id: storage_box.id,
+                    name: storage_box.name,
+                    created,
+                    placed,
+                });
+                placed_rows += placed;
+            }
+        }
+
+        info!(
+            "Imported {} boxes: {} rows placed, {} placeholder samples created, {} unresolved",
+            boxes.len(),
+            placed_rows,
+            import.placeholders_created,
+            unresolved.len()
+        );
+
+        Ok(BoxImportResponse {
+            boxes,
+            placed_rows,
+            placeholders_created: import.placeholders_created,
+            unresolved,
+        })
+    }
+
+    /// Places one row's item in its box, creating the box in memory if
+    /// it's the first to be placed, or explains why it can't be.
+    async fn place_row(
+        &self,
+        import: &mut Import,
+        storage_box: &mut Option<StorageBox>,
+        row: &BoxCsvRow,
+        imported_by: &str,
+    ) -> Result<Result<(), String>, DomainError> {
+        let dimension = storage_box
+            .as_ref()
+            .map_or(import.dimension, |b| b.dimension);
+        let position = match BoxPosition::parse(&row.position, &dimension) {
+            Ok(position) => position,
+            Err(e) => return Ok(Err(e.to_string())),
+        };
+        if let Some(item) = storage_box.as_ref().and_then(|b| b.get_item(&position)) {
+            return Ok(Err(format!(
+                "{} already holds {} {}",
+                position, item.item_type, item.item_id
+            )));
+        }
+
+        let item = match self.resolve(&row.barcode).await? {
+            Some(item) => item,
+            None => match import.placeholder_project_id {
+                None => {
+                    return Ok(Err(
+                        "No sample, library or pool has this barcode".to_string()
+                    ))
+                }
+                Some(_) if storage_box
+                    .as_ref()
+                    .is_some_and(|b| b.storable_type != StorableType::Sample) =>
+                {
+                    return Ok(Err(
+                        "No sample, library or pool has this barcode, and the box doesn't hold samples"
+                            .to_string(),
+                    ))
+                }
+                Some(project_id) => {
+                    let Ok(barcode) = Barcode::new(row.barcode.clone()) else {
+                        return Ok(Err("Not a valid barcode for a placeholder sample".to_string()));
+                    };
+                    let id = self
+                        .create_placeholder(barcode, project_id, imported_by)
+                        .await?;
+                    import.placeholders_created += 1;
+                    StorableItem::sample(id)
+                }
+            },
+        };
+
+        if !import.placed.insert(item.clone()) {
+            return Ok(Err("Listed more than once".to_string()));
+        }
+        if let Some((other, at)) = self
+            .boxes
+            .find_by_item(item.item_type, item.item_id)
+            .await?
+        {
+            return Ok(Err(format!("Already stored in {} at {}", other.name, at)));
+        }
+
+        let storage_box = storage_box.get_or_insert_with(|| {
+            let mut storage_box =
+                StorageBox::new(0, row.box_label.clone(), dimension, item.item_type);
+            storage_box.barcode = Some(row.box_label.clone());
+            storage_box.location = import.location.clone();
+            storage_box
+        });
+        if item.item_type != storage_box.storable_type {
+            return Ok(Err(format!(
+                "{} holds {}s, not {}s",
+                storage_box.name, storage_box.storable_type, item.item_type
+            )));
+        }
+        storage_box.place_item(position, item)?;
+
+        Ok(Ok(()))
+    }
+
+    /// Finds the sample, library or pool with a barcode.
+    async fn resolve(&self, barcode: &str) -> Result<Option<StorableItem>, DomainError> {
+        if let Some(sample) = self.samples.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::sample(sample.id)));
+        }
+        if let Some(library) = self.libraries.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::library(library.id)));
+        }
+        if let Some(pool) = self.pools.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::pool(pool.id)));
+        }
+        Ok(None)
+    }
+
+    /// Creates a plain sample standing in for a tube nothing is recorded
+    /// for yet, named after its barcode.
+    async fn create_placeholder(
+        &self,
+        barcode: Barcode,
+        project_id: EntityId,
+        imported_by: &str,
+    ) -> Result<EntityId, DomainError> {
+        let mut sample = Sample::new_plain(
+            0,
+            barcode.to_string(),
+            barcode,
+            project_id,
+            "Unknown".to_string(),
+            imported_by.to_string(),
+        );
+        sample.description = Some("Placeholder created by box import".to_string());
+        sample.id = self.samples.save(&sample).await?;
+        self.audit
+            .created("Sample", sample.id, &sample, imported_by)
+            .await?;
+        Ok(sample.id)
+    }
+}
+
+/// What an import needs across all its boxes.
+struct Import {
+    dimension: Dimension,
+    location: StorageLocation,
+    placeholder_project_id: Option<EntityId>,
+    /// Items placed so far, so none is placed twice
+    placed: HashSet<StorableItem>,
+    placeholders_created: usize,
+}
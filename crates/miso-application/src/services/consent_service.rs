@@ -0,0 +1,289 @@
+//! Consent service.
+//!
+//! Consent is recorded against an identity and decides what of its
+//! samples exports may share; see [`crate::redaction`].
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{Consent, EntityId, SampleClass};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ConsentRepository, SampleRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{ConsentResponse, SetConsentRequest};
+
+/// Service for recording participants' consent.
+pub struct ConsentService {
+    consents: Arc<dyn ConsentRepository>,
+    samples: Arc<dyn SampleRepository>,
+    audit: AuditTrail,
+}
+
+impl ConsentService {
+    /// Creates a new consent service.
+    pub fn new(consents: Arc<dyn ConsentRepository>, samples: Arc<dyn SampleRepository>) -> Self {
+        Self {
+            consents,
+            samples,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records consent changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Gets the consent recorded for an identity.
+    #[instrument(skip(self))]
+    pub async fn get_consent(&self, identity_id: EntityId) -> Result<ConsentResponse, DomainError> {
+        let consent = self
+            .consents
+            .find_by_identity(identity_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Consent".to_string(),
+                id: identity_id.to_string(),
+            })?;
+        Ok(consent.into())
+    }
+
+    /// Records an identity's consent, replacing any recorded before.
+    #[instrument(skip(self, request))]
+    pub async fn set_consent(
+        &self,
+        identity_id: EntityId,
+        request: SetConsentRequest,
+        recorded_by: &str,
+    ) -> Result<ConsentResponse, DomainError> {
+        let identity = self
+            .samples
+            .find_by_id(identity_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: identity_id.to_string(),
+            })?;
+        if identity.sample_class() != SampleClass::Identity {
+            return Err(DomainError::Validation(format!(
+                "Consent is recorded against identities, not {}",
+                identity.name
+            )));
+        }
+
+        let consent = match self.consents.find_by_identity(identity_id).await? {
+            Some(before) => {
+                let mut consent = before.clone();
+                consent.sharing = request.sharing;
+                consent.notes = request.notes;
+                consent.recorded_by = recorded_by.to_string();
+                consent.recorded_at = Utc::now();
+                self.consents.save(&consent).await?;
+                self.audit
+                    .updated("Consent", consent.id, &before, &consent, recorded_by)
+                    .await?;
+                consent
+            }
+            None => {
+                let mut consent = Consent::new(
+                    identity_id,
+                    request.sharing,
+                    request.notes,
+                    recorded_by.to_string(),
+                );
+                consent.id = self.consents.save(&consent).await?;
+                self.audit
+                    .created("Consent", consent.id, &consent, recorded_by)
+                    .await?;
+                consent
+            }
+        };
+
+        info!(
+            "Recorded {} consent for identity {}",
+            consent.sharing, identity_id
+        );
+
+        Ok(consent.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{ConsentSharing, Sample, SampleDetails};
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryConsents {
+        consents: Mutex<HashMap<EntityId, Consent>>,
+    }
+
+    #[async_trait]
+    impl ConsentRepository for InMemoryConsents {
+        async fn find_by_identity(
+            &self,
+            identity_id: EntityId,
+        ) -> Result<Option<Consent>, DomainError> {
+            Ok(self.consents.lock().unwrap().get(&identity_id).cloned())
+        }
+        async fn find_by_identities(&self, _: &[EntityId]) -> Result<Vec<Consent>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, consent: &Consent) -> Result<EntityId, DomainError> {
+            let mut consents = self.consents.lock().unwrap();
+            let mut consent = consent.clone();
+            if consent.id == 0 {
+                consent.id = consents.len() as EntityId + 1;
+            }
+            consents.insert(consent.identity_id, consent.clone());
+            Ok(consent.id)
+        }
+    }
+
+    /// Holds identity 1 and its tissue, 2.
+    struct TwoSamples;
+
+    #[async_trait]
+    impl SampleRepository for TwoSamples {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            if !(1..=2).contains(&id) {
+                return Ok(None);
+            }
+            let mut sample = Sample::new_identity(
+                id,
+                format!("PAT_{}", id),
+                Barcode::new_unchecked(format!("SAM{}", id)),
+                1,
+                "MRN-1".to_string(),
+                "tech".to_string(),
+            );
+            if let (SampleDetails::Detailed(details), 2) = (&mut sample.details, id) {
+                details.sample_class = SampleClass::Tissue;
+                details.parent_id = Some(1);
+            }
+            Ok(Some(sample))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn request(sharing: ConsentSharing) -> SetConsentRequest {
+        SetConsentRequest {
+            sharing,
+            notes: Some("Form CF-12".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consent_is_recorded_and_replaced() {
+        let service = ConsentService::new(Arc::new(InMemoryConsents::default()), Arc::new(TwoSamples));
+        assert!(service.get_consent(1).await.is_err());
+
+        let first = service
+            .set_consent(1, request(ConsentSharing::Full), "manager")
+            .await
+            .unwrap();
+        let second = service
+            .set_consent(1, request(ConsentSharing::None), "dpo")
+            .await
+            .unwrap();
+
+        assert_eq!(second.id, first.id);
+        let consent = service.get_consent(1).await.unwrap();
+        assert_eq!(consent.sharing, ConsentSharing::None);
+        assert_eq!(consent.recorded_by, "dpo");
+    }
+
+    #[tokio::test]
+    async fn test_consent_is_only_recorded_for_identities() {
+        let service = ConsentService::new(Arc::new(InMemoryConsents::default()), Arc::new(TwoSamples));
+
+        let err = service
+            .set_consent(2, request(ConsentSharing::Full), "manager")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+
+        let err = service
+            .set_consent(9, request(ConsentSharing::Full), "manager")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::NotFound { .. }));
+    }
+}
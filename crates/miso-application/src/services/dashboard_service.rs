@@ -0,0 +1,202 @@
+//! Dashboard statistics service.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use miso_domain::entities::{EntityId, Platform, Run, RunStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    QueryOptions, RunRepository, SampleRepository, SequencerRepository, StorageBoxRepository,
+};
+use tracing::instrument;
+
+use crate::dto::{
+    codes, ActiveRun, DailyIntake, DashboardStats, FreezerCapacity, PlatformRuns, QcBreakdown,
+};
+
+/// Group name for boxes without a freezer.
+const UNASSIGNED_FREEZER: &str = "Unassigned";
+
+/// Service gathering the statistics shown on the dashboard.
+///
+/// Sample statistics are always available; run and freezer sections are
+/// reported as unavailable until their repositories are supplied.
+pub struct DashboardService {
+    samples: Arc<dyn SampleRepository>,
+    runs: Option<(Arc<dyn RunRepository>, Arc<dyn SequencerRepository>)>,
+    boxes: Option<Arc<dyn StorageBoxRepository>>,
+}
+
+impl DashboardService {
+    /// Creates a service reporting sample statistics only.
+    pub fn new(samples: Arc<dyn SampleRepository>) -> Self {
+        Self {
+            samples,
+            runs: None,
+            boxes: None,
+        }
+    }
+
+    /// Enables the runs per platform and active runs sections.
+    pub fn with_runs(
+        mut self,
+        runs: Arc<dyn RunRepository>,
+        sequencers: Arc<dyn SequencerRepository>,
+    ) -> Self {
+        self.runs = Some((runs, sequencers));
+        self
+    }
+
+    /// Enables the freezer capacity section.
+    pub fn with_boxes(mut self, boxes: Arc<dyn StorageBoxRepository>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
+    /// Gathers statistics for the `days` days ending at `now`.
+    #[instrument(skip(self))]
+    pub async fn stats(
+        &self,
+        now: DateTime<Utc>,
+        days: u32,
+    ) -> Result<DashboardStats, DomainError> {
+        let days = days.max(1);
+        let today = now.date_naive();
+        let since = (today - Duration::days(days as i64 - 1))
+            .and_hms_opt(0, 0, 0)
+            .map(|t| t.and_utc())
+            .unwrap_or(now);
+
+        let intake = self.samples.count_received_by_day(since).await?;
+        let qc = self.samples.count_by_qc_status().await?;
+
+        let (runs_per_platform, active_runs) = match &self.runs {
+            Some((runs, sequencers)) => {
+                let platforms: HashMap<EntityId, Platform> = sequencers
+                    .list()
+                    .await?
+                    .into_iter()
+                    .map(|s| (s.id, s.model.platform))
+                    .collect();
+                let runs = runs.list(QueryOptions::new()).await?;
+                (
+                    Some(Self::runs_per_platform(&runs, &platforms, since)),
+                    Some(Self::active_runs(&runs, &platforms)),
+                )
+            }
+            None => (None, None),
+        };
+        let freezers = match &self.boxes {
+            Some(boxes) => Some(Self::freezers(boxes).await?),
+            None => None,
+        };
+
+        Ok(DashboardStats {
+            generated_at: now,
+            days,
+            sample_intake: DailyIntake::fill(&intake, today, days),
+            qc: QcBreakdown::from_counts(&qc),
+            runs_per_platform,
+            active_runs,
+            freezers,
+        })
+    }
+
+    fn runs_per_platform(
+        runs: &[Run],
+        platforms: &HashMap<EntityId, Platform>,
+        since: DateTime<Utc>,
+    ) -> Vec<PlatformRuns> {
+        let mut counts: Vec<PlatformRuns> = Vec::new();
+        for run in runs {
+            let started = run.started_at.is_some_and(|t| t >= since);
+            let running = run.status == RunStatus::Running;
+            if !started && !running {
+                continue;
+            }
+
+            let platform = Self::platform_code(platforms, run);
+            let entry = match counts.iter().position(|c| c.platform == platform) {
+                Some(i) => &mut counts[i],
+                None => {
+                    counts.push(PlatformRuns {
+                        platform: platform.to_string(),
+                        runs: 0,
+                        running: 0,
+                    });
+                    counts.last_mut().expect("just pushed")
+                }
+            };
+            entry.runs += started as u64;
+            entry.running += running as u64;
+        }
+
+        counts.sort_by_key(|c| std::cmp::Reverse(c.runs));
+        counts
+    }
+
+    /// Lists runs sequencing or paused now, those expected to finish soonest
+    /// first and those with no estimate last.
+    fn active_runs(runs: &[Run], platforms: &HashMap<EntityId, Platform>) -> Vec<ActiveRun> {
+        let mut active: Vec<ActiveRun> = runs
+            .iter()
+            .filter(|run| matches!(run.status, RunStatus::Running | RunStatus::Paused))
+            .map(|run| ActiveRun {
+                id: run.id,
+                name: run.name.clone(),
+                platform: Self::platform_code(platforms, run).to_string(),
+                status: codes::run_status(run.status).to_string(),
+                started_at: run.started_at,
+                estimated_completion: run.estimated_completion,
+                cycles_completed: run.cycles_completed,
+                total_cycles: run.total_cycles(),
+            })
+            .collect();
+        active.sort_by_key(|run| (run.estimated_completion.is_none(), run.estimated_completion));
+        active
+    }
+
+    fn platform_code(platforms: &HashMap<EntityId, Platform>, run: &Run) -> &'static str {
+        let platform = platforms
+            .get(&run.sequencer_id)
+            .copied()
+            .unwrap_or(Platform::Other);
+        codes::platform(platform)
+    }
+
+    async fn freezers(
+        boxes: &Arc<dyn StorageBoxRepository>,
+    ) -> Result<Vec<FreezerCapacity>, DomainError> {
+        let mut freezers: BTreeMap<String, FreezerCapacity> = BTreeMap::new();
+        for storage_box in boxes.list(QueryOptions::new()).await? {
+            let name = storage_box
+                .location
+                .freezer
+                .clone()
+                .unwrap_or_else(|| UNASSIGNED_FREEZER.to_string());
+            let freezer = freezers
+                .entry(name.clone())
+                .or_insert_with(|| FreezerCapacity {
+                    freezer: name,
+                    boxes: 0,
+                    capacity: 0,
+                    occupied: 0,
+                    fill_percent: 0.0,
+                });
+            freezer.boxes += 1;
+            freezer.capacity += storage_box.capacity();
+            freezer.occupied += storage_box.item_count();
+        }
+
+        Ok(freezers
+            .into_values()
+            .map(|mut f| {
+                if f.capacity > 0 {
+                    f.fill_percent = f.occupied as f64 * 100.0 / f.capacity as f64;
+                }
+                f
+            })
+            .collect())
+    }
+}
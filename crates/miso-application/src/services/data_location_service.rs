@@ -0,0 +1,226 @@
+//! Data location service for the run data registry.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{DataLocation, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::DataLocationRepository;
+use miso_domain::services::StorageUsage;
+use tracing::{info, instrument};
+
+use crate::dto::{
+    DataLocationFilter, DataLocationResponse, RegisterDataLocationRequest, StorageUsageReport,
+    UpdateDataLocationRequest,
+};
+
+/// Service recording where sequencing runs' data is stored.
+pub struct DataLocationService<R: DataLocationRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: DataLocationRepository + ?Sized> DataLocationService<R> {
+    /// Creates a new data location service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Registers a location holding a run's data.
+    ///
+    /// Registering the same URI for the same run and lane again updates the
+    /// existing record instead of adding a duplicate, so pipelines can
+    /// safely re-register after a retry.
+    #[instrument(skip(self, request))]
+    pub async fn register(
+        &self,
+        run_id: EntityId,
+        request: RegisterDataLocationRequest,
+        registered_by: &str,
+    ) -> Result<DataLocationResponse, DomainError> {
+        let mut location = DataLocation::new(
+            run_id,
+            request.lane,
+            request.uri,
+            request.retention_class.unwrap_or_default(),
+            registered_by.to_string(),
+        )?;
+        location.checksum_manifest = request.checksum_manifest;
+        location.size_bytes = request.size_bytes;
+        location.platform = request.platform;
+        location.project_ids = request.project_ids.unwrap_or_default();
+
+        if let Some(existing) = self
+            .repository
+            .find_by_run(run_id)
+            .await?
+            .into_iter()
+            .find(|l| l.lane == location.lane && l.uri == location.uri)
+        {
+            if existing.is_purged() {
+                return Err(DomainError::Validation(format!(
+                    "{} was purged and cannot be registered again",
+                    existing.uri
+                )));
+            }
+            location.id = existing.id;
+            location.registered_at = existing.registered_at;
+            location.purge_requested_by = existing.purge_requested_by;
+            location.purge_requested_at = existing.purge_requested_at;
+        }
+
+        location.id = self.repository.save(&location).await?;
+
+        info!(
+            "Registered data location for run {} (ID: {}): {}",
+            run_id, location.id, location.uri
+        );
+
+        Ok(location.into())
+    }
+
+    /// Lists where a run's data is stored.
+    #[instrument(skip(self))]
+    pub async fn list_for_run(
+        &self,
+        run_id: EntityId,
+        filter: DataLocationFilter,
+    ) -> Result<Vec<DataLocationResponse>, DomainError> {
+        let locations = self.repository.find_by_run(run_id).await?;
+
+        Ok(locations
+            .into_iter()
+            .filter(|l| filter.lane.is_none() || l.lane.is_none() || l.lane == filter.lane)
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Updates a registered data location.
+    #[instrument(skip(self, request))]
+    pub async fn update(
+        &self,
+        run_id: EntityId,
+        id: EntityId,
+        request: UpdateDataLocationRequest,
+    ) -> Result<DataLocationResponse, DomainError> {
+        let mut location = self.find_location(run_id, id).await?;
+
+        if let Some(checksum_manifest) = request.checksum_manifest {
+            location.checksum_manifest = Some(checksum_manifest);
+        }
+        if let Some(retention_class) = request.retention_class {
+            location.retention_class = retention_class;
+        }
+        if let Some(size_bytes) = request.size_bytes {
+            location.size_bytes = Some(size_bytes);
+        }
+        if let Some(platform) = request.platform {
+            location.platform = Some(platform);
+        }
+        if let Some(project_ids) = request.project_ids {
+            location.project_ids = project_ids;
+        }
+
+        self.repository.save(&location).await?;
+
+        info!("Updated data location {} for run {}", id, run_id);
+
+        Ok(location.into())
+    }
+
+    /// Reports bytes stored per project and per platform.
+    #[instrument(skip(self))]
+    pub async fn storage_usage(&self) -> Result<StorageUsageReport, DomainError> {
+        let locations = self.repository.list_unpurged().await?;
+        Ok(StorageUsage::summarize(&locations).into())
+    }
+
+    /// Lists locations past their retention date that have not been
+    /// purged, oldest first, including those already requested.
+    #[instrument(skip(self))]
+    pub async fn purge_candidates(&self) -> Result<Vec<DataLocationResponse>, DomainError> {
+        let now = Utc::now();
+        let locations = self.repository.list_unpurged().await?;
+
+        Ok(locations
+            .into_iter()
+            .filter(|l| l.is_due_for_purge(now))
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Requests that a location's data be purged.
+    #[instrument(skip(self))]
+    pub async fn request_purge(
+        &self,
+        run_id: EntityId,
+        id: EntityId,
+        requested_by: &str,
+    ) -> Result<DataLocationResponse, DomainError> {
+        let mut location = self.find_location(run_id, id).await?;
+        location.request_purge(requested_by.to_string(), Utc::now())?;
+        self.repository.save(&location).await?;
+
+        info!("Purge of {} requested by {}", location.uri, requested_by);
+
+        Ok(location.into())
+    }
+
+    /// Withdraws a pending purge request.
+    #[instrument(skip(self))]
+    pub async fn cancel_purge(
+        &self,
+        run_id: EntityId,
+        id: EntityId,
+    ) -> Result<DataLocationResponse, DomainError> {
+        let mut location = self.find_location(run_id, id).await?;
+        location.cancel_purge()?;
+        self.repository.save(&location).await?;
+
+        info!("Purge request for {} withdrawn", location.uri);
+
+        Ok(location.into())
+    }
+
+    /// Confirms that a location's data has been deleted, marking it purged.
+    #[instrument(skip(self))]
+    pub async fn confirm_purge(
+        &self,
+        run_id: EntityId,
+        id: EntityId,
+        confirmed_by: &str,
+    ) -> Result<DataLocationResponse, DomainError> {
+        let mut location = self.find_location(run_id, id).await?;
+        location.confirm_purge(confirmed_by.to_string(), Utc::now())?;
+        self.repository.save(&location).await?;
+
+        info!("Purge of {} confirmed by {}", location.uri, confirmed_by);
+
+        Ok(location.into())
+    }
+
+    /// Removes a data location from the registry.
+    #[instrument(skip(self))]
+    pub async fn delete(&self, run_id: EntityId, id: EntityId) -> Result<(), DomainError> {
+        self.find_location(run_id, id).await?;
+        self.repository.delete(id).await?;
+
+        info!("Deleted data location {} for run {}", id, run_id);
+
+        Ok(())
+    }
+
+    async fn find_location(
+        &self,
+        run_id: EntityId,
+        id: EntityId,
+    ) -> Result<DataLocation, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .filter(|l| l.run_id == run_id)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "DataLocation".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
@@ -0,0 +1,531 @@
+//! Data delivery service.
+//!
+//! Projects register where their data is released to - an S3 bucket and
+//! prefix, or a directory on an SFTP host. The data-release workflow copies
+//! a run's data there, records the delivery, and then records whether the
+//! copy matched the run's checksum manifest, so the project's delivery log
+//! says what was released where, by whom, and whether it arrived intact.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{Delivery, DeliveryTarget, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    DataLocationRepository, DeliveryRepository, DeliveryTargetRepository, ProjectRepository,
+    RunRepository,
+};
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateDeliveryTargetRequest, DeliveryFilter, DeliveryResponse, DeliveryTargetResponse,
+    RecordDeliveryRequest, UpdateDeliveryTargetRequest, VerifyDeliveryRequest,
+};
+
+/// Service for delivery targets and deliveries.
+///
+/// Until the run repository is supplied, runs' data are delivered to
+/// directories named by run ID rather than run name.
+pub struct DeliveryService {
+    targets: Arc<dyn DeliveryTargetRepository>,
+    deliveries: Arc<dyn DeliveryRepository>,
+    projects: Arc<dyn ProjectRepository>,
+    data_locations: Arc<dyn DataLocationRepository>,
+    runs: Option<Arc<dyn RunRepository>>,
+    audit: AuditTrail,
+}
+
+impl DeliveryService {
+    /// Creates a new delivery service.
+    pub fn new(
+        targets: Arc<dyn DeliveryTargetRepository>,
+        deliveries: Arc<dyn DeliveryRepository>,
+        projects: Arc<dyn ProjectRepository>,
+        data_locations: Arc<dyn DataLocationRepository>,
+    ) -> Self {
+        Self {
+            targets,
+            deliveries,
+            projects,
+            data_locations,
+            runs: None,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Checks that delivered runs exist and names their directories after
+    /// them.
+    pub fn with_runs(mut self, runs: Arc<dyn RunRepository>) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Records target changes and deliveries in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Lists a project's delivery targets, retired ones included.
+    pub async fn list_targets(
+        &self,
+        project_id: EntityId,
+    ) -> Result<Vec<DeliveryTargetResponse>, DomainError> {
+        self.find_project(project_id).await?;
+        let targets = self.targets.find_by_project(project_id).await?;
+        Ok(targets.into_iter().map(Into::into).collect())
+    }
+
+    /// Registers a place a project's data is released to.
+    #[instrument(skip(self, request))]
+    pub async fn create_target(
+        &self,
+        project_id: EntityId,
+        request: CreateDeliveryTargetRequest,
+        created_by: &str,
+    ) -> Result<DeliveryTargetResponse, DomainError> {
+        let project = self.find_project(project_id).await?;
+        let mut target = DeliveryTarget::new(
+            project_id,
+            request.name,
+            request.destination,
+            created_by.to_string(),
+        )?;
+
+        target.id = self.targets.save(&target).await?;
+        self.audit
+            .created("DeliveryTarget", target.id, &target, created_by)
+            .await?;
+
+        info!(
+            "Registered delivery target {} for project {}: {}",
+            target.name, project, target.destination
+        );
+
+        Ok(target.into())
+    }
+
+    /// Changes a delivery target, or retires it.
+    #[instrument(skip(self, request))]
+    pub async fn update_target(
+        &self,
+        project_id: EntityId,
+        target_id: EntityId,
+        request: UpdateDeliveryTargetRequest,
+        updated_by: &str,
+    ) -> Result<DeliveryTargetResponse, DomainError> {
+        let before = self.find_target(project_id, target_id).await?;
+        let mut target = before.clone();
+
+        if let Some(name) = request.name {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(DomainError::Validation(
+                    "Delivery target name is required".to_string(),
+                ));
+            }
+            target.name = name;
+        }
+        if let Some(destination) = request.destination {
+            target.destination = destination.normalized()?;
+        }
+        if let Some(active) = request.active {
+            target.active = active;
+        }
+
+        self.targets.save(&target).await?;
+        self.audit
+            .updated("DeliveryTarget", target.id, &before, &target, updated_by)
+            .await?;
+
+        info!("Updated delivery target {} of project {}", target_id, project_id);
+
+        Ok(target.into())
+    }
+
+    /// Records that a run's data was copied to one of a project's targets.
+    ///
+    /// The run must have stored data, some of it the project's if its data
+    /// locations say which projects they hold.
+    #[instrument(skip(self, request))]
+    pub async fn record_delivery(
+        &self,
+        project_id: EntityId,
+        request: RecordDeliveryRequest,
+        delivered_by: &str,
+    ) -> Result<DeliveryResponse, DomainError> {
+        let target = self.find_target(project_id, request.target_id).await?;
+        if !target.active {
+            return Err(DomainError::Validation(format!(
+                "Delivery target {} is retired",
+                target.name
+            )));
+        }
+
+        let directory = match &self.runs {
+            Some(runs) => {
+                runs.find_by_id(request.run_id)
+                    .await?
+                    .ok_or_else(|| DomainError::NotFound {
+                        entity_type: "Run".to_string(),
+                        id: request.run_id.to_string(),
+                    })?
+                    .name
+            }
+            None => request.run_id.to_string(),
+        };
+
+        let locations: Vec<_> = self
+            .data_locations
+            .find_by_run(request.run_id)
+            .await?
+            .into_iter()
+            .filter(|l| !l.is_purged())
+            .collect();
+        if locations.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Run {} has no stored data to deliver",
+                request.run_id
+            )));
+        }
+        if locations.iter().any(|l| !l.project_ids.is_empty())
+            && !locations.iter().any(|l| l.project_ids.contains(&project_id))
+        {
+            return Err(DomainError::Validation(format!(
+                "Run {} holds no data of project {}",
+                request.run_id, project_id
+            )));
+        }
+
+        let uri = match request.uri {
+            Some(uri) if !uri.trim().is_empty() => uri.trim().to_string(),
+            _ => target.destination.uri_of(&directory),
+        };
+        let mut delivery = Delivery::new(&target, request.run_id, uri, delivered_by.to_string());
+        delivery.checksum_manifest = request.checksum_manifest.or_else(|| {
+            locations
+                .iter()
+                .find(|l| l.lane.is_none() && l.checksum_manifest.is_some())
+                .and_then(|l| l.checksum_manifest.clone())
+        });
+        delivery.size_bytes = request.size_bytes;
+
+        delivery.id = self.deliveries.save(&delivery).await?;
+        self.audit
+            .created("Delivery", delivery.id, &delivery, delivered_by)
+            .await?;
+
+        info!(
+            "Delivered run {} for project {} to {}",
+            delivery.run_id, project_id, delivery.uri
+        );
+
+        Ok(delivery.into())
+    }
+
+    /// Records whether a delivered copy matched its checksum manifest.
+    #[instrument(skip(self, request))]
+    pub async fn verify_delivery(
+        &self,
+        project_id: EntityId,
+        delivery_id: EntityId,
+        request: VerifyDeliveryRequest,
+        verified_by: &str,
+    ) -> Result<DeliveryResponse, DomainError> {
+        let before = self
+            .deliveries
+            .find_by_id(delivery_id)
+            .await?
+            .filter(|d| d.project_id == project_id)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Delivery".to_string(),
+                id: delivery_id.to_string(),
+            })?;
+        let mut delivery = before.clone();
+        delivery.verify(
+            request.complete,
+            request.note,
+            verified_by.to_string(),
+            Utc::now(),
+        )?;
+
+        self.deliveries.save(&delivery).await?;
+        self.audit
+            .updated("Delivery", delivery.id, &before, &delivery, verified_by)
+            .await?;
+
+        if request.complete {
+            info!("Verified delivery {} to {}", delivery.id, delivery.uri);
+        } else {
+            warn!(
+                "Delivery {} to {} failed verification: {}",
+                delivery.id,
+                delivery.uri,
+                delivery.note.as_deref().unwrap_or("no details")
+            );
+        }
+
+        Ok(delivery.into())
+    }
+
+    /// Lists a project's deliveries, newest first.
+    pub async fn list_deliveries(
+        &self,
+        project_id: EntityId,
+        filter: DeliveryFilter,
+    ) -> Result<Vec<DeliveryResponse>, DomainError> {
+        self.find_project(project_id).await?;
+        let deliveries = self.deliveries.find_by_project(project_id).await?;
+
+        Ok(deliveries
+            .into_iter()
+            .filter(|d| filter.run_id.is_none_or(|id| d.run_id == id))
+            .filter(|d| filter.target_id.is_none_or(|id| d.target_id == id))
+            .filter(|d| filter.status.is_none_or(|status| d.status == status))
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Lists where a run's data has been delivered, newest first.
+    pub async fn list_for_run(&self, run_id: EntityId) -> Result<Vec<DeliveryResponse>, DomainError> {
+        let deliveries = self.deliveries.find_by_run(run_id).await?;
+        Ok(deliveries.into_iter().map(Into::into).collect())
+    }
+
+    /// Finds a project, returning its code.
+    async fn find_project(&self, project_id: EntityId) -> Result<String, DomainError> {
+        self.projects
+            .find_by_id(project_id)
+            .await?
+            .map(|p| p.code)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: project_id.to_string(),
+            })
+    }
+
+    /// Finds one of a project's delivery targets.
+    async fn find_target(
+        &self,
+        project_id: EntityId,
+        target_id: EntityId,
+    ) -> Result<DeliveryTarget, DomainError> {
+        self.targets
+            .find_by_id(target_id)
+            .await?
+            .filter(|t| t.project_id == project_id)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "DeliveryTarget".to_string(),
+                id: target_id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        DataLocation, DeliveryDestination, DeliveryStatus, Project, RetentionClass,
+    };
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryTargets(Mutex<Vec<DeliveryTarget>>);
+
+    #[async_trait]
+    impl DeliveryTargetRepository for InMemoryTargets {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<DeliveryTarget>, DomainError> {
+            Ok(self.0.lock().unwrap().iter().find(|t| t.id == id).cloned())
+        }
+        async fn find_by_project(
+            &self,
+            project_id: EntityId,
+        ) -> Result<Vec<DeliveryTarget>, DomainError> {
+            let targets = self.0.lock().unwrap();
+            Ok(targets.iter().filter(|t| t.project_id == project_id).cloned().collect())
+        }
+        async fn save(&self, target: &DeliveryTarget) -> Result<EntityId, DomainError> {
+            let mut targets = self.0.lock().unwrap();
+            let mut target = target.clone();
+            if target.id == 0 {
+                target.id = targets.len() as EntityId + 1;
+            }
+            targets.retain(|t| t.id != target.id);
+            targets.push(target.clone());
+            Ok(target.id)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryDeliveries(Mutex<Vec<Delivery>>);
+
+    #[async_trait]
+    impl DeliveryRepository for InMemoryDeliveries {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Delivery>, DomainError> {
+            Ok(self.0.lock().unwrap().iter().find(|d| d.id == id).cloned())
+        }
+        async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Delivery>, DomainError> {
+            let deliveries = self.0.lock().unwrap();
+            Ok(deliveries.iter().rev().filter(|d| d.project_id == project_id).cloned().collect())
+        }
+        async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<Delivery>, DomainError> {
+            let deliveries = self.0.lock().unwrap();
+            Ok(deliveries.iter().rev().filter(|d| d.run_id == run_id).cloned().collect())
+        }
+        async fn save(&self, delivery: &Delivery) -> Result<EntityId, DomainError> {
+            let mut deliveries = self.0.lock().unwrap();
+            let mut delivery = delivery.clone();
+            if delivery.id == 0 {
+                delivery.id = deliveries.len() as EntityId + 1;
+                deliveries.push(delivery.clone());
+            } else if let Some(existing) = deliveries.iter_mut().find(|d| d.id == delivery.id) {
+                *existing = delivery.clone();
+            }
+            Ok(delivery.id)
+        }
+    }
+
+    struct Locations(Vec<DataLocation>);
+
+    #[async_trait]
+    impl DataLocationRepository for Locations {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<DataLocation>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<DataLocation>, DomainError> {
+            Ok(self.0.iter().filter(|l| l.run_id == run_id).cloned().collect())
+        }
+        async fn list_unpurged(&self) -> Result<Vec<DataLocation>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &DataLocation) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct Projects(Vec<Project>);
+
+    #[async_trait]
+    impl ProjectRepository for Projects {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Project>, DomainError> {
+            Ok(self.0.iter().find(|p| p.id == id).cloned())
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn location(run_id: EntityId, project_ids: Vec<EntityId>) -> DataLocation {
+        let mut location = DataLocation::new(
+            run_id,
+            None,
+            format!("/data/runs/{}", run_id),
+            RetentionClass::Standard,
+            "pipeline".to_string(),
+        )
+        .unwrap();
+        location.checksum_manifest = Some(format!("/data/runs/{}/md5sums.txt", run_id));
+        location.project_ids = project_ids;
+        location
+    }
+
+    fn record(target_id: EntityId, run_id: EntityId) -> RecordDeliveryRequest {
+        RecordDeliveryRequest {
+            target_id,
+            run_id,
+            uri: None,
+            checksum_manifest: None,
+            size_bytes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runs_are_delivered_to_project_targets_and_verified() {
+        let service = DeliveryService::new(
+            Arc::new(InMemoryTargets::default()),
+            Arc::new(InMemoryDeliveries::default()),
+            Arc::new(Projects(vec![
+                Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string()),
+                Project::new(2, "PROJ2".to_string(), "Two".to_string(), "lm".to_string()),
+            ])),
+            Arc::new(Locations(vec![location(42, vec![1]), location(43, vec![2])])),
+        );
+
+        let target = service
+            .create_target(
+                1,
+                CreateDeliveryTargetRequest {
+                    name: "Collaborator".to_string(),
+                    destination: DeliveryDestination::S3 {
+                        bucket: "collab-data".to_string(),
+                        prefix: "/releases/".to_string(),
+                    },
+                },
+                "lm",
+            )
+            .await
+            .unwrap();
+        assert_eq!(target.uri, "s3://collab-data/releases");
+
+        let delivery = service.record_delivery(1, record(target.id, 42), "pipeline").await.unwrap();
+        assert_eq!(delivery.uri, "s3://collab-data/releases/42");
+        assert_eq!(delivery.checksum_manifest.as_deref(), Some("/data/runs/42/md5sums.txt"));
+
+        // Another project's run, a run without data, and another project's target
+        assert!(service.record_delivery(1, record(target.id, 43), "pipeline").await.is_err());
+        assert!(service.record_delivery(1, record(target.id, 44), "pipeline").await.is_err());
+        assert!(matches!(
+            service.record_delivery(2, record(target.id, 43), "pipeline").await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        let verify = |complete| VerifyDeliveryRequest { complete, note: None };
+        let verified = service.verify_delivery(1, delivery.id, verify(true), "pipeline").await.unwrap();
+        assert_eq!(verified.status, DeliveryStatus::Verified);
+        assert!(service.verify_delivery(1, delivery.id, verify(false), "pipeline").await.is_err());
+
+        service
+            .update_target(
+                1,
+                target.id,
+                UpdateDeliveryTargetRequest {
+                    active: Some(false),
+                    ..Default::default()
+                },
+                "lm",
+            )
+            .await
+            .unwrap();
+        assert!(service.record_delivery(1, record(target.id, 42), "pipeline").await.is_err());
+
+        let filter = DeliveryFilter {
+            status: Some(DeliveryStatus::Verified),
+            ..Default::default()
+        };
+        assert_eq!(service.list_deliveries(1, filter).await.unwrap().len(), 1);
+        assert_eq!(service.list_for_run(42).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,813 @@
+//! Erasure service.
+//!
+//! Erasing an identity scrubs its external name and the descriptions of it
+//! and every sample derived from it, including samples it was pooled
+//! into, and removes the name wherever the
+//! change log, audit log, event store and duplicate review recorded it.
+//! Erased values keep their hashes, so the logs and events are still
+//! checked against their hash chains. The samples
+//! stay, with their names, barcodes, QC and amounts, so project counts
+//! and downstream libraries still add up.
+//!
+//! The external names are kept only as SHA-256 hashes of their normalized
+//! form, which is enough to refuse a new identity with an erased name.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use miso_domain::entities::{ChangeLogEntry, Erasure, Sample, SampleClass, SampleDetails};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    AuditLogRepository, ChangeLogRepository, ErasureRepository, EventStoreRepository,
+    PossibleDuplicateRepository, SampleCompositionRepository, SampleRepository,
+};
+use miso_domain::services::normalize_external_name;
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument};
+
+use crate::dto::{EraseIdentityRequest, ErasureResponse};
+
+/// Audited sample fields that can identify a participant.
+const IDENTIFYING_FIELDS: &[&str] = &["details.external_name", "description"];
+
+/// Service for erasing withdrawn participants' identifying details.
+pub struct ErasureService {
+    samples: Arc<dyn SampleRepository>,
+    change_log: Arc<dyn ChangeLogRepository>,
+    audit_log: Arc<dyn AuditLogRepository>,
+    possible_duplicates: Arc<dyn PossibleDuplicateRepository>,
+    erasures: Arc<dyn ErasureRepository>,
+    compositions: Option<Arc<dyn SampleCompositionRepository>>,
+    events: Option<Arc<dyn EventStoreRepository>>,
+}
+
+impl ErasureService {
+    /// Creates a new erasure service.
+    pub fn new(
+        samples: Arc<dyn SampleRepository>,
+        change_log: Arc<dyn ChangeLogRepository>,
+        audit_log: Arc<dyn AuditLogRepository>,
+        possible_duplicates: Arc<dyn PossibleDuplicateRepository>,
+        erasures: Arc<dyn ErasureRepository>,
+    ) -> Self {
+        Self {
+            samples,
+            change_log,
+            audit_log,
+            possible_duplicates,
+            erasures,
+            compositions: None,
+            events: None,
+        }
+    }
+
+    /// Also erases samples the identity was pooled into, and those derived
+    /// from them.
+    pub fn with_compositions(mut self, compositions: Arc<dyn SampleCompositionRepository>) -> Self {
+        self.compositions = Some(compositions);
+        self
+    }
+
+    /// Also erases the identifying fields from the samples' stored events
+    /// and the event store's read model.
+    pub fn with_event_store(mut self, events: Arc<dyn EventStoreRepository>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Erases an identity's identifying details, and those of the samples
+    /// derived from it.
+    ///
+    /// Fails with a concurrent modification error, erasing nothing, if any
+    /// of the samples changes while being erased.
+    #[instrument(skip(self, request), fields(identity_id = request.identity_id))]
+    pub async fn erase_identity(
+        &self,
+        request: EraseIdentityRequest,
+        erased_by: &str,
+    ) -> Result<ErasureResponse, DomainError> {
+        let identity_id = request.identity_id;
+        let identity = self
+            .samples
+            .find_by_id(identity_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: identity_id.to_string(),
+            })?;
+        let external_name = match &identity.details {
+            SampleDetails::Detailed(details) if details.sample_class == SampleClass::Identity => {
+                details.external_name.clone().ok_or_else(|| {
+                    DomainError::Validation(format!(
+                        "Identity {} has already been erased",
+                        identity.name
+                    ))
+                })?
+            }
+            _ => {
+                return Err(DomainError::Validation(format!(
+                    "Sample {} is not an identity",
+                    identity.name
+                )))
+            }
+        };
+
+        let mut samples = self.with_descendants(identity).await?;
+        let mut erasure = Erasure::new(
+            identity_id,
+            hash_external_names(&external_name),
+            samples.len() as u32,
+            request.reason,
+            erased_by.to_string(),
+        )?;
+
+        for sample in &mut samples {
+            sample.erase_identifying_details();
+        }
+        let conflicts = self.samples.update_all_versioned(&samples).await?;
+        if let Some(conflict) = conflicts.first() {
+            return Err(DomainError::ConcurrentModification {
+                entity_type: "Sample".to_string(),
+                id: conflict.id.to_string(),
+            });
+        }
+
+        // Longest first, so the full name goes before the names in it
+        let mut names: Vec<&str> = external_name
+            .split(',')
+            .map(str::trim)
+            .chain(std::iter::once(external_name.as_str()))
+            .filter(|name| !name.is_empty())
+            .collect();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        names.dedup();
+
+        let mut change_log = Vec::new();
+        let mut audit_log = Vec::new();
+        let mut events = Vec::new();
+        for sample in &samples {
+            for mut entry in self.change_log.find_by_entity("Sample", sample.id).await? {
+                let mut redacted = false;
+                for name in &names {
+                    redacted |= entry.redact(name);
+                }
+                if redacted {
+                    change_log.push(entry);
+                }
+            }
+            for mut entry in self.audit_log.find_by_entity("Sample", sample.id).await? {
+                if entry.erase_fields(IDENTIFYING_FIELDS) {
+                    audit_log.push(entry);
+                }
+            }
+            if let Some(store) = &self.events {
+                for mut event in store.find_by_entity("Sample", sample.id).await? {
+                    if event.erase_fields(IDENTIFYING_FIELDS) {
+                        events.push(event);
+                    }
+                }
+            }
+        }
+        self.change_log.update_all(&change_log).await?;
+        self.audit_log.update_all(&audit_log).await?;
+        if let Some(store) = &self.events {
+            store.update_all(&events).await?;
+        }
+
+        for mut duplicate in self.possible_duplicates.find_by_identity(identity_id).await? {
+            duplicate.erase_names_of(identity_id);
+            self.possible_duplicates.save(&duplicate).await?;
+        }
+
+        erasure.id = self.erasures.save(&erasure).await?;
+        self.change_log
+            .save_all(&[ChangeLogEntry::new(
+                "Sample",
+                identity_id,
+                format!("Identifying details erased from {} samples", samples.len()),
+                erased_by,
+            )
+            .with_reason(Some(erasure.reason.clone()))])
+            .await?;
+
+        info!(
+            "Erased identity {} and {} derived samples",
+            identity_id,
+            samples.len() - 1
+        );
+
+        Ok(erasure.into())
+    }
+
+    /// Lists erasures, newest first.
+    #[instrument(skip(self))]
+    pub async fn list_erasures(&self) -> Result<Vec<ErasureResponse>, DomainError> {
+        let erasures = self.erasures.list().await?;
+        Ok(erasures.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the identity and every sample below it in the hierarchy,
+    /// starting from the identity and the samples it was pooled into.
+    async fn with_descendants(&self, identity: Sample) -> Result<Vec<Sample>, DomainError> {
+        let mut seen = HashSet::from([identity.id]);
+        let pooled = match &self.compositions {
+            Some(compositions) => {
+                let ids: Vec<_> = compositions
+                    .find_by_identity(identity.id)
+                    .await?
+                    .into_iter()
+                    .map(|c| c.sample_id)
+                    .collect();
+                if ids.is_empty() {
+                    Vec::new()
+                } else {
+                    self.samples.find_by_ids(&ids).await?
+                }
+            }
+            None => Vec::new(),
+        };
+        let mut samples = vec![identity];
+        for sample in pooled {
+            if seen.insert(sample.id) {
+                samples.push(sample);
+            }
+        }
+        let mut next = 0;
+        while next < samples.len() {
+            let parent = samples[next].id;
+            for child in self.samples.find_by_parent(parent).await? {
+                if seen.insert(child.id) {
+                    samples.push(child);
+                }
+            }
+            next += 1;
+        }
+        Ok(samples)
+    }
+}
+
+/// Returns the hex-encoded SHA-256 hashes of an identity's normalized
+/// external names.
+pub fn hash_external_names(external_name: &str) -> Vec<String> {
+    let mut hashes: Vec<String> = external_name
+        .split(',')
+        .map(normalize_external_name)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            Sha256::digest(name.as_bytes())
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect()
+        })
+        .collect();
+    hashes.sort();
+    hashes.dedup();
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        AuditAction, EntityId, AuditEntry, AuditQuery, EntitySnapshot, FieldChange,
+        PossibleDuplicate, SampleComposition, StoredEvent, ERASED,
+    };
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::audit::AuditTrail;
+    use crate::services::EventStoreService;
+
+    #[derive(Default)]
+    struct InMemorySamples {
+        samples: Mutex<HashMap<EntityId, Sample>>,
+    }
+
+    #[async_trait]
+    impl SampleRepository for InMemorySamples {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok(self.samples.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, parent_id: EntityId) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(samples
+                .values()
+                .filter(|s| s.parent_id() == Some(parent_id))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(ids.iter().filter_map(|id| samples.get(id).cloned()).collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            updates: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            let mut samples = self.samples.lock().unwrap();
+            for u in updates {
+                let mut stored = u.clone();
+                stored.version += 1;
+                samples.insert(u.id, stored);
+            }
+            Ok(Vec::new())
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryChangeLog {
+        entries: Mutex<Vec<ChangeLogEntry>>,
+    }
+
+    #[async_trait]
+    impl ChangeLogRepository for InMemoryChangeLog {
+        async fn find_by_entity(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .filter(|e| e.entity_type == entity_type && e.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+        async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            let mut stored = self.entries.lock().unwrap();
+            for entry in entries {
+                let mut entry = entry.clone();
+                entry.id = stored.len() as EntityId + 1;
+                stored.push(entry);
+            }
+            Ok(())
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            let mut stored = self.entries.lock().unwrap();
+            for entry in entries {
+                if let Some(e) = stored.iter_mut().find(|e| e.id == entry.id) {
+                    *e = entry.clone();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryAuditLog {
+        entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditLogRepository for InMemoryAuditLog {
+        async fn find(&self, _: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn save_all(&self, _: &[AuditEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_entity(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Vec<AuditEntry>, DomainError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .filter(|e| e.entity_type == entity_type && e.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+        async fn update_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+            let mut stored = self.entries.lock().unwrap();
+            for entry in entries {
+                if let Some(e) = stored.iter_mut().find(|e| e.id == entry.id) {
+                    *e = entry.clone();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryDuplicates {
+        duplicates: Mutex<Vec<PossibleDuplicate>>,
+    }
+
+    #[async_trait]
+    impl PossibleDuplicateRepository for InMemoryDuplicates {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<PossibleDuplicate>, DomainError> {
+            unimplemented!()
+        }
+        async fn list_unresolved(&self) -> Result<Vec<PossibleDuplicate>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_identity(
+            &self,
+            identity_id: EntityId,
+        ) -> Result<Vec<PossibleDuplicate>, DomainError> {
+            let duplicates = self.duplicates.lock().unwrap();
+            Ok(duplicates
+                .iter()
+                .filter(|d| d.identity_id == identity_id || d.match_id == identity_id)
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, duplicate: &PossibleDuplicate) -> Result<EntityId, DomainError> {
+            let mut duplicates = self.duplicates.lock().unwrap();
+            duplicates.retain(|d| d.id != duplicate.id);
+            duplicates.push(duplicate.clone());
+            Ok(duplicate.id)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryErasures {
+        erasures: Mutex<Vec<Erasure>>,
+    }
+
+    #[async_trait]
+    impl ErasureRepository for InMemoryErasures {
+        async fn list(&self) -> Result<Vec<Erasure>, DomainError> {
+            Ok(self.erasures.lock().unwrap().clone())
+        }
+        async fn is_erased(&self, name_hashes: &[String]) -> Result<bool, DomainError> {
+            let erasures = self.erasures.lock().unwrap();
+            Ok(erasures
+                .iter()
+                .any(|e| e.name_hashes.iter().any(|h| name_hashes.contains(h))))
+        }
+        async fn save(&self, erasure: &Erasure) -> Result<EntityId, DomainError> {
+            let mut erasures = self.erasures.lock().unwrap();
+            let mut erasure = erasure.clone();
+            erasure.id = erasures.len() as EntityId + 1;
+            erasures.push(erasure.clone());
+            Ok(erasure.id)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryEvents {
+        events: Mutex<Vec<StoredEvent>>,
+    }
+
+    #[async_trait]
+    impl EventStoreRepository for InMemoryEvents {
+        async fn last(&self) -> Result<Option<StoredEvent>, DomainError> {
+            Ok(self.events.lock().unwrap().last().cloned())
+        }
+        async fn append(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+            let mut stored = self.events.lock().unwrap();
+            for event in events {
+                let mut event = event.clone();
+                event.sequence = stored.len() as i64 + 1;
+                stored.push(event);
+            }
+            Ok(())
+        }
+        async fn list_after(
+            &self,
+            sequence: i64,
+            limit: u64,
+        ) -> Result<Vec<StoredEvent>, DomainError> {
+            let stored = self.events.lock().unwrap();
+            Ok(stored
+                .iter()
+                .filter(|e| e.sequence > sequence)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_entity(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Vec<StoredEvent>, DomainError> {
+            let stored = self.events.lock().unwrap();
+            Ok(stored
+                .iter()
+                .filter(|e| e.entity_type == entity_type && e.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+        async fn update_all(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+            let mut stored = self.events.lock().unwrap();
+            for event in events {
+                if let Some(e) = stored.iter_mut().find(|e| e.sequence == event.sequence) {
+                    *e = event.clone();
+                }
+            }
+            Ok(())
+        }
+        async fn find_snapshot(
+            &self,
+            _: &str,
+            _: EntityId,
+        ) -> Result<Option<EntitySnapshot>, DomainError> {
+            unimplemented!()
+        }
+        async fn replace_snapshots(&self, _: &[EntitySnapshot]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct InMemoryCompositions {
+        compositions: Vec<SampleComposition>,
+    }
+
+    #[async_trait]
+    impl SampleCompositionRepository for InMemoryCompositions {
+        async fn find_by_samples(&self, _: &[EntityId]) -> Result<Vec<SampleComposition>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_identity(
+            &self,
+            identity_id: EntityId,
+        ) -> Result<Vec<SampleComposition>, DomainError> {
+            Ok(self
+                .compositions
+                .iter()
+                .filter(|c| c.identity_ids.contains(&identity_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, _: &SampleComposition) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn sample(id: EntityId, parent_id: Option<EntityId>, external_name: &str) -> Sample {
+        let mut sample = Sample::new_identity(
+            id,
+            format!("PAT_{}", id),
+            Barcode::new_unchecked(format!("SAM{}", id)),
+            1,
+            external_name.to_string(),
+            "tech".to_string(),
+        );
+        sample.description = Some(format!("Collected from {}", external_name));
+        if let (SampleDetails::Detailed(details), Some(parent_id)) =
+            (&mut sample.details, parent_id)
+        {
+            details.sample_class = SampleClass::Tissue;
+            details.parent_id = Some(parent_id);
+        }
+        sample
+    }
+
+    fn request(identity_id: EntityId) -> EraseIdentityRequest {
+        EraseIdentityRequest {
+            identity_id,
+            reason: "Withdrawal REQ-42".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_erasing_an_identity_scrubs_its_samples_and_logs() {
+        let samples = Arc::new(InMemorySamples::default());
+        for s in [
+            sample(1, None, "MRN-1, NHS-9"),
+            sample(2, Some(1), "MRN-1"),
+            sample(3, None, "MRN-7"),
+        ] {
+            samples.samples.lock().unwrap().insert(s.id, s);
+        }
+        let change_log = Arc::new(InMemoryChangeLog::default());
+        change_log
+            .save_all(&[
+                ChangeLogEntry::new("Sample", 2, "Received from MRN-1's clinic", "tech"),
+                ChangeLogEntry::new("Sample", 3, "Received from MRN-7's clinic", "tech"),
+            ])
+            .await
+            .unwrap();
+        let audit_log = Arc::new(InMemoryAuditLog::default());
+        let mut created = AuditEntry::new(
+            "Sample",
+            1,
+            AuditAction::Create,
+            vec![
+                FieldChange::new(
+                    "details.external_name",
+                    None,
+                    Some("\"MRN-1, NHS-9\"".to_string()),
+                ),
+                FieldChange::new("name", None, Some("\"PAT_1\"".to_string())),
+            ],
+            "tech",
+        );
+        created.id = 1;
+        audit_log.entries.lock().unwrap().push(created);
+        let duplicates = Arc::new(InMemoryDuplicates::default());
+        let mut duplicate =
+            PossibleDuplicate::new(3, 1, "MRN-7".to_string(), "MRN-1".to_string(), 1, "tech".to_string());
+        duplicate.id = 1;
+        duplicates.duplicates.lock().unwrap().push(duplicate);
+        let erasures = Arc::new(InMemoryErasures::default());
+        let service = ErasureService::new(
+            samples.clone(),
+            change_log.clone(),
+            audit_log.clone(),
+            duplicates.clone(),
+            erasures.clone(),
+        );
+
+        let erasure = service.erase_identity(request(1), "dpo").await.unwrap();
+        assert_eq!(erasure.samples_erased, 2);
+        assert_eq!(erasure.erased_by, "dpo");
+
+        let stored = samples.samples.lock().unwrap().clone();
+        for id in [1, 2] {
+            assert_eq!(stored[&id].description, None);
+            assert_eq!(stored[&id].version, 2);
+            let SampleDetails::Detailed(details) = &stored[&id].details else {
+                panic!("sample {} is not detailed", id);
+            };
+            assert_eq!(details.external_name, None);
+        }
+        assert!(stored[&3].description.is_some());
+
+        let entries = change_log.entries.lock().unwrap().clone();
+        assert_eq!(entries[0].summary, format!("Received from {}'s clinic", ERASED));
+        assert_eq!(entries[1].summary, "Received from MRN-7's clinic");
+        assert_eq!(entries[2].entity_id, 1);
+        assert_eq!(entries[2].reason.as_deref(), Some("Withdrawal REQ-42"));
+
+        let audited = audit_log.entries.lock().unwrap()[0].clone();
+        assert_eq!(audited.changes[0].old, None);
+        assert_eq!(audited.changes[0].new, Some(format!("\"{}\"", ERASED)));
+        assert_eq!(audited.changes[1].new.as_deref(), Some("\"PAT_1\""));
+
+        let duplicate = duplicates.duplicates.lock().unwrap()[0].clone();
+        assert_eq!(duplicate.external_name, "MRN-7");
+        assert_eq!(duplicate.matched_name, ERASED);
+
+        // Both of its names are now refused
+        assert!(erasures
+            .is_erased(&hash_external_names("nhs9"))
+            .await
+            .unwrap());
+        assert!(erasures
+            .is_erased(&hash_external_names("mrn 1"))
+            .await
+            .unwrap());
+        assert!(!erasures
+            .is_erased(&hash_external_names("MRN-7"))
+            .await
+            .unwrap());
+
+        // An identity can only be erased once, and only identities can be
+        assert!(service.erase_identity(request(1), "dpo").await.is_err());
+        assert!(service.erase_identity(request(2), "dpo").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_erasing_an_identity_scrubs_samples_it_was_pooled_into() {
+        // 4 pooled from 1 and 3, 5 <- 4
+        let samples = Arc::new(InMemorySamples::default());
+        let mut pooled = Sample::new_pooled(
+            4,
+            "POOL_4".to_string(),
+            Barcode::new_unchecked("SAM4".to_string()),
+            1,
+            "tech".to_string(),
+        );
+        pooled.description = Some("Pooled from MRN-1 and MRN-7".to_string());
+        for s in [
+            sample(1, None, "MRN-1"),
+            sample(3, None, "MRN-7"),
+            pooled,
+            sample(5, Some(4), "MRN-1"),
+        ] {
+            samples.samples.lock().unwrap().insert(s.id, s);
+        }
+        let compositions = Arc::new(InMemoryCompositions {
+            compositions: vec![SampleComposition {
+                sample_id: 4,
+                identity_ids: vec![1, 3],
+            }],
+        });
+        let service = ErasureService::new(
+            samples.clone(),
+            Arc::new(InMemoryChangeLog::default()),
+            Arc::new(InMemoryAuditLog::default()),
+            Arc::new(InMemoryDuplicates::default()),
+            Arc::new(InMemoryErasures::default()),
+        )
+        .with_compositions(compositions);
+
+        let erasure = service.erase_identity(request(1), "dpo").await.unwrap();
+        assert_eq!(erasure.samples_erased, 3);
+
+        let stored = samples.samples.lock().unwrap().clone();
+        for id in [1, 4, 5] {
+            assert_eq!(stored[&id].description, None);
+        }
+        assert!(stored[&3].description.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_erasing_an_identity_scrubs_its_stored_events() {
+        let samples = Arc::new(InMemorySamples::default());
+        let events = Arc::new(InMemoryEvents::default());
+        let audit = AuditTrail::default().with_event_store(events.clone());
+        for s in [
+            sample(1, None, "MRN-1"),
+            sample(2, Some(1), "MRN-1"),
+            sample(3, None, "MRN-7"),
+        ] {
+            audit.created("Sample", s.id, &s, "tech").await.unwrap();
+            samples.samples.lock().unwrap().insert(s.id, s);
+        }
+        let before = events.events.lock().unwrap().clone();
+        let service = ErasureService::new(
+            samples,
+            Arc::new(InMemoryChangeLog::default()),
+            Arc::new(InMemoryAuditLog::default()),
+            Arc::new(InMemoryDuplicates::default()),
+            Arc::new(InMemoryErasures::default()),
+        )
+        .with_event_store(events.clone());
+
+        service.erase_identity(request(1), "dpo").await.unwrap();
+
+        let stored = events.events.lock().unwrap().clone();
+        for event in &stored[..2] {
+            let state = event.state.as_deref().unwrap();
+            assert!(!state.contains("MRN-1"));
+            assert!(state.contains("PAT_"));
+            assert!(!event.erased_hashes.is_empty());
+        }
+        assert_eq!(stored[2], before[2]);
+        assert!(stored.iter().zip(&before).all(|(a, b)| a.hash == b.hash));
+
+        // The chain still holds, with the erased events checked in full
+        let report = EventStoreService::new(events).replay(false).await.unwrap();
+        assert!(report.is_intact());
+    }
+}
@@ -0,0 +1,301 @@
+//! Event store service for replaying and reading the event store.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{EntityId, EntitySnapshot, StoredEvent};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::EventStoreRepository;
+use tracing::{info, instrument, warn};
+
+use crate::audit::event_hash;
+use crate::dto::{BrokenLink, EntitySnapshotResponse, ReplayReport};
+
+/// Events read at a time while replaying.
+const REPLAY_PAGE_SIZE: u64 = 500;
+
+/// Service for replaying the event store and reading its read model.
+pub struct EventStoreService<E: EventStoreRepository + ?Sized> {
+    events: Arc<E>,
+}
+
+impl<E: EventStoreRepository + ?Sized> EventStoreService<E> {
+    /// Creates a new event store service.
+    pub fn new(events: Arc<E>) -> Self {
+        Self { events }
+    }
+
+    /// Replays every stored event in order, checking that each fits the
+    /// hash chain, and works out each entity's current state.
+    ///
+    /// With `rebuild`, the read model is then replaced with those states.
+    /// Replay stops at the first event that doesn't fit the chain, and the
+    /// read model is left alone, as it can't be trusted from there on.
+    #[instrument(skip(self))]
+    pub async fn replay(&self, rebuild: bool) -> Result<ReplayReport, DomainError> {
+        let started_at = Utc::now();
+
+        let mut states: BTreeMap<(String, EntityId), EntitySnapshot> = BTreeMap::new();
+        let mut previous_hash = String::new();
+        let mut last_sequence = None;
+        let mut replayed = 0;
+        let mut broken_link = None;
+        'pages: loop {
+            let page = self
+                .events
+                .list_after(last_sequence.unwrap_or(0), REPLAY_PAGE_SIZE)
+                .await?;
+            let full_page = page.len() as u64 == REPLAY_PAGE_SIZE;
+
+            for event in page {
+                if let Some(reason) = check_link(&event, &previous_hash) {
+                    broken_link = Some(BrokenLink {
+                        sequence: event.sequence,
+                        reason,
+                    });
+                    break 'pages;
+                }
+                previous_hash = event.hash;
+                last_sequence = Some(event.sequence);
+                replayed += 1;
+
+                let key = (event.entity_type, event.entity_id);
+                match event.state {
+                    Some(state) => {
+                        let snapshot = EntitySnapshot {
+                            entity_type: key.0.clone(),
+                            entity_id: key.1,
+                            state,
+                            sequence: event.sequence,
+                        };
+                        states.insert(key, snapshot);
+                    }
+                    None => {
+                        states.remove(&key);
+                    }
+                }
+            }
+            if !full_page {
+                break;
+            }
+        }
+
+        let rebuilt = rebuild && broken_link.is_none();
+        if rebuilt {
+            let snapshots: Vec<EntitySnapshot> = states.values().cloned().collect();
+            self.events.replace_snapshots(&snapshots).await?;
+        }
+
+        match &broken_link {
+            Some(link) => warn!(
+                "Event store chain breaks at event {}: {}",
+                link.sequence, link.reason
+            ),
+            None => info!(
+                "Replayed {} events into {} entities{}",
+                replayed,
+                states.len(),
+                if rebuilt { "; read model rebuilt" } else { "" }
+            ),
+        }
+
+        Ok(ReplayReport {
+            started_at,
+            completed_at: Utc::now(),
+            events: replayed,
+            entities: states.len(),
+            last_sequence,
+            broken_link,
+            rebuilt,
+        })
+    }
+
+    /// Gets an entity's current state from the read model.
+    #[instrument(skip(self))]
+    pub async fn get_snapshot(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<EntitySnapshotResponse, DomainError> {
+        let snapshot = self
+            .events
+            .find_snapshot(entity_type, entity_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: entity_type.to_string(),
+                id: entity_id.to_string(),
+            })?;
+
+        Ok(snapshot.into())
+    }
+}
+
+/// Explains why an event doesn't follow the one whose hash is given, if it
+/// doesn't.
+fn check_link(event: &StoredEvent, previous_hash: &str) -> Option<String> {
+    if event.previous_hash != previous_hash {
+        Some("Its previous hash is not the hash of the event before it".to_string())
+    } else if event.hash != event_hash(event) {
+        Some("Its hash does not match its contents".to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use serde::Serialize;
+
+    use miso_domain::entities::ERASED;
+
+    use super::*;
+    use crate::audit::AuditTrail;
+
+    #[derive(Default)]
+    struct InMemoryEvents {
+        events: Mutex<Vec<StoredEvent>>,
+        snapshots: Mutex<Vec<EntitySnapshot>>,
+    }
+
+    #[async_trait]
+    impl EventStoreRepository for InMemoryEvents {
+        async fn last(&self) -> Result<Option<StoredEvent>, DomainError> {
+            Ok(self.events.lock().unwrap().last().cloned())
+        }
+        async fn append(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+            let mut stored = self.events.lock().unwrap();
+            for event in events {
+                let mut event = event.clone();
+                event.sequence = stored.len() as i64 + 1;
+                stored.push(event);
+            }
+            Ok(())
+        }
+        async fn list_after(
+            &self,
+            sequence: i64,
+            limit: u64,
+        ) -> Result<Vec<StoredEvent>, DomainError> {
+            let stored = self.events.lock().unwrap();
+            Ok(stored
+                .iter()
+                .filter(|e| e.sequence > sequence)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_entity(
+            &self,
+            _: &str,
+            _: EntityId,
+        ) -> Result<Vec<StoredEvent>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[StoredEvent]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn find_snapshot(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Option<EntitySnapshot>, DomainError> {
+            let snapshots = self.snapshots.lock().unwrap();
+            Ok(snapshots
+                .iter()
+                .find(|s| s.entity_type == entity_type && s.entity_id == entity_id)
+                .cloned())
+        }
+        async fn replace_snapshots(&self, snapshots: &[EntitySnapshot]) -> Result<(), DomainError> {
+            *self.snapshots.lock().unwrap() = snapshots.to_vec();
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Tube {
+        name: &'static str,
+    }
+
+    async fn store_with_history() -> Arc<InMemoryEvents> {
+        let events = Arc::new(InMemoryEvents::default());
+        let audit = AuditTrail::default().with_event_store(events.clone());
+        let (a, b) = (Tube { name: "A" }, Tube { name: "B" });
+        audit.created("Tube", 1, &a, "tech").await.unwrap();
+        audit.created("Tube", 2, &a, "tech").await.unwrap();
+        audit.updated("Tube", 1, &a, &b, "tech").await.unwrap();
+        audit.deleted("Tube", 2, &a, "tech").await.unwrap();
+        events
+    }
+
+    #[tokio::test]
+    async fn test_replay_rebuilds_current_states() {
+        let events = store_with_history().await;
+        let service = EventStoreService::new(events.clone());
+
+        let checked = service.replay(false).await.unwrap();
+        assert!(checked.is_intact());
+        assert!(!checked.rebuilt);
+        assert!(events.snapshots.lock().unwrap().is_empty());
+
+        let report = service.replay(true).await.unwrap();
+        assert_eq!(report.events, 4);
+        assert_eq!(report.entities, 1);
+        assert_eq!(report.last_sequence, Some(4));
+        assert!(report.rebuilt);
+
+        let tube = service.get_snapshot("Tube", 1).await.unwrap();
+        assert_eq!(tube.state["name"], "B");
+        assert_eq!(tube.sequence, 3);
+        assert!(matches!(
+            service.get_snapshot("Tube", 2).await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replay_stops_at_tampered_event() {
+        let events = store_with_history().await;
+        events.events.lock().unwrap()[1].state = Some("{\"name\":\"C\"}".to_string());
+        let service = EventStoreService::new(events.clone());
+
+        let report = service.replay(true).await.unwrap();
+        assert!(!report.is_intact());
+        assert_eq!(report.broken_link.as_ref().unwrap().sequence, 2);
+        assert_eq!(report.events, 1);
+        assert!(!report.rebuilt);
+
+        // Removing an event breaks the link from the one after it
+        let events = store_with_history().await;
+        events.events.lock().unwrap().remove(2);
+        let report = EventStoreService::new(events).replay(false).await.unwrap();
+        assert_eq!(report.broken_link.unwrap().sequence, 4);
+    }
+
+    #[tokio::test]
+    async fn test_replay_checks_erased_events_in_full() {
+        let events = store_with_history().await;
+        assert!(events.events.lock().unwrap()[0].erase_fields(&["name"]));
+        let service = EventStoreService::new(events.clone());
+
+        let report = service.replay(true).await.unwrap();
+        assert!(report.is_intact());
+        assert_eq!(report.events, 4);
+
+        // Rewriting the rest of an erased event is noticed
+        events.events.lock().unwrap()[0].recorded_by = "someone else".to_string();
+        let report = service.replay(false).await.unwrap();
+        assert_eq!(report.broken_link.unwrap().sequence, 1);
+
+        // So is planting the erased marker without erasing
+        events.events.lock().unwrap()[0].recorded_by = "tech".to_string();
+        events.events.lock().unwrap()[2].state = Some(format!("{{\"name\":\"{}\"}}", ERASED));
+        let report = service.replay(false).await.unwrap();
+        let broken = report.broken_link.unwrap();
+        assert_eq!(broken.sequence, 3);
+        assert_eq!(broken.reason, "Its hash does not match its contents");
+    }
+}
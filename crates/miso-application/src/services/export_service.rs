@@ -0,0 +1,422 @@
+//! Export service for admin-defined delimited exports.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, ExportEntityType, ExportTemplate};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    ExportTemplateRepository, ProjectRepository, QueryOptions, SampleRepository,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, instrument};
+
+use crate::access::{ProjectAccess, Requester};
+use crate::dto::{
+    CreateExportTemplateRequest, ExportFile, ExportFilter, ExportTemplateResponse,
+    ProjectResponse, SampleResponse, UpdateExportTemplateRequest,
+};
+use crate::exporters::render_delimited;
+use crate::redaction::Redaction;
+
+/// Service for managing export templates and rendering exports.
+pub struct ExportService<T, P, S>
+where
+    T: ExportTemplateRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    templates: Arc<T>,
+    projects: Arc<P>,
+    samples: Arc<S>,
+    redaction: Redaction,
+    access: ProjectAccess,
+}
+
+impl<T, P, S> ExportService<T, P, S>
+where
+    T: ExportTemplateRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new export service.
+    pub fn new(templates: Arc<T>, projects: Arc<P>, samples: Arc<S>) -> Self {
+        Self {
+            templates,
+            projects,
+            samples,
+            redaction: Redaction::default(),
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Holds back sample details their participants didn't consent to
+    /// share.
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Keeps exports to the projects the requester is a member of.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Creates an export template.
+    #[instrument(skip(self))]
+    pub async fn create_template(
+        &self,
+        request: CreateExportTemplateRequest,
+        created_by: &str,
+    ) -> Result<ExportTemplateResponse, DomainError> {
+        if self.templates.find_by_name(&request.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "ExportTemplate".to_string(),
+                field: "name".to_string(),
+                value: request.name,
+            });
+        }
+
+        let mut template = ExportTemplate::new(
+            0,
+            request.name,
+            request.entity_type,
+            request.columns,
+            request.delimiter.unwrap_or_default(),
+            created_by.to_string(),
+        )?;
+        if let Some(include_header) = request.include_header {
+            template.include_header = include_header;
+        }
+
+        template.id = self.templates.save(&template).await?;
+
+        info!("Created export template: {} (ID: {})", template.name, template.id);
+
+        Ok(template.into())
+    }
+
+    /// Gets an export template by ID.
+    #[instrument(skip(self))]
+    pub async fn get_template(&self, id: i32) -> Result<ExportTemplateResponse, DomainError> {
+        Ok(self.find_template(id).await?.into())
+    }
+
+    /// Lists all export templates.
+    #[instrument(skip(self))]
+    pub async fn list_templates(&self) -> Result<Vec<ExportTemplateResponse>, DomainError> {
+        let templates = self.templates.list().await?;
+        Ok(templates.into_iter().map(Into::into).collect())
+    }
+
+    /// Updates an export template.
+    #[instrument(skip(self))]
+    pub async fn update_template(
+        &self,
+        id: i32,
+        request: UpdateExportTemplateRequest,
+    ) -> Result<ExportTemplateResponse, DomainError> {
+        let mut template = self.find_template(id).await?;
+
+        if let Some(name) = request.name {
+            if name != template.name && self.templates.find_by_name(&name).await?.is_some() {
+                return Err(DomainError::Duplicate {
+                    entity_type: "ExportTemplate".to_string(),
+                    field: "name".to_string(),
+                    value: name,
+                });
+            }
+            template.name = name;
+        }
+        if let Some(columns) = request.columns {
+            template.set_columns(columns)?;
+        }
+        if let Some(delimiter) = request.delimiter {
+            template.delimiter = delimiter;
+        }
+        if let Some(include_header) = request.include_header {
+            template.include_header = include_header;
+        }
+
+        template.updated_at = chrono::Utc::now();
+
+        self.templates.save(&template).await?;
+
+        info!("Updated export template: {} (ID: {})", template.name, id);
+
+        Ok(template.into())
+    }
+
+    /// Deletes an export template.
+    #[instrument(skip(self))]
+    pub async fn delete_template(&self, id: i32) -> Result<(), DomainError> {
+        self.find_template(id).await?;
+        self.templates.delete(id).await?;
+
+        info!("Deleted export template: {}", id);
+
+        Ok(())
+    }
+
+    /// Renders an export using a saved template. Rows are restricted to
+    /// the projects the requester may reach, and samples to what their
+    /// participants consented to share.
+    #[instrument(skip(self))]
+    pub async fn render(
+        &self,
+        id: i32,
+        filter: ExportFilter,
+        requester: Option<Requester<'_>>,
+    ) -> Result<ExportFile, DomainError> {
+        let template = self.find_template(id).await?;
+        if let Some(project_id) = filter.project_id {
+            self.access.check(project_id, requester).await?;
+        }
+        let reachable = self.access.projects(requester).await?;
+        let reaches = |project_id: &EntityId| {
+            reachable.as_ref().is_none_or(|p| p.contains(project_id))
+        };
+
+        let mut options = QueryOptions::new();
+        if let Some(limit) = filter.limit {
+            options = options.limit(limit);
+        }
+        if let Some(offset) = filter.offset {
+            options = options.offset(offset);
+        }
+
+        let rows = match template.entity_type {
+            ExportEntityType::Project => {
+                let projects = self.projects.list(options).await?;
+                to_rows(
+                    projects
+                        .into_iter()
+                        .filter(|p| reaches(&p.id))
+                        .map(ProjectResponse::from),
+                )?
+            }
+            ExportEntityType::Sample => {
+                let samples = match filter.project_id {
+                    Some(project_id) => self.samples.find_by_project(project_id, options).await?,
+                    None => self.samples.list(options).await?,
+                };
+                let samples = samples.into_iter().filter(|s| reaches(&s.project_id)).collect();
+                let samples = self.redaction.samples(samples).await?;
+                to_rows(samples.into_iter().map(SampleResponse::from))?
+            }
+        };
+
+        let extension = template.delimiter.file_extension();
+        let content_type = match extension {
+            "csv" => "text/csv",
+            "tsv" => "text/tab-separated-values",
+            _ => "text/plain",
+        };
+
+        Ok(ExportFile {
+            file_name: format!("{}.{}", template.name.replace(' ', "_"), extension),
+            content_type: content_type.to_string(),
+            body: render_delimited(&template, &rows),
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn find_template(&self, id: i32) -> Result<ExportTemplate, DomainError> {
+        self.templates
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ExportTemplate".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+fn to_rows<I: Serialize>(items: impl Iterator<Item = I>) -> Result<Vec<Value>, DomainError> {
+    items
+        .map(|item| {
+            serde_json::to_value(item).map_err(|e| DomainError::Validation(e.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use miso_domain::entities::{ExportColumn, Project, Role, Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::test_support::Members;
+
+    /// Projects 1 and 2 with a sample each, and a template listing
+    /// sample barcodes.
+    struct Lab;
+
+    fn sample(project_id: EntityId) -> Sample {
+        Sample::new_plain(
+            project_id,
+            format!("SAMPLE{}", project_id),
+            Barcode::new(format!("SAM-{}", project_id)).unwrap(),
+            project_id,
+            "Homo sapiens".to_string(),
+            "tech".to_string(),
+        )
+    }
+
+    #[async_trait]
+    impl ExportTemplateRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ExportTemplate>, DomainError> {
+            let column = ExportColumn {
+                field: "barcode".to_string(),
+                header: None,
+            };
+            Ok(Some(ExportTemplate::new(
+                id,
+                "Barcodes".to_string(),
+                ExportEntityType::Sample,
+                vec![column],
+                Default::default(),
+                "admin".to_string(),
+            )?))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<ExportTemplate>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<ExportTemplate>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &ExportTemplate) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl ProjectRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            project_id: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(vec![sample(project_id)])
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            Ok(vec![sample(1), sample(2)])
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exports_are_limited_to_members_projects() {
+        let service = ExportService::new(Arc::new(Lab), Arc::new(Lab), Arc::new(Lab))
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let member = Some(Requester::new("member", Role::Technician));
+        let export = |project_id| ExportFilter {
+            project_id,
+            ..Default::default()
+        };
+
+        let file = service.render(1, export(None), member).await.unwrap();
+        assert_eq!(file.body, "barcode\nSAM-1\n");
+        let file = service.render(1, export(Some(1)), member).await.unwrap();
+        assert_eq!(file.body, "barcode\nSAM-1\n");
+        assert!(matches!(
+            service.render(1, export(Some(2)), member).await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+
+        let admin = Some(Requester::new("admin", Role::Admin));
+        let file = service.render(1, export(None), admin).await.unwrap();
+        assert_eq!(file.body, "barcode\nSAM-1\nSAM-2\n");
+    }
+}
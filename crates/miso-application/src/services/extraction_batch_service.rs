@@ -0,0 +1,759 @@
+//! Extraction batch service.
+//!
+//! Extraction batches record which Stock samples were extracted together,
+//! with which instrument and kit lot, and the reagent blanks run alongside
+//! them. Each sample is extracted in at most one batch. The yield report
+//! compares batches and kit lots so a failing lot stands out.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{ControlType, EntityId, ExtractionBatch, Sample, SampleClass};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ExtractionBatchRepository, QueryOptions, SampleRepository};
+use miso_domain::services::YieldStatistics;
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    BatchYieldResponse, BlankYieldResponse, CreateExtractionBatchRequest,
+    ExtractionBatchFilter, ExtractionBatchResponse, ExtractionBatchSamplesRequest,
+    ExtractionYieldFilter, ExtractionYieldReport, KitLotYieldResponse,
+    UpdateExtractionBatchRequest,
+};
+
+/// Service for extraction batch operations.
+pub struct ExtractionBatchService<E, S>
+where
+    E: ExtractionBatchRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    batches: Arc<E>,
+    samples: Arc<S>,
+    audit: AuditTrail,
+}
+
+impl<E, S> ExtractionBatchService<E, S>
+where
+    E: ExtractionBatchRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new extraction batch service.
+    pub fn new(batches: Arc<E>, samples: Arc<S>) -> Self {
+        Self {
+            batches,
+            samples,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records extraction batch changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Records an extraction batch with the samples and blanks already
+    /// known.
+    ///
+    /// Batch names are unique. Samples must be Stock samples and blanks
+    /// negative or no-template controls, none already in another batch.
+    #[instrument(skip(self, request), fields(name = %request.name))]
+    pub async fn create_batch(
+        &self,
+        request: CreateExtractionBatchRequest,
+        created_by: &str,
+    ) -> Result<ExtractionBatchResponse, DomainError> {
+        let mut batch = ExtractionBatch::new(
+            &request.name,
+            &request.kit,
+            &request.kit_lot,
+            &request.operator,
+            request.extracted_at.unwrap_or_else(Utc::now),
+            created_by.to_string(),
+        )?;
+        batch.instrument = trimmed(request.instrument);
+        self.ensure_name_free(&batch.name, None).await?;
+
+        self.check_members(&request.sample_ids, &request.blank_ids, None)
+            .await?;
+        let now = Utc::now();
+        for &sample_id in &request.sample_ids {
+            batch.add_sample(sample_id, now);
+        }
+        for &blank_id in &request.blank_ids {
+            batch.add_blank(blank_id, now);
+        }
+
+        batch.id = self.batches.save(&batch).await?;
+        self.audit
+            .created("ExtractionBatch", batch.id, &batch, created_by)
+            .await?;
+
+        info!(
+            "Created extraction batch {} with {} samples and {} blanks using {} lot {} (ID: {})",
+            batch.name,
+            batch.sample_ids.len(),
+            batch.blank_ids.len(),
+            batch.kit,
+            batch.kit_lot,
+            batch.id
+        );
+
+        Ok(batch.into())
+    }
+
+    /// Gets an extraction batch by ID.
+    pub async fn get_batch(&self, id: EntityId) -> Result<ExtractionBatchResponse, DomainError> {
+        Ok(self.find_batch(id).await?.into())
+    }
+
+    /// Lists extraction batches, most recently extracted first, or the
+    /// batch a sample was extracted in.
+    pub async fn list_batches(
+        &self,
+        filter: ExtractionBatchFilter,
+    ) -> Result<Vec<ExtractionBatchResponse>, DomainError> {
+        let batches = match filter.sample_id {
+            Some(sample_id) => self
+                .batches
+                .find_by_sample(sample_id)
+                .await?
+                .into_iter()
+                .collect(),
+            None => {
+                let options = QueryOptions {
+                    limit: Some(filter.limit.unwrap_or(50).min(500)),
+                    offset: filter.offset,
+                    ..Default::default()
+                };
+                self.batches.list(options).await?
+            }
+        };
+
+        Ok(batches.into_iter().map(Into::into).collect())
+    }
+
+    /// Corrects an extraction batch's name, instrument, kit, lot, operator
+    /// or time.
+    #[instrument(skip(self, request))]
+    pub async fn update_batch(
+        &self,
+        id: EntityId,
+        request: UpdateExtractionBatchRequest,
+        updated_by: &str,
+    ) -> Result<ExtractionBatchResponse, DomainError> {
+        let mut batch = self.find_batch(id).await?;
+        let before = batch.clone();
+
+        if let Some(name) = request.name {
+            let name = name.trim().to_string();
+            self.ensure_name_free(&name, Some(id)).await?;
+            batch.name = name;
+        }
+        if request.instrument.is_some() {
+            batch.instrument = trimmed(request.instrument);
+        }
+        if let Some(kit) = request.kit {
+            batch.kit = kit.trim().to_string();
+        }
+        if let Some(kit_lot) = request.kit_lot {
+            batch.kit_lot = kit_lot.trim().to_string();
+        }
+        if let Some(operator) = request.operator {
+            batch.operator = operator.trim().to_string();
+        }
+        if let Some(extracted_at) = request.extracted_at {
+            batch.extracted_at = extracted_at;
+        }
+        if [&batch.name, &batch.kit, &batch.kit_lot, &batch.operator]
+            .iter()
+            .any(|f| f.is_empty())
+        {
+            return Err(DomainError::Validation(
+                "An extraction batch needs a name, kit, kit lot and operator".to_string(),
+            ));
+        }
+        batch.updated_at = Utc::now();
+
+        self.save(&before, &batch, updated_by).await?;
+        info!("Updated extraction batch {} (ID: {})", batch.name, id);
+
+        Ok(batch.into())
+    }
+
+    /// Deletes an extraction batch, releasing its samples and blanks.
+    #[instrument(skip(self))]
+    pub async fn delete_batch(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let batch = self.find_batch(id).await?;
+
+        self.batches.delete(id).await?;
+        self.audit
+            .deleted("ExtractionBatch", id, &batch, deleted_by)
+            .await?;
+
+        info!("Deleted extraction batch {} (ID: {})", batch.name, id);
+        Ok(())
+    }
+
+    /// Adds samples and blanks to an extraction batch. Those already in
+    /// this batch are skipped.
+    #[instrument(skip(self, request))]
+    pub async fn add_samples(
+        &self,
+        id: EntityId,
+        request: ExtractionBatchSamplesRequest,
+        updated_by: &str,
+    ) -> Result<ExtractionBatchResponse, DomainError> {
+        let mut batch = self.find_batch(id).await?;
+        let before = batch.clone();
+
+        self.check_members(&request.sample_ids, &request.blank_ids, Some(id))
+            .await?;
+        let now = Utc::now();
+        let mut added = 0;
+        for &sample_id in &request.sample_ids {
+            added += batch.add_sample(sample_id, now) as usize;
+        }
+        for &blank_id in &request.blank_ids {
+            added += batch.add_blank(blank_id, now) as usize;
+        }
+
+        if added > 0 {
+            self.save(&before, &batch, updated_by).await?;
+            info!(
+                "Added {} samples to extraction batch {} (ID: {})",
+                added, batch.name, id
+            );
+        }
+
+        Ok(batch.into())
+    }
+
+    /// Removes samples and blanks from an extraction batch. Those not in
+    /// it are skipped.
+    #[instrument(skip(self, request))]
+    pub async fn remove_samples(
+        &self,
+        id: EntityId,
+        request: ExtractionBatchSamplesRequest,
+        updated_by: &str,
+    ) -> Result<ExtractionBatchResponse, DomainError> {
+        let mut batch = self.find_batch(id).await?;
+        let before = batch.clone();
+
+        let now = Utc::now();
+        let removed = request
+            .sample_ids
+            .iter()
+            .chain(&request.blank_ids)
+            .filter(|&&sample_id| batch.remove(sample_id, now))
+            .count();
+
+        if removed > 0 {
+            self.save(&before, &batch, updated_by).await?;
+            info!(
+                "Removed {} samples from extraction batch {} (ID: {})",
+                removed, batch.name, id
+            );
+        }
+
+        Ok(batch.into())
+    }
+
+    /// Reports the yield distribution of each recent batch and of each kit
+    /// lot across them, and the yields of their blanks.
+    #[instrument(skip(self))]
+    pub async fn yield_report(
+        &self,
+        filter: ExtractionYieldFilter,
+    ) -> Result<ExtractionYieldReport, DomainError> {
+        let options = QueryOptions {
+            limit: Some(filter.limit.unwrap_or(100).min(1000)),
+            ..Default::default()
+        };
+        let batches: Vec<ExtractionBatch> = self
+            .batches
+            .list(options)
+            .await?
+            .into_iter()
+            .filter(|b| filter.kit.as_ref().is_none_or(|kit| &b.kit == kit))
+            .filter(|b| filter.kit_lot.as_ref().is_none_or(|lot| &b.kit_lot == lot))
+            .collect();
+
+        let ids: Vec<EntityId> = batches
+            .iter()
+            .flat_map(|b| b.sample_ids.iter().chain(&b.blank_ids).copied())
+            .collect();
+        let samples: HashMap<EntityId, Sample> = if ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.samples
+                .find_by_ids(&ids)
+                .await?
+                .into_iter()
+                .map(|s| (s.id, s))
+                .collect()
+        };
+        let yield_of = |id: &EntityId| samples.get(id).and_then(Sample::yield_ng);
+
+        let mut lots: BTreeMap<(String, String), (usize, Vec<f64>)> = BTreeMap::new();
+        let mut report = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let yields: Vec<f64> = batch.sample_ids.iter().filter_map(yield_of).collect();
+            let lot = lots
+                .entry((batch.kit.clone(), batch.kit_lot.clone()))
+                .or_default();
+            lot.0 += 1;
+            lot.1.extend(&yields);
+
+            let blanks: Vec<BlankYieldResponse> = batch
+                .blank_ids
+                .iter()
+                .map(|id| BlankYieldResponse {
+                    sample_id: *id,
+                    name: samples.get(id).map(|s| s.name.clone()).unwrap_or_default(),
+                    yield_ng: yield_of(id),
+                })
+                .collect();
+            let contaminated = blanks.iter().any(|b| b.yield_ng.is_some_and(|y| y > 0.0));
+            if contaminated {
+                warn!(
+                    "A reagent blank of extraction batch {} (ID: {}) has a yield",
+                    batch.name, batch.id
+                );
+            }
+
+            report.push(BatchYieldResponse {
+                batch_id: batch.id,
+                name: batch.name,
+                kit: batch.kit,
+                kit_lot: batch.kit_lot,
+                extracted_at: batch.extracted_at,
+                samples: batch.sample_ids.len(),
+                yields: YieldStatistics::of(&yields),
+                blanks,
+                contaminated,
+            });
+        }
+
+        Ok(ExtractionYieldReport {
+            batches: report,
+            lots: lots
+                .into_iter()
+                .map(|((kit, kit_lot), (batches, yields))| KitLotYieldResponse {
+                    kit,
+                    kit_lot,
+                    batches,
+                    yields: YieldStatistics::of(&yields),
+                })
+                .collect(),
+        })
+    }
+
+    async fn save(
+        &self,
+        before: &ExtractionBatch,
+        batch: &ExtractionBatch,
+        updated_by: &str,
+    ) -> Result<(), DomainError> {
+        self.batches.save(batch).await?;
+        self.audit
+            .updated("ExtractionBatch", batch.id, before, batch, updated_by)
+            .await
+    }
+
+    /// Fails unless every sample is a Stock sample, every blank a negative
+    /// or no-template control, and none is in a batch other than
+    /// `batch_id`.
+    async fn check_members(
+        &self,
+        sample_ids: &[EntityId],
+        blank_ids: &[EntityId],
+        batch_id: Option<EntityId>,
+    ) -> Result<(), DomainError> {
+        let ids: Vec<EntityId> = sample_ids.iter().chain(blank_ids).copied().collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let found = self.samples.find_by_ids(&ids).await?;
+        for &id in &ids {
+            let sample = found
+                .iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| DomainError::NotFound {
+                    entity_type: "Sample".to_string(),
+                    id: id.to_string(),
+                })?;
+
+            if blank_ids.contains(&id) {
+                if !matches!(
+                    sample.control_type,
+                    Some(ControlType::Negative | ControlType::Ntc)
+                ) {
+                    return Err(DomainError::Validation(format!(
+                        "Sample {} is not a negative or no-template control, so it can't be a reagent blank",
+                        sample.name
+                    )));
+                }
+            } else if sample.sample_class() != SampleClass::Stock || sample.is_control() {
+                return Err(DomainError::Validation(format!(
+                    "Sample {} is not a Stock sample, so it can't be in an extraction batch",
+                    sample.name
+                )));
+            }
+
+            if let Some(other) = self.batches.find_by_sample(id).await? {
+                if Some(other.id) != batch_id {
+                    return Err(DomainError::Validation(format!(
+                        "Sample {} is already in extraction batch {}",
+                        sample.name, other.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn ensure_name_free(&self, name: &str, id: Option<EntityId>) -> Result<(), DomainError> {
+        match self.batches.find_by_name(name).await? {
+            Some(existing) if Some(existing.id) != id => Err(DomainError::Duplicate {
+                entity_type: "ExtractionBatch".to_string(),
+                field: "name".to_string(),
+                value: name.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn find_batch(&self, id: EntityId) -> Result<ExtractionBatch, DomainError> {
+        self.batches
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ExtractionBatch".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+/// Trims an optional field, treating blank as unset.
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::SampleDetails;
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{
+        Barcode, Concentration, ConcentrationUnit, Mass, MassUnit, QcStatus, Volume,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryBatches {
+        batches: Mutex<Vec<ExtractionBatch>>,
+    }
+
+    #[async_trait]
+    impl ExtractionBatchRepository for InMemoryBatches {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ExtractionBatch>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().find(|b| b.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<ExtractionBatch>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().find(|b| b.name == name).cloned())
+        }
+        async fn find_by_sample(
+            &self,
+            sample_id: EntityId,
+        ) -> Result<Option<ExtractionBatch>, DomainError> {
+            Ok(self
+                .batches
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|b| b.contains(sample_id))
+                .cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<ExtractionBatch>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().rev().cloned().collect())
+        }
+        async fn save(&self, batch: &ExtractionBatch) -> Result<EntityId, DomainError> {
+            let mut batches = self.batches.lock().unwrap();
+            let mut batch = batch.clone();
+            if batch.id == 0 {
+                batch.id = batches.len() as EntityId + 1;
+            }
+            let id = batch.id;
+            match batches.iter_mut().find(|b| b.id == id) {
+                Some(stored) => *stored = batch,
+                None => batches.push(batch),
+            }
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.batches.lock().unwrap().retain(|b| b.id != id);
+            Ok(())
+        }
+    }
+
+    /// Samples 1-4 are Stocks yielding 100 ng times their ID, measured as
+    /// mass for odd IDs and concentration times volume for even ones.
+    /// Sample 5 is an unquantified Stock, 6 a plain sample, 7 an NTC blank
+    /// with no yield and 8 a negative control blank yielding 2 ng.
+    struct ExtractedSamples;
+
+    fn sample(id: EntityId) -> Sample {
+        let barcode = Barcode::new(format!("SAM-{}", id)).unwrap();
+        if id == 6 {
+            return Sample::new_plain(
+                id,
+                format!("SAM{}", id),
+                barcode,
+                1,
+                "Homo sapiens".to_string(),
+                "tech".to_string(),
+            );
+        }
+        let mut sample = Sample::new_pooled(
+            id,
+            format!("SAM{}", id),
+            barcode,
+            1,
+            "tech".to_string(),
+        );
+        if let SampleDetails::Detailed(details) = &mut sample.details {
+            details.sample_class = SampleClass::Stock;
+            details.pooled = false;
+        }
+        match id {
+            1 | 3 => sample.mass = Some(Mass::new(id as f64 * 100.0, MassUnit::Nanograms)),
+            2 | 4 => {
+                sample.concentration =
+                    Some(Concentration::new(id as f64 * 2.0, ConcentrationUnit::NgPerUl));
+                sample.volume = Some(Volume::microliters(50.0));
+            }
+            7 => sample.control_type = Some(ControlType::Ntc),
+            8 => {
+                sample.control_type = Some(ControlType::Negative);
+                sample.mass = Some(Mass::new(2.0, MassUnit::Nanograms));
+            }
+            _ => {}
+        }
+        sample
+    }
+
+    #[async_trait]
+    impl SampleRepository for ExtractedSamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(ids
+                .iter()
+                .filter(|id| (1..=8).contains(*id))
+                .map(|&id| sample(id))
+                .collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn create(
+        name: &str,
+        kit_lot: &str,
+        sample_ids: Vec<i32>,
+        blank_ids: Vec<i32>,
+    ) -> CreateExtractionBatchRequest {
+        CreateExtractionBatchRequest {
+            name: name.to_string(),
+            instrument: Some("QIAcube".to_string()),
+            kit: "DNeasy".to_string(),
+            kit_lot: kit_lot.to_string(),
+            operator: "jo".to_string(),
+            extracted_at: None,
+            sample_ids,
+            blank_ids,
+        }
+    }
+
+    fn service() -> ExtractionBatchService<InMemoryBatches, ExtractedSamples> {
+        ExtractionBatchService::new(Arc::new(InMemoryBatches::default()), Arc::new(ExtractedSamples))
+    }
+
+    #[tokio::test]
+    async fn test_batches_take_stocks_and_blanks_once() {
+        let service = service();
+
+        for (samples, blanks) in [(vec![6], vec![]), (vec![7], vec![]), (vec![], vec![1])] {
+            assert!(matches!(
+                service.create_batch(create("EXT-1", "L1", samples, blanks), "tech").await,
+                Err(DomainError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            service.create_batch(create("EXT-1", "L1", vec![9], vec![]), "tech").await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        let first = service
+            .create_batch(create("EXT-1", "L1", vec![1, 2], vec![7]), "tech")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.create_batch(create("EXT-1", "L1", vec![], vec![]), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service.create_batch(create("EXT-2", "L1", vec![2], vec![]), "tech").await,
+            Err(DomainError::Validation(_))
+        ));
+
+        let members = |sample_ids, blank_ids| ExtractionBatchSamplesRequest {
+            sample_ids,
+            blank_ids,
+        };
+        service
+            .remove_samples(first.id, members(vec![2], vec![7]), "tech")
+            .await
+            .unwrap();
+        let second = service
+            .create_batch(create("EXT-2", "L1", vec![2], vec![]), "tech")
+            .await
+            .unwrap();
+        let second = service
+            .add_samples(second.id, members(vec![2, 3], vec![7]), "tech")
+            .await
+            .unwrap();
+        assert_eq!((second.sample_ids, second.blank_ids), (vec![2, 3], vec![7]));
+
+        let of_sample = service
+            .list_batches(ExtractionBatchFilter {
+                sample_id: Some(7),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(of_sample[0].name, "EXT-2");
+    }
+
+    #[tokio::test]
+    async fn test_yield_report_by_batch_and_lot() {
+        let service = service();
+        service
+            .create_batch(create("EXT-1", "L1", vec![1, 2, 5], vec![7]), "tech")
+            .await
+            .unwrap();
+        service
+            .create_batch(create("EXT-2", "L1", vec![3], vec![]), "tech")
+            .await
+            .unwrap();
+        service
+            .create_batch(create("EXT-3", "L2", vec![4], vec![8]), "tech")
+            .await
+            .unwrap();
+
+        let report = service
+            .yield_report(ExtractionYieldFilter::default())
+            .await
+            .unwrap();
+        let names: Vec<&str> = report.batches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["EXT-3", "EXT-2", "EXT-1"]);
+
+        let first = &report.batches[2];
+        assert_eq!(first.samples, 3);
+        let yields = first.yields.unwrap();
+        assert_eq!((yields.count, yields.min, yields.max), (2, 100.0, 200.0));
+        assert_eq!(first.blanks[0].yield_ng, None);
+        assert!(!first.contaminated);
+        assert!(report.batches[0].contaminated);
+
+        let lots: Vec<_> = report
+            .lots
+            .iter()
+            .map(|l| (l.kit_lot.as_str(), l.batches, l.yields.unwrap().median))
+            .collect();
+        assert_eq!(lots, vec![("L1", 2, 200.0), ("L2", 1, 400.0)]);
+
+        let lot_two = service
+            .yield_report(ExtractionYieldFilter {
+                kit_lot: Some("L2".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(lot_two.batches.len(), 1);
+    }
+}
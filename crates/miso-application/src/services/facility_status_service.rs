@@ -0,0 +1,366 @@
+//! Facility status service.
+//!
+//! Lab managers announce maintenance windows for a sequencer or for the
+//! whole facility. Together with the sequencers' recorded statuses these
+//! make up the public status page, which tells collaborators whether runs
+//! are being taken without them having to ask. A sequencer inside one of
+//! its maintenance windows is shown as under maintenance whatever status
+//! was recorded for it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{EntityId, MaintenanceWindow, SequencerStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{MaintenanceWindowRepository, SequencerRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateMaintenanceRequest, FacilityStatusResponse, InstrumentAvailability,
+    MaintenanceWindowResponse, PlannedMaintenance,
+};
+
+/// Service for maintenance windows and the facility status.
+///
+/// Only facility-wide maintenance can be announced, and no sequencers are
+/// listed, until the sequencer repository is supplied.
+pub struct FacilityStatusService {
+    maintenance: Arc<dyn MaintenanceWindowRepository>,
+    sequencers: Option<Arc<dyn SequencerRepository>>,
+    audit: AuditTrail,
+}
+
+impl FacilityStatusService {
+    /// Creates a new facility status service.
+    pub fn new(maintenance: Arc<dyn MaintenanceWindowRepository>) -> Self {
+        Self {
+            maintenance,
+            sequencers: None,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Enables sequencer maintenance and lists sequencers on the status
+    /// page.
+    pub fn with_sequencers(mut self, sequencers: Arc<dyn SequencerRepository>) -> Self {
+        self.sequencers = Some(sequencers);
+        self
+    }
+
+    /// Records maintenance announcements in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Announces a maintenance window.
+    #[instrument(skip(self, request))]
+    pub async fn schedule_maintenance(
+        &self,
+        request: CreateMaintenanceRequest,
+        created_by: &str,
+    ) -> Result<MaintenanceWindowResponse, DomainError> {
+        if let Some(sequencer_id) = request.sequencer_id {
+            let sequencer = match &self.sequencers {
+                Some(sequencers) => sequencers.find_by_id(sequencer_id).await?,
+                None => None,
+            };
+            if sequencer.is_none() {
+                return Err(DomainError::NotFound {
+                    entity_type: "Sequencer".to_string(),
+                    id: sequencer_id.to_string(),
+                });
+            }
+        }
+
+        let mut window = MaintenanceWindow::new(
+            request.sequencer_id,
+            request.starts_at,
+            request.ends_at,
+            &request.reason,
+            created_by.to_string(),
+        )?;
+        window.id = self.maintenance.save(&window).await?;
+        self.audit
+            .created("MaintenanceWindow", window.id, &window, created_by)
+            .await?;
+
+        info!(
+            "Maintenance of {} planned from {} to {} (ID: {})",
+            window
+                .sequencer_id
+                .map_or_else(|| "the facility".to_string(), |id| format!("sequencer {}", id)),
+            window.starts_at,
+            window.ends_at,
+            window.id
+        );
+
+        Ok(window.into())
+    }
+
+    /// Lists maintenance windows that haven't ended, soonest first.
+    pub async fn list_maintenance(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<MaintenanceWindowResponse>, DomainError> {
+        Ok(self
+            .maintenance
+            .find_upcoming(now)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Cancels a maintenance window, or takes down one that has passed.
+    #[instrument(skip(self))]
+    pub async fn cancel_maintenance(
+        &self,
+        id: EntityId,
+        deleted_by: &str,
+    ) -> Result<(), DomainError> {
+        let window = self
+            .maintenance
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "MaintenanceWindow".to_string(),
+                id: id.to_string(),
+            })?;
+
+        self.maintenance.delete(id).await?;
+        self.audit
+            .deleted("MaintenanceWindow", id, &window, deleted_by)
+            .await?;
+
+        info!("Cancelled maintenance window {}", id);
+        Ok(())
+    }
+
+    /// Summarises which sequencers can take runs at `now` and what
+    /// maintenance is under way or coming up.
+    ///
+    /// Retired sequencers aren't listed, nor is maintenance of them.
+    pub async fn status(&self, now: DateTime<Utc>) -> Result<FacilityStatusResponse, DomainError> {
+        let windows = self.maintenance.find_upcoming(now).await?;
+        let mut sequencers = match &self.sequencers {
+            Some(sequencers) => sequencers.list().await?,
+            None => Vec::new(),
+        };
+        sequencers.retain(|s| s.status != SequencerStatus::Retired);
+        sequencers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let instruments: Vec<InstrumentAvailability> = sequencers
+            .iter()
+            .map(|sequencer| {
+                let back_at = windows
+                    .iter()
+                    .filter(|w| w.covers(sequencer.id) && w.is_active(now))
+                    .map(|w| w.ends_at)
+                    .max();
+                let status = match back_at {
+                    Some(_) => SequencerStatus::Maintenance,
+                    None => sequencer.status,
+                };
+                InstrumentAvailability {
+                    name: sequencer.name.clone(),
+                    model: sequencer.model.name.clone(),
+                    platform: sequencer.platform().to_string(),
+                    status,
+                    available: matches!(
+                        status,
+                        SequencerStatus::Available | SequencerStatus::Running
+                    ),
+                    back_at,
+                }
+            })
+            .collect();
+
+        let names: HashMap<EntityId, &str> = sequencers
+            .iter()
+            .map(|s| (s.id, s.name.as_str()))
+            .collect();
+        let planned_maintenance = windows
+            .iter()
+            .filter_map(|window| {
+                let instrument = match window.sequencer_id {
+                    Some(id) => Some(names.get(&id)?.to_string()),
+                    None => None,
+                };
+                Some(PlannedMaintenance {
+                    instrument,
+                    starts_at: window.starts_at,
+                    ends_at: window.ends_at,
+                    reason: window.reason.clone(),
+                    in_progress: window.is_active(now),
+                })
+            })
+            .collect();
+
+        let facility_down = windows
+            .iter()
+            .any(|w| w.is_facility_wide() && w.is_active(now));
+
+        Ok(FacilityStatusResponse {
+            accepting_runs: !facility_down && instruments.iter().any(|i| i.available),
+            instruments,
+            planned_maintenance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use miso_domain::entities::{InstrumentModel, Platform, Sequencer};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryWindows {
+        windows: Mutex<Vec<MaintenanceWindow>>,
+    }
+
+    #[async_trait]
+    impl MaintenanceWindowRepository for InMemoryWindows {
+        async fn find_by_id(
+            &self,
+            id: EntityId,
+        ) -> Result<Option<MaintenanceWindow>, DomainError> {
+            Ok(self.windows.lock().unwrap().iter().find(|w| w.id == id).cloned())
+        }
+        async fn find_upcoming(
+            &self,
+            now: DateTime<Utc>,
+        ) -> Result<Vec<MaintenanceWindow>, DomainError> {
+            let mut windows: Vec<_> = self
+                .windows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|w| w.ends_at > now)
+                .cloned()
+                .collect();
+            windows.sort_by_key(|w| (w.starts_at, w.id));
+            Ok(windows)
+        }
+        async fn save(&self, window: &MaintenanceWindow) -> Result<EntityId, DomainError> {
+            let mut windows = self.windows.lock().unwrap();
+            let mut window = window.clone();
+            window.id = windows.len() as EntityId + 1;
+            let id = window.id;
+            windows.push(window);
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.windows.lock().unwrap().retain(|w| w.id != id);
+            Ok(())
+        }
+    }
+
+    struct Fleet(Vec<Sequencer>);
+
+    #[async_trait]
+    impl SequencerRepository for Fleet {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sequencer>, DomainError> {
+            Ok(self.0.iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Sequencer>, DomainError> {
+            Ok(self.0.iter().find(|s| s.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+            Ok(self.0.clone())
+        }
+        async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+            Ok(self.0.iter().filter(|s| s.can_run()).cloned().collect())
+        }
+        async fn save(&self, sequencer: &Sequencer) -> Result<EntityId, DomainError> {
+            Ok(sequencer.id)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn sequencer(id: EntityId, name: &str, status: SequencerStatus) -> Sequencer {
+        let model = InstrumentModel::new(Platform::Illumina, "NovaSeq X".to_string(), 8);
+        let mut sequencer = Sequencer::new(id, name.to_string(), model);
+        sequencer.status = status;
+        sequencer
+    }
+
+    fn maintenance(
+        sequencer_id: Option<EntityId>,
+        starts_at: DateTime<Utc>,
+        hours: i64,
+    ) -> CreateMaintenanceRequest {
+        CreateMaintenanceRequest {
+            sequencer_id,
+            starts_at,
+            ends_at: starts_at + Duration::hours(hours),
+            reason: "Preventive maintenance".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_reflects_maintenance() {
+        let service = FacilityStatusService::new(Arc::new(InMemoryWindows::default()))
+            .with_sequencers(Arc::new(Fleet(vec![
+                sequencer(1, "NovaSeqX-A", SequencerStatus::Running),
+                sequencer(2, "NovaSeqX-B", SequencerStatus::Available),
+                sequencer(3, "HiSeq-Old", SequencerStatus::Retired),
+            ])));
+        let now = Utc::now();
+
+        assert!(matches!(
+            service.schedule_maintenance(maintenance(Some(9), now, 2), "lm").await,
+            Err(DomainError::NotFound { .. })
+        ));
+        service
+            .schedule_maintenance(maintenance(Some(2), now - Duration::hours(1), 3), "lm")
+            .await
+            .unwrap();
+        service
+            .schedule_maintenance(maintenance(Some(3), now, 3), "lm")
+            .await
+            .unwrap();
+        let shutdown = service
+            .schedule_maintenance(maintenance(None, now + Duration::days(7), 24), "lm")
+            .await
+            .unwrap();
+
+        let status = service.status(now).await.unwrap();
+        assert!(status.accepting_runs);
+        let instruments: Vec<_> = status
+            .instruments
+            .iter()
+            .map(|i| (i.name.as_str(), i.status, i.available))
+            .collect();
+        assert_eq!(
+            instruments,
+            vec![
+                ("NovaSeqX-A", SequencerStatus::Running, true),
+                ("NovaSeqX-B", SequencerStatus::Maintenance, false),
+            ]
+        );
+        assert_eq!(status.instruments[1].back_at, Some(now + Duration::hours(2)));
+        // Maintenance of the retired sequencer isn't published
+        let planned: Vec<_> = status
+            .planned_maintenance
+            .iter()
+            .map(|p| (p.instrument.as_deref(), p.in_progress))
+            .collect();
+        assert_eq!(planned, vec![(Some("NovaSeqX-B"), true), (None, false)]);
+
+        let during_shutdown = service.status(shutdown.starts_at).await.unwrap();
+        assert!(!during_shutdown.accepting_runs);
+
+        service.cancel_maintenance(shutdown.id, "lm").await.unwrap();
+        assert_eq!(service.list_maintenance(now).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,171 @@
+//! Index catalog service.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, IndexSet};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::IndexSetRepository;
+use miso_domain::value_objects::DnaIndex;
+use tracing::{info, instrument};
+
+use super::library_service::parse_index_family;
+use crate::dto::{CreateIndexSetRequest, IndexSetResponse, IndexSetSummary};
+
+/// Service for the catalog of index kits libraries are indexed from.
+pub struct IndexSetService<I: IndexSetRepository + ?Sized> {
+    index_sets: Arc<I>,
+}
+
+impl<I: IndexSetRepository + ?Sized> IndexSetService<I> {
+    /// Creates a new index catalog service.
+    pub fn new(index_sets: Arc<I>) -> Self {
+        Self { index_sets }
+    }
+
+    /// Registers an index kit or plate with all its indices.
+    ///
+    /// Sequences are checked and normalised as when setting a library's
+    /// index. Set names are unique.
+    #[instrument(skip(self, request), fields(name = %request.name))]
+    pub async fn create_index_set(
+        &self,
+        request: CreateIndexSetRequest,
+        created_by: &str,
+    ) -> Result<IndexSetResponse, DomainError> {
+        let family = parse_index_family(&request.family)?;
+        let indices = request
+            .indices
+            .into_iter()
+            .map(|entry| match entry.i5 {
+                Some(i5) => DnaIndex::dual(entry.name, entry.i7, i5, family),
+                None => DnaIndex::single(entry.name, entry.i7, family),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut set = IndexSet::new(request.name, family, indices, created_by.to_string())?;
+
+        if self.index_sets.find_by_name(&set.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "IndexSet".to_string(),
+                field: "name".to_string(),
+                value: set.name,
+            });
+        }
+
+        set.id = self.index_sets.save(&set).await?;
+
+        info!(
+            "Registered index set: {} with {} indices (ID: {})",
+            set.name,
+            set.indices.len(),
+            set.id
+        );
+
+        Ok(set.into())
+    }
+
+    /// Gets an index set and its indices by ID.
+    #[instrument(skip(self))]
+    pub async fn get_index_set(&self, id: EntityId) -> Result<IndexSetResponse, DomainError> {
+        let set = self
+            .index_sets
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "IndexSet".to_string(),
+                id: id.to_string(),
+            })?;
+
+        Ok(set.into())
+    }
+
+    /// Lists every index set in the catalog.
+    #[instrument(skip(self))]
+    pub async fn list_index_sets(&self) -> Result<Vec<IndexSetSummary>, DomainError> {
+        let sets = self.index_sets.list().await?;
+        Ok(sets.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::dto::IndexSetEntryRequest;
+
+    #[derive(Default)]
+    struct InMemoryIndexSets {
+        sets: Mutex<Vec<IndexSet>>,
+    }
+
+    #[async_trait]
+    impl IndexSetRepository for InMemoryIndexSets {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<IndexSet>, DomainError> {
+            Ok(self.sets.lock().unwrap().iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<IndexSet>, DomainError> {
+            Ok(self.sets.lock().unwrap().iter().find(|s| s.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<IndexSet>, DomainError> {
+            Ok(self.sets.lock().unwrap().clone())
+        }
+        async fn save(&self, set: &IndexSet) -> Result<EntityId, DomainError> {
+            let mut sets = self.sets.lock().unwrap();
+            let mut stored = set.clone();
+            stored.id = sets.len() as EntityId + 1;
+            sets.push(stored);
+            Ok(sets.len() as EntityId)
+        }
+    }
+
+    fn request(name: &str, family: &str, i7s: &[&str]) -> CreateIndexSetRequest {
+        CreateIndexSetRequest {
+            name: name.to_string(),
+            family: family.to_string(),
+            indices: i7s
+                .iter()
+                .enumerate()
+                .map(|(i, i7)| IndexSetEntryRequest {
+                    name: format!("UDP{:04}", i + 1),
+                    i7: i7.to_string(),
+                    i5: Some("AGGCTATA".to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_read_index_sets() {
+        let service = IndexSetService::new(Arc::new(InMemoryIndexSets::default()));
+
+        let set = service
+            .create_index_set(
+                request("IDT UDI Plate 1", "idt_udi", &["gaactgagcg", "AGGTCAGATA"]),
+                "admin",
+            )
+            .await
+            .unwrap();
+        assert_eq!(set.family, "idt_udi");
+        assert_eq!(set.indices[1].position, 2);
+        assert_eq!(set.indices[0].i7, "GAACTGAGCG");
+        assert_eq!(service.get_index_set(set.id).await.unwrap(), set);
+
+        let summaries = service.list_index_sets().await.unwrap();
+        assert_eq!(summaries[0].index_count, 2);
+
+        let duplicate = service
+            .create_index_set(request("IDT UDI Plate 1", "idt_udi", &["GAACTGAGCG"]), "admin")
+            .await;
+        assert!(matches!(duplicate, Err(DomainError::Duplicate { .. })));
+        let bad_sequence = service
+            .create_index_set(request("Plate 2", "idt_udi", &["GAACXGAGCG"]), "admin")
+            .await;
+        assert!(bad_sequence.is_err());
+        let bad_family = service
+            .create_index_set(request("Plate 2", "illumina", &["GAACTGAGCG"]), "admin")
+            .await;
+        assert!(matches!(bad_family, Err(DomainError::Validation(_))));
+    }
+}
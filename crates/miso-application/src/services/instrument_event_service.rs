@@ -0,0 +1,82 @@
+//! Instrument event service for ingesting run folder logs.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, InstrumentEvent};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::InstrumentEventRepository;
+use tracing::{info, instrument};
+
+use crate::dto::{IngestInstrumentLogRequest, InstrumentLogFormat, RunInstrumentEventsResponse};
+use crate::importers::{parse_illumina_log, parse_ont_report};
+
+/// Service for instrument-reported run errors and warnings.
+pub struct InstrumentEventService<R: InstrumentEventRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: InstrumentEventRepository + ?Sized> InstrumentEventService<R> {
+    /// Creates a new instrument event service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Ingests an instrument log, replacing the events previously read
+    /// from the same file, and returns all of the run's events.
+    #[instrument(skip(self, request), fields(file_name = %request.file_name))]
+    pub async fn ingest_log(
+        &self,
+        run_id: EntityId,
+        request: IngestInstrumentLogRequest,
+        ingested_by: &str,
+    ) -> Result<RunInstrumentEventsResponse, DomainError> {
+        let reported = match request.format {
+            InstrumentLogFormat::IlluminaLog => parse_illumina_log(&request.contents),
+            InstrumentLogFormat::OntReport => {
+                let data = serde_json::from_str(&request.contents).map_err(|e| {
+                    DomainError::Validation(format!("Invalid MinKNOW report JSON: {}", e))
+                })?;
+                parse_ont_report(&data)?
+            }
+        };
+
+        let events = reported
+            .into_iter()
+            .map(|r| {
+                let mut event = InstrumentEvent::new(
+                    run_id,
+                    r.severity,
+                    request.file_name.clone(),
+                    r.message,
+                    ingested_by.to_string(),
+                )?;
+                event.code = r.code;
+                event.occurred_at = r.occurred_at;
+                Ok(event)
+            })
+            .collect::<Result<Vec<_>, DomainError>>()?;
+
+        self.repository
+            .replace_for_source(run_id, &request.file_name, &events)
+            .await?;
+
+        info!(
+            "Ingested {} instrument events for run {} from {}",
+            events.len(),
+            run_id,
+            request.file_name
+        );
+
+        self.list_for_run(run_id).await
+    }
+
+    /// Lists the instrument events reported for a run.
+    #[instrument(skip(self))]
+    pub async fn list_for_run(
+        &self,
+        run_id: EntityId,
+    ) -> Result<RunInstrumentEventsResponse, DomainError> {
+        let events = self.repository.find_by_run(run_id).await?;
+        Ok(RunInstrumentEventsResponse::new(run_id, events))
+    }
+}
@@ -0,0 +1,318 @@
+//! Instrument model catalog service.
+
+use std::sync::Arc;
+
+use miso_domain::entities::InstrumentModel;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::InstrumentModelRepository;
+use tracing::{info, instrument};
+
+use crate::dto::{
+    CreateInstrumentModelRequest, InstrumentModelResponse, UpdateInstrumentModelRequest,
+};
+
+/// Service for managing the instrument model catalog.
+pub struct InstrumentModelService<R: InstrumentModelRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: InstrumentModelRepository + ?Sized> InstrumentModelService<R> {
+    /// Creates a new instrument model service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Adds an instrument model to the catalog.
+    #[instrument(skip(self))]
+    pub async fn create_model(
+        &self,
+        request: CreateInstrumentModelRequest,
+    ) -> Result<InstrumentModelResponse, DomainError> {
+        self.ensure_name_free(&request.name).await?;
+
+        let mut model = InstrumentModel::new(request.platform, request.name, request.partitions);
+        model.description = request.description;
+        model.container_models = request.container_models.unwrap_or_default();
+        model.patterned_containers = request.patterned_containers.unwrap_or_default();
+        model.chemistries = request.chemistries.unwrap_or_default();
+        model.max_read_length = request.max_read_length;
+        model.expected_run_hours = request.expected_run_hours;
+        model.seconds_per_cycle = request.seconds_per_cycle;
+        model.validate()?;
+
+        model.id = self.repository.save(&model).await?;
+
+        info!(
+            "Created instrument model: {} (ID: {})",
+            model.name, model.id
+        );
+
+        Ok(model.into())
+    }
+
+    /// Gets an instrument model by ID.
+    #[instrument(skip(self))]
+    pub async fn get_model(&self, id: i32) -> Result<InstrumentModelResponse, DomainError> {
+        Ok(self.find_model(id).await?.into())
+    }
+
+    /// Lists the instrument model catalog.
+    #[instrument(skip(self))]
+    pub async fn list_models(&self) -> Result<Vec<InstrumentModelResponse>, DomainError> {
+        let models = self.repository.list().await?;
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    /// Updates a catalogued instrument model.
+    #[instrument(skip(self))]
+    pub async fn update_model(
+        &self,
+        id: i32,
+        request: UpdateInstrumentModelRequest,
+    ) -> Result<InstrumentModelResponse, DomainError> {
+        let mut model = self.find_model(id).await?;
+
+        if let Some(name) = request.name {
+            if name != model.name {
+                self.ensure_name_free(&name).await?;
+            }
+            model.name = name;
+        }
+        if let Some(platform) = request.platform {
+            model.platform = platform;
+        }
+        if let Some(partitions) = request.partitions {
+            model.partitions = partitions;
+        }
+        if let Some(description) = request.description {
+            model.description = Some(description);
+        }
+        if let Some(container_models) = request.container_models {
+            model.container_models = container_models;
+        }
+        if let Some(patterned_containers) = request.patterned_containers {
+            model.patterned_containers = patterned_containers;
+        }
+        if let Some(chemistries) = request.chemistries {
+            model.chemistries = chemistries;
+        }
+        if let Some(max_read_length) = request.max_read_length {
+            model.max_read_length = Some(max_read_length);
+        }
+        if let Some(expected_run_hours) = request.expected_run_hours {
+            model.expected_run_hours = Some(expected_run_hours);
+        }
+        if let Some(seconds_per_cycle) = request.seconds_per_cycle {
+            model.seconds_per_cycle = Some(seconds_per_cycle);
+        }
+        model.validate()?;
+
+        self.repository.save(&model).await?;
+
+        info!("Updated instrument model: {} (ID: {})", model.name, id);
+
+        Ok(model.into())
+    }
+
+    /// Records that an instrument model accepts a container model.
+    #[instrument(skip(self))]
+    pub async fn link_container_model(
+        &self,
+        id: i32,
+        container_model: &str,
+    ) -> Result<InstrumentModelResponse, DomainError> {
+        let mut model = self.find_model(id).await?;
+
+        if !model.supports_container(container_model) {
+            model.container_models.push(container_model.to_string());
+            model.validate()?;
+            self.repository.save(&model).await?;
+
+            info!(
+                "Linked container model {} to {}",
+                container_model, model.name
+            );
+        }
+
+        Ok(model.into())
+    }
+
+    /// Removes a container model from those an instrument model accepts.
+    #[instrument(skip(self))]
+    pub async fn unlink_container_model(
+        &self,
+        id: i32,
+        container_model: &str,
+    ) -> Result<InstrumentModelResponse, DomainError> {
+        let mut model = self.find_model(id).await?;
+
+        if model.supports_container(container_model) {
+            model.container_models.retain(|c| c != container_model);
+            model.patterned_containers.retain(|c| c != container_model);
+            self.repository.save(&model).await?;
+
+            info!(
+                "Unlinked container model {} from {}",
+                container_model, model.name
+            );
+        }
+
+        Ok(model.into())
+    }
+
+    /// Removes an instrument model from the catalog.
+    #[instrument(skip(self))]
+    pub async fn delete_model(&self, id: i32) -> Result<(), DomainError> {
+        self.find_model(id).await?;
+        self.repository.delete(id).await?;
+
+        info!("Deleted instrument model: {}", id);
+
+        Ok(())
+    }
+
+    async fn find_model(&self, id: i32) -> Result<InstrumentModel, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "InstrumentModel".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn ensure_name_free(&self, name: &str) -> Result<(), DomainError> {
+        if self.repository.find_by_name(name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "InstrumentModel".to_string(),
+                field: "name".to_string(),
+                value: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{EntityId, Platform};
+    use miso_domain::errors::RunError;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryModels {
+        models: Mutex<Vec<InstrumentModel>>,
+    }
+
+    #[async_trait]
+    impl InstrumentModelRepository for InMemoryModels {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<InstrumentModel>, DomainError> {
+            let models = self.models.lock().unwrap();
+            Ok(models.iter().find(|m| m.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<InstrumentModel>, DomainError> {
+            let models = self.models.lock().unwrap();
+            Ok(models.iter().find(|m| m.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError> {
+            Ok(self.models.lock().unwrap().clone())
+        }
+        async fn save(&self, model: &InstrumentModel) -> Result<EntityId, DomainError> {
+            let mut models = self.models.lock().unwrap();
+            let mut model = model.clone();
+            if model.id == 0 {
+                model.id = models.len() as EntityId + 1;
+            }
+            models.retain(|m| m.id != model.id);
+            models.push(model.clone());
+            Ok(model.id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.models.lock().unwrap().retain(|m| m.id != id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_model() {
+        let service = InstrumentModelService::new(Arc::new(InMemoryModels::default()));
+        let created = service
+            .create_model(CreateInstrumentModelRequest {
+                platform: Platform::Illumina,
+                name: "MiSeq".to_string(),
+                partitions: 1,
+                description: None,
+                container_models: Some(vec!["MiSeq v3 Flow Cell".to_string()]),
+                patterned_containers: None,
+                chemistries: None,
+                max_read_length: Some(300),
+                expected_run_hours: None,
+                seconds_per_cycle: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.id, 1);
+        assert_eq!(created.max_read_length, Some(300));
+
+        let duplicate = CreateInstrumentModelRequest {
+            platform: Platform::Illumina,
+            name: "MiSeq".to_string(),
+            partitions: 1,
+            description: None,
+            container_models: None,
+            patterned_containers: None,
+            chemistries: None,
+            max_read_length: None,
+            expected_run_hours: None,
+            seconds_per_cycle: None,
+        };
+        assert!(matches!(
+            service.create_model(duplicate).await,
+            Err(DomainError::Duplicate { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_container_links() {
+        let repository = Arc::new(InMemoryModels::default());
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq X".to_string(), 2);
+        model.container_models = vec!["10B Flow Cell".to_string()];
+        model.patterned_containers = vec!["10B Flow Cell".to_string()];
+        let id = repository.save(&model).await.unwrap();
+        let service = InstrumentModelService::new(repository.clone());
+
+        service
+            .link_container_model(id, "25B Flow Cell")
+            .await
+            .unwrap();
+        let linked = service
+            .link_container_model(id, "25B Flow Cell")
+            .await
+            .unwrap();
+        assert_eq!(
+            linked.container_models,
+            vec!["10B Flow Cell", "25B Flow Cell"]
+        );
+
+        service
+            .unlink_container_model(id, "10B Flow Cell")
+            .await
+            .unwrap();
+        let stored = repository.find_by_id(id).await.unwrap().unwrap();
+        assert_eq!(stored.container_models, vec!["25B Flow Cell"]);
+        assert!(stored.patterned_containers.is_empty());
+        assert!(matches!(
+            stored.check_container("10B Flow Cell"),
+            Err(RunError::IncompatibleContainer(_, _))
+        ));
+
+        assert!(matches!(
+            service.link_container_model(99, "25B Flow Cell").await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+}
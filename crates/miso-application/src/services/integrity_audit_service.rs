@@ -0,0 +1,165 @@
+//! Integrity audit service for checking cross-table invariants.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::EntityId;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    LibraryRepository, PoolRepository, ProjectRepository, QueryOptions, SampleRepository,
+    StorageBoxRepository,
+};
+use miso_domain::services::{IntegrityAuditor, IntegrityCheck, IntegrityViolation};
+use tracing::{info, instrument};
+
+use crate::dto::{CheckOutcome, CheckStatus, IntegrityReport};
+
+/// Service running the integrity audit over whichever repositories are
+/// available.
+///
+/// Checks whose repositories haven't been supplied are reported as skipped
+/// rather than failing the audit.
+#[derive(Default)]
+pub struct IntegrityAuditService {
+    projects: Option<Arc<dyn ProjectRepository>>,
+    samples: Option<Arc<dyn SampleRepository>>,
+    pools: Option<Arc<dyn PoolRepository>>,
+    libraries: Option<Arc<dyn LibraryRepository>>,
+    boxes: Option<Arc<dyn StorageBoxRepository>>,
+}
+
+impl IntegrityAuditService {
+    /// Creates a service with no checks enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the sample project and hierarchy checks.
+    pub fn with_samples(
+        mut self,
+        projects: Arc<dyn ProjectRepository>,
+        samples: Arc<dyn SampleRepository>,
+    ) -> Self {
+        self.projects = Some(projects);
+        self.samples = Some(samples);
+        self
+    }
+
+    /// Enables the pool element check.
+    pub fn with_pools(
+        mut self,
+        pools: Arc<dyn PoolRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+    ) -> Self {
+        self.pools = Some(pools);
+        self.libraries = Some(libraries);
+        self
+    }
+
+    /// Enables the box contents check.
+    pub fn with_boxes(mut self, boxes: Arc<dyn StorageBoxRepository>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
+    /// Runs every enabled check.
+    #[instrument(skip(self))]
+    pub async fn run(&self) -> Result<IntegrityReport, DomainError> {
+        let started_at = Utc::now();
+        let mut checks = Vec::new();
+        let mut violations = Vec::new();
+
+        let mut record = |check, entities_checked, found: Option<Vec<IntegrityViolation>>| {
+            let status = if found.is_some() {
+                CheckStatus::Ran
+            } else {
+                CheckStatus::Skipped
+            };
+            let found = found.unwrap_or_default();
+            checks.push(CheckOutcome {
+                check,
+                status,
+                entities_checked,
+                violation_count: found.len(),
+            });
+            violations.extend(found);
+        };
+
+        match (&self.projects, &self.samples) {
+            (Some(projects), Some(samples)) => {
+                let project_ids: HashSet<EntityId> = projects
+                    .list(QueryOptions::new())
+                    .await?
+                    .iter()
+                    .map(|p| p.id)
+                    .collect();
+                let samples = samples.list(QueryOptions::new()).await?;
+
+                record(
+                    IntegrityCheck::SampleProject,
+                    samples.len(),
+                    Some(IntegrityAuditor::check_sample_projects(
+                        &samples,
+                        &project_ids,
+                    )),
+                );
+                record(
+                    IntegrityCheck::SampleHierarchy,
+                    samples.len(),
+                    Some(IntegrityAuditor::check_sample_hierarchy(&samples)),
+                );
+            }
+            _ => {
+                record(IntegrityCheck::SampleProject, 0, None);
+                record(IntegrityCheck::SampleHierarchy, 0, None);
+            }
+        }
+
+        match (&self.pools, &self.libraries) {
+            (Some(pools), Some(libraries)) => {
+                let pools = pools.list(QueryOptions::new()).await?;
+                let mut library_ids: Vec<EntityId> =
+                    pools.iter().flat_map(|p| p.library_ids()).collect();
+                library_ids.sort_unstable();
+                library_ids.dedup();
+                let libraries = libraries.find_by_ids(&library_ids).await?;
+
+                record(
+                    IntegrityCheck::PoolElements,
+                    pools.len(),
+                    Some(IntegrityAuditor::check_pool_elements(&pools, &libraries)),
+                );
+            }
+            _ => record(IntegrityCheck::PoolElements, 0, None),
+        }
+
+        match &self.boxes {
+            Some(boxes) => {
+                let boxes = boxes.list(QueryOptions::new()).await?;
+                record(
+                    IntegrityCheck::BoxContents,
+                    boxes.len(),
+                    Some(IntegrityAuditor::check_box_contents(&boxes)),
+                );
+            }
+            None => record(IntegrityCheck::BoxContents, 0, None),
+        }
+
+        info!(
+            "Integrity audit found {} violation(s) across {} check(s)",
+            violations.len(),
+            checks
+                .iter()
+                .filter(|c| c.status == CheckStatus::Ran)
+                .count()
+        );
+
+        Ok(IntegrityReport {
+            started_at,
+            completed_at: Utc::now(),
+            checks,
+            violations,
+        })
+    }
+}
@@ -0,0 +1,370 @@
+//! Label print service.
+//!
+//! Every label goes through [`LabelPrintService::print_all`], which records
+//! the prints before sending it to the printer. Templates in unique mode print
+//! once per entity; a reprint needs a lab manager's override, which is kept
+//! with the record of the print.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{EntityId, LabelPrint, LabelUsage, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::labels::{Label, LabelPrinter};
+use miso_domain::repositories::LabelPrintRepository;
+use tracing::{info, instrument, warn};
+
+use crate::dto::{LabelJobResponse, LabelPrintResponse, LabelUsageResponse};
+
+/// Service for printing labels and keeping track of the prints.
+pub struct LabelPrintService<P: LabelPrintRepository + ?Sized> {
+    prints: Arc<P>,
+    unique_templates: HashSet<String>,
+}
+
+impl<P: LabelPrintRepository + ?Sized> LabelPrintService<P> {
+    /// Creates a new label print service. No template is unique until set.
+    pub fn new(prints: Arc<P>) -> Self {
+        Self {
+            prints,
+            unique_templates: HashSet::new(),
+        }
+    }
+
+    /// Sets the templates that print once per entity.
+    pub fn with_unique_templates<I, S>(mut self, templates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.unique_templates = templates.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns true if a template prints once per entity.
+    pub fn is_unique(&self, template: &str) -> bool {
+        self.unique_templates.contains(template)
+    }
+
+    /// Prints a label, refusing a second print of a unique label unless a
+    /// lab manager overrides with a reason.
+    #[instrument(skip(self, printer, label), fields(template = %label.template, entity_id = label.entity_id))]
+    pub async fn print(
+        &self,
+        printer: &dyn LabelPrinter,
+        label: &Label,
+        printed_by: &str,
+        role: Role,
+        override_reason: Option<&str>,
+    ) -> Result<LabelPrintResponse, DomainError> {
+        let job = self
+            .print_all(printer, std::slice::from_ref(label), printed_by, role, override_reason)
+            .await?;
+        job.prints
+            .into_iter()
+            .next()
+            .ok_or_else(|| DomainError::Validation("Nothing was printed".to_string()))
+    }
+
+    /// Prints labels of one template as a single job and records the
+    /// labels it used.
+    ///
+    /// The prints are recorded first, so two users printing at once can't
+    /// both print the first copy; the records are removed if any label is
+    /// refused or printing fails, so the job prints whole or not at all.
+    #[instrument(skip(self, printer, labels), fields(labels = labels.len()))]
+    pub async fn print_all(
+        &self,
+        printer: &dyn LabelPrinter,
+        labels: &[Label],
+        printed_by: &str,
+        role: Role,
+        override_reason: Option<&str>,
+    ) -> Result<LabelJobResponse, DomainError> {
+        let Some(template) = labels.first().map(|l| l.template.clone()) else {
+            return Err(DomainError::Validation("No labels to print".to_string()));
+        };
+        if labels.iter().any(|l| l.template != template) {
+            return Err(DomainError::Validation(
+                "A print job takes labels of one template".to_string(),
+            ));
+        }
+
+        let mut prints = Vec::with_capacity(labels.len());
+        for label in labels {
+            match self.record(label, printed_by, role, override_reason).await {
+                Ok(print) => prints.push(print),
+                Err(e) => {
+                    self.forget(&prints).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let job = match printer.print_job(labels).await {
+            Ok(job) => job,
+            Err(e) => {
+                self.forget(&prints).await;
+                return Err(e);
+            }
+        };
+
+        for print in prints.iter().filter(|p| p.override_reason.is_some()) {
+            info!(
+                "{} reprinted {} label of {} {} as copy {}",
+                printed_by, print.template, print.entity_type, print.entity_id, print.copy
+            );
+        }
+
+        // The labels are printed by now, so a failure to count them is only
+        // logged
+        let mut usage = LabelUsage::of_job(printer.name(), template, job, printed_by);
+        match self.prints.save_usage(&usage).await {
+            Ok(id) => usage.id = id,
+            Err(e) => warn!("Failed to record label usage of {}: {}", usage.printer, e),
+        }
+
+        Ok(LabelJobResponse {
+            prints: prints.into_iter().map(Into::into).collect(),
+            usage: usage.into(),
+        })
+    }
+
+    /// Records the next print of a label.
+    async fn record(
+        &self,
+        label: &Label,
+        printed_by: &str,
+        role: Role,
+        override_reason: Option<&str>,
+    ) -> Result<LabelPrint, DomainError> {
+        let last = self
+            .prints
+            .find_last(&label.entity_type, label.entity_id, &label.template)
+            .await?;
+        let mut print = LabelPrint::next(
+            label,
+            last.as_ref(),
+            self.is_unique(&label.template),
+            printed_by,
+            role,
+            override_reason,
+        )?;
+        print.id = self.prints.save(&print).await?;
+        Ok(print)
+    }
+
+    /// Removes the records of prints that didn't happen.
+    async fn forget(&self, prints: &[LabelPrint]) {
+        for print in prints {
+            if let Err(e) = self.prints.delete(print.id).await {
+                warn!("Failed to remove record of failed print {}: {}", print.id, e);
+            }
+        }
+    }
+
+    /// Lists the labels printed for an entity, oldest first.
+    pub async fn history(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<LabelPrintResponse>, DomainError> {
+        let prints = self.prints.list_for_entity(entity_type, entity_id).await?;
+        Ok(prints.into_iter().map(Into::into).collect())
+    }
+
+    /// Lists the print jobs since a time, oldest first, for tracking label
+    /// stock.
+    pub async fn usage(&self, since: DateTime<Utc>) -> Result<Vec<LabelUsageResponse>, DomainError> {
+        let usage = self.prints.list_usage(since).await?;
+        Ok(usage.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryPrints {
+        prints: Mutex<Vec<LabelPrint>>,
+        usage: Mutex<Vec<LabelUsage>>,
+    }
+
+    #[async_trait]
+    impl LabelPrintRepository for InMemoryPrints {
+        async fn find_last(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+            template: &str,
+        ) -> Result<Option<LabelPrint>, DomainError> {
+            Ok(self
+                .prints
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| {
+                    p.entity_type == entity_type && p.entity_id == entity_id && p.template == template
+                })
+                .max_by_key(|p| p.copy)
+                .cloned())
+        }
+
+        async fn list_for_entity(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Vec<LabelPrint>, DomainError> {
+            Ok(self
+                .prints
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| p.entity_type == entity_type && p.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn save(&self, print: &LabelPrint) -> Result<EntityId, DomainError> {
+            let mut prints = self.prints.lock().unwrap();
+            let id = prints.len() as EntityId + 1;
+            prints.push(LabelPrint { id, ..print.clone() });
+            Ok(id)
+        }
+
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.prints.lock().unwrap().retain(|p| p.id != id);
+            Ok(())
+        }
+
+        async fn save_usage(&self, usage: &LabelUsage) -> Result<EntityId, DomainError> {
+            let mut jobs = self.usage.lock().unwrap();
+            jobs.push(usage.clone());
+            Ok(jobs.len() as EntityId)
+        }
+
+        async fn list_usage(&self, since: DateTime<Utc>) -> Result<Vec<LabelUsage>, DomainError> {
+            Ok(self
+                .usage
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|u| u.printed_at >= since)
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// A printer that counts its labels, or fails if it's out of them.
+    #[derive(Default)]
+    struct CountingPrinter {
+        printed: Mutex<u32>,
+        out_of_labels: bool,
+    }
+
+    #[async_trait]
+    impl LabelPrinter for CountingPrinter {
+        fn name(&self) -> String {
+            "counter".to_string()
+        }
+
+        async fn print(&self, _label: &Label) -> Result<(), DomainError> {
+            if self.out_of_labels {
+                return Err(DomainError::Validation("Out of labels".to_string()));
+            }
+            *self.printed.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    fn service() -> LabelPrintService<InMemoryPrints> {
+        LabelPrintService::new(Arc::new(InMemoryPrints::default()))
+            .with_unique_templates(["sample"])
+    }
+
+    #[tokio::test]
+    async fn test_unique_label_prints_once_without_override() {
+        let service = service();
+        let printer = CountingPrinter::default();
+        let label = Label::sample(1, "SAM-1", "S1", "PROJ");
+
+        let first = service
+            .print(&printer, &label, "tech", Role::Technician, None)
+            .await
+            .unwrap();
+        assert_eq!(first.copy, 1);
+
+        let refused = service
+            .print(&printer, &label, "tech", Role::Technician, None)
+            .await;
+        assert!(matches!(refused, Err(DomainError::Duplicate { .. })));
+
+        let second = service
+            .print(&printer, &label, "boss", Role::LabManager, Some("Tube cracked"))
+            .await
+            .unwrap();
+        assert_eq!((second.copy, second.override_reason.as_deref()), (2, Some("Tube cracked")));
+        assert_eq!(*printer.printed.lock().unwrap(), 2);
+        assert_eq!(service.history("Sample", 1).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_print_is_not_recorded() {
+        let service = service();
+        let label = Label::sample(1, "SAM-1", "S1", "PROJ");
+        let jammed = CountingPrinter {
+            out_of_labels: true,
+            ..Default::default()
+        };
+
+        assert!(service
+            .print(&jammed, &label, "tech", Role::Technician, None)
+            .await
+            .is_err());
+        assert!(service.history("Sample", 1).await.unwrap().is_empty());
+
+        // The label can still be printed once the printer is fixed
+        let printer = CountingPrinter::default();
+        assert!(service
+            .print(&printer, &label, "tech", Role::Technician, None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_job_prints_whole_or_not_at_all() {
+        let service = service();
+        let printer = CountingPrinter::default();
+        service
+            .print(&printer, &Label::sample(2, "SAM-2", "S2", "PROJ"), "tech", Role::Technician, None)
+            .await
+            .unwrap();
+
+        // Sample 2's label was printed, so the job is refused whole
+        let labels: Vec<_> = (1..=3)
+            .map(|id| Label::sample(id, &format!("SAM-{id}"), &format!("S{id}"), "PROJ"))
+            .collect();
+        let refused = service
+            .print_all(&printer, &labels, "tech", Role::Technician, None)
+            .await;
+        assert!(matches!(refused, Err(DomainError::Duplicate { .. })));
+        assert!(service.history("Sample", 1).await.unwrap().is_empty());
+
+        let job = service
+            .print_all(&printer, &[labels[0].clone(), labels[2].clone()], "tech", Role::Technician, None)
+            .await
+            .unwrap();
+        assert_eq!(job.prints.len(), 2);
+        assert_eq!((job.usage.printer.as_str(), job.usage.labels), ("counter", 2));
+        assert_eq!(*printer.printed.lock().unwrap(), 3);
+
+        let usage = service.usage(Utc::now() - chrono::Duration::hours(1)).await.unwrap();
+        assert_eq!(usage.iter().map(|u| u.labels).sum::<u32>(), 3);
+    }
+}
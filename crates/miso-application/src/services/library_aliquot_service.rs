@@ -0,0 +1,343 @@
+//! Library aliquot service.
+//!
+//! Aliquots are portions of a library taken out for pooling. Taking one
+//! withdraws its volume from the library, and both are saved together so
+//! the library's volume never counts an aliquot twice or misses one.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, Library, LibraryAliquot, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{LibraryAliquotRepository, LibraryRepository};
+use miso_domain::value_objects::{Barcode, Concentration, Volume};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{CreateLibraryAliquotRequest, LibraryAliquotResponse};
+use crate::locks::ProjectLocks;
+
+/// Service for library aliquot operations.
+pub struct LibraryAliquotService<A, L>
+where
+    A: LibraryAliquotRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    aliquots: Arc<A>,
+    libraries: Arc<L>,
+    audit: AuditTrail,
+    locks: ProjectLocks,
+}
+
+impl<A, L> LibraryAliquotService<A, L>
+where
+    A: LibraryAliquotRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    /// Creates a new library aliquot service.
+    pub fn new(aliquots: Arc<A>, libraries: Arc<L>) -> Self {
+        Self {
+            aliquots,
+            libraries,
+            audit: AuditTrail::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Records aliquots and the library volume they take in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Refuses aliquots of libraries of locked projects, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Takes an aliquot of a library, withdrawing its volume.
+    ///
+    /// The library must not be archived and must have the volume left.
+    /// The aliquot has the library's concentration unless it was diluted.
+    #[instrument(skip(self, request))]
+    pub async fn create_aliquot(
+        &self,
+        library_id: EntityId,
+        request: CreateLibraryAliquotRequest,
+        created_by: &str,
+        role: Role,
+    ) -> Result<LibraryAliquotResponse, DomainError> {
+        let before = self.find_library(library_id).await?;
+        self.locks.check(before.project_id, role).await?;
+        if before.archived {
+            return Err(DomainError::Validation(format!(
+                "Library {} is archived",
+                before.name
+            )));
+        }
+        if request.volume_ul <= 0.0 {
+            return Err(DomainError::Validation(
+                "An aliquot must have some volume".to_string(),
+            ));
+        }
+
+        let barcode = match request.barcode {
+            Some(barcode) => Some(self.new_barcode(barcode).await?),
+            None => None,
+        };
+
+        let volume = Volume::microliters(request.volume_ul);
+        let mut library = before.clone();
+        library.withdraw_volume(volume).map_err(|e| {
+            DomainError::Validation(format!("Library {}: {}", library.name, e))
+        })?;
+
+        let concentration = request
+            .concentration_ng_ul
+            .map(Concentration::ng_per_ul)
+            .or(library.concentration);
+        let mut aliquot = LibraryAliquot::new(
+            0,
+            library.id,
+            Some(volume),
+            concentration,
+            created_by.to_string(),
+        );
+        aliquot.barcode = barcode;
+
+        aliquot.id = self.aliquots.create(&aliquot, &library).await?;
+        self.audit
+            .created("LibraryAliquot", aliquot.id, &aliquot, created_by)
+            .await?;
+        self.audit
+            .updated("Library", library.id, &before, &library, created_by)
+            .await?;
+
+        info!(
+            "Took aliquot {} of {} from library {}",
+            aliquot.id, volume, library.name
+        );
+
+        Ok(aliquot.into())
+    }
+
+    /// Gets an aliquot by ID.
+    pub async fn get_aliquot(&self, id: EntityId) -> Result<LibraryAliquotResponse, DomainError> {
+        self.aliquots
+            .find_by_id(id)
+            .await?
+            .map(Into::into)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "LibraryAliquot".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// Lists a library's aliquots, oldest first.
+    pub async fn list_aliquots(
+        &self,
+        library_id: EntityId,
+    ) -> Result<Vec<LibraryAliquotResponse>, DomainError> {
+        self.find_library(library_id).await?;
+        let aliquots = self.aliquots.find_by_library(library_id).await?;
+        Ok(aliquots.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_library(&self, id: EntityId) -> Result<Library, DomainError> {
+        self.libraries
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Library".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// Checks an aliquot's own barcode is valid and not already taken.
+    async fn new_barcode(&self, barcode: String) -> Result<Barcode, DomainError> {
+        let barcode = Barcode::new(barcode)?;
+        if self
+            .aliquots
+            .find_by_barcode(barcode.as_str())
+            .await?
+            .is_some()
+        {
+            return Err(DomainError::Duplicate {
+                entity_type: "LibraryAliquot".to_string(),
+                field: "barcode".to_string(),
+                value: barcode.to_string(),
+            });
+        }
+        Ok(barcode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{LibraryDesign, LibraryType, Sample};
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    /// Libraries and their aliquots, saved together as in one database.
+    #[derive(Default)]
+    struct InMemoryStore {
+        libraries: Mutex<HashMap<EntityId, Library>>,
+        aliquots: Mutex<Vec<LibraryAliquot>>,
+    }
+
+    #[async_trait]
+    impl LibraryRepository for InMemoryStore {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(self.libraries.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl LibraryAliquotRepository for InMemoryStore {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<LibraryAliquot>, DomainError> {
+            Ok(self.aliquots.lock().unwrap().iter().find(|a| a.id == id).cloned())
+        }
+        async fn find_by_barcode(
+            &self,
+            barcode: &str,
+        ) -> Result<Option<LibraryAliquot>, DomainError> {
+            Ok(self
+                .aliquots
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.barcode.as_ref().is_some_and(|b| b.as_str() == barcode))
+                .cloned())
+        }
+        async fn find_by_library(
+            &self,
+            library_id: EntityId,
+        ) -> Result<Vec<LibraryAliquot>, DomainError> {
+            Ok(self
+                .aliquots
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.library_id == library_id)
+                .cloned()
+                .collect())
+        }
+        async fn create(
+            &self,
+            aliquot: &LibraryAliquot,
+            library: &Library,
+        ) -> Result<EntityId, DomainError> {
+            let mut aliquots = self.aliquots.lock().unwrap();
+            let id = aliquots.len() as EntityId + 1;
+            aliquots.push(LibraryAliquot { id, ..aliquot.clone() });
+            self.libraries
+                .lock()
+                .unwrap()
+                .insert(library.id, library.clone());
+            Ok(id)
+        }
+    }
+
+    fn service() -> LibraryAliquotService<InMemoryStore, InMemoryStore> {
+        let mut library = Library::new(
+            1,
+            "LIB001".to_string(),
+            Barcode::new("LIB-001").unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "admin".to_string(),
+        );
+        library.volume = Some(Volume::microliters(20.0));
+        library.concentration = Some(Concentration::ng_per_ul(4.0));
+        let store = Arc::new(InMemoryStore::default());
+        store.libraries.lock().unwrap().insert(1, library);
+        LibraryAliquotService::new(store.clone(), store)
+    }
+
+    fn request(volume_ul: f64, barcode: Option<&str>) -> CreateLibraryAliquotRequest {
+        CreateLibraryAliquotRequest {
+            volume_ul,
+            concentration_ng_ul: None,
+            barcode: barcode.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aliquot_withdraws_library_volume() {
+        let service = service();
+
+        let aliquot = service
+            .create_aliquot(1, request(8.0, Some("LA-1")), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(aliquot.volume_ul, Some(8.0));
+        assert_eq!(aliquot.concentration_ng_ul, Some(4.0));
+        let library = service.find_library(1).await.unwrap();
+        assert_eq!(library.volume.unwrap().as_microliters(), 12.0);
+
+        // Too much volume, or a barcode already taken, takes nothing
+        let result = service
+            .create_aliquot(1, request(15.0, None), "tech", Role::Technician)
+            .await;
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+        let result = service
+            .create_aliquot(1, request(2.0, Some("LA-1")), "tech", Role::Technician)
+            .await;
+        assert!(matches!(result, Err(DomainError::Duplicate { .. })));
+        let library = service.find_library(1).await.unwrap();
+        assert_eq!(library.volume.unwrap().as_microliters(), 12.0);
+        assert_eq!(service.list_aliquots(1).await.unwrap(), vec![aliquot]);
+    }
+
+    #[tokio::test]
+    async fn test_aliquot_of_unknown_library() {
+        let result = service()
+            .create_aliquot(9, request(1.0, None), "tech", Role::Technician)
+            .await;
+        assert!(matches!(result, Err(DomainError::NotFound { .. })));
+        assert!(service().list_aliquots(9).await.is_err());
+    }
+}
@@ -0,0 +1,1716 @@
+//! Library service for library operations.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{IndexSet, Library, LibraryDesign, LibraryType, Role, Sample};
+use miso_domain::errors::DomainError;
+use miso_domain::plugins::{DomainEvent, Operation};
+use miso_domain::repositories::{
+    IndexSetRepository, LibraryRepository, PanelRepository, QueryOptions,
+    ReferenceGenomeRepository, SampleRepository,
+};
+use miso_domain::services::{BarcodeValidator, KitCompatibility, QcTransitionPolicy};
+use miso_domain::value_objects::{Barcode, Concentration, DnaIndex, IndexFamily, Mass, QcStatus, Volume};
+use tracing::{info, instrument};
+
+use super::sample_service::parse_control_type;
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateLibraryRequest, ImportLibrariesRequest, LibraryImportResponse, LibraryImportRowResult,
+    LibraryResponse, LibrarySummary, SetCatalogIndexRequest, SetLibraryIndexRequest,
+    UpdateLibraryRequest,
+};
+use crate::importers::{parse_library_csv, LibraryCsvRow};
+use crate::access::{ProjectAccess, Requester};
+use crate::locks::ProjectLocks;
+use crate::plugins::PluginRegistry;
+
+/// Service for library operations.
+pub struct LibraryService<L, S>
+where
+    L: LibraryRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    repository: Arc<L>,
+    samples: Arc<S>,
+    barcode_validator: BarcodeValidator,
+    kits: KitCompatibility,
+    panels: Option<Arc<dyn PanelRepository>>,
+    genomes: Option<Arc<dyn ReferenceGenomeRepository>>,
+    index_sets: Option<Arc<dyn IndexSetRepository>>,
+    plugins: Arc<PluginRegistry>,
+    audit: AuditTrail,
+    locks: ProjectLocks,
+    access: ProjectAccess,
+}
+
+impl<L, S> LibraryService<L, S>
+where
+    L: LibraryRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new library service.
+    pub fn new(repository: Arc<L>, samples: Arc<S>) -> Self {
+        Self {
+            repository,
+            samples,
+            barcode_validator: BarcodeValidator::new(),
+            kits: KitCompatibility::new(),
+            panels: None,
+            genomes: None,
+            index_sets: None,
+            plugins: Arc::new(PluginRegistry::new()),
+            audit: AuditTrail::default(),
+            locks: ProjectLocks::default(),
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Checks libraries' kits against the designs and platforms they
+    /// support.
+    pub fn with_kits(mut self, kits: KitCompatibility) -> Self {
+        self.kits = kits;
+        self
+    }
+
+    /// Lets targeted panel libraries reference the panel they captured.
+    ///
+    /// Without panels, libraries cannot reference one.
+    pub fn with_panels(mut self, panels: Arc<dyn PanelRepository>) -> Self {
+        self.panels = Some(panels);
+        self
+    }
+
+    /// Lets libraries name a reference genome for their assay.
+    ///
+    /// Without the registry, libraries cannot name one.
+    pub fn with_reference_genomes(mut self, genomes: Arc<dyn ReferenceGenomeRepository>) -> Self {
+        self.genomes = Some(genomes);
+        self
+    }
+
+    /// Lets imported libraries be given indices from the index catalog.
+    ///
+    /// Without the catalog, imported libraries cannot name an index.
+    pub fn with_index_sets(mut self, index_sets: Arc<dyn IndexSetRepository>) -> Self {
+        self.index_sets = Some(index_sets);
+        self
+    }
+
+    /// Runs site plugins' hooks on library changes.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Records library changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Refuses changes to libraries of locked projects, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Keeps libraries to the members of their projects.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Prepares a new library from a sample.
+    ///
+    /// The library belongs to the sample's project. The sample must be of
+    /// a class libraries are made from, have passed QC and not be archived.
+    /// Any input mass is withdrawn from the sample, which must have it left.
+    #[instrument(skip(self))]
+    pub async fn create_library(
+        &self,
+        request: CreateLibraryRequest,
+        created_by: &str,
+        role: Role,
+    ) -> Result<LibraryResponse, DomainError> {
+        let mut sample = self
+            .samples
+            .find_by_id(request.sample_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: request.sample_id.to_string(),
+            })?;
+
+        self.access
+            .check(sample.project_id, Some(Requester::new(created_by, role)))
+            .await?;
+        self.locks.check(sample.project_id, role).await?;
+        check_library_source(&sample)?;
+        let before = sample.clone();
+        if let Some(ng) = request.input_ng {
+            sample.withdraw_mass(Mass::nanograms(ng)).map_err(|e| {
+                DomainError::Validation(format!("Sample {}: {}", sample.name, e))
+            })?;
+        }
+        let barcode = self.new_barcode().await?;
+
+        let mut library = Library::new(
+            0,
+            request.name,
+            barcode,
+            sample.id,
+            sample.project_id,
+            LibraryDesign::from_code(&request.design),
+            parse_library_type(&request.library_type)?,
+            request.platform,
+            created_by.to_string(),
+        );
+        library.description = request.description;
+        library.kit_name = request.kit_name;
+        library.panel_id = request.panel_id;
+        library.reference_genome_id = request.reference_genome_id;
+        library.insert_size = request.insert_size;
+        library.volume = request.volume_ul.map(Volume::microliters);
+        library.concentration = request.concentration_ng_ul.map(Concentration::ng_per_ul);
+        library.pcr_cycles = request.pcr_cycles;
+        library.control_type = match request.control_type.as_deref() {
+            Some(code) => parse_control_type(code)?,
+            None => sample.control_type,
+        };
+        self.check_kit(&library)?;
+        self.check_panel(&library).await?;
+        self.check_reference_genome(&library).await?;
+        if let Some(name) = self.plugins.library_name(&library, &sample).await? {
+            library.name = name;
+        }
+
+        if self.repository.find_by_name(&library.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Library".to_string(),
+                field: "name".to_string(),
+                value: library.name,
+            });
+        }
+        self.plugins
+            .validate_library(Operation::Create, &library)
+            .await?;
+
+        // The input is withdrawn in the same transaction the library is
+        // made in, so neither is saved without the other
+        let id = if request.input_ng.is_some() {
+            let id = self.repository.create_from_sample(&library, &sample).await?;
+            sample.version += 1;
+            self.audit
+                .updated("Sample", sample.id, &before, &sample, created_by)
+                .await?;
+            id
+        } else {
+            self.repository.save(&library).await?
+        };
+        library.id = id;
+        self.audit
+            .created("Library", id, &library, created_by)
+            .await?;
+
+        info!(
+            "Created library: {} (ID: {}) from sample {}",
+            library.name, id, sample.id
+        );
+
+        if request.input_ng.is_some() {
+            self.plugins
+                .publish(DomainEvent::SampleUpdated(sample))
+                .await;
+        }
+        self.plugins
+            .publish(DomainEvent::LibraryCreated(library.clone()))
+            .await;
+
+        Ok(library.into())
+    }
+
+    /// Imports a library sheet, creating a library for each row.
+    ///
+    /// Each row names its sample by barcode, and the same checks are made
+    /// as when creating a library singly. Indices are found by name in the
+    /// index catalog; a row must name the index's set if the name alone is
+    /// ambiguous. If any row has errors, or on a dry run, nothing is
+    /// created and the per-row results say what would happen; otherwise
+    /// all libraries are created in one transaction.
+    #[instrument(skip(self, request), fields(dry_run = request.dry_run))]
+    pub async fn import_libraries(
+        &self,
+        request: ImportLibrariesRequest,
+        imported_by: &str,
+        role: Role,
+    ) -> Result<LibraryImportResponse, DomainError> {
+        let rows = parse_library_csv(&request.contents)?;
+        if rows.is_empty() {
+            return Err(DomainError::Validation(
+                "The library file lists no libraries".to_string(),
+            ));
+        }
+
+        let barcodes: Vec<String> = rows
+            .iter()
+            .map(|row| row.sample.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let samples: HashMap<String, Sample> = self
+            .samples
+            .find_by_barcodes(&barcodes)
+            .await?
+            .into_iter()
+            .map(|s| (s.barcode.to_string(), s))
+            .collect();
+        let catalog = match &self.index_sets {
+            Some(index_sets) if rows.iter().any(|row| row.index.is_some()) => {
+                index_sets.list().await?
+            }
+            _ => Vec::new(),
+        };
+
+        let mut results = Vec::with_capacity(rows.len());
+        let mut batch: Vec<Library> = Vec::with_capacity(rows.len());
+        let mut names = HashSet::new();
+        for row in &rows {
+            let mut errors = Vec::new();
+            let library = match self
+                .build_library(row, &samples, &catalog, imported_by, role)
+                .await
+            {
+                Ok(library) => Some(library),
+                Err(e) => {
+                    errors.push(e.to_string());
+                    None
+                }
+            };
+
+            if let Some(library) = &library {
+                if !names.insert(library.name.clone()) {
+                    errors.push(format!("Library name {} appears more than once", library.name));
+                } else if self.repository.find_by_name(&library.name).await?.is_some() {
+                    errors.push(format!("Library {} already exists", library.name));
+                }
+            }
+
+            results.push(LibraryImportRowResult {
+                line: row.line,
+                name: library.as_ref().map_or_else(|| row.name.clone(), |l| l.name.clone()),
+                sample: row.sample.clone(),
+                index: library
+                    .as_ref()
+                    .and_then(|l| l.index.as_ref())
+                    .map(|index| index.name().to_string()),
+                errors,
+            });
+            batch.extend(library);
+        }
+
+        let failed = results.iter().filter(|r| !r.errors.is_empty()).count();
+        if failed > 0 || request.dry_run {
+            info!(
+                "{} import of {} libraries: {} row(s) failed",
+                if request.dry_run { "Checked" } else { "Rejected" },
+                results.len(),
+                failed
+            );
+            return Ok(LibraryImportResponse {
+                dry_run: request.dry_run,
+                applied: false,
+                results,
+                libraries: Vec::new(),
+            });
+        }
+
+        let ids = self.repository.insert_all(&batch).await?;
+        for (library, id) in batch.iter_mut().zip(ids) {
+            library.id = id;
+            self.audit
+                .created("Library", id, &*library, imported_by)
+                .await?;
+        }
+
+        info!("Imported {} libraries", batch.len());
+
+        for library in &batch {
+            self.plugins
+                .publish(DomainEvent::LibraryCreated(library.clone()))
+                .await;
+        }
+
+        Ok(LibraryImportResponse {
+            dry_run: false,
+            applied: true,
+            results,
+            libraries: batch.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Builds the library a library sheet row describes, or explains what
+    /// is wrong with it. Name clashes are checked by the caller.
+    async fn build_library(
+        &self,
+        row: &LibraryCsvRow,
+        samples: &HashMap<String, Sample>,
+        catalog: &[IndexSet],
+        imported_by: &str,
+        role: Role,
+    ) -> Result<Library, DomainError> {
+        let sample = samples.get(&row.sample).ok_or_else(|| DomainError::NotFound {
+            entity_type: "Sample".to_string(),
+            id: row.sample.clone(),
+        })?;
+        self.access
+            .check(sample.project_id, Some(Requester::new(imported_by, role)))
+            .await?;
+        self.locks.check(sample.project_id, role).await?;
+        check_library_source(sample)?;
+
+        let mut library = Library::new(
+            0,
+            row.name.clone(),
+            self.new_barcode().await?,
+            sample.id,
+            sample.project_id,
+            LibraryDesign::from_code(&row.design),
+            parse_library_type(row.library_type.as_deref().unwrap_or("paired_end"))?,
+            row.platform.clone(),
+            imported_by.to_string(),
+        );
+        library.description = row.description.clone();
+        library.kit_name = row.kit.clone();
+        library.control_type = sample.control_type;
+        self.check_kit(&library)?;
+
+        match (&row.index, &row.index_set) {
+            (Some(index), set) => {
+                library.set_index(resolve_index(catalog, index, set.as_deref())?);
+            }
+            (None, Some(set)) => {
+                return Err(DomainError::Validation(format!(
+                    "Index set {} is given without an index",
+                    set
+                )));
+            }
+            (None, None) => {}
+        }
+
+        if let Some(name) = self.plugins.library_name(&library, sample).await? {
+            library.name = name;
+        }
+        if library.name.is_empty() {
+            return Err(DomainError::Validation("A library needs a name".to_string()));
+        }
+        self.plugins
+            .validate_library(Operation::Create, &library)
+            .await?;
+
+        Ok(library)
+    }
+
+    /// Gets a library by ID.
+    #[instrument(skip(self))]
+    pub async fn get_library(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<LibraryResponse, DomainError> {
+        let library = self.find_library(id).await?;
+        self.access.check(library.project_id, requester).await?;
+
+        Ok(library.into())
+    }
+
+    /// Gets a library by barcode.
+    #[instrument(skip(self))]
+    pub async fn get_library_by_barcode(
+        &self,
+        barcode: &str,
+        requester: Option<Requester<'_>>,
+    ) -> Result<LibraryResponse, DomainError> {
+        let library = self
+            .repository
+            .find_by_barcode(barcode)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Library".to_string(),
+                id: barcode.to_string(),
+            })?;
+        self.access.check(library.project_id, requester).await?;
+
+        Ok(library.into())
+    }
+
+    /// Lists libraries for a project.
+    #[instrument(skip(self))]
+    pub async fn list_libraries_by_project(
+        &self,
+        project_id: i32,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<LibrarySummary>, DomainError> {
+        self.access.check(project_id, requester).await?;
+
+        let options = QueryOptions::new()
+            .limit(limit.unwrap_or(100))
+            .offset(offset.unwrap_or(0))
+            .sort_by("name")
+            .ascending();
+
+        let libraries = self.repository.find_by_project(project_id, options).await?;
+
+        Ok(libraries.into_iter().map(|l| l.into()).collect())
+    }
+
+    /// Lists the libraries made from a sample.
+    #[instrument(skip(self))]
+    pub async fn list_libraries_by_sample(
+        &self,
+        sample_id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<LibrarySummary>, DomainError> {
+        let libraries = self.repository.find_by_sample(sample_id).await?;
+        if let Some(library) = libraries.first() {
+            self.access.check(library.project_id, requester).await?;
+        }
+
+        Ok(libraries.into_iter().map(|l| l.into()).collect())
+    }
+
+    /// Updates a library.
+    #[instrument(skip(self))]
+    pub async fn update_library(
+        &self,
+        id: i32,
+        request: UpdateLibraryRequest,
+        updated_by: &str,
+        role: Role,
+    ) -> Result<LibraryResponse, DomainError> {
+        let mut library = self.find_active_library(id).await?;
+        self.access
+            .check(library.project_id, Some(Requester::new(updated_by, role)))
+            .await?;
+        self.locks.check(library.project_id, role).await?;
+        let before = library.clone();
+
+        if let Some(description) = request.description {
+            library.description = Some(description);
+        }
+        if let Some(kit_name) = request.kit_name {
+            library.kit_name = Some(kit_name);
+            self.check_kit(&library)?;
+        }
+        if let Some(panel_id) = request.panel_id {
+            library.panel_id = Some(panel_id);
+            self.check_panel(&library).await?;
+        }
+        if let Some(genome_id) = request.reference_genome_id {
+            library.reference_genome_id = Some(genome_id);
+            self.check_reference_genome(&library).await?;
+        }
+        if let Some(insert_size) = request.insert_size {
+            library.insert_size = Some(insert_size);
+        }
+        if let Some(volume) = request.volume_ul {
+            library.volume = Some(Volume::microliters(volume));
+        }
+        if let Some(mass) = request.mass_ng {
+            library.mass = Some(Mass::nanograms(mass));
+        }
+        if let Some(concentration) = request.concentration_ng_ul {
+            library.concentration = Some(Concentration::ng_per_ul(concentration));
+        }
+        if let Some(cycles) = request.pcr_cycles {
+            library.pcr_cycles = Some(cycles);
+        }
+        if let Some(low_quality) = request.low_quality {
+            library.low_quality = low_quality;
+        }
+        if let Some(code) = request.control_type {
+            library.control_type = parse_control_type(&code)?;
+        }
+        if let Some(status) = request.qc_status {
+            let qc = parse_qc_status(&status)?;
+            let reason = request
+                .qc_reason
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty());
+            QcTransitionPolicy::check(library.qc_status, qc, role, reason.as_deref())?;
+
+            if qc != library.qc_status {
+                info!(
+                    "Library {} QC status changed from {} to {} by {}: {}",
+                    library.name,
+                    library.qc_status,
+                    qc,
+                    updated_by,
+                    reason.as_deref().unwrap_or("no reason given")
+                );
+                library.set_qc_status(qc);
+            }
+        }
+        library.updated_at = Utc::now();
+        self.save_update(&before, &library, updated_by).await?;
+
+        info!("Updated library: {} (ID: {})", library.name, id);
+
+        Ok(library.into())
+    }
+
+    /// Assigns a library's index, replacing any it had.
+    #[instrument(skip(self))]
+    pub async fn set_index(
+        &self,
+        id: i32,
+        request: SetLibraryIndexRequest,
+        updated_by: &str,
+        role: Role,
+    ) -> Result<LibraryResponse, DomainError> {
+        let mut library = self.find_active_library(id).await?;
+        self.access
+            .check(library.project_id, Some(Requester::new(updated_by, role)))
+            .await?;
+        self.locks.check(library.project_id, role).await?;
+        let before = library.clone();
+
+        let family = parse_index_family(&request.family)?;
+        let index = match request.i5 {
+            Some(i5) => DnaIndex::dual(request.name, request.i7, i5, family)?,
+            None => DnaIndex::single(request.name, request.i7, family)?,
+        };
+        library.set_index(index);
+        self.save_update(&before, &library, updated_by).await?;
+
+        info!("Set index of library: {} (ID: {})", library.name, id);
+
+        Ok(library.into())
+    }
+
+    /// Gives a library the index at a position of a catalog index set,
+    /// replacing any it had.
+    #[instrument(skip(self))]
+    pub async fn set_catalog_index(
+        &self,
+        id: i32,
+        request: SetCatalogIndexRequest,
+        updated_by: &str,
+        role: Role,
+    ) -> Result<LibraryResponse, DomainError> {
+        let mut library = self.find_active_library(id).await?;
+        self.access
+            .check(library.project_id, Some(Requester::new(updated_by, role)))
+            .await?;
+        self.locks.check(library.project_id, role).await?;
+        let before = library.clone();
+
+        let set = match &self.index_sets {
+            Some(index_sets) => index_sets.find_by_id(request.index_set_id).await?,
+            None => None,
+        }
+        .ok_or_else(|| DomainError::NotFound {
+            entity_type: "IndexSet".to_string(),
+            id: request.index_set_id.to_string(),
+        })?;
+        let index = set.index_at(request.position).ok_or_else(|| {
+            DomainError::Validation(format!(
+                "Index set {} has no position {}; it has {} indices",
+                set.name,
+                request.position,
+                set.indices.len()
+            ))
+        })?;
+        library.set_index(index.clone());
+        self.save_update(&before, &library, updated_by).await?;
+
+        info!(
+            "Set index of library: {} (ID: {}) to {} from {}",
+            library.name,
+            id,
+            index.name(),
+            set.name
+        );
+
+        Ok(library.into())
+    }
+
+    /// Archives a library, taking it out of pooling.
+    #[instrument(skip(self))]
+    pub async fn archive_library(
+        &self,
+        id: i32,
+        archived_by: &str,
+        role: Role,
+    ) -> Result<LibraryResponse, DomainError> {
+        let mut library = self.find_library(id).await?;
+        self.access
+            .check(library.project_id, Some(Requester::new(archived_by, role)))
+            .await?;
+        self.locks.check(library.project_id, role).await?;
+        if library.archived {
+            return Ok(library.into());
+        }
+
+        let before = library.clone();
+        library.archive();
+        self.save_update(&before, &library, archived_by).await?;
+
+        info!("Archived library: {} (ID: {})", library.name, id);
+
+        Ok(library.into())
+    }
+
+    /// Saves a changed library, checked by and announced to plugins.
+    async fn save_update(
+        &self,
+        before: &Library,
+        library: &Library,
+        updated_by: &str,
+    ) -> Result<(), DomainError> {
+        self.plugins
+            .validate_library(Operation::Update, library)
+            .await?;
+        self.repository.save(library).await?;
+        self.audit
+            .updated("Library", library.id, before, library, updated_by)
+            .await?;
+        self.plugins
+            .publish(DomainEvent::LibraryUpdated(library.clone()))
+            .await;
+
+        Ok(())
+    }
+
+    async fn find_library(&self, id: i32) -> Result<Library, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Library".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// Generates an unused library barcode.
+    async fn new_barcode(&self) -> Result<Barcode, DomainError> {
+        let barcode = self.barcode_validator.generate_barcode("LIB");
+        if self
+            .repository
+            .find_by_barcode(barcode.as_str())
+            .await?
+            .is_some()
+        {
+            return Err(DomainError::Duplicate {
+                entity_type: "Library".to_string(),
+                field: "barcode".to_string(),
+                value: barcode.to_string(),
+            });
+        }
+        Ok(barcode)
+    }
+
+    /// Checks that a library's kit, if it has one, can prepare its design
+    /// for its platform.
+    fn check_kit(&self, library: &Library) -> Result<(), DomainError> {
+        match &library.kit_name {
+            Some(kit) => Ok(self.kits.check(kit, &library.design, &library.platform)?),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that a library referencing a panel is of a targeted panel
+    /// design and that the panel version exists.
+    async fn check_panel(&self, library: &Library) -> Result<(), DomainError> {
+        let Some(panel_id) = library.panel_id else {
+            return Ok(());
+        };
+        if library.design != LibraryDesign::TargetedPanel {
+            return Err(DomainError::Validation(format!(
+                "Only targeted panel libraries reference a panel, not {} libraries",
+                library.design
+            )));
+        }
+
+        let panel = match &self.panels {
+            Some(panels) => panels.find_by_id(panel_id).await?,
+            None => None,
+        };
+        if panel.is_none() {
+            return Err(DomainError::NotFound {
+                entity_type: "Panel".to_string(),
+                id: panel_id.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the reference genome a library names is registered.
+    async fn check_reference_genome(&self, library: &Library) -> Result<(), DomainError> {
+        let Some(genome_id) = library.reference_genome_id else {
+            return Ok(());
+        };
+
+        let genome = match &self.genomes {
+            Some(genomes) => genomes.find_by_id(genome_id).await?,
+            None => None,
+        };
+        if genome.is_none() {
+            return Err(DomainError::NotFound {
+                entity_type: "ReferenceGenome".to_string(),
+                id: genome_id.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Finds a library that can still be changed.
+    async fn find_active_library(&self, id: i32) -> Result<Library, DomainError> {
+        let library = self.find_library(id).await?;
+        if library.archived {
+            return Err(DomainError::Validation(format!(
+                "Library {} is archived",
+                library.name
+            )));
+        }
+        Ok(library)
+    }
+}
+
+/// Checks that libraries can be made from a sample: it must be of a class
+/// libraries are made from, have passed QC and not be archived.
+fn check_library_source(sample: &Sample) -> Result<(), DomainError> {
+    if sample.archived {
+        return Err(DomainError::Validation(format!(
+            "Sample {} is archived",
+            sample.name
+        )));
+    }
+    if !sample.details.can_create_library() {
+        return Err(DomainError::Validation(format!(
+            "Libraries cannot be made from {} samples",
+            sample.sample_class()
+        )));
+    }
+    if !sample.qc_status.allows_progression() {
+        return Err(DomainError::Validation(format!(
+            "Sample {} has not passed QC",
+            sample.name
+        )));
+    }
+    Ok(())
+}
+
+/// Finds an index in the catalog by name. The index's set must be named
+/// if more than one set has an index of that name.
+fn resolve_index(
+    catalog: &[IndexSet],
+    name: &str,
+    set_name: Option<&str>,
+) -> Result<DnaIndex, DomainError> {
+    if let Some(set_name) = set_name {
+        let set = catalog
+            .iter()
+            .find(|set| set.name.eq_ignore_ascii_case(set_name.trim()))
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "IndexSet".to_string(),
+                id: set_name.to_string(),
+            })?;
+        return set.find_index(name).cloned().ok_or_else(|| {
+            DomainError::Validation(format!("Index set {} has no index {}", set.name, name))
+        });
+    }
+
+    let mut found = catalog
+        .iter()
+        .filter_map(|set| set.find_index(name).map(|index| (set, index)));
+    match (found.next(), found.next()) {
+        (Some((_, index)), None) => Ok(index.clone()),
+        (Some((first, _)), Some((second, _))) => Err(DomainError::Validation(format!(
+            "Index {} is in more than one set ({}, {}); name its index set",
+            name, first.name, second.name
+        ))),
+        (None, _) => Err(DomainError::NotFound {
+            entity_type: "Index".to_string(),
+            id: name.to_string(),
+        }),
+    }
+}
+
+fn parse_library_type(code: &str) -> Result<LibraryType, DomainError> {
+    match code {
+        "paired_end" => Ok(LibraryType::PairedEnd),
+        "single_end" => Ok(LibraryType::SingleEnd),
+        "mate_pair" => Ok(LibraryType::MatePair),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid library type: {}",
+            code
+        ))),
+    }
+}
+
+pub(super) fn parse_index_family(code: &str) -> Result<IndexFamily, DomainError> {
+    match code {
+        "tru_seq" => Ok(IndexFamily::TruSeq),
+        "nextera" => Ok(IndexFamily::Nextera),
+        "idt_udi" => Ok(IndexFamily::IdtUdi),
+        "ten_x" => Ok(IndexFamily::TenX),
+        "custom" => Ok(IndexFamily::Custom),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid index family: {}",
+            code
+        ))),
+    }
+}
+
+fn parse_qc_status(code: &str) -> Result<QcStatus, DomainError> {
+    match code {
+        "not_ready" => Ok(QcStatus::NotReady),
+        "ready" => Ok(QcStatus::Ready),
+        "passed" => Ok(QcStatus::Passed),
+        "failed" => Ok(QcStatus::Failed),
+        "needs_review" => Ok(QcStatus::NeedsReview),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid QC status: {}",
+            code
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        BedFile, EntityId, Panel, Project, ReferenceGenome, Sample, SampleClass,
+    };
+    use miso_domain::errors::LibraryError;
+    use miso_domain::plugins::{NamingHook, ValidationHook};
+    use miso_domain::repositories::{NewSample, VersionConflict};
+
+    use super::*;
+    use crate::test_support::OneProject;
+
+    #[derive(Default)]
+    struct InMemoryLibraries {
+        libraries: Mutex<HashMap<EntityId, Library>>,
+        /// Where samples that libraries are made from are saved
+        samples: Arc<InMemorySamples>,
+    }
+
+    #[async_trait]
+    impl LibraryRepository for InMemoryLibraries {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(self.libraries.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Library>, DomainError> {
+            let libraries = self.libraries.lock().unwrap();
+            Ok(libraries
+                .values()
+                .find(|l| l.barcode.as_str() == barcode)
+                .cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Library>, DomainError> {
+            let libraries = self.libraries.lock().unwrap();
+            Ok(libraries.values().find(|l| l.name == name).cloned())
+        }
+        async fn find_by_sample(&self, sample_id: EntityId) -> Result<Vec<Library>, DomainError> {
+            let libraries = self.libraries.lock().unwrap();
+            Ok(libraries
+                .values()
+                .filter(|l| l.sample_id == sample_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_project(
+            &self,
+            project_id: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            let libraries = self.libraries.lock().unwrap();
+            Ok(libraries
+                .values()
+                .filter(|l| l.project_id == project_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn save(&self, library: &Library) -> Result<EntityId, DomainError> {
+            let mut libraries = self.libraries.lock().unwrap();
+            let mut stored = library.clone();
+            if stored.id == 0 {
+                stored.id = libraries.len() as EntityId + 1;
+            }
+            let id = stored.id;
+            libraries.insert(id, stored);
+            Ok(id)
+        }
+        async fn insert_all(&self, libraries: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            let mut ids = Vec::new();
+            for library in libraries {
+                ids.push(self.save(library).await?);
+            }
+            Ok(ids)
+        }
+        async fn create_from_sample(
+            &self,
+            library: &Library,
+            sample: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            let conflicts = self
+                .samples
+                .update_all_versioned(std::slice::from_ref(sample))
+                .await?;
+            if !conflicts.is_empty() {
+                return Err(DomainError::ConcurrentModification {
+                    entity_type: "Sample".to_string(),
+                    id: sample.id.to_string(),
+                });
+            }
+            self.save(library).await
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemorySamples {
+        samples: Mutex<HashMap<EntityId, Sample>>,
+    }
+
+    #[async_trait]
+    impl SampleRepository for InMemorySamples {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok(self.samples.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_barcodes(&self, barcodes: &[String]) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(samples
+                .values()
+                .filter(|s| barcodes.contains(&s.barcode.to_string()))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn save(&self, sample: &Sample) -> Result<EntityId, DomainError> {
+            Ok(sample.id)
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            samples: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            let mut stored = self.samples.lock().unwrap();
+            for sample in samples {
+                let mut sample = sample.clone();
+                sample.version += 1;
+                stored.insert(sample.id, sample);
+            }
+            Ok(Vec::new())
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            Ok(0)
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            Ok(Vec::new())
+        }
+    }
+
+    struct OnePanel(Panel);
+
+    #[async_trait]
+    impl PanelRepository for OnePanel {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Panel>, DomainError> {
+            Ok(Some(self.0.clone()).filter(|p| p.id == id))
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Panel>, DomainError> {
+            Ok(vec![self.0.clone()])
+        }
+        async fn find_latest(&self, _: &str) -> Result<Option<Panel>, DomainError> {
+            Ok(Some(self.0.clone()))
+        }
+        async fn list(&self) -> Result<Vec<Panel>, DomainError> {
+            Ok(vec![self.0.clone()])
+        }
+        async fn save(&self, panel: &Panel) -> Result<EntityId, DomainError> {
+            Ok(panel.id)
+        }
+    }
+
+    struct OneGenome(ReferenceGenome);
+
+    #[async_trait]
+    impl ReferenceGenomeRepository for OneGenome {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ReferenceGenome>, DomainError> {
+            Ok(Some(self.0.clone()).filter(|g| g.id == id))
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<ReferenceGenome>, DomainError> {
+            Ok(Some(self.0.clone()).filter(|g| g.name == name))
+        }
+        async fn list(&self) -> Result<Vec<ReferenceGenome>, DomainError> {
+            Ok(vec![self.0.clone()])
+        }
+        async fn save(&self, genome: &ReferenceGenome) -> Result<EntityId, DomainError> {
+            Ok(genome.id)
+        }
+    }
+
+    struct Catalog(Vec<IndexSet>);
+
+    #[async_trait]
+    impl IndexSetRepository for Catalog {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<IndexSet>, DomainError> {
+            Ok(self.0.iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<IndexSet>, DomainError> {
+            Ok(self.0.iter().find(|s| s.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<IndexSet>, DomainError> {
+            Ok(self.0.clone())
+        }
+        async fn save(&self, set: &IndexSet) -> Result<EntityId, DomainError> {
+            Ok(set.id)
+        }
+    }
+
+    fn index_set(name: &str, indices: &[(&str, &str)]) -> IndexSet {
+        let indices = indices
+            .iter()
+            .map(|(name, i7)| DnaIndex::dual(*name, *i7, "AGGCTATA", IndexFamily::IdtUdi).unwrap())
+            .collect();
+        IndexSet::new(name.to_string(), IndexFamily::IdtUdi, indices, "admin".to_string()).unwrap()
+    }
+
+    type TestService = LibraryService<InMemoryLibraries, InMemorySamples>;
+
+    fn service_with_sample(qc_status: QcStatus, archived: bool) -> TestService {
+        let samples = Arc::new(InMemorySamples::default());
+        let mut sample = Sample::new_plain(
+            4,
+            "SAM4".to_string(),
+            Barcode::new("SAM-4").unwrap(),
+            2,
+            "Homo sapiens".to_string(),
+            "tech".to_string(),
+        );
+        sample.qc_status = qc_status;
+        sample.archived = archived;
+        samples.samples.lock().unwrap().insert(4, sample);
+
+        let libraries = InMemoryLibraries {
+            samples: samples.clone(),
+            ..Default::default()
+        };
+        LibraryService::new(Arc::new(libraries), samples)
+    }
+
+    fn create_request(name: &str) -> CreateLibraryRequest {
+        CreateLibraryRequest {
+            sample_id: 4,
+            name: name.to_string(),
+            design: "rna_seq".to_string(),
+            library_type: "paired_end".to_string(),
+            platform: "ILLUMINA".to_string(),
+            description: None,
+            kit_name: Some("TruSeq Stranded".to_string()),
+            panel_id: None,
+            reference_genome_id: None,
+            insert_size: Some(350),
+            volume_ul: None,
+            concentration_ng_ul: None,
+            pcr_cycles: None,
+            control_type: None,
+            input_ng: None,
+        }
+    }
+
+    fn index_request(i7: &str) -> SetLibraryIndexRequest {
+        SetLibraryIndexRequest {
+            name: "UDP0001".to_string(),
+            family: "idt_udi".to_string(),
+            i7: i7.to_string(),
+            i5: Some("TCGTGGAGCG".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_library_joins_its_samples_project() {
+        let service = service_with_sample(QcStatus::Passed, false);
+
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        assert_eq!(library.sample_id, 4);
+        assert_eq!(library.project_id, 2);
+        assert_eq!(library.design, "rna_seq");
+        assert!(library.barcode.starts_with("LIB-"));
+        assert_eq!(service.list_libraries_by_sample(4, None).await.unwrap().len(), 1);
+
+        let duplicate = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await;
+        assert!(matches!(duplicate, Err(DomainError::Duplicate { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_only_passed_active_samples_get_libraries() {
+        for service in [
+            service_with_sample(QcStatus::Passed, true),
+            service_with_sample(QcStatus::Ready, false),
+        ] {
+            let result = service
+                .create_library(create_request("LIB_A"), "tech", Role::Technician)
+                .await;
+
+            assert!(matches!(result, Err(DomainError::Validation(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_library_input_is_withdrawn_from_its_sample() {
+        let service = service_with_sample(QcStatus::Passed, false);
+        let with_input = |name: &str, ng: f64| CreateLibraryRequest {
+            input_ng: Some(ng),
+            ..create_request(name)
+        };
+
+        // Without a concentration there's no telling the volume taken
+        let result = service
+            .create_library(with_input("LIB_A", 200.0), "tech", Role::Technician)
+            .await;
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+
+        {
+            let mut samples = service.samples.samples.lock().unwrap();
+            let sample = samples.get_mut(&4).unwrap();
+            sample.mass = Some(Mass::nanograms(1000.0));
+            sample.volume = Some(Volume::microliters(20.0));
+            sample.concentration = Some(Concentration::ng_per_ul(50.0));
+        }
+        service
+            .create_library(with_input("LIB_A", 200.0), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let sample = service.samples.samples.lock().unwrap()[&4].clone();
+        assert_eq!(sample.mass.unwrap().as_nanograms(), 800.0);
+        assert_eq!(sample.volume.unwrap().as_microliters(), 16.0);
+        assert_eq!(sample.version, 2);
+
+        // Nothing is made, or taken, from a sample without enough left
+        let result = service
+            .create_library(with_input("LIB_B", 900.0), "tech", Role::Technician)
+            .await;
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+        assert_eq!(service.list_libraries_by_sample(4, None).await.unwrap().len(), 1);
+        let sample = service.samples.samples.lock().unwrap()[&4].clone();
+        assert_eq!(sample.mass.unwrap().as_nanograms(), 800.0);
+    }
+
+    #[tokio::test]
+    async fn test_kit_must_suit_design_and_platform() {
+        let service = service_with_sample(QcStatus::Passed, false).with_kits(
+            KitCompatibility::new()
+                .with_combination("TruSeq Stranded", LibraryDesign::RnaSeq, "illumina")
+                .with_combination("TruSeq DNA PCR-Free", LibraryDesign::Wgs, "illumina"),
+        );
+
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        let mut wrong_design = create_request("LIB_B");
+        wrong_design.design = "wgs".to_string();
+        let result = service.create_library(wrong_design, "tech", Role::Technician).await;
+        assert!(matches!(
+            result,
+            Err(DomainError::Library(LibraryError::InvalidKitType { .. }))
+        ));
+
+        let result = service
+            .update_library(
+                library.id,
+                UpdateLibraryRequest {
+                    kit_name: Some("TruSeq DNA PCR-Free".to_string()),
+                    ..Default::default()
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(DomainError::Library(LibraryError::InvalidKitType { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_only_targeted_libraries_reference_panels() {
+        let mut panel = Panel::new(
+            "CancerHotspot".to_string(),
+            "GRCh38".to_string(),
+            BedFile::parse("hotspot.bed", "chr1\t100\t200\n").unwrap(),
+            "admin".to_string(),
+        )
+        .unwrap();
+        panel.id = 3;
+        let service =
+            service_with_sample(QcStatus::Passed, false).with_panels(Arc::new(OnePanel(panel)));
+
+        let targeted = |name: &str, panel_id: i32| CreateLibraryRequest {
+            design: "targeted_panel".to_string(),
+            panel_id: Some(panel_id),
+            ..create_request(name)
+        };
+        let library = service
+            .create_library(targeted("LIB_A", 3), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(library.panel_id, Some(3));
+
+        let missing = service.create_library(targeted("LIB_B", 4), "tech", Role::Technician).await;
+        assert!(matches!(missing, Err(DomainError::NotFound { .. })));
+
+        let rna_seq = CreateLibraryRequest {
+            panel_id: Some(3),
+            ..create_request("LIB_C")
+        };
+        let result = service.create_library(rna_seq, "tech", Role::Technician).await;
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_libraries_name_registered_genomes() {
+        let mut genome = ReferenceGenome::new(
+            "mm39".to_string(),
+            "Mus musculus".to_string(),
+            None,
+            "admin".to_string(),
+        )
+        .unwrap();
+        genome.id = 2;
+        let service = service_with_sample(QcStatus::Passed, false)
+            .with_reference_genomes(Arc::new(OneGenome(genome)));
+
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(library.reference_genome_id, None);
+
+        let update = |genome_id: i32| UpdateLibraryRequest {
+            reference_genome_id: Some(genome_id),
+            ..Default::default()
+        };
+        let missing = service.update_library(library.id, update(5), "tech", Role::Technician).await;
+        assert!(matches!(missing, Err(DomainError::NotFound { .. })));
+        let updated = service
+            .update_library(library.id, update(2), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(updated.reference_genome_id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_set_index_checks_sequences() {
+        let service = service_with_sample(QcStatus::Passed, false);
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        assert!(service
+            .set_index(library.id, index_request("GAACXGAGCG"), "tech", Role::Technician)
+            .await
+            .is_err());
+
+        let indexed = service
+            .set_index(library.id, index_request("gaactgagcg"), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let index = indexed.index.unwrap();
+        assert_eq!(index.i7, "GAACTGAGCG");
+        assert_eq!(index.family, "idt_udi");
+    }
+
+    #[tokio::test]
+    async fn test_archived_libraries_cannot_change() {
+        let service = service_with_sample(QcStatus::Passed, false);
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        let archived = service
+            .archive_library(library.id, "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(archived.archived);
+
+        let update = UpdateLibraryRequest {
+            qc_status: Some("passed".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service.update_library(library.id, update, "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_qc_status_changes_follow_the_transition_policy() {
+        let service = service_with_sample(QcStatus::Passed, false);
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        let update = |status: &str, reason: Option<&str>| UpdateLibraryRequest {
+            qc_status: Some(status.to_string()),
+            qc_reason: reason.map(str::to_string),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service
+                .update_library(
+                    library.id,
+                    update("failed", Some("  ")),
+                    "tech",
+                    Role::Technician
+                )
+                .await,
+            Err(DomainError::Validation(_))
+        ));
+        let passed = service
+            .update_library(library.id, update("passed", None), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(passed.qc_status, "Passed");
+
+        assert!(matches!(
+            service
+                .update_library(
+                    library.id,
+                    update("ready", None),
+                    "manager",
+                    Role::LabManager
+                )
+                .await,
+            Err(DomainError::InvalidStateTransition { .. })
+        ));
+        assert!(matches!(
+            service
+                .update_library(
+                    library.id,
+                    update("failed", Some("Degraded on recheck")),
+                    "tech",
+                    Role::Technician
+                )
+                .await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+        let failed = service
+            .update_library(
+                library.id,
+                update("failed", Some("Degraded on recheck")),
+                "manager",
+                Role::LabManager,
+            )
+            .await
+            .unwrap();
+        assert_eq!(failed.qc_status, "Failed");
+    }
+
+    #[tokio::test]
+    async fn test_locked_projects_refuse_all_but_admins() {
+        let projects = Arc::new(OneProject::new(Project::new(
+            2,
+            "PROJ2".to_string(),
+            "Two".to_string(),
+            "admin".to_string(),
+        )));
+        let service = service_with_sample(QcStatus::Passed, false)
+            .with_project_locks(ProjectLocks::new(projects.clone()));
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        projects
+            .0
+            .lock()
+            .unwrap()
+            .lock("PI review".to_string(), "manager".to_string(), None);
+        let update = || UpdateLibraryRequest {
+            pcr_cycles: Some(8),
+            ..Default::default()
+        };
+        for refused in [
+            service
+                .update_library(library.id, update(), "manager", Role::LabManager)
+                .await
+                .map(|_| ()),
+            service
+                .archive_library(library.id, "tech", Role::Technician)
+                .await
+                .map(|_| ()),
+            service
+                .create_library(create_request("LIB_B"), "tech", Role::Technician)
+                .await
+                .map(|_| ()),
+        ] {
+            assert!(matches!(refused, Err(DomainError::ProjectLocked { .. })));
+        }
+
+        let imported = service
+            .import_libraries(
+                ImportLibrariesRequest {
+                    contents: "Sample,Name,Design,Platform\nSAM-4,LIB_C,rna_seq,ILLUMINA\n"
+                        .to_string(),
+                    dry_run: true,
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert_eq!(imported.results[0].errors, ["Project PROJ2 is locked: PI review"]);
+
+        let updated = service
+            .update_library(library.id, update(), "admin", Role::Admin)
+            .await
+            .unwrap();
+        assert_eq!(updated.pcr_cycles, Some(8));
+    }
+
+    struct SiteRules;
+
+    #[async_trait]
+    impl NamingHook for SiteRules {
+        async fn library_name(
+            &self,
+            _: &Library,
+            sample: &Sample,
+        ) -> Result<Option<String>, DomainError> {
+            Ok(Some(format!("{}_LIB", sample.name)))
+        }
+    }
+
+    #[async_trait]
+    impl ValidationHook for SiteRules {
+        async fn validate_library(
+            &self,
+            _: Operation,
+            library: &Library,
+        ) -> Result<(), DomainError> {
+            match library.kit_name {
+                Some(_) => Ok(()),
+                None => Err(DomainError::Validation("Kit is required".to_string())),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugins_name_and_check_libraries() {
+        let rules = Arc::new(SiteRules);
+        let mut plugins = PluginRegistry::new();
+        plugins.add_naming(rules.clone());
+        plugins.add_validator(rules);
+        let service = service_with_sample(QcStatus::Passed, false).with_plugins(Arc::new(plugins));
+
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(library.name, "SAM4_LIB");
+
+        // The site's name is the one checked for uniqueness
+        let duplicate = service
+            .create_library(create_request("LIB_B"), "tech", Role::Technician)
+            .await;
+        assert!(matches!(duplicate, Err(DomainError::Duplicate { .. })));
+
+        let no_kit = CreateLibraryRequest {
+            kit_name: None,
+            ..create_request("LIB_C")
+        };
+        service.repository.libraries.lock().unwrap().clear();
+        assert!(matches!(
+            service.create_library(no_kit, "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_import_resolves_indices_from_catalog() {
+        let service = service_with_sample(QcStatus::Passed, false).with_index_sets(Arc::new(
+            Catalog(vec![
+                index_set("Plate 1", &[("UDP0001", "GAACTGAGCG"), ("UDP0002", "AGGTCAGATA")]),
+                index_set("Plate 2", &[("UDP0002", "CGTCTCATAT"), ("UDP0097", "ATTCCATAAG")]),
+            ]),
+        ));
+        let import = |contents: &str, dry_run: bool| ImportLibrariesRequest {
+            contents: contents.to_string(),
+            dry_run,
+        };
+
+        let contents = "Sample,Name,Design,Platform,Index,Index Set\n\
+            SAM-4,LIB_A,wgs,ILLUMINA,udp0001,\n\
+            SAM-4,LIB_B,wgs,ILLUMINA,UDP0002,Plate 2\n";
+        let checked = service
+            .import_libraries(import(contents, true), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(!checked.applied);
+        assert!(checked.results.iter().all(|r| r.errors.is_empty()));
+        assert!(service.repository.libraries.lock().unwrap().is_empty());
+
+        let imported = service
+            .import_libraries(import(contents, false), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(imported.applied);
+        assert_eq!(imported.libraries.len(), 2);
+        assert_eq!(imported.results[0].index.as_deref(), Some("UDP0001"));
+        let index = imported.libraries[1].index.as_ref().unwrap();
+        assert_eq!(index.i7, "CGTCTCATAT");
+        assert_eq!(service.list_libraries_by_sample(4, None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_reports_every_bad_row() {
+        let service = service_with_sample(QcStatus::Passed, false).with_index_sets(Arc::new(
+            Catalog(vec![
+                index_set("Plate 1", &[("UDP0002", "AGGTCAGATA")]),
+                index_set("Plate 2", &[("UDP0002", "CGTCTCATAT")]),
+            ]),
+        ));
+        service
+            .create_library(create_request("LIB_OLD"), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        let contents = "Sample,Name,Design,Platform,Index,Library Type\n\
+            SAM-4,LIB_A,wgs,ILLUMINA,,\n\
+            SAM-9,LIB_B,wgs,ILLUMINA,,\n\
+            SAM-4,LIB_A,wgs,ILLUMINA,,\n\
+            SAM-4,LIB_OLD,wgs,ILLUMINA,,\n\
+            SAM-4,LIB_C,wgs,ILLUMINA,UDP0002,\n\
+            SAM-4,LIB_D,wgs,ILLUMINA,UDP9999,\n\
+            SAM-4,LIB_E,wgs,ILLUMINA,,circular\n";
+        let response = service
+            .import_libraries(
+                ImportLibrariesRequest {
+                    contents: contents.to_string(),
+                    dry_run: false,
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+
+        assert!(!response.applied);
+        let failed: Vec<usize> = response
+            .results
+            .iter()
+            .filter(|r| !r.errors.is_empty())
+            .map(|r| r.line)
+            .collect();
+        assert_eq!(failed, vec![3, 4, 5, 6, 7, 8]);
+        assert!(response.results[4].errors[0].contains("name its index set"));
+        assert_eq!(service.list_libraries_by_sample(4, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_index_by_set_and_position() {
+        let mut plate = index_set("Plate 1", &[("UDP0001", "GAACTGAGCG"), ("UDP0002", "AGGTCAGATA")]);
+        plate.id = 6;
+        let service = service_with_sample(QcStatus::Passed, false)
+            .with_index_sets(Arc::new(Catalog(vec![plate])));
+        let library = service
+            .create_library(create_request("LIB_A"), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let request = |index_set_id: i32, position: usize| SetCatalogIndexRequest {
+            index_set_id,
+            position,
+        };
+
+        let indexed = service
+            .set_catalog_index(library.id, request(6, 2), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let index = indexed.index.unwrap();
+        assert_eq!(index.name, "UDP0002");
+        assert_eq!(index.i7, "AGGTCAGATA");
+
+        assert!(matches!(
+            service.set_catalog_index(library.id, request(6, 3), "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service.set_catalog_index(library.id, request(7, 1), "tech", Role::Technician).await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+}
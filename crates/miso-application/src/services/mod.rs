@@ -1,8 +1,104 @@
 //! Application services for coordinating complex workflows.
 
+mod activity_service;
+mod api_key_service;
+mod api_usage_service;
+mod archive_rule_service;
+mod attachment_service;
+mod attribute_definition_service;
+mod audit_service;
+mod box_import_service;
+mod consent_service;
+mod dashboard_service;
+mod data_location_service;
+mod delivery_service;
+mod erasure_service;
+mod event_store_service;
+mod export_service;
+mod extraction_batch_service;
+mod facility_status_service;
+mod index_set_service;
+mod instrument_event_service;
+mod instrument_model_service;
+mod integrity_audit_service;
+mod label_print_service;
+mod library_aliquot_service;
+mod library_service;
+mod panel_service;
+mod pool_service;
+mod project_member_service;
 mod project_service;
+mod qc_service;
+mod qc_report_service;
+mod reagent_inventory_service;
+mod reference_genome_service;
+mod requisition_service;
+mod retention_service;
+mod run_metrics_service;
+mod run_monitor_service;
+mod run_service;
+mod sample_import_service;
+mod sample_list_service;
 mod sample_service;
+mod sample_sheet_import_service;
+mod sample_sheet_service;
+mod search_service;
+mod sequencer_service;
+mod storage_audit_service;
+mod storage_browser_service;
+mod storage_unit_service;
+mod subproject_service;
+mod transfer_service;
+mod workset_service;
 
+pub use activity_service::ActivityService;
+pub use api_key_service::ApiKeyService;
+pub use api_usage_service::{ApiCall, ApiUsageService};
+pub use archive_rule_service::ArchiveRuleService;
+pub use attachment_service::AttachmentService;
+pub use attribute_definition_service::AttributeDefinitionService;
+pub use audit_service::AuditService;
+pub use box_import_service::BoxImportService;
+pub use consent_service::ConsentService;
+pub use dashboard_service::DashboardService;
+pub use data_location_service::DataLocationService;
+pub use delivery_service::DeliveryService;
+pub use erasure_service::{hash_external_names, ErasureService};
+pub use event_store_service::EventStoreService;
+pub use export_service::ExportService;
+pub use extraction_batch_service::ExtractionBatchService;
+pub use facility_status_service::FacilityStatusService;
+pub use index_set_service::IndexSetService;
+pub use instrument_event_service::InstrumentEventService;
+pub use instrument_model_service::InstrumentModelService;
+pub use integrity_audit_service::IntegrityAuditService;
+pub use label_print_service::LabelPrintService;
+pub use library_aliquot_service::LibraryAliquotService;
+pub use library_service::LibraryService;
+pub use panel_service::PanelService;
+pub use pool_service::PoolService;
+pub use project_member_service::ProjectMemberService;
 pub use project_service::ProjectService;
+pub use qc_service::QcService;
+pub use qc_report_service::QcReportService;
+pub use reagent_inventory_service::{ReagentInventoryService, DEFAULT_EXPIRY_DAYS};
+pub use reference_genome_service::ReferenceGenomeService;
+pub use requisition_service::RequisitionService;
+pub use retention_service::{RetentionPolicy, RetentionService};
+pub use run_metrics_service::RunMetricsService;
+pub use run_monitor_service::RunMonitorService;
+pub use run_service::RunService;
+pub use sample_import_service::SampleImportService;
+pub use sample_list_service::{SampleListProjection, SampleListService};
 pub use sample_service::SampleService;
+pub use sample_sheet_import_service::SampleSheetImportService;
+pub use sample_sheet_service::SampleSheetService;
+pub use search_service::SearchService;
+pub use sequencer_service::SequencerService;
+pub use storage_audit_service::StorageAuditService;
+pub use storage_browser_service::StorageBrowserService;
+pub use storage_unit_service::StorageUnitService;
+pub use subproject_service::SubprojectService;
+pub use transfer_service::TransferService;
+pub use workset_service::WorksetService;
 
@@ -0,0 +1,234 @@
+//! Targeted panel service.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{BedFile, Panel};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::PanelRepository;
+use tracing::{info, instrument};
+
+use crate::dto::{CreatePanelRequest, ExportFile, PanelResponse, PanelVersionRequest};
+
+/// Service for targeted panels and their versioned BED files.
+pub struct PanelService<R: PanelRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: PanelRepository + ?Sized> PanelService<R> {
+    /// Creates a new panel service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Creates the first version of a panel.
+    #[instrument(skip(self, request), fields(name = %request.name))]
+    pub async fn create_panel(
+        &self,
+        request: CreatePanelRequest,
+        created_by: &str,
+    ) -> Result<PanelResponse, DomainError> {
+        if self.repository.find_latest(&request.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Panel".to_string(),
+                field: "name".to_string(),
+                value: request.name,
+            });
+        }
+
+        let bed_file = BedFile::parse(&request.bed_file_name, &request.bed_contents)?;
+        let mut panel = Panel::new(
+            request.name,
+            request.genome_build,
+            bed_file,
+            created_by.to_string(),
+        )?;
+        panel.description = request.description;
+
+        panel.id = self.repository.save(&panel).await?;
+
+        info!(
+            "Created panel: {} (ID: {}) with {} intervals",
+            panel.identifier(),
+            panel.id,
+            panel.bed_file.intervals
+        );
+
+        Ok(panel.into())
+    }
+
+    /// Records a new version of a panel, numbered after its latest version
+    /// whichever version `id` names.
+    #[instrument(skip(self, request))]
+    pub async fn create_version(
+        &self,
+        id: i32,
+        request: PanelVersionRequest,
+        created_by: &str,
+    ) -> Result<PanelResponse, DomainError> {
+        let panel = self.find_panel(id).await?;
+        let latest = self
+            .repository
+            .find_latest(&panel.name)
+            .await?
+            .unwrap_or(panel);
+
+        let bed_file = BedFile::parse(&request.bed_file_name, &request.bed_contents)?;
+        let mut version = latest.next_version(bed_file, created_by.to_string());
+        if let Some(genome_build) = request.genome_build {
+            version.genome_build = genome_build;
+        }
+        if let Some(description) = request.description {
+            version.description = Some(description);
+        }
+        version.validate()?;
+
+        version.id = self.repository.save(&version).await?;
+
+        info!(
+            "Created panel version: {} (ID: {})",
+            version.identifier(),
+            version.id
+        );
+
+        Ok(version.into())
+    }
+
+    /// Gets a panel version by ID.
+    #[instrument(skip(self))]
+    pub async fn get_panel(&self, id: i32) -> Result<PanelResponse, DomainError> {
+        Ok(self.find_panel(id).await?.into())
+    }
+
+    /// Lists every version of every panel.
+    #[instrument(skip(self))]
+    pub async fn list_panels(&self) -> Result<Vec<PanelResponse>, DomainError> {
+        let panels = self.repository.list().await?;
+        Ok(panels.into_iter().map(Into::into).collect())
+    }
+
+    /// Gets a panel version's BED file, as uploaded.
+    #[instrument(skip(self))]
+    pub async fn bed_file(&self, id: i32) -> Result<ExportFile, DomainError> {
+        let panel = self.find_panel(id).await?;
+
+        Ok(ExportFile {
+            file_name: panel.bed_file.file_name,
+            content_type: "text/plain".to_string(),
+            body: panel.bed_file.contents,
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn find_panel(&self, id: i32) -> Result<Panel, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Panel".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::EntityId;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryPanels {
+        panels: Mutex<Vec<Panel>>,
+    }
+
+    #[async_trait]
+    impl PanelRepository for InMemoryPanels {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Panel>, DomainError> {
+            let panels = self.panels.lock().unwrap();
+            Ok(panels.iter().find(|p| p.id == id).cloned())
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Panel>, DomainError> {
+            let panels = self.panels.lock().unwrap();
+            Ok(panels.iter().filter(|p| ids.contains(&p.id)).cloned().collect())
+        }
+        async fn find_latest(&self, name: &str) -> Result<Option<Panel>, DomainError> {
+            let panels = self.panels.lock().unwrap();
+            Ok(panels
+                .iter()
+                .filter(|p| p.name == name)
+                .max_by_key(|p| p.version)
+                .cloned())
+        }
+        async fn list(&self) -> Result<Vec<Panel>, DomainError> {
+            Ok(self.panels.lock().unwrap().clone())
+        }
+        async fn save(&self, panel: &Panel) -> Result<EntityId, DomainError> {
+            let mut panels = self.panels.lock().unwrap();
+            let mut panel = panel.clone();
+            if panel.id == 0 {
+                panel.id = panels.len() as EntityId + 1;
+            }
+            panels.retain(|p| p.id != panel.id);
+            panels.push(panel.clone());
+            Ok(panel.id)
+        }
+    }
+
+    fn create_request(bed_contents: &str) -> CreatePanelRequest {
+        CreatePanelRequest {
+            name: "CancerHotspot".to_string(),
+            genome_build: "GRCh37".to_string(),
+            description: None,
+            bed_file_name: "hotspot.bed".to_string(),
+            bed_contents: bed_contents.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_versions_follow_the_latest() {
+        let service = PanelService::new(Arc::new(InMemoryPanels::default()));
+        let v1 = service
+            .create_panel(create_request("chr1\t100\t200\n"), "admin")
+            .await
+            .unwrap();
+        assert_eq!(v1.identifier, "CancerHotspot_v1");
+
+        let duplicate = service
+            .create_panel(create_request("chr1\t100\t200\n"), "admin")
+            .await;
+        assert!(matches!(duplicate, Err(DomainError::Duplicate { .. })));
+
+        let version = |bed: &str| PanelVersionRequest {
+            genome_build: Some("GRCh38".to_string()),
+            description: None,
+            bed_file_name: "hotspot_v2.bed".to_string(),
+            bed_contents: bed.to_string(),
+        };
+        let v2 = service
+            .create_version(v1.id, version("chr1\t150\t250\nchr2\t0\t50\n"), "admin")
+            .await
+            .unwrap();
+        assert_eq!(v2.version, 2);
+        assert_eq!(v2.genome_build, "GRCh38");
+        assert_eq!(v2.intervals, 2);
+
+        // Versioning from an old version still numbers after the latest
+        let v3 = service
+            .create_version(v1.id, version("chr1\t150\t250\n"), "admin")
+            .await
+            .unwrap();
+        assert_eq!(v3.identifier, "CancerHotspot_v3");
+
+        assert!(service
+            .create_version(v1.id, version("chr1\t250\t150\n"), "admin")
+            .await
+            .is_err());
+
+        let bed = service.bed_file(v1.id).await.unwrap();
+        assert_eq!(bed.file_name, "hotspot.bed");
+        assert_eq!(bed.body, "chr1\t100\t200\n");
+    }
+}
@@ -0,0 +1,1338 @@
+//! Pool service for pool operations.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, Library, Pool, PoolElement, Role};
+use miso_domain::errors::{DomainError, PoolError};
+use miso_domain::repositories::{
+    IndexSetRepository, InstrumentModelRepository, LibraryRepository, PoolRepository,
+    QueryOptions,
+};
+use miso_domain::services::{
+    BarcodeValidator, CollisionCheckConfig, EquimolarPooler, IndexAssigner,
+    IndexCollisionChecker, Normalizer, PlexityLimits, PoolBalance, PoolBalancer, PoolMember,
+    PoolingLibrary, YieldTargets, DEFAULT_MIN_PIPETTE_UL,
+};
+use miso_domain::value_objects::{Concentration, DnaIndex, Volume};
+use tracing::{info, instrument};
+
+use crate::access::{ProjectAccess, Requester};
+use crate::audit::AuditTrail;
+use crate::dto::{
+    codes, AddPoolElementRequest, BalancePoolRequest, CreatePoolRequest, IndexAssignmentResponse,
+    IndexCollisionResponse, IndexSuggestionResponse, PipettingStepResponse, PoolBalanceResponse,
+    PoolResponse, PoolSummary, PoolValidationResponse, PoolingWorksheetRequest,
+    PoolingWorksheetResponse, SuggestIndicesRequest,
+};
+use crate::locks::ProjectLocks;
+
+/// Service for pool operations.
+pub struct PoolService<P, L>
+where
+    P: PoolRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    repository: Arc<P>,
+    libraries: Arc<L>,
+    barcode_validator: BarcodeValidator,
+    collision_checker: IndexCollisionChecker,
+    assigner: IndexAssigner,
+    index_sets: Option<Arc<dyn IndexSetRepository>>,
+    instrument_models: Option<Arc<dyn InstrumentModelRepository>>,
+    limits: PlexityLimits,
+    targets: YieldTargets,
+    audit: AuditTrail,
+    access: ProjectAccess,
+    locks: ProjectLocks,
+}
+
+impl<P, L> PoolService<P, L>
+where
+    P: PoolRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    /// Creates a new pool service.
+    pub fn new(repository: Arc<P>, libraries: Arc<L>) -> Self {
+        Self {
+            repository,
+            libraries,
+            barcode_validator: BarcodeValidator::new(),
+            collision_checker: IndexCollisionChecker::new(),
+            assigner: IndexAssigner::default(),
+            index_sets: None,
+            instrument_models: None,
+            limits: PlexityLimits::new(),
+            targets: YieldTargets::new(),
+            audit: AuditTrail::default(),
+            access: ProjectAccess::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Limits how many library aliquots pools may hold.
+    pub fn with_limits(mut self, limits: PlexityLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the reads library designs need and lanes give, from which
+    /// pool proportions are proposed.
+    pub fn with_yield_targets(mut self, targets: YieldTargets) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Sets how far apart the indices of a pool must be.
+    pub fn with_collision_config(mut self, config: CollisionCheckConfig) -> Self {
+        self.collision_checker = IndexCollisionChecker::with_config(config.clone());
+        self.assigner = IndexAssigner::new(config);
+        self
+    }
+
+    /// Uses the index catalog to suggest indices for libraries to pool.
+    pub fn with_index_sets(mut self, index_sets: Arc<dyn IndexSetRepository>) -> Self {
+        self.index_sets = Some(index_sets);
+        self
+    }
+
+    /// Uses the instrument model catalog to tell whether a pool's
+    /// container model is a patterned flow cell.
+    pub fn with_instrument_models(mut self, models: Arc<dyn InstrumentModelRepository>) -> Self {
+        self.instrument_models = Some(models);
+        self
+    }
+
+    /// Records pool changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Keeps pools to the members of their libraries' projects.
+    ///
+    /// A pool belongs to the projects of its libraries, and anyone in one
+    /// of them may see and change it; an empty pool is open to all.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Refuses changes to pools holding libraries of locked projects, and
+    /// adding those libraries to pools, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Creates a new, empty pool.
+    #[instrument(skip(self))]
+    pub async fn create_pool(
+        &self,
+        request: CreatePoolRequest,
+        created_by: &str,
+    ) -> Result<PoolResponse, DomainError> {
+        // Generate a unique barcode
+        let barcode = self.barcode_validator.generate_barcode("POOL");
+        if self
+            .repository
+            .find_by_barcode(barcode.as_str())
+            .await?
+            .is_some()
+        {
+            return Err(DomainError::Duplicate {
+                entity_type: "Pool".to_string(),
+                field: "barcode".to_string(),
+                value: barcode.to_string(),
+            });
+        }
+
+        let mut pool = Pool::new(
+            0,
+            request.name,
+            barcode,
+            request.platform,
+            created_by.to_string(),
+        );
+        pool.description = request.description;
+        pool.container_model = request.container_model;
+        pool.volume = request.volume_ul.map(Volume::microliters);
+        self.apply_limit(&mut pool);
+
+        let id = self.repository.save(&pool).await?;
+        pool.id = id;
+        self.audit.created("Pool", id, &pool, created_by).await?;
+
+        info!("Created pool: {} (ID: {})", pool.name, id);
+
+        Ok(pool.into())
+    }
+
+    /// Gets a pool by ID.
+    #[instrument(skip(self))]
+    pub async fn get_pool(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<PoolResponse, DomainError> {
+        let pool = self.find_pool(id).await?;
+        self.check_access(&pool, requester).await?;
+
+        Ok(pool.into())
+    }
+
+    /// Lists pools, newest first.
+    ///
+    /// Pools the requester can't see are left out of the page, so a page
+    /// may hold fewer than `limit`.
+    #[instrument(skip(self))]
+    pub async fn list_pools(
+        &self,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<PoolSummary>, DomainError> {
+        let options = QueryOptions::new()
+            .limit(limit.unwrap_or(100))
+            .offset(offset.unwrap_or(0))
+            .sort_by("created_at")
+            .descending();
+
+        let mut pools = self.repository.list(options).await?;
+        if let Some(projects) = self.access.projects(requester).await? {
+            let ids: Vec<EntityId> = pools.iter().flat_map(|p| p.library_ids()).collect();
+            let project_of: HashMap<EntityId, EntityId> = self
+                .libraries
+                .find_by_ids(&ids)
+                .await?
+                .into_iter()
+                .map(|l| (l.id, l.project_id))
+                .collect();
+            pools.retain(|pool| {
+                let mut pool_projects = pool
+                    .library_ids()
+                    .into_iter()
+                    .filter_map(|id| project_of.get(&id))
+                    .peekable();
+                pool_projects.peek().is_none() || pool_projects.any(|p| projects.contains(p))
+            });
+        }
+
+        Ok(pools.into_iter().map(|p| p.into()).collect())
+    }
+
+    /// Adds a library aliquot to a pool.
+    ///
+    /// The library must be poolable, and its index far enough from those
+    /// already in the pool to be demultiplexed; otherwise this fails with
+    /// [`PoolError::IndexCollision`] naming the closest pair. A pool at its
+    /// plexity limit fails with [`PoolError::CapacityExceeded`].
+    #[instrument(skip(self))]
+    pub async fn add_element(
+        &self,
+        id: i32,
+        request: AddPoolElementRequest,
+        added_by: &str,
+        role: Role,
+    ) -> Result<PoolResponse, DomainError> {
+        let requester = Requester::new(added_by, role);
+        let mut pool = self.find_pool(id).await?;
+        self.check_changeable(&pool, requester).await?;
+        let before = pool.clone();
+        if pool.sequenced {
+            return Err(PoolError::AlreadySequenced(pool.name).into());
+        }
+
+        let library = self
+            .libraries
+            .find_by_id(request.library_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Library".to_string(),
+                id: request.library_id.to_string(),
+            })?;
+        self.access.check(library.project_id, Some(requester)).await?;
+        self.locks.check(library.project_id, role).await?;
+        if let Some(reason) = unpoolable_reason(&library) {
+            return Err(DomainError::Validation(format!(
+                "Library {} cannot be pooled: {}",
+                library.name, reason
+            )));
+        }
+
+        let members = self.libraries.find_by_ids(&pool.library_ids()).await?;
+        pool.add_element(PoolElement {
+            library_aliquot_id: request.library_aliquot_id.unwrap_or(library.id),
+            library_id: library.id,
+            volume: request.volume_ul.map(Volume::microliters),
+            proportion: request.proportion,
+        })?;
+
+        let existing: Vec<(String, DnaIndex)> = members
+            .into_iter()
+            .filter_map(|l| l.index.map(|index| (l.name, index)))
+            .collect();
+        if let Some(index) = &library.index {
+            let closest = existing
+                .iter()
+                .filter_map(|entry| {
+                    self.collision_checker
+                        .can_add_index(std::slice::from_ref(entry), &library.name, index)
+                        .err()
+                })
+                .min_by_key(|c| c.distance);
+            if let Some(collision) = closest {
+                return Err(collision.to_error().into());
+            }
+        }
+
+        self.repository.save(&pool).await?;
+        self.audit
+            .updated("Pool", id, &before, &pool, added_by)
+            .await?;
+
+        info!(
+            "Added library {} to pool: {} (ID: {})",
+            library.name, pool.name, id
+        );
+
+        Ok(pool.into())
+    }
+
+    /// Removes a library aliquot from a pool.
+    #[instrument(skip(self))]
+    pub async fn remove_element(
+        &self,
+        id: i32,
+        library_aliquot_id: i32,
+        removed_by: &str,
+        role: Role,
+    ) -> Result<PoolResponse, DomainError> {
+        let mut pool = self.find_pool(id).await?;
+        self.check_changeable(&pool, Requester::new(removed_by, role))
+            .await?;
+        let before = pool.clone();
+        if !pool
+            .elements
+            .iter()
+            .any(|e| e.library_aliquot_id == library_aliquot_id)
+        {
+            return Err(DomainError::NotFound {
+                entity_type: "PoolElement".to_string(),
+                id: library_aliquot_id.to_string(),
+            });
+        }
+
+        pool.remove_element(library_aliquot_id)?;
+        self.repository.save(&pool).await?;
+        self.audit
+            .updated("Pool", id, &before, &pool, removed_by)
+            .await?;
+
+        info!(
+            "Removed aliquot {} from pool: {} (ID: {})",
+            library_aliquot_id, pool.name, id
+        );
+
+        Ok(pool.into())
+    }
+
+    /// Returns false only if a catalogued instrument model accepts the
+    /// pool's container model and none lists it as patterned.
+    async fn is_patterned(&self, pool: &Pool) -> Result<bool, DomainError> {
+        let (Some(models), Some(container_model)) =
+            (&self.instrument_models, &pool.container_model)
+        else {
+            return Ok(true);
+        };
+        let models = models.list().await?;
+        let accepting: Vec<_> = models
+            .iter()
+            .filter(|m| m.supports_container(container_model))
+            .collect();
+        Ok(accepting.is_empty() || accepting.iter().any(|m| m.is_patterned(container_model)))
+    }
+
+    /// Checks whether a pool's libraries can be sequenced together.
+    ///
+    /// Index hopping is assessed for the pool's container model, or for a
+    /// patterned flow cell, the worst case, if the catalog doesn't know it.
+    #[instrument(skip(self))]
+    pub async fn validate_pool(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<PoolValidationResponse, DomainError> {
+        let pool = self.find_pool(id).await?;
+        self.check_access(&pool, requester).await?;
+        let members = self.libraries.find_by_ids(&pool.library_ids()).await?;
+
+        let collisions: Vec<IndexCollisionResponse> = self
+            .collision_checker
+            .check_libraries(&members)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let unpoolable_libraries: Vec<String> = members
+            .iter()
+            .filter(|l| !l.can_pool())
+            .map(|l| l.name.clone())
+            .collect();
+        let hopping = pool.assess_index_hopping(&members, self.is_patterned(&pool).await?);
+
+        let mut warnings = hopping.warnings();
+        if pool.is_empty() {
+            warnings.insert(0, PoolError::EmptyPool(pool.name.clone()).to_string());
+        }
+        if let Some(max_size) = pool.max_size.filter(|max| pool.size() > *max) {
+            warnings.insert(
+                0,
+                format!(
+                    "Pool {} holds {} libraries, more than its limit of {}",
+                    pool.name,
+                    pool.size(),
+                    max_size
+                ),
+            );
+        }
+
+        Ok(PoolValidationResponse {
+            valid: !pool.is_empty() && collisions.is_empty() && unpoolable_libraries.is_empty(),
+            collisions,
+            unpoolable_libraries,
+            hopping_risk: crate::dto::codes::hop_risk(hopping.risk).to_string(),
+            warnings,
+        })
+    }
+
+    /// Suggests catalog indices for libraries about to be pooled, so that
+    /// no two indices in the pool collide.
+    ///
+    /// Libraries that have indices keep them; the others are given indices
+    /// from the named sets, tried in order. Nothing is changed; each
+    /// suggestion names the set and position to give the library.
+    #[instrument(skip(self))]
+    pub async fn suggest_indices(
+        &self,
+        request: SuggestIndicesRequest,
+        requester: Option<Requester<'_>>,
+    ) -> Result<IndexAssignmentResponse, DomainError> {
+        let found = self.libraries.find_by_ids(&request.library_ids).await?;
+        let mut libraries: Vec<&Library> = Vec::with_capacity(request.library_ids.len());
+        for id in &request.library_ids {
+            let library = found.iter().find(|l| l.id == *id).ok_or_else(|| {
+                DomainError::NotFound {
+                    entity_type: "Library".to_string(),
+                    id: id.to_string(),
+                }
+            })?;
+            if libraries.iter().any(|l| l.id == *id) {
+                return Err(DomainError::Validation(format!(
+                    "Library {} is listed more than once",
+                    library.name
+                )));
+            }
+            self.access.check(library.project_id, requester).await?;
+            libraries.push(library);
+        }
+
+        let mut sets = Vec::with_capacity(request.index_set_ids.len());
+        for id in &request.index_set_ids {
+            let set = match &self.index_sets {
+                Some(index_sets) => index_sets.find_by_id(*id).await?,
+                None => None,
+            }
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "IndexSet".to_string(),
+                id: id.to_string(),
+            })?;
+            sets.push(set);
+        }
+
+        // Indices libraries have can't be changed here, so must already fit
+        let fixed: Vec<(String, DnaIndex)> = libraries
+            .iter()
+            .filter_map(|l| l.index.clone().map(|index| (l.name.clone(), index)))
+            .collect();
+        if let Some(collision) = self
+            .collision_checker
+            .check_indices(&fixed)
+            .into_iter()
+            .min_by_key(|c| c.distance)
+        {
+            return Err(collision.to_error().into());
+        }
+
+        let candidates: Vec<(i32, usize, &DnaIndex)> = sets
+            .iter()
+            .flat_map(|set| {
+                set.indices
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, index)| (set.id, i + 1, index))
+            })
+            .collect();
+        let unindexed = libraries.iter().filter(|l| l.index.is_none()).count();
+        let fixed_indices: Vec<DnaIndex> = fixed.into_iter().map(|(_, index)| index).collect();
+        let candidate_indices: Vec<DnaIndex> =
+            candidates.iter().map(|(.., index)| (*index).clone()).collect();
+        let assignment = self
+            .assigner
+            .assign(&fixed_indices, unindexed, &candidate_indices)
+            .ok_or_else(|| {
+                let set_names: Vec<&str> = sets.iter().map(|s| s.name.as_str()).collect();
+                DomainError::Validation(format!(
+                    "No {} indices from {} are at least {} apart from each other and the \
+                     libraries' own indices",
+                    unindexed,
+                    set_names.join(", "),
+                    self.assigner.config().min_distance
+                ))
+            })?;
+
+        let mut assigned = assignment.candidates.iter().map(|&i| candidates[i]);
+        let mut suggestions = Vec::with_capacity(libraries.len());
+        for library in &libraries {
+            let (index_set_id, position, index) = match &library.index {
+                Some(index) => (None, None, index),
+                None => match assigned.next() {
+                    Some((set_id, position, index)) => (Some(set_id), Some(position), index),
+                    None => break,
+                },
+            };
+            suggestions.push(IndexSuggestionResponse {
+                library_id: library.id,
+                library_name: library.name.clone(),
+                kept: index_set_id.is_none(),
+                index_set_id,
+                position,
+                index_name: index.name().to_string(),
+                i7: index.i7().to_string(),
+                i5: index.i5().map(str::to_string),
+            });
+        }
+
+        info!(
+            "Suggested {} indices from {} set(s) for {} libraries",
+            unindexed,
+            sets.len(),
+            libraries.len()
+        );
+
+        Ok(IndexAssignmentResponse {
+            min_distance: self.assigner.config().min_distance,
+            method: (unindexed > 0)
+                .then(|| codes::assignment_method(assignment.method).to_string()),
+            libraries: suggestions,
+        })
+    }
+
+    /// Proposes each library's share of a pool, from the reads its design
+    /// needs and the reads a lane gives, and with a target molarity the
+    /// volume of each to pool.
+    #[instrument(skip(self))]
+    pub async fn balance_pool(
+        &self,
+        id: i32,
+        request: BalancePoolRequest,
+        requester: Option<Requester<'_>>,
+    ) -> Result<PoolBalanceResponse, DomainError> {
+        let pool = self.find_pool(id).await?;
+        self.check_access(&pool, requester).await?;
+        let balance = self.balance(&pool, &request).await?;
+        Ok(PoolBalanceResponse::new(pool.id, &balance))
+    }
+
+    /// Sets each library's share of a pool, and its volume if one can be
+    /// worked out, to those proposed.
+    #[instrument(skip(self))]
+    pub async fn apply_balance(
+        &self,
+        id: i32,
+        request: BalancePoolRequest,
+        balanced_by: &str,
+        role: Role,
+    ) -> Result<PoolResponse, DomainError> {
+        let mut pool = self.find_pool(id).await?;
+        self.check_changeable(&pool, Requester::new(balanced_by, role))
+            .await?;
+        if pool.sequenced {
+            return Err(PoolError::AlreadySequenced(pool.name).into());
+        }
+        let before = pool.clone();
+        let balance = self.balance(&pool, &request).await?;
+
+        for (element, share) in pool.elements.iter_mut().zip(&balance.shares) {
+            element.proportion = Some(share.proportion);
+            if share.volume.is_some() {
+                element.volume = share.volume;
+            }
+        }
+        if let Some(volume_ul) = request.volume_ul.filter(|_| balance.diluent.is_some()) {
+            pool.volume = Some(Volume::microliters(volume_ul));
+        }
+        pool.updated_at = chrono::Utc::now();
+        self.repository.save(&pool).await?;
+        self.audit
+            .updated("Pool", id, &before, &pool, balanced_by)
+            .await?;
+
+        info!(
+            "Balanced pool: {} (ID: {}) over {} lane(s)",
+            pool.name, id, balance.lanes
+        );
+
+        Ok(pool.into())
+    }
+
+    /// Works out the volume of each library to pipette to pool them
+    /// equimolar at a target molarity and volume.
+    ///
+    /// Libraries are taken at their recorded concentration and insert size
+    /// unless the request gives a molarity or other measurements.
+    #[instrument(skip(self, request), fields(libraries = request.libraries.len()))]
+    pub async fn pooling_worksheet(
+        &self,
+        request: PoolingWorksheetRequest,
+        requester: Option<Requester<'_>>,
+    ) -> Result<PoolingWorksheetResponse, DomainError> {
+        let ids: Vec<i32> = request.libraries.iter().map(|l| l.library_id).collect();
+        let libraries = self.libraries.find_by_ids(&ids).await?;
+
+        let mut pooled = Vec::with_capacity(request.libraries.len());
+        for wanted in &request.libraries {
+            let library = libraries
+                .iter()
+                .find(|l| l.id == wanted.library_id)
+                .ok_or_else(|| DomainError::NotFound {
+                    entity_type: "Library".to_string(),
+                    id: wanted.library_id.to_string(),
+                })?;
+            self.access.check(library.project_id, requester).await?;
+            let concentration = match (wanted.molarity_nm, wanted.concentration_ng_ul) {
+                (Some(nm), _) => Concentration::nanomolar(nm),
+                (None, Some(ng_ul)) => Concentration::ng_per_ul(ng_ul),
+                (None, None) => library.concentration.ok_or_else(|| {
+                    DomainError::Validation(format!(
+                        "Library {} has no concentration; give one",
+                        library.name
+                    ))
+                })?,
+            };
+            pooled.push(PoolingLibrary {
+                library_id: library.id,
+                concentration,
+                // The insert size stands in for the fragment size
+                fragment_size_bp: wanted.fragment_size.or(library.insert_size),
+            });
+        }
+
+        let worksheet = EquimolarPooler::worksheet(
+            &pooled,
+            Concentration::nanomolar(request.target_nm),
+            Volume::microliters(request.volume_ul),
+            Volume::microliters(request.min_pipette_ul.unwrap_or(DEFAULT_MIN_PIPETTE_UL)),
+        )?;
+
+        let steps = worksheet
+            .steps
+            .iter()
+            .map(|step| {
+                let library = libraries
+                    .iter()
+                    .find(|l| l.id == step.library_id)
+                    .expect("every step is of a library found above");
+                PipettingStepResponse {
+                    library_id: library.id,
+                    library_name: library.name.clone(),
+                    barcode: library.barcode.to_string(),
+                    molarity_nm: step.molarity_nm,
+                    predilution_library_ul: step.predilution.map(|d| d.library.as_microliters()),
+                    predilution_diluent_ul: step.predilution.map(|d| d.diluent.as_microliters()),
+                    volume_ul: step.volume.as_microliters(),
+                }
+            })
+            .collect();
+
+        Ok(PoolingWorksheetResponse {
+            target_nm: worksheet.target_nm,
+            volume_ul: worksheet.final_volume.as_microliters(),
+            per_library_nm: worksheet.per_library_nm,
+            steps,
+            diluent_ul: worksheet.diluent.as_microliters(),
+        })
+    }
+
+    async fn balance(
+        &self,
+        pool: &Pool,
+        request: &BalancePoolRequest,
+    ) -> Result<PoolBalance, DomainError> {
+        let lane_reads = request
+            .lane_reads
+            .or_else(|| {
+                self.targets
+                    .lane_reads(&pool.platform, pool.container_model.as_deref())
+            })
+            .ok_or_else(|| {
+                DomainError::Validation(format!(
+                    "No lane output is configured for pool {}; give the reads a lane gives",
+                    pool.name
+                ))
+            })?;
+
+        let libraries = self.libraries.find_by_ids(&pool.library_ids()).await?;
+        let mut members = Vec::with_capacity(pool.elements.len());
+        let mut untargeted = Vec::new();
+        for element in &pool.elements {
+            let Some(library) = libraries.iter().find(|l| l.id == element.library_id) else {
+                return Err(DomainError::NotFound {
+                    entity_type: "Library".to_string(),
+                    id: element.library_id.to_string(),
+                });
+            };
+            match self.targets.required_reads(&library.design) {
+                Some(required_reads) => members.push(PoolMember {
+                    library_aliquot_id: element.library_aliquot_id,
+                    library_id: library.id,
+                    required_reads,
+                    // The insert size stands in for the fragment size
+                    molarity_nm: library.concentration.and_then(|concentration| {
+                        Normalizer::molarity_nm(concentration, library.insert_size)
+                    }),
+                }),
+                None => untargeted.push(format!("{} ({})", library.name, library.design)),
+            }
+        }
+        if !untargeted.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "No read target is configured for the design of {}",
+                untargeted.join(", ")
+            )));
+        }
+
+        let volume_ul = request
+            .volume_ul
+            .or_else(|| pool.volume.map(|v| v.as_microliters()));
+        let dilution = match (request.target_nm, volume_ul) {
+            (Some(target_nm), Some(volume_ul)) => Some((
+                Concentration::nanomolar(target_nm),
+                Volume::microliters(volume_ul),
+            )),
+            (Some(_), None) => {
+                return Err(DomainError::Validation(format!(
+                    "Pool {} has no volume; give the volume to make",
+                    pool.name
+                )))
+            }
+            _ => None,
+        };
+
+        PoolBalancer::balance(&members, lane_reads, dilution)
+    }
+
+    /// Checks that a requester may reach a pool through one of its
+    /// libraries' projects.
+    async fn check_access(
+        &self,
+        pool: &Pool,
+        requester: Option<Requester<'_>>,
+    ) -> Result<(), DomainError> {
+        if !self.access.is_enforced() {
+            return Ok(());
+        }
+        let projects = self.projects_of(pool).await?;
+        self.access.check_any(&projects, requester).await
+    }
+
+    /// Checks that the requester may see a pool and that none of its
+    /// projects is locked to them.
+    async fn check_changeable(
+        &self,
+        pool: &Pool,
+        requester: Requester<'_>,
+    ) -> Result<(), DomainError> {
+        let projects = self.projects_of(pool).await?;
+        self.access.check_any(&projects, Some(requester)).await?;
+        for &project_id in &projects {
+            self.locks.check(project_id, requester.role).await?;
+        }
+        Ok(())
+    }
+
+    /// The projects of a pool's libraries.
+    async fn projects_of(&self, pool: &Pool) -> Result<HashSet<EntityId>, DomainError> {
+        Ok(self
+            .libraries
+            .find_by_ids(&pool.library_ids())
+            .await?
+            .into_iter()
+            .map(|l| l.project_id)
+            .collect())
+    }
+
+    async fn find_pool(&self, id: i32) -> Result<Pool, DomainError> {
+        let mut pool = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Pool".to_string(),
+                id: id.to_string(),
+            })?;
+        self.apply_limit(&mut pool);
+        Ok(pool)
+    }
+
+    fn apply_limit(&self, pool: &mut Pool) {
+        pool.max_size = self
+            .limits
+            .max_size(&pool.platform, pool.container_model.as_deref());
+    }
+}
+
+/// Why a library can't be pooled, if it can't.
+fn unpoolable_reason(library: &Library) -> Option<&'static str> {
+    if library.archived {
+        Some("it is archived")
+    } else if library.index.is_none() {
+        Some("it has no index")
+    } else if !library.qc_status.allows_progression() {
+        Some("it has not passed QC")
+    } else if library.low_quality {
+        Some("it is marked low quality")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        EntityId, IndexSet, InstrumentModel, LibraryDesign, LibraryType, Platform, Project, Sample,
+    };
+    use miso_domain::value_objects::{Barcode, IndexFamily, QcStatus};
+
+    use super::*;
+    use crate::test_support::{Members, OneProject};
+
+    #[derive(Default)]
+    struct InMemoryPools {
+        pools: Mutex<HashMap<EntityId, Pool>>,
+    }
+
+    #[async_trait]
+    impl PoolRepository for InMemoryPools {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Pool>, DomainError> {
+            Ok(self.pools.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            Ok(None)
+        }
+
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            Ok(self.pools.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn save(&self, pool: &Pool) -> Result<EntityId, DomainError> {
+            let mut pools = self.pools.lock().unwrap();
+            let mut stored = pool.clone();
+            if stored.id == 0 {
+                stored.id = pools.len() as EntityId + 1;
+            }
+            let id = stored.id;
+            pools.insert(id, stored);
+            Ok(id)
+        }
+
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryLibraries {
+        libraries: HashMap<EntityId, Library>,
+    }
+
+    #[async_trait]
+    impl LibraryRepository for InMemoryLibraries {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(self.libraries.get(&id).cloned())
+        }
+
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(ids
+                .iter()
+                .filter_map(|id| self.libraries.get(id).cloned())
+                .collect())
+        }
+
+        async fn save(&self, library: &Library) -> Result<EntityId, DomainError> {
+            Ok(library.id)
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    fn library(id: EntityId, i7: Option<&str>) -> Library {
+        let mut library = Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        library.set_qc_status(QcStatus::Passed);
+        if let Some(i7) = i7 {
+            library
+                .set_index(DnaIndex::single(format!("I{}", id), i7, IndexFamily::TruSeq).unwrap());
+        }
+        library
+    }
+
+    async fn service_with_pool() -> PoolService<InMemoryPools, InMemoryLibraries> {
+        service_with_limits(PlexityLimits::new()).await
+    }
+
+    async fn service_with_limits(
+        limits: PlexityLimits,
+    ) -> PoolService<InMemoryPools, InMemoryLibraries> {
+        let libraries = InMemoryLibraries {
+            libraries: [
+                library(1, Some("ACGTACGT")),
+                library(2, Some("ACGTACGA")),
+                library(3, Some("TTTTGGGG")),
+                library(4, None),
+            ]
+            .into_iter()
+            .map(|l| (l.id, l))
+            .collect(),
+        };
+        let service = PoolService::new(Arc::new(InMemoryPools::default()), Arc::new(libraries))
+            .with_limits(limits);
+        let request = CreatePoolRequest {
+            name: "POOL_A".to_string(),
+            platform: "ILLUMINA".to_string(),
+            container_model: None,
+            description: None,
+            volume_ul: None,
+        };
+        service.create_pool(request, "tech").await.unwrap();
+        service
+    }
+
+    fn add(library_id: EntityId) -> AddPoolElementRequest {
+        AddPoolElementRequest {
+            library_id,
+            library_aliquot_id: None,
+            volume_ul: Some(5.0),
+            proportion: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_colliding_library_is_rejected() {
+        let service = service_with_pool().await;
+        service.add_element(1, add(1), "tech", Role::Technician).await.unwrap();
+        service.add_element(1, add(3), "tech", Role::Technician).await.unwrap();
+
+        let result = service.add_element(1, add(2), "tech", Role::Technician).await;
+
+        match result {
+            Err(DomainError::Pool(PoolError::IndexCollision {
+                lib1,
+                lib2,
+                distance,
+                required,
+            })) => {
+                assert_eq!((lib1.as_str(), lib2.as_str()), ("LIB1", "LIB2"));
+                assert_eq!((distance, required), (1, 3));
+            }
+            other => panic!("expected a collision, got {:?}", other),
+        }
+        assert_eq!(service.get_pool(1, None).await.unwrap().elements.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unindexed_and_duplicate_libraries_are_rejected() {
+        let service = service_with_pool().await;
+        service.add_element(1, add(1), "tech", Role::Technician).await.unwrap();
+
+        assert!(matches!(
+            service.add_element(1, add(4), "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service.add_element(1, add(1), "tech", Role::Technician).await,
+            Err(DomainError::Pool(PoolError::DuplicateLibrary(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pool_at_platform_limit_is_full() {
+        let service = service_with_limits(PlexityLimits::new().with_platform("illumina", 1)).await;
+        service.add_element(1, add(1), "tech", Role::Technician).await.unwrap();
+
+        assert!(matches!(
+            service.add_element(1, add(3), "tech", Role::Technician).await,
+            Err(DomainError::Pool(PoolError::CapacityExceeded(_, 1)))
+        ));
+        assert_eq!(service.get_pool(1, None).await.unwrap().max_size, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_validate_and_remove() {
+        let service = service_with_pool().await;
+        assert!(!service.validate_pool(1, None).await.unwrap().valid);
+
+        service.add_element(1, add(1), "tech", Role::Technician).await.unwrap();
+        service.add_element(1, add(3), "tech", Role::Technician).await.unwrap();
+        let validation = service.validate_pool(1, None).await.unwrap();
+        assert!(validation.valid);
+        assert_eq!(validation.hopping_risk, "high");
+
+        let pool = service.remove_element(1, 3, "tech", Role::Technician).await.unwrap();
+        assert_eq!(pool.elements.len(), 1);
+        assert!(matches!(
+            service.remove_element(1, 3, "tech", Role::Technician).await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+
+    struct Instruments(Vec<InstrumentModel>);
+
+    #[async_trait]
+    impl InstrumentModelRepository for Instruments {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError> {
+            Ok(self.0.clone())
+        }
+        async fn save(&self, _: &InstrumentModel) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hopping_is_assessed_for_the_pools_container() {
+        let mut miseq = InstrumentModel::new(Platform::Illumina, "MiSeq".to_string(), 1);
+        miseq.container_models = vec!["MiSeq v3 Flow Cell".to_string()];
+        let mut novaseq = InstrumentModel::new(Platform::Illumina, "NovaSeq 6000".to_string(), 4);
+        novaseq.container_models = vec!["S4 Flow Cell".to_string()];
+        novaseq.patterned_containers = vec!["S4 Flow Cell".to_string()];
+        let service = service_with_pool()
+            .await
+            .with_instrument_models(Arc::new(Instruments(vec![miseq, novaseq])));
+
+        let mut risks = Vec::new();
+        for container_model in ["MiSeq v3 Flow Cell", "S4 Flow Cell", "Flongle"] {
+            let pool = service
+                .create_pool(
+                    CreatePoolRequest {
+                        name: format!("POOL_{}", container_model),
+                        platform: "ILLUMINA".to_string(),
+                        container_model: Some(container_model.to_string()),
+                        description: None,
+                        volume_ul: None,
+                    },
+                    "tech",
+                )
+                .await
+                .unwrap();
+            for aliquot in [1, 3] {
+                service
+                    .add_element(pool.id, add(aliquot), "tech", Role::Technician)
+                    .await
+                    .unwrap();
+            }
+            risks.push(service.validate_pool(pool.id, None).await.unwrap().hopping_risk);
+        }
+        // An uncatalogued container is assessed as patterned, the worst case
+        assert_eq!(risks, vec!["low", "high", "high"]);
+    }
+
+    #[tokio::test]
+    async fn test_locked_projects_pools_change_only_by_admins() {
+        let mut project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        project.lock("PI review".to_string(), "lm".to_string(), None);
+        let service = service_with_pool()
+            .await
+            .with_project_locks(ProjectLocks::new(Arc::new(OneProject::new(project))));
+
+        assert!(matches!(
+            service.add_element(1, add(1), "tech", Role::Technician).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        service.add_element(1, add(1), "admin", Role::Admin).await.unwrap();
+
+        assert!(matches!(
+            service.remove_element(1, 1, "tech", Role::Technician).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        assert_eq!(service.get_pool(1, None).await.unwrap().elements.len(), 1);
+        service.remove_element(1, 1, "admin", Role::Admin).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pool_planning_reaches_members_only() {
+        let service = service_with_pool()
+            .await
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        service.add_element(1, add(1), "member", Role::Technician).await.unwrap();
+        let worksheet = || PoolingWorksheetRequest {
+            target_nm: 4.0,
+            volume_ul: 50.0,
+            min_pipette_ul: None,
+            libraries: vec![crate::dto::PoolingLibraryRequest {
+                library_id: 1,
+                molarity_nm: Some(10.0),
+                concentration_ng_ul: None,
+                fragment_size: None,
+            }],
+        };
+        let suggestion = || SuggestIndicesRequest {
+            library_ids: vec![1],
+            index_set_ids: Vec::new(),
+        };
+
+        let member = Some(Requester::new("member", Role::Technician));
+        assert!(service.validate_pool(1, member).await.unwrap().valid);
+        assert!(service.pooling_worksheet(worksheet(), member).await.is_ok());
+        for requester in [None, Some(Requester::new("outsider", Role::Technician))] {
+            let denied = |result: Result<(), DomainError>| {
+                matches!(result, Err(DomainError::PermissionDenied(_)))
+            };
+            assert!(denied(service.validate_pool(1, requester).await.map(drop)));
+            assert!(denied(
+                service
+                    .balance_pool(1, BalancePoolRequest::default(), requester)
+                    .await
+                    .map(drop)
+            ));
+            assert!(denied(service.suggest_indices(suggestion(), requester).await.map(drop)));
+            assert!(denied(service.pooling_worksheet(worksheet(), requester).await.map(drop)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balance_follows_design_read_targets() {
+        let mut wgs = library(1, Some("ACGTACGT"));
+        wgs.concentration = Some(Concentration::nanomolar(10.0));
+        let mut rna = library(3, Some("TTTTGGGG"));
+        rna.design = LibraryDesign::RnaSeq;
+        rna.concentration = Some(Concentration::nanomolar(5.0));
+        let libraries = InMemoryLibraries {
+            libraries: [wgs, rna].into_iter().map(|l| (l.id, l)).collect(),
+        };
+        let service = PoolService::new(Arc::new(InMemoryPools::default()), Arc::new(libraries))
+            .with_yield_targets(
+                YieldTargets::new()
+                    .with_design(LibraryDesign::Wgs, 300_000_000)
+                    .with_platform("illumina", 400_000_000),
+            );
+        let request = CreatePoolRequest {
+            name: "POOL_A".to_string(),
+            platform: "ILLUMINA".to_string(),
+            container_model: None,
+            description: None,
+            volume_ul: Some(50.0),
+        };
+        service.create_pool(request, "tech").await.unwrap();
+        service.add_element(1, add(1), "tech", Role::Technician).await.unwrap();
+        service.add_element(1, add(3), "tech", Role::Technician).await.unwrap();
+
+        assert!(service
+            .balance_pool(1, BalancePoolRequest::default(), None)
+            .await
+            .is_err());
+
+        let service = service.with_yield_targets(
+            YieldTargets::new()
+                .with_design(LibraryDesign::Wgs, 300_000_000)
+                .with_design(LibraryDesign::RnaSeq, 100_000_000)
+                .with_platform("illumina", 400_000_000),
+        );
+        let request = BalancePoolRequest {
+            target_nm: Some(2.0),
+            ..Default::default()
+        };
+        let balance = service.balance_pool(1, request.clone(), None).await.unwrap();
+        assert_eq!(balance.lanes, 1);
+        assert!((balance.shares[0].proportion - 0.75).abs() < 1e-9);
+        assert!((balance.diluent_ul.unwrap() - 37.5).abs() < 1e-9);
+
+        let pool = service.apply_balance(1, request, "tech", Role::Technician).await.unwrap();
+        assert_eq!(pool.elements[1].proportion, Some(0.25));
+        assert!((pool.elements[1].volume_ul.unwrap() - 5.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_worksheet_uses_recorded_or_given_measurements() {
+        let mut stock = library(1, Some("ACGTACGT"));
+        stock.concentration = Some(Concentration::ng_per_ul(3.3));
+        stock.insert_size = Some(500);
+        let libraries = InMemoryLibraries {
+            libraries: [stock, library(2, Some("ACGTACGA"))]
+                .into_iter()
+                .map(|l| (l.id, l))
+                .collect(),
+        };
+        let service = PoolService::new(Arc::new(InMemoryPools::default()), Arc::new(libraries));
+        let entry = |library_id, molarity_nm| crate::dto::PoolingLibraryRequest {
+            library_id,
+            molarity_nm,
+            concentration_ng_ul: None,
+            fragment_size: None,
+        };
+        let request = |libraries| PoolingWorksheetRequest {
+            target_nm: 4.0,
+            volume_ul: 50.0,
+            min_pipette_ul: None,
+            libraries,
+        };
+
+        // Library 2 has no recorded concentration
+        let error = service
+            .pooling_worksheet(request(vec![entry(1, None), entry(2, None)]), None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("LIB2"));
+
+        // 3.3 ng/µL of 500 bp is 10 nM
+        let worksheet = service
+            .pooling_worksheet(request(vec![entry(1, None), entry(2, Some(20.0))]), None)
+            .await
+            .unwrap();
+        assert_eq!(worksheet.steps[0].library_name, "LIB1");
+        assert!((worksheet.steps[0].volume_ul - 10.0).abs() < 1e-9);
+        assert!((worksheet.steps[1].volume_ul - 5.0).abs() < 1e-9);
+        assert!((worksheet.diluent_ul - 35.0).abs() < 1e-9);
+
+        assert!(matches!(
+            service.pooling_worksheet(request(vec![entry(9, Some(5.0))]), None).await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+
+    struct Catalog(IndexSet);
+
+    #[async_trait]
+    impl IndexSetRepository for Catalog {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<IndexSet>, DomainError> {
+            Ok(Some(self.0.clone()).filter(|set| set.id == id))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<IndexSet>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<IndexSet>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &IndexSet) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suggest_indices_around_existing_ones() {
+        let indices = [("A01", "ACGTACGA"), ("B01", "GGGGAAAA"), ("C01", "GGGGAAAC")]
+            .map(|(name, i7)| DnaIndex::single(name, i7, IndexFamily::TruSeq).unwrap());
+        let mut set = IndexSet::new(
+            "TruSeq Plate".to_string(),
+            IndexFamily::TruSeq,
+            indices.to_vec(),
+            "admin".to_string(),
+        )
+        .unwrap();
+        set.id = 7;
+        let service = service_with_pool()
+            .await
+            .with_index_sets(Arc::new(Catalog(set)));
+        let suggest = |library_ids: Vec<EntityId>, index_set_ids: Vec<EntityId>| {
+            service.suggest_indices(
+                SuggestIndicesRequest {
+                    library_ids,
+                    index_set_ids,
+                },
+                None,
+            )
+        };
+
+        // A01 is too close to library 1's own index
+        let assignment = suggest(vec![4, 1, 3], vec![7]).await.unwrap();
+        assert_eq!(assignment.min_distance, 3);
+        assert_eq!(assignment.method.as_deref(), Some("exhaustive"));
+        let suggested = &assignment.libraries[0];
+        assert_eq!(suggested.library_id, 4);
+        assert!(!suggested.kept);
+        assert_eq!((suggested.index_set_id, suggested.position), (Some(7), Some(2)));
+        assert_eq!(suggested.index_name, "B01");
+        assert!(assignment.libraries[1].kept);
+
+        let assignment = suggest(vec![1, 3], vec![7]).await.unwrap();
+        assert_eq!(assignment.method, None);
+
+        // Libraries 1 and 2 already collide
+        assert!(matches!(
+            suggest(vec![1, 2, 4], vec![7]).await,
+            Err(DomainError::Pool(PoolError::IndexCollision { .. }))
+        ));
+        assert!(matches!(
+            suggest(vec![4], vec![8]).await,
+            Err(DomainError::NotFound { .. })
+        ));
+        assert!(suggest(vec![4, 4], vec![7]).await.is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Project member service.
+//!
+//! Lab managers say who belongs to each project. Where membership is
+//! enforced (see [`ProjectAccess`](crate::access::ProjectAccess)), only
+//! members, and admins, see and change a project's samples, libraries and
+//! pools.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, ProjectMember};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectMemberRepository, ProjectRepository, UserRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{AddProjectMemberRequest, ProjectMemberResponse};
+
+/// Service for project memberships.
+pub struct ProjectMemberService {
+    members: Arc<dyn ProjectMemberRepository>,
+    projects: Arc<dyn ProjectRepository>,
+    users: Arc<dyn UserRepository>,
+    audit: AuditTrail,
+}
+
+impl ProjectMemberService {
+    /// Creates a new project member service.
+    pub fn new(
+        members: Arc<dyn ProjectMemberRepository>,
+        projects: Arc<dyn ProjectRepository>,
+        users: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            members,
+            projects,
+            users,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records membership changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Lists a project's members, longest-standing first.
+    pub async fn list_members(
+        &self,
+        project_id: EntityId,
+    ) -> Result<Vec<ProjectMemberResponse>, DomainError> {
+        self.find_project(project_id).await?;
+
+        let mut members = Vec::new();
+        for member in self.members.find_by_project(project_id).await? {
+            // Deleting a user removes their memberships
+            if let Some(user) = self.users.find_by_id(member.user_id).await? {
+                members.push(response(member, user.username, user.display_name));
+            }
+        }
+        Ok(members)
+    }
+
+    /// Adds a user to a project.
+    #[instrument(skip(self))]
+    pub async fn add_member(
+        &self,
+        project_id: EntityId,
+        request: AddProjectMemberRequest,
+        added_by: &str,
+    ) -> Result<ProjectMemberResponse, DomainError> {
+        let project = self.find_project(project_id).await?;
+        let user = self
+            .users
+            .find_by_id(request.user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "User".to_string(),
+                id: request.user_id.to_string(),
+            })?;
+
+        let member = ProjectMember::new(project_id, user.id, added_by.to_string());
+        self.members.add(&member).await?;
+        self.audit
+            .created("ProjectMember", project_id, &member, added_by)
+            .await?;
+
+        info!("Added {} to project {}", user.username, project);
+
+        Ok(response(member, user.username, user.display_name))
+    }
+
+    /// Removes a user from a project.
+    #[instrument(skip(self))]
+    pub async fn remove_member(
+        &self,
+        project_id: EntityId,
+        user_id: EntityId,
+        removed_by: &str,
+    ) -> Result<(), DomainError> {
+        let project = self.find_project(project_id).await?;
+        let member = self
+            .members
+            .find_by_project(project_id)
+            .await?
+            .into_iter()
+            .find(|m| m.user_id == user_id)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ProjectMember".to_string(),
+                id: user_id.to_string(),
+            })?;
+
+        self.members.remove(project_id, user_id).await?;
+        self.audit
+            .deleted("ProjectMember", project_id, &member, removed_by)
+            .await?;
+
+        info!("Removed user {} from project {}", user_id, project);
+        Ok(())
+    }
+
+    /// Finds a project, returning its code.
+    async fn find_project(&self, project_id: EntityId) -> Result<String, DomainError> {
+        self.projects
+            .find_by_id(project_id)
+            .await?
+            .map(|p| p.code)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: project_id.to_string(),
+            })
+    }
+}
+
+fn response(member: ProjectMember, username: String, display_name: String) -> ProjectMemberResponse {
+    ProjectMemberResponse {
+        project_id: member.project_id,
+        user_id: member.user_id,
+        username,
+        display_name,
+        added_by: member.added_by,
+        added_at: member.added_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Project, Role, User};
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+    use crate::access::{ProjectAccess, Requester};
+
+    #[derive(Default)]
+    struct InMemoryMembers(Mutex<Vec<ProjectMember>>);
+
+    #[async_trait]
+    impl ProjectMemberRepository for InMemoryMembers {
+        async fn find_by_project(
+            &self,
+            project_id: EntityId,
+        ) -> Result<Vec<ProjectMember>, DomainError> {
+            let members = self.0.lock().unwrap();
+            Ok(members.iter().filter(|m| m.project_id == project_id).cloned().collect())
+        }
+        async fn find_projects(&self, user_id: EntityId) -> Result<Vec<EntityId>, DomainError> {
+            let members = self.0.lock().unwrap();
+            Ok(members.iter().filter(|m| m.user_id == user_id).map(|m| m.project_id).collect())
+        }
+        async fn add(&self, member: &ProjectMember) -> Result<(), DomainError> {
+            let mut members = self.0.lock().unwrap();
+            if members
+                .iter()
+                .any(|m| m.project_id == member.project_id && m.user_id == member.user_id)
+            {
+                return Err(DomainError::Duplicate {
+                    entity_type: "ProjectMember".to_string(),
+                    field: "user_id".to_string(),
+                    value: member.user_id.to_string(),
+                });
+            }
+            members.push(member.clone());
+            Ok(())
+        }
+        async fn remove(&self, project_id: EntityId, user_id: EntityId) -> Result<(), DomainError> {
+            self.0
+                .lock()
+                .unwrap()
+                .retain(|m| m.project_id != project_id || m.user_id != user_id);
+            Ok(())
+        }
+    }
+
+    struct Projects(Vec<Project>);
+
+    #[async_trait]
+    impl ProjectRepository for Projects {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Project>, DomainError> {
+            Ok(self.0.iter().find(|p| p.id == id).cloned())
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct Users(Vec<User>);
+
+    #[async_trait]
+    impl UserRepository for Users {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<User>, DomainError> {
+            Ok(self.0.iter().find(|u| u.id == id).cloned())
+        }
+        async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+            Ok(self.0.iter().find(|u| u.username == username).cloned())
+        }
+        async fn find_by_email(&self, _: &str) -> Result<Option<User>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<User>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &User) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn find_password_hash(&self, _: EntityId) -> Result<Option<String>, DomainError> {
+            unimplemented!()
+        }
+        async fn set_password_hash(&self, _: EntityId, _: &str) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn user(id: EntityId, username: &str, role: Role) -> User {
+        User::new_internal(
+            id,
+            username.to_string(),
+            username.to_string(),
+            format!("{}@example.org", username),
+            role,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_members_reach_their_projects_only() {
+        let members = Arc::new(InMemoryMembers::default());
+        let users = Arc::new(Users(vec![
+            user(1, "alice", Role::Technician),
+            user(2, "bob", Role::Technician),
+        ]));
+        let projects = Arc::new(Projects(vec![
+            Project::new(10, "PROJ10".to_string(), "Ten".to_string(), "lm".to_string()),
+            Project::new(20, "PROJ20".to_string(), "Twenty".to_string(), "lm".to_string()),
+        ]));
+        let service = ProjectMemberService::new(members.clone(), projects, users.clone());
+        let access = ProjectAccess::new(members, users);
+        let alice = Some(Requester::new("alice", Role::Technician));
+
+        service
+            .add_member(10, AddProjectMemberRequest { user_id: 1 }, "lm")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.add_member(10, AddProjectMemberRequest { user_id: 1 }, "lm").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service.add_member(30, AddProjectMemberRequest { user_id: 1 }, "lm").await,
+            Err(DomainError::NotFound { .. })
+        ));
+        let listed = service.list_members(10).await.unwrap();
+        assert_eq!(listed.iter().map(|m| m.username.as_str()).collect::<Vec<_>>(), ["alice"]);
+
+        assert!(access.check(10, alice).await.is_ok());
+        assert!(matches!(
+            access.check(20, alice).await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+        assert!(access.check(10, Some(Requester::new("bob", Role::Technician))).await.is_err());
+        assert!(access.check(10, None).await.is_err());
+        // Admins reach every project, and unguarded services check nothing
+        assert!(access.check(20, Some(Requester::new("root", Role::Admin))).await.is_ok());
+        assert!(ProjectAccess::default().check(20, None).await.is_ok());
+        // A pool reaching into any of the requester's projects is theirs
+        assert!(access.check_any(&HashSet::from([10, 20]), alice).await.is_ok());
+        assert!(access.check_any(&HashSet::new(), None).await.is_ok());
+
+        service.remove_member(10, 1, "lm").await.unwrap();
+        assert!(access.check(10, alice).await.is_err());
+        assert!(matches!(
+            service.remove_member(10, 1, "lm").await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+}
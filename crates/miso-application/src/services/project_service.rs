@@ -2,22 +2,57 @@
 
 use std::sync::Arc;
 
-use miso_domain::entities::Project;
+use miso_domain::entities::{Project, Role};
 use miso_domain::errors::DomainError;
-use miso_domain::repositories::{ProjectRepository, QueryOptions};
+use miso_domain::plugins::{DomainEvent, Operation};
+use miso_domain::repositories::{ProjectRepository, QueryOptions, ReferenceGenomeRepository};
 use tracing::{info, instrument};
 
-use crate::dto::{CreateProjectRequest, ProjectResponse, ProjectSummary, UpdateProjectRequest};
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateProjectRequest, LockProjectRequest, ProjectResponse, ProjectSummary,
+    UpdateProjectRequest,
+};
+use crate::plugins::PluginRegistry;
 
 /// Service for project operations.
-pub struct ProjectService<R: ProjectRepository> {
+pub struct ProjectService<R: ProjectRepository + ?Sized> {
     repository: Arc<R>,
+    genomes: Option<Arc<dyn ReferenceGenomeRepository>>,
+    plugins: Arc<PluginRegistry>,
+    audit: AuditTrail,
 }
 
-impl<R: ProjectRepository> ProjectService<R> {
+impl<R: ProjectRepository + ?Sized> ProjectService<R> {
     /// Creates a new project service.
     pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            genomes: None,
+            plugins: Arc::new(PluginRegistry::new()),
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Lets projects name the reference genome their data is analysed
+    /// against.
+    ///
+    /// Without the registry, projects cannot name one.
+    pub fn with_reference_genomes(mut self, genomes: Arc<dyn ReferenceGenomeRepository>) -> Self {
+        self.genomes = Some(genomes);
+        self
+    }
+
+    /// Runs site plugins' hooks on project changes.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Records project changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
     }
 
     /// Creates a new project.
@@ -43,12 +78,24 @@ impl<R: ProjectRepository> ProjectService<R> {
         project.pi_email = request.pi_email;
         project.reference_number = request.reference_number;
         project.target_sample_count = request.target_sample_count;
+        project.reference_genome_id = request.reference_genome_id;
+        self.check_reference_genome(&project).await?;
+        self.plugins
+            .validate_project(Operation::Create, &project)
+            .await?;
 
         let id = self.repository.save(&project).await?;
         project.id = id;
+        self.audit
+            .created("Project", id, &project, created_by)
+            .await?;
 
         info!("Created project: {} (ID: {})", project.code, id);
 
+        self.plugins
+            .publish(DomainEvent::ProjectCreated(project.clone()))
+            .await;
+
         Ok(project.into())
     }
 
@@ -102,13 +149,12 @@ impl<R: ProjectRepository> ProjectService<R> {
         &self,
         id: i32,
         request: UpdateProjectRequest,
+        updated_by: &str,
+        role: Role,
     ) -> Result<ProjectResponse, DomainError> {
-        let mut project = self.repository.find_by_id(id).await?.ok_or_else(|| {
-            DomainError::NotFound {
-                entity_type: "Project".to_string(),
-                id: id.to_string(),
-            }
-        })?;
+        let mut project = self.find_project(id).await?;
+        project.check_unlocked(role)?;
+        let before = project.clone();
 
         // Apply updates
         if let Some(name) = request.name {
@@ -129,6 +175,10 @@ impl<R: ProjectRepository> ProjectService<R> {
         if let Some(target) = request.target_sample_count {
             project.target_sample_count = Some(target);
         }
+        if let Some(genome_id) = request.reference_genome_id {
+            project.reference_genome_id = Some(genome_id);
+            self.check_reference_genome(&project).await?;
+        }
         if let Some(status) = request.status {
             match status.as_str() {
                 "active" => project.activate(),
@@ -140,29 +190,113 @@ impl<R: ProjectRepository> ProjectService<R> {
         }
 
         project.updated_at = chrono::Utc::now();
+        self.plugins
+            .validate_project(Operation::Update, &project)
+            .await?;
 
         self.repository.save(&project).await?;
+        self.audit
+            .updated("Project", id, &before, &project, updated_by)
+            .await?;
 
         info!("Updated project: {} (ID: {})", project.code, id);
 
+        self.plugins
+            .publish(DomainEvent::ProjectUpdated(project.clone()))
+            .await;
+
+        Ok(project.into())
+    }
+
+    /// Locks a project's data against changes by anyone but admins, e.g.
+    /// for a PI review, replacing any lock it had.
+    #[instrument(skip(self))]
+    pub async fn lock_project(
+        &self,
+        id: i32,
+        request: LockProjectRequest,
+        locked_by: &str,
+    ) -> Result<ProjectResponse, DomainError> {
+        let mut project = self.find_project(id).await?;
+        let before = project.clone();
+
+        let reason = request.reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(DomainError::Validation(
+                "A project lock needs a reason".to_string(),
+            ));
+        }
+        if request.unlock_at.is_some_and(|at| at <= chrono::Utc::now()) {
+            return Err(DomainError::Validation(
+                "A project lock must be set to lapse in the future".to_string(),
+            ));
+        }
+        project.lock(reason, locked_by.to_string(), request.unlock_at);
+
+        self.repository.save(&project).await?;
+        self.audit
+            .updated("Project", id, &before, &project, locked_by)
+            .await?;
+
+        info!("Locked project: {} (ID: {}) by {}", project.code, id, locked_by);
+
+        self.plugins
+            .publish(DomainEvent::ProjectUpdated(project.clone()))
+            .await;
+
+        Ok(project.into())
+    }
+
+    /// Lifts a project's lock. Unlocking a project that isn't locked does
+    /// nothing.
+    #[instrument(skip(self))]
+    pub async fn unlock_project(
+        &self,
+        id: i32,
+        unlocked_by: &str,
+    ) -> Result<ProjectResponse, DomainError> {
+        let mut project = self.find_project(id).await?;
+        if project.lock.is_none() {
+            return Ok(project.into());
+        }
+        let before = project.clone();
+
+        project.unlock();
+        self.repository.save(&project).await?;
+        self.audit
+            .updated("Project", id, &before, &project, unlocked_by)
+            .await?;
+
+        info!("Unlocked project: {} (ID: {}) by {}", project.code, id, unlocked_by);
+
+        self.plugins
+            .publish(DomainEvent::ProjectUpdated(project.clone()))
+            .await;
+
         Ok(project.into())
     }
 
     /// Deletes a project.
     #[instrument(skip(self))]
-    pub async fn delete_project(&self, id: i32) -> Result<(), DomainError> {
+    pub async fn delete_project(
+        &self,
+        id: i32,
+        deleted_by: &str,
+        role: Role,
+    ) -> Result<(), DomainError> {
         // Check if project exists
-        self.repository.find_by_id(id).await?.ok_or_else(|| {
-            DomainError::NotFound {
-                entity_type: "Project".to_string(),
-                id: id.to_string(),
-            }
-        })?;
+        let project = self.find_project(id).await?;
+        project.check_unlocked(role)?;
 
         self.repository.delete(id).await?;
+        self.audit
+            .deleted("Project", id, &project, deleted_by)
+            .await?;
 
         info!("Deleted project: {}", id);
 
+        self.plugins.publish(DomainEvent::ProjectDeleted(id)).await;
+
         Ok(())
     }
 
@@ -171,5 +305,34 @@ impl<R: ProjectRepository> ProjectService<R> {
     pub async fn count_projects(&self) -> Result<u64, DomainError> {
         self.repository.count().await
     }
+
+    async fn find_project(&self, id: i32) -> Result<Project, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// Checks that the reference genome a project names is registered.
+    async fn check_reference_genome(&self, project: &Project) -> Result<(), DomainError> {
+        let Some(genome_id) = project.reference_genome_id else {
+            return Ok(());
+        };
+
+        let genome = match &self.genomes {
+            Some(genomes) => genomes.find_by_id(genome_id).await?,
+            None => None,
+        };
+        if genome.is_none() {
+            return Err(DomainError::NotFound {
+                entity_type: "ReferenceGenome".to_string(),
+                id: genome_id.to_string(),
+            });
+        }
+        Ok(())
+    }
 }
 
@@ -0,0 +1,73 @@
+//! QC report service for attaching external QC reports to runs.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, RunQcReport};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::QcReportRepository;
+use tracing::{info, instrument};
+
+use crate::dto::{AttachQcReportRequest, RunQcReportResponse};
+use crate::importers::parse_multiqc_summary;
+
+/// Service for QC report operations.
+pub struct QcReportService<R: QcReportRepository + ?Sized> {
+    repository: Arc<R>,
+}
+
+impl<R: QcReportRepository + ?Sized> QcReportService<R> {
+    /// Creates a new QC report service.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Attaches a MultiQC report to a run, replacing any existing report.
+    ///
+    /// If the summary JSON is supplied, its per-sample metrics are stored
+    /// alongside the link.
+    #[instrument(skip(self, request))]
+    pub async fn attach_report(
+        &self,
+        run_id: EntityId,
+        request: AttachQcReportRequest,
+        attached_by: &str,
+    ) -> Result<RunQcReportResponse, DomainError> {
+        let mut report = RunQcReport::new(
+            run_id,
+            request.report_url,
+            request.file_name,
+            attached_by.to_string(),
+        )?;
+
+        if let Some(summary) = request.summary {
+            let summary = parse_multiqc_summary(&summary)?;
+            report.multiqc_version = summary.version;
+            report.samples = summary.samples;
+        }
+
+        report.id = self.repository.save(&report).await?;
+
+        info!(
+            "Attached QC report to run {} with {} samples",
+            run_id,
+            report.samples.len()
+        );
+
+        Ok(report.into())
+    }
+
+    /// Gets the QC report attached to a run.
+    #[instrument(skip(self))]
+    pub async fn get_report(&self, run_id: EntityId) -> Result<RunQcReportResponse, DomainError> {
+        let report = self
+            .repository
+            .find_by_run(run_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "QcReport".to_string(),
+                id: run_id.to_string(),
+            })?;
+
+        Ok(report.into())
+    }
+}
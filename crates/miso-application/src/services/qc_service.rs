@@ -0,0 +1,875 @@
+//! QC measurement service.
+//!
+//! Samples, libraries and pools each keep every QC measurement taken of
+//! them, as it was taken. Each new measurement rolls the entity's QC status
+//! up again from all of them, judged against the site's thresholds. Final
+//! Passed or Failed statuses are left for a reviewer rather than overturned.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{ChangeLogEntry, EntityId, QcRecord, QcTarget, Role};
+use miso_domain::errors::DomainError;
+use miso_domain::plugins::DomainEvent;
+use miso_domain::repositories::{
+    ChangeLogRepository, LibraryRepository, PoolRepository, QcRecordRepository,
+    SampleRepository,
+};
+use miso_domain::services::{QcEvaluator, QcRollup};
+use miso_domain::value_objects::{QcResult, QcStatus};
+use tracing::{info, instrument, warn};
+
+use crate::access::{ProjectAccess, Requester};
+use crate::audit::AuditTrail;
+use crate::dto::{CreateQcRequest, QcResponse};
+use crate::locks::ProjectLocks;
+use crate::plugins::PluginRegistry;
+
+/// Reason recorded for QC status changes made by rolling measurements up.
+const ROLLED_UP: &str = "Rolled up from QC results";
+
+/// Service for recording and listing QC measurements.
+pub struct QcService {
+    records: Arc<dyn QcRecordRepository>,
+    samples: Arc<dyn SampleRepository>,
+    libraries: Arc<dyn LibraryRepository>,
+    pools: Arc<dyn PoolRepository>,
+    change_log: Arc<dyn ChangeLogRepository>,
+    evaluator: QcEvaluator,
+    audit: AuditTrail,
+    plugins: Arc<PluginRegistry>,
+    access: ProjectAccess,
+    locks: ProjectLocks,
+}
+
+impl QcService {
+    /// Creates a new QC service.
+    pub fn new(
+        records: Arc<dyn QcRecordRepository>,
+        samples: Arc<dyn SampleRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+        pools: Arc<dyn PoolRepository>,
+        change_log: Arc<dyn ChangeLogRepository>,
+    ) -> Self {
+        Self {
+            records,
+            samples,
+            libraries,
+            pools,
+            change_log,
+            evaluator: QcEvaluator::default(),
+            audit: AuditTrail::default(),
+            plugins: Arc::new(PluginRegistry::new()),
+            access: ProjectAccess::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Judges measurements against the site's thresholds.
+    pub fn with_evaluator(mut self, evaluator: QcEvaluator) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Records measurements in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Publishes measurements, and the status changes they cause, to site
+    /// plugins' event subscribers.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Limits measurements to the members of the measured entities'
+    /// projects.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Refuses measurements of locked projects' entities, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Records a QC measurement of a sample, library or pool, and rolls the
+    /// entity's QC status up again. Entities of locked projects can only be
+    /// measured by admins.
+    #[instrument(skip(self, request))]
+    pub async fn add_qc(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+        request: CreateQcRequest,
+        performed_by: &str,
+        role: Role,
+    ) -> Result<QcResponse, DomainError> {
+        let projects = self.projects_of(target, entity_id).await?;
+        self.access
+            .check_any(&projects, Some(Requester::new(performed_by, role)))
+            .await?;
+        for project_id in projects {
+            self.locks.check(project_id, role).await?;
+        }
+
+        let status = parse_qc_status(&request.status)?;
+        let test_type = request
+            .test_type
+            .parse()
+            .unwrap_or_else(|never| match never {});
+        let result = QcResult {
+            test_type,
+            value: request.value,
+            unit: request.unit,
+            status,
+            notes: request.notes,
+            performed_at: request.performed_at.unwrap_or_else(Utc::now),
+            performed_by: performed_by.to_string(),
+        };
+
+        let mut record = QcRecord::new(target, entity_id, result);
+        record.id = self.records.save(&record).await?;
+        self.audit
+            .created("QcResult", record.id, &record, performed_by)
+            .await?;
+
+        info!(
+            "Recorded {} QC of {} {} (ID: {})",
+            record.result.test_type, target, entity_id, record.id
+        );
+
+        let needs_review = self.roll_up(target, entity_id, performed_by).await?;
+        self.plugins
+            .publish(DomainEvent::QcRecorded(record.clone()))
+            .await;
+
+        let mut response: QcResponse = record.into();
+        response.status_needs_review = needs_review;
+        Ok(response)
+    }
+
+    /// Lists the QC measurements of a sample, library or pool, oldest
+    /// first.
+    #[instrument(skip(self))]
+    pub async fn list_qcs(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<QcResponse>, DomainError> {
+        let projects = self.projects_of(target, entity_id).await?;
+        self.access.check_any(&projects, requester).await?;
+
+        let records = self.records.find_by_entity(target, entity_id).await?;
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+
+    /// Sets an entity's QC status to that rolled up from its measurements,
+    /// if it differs. A final status the measurements disagree with is
+    /// kept and noted in the change log; returns true if it needs review.
+    async fn roll_up(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+        changed_by: &str,
+    ) -> Result<bool, DomainError> {
+        let results: Vec<QcResult> = self
+            .records
+            .find_by_entity(target, entity_id)
+            .await?
+            .into_iter()
+            .map(|record| record.result)
+            .collect();
+        let not_found = |entity_type: &str| DomainError::NotFound {
+            entity_type: entity_type.to_string(),
+            id: entity_id.to_string(),
+        };
+
+        match target {
+            QcTarget::Sample => {
+                let mut sample = self
+                    .samples
+                    .find_by_id(entity_id)
+                    .await?
+                    .ok_or_else(|| not_found("Sample"))?;
+                let status = match self.evaluator.settle(sample.qc_status, target, &results) {
+                    QcRollup::Unchanged => return Ok(false),
+                    QcRollup::NeedsReview(status) => {
+                        return self
+                            .flag_for_review(
+                                "Sample",
+                                entity_id,
+                                sample.qc_status,
+                                status,
+                                changed_by,
+                            )
+                            .await
+                    }
+                    QcRollup::Changed(status) => status,
+                };
+                let before = sample.clone();
+                let entry = ChangeLogEntry::new(
+                    "Sample",
+                    entity_id,
+                    format!("QC status changed from {} to {}", sample.qc_status, status),
+                    changed_by,
+                )
+                .with_reason(Some(ROLLED_UP.to_string()));
+                sample.set_qc_status(status);
+
+                let conflicts = self
+                    .samples
+                    .update_all_versioned(std::slice::from_ref(&sample))
+                    .await?;
+                if !conflicts.is_empty() {
+                    return Err(DomainError::ConcurrentModification {
+                        entity_type: "Sample".to_string(),
+                        id: entity_id.to_string(),
+                    });
+                }
+                sample.version += 1;
+
+                self.change_log.save_all(&[entry]).await?;
+                self.audit
+                    .updated("Sample", entity_id, &before, &sample, changed_by)
+                    .await?;
+                self.plugins.publish(DomainEvent::SampleUpdated(sample)).await;
+                info!("Rolled {} {} QC status up to {}", target, entity_id, status);
+            }
+            QcTarget::Library => {
+                let mut library = self
+                    .libraries
+                    .find_by_id(entity_id)
+                    .await?
+                    .ok_or_else(|| not_found("Library"))?;
+                let status = match self.evaluator.settle(library.qc_status, target, &results) {
+                    QcRollup::Unchanged => return Ok(false),
+                    QcRollup::NeedsReview(status) => {
+                        return self
+                            .flag_for_review(
+                                "Library",
+                                entity_id,
+                                library.qc_status,
+                                status,
+                                changed_by,
+                            )
+                            .await
+                    }
+                    QcRollup::Changed(status) => status,
+                };
+                let before = library.clone();
+                library.set_qc_status(status);
+                self.libraries.save(&library).await?;
+                self.audit
+                    .updated("Library", entity_id, &before, &library, changed_by)
+                    .await?;
+                self.plugins.publish(DomainEvent::LibraryUpdated(library)).await;
+                info!("Rolled {} {} QC status up to {}", target, entity_id, status);
+            }
+            QcTarget::Pool => {
+                let mut pool = self
+                    .pools
+                    .find_by_id(entity_id)
+                    .await?
+                    .ok_or_else(|| not_found("Pool"))?;
+                let status = match self.evaluator.settle(pool.qc_status, target, &results) {
+                    QcRollup::Unchanged => return Ok(false),
+                    QcRollup::NeedsReview(status) => {
+                        return self
+                            .flag_for_review(
+                                "Pool",
+                                entity_id,
+                                pool.qc_status,
+                                status,
+                                changed_by,
+                            )
+                            .await
+                    }
+                    QcRollup::Changed(status) => status,
+                };
+                let before = pool.clone();
+                pool.set_qc_status(status);
+                self.pools.save(&pool).await?;
+                self.audit
+                    .updated("Pool", entity_id, &before, &pool, changed_by)
+                    .await?;
+                info!("Rolled {} {} QC status up to {}", target, entity_id, status);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Notes in the change log that an entity's measurements no longer
+    /// agree with its final QC status, which is left as it is.
+    async fn flag_for_review(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+        kept: QcStatus,
+        rolled_up: QcStatus,
+        flagged_by: &str,
+    ) -> Result<bool, DomainError> {
+        let entry = ChangeLogEntry::new(
+            entity_type,
+            entity_id,
+            format!(
+                "QC results now roll up to {}; final status {} kept for review",
+                rolled_up, kept
+            ),
+            flagged_by,
+        )
+        .with_reason(Some(ROLLED_UP.to_string()));
+        self.change_log.save_all(&[entry]).await?;
+
+        warn!(
+            "{} {} QC results roll up to {}, but its final {} status was kept for review",
+            entity_type, entity_id, rolled_up, kept
+        );
+
+        Ok(true)
+    }
+
+    /// Finds the projects a sample, library or pool belongs to; a pool
+    /// belongs to those of its libraries.
+    async fn projects_of(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+    ) -> Result<HashSet<EntityId>, DomainError> {
+        let not_found = |entity_type: &str| DomainError::NotFound {
+            entity_type: entity_type.to_string(),
+            id: entity_id.to_string(),
+        };
+
+        Ok(match target {
+            QcTarget::Sample => {
+                let sample = self.samples.find_by_id(entity_id).await?;
+                HashSet::from([sample.ok_or_else(|| not_found("Sample"))?.project_id])
+            }
+            QcTarget::Library => {
+                let library = self.libraries.find_by_id(entity_id).await?;
+                HashSet::from([library.ok_or_else(|| not_found("Library"))?.project_id])
+            }
+            QcTarget::Pool => {
+                let pool = self
+                    .pools
+                    .find_by_id(entity_id)
+                    .await?
+                    .ok_or_else(|| not_found("Pool"))?;
+                self.libraries
+                    .find_by_ids(&pool.library_ids())
+                    .await?
+                    .into_iter()
+                    .map(|l| l.project_id)
+                    .collect()
+            }
+        })
+    }
+}
+
+pub(crate) fn parse_qc_status(code: &str) -> Result<QcStatus, DomainError> {
+    match code {
+        "not_ready" => Ok(QcStatus::NotReady),
+        "ready" => Ok(QcStatus::Ready),
+        "passed" => Ok(QcStatus::Passed),
+        "failed" => Ok(QcStatus::Failed),
+        "needs_review" => Ok(QcStatus::NeedsReview),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid QC status: {}",
+            code
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Library, Pool, Project, Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::services::QcThreshold;
+    use miso_domain::value_objects::{Barcode, QcTestType};
+
+    use super::*;
+    use crate::test_support::{Members, OneProject};
+
+    #[derive(Default)]
+    struct InMemoryQcRecords {
+        records: Mutex<Vec<QcRecord>>,
+    }
+
+    #[async_trait]
+    impl QcRecordRepository for InMemoryQcRecords {
+        async fn find_by_entity(
+            &self,
+            target: QcTarget,
+            entity_id: EntityId,
+        ) -> Result<Vec<QcRecord>, DomainError> {
+            let records = self.records.lock().unwrap();
+            Ok(records
+                .iter()
+                .filter(|r| r.target == target && r.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, record: &QcRecord) -> Result<EntityId, DomainError> {
+            let mut records = self.records.lock().unwrap();
+            let mut record = record.clone();
+            record.id = records.len() as EntityId + 1;
+            records.push(record.clone());
+            Ok(record.id)
+        }
+    }
+
+    /// Holds the one sample with ID 1.
+    struct OneSample {
+        sample: Mutex<Sample>,
+    }
+
+    impl Default for OneSample {
+        fn default() -> Self {
+            Self {
+                sample: Mutex::new(Sample::new_identity(
+                    1,
+                    "PAT_1".to_string(),
+                    Barcode::new_unchecked("SAM1".to_string()),
+                    1,
+                    "P1".to_string(),
+                    "tech".to_string(),
+                )),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for OneSample {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok((id == 1).then(|| self.sample.lock().unwrap().clone()))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            updates: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            let mut sample = self.sample.lock().unwrap();
+            for u in updates {
+                *sample = u.clone();
+                sample.version += 1;
+            }
+            Ok(Vec::new())
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryChangeLog {
+        entries: Mutex<Vec<ChangeLogEntry>>,
+    }
+
+    #[async_trait]
+    impl ChangeLogRepository for InMemoryChangeLog {
+        async fn find_by_entity(
+            &self,
+            _: &str,
+            _: EntityId,
+        ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            self.entries.lock().unwrap().extend_from_slice(entries);
+            Ok(())
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct NoLibraries;
+
+    #[async_trait]
+    impl LibraryRepository for NoLibraries {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct NoPools;
+
+    #[async_trait]
+    impl PoolRepository for NoPools {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Pool>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Pool) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn service() -> QcService {
+        service_with(Arc::new(OneSample::default()), Arc::new(InMemoryChangeLog::default()))
+    }
+
+    fn service_with(samples: Arc<OneSample>, change_log: Arc<InMemoryChangeLog>) -> QcService {
+        QcService::new(
+            Arc::new(InMemoryQcRecords::default()),
+            samples,
+            Arc::new(NoLibraries),
+            Arc::new(NoPools),
+            change_log,
+        )
+    }
+
+    fn request(test_type: &str, value: f64, status: &str) -> CreateQcRequest {
+        CreateQcRequest {
+            test_type: test_type.to_string(),
+            value: Some(value),
+            unit: Some("ng/µL".to_string()),
+            status: status.to_string(),
+            notes: None,
+            performed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_measurements_are_listed_oldest_first() {
+        let service = service();
+
+        service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 12.5, "passed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("Fragment Analyzer", 3.0, "failed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+
+        let qcs = service.list_qcs(QcTarget::Sample, 1, None).await.unwrap();
+        assert_eq!(qcs.len(), 2);
+        assert_eq!(qcs[0].test_type, QcTestType::Qubit.to_string());
+        assert_eq!(qcs[0].value, Some(12.5));
+        assert_eq!(qcs[0].performed_by, "tech");
+        assert_eq!(qcs[1].test_type, "Fragment Analyzer");
+        assert_eq!(qcs[1].status, QcStatus::Failed.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_measurement_of_a_missing_entity_is_refused() {
+        let service = service();
+
+        let err = service
+            .add_qc(
+                QcTarget::Library,
+                1,
+                request("qubit", 1.0, "passed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::NotFound { entity_type, .. } if entity_type == "Library"));
+
+        assert!(service.list_qcs(QcTarget::Pool, 1, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_locked_projects_are_measured_only_by_admins() {
+        let mut project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        project.lock("PI review".to_string(), "lm".to_string(), None);
+        let service =
+            service().with_project_locks(ProjectLocks::new(Arc::new(OneProject::new(project))));
+
+        let refused = service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 12.5, "passed"),
+                "tech",
+                Role::Technician,
+            )
+            .await;
+        assert!(matches!(refused, Err(DomainError::ProjectLocked { .. })));
+        assert!(service.list_qcs(QcTarget::Sample, 1, None).await.unwrap().is_empty());
+
+        service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 12.5, "passed"),
+                "admin",
+                Role::Admin,
+            )
+            .await
+            .unwrap();
+        assert_eq!(service.list_qcs(QcTarget::Sample, 1, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_measurements_reach_project_members_only() {
+        let service = service()
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let measure = |by| {
+            service.add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 12.5, "passed"),
+                by,
+                Role::Technician,
+            )
+        };
+
+        measure("member").await.unwrap();
+        assert!(matches!(
+            measure("outsider").await,
+            Err(DomainError::PermissionDenied(_))
+        ));
+
+        let member = Some(Requester::new("member", Role::Technician));
+        assert_eq!(service.list_qcs(QcTarget::Sample, 1, member).await.unwrap().len(), 1);
+        for requester in [None, Some(Requester::new("outsider", Role::Technician))] {
+            assert!(matches!(
+                service.list_qcs(QcTarget::Sample, 1, requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_status_is_refused() {
+        let service = service();
+
+        let err = service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 1.0, "great"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert!(service.list_qcs(QcTarget::Sample, 1, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_measurements_roll_the_status_up() {
+        let samples = Arc::new(OneSample::default());
+        let change_log = Arc::new(InMemoryChangeLog::default());
+        let service = service_with(samples.clone(), change_log.clone()).with_evaluator(
+            QcEvaluator::new().with_threshold(
+                QcTarget::Sample,
+                &QcTestType::Qubit,
+                QcThreshold {
+                    min: Some(10.0),
+                    max: None,
+                },
+            ),
+        );
+
+        // The threshold overrides the recorded status
+        service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 25.0, "ready"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert_eq!(samples.sample.lock().unwrap().qc_status, QcStatus::Passed);
+
+        service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 8.0, "passed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        let sample = samples.sample.lock().unwrap().clone();
+        assert_eq!(sample.qc_status, QcStatus::Passed);
+        assert_eq!(sample.version, 2);
+
+        let entries = change_log.entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].summary, "QC status changed from Not Ready to Passed");
+        assert_eq!(entries[0].reason.as_deref(), Some("Rolled up from QC results"));
+    }
+
+    #[tokio::test]
+    async fn test_measurements_do_not_overturn_a_final_status() {
+        let samples = Arc::new(OneSample::default());
+        samples.sample.lock().unwrap().qc_status = QcStatus::Passed;
+        let change_log = Arc::new(InMemoryChangeLog::default());
+        let service = service_with(samples.clone(), change_log.clone());
+
+        let qc = service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("qubit", 4.0, "passed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert!(!qc.status_needs_review);
+        assert!(change_log.entries.lock().unwrap().is_empty());
+
+        let qc = service
+            .add_qc(
+                QcTarget::Sample,
+                1,
+                request("tapestation", 2.1, "failed"),
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert!(qc.status_needs_review);
+
+        let sample = samples.sample.lock().unwrap().clone();
+        assert_eq!(sample.qc_status, QcStatus::Passed);
+        assert_eq!(sample.version, 1);
+
+        let entries = change_log.entries.lock().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].summary,
+            "QC results now roll up to Failed; final status Passed kept for review"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Reagent inventory service.
+//!
+//! Tracks the kit lots held in stock and reports the lots that have
+//! expired or are about to, and the kits that have run low enough to
+//! reorder. Expired lots don't count towards a kit's stock.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use miso_domain::entities::{EntityId, ReagentLot};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, ReagentLotRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateReagentLotRequest, ExpiringLotResponse, LowStockResponse, ReagentAlertReport,
+    ReagentLotFilter, ReagentLotResponse, UpdateReagentLotRequest,
+};
+
+/// Days ahead the expiry report looks if not told otherwise.
+pub const DEFAULT_EXPIRY_DAYS: u32 = 30;
+
+/// Service for reagent inventory operations.
+pub struct ReagentInventoryService<R: ReagentLotRepository + ?Sized> {
+    lots: Arc<R>,
+    audit: AuditTrail,
+}
+
+impl<R: ReagentLotRepository + ?Sized> ReagentInventoryService<R> {
+    /// Creates a new reagent inventory service.
+    pub fn new(lots: Arc<R>) -> Self {
+        Self {
+            lots,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records reagent lot changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Records a lot received into stock. Lot numbers are unique per kit.
+    #[instrument(skip(self, request), fields(kit = %request.kit, lot = %request.lot_number))]
+    pub async fn create_lot(
+        &self,
+        request: CreateReagentLotRequest,
+        created_by: &str,
+    ) -> Result<ReagentLotResponse, DomainError> {
+        let mut lot = ReagentLot::new(
+            &request.kit,
+            &request.lot_number,
+            request.expires_on,
+            request.quantity,
+            request.reorder_level,
+            created_by.to_string(),
+        )?;
+        if self.lots.find_by_lot(&lot.kit, &lot.lot_number).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "ReagentLot".to_string(),
+                field: "lot_number".to_string(),
+                value: lot.lot_number,
+            });
+        }
+
+        lot.id = self.lots.save(&lot).await?;
+        self.audit
+            .created("ReagentLot", lot.id, &lot, created_by)
+            .await?;
+
+        info!(
+            "Received lot {} of {} with {} units, expiring {} (ID: {})",
+            lot.lot_number, lot.kit, lot.quantity, lot.expires_on, lot.id
+        );
+
+        Ok(lot.into())
+    }
+
+    /// Gets a reagent lot by ID.
+    pub async fn get_lot(&self, id: EntityId) -> Result<ReagentLotResponse, DomainError> {
+        Ok(self.find_lot(id).await?.into())
+    }
+
+    /// Lists reagent lots by kit, soonest expiring first.
+    pub async fn list_lots(
+        &self,
+        filter: ReagentLotFilter,
+    ) -> Result<Vec<ReagentLotResponse>, DomainError> {
+        let options = QueryOptions {
+            limit: Some(filter.limit.unwrap_or(100).min(1000)),
+            offset: filter.offset,
+            ..Default::default()
+        };
+
+        Ok(self
+            .lots
+            .list(options)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Corrects a lot's expiry date or reorder level, or records the units
+    /// left.
+    #[instrument(skip(self, request))]
+    pub async fn update_lot(
+        &self,
+        id: EntityId,
+        request: UpdateReagentLotRequest,
+        updated_by: &str,
+    ) -> Result<ReagentLotResponse, DomainError> {
+        let mut lot = self.find_lot(id).await?;
+        let before = lot.clone();
+        let now = Utc::now();
+
+        if let Some(expires_on) = request.expires_on {
+            lot.expires_on = expires_on;
+        }
+        if let Some(quantity) = request.quantity {
+            lot.set_quantity(quantity, now)?;
+        }
+        if let Some(reorder_level) = request.reorder_level {
+            if reorder_level < 0 {
+                return Err(DomainError::Validation(
+                    "Reagent quantities can't be negative".to_string(),
+                ));
+            }
+            lot.reorder_level = reorder_level;
+        }
+        lot.updated_at = now;
+
+        self.lots.save(&lot).await?;
+        self.audit
+            .updated("ReagentLot", id, &before, &lot, updated_by)
+            .await?;
+
+        info!(
+            "Updated lot {} of {}: {} units left (ID: {})",
+            lot.lot_number, lot.kit, lot.quantity, id
+        );
+
+        Ok(lot.into())
+    }
+
+    /// Deletes a reagent lot.
+    #[instrument(skip(self))]
+    pub async fn delete_lot(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let lot = self.find_lot(id).await?;
+
+        self.lots.delete(id).await?;
+        self.audit
+            .deleted("ReagentLot", id, &lot, deleted_by)
+            .await?;
+
+        info!("Deleted lot {} of {} (ID: {})", lot.lot_number, lot.kit, id);
+        Ok(())
+    }
+
+    /// Lists lots with units left that have expired or expire within
+    /// `days` of `today`, soonest first.
+    pub async fn expiring_soon(
+        &self,
+        days: u32,
+        today: NaiveDate,
+    ) -> Result<Vec<ExpiringLotResponse>, DomainError> {
+        let mut expiring: Vec<ExpiringLotResponse> = self
+            .lots
+            .find_in_stock()
+            .await?
+            .into_iter()
+            .filter(|lot| lot.days_left(today) <= i64::from(days))
+            .map(|lot| ExpiringLotResponse {
+                days_left: lot.days_left(today),
+                expired: lot.is_expired(today),
+                lot: lot.into(),
+            })
+            .collect();
+        expiring.sort_by_key(|e| (e.days_left, e.lot.kit.clone()));
+
+        Ok(expiring)
+    }
+
+    /// Lists kits whose unexpired units are at or below their reorder
+    /// level. A kit's reorder level is that of its most recently received
+    /// lot.
+    pub async fn low_stock(&self, today: NaiveDate) -> Result<Vec<LowStockResponse>, DomainError> {
+        let mut kits: BTreeMap<String, Vec<ReagentLot>> = BTreeMap::new();
+        for lot in self.lots.list(QueryOptions::default()).await? {
+            kits.entry(lot.kit.clone()).or_default().push(lot);
+        }
+
+        Ok(kits
+            .into_iter()
+            .filter_map(|(kit, lots)| {
+                let reorder_level = lots
+                    .iter()
+                    .max_by_key(|lot| (lot.created_at, lot.id))
+                    .map(|lot| lot.reorder_level)?;
+                let usable: Vec<&ReagentLot> = lots
+                    .iter()
+                    .filter(|lot| lot.in_stock() && !lot.is_expired(today))
+                    .collect();
+                let on_hand = usable.iter().map(|lot| lot.quantity).sum();
+
+                (on_hand <= reorder_level).then(|| LowStockResponse {
+                    kit,
+                    on_hand,
+                    reorder_level,
+                    lot_numbers: usable.iter().map(|lot| lot.lot_number.clone()).collect(),
+                })
+            })
+            .collect())
+    }
+
+    /// Reports lots expiring within `days` of `today` and kits running
+    /// low.
+    #[instrument(skip(self))]
+    pub async fn alerts(
+        &self,
+        days: u32,
+        today: NaiveDate,
+    ) -> Result<ReagentAlertReport, DomainError> {
+        Ok(ReagentAlertReport {
+            days,
+            expiring: self.expiring_soon(days, today).await?,
+            low_stock: self.low_stock(today).await?,
+        })
+    }
+
+    async fn find_lot(&self, id: EntityId) -> Result<ReagentLot, DomainError> {
+        self.lots
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ReagentLot".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryLots {
+        lots: Mutex<Vec<ReagentLot>>,
+    }
+
+    #[async_trait]
+    impl ReagentLotRepository for InMemoryLots {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ReagentLot>, DomainError> {
+            Ok(self.lots.lock().unwrap().iter().find(|l| l.id == id).cloned())
+        }
+        async fn find_by_lot(
+            &self,
+            kit: &str,
+            lot_number: &str,
+        ) -> Result<Option<ReagentLot>, DomainError> {
+            Ok(self
+                .lots
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|l| l.kit == kit && l.lot_number == lot_number)
+                .cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<ReagentLot>, DomainError> {
+            Ok(self.lots.lock().unwrap().clone())
+        }
+        async fn find_in_stock(&self) -> Result<Vec<ReagentLot>, DomainError> {
+            Ok(self
+                .lots
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|l| l.in_stock())
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, lot: &ReagentLot) -> Result<EntityId, DomainError> {
+            let mut lots = self.lots.lock().unwrap();
+            let mut lot = lot.clone();
+            if lot.id == 0 {
+                lot.id = lots.len() as EntityId + 1;
+            }
+            let id = lot.id;
+            match lots.iter_mut().find(|l| l.id == id) {
+                Some(stored) => *stored = lot,
+                None => lots.push(lot),
+            }
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.lots.lock().unwrap().retain(|l| l.id != id);
+            Ok(())
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    fn lot(kit: &str, lot_number: &str, expires: u32, quantity: i32) -> CreateReagentLotRequest {
+        CreateReagentLotRequest {
+            kit: kit.to_string(),
+            lot_number: lot_number.to_string(),
+            expires_on: date(expires),
+            quantity,
+            reorder_level: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expiring_and_low_stock_reports() {
+        let service = ReagentInventoryService::new(Arc::new(InMemoryLots::default()));
+        let today = date(10);
+
+        service.create_lot(lot("DNeasy", "D1", 5, 20), "tech").await.unwrap();
+        service.create_lot(lot("DNeasy", "D2", 30, 4), "tech").await.unwrap();
+        let used_up = service.create_lot(lot("Qubit", "Q1", 12, 0), "tech").await.unwrap();
+        service.create_lot(lot("TruSeq", "T1", 15, 50), "tech").await.unwrap();
+        assert!(matches!(
+            service.create_lot(lot("DNeasy", "D1", 5, 1), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+
+        // The used-up lot isn't listed as expiring
+        let expiring = service.expiring_soon(7, today).await.unwrap();
+        let lots: Vec<_> = expiring
+            .iter()
+            .map(|e| (e.lot.lot_number.as_str(), e.days_left, e.expired))
+            .collect();
+        assert_eq!(lots, vec![("D1", -5, true), ("T1", 5, false)]);
+        assert_eq!(service.expiring_soon(30, today).await.unwrap().len(), 3);
+
+        // The expired DNeasy lot doesn't count towards its stock
+        let low = service.low_stock(today).await.unwrap();
+        let kits: Vec<_> = low
+            .iter()
+            .map(|l| (l.kit.as_str(), l.on_hand, l.lot_numbers.clone()))
+            .collect();
+        assert_eq!(
+            kits,
+            vec![("DNeasy", 4, vec!["D2".to_string()]), ("Qubit", 0, vec![])]
+        );
+
+        service
+            .update_lot(
+                used_up.id,
+                UpdateReagentLotRequest {
+                    expires_on: None,
+                    quantity: Some(10),
+                    reorder_level: None,
+                },
+                "tech",
+            )
+            .await
+            .unwrap();
+        let report = service.alerts(7, today).await.unwrap();
+        assert_eq!(report.low_stock.len(), 1);
+        assert_eq!(report.expiring.len(), 3);
+    }
+}
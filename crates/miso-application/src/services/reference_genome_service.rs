@@ -0,0 +1,367 @@
+//! Reference genome registry service.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, ReferenceGenome};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{LibraryRepository, ProjectRepository, ReferenceGenomeRepository};
+use tracing::{info, instrument};
+
+use crate::dto::{
+    CreateReferenceGenomeRequest, LibraryReferenceGenome, ReferenceGenomeResponse,
+    UpdateReferenceGenomeRequest,
+};
+
+/// Service for the reference genome registry, and for telling pipelines
+/// which genome a library is analysed against.
+pub struct ReferenceGenomeService<G, L, P>
+where
+    G: ReferenceGenomeRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+{
+    genomes: Arc<G>,
+    libraries: Arc<L>,
+    projects: Arc<P>,
+}
+
+impl<G, L, P> ReferenceGenomeService<G, L, P>
+where
+    G: ReferenceGenomeRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    P: ProjectRepository + ?Sized,
+{
+    /// Creates a new reference genome service.
+    pub fn new(genomes: Arc<G>, libraries: Arc<L>, projects: Arc<P>) -> Self {
+        Self {
+            genomes,
+            libraries,
+            projects,
+        }
+    }
+
+    /// Registers a reference genome.
+    #[instrument(skip(self, request), fields(name = %request.name))]
+    pub async fn create_genome(
+        &self,
+        request: CreateReferenceGenomeRequest,
+        created_by: &str,
+    ) -> Result<ReferenceGenomeResponse, DomainError> {
+        let mut genome = ReferenceGenome::new(
+            request.name,
+            request.species,
+            request.fasta_uri,
+            created_by.to_string(),
+        )?;
+        genome.description = request.description;
+
+        if self.genomes.find_by_name(&genome.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "ReferenceGenome".to_string(),
+                field: "name".to_string(),
+                value: genome.name,
+            });
+        }
+
+        genome.id = self.genomes.save(&genome).await?;
+
+        info!(
+            "Registered reference genome: {} (ID: {})",
+            genome.name, genome.id
+        );
+
+        Ok(genome.into())
+    }
+
+    /// Updates a reference genome.
+    #[instrument(skip(self, request))]
+    pub async fn update_genome(
+        &self,
+        id: EntityId,
+        request: UpdateReferenceGenomeRequest,
+    ) -> Result<ReferenceGenomeResponse, DomainError> {
+        let mut genome = self.find_genome(id).await?;
+
+        if let Some(species) = request.species {
+            genome.species = species.trim().to_string();
+        }
+        if let Some(fasta_uri) = request.fasta_uri {
+            genome.fasta_uri = Some(fasta_uri);
+        }
+        if let Some(description) = request.description {
+            genome.description = Some(description);
+        }
+        genome.validate()?;
+        genome.updated_at = chrono::Utc::now();
+
+        self.genomes.save(&genome).await?;
+
+        info!("Updated reference genome: {} (ID: {})", genome.name, id);
+
+        Ok(genome.into())
+    }
+
+    /// Gets a reference genome by ID.
+    #[instrument(skip(self))]
+    pub async fn get_genome(&self, id: EntityId) -> Result<ReferenceGenomeResponse, DomainError> {
+        Ok(self.find_genome(id).await?.into())
+    }
+
+    /// Lists all reference genomes.
+    #[instrument(skip(self))]
+    pub async fn list_genomes(&self) -> Result<Vec<ReferenceGenomeResponse>, DomainError> {
+        let genomes = self.genomes.list().await?;
+        Ok(genomes.into_iter().map(Into::into).collect())
+    }
+
+    /// Gets the reference genome a library is analysed against: its own,
+    /// or else its project's.
+    #[instrument(skip(self))]
+    pub async fn for_library(
+        &self,
+        library_id: EntityId,
+    ) -> Result<LibraryReferenceGenome, DomainError> {
+        let library = self
+            .libraries
+            .find_by_id(library_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Library".to_string(),
+                id: library_id.to_string(),
+            })?;
+
+        let (genome_id, inherited) = match library.reference_genome_id {
+            Some(id) => (Some(id), false),
+            None => {
+                let project = self.projects.find_by_id(library.project_id).await?;
+                (project.and_then(|p| p.reference_genome_id), true)
+            }
+        };
+        let Some(genome_id) = genome_id else {
+            return Err(DomainError::Validation(format!(
+                "Neither library {} nor its project names a reference genome",
+                library.name
+            )));
+        };
+
+        Ok(LibraryReferenceGenome {
+            library_id,
+            project_id: library.project_id,
+            inherited,
+            reference_genome: self.find_genome(genome_id).await?.into(),
+        })
+    }
+
+    async fn find_genome(&self, id: EntityId) -> Result<ReferenceGenome, DomainError> {
+        self.genomes
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "ReferenceGenome".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Library, LibraryDesign, LibraryType, Project, Sample};
+    use miso_domain::repositories::QueryOptions;
+    use miso_domain::value_objects::Barcode;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryGenomes {
+        genomes: Mutex<Vec<ReferenceGenome>>,
+    }
+
+    #[async_trait]
+    impl ReferenceGenomeRepository for InMemoryGenomes {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<ReferenceGenome>, DomainError> {
+            let genomes = self.genomes.lock().unwrap();
+            Ok(genomes.iter().find(|g| g.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<ReferenceGenome>, DomainError> {
+            let genomes = self.genomes.lock().unwrap();
+            Ok(genomes.iter().find(|g| g.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<ReferenceGenome>, DomainError> {
+            Ok(self.genomes.lock().unwrap().clone())
+        }
+        async fn save(&self, genome: &ReferenceGenome) -> Result<EntityId, DomainError> {
+            let mut genomes = self.genomes.lock().unwrap();
+            let mut genome = genome.clone();
+            if genome.id == 0 {
+                genome.id = genomes.len() as EntityId + 1;
+            }
+            genomes.retain(|g| g.id != genome.id);
+            genomes.push(genome.clone());
+            Ok(genome.id)
+        }
+    }
+
+    struct Libraries(Vec<Library>);
+
+    #[async_trait]
+    impl LibraryRepository for Libraries {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+            Ok(self.0.iter().find(|l| l.id == id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn save(&self, library: &Library) -> Result<EntityId, DomainError> {
+            Ok(library.id)
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    struct Projects(Vec<Project>);
+
+    #[async_trait]
+    impl ProjectRepository for Projects {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Project>, DomainError> {
+            Ok(self.0.iter().find(|p| p.id == id).cloned())
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            Ok(None)
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            Ok(self.0.clone())
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn save(&self, project: &Project) -> Result<EntityId, DomainError> {
+            Ok(project.id)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            Ok(self.0.len() as u64)
+        }
+    }
+
+    fn library(id: EntityId, project_id: EntityId, genome: Option<EntityId>) -> Library {
+        let mut library = Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            project_id,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        library.reference_genome_id = genome;
+        library
+    }
+
+    fn request(name: &str, fasta_uri: Option<&str>) -> CreateReferenceGenomeRequest {
+        CreateReferenceGenomeRequest {
+            name: name.to_string(),
+            species: "Homo sapiens".to_string(),
+            fasta_uri: fasta_uri.map(str::to_string),
+            description: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_libraries_fall_back_to_their_projects_genome() {
+        let mut human = Project::new(1, "PRJ1".to_string(), "Human".to_string(), "pi".to_string());
+        human.reference_genome_id = Some(1);
+        let unset = Project::new(2, "PRJ2".to_string(), "Unset".to_string(), "pi".to_string());
+        let service = ReferenceGenomeService::new(
+            Arc::new(InMemoryGenomes::default()),
+            Arc::new(Libraries(vec![
+                library(1, 1, None),
+                library(2, 1, Some(2)),
+                library(3, 2, None),
+            ])),
+            Arc::new(Projects(vec![human, unset])),
+        );
+
+        let grch38 = service
+            .create_genome(request("GRCh38", None), "admin")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .create_genome(request("GRCh38", None), "admin")
+                .await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(service
+            .create_genome(request("hg38-custom", Some("/refs/hg38.fa")), "admin")
+            .await
+            .is_err());
+        let custom = service
+            .create_genome(request("hg38-custom", Some("s3://refs/hg38.fa")), "admin")
+            .await
+            .unwrap();
+
+        let inherited = service.for_library(1).await.unwrap();
+        assert!(inherited.inherited);
+        assert_eq!(inherited.reference_genome.id, grch38.id);
+
+        let own = service.for_library(2).await.unwrap();
+        assert!(!own.inherited);
+        assert_eq!(own.reference_genome.name, "hg38-custom");
+        assert_eq!(
+            own.reference_genome.fasta_uri.as_deref(),
+            Some("s3://refs/hg38.fa")
+        );
+
+        assert!(service.for_library(3).await.is_err());
+
+        let update = |fasta_uri: &str| UpdateReferenceGenomeRequest {
+            species: None,
+            fasta_uri: Some(fasta_uri.to_string()),
+            description: None,
+        };
+        assert!(service
+            .update_genome(custom.id, update("refs/hg38.fa"))
+            .await
+            .is_err());
+        let updated = service
+            .update_genome(custom.id, update("https://refs.example.org/hg38.fa"))
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "hg38-custom");
+        assert_eq!(service.list_genomes().await.unwrap().len(), 2);
+    }
+}
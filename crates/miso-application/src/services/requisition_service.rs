@@ -0,0 +1,613 @@
+//! Requisition service.
+//!
+//! Requisitions record the external orders samples are received for. Each
+//! sample is attached to at most one requisition, and work on an order can
+//! be paused while it is on hold or stopped if it is cancelled.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{EntityId, Requisition};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, RequisitionRepository, SampleRepository};
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateRequisitionRequest, RequisitionFilter, RequisitionHoldRequest, RequisitionResponse,
+    RequisitionSamplesRequest, UpdateRequisitionRequest,
+};
+
+/// Service for requisition operations.
+pub struct RequisitionService<R, S>
+where
+    R: RequisitionRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    requisitions: Arc<R>,
+    samples: Arc<S>,
+    audit: AuditTrail,
+}
+
+impl<R, S> RequisitionService<R, S>
+where
+    R: RequisitionRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new requisition service.
+    pub fn new(requisitions: Arc<R>, samples: Arc<S>) -> Self {
+        Self {
+            requisitions,
+            samples,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records requisition changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Records an external order and attaches the samples already received
+    /// for it.
+    ///
+    /// Order IDs are unique. Every sample must exist and not be attached to
+    /// another requisition.
+    #[instrument(skip(self, request), fields(alias = %request.alias))]
+    pub async fn create_requisition(
+        &self,
+        request: CreateRequisitionRequest,
+        created_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition =
+            Requisition::new(&request.alias, &request.assay, created_by.to_string())?;
+        self.ensure_alias_free(&requisition.alias, None).await?;
+
+        self.check_samples(&request.sample_ids, None).await?;
+        let now = Utc::now();
+        for &sample_id in &request.sample_ids {
+            requisition.attach(sample_id, now)?;
+        }
+
+        requisition.id = self.requisitions.save(&requisition).await?;
+        self.audit
+            .created("Requisition", requisition.id, &requisition, created_by)
+            .await?;
+
+        info!(
+            "Created requisition {} for {} with {} samples (ID: {})",
+            requisition.alias,
+            requisition.assay,
+            requisition.sample_ids.len(),
+            requisition.id
+        );
+
+        Ok(requisition.into())
+    }
+
+    /// Gets a requisition by ID.
+    pub async fn get_requisition(&self, id: EntityId) -> Result<RequisitionResponse, DomainError> {
+        Ok(self.find_requisition(id).await?.into())
+    }
+
+    /// Lists requisitions, newest first, or the requisition a sample is
+    /// attached to.
+    pub async fn list_requisitions(
+        &self,
+        filter: RequisitionFilter,
+    ) -> Result<Vec<RequisitionResponse>, DomainError> {
+        let requisitions = match filter.sample_id {
+            Some(sample_id) => self
+                .requisitions
+                .find_by_sample(sample_id)
+                .await?
+                .into_iter()
+                .collect(),
+            None => {
+                let options = QueryOptions {
+                    limit: Some(filter.limit.unwrap_or(50).min(500)),
+                    offset: filter.offset,
+                    ..Default::default()
+                };
+                self.requisitions.list(options).await?
+            }
+        };
+
+        Ok(requisitions.into_iter().map(Into::into).collect())
+    }
+
+    /// Corrects a requisition's order ID or assay.
+    #[instrument(skip(self, request))]
+    pub async fn update_requisition(
+        &self,
+        id: EntityId,
+        request: UpdateRequisitionRequest,
+        updated_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        if let Some(alias) = request.alias {
+            let alias = alias.trim().to_string();
+            self.ensure_alias_free(&alias, Some(id)).await?;
+            requisition.alias = alias;
+        }
+        if let Some(assay) = request.assay {
+            requisition.assay = assay.trim().to_string();
+        }
+        if requisition.alias.is_empty() || requisition.assay.is_empty() {
+            return Err(DomainError::Validation(
+                "A requisition needs an order ID and an assay".to_string(),
+            ));
+        }
+        requisition.updated_at = Utc::now();
+
+        self.save(&before, &requisition, updated_by).await?;
+        info!("Updated requisition {} (ID: {})", requisition.alias, id);
+
+        Ok(requisition.into())
+    }
+
+    /// Deletes a requisition, detaching its samples.
+    #[instrument(skip(self))]
+    pub async fn delete_requisition(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let requisition = self.find_requisition(id).await?;
+
+        self.requisitions.delete(id).await?;
+        self.audit
+            .deleted("Requisition", id, &requisition, deleted_by)
+            .await?;
+
+        info!("Deleted requisition {} (ID: {})", requisition.alias, id);
+        Ok(())
+    }
+
+    /// Attaches samples to a requisition. Every sample must exist and not
+    /// be attached to another requisition; samples already attached to
+    /// this one are skipped.
+    #[instrument(skip(self, request))]
+    pub async fn add_samples(
+        &self,
+        id: EntityId,
+        request: RequisitionSamplesRequest,
+        updated_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        self.check_samples(&request.sample_ids, Some(id)).await?;
+        let now = Utc::now();
+        let mut added = 0;
+        for &sample_id in &request.sample_ids {
+            added += requisition.attach(sample_id, now)? as usize;
+        }
+
+        if added > 0 {
+            self.save(&before, &requisition, updated_by).await?;
+            info!(
+                "Attached {} samples to requisition {} (ID: {})",
+                added, requisition.alias, id
+            );
+        }
+
+        Ok(requisition.into())
+    }
+
+    /// Detaches samples from a requisition. Samples not attached to it are
+    /// skipped.
+    #[instrument(skip(self, request))]
+    pub async fn remove_samples(
+        &self,
+        id: EntityId,
+        request: RequisitionSamplesRequest,
+        updated_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        let now = Utc::now();
+        let removed = request
+            .sample_ids
+            .iter()
+            .filter(|&&sample_id| requisition.detach(sample_id, now))
+            .count();
+
+        if removed > 0 {
+            self.save(&before, &requisition, updated_by).await?;
+            info!(
+                "Detached {} samples from requisition {} (ID: {})",
+                removed, requisition.alias, id
+            );
+        }
+
+        Ok(requisition.into())
+    }
+
+    /// Puts work on a requisition on hold.
+    #[instrument(skip(self, request))]
+    pub async fn pause(
+        &self,
+        id: EntityId,
+        request: RequisitionHoldRequest,
+        paused_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        requisition.pause(&request.reason, Utc::now())?;
+        self.save(&before, &requisition, paused_by).await?;
+
+        info!(
+            "Requisition {} (ID: {}) was paused by {}: {}",
+            requisition.alias,
+            id,
+            paused_by,
+            request.reason.trim()
+        );
+
+        Ok(requisition.into())
+    }
+
+    /// Takes work on a requisition off hold.
+    #[instrument(skip(self))]
+    pub async fn resume(
+        &self,
+        id: EntityId,
+        resumed_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        requisition.resume(Utc::now())?;
+        self.save(&before, &requisition, resumed_by).await?;
+
+        info!(
+            "Requisition {} (ID: {}) was resumed by {}",
+            requisition.alias, id, resumed_by
+        );
+
+        Ok(requisition.into())
+    }
+
+    /// Stops work on a requisition for good.
+    #[instrument(skip(self, request))]
+    pub async fn stop(
+        &self,
+        id: EntityId,
+        request: RequisitionHoldRequest,
+        stopped_by: &str,
+    ) -> Result<RequisitionResponse, DomainError> {
+        let mut requisition = self.find_requisition(id).await?;
+        let before = requisition.clone();
+
+        requisition.stop(&request.reason, Utc::now())?;
+        self.save(&before, &requisition, stopped_by).await?;
+
+        warn!(
+            "Requisition {} (ID: {}) was stopped by {}: {}",
+            requisition.alias,
+            id,
+            stopped_by,
+            request.reason.trim()
+        );
+
+        Ok(requisition.into())
+    }
+
+    async fn save(
+        &self,
+        before: &Requisition,
+        requisition: &Requisition,
+        updated_by: &str,
+    ) -> Result<(), DomainError> {
+        self.requisitions.save(requisition).await?;
+        self.audit
+            .updated("Requisition", requisition.id, before, requisition, updated_by)
+            .await
+    }
+
+    /// Fails unless every sample exists and none is attached to a
+    /// requisition other than `requisition_id`.
+    async fn check_samples(
+        &self,
+        sample_ids: &[EntityId],
+        requisition_id: Option<EntityId>,
+    ) -> Result<(), DomainError> {
+        if sample_ids.is_empty() {
+            return Ok(());
+        }
+
+        let found = self.samples.find_by_ids(sample_ids).await?;
+        for &sample_id in sample_ids {
+            if !found.iter().any(|s| s.id == sample_id) {
+                return Err(DomainError::NotFound {
+                    entity_type: "Sample".to_string(),
+                    id: sample_id.to_string(),
+                });
+            }
+            if let Some(other) = self.requisitions.find_by_sample(sample_id).await? {
+                if Some(other.id) != requisition_id {
+                    return Err(DomainError::Validation(format!(
+                        "Sample {} is already attached to requisition {}",
+                        sample_id, other.alias
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn ensure_alias_free(
+        &self,
+        alias: &str,
+        id: Option<EntityId>,
+    ) -> Result<(), DomainError> {
+        match self.requisitions.find_by_alias(alias).await? {
+            Some(existing) if Some(existing.id) != id => Err(DomainError::Duplicate {
+                entity_type: "Requisition".to_string(),
+                field: "alias".to_string(),
+                value: alias.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn find_requisition(&self, id: EntityId) -> Result<Requisition, DomainError> {
+        self.requisitions
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Requisition".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryRequisitions {
+        requisitions: Mutex<Vec<Requisition>>,
+    }
+
+    #[async_trait]
+    impl RequisitionRepository for InMemoryRequisitions {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Requisition>, DomainError> {
+            Ok(self.requisitions.lock().unwrap().iter().find(|r| r.id == id).cloned())
+        }
+        async fn find_by_alias(&self, alias: &str) -> Result<Option<Requisition>, DomainError> {
+            Ok(self
+                .requisitions
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|r| r.alias == alias)
+                .cloned())
+        }
+        async fn find_by_sample(
+            &self,
+            sample_id: EntityId,
+        ) -> Result<Option<Requisition>, DomainError> {
+            Ok(self
+                .requisitions
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|r| r.sample_ids.contains(&sample_id))
+                .cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Requisition>, DomainError> {
+            Ok(self.requisitions.lock().unwrap().iter().rev().cloned().collect())
+        }
+        async fn save(&self, requisition: &Requisition) -> Result<EntityId, DomainError> {
+            let mut requisitions = self.requisitions.lock().unwrap();
+            let mut requisition = requisition.clone();
+            if requisition.id == 0 {
+                requisition.id = requisitions.len() as EntityId + 1;
+            }
+            let id = requisition.id;
+            match requisitions.iter_mut().find(|r| r.id == id) {
+                Some(stored) => *stored = requisition,
+                None => requisitions.push(requisition),
+            }
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.requisitions.lock().unwrap().retain(|r| r.id != id);
+            Ok(())
+        }
+    }
+
+    /// Holds samples 1 and 2.
+    struct TwoSamples;
+
+    #[async_trait]
+    impl SampleRepository for TwoSamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(ids
+                .iter()
+                .filter(|id| (1..=2).contains(*id))
+                .map(|&id| {
+                    Sample::new_plain(
+                        id,
+                        format!("SAM{}", id),
+                        Barcode::new(format!("SAM-{}", id)).unwrap(),
+                        1,
+                        "Homo sapiens".to_string(),
+                        "admin".to_string(),
+                    )
+                })
+                .collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn create(alias: &str, sample_ids: Vec<i32>) -> CreateRequisitionRequest {
+        CreateRequisitionRequest {
+            alias: alias.to_string(),
+            assay: "WGS 30X".to_string(),
+            sample_ids,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_samples_belong_to_one_requisition() {
+        let service =
+            RequisitionService::new(Arc::new(InMemoryRequisitions::default()), Arc::new(TwoSamples));
+
+        assert!(matches!(
+            service.create_requisition(create("ORD-1", vec![3]), "tech").await,
+            Err(DomainError::NotFound { .. })
+        ));
+        let first = service
+            .create_requisition(create("ORD-1", vec![1]), "tech")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.create_requisition(create("ORD-1", vec![]), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service.create_requisition(create("ORD-2", vec![1]), "tech").await,
+            Err(DomainError::Validation(_))
+        ));
+
+        let second = service
+            .create_requisition(create("ORD-2", vec![2]), "tech")
+            .await
+            .unwrap();
+        let samples = |sample_ids| RequisitionSamplesRequest { sample_ids };
+        service
+            .remove_samples(second.id, samples(vec![2]), "tech")
+            .await
+            .unwrap();
+        let first = service
+            .add_samples(first.id, samples(vec![1, 2]), "tech")
+            .await
+            .unwrap();
+        assert_eq!(first.sample_ids, vec![1, 2]);
+
+        let of_sample = service
+            .list_requisitions(RequisitionFilter {
+                sample_id: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(of_sample[0].alias, "ORD-1");
+    }
+
+    #[tokio::test]
+    async fn test_stopped_requisition_takes_no_samples() {
+        let service =
+            RequisitionService::new(Arc::new(InMemoryRequisitions::default()), Arc::new(TwoSamples));
+        let requisition = service
+            .create_requisition(create("ORD-1", vec![1]), "tech")
+            .await
+            .unwrap();
+        let hold = |reason: &str| RequisitionHoldRequest {
+            reason: reason.to_string(),
+        };
+
+        let paused = service
+            .pause(requisition.id, hold("Awaiting consent"), "tech")
+            .await
+            .unwrap();
+        assert!(paused.paused && !paused.active);
+        assert!(service.resume(requisition.id, "tech").await.unwrap().active);
+
+        let stopped = service
+            .stop(requisition.id, hold("Order cancelled"), "tech")
+            .await
+            .unwrap();
+        assert_eq!(stopped.stop_reason.as_deref(), Some("Order cancelled"));
+        assert!(service
+            .add_samples(
+                requisition.id,
+                RequisitionSamplesRequest {
+                    sample_ids: vec![2]
+                },
+                "tech"
+            )
+            .await
+            .is_err());
+        assert!(service.resume(requisition.id, "tech").await.is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! Log retention service.
+//!
+//! Rows of the audit log, change log and storage audit history older than
+//! their retention period are written to gzip-compressed JSON-lines files,
+//! one row per line, and deleted from the database. Each file is recorded
+//! so that its rows can be put back exactly as they were.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use miso_domain::entities::{ArchivedLog, ArchivedRow, EntityId, LogArchive};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::LogArchiveRepository;
+use tracing::{info, instrument, warn};
+
+use crate::dto::{ArchivalReport, LogArchiveResponse, LogTableSizeResponse};
+
+/// Largest number of rows written to one archive file.
+const ARCHIVE_BATCH_SIZE: u64 = 10_000;
+
+/// How long each log's rows are kept before they are archived.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    periods: Vec<(ArchivedLog, Duration)>,
+}
+
+impl RetentionPolicy {
+    /// Creates a policy that keeps every row.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps a log's rows for `days` days.
+    pub fn keep(mut self, log: ArchivedLog, days: u32) -> Self {
+        self.periods.retain(|(kept, _)| *kept != log);
+        self.periods.push((log, Duration::days(days.into())));
+        self
+    }
+
+    /// Returns how long a log's rows are kept, or `None` if they are kept
+    /// for good.
+    pub fn period(&self, log: ArchivedLog) -> Option<Duration> {
+        self.periods
+            .iter()
+            .find(|(kept, _)| *kept == log)
+            .map(|(_, period)| *period)
+    }
+}
+
+/// Service for archiving old log rows and restoring them.
+pub struct RetentionService<A: LogArchiveRepository + ?Sized> {
+    archives: Arc<A>,
+    directory: PathBuf,
+    policy: RetentionPolicy,
+}
+
+impl<A: LogArchiveRepository + ?Sized> RetentionService<A> {
+    /// Creates a new retention service writing archives to `directory`.
+    /// Nothing is archived until a policy is set.
+    pub fn new(archives: Arc<A>, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            archives,
+            directory: directory.into(),
+            policy: RetentionPolicy::new(),
+        }
+    }
+
+    /// Sets how long each log's rows are kept.
+    pub fn with_policy(mut self, policy: RetentionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Archives every row past its log's retention period.
+    ///
+    /// Each archive file is written in full before its rows are deleted; if
+    /// the delete fails, the file is removed again and the rows stay.
+    #[instrument(skip(self))]
+    pub async fn archive_expired(&self) -> Result<ArchivalReport, DomainError> {
+        let started_at = Utc::now();
+
+        let mut archives = Vec::new();
+        for log in ArchivedLog::ALL {
+            let Some(period) = self.policy.period(log) else {
+                continue;
+            };
+            let before = started_at - period;
+            loop {
+                let rows = self
+                    .archives
+                    .list_expired(log, before, ARCHIVE_BATCH_SIZE)
+                    .await?;
+                if rows.is_empty() {
+                    break;
+                }
+                let full_batch = rows.len() as u64 == ARCHIVE_BATCH_SIZE;
+                archives.push(self.archive_rows(log, rows).await?);
+                if !full_batch {
+                    break;
+                }
+            }
+        }
+
+        let rows = archives.iter().map(|a| a.rows).sum();
+        info!("Archived {} log rows into {} files", rows, archives.len());
+
+        Ok(ArchivalReport {
+            started_at,
+            completed_at: Utc::now(),
+            rows,
+            archives: archives.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Writes rows to a new archive file, then deletes them.
+    async fn archive_rows(
+        &self,
+        log: ArchivedLog,
+        rows: Vec<ArchivedRow>,
+    ) -> Result<LogArchive, DomainError> {
+        let (first, last) = (rows[0].id, rows[rows.len() - 1].id);
+        let path = self
+            .directory
+            .join(format!("{}-{:010}-{:010}.jsonl.gz", log, first, last));
+        let mut archive = LogArchive::of_rows(log, path.to_string_lossy(), &rows)?;
+
+        let lines: Vec<String> = rows.iter().map(|row| row.json.clone()).collect();
+        let file = path.clone();
+        tokio::task::spawn_blocking(move || write_archive(&file, &lines))
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))??;
+
+        let ids: Vec<EntityId> = rows.iter().map(|row| row.id).collect();
+        match self.archives.archive(&archive, &ids).await {
+            Ok(id) => archive.id = id,
+            Err(e) => {
+                if let Err(remove) = std::fs::remove_file(&path) {
+                    warn!("Could not remove unused archive {}: {}", path.display(), remove);
+                }
+                return Err(e);
+            }
+        }
+
+        info!("Archived {} {} rows to {}", archive.rows, log, path.display());
+        Ok(archive)
+    }
+
+    /// Puts an archive's rows back into their table.
+    ///
+    /// The archives of a chained log must be restored newest first, so the
+    /// log stays one unbroken chain. Restored rows are still past their
+    /// retention period and are archived again by the next pass unless the
+    /// period is lengthened.
+    #[instrument(skip(self))]
+    pub async fn restore(&self, id: EntityId) -> Result<LogArchiveResponse, DomainError> {
+        let archive = self
+            .archives
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "LogArchive".to_string(),
+                id: id.to_string(),
+            })?;
+        if archive.is_restored() {
+            return Err(DomainError::Validation(format!(
+                "Archive {} has already been restored",
+                id
+            )));
+        }
+        if archive.log.is_chained() {
+            let newest = self
+                .archives
+                .list(archive.log)
+                .await?
+                .into_iter()
+                .find(|a| !a.is_restored());
+            if let Some(newest) = newest.filter(|newest| newest.id != archive.id) {
+                return Err(DomainError::Validation(format!(
+                    "The {} is restored newest archive first; restore archive {} first",
+                    archive.log, newest.id
+                )));
+            }
+        }
+
+        let file = PathBuf::from(&archive.file);
+        let rows = tokio::task::spawn_blocking(move || read_archive(&file))
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))??;
+        if rows.len() as u64 != archive.rows {
+            return Err(DomainError::Validation(format!(
+                "Archive {} should hold {} rows but its file holds {}",
+                id,
+                archive.rows,
+                rows.len()
+            )));
+        }
+
+        self.archives.restore(&archive, &rows).await?;
+        info!("Restored {} {} rows from {}", archive.rows, archive.log, archive.file);
+
+        let mut restored = archive;
+        restored.restored_at = Some(Utc::now());
+        Ok(restored.into())
+    }
+
+    /// Lists a log's archives, newest first.
+    #[instrument(skip(self))]
+    pub async fn list_archives(
+        &self,
+        log: ArchivedLog,
+    ) -> Result<Vec<LogArchiveResponse>, DomainError> {
+        let archives = self.archives.list(log).await?;
+        Ok(archives.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns how big each log has grown, and how much has been archived.
+    #[instrument(skip(self))]
+    pub async fn sizes(&self) -> Result<Vec<LogTableSizeResponse>, DomainError> {
+        let sizes = self.archives.sizes().await?;
+        Ok(sizes.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Writes rows to a compressed archive file, replacing it only once it is
+/// complete.
+fn write_archive(path: &Path, rows: &[String]) -> Result<(), DomainError> {
+    let failed = |e: std::io::Error| {
+        DomainError::Validation(format!("Failed to write archive {}: {}", path.display(), e))
+    };
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(failed)?;
+    }
+
+    let partial = path.with_extension("gz.partial");
+    let mut writer = GzEncoder::new(
+        BufWriter::new(File::create(&partial).map_err(failed)?),
+        Compression::default(),
+    );
+    for row in rows {
+        writeln!(writer, "{}", row).map_err(failed)?;
+    }
+    let file = writer
+        .finish()
+        .map_err(failed)?
+        .into_inner()
+        .map_err(|e| failed(e.into_error()))?;
+    file.sync_all().map_err(failed)?;
+    std::fs::rename(&partial, path).map_err(failed)
+}
+
+/// Reads the rows of a compressed archive file.
+fn read_archive(path: &Path) -> Result<Vec<String>, DomainError> {
+    let failed = |e: std::io::Error| {
+        DomainError::Validation(format!("Failed to read archive {}: {}", path.display(), e))
+    };
+    let reader = BufReader::new(GzDecoder::new(File::open(path).map_err(failed)?));
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.is_empty()))
+        .collect::<Result<_, _>>()
+        .map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::DateTime;
+    use miso_domain::entities::LogTableSize;
+
+    use super::*;
+
+    /// A log whose rows are numbered and `days_old` days old.
+    #[derive(Default)]
+    struct InMemoryArchives {
+        rows: Mutex<Vec<(ArchivedLog, EntityId, i64)>>,
+        archives: Mutex<Vec<LogArchive>>,
+    }
+
+    impl InMemoryArchives {
+        fn with_rows(log: ArchivedLog, ages: &[i64]) -> Self {
+            let archives = Self::default();
+            archives.rows.lock().unwrap().extend(
+                ages.iter()
+                    .enumerate()
+                    .map(|(i, days_old)| (log, i as EntityId + 1, *days_old)),
+            );
+            archives
+        }
+
+        fn ids(&self, log: ArchivedLog) -> Vec<EntityId> {
+            let mut ids: Vec<EntityId> = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(l, _, _)| *l == log)
+                .map(|(_, id, _)| *id)
+                .collect();
+            ids.sort();
+            ids
+        }
+    }
+
+    #[async_trait]
+    impl LogArchiveRepository for InMemoryArchives {
+        async fn list_expired(
+            &self,
+            log: ArchivedLog,
+            before: DateTime<Utc>,
+            limit: u64,
+        ) -> Result<Vec<ArchivedRow>, DomainError> {
+            let mut rows: Vec<_> = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(l, _, _)| *l == log)
+                .cloned()
+                .collect();
+            rows.sort_by_key(|(_, id, _)| *id);
+            Ok(rows
+                .into_iter()
+                .take(limit as usize)
+                .take_while(|(_, _, days_old)| Utc::now() - Duration::days(*days_old) < before)
+                .map(|(_, id, days_old)| ArchivedRow {
+                    id,
+                    hash: format!("hash{}", id),
+                    json: format!("{{\"id\":{},\"days_old\":{}}}", id, days_old),
+                })
+                .collect())
+        }
+        async fn archive(
+            &self,
+            archive: &LogArchive,
+            ids: &[EntityId],
+        ) -> Result<EntityId, DomainError> {
+            self.rows
+                .lock()
+                .unwrap()
+                .retain(|(log, id, _)| *log != archive.log || !ids.contains(id));
+            let mut archives = self.archives.lock().unwrap();
+            let mut archive = archive.clone();
+            archive.id = archives.len() as EntityId + 1;
+            archives.push(archive);
+            Ok(archives.len() as EntityId)
+        }
+        async fn restore(&self, archive: &LogArchive, rows: &[String]) -> Result<(), DomainError> {
+            for row in rows {
+                let row: serde_json::Value = serde_json::from_str(row).unwrap();
+                self.rows.lock().unwrap().push((
+                    archive.log,
+                    row["id"].as_i64().unwrap() as EntityId,
+                    row["days_old"].as_i64().unwrap(),
+                ));
+            }
+            self.archives.lock().unwrap()[archive.id as usize - 1].restored_at = Some(Utc::now());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<LogArchive>, DomainError> {
+            Ok(self.archives.lock().unwrap().get(id as usize - 1).cloned())
+        }
+        async fn list(&self, log: ArchivedLog) -> Result<Vec<LogArchive>, DomainError> {
+            let archives = self.archives.lock().unwrap();
+            Ok(archives
+                .iter()
+                .rev()
+                .filter(|a| a.log == log)
+                .cloned()
+                .collect())
+        }
+        async fn sizes(&self) -> Result<Vec<LogTableSize>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("miso-retention-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_expired_rows_are_archived_and_restored() {
+        let log = ArchivedLog::AuditLog;
+        // The fourth row is recent, so it and everything after it stays
+        let repo = Arc::new(InMemoryArchives::with_rows(log, &[400, 390, 380, 10, 500]));
+        let dir = temp_dir("archive");
+        let service = RetentionService::new(repo.clone(), &dir)
+            .with_policy(RetentionPolicy::new().keep(log, 365));
+
+        let report = service.archive_expired().await.unwrap();
+        assert_eq!(report.rows, 3);
+        assert_eq!(report.archives.len(), 1);
+        assert_eq!((report.archives[0].first_id, report.archives[0].last_id), (1, 3));
+        assert_eq!(repo.ids(log), vec![4, 5]);
+        assert!(Path::new(&report.archives[0].file).exists());
+
+        // Nothing left to archive
+        assert_eq!(service.archive_expired().await.unwrap().rows, 0);
+
+        let restored = service.restore(report.archives[0].id).await.unwrap();
+        assert!(restored.restored_at.is_some());
+        assert_eq!(repo.ids(log), vec![1, 2, 3, 4, 5]);
+        assert!(service.restore(report.archives[0].id).await.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_chained_logs_restore_newest_archive_first() {
+        let log = ArchivedLog::ChangeLog;
+        let repo = Arc::new(InMemoryArchives::with_rows(log, &[400, 400]));
+        let dir = temp_dir("order");
+        let service = RetentionService::new(repo.clone(), &dir)
+            .with_policy(RetentionPolicy::new().keep(log, 365));
+
+        let first = service.archive_expired().await.unwrap().archives[0].id;
+        repo.rows.lock().unwrap().push((log, 3, 400));
+        let second = service.archive_expired().await.unwrap().archives[0].id;
+
+        let error = service.restore(first).await.unwrap_err();
+        assert!(error.to_string().contains("restore archive 2 first"));
+        service.restore(second).await.unwrap();
+        service.restore(first).await.unwrap();
+        assert_eq!(repo.ids(log), vec![1, 2, 3]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_logs_without_a_period_are_kept() {
+        let repo = Arc::new(InMemoryArchives::with_rows(ArchivedLog::StorageAudit, &[4000]));
+        let service = RetentionService::new(repo.clone(), temp_dir("kept"))
+            .with_policy(RetentionPolicy::new().keep(ArchivedLog::AuditLog, 1));
+
+        assert_eq!(service.archive_expired().await.unwrap().rows, 0);
+        assert_eq!(repo.ids(ArchivedLog::StorageAudit), vec![1]);
+    }
+}
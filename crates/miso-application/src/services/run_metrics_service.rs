@@ -0,0 +1,94 @@
+//! Run metrics service for ingesting demultiplexing results.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, LibraryRunMetrics};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::RunMetricsRepository;
+use miso_domain::services::AssayCompletionEvaluator;
+use tracing::{info, instrument};
+
+use crate::dto::{RunMetricsResponse, SubmitRunMetricsRequest};
+
+/// Service for run metrics operations.
+pub struct RunMetricsService<R: RunMetricsRepository + ?Sized> {
+    repository: Arc<R>,
+    evaluator: AssayCompletionEvaluator,
+}
+
+impl<R: RunMetricsRepository + ?Sized> RunMetricsService<R> {
+    /// Creates a new run metrics service with default assay requirements.
+    pub fn new(repository: Arc<R>) -> Self {
+        Self {
+            repository,
+            evaluator: AssayCompletionEvaluator::new(),
+        }
+    }
+
+    /// Sets the evaluator used for assay completion.
+    pub fn with_evaluator(mut self, evaluator: AssayCompletionEvaluator) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Submits metrics for a run, replacing any earlier values for the same
+    /// lane and library.
+    #[instrument(skip(self, request))]
+    pub async fn submit_metrics(
+        &self,
+        run_id: EntityId,
+        request: SubmitRunMetricsRequest,
+        submitted_by: &str,
+    ) -> Result<RunMetricsResponse, DomainError> {
+        let metrics = request
+            .metrics
+            .into_iter()
+            .map(|m| {
+                LibraryRunMetrics::new(
+                    run_id,
+                    m.partition_number,
+                    m.library_id,
+                    m.reads,
+                    m.yield_bases,
+                    m.q30_percent,
+                    m.index_hopping_percent,
+                    submitted_by.to_string(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (i, m) in metrics.iter().enumerate() {
+            if metrics[..i].iter().any(|other| other.same_slot(m)) {
+                return Err(DomainError::Duplicate {
+                    entity_type: "RunMetrics".to_string(),
+                    field: "partition_number/library_id".to_string(),
+                    value: format!("{}/{}", m.partition_number, m.library_id),
+                });
+            }
+        }
+
+        self.repository.save_all(&metrics).await?;
+
+        info!(
+            "Stored {} metrics records for run {} (submitted by {})",
+            metrics.len(),
+            run_id,
+            submitted_by
+        );
+
+        self.get_metrics(run_id).await
+    }
+
+    /// Gets all metrics for a run together with per-library completion.
+    #[instrument(skip(self))]
+    pub async fn get_metrics(&self, run_id: EntityId) -> Result<RunMetricsResponse, DomainError> {
+        let metrics = self.repository.find_by_run(run_id).await?;
+        let completion = self.evaluator.evaluate(&metrics);
+
+        Ok(RunMetricsResponse {
+            run_id,
+            metrics: metrics.into_iter().map(Into::into).collect(),
+            completion: completion.into_iter().map(Into::into).collect(),
+        })
+    }
+}
@@ -0,0 +1,115 @@
+//! Run monitoring service.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{EntityId, Run};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{LibraryRepository, PoolRepository, RunRepository};
+use tracing::{info, instrument};
+
+use crate::dto::{PoolOverview, RunOverviewResponse, SignOffRunQcRequest};
+
+/// Service behind the run detail page: lane metrics, loaded pools and QC
+/// sign-off.
+///
+/// Pools are reported as unavailable until their repositories are
+/// supplied.
+pub struct RunMonitorService {
+    runs: Arc<dyn RunRepository>,
+    pools: Option<(Arc<dyn PoolRepository>, Arc<dyn LibraryRepository>)>,
+}
+
+impl RunMonitorService {
+    /// Creates a service reporting runs and their lanes only.
+    pub fn new(runs: Arc<dyn RunRepository>) -> Self {
+        Self { runs, pools: None }
+    }
+
+    /// Enables the pools and libraries section.
+    pub fn with_pools(
+        mut self,
+        pools: Arc<dyn PoolRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+    ) -> Self {
+        self.pools = Some((pools, libraries));
+        self
+    }
+
+    /// Returns a run with its lanes, pools and QC decision.
+    #[instrument(skip(self))]
+    pub async fn overview(&self, id: EntityId) -> Result<RunOverviewResponse, DomainError> {
+        let run = self.find_run(id).await?;
+        self.build_overview(&run).await
+    }
+
+    /// Records a reviewer's QC decision on a run.
+    #[instrument(skip(self, request))]
+    pub async fn sign_off_qc(
+        &self,
+        id: EntityId,
+        request: SignOffRunQcRequest,
+        reviewer: &str,
+        now: DateTime<Utc>,
+    ) -> Result<RunOverviewResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+        run.sign_off_qc(request.passed, reviewer.to_string(), request.note, now)?;
+        self.runs.save(&run).await?;
+
+        info!(
+            run = %run.name,
+            passed = request.passed,
+            reviewer,
+            "Run QC signed off"
+        );
+
+        self.build_overview(&run).await
+    }
+
+    async fn find_run(&self, id: EntityId) -> Result<Run, DomainError> {
+        self.runs
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Run".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn build_overview(&self, run: &Run) -> Result<RunOverviewResponse, DomainError> {
+        let mut overview = RunOverviewResponse::new(run);
+
+        if let Some((pools, libraries)) = &self.pools {
+            let mut pool_ids: Vec<EntityId> =
+                run.partitions.iter().filter_map(|p| p.pool_id).collect();
+            pool_ids.sort_unstable();
+            pool_ids.dedup();
+
+            let mut loaded = Vec::with_capacity(pool_ids.len());
+            for pool_id in pool_ids {
+                if let Some(pool) = pools.find_by_id(pool_id).await? {
+                    loaded.push(pool);
+                }
+            }
+
+            let library_ids: Vec<EntityId> = loaded
+                .iter()
+                .flat_map(|p| p.elements.iter().map(|e| e.library_id))
+                .collect();
+            let found = if library_ids.is_empty() {
+                Vec::new()
+            } else {
+                libraries.find_by_ids(&library_ids).await?
+            };
+
+            overview.pools = Some(
+                loaded
+                    .iter()
+                    .map(|pool| PoolOverview::new(pool, run, &found))
+                    .collect(),
+            );
+        }
+
+        Ok(overview)
+    }
+}
@@ -0,0 +1,1013 @@
+//! Run service for the run lifecycle: setting a run up on a sequencer,
+//! loading pools and moving it through sequencing.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{ControlType, EntityId, LoadScan, Run, RunStatus, Sequencer};
+use miso_domain::errors::{DomainError, PoolError, RunError};
+use miso_domain::repositories::{
+    LibraryRepository, PoolRepository, QueryOptions, RunRepository, SequencerRepository,
+};
+use miso_domain::services::ControlPolicy;
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    AssignPoolRequest, CreateRunRequest, LoadCheckRequest, LoadCheckResponse, PauseRunRequest,
+    ResumeRunRequest, RunProgressRequest, RunResponse, RunSummary,
+};
+
+/// Service for run lifecycle operations.
+pub struct RunService<R, S, P>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+{
+    repository: Arc<R>,
+    sequencers: Arc<S>,
+    pools: Arc<P>,
+    libraries: Option<Arc<dyn LibraryRepository>>,
+    controls: ControlPolicy,
+    audit: AuditTrail,
+}
+
+impl<R, S, P> RunService<R, S, P>
+where
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+{
+    /// Creates a new run service.
+    pub fn new(repository: Arc<R>, sequencers: Arc<S>, pools: Arc<P>) -> Self {
+        Self {
+            repository,
+            sequencers,
+            pools,
+            libraries: None,
+            controls: ControlPolicy::default(),
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Requires the controls a run needs, among the libraries of its pools,
+    /// before it starts.
+    pub fn with_controls(
+        mut self,
+        controls: ControlPolicy,
+        libraries: Arc<dyn LibraryRepository>,
+    ) -> Self {
+        self.controls = controls;
+        self.libraries = Some(libraries);
+        self
+    }
+
+    /// Records run changes, and the pools they sequence, in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Sets up a run on a sequencer, with one partition per lane of the
+    /// sequencer's instrument model.
+    ///
+    /// The sequencer must be available, and the container and chemistry,
+    /// if given, supported by its model. A container is given as a barcode
+    /// together with its model.
+    #[instrument(skip(self))]
+    pub async fn create_run(
+        &self,
+        request: CreateRunRequest,
+        created_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        if self.repository.find_by_name(&request.name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Run".to_string(),
+                field: "name".to_string(),
+                value: request.name,
+            });
+        }
+
+        let sequencer = self.find_sequencer(request.sequencer_id).await?;
+        check_can_run(&sequencer)?;
+
+        let mut run = Run::new(
+            0,
+            request.name,
+            sequencer.id,
+            sequencer.num_partitions(),
+            created_by.to_string(),
+        );
+        run.alias = request.alias;
+        run.read_length = request.read_length;
+        run.description = request.description;
+        match (request.container_barcode, request.container_model) {
+            (Some(barcode), Some(model)) => {
+                run.assign_container(barcode, &model, &sequencer.model)?
+            }
+            (Some(_), None) => {
+                return Err(DomainError::Validation(
+                    "A container barcode needs a container model".to_string(),
+                ))
+            }
+            (None, Some(_)) => {
+                return Err(DomainError::Validation(
+                    "A container model needs a container barcode".to_string(),
+                ))
+            }
+            (None, None) => {}
+        }
+        sequencer
+            .model
+            .check_run(&run, request.chemistry.as_deref())?;
+
+        let id = self.repository.save(&run).await?;
+        run.id = id;
+        self.audit.created("Run", id, &run, created_by).await?;
+
+        info!(
+            "Created run: {} on {} (ID: {})",
+            run.name, sequencer.name, id
+        );
+
+        Ok(run.into())
+    }
+
+    /// Gets a run by ID.
+    #[instrument(skip(self))]
+    pub async fn get_run(&self, id: EntityId) -> Result<RunResponse, DomainError> {
+        Ok(self.find_run(id).await?.into())
+    }
+
+    /// Lists runs, newest first, optionally only those with a status code
+    /// or on a sequencer.
+    #[instrument(skip(self))]
+    pub async fn list_runs(
+        &self,
+        status: Option<&str>,
+        sequencer_id: Option<EntityId>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<RunSummary>, DomainError> {
+        let status = status.map(parse_run_status).transpose()?;
+        let limit = limit.unwrap_or(100);
+        let offset = offset.unwrap_or(0);
+
+        let mut runs = match (status, sequencer_id) {
+            (None, None) => {
+                let options = QueryOptions::new()
+                    .limit(limit)
+                    .offset(offset)
+                    .sort_by("created_at")
+                    .descending();
+                let runs = self.repository.list(options).await?;
+                return Ok(runs.into_iter().map(Into::into).collect());
+            }
+            (_, Some(sequencer_id)) => self.repository.find_by_sequencer(sequencer_id).await?,
+            (Some(status), None) => self.repository.find_by_status(status).await?,
+        };
+        if let Some(status) = status {
+            runs.retain(|r| r.status == status);
+        }
+        runs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+
+        Ok(runs
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Loads a pool on one of a run's partitions, replacing any pool there.
+    ///
+    /// The run must not have finished, and the pool must be ready to
+    /// sequence: not empty, past QC and not sequenced already.
+    #[instrument(skip(self))]
+    pub async fn assign_pool(
+        &self,
+        id: EntityId,
+        partition_number: u8,
+        request: AssignPoolRequest,
+        assigned_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+        if run.status.is_terminal() {
+            return Err(RunError::AlreadyComplete(run.name).into());
+        }
+        let before = run.clone();
+
+        let pool = self
+            .pools
+            .find_by_id(request.pool_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Pool".to_string(),
+                id: request.pool_id.to_string(),
+            })?;
+        if pool.is_empty() {
+            return Err(PoolError::EmptyPool(pool.name).into());
+        }
+        if pool.sequenced {
+            return Err(PoolError::AlreadySequenced(pool.name).into());
+        }
+        if !pool.can_sequence() {
+            return Err(DomainError::Validation(format!(
+                "Pool {} has not passed QC",
+                pool.name
+            )));
+        }
+
+        let run_name = run.name.clone();
+        run.get_partition_mut(partition_number)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "RunPartition".to_string(),
+                id: format!("{}:{}", run_name, partition_number),
+            })?
+            .set_pool(pool.id, request.loading_concentration);
+        self.repository.save(&run).await?;
+        self.audit
+            .updated("Run", id, &before, &run, assigned_by)
+            .await?;
+
+        info!(
+            "Loaded pool {} on partition {} of run: {} (ID: {})",
+            pool.name, partition_number, run.name, id
+        );
+
+        Ok(run.into())
+    }
+
+    /// Checks the flow cell and pool tubes scanned at the instrument against
+    /// the run's plan, refusing with every mismatch found so that the wrong
+    /// pool or flow cell isn't loaded.
+    #[instrument(skip(self, request))]
+    pub async fn check_load(
+        &self,
+        id: EntityId,
+        request: LoadCheckRequest,
+        checked_by: &str,
+    ) -> Result<LoadCheckResponse, DomainError> {
+        let run = self.find_run(id).await?;
+
+        let mut scans = Vec::with_capacity(request.pools.len());
+        for scanned in request.pools {
+            let barcode = scanned.pool_barcode.trim().to_string();
+            let pool = self.pools.find_by_barcode(&barcode).await?;
+            scans.push(LoadScan {
+                barcode,
+                pool_id: pool.map(|p| p.id),
+                partition: scanned.partition,
+            });
+        }
+
+        if let Err(e) = run.check_load(&request.container_barcode, &scans) {
+            warn!("{} was refused loading run {}: {}", checked_by, run.name, e);
+            return Err(e.into());
+        }
+
+        info!("{} checked the load of run: {} (ID: {})", checked_by, run.name, id);
+
+        Ok(LoadCheckResponse {
+            run_id: run.id,
+            container_barcode: request.container_barcode.trim().to_string(),
+            pool_ids: run.pool_ids(),
+            checked_by: checked_by.to_string(),
+            checked_at: Utc::now(),
+        })
+    }
+
+    /// Moves a run to "running", "completed" or "failed".
+    ///
+    /// Starting a run needs an available sequencer, which then runs until
+    /// the run completes or fails, and estimates when the run will finish
+    /// from the sequencer's instrument model. Completing a run marks its pools
+    /// sequenced. A paused run can fail but must resume to complete.
+    ///
+    /// With a control policy set, a run starts only if its pools hold the
+    /// controls the policy requires of a run.
+    #[instrument(skip(self))]
+    pub async fn update_status(
+        &self,
+        id: EntityId,
+        status: &str,
+        updated_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+        let before = run.clone();
+        let target = parse_run_status(status)?;
+
+        let expected: &[RunStatus] = match target {
+            RunStatus::Running => &[RunStatus::Unknown],
+            RunStatus::Completed => &[RunStatus::Running],
+            RunStatus::Failed => &[RunStatus::Running, RunStatus::Paused],
+            _ => {
+                return Err(DomainError::Validation(format!(
+                    "A run's status can only be set to running, completed or failed, not {}",
+                    status
+                )))
+            }
+        };
+        if !expected.contains(&run.status) {
+            return Err(DomainError::InvalidStateTransition {
+                entity: format!("Run {}", run.name),
+                from: run.status.to_string(),
+                to: target.to_string(),
+            });
+        }
+
+        let mut sequencer = self.find_sequencer(run.sequencer_id).await?;
+        match target {
+            RunStatus::Running => {
+                check_can_run(&sequencer)?;
+                self.check_controls(&run).await?;
+                sequencer.start_run();
+                run.start();
+                run.estimate_completion(&sequencer.model);
+            }
+            RunStatus::Completed => {
+                for pool_id in run.pool_ids() {
+                    if let Some(mut pool) = self.pools.find_by_id(pool_id).await? {
+                        let unsequenced = pool.clone();
+                        pool.mark_sequenced();
+                        self.pools.save(&pool).await?;
+                        self.audit
+                            .updated("Pool", pool_id, &unsequenced, &pool, updated_by)
+                            .await?;
+                    }
+                }
+                sequencer.complete_run();
+                run.complete();
+            }
+            _ => {
+                sequencer.complete_run();
+                run.fail();
+            }
+        }
+        self.sequencers.save(&sequencer).await?;
+        self.repository.save(&run).await?;
+        self.audit
+            .updated("Run", id, &before, &run, updated_by)
+            .await?;
+
+        info!(
+            "Run {} (ID: {}) is now {} on {}",
+            run.name, id, run.status, sequencer.name
+        );
+
+        Ok(run.into())
+    }
+
+    /// Pauses a running run, recording the interruption's cause and any
+    /// lanes flagged for extra QC review. The sequencer stays held by the
+    /// run while it is paused.
+    #[instrument(skip(self, request))]
+    pub async fn pause_run(
+        &self,
+        id: EntityId,
+        request: PauseRunRequest,
+        paused_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+        let before = run.clone();
+
+        run.pause(
+            &request.cause,
+            &request.flagged_lanes,
+            paused_by.to_string(),
+            Utc::now(),
+        )?;
+        self.repository.save(&run).await?;
+        self.audit
+            .updated("Run", id, &before, &run, paused_by)
+            .await?;
+
+        warn!(
+            "Run {} (ID: {}) was paused by {}: {}",
+            run.name,
+            id,
+            paused_by,
+            request.cause.trim()
+        );
+
+        Ok(run.into())
+    }
+
+    /// Resumes a paused run, ending its interruption and flagging any lanes
+    /// found to be affected.
+    #[instrument(skip(self, request))]
+    pub async fn resume_run(
+        &self,
+        id: EntityId,
+        request: ResumeRunRequest,
+        resumed_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+        let before = run.clone();
+
+        run.resume(&request.flagged_lanes, Utc::now())?;
+        self.repository.save(&run).await?;
+        self.audit
+            .updated("Run", id, &before, &run, resumed_by)
+            .await?;
+
+        info!(
+            "Run {} (ID: {}) was resumed by {} after {} minutes paused in total",
+            run.name,
+            id,
+            resumed_by,
+            run.downtime(Utc::now()).num_minutes()
+        );
+
+        Ok(run.into())
+    }
+
+    /// Records the sequencing cycles a running run has completed and
+    /// re-estimates when it will finish.
+    ///
+    /// Progress is reported often while a run sequences, so it is logged
+    /// but not audited.
+    #[instrument(skip(self))]
+    pub async fn record_progress(
+        &self,
+        id: EntityId,
+        request: RunProgressRequest,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self.find_run(id).await?;
+
+        run.record_progress(request.cycles_completed, Utc::now())?;
+        self.repository.save(&run).await?;
+
+        info!(
+            "Run {} (ID: {}) has completed {} cycles, expected to finish at {:?}",
+            run.name, id, request.cycles_completed, run.estimated_completion
+        );
+
+        Ok(run.into())
+    }
+
+    async fn find_run(&self, id: EntityId) -> Result<Run, DomainError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Run".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn check_controls(&self, run: &Run) -> Result<(), DomainError> {
+        let Some(libraries) = &self.libraries else {
+            return Ok(());
+        };
+        let mut library_ids = Vec::new();
+        for pool_id in run.pool_ids() {
+            if let Some(pool) = self.pools.find_by_id(pool_id).await? {
+                library_ids.extend(pool.library_ids());
+            }
+        }
+        let controls: Vec<ControlType> = if library_ids.is_empty() {
+            Vec::new()
+        } else {
+            libraries
+                .find_by_ids(&library_ids)
+                .await?
+                .iter()
+                .filter_map(|l| l.control_type)
+                .collect()
+        };
+        self.controls.check_run(&run.name, &controls)
+    }
+
+    async fn find_sequencer(&self, id: EntityId) -> Result<Sequencer, DomainError> {
+        self.sequencers
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sequencer".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+/// Fails unless the sequencer can take a new run.
+fn check_can_run(sequencer: &Sequencer) -> Result<(), DomainError> {
+    if sequencer.can_run() {
+        Ok(())
+    } else {
+        Err(RunError::InvalidSequencer(format!(
+            "{} is {} and cannot take a new run",
+            sequencer.name, sequencer.status
+        ))
+        .into())
+    }
+}
+
+fn parse_run_status(code: &str) -> Result<RunStatus, DomainError> {
+    match code {
+        "unknown" => Ok(RunStatus::Unknown),
+        "running" => Ok(RunStatus::Running),
+        "paused" => Ok(RunStatus::Paused),
+        "completed" => Ok(RunStatus::Completed),
+        "failed" => Ok(RunStatus::Failed),
+        "stopped" => Ok(RunStatus::Stopped),
+        "qc_in_progress" => Ok(RunStatus::QcInProgress),
+        "qc_passed" => Ok(RunStatus::QcPassed),
+        "qc_failed" => Ok(RunStatus::QcFailed),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid run status: {}",
+            code
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        InstrumentModel, Library, LibraryDesign, LibraryType, Platform, Pool, PoolElement, Sample,
+        SequencerStatus,
+    };
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::dto::LoadCheckScan;
+
+    #[derive(Default)]
+    struct InMemoryRuns {
+        runs: Mutex<HashMap<EntityId, Run>>,
+    }
+
+    impl InMemoryRuns {
+        fn matching(&self, keep: impl Fn(&Run) -> bool) -> Vec<Run> {
+            self.runs
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| keep(r))
+                .cloned()
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl RunRepository for InMemoryRuns {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Run>, DomainError> {
+            Ok(self.runs.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_name(&self, name: &str) -> Result<Option<Run>, DomainError> {
+            Ok(self.matching(|r| r.name == name).pop())
+        }
+
+        async fn find_by_sequencer(&self, id: EntityId) -> Result<Vec<Run>, DomainError> {
+            Ok(self.matching(|r| r.sequencer_id == id))
+        }
+
+        async fn find_by_status(&self, status: RunStatus) -> Result<Vec<Run>, DomainError> {
+            Ok(self.matching(|r| r.status == status))
+        }
+
+        async fn find_by_pool(&self, id: EntityId) -> Result<Vec<Run>, DomainError> {
+            Ok(self.matching(|r| r.pool_ids().contains(&id)))
+        }
+
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            Ok(self.matching(|_| true))
+        }
+
+        async fn save(&self, run: &Run) -> Result<EntityId, DomainError> {
+            let mut runs = self.runs.lock().unwrap();
+            let mut stored = run.clone();
+            if stored.id == 0 {
+                stored.id = runs.len() as EntityId + 1;
+            }
+            let id = stored.id;
+            runs.insert(id, stored);
+            Ok(id)
+        }
+
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.runs.lock().unwrap().remove(&id);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemorySequencers {
+        sequencers: Mutex<HashMap<EntityId, Sequencer>>,
+    }
+
+    #[async_trait]
+    impl SequencerRepository for InMemorySequencers {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sequencer>, DomainError> {
+            Ok(self.sequencers.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_name(&self, _: &str) -> Result<Option<Sequencer>, DomainError> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+            Ok(self.sequencers.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn save(&self, sequencer: &Sequencer) -> Result<EntityId, DomainError> {
+            self.sequencers
+                .lock()
+                .unwrap()
+                .insert(sequencer.id, sequencer.clone());
+            Ok(sequencer.id)
+        }
+
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryPools {
+        pools: Mutex<HashMap<EntityId, Pool>>,
+    }
+
+    #[async_trait]
+    impl PoolRepository for InMemoryPools {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Pool>, DomainError> {
+            Ok(self.pools.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Pool>, DomainError> {
+            Ok(self
+                .pools
+                .lock()
+                .unwrap()
+                .values()
+                .find(|p| p.barcode.as_str() == barcode)
+                .cloned())
+        }
+
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            Ok(self.pools.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn save(&self, pool: &Pool) -> Result<EntityId, DomainError> {
+            self.pools.lock().unwrap().insert(pool.id, pool.clone());
+            Ok(pool.id)
+        }
+
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    /// Holds a library for every ID; library 3 is a no-template control.
+    struct NtcLibraries;
+
+    #[async_trait]
+    impl LibraryRepository for NtcLibraries {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(ids
+                .iter()
+                .map(|&id| {
+                    let mut library = Library::new(
+                        id,
+                        format!("LIB{}", id),
+                        Barcode::new(format!("LIB-{}", id)).unwrap(),
+                        id,
+                        1,
+                        LibraryDesign::Wgs,
+                        LibraryType::PairedEnd,
+                        "ILLUMINA".to_string(),
+                        "tech".to_string(),
+                    );
+                    if id == 3 {
+                        library.control_type = Some(ControlType::Ntc);
+                    }
+                    library
+                })
+                .collect())
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    type TestService = RunService<InMemoryRuns, InMemorySequencers, InMemoryPools>;
+
+    fn pool(id: EntityId, qc_status: QcStatus) -> Pool {
+        let mut pool = Pool::new(
+            id,
+            format!("POOL{}", id),
+            Barcode::new(format!("POOL-{}", id)).unwrap(),
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        pool.elements.push(PoolElement {
+            library_aliquot_id: id,
+            library_id: id,
+            volume: None,
+            proportion: None,
+        });
+        pool.set_qc_status(qc_status);
+        pool
+    }
+
+    fn service() -> (TestService, Arc<InMemorySequencers>, Arc<InMemoryPools>) {
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq X".to_string(), 2);
+        model.container_models = vec!["10B Flow Cell".to_string()];
+        model.max_read_length = Some(150);
+        model.seconds_per_cycle = Some(600);
+
+        let sequencers = Arc::new(InMemorySequencers::default());
+        for (id, name, status) in [
+            (1, "NovaSeqX01", SequencerStatus::Available),
+            (2, "NovaSeqX02", SequencerStatus::Maintenance),
+        ] {
+            let mut sequencer = Sequencer::new(id, name.to_string(), model.clone());
+            sequencer.status = status;
+            sequencers.sequencers.lock().unwrap().insert(id, sequencer);
+        }
+
+        let pools = Arc::new(InMemoryPools::default());
+        for pool in [pool(1, QcStatus::Passed), pool(2, QcStatus::Failed)] {
+            pools.pools.lock().unwrap().insert(pool.id, pool);
+        }
+
+        let service = RunService::new(
+            Arc::new(InMemoryRuns::default()),
+            sequencers.clone(),
+            pools.clone(),
+        );
+        (service, sequencers, pools)
+    }
+
+    fn create(name: &str, sequencer_id: EntityId) -> CreateRunRequest {
+        CreateRunRequest {
+            name: name.to_string(),
+            alias: None,
+            sequencer_id,
+            container_barcode: Some("FC123".to_string()),
+            container_model: Some("10B Flow Cell".to_string()),
+            chemistry: None,
+            read_length: Some("2x150".to_string()),
+            description: None,
+        }
+    }
+
+    fn load(pool_id: EntityId) -> AssignPoolRequest {
+        AssignPoolRequest {
+            pool_id,
+            loading_concentration: 180.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_run_checks_sequencer_and_model() {
+        let (service, _, _) = service();
+
+        let run = service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        assert_eq!(run.lanes.len(), 2);
+        assert_eq!(run.status, "unknown");
+
+        assert!(matches!(
+            service.create_run(create("RUN2", 2), "tech").await,
+            Err(DomainError::Run(RunError::InvalidSequencer(_)))
+        ));
+        let mut too_long = create("RUN3", 1);
+        too_long.read_length = Some("2x250".to_string());
+        assert!(matches!(
+            service.create_run(too_long, "tech").await,
+            Err(DomainError::Run(RunError::InvalidParameters(_)))
+        ));
+        let mut unchecked = create("RUN4", 1);
+        unchecked.container_model = None;
+        assert!(matches!(
+            service.create_run(unchecked, "tech").await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service.create_run(create("RUN1", 1), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_holds_sequencer_and_marks_pools_sequenced() {
+        let (service, sequencers, pools) = service();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+
+        assert!(matches!(
+            service.assign_pool(1, 1, load(2), "tech").await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service.assign_pool(1, 3, load(1), "tech").await,
+            Err(DomainError::NotFound { .. })
+        ));
+        let run = service.assign_pool(1, 1, load(1), "tech").await.unwrap();
+        assert_eq!(run.lanes[0].pool_id, Some(1));
+
+        assert!(matches!(
+            service.update_status(1, "completed", "tech").await,
+            Err(DomainError::InvalidStateTransition { .. })
+        ));
+        service.update_status(1, "running", "tech").await.unwrap();
+        assert!(!sequencers.find_by_id(1).await.unwrap().unwrap().can_run());
+        assert!(matches!(
+            service.create_run(create("RUN2", 1), "tech").await,
+            Err(DomainError::Run(RunError::InvalidSequencer(_)))
+        ));
+
+        let run = service.update_status(1, "completed", "tech").await.unwrap();
+        assert_eq!(run.status, "completed");
+        assert!(run.completed_at.is_some());
+        assert!(sequencers.find_by_id(1).await.unwrap().unwrap().can_run());
+        assert!(pools.find_by_id(1).await.unwrap().unwrap().sequenced);
+        assert!(matches!(
+            service.assign_pool(1, 2, load(1), "tech").await,
+            Err(DomainError::Run(RunError::AlreadyComplete(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_paused_run_keeps_sequencer_until_resumed_or_failed() {
+        let (service, sequencers, _) = service();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        service.update_status(1, "running", "tech").await.unwrap();
+
+        let pause = PauseRunRequest {
+            cause: "Power blip".to_string(),
+            flagged_lanes: vec![2],
+        };
+        let run = service.pause_run(1, pause.clone(), "tech").await.unwrap();
+        assert_eq!(run.status, "paused");
+        assert_eq!(run.interruptions[0].flagged_lanes, vec![2]);
+        assert!(!sequencers.find_by_id(1).await.unwrap().unwrap().can_run());
+        assert!(matches!(
+            service.update_status(1, "completed", "tech").await,
+            Err(DomainError::InvalidStateTransition { .. })
+        ));
+
+        let resume = ResumeRunRequest {
+            flagged_lanes: vec![1],
+        };
+        let run = service.resume_run(1, resume, "tech").await.unwrap();
+        assert_eq!(run.status, "running");
+        assert_eq!(run.interruptions[0].flagged_lanes, vec![1, 2]);
+        assert!(run.interruptions[0].ended_at.is_some());
+
+        service.pause_run(1, pause, "tech").await.unwrap();
+        let run = service.update_status(1, "failed", "tech").await.unwrap();
+        assert_eq!(run.interruptions.len(), 2);
+        assert!(sequencers.find_by_id(1).await.unwrap().unwrap().can_run());
+    }
+
+    #[tokio::test]
+    async fn test_run_starts_only_with_its_required_controls() {
+        let (service, sequencers, pools) = service();
+        let service = service.with_controls(
+            ControlPolicy::new().with_run_control(ControlType::Ntc),
+            Arc::new(NtcLibraries),
+        );
+        pools.save(&pool(3, QcStatus::Passed)).await.unwrap();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        service.assign_pool(1, 1, load(1), "tech").await.unwrap();
+
+        let refused = service.update_status(1, "running", "tech").await;
+        assert!(matches!(refused, Err(DomainError::Validation(m)) if m.ends_with("controls: ntc")));
+        assert!(sequencers.find_by_id(1).await.unwrap().unwrap().can_run());
+
+        service.assign_pool(1, 2, load(3), "tech").await.unwrap();
+        let run = service.update_status(1, "running", "tech").await.unwrap();
+        assert_eq!(run.status, "running");
+    }
+
+    #[tokio::test]
+    async fn test_start_estimates_completion_and_progress_refines_it() {
+        let (service, _, _) = service();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        let progress = |cycles_completed| RunProgressRequest { cycles_completed };
+
+        assert!(matches!(
+            service.record_progress(1, progress(10)).await,
+            Err(DomainError::Run(RunError::InvalidParameters(_)))
+        ));
+
+        let run = service.update_status(1, "running", "tech").await.unwrap();
+        assert_eq!(run.total_cycles, Some(300));
+        assert_eq!(
+            run.estimated_completion,
+            run.started_at.map(|t| t + chrono::Duration::hours(50))
+        );
+
+        assert!(service.record_progress(1, progress(301)).await.is_err());
+        let run = service.record_progress(1, progress(30)).await.unwrap();
+        assert_eq!(run.cycles_completed, Some(30));
+        // Far faster than the model's 10 minutes a cycle so far
+        assert!(run.estimated_completion < run.started_at.map(|t| t + chrono::Duration::hours(1)));
+    }
+
+    #[tokio::test]
+    async fn test_load_check_blocks_wrong_pool_and_flow_cell() {
+        let (service, _, _) = service();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        service.assign_pool(1, 1, load(1), "tech").await.unwrap();
+        let check = |container: &str, pool: &str| LoadCheckRequest {
+            container_barcode: container.to_string(),
+            pools: vec![LoadCheckScan {
+                pool_barcode: pool.to_string(),
+                partition: Some(1),
+            }],
+        };
+
+        let checked = service
+            .check_load(1, check("FC123", " POOL-1 "), "tech")
+            .await
+            .unwrap();
+        assert_eq!(checked.pool_ids, vec![1]);
+
+        let Err(DomainError::Run(RunError::LoadMismatch { mismatches, .. })) =
+            service.check_load(1, check("FC999", "POOL-2"), "tech").await
+        else {
+            panic!("expected a mismatch");
+        };
+        assert_eq!(mismatches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_runs_filters_by_status_and_sequencer() {
+        let (service, _, _) = service();
+        service.create_run(create("RUN1", 1), "tech").await.unwrap();
+        service.create_run(create("RUN2", 1), "tech").await.unwrap();
+        service.update_status(2, "running", "tech").await.unwrap();
+        service.update_status(2, "failed", "tech").await.unwrap();
+
+        let failed = service
+            .list_runs(Some("failed"), Some(1), None, None)
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].name, "RUN2");
+        assert_eq!(
+            service
+                .list_runs(None, Some(1), None, None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(service
+            .list_runs(None, Some(2), None, None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            service.list_runs(Some("done"), None, None, None).await,
+            Err(DomainError::Validation(_))
+        ));
+    }
+}
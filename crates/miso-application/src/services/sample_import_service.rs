@@ -0,0 +1,673 @@
+//! Sample import service for manifests of new samples.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use miso_domain::entities::{
+    DetailedSampleData, EntityId, PlainSampleData, Project, Role, Sample, SampleClass,
+    SampleDetails,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::plugins::{DomainEvent, Operation};
+use miso_domain::repositories::{NewSample, ProjectRepository, SampleRepository};
+use miso_domain::services::BarcodeValidator;
+use miso_domain::value_objects::Barcode;
+use tracing::{info, instrument};
+
+use crate::access::{ProjectAccess, Requester};
+use crate::audit::AuditTrail;
+use crate::dto::{ImportSamplesRequest, SampleImportResponse, SampleImportRowResult};
+use crate::importers::{parse_sample_csv, SampleCsvRow};
+use crate::plugins::PluginRegistry;
+
+/// Service that creates samples from a CSV or TSV manifest.
+pub struct SampleImportService<S, J>
+where
+    S: SampleRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    samples: Arc<S>,
+    projects: Arc<J>,
+    barcode_validator: BarcodeValidator,
+    plugins: Arc<PluginRegistry>,
+    audit: AuditTrail,
+    access: ProjectAccess,
+}
+
+/// A parsed manifest and what is already known about it.
+struct Manifest {
+    rows: Vec<SampleCsvRow>,
+    /// Each row's barcode, given or generated
+    barcodes: Vec<String>,
+    /// Existing samples with barcodes the manifest mentions
+    existing: HashMap<String, Sample>,
+}
+
+impl<S, J> SampleImportService<S, J>
+where
+    S: SampleRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    /// Creates a new import service.
+    pub fn new(samples: Arc<S>, projects: Arc<J>) -> Self {
+        Self {
+            samples,
+            projects,
+            barcode_validator: BarcodeValidator::new(),
+            plugins: Arc::new(PluginRegistry::new()),
+            audit: AuditTrail::default(),
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Runs site plugins' naming and validation hooks on imported samples.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Records imported samples in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Keeps imports to the projects the importer is a member of.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Imports a sample manifest.
+    ///
+    /// Every row is checked before anything is written: barcodes must
+    /// follow the sample barcode rules and be unused, projects must exist,
+    /// be reachable by the importer and be unlocked (unless an admin
+    /// imports), and detailed samples need a parent of the class their own
+    /// class descends from, either an existing sample in the same project
+    /// or an earlier row. Rows without a barcode are given one. If any row
+    /// has errors, or on a dry run, nothing is created and the per-row
+    /// results say what would happen; otherwise all samples are created in
+    /// one transaction.
+    #[instrument(skip(self, request), fields(dry_run = request.dry_run))]
+    pub async fn import(
+        &self,
+        request: ImportSamplesRequest,
+        imported_by: &str,
+        role: Role,
+    ) -> Result<SampleImportResponse, DomainError> {
+        let rows = parse_sample_csv(&request.contents)?;
+        if rows.is_empty() {
+            return Err(DomainError::Validation(
+                "The sample file lists no samples".to_string(),
+            ));
+        }
+
+        let mut projects: HashMap<String, Option<Project>> = HashMap::new();
+        for row in &rows {
+            if !projects.contains_key(&row.project) {
+                let project = self.projects.find_by_code(&row.project).await?;
+                projects.insert(row.project.clone(), project);
+            }
+        }
+        let requester = Requester::new(imported_by, role);
+        let mut refused: HashMap<EntityId, String> = HashMap::new();
+        for project in projects.values().flatten() {
+            if let Err(e) = self.access.check(project.id, Some(requester)).await {
+                refused.insert(project.id, e.to_string());
+            }
+        }
+
+        // Rows without a barcode are given one up front, so that the
+        // generated barcodes are checked for clashes like any other
+        let barcodes: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                row.barcode.clone().unwrap_or_else(|| {
+                    self.barcode_validator.generate_barcode("SAM").into_inner()
+                })
+            })
+            .collect();
+        let lookup: Vec<String> = barcodes
+            .iter()
+            .chain(rows.iter().filter_map(|row| row.parent.as_ref()))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let existing: HashMap<String, Sample> = self
+            .samples
+            .find_by_barcodes(&lookup)
+            .await?
+            .into_iter()
+            .map(|s| (s.barcode.to_string(), s))
+            .collect();
+
+        let manifest = Manifest {
+            rows,
+            barcodes,
+            existing,
+        };
+
+        let mut results = Vec::with_capacity(manifest.rows.len());
+        let mut batch: Vec<NewSample> = Vec::with_capacity(manifest.rows.len());
+        for (index, row) in manifest.rows.iter().enumerate() {
+            let barcode = &manifest.barcodes[index];
+            let mut errors = Vec::new();
+
+            match self.barcode_validator.validate_sample(barcode) {
+                Err(e) => errors.push(e.to_string()),
+                Ok(_) if manifest.existing.contains_key(barcode) => {
+                    errors.push(format!("Barcode {} is already used", barcode))
+                }
+                Ok(_) if manifest.barcodes[..index].contains(barcode) => {
+                    errors.push(format!("Barcode {} appears more than once", barcode))
+                }
+                Ok(_) => {}
+            }
+
+            let project = projects.get(&row.project).and_then(Option::as_ref);
+            match project {
+                None => errors.push(format!("Project {} does not exist", row.project)),
+                Some(project) => {
+                    if let Some(e) = refused.get(&project.id) {
+                        errors.push(e.clone());
+                    }
+                    if let Err(e) = project.check_unlocked(role) {
+                        errors.push(e.to_string());
+                    }
+                }
+            }
+
+            let mut parent_index = None;
+            let mut sample = match build_sample(&manifest, index, &batch) {
+                Ok((sample, parent)) => {
+                    parent_index = parent;
+                    Some(sample)
+                }
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            };
+
+            if let (Some(sample), Some(project)) = (&mut sample, project) {
+                sample.barcode = Barcode::new_unchecked(barcode.clone());
+                sample.project_id = project.id;
+                sample.created_by = imported_by.to_string();
+                if let Some(parent) = &row.parent {
+                    let parent_project = match parent_index {
+                        Some(i) => projects
+                            .get(&manifest.rows[i].project)
+                            .and_then(Option::as_ref)
+                            .map(|p| p.id),
+                        None => manifest.existing.get(parent).map(|p| p.project_id),
+                    };
+                    if parent_project.is_some_and(|p| p != project.id) {
+                        errors.push(format!("Parent {} is in another project", parent));
+                    }
+                }
+                match self.plugins.sample_name(sample).await {
+                    Ok(Some(name)) => sample.name = name,
+                    Ok(None) => {}
+                    Err(e) => errors.push(e.to_string()),
+                }
+                if let Err(e) = self
+                    .plugins
+                    .validate_sample(Operation::Create, sample)
+                    .await
+                {
+                    errors.push(e.to_string());
+                }
+            }
+
+            results.push(SampleImportRowResult {
+                line: row.line,
+                name: sample.as_ref().map_or_else(|| row.name.clone(), |s| s.name.clone()),
+                barcode: barcode.clone(),
+                errors,
+            });
+            // Rows that couldn't be built still hold their place, so that
+            // later rows' parents are found by position
+            batch.push(NewSample {
+                sample: sample.unwrap_or_else(|| placeholder(row, barcode)),
+                parent_index,
+            });
+        }
+
+        let failed = results.iter().filter(|r| !r.errors.is_empty()).count();
+        if failed > 0 || request.dry_run {
+            info!(
+                "{} import of {} samples: {} row(s) failed",
+                if request.dry_run { "Checked" } else { "Rejected" },
+                results.len(),
+                failed
+            );
+            return Ok(SampleImportResponse {
+                dry_run: request.dry_run,
+                applied: false,
+                results,
+                samples: Vec::new(),
+            });
+        }
+
+        let ids = self.samples.insert_all(&batch).await?;
+        let mut saved = self.samples.find_by_ids(&ids).await?;
+        saved.sort_by_key(|s| ids.iter().position(|id| *id == s.id));
+        for sample in &saved {
+            self.audit
+                .created("Sample", sample.id, sample, imported_by)
+                .await?;
+        }
+
+        info!("Imported {} samples", saved.len());
+
+        for sample in &saved {
+            self.plugins
+                .publish(DomainEvent::SampleCreated(sample.clone()))
+                .await;
+        }
+
+        Ok(SampleImportResponse {
+            dry_run: false,
+            applied: true,
+            results,
+            samples: saved.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+/// Builds the sample a manifest row describes, checking its class, its
+/// parent and the details its class needs, or explains what is wrong.
+/// Returns the position of its parent if that is an earlier row.
+///
+/// The sample is left without its barcode and project, which are checked
+/// separately.
+fn build_sample(
+    manifest: &Manifest,
+    index: usize,
+    batch: &[NewSample],
+) -> Result<(Sample, Option<usize>), String> {
+    let row = &manifest.rows[index];
+    if row.name.is_empty() {
+        return Err("A sample needs a name".to_string());
+    }
+    let class = match &row.class {
+        Some(class) => {
+            parse_sample_class(class).ok_or_else(|| format!("Unknown sample class {}", class))?
+        }
+        None => SampleClass::Plain,
+    };
+
+    let mut sample = Sample::new_plain(
+        0,
+        row.name.clone(),
+        Barcode::new_unchecked(String::new()),
+        0,
+        String::new(),
+        String::new(),
+    );
+    sample.description = row.description.clone();
+
+    let Some(expected) = class.expected_parent() else {
+        if row.parent.is_some() {
+            return Err(format!("A {} has no parent", class));
+        }
+        match class {
+            SampleClass::Identity => {
+                if row.external_name.is_none() {
+                    return Err("An identity needs an external name".to_string());
+                }
+                sample.details = SampleDetails::Detailed(detailed(row, class, None));
+            }
+            _ => {
+                let scientific_name = row
+                    .scientific_name
+                    .clone()
+                    .ok_or_else(|| "A plain sample needs a scientific name".to_string())?;
+                sample.details = SampleDetails::Plain(PlainSampleData {
+                    scientific_name,
+                    sample_type: None,
+                });
+            }
+        }
+        return Ok((sample, None));
+    };
+
+    let parent = row
+        .parent
+        .as_deref()
+        .ok_or_else(|| format!("A {} needs a {} parent", class, expected))?;
+    let (parent_class, parent_id, parent_index) =
+        match manifest.barcodes[..index].iter().position(|b| b == parent) {
+            Some(i) => (row_class(&manifest.rows[i], &batch[i]), None, Some(i)),
+            None => {
+                let existing = manifest.existing.get(parent).ok_or_else(|| {
+                    format!("Parent {} is not an existing sample or an earlier row", parent)
+                })?;
+                (existing.sample_class(), Some(existing.id), None)
+            }
+        };
+    if parent_class != expected {
+        return Err(format!(
+            "Parent {} is a {}, but a {} needs a {} parent",
+            parent, parent_class, class, expected
+        ));
+    }
+
+    sample.details = SampleDetails::Detailed(detailed(row, class, parent_id));
+    Ok((sample, parent_index))
+}
+
+/// Parses a sample class as written in a manifest, ignoring case, spaces
+/// and underscores.
+fn parse_sample_class(class: &str) -> Option<SampleClass> {
+    let key: String = class
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    Some(match key.as_str() {
+        "plain" | "plainsample" => SampleClass::Plain,
+        "identity" => SampleClass::Identity,
+        "tissue" => SampleClass::Tissue,
+        "tissueprocessing" => SampleClass::TissueProcessing,
+        "stock" => SampleClass::Stock,
+        "aliquot" => SampleClass::Aliquot,
+        "singlecell" => SampleClass::SingleCell,
+        "wholetranscriptome" => SampleClass::WholeTranscriptome,
+        _ => return None,
+    })
+}
+
+/// The class a row's sample has, as far as its later rows are concerned.
+fn row_class(row: &SampleCsvRow, new: &NewSample) -> SampleClass {
+    row.class
+        .as_deref()
+        .and_then(parse_sample_class)
+        .unwrap_or_else(|| new.sample.sample_class())
+}
+
+/// Detailed sample data from a row.
+fn detailed(row: &SampleCsvRow, class: SampleClass, parent_id: Option<EntityId>) -> DetailedSampleData {
+    DetailedSampleData {
+        parent_id,
+        sample_class: class,
+        external_name: row.external_name.clone(),
+        tissue_origin: row.tissue_origin.clone(),
+        tissue_type: row.tissue_type.clone(),
+        time_point: None,
+        group_id: None,
+        group_description: None,
+        passage: None,
+        analyte_type: row.analyte_type.clone(),
+        purpose: None,
+        pooled: false,
+    }
+}
+
+/// Stands in for a row that couldn't be built; never written.
+fn placeholder(row: &SampleCsvRow, barcode: &str) -> Sample {
+    Sample::new_plain(
+        0,
+        row.name.clone(),
+        Barcode::new_unchecked(barcode.to_string()),
+        0,
+        String::new(),
+        String::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::repositories::{QueryOptions, VersionConflict};
+    use miso_domain::value_objects::QcStatus;
+
+    use super::*;
+    use crate::test_support::Members;
+
+    #[derive(Default)]
+    struct InMemorySamples {
+        samples: Mutex<Vec<Sample>>,
+    }
+
+    #[async_trait]
+    impl SampleRepository for InMemorySamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, barcodes: &[String]) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(samples
+                .iter()
+                .filter(|s| barcodes.contains(&s.barcode.to_string()))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(samples.iter().filter(|s| ids.contains(&s.id)).cloned().collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, batch: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            let mut samples = self.samples.lock().unwrap();
+            let mut ids: Vec<EntityId> = Vec::new();
+            for new in batch {
+                let mut sample = new.sample.clone();
+                sample.id = samples.len() as EntityId + 1;
+                if let (Some(index), SampleDetails::Detailed(details)) =
+                    (new.parent_index, &mut sample.details)
+                {
+                    details.parent_id = Some(ids[index]);
+                }
+                ids.push(sample.id);
+                samples.push(sample);
+            }
+            Ok(ids)
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct TwoProjects;
+
+    #[async_trait]
+    impl ProjectRepository for TwoProjects {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_code(&self, code: &str) -> Result<Option<Project>, DomainError> {
+            Ok(match code {
+                "PROJ1" => Some(Project::new(1, code.to_string(), "One".to_string(), "admin".to_string())),
+                "PROJ2" => Some(Project::new(2, code.to_string(), "Two".to_string(), "admin".to_string())),
+                _ => None,
+            })
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// A service whose store already holds identity SAM-ID1 in PROJ1.
+    fn service() -> SampleImportService<InMemorySamples, TwoProjects> {
+        let samples = InMemorySamples::default();
+        samples.samples.lock().unwrap().push(Sample::new_identity(
+            1,
+            "Patient 1".to_string(),
+            Barcode::new_unchecked("SAM-ID1".to_string()),
+            1,
+            "P-0001".to_string(),
+            "admin".to_string(),
+        ));
+        SampleImportService::new(Arc::new(samples), Arc::new(TwoProjects))
+    }
+
+    fn request(contents: &str, dry_run: bool) -> ImportSamplesRequest {
+        ImportSamplesRequest {
+            contents: contents.to_string(),
+            dry_run,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_reports_every_bad_row_and_writes_nothing() {
+        let service = service();
+        let contents = "Name,Project,Barcode,Class,Parent,Scientific Name\n\
+            Soil,PROJ1,SAM-SOIL1,,,Soil metagenome\n\
+            Biopsy,PROJ1,SAM-T1,Tissue,SAM-ID1,\n\
+            Curls,PROJ9,SAM-C1,Tissue Processing,SAM-T1,\n\
+            Extract,PROJ1,SAM-X1,Stock,SAM-T1,\n\
+            Other biopsy,PROJ2,SAM-T2,Tissue,SAM-ID1,\n\
+            Copy,PROJ1,SAM-ID1,,,Soil metagenome\n\
+            Badly named,PROJ1,XYZ-1,,,Soil metagenome\n";
+
+        let response = service
+            .import(request(contents, false), "tech", Role::Technician)
+            .await
+            .unwrap();
+
+        assert!(!response.applied);
+        assert!(response.samples.is_empty());
+        let errors: Vec<&[String]> = response.results.iter().map(|r| r.errors.as_slice()).collect();
+        assert!(errors[0].is_empty());
+        assert!(errors[1].is_empty());
+        assert_eq!(errors[2], ["Project PROJ9 does not exist"]);
+        assert_eq!(
+            errors[3],
+            ["Parent SAM-T1 is a Tissue, but a Stock needs a Tissue Processing parent"]
+        );
+        assert_eq!(errors[4], ["Parent SAM-ID1 is in another project"]);
+        assert_eq!(errors[5], ["Barcode SAM-ID1 is already used"]);
+        assert_eq!(errors[6].len(), 1);
+        assert_eq!(service.samples.samples.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_creates_hierarchy_in_one_batch() {
+        let service = service();
+        let contents = "Name\tProject\tBarcode\tClass\tParent\tExternal Name\n\
+            Patient 2\tPROJ1\tSAM-ID2\tIdentity\t\tP-0002\n\
+            Biopsy\tPROJ1\t\tTissue\tSAM-ID2\t\n\
+            Second biopsy\tPROJ1\t\tTissue\tSAM-ID1\t\n";
+
+        let checked = service
+            .import(request(contents, true), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(checked.dry_run);
+        assert!(!checked.applied);
+        assert!(checked.results.iter().all(|r| r.errors.is_empty()));
+        assert!(checked.results[1].barcode.starts_with("SAM-"));
+        assert_eq!(service.samples.samples.lock().unwrap().len(), 1);
+
+        let imported = service
+            .import(request(contents, false), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(imported.applied);
+        assert_eq!(imported.samples.len(), 3);
+
+        let samples = service.samples.samples.lock().unwrap();
+        assert_eq!(samples[1].sample_class(), SampleClass::Identity);
+        assert_eq!(samples[2].parent_id(), Some(samples[1].id));
+        assert_eq!(samples[3].parent_id(), Some(1));
+        assert_eq!(samples[3].created_by, "tech");
+    }
+
+    #[tokio::test]
+    async fn test_import_reaches_members_projects_only() {
+        let service = service()
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let contents = "Name,Project,Barcode,Class,Parent,Scientific Name\n\
+            Soil,PROJ1,SAM-SOIL1,,,Soil metagenome\n\
+            Water,PROJ2,SAM-WATER1,,,Freshwater metagenome\n";
+
+        let response = service
+            .import(request(contents, false), "member", Role::Technician)
+            .await
+            .unwrap();
+        assert!(!response.applied);
+        assert!(response.results[0].errors.is_empty());
+        assert_eq!(
+            response.results[1].errors,
+            ["Permission denied: member is not a member of project 2"]
+        );
+
+        let response = service
+            .import(request(contents, false), "admin", Role::Admin)
+            .await
+            .unwrap();
+        assert!(response.applied);
+    }
+}
@@ -0,0 +1,425 @@
+//! Sample list service and the projection that keeps it up to date.
+//!
+//! The sample list is a read model: one denormalized row per sample with
+//! its project, latest QC measurement and box position, and materialized
+//! counts of samples by project and QC status. [`SampleListProjection`]
+//! subscribes to domain events and applies each change to the rows it
+//! touches, so listing never joins the tables the rows are drawn from.
+//! Changes made without events, such as box imports, are picked up by
+//! rebuilding.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use miso_domain::entities::{
+    EntityId, ListedLocation, QcTarget, Sample, SampleListFilter, SampleListRow, StorableType,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::plugins::{DomainEvent, EventSubscriber};
+use miso_domain::repositories::{ProjectRepository, QueryOptions, SampleListRepository};
+use tracing::{debug, info, instrument};
+
+use super::qc_service::parse_qc_status;
+use crate::access::{ProjectAccess, Requester};
+use crate::dto::{
+    SampleListCountResponse, SampleListItem, SampleListQuery, SampleListRebuildReport,
+};
+
+/// Rows listed when no limit is given.
+const DEFAULT_PAGE_SIZE: u64 = 100;
+/// Most rows listed at once.
+const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Service for reading and rebuilding the sample list.
+pub struct SampleListService<L: SampleListRepository + ?Sized> {
+    rows: Arc<L>,
+    access: ProjectAccess,
+}
+
+impl<L: SampleListRepository + ?Sized> SampleListService<L> {
+    /// Creates a new sample list service.
+    pub fn new(rows: Arc<L>) -> Self {
+        Self {
+            rows,
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Limits the list and counts to the projects the requester is a
+    /// member of.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Lists samples matching a query, by name unless sorted otherwise.
+    #[instrument(skip(self))]
+    pub async fn list(
+        &self,
+        query: SampleListQuery,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<SampleListItem>, DomainError> {
+        let project_ids = self.reachable(query.project_id, requester).await?;
+        let filter = SampleListFilter {
+            project_id: query.project_id,
+            project_ids,
+            qc_status: query.qc_status.as_deref().map(parse_qc_status).transpose()?,
+            search: query.search.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            include_archived: query.include_archived,
+        };
+        let options = QueryOptions::new()
+            .limit(query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE))
+            .offset(query.offset.unwrap_or(0))
+            .sort_by(query.sort_by.as_deref().unwrap_or("name"));
+        let options = if query.descending {
+            options.descending()
+        } else {
+            options.ascending()
+        };
+
+        let rows = self.rows.list(&filter, options).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the counts of unarchived samples by QC status, of one
+    /// project or all.
+    #[instrument(skip(self))]
+    pub async fn counts(
+        &self,
+        project_id: Option<EntityId>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<SampleListCountResponse>, DomainError> {
+        let project_ids = self.reachable(project_id, requester).await?;
+        let counts = self.rows.counts(project_id).await?;
+        Ok(counts
+            .into_iter()
+            .filter(|c| project_ids.as_ref().is_none_or(|p| p.contains(&c.project_id)))
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Checks the requester may see the project asked for, if any, and
+    /// returns the projects they may see, or `None` for every project.
+    async fn reachable(
+        &self,
+        project_id: Option<EntityId>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Option<Vec<EntityId>>, DomainError> {
+        if let Some(project_id) = project_id {
+            self.access.check(project_id, requester).await?;
+        }
+        let projects = self.access.projects(requester).await?;
+        Ok(projects.map(|p| {
+            let mut ids: Vec<EntityId> = p.into_iter().collect();
+            ids.sort_unstable();
+            ids
+        }))
+    }
+
+    /// Rebuilds every row and count from the tables they are drawn from.
+    #[instrument(skip(self))]
+    pub async fn rebuild(&self) -> Result<SampleListRebuildReport, DomainError> {
+        let started_at = Utc::now();
+        let rows = self.rows.rebuild().await?;
+        info!("Rebuilt the sample list with {} rows", rows);
+
+        Ok(SampleListRebuildReport {
+            started_at,
+            completed_at: Utc::now(),
+            rows,
+        })
+    }
+}
+
+/// Event subscriber that applies changes to the sample list.
+pub struct SampleListProjection {
+    rows: Arc<dyn SampleListRepository>,
+    projects: Arc<dyn ProjectRepository>,
+}
+
+impl SampleListProjection {
+    /// Creates a projection writing to `rows`, looking projects up in
+    /// `projects`.
+    pub fn new(rows: Arc<dyn SampleListRepository>, projects: Arc<dyn ProjectRepository>) -> Self {
+        Self { rows, projects }
+    }
+
+    async fn sample_saved(&self, sample: &Sample) -> Result<(), DomainError> {
+        let project = self
+            .projects
+            .find_by_id(sample.project_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: sample.project_id.to_string(),
+            })?;
+        let row = match self.rows.find(sample.id).await? {
+            Some(mut row) => {
+                row.refresh(sample, &project);
+                row
+            }
+            None => SampleListRow::new(sample, &project),
+        };
+        self.rows.save(&row).await
+    }
+
+    /// Applies a change to a sample's row. Samples without a row were
+    /// never listed, and wait for a rebuild.
+    async fn update_row(
+        &self,
+        sample_id: EntityId,
+        change: impl FnOnce(&mut SampleListRow) -> bool,
+    ) -> Result<(), DomainError> {
+        let Some(mut row) = self.rows.find(sample_id).await? else {
+            debug!("Sample {} has no sample list row", sample_id);
+            return Ok(());
+        };
+        if change(&mut row) {
+            self.rows.save(&row).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for SampleListProjection {
+    async fn on_event(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::SampleCreated(sample) | DomainEvent::SampleUpdated(sample) => {
+                self.sample_saved(sample).await
+            }
+            DomainEvent::SampleDeleted(id) => self.rows.delete(*id).await,
+            DomainEvent::ProjectUpdated(project) => {
+                self.rows
+                    .rename_project(project.id, &project.code, &project.name)
+                    .await
+            }
+            DomainEvent::QcRecorded(record) if record.target == QcTarget::Sample => {
+                self.update_row(record.entity_id, |row| row.record_qc(record))
+                    .await
+            }
+            DomainEvent::ItemStored {
+                item,
+                box_id,
+                box_name,
+                position,
+            } if item.item_type == StorableType::Sample => {
+                let location = ListedLocation {
+                    box_id: *box_id,
+                    box_name: box_name.clone(),
+                    position: position.clone(),
+                };
+                self.update_row(item.item_id, |row| {
+                    row.location.replace(location.clone()) != Some(location)
+                })
+                .await
+            }
+            DomainEvent::ItemRemoved(item) if item.item_type == StorableType::Sample => {
+                self.update_row(item.item_id, |row| row.location.take().is_some())
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use miso_domain::entities::{Project, QcRecord, Role, SampleListCount, StorableItem};
+    use miso_domain::value_objects::{Barcode, QcResult, QcStatus, QcTestType};
+
+    use super::*;
+    use crate::test_support::{Members, OneProject};
+
+    /// Rows kept in memory, with counts worked out on demand.
+    #[derive(Default)]
+    struct InMemoryRows {
+        rows: Mutex<BTreeMap<EntityId, SampleListRow>>,
+    }
+
+    #[async_trait]
+    impl SampleListRepository for InMemoryRows {
+        async fn find(&self, sample_id: EntityId) -> Result<Option<SampleListRow>, DomainError> {
+            Ok(self.rows.lock().unwrap().get(&sample_id).cloned())
+        }
+        async fn save(&self, row: &SampleListRow) -> Result<(), DomainError> {
+            self.rows.lock().unwrap().insert(row.sample_id, row.clone());
+            Ok(())
+        }
+        async fn delete(&self, sample_id: EntityId) -> Result<(), DomainError> {
+            self.rows.lock().unwrap().remove(&sample_id);
+            Ok(())
+        }
+        async fn rename_project(
+            &self,
+            project_id: EntityId,
+            code: &str,
+            name: &str,
+        ) -> Result<(), DomainError> {
+            for row in self.rows.lock().unwrap().values_mut() {
+                if row.project_id == project_id {
+                    row.project_code = code.to_string();
+                    row.project_name = name.to_string();
+                }
+            }
+            Ok(())
+        }
+        async fn list(
+            &self,
+            filter: &SampleListFilter,
+            _: QueryOptions,
+        ) -> Result<Vec<SampleListRow>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| {
+                    filter.project_ids.as_ref().is_none_or(|p| p.contains(&r.project_id))
+                })
+                .cloned()
+                .collect())
+        }
+        async fn counts(
+            &self,
+            _: Option<EntityId>,
+        ) -> Result<Vec<SampleListCount>, DomainError> {
+            let mut counts: Vec<SampleListCount> = Vec::new();
+            for row in self.rows.lock().unwrap().values().filter(|r| !r.archived) {
+                match counts.iter_mut().find(|c| c.qc_status == row.qc_status) {
+                    Some(count) => count.count += 1,
+                    None => counts.push(SampleListCount {
+                        project_id: row.project_id,
+                        qc_status: row.qc_status,
+                        count: 1,
+                    }),
+                }
+            }
+            Ok(counts)
+        }
+        async fn rebuild(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn sample() -> Sample {
+        let mut sample = Sample::new_plain(
+            5,
+            "SAM5".to_string(),
+            Barcode::new("SAM-5").unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "tech".to_string(),
+        );
+        sample.qc_status = QcStatus::Ready;
+        sample
+    }
+
+    fn stored(box_id: EntityId, position: &str) -> DomainEvent {
+        DomainEvent::ItemStored {
+            item: StorableItem::new(StorableType::Sample, 5),
+            box_id,
+            box_name: format!("Box {}", box_id),
+            position: position.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_projection_follows_sample_qc_and_storage_events() {
+        let rows = Arc::new(InMemoryRows::default());
+        let projection = SampleListProjection::new(
+            rows.clone(),
+            Arc::new(OneProject::new(Project::new(
+                1,
+                "PROJ1".to_string(),
+                "One".to_string(),
+                "admin".to_string(),
+            ))),
+        );
+        let service = SampleListService::new(rows.clone());
+
+        // Events for samples without a row wait for a rebuild
+        projection.on_event(&stored(3, "A01")).await.unwrap();
+        assert!(rows.find(5).await.unwrap().is_none());
+
+        projection.on_event(&DomainEvent::SampleCreated(sample())).await.unwrap();
+        projection.on_event(&stored(3, "A01")).await.unwrap();
+        projection.on_event(&stored(4, "H12")).await.unwrap();
+        let mut record = QcRecord::new(
+            QcTarget::Sample,
+            5,
+            QcResult::failed(QcTestType::Qubit, Some(0.2), None, "tech", "Too dilute"),
+        );
+        record.id = 9;
+        projection.on_event(&DomainEvent::QcRecorded(record)).await.unwrap();
+        let mut failed = sample();
+        failed.qc_status = QcStatus::Failed;
+        projection.on_event(&DomainEvent::SampleUpdated(failed)).await.unwrap();
+
+        let listed = service.list(SampleListQuery::default(), None).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].project_code, "PROJ1");
+        assert_eq!(listed[0].qc_status, "Failed");
+        assert_eq!(listed[0].latest_qc.as_ref().map(|qc| qc.id), Some(9));
+        assert_eq!(listed[0].box_name.as_deref(), Some("Box 4"));
+        assert_eq!(listed[0].position.as_deref(), Some("H12"));
+
+        let counts = service.counts(None, None).await.unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!((counts[0].qc_status.as_str(), counts[0].count), ("Failed", 1));
+
+        let item = StorableItem::new(StorableType::Sample, 5);
+        projection.on_event(&DomainEvent::ItemRemoved(item)).await.unwrap();
+        assert!(rows.find(5).await.unwrap().unwrap().location.is_none());
+
+        projection.on_event(&DomainEvent::SampleDeleted(5)).await.unwrap();
+        assert!(service.list(SampleListQuery::default(), None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_unknown_qc_status() {
+        let service = SampleListService::new(Arc::new(InMemoryRows::default()));
+        let query = SampleListQuery {
+            qc_status: Some("great".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service.list(query, None).await,
+            Err(DomainError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_counts_reach_members_projects_only() {
+        let rows = Arc::new(InMemoryRows::default());
+        let project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        rows.save(&SampleListRow::new(&sample(), &project)).await.unwrap();
+        let service = SampleListService::new(rows)
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let member = Some(Requester::new("member", Role::Technician));
+        let outsider = Some(Requester::new("outsider", Role::Technician));
+        let of_project = SampleListQuery {
+            project_id: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(service.list(SampleListQuery::default(), member).await.unwrap().len(), 1);
+        assert_eq!(service.counts(None, member).await.unwrap().len(), 1);
+        for requester in [None, outsider] {
+            assert!(service.list(SampleListQuery::default(), requester).await.unwrap().is_empty());
+            assert!(service.counts(None, requester).await.unwrap().is_empty());
+            assert!(matches!(
+                service.list(of_project.clone(), requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+            assert!(matches!(
+                service.counts(Some(1), requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+        }
+    }
+}
@@ -1,37 +1,163 @@
 //! Sample service for sample operations.
 
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
-use miso_domain::entities::{PlainSampleData, Sample, SampleDetails};
+use chrono::Utc;
+use miso_domain::entities::{
+    check_attributes, AttributeDefinition, AttributeTarget, ChangeLogEntry, ControlType, EntityId,
+    PossibleDuplicate, Role, Sample, SampleClass, SampleComposition, SampleDetails,
+};
 use miso_domain::errors::DomainError;
-use miso_domain::repositories::{QueryOptions, SampleRepository};
-use miso_domain::services::BarcodeValidator;
-use tracing::{info, instrument};
+use miso_domain::plugins::{DomainEvent, Operation};
+use miso_domain::repositories::{
+    AttributeDefinitionRepository, ChangeLogRepository, ErasureRepository,
+    PossibleDuplicateRepository, QueryOptions, SampleCompositionRepository, SampleRepository,
+    SubprojectRepository,
+};
+use miso_domain::services::{BarcodeValidator, IdentityMatcher, QcTransitionPolicy};
+use tracing::{info, instrument, warn};
 
-use crate::dto::{CreatePlainSampleRequest, SampleResponse, SampleSummary, UpdateSampleRequest};
+use crate::audit::AuditTrail;
+use crate::dto::{
+    BulkUpdateRowResult, BulkUpdateRowStatus, BulkUpdateSamplesRequest, BulkUpdateSamplesResponse,
+    ChangeLogEntryResponse, CreateIdentityRequest, CreateIdentityResponse,
+    CreatePlainSampleRequest, CreatePooledSampleRequest, PossibleDuplicateResponse,
+    ResolveDuplicateRequest, SampleLineageResponse, SampleResponse, SampleSummary,
+    UpdateSampleRequest,
+};
+use crate::access::{ProjectAccess, Requester};
+use crate::locks::ProjectLocks;
+use crate::plugins::PluginRegistry;
+use crate::services::hash_external_names;
 
 /// Service for sample operations.
-pub struct SampleService<R: SampleRepository> {
+pub struct SampleService<R, C>
+where
+    R: SampleRepository + ?Sized,
+    C: ChangeLogRepository + ?Sized,
+{
     repository: Arc<R>,
+    change_log: Arc<C>,
     barcode_validator: BarcodeValidator,
+    attribute_definitions: Option<Arc<dyn AttributeDefinitionRepository>>,
+    possible_duplicates: Option<Arc<dyn PossibleDuplicateRepository>>,
+    erasures: Option<Arc<dyn ErasureRepository>>,
+    compositions: Option<Arc<dyn SampleCompositionRepository>>,
+    subprojects: Option<Arc<dyn SubprojectRepository>>,
+    plugins: Arc<PluginRegistry>,
+    audit: AuditTrail,
+    locks: ProjectLocks,
+    access: ProjectAccess,
 }
 
-impl<R: SampleRepository> SampleService<R> {
+impl<R, C> SampleService<R, C>
+where
+    R: SampleRepository + ?Sized,
+    C: ChangeLogRepository + ?Sized,
+{
     /// Creates a new sample service.
-    pub fn new(repository: Arc<R>) -> Self {
+    pub fn new(repository: Arc<R>, change_log: Arc<C>) -> Self {
         Self {
             repository,
+            change_log,
             barcode_validator: BarcodeValidator::new(),
+            attribute_definitions: None,
+            possible_duplicates: None,
+            erasures: None,
+            compositions: None,
+            subprojects: None,
+            plugins: Arc::new(PluginRegistry::new()),
+            audit: AuditTrail::default(),
+            locks: ProjectLocks::default(),
+            access: ProjectAccess::default(),
         }
     }
 
+    /// Checks custom attribute values against admin-defined attributes.
+    ///
+    /// Without definitions, samples cannot have custom attributes.
+    pub fn with_attribute_definitions(
+        mut self,
+        definitions: Arc<dyn AttributeDefinitionRepository>,
+    ) -> Self {
+        self.attribute_definitions = Some(definitions);
+        self
+    }
+
+    /// Keeps identities created despite looking like existing ones for
+    /// review.
+    ///
+    /// Without it, such identities are only logged.
+    pub fn with_possible_duplicates(
+        mut self,
+        possible_duplicates: Arc<dyn PossibleDuplicateRepository>,
+    ) -> Self {
+        self.possible_duplicates = Some(possible_duplicates);
+        self
+    }
+
+    /// Refuses identities with the external names of erased participants.
+    pub fn with_erasures(mut self, erasures: Arc<dyn ErasureRepository>) -> Self {
+        self.erasures = Some(erasures);
+        self
+    }
+
+    /// Records the identities pooled samples were made from.
+    ///
+    /// Without it, pooled samples can't be created and lineage stops at
+    /// them.
+    pub fn with_compositions(mut self, compositions: Arc<dyn SampleCompositionRepository>) -> Self {
+        self.compositions = Some(compositions);
+        self
+    }
+
+    /// Lets samples be assigned to subprojects of their projects.
+    ///
+    /// Without it, subproject assignments are refused.
+    pub fn with_subprojects(mut self, subprojects: Arc<dyn SubprojectRepository>) -> Self {
+        self.subprojects = Some(subprojects);
+        self
+    }
+
+    /// Runs site plugins' hooks on sample changes.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Records sample changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Refuses changes to samples of locked projects, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Keeps samples to the members of their projects.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
     /// Creates a new plain sample.
     #[instrument(skip(self))]
     pub async fn create_plain_sample(
         &self,
         request: CreatePlainSampleRequest,
         created_by: &str,
+        role: Role,
     ) -> Result<SampleResponse, DomainError> {
+        self.access
+            .check(request.project_id, Some(Requester::new(created_by, role)))
+            .await?;
+        self.locks.check(request.project_id, role).await?;
+
         // Generate a unique barcode
         let barcode = self.barcode_validator.generate_barcode("SAM");
 
@@ -44,7 +170,21 @@ impl<R: SampleRepository> SampleService<R> {
             });
         }
 
-        let sample = Sample::new_plain(
+        let attributes = normalize_attributes(request.attributes.unwrap_or_default());
+        let definitions = self.attribute_definitions(request.project_id).await?;
+        check_attributes(&definitions, &attributes)?;
+
+        let control_type = request
+            .control_type
+            .as_deref()
+            .map(parse_control_type)
+            .transpose()?
+            .flatten();
+
+        let subprojects = self.subproject_projects(request.subproject_id).await?;
+        check_subproject(request.project_id, request.subproject_id, &subprojects)?;
+
+        let mut sample = Sample::new_plain(
             0,
             request.name,
             barcode,
@@ -52,6 +192,15 @@ impl<R: SampleRepository> SampleService<R> {
             request.scientific_name,
             created_by.to_string(),
         );
+        sample.attributes = attributes;
+        sample.control_type = control_type;
+        sample.subproject_id = request.subproject_id;
+        if let Some(name) = self.plugins.sample_name(&sample).await? {
+            sample.name = name;
+        }
+        self.plugins
+            .validate_sample(Operation::Create, &sample)
+            .await?;
 
         let id = self.repository.save(&sample).await?;
 
@@ -64,36 +213,318 @@ impl<R: SampleRepository> SampleService<R> {
                 id: id.to_string(),
             }
         })?;
+        self.audit.created("Sample", id, &saved, created_by).await?;
+
+        self.plugins
+            .publish(DomainEvent::SampleCreated(saved.clone()))
+            .await;
+
+        Ok(saved.into())
+    }
+
+    /// Creates a new identity, unless existing identities have similar
+    /// external names.
+    ///
+    /// External names of participants whose details were erased can't be
+    /// used again.
+    ///
+    /// Similar identities are returned instead, and the identity is only
+    /// created if the request says to create it anyway. Each ignored match
+    /// is then kept for a reviewer to decide whether the two should be
+    /// merged.
+    #[instrument(skip(self))]
+    pub async fn create_identity(
+        &self,
+        request: CreateIdentityRequest,
+        created_by: &str,
+        role: Role,
+    ) -> Result<CreateIdentityResponse, DomainError> {
+        self.access
+            .check(request.project_id, Some(Requester::new(created_by, role)))
+            .await?;
+        self.locks.check(request.project_id, role).await?;
+
+        let external_name = request.external_name.trim().to_string();
+        if external_name.is_empty() {
+            return Err(DomainError::Validation(
+                "An identity needs an external name".to_string(),
+            ));
+        }
+
+        let subprojects = self.subproject_projects(request.subproject_id).await?;
+        check_subproject(request.project_id, request.subproject_id, &subprojects)?;
+
+        if let Some(erasures) = &self.erasures {
+            if erasures.is_erased(&hash_external_names(&external_name)).await? {
+                return Err(DomainError::Validation(
+                    "The external name belongs to a participant whose details were erased"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let identities = self.repository.find_by_class(&SampleClass::Identity).await?;
+        let matches = IdentityMatcher::find_matches(&external_name, &identities);
+        if !matches.is_empty() && !request.create_anyway {
+            info!(
+                "Identity {} not created: {} similar identities",
+                external_name,
+                matches.len()
+            );
+            return Ok(CreateIdentityResponse {
+                identity: None,
+                matches: matches.into_iter().map(Into::into).collect(),
+            });
+        }
+
+        let barcode = self.barcode_validator.generate_barcode("SAM");
+        if self.repository.find_by_barcode(barcode.as_str()).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Sample".to_string(),
+                field: "barcode".to_string(),
+                value: barcode.to_string(),
+            });
+        }
+
+        let mut sample = Sample::new_identity(
+            0,
+            request.name,
+            barcode,
+            request.project_id,
+            external_name.clone(),
+            created_by.to_string(),
+        );
+        sample.description = request.description;
+        sample.subproject_id = request.subproject_id;
+        if let Some(name) = self.plugins.sample_name(&sample).await? {
+            sample.name = name;
+        }
+        self.plugins
+            .validate_sample(Operation::Create, &sample)
+            .await?;
+
+        let id = self.repository.save(&sample).await?;
+
+        info!("Created identity: {} (ID: {})", sample.name, id);
+
+        for m in &matches {
+            warn!(
+                "Identity {} ({}) created by {} despite similarity to {} ({})",
+                id, external_name, created_by, m.identity_id, m.external_name
+            );
+            if let Some(possible_duplicates) = &self.possible_duplicates {
+                possible_duplicates
+                    .save(&PossibleDuplicate::new(
+                        id,
+                        m.identity_id,
+                        external_name.clone(),
+                        m.external_name.clone(),
+                        m.distance,
+                        created_by.to_string(),
+                    ))
+                    .await?;
+            }
+        }
+
+        let saved = self.repository.find_by_id(id).await?.ok_or_else(|| {
+            DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: id.to_string(),
+            }
+        })?;
+        self.audit.created("Sample", id, &saved, created_by).await?;
+
+        self.plugins
+            .publish(DomainEvent::SampleCreated(saved.clone()))
+            .await;
+
+        Ok(CreateIdentityResponse {
+            identity: Some(saved.into()),
+            matches: matches.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Creates a tissue pooled from several identities.
+    ///
+    /// The pooled sample has no parent; the identities it was made from are
+    /// recorded as its composition instead.
+    #[instrument(skip(self))]
+    pub async fn create_pooled_sample(
+        &self,
+        request: CreatePooledSampleRequest,
+        created_by: &str,
+        role: Role,
+    ) -> Result<SampleResponse, DomainError> {
+        let compositions = self.compositions()?;
+        self.access
+            .check(request.project_id, Some(Requester::new(created_by, role)))
+            .await?;
+        self.locks.check(request.project_id, role).await?;
+
+        let identities = self.repository.find_by_ids(&request.identity_ids).await?;
+        if let Some(missing) = request
+            .identity_ids
+            .iter()
+            .find(|&&id| !identities.iter().any(|s| s.id == id))
+        {
+            return Err(DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: missing.to_string(),
+            });
+        }
+
+        let barcode = self.barcode_validator.generate_barcode("SAM");
+        if self.repository.find_by_barcode(barcode.as_str()).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Sample".to_string(),
+                field: "barcode".to_string(),
+                value: barcode.to_string(),
+            });
+        }
+
+        let mut sample = Sample::new_pooled(
+            0,
+            request.name,
+            barcode,
+            request.project_id,
+            created_by.to_string(),
+        );
+        sample.description = request.description;
+        if let SampleDetails::Detailed(details) = &mut sample.details {
+            details.tissue_origin = request.tissue_origin;
+            details.tissue_type = request.tissue_type;
+        }
+        if let Some(name) = self.plugins.sample_name(&sample).await? {
+            sample.name = name;
+        }
+        // Checked before saving, so nothing is created for a bad pool
+        SampleComposition::new(&sample, &identities)?;
+        self.plugins
+            .validate_sample(Operation::Create, &sample)
+            .await?;
+
+        let id = self.repository.save(&sample).await?;
+        let saved = self.repository.find_by_id(id).await?.ok_or_else(|| {
+            DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: id.to_string(),
+            }
+        })?;
+        let composition = SampleComposition::new(&saved, &identities)?;
+        compositions.save(&composition).await?;
+
+        info!(
+            "Created pooled sample: {} (ID: {}) from {} identities",
+            saved.name,
+            id,
+            composition.identity_ids.len()
+        );
+
+        self.audit.created("Sample", id, &saved, created_by).await?;
+
+        self.plugins
+            .publish(DomainEvent::SampleCreated(saved.clone()))
+            .await;
 
         Ok(saved.into())
     }
 
+    /// Lists identities created despite looking like existing ones that
+    /// haven't been reviewed yet.
+    #[instrument(skip(self))]
+    pub async fn list_possible_duplicates(
+        &self,
+    ) -> Result<Vec<PossibleDuplicateResponse>, DomainError> {
+        let duplicates = self.possible_duplicates()?.list_unresolved().await?;
+        Ok(duplicates.into_iter().map(Into::into).collect())
+    }
+
+    /// Records a reviewer's decision on a possible duplicate.
+    #[instrument(skip(self))]
+    pub async fn resolve_possible_duplicate(
+        &self,
+        id: EntityId,
+        request: ResolveDuplicateRequest,
+        resolved_by: &str,
+    ) -> Result<PossibleDuplicateResponse, DomainError> {
+        let possible_duplicates = self.possible_duplicates()?;
+        let mut duplicate =
+            possible_duplicates
+                .find_by_id(id)
+                .await?
+                .ok_or_else(|| DomainError::NotFound {
+                    entity_type: "PossibleDuplicate".to_string(),
+                    id: id.to_string(),
+                })?;
+
+        let resolution = request.resolution.parse()?;
+        duplicate.resolve(resolution, resolved_by.to_string())?;
+        possible_duplicates.save(&duplicate).await?;
+
+        info!(
+            "Identities {} and {} reviewed as {} by {}",
+            duplicate.identity_id, duplicate.match_id, resolution, resolved_by
+        );
+
+        Ok(duplicate.into())
+    }
+
     /// Gets a sample by ID.
     #[instrument(skip(self))]
-    pub async fn get_sample(&self, id: i32) -> Result<SampleResponse, DomainError> {
+    pub async fn get_sample(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<SampleResponse, DomainError> {
         let sample = self.repository.find_by_id(id).await?.ok_or_else(|| {
             DomainError::NotFound {
                 entity_type: "Sample".to_string(),
                 id: id.to_string(),
             }
         })?;
+        self.access.check(sample.project_id, requester).await?;
 
         Ok(sample.into())
     }
 
     /// Gets a sample by barcode.
     #[instrument(skip(self))]
-    pub async fn get_sample_by_barcode(&self, barcode: &str) -> Result<SampleResponse, DomainError> {
+    pub async fn get_sample_by_barcode(
+        &self,
+        barcode: &str,
+        requester: Option<Requester<'_>>,
+    ) -> Result<SampleResponse, DomainError> {
         let sample = self.repository.find_by_barcode(barcode).await?.ok_or_else(|| {
             DomainError::NotFound {
                 entity_type: "Sample".to_string(),
                 id: barcode.to_string(),
             }
         })?;
+        self.access.check(sample.project_id, requester).await?;
 
         Ok(sample.into())
     }
 
+    /// Gets the samples matching a batch of barcodes.
+    ///
+    /// Barcodes with no matching sample, or matching a sample the requester
+    /// can't see, are omitted from the result.
+    #[instrument(skip(self, barcodes), fields(count = barcodes.len()))]
+    pub async fn get_samples_by_barcodes(
+        &self,
+        barcodes: &[String],
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<SampleSummary>, DomainError> {
+        let samples = self.repository.find_by_barcodes(barcodes).await?;
+        let projects = self.access.projects(requester).await?;
+
+        Ok(samples
+            .into_iter()
+            .filter(|s| projects.as_ref().is_none_or(|p| p.contains(&s.project_id)))
+            .map(|s| s.into())
+            .collect())
+    }
+
     /// Lists samples for a project.
     #[instrument(skip(self))]
     pub async fn list_samples_by_project(
@@ -101,7 +532,10 @@ impl<R: SampleRepository> SampleService<R> {
         project_id: i32,
         limit: Option<u64>,
         offset: Option<u64>,
+        requester: Option<Requester<'_>>,
     ) -> Result<Vec<SampleSummary>, DomainError> {
+        self.access.check(project_id, requester).await?;
+
         let options = QueryOptions::new()
             .limit(limit.unwrap_or(100))
             .offset(offset.unwrap_or(0))
@@ -113,20 +547,131 @@ impl<R: SampleRepository> SampleService<R> {
         Ok(samples.into_iter().map(|s| s.into()).collect())
     }
 
+    /// Lists the samples assigned to a subproject.
+    ///
+    /// If `project_id` is given, the subproject must be one of that
+    /// project's.
+    #[instrument(skip(self))]
+    pub async fn list_samples_by_subproject(
+        &self,
+        subproject_id: i32,
+        project_id: Option<i32>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<SampleSummary>, DomainError> {
+        let subprojects = self.subproject_projects(Some(subproject_id)).await?;
+        let owner = subprojects
+            .get(&subproject_id)
+            .copied()
+            .filter(|&owner| project_id.is_none_or(|id| id == owner))
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Subproject".to_string(),
+                id: subproject_id.to_string(),
+            })?;
+        self.access.check(owner, requester).await?;
+
+        let options = QueryOptions::new()
+            .limit(limit.unwrap_or(100))
+            .offset(offset.unwrap_or(0));
+
+        let samples = self
+            .repository
+            .find_by_subproject(subproject_id, options)
+            .await?;
+
+        Ok(samples.into_iter().map(|s| s.into()).collect())
+    }
+
     /// Lists child samples (for detailed sample hierarchy).
     #[instrument(skip(self))]
-    pub async fn list_child_samples(&self, parent_id: i32) -> Result<Vec<SampleSummary>, DomainError> {
+    pub async fn list_child_samples(
+        &self,
+        parent_id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<SampleSummary>, DomainError> {
         let samples = self.repository.find_by_parent(parent_id).await?;
+        let projects = self.access.projects(requester).await?;
 
-        Ok(samples.into_iter().map(|s| s.into()).collect())
+        Ok(samples
+            .into_iter()
+            .filter(|s| projects.as_ref().is_none_or(|p| p.contains(&s.project_id)))
+            .map(|s| s.into())
+            .collect())
+    }
+
+    /// Gets where a sample comes from and the samples derived from it.
+    ///
+    /// A sample pooled from several identities leads back to each of them,
+    /// and an identity leads on to the samples it was pooled into.
+    #[instrument(skip(self))]
+    pub async fn get_sample_lineage(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<SampleLineageResponse, DomainError> {
+        let sample = self.repository.find_by_id(id).await?.ok_or_else(|| {
+            DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: id.to_string(),
+            }
+        })?;
+        self.access.check(sample.project_id, requester).await?;
+
+        let mut seen = HashSet::from([sample.id]);
+        let mut ancestors: Vec<Sample> = Vec::new();
+        let mut parent_id = sample.parent_id();
+        while let Some(id) = parent_id.filter(|&id| seen.insert(id)) {
+            let Some(parent) = self.repository.find_by_id(id).await? else {
+                break;
+            };
+            parent_id = parent.parent_id();
+            ancestors.push(parent);
+        }
+
+        let root = ancestors.last().unwrap_or(&sample);
+        let identities = if root.sample_class() == SampleClass::Identity {
+            vec![root.clone()]
+        } else if root.is_pooled() {
+            self.pooled_from(root.id).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut seen = HashSet::from([sample.id]);
+        let mut family = vec![sample.clone()];
+        let mut next = 0;
+        while next < family.len() {
+            let (id, class) = (family[next].id, family[next].sample_class());
+            let mut derived = self.repository.find_by_parent(id).await?;
+            if class == SampleClass::Identity {
+                derived.extend(self.pooled_into(id).await?);
+            }
+            family.extend(derived.into_iter().filter(|s| seen.insert(s.id)));
+            next += 1;
+        }
+        let descendants = family.split_off(1);
+
+        Ok(SampleLineageResponse {
+            sample: sample.into(),
+            ancestors: ancestors.into_iter().map(Into::into).collect(),
+            identities: identities.into_iter().map(Into::into).collect(),
+            descendants: descendants.into_iter().map(Into::into).collect(),
+        })
     }
 
     /// Updates a sample.
+    ///
+    /// QC status changes are checked against [`QcTransitionPolicy`] and
+    /// recorded in the change log. Fails with a concurrent modification
+    /// error if the sample changes between being read and written.
     #[instrument(skip(self))]
     pub async fn update_sample(
         &self,
         id: i32,
         request: UpdateSampleRequest,
+        changed_by: &str,
+        role: Role,
     ) -> Result<SampleResponse, DomainError> {
         let mut sample = self.repository.find_by_id(id).await?.ok_or_else(|| {
             DomainError::NotFound {
@@ -134,59 +679,1368 @@ impl<R: SampleRepository> SampleService<R> {
                 id: id.to_string(),
             }
         })?;
+        self.access
+            .check(sample.project_id, Some(Requester::new(changed_by, role)))
+            .await?;
+        self.locks.check(sample.project_id, role).await?;
+        let before = sample.clone();
 
-        // Apply updates
-        if let Some(desc) = request.description {
-            sample.description = Some(desc);
-        }
-        if let Some(vol) = request.volume_ul {
-            sample.volume = Some(miso_domain::value_objects::Volume::microliters(vol));
-        }
-        if let Some(conc) = request.concentration_ng_ul {
-            sample.concentration = Some(miso_domain::value_objects::Concentration::ng_per_ul(conc));
-        }
-        if let Some(status) = request.qc_status {
-            use miso_domain::value_objects::QcStatus;
-            let qc = match status.as_str() {
-                "not_ready" => QcStatus::NotReady,
-                "ready" => QcStatus::Ready,
-                "passed" => QcStatus::Passed,
-                "failed" => QcStatus::Failed,
-                "needs_review" => QcStatus::NeedsReview,
-                _ => return Err(DomainError::Validation(format!("Invalid QC status: {}", status))),
-            };
-            sample.set_qc_status(qc);
+        let definitions = match request.attributes {
+            Some(_) => self.attribute_definitions(sample.project_id).await?,
+            None => Vec::new(),
+        };
+        let subprojects = self.subproject_projects(request.subproject_id).await?;
+        let change = apply_changes(
+            &mut sample,
+            request,
+            &definitions,
+            &subprojects,
+            changed_by,
+            role,
+        )?;
+        self.plugins
+            .validate_sample(Operation::Update, &sample)
+            .await?;
+
+        let conflicts = self
+            .repository
+            .update_all_versioned(std::slice::from_ref(&sample))
+            .await?;
+        if !conflicts.is_empty() {
+            return Err(DomainError::ConcurrentModification {
+                entity_type: "Sample".to_string(),
+                id: id.to_string(),
+            });
         }
+        sample.version += 1;
 
-        self.repository.save(&sample).await?;
+        if let Some(entry) = change {
+            self.change_log.save_all(&[entry]).await?;
+        }
+        self.audit
+            .updated("Sample", id, &before, &sample, changed_by)
+            .await?;
 
         info!("Updated sample: {} (ID: {})", sample.name, id);
 
+        self.plugins
+            .publish(DomainEvent::SampleUpdated(sample.clone()))
+            .await;
+
         Ok(sample.into())
     }
 
+    /// Updates many samples in one transaction.
+    ///
+    /// Each row carries the version the client last read. If any row is
+    /// missing, stale, invalid or in a locked project, nothing is written
+    /// and the per-row results say which rows need attention.
+    #[instrument(skip(self, request), fields(count = request.updates.len()))]
+    pub async fn bulk_update_samples(
+        &self,
+        request: BulkUpdateSamplesRequest,
+        changed_by: &str,
+        role: Role,
+    ) -> Result<BulkUpdateSamplesResponse, DomainError> {
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = request.updates.iter().find(|u| !seen.insert(u.id)) {
+            return Err(DomainError::Validation(format!(
+                "Sample {} appears more than once",
+                duplicate.id
+            )));
+        }
+
+        let ids: Vec<i32> = request.updates.iter().map(|u| u.id).collect();
+        let mut existing: HashMap<i32, Sample> = self
+            .repository
+            .find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+
+        let mut definitions: HashMap<EntityId, Vec<AttributeDefinition>> = HashMap::new();
+        for update in request.updates.iter().filter(|u| u.changes.attributes.is_some()) {
+            if let Some(sample) = existing.get(&update.id) {
+                if let Entry::Vacant(entry) = definitions.entry(sample.project_id) {
+                    entry.insert(self.attribute_definitions(sample.project_id).await?);
+                }
+            }
+        }
+
+        let subprojects = self
+            .subproject_projects(request.updates.iter().filter_map(|u| u.changes.subproject_id))
+            .await?;
+
+        // Rows of locked projects, or of projects the user isn't a member
+        // of, are refused like invalid ones
+        let requester = Some(Requester::new(changed_by, role));
+        let mut locked: HashMap<EntityId, Option<String>> = HashMap::new();
+        for sample in existing.values() {
+            if let Entry::Vacant(entry) = locked.entry(sample.project_id) {
+                let check = match self.access.check(sample.project_id, requester).await {
+                    Ok(()) => self.locks.check(sample.project_id, role).await,
+                    Err(e) => Err(e),
+                };
+                entry.insert(match check {
+                    Ok(()) => None,
+                    Err(
+                        e @ (DomainError::ProjectLocked { .. } | DomainError::PermissionDenied(_)),
+                    ) => Some(e.to_string()),
+                    Err(e) => return Err(e),
+                });
+            }
+        }
+
+        let mut results = Vec::with_capacity(request.updates.len());
+        let mut samples = Vec::with_capacity(request.updates.len());
+        let mut originals = Vec::with_capacity(request.updates.len());
+        let mut changes = Vec::new();
+        for update in request.updates {
+            let row = |status, current_version, message| BulkUpdateRowResult {
+                id: update.id,
+                status,
+                current_version,
+                message,
+            };
+            results.push(match existing.remove(&update.id) {
+                None => row(BulkUpdateRowStatus::NotFound, None, None),
+                Some(sample) if sample.version != update.version => {
+                    row(BulkUpdateRowStatus::Conflict, Some(sample.version), None)
+                }
+                Some(sample) if locked[&sample.project_id].is_some() => row(
+                    BulkUpdateRowStatus::Invalid,
+                    Some(sample.version),
+                    locked[&sample.project_id].clone(),
+                ),
+                Some(mut sample) => {
+                    let original = sample.clone();
+                    let definitions = definitions
+                        .get(&sample.project_id)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    let applied = apply_changes(
+                        &mut sample,
+                        update.changes,
+                        definitions,
+                        &subprojects,
+                        changed_by,
+                        role,
+                    );
+                    let checked = match applied {
+                        Ok(change) => self
+                            .plugins
+                            .validate_sample(Operation::Update, &sample)
+                            .await
+                            .map(|_| change),
+                        Err(e) => Err(e),
+                    };
+                    match checked {
+                        Ok(change) => {
+                            changes.extend(change);
+                            let version = sample.version;
+                            originals.push(original);
+                            samples.push(sample);
+                            row(BulkUpdateRowStatus::Skipped, Some(version), None)
+                        }
+                        Err(e) => row(
+                            BulkUpdateRowStatus::Invalid,
+                            Some(sample.version),
+                            Some(e.to_string()),
+                        ),
+                    }
+                }
+            });
+        }
+
+        if samples.len() == results.len() {
+            let conflicts = self.repository.update_all_versioned(&samples).await?;
+
+            if conflicts.is_empty() {
+                for (result, sample) in results.iter_mut().zip(samples.iter_mut()) {
+                    sample.version += 1;
+                    result.status = BulkUpdateRowStatus::Updated;
+                    result.current_version = Some(sample.version);
+                }
+                self.change_log.save_all(&changes).await?;
+                let audited: Vec<(EntityId, &Sample, &Sample)> = originals
+                    .iter()
+                    .zip(&samples)
+                    .map(|(before, after)| (after.id, before, after))
+                    .collect();
+                self.audit
+                    .updated_all("Sample", &audited, changed_by)
+                    .await?;
+
+                info!("Bulk updated {} samples", samples.len());
+
+                for sample in &samples {
+                    self.plugins
+                        .publish(DomainEvent::SampleUpdated(sample.clone()))
+                        .await;
+                }
+
+                return Ok(BulkUpdateSamplesResponse {
+                    applied: true,
+                    results,
+                    samples: samples.into_iter().map(Into::into).collect(),
+                });
+            }
+
+            // Another writer got in between our read and write
+            for conflict in conflicts {
+                if let Some(result) = results.iter_mut().find(|r| r.id == conflict.id) {
+                    result.status = match conflict.current_version {
+                        Some(_) => BulkUpdateRowStatus::Conflict,
+                        None => BulkUpdateRowStatus::NotFound,
+                    };
+                    result.current_version = conflict.current_version;
+                }
+            }
+        }
+
+        info!(
+            "Rejected bulk update of {} samples: {} row(s) failed",
+            results.len(),
+            results
+                .iter()
+                .filter(|r| r.status != BulkUpdateRowStatus::Skipped)
+                .count()
+        );
+
+        Ok(BulkUpdateSamplesResponse {
+            applied: false,
+            results,
+            samples: Vec::new(),
+        })
+    }
+
+    /// Gets the change log of a sample, oldest first.
+    #[instrument(skip(self))]
+    pub async fn get_sample_change_log(
+        &self,
+        id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<Vec<ChangeLogEntryResponse>, DomainError> {
+        let sample = self.repository.find_by_id(id).await?.ok_or_else(|| {
+            DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: id.to_string(),
+            }
+        })?;
+        self.access.check(sample.project_id, requester).await?;
+
+        let entries = self.change_log.find_by_entity("Sample", id).await?;
+
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
     /// Deletes a sample.
     #[instrument(skip(self))]
-    pub async fn delete_sample(&self, id: i32) -> Result<(), DomainError> {
+    pub async fn delete_sample(
+        &self,
+        id: i32,
+        deleted_by: &str,
+        role: Role,
+    ) -> Result<(), DomainError> {
         // Check if sample exists
-        self.repository.find_by_id(id).await?.ok_or_else(|| {
+        let sample = self.repository.find_by_id(id).await?.ok_or_else(|| {
             DomainError::NotFound {
                 entity_type: "Sample".to_string(),
                 id: id.to_string(),
             }
         })?;
+        self.access
+            .check(sample.project_id, Some(Requester::new(deleted_by, role)))
+            .await?;
+        self.locks.check(sample.project_id, role).await?;
 
         self.repository.delete(id).await?;
+        self.audit
+            .deleted("Sample", id, &sample, deleted_by)
+            .await?;
 
         info!("Deleted sample: {}", id);
 
+        self.plugins.publish(DomainEvent::SampleDeleted(id)).await;
+
         Ok(())
     }
 
-    /// Counts samples in a project.
+    /// Counts samples in a project, leaving out controls.
     #[instrument(skip(self))]
-    pub async fn count_samples_by_project(&self, project_id: i32) -> Result<u64, DomainError> {
+    pub async fn count_samples_by_project(
+        &self,
+        project_id: i32,
+        requester: Option<Requester<'_>>,
+    ) -> Result<u64, DomainError> {
+        self.access.check(project_id, requester).await?;
         self.repository.count_by_project(project_id).await
     }
+
+    fn possible_duplicates(&self) -> Result<&Arc<dyn PossibleDuplicateRepository>, DomainError> {
+        self.possible_duplicates.as_ref().ok_or_else(|| {
+            DomainError::Validation("Duplicate identity review is not configured".to_string())
+        })
+    }
+
+    fn compositions(&self) -> Result<&Arc<dyn SampleCompositionRepository>, DomainError> {
+        self.compositions.as_ref().ok_or_else(|| {
+            DomainError::Validation("Sample pooling is not configured".to_string())
+        })
+    }
+
+    /// The identities the pooled sample with `sample_id` was made from.
+    async fn pooled_from(&self, sample_id: EntityId) -> Result<Vec<Sample>, DomainError> {
+        let Some(compositions) = &self.compositions else {
+            return Ok(Vec::new());
+        };
+        let identity_ids: Vec<EntityId> = compositions
+            .find_by_samples(&[sample_id])
+            .await?
+            .into_iter()
+            .flat_map(|c| c.identity_ids)
+            .collect();
+        if identity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.repository.find_by_ids(&identity_ids).await
+    }
+
+    /// The pooled samples the identity with `identity_id` went into.
+    async fn pooled_into(&self, identity_id: EntityId) -> Result<Vec<Sample>, DomainError> {
+        let Some(compositions) = &self.compositions else {
+            return Ok(Vec::new());
+        };
+        let sample_ids: Vec<EntityId> = compositions
+            .find_by_identity(identity_id)
+            .await?
+            .into_iter()
+            .map(|c| c.sample_id)
+            .collect();
+        if sample_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.repository.find_by_ids(&sample_ids).await
+    }
+
+    /// The custom attributes samples in a project may have.
+    async fn attribute_definitions(
+        &self,
+        project_id: EntityId,
+    ) -> Result<Vec<AttributeDefinition>, DomainError> {
+        match &self.attribute_definitions {
+            Some(definitions) => {
+                definitions
+                    .find_for_project(AttributeTarget::Sample, project_id)
+                    .await
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Looks up the projects of subprojects, by subproject ID. Unknown
+    /// subprojects are left out.
+    async fn subproject_projects(
+        &self,
+        ids: impl IntoIterator<Item = EntityId>,
+    ) -> Result<HashMap<EntityId, EntityId>, DomainError> {
+        let ids: HashSet<EntityId> = ids.into_iter().collect();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let Some(repository) = &self.subprojects else {
+            return Err(DomainError::Validation(
+                "Subprojects are not enabled".to_string(),
+            ));
+        };
+
+        let mut projects = HashMap::new();
+        for id in ids {
+            if let Some(subproject) = repository.find_by_id(id).await? {
+                projects.insert(id, subproject.project_id);
+            }
+        }
+        Ok(projects)
+    }
+}
+
+/// Checks that a sample of a project can be assigned to a subproject, given
+/// the projects of the subprojects involved.
+fn check_subproject(
+    project_id: EntityId,
+    subproject_id: Option<EntityId>,
+    subprojects: &HashMap<EntityId, EntityId>,
+) -> Result<(), DomainError> {
+    let Some(id) = subproject_id else {
+        return Ok(());
+    };
+    match subprojects.get(&id) {
+        Some(&owner) if owner == project_id => Ok(()),
+        Some(_) => Err(DomainError::Validation(format!(
+            "Subproject {} belongs to another project",
+            id
+        ))),
+        None => Err(DomainError::NotFound {
+            entity_type: "Subproject".to_string(),
+            id: id.to_string(),
+        }),
+    }
 }
 
+/// Parses a control type code; "none" means the item isn't a control.
+pub(crate) fn parse_control_type(code: &str) -> Result<Option<ControlType>, DomainError> {
+    match code.trim() {
+        "none" => Ok(None),
+        code => code.parse().map(Some).map_err(DomainError::Validation),
+    }
+}
+
+/// Trims attribute values and drops blank ones, which mean "no value".
+fn normalize_attributes(attributes: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    attributes
+        .into_iter()
+        .map(|(key, value)| (key, value.trim().to_string()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
+/// Applies the requested changes to a sample.
+///
+/// New attribute values are checked against `definitions`, the attributes
+/// that apply to the sample, and a new subproject against `subprojects`,
+/// the projects of the subprojects named. Returns the change log entry to
+/// record, if the QC status changed.
+fn apply_changes(
+    sample: &mut Sample,
+    request: UpdateSampleRequest,
+    definitions: &[AttributeDefinition],
+    subprojects: &HashMap<EntityId, EntityId>,
+    changed_by: &str,
+    role: Role,
+) -> Result<Option<ChangeLogEntry>, DomainError> {
+    let mut change = None;
+
+    if let Some(desc) = request.description {
+        sample.description = Some(desc);
+    }
+    if let Some(vol) = request.volume_ul {
+        sample.volume = Some(miso_domain::value_objects::Volume::microliters(vol));
+    }
+    if let Some(mass) = request.mass_ng {
+        sample.mass = Some(miso_domain::value_objects::Mass::nanograms(mass));
+    }
+    if let Some(conc) = request.concentration_ng_ul {
+        sample.concentration = Some(miso_domain::value_objects::Concentration::ng_per_ul(conc));
+    }
+    if let Some(attributes) = request.attributes {
+        let attributes = normalize_attributes(attributes);
+        check_attributes(definitions, &attributes)?;
+        sample.attributes = attributes;
+    }
+    if let Some(code) = request.control_type {
+        sample.control_type = parse_control_type(&code)?;
+    }
+    if let Some(id) = request.subproject_id {
+        check_subproject(sample.project_id, Some(id), subprojects)?;
+        sample.subproject_id = Some(id);
+    }
+    if let Some(status) = request.qc_status {
+        use miso_domain::value_objects::QcStatus;
+        let qc = match status.as_str() {
+            "not_ready" => QcStatus::NotReady,
+            "ready" => QcStatus::Ready,
+            "passed" => QcStatus::Passed,
+            "failed" => QcStatus::Failed,
+            "needs_review" => QcStatus::NeedsReview,
+            _ => return Err(DomainError::Validation(format!("Invalid QC status: {}", status))),
+        };
+        let reason = request
+            .qc_reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+        QcTransitionPolicy::check(sample.qc_status, qc, role, reason.as_deref())?;
+
+        if qc != sample.qc_status {
+            change = Some(
+                ChangeLogEntry::new(
+                    "Sample",
+                    sample.id,
+                    format!("QC status changed from {} to {}", sample.qc_status, qc),
+                    changed_by,
+                )
+                .with_reason(reason),
+            );
+            sample.set_qc_status(qc);
+        }
+    }
+    sample.updated_at = Utc::now();
+
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        AuditAction, AuditEntry, AuditQuery, EntityId, SampleClass,
+    };
+    use miso_domain::repositories::{AuditLogRepository, NewSample, VersionConflict};
+    use miso_domain::value_objects::Barcode;
+    use miso_domain::value_objects::QcStatus;
+
+    use super::*;
+    use crate::dto::BulkSampleUpdate;
+    use crate::test_support::Members;
+
+    /// In-memory repository that can simulate a concurrent writer.
+    #[derive(Default)]
+    struct InMemorySamples {
+        samples: Mutex<HashMap<EntityId, Sample>>,
+        bump_before_write: Mutex<Option<EntityId>>,
+    }
+
+    #[derive(Default)]
+    struct InMemoryChangeLog {
+        entries: Mutex<Vec<ChangeLogEntry>>,
+    }
+
+    #[async_trait]
+    impl ChangeLogRepository for InMemoryChangeLog {
+        async fn find_by_entity(
+            &self,
+            entity_type: &str,
+            entity_id: EntityId,
+        ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .filter(|e| e.entity_type == entity_type && e.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+        async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            self.entries.lock().unwrap().extend_from_slice(entries);
+            Ok(())
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<ChangeLogEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[ChangeLogEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    type TestService = SampleService<InMemorySamples, InMemoryChangeLog>;
+
+    #[derive(Default)]
+    struct InMemoryAuditLog {
+        entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditLogRepository for InMemoryAuditLog {
+        async fn find(&self, _: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+        async fn save_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+            self.entries.lock().unwrap().extend_from_slice(entries);
+            Ok(())
+        }
+        async fn list_after(&self, _: EntityId, _: u64) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_entity(&self, _: &str, _: EntityId) -> Result<Vec<AuditEntry>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all(&self, _: &[AuditEntry]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for InMemorySamples {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok(self.samples.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_subproject(
+            &self,
+            subproject_id: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            let mut assigned: Vec<Sample> = samples
+                .values()
+                .filter(|s| s.subproject_id == Some(subproject_id))
+                .cloned()
+                .collect();
+            assigned.sort_by_key(|s| s.id);
+            Ok(assigned)
+        }
+        async fn find_by_parent(&self, parent_id: EntityId) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            let mut children: Vec<Sample> = samples
+                .values()
+                .filter(|s| s.parent_id() == Some(parent_id))
+                .cloned()
+                .collect();
+            children.sort_by_key(|s| s.id);
+            Ok(children)
+        }
+        async fn find_by_class(&self, class: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(samples
+                .values()
+                .filter(|s| s.sample_class() == *class)
+                .cloned()
+                .collect())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            let samples = self.samples.lock().unwrap();
+            Ok(ids
+                .iter()
+                .filter_map(|id| samples.get(id).cloned())
+                .collect())
+        }
+        async fn save(&self, sample: &Sample) -> Result<EntityId, DomainError> {
+            let mut samples = self.samples.lock().unwrap();
+            let mut sample = sample.clone();
+            if sample.id == 0 {
+                sample.id = samples.keys().max().copied().unwrap_or(0) + 1;
+            }
+            samples.insert(sample.id, sample.clone());
+            Ok(sample.id)
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            updates: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            let mut samples = self.samples.lock().unwrap();
+            if let Some(id) = self.bump_before_write.lock().unwrap().take() {
+                samples.get_mut(&id).unwrap().version += 1;
+            }
+
+            let conflicts: Vec<_> = updates
+                .iter()
+                .filter_map(|u| {
+                    let current = samples.get(&u.id).map(|s| s.version);
+                    (current != Some(u.version)).then_some(VersionConflict {
+                        id: u.id,
+                        expected_version: u.version,
+                        current_version: current,
+                    })
+                })
+                .collect();
+            if conflicts.is_empty() {
+                for u in updates {
+                    let mut stored = u.clone();
+                    stored.version += 1;
+                    samples.insert(u.id, stored);
+                }
+            }
+            Ok(conflicts)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            Ok(0)
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn count_by_qc_status(
+            &self,
+        ) -> Result<Vec<(miso_domain::value_objects::QcStatus, u64)>, DomainError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn service_with_samples(ids: &[EntityId]) -> (Arc<InMemorySamples>, TestService) {
+        let repository = Arc::new(InMemorySamples::default());
+        for &id in ids {
+            let sample = Sample::new_plain(
+                id,
+                format!("SAM{}", id),
+                Barcode::new(format!("SAM-{}", id)).unwrap(),
+                1,
+                "Homo sapiens".to_string(),
+                "admin".to_string(),
+            );
+            repository.samples.lock().unwrap().insert(id, sample);
+        }
+        (
+            repository.clone(),
+            SampleService::new(repository, Arc::new(InMemoryChangeLog::default())),
+        )
+    }
+
+    #[derive(Default)]
+    struct InMemoryDuplicates {
+        duplicates: Mutex<Vec<PossibleDuplicate>>,
+    }
+
+    #[async_trait]
+    impl PossibleDuplicateRepository for InMemoryDuplicates {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<PossibleDuplicate>, DomainError> {
+            let duplicates = self.duplicates.lock().unwrap();
+            Ok(duplicates.iter().find(|d| d.id == id).cloned())
+        }
+        async fn list_unresolved(&self) -> Result<Vec<PossibleDuplicate>, DomainError> {
+            let duplicates = self.duplicates.lock().unwrap();
+            Ok(duplicates
+                .iter()
+                .filter(|d| d.resolution.is_none())
+                .cloned()
+                .collect())
+        }
+        async fn find_by_identity(&self, _: EntityId) -> Result<Vec<PossibleDuplicate>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, duplicate: &PossibleDuplicate) -> Result<EntityId, DomainError> {
+            let mut duplicates = self.duplicates.lock().unwrap();
+            let mut duplicate = duplicate.clone();
+            if duplicate.id == 0 {
+                duplicate.id = duplicates.len() as EntityId + 1;
+            }
+            duplicates.retain(|d| d.id != duplicate.id);
+            duplicates.push(duplicate.clone());
+            Ok(duplicate.id)
+        }
+    }
+
+    /// Attribute definitions that never change.
+    struct FixedDefinitions(Vec<AttributeDefinition>);
+
+    #[async_trait]
+    impl AttributeDefinitionRepository for FixedDefinitions {
+        async fn find_by_id(
+            &self,
+            id: EntityId,
+        ) -> Result<Option<AttributeDefinition>, DomainError> {
+            Ok(self.0.iter().find(|d| d.id == id).cloned())
+        }
+        async fn list(
+            &self,
+            _: Option<AttributeTarget>,
+        ) -> Result<Vec<AttributeDefinition>, DomainError> {
+            Ok(self.0.clone())
+        }
+        async fn find_for_project(
+            &self,
+            target: AttributeTarget,
+            project_id: EntityId,
+        ) -> Result<Vec<AttributeDefinition>, DomainError> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|d| d.target == target && d.applies_to(project_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, _: &AttributeDefinition) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn update(id: EntityId, version: i32, qc_status: &str) -> BulkSampleUpdate {
+        BulkSampleUpdate {
+            id,
+            version,
+            changes: UpdateSampleRequest {
+                description: None,
+                volume_ul: None,
+                mass_ng: None,
+                concentration_ng_ul: None,
+                qc_status: Some(qc_status.to_string()),
+                qc_reason: None,
+                attributes: None,
+                control_type: None,
+                subproject_id: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sample_counts_reach_project_members_only() {
+        let (_, service) = service_with_samples(&[1]);
+        let service =
+            service.with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+
+        let member = Some(Requester::new("member", Role::Technician));
+        assert_eq!(service.count_samples_by_project(1, member).await.unwrap(), 0);
+        for requester in [None, Some(Requester::new("outsider", Role::Technician))] {
+            assert!(matches!(
+                service.count_samples_by_project(1, requester).await,
+                Err(DomainError::PermissionDenied(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_is_all_or_nothing() {
+        let (repository, service) = service_with_samples(&[1, 2, 3]);
+
+        let rejected = service
+            .bulk_update_samples(
+                BulkUpdateSamplesRequest {
+                    updates: vec![
+                        update(1, 1, "ready"),
+                        update(2, 7, "ready"),
+                        update(3, 1, "bogus"),
+                        update(4, 1, "ready"),
+                    ],
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        let statuses: Vec<_> = rejected.results.iter().map(|r| r.status).collect();
+        assert!(!rejected.applied);
+        assert_eq!(
+            statuses,
+            vec![
+                BulkUpdateRowStatus::Skipped,
+                BulkUpdateRowStatus::Conflict,
+                BulkUpdateRowStatus::Invalid,
+                BulkUpdateRowStatus::NotFound,
+            ]
+        );
+        assert_eq!(rejected.results[1].current_version, Some(1));
+        assert_eq!(repository.samples.lock().unwrap()[&1].version, 1);
+
+        let applied = service
+            .bulk_update_samples(
+                BulkUpdateSamplesRequest {
+                    updates: vec![update(1, 1, "ready"), update(2, 1, "passed")],
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert!(applied.applied);
+        assert_eq!(applied.samples[1].qc_status, "Passed");
+        assert_eq!(applied.results[0].current_version, Some(2));
+        assert_eq!(repository.samples.lock().unwrap()[&2].version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_reports_write_time_conflicts() {
+        let (repository, service) = service_with_samples(&[1, 2]);
+        *repository.bump_before_write.lock().unwrap() = Some(2);
+
+        let response = service
+            .bulk_update_samples(
+                BulkUpdateSamplesRequest {
+                    updates: vec![update(1, 1, "ready"), update(2, 1, "ready")],
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+
+        assert!(!response.applied);
+        assert_eq!(response.results[0].status, BulkUpdateRowStatus::Skipped);
+        assert_eq!(response.results[1].status, BulkUpdateRowStatus::Conflict);
+        assert_eq!(response.results[1].current_version, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_updates_and_deletes_are_audited() {
+        let (_, service) = service_with_samples(&[1, 2]);
+        let log = Arc::new(InMemoryAuditLog::default());
+        let service = service.with_audit(AuditTrail::new(log.clone()));
+
+        service
+            .bulk_update_samples(
+                BulkUpdateSamplesRequest {
+                    updates: vec![update(1, 1, "ready"), update(2, 1, "ready")],
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        service.delete_sample(2, "manager", Role::Technician).await.unwrap();
+
+        let entries = log.entries.lock().unwrap().clone();
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.entity_id, e.action, e.changed_by.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, AuditAction::Update, "tech"),
+                (2, AuditAction::Update, "tech"),
+                (2, AuditAction::Delete, "manager"),
+            ]
+        );
+        let fields: Vec<_> = entries[0].changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["qc_status"]);
+    }
+
+    #[tokio::test]
+    async fn test_qc_changes_are_checked_and_logged() {
+        let (repository, service) = service_with_samples(&[1]);
+        repository
+            .samples
+            .lock()
+            .unwrap()
+            .get_mut(&1)
+            .unwrap()
+            .qc_status = QcStatus::Passed;
+        let fail = |reason: &str| UpdateSampleRequest {
+            description: None,
+            volume_ul: None,
+            mass_ng: None,
+            concentration_ng_ul: None,
+            qc_status: Some("failed".to_string()),
+            qc_reason: Some(reason.to_string()),
+            attributes: None,
+            control_type: None,
+            subproject_id: None,
+        };
+
+        let denied = service
+            .update_sample(1, fail("Contaminated"), "tech", Role::Technician)
+            .await;
+        assert!(matches!(denied, Err(DomainError::PermissionDenied(_))));
+
+        let missing_reason = service
+            .update_sample(1, fail(" "), "manager", Role::LabManager)
+            .await;
+        assert!(matches!(missing_reason, Err(DomainError::Validation(_))));
+
+        let updated = service
+            .update_sample(1, fail("Contaminated"), "manager", Role::LabManager)
+            .await
+            .unwrap();
+        assert_eq!(updated.qc_status, "Failed");
+
+        let log = service.get_sample_change_log(1, None).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].summary, "QC status changed from Passed to Failed");
+        assert_eq!(log[0].reason.as_deref(), Some("Contaminated"));
+        assert_eq!(log[0].changed_by, "manager");
+    }
+
+    #[tokio::test]
+    async fn test_custom_attributes_are_checked() {
+        use miso_domain::entities::AttributeType;
+
+        let (repository, service) = service_with_samples(&[1]);
+        let mut donor = AttributeDefinition::new(
+            AttributeTarget::Sample,
+            "donor_id".to_string(),
+            "Donor ID".to_string(),
+            AttributeType::Text,
+        );
+        donor.project_id = Some(1);
+        donor.required = true;
+        let service =
+            service.with_attribute_definitions(Arc::new(FixedDefinitions(vec![donor])));
+        let attributes = |value: &str| {
+            Some(BTreeMap::from([(
+                "donor_id".to_string(),
+                value.to_string(),
+            )]))
+        };
+        let request = |attributes| UpdateSampleRequest {
+            description: None,
+            volume_ul: None,
+            mass_ng: None,
+            concentration_ng_ul: None,
+            qc_status: None,
+            qc_reason: None,
+            attributes,
+            control_type: None,
+            subproject_id: None,
+        };
+
+        let missing = service
+            .update_sample(1, request(attributes(" ")), "tech", Role::Technician)
+            .await;
+        assert!(matches!(missing, Err(DomainError::Validation(_))));
+
+        let updated = service
+            .update_sample(1, request(attributes(" D-12 ")), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(updated.attributes["donor_id"], "D-12");
+
+        // Leaving attributes out keeps the stored values
+        service
+            .update_sample(1, request(None), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let stored = repository.samples.lock().unwrap()[&1].clone();
+        assert_eq!(stored.attributes["donor_id"], "D-12");
+    }
+
+    struct FixedSubprojects(Vec<miso_domain::entities::Subproject>);
+
+    #[async_trait]
+    impl SubprojectRepository for FixedSubprojects {
+        async fn find_by_id(
+            &self,
+            id: EntityId,
+        ) -> Result<Option<miso_domain::entities::Subproject>, DomainError> {
+            Ok(self.0.iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+        ) -> Result<Vec<miso_domain::entities::Subproject>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &miso_domain::entities::Subproject) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_samples_are_assigned_to_their_projects_subprojects() {
+        use miso_domain::entities::Subproject;
+
+        let (repository, service) = service_with_samples(&[1, 2]);
+        let move_to = |subproject_id| UpdateSampleRequest {
+            subproject_id: Some(subproject_id),
+            ..Default::default()
+        };
+
+        // Without subprojects, assignments are refused
+        assert!(matches!(
+            service.update_sample(1, move_to(1), "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+
+        let subproject = |id, project_id| {
+            let mut subproject =
+                Subproject::new(project_id, format!("COHORT-{}", id), None, "lm".to_string())
+                    .unwrap();
+            subproject.id = id;
+            subproject
+        };
+        let service = service.with_subprojects(Arc::new(FixedSubprojects(vec![
+            subproject(1, 1),
+            subproject(2, 2),
+        ])));
+
+        let updated = service
+            .update_sample(1, move_to(1), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert_eq!(updated.subproject_id, Some(1));
+        assert!(matches!(
+            service.update_sample(2, move_to(2), "tech", Role::Technician).await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service.update_sample(2, move_to(3), "tech", Role::Technician).await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        // A bulk update naming another project's subproject writes nothing
+        let bulk = service
+            .bulk_update_samples(
+                BulkUpdateSamplesRequest {
+                    updates: vec![
+                        BulkSampleUpdate { id: 1, version: 2, changes: move_to(1) },
+                        BulkSampleUpdate { id: 2, version: 1, changes: move_to(2) },
+                    ],
+                },
+                "tech",
+                Role::Technician,
+            )
+            .await
+            .unwrap();
+        assert!(!bulk.applied);
+        assert_eq!(bulk.results[1].status, BulkUpdateRowStatus::Invalid);
+        assert_eq!(repository.samples.lock().unwrap()[&2].subproject_id, None);
+
+        let listed = service
+            .list_samples_by_subproject(1, Some(1), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(listed.iter().map(|s| s.id).collect::<Vec<_>>(), vec![1]);
+        assert!(service
+            .list_samples_by_subproject(1, Some(2), None, None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_similar_identities_need_confirmation() {
+        let (_, service) = service_with_samples(&[]);
+        let service = service.with_possible_duplicates(Arc::new(InMemoryDuplicates::default()));
+        let request = |external_name: &str, create_anyway| CreateIdentityRequest {
+            name: "PAT".to_string(),
+            project_id: 1,
+            external_name: external_name.to_string(),
+            description: None,
+            create_anyway,
+            subproject_id: None,
+        };
+
+        let first = service
+            .create_identity(request("MRN-00123456", false), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let first = first.identity.unwrap();
+        assert_eq!(first.sample_class, "Identity");
+        assert_eq!(first.external_name.as_deref(), Some("MRN-00123456"));
+
+        // A typo is caught, and nothing is created
+        let refused = service
+            .create_identity(request("mrn 00123465", false), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(refused.identity.is_none());
+        assert_eq!(refused.matches.len(), 1);
+        assert_eq!(refused.matches[0].identity_id, first.id);
+        assert!(service.list_possible_duplicates().await.unwrap().is_empty());
+
+        // Confirmed, it is created and kept for review
+        let confirmed = service
+            .create_identity(request("mrn 00123465", true), "tech", Role::Technician)
+            .await
+            .unwrap();
+        let second = confirmed.identity.unwrap();
+        assert_ne!(second.id, first.id);
+        let pending = service.list_possible_duplicates().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].identity_id, second.id);
+        assert_eq!(pending[0].match_id, first.id);
+        assert_eq!(pending[0].confirmed_by, "tech");
+
+        let resolve = |resolution: &str| ResolveDuplicateRequest {
+            resolution: resolution.to_string(),
+        };
+        assert!(service
+            .resolve_possible_duplicate(pending[0].id, resolve("merged"), "manager")
+            .await
+            .is_err());
+        let resolved = service
+            .resolve_possible_duplicate(pending[0].id, resolve("distinct"), "manager")
+            .await
+            .unwrap();
+        assert_eq!(resolved.resolution.as_deref(), Some("distinct"));
+        assert!(service.list_possible_duplicates().await.unwrap().is_empty());
+
+        // Unrelated names don't need confirmation
+        let other = service
+            .create_identity(request("MRN-00999999", false), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(other.identity.is_some());
+        assert!(other.matches.is_empty());
+    }
+
+    /// Erasures of the given name hashes.
+    struct FixedErasures(Vec<String>);
+
+    #[async_trait]
+    impl ErasureRepository for FixedErasures {
+        async fn list(&self) -> Result<Vec<miso_domain::entities::Erasure>, DomainError> {
+            unimplemented!()
+        }
+        async fn is_erased(&self, name_hashes: &[String]) -> Result<bool, DomainError> {
+            Ok(name_hashes.iter().any(|hash| self.0.contains(hash)))
+        }
+        async fn save(&self, _: &miso_domain::entities::Erasure) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_erased_external_names_are_refused() {
+        let (_, service) = service_with_samples(&[]);
+        let service = service.with_erasures(Arc::new(FixedErasures(hash_external_names(
+            "MRN-00123456",
+        ))));
+        let request = |external_name: &str| CreateIdentityRequest {
+            name: "PAT".to_string(),
+            project_id: 1,
+            external_name: external_name.to_string(),
+            description: None,
+            create_anyway: true,
+            subproject_id: None,
+        };
+
+        // However it is written, and even alongside another name
+        assert!(matches!(
+            service
+                .create_identity(request("mrn 00123456"), "tech", Role::Technician)
+                .await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            service
+                .create_identity(request("MRN-00999999, MRN-00123456"), "tech", Role::Technician)
+                .await,
+            Err(DomainError::Validation(_))
+        ));
+        assert!(service
+            .create_identity(request("MRN-00999999"), "tech", Role::Technician)
+            .await
+            .unwrap()
+            .identity
+            .is_some());
+    }
+
+    #[derive(Default)]
+    struct InMemoryCompositions {
+        compositions: Mutex<Vec<SampleComposition>>,
+    }
+
+    #[async_trait]
+    impl SampleCompositionRepository for InMemoryCompositions {
+        async fn find_by_samples(
+            &self,
+            sample_ids: &[EntityId],
+        ) -> Result<Vec<SampleComposition>, DomainError> {
+            let compositions = self.compositions.lock().unwrap();
+            Ok(compositions
+                .iter()
+                .filter(|c| sample_ids.contains(&c.sample_id))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_identity(
+            &self,
+            identity_id: EntityId,
+        ) -> Result<Vec<SampleComposition>, DomainError> {
+            let compositions = self.compositions.lock().unwrap();
+            Ok(compositions
+                .iter()
+                .filter(|c| c.identity_ids.contains(&identity_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, composition: &SampleComposition) -> Result<(), DomainError> {
+            let mut compositions = self.compositions.lock().unwrap();
+            compositions.retain(|c| c.sample_id != composition.sample_id);
+            compositions.push(composition.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pooled_samples_trace_back_to_each_identity() {
+        let (repository, service) = service_with_samples(&[1]);
+        for id in [2, 3] {
+            let identity = Sample::new_identity(
+                id,
+                format!("PAT_{}", id),
+                Barcode::new_unchecked(format!("SAM-{}", id)),
+                1,
+                format!("MRN-{}", id),
+                "tech".to_string(),
+            );
+            repository.samples.lock().unwrap().insert(id, identity);
+        }
+        let request = |identity_ids: Vec<EntityId>| CreatePooledSampleRequest {
+            name: "SOIL_POOL".to_string(),
+            project_id: 1,
+            identity_ids,
+            tissue_origin: Some("Soil".to_string()),
+            tissue_type: None,
+            description: None,
+        };
+
+        // Pooling needs compositions, known identities and no plain samples
+        assert!(service
+            .create_pooled_sample(request(vec![2, 3]), "tech", Role::Technician)
+            .await
+            .is_err());
+        let service = service.with_compositions(Arc::new(InMemoryCompositions::default()));
+        assert!(matches!(
+            service
+                .create_pooled_sample(request(vec![2, 9]), "tech", Role::Technician)
+                .await,
+            Err(DomainError::NotFound { .. })
+        ));
+        assert!(service
+            .create_pooled_sample(request(vec![1, 2]), "tech", Role::Technician)
+            .await
+            .is_err());
+        assert_eq!(repository.samples.lock().unwrap().len(), 3);
+
+        let pooled = service
+            .create_pooled_sample(request(vec![3, 2]), "tech", Role::Technician)
+            .await
+            .unwrap();
+        assert!(pooled.pooled);
+        assert_eq!(pooled.sample_class, "Tissue");
+        assert_eq!(pooled.parent_id, None);
+
+        // A stock extracted from the pool
+        let mut stock = Sample::new_pooled(
+            0,
+            "SOIL_POOL_STOCK".to_string(),
+            Barcode::new_unchecked("SAM-STOCK".to_string()),
+            1,
+            "tech".to_string(),
+        );
+        if let SampleDetails::Detailed(details) = &mut stock.details {
+            details.sample_class = SampleClass::Stock;
+            details.parent_id = Some(pooled.id);
+            details.pooled = false;
+        }
+        let stock_id = repository.save(&stock).await.unwrap();
+
+        let lineage = service.get_sample_lineage(stock_id, None).await.unwrap();
+        let ids = |samples: &[SampleSummary]| samples.iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(&lineage.ancestors), vec![pooled.id]);
+        assert_eq!(ids(&lineage.identities), vec![3, 2]);
+        assert!(lineage.descendants.is_empty());
+
+        let lineage = service.get_sample_lineage(2, None).await.unwrap();
+        assert!(lineage.ancestors.is_empty());
+        assert_eq!(ids(&lineage.identities), vec![2]);
+        assert_eq!(ids(&lineage.descendants), vec![pooled.id, stock_id]);
+    }
+}
@@ -0,0 +1,511 @@
+//! Sample sheet import service for runs planned outside the LIMS.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, InstrumentModel, Library, Pool, PoolElement, Run};
+use miso_domain::errors::{DomainError, RunError};
+use miso_domain::repositories::{
+    InstrumentModelRepository, LibraryRepository, PoolRepository, RunRepository,
+    SequencerRepository,
+};
+use miso_domain::value_objects::Barcode;
+use tracing::{info, instrument};
+
+use crate::dto::{ImportSampleSheetRequest, ImportedPool, SampleSheetImportResponse, UnmatchedRow};
+use crate::importers::{parse_sample_sheet, SampleSheetRow};
+
+/// Service that turns an Illumina sample sheet into a run with pooled lanes.
+pub struct SampleSheetImportService<L, P, R, S, M>
+where
+    L: LibraryRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    M: InstrumentModelRepository + ?Sized,
+{
+    libraries: Arc<L>,
+    pools: Arc<P>,
+    runs: Arc<R>,
+    sequencers: Arc<S>,
+    instrument_models: Arc<M>,
+}
+
+impl<L, P, R, S, M> SampleSheetImportService<L, P, R, S, M>
+where
+    L: LibraryRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    R: RunRepository + ?Sized,
+    S: SequencerRepository + ?Sized,
+    M: InstrumentModelRepository + ?Sized,
+{
+    /// Creates a new import service.
+    pub fn new(
+        libraries: Arc<L>,
+        pools: Arc<P>,
+        runs: Arc<R>,
+        sequencers: Arc<S>,
+        instrument_models: Arc<M>,
+    ) -> Self {
+        Self {
+            libraries,
+            pools,
+            runs,
+            sequencers,
+            instrument_models,
+        }
+    }
+
+    /// Imports a sample sheet, creating a new run.
+    ///
+    /// Each row is matched to an existing library by barcode or name and its
+    /// index is verified. Matched libraries are grouped per lane; a lane
+    /// reuses an existing pool holding exactly the same libraries, otherwise
+    /// a new pool is created. Rows that can't be matched are reported back
+    /// for manual resolution rather than failing the import.
+    ///
+    /// A container barcode must come with its container model, which the
+    /// sequencer's instrument model must accept.
+    #[instrument(skip(self, request), fields(run_name = %request.run_name))]
+    pub async fn import(
+        &self,
+        request: ImportSampleSheetRequest,
+        created_by: &str,
+    ) -> Result<SampleSheetImportResponse, DomainError> {
+        let sheet = parse_sample_sheet(&request.contents)?;
+
+        if self.runs.find_by_name(&request.run_name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Run".to_string(),
+                field: "name".to_string(),
+                value: request.run_name,
+            });
+        }
+
+        let mut lanes: BTreeMap<u8, Vec<Library>> = BTreeMap::new();
+        let mut unmatched = Vec::new();
+        let mut matched_rows = 0;
+
+        for row in &sheet.rows {
+            match self.match_row(row).await? {
+                Ok(library) => {
+                    let lane = lanes.entry(row.lane.unwrap_or(1)).or_default();
+                    if !lane.iter().any(|l| l.id == library.id) {
+                        lane.push(library);
+                    }
+                    matched_rows += 1;
+                }
+                Err(reason) => unmatched.push(UnmatchedRow {
+                    line: row.line,
+                    lane: row.lane,
+                    sample_id: row.sample_id.clone(),
+                    index: row.index.clone(),
+                    index2: row.index2.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        let partitions = request
+            .partitions
+            .unwrap_or(1)
+            .max(sheet.max_lane().unwrap_or(1));
+
+        let mut run = Run::new(
+            0,
+            request.run_name.clone(),
+            request.sequencer_id,
+            partitions,
+            created_by.to_string(),
+        );
+        run.read_length = sheet.read_length();
+        if let Some(barcode) = request.container_barcode {
+            let container_model = request.container_model.as_deref().ok_or_else(|| {
+                DomainError::Validation(
+                    "A container model is required with a container barcode".to_string(),
+                )
+            })?;
+            let instrument = self.instrument_model_for(request.sequencer_id).await?;
+            run.assign_container(barcode, container_model, &instrument)?;
+        }
+
+        let mut pools = Vec::new();
+        for (lane, libraries) in lanes {
+            let imported = self
+                .pool_for_lane(&request.run_name, lane, &libraries, created_by)
+                .await?;
+
+            if let Some(partition) = run.get_partition_mut(lane) {
+                partition.pool_id = Some(imported.pool_id);
+            }
+            pools.push(imported);
+        }
+
+        run.id = self.runs.save(&run).await?;
+
+        info!(
+            "Imported sample sheet as run {} (ID: {}): {} rows matched, {} unmatched",
+            run.name,
+            run.id,
+            matched_rows,
+            unmatched.len()
+        );
+
+        Ok(SampleSheetImportResponse {
+            run_id: run.id,
+            run_name: run.name,
+            pools,
+            matched_rows,
+            unmatched,
+        })
+    }
+
+    /// Looks up the catalogued instrument model of a sequencer.
+    async fn instrument_model_for(
+        &self,
+        sequencer_id: EntityId,
+    ) -> Result<InstrumentModel, DomainError> {
+        let sequencer = self
+            .sequencers
+            .find_by_id(sequencer_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sequencer".to_string(),
+                id: sequencer_id.to_string(),
+            })?;
+
+        self.instrument_models
+            .find_by_name(&sequencer.model.name)
+            .await?
+            .ok_or_else(|| {
+                RunError::InvalidSequencer(format!(
+                    "{} is not a catalogued instrument model",
+                    sequencer.model.name
+                ))
+                .into()
+            })
+    }
+
+    /// Finds the library for a row, or explains why none matched.
+    async fn match_row(
+        &self,
+        row: &SampleSheetRow,
+    ) -> Result<Result<Library, String>, DomainError> {
+        let mut library = self.libraries.find_by_barcode(&row.sample_id).await?;
+        if library.is_none() {
+            library = self.libraries.find_by_name(&row.sample_id).await?;
+        }
+        if library.is_none() {
+            if let Some(name) = &row.sample_name {
+                library = self.libraries.find_by_name(name).await?;
+            }
+        }
+
+        let Some(library) = library else {
+            return Ok(Err("No library with this barcode or name".to_string()));
+        };
+
+        match (&library.index, &row.index) {
+            (Some(index), Some(i7)) if !index.matches_sequences(i7, row.index2.as_deref()) => {
+                Ok(Err(format!(
+                    "Index does not match library {} ({})",
+                    library.name, index
+                )))
+            }
+            (None, Some(_)) => Ok(Err(format!(
+                "Library {} has no index assigned",
+                library.name
+            ))),
+            _ => Ok(Ok(library)),
+        }
+    }
+
+    /// Reuses or creates the pool for a lane.
+    async fn pool_for_lane(
+        &self,
+        run_name: &str,
+        lane: u8,
+        libraries: &[Library],
+        created_by: &str,
+    ) -> Result<ImportedPool, DomainError> {
+        let wanted: BTreeSet<EntityId> = libraries.iter().map(|l| l.id).collect();
+
+        let candidates = self.pools.find_by_library(libraries[0].id).await?;
+        if let Some(existing) = candidates
+            .into_iter()
+            .find(|p| p.library_ids().into_iter().collect::<BTreeSet<_>>() == wanted)
+        {
+            return Ok(ImportedPool {
+                lane,
+                pool_id: existing.id,
+                pool_name: existing.name,
+                created: false,
+                library_ids: wanted.into_iter().collect(),
+            });
+        }
+
+        let name: String = format!("{}_L{}", run_name, lane)
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let mut pool = Pool::new(
+            0,
+            name.clone(),
+            Barcode::new(name.clone())?,
+            "Illumina".to_string(),
+            created_by.to_string(),
+        );
+        for library in libraries {
+            // Sample sheets don't identify aliquots, so none is recorded
+            pool.add_element(PoolElement {
+                library_aliquot_id: 0,
+                library_id: library.id,
+                volume: None,
+                proportion: None,
+            })?;
+        }
+
+        let pool_id = self.pools.save(&pool).await?;
+
+        Ok(ImportedPool {
+            lane,
+            pool_id,
+            pool_name: name,
+            created: true,
+            library_ids: wanted.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{LibraryDesign, LibraryType, RunStatus, Sample, Sequencer};
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    /// Libraries LIB1 and LIB2, and the pools and runs imported so far.
+    #[derive(Default)]
+    struct Lab {
+        pools: Mutex<Vec<Pool>>,
+        runs: Mutex<Vec<Run>>,
+    }
+
+    fn library(id: EntityId) -> Library {
+        Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        )
+    }
+
+    #[async_trait]
+    impl LibraryRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Library>, DomainError> {
+            Ok([library(1), library(2)]
+                .into_iter()
+                .find(|l| l.barcode.as_str() == barcode))
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Library>, DomainError> {
+            Ok([library(1), library(2)].into_iter().find(|l| l.name == name))
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl PoolRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_library(&self, library_id: EntityId) -> Result<Vec<Pool>, DomainError> {
+            let pools = self.pools.lock().unwrap();
+            Ok(pools
+                .iter()
+                .filter(|p| p.library_ids().contains(&library_id))
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, pool: &Pool) -> Result<EntityId, DomainError> {
+            let mut pools = self.pools.lock().unwrap();
+            let mut stored = pool.clone();
+            stored.id = pools.len() as EntityId + 1;
+            pools.push(stored);
+            Ok(pools.len() as EntityId)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl RunRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Run>, DomainError> {
+            Ok(self.runs.lock().unwrap().iter().find(|r| r.name == name).cloned())
+        }
+        async fn find_by_sequencer(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_status(&self, _: RunStatus) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_pool(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, run: &Run) -> Result<EntityId, DomainError> {
+            let mut runs = self.runs.lock().unwrap();
+            let mut stored = run.clone();
+            stored.id = runs.len() as EntityId + 1;
+            runs.push(stored);
+            Ok(runs.len() as EntityId)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SequencerRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sequencer) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl InstrumentModelRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &InstrumentModel) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn request(run_name: &str) -> ImportSampleSheetRequest {
+        ImportSampleSheetRequest {
+            contents: "[Data]\nLane,Sample_ID\n1,LIB-1\n1,LIB2\n2,LIB-9\n".to_string(),
+            run_name: run_name.to_string(),
+            sequencer_id: 1,
+            partitions: None,
+            container_barcode: None,
+            container_model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_pools_matched_rows_per_lane() {
+        let lab = Arc::new(Lab::default());
+        let service = SampleSheetImportService::new(
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+        );
+
+        let imported = service.import(request("RUN1"), "tech").await.unwrap();
+        assert_eq!(imported.matched_rows, 2);
+        assert_eq!(imported.unmatched.len(), 1);
+        assert_eq!(imported.unmatched[0].sample_id, "LIB-9");
+        assert_eq!(imported.pools.len(), 1);
+        assert!(imported.pools[0].created);
+        assert_eq!(imported.pools[0].library_ids, [1, 2]);
+        {
+            let runs = lab.runs.lock().unwrap();
+            assert_eq!(runs[0].partitions.len(), 2);
+            assert_eq!(runs[0].partitions[0].pool_id, Some(imported.pools[0].pool_id));
+            assert_eq!(runs[0].partitions[1].pool_id, None);
+        }
+
+        // The same libraries on a later run reuse their pool
+        let again = service.import(request("RUN2"), "tech").await.unwrap();
+        assert!(!again.pools[0].created);
+        assert_eq!(again.pools[0].pool_id, imported.pools[0].pool_id);
+        assert!(matches!(
+            service.import(request("RUN1"), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+    }
+}
@@ -0,0 +1,833 @@
+//! Sample sheet generation for planned runs.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{EntityId, InstrumentModel, Platform, Project, Run};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    LibraryRepository, PanelRepository, PoolRepository, ProjectRepository,
+    ReferenceGenomeRepository, RunRepository, SequencerRepository,
+};
+use miso_domain::run_planning::RunPlanner;
+use miso_domain::services::{CarryoverChecker, PlannedLane};
+use miso_domain::value_objects::DnaIndex;
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    ExportFile, MarkReadyToLoadRequest, RunCarryoverResponse, RunResponse, SampleSheetFilter,
+    SampleSheetFormat,
+};
+use crate::exporters::{
+    minknow_barcode, render_minknow_sample_sheet, render_sample_sheet, render_sample_sheet_v2,
+    sample_sheet_reads, MinKnowRow,
+};
+use crate::importers::{SampleSheet, SampleSheetRow};
+
+/// Service that writes Illumina and Oxford Nanopore sample sheets for runs,
+/// optionally restricted to one lane or one project, and sends them to
+/// sequencers when runs are ready to load.
+pub struct SampleSheetService<R, P, L, J>
+where
+    R: RunRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    runs: Arc<R>,
+    pools: Arc<P>,
+    libraries: Arc<L>,
+    projects: Arc<J>,
+    panels: Option<Arc<dyn PanelRepository>>,
+    genomes: Option<Arc<dyn ReferenceGenomeRepository>>,
+    sequencers: Option<Arc<dyn SequencerRepository>>,
+    planner: Option<Arc<dyn RunPlanner>>,
+    audit: AuditTrail,
+}
+
+impl<R, P, L, J> SampleSheetService<R, P, L, J>
+where
+    R: RunRepository + ?Sized,
+    P: PoolRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+    J: ProjectRepository + ?Sized,
+{
+    /// Creates a new sample sheet service.
+    pub fn new(runs: Arc<R>, pools: Arc<P>, libraries: Arc<L>, projects: Arc<J>) -> Self {
+        Self {
+            runs,
+            pools,
+            libraries,
+            projects,
+            panels: None,
+            genomes: None,
+            sequencers: None,
+            planner: None,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Describes targeted panel libraries' rows by the panel version they
+    /// captured.
+    pub fn with_panels(mut self, panels: Arc<dyn PanelRepository>) -> Self {
+        self.panels = Some(panels);
+        self
+    }
+
+    /// Gives each row the reference genome its library is analysed against.
+    pub fn with_reference_genomes(mut self, genomes: Arc<dyn ReferenceGenomeRepository>) -> Self {
+        self.genomes = Some(genomes);
+        self
+    }
+
+    /// Writes i5 as the run's sequencer reads it in v1 sheets, and tells
+    /// whether the run's flow cell is patterned.
+    pub fn with_sequencers(mut self, sequencers: Arc<dyn SequencerRepository>) -> Self {
+        self.sequencers = Some(sequencers);
+        self
+    }
+
+    /// Sends runs' sample sheets to their sequencers when they're marked
+    /// ready to load.
+    pub fn with_run_planner(mut self, planner: Arc<dyn RunPlanner>) -> Self {
+        self.planner = Some(planner);
+        self
+    }
+
+    /// Records runs being marked ready to load in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Marks a run ready to load, sending its whole sample sheet to its
+    /// sequencer if a run planner is configured.
+    ///
+    /// The run is only marked once the sequencer has accepted the sheet,
+    /// so a failed push can simply be retried. Marking a run again sends
+    /// its sheet again, e.g. after a pool has been swapped. Index pairs
+    /// that could be carried over are logged as warnings but don't stop
+    /// the run being marked.
+    #[instrument(skip(self))]
+    pub async fn mark_ready_to_load(
+        &self,
+        run_id: EntityId,
+        request: MarkReadyToLoadRequest,
+        marked_by: &str,
+    ) -> Result<RunResponse, DomainError> {
+        let mut run = self
+            .runs
+            .find_by_id(run_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Run".to_string(),
+                id: run_id.to_string(),
+            })?;
+        let before = run.clone();
+        run.mark_ready_to_load(Utc::now())?;
+
+        if let Some(planner) = &self.planner {
+            let sequencer = match &self.sequencers {
+                Some(sequencers) => sequencers.find_by_id(run.sequencer_id).await?,
+                None => None,
+            }
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sequencer".to_string(),
+                id: run.sequencer_id.to_string(),
+            })?;
+            let format = request.format.unwrap_or(
+                if sequencer.model.platform == Platform::OxfordNanopore {
+                    SampleSheetFormat::MinKnow
+                } else {
+                    SampleSheetFormat::V1
+                },
+            );
+
+            let sheet = self
+                .generate(run_id, SampleSheetFilter::default(), format)
+                .await?;
+            planner
+                .push_sample_sheet(&sequencer, &sheet.file_name, &sheet.body)
+                .await?;
+        }
+
+        self.runs.save(&run).await?;
+        self.audit
+            .updated("Run", run.id, &before, &run, marked_by)
+            .await?;
+
+        for warning in self.check_carryover(run_id).await?.warnings {
+            warn!("{}", warning.message);
+        }
+        info!("Marked run {} ready to load (ID: {})", run.name, run.id);
+
+        Ok(run.into())
+    }
+
+    /// Checks a run for libraries whose index pair was sequenced in an
+    /// earlier lane, of the run itself or of the run before it on the same
+    /// sequencer, so that carry-over between them can be planned around.
+    ///
+    /// The run before it is the last on the sequencer to have started
+    /// before this one did, or before now if this one hasn't started.
+    #[instrument(skip(self))]
+    pub async fn check_carryover(
+        &self,
+        run_id: EntityId,
+    ) -> Result<RunCarryoverResponse, DomainError> {
+        let run = self
+            .runs
+            .find_by_id(run_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Run".to_string(),
+                id: run_id.to_string(),
+            })?;
+
+        let started = run.started_at.unwrap_or_else(Utc::now);
+        let previous = self
+            .runs
+            .find_by_sequencer(run.sequencer_id)
+            .await?
+            .into_iter()
+            .filter(|other| other.id != run.id && other.started_at.is_some_and(|at| at < started))
+            .max_by_key(|other| other.started_at);
+
+        let lanes = self.planned_lanes(&run).await?;
+        let previous_lanes = match &previous {
+            Some(previous) => self.planned_lanes(previous).await?,
+            None => Vec::new(),
+        };
+
+        Ok(RunCarryoverResponse {
+            run: run.name,
+            previous_run: previous.map(|p| p.name),
+            warnings: CarryoverChecker::check(&lanes, &previous_lanes)
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        })
+    }
+
+    /// Generates the sample sheet for a run.
+    ///
+    /// Each library in each lane's pool becomes a row, identified by its
+    /// barcode so that the sheet can be imported back, described by its
+    /// targeted panel's identifier if it has one, and given its own
+    /// reference genome or else its project's. The filter narrows the
+    /// sheet to one lane and/or one project; lanes keep their numbers.
+    ///
+    /// bcl2fastq matches i5 as the instrument read it, so v1 sheets for
+    /// sequencers that read its reverse complement carry the reverse
+    /// complement. BCL Convert works the orientation out itself, so v2
+    /// sheets always carry i5 as the adapter has it.
+    ///
+    /// Each lane's pool is assessed for index hopping on the run's flow
+    /// cell, taken to be patterned unless its model is known not to be,
+    /// and the warnings are returned with the sheet.
+    ///
+    /// MinKNOW sheets are written for Oxford Nanopore runs instead; see
+    /// [`Self::minknow_sheet`].
+    #[instrument(skip(self))]
+    pub async fn generate(
+        &self,
+        run_id: EntityId,
+        filter: SampleSheetFilter,
+        format: SampleSheetFormat,
+    ) -> Result<ExportFile, DomainError> {
+        let run = self
+            .runs
+            .find_by_id(run_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Run".to_string(),
+                id: run_id.to_string(),
+            })?;
+
+        let project =
+            match filter.project_id {
+                Some(id) => Some(self.projects.find_by_id(id).await?.ok_or_else(|| {
+                    DomainError::NotFound {
+                        entity_type: "Project".to_string(),
+                        id: id.to_string(),
+                    }
+                })?),
+                None => None,
+            };
+
+        if let Some(lane) = filter.lane {
+            if run.get_partition(lane).is_none() {
+                return Err(DomainError::Validation(format!(
+                    "Run {} has no lane {}",
+                    run.name, lane
+                )));
+            }
+        }
+
+        let instrument = match &self.sequencers {
+            Some(sequencers) => sequencers
+                .find_by_id(run.sequencer_id)
+                .await?
+                .map(|s| s.model),
+            None => None,
+        };
+        if format == SampleSheetFormat::MinKnow {
+            return self
+                .minknow_sheet(&run, &filter, project.as_ref(), instrument.as_ref())
+                .await;
+        }
+
+        let reverse_complement_i5 = format == SampleSheetFormat::V1
+            && instrument
+                .as_ref()
+                .is_some_and(|m| m.reads_i5_reverse_complement());
+
+        let mut sheet = SampleSheet::default();
+        if format == SampleSheetFormat::V2 {
+            sheet
+                .header
+                .insert("FileFormatVersion".to_string(), "2".to_string());
+            sheet.header.insert("RunName".to_string(), run.name.clone());
+            if let Some(instrument) = &instrument {
+                sheet
+                    .header
+                    .insert("InstrumentType".to_string(), instrument.name.clone());
+            }
+        } else {
+            sheet
+                .header
+                .insert("IEMFileVersion".to_string(), "5".to_string());
+            sheet
+                .header
+                .insert("Experiment Name".to_string(), run.name.clone());
+        }
+        sheet.reads = run
+            .read_length
+            .as_deref()
+            .map(sample_sheet_reads)
+            .unwrap_or_default();
+
+        let mut projects: HashMap<EntityId, Project> = HashMap::new();
+        let mut panel_identifiers: HashMap<EntityId, String> = HashMap::new();
+        let mut genome_names: HashMap<EntityId, String> = HashMap::new();
+        if let Some(project) = &project {
+            projects.insert(project.id, project.clone());
+        }
+
+        // Without the flow cell's model, assume the worst case, a patterned one
+        let patterned = match (&instrument, &run.container_model) {
+            (Some(instrument), Some(container_model)) => instrument.is_patterned(container_model),
+            _ => true,
+        };
+        let mut warnings = Vec::new();
+
+        for partition in &run.partitions {
+            if filter.lane.is_some_and(|l| l != partition.partition_number) {
+                continue;
+            }
+            let Some(pool_id) = partition.pool_id else {
+                continue;
+            };
+            let Some(pool) = self.pools.find_by_id(pool_id).await? else {
+                continue;
+            };
+
+            let library_ids: Vec<EntityId> = pool.elements.iter().map(|e| e.library_id).collect();
+            let mut libraries = self.libraries.find_by_ids(&library_ids).await?;
+            // Reads hop between every library in the lane, not just those on the sheet
+            warnings.extend(
+                pool.assess_index_hopping(&libraries, patterned)
+                    .warnings()
+                    .into_iter()
+                    .map(|w| format!("Lane {}: {}", partition.partition_number, w)),
+            );
+            libraries.retain(|l| filter.project_id.is_none_or(|p| l.project_id == p));
+            libraries.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for library in libraries {
+                if !projects.contains_key(&library.project_id) {
+                    if let Some(project) = self.projects.find_by_id(library.project_id).await? {
+                        projects.insert(project.id, project);
+                    }
+                }
+                let library_project = projects.get(&library.project_id);
+
+                let genome_id = library
+                    .reference_genome_id
+                    .or(library_project.and_then(|p| p.reference_genome_id));
+                let reference_genome = match genome_id {
+                    Some(id) => self.genome_name(id, &mut genome_names).await?,
+                    None => None,
+                };
+
+                let panel = match library.panel_id {
+                    Some(id) => self.panel_identifier(id, &mut panel_identifiers).await?,
+                    None => None,
+                };
+
+                sheet.rows.push(SampleSheetRow {
+                    line: 0,
+                    lane: Some(partition.partition_number),
+                    sample_id: library.barcode.to_string(),
+                    sample_name: Some(library.name.clone()),
+                    index: library.index.as_ref().map(|i| i.i7().to_string()),
+                    index2: library.index.as_ref().and_then(|i| i.i5()).map(|i5| {
+                        if reverse_complement_i5 {
+                            DnaIndex::reverse_complement(i5)
+                        } else {
+                            i5.to_string()
+                        }
+                    }),
+                    project: library_project.map(|p| p.code.clone()),
+                    description: panel,
+                    reference_genome,
+                });
+            }
+        }
+
+        if sheet.rows.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "No libraries on run {} match the requested lane and project",
+                run.name
+            )));
+        }
+
+        info!(
+            "Generated sample sheet for run {} with {} rows",
+            run.name,
+            sheet.rows.len()
+        );
+
+        Ok(ExportFile {
+            file_name: file_name(&run, filter.lane, project.as_ref()),
+            content_type: "text/csv".to_string(),
+            body: if format == SampleSheetFormat::V2 {
+                render_sample_sheet_v2(&sheet)
+            } else {
+                render_sample_sheet(&sheet)
+            },
+            warnings,
+        })
+    }
+
+    /// Generates the MinKNOW sample sheet for an Oxford Nanopore run, so
+    /// that it can be started without retyping metadata.
+    ///
+    /// The run's container barcode is the flow cell ID, its name the
+    /// experiment ID and the loaded pool's name the sample ID. All the
+    /// libraries must share a kit, which MinKNOW is started with. A pool of
+    /// several libraries is barcoded: each library's index name gives its
+    /// MinKNOW barcode, and its barcode is the alias the reads are named by.
+    async fn minknow_sheet(
+        &self,
+        run: &Run,
+        filter: &SampleSheetFilter,
+        project: Option<&Project>,
+        instrument: Option<&InstrumentModel>,
+    ) -> Result<ExportFile, DomainError> {
+        if let Some(instrument) = instrument {
+            if instrument.platform != Platform::OxfordNanopore {
+                return Err(DomainError::Validation(format!(
+                    "Run {} is on a {}, not an Oxford Nanopore sequencer",
+                    run.name, instrument.name
+                )));
+            }
+        }
+        let flow_cell_id = run.container_barcode.clone().ok_or_else(|| {
+            DomainError::Validation(format!("Run {} has no flow cell ID", run.name))
+        })?;
+
+        let pool_ids: Vec<EntityId> = run
+            .partitions
+            .iter()
+            .filter(|p| filter.lane.is_none_or(|l| l == p.partition_number))
+            .filter_map(|p| p.pool_id)
+            .collect();
+        let pool_id = match pool_ids.as_slice() {
+            [] => {
+                return Err(DomainError::Validation(format!(
+                    "Run {} has no pool loaded",
+                    run.name
+                )))
+            }
+            [pool_id] => *pool_id,
+            _ => {
+                return Err(DomainError::Validation(format!(
+                    "Run {} has more than one pool loaded; choose a position",
+                    run.name
+                )))
+            }
+        };
+        let pool = self
+            .pools
+            .find_by_id(pool_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Pool".to_string(),
+                id: pool_id.to_string(),
+            })?;
+
+        let library_ids: Vec<EntityId> = pool.elements.iter().map(|e| e.library_id).collect();
+        let mut libraries = self.libraries.find_by_ids(&library_ids).await?;
+        let barcoded = libraries.len() > 1;
+        libraries.retain(|l| filter.project_id.is_none_or(|p| l.project_id == p));
+        libraries.sort_by(|a, b| a.name.cmp(&b.name));
+        if libraries.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "No libraries on run {} match the requested lane and project",
+                run.name
+            )));
+        }
+
+        let mut kit: Option<&str> = None;
+        let mut barcodes = HashSet::new();
+        let mut rows = Vec::new();
+        for library in &libraries {
+            let library_kit = library.kit_name.as_deref().ok_or_else(|| {
+                DomainError::Validation(format!("Library {} has no kit", library.name))
+            })?;
+            match kit {
+                Some(kit) if kit != library_kit => {
+                    return Err(DomainError::Validation(format!(
+                        "Libraries on run {} were prepared with both {} and {}",
+                        run.name, kit, library_kit
+                    )))
+                }
+                _ => kit = Some(library_kit),
+            }
+
+            let barcode = if barcoded {
+                let barcode = library
+                    .index
+                    .as_ref()
+                    .and_then(|i| minknow_barcode(i.name()))
+                    .ok_or_else(|| {
+                        DomainError::Validation(format!(
+                            "Library {} has no Oxford Nanopore barcode",
+                            library.name
+                        ))
+                    })?;
+                if !barcodes.insert(barcode.clone()) {
+                    return Err(DomainError::Validation(format!(
+                        "More than one library on run {} has {}",
+                        run.name, barcode
+                    )));
+                }
+                Some(barcode)
+            } else {
+                None
+            };
+
+            rows.push(MinKnowRow {
+                flow_cell_id: flow_cell_id.clone(),
+                kit: library_kit.to_string(),
+                experiment_id: run.name.clone(),
+                sample_id: pool.name.clone(),
+                alias: barcode.as_ref().map(|_| library.barcode.to_string()),
+                barcode,
+            });
+        }
+
+        info!(
+            "Generated MinKNOW sample sheet for run {} with {} rows",
+            run.name,
+            rows.len()
+        );
+
+        Ok(ExportFile {
+            file_name: file_name(run, filter.lane, project),
+            content_type: "text/csv".to_string(),
+            body: render_minknow_sample_sheet(&rows),
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Lists the libraries loaded in each lane that has a pool.
+    async fn planned_lanes(&self, run: &Run) -> Result<Vec<PlannedLane>, DomainError> {
+        let mut lanes = Vec::new();
+        for partition in &run.partitions {
+            let Some(pool_id) = partition.pool_id else {
+                continue;
+            };
+            let Some(pool) = self.pools.find_by_id(pool_id).await? else {
+                continue;
+            };
+            lanes.push(PlannedLane {
+                run: run.name.clone(),
+                lane: partition.partition_number,
+                libraries: self.libraries.find_by_ids(&pool.library_ids()).await?,
+            });
+        }
+        Ok(lanes)
+    }
+
+    /// Looks up a panel version's identifier, remembering those already
+    /// looked up.
+    async fn panel_identifier(
+        &self,
+        id: EntityId,
+        identifiers: &mut HashMap<EntityId, String>,
+    ) -> Result<Option<String>, DomainError> {
+        if let Some(identifier) = identifiers.get(&id) {
+            return Ok(Some(identifier.clone()));
+        }
+        let Some(panels) = &self.panels else {
+            return Ok(None);
+        };
+
+        let identifier = panels.find_by_id(id).await?.map(|p| p.identifier());
+        if let Some(identifier) = &identifier {
+            identifiers.insert(id, identifier.clone());
+        }
+        Ok(identifier)
+    }
+
+    /// Looks up a reference genome's name, remembering those already looked
+    /// up.
+    async fn genome_name(
+        &self,
+        id: EntityId,
+        names: &mut HashMap<EntityId, String>,
+    ) -> Result<Option<String>, DomainError> {
+        if let Some(name) = names.get(&id) {
+            return Ok(Some(name.clone()));
+        }
+        let Some(genomes) = &self.genomes else {
+            return Ok(None);
+        };
+
+        let name = genomes.find_by_id(id).await?.map(|g| g.name);
+        if let Some(name) = &name {
+            names.insert(id, name.clone());
+        }
+        Ok(name)
+    }
+}
+
+/// Names a derived sheet after the part of the run it covers, e.g.
+/// `RUN42_L002_PRJ1.csv`.
+fn file_name(run: &Run, lane: Option<u8>, project: Option<&Project>) -> String {
+    let mut name = run.name.clone();
+    if let Some(lane) = lane {
+        name.push_str(&format!("_L{:03}", lane));
+    }
+    if let Some(project) = project {
+        name.push('_');
+        name.push_str(&project.code);
+    }
+    name.push_str(".csv");
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        Library, LibraryDesign, LibraryType, Pool, PoolElement, RunStatus, Sample, Sequencer,
+    };
+    use miso_domain::repositories::QueryOptions;
+    use miso_domain::value_objects::{Barcode, IndexFamily};
+
+    use super::*;
+    use crate::test_support::OneProject;
+
+    /// A run on a NovaSeq with pool 1, of LIB1 and LIB2, in lane 1.
+    struct Lab {
+        run: Run,
+    }
+
+    fn novaseq() -> InstrumentModel {
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq 6000".to_string(), 2);
+        model.container_models = vec!["S4 Flow Cell".to_string(), "SP Flow Cell".to_string()];
+        model.patterned_containers = vec!["S4 Flow Cell".to_string()];
+        model
+    }
+
+    fn lab(container_model: &str) -> Lab {
+        let mut run = Run::new(1, "RUN1".to_string(), 1, 2, "tech".to_string());
+        run.assign_container("FC1".to_string(), container_model, &novaseq())
+            .unwrap();
+        run.get_partition_mut(1).unwrap().pool_id = Some(1);
+        Lab { run }
+    }
+
+    fn library(id: EntityId, i7: &str) -> Library {
+        let mut library = Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        library.set_index(DnaIndex::single(format!("I{}", id), i7, IndexFamily::TruSeq).unwrap());
+        library
+    }
+
+    #[async_trait]
+    impl RunRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Run>, DomainError> {
+            Ok(Some(self.run.clone()).filter(|r| r.id == id))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sequencer(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_status(&self, _: RunStatus) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_pool(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Run) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl PoolRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Pool>, DomainError> {
+            let mut pool = Pool::new(
+                1,
+                "POOL1".to_string(),
+                Barcode::new("POOL-1".to_string()).unwrap(),
+                "ILLUMINA".to_string(),
+                "tech".to_string(),
+            );
+            for library_id in [1, 2] {
+                pool.elements.push(PoolElement {
+                    library_aliquot_id: library_id,
+                    library_id,
+                    volume: None,
+                    proportion: None,
+                });
+            }
+            Ok(Some(pool).filter(|p| p.id == id))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Pool) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl LibraryRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok([library(1, "ACGTACGT"), library(2, "TTTTGGGG")]
+                .into_iter()
+                .filter(|l| ids.contains(&l.id))
+                .collect())
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SequencerRepository for Lab {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sequencer>, DomainError> {
+            Ok(Some(Sequencer::new(1, "NovaSeq01".to_string(), novaseq())).filter(|s| s.id == id))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sequencer) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    async fn warnings(container_model: &str) -> Vec<String> {
+        let lab = Arc::new(lab(container_model));
+        let project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        let service = SampleSheetService::new(
+            lab.clone(),
+            lab.clone(),
+            lab.clone(),
+            Arc::new(OneProject::new(project)),
+        )
+        .with_sequencers(lab);
+
+        let sheet = service
+            .generate(1, SampleSheetFilter::default(), SampleSheetFormat::V1)
+            .await
+            .unwrap();
+        assert!(sheet.body.contains("LIB-1") && sheet.body.contains("LIB-2"));
+        sheet.warnings
+    }
+
+    #[tokio::test]
+    async fn test_sheet_warns_of_index_hopping_on_the_runs_flow_cell() {
+        let patterned = warnings("S4 Flow Cell").await;
+        assert_eq!(patterned.len(), 1);
+        assert!(patterned[0].starts_with("Lane 1: High index hopping risk"));
+
+        let unpatterned = warnings("SP Flow Cell").await;
+        assert_eq!(unpatterned.len(), 1);
+        assert!(unpatterned[0].starts_with("Lane 1: Low index hopping risk"));
+    }
+}
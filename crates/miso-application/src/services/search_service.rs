@@ -0,0 +1,247 @@
+//! Quick search across projects and samples.
+
+use std::sync::Arc;
+
+use miso_domain::entities::EntityId;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectRepository, SampleRepository};
+use tracing::instrument;
+
+use crate::access::{ProjectAccess, Requester};
+use crate::dto::{SearchHit, SearchResponse};
+
+/// Hits of each kind returned when no limit is given.
+const DEFAULT_LIMIT: u64 = 10;
+
+/// Most hits of each kind returned for one search.
+const MAX_LIMIT: u64 = 50;
+
+/// Service behind the command palette's entity search.
+pub struct SearchService<P, S>
+where
+    P: ProjectRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    projects: Arc<P>,
+    samples: Arc<S>,
+    access: ProjectAccess,
+}
+
+impl<P, S> SearchService<P, S>
+where
+    P: ProjectRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new search service.
+    pub fn new(projects: Arc<P>, samples: Arc<S>) -> Self {
+        Self {
+            projects,
+            samples,
+            access: ProjectAccess::default(),
+        }
+    }
+
+    /// Leaves out projects, and their samples, the requester isn't a
+    /// member of.
+    pub fn with_project_access(mut self, access: ProjectAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Finds projects and samples matching `query`, up to `limit` of each.
+    /// A scanned sample barcode is always found, however many other
+    /// samples match.
+    #[instrument(skip(self))]
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: Option<u64>,
+        requester: Option<Requester<'_>>,
+    ) -> Result<SearchResponse, DomainError> {
+        let query = query.trim();
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        if query.is_empty() {
+            return Ok(SearchResponse::new(query, Vec::new()));
+        }
+
+        let reachable = self.access.projects(requester).await?;
+        let may_see =
+            |project_id: &EntityId| reachable.as_ref().is_none_or(|p| p.contains(project_id));
+        let scanned = self.samples.find_by_barcode(query).await?;
+        let projects = self.projects.search(query, limit).await?;
+        let samples = self.samples.search(query, limit).await?;
+
+        let hits = projects
+            .into_iter()
+            .filter(|p| may_see(&p.id))
+            .map(SearchHit::from)
+            .chain(
+                scanned
+                    .into_iter()
+                    .chain(samples)
+                    .filter(|s| may_see(&s.project_id))
+                    .map(SearchHit::from),
+            );
+        Ok(SearchResponse::new(query, hits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use miso_domain::entities::{Project, Role, Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, QueryOptions, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::test_support::Members;
+
+    /// Projects 1 and 2, each with one sample of the same name.
+    struct Lab;
+
+    fn sample(project_id: EntityId) -> Sample {
+        Sample::new_plain(
+            project_id,
+            "TUMOUR".to_string(),
+            Barcode::new(format!("SAM-{}", project_id)).unwrap(),
+            project_id,
+            "Homo sapiens".to_string(),
+            "tech".to_string(),
+        )
+    }
+
+    #[async_trait]
+    impl ProjectRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            Ok([1, 2]
+                .into_iter()
+                .map(|id| {
+                    let code = format!("TUMOUR{}", id);
+                    Project::new(id, code.clone(), code, "lm".to_string())
+                })
+                .collect())
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl SampleRepository for Lab {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Sample>, DomainError> {
+            Ok([sample(1), sample(2)]
+                .into_iter()
+                .find(|s| s.barcode.as_str() == barcode))
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            Ok(vec![sample(1), sample(2)])
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hits_are_limited_to_members_projects() {
+        let service = SearchService::new(Arc::new(Lab), Arc::new(Lab))
+            .with_project_access(ProjectAccess::new(Arc::new(Members), Arc::new(Members)));
+        let projects_of = |response: SearchResponse| {
+            let mut projects: Vec<EntityId> = response.hits.iter().map(|h| h.project_id).collect();
+            projects.dedup();
+            projects
+        };
+
+        let found = service
+            .search("tumour", None, Some(Requester::new("member", Role::Technician)))
+            .await
+            .unwrap();
+        assert_eq!(projects_of(found), [1]);
+        let found = service
+            .search("SAM-2", None, Some(Requester::new("admin", Role::Admin)))
+            .await
+            .unwrap();
+        assert_eq!(found.hits.len(), 4);
+
+        for requester in [None, Some(Requester::new("outsider", Role::Technician))] {
+            assert!(service.search("tumour", None, requester).await.unwrap().hits.is_empty());
+            assert!(service.search("SAM-2", None, requester).await.unwrap().hits.is_empty());
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Sequencer registry service.
+//!
+//! Lab managers register the facility's sequencers against the instrument
+//! model catalog and move them between the statuses set by staff:
+//! maintenance, out of service, back in service and retired. Runs set a
+//! sequencer running and available again themselves, so a sequencer taken
+//! out of service mid-run stays out until its run ends. A sequencer that
+//! has had runs can't be deleted, only retired, so its runs keep their
+//! instrument.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{EntityId, InstrumentModel, RunStatus, Sequencer, SequencerStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{InstrumentModelRepository, RunRepository, SequencerRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{CreateSequencerRequest, SequencerResponse, UpdateSequencerRequest};
+
+/// Service for registering sequencers and changing their status.
+///
+/// Sequencers can be deleted, and returned to service, whatever runs they
+/// had until the run repository is supplied.
+pub struct SequencerService {
+    sequencers: Arc<dyn SequencerRepository>,
+    models: Arc<dyn InstrumentModelRepository>,
+    runs: Option<Arc<dyn RunRepository>>,
+    audit: AuditTrail,
+}
+
+impl SequencerService {
+    /// Creates a new sequencer service.
+    pub fn new(
+        sequencers: Arc<dyn SequencerRepository>,
+        models: Arc<dyn InstrumentModelRepository>,
+    ) -> Self {
+        Self {
+            sequencers,
+            models,
+            runs: None,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Refuses to delete sequencers that have had runs, and to move ones
+    /// with an unfinished run off out of service.
+    pub fn with_runs(mut self, runs: Arc<dyn RunRepository>) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Records registrations and status changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Lists sequencers by name, optionally only those with a status.
+    #[instrument(skip(self))]
+    pub async fn list(
+        &self,
+        status: Option<SequencerStatus>,
+    ) -> Result<Vec<SequencerResponse>, DomainError> {
+        Ok(self
+            .sequencers
+            .list()
+            .await?
+            .into_iter()
+            .filter(|s| status.is_none_or(|status| s.status == status))
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Gets a sequencer by ID.
+    #[instrument(skip(self))]
+    pub async fn get(&self, id: EntityId) -> Result<SequencerResponse, DomainError> {
+        Ok(self.find(id).await?.into())
+    }
+
+    /// Registers a sequencer.
+    #[instrument(skip(self, request))]
+    pub async fn create(
+        &self,
+        request: CreateSequencerRequest,
+        created_by: &str,
+    ) -> Result<SequencerResponse, DomainError> {
+        self.ensure_name_free(&request.name).await?;
+        let model = self.find_model(request.instrument_model_id).await?;
+
+        let mut sequencer = Sequencer::new(0, request.name, model);
+        sequencer.serial_number = request.serial_number;
+        sequencer.location = request.location;
+        sequencer.ip_address = request.ip_address;
+        sequencer.date_commissioned = request.date_commissioned;
+
+        sequencer.id = self.sequencers.save(&sequencer).await?;
+        self.audit
+            .created("Sequencer", sequencer.id, &sequencer, created_by)
+            .await?;
+
+        info!(
+            "Registered sequencer: {} (ID: {})",
+            sequencer.name, sequencer.id
+        );
+
+        Ok(sequencer.into())
+    }
+
+    /// Updates a sequencer's details.
+    #[instrument(skip(self, request))]
+    pub async fn update(
+        &self,
+        id: EntityId,
+        request: UpdateSequencerRequest,
+        updated_by: &str,
+    ) -> Result<SequencerResponse, DomainError> {
+        let before = self.find(id).await?;
+        let mut sequencer = before.clone();
+
+        if let Some(name) = request.name {
+            if name != sequencer.name {
+                self.ensure_name_free(&name).await?;
+            }
+            sequencer.name = name;
+        }
+        if let Some(model_id) = request.instrument_model_id {
+            if model_id != sequencer.model.id {
+                sequencer.model = self.find_model(model_id).await?;
+            }
+        }
+        if let Some(serial_number) = request.serial_number {
+            sequencer.serial_number = Some(serial_number);
+        }
+        if let Some(location) = request.location {
+            sequencer.location = Some(location);
+        }
+        if let Some(ip_address) = request.ip_address {
+            sequencer.ip_address = Some(ip_address);
+        }
+        if let Some(date_commissioned) = request.date_commissioned {
+            sequencer.date_commissioned = Some(date_commissioned);
+        }
+
+        self.save(&before, sequencer, updated_by).await
+    }
+
+    /// Moves a sequencer to a status set by staff.
+    #[instrument(skip(self))]
+    pub async fn change_status(
+        &self,
+        id: EntityId,
+        status: SequencerStatus,
+        changed_by: &str,
+    ) -> Result<SequencerResponse, DomainError> {
+        let before = self.find(id).await?;
+        let mut sequencer = before.clone();
+        sequencer.change_status(status)?;
+        if status != SequencerStatus::OutOfService {
+            self.ensure_no_unfinished_run(&sequencer).await?;
+        }
+
+        info!("Sequencer {} is now {}", sequencer.name, sequencer.status);
+
+        self.save(&before, sequencer, changed_by).await
+    }
+
+    /// Deletes a sequencer registered by mistake. Sequencers that have had
+    /// runs are retired instead.
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let sequencer = self.find(id).await?;
+
+        if let Some(runs) = &self.runs {
+            let count = runs.find_by_sequencer(id).await?.len();
+            if count > 0 {
+                return Err(DomainError::Validation(format!(
+                    "Sequencer {} has {} runs; retire it instead",
+                    sequencer.name, count
+                )));
+            }
+        }
+
+        self.sequencers.delete(id).await?;
+        self.audit
+            .deleted("Sequencer", id, &sequencer, deleted_by)
+            .await?;
+
+        info!("Deleted sequencer: {}", id);
+        Ok(())
+    }
+
+    /// Checks the sequencer isn't held by a run still running or paused.
+    async fn ensure_no_unfinished_run(&self, sequencer: &Sequencer) -> Result<(), DomainError> {
+        let Some(runs) = &self.runs else {
+            return Ok(());
+        };
+        let unfinished = runs
+            .find_by_sequencer(sequencer.id)
+            .await?
+            .into_iter()
+            .find(|r| matches!(r.status, RunStatus::Running | RunStatus::Paused));
+        match unfinished {
+            Some(run) => Err(DomainError::Validation(format!(
+                "Sequencer {} still holds run {} ({}); wait for it to end",
+                sequencer.name, run.name, run.status
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    async fn save(
+        &self,
+        before: &Sequencer,
+        mut sequencer: Sequencer,
+        by: &str,
+    ) -> Result<SequencerResponse, DomainError> {
+        sequencer.updated_at = Utc::now();
+        self.sequencers.save(&sequencer).await?;
+        self.audit
+            .updated("Sequencer", sequencer.id, before, &sequencer, by)
+            .await?;
+
+        Ok(sequencer.into())
+    }
+
+    async fn find(&self, id: EntityId) -> Result<Sequencer, DomainError> {
+        self.sequencers
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sequencer".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn find_model(&self, id: EntityId) -> Result<InstrumentModel, DomainError> {
+        self.models
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "InstrumentModel".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn ensure_name_free(&self, name: &str) -> Result<(), DomainError> {
+        if self.sequencers.find_by_name(name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Sequencer".to_string(),
+                field: "name".to_string(),
+                value: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Platform, Run};
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemorySequencers {
+        sequencers: Mutex<Vec<Sequencer>>,
+    }
+
+    #[async_trait]
+    impl SequencerRepository for InMemorySequencers {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sequencer>, DomainError> {
+            Ok(self.sequencers.lock().unwrap().iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Sequencer>, DomainError> {
+            Ok(self.sequencers.lock().unwrap().iter().find(|s| s.name == name).cloned())
+        }
+        async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+            Ok(self.sequencers.lock().unwrap().clone())
+        }
+        async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, sequencer: &Sequencer) -> Result<EntityId, DomainError> {
+            let mut sequencers = self.sequencers.lock().unwrap();
+            let mut sequencer = sequencer.clone();
+            if sequencer.id == 0 {
+                sequencer.id = sequencers.len() as EntityId + 1;
+            }
+            sequencers.retain(|s| s.id != sequencer.id);
+            sequencers.push(sequencer.clone());
+            Ok(sequencer.id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.sequencers.lock().unwrap().retain(|s| s.id != id);
+            Ok(())
+        }
+    }
+
+    struct Catalog(InstrumentModel);
+
+    #[async_trait]
+    impl InstrumentModelRepository for Catalog {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<InstrumentModel>, DomainError> {
+            Ok(Some(self.0.clone()).filter(|m| m.id == id))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &InstrumentModel) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// Runs on sequencer 1, whose status the test sets.
+    #[derive(Default)]
+    struct OneRun(Mutex<Option<Run>>);
+
+    #[async_trait]
+    impl RunRepository for OneRun {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sequencer(&self, id: EntityId) -> Result<Vec<Run>, DomainError> {
+            let run = self.0.lock().unwrap().clone();
+            Ok(run.filter(|r| r.sequencer_id == id).into_iter().collect())
+        }
+        async fn find_by_status(&self, _: RunStatus) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_pool(&self, _: EntityId) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Run>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Run) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn request(name: &str, instrument_model_id: EntityId) -> CreateSequencerRequest {
+        CreateSequencerRequest {
+            name: name.to_string(),
+            instrument_model_id,
+            serial_number: Some("LH00123".to_string()),
+            location: None,
+            ip_address: None,
+            date_commissioned: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_and_status_changes() {
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq X".to_string(), 8);
+        model.id = 3;
+        let service = SequencerService::new(
+            Arc::new(InMemorySequencers::default()),
+            Arc::new(Catalog(model)),
+        );
+
+        let created = service.create(request("NovaSeqX01", 3), "manager").await.unwrap();
+        assert_eq!(created.model, "NovaSeq X");
+        assert_eq!(created.partitions, 8);
+        assert!(created.can_run);
+        assert!(matches!(
+            service.create(request("NovaSeqX01", 3), "manager").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service.create(request("NovaSeqX02", 4), "manager").await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        let id = created.id;
+        let sequencer = service
+            .change_status(id, SequencerStatus::Maintenance, "manager")
+            .await
+            .unwrap();
+        assert!(!sequencer.can_run);
+        assert_eq!(
+            service.list(Some(SequencerStatus::Maintenance)).await.unwrap().len(),
+            1
+        );
+        assert!(service.list(Some(SequencerStatus::Available)).await.unwrap().is_empty());
+
+        let sequencer = service
+            .change_status(id, SequencerStatus::Available, "manager")
+            .await
+            .unwrap();
+        assert!(sequencer.last_service_date.is_some());
+
+        service
+            .change_status(id, SequencerStatus::Retired, "manager")
+            .await
+            .unwrap();
+        assert!(service
+            .change_status(id, SequencerStatus::Available, "manager")
+            .await
+            .is_err());
+
+        service.delete(id, "manager").await.unwrap();
+        assert!(service.get(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sequencer_out_of_service_mid_run_waits_for_the_run() {
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq X".to_string(), 8);
+        model.id = 3;
+        let runs = Arc::new(OneRun::default());
+        let service = SequencerService::new(
+            Arc::new(InMemorySequencers::default()),
+            Arc::new(Catalog(model)),
+        )
+        .with_runs(runs.clone());
+        let id = service.create(request("NovaSeqX01", 3), "manager").await.unwrap().id;
+
+        let mut run = Run::new(1, "RUN1".to_string(), id, 1, "tech".to_string());
+        run.status = RunStatus::Running;
+        *runs.0.lock().unwrap() = Some(run.clone());
+        service
+            .change_status(id, SequencerStatus::OutOfService, "tech")
+            .await
+            .unwrap();
+        for status in [RunStatus::Running, RunStatus::Paused] {
+            run.status = status;
+            *runs.0.lock().unwrap() = Some(run.clone());
+            assert!(matches!(
+                service.change_status(id, SequencerStatus::Available, "tech").await,
+                Err(DomainError::Validation(_))
+            ));
+        }
+        assert_eq!(service.get(id).await.unwrap().status, SequencerStatus::OutOfService);
+
+        run.status = RunStatus::Failed;
+        *runs.0.lock().unwrap() = Some(run);
+        let sequencer = service
+            .change_status(id, SequencerStatus::Available, "tech")
+            .await
+            .unwrap();
+        assert!(sequencer.can_run);
+    }
+}
@@ -0,0 +1,631 @@
+//! Storage audit service for physical inventories of freezers.
+//!
+//! An audit prompts for a freezer's boxes one at a time, shelf by shelf and
+//! rack by rack. Each scan is compared with the box's records, and once
+//! every box is scanned a manager accepts the corrections they agree with,
+//! bringing the records in line with the shelf.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use miso_domain::entities::{
+    EntityId, ScanDiscrepancy, ScannedTube, StorableItem, StorageAudit, StorageBox,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    LibraryRepository, PoolRepository, QueryOptions, SampleRepository, StorageAuditRepository,
+    StorageBoxRepository,
+};
+use miso_domain::value_objects::BoxPosition;
+use tracing::{info, instrument};
+
+use crate::dto::{
+    AcceptCorrectionsRequest, AuditBoxPrompt, RecordBoxScanRequest, ScanDiscrepancyResponse,
+    StartStorageAuditRequest, StorageAuditResponse,
+};
+
+/// Service for guided physical inventories of freezers.
+pub struct StorageAuditService {
+    audits: Arc<dyn StorageAuditRepository>,
+    boxes: Arc<dyn StorageBoxRepository>,
+    samples: Arc<dyn SampleRepository>,
+    libraries: Arc<dyn LibraryRepository>,
+    pools: Arc<dyn PoolRepository>,
+}
+
+impl StorageAuditService {
+    /// Creates a new storage audit service.
+    pub fn new(
+        audits: Arc<dyn StorageAuditRepository>,
+        boxes: Arc<dyn StorageBoxRepository>,
+        samples: Arc<dyn SampleRepository>,
+        libraries: Arc<dyn LibraryRepository>,
+        pools: Arc<dyn PoolRepository>,
+    ) -> Self {
+        Self {
+            audits,
+            boxes,
+            samples,
+            libraries,
+            pools,
+        }
+    }
+
+    /// Starts auditing every box in a freezer.
+    #[instrument(skip(self, request), fields(freezer = %request.freezer))]
+    pub async fn start_audit(
+        &self,
+        request: StartStorageAuditRequest,
+        started_by: &str,
+    ) -> Result<StorageAuditResponse, DomainError> {
+        let mut boxes = self.boxes.find_by_location(&request.freezer).await?;
+        boxes.sort_by(|a, b| {
+            (&a.location.shelf, &a.location.rack, &a.name)
+                .cmp(&(&b.location.shelf, &b.location.rack, &b.name))
+        });
+
+        let mut audit = StorageAudit::new(
+            request.freezer,
+            boxes.iter().map(|b| b.id).collect(),
+            started_by.to_string(),
+        )?;
+        audit.id = self.audits.save(&audit).await?;
+
+        info!(
+            "Started audit {} of {} ({} boxes)",
+            audit.id,
+            audit.freezer,
+            audit.box_ids.len()
+        );
+
+        self.respond(audit).await
+    }
+
+    /// Gets an audit and the box to scan next.
+    #[instrument(skip(self))]
+    pub async fn get_audit(&self, id: EntityId) -> Result<StorageAuditResponse, DomainError> {
+        let audit = self.find_audit(id).await?;
+        self.respond(audit).await
+    }
+
+    /// Lists audits, newest first.
+    #[instrument(skip(self))]
+    pub async fn list_audits(
+        &self,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<StorageAuditResponse>, DomainError> {
+        let audits = self
+            .audits
+            .list(QueryOptions {
+                limit: Some(limit.unwrap_or(50).min(200)),
+                offset,
+                ..Default::default()
+            })
+            .await?;
+
+        let mut responses = Vec::with_capacity(audits.len());
+        for audit in audits {
+            responses.push(self.respond(audit).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Records a scan of one of the audit's boxes, comparing each barcode
+    /// read with what the box records at its position.
+    #[instrument(skip(self, request), fields(box_id = request.box_id))]
+    pub async fn record_scan(
+        &self,
+        id: EntityId,
+        request: RecordBoxScanRequest,
+    ) -> Result<StorageAuditResponse, DomainError> {
+        let mut audit = self.find_audit(id).await?;
+        let storage_box = self.find_box(request.box_id).await?;
+
+        let mut scanned = Vec::with_capacity(request.positions.len());
+        for (position, barcode) in request.positions {
+            let barcode = barcode.trim().to_string();
+            if barcode.is_empty() {
+                continue;
+            }
+            scanned.push(ScannedTube {
+                position: BoxPosition::parse(&position, &storage_box.dimension)?,
+                item: self.resolve(&barcode).await?,
+                barcode,
+            });
+        }
+
+        audit.record_scan(&storage_box, &scanned)?;
+        self.audits.save(&audit).await?;
+
+        info!(
+            "Audit {} scanned box {}: {} discrepancies so far",
+            audit.id,
+            storage_box.name,
+            audit.discrepancies.len()
+        );
+
+        self.respond(audit).await
+    }
+
+    /// Accepts the chosen discrepancies and corrects the box records to
+    /// match the scans, closing the audit.
+    ///
+    /// Recorded items are taken out of their positions before scanned ones
+    /// are put in, so items found swapped are corrected together. An item
+    /// found somewhere other than where a box records it is taken out of
+    /// that box.
+    #[instrument(skip(self, request))]
+    pub async fn accept_corrections(
+        &self,
+        id: EntityId,
+        request: AcceptCorrectionsRequest,
+        accepted_by: &str,
+    ) -> Result<StorageAuditResponse, DomainError> {
+        let mut audit = self.find_audit(id).await?;
+        let accepted = audit.accept(&request.discrepancies, accepted_by.to_string())?;
+
+        let mut boxes: HashMap<EntityId, StorageBox> = HashMap::new();
+        for discrepancy in accepted.iter().filter(|d| d.recorded.is_some()) {
+            let (storage_box, position) = self.locate(&mut boxes, discrepancy).await?;
+            storage_box.remove_item(&position);
+        }
+        for discrepancy in &accepted {
+            let Some(item) = &discrepancy.scanned else {
+                continue;
+            };
+            if let Some((elsewhere, at)) =
+                self.boxes.find_by_item(item.item_type, item.item_id).await?
+            {
+                let elsewhere = self.cached(&mut boxes, elsewhere.id).await?;
+                if elsewhere.get_item(&at) == Some(item) {
+                    elsewhere.remove_item(&at);
+                }
+            }
+            let (storage_box, position) = self.locate(&mut boxes, discrepancy).await?;
+            storage_box.place_item(position, item.clone())?;
+        }
+
+        for storage_box in boxes.values() {
+            self.boxes.save(storage_box).await?;
+        }
+        self.audits.save(&audit).await?;
+
+        info!(
+            "Closed audit {} of {}: {} corrections accepted across {} boxes",
+            audit.id,
+            audit.freezer,
+            accepted.len(),
+            boxes.len()
+        );
+
+        self.respond(audit).await
+    }
+
+    async fn find_audit(&self, id: EntityId) -> Result<StorageAudit, DomainError> {
+        self.audits
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "StorageAudit".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn find_box(&self, id: EntityId) -> Result<StorageBox, DomainError> {
+        self.boxes
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Box".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    /// Loads a box once, so that corrections to it accumulate.
+    async fn cached<'a>(
+        &self,
+        boxes: &'a mut HashMap<EntityId, StorageBox>,
+        id: EntityId,
+    ) -> Result<&'a mut StorageBox, DomainError> {
+        Ok(match boxes.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(self.find_box(id).await?),
+        })
+    }
+
+    /// Finds the box and position a discrepancy was found at.
+    async fn locate<'a>(
+        &self,
+        boxes: &'a mut HashMap<EntityId, StorageBox>,
+        discrepancy: &ScanDiscrepancy,
+    ) -> Result<(&'a mut StorageBox, BoxPosition), DomainError> {
+        let storage_box = self.cached(boxes, discrepancy.box_id).await?;
+        let position = BoxPosition::parse(&discrepancy.position, &storage_box.dimension)?;
+        Ok((storage_box, position))
+    }
+
+    /// Finds the sample, library or pool with a barcode.
+    async fn resolve(&self, barcode: &str) -> Result<Option<StorableItem>, DomainError> {
+        if let Some(sample) = self.samples.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::sample(sample.id)));
+        }
+        if let Some(library) = self.libraries.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::library(library.id)));
+        }
+        if let Some(pool) = self.pools.find_by_barcode(barcode).await? {
+            return Ok(Some(StorableItem::pool(pool.id)));
+        }
+        Ok(None)
+    }
+
+    async fn respond(&self, audit: StorageAudit) -> Result<StorageAuditResponse, DomainError> {
+        let next_box = match audit.next_box() {
+            Some(id) => self.boxes.find_by_id(id).await?.map(|b| AuditBoxPrompt {
+                box_id: b.id,
+                location: b.location.path(),
+                name: b.name,
+                barcode: b.barcode,
+            }),
+            None => None,
+        };
+
+        Ok(StorageAuditResponse {
+            id: audit.id,
+            freezer: audit.freezer,
+            status: audit.status,
+            boxes_total: audit.box_ids.len(),
+            boxes_scanned: audit.scanned_box_ids.len(),
+            next_box,
+            discrepancies: audit
+                .discrepancies
+                .into_iter()
+                .enumerate()
+                .map(|(index, discrepancy)| ScanDiscrepancyResponse { index, discrepancy })
+                .collect(),
+            started_by: audit.started_by,
+            started_at: audit.started_at,
+            closed_by: audit.closed_by,
+            closed_at: audit.closed_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{
+        Library, Pool, Sample, SampleClass, ScanDiscrepancyKind, StorableType, StorageAuditStatus,
+    };
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{Barcode, Dimension, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryAudits {
+        audits: Mutex<HashMap<EntityId, StorageAudit>>,
+    }
+
+    #[async_trait]
+    impl StorageAuditRepository for InMemoryAudits {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageAudit>, DomainError> {
+            Ok(self.audits.lock().unwrap().get(&id).cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<StorageAudit>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, audit: &StorageAudit) -> Result<EntityId, DomainError> {
+            let mut audits = self.audits.lock().unwrap();
+            let mut audit = audit.clone();
+            if audit.id == 0 {
+                audit.id = audits.len() as EntityId + 1;
+            }
+            audits.insert(audit.id, audit.clone());
+            Ok(audit.id)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryBoxes {
+        boxes: Mutex<HashMap<EntityId, StorageBox>>,
+    }
+
+    #[async_trait]
+    impl StorageBoxRepository for InMemoryBoxes {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageBox>, DomainError> {
+            Ok(self.boxes.lock().unwrap().get(&id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_location(&self, freezer: &str) -> Result<Vec<StorageBox>, DomainError> {
+            let boxes = self.boxes.lock().unwrap();
+            Ok(boxes
+                .values()
+                .filter(|b| b.location.freezer.as_deref() == Some(freezer))
+                .cloned()
+                .collect())
+        }
+        async fn find_by_rack(&self, _: EntityId) -> Result<Vec<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_item(
+            &self,
+            item_type: StorableType,
+            item_id: EntityId,
+        ) -> Result<Option<(StorageBox, BoxPosition)>, DomainError> {
+            let item = StorableItem::new(item_type, item_id);
+            let boxes = self.boxes.lock().unwrap();
+            Ok(boxes.values().find_map(|b| {
+                b.all_contents()
+                    .into_iter()
+                    .find(|(_, stored)| **stored == item)
+                    .map(|(position, _)| (b.clone(), *position))
+            }))
+        }
+        async fn save(&self, storage_box: &StorageBox) -> Result<EntityId, DomainError> {
+            let mut boxes = self.boxes.lock().unwrap();
+            boxes.insert(storage_box.id, storage_box.clone());
+            Ok(storage_box.id)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// Has samples 1 to 9, barcoded SAM1 to SAM9.
+    struct NineSamples;
+
+    #[async_trait]
+    impl SampleRepository for NineSamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Sample>, DomainError> {
+            let id = barcode
+                .strip_prefix("SAM")
+                .and_then(|n| n.parse::<EntityId>().ok())
+                .filter(|n| (1..=9).contains(n));
+            Ok(id.map(|id| {
+                Sample::new_plain(
+                    id,
+                    format!("S{}", id),
+                    Barcode::new_unchecked(barcode.to_string()),
+                    1,
+                    "Homo sapiens".to_string(),
+                    "tech".to_string(),
+                )
+            }))
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct NoLibraries;
+
+    #[async_trait]
+    impl LibraryRepository for NoLibraries {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            Ok(None)
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct NoPools;
+
+    #[async_trait]
+    impl PoolRepository for NoPools {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Pool>, DomainError> {
+            Ok(None)
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_library(&self, _: EntityId) -> Result<Vec<Pool>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Pool) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn stored_box(id: EntityId, freezer: &str, contents: &[(&str, EntityId)]) -> StorageBox {
+        let mut storage_box = StorageBox::sample_box_9x9(id, format!("Box {}", id));
+        storage_box.location.freezer = Some(freezer.to_string());
+        for (position, sample) in contents {
+            let position = BoxPosition::parse(position, &storage_box.dimension).unwrap();
+            storage_box
+                .place_item(position, StorableItem::sample(*sample))
+                .unwrap();
+        }
+        storage_box
+    }
+
+    fn item_at(boxes: &InMemoryBoxes, box_id: EntityId, position: &str) -> Option<StorableItem> {
+        let boxes = boxes.boxes.lock().unwrap();
+        let position = BoxPosition::parse(position, &Dimension::CRYOBOX_9X9).unwrap();
+        boxes[&box_id].get_item(&position).cloned()
+    }
+
+    #[tokio::test]
+    async fn test_audit_corrects_accepted_discrepancies() {
+        let boxes = Arc::new(InMemoryBoxes::default());
+        for storage_box in [
+            stored_box(1, "Freezer 1", &[("A1", 1), ("A2", 2)]),
+            stored_box(2, "Freezer 2", &[("B1", 3)]),
+        ] {
+            boxes.save(&storage_box).await.unwrap();
+        }
+        let service = StorageAuditService::new(
+            Arc::new(InMemoryAudits::default()),
+            boxes.clone(),
+            Arc::new(NineSamples),
+            Arc::new(NoLibraries),
+            Arc::new(NoPools),
+        );
+
+        let audit = service
+            .start_audit(
+                StartStorageAuditRequest {
+                    freezer: "Freezer 1".to_string(),
+                },
+                "tech",
+            )
+            .await
+            .unwrap();
+        assert_eq!(audit.boxes_total, 1);
+        assert_eq!(audit.next_box.unwrap().box_id, 1);
+
+        // Samples 1 and 2 are swapped, sample 3 has wandered over from
+        // box 2, and A4 holds a tube nobody knows
+        let positions = [("A01", "SAM2"), ("A02", "SAM1"), ("A03", "SAM3"), ("A04", "XYZ")]
+            .into_iter()
+            .map(|(p, b)| (p.to_string(), b.to_string()))
+            .collect();
+        let audit = service
+            .record_scan(audit.id, RecordBoxScanRequest { box_id: 1, positions })
+            .await
+            .unwrap();
+        assert_eq!(audit.status, StorageAuditStatus::Review);
+        assert!(audit.next_box.is_none());
+        let kinds: Vec<ScanDiscrepancyKind> =
+            audit.discrepancies.iter().map(|d| d.discrepancy.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ScanDiscrepancyKind::Mismatched,
+                ScanDiscrepancyKind::Mismatched,
+                ScanDiscrepancyKind::Unexpected,
+                ScanDiscrepancyKind::UnknownBarcode,
+            ]
+        );
+
+        let audit = service
+            .accept_corrections(
+                audit.id,
+                AcceptCorrectionsRequest {
+                    discrepancies: vec![0, 1, 2],
+                },
+                "manager",
+            )
+            .await
+            .unwrap();
+        assert_eq!(audit.status, StorageAuditStatus::Closed);
+
+        assert_eq!(item_at(&boxes, 1, "A1"), Some(StorableItem::sample(2)));
+        assert_eq!(item_at(&boxes, 1, "A2"), Some(StorableItem::sample(1)));
+        assert_eq!(item_at(&boxes, 1, "A3"), Some(StorableItem::sample(3)));
+        assert_eq!(item_at(&boxes, 1, "A4"), None);
+        assert_eq!(item_at(&boxes, 2, "B1"), None);
+    }
+}
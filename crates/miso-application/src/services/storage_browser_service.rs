@@ -0,0 +1,329 @@
+//! Box storage browsing service.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, StorableItem, StorableType, StorageBox, StorageLocation};
+use miso_domain::errors::{DomainError, StorageError};
+use miso_domain::plugins::DomainEvent;
+use miso_domain::repositories::{QueryOptions, SampleRepository, StorageBoxRepository};
+use miso_domain::value_objects::{BoxPosition, Dimension};
+use tracing::{info, instrument};
+
+use crate::dto::{
+    codes, BoxContents, BoxSummary, CreateBoxRequest, ItemLocation, ItemMoved, MoveItemRequest,
+    PlaceItemRequest, PlateMap, StorageNode, StoredItem,
+};
+use crate::plugins::PluginRegistry;
+
+/// Service for browsing the Freezer → Shelf → Rack → Box hierarchy,
+/// finding where items are stored and moving them.
+pub struct StorageBrowserService<B, S>
+where
+    B: StorageBoxRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    boxes: Arc<B>,
+    samples: Arc<S>,
+    plugins: Arc<PluginRegistry>,
+}
+
+impl<B, S> StorageBrowserService<B, S>
+where
+    B: StorageBoxRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+{
+    /// Creates a new storage browser service.
+    pub fn new(boxes: Arc<B>, samples: Arc<S>) -> Self {
+        Self {
+            boxes,
+            samples,
+            plugins: Arc::new(PluginRegistry::new()),
+        }
+    }
+
+    /// Publishes items being placed, moved and removed to site plugins'
+    /// event subscribers.
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Returns every box arranged by freezer, shelf and rack, with fill
+    /// levels at each level.
+    #[instrument(skip(self))]
+    pub async fn hierarchy(&self) -> Result<Vec<StorageNode>, DomainError> {
+        let boxes = self.boxes.list(QueryOptions::new()).await?;
+        Ok(StorageNode::build(&boxes))
+    }
+
+    /// Returns a box and its contents.
+    #[instrument(skip(self))]
+    pub async fn box_contents(&self, id: EntityId) -> Result<BoxContents, DomainError> {
+        let storage_box = self.find_box(id).await?;
+        Ok(BoxContents::from(&storage_box))
+    }
+
+    /// Returns a box as a dense grid, with sample names and barcodes
+    /// filled in.
+    #[instrument(skip(self))]
+    pub async fn plate_map(&self, id: EntityId) -> Result<PlateMap, DomainError> {
+        let storage_box = self.find_box(id).await?;
+        let mut map = PlateMap::from(&storage_box);
+        if storage_box.storable_type != StorableType::Sample || storage_box.is_empty() {
+            return Ok(map);
+        }
+
+        let ids: Vec<EntityId> = storage_box
+            .all_contents()
+            .into_iter()
+            .map(|(_, item)| item.item_id)
+            .collect();
+        let samples: HashMap<EntityId, _> = self
+            .samples
+            .find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|sample| (sample.id, sample))
+            .collect();
+
+        for well in map.wells.iter_mut().flatten().flatten() {
+            if let Some(sample) = samples.get(&well.item_id) {
+                well.name = Some(sample.name.clone());
+                well.barcode = Some(sample.barcode.to_string());
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Creates an empty box.
+    #[instrument(skip(self))]
+    pub async fn create_box(&self, request: CreateBoxRequest) -> Result<BoxSummary, DomainError> {
+        if let Some(barcode) = &request.barcode {
+            if self.boxes.find_by_barcode(barcode).await?.is_some() {
+                return Err(DomainError::Duplicate {
+                    entity_type: "Box".to_string(),
+                    field: "barcode".to_string(),
+                    value: barcode.clone(),
+                });
+            }
+        }
+
+        let mut storage_box = StorageBox::new(
+            0,
+            request.name,
+            Dimension::new(request.rows, request.cols),
+            parse_storable_type(&request.storable_type)?,
+        );
+        storage_box.barcode = request.barcode;
+        storage_box.location = StorageLocation {
+            freezer: request.freezer,
+            shelf: request.shelf,
+            rack: request.rack,
+            temperature: None,
+        };
+        storage_box.description = request.description;
+
+        storage_box.id = self.boxes.save(&storage_box).await?;
+        info!("Created box {} ({})", storage_box.name, storage_box.id);
+
+        Ok(BoxSummary::from(&storage_box))
+    }
+
+    /// Places an item at an empty position in a box.
+    ///
+    /// An item is stored in one place at a time: one already in a box
+    /// must be moved rather than placed again.
+    #[instrument(skip(self))]
+    pub async fn place_item(
+        &self,
+        id: EntityId,
+        position: &str,
+        request: PlaceItemRequest,
+    ) -> Result<StoredItem, DomainError> {
+        let mut storage_box = self.find_box(id).await?;
+        let position = BoxPosition::parse(position, &storage_box.dimension)?;
+        let item = StorableItem::new(parse_storable_type(&request.item_type)?, request.item_id);
+
+        if item.item_type == StorableType::Sample
+            && self.samples.find_by_id(item.item_id).await?.is_none()
+        {
+            return Err(DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: item.item_id.to_string(),
+            });
+        }
+
+        if let Some((current, at)) = self
+            .boxes
+            .find_by_item(item.item_type, item.item_id)
+            .await?
+        {
+            return Err(DomainError::Duplicate {
+                entity_type: "Storage location".to_string(),
+                field: format!("{} {}", item.item_type, item.item_id),
+                value: format!("{} {}", current.name, at),
+            });
+        }
+
+        storage_box.place_item(position, item.clone())?;
+        self.boxes.save(&storage_box).await?;
+        info!(
+            "Placed {} {} in box {} {}",
+            item.item_type, item.item_id, id, position
+        );
+        self.publish_stored(&item, &storage_box, position).await;
+
+        Ok(stored_item(position, &item))
+    }
+
+    /// Removes the item at a position in a box.
+    #[instrument(skip(self))]
+    pub async fn remove_item(
+        &self,
+        id: EntityId,
+        position: &str,
+    ) -> Result<StoredItem, DomainError> {
+        let mut storage_box = self.find_box(id).await?;
+        let position = BoxPosition::parse(position, &storage_box.dimension)?;
+        let item = storage_box
+            .remove_item(&position)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: format!("Item in box {}", storage_box.name),
+                id: position.to_string(),
+            })?;
+
+        self.boxes.save(&storage_box).await?;
+        info!(
+            "Removed {} {} from box {} {}",
+            item.item_type, item.item_id, id, position
+        );
+        self.plugins
+            .publish(DomainEvent::ItemRemoved(item.clone()))
+            .await;
+
+        Ok(stored_item(position, &item))
+    }
+
+    /// Finds the box and position holding the sample with `barcode`.
+    #[instrument(skip(self))]
+    pub async fn locate(&self, barcode: &str) -> Result<ItemLocation, DomainError> {
+        let sample = self
+            .samples
+            .find_by_barcode(barcode)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: barcode.to_string(),
+            })?;
+
+        let (storage_box, position) = self
+            .boxes
+            .find_by_item(StorableType::Sample, sample.id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Storage location for sample".to_string(),
+                id: barcode.to_string(),
+            })?;
+
+        Ok(ItemLocation {
+            item_type: codes::storable_type(StorableType::Sample).to_string(),
+            item_id: sample.id,
+            barcode: sample.barcode.to_string(),
+            name: sample.name,
+            storage_box: BoxSummary::from(&storage_box),
+            position: position.to_string(),
+        })
+    }
+
+    /// Moves an item to another position, in the same box or another.
+    ///
+    /// Boxes are saved one at a time, target first: should saving the
+    /// source fail, the item is recorded in both boxes rather than in
+    /// neither.
+    #[instrument(skip(self))]
+    pub async fn move_item(&self, request: MoveItemRequest) -> Result<ItemMoved, DomainError> {
+        let mut source = self.find_box(request.from_box_id).await?;
+        let from = BoxPosition::parse(&request.from_position, &source.dimension)?;
+
+        let (item, to) = if request.to_box_id == request.from_box_id {
+            let to = BoxPosition::parse(&request.to_position, &source.dimension)?;
+            let item = source.get_item(&from).cloned();
+            source.move_item(&from, to)?;
+            self.boxes.save(&source).await?;
+            let item = item.expect("move_item checks the source position");
+            self.publish_stored(&item, &source, to).await;
+            (item, to)
+        } else {
+            let mut target = self.find_box(request.to_box_id).await?;
+            let to = BoxPosition::parse(&request.to_position, &target.dimension)?;
+            let item = source
+                .remove_item(&from)
+                .ok_or_else(|| StorageError::ItemNotInBox(from.to_string(), source.name.clone()))?;
+            target.place_item(to, item.clone())?;
+            self.boxes.save(&target).await?;
+            self.boxes.save(&source).await?;
+            self.publish_stored(&item, &target, to).await;
+            (item, to)
+        };
+
+        info!(
+            "Moved {} {} from box {} {} to box {} {}",
+            item.item_type, item.item_id, request.from_box_id, from, request.to_box_id, to
+        );
+
+        Ok(ItemMoved {
+            item_type: codes::storable_type(item.item_type).to_string(),
+            item_id: item.item_id,
+            from_box_id: request.from_box_id,
+            from_position: from.to_string(),
+            to_box_id: request.to_box_id,
+            to_position: to.to_string(),
+        })
+    }
+
+    async fn publish_stored(&self, item: &StorableItem, storage_box: &StorageBox, at: BoxPosition) {
+        self.plugins
+            .publish(DomainEvent::ItemStored {
+                item: item.clone(),
+                box_id: storage_box.id,
+                box_name: storage_box.name.clone(),
+                position: at.to_string(),
+            })
+            .await;
+    }
+
+    async fn find_box(&self, id: EntityId) -> Result<StorageBox, DomainError> {
+        self.boxes
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Box".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+fn stored_item(position: BoxPosition, item: &StorableItem) -> StoredItem {
+    StoredItem {
+        position: position.to_string(),
+        row: position.row_index(),
+        col: position.col(),
+        item_type: codes::storable_type(item.item_type).to_string(),
+        item_id: item.item_id,
+    }
+}
+
+fn parse_storable_type(code: &str) -> Result<StorableType, DomainError> {
+    match code {
+        "sample" => Ok(StorableType::Sample),
+        "library" => Ok(StorableType::Library),
+        "library_aliquot" => Ok(StorableType::LibraryAliquot),
+        "pool" => Ok(StorableType::Pool),
+        _ => Err(DomainError::Validation(format!(
+            "Invalid storable type: {}",
+            code
+        ))),
+    }
+}
@@ -0,0 +1,645 @@
+//! Storage unit service.
+//!
+//! Freezers, shelves and racks are recorded as units so that boxes are
+//! assigned to racks that exist and have room, rather than to free-text
+//! locations. A box in a rack has its location path filled in from the
+//! rack's shelf and freezer, so box browsing, audits and the dashboard see
+//! the same names.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{
+    EntityId, StorageBox, StorageLocation, StorageUnit, StorageUnitKind,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{StorageBoxRepository, StorageUnitRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    AssignBoxRequest, BoxSummary, CreateStorageUnitRequest, StorageUnitFilter, StorageUnitMap,
+    StorageUnitResponse, UpdateStorageUnitRequest,
+};
+
+/// Service for the freezers, shelves and racks boxes are kept in.
+pub struct StorageUnitService<U, B>
+where
+    U: StorageUnitRepository + ?Sized,
+    B: StorageBoxRepository + ?Sized,
+{
+    units: Arc<U>,
+    boxes: Arc<B>,
+    audit: AuditTrail,
+}
+
+impl<U, B> StorageUnitService<U, B>
+where
+    U: StorageUnitRepository + ?Sized,
+    B: StorageBoxRepository + ?Sized,
+{
+    /// Creates a new storage unit service.
+    pub fn new(units: Arc<U>, boxes: Arc<B>) -> Self {
+        Self {
+            units,
+            boxes,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Records storage unit changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Adds a freezer, a shelf to a freezer or a rack to a shelf.
+    ///
+    /// The parent must have room for another unit, names are unique among
+    /// the units in the same parent, and barcodes are unique.
+    #[instrument(skip(self, request), fields(kind = %request.kind, name = %request.name))]
+    pub async fn create_unit(
+        &self,
+        request: CreateStorageUnitRequest,
+        created_by: &str,
+    ) -> Result<StorageUnitResponse, DomainError> {
+        let kind: StorageUnitKind = request.kind.parse().map_err(DomainError::Validation)?;
+        let parent = match request.parent_id {
+            Some(parent_id) => Some(self.find_unit(parent_id).await?),
+            None => None,
+        };
+
+        let mut unit = StorageUnit::new(kind, &request.name, parent.as_ref(), created_by.to_string())?;
+        let siblings = self.siblings(&unit).await?;
+        if let Some(parent) = &parent {
+            parent.check_room(siblings.len())?;
+        }
+        ensure_name_free(&siblings, &unit)?;
+
+        unit.barcode = self.free_barcode(request.barcode, None).await?;
+        unit.capacity = request.capacity;
+        unit.temperature = request.temperature;
+        unit.validate()?;
+
+        unit.id = self.units.save(&unit).await?;
+        self.audit
+            .created("StorageUnit", unit.id, &unit, created_by)
+            .await?;
+
+        info!("Created {} {} (ID: {})", unit.kind, unit.name, unit.id);
+        Ok(unit.into())
+    }
+
+    /// Gets a unit by ID.
+    pub async fn get_unit(&self, id: EntityId) -> Result<StorageUnitResponse, DomainError> {
+        Ok(self.find_unit(id).await?.into())
+    }
+
+    /// Lists the units in a unit, or the freezers.
+    pub async fn list_units(
+        &self,
+        filter: StorageUnitFilter,
+    ) -> Result<Vec<StorageUnitResponse>, DomainError> {
+        let units = match filter.parent_id {
+            Some(parent_id) => self.units.find_children(parent_id).await?,
+            None => self.units.list_freezers().await?,
+        };
+
+        Ok(units.into_iter().map(Into::into).collect())
+    }
+
+    /// Renames, relabels or resizes a unit, or records its temperature.
+    ///
+    /// A unit can't be made smaller than what it already holds. Boxes below
+    /// a renamed unit, or one whose temperature changed, are relocated to
+    /// match.
+    #[instrument(skip(self, request))]
+    pub async fn update_unit(
+        &self,
+        id: EntityId,
+        request: UpdateStorageUnitRequest,
+        updated_by: &str,
+    ) -> Result<StorageUnitResponse, DomainError> {
+        let mut unit = self.find_unit(id).await?;
+        let before = unit.clone();
+
+        if let Some(name) = request.name {
+            unit.name = name.trim().to_string();
+            if unit.name != before.name {
+                ensure_name_free(&self.siblings(&unit).await?, &unit)?;
+            }
+        }
+        if request.barcode.is_some() {
+            unit.barcode = self.free_barcode(request.barcode, Some(id)).await?;
+        }
+        if let Some(capacity) = request.capacity {
+            let held = self.held(&unit).await?;
+            if (capacity as usize) < held {
+                return Err(DomainError::Validation(format!(
+                    "{} {} already holds {}, more than a capacity of {}",
+                    unit.kind, unit.name, held, capacity
+                )));
+            }
+            unit.capacity = Some(capacity);
+        }
+        if request.temperature.is_some() {
+            unit.temperature = request.temperature;
+        }
+        unit.validate()?;
+
+        if unit == before {
+            return Ok(unit.into());
+        }
+        unit.updated_at = Utc::now();
+        self.units.save(&unit).await?;
+        self.audit
+            .updated("StorageUnit", id, &before, &unit, updated_by)
+            .await?;
+
+        if unit.name != before.name || unit.temperature != before.temperature {
+            self.relocate_boxes(&unit).await?;
+        }
+
+        info!("Updated {} {} (ID: {})", unit.kind, unit.name, id);
+        Ok(unit.into())
+    }
+
+    /// Deletes an empty unit.
+    #[instrument(skip(self))]
+    pub async fn delete_unit(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let unit = self.find_unit(id).await?;
+        let held = self.held(&unit).await?;
+        if held > 0 {
+            return Err(DomainError::Validation(format!(
+                "{} {} still holds {} {}",
+                unit.kind,
+                unit.name,
+                held,
+                match unit.kind {
+                    StorageUnitKind::Freezer => "shelves",
+                    StorageUnitKind::Shelf => "racks",
+                    StorageUnitKind::Rack => "boxes",
+                }
+            )));
+        }
+
+        self.units.delete(id).await?;
+        self.audit
+            .deleted("StorageUnit", id, &unit, deleted_by)
+            .await?;
+
+        info!("Deleted {} {} (ID: {})", unit.kind, unit.name, id);
+        Ok(())
+    }
+
+    /// Puts a box in a rack with room for it, or takes it out of storage.
+    ///
+    /// The box's location is set to the rack's freezer, shelf and rack, and
+    /// cleared when it is taken out.
+    #[instrument(skip(self))]
+    pub async fn assign_box(
+        &self,
+        box_id: EntityId,
+        request: AssignBoxRequest,
+    ) -> Result<BoxSummary, DomainError> {
+        let mut storage_box = self.find_box(box_id).await?;
+        if storage_box.rack_id == request.rack_id {
+            return Ok(BoxSummary::from(&storage_box));
+        }
+
+        match request.rack_id {
+            Some(rack_id) => {
+                let rack = self.find_unit(rack_id).await?;
+                if rack.kind != StorageUnitKind::Rack {
+                    return Err(DomainError::Validation(format!(
+                        "Boxes go in racks, not in {} {}",
+                        rack.kind, rack.name
+                    )));
+                }
+                rack.check_room(self.boxes.find_by_rack(rack_id).await?.len())?;
+
+                let (freezer, shelf) = self.rack_path(&rack).await?;
+                storage_box.location = StorageLocation::in_rack(&freezer, &shelf, &rack);
+            }
+            None => storage_box.location = StorageLocation::new(),
+        }
+        storage_box.rack_id = request.rack_id;
+        storage_box.updated_at = Utc::now();
+        self.boxes.save(&storage_box).await?;
+
+        info!(
+            "Moved box {} (ID: {}) to {}",
+            storage_box.name,
+            box_id,
+            match storage_box.location.path() {
+                path if path.is_empty() => "no location".to_string(),
+                path => path,
+            }
+        );
+        Ok(BoxSummary::from(&storage_box))
+    }
+
+    /// Maps a freezer, shelf or rack: the units in it, down to the boxes in
+    /// each rack.
+    #[instrument(skip(self))]
+    pub async fn unit_map(&self, id: EntityId) -> Result<StorageUnitMap, DomainError> {
+        let unit = self.find_unit(id).await?;
+        match unit.kind {
+            StorageUnitKind::Rack => self.rack_map(&unit).await,
+            StorageUnitKind::Shelf => self.shelf_map(&unit).await,
+            StorageUnitKind::Freezer => {
+                let mut shelves = Vec::new();
+                for shelf in self.units.find_children(id).await? {
+                    shelves.push(self.shelf_map(&shelf).await?);
+                }
+                Ok(StorageUnitMap::new(&unit, shelves, Vec::new()))
+            }
+        }
+    }
+
+    async fn shelf_map(&self, shelf: &StorageUnit) -> Result<StorageUnitMap, DomainError> {
+        let mut racks = Vec::new();
+        for rack in self.units.find_children(shelf.id).await? {
+            racks.push(self.rack_map(&rack).await?);
+        }
+        Ok(StorageUnitMap::new(shelf, racks, Vec::new()))
+    }
+
+    async fn rack_map(&self, rack: &StorageUnit) -> Result<StorageUnitMap, DomainError> {
+        let boxes = self.boxes.find_by_rack(rack.id).await?;
+        let summaries = boxes.iter().map(BoxSummary::from).collect();
+        Ok(StorageUnitMap::new(rack, Vec::new(), summaries))
+    }
+
+    /// Sets the location of every box below `unit` from its rack's path.
+    async fn relocate_boxes(&self, unit: &StorageUnit) -> Result<(), DomainError> {
+        let racks = match unit.kind {
+            StorageUnitKind::Rack => vec![unit.clone()],
+            StorageUnitKind::Shelf => self.units.find_children(unit.id).await?,
+            StorageUnitKind::Freezer => {
+                let mut racks = Vec::new();
+                for shelf in self.units.find_children(unit.id).await? {
+                    racks.extend(self.units.find_children(shelf.id).await?);
+                }
+                racks
+            }
+        };
+
+        for rack in racks {
+            let (freezer, shelf) = self.rack_path(&rack).await?;
+            let location = StorageLocation::in_rack(&freezer, &shelf, &rack);
+            for mut storage_box in self.boxes.find_by_rack(rack.id).await? {
+                if storage_box.location != location {
+                    storage_box.location = location.clone();
+                    storage_box.updated_at = Utc::now();
+                    self.boxes.save(&storage_box).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the freezer and shelf a rack is in.
+    async fn rack_path(&self, rack: &StorageUnit) -> Result<(StorageUnit, StorageUnit), DomainError> {
+        let shelf = self.parent(rack).await?;
+        let freezer = self.parent(&shelf).await?;
+        Ok((freezer, shelf))
+    }
+
+    async fn parent(&self, unit: &StorageUnit) -> Result<StorageUnit, DomainError> {
+        let parent_id = unit.parent_id.ok_or_else(|| {
+            DomainError::Validation(format!("{} {} is not in storage", unit.kind, unit.name))
+        })?;
+        self.find_unit(parent_id).await
+    }
+
+    /// Returns the units sharing `unit`'s parent, including itself once
+    /// saved.
+    async fn siblings(&self, unit: &StorageUnit) -> Result<Vec<StorageUnit>, DomainError> {
+        match unit.parent_id {
+            Some(parent_id) => self.units.find_children(parent_id).await,
+            None => self.units.list_freezers().await,
+        }
+    }
+
+    /// Returns how many units, or for a rack boxes, `unit` holds.
+    async fn held(&self, unit: &StorageUnit) -> Result<usize, DomainError> {
+        Ok(match unit.kind {
+            StorageUnitKind::Rack => self.boxes.find_by_rack(unit.id).await?.len(),
+            _ => self.units.find_children(unit.id).await?.len(),
+        })
+    }
+
+    /// Trims a barcode and fails if another unit already has it.
+    async fn free_barcode(
+        &self,
+        barcode: Option<String>,
+        id: Option<EntityId>,
+    ) -> Result<Option<String>, DomainError> {
+        let Some(barcode) = barcode
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        match self.units.find_by_barcode(&barcode).await? {
+            Some(existing) if Some(existing.id) != id => Err(DomainError::Duplicate {
+                entity_type: "StorageUnit".to_string(),
+                field: "barcode".to_string(),
+                value: barcode,
+            }),
+            _ => Ok(Some(barcode)),
+        }
+    }
+
+    async fn find_unit(&self, id: EntityId) -> Result<StorageUnit, DomainError> {
+        self.units
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "StorageUnit".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn find_box(&self, id: EntityId) -> Result<StorageBox, DomainError> {
+        self.boxes
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Box".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+/// Fails if another of `siblings` has `unit`'s name, ignoring case.
+fn ensure_name_free(siblings: &[StorageUnit], unit: &StorageUnit) -> Result<(), DomainError> {
+    if siblings
+        .iter()
+        .any(|s| s.id != unit.id && s.name.eq_ignore_ascii_case(&unit.name))
+    {
+        return Err(DomainError::Duplicate {
+            entity_type: "StorageUnit".to_string(),
+            field: "name".to_string(),
+            value: unit.name.clone(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::StorableType;
+    use miso_domain::repositories::QueryOptions;
+    use miso_domain::value_objects::{BoxPosition, Dimension};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryUnits {
+        units: Mutex<Vec<StorageUnit>>,
+    }
+
+    #[async_trait]
+    impl StorageUnitRepository for InMemoryUnits {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageUnit>, DomainError> {
+            Ok(self.units.lock().unwrap().iter().find(|u| u.id == id).cloned())
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<StorageUnit>, DomainError> {
+            Ok(self
+                .units
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|u| u.barcode.as_deref() == Some(barcode))
+                .cloned())
+        }
+        async fn find_children(&self, parent_id: EntityId) -> Result<Vec<StorageUnit>, DomainError> {
+            Ok(self
+                .units
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|u| u.parent_id == Some(parent_id))
+                .cloned()
+                .collect())
+        }
+        async fn list_freezers(&self) -> Result<Vec<StorageUnit>, DomainError> {
+            Ok(self
+                .units
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|u| u.parent_id.is_none())
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, unit: &StorageUnit) -> Result<EntityId, DomainError> {
+            let mut units = self.units.lock().unwrap();
+            let mut unit = unit.clone();
+            if unit.id == 0 {
+                unit.id = units.len() as EntityId + 1;
+            }
+            let id = unit.id;
+            match units.iter_mut().find(|u| u.id == id) {
+                Some(stored) => *stored = unit,
+                None => units.push(unit),
+            }
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.units.lock().unwrap().retain(|u| u.id != id);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryBoxes {
+        boxes: Mutex<Vec<StorageBox>>,
+    }
+
+    #[async_trait]
+    impl StorageBoxRepository for InMemoryBoxes {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageBox>, DomainError> {
+            Ok(self.boxes.lock().unwrap().iter().find(|b| b.id == id).cloned())
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_location(&self, _: &str) -> Result<Vec<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_rack(&self, rack_id: EntityId) -> Result<Vec<StorageBox>, DomainError> {
+            Ok(self
+                .boxes
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| b.rack_id == Some(rack_id))
+                .cloned()
+                .collect())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<StorageBox>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_item(
+            &self,
+            _: StorableType,
+            _: EntityId,
+        ) -> Result<Option<(StorageBox, BoxPosition)>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, storage_box: &StorageBox) -> Result<EntityId, DomainError> {
+            let mut boxes = self.boxes.lock().unwrap();
+            boxes.retain(|b| b.id != storage_box.id);
+            boxes.push(storage_box.clone());
+            Ok(storage_box.id)
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn service() -> StorageUnitService<InMemoryUnits, InMemoryBoxes> {
+        let boxes = InMemoryBoxes::default();
+        for id in 1..=3 {
+            boxes.boxes.lock().unwrap().push(StorageBox::new(
+                id,
+                format!("Box {}", id),
+                Dimension::CRYOBOX_9X9,
+                StorableType::Sample,
+            ));
+        }
+        StorageUnitService::new(Arc::new(InMemoryUnits::default()), Arc::new(boxes))
+    }
+
+    fn request(kind: &str, name: &str, parent_id: Option<EntityId>) -> CreateStorageUnitRequest {
+        CreateStorageUnitRequest {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            parent_id,
+            barcode: None,
+            capacity: None,
+            temperature: None,
+        }
+    }
+
+    /// Creates Freezer 1 (-80 °C) > Shelf 1 > Rack A, holding two boxes.
+    async fn rack(service: &StorageUnitService<InMemoryUnits, InMemoryBoxes>) -> EntityId {
+        let freezer = service
+            .create_unit(
+                CreateStorageUnitRequest {
+                    temperature: Some(-80),
+                    ..request("freezer", "Freezer 1", None)
+                },
+                "tech",
+            )
+            .await
+            .unwrap();
+        let shelf = service
+            .create_unit(request("shelf", "Shelf 1", Some(freezer.id)), "tech")
+            .await
+            .unwrap();
+        let rack = service
+            .create_unit(
+                CreateStorageUnitRequest {
+                    capacity: Some(2),
+                    ..request("rack", "Rack A", Some(shelf.id))
+                },
+                "tech",
+            )
+            .await
+            .unwrap();
+        rack.id
+    }
+
+    #[tokio::test]
+    async fn test_units_nest_with_unique_names() {
+        let service = service();
+        let rack_id = rack(&service).await;
+        let shelf_id = service.get_unit(rack_id).await.unwrap().parent_id.unwrap();
+
+        assert!(service
+            .create_unit(request("rack", "rack a", Some(shelf_id)), "tech")
+            .await
+            .is_err());
+        assert!(service
+            .create_unit(request("rack", "Rack B", Some(rack_id)), "tech")
+            .await
+            .is_err());
+        assert!(service
+            .create_unit(request("drawer", "Drawer 1", None), "tech")
+            .await
+            .is_err());
+
+        let racks = service
+            .list_units(StorageUnitFilter {
+                parent_id: Some(shelf_id),
+            })
+            .await
+            .unwrap();
+        assert_eq!(racks.len(), 1);
+        assert_eq!(racks[0].kind, "rack");
+        assert!(service.delete_unit(shelf_id, "tech").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_boxes_are_assigned_to_racks_with_room() {
+        let service = service();
+        let rack_id = rack(&service).await;
+        let shelf_id = service.get_unit(rack_id).await.unwrap().parent_id.unwrap();
+        let assign = |rack_id| AssignBoxRequest { rack_id };
+
+        assert!(service.assign_box(1, assign(Some(shelf_id))).await.is_err());
+        let summary = service.assign_box(1, assign(Some(rack_id))).await.unwrap();
+        assert_eq!(summary.rack_id, Some(rack_id));
+        assert_eq!(summary.freezer.as_deref(), Some("Freezer 1"));
+        assert_eq!(summary.rack.as_deref(), Some("Rack A"));
+        service.assign_box(2, assign(Some(rack_id))).await.unwrap();
+        assert!(service.assign_box(3, assign(Some(rack_id))).await.is_err());
+
+        let map = service.unit_map(1).await.unwrap();
+        let rack_map = &map.children[0].children[0];
+        assert_eq!(rack_map.boxes.len(), 2);
+        assert!(rack_map.full);
+        assert!(service
+            .update_unit(
+                rack_id,
+                UpdateStorageUnitRequest {
+                    name: None,
+                    barcode: None,
+                    capacity: Some(1),
+                    temperature: None,
+                },
+                "tech",
+            )
+            .await
+            .is_err());
+
+        service
+            .update_unit(
+                1,
+                UpdateStorageUnitRequest {
+                    name: Some("Freezer 7".to_string()),
+                    barcode: None,
+                    capacity: None,
+                    temperature: None,
+                },
+                "tech",
+            )
+            .await
+            .unwrap();
+        let moved = service.boxes.find_by_id(2).await.unwrap().unwrap();
+        assert_eq!(moved.location.path(), "Freezer 7 / Shelf 1 / Rack A");
+        assert_eq!(moved.location.temperature, Some(-80));
+
+        let removed = service.assign_box(2, assign(None)).await.unwrap();
+        assert_eq!(removed.freezer, None);
+        service.assign_box(3, assign(Some(rack_id))).await.unwrap();
+    }
+}
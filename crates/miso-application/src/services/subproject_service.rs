@@ -0,0 +1,333 @@
+//! Subproject service.
+//!
+//! Large projects are partitioned into subprojects - cohorts, collection
+//! sites, phases - so their samples can be listed and filtered a part at a
+//! time. Subprojects only group samples: access and locking stay with the
+//! project, and deleting a subproject leaves its samples in the project.
+
+use std::sync::Arc;
+
+use miso_domain::entities::{EntityId, Role, Subproject};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectRepository, SubprojectRepository};
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{CreateSubprojectRequest, SubprojectResponse, UpdateSubprojectRequest};
+use crate::locks::ProjectLocks;
+
+/// Service for subprojects.
+pub struct SubprojectService {
+    subprojects: Arc<dyn SubprojectRepository>,
+    projects: Arc<dyn ProjectRepository>,
+    audit: AuditTrail,
+    locks: ProjectLocks,
+}
+
+impl SubprojectService {
+    /// Creates a new subproject service.
+    pub fn new(
+        subprojects: Arc<dyn SubprojectRepository>,
+        projects: Arc<dyn ProjectRepository>,
+    ) -> Self {
+        Self {
+            subprojects,
+            projects,
+            audit: AuditTrail::default(),
+            locks: ProjectLocks::default(),
+        }
+    }
+
+    /// Records subproject changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Refuses changes to locked projects' subprojects, except by admins.
+    pub fn with_project_locks(mut self, locks: ProjectLocks) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Lists a project's subprojects by alias.
+    pub async fn list(&self, project_id: EntityId) -> Result<Vec<SubprojectResponse>, DomainError> {
+        self.find_project(project_id).await?;
+        let subprojects = self.subprojects.find_by_project(project_id).await?;
+        Ok(subprojects.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates a subproject of a project.
+    #[instrument(skip(self, request))]
+    pub async fn create(
+        &self,
+        project_id: EntityId,
+        request: CreateSubprojectRequest,
+        created_by: &str,
+        role: Role,
+    ) -> Result<SubprojectResponse, DomainError> {
+        let project = self.find_project(project_id).await?;
+        self.locks.check(project_id, role).await?;
+        let mut subproject = Subproject::new(
+            project_id,
+            request.alias,
+            request.description,
+            created_by.to_string(),
+        )?;
+
+        subproject.id = self.subprojects.save(&subproject).await?;
+        self.audit
+            .created("Subproject", subproject.id, &subproject, created_by)
+            .await?;
+
+        info!("Created subproject {} of project {}", subproject.alias, project);
+
+        Ok(subproject.into())
+    }
+
+    /// Renames a subproject or changes its description.
+    #[instrument(skip(self, request))]
+    pub async fn update(
+        &self,
+        project_id: EntityId,
+        subproject_id: EntityId,
+        request: UpdateSubprojectRequest,
+        updated_by: &str,
+        role: Role,
+    ) -> Result<SubprojectResponse, DomainError> {
+        let before = self.find_subproject(project_id, subproject_id).await?;
+        self.locks.check(project_id, role).await?;
+        let mut subproject = before.clone();
+
+        if let Some(alias) = request.alias {
+            subproject.rename(alias)?;
+        }
+        if let Some(description) = request.description {
+            subproject.description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+        }
+
+        self.subprojects.save(&subproject).await?;
+        self.audit
+            .updated("Subproject", subproject.id, &before, &subproject, updated_by)
+            .await?;
+
+        info!("Updated subproject {} of project {}", subproject_id, project_id);
+
+        Ok(subproject.into())
+    }
+
+    /// Deletes a subproject. Its samples stay in the project, unassigned.
+    #[instrument(skip(self))]
+    pub async fn delete(
+        &self,
+        project_id: EntityId,
+        subproject_id: EntityId,
+        deleted_by: &str,
+        role: Role,
+    ) -> Result<(), DomainError> {
+        let subproject = self.find_subproject(project_id, subproject_id).await?;
+        self.locks.check(project_id, role).await?;
+
+        self.subprojects.delete(subproject_id).await?;
+        self.audit
+            .deleted("Subproject", subproject_id, &subproject, deleted_by)
+            .await?;
+
+        info!("Deleted subproject {} of project {}", subproject.alias, project_id);
+
+        Ok(())
+    }
+
+    /// Finds a project, returning its code.
+    async fn find_project(&self, project_id: EntityId) -> Result<String, DomainError> {
+        self.projects
+            .find_by_id(project_id)
+            .await?
+            .map(|p| p.code)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Project".to_string(),
+                id: project_id.to_string(),
+            })
+    }
+
+    /// Finds one of a project's subprojects.
+    async fn find_subproject(
+        &self,
+        project_id: EntityId,
+        subproject_id: EntityId,
+    ) -> Result<Subproject, DomainError> {
+        self.subprojects
+            .find_by_id(subproject_id)
+            .await?
+            .filter(|s| s.project_id == project_id)
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Subproject".to_string(),
+                id: subproject_id.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::Project;
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemorySubprojects(Mutex<Vec<Subproject>>);
+
+    #[async_trait]
+    impl SubprojectRepository for InMemorySubprojects {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Subproject>, DomainError> {
+            Ok(self.0.lock().unwrap().iter().find(|s| s.id == id).cloned())
+        }
+        async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Subproject>, DomainError> {
+            let mut subprojects: Vec<Subproject> = self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| s.project_id == project_id)
+                .cloned()
+                .collect();
+            subprojects.sort_by(|a, b| a.alias.cmp(&b.alias));
+            Ok(subprojects)
+        }
+        async fn save(&self, subproject: &Subproject) -> Result<EntityId, DomainError> {
+            let mut subprojects = self.0.lock().unwrap();
+            if subprojects.iter().any(|s| {
+                s.id != subproject.id
+                    && s.project_id == subproject.project_id
+                    && s.alias == subproject.alias
+            }) {
+                return Err(DomainError::Duplicate {
+                    entity_type: "Subproject".to_string(),
+                    field: "alias".to_string(),
+                    value: subproject.alias.clone(),
+                });
+            }
+            let mut subproject = subproject.clone();
+            if subproject.id == 0 {
+                subproject.id = subprojects.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+            }
+            subprojects.retain(|s| s.id != subproject.id);
+            subprojects.push(subproject.clone());
+            Ok(subproject.id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.0.lock().unwrap().retain(|s| s.id != id);
+            Ok(())
+        }
+    }
+
+    struct Projects(Vec<Project>);
+
+    #[async_trait]
+    impl ProjectRepository for Projects {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Project>, DomainError> {
+            Ok(self.0.iter().find(|p| p.id == id).cloned())
+        }
+        async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+            unimplemented!()
+        }
+        async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count(&self) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subprojects_partition_their_own_project() {
+        let service = SubprojectService::new(
+            Arc::new(InMemorySubprojects::default()),
+            Arc::new(Projects(vec![
+                Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string()),
+                Project::new(2, "PROJ2".to_string(), "Two".to_string(), "lm".to_string()),
+            ])),
+        );
+        let create = |alias: &str| CreateSubprojectRequest {
+            alias: alias.to_string(),
+            description: None,
+        };
+
+        let cohort_b = service.create(1, create("COHORT-B"), "lm", Role::LabManager).await.unwrap();
+        service.create(1, create("COHORT-A"), "lm", Role::LabManager).await.unwrap();
+        service.create(2, create("COHORT-A"), "lm", Role::LabManager).await.unwrap();
+        assert!(matches!(
+            service.create(1, create(" COHORT-A "), "lm", Role::LabManager).await,
+            Err(DomainError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            service.create(3, create("COHORT-A"), "lm", Role::LabManager).await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        let aliases: Vec<String> = service.list(1).await.unwrap().into_iter().map(|s| s.alias).collect();
+        assert_eq!(aliases, vec!["COHORT-A", "COHORT-B"]);
+
+        // Another project's subproject can't be reached through this one
+        let rename = UpdateSubprojectRequest {
+            alias: Some("COHORT-C".to_string()),
+            description: Some("Second wave".to_string()),
+        };
+        assert!(service
+            .update(2, cohort_b.id, rename.clone(), "lm", Role::LabManager)
+            .await
+            .is_err());
+        let renamed = service.update(1, cohort_b.id, rename, "lm", Role::LabManager).await.unwrap();
+        assert_eq!(renamed.alias, "COHORT-C");
+        assert_eq!(renamed.description.as_deref(), Some("Second wave"));
+
+        service.delete(1, cohort_b.id, "lm", Role::LabManager).await.unwrap();
+        assert_eq!(service.list(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_locked_projects_subprojects_change_only_by_admins() {
+        let mut project = Project::new(1, "PROJ1".to_string(), "One".to_string(), "lm".to_string());
+        project.lock("PI review".to_string(), "lm".to_string(), None);
+        let projects = Arc::new(Projects(vec![project]));
+        let service =
+            SubprojectService::new(Arc::new(InMemorySubprojects::default()), projects.clone())
+                .with_project_locks(ProjectLocks::new(projects));
+        let create = CreateSubprojectRequest {
+            alias: "COHORT-A".to_string(),
+            description: None,
+        };
+
+        assert!(matches!(
+            service.create(1, create.clone(), "lm", Role::LabManager).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        let cohort = service.create(1, create, "admin", Role::Admin).await.unwrap();
+
+        let rename = UpdateSubprojectRequest {
+            alias: Some("COHORT-B".to_string()),
+            description: None,
+        };
+        assert!(matches!(
+            service.update(1, cohort.id, rename, "lm", Role::LabManager).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        assert!(matches!(
+            service.delete(1, cohort.id, "lm", Role::LabManager).await,
+            Err(DomainError::ProjectLocked { .. })
+        ));
+        service.delete(1, cohort.id, "admin", Role::Admin).await.unwrap();
+    }
+}
@@ -0,0 +1,763 @@
+//! Transfer service.
+//!
+//! Transfers record samples and libraries handed between labs or people,
+//! and what the recipient found when they arrived. Listing the transfers of
+//! one sample or library, oldest first, gives its chain of custody.
+//!
+//! Transfers sent by courier are followed by their tracking number, either
+//! by polling a tracking service or from status updates it pushes. When a
+//! shipment goes out for delivery, technicians and the transfer's
+//! recipient are told so someone is there to take it in.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{
+    EntityId, Role, ShipmentStatus, Transfer, TransferItemType, TransferReceipt,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+use miso_domain::repositories::{
+    LibraryRepository, QueryOptions, SampleRepository, TransferRepository,
+};
+use miso_domain::shipments::ShipmentTracker;
+use tracing::{info, instrument, warn};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    CreateTransferRequest, ReceiveTransferRequest, TrackingSummary, TrackingUpdateRequest,
+    TransferFilter, TransferResponse, UpdateShipmentRequest,
+};
+
+/// Who courier status updates found by polling are recorded as made by.
+const TRACKING_USER: &str = "shipment-tracking";
+
+/// Service for transfer operations.
+pub struct TransferService<T, S, L>
+where
+    T: TransferRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    transfers: Arc<T>,
+    samples: Arc<S>,
+    libraries: Arc<L>,
+    notifier: Option<Arc<dyn Notifier>>,
+    audit: AuditTrail,
+}
+
+impl<T, S, L> TransferService<T, S, L>
+where
+    T: TransferRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    /// Creates a new transfer service.
+    pub fn new(transfers: Arc<T>, samples: Arc<S>, libraries: Arc<L>) -> Self {
+        Self {
+            transfers,
+            samples,
+            libraries,
+            notifier: None,
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Tells technicians and recipients when shipments are out for
+    /// delivery.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Records transfers and receipts in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Records a transfer. Every sample and library sent must exist.
+    #[instrument(skip(self, request), fields(sender = %request.sender, recipient = %request.recipient))]
+    pub async fn create_transfer(
+        &self,
+        request: CreateTransferRequest,
+        created_by: &str,
+    ) -> Result<TransferResponse, DomainError> {
+        let mut items = Vec::new();
+
+        if !request.sample_ids.is_empty() {
+            let found = self.samples.find_by_ids(&request.sample_ids).await?;
+            for &id in &request.sample_ids {
+                if !found.iter().any(|s| s.id == id) {
+                    return Err(DomainError::NotFound {
+                        entity_type: "Sample".to_string(),
+                        id: id.to_string(),
+                    });
+                }
+                items.push((TransferItemType::Sample, id));
+            }
+        }
+
+        if !request.library_ids.is_empty() {
+            let found = self.libraries.find_by_ids(&request.library_ids).await?;
+            for &id in &request.library_ids {
+                if !found.iter().any(|l| l.id == id) {
+                    return Err(DomainError::NotFound {
+                        entity_type: "Library".to_string(),
+                        id: id.to_string(),
+                    });
+                }
+                items.push((TransferItemType::Library, id));
+            }
+        }
+
+        let mut transfer = Transfer::new(
+            &request.sender,
+            &request.recipient,
+            &items,
+            request.sent_at.unwrap_or_else(Utc::now),
+            created_by.to_string(),
+        )?;
+        transfer.notes = request.notes.filter(|n| !n.trim().is_empty());
+        transfer.set_shipment(request.courier, request.tracking_number, Utc::now())?;
+        transfer.recipient_email = request.recipient_email.filter(|e| !e.trim().is_empty());
+
+        transfer.id = self.transfers.save(&transfer).await?;
+        self.audit
+            .created("Transfer", transfer.id, &transfer, created_by)
+            .await?;
+
+        info!(
+            "Recorded transfer of {} items from {} to {} (ID: {})",
+            transfer.items.len(),
+            transfer.sender,
+            transfer.recipient,
+            transfer.id
+        );
+
+        Ok(transfer.into())
+    }
+
+    /// Gets a transfer by ID.
+    pub async fn get_transfer(&self, id: EntityId) -> Result<TransferResponse, DomainError> {
+        Ok(self.find_transfer(id).await?.into())
+    }
+
+    /// Lists transfers, most recently sent first, or the transfers of one
+    /// sample or library in the order sent.
+    pub async fn list_transfers(
+        &self,
+        filter: TransferFilter,
+    ) -> Result<Vec<TransferResponse>, DomainError> {
+        let item = match (filter.sample_id, filter.library_id) {
+            (Some(_), Some(_)) => {
+                return Err(DomainError::Validation(
+                    "Filter by a sample or a library, not both".to_string(),
+                ))
+            }
+            (Some(id), None) => Some((TransferItemType::Sample, id)),
+            (None, Some(id)) => Some((TransferItemType::Library, id)),
+            (None, None) => None,
+        };
+
+        let transfers = match item {
+            Some((item_type, id)) => self.transfers.find_by_item(item_type, id).await?,
+            None => {
+                let options = QueryOptions {
+                    limit: Some(filter.limit.unwrap_or(50).min(500)),
+                    offset: filter.offset,
+                    ..Default::default()
+                };
+                self.transfers.list(options).await?
+            }
+        };
+
+        Ok(transfers.into_iter().map(Into::into).collect())
+    }
+
+    /// Records whether items arrived and passed QC. The transfer is marked
+    /// received once every item is checked in.
+    #[instrument(skip(self, request))]
+    pub async fn receive(
+        &self,
+        id: EntityId,
+        request: ReceiveTransferRequest,
+        received_by: &str,
+    ) -> Result<TransferResponse, DomainError> {
+        let mut transfer = self.find_transfer(id).await?;
+        let before = transfer.clone();
+
+        let receipts: Vec<TransferReceipt> = request.items.into_iter().map(Into::into).collect();
+        transfer.receive(&receipts, received_by, Utc::now())?;
+
+        self.transfers.save(&transfer).await?;
+        self.audit
+            .updated("Transfer", id, &before, &transfer, received_by)
+            .await?;
+
+        for item in transfer.items.iter().filter(|i| {
+            i.received == Some(false) || i.qc_passed == Some(false)
+        }) {
+            warn!(
+                "{} {} of transfer {} from {} did not arrive in good condition",
+                item.item_type, item.entity_id, id, transfer.sender
+            );
+        }
+        if transfer.received_at.is_some() && before.received_at.is_none() {
+            info!("Transfer {} was received by {}", id, received_by);
+        }
+
+        Ok(transfer.into())
+    }
+
+    /// Sets or corrects the courier, tracking number and recipient address
+    /// of a transfer.
+    #[instrument(skip(self, request))]
+    pub async fn update_shipment(
+        &self,
+        id: EntityId,
+        request: UpdateShipmentRequest,
+        updated_by: &str,
+    ) -> Result<TransferResponse, DomainError> {
+        let mut transfer = self.find_transfer(id).await?;
+        let before = transfer.clone();
+
+        transfer.set_shipment(request.courier, request.tracking_number, Utc::now())?;
+        transfer.recipient_email = request.recipient_email.filter(|e| !e.trim().is_empty());
+
+        self.transfers.save(&transfer).await?;
+        self.audit
+            .updated("Transfer", id, &before, &transfer, updated_by)
+            .await?;
+
+        info!(
+            "Transfer {} is shipped by {} under {}",
+            id,
+            transfer.courier.as_deref().unwrap_or("no courier"),
+            transfer.tracking_number.as_deref().unwrap_or("no tracking number")
+        );
+
+        Ok(transfer.into())
+    }
+
+    /// Records a status pushed by a courier or tracking service for every
+    /// transfer sent under the tracking number.
+    #[instrument(skip(self, request), fields(tracking_number = %request.tracking_number))]
+    pub async fn record_tracking(
+        &self,
+        request: TrackingUpdateRequest,
+        updated_by: &str,
+    ) -> Result<Vec<TransferResponse>, DomainError> {
+        let transfers = self
+            .transfers
+            .find_by_tracking_number(request.tracking_number.trim())
+            .await?;
+        if transfers.is_empty() {
+            return Err(DomainError::NotFound {
+                entity_type: "Transfer".to_string(),
+                id: request.tracking_number,
+            });
+        }
+
+        let mut updated = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            updated.push(self.track(transfer, request.status, updated_by).await?.into());
+        }
+        Ok(updated)
+    }
+
+    /// Asks the tracking service where every shipment in transit has got
+    /// to. A shipment that can't be looked up is skipped and tried again
+    /// next time.
+    #[instrument(skip(self, tracker), fields(tracker = tracker.name()))]
+    pub async fn poll_tracking(
+        &self,
+        tracker: &dyn ShipmentTracker,
+    ) -> Result<TrackingSummary, DomainError> {
+        let mut summary = TrackingSummary::default();
+
+        for transfer in self.transfers.find_in_transit().await? {
+            let (Some(courier), Some(tracking_number)) =
+                (transfer.courier.clone(), transfer.tracking_number.clone())
+            else {
+                continue;
+            };
+            summary.checked += 1;
+
+            match tracker.status(&courier, &tracking_number).await {
+                Ok(Some(status)) if transfer.shipment_status != Some(status) => {
+                    self.track(transfer, status, TRACKING_USER).await?;
+                    summary.updated += 1;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("Couldn't track {} {}: {}", courier, tracking_number, e);
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Checked {} shipments: {} updated, {} failed",
+            summary.checked, summary.updated, summary.failed
+        );
+        Ok(summary)
+    }
+
+    /// Records a courier status, telling technicians and the recipient if
+    /// the shipment has just gone out for delivery.
+    async fn track(
+        &self,
+        mut transfer: Transfer,
+        status: ShipmentStatus,
+        updated_by: &str,
+    ) -> Result<Transfer, DomainError> {
+        let before = transfer.clone();
+        if !transfer.track(status, Utc::now())? {
+            return Ok(transfer);
+        }
+
+        self.transfers.save(&transfer).await?;
+        self.audit
+            .updated("Transfer", transfer.id, &before, &transfer, updated_by)
+            .await?;
+        info!("Transfer {} is now {}", transfer.id, status);
+
+        if status == ShipmentStatus::OutForDelivery {
+            if let Some(notifier) = &self.notifier {
+                if let Err(e) = notifier.notify(&out_for_delivery(&transfer)).await {
+                    warn!("Couldn't send delivery notice for transfer {}: {}", transfer.id, e);
+                }
+            }
+        }
+
+        Ok(transfer)
+    }
+
+    async fn find_transfer(&self, id: EntityId) -> Result<Transfer, DomainError> {
+        self.transfers
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Transfer".to_string(),
+                id: id.to_string(),
+            })
+    }
+}
+
+/// Builds the notice that a shipment is out for delivery.
+fn out_for_delivery(transfer: &Transfer) -> Notification {
+    let notification = Notification::new(
+        Role::Technician,
+        format!(
+            "Transfer {} from {} is out for delivery",
+            transfer.id, transfer.sender
+        ),
+        format!(
+            "{} item(s) sent by {} to {} on {} are out for delivery with {} (tracking number {}).",
+            transfer.items.len(),
+            transfer.sender,
+            transfer.recipient,
+            transfer.sent_at.format("%Y-%m-%d"),
+            transfer.courier.as_deref().unwrap_or("the courier"),
+            transfer.tracking_number.as_deref().unwrap_or("unknown")
+        ),
+    );
+
+    match &transfer.recipient_email {
+        Some(address) => notification.with_address(address),
+        None => notification,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Library, Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+    use crate::dto::TransferReceiptRequest;
+
+    #[derive(Default)]
+    struct Outbox {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Outbox {
+        async fn notify(&self, notification: &Notification) -> Result<(), DomainError> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    /// Says every shipment is out for delivery, except those it can't find.
+    struct OutForDelivery;
+
+    #[async_trait]
+    impl ShipmentTracker for OutForDelivery {
+        fn name(&self) -> &str {
+            "test"
+        }
+        async fn status(
+            &self,
+            _courier: &str,
+            tracking_number: &str,
+        ) -> Result<Option<ShipmentStatus>, DomainError> {
+            match tracking_number {
+                "LOST" => Err(DomainError::Validation("Tracking service is down".to_string())),
+                _ => Ok(Some(ShipmentStatus::OutForDelivery)),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryTransfers {
+        transfers: Mutex<Vec<Transfer>>,
+    }
+
+    #[async_trait]
+    impl TransferRepository for InMemoryTransfers {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Transfer>, DomainError> {
+            Ok(self.transfers.lock().unwrap().iter().find(|t| t.id == id).cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Transfer>, DomainError> {
+            Ok(self.transfers.lock().unwrap().iter().rev().cloned().collect())
+        }
+        async fn find_by_item(
+            &self,
+            item_type: TransferItemType,
+            entity_id: EntityId,
+        ) -> Result<Vec<Transfer>, DomainError> {
+            Ok(self
+                .transfers
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.item(item_type, entity_id).is_some())
+                .cloned()
+                .collect())
+        }
+        async fn find_by_tracking_number(
+            &self,
+            tracking_number: &str,
+        ) -> Result<Vec<Transfer>, DomainError> {
+            Ok(self
+                .transfers
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.tracking_number.as_deref() == Some(tracking_number))
+                .cloned()
+                .collect())
+        }
+        async fn find_in_transit(&self) -> Result<Vec<Transfer>, DomainError> {
+            Ok(self
+                .transfers
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.is_in_transit())
+                .cloned()
+                .collect())
+        }
+        async fn save(&self, transfer: &Transfer) -> Result<EntityId, DomainError> {
+            let mut transfers = self.transfers.lock().unwrap();
+            let mut transfer = transfer.clone();
+            if transfer.id == 0 {
+                transfer.id = transfers.len() as EntityId + 1;
+            }
+            let id = transfer.id;
+            match transfers.iter_mut().find(|t| t.id == id) {
+                Some(stored) => *stored = transfer,
+                None => transfers.push(transfer),
+            }
+            Ok(id)
+        }
+    }
+
+    /// Holds samples 1 and 2.
+    struct TwoSamples;
+
+    #[async_trait]
+    impl SampleRepository for TwoSamples {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, _: &[String]) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(ids
+                .iter()
+                .filter(|id| (1..=2).contains(*id))
+                .map(|&id| {
+                    Sample::new_plain(
+                        id,
+                        format!("SAM{}", id),
+                        Barcode::new(format!("SAM-{}", id)).unwrap(),
+                        1,
+                        "Homo sapiens".to_string(),
+                        "admin".to_string(),
+                    )
+                })
+                .collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct NoLibraries;
+
+    #[async_trait]
+    impl LibraryRepository for NoLibraries {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, _: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn create(sender: &str, recipient: &str, sample_ids: Vec<i32>) -> CreateTransferRequest {
+        CreateTransferRequest {
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            sent_at: None,
+            notes: None,
+            courier: None,
+            tracking_number: None,
+            recipient_email: None,
+            sample_ids,
+            library_ids: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_of_custody() {
+        let service = TransferService::new(
+            Arc::new(InMemoryTransfers::default()),
+            Arc::new(TwoSamples),
+            Arc::new(NoLibraries),
+        );
+
+        assert!(matches!(
+            service.create_transfer(create("Lab A", "Lab B", vec![1, 3]), "tech").await,
+            Err(DomainError::NotFound { .. })
+        ));
+        let mut with_library = create("Lab A", "Lab B", vec![1]);
+        with_library.library_ids = vec![9];
+        assert!(service.create_transfer(with_library, "tech").await.is_err());
+
+        let first = service
+            .create_transfer(create("Lab A", "Lab B", vec![1, 2]), "tech")
+            .await
+            .unwrap();
+        service
+            .create_transfer(create("Lab B", "Sequencing core", vec![1]), "tech")
+            .await
+            .unwrap();
+
+        let receipt = |entity_id| TransferReceiptRequest {
+            item_type: TransferItemType::Sample,
+            entity_id,
+            received: true,
+            qc_passed: Some(true),
+            qc_note: None,
+        };
+        let received = service
+            .receive(
+                first.id,
+                ReceiveTransferRequest {
+                    items: vec![receipt(1), receipt(2)],
+                },
+                "recv",
+            )
+            .await
+            .unwrap();
+        assert_eq!(received.received_by.as_deref(), Some("recv"));
+
+        let custody = service
+            .list_transfers(TransferFilter {
+                sample_id: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let hops: Vec<_> = custody.iter().map(|t| t.recipient.as_str()).collect();
+        assert_eq!(hops, vec!["Lab B", "Sequencing core"]);
+        assert!(custody[1].received_at.is_none());
+
+        let sample_2 = TransferFilter {
+            sample_id: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(service.list_transfers(sample_2).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_out_for_delivery_notifies_recipient_once() {
+        let outbox = Arc::new(Outbox::default());
+        let service = TransferService::new(
+            Arc::new(InMemoryTransfers::default()),
+            Arc::new(TwoSamples),
+            Arc::new(NoLibraries),
+        )
+        .with_notifier(outbox.clone());
+
+        let mut untracked = create("Lab A", "Lab B", vec![1]);
+        untracked.tracking_number = Some("1Z999".to_string());
+        assert!(service.create_transfer(untracked, "tech").await.is_err());
+
+        let mut shipped = create("Lab A", "Lab B", vec![1]);
+        shipped.courier = Some("UPS".to_string());
+        shipped.tracking_number = Some("1Z999".to_string());
+        shipped.recipient_email = Some("receiving@lab-b.org".to_string());
+        let shipped = service.create_transfer(shipped, "tech").await.unwrap();
+        let mut lost = create("Lab A", "Lab C", vec![2]);
+        lost.courier = Some("UPS".to_string());
+        lost.tracking_number = Some("LOST".to_string());
+        service.create_transfer(lost, "tech").await.unwrap();
+        service
+            .create_transfer(create("Lab A", "Lab D", vec![2]), "tech")
+            .await
+            .unwrap();
+
+        let summary = service.poll_tracking(&OutForDelivery).await.unwrap();
+        assert_eq!(
+            summary,
+            TrackingSummary {
+                checked: 2,
+                updated: 1,
+                failed: 1
+            }
+        );
+        // Hearing the same status again, polled or pushed, doesn't notify twice
+        assert_eq!(service.poll_tracking(&OutForDelivery).await.unwrap().updated, 0);
+        let pushed = service
+            .record_tracking(
+                TrackingUpdateRequest {
+                    tracking_number: "1Z999".to_string(),
+                    status: ShipmentStatus::OutForDelivery,
+                },
+                "courier",
+            )
+            .await
+            .unwrap();
+        assert_eq!(pushed[0].id, shipped.id);
+        assert_eq!(pushed[0].shipment_status, Some(ShipmentStatus::OutForDelivery));
+
+        let sent = outbox.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].addresses, vec!["receiving@lab-b.org".to_string()]);
+
+        assert!(matches!(
+            service
+                .record_tracking(
+                    TrackingUpdateRequest {
+                        tracking_number: "unknown".to_string(),
+                        status: ShipmentStatus::Delivered,
+                    },
+                    "courier",
+                )
+                .await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+}
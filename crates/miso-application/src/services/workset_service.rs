@@ -0,0 +1,660 @@
+//! Workset service.
+//!
+//! A workset is a batch of samples and libraries taken through a bench
+//! session together. Members are added by ID or by barcode, so a rack scan
+//! can become a workset as it is, and changes can then be made to every
+//! member at once, provided the workset holds the controls a prep batch
+//! needs.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use miso_domain::entities::{ControlType, EntityId, Workset, WorksetItemType};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    LibraryRepository, QueryOptions, SampleRepository, WorksetRepository,
+};
+use miso_domain::services::ControlPolicy;
+use tracing::{info, instrument};
+
+use crate::audit::AuditTrail;
+use crate::dto::{
+    BulkSampleUpdate, BulkUpdateSamplesRequest, CreateWorksetRequest, UpdateSampleRequest,
+    WorksetItemsRequest, WorksetResponse,
+};
+
+/// Service for workset operations.
+pub struct WorksetService<W, S, L>
+where
+    W: WorksetRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    worksets: Arc<W>,
+    samples: Arc<S>,
+    libraries: Arc<L>,
+    controls: ControlPolicy,
+    audit: AuditTrail,
+}
+
+impl<W, S, L> WorksetService<W, S, L>
+where
+    W: WorksetRepository + ?Sized,
+    S: SampleRepository + ?Sized,
+    L: LibraryRepository + ?Sized,
+{
+    /// Creates a new workset service.
+    pub fn new(worksets: Arc<W>, samples: Arc<S>, libraries: Arc<L>) -> Self {
+        Self {
+            worksets,
+            samples,
+            libraries,
+            controls: ControlPolicy::default(),
+            audit: AuditTrail::default(),
+        }
+    }
+
+    /// Requires the controls a prep batch needs before a workset's samples
+    /// are updated together.
+    pub fn with_controls(mut self, controls: ControlPolicy) -> Self {
+        self.controls = controls;
+        self
+    }
+
+    /// Records workset changes in the audit log.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Creates a workset with its initial members.
+    ///
+    /// Workset names are unique. Every member must exist; an unknown
+    /// barcode refuses the whole request.
+    #[instrument(skip(self, request), fields(name = %request.name))]
+    pub async fn create_workset(
+        &self,
+        request: CreateWorksetRequest,
+        created_by: &str,
+    ) -> Result<WorksetResponse, DomainError> {
+        let name = request.name.trim().to_string();
+        if self.worksets.find_by_name(&name).await?.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "Workset".to_string(),
+                field: "name".to_string(),
+                value: name,
+            });
+        }
+
+        let mut workset = Workset::new(name, request.description, created_by.to_string());
+        let now = Utc::now();
+        for (item_type, id) in self.resolve(&request.items).await? {
+            workset.add(item_type, id, now);
+        }
+
+        workset.id = self.worksets.save(&workset).await?;
+        self.audit
+            .created("Workset", workset.id, &workset, created_by)
+            .await?;
+
+        info!(
+            "Created workset {} of {} items (ID: {})",
+            workset.name,
+            workset.items.len(),
+            workset.id
+        );
+
+        Ok(workset.into())
+    }
+
+    /// Gets a workset by ID.
+    pub async fn get_workset(&self, id: EntityId) -> Result<WorksetResponse, DomainError> {
+        Ok(self.find_workset(id).await?.into())
+    }
+
+    /// Lists worksets, newest first.
+    pub async fn list_worksets(
+        &self,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<WorksetResponse>, DomainError> {
+        let options = QueryOptions {
+            limit: Some(limit.unwrap_or(50).min(500)),
+            offset,
+            ..Default::default()
+        };
+        let worksets = self.worksets.list(options).await?;
+        Ok(worksets.into_iter().map(Into::into).collect())
+    }
+
+    /// Adds members to a workset. Members already in it are left as they
+    /// are.
+    #[instrument(skip(self, request))]
+    pub async fn add_items(
+        &self,
+        id: EntityId,
+        request: WorksetItemsRequest,
+        changed_by: &str,
+    ) -> Result<WorksetResponse, DomainError> {
+        let mut workset = self.find_workset(id).await?;
+        let before = workset.clone();
+
+        let now = Utc::now();
+        for (item_type, entity_id) in self.resolve(&request).await? {
+            workset.add(item_type, entity_id, now);
+        }
+
+        self.save(&before, workset, changed_by).await
+    }
+
+    /// Removes members from a workset. IDs are taken as they are, so that
+    /// deleted samples and libraries can be removed too.
+    #[instrument(skip(self, request))]
+    pub async fn remove_items(
+        &self,
+        id: EntityId,
+        request: WorksetItemsRequest,
+        changed_by: &str,
+    ) -> Result<WorksetResponse, DomainError> {
+        let mut workset = self.find_workset(id).await?;
+        let before = workset.clone();
+
+        let by_id = request
+            .sample_ids
+            .iter()
+            .map(|&id| (WorksetItemType::Sample, id))
+            .chain(request.library_ids.iter().map(|&id| (WorksetItemType::Library, id)));
+        let items: Vec<_> = by_id
+            .chain(self.resolve_barcodes(&request.barcodes).await?)
+            .collect();
+
+        let now = Utc::now();
+        for (item_type, entity_id) in items {
+            workset.remove(item_type, entity_id, now);
+        }
+
+        self.save(&before, workset, changed_by).await
+    }
+
+    /// Deletes a workset. Its members are not affected.
+    #[instrument(skip(self))]
+    pub async fn delete_workset(&self, id: EntityId, deleted_by: &str) -> Result<(), DomainError> {
+        let workset = self.find_workset(id).await?;
+        self.worksets.delete(id).await?;
+        self.audit
+            .deleted("Workset", id, &workset, deleted_by)
+            .await?;
+
+        info!("Deleted workset {} (ID: {})", workset.name, id);
+        Ok(())
+    }
+
+    /// Builds a bulk update making the same changes to every sample in a
+    /// workset, at the versions they have now.
+    ///
+    /// The workset is a prep batch, so it must hold the controls the
+    /// control policy requires of one, as samples or libraries.
+    pub async fn sample_updates(
+        &self,
+        id: EntityId,
+        changes: UpdateSampleRequest,
+    ) -> Result<BulkUpdateSamplesRequest, DomainError> {
+        let workset = self.find_workset(id).await?;
+        let ids = workset.ids(WorksetItemType::Sample);
+        let samples = if ids.is_empty() {
+            Vec::new()
+        } else {
+            self.samples.find_by_ids(&ids).await?
+        };
+        if samples.is_empty() {
+            return Err(DomainError::Validation(
+                "The workset has no samples".to_string(),
+            ));
+        }
+
+        let mut controls: Vec<ControlType> =
+            samples.iter().filter_map(|s| s.control_type).collect();
+        let library_ids = workset.ids(WorksetItemType::Library);
+        if !library_ids.is_empty() {
+            let libraries = self.libraries.find_by_ids(&library_ids).await?;
+            controls.extend(libraries.iter().filter_map(|l| l.control_type));
+        }
+        self.controls.check_batch(&workset.name, &controls)?;
+
+        Ok(BulkUpdateSamplesRequest {
+            updates: samples
+                .into_iter()
+                .map(|sample| BulkSampleUpdate {
+                    id: sample.id,
+                    version: sample.version,
+                    changes: changes.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn find_workset(&self, id: EntityId) -> Result<Workset, DomainError> {
+        self.worksets
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound {
+                entity_type: "Workset".to_string(),
+                id: id.to_string(),
+            })
+    }
+
+    async fn save(
+        &self,
+        before: &Workset,
+        workset: Workset,
+        changed_by: &str,
+    ) -> Result<WorksetResponse, DomainError> {
+        if workset == *before {
+            return Ok(workset.into());
+        }
+
+        self.worksets.save(&workset).await?;
+        self.audit
+            .updated("Workset", workset.id, before, &workset, changed_by)
+            .await?;
+
+        Ok(workset.into())
+    }
+
+    /// Looks up the members of a request, checking they all exist.
+    async fn resolve(
+        &self,
+        request: &WorksetItemsRequest,
+    ) -> Result<Vec<(WorksetItemType, EntityId)>, DomainError> {
+        let mut items = Vec::new();
+
+        if !request.sample_ids.is_empty() {
+            let found = self.samples.find_by_ids(&request.sample_ids).await?;
+            for &id in &request.sample_ids {
+                if !found.iter().any(|s| s.id == id) {
+                    return Err(DomainError::NotFound {
+                        entity_type: "Sample".to_string(),
+                        id: id.to_string(),
+                    });
+                }
+                items.push((WorksetItemType::Sample, id));
+            }
+        }
+
+        if !request.library_ids.is_empty() {
+            let found = self.libraries.find_by_ids(&request.library_ids).await?;
+            for &id in &request.library_ids {
+                if !found.iter().any(|l| l.id == id) {
+                    return Err(DomainError::NotFound {
+                        entity_type: "Library".to_string(),
+                        id: id.to_string(),
+                    });
+                }
+                items.push((WorksetItemType::Library, id));
+            }
+        }
+
+        items.extend(self.resolve_barcodes(&request.barcodes).await?);
+        Ok(items)
+    }
+
+    /// Looks up scanned barcodes as samples, or else libraries, in the
+    /// order scanned. Any barcode that is neither refuses them all.
+    async fn resolve_barcodes(
+        &self,
+        barcodes: &[String],
+    ) -> Result<Vec<(WorksetItemType, EntityId)>, DomainError> {
+        let barcodes: Vec<String> = barcodes
+            .iter()
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty())
+            .collect();
+        if barcodes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let samples: HashMap<String, EntityId> = self
+            .samples
+            .find_by_barcodes(&barcodes)
+            .await?
+            .into_iter()
+            .map(|s| (s.barcode.to_string(), s.id))
+            .collect();
+
+        let mut items = Vec::with_capacity(barcodes.len());
+        let mut unknown = Vec::new();
+        for barcode in &barcodes {
+            if let Some(&id) = samples.get(barcode) {
+                items.push((WorksetItemType::Sample, id));
+            } else if let Some(library) = self.libraries.find_by_barcode(barcode).await? {
+                items.push((WorksetItemType::Library, library.id));
+            } else {
+                unknown.push(barcode.as_str());
+            }
+        }
+
+        if !unknown.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "No sample or library has barcode {}",
+                unknown.join(", ")
+            )));
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{Library, LibraryDesign, LibraryType, Sample, SampleClass};
+    use miso_domain::repositories::{NewSample, VersionConflict};
+    use miso_domain::value_objects::{Barcode, QcStatus};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryWorksets {
+        worksets: Mutex<Vec<Workset>>,
+    }
+
+    #[async_trait]
+    impl WorksetRepository for InMemoryWorksets {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Workset>, DomainError> {
+            Ok(self.worksets.lock().unwrap().iter().find(|w| w.id == id).cloned())
+        }
+        async fn find_by_name(&self, name: &str) -> Result<Option<Workset>, DomainError> {
+            Ok(self.worksets.lock().unwrap().iter().find(|w| w.name == name).cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Workset>, DomainError> {
+            Ok(self.worksets.lock().unwrap().iter().rev().cloned().collect())
+        }
+        async fn save(&self, workset: &Workset) -> Result<EntityId, DomainError> {
+            let mut worksets = self.worksets.lock().unwrap();
+            let mut workset = workset.clone();
+            if workset.id == 0 {
+                workset.id = worksets.len() as EntityId + 1;
+            }
+            let id = workset.id;
+            worksets.retain(|w| w.id != id);
+            worksets.push(workset);
+            Ok(id)
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.worksets.lock().unwrap().retain(|w| w.id != id);
+            Ok(())
+        }
+    }
+
+    /// Holds samples 1 to 3, barcoded SAM-1 to SAM-3; sample 3 is a
+    /// negative control.
+    struct ThreeSamples;
+
+    fn sample(id: EntityId) -> Sample {
+        let mut sample = Sample::new_plain(
+            id,
+            format!("SAM{}", id),
+            Barcode::new(format!("SAM-{}", id)).unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        );
+        sample.version = id + 1;
+        if id == 3 {
+            sample.control_type = Some(ControlType::Negative);
+        }
+        sample
+    }
+
+    #[async_trait]
+    impl SampleRepository for ThreeSamples {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<Sample>, DomainError> {
+            Ok((1..=3).contains(&id).then(|| sample(id)))
+        }
+        async fn find_by_barcode(&self, _: &str) -> Result<Option<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcodes(&self, barcodes: &[String]) -> Result<Vec<Sample>, DomainError> {
+            Ok((1..=3)
+                .map(sample)
+                .filter(|s| barcodes.contains(&s.barcode.to_string()))
+                .collect())
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_subproject(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_parent(&self, _: EntityId) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_class(&self, _: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn search(&self, _: &str, _: u64) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+            Ok(ids
+                .iter()
+                .filter(|id| (1..=3).contains(*id))
+                .map(|&id| sample(id))
+                .collect())
+        }
+        async fn save(&self, _: &Sample) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn update_all_versioned(
+            &self,
+            _: &[Sample],
+        ) -> Result<Vec<VersionConflict>, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_project(&self, _: EntityId) -> Result<u64, DomainError> {
+            unimplemented!()
+        }
+        async fn find_qc_failed_since(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<Sample>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_received_by_day(
+            &self,
+            _: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError> {
+            unimplemented!()
+        }
+        async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// Holds library 7, barcoded LIB-7, a no-template control.
+    struct OneLibrary;
+
+    fn library() -> Library {
+        let mut library = Library::new(
+            7,
+            "LIB7".to_string(),
+            Barcode::new("LIB-7").unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "admin".to_string(),
+        );
+        library.control_type = Some(ControlType::Ntc);
+        library
+    }
+
+    #[async_trait]
+    impl LibraryRepository for OneLibrary {
+        async fn find_by_id(&self, _: EntityId) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Library>, DomainError> {
+            Ok((barcode == "LIB-7").then(library))
+        }
+        async fn find_by_name(&self, _: &str) -> Result<Option<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_sample(&self, _: EntityId) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_project(
+            &self,
+            _: EntityId,
+            _: QueryOptions,
+        ) -> Result<Vec<Library>, DomainError> {
+            unimplemented!()
+        }
+        async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+            Ok(ids.contains(&7).then(library).into_iter().collect())
+        }
+        async fn save(&self, _: &Library) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn insert_all(&self, _: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+            unimplemented!()
+        }
+        async fn create_from_sample(
+            &self,
+            _: &Library,
+            _: &Sample,
+        ) -> Result<EntityId, DomainError> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    type TestService = WorksetService<InMemoryWorksets, ThreeSamples, OneLibrary>;
+
+    fn service() -> TestService {
+        WorksetService::new(
+            Arc::new(InMemoryWorksets::default()),
+            Arc::new(ThreeSamples),
+            Arc::new(OneLibrary),
+        )
+    }
+
+    fn create(name: &str, items: WorksetItemsRequest) -> CreateWorksetRequest {
+        CreateWorksetRequest {
+            name: name.to_string(),
+            description: None,
+            items,
+        }
+    }
+
+    fn scanned(barcodes: &[&str]) -> WorksetItemsRequest {
+        WorksetItemsRequest {
+            barcodes: barcodes.iter().map(|b| b.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workset_from_scan() {
+        let service = service();
+
+        let workset = service
+            .create_workset(create("Bench 1", scanned(&["SAM-2", " LIB-7", "SAM-1"])), "tech")
+            .await
+            .unwrap();
+        assert_eq!((workset.sample_ids, workset.library_ids), (vec![2, 1], vec![7]));
+
+        let refused = service
+            .create_workset(create("Bench 2", scanned(&["SAM-3", "NOPE"])), "tech")
+            .await;
+        assert!(matches!(refused, Err(DomainError::Validation(m)) if m.contains("NOPE")));
+        assert!(matches!(
+            service.create_workset(create("Bench 1", scanned(&[])), "tech").await,
+            Err(DomainError::Duplicate { .. })
+        ));
+
+        let added = WorksetItemsRequest {
+            sample_ids: vec![3, 2],
+            ..Default::default()
+        };
+        let workset = service.add_items(workset.id, added, "tech").await.unwrap();
+        assert_eq!(workset.sample_ids, vec![2, 1, 3]);
+
+        let workset = service
+            .remove_items(workset.id, scanned(&["SAM-1", "LIB-7"]), "tech")
+            .await
+            .unwrap();
+        assert_eq!((workset.sample_ids, workset.library_ids), (vec![2, 3], vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_sample_updates_cover_every_sample_at_its_version() {
+        let service = service();
+        let workset = service
+            .create_workset(create("Bench 1", scanned(&["SAM-1", "SAM-3", "LIB-7"])), "tech")
+            .await
+            .unwrap();
+
+        let changes = UpdateSampleRequest {
+            description: Some("Extracted".to_string()),
+            ..Default::default()
+        };
+        let bulk = service.sample_updates(workset.id, changes).await.unwrap();
+        let rows: Vec<_> = bulk.updates.iter().map(|u| (u.id, u.version)).collect();
+        assert_eq!(rows, vec![(1, 2), (3, 4)]);
+        assert!(bulk
+            .updates
+            .iter()
+            .all(|u| u.changes.description.as_deref() == Some("Extracted")));
+    }
+
+    #[tokio::test]
+    async fn test_sample_updates_need_the_batch_controls() {
+        let service = service().with_controls(
+            ControlPolicy::new()
+                .with_batch_control(ControlType::Negative)
+                .with_batch_control(ControlType::Ntc),
+        );
+        let workset = service
+            .create_workset(create("Extraction 1", scanned(&["SAM-1", "SAM-3"])), "tech")
+            .await
+            .unwrap();
+
+        let refused = service
+            .sample_updates(workset.id, UpdateSampleRequest::default())
+            .await;
+        assert!(matches!(refused, Err(DomainError::Validation(m)) if m.ends_with("controls: ntc")));
+
+        service
+            .add_items(workset.id, scanned(&["LIB-7"]), "tech")
+            .await
+            .unwrap();
+        let bulk = service
+            .sample_updates(workset.id, UpdateSampleRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(bulk.updates.len(), 2);
+    }
+}
@@ -0,0 +1,99 @@
+//! Repository mocks shared by the services' tests.
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use miso_domain::entities::{EntityId, Project, ProjectMember, Role, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{
+    ProjectMemberRepository, ProjectRepository, QueryOptions, UserRepository,
+};
+
+/// Holds a single project, which tests may lock after handing it out.
+pub(crate) struct OneProject(pub(crate) Mutex<Project>);
+
+impl OneProject {
+    pub(crate) fn new(project: Project) -> Self {
+        Self(Mutex::new(project))
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for OneProject {
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Project>, DomainError> {
+        let project = self.0.lock().unwrap();
+        Ok((project.id == id).then(|| project.clone()))
+    }
+    async fn find_by_code(&self, _: &str) -> Result<Option<Project>, DomainError> {
+        unimplemented!()
+    }
+    async fn list(&self, _: QueryOptions) -> Result<Vec<Project>, DomainError> {
+        unimplemented!()
+    }
+    async fn search(&self, _: &str, _: u64) -> Result<Vec<Project>, DomainError> {
+        unimplemented!()
+    }
+    async fn save(&self, _: &Project) -> Result<EntityId, DomainError> {
+        unimplemented!()
+    }
+    async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+    async fn count(&self) -> Result<u64, DomainError> {
+        unimplemented!()
+    }
+}
+
+/// "member" belongs to project 1; everyone else to none.
+pub(crate) struct Members;
+
+#[async_trait]
+impl ProjectMemberRepository for Members {
+    async fn find_by_project(&self, _: EntityId) -> Result<Vec<ProjectMember>, DomainError> {
+        unimplemented!()
+    }
+    async fn find_projects(&self, user_id: EntityId) -> Result<Vec<EntityId>, DomainError> {
+        Ok(if user_id == 1 { vec![1] } else { Vec::new() })
+    }
+    async fn add(&self, _: &ProjectMember) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+    async fn remove(&self, _: EntityId, _: EntityId) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl UserRepository for Members {
+    async fn find_by_id(&self, _: EntityId) -> Result<Option<User>, DomainError> {
+        unimplemented!()
+    }
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        let id = if username == "member" { 1 } else { 2 };
+        Ok(Some(User::new_internal(
+            id,
+            username.to_string(),
+            username.to_string(),
+            format!("{}@example.org", username),
+            Role::Technician,
+        )))
+    }
+    async fn find_by_email(&self, _: &str) -> Result<Option<User>, DomainError> {
+        unimplemented!()
+    }
+    async fn list(&self, _: QueryOptions) -> Result<Vec<User>, DomainError> {
+        unimplemented!()
+    }
+    async fn save(&self, _: &User) -> Result<EntityId, DomainError> {
+        unimplemented!()
+    }
+    async fn find_password_hash(&self, _: EntityId) -> Result<Option<String>, DomainError> {
+        unimplemented!()
+    }
+    async fn set_password_hash(&self, _: EntityId, _: &str) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+    async fn delete(&self, _: EntityId) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,383 @@
+//! MISO LIMS load test harness.
+//!
+//! Seeds a database with a configurable volume of projects and samples,
+//! then times repository hot paths and API endpoints against it so that
+//! regressions in query patterns show up as latency changes.
+//!
+//! Point `DATABASE_URL` at a disposable database: migrations are applied
+//! and seeded rows are left in place.
+//!
+//! Usage:
+//!   miso-bench [--projects N] [--samples-per-project N] [--iterations N]
+
+mod seed;
+mod stats;
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use axum::body::Body;
+use axum::http::{header, Request};
+use axum::Router;
+use miso_api::middleware::create_token;
+use miso_api::{routes, state::Repositories, AppState, Config};
+use miso_domain::entities::{EntityId, Role, User};
+use miso_domain::repositories::{
+    ProjectRepository, QueryOptions, SampleRepository, UserRepository,
+};
+use miso_infrastructure::persistence::{
+    database::{Database, DatabaseConfig},
+    repositories::{
+        SeaOrmApiKeyRepository, SeaOrmAttributeDefinitionRepository, SeaOrmAuditLogRepository, SeaOrmChangeLogRepository, SeaOrmConsentRepository,
+        SeaOrmDataLocationRepository, SeaOrmDeliveryRepository, SeaOrmDeliveryTargetRepository, SeaOrmErasureRepository, SeaOrmEventStoreRepository, SeaOrmExportTemplateRepository, SeaOrmIndexSetRepository,
+        SeaOrmInstrumentEventRepository, SeaOrmInstrumentModelRepository, SeaOrmLibraryAliquotRepository, SeaOrmLibraryRepository, SeaOrmLabelPrintRepository, SeaOrmLogArchiveRepository, SeaOrmMaintenanceWindowRepository, SeaOrmApiUsageRepository, SeaOrmArchiveRuleRepository, SeaOrmAutoArchivalRepository, SeaOrmPanelRepository, SeaOrmPoolRepository, SeaOrmPossibleDuplicateRepository, SeaOrmReferenceGenomeRepository,
+        SeaOrmProjectActivityRepository, SeaOrmProjectMemberRepository, SeaOrmProjectRepository, SeaOrmQcRecordRepository, SeaOrmQcReportRepository,
+        SeaOrmExtractionBatchRepository, SeaOrmAttachmentRepository, SeaOrmReagentLotRepository, SeaOrmRequisitionRepository,
+        SeaOrmRunMetricsRepository, SeaOrmRunRepository, SeaOrmSampleCompositionRepository, SeaOrmSampleListRepository, SeaOrmSampleRepository,
+        SeaOrmSequencerRepository, SeaOrmStorageAuditRepository, SeaOrmStorageBoxRepository, SeaOrmStorageUnitRepository, SeaOrmSubprojectRepository,
+        SeaOrmTransferRepository, SeaOrmUserRepository, SeaOrmWorksetRepository,
+    },
+};
+use miso_migration::{Migrator, MigratorTrait};
+use tower::ServiceExt;
+
+use seed::{SeedVolumes, SeededData};
+use stats::LatencySummary;
+
+/// Number of tubes in a full rack scan.
+const RACK_SIZE: usize = 96;
+
+const JWT_SECRET: &str = "miso-bench";
+
+/// Builds the request URI for an iteration.
+type UriFn<'a> = Box<dyn Fn(usize) -> String + 'a>;
+
+struct Options {
+    volumes: SeedVolumes,
+    iterations: usize,
+}
+
+impl Options {
+    fn from_args() -> Result<Self> {
+        let mut options = Self {
+            volumes: SeedVolumes {
+                projects: 10,
+                samples_per_project: 1_000,
+            },
+            iterations: 200,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            let mut value = || -> Result<usize> {
+                args.next()
+                    .with_context(|| format!("{} needs a value", flag))?
+                    .parse()
+                    .with_context(|| format!("{} needs a number", flag))
+            };
+            match flag.as_str() {
+                "--projects" => options.volumes.projects = value()?,
+                "--samples-per-project" => options.volumes.samples_per_project = value()?,
+                "--iterations" => options.iterations = value()?,
+                other => bail!("Unknown argument: {}", other),
+            }
+        }
+
+        if options.volumes.projects == 0 || options.volumes.samples_per_project == 0 {
+            bail!("At least one project and one sample per project are required");
+        }
+
+        Ok(options)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _ = dotenvy::dotenv();
+    let options = Options::from_args()?;
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+
+    let db = Database::connect(DatabaseConfig::new(&database_url)).await?;
+    Migrator::up(db.connection(), None).await?;
+
+    let projects = Arc::new(SeaOrmProjectRepository::new(db.connection().clone()));
+    let samples = Arc::new(SeaOrmSampleRepository::new(db.connection().clone()));
+
+    let run_tag = format!(
+        "{:x}",
+        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
+    );
+    println!(
+        "Seeding {} projects x {} samples (tag {})",
+        options.volumes.projects, options.volumes.samples_per_project, run_tag
+    );
+    let started = Instant::now();
+    let seeded = seed::seed(
+        projects.as_ref(),
+        samples.as_ref(),
+        options.volumes,
+        &run_tag,
+    )
+    .await?;
+    println!("Seeded in {:.1}s\n", started.elapsed().as_secs_f64());
+
+    bench_repositories(
+        projects.as_ref(),
+        samples.as_ref(),
+        &seeded,
+        options.iterations,
+    )
+    .await?;
+
+    // Requests are made as a user of their own, since tokens are checked
+    // against the user table
+    let users = Arc::new(SeaOrmUserRepository::new(db.connection().clone()));
+    let user_id = users
+        .save(&User::new_internal(
+            0,
+            format!("bench-{}", run_tag),
+            "Load test".to_string(),
+            format!("bench-{}@localhost", run_tag),
+            Role::Admin,
+        ))
+        .await?;
+
+    let config = Config {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        database_url,
+        jwt_secret: JWT_SECRET.to_string(),
+        jwt_expiration_hours: 1,
+        cors_enabled: false,
+        log_level: "warn".to_string(),
+        event_store: false,
+        project_membership: false,
+        slow_query_ms: 0,
+        email: None,
+        digest: None,
+        pool_limits: None,
+        min_index_distance: 3,
+        pool_targets: None,
+        library_kits: None,
+        qc_thresholds: None,
+        controls: None,
+        ldap: None,
+        oidc: None,
+        run_planning: None,
+        basespace: None,
+        retention: None,
+        labels: None,
+        reagents: None,
+        attachments: None,
+        shipment_tracking: None,
+        api_usage: None,
+        auto_archive: None,
+        reconciliation: None,
+        stale_runs: None,
+    };
+    let repositories = Repositories {
+        projects,
+        samples,
+        libraries: Arc::new(SeaOrmLibraryRepository::new(db.connection().clone())),
+        index_sets: Arc::new(SeaOrmIndexSetRepository::new(db.connection().clone())),
+        pools: Arc::new(SeaOrmPoolRepository::new(db.connection().clone())),
+        run_metrics: Arc::new(SeaOrmRunMetricsRepository::new(db.connection().clone())),
+        qc_reports: Arc::new(SeaOrmQcReportRepository::new(db.connection().clone())),
+        export_templates: Arc::new(SeaOrmExportTemplateRepository::new(db.connection().clone())),
+        change_logs: Arc::new(SeaOrmChangeLogRepository::new(db.connection().clone())),
+        project_activity: Arc::new(SeaOrmProjectActivityRepository::new(db.connection().clone())),
+        instrument_models: Arc::new(SeaOrmInstrumentModelRepository::new(db.connection().clone())),
+        data_locations: Arc::new(SeaOrmDataLocationRepository::new(db.connection().clone())),
+        instrument_events: Arc::new(SeaOrmInstrumentEventRepository::new(db.connection().clone())),
+        attribute_definitions: Arc::new(SeaOrmAttributeDefinitionRepository::new(
+            db.connection().clone(),
+        )),
+        users,
+        panels: Arc::new(SeaOrmPanelRepository::new(db.connection().clone())),
+        reference_genomes: Arc::new(SeaOrmReferenceGenomeRepository::new(
+            db.connection().clone(),
+        )),
+        api_keys: Arc::new(SeaOrmApiKeyRepository::new(db.connection().clone())),
+        possible_duplicates: Arc::new(SeaOrmPossibleDuplicateRepository::new(
+            db.connection().clone(),
+        )),
+        audit_log: Arc::new(SeaOrmAuditLogRepository::new(db.connection().clone())),
+        event_store: Arc::new(SeaOrmEventStoreRepository::new(db.connection().clone())),
+        erasures: Arc::new(SeaOrmErasureRepository::new(db.connection().clone())),
+        qc_records: Arc::new(SeaOrmQcRecordRepository::new(db.connection().clone())),
+        consents: Arc::new(SeaOrmConsentRepository::new(db.connection().clone())),
+        storage_audits: Arc::new(SeaOrmStorageAuditRepository::new(db.connection().clone())),
+        log_archives: Arc::new(SeaOrmLogArchiveRepository::new(db.connection().clone())),
+        label_prints: Arc::new(SeaOrmLabelPrintRepository::new(db.connection().clone())),
+        library_aliquots: Arc::new(SeaOrmLibraryAliquotRepository::new(db.connection().clone())),
+        worksets: Arc::new(SeaOrmWorksetRepository::new(db.connection().clone())),
+        transfers: Arc::new(SeaOrmTransferRepository::new(db.connection().clone())),
+        requisitions: Arc::new(SeaOrmRequisitionRepository::new(db.connection().clone())),
+        extraction_batches: Arc::new(SeaOrmExtractionBatchRepository::new(
+            db.connection().clone(),
+        )),
+        reagent_lots: Arc::new(SeaOrmReagentLotRepository::new(db.connection().clone())),
+        attachments: Arc::new(SeaOrmAttachmentRepository::new(db.connection().clone())),
+        project_members: Arc::new(SeaOrmProjectMemberRepository::new(db.connection().clone())),
+        delivery_targets: Arc::new(SeaOrmDeliveryTargetRepository::new(db.connection().clone())),
+        deliveries: Arc::new(SeaOrmDeliveryRepository::new(db.connection().clone())),
+        subprojects: Arc::new(SeaOrmSubprojectRepository::new(db.connection().clone())),
+        archive_rules: Arc::new(SeaOrmArchiveRuleRepository::new(db.connection().clone())),
+        auto_archivals: Arc::new(SeaOrmAutoArchivalRepository::new(db.connection().clone())),
+        maintenance_windows: Arc::new(SeaOrmMaintenanceWindowRepository::new(
+            db.connection().clone(),
+        )),
+        api_usage: Arc::new(SeaOrmApiUsageRepository::new(db.connection().clone())),
+        storage_units: Arc::new(SeaOrmStorageUnitRepository::new(db.connection().clone())),
+        sample_compositions: Arc::new(SeaOrmSampleCompositionRepository::new(
+            db.connection().clone(),
+        )),
+        sample_list: Arc::new(SeaOrmSampleListRepository::new(db.connection().clone())),
+        boxes: Some(Arc::new(SeaOrmStorageBoxRepository::new(
+            db.connection().clone(),
+        ))),
+        runs: Some(Arc::new(SeaOrmRunRepository::new(db.connection().clone()))),
+        sequencers: Some(Arc::new(SeaOrmSequencerRepository::new(db.connection().clone()))),
+    };
+    let router = routes::create_router(AppState::new(config, repositories));
+
+    bench_endpoints(router, user_id, &seeded, options.iterations).await
+}
+
+/// Times repository hot paths.
+async fn bench_repositories(
+    projects: &dyn ProjectRepository,
+    samples: &dyn SampleRepository,
+    seeded: &SeededData,
+    iterations: usize,
+) -> Result<()> {
+    println!("Repositories");
+
+    let project_id = seeded.project_ids[seeded.project_ids.len() / 2];
+    let rack: Vec<String> = seeded
+        .sample_barcodes
+        .iter()
+        .step_by((seeded.sample_barcodes.len() / RACK_SIZE).max(1))
+        .take(RACK_SIZE)
+        .cloned()
+        .collect();
+
+    report(
+        "projects.list(100)",
+        time(iterations, |_| async {
+            projects.list(QueryOptions::new().limit(100)).await?;
+            Ok(())
+        })
+        .await?,
+    );
+    report(
+        "samples.find_by_barcode",
+        time(iterations, |i| {
+            let barcode = &seeded.sample_barcodes[i % seeded.sample_barcodes.len()];
+            async move {
+                samples.find_by_barcode(barcode).await?;
+                Ok(())
+            }
+        })
+        .await?,
+    );
+    report(
+        &format!("samples.find_by_barcodes({})", rack.len()),
+        time(iterations, |_| async {
+            samples.find_by_barcodes(&rack).await?;
+            Ok(())
+        })
+        .await?,
+    );
+    report(
+        "samples.find_by_project(100, by name)",
+        time(iterations, |i| async move {
+            let options = QueryOptions::new()
+                .limit(100)
+                .offset((i * 100) as u64 % 1_000)
+                .sort_by("name")
+                .ascending();
+            samples.find_by_project(project_id, options).await?;
+            Ok(())
+        })
+        .await?,
+    );
+
+    println!();
+    Ok(())
+}
+
+/// Times API endpoints through the full router stack.
+async fn bench_endpoints(
+    router: Router,
+    user_id: EntityId,
+    seeded: &SeededData,
+    iterations: usize,
+) -> Result<()> {
+    println!("Endpoints");
+
+    let token = create_token(&user_id.to_string(), "bench", "admin", JWT_SECRET, 1)?;
+    let project_id = seeded.project_ids[seeded.project_ids.len() / 2];
+
+    let endpoints: Vec<(&str, UriFn)> = vec![
+        (
+            "GET /api/v1/projects",
+            Box::new(|_| "/api/v1/projects?limit=100".to_string()),
+        ),
+        (
+            "GET /api/v1/samples?project_id",
+            Box::new(move |_| format!("/api/v1/samples?project_id={}&limit=100", project_id)),
+        ),
+        (
+            "GET /api/v1/samples/barcode/{barcode}",
+            Box::new(|i| {
+                format!(
+                    "/api/v1/samples/barcode/{}",
+                    seeded.sample_barcodes[i % seeded.sample_barcodes.len()]
+                )
+            }),
+        ),
+    ];
+
+    for (name, uri) in endpoints {
+        let summary = time(iterations, |i| {
+            let request = Request::get(uri(i))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty());
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(request?).await?;
+                if !response.status().is_success() {
+                    bail!("{} returned {}", name, response.status());
+                }
+                Ok(())
+            }
+        })
+        .await?;
+        report(name, summary);
+    }
+
+    Ok(())
+}
+
+/// Runs `op` sequentially `iterations` times, timing each call.
+async fn time<F, Fut>(iterations: usize, mut op: F) -> Result<LatencySummary>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut samples = Vec::with_capacity(iterations);
+    for i in 0..iterations {
+        let started = Instant::now();
+        op(i).await?;
+        samples.push(started.elapsed());
+    }
+
+    LatencySummary::from_samples(samples).context("--iterations must be at least 1")
+}
+
+fn report(name: &str, summary: LatencySummary) {
+    println!("  {:<40} {}", name, summary);
+}
@@ -0,0 +1,61 @@
+//! Seeding of benchmark data.
+
+use miso_domain::entities::{EntityId, Project, Sample};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ProjectRepository, SampleRepository};
+use miso_domain::value_objects::Barcode;
+
+/// Data volumes to seed.
+#[derive(Debug, Clone, Copy)]
+pub struct SeedVolumes {
+    pub projects: usize,
+    pub samples_per_project: usize,
+}
+
+/// What was seeded, for building queries against it.
+#[derive(Debug, Default)]
+pub struct SeededData {
+    pub project_ids: Vec<EntityId>,
+    pub sample_barcodes: Vec<String>,
+}
+
+/// Seeds projects and plain samples.
+///
+/// Codes and barcodes include `run_tag` so that repeated runs against the
+/// same database don't collide.
+pub async fn seed(
+    projects: &dyn ProjectRepository,
+    samples: &dyn SampleRepository,
+    volumes: SeedVolumes,
+    run_tag: &str,
+) -> Result<SeededData, DomainError> {
+    let mut seeded = SeededData::default();
+
+    for p in 0..volumes.projects {
+        let code = format!("B{}P{:04}", run_tag, p);
+        let project = Project::new(
+            0,
+            code.clone(),
+            format!("Benchmark project {}", p),
+            "bench".to_string(),
+        );
+        let project_id = projects.save(&project).await?;
+        seeded.project_ids.push(project_id);
+
+        for s in 0..volumes.samples_per_project {
+            let barcode = format!("SAM-{}-{:04}-{:06}", run_tag, p, s);
+            let sample = Sample::new_plain(
+                0,
+                format!("{}_S{:06}", code, s),
+                Barcode::new(barcode.clone())?,
+                project_id,
+                "Homo sapiens".to_string(),
+                "bench".to_string(),
+            );
+            samples.save(&sample).await?;
+            seeded.sample_barcodes.push(barcode);
+        }
+    }
+
+    Ok(seeded)
+}
@@ -0,0 +1,347 @@
+//! The client and its builder.
+
+use std::time::Duration;
+
+use reqwest::{Method, Request, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::error::ClientError;
+use crate::retry::{is_idempotent, RetryPolicy};
+
+/// Client for one MISO server.
+///
+/// Cloning is cheap: clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct MisoClient {
+    http: reqwest::Client,
+    /// The server's `/api/v1` URL
+    api_url: Url,
+    token: Option<String>,
+    retry: RetryPolicy,
+}
+
+/// Builder for [`MisoClient`].
+#[derive(Debug, Clone)]
+pub struct MisoClientBuilder {
+    base_url: String,
+    token: Option<String>,
+    timeout: Duration,
+    retry: RetryPolicy,
+    user_agent: String,
+}
+
+impl MisoClientBuilder {
+    /// Sends `token` as a bearer token with every request.
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Gives up on a request, including reading its response, after
+    /// `timeout`. Defaults to 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets when failed requests are repeated.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the User-Agent header, so that the server's logs show which
+    /// pipeline sent a request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Builds the client.
+    pub fn build(self) -> Result<MisoClient, ClientError> {
+        let mut api_url =
+            Url::parse(&self.base_url).map_err(|e| ClientError::InvalidUrl(e.to_string()))?;
+        api_url
+            .path_segments_mut()
+            .map_err(|_| ClientError::InvalidUrl(self.base_url.clone()))?
+            .pop_if_empty()
+            .extend(["api", "v1"]);
+
+        let http = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent)
+            .build()?;
+
+        Ok(MisoClient {
+            http,
+            api_url,
+            token: self.token,
+            retry: self.retry,
+        })
+    }
+}
+
+/// Error body returned by the API.
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: String,
+    message: String,
+}
+
+impl MisoClient {
+    /// Starts building a client for the server at `base_url`, e.g.
+    /// `https://miso.example.org`.
+    pub fn builder(base_url: impl Into<String>) -> MisoClientBuilder {
+        MisoClientBuilder {
+            base_url: base_url.into(),
+            token: None,
+            timeout: Duration::from_secs(30),
+            retry: RetryPolicy::default(),
+            user_agent: concat!("miso-client/", env!("CARGO_PKG_VERSION")).to_string(),
+        }
+    }
+
+    /// Starts a request to the API path made of `segments`, which are
+    /// escaped, so barcodes and names can be passed as they are.
+    pub(crate) fn request<S: AsRef<str>>(
+        &self,
+        method: Method,
+        segments: impl IntoIterator<Item = S>,
+    ) -> RequestBuilder {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("checked by the builder")
+            .extend(segments);
+
+        let builder = self.http.request(method, url);
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    /// Sends a request and returns its response if it succeeded.
+    pub(crate) async fn send(&self, builder: RequestBuilder) -> Result<Response, ClientError> {
+        let response = self.execute(builder.build()?).await?;
+        check(response).await
+    }
+
+    /// Sends a request and reads its JSON response.
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        builder: RequestBuilder,
+    ) -> Result<T, ClientError> {
+        Ok(self.send(builder).await?.json().await?)
+    }
+
+    /// Sends a request, repeating it as the retry policy allows.
+    pub(crate) async fn execute(&self, request: Request) -> Result<Response, ClientError> {
+        let retries = if is_idempotent(request.method()) {
+            self.retry.max_retries
+        } else {
+            0
+        };
+
+        let mut attempt = 0;
+        loop {
+            // The last attempt, or one whose body can't be replayed, sends
+            // the original request and returns whatever comes back.
+            let Some(next) = (attempt < retries).then(|| request.try_clone()).flatten() else {
+                return Ok(self.http.execute(request).await?);
+            };
+
+            let wait = match self.http.execute(next).await {
+                Ok(response) => match self.retry.after_response(&response, attempt) {
+                    Some(wait) => {
+                        warn!(
+                            "{} {} answered {}, retrying in {:?}",
+                            request.method(),
+                            request.url().path(),
+                            response.status(),
+                            wait
+                        );
+                        wait
+                    }
+                    None => return Ok(response),
+                },
+                Err(e) => match self.retry.after_error(&e, attempt) {
+                    Some(wait) => {
+                        warn!(
+                            "{} {} failed: {}, retrying in {:?}",
+                            request.method(),
+                            request.url().path(),
+                            e,
+                            wait
+                        );
+                        wait
+                    }
+                    None => return Err(e.into()),
+                },
+            };
+
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Turns an unsuccessful response into an error.
+pub(crate) async fn check(response: Response) -> Result<Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let reason = status.canonical_reason().unwrap_or("Unknown status");
+    let (error, message) = match response.json::<ErrorBody>().await {
+        Ok(body) => (body.error, body.message),
+        Err(_) => (reason.to_lowercase().replace(' ', "_"), reason.to_string()),
+    };
+
+    Err(ClientError::Api {
+        status: status.as_u16(),
+        error,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use axum::extract::{Path, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// Serves `router` on a local port and returns a client for it.
+    async fn serve(router: Router) -> MisoClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        MisoClient::builder(format!("http://{}", address))
+            .bearer_token("secret")
+            .retry(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(5),
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sends_token_and_escapes_path_segments() {
+        let router = Router::new().route(
+            "/api/v1/echo/{value}",
+            get(|Path(value): Path<String>, headers: HeaderMap| async move {
+                let auth = headers["authorization"].to_str().unwrap().to_string();
+                Json(json!({ "value": value, "auth": auth }))
+            }),
+        );
+        let client = serve(router).await;
+
+        let body: Value = client
+            .send_json(client.request(Method::GET, ["echo", "A/1 B"]))
+            .await
+            .unwrap();
+
+        assert_eq!(body["value"], "A/1 B");
+        assert_eq!(body["auth"], "Bearer secret");
+    }
+
+    #[tokio::test]
+    async fn test_maps_error_bodies() {
+        let router = Router::new().route(
+            "/api/v1/missing",
+            get(|| async {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({ "error": "not_found", "message": "Sample not found: 7" })),
+                )
+            }),
+        );
+        let client = serve(router).await;
+
+        let err = client
+            .send(client.request(Method::GET, ["missing"]))
+            .await
+            .unwrap_err();
+
+        assert!(err.is_not_found());
+        match err {
+            ClientError::Api { error, message, .. } => {
+                assert_eq!(error, "not_found");
+                assert_eq!(message, "Sample not found: 7");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_reads_but_not_creates() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let flaky = |State(calls): State<Arc<AtomicU32>>| async move {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::OK,
+            }
+        };
+        let router = Router::new()
+            .route("/api/v1/flaky", get(flaky).post(flaky))
+            .with_state(calls.clone());
+        let client = serve(router).await;
+
+        client
+            .send(client.request(Method::GET, ["flaky"]))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let err = client
+            .send(client.request(Method::POST, ["flaky"]))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_the_last_retry() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let router = Router::new()
+            .route(
+                "/api/v1/down",
+                get(|State(calls): State<Arc<AtomicU32>>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    StatusCode::BAD_GATEWAY
+                }),
+            )
+            .with_state(calls.clone());
+        let client = serve(router).await;
+
+        let err = client
+            .send(client.request(Method::GET, ["down"]))
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), Some(502));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_rejects_base_urls_without_a_path() {
+        let err = MisoClient::builder("mailto:lab@example.org")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ClientError::InvalidUrl(_)));
+    }
+}
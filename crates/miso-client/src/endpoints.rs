@@ -0,0 +1,262 @@
+//! One method per API endpoint.
+
+use futures_util::Stream;
+use miso_dto::{
+    AttributeSchemaResponse, BoxContents, BulkUpdateSamplesRequest, BulkUpdateSamplesResponse,
+    CreatePlainSampleRequest, CreateProjectRequest, ItemLocation, ItemMoved, MoveItemRequest,
+    ProjectResponse, ProjectSummary, RunMetricsResponse, RunOverviewResponse, SampleResponse,
+    SampleSummary, SearchResponse, SignOffRunQcRequest, StorageNode, SubmitRunMetricsRequest,
+    UpdateProjectRequest, UpdateSampleRequest,
+};
+use reqwest::{Method, StatusCode};
+
+use crate::client::{check, MisoClient};
+use crate::error::ClientError;
+use crate::pages::paginate;
+
+/// Projects
+impl MisoClient {
+    /// Lists one page of projects.
+    pub async fn list_projects(
+        &self,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<ProjectSummary>, ClientError> {
+        self.send_json(
+            self.request(Method::GET, ["projects"])
+                .query(&[("limit", limit), ("offset", offset)]),
+        )
+        .await
+    }
+
+    /// Streams every project, fetching `page_size` at a time.
+    pub fn all_projects(
+        &self,
+        page_size: u64,
+    ) -> impl Stream<Item = Result<ProjectSummary, ClientError>> + '_ {
+        paginate(page_size, move |limit, offset| {
+            self.list_projects(limit, offset)
+        })
+    }
+
+    /// Gets a project.
+    pub async fn project(&self, id: i32) -> Result<ProjectResponse, ClientError> {
+        self.send_json(self.request(Method::GET, ["projects", &id.to_string()]))
+            .await
+    }
+
+    /// Creates a project.
+    pub async fn create_project(
+        &self,
+        request: &CreateProjectRequest,
+    ) -> Result<ProjectResponse, ClientError> {
+        self.send_json(self.request(Method::POST, ["projects"]).json(request))
+            .await
+    }
+
+    /// Updates a project.
+    pub async fn update_project(
+        &self,
+        id: i32,
+        request: &UpdateProjectRequest,
+    ) -> Result<ProjectResponse, ClientError> {
+        self.send_json(
+            self.request(Method::PUT, ["projects", &id.to_string()])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Deletes a project.
+    pub async fn delete_project(&self, id: i32) -> Result<(), ClientError> {
+        self.send(self.request(Method::DELETE, ["projects", &id.to_string()]))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Samples
+impl MisoClient {
+    /// Gets a sample.
+    pub async fn sample(&self, id: i32) -> Result<SampleResponse, ClientError> {
+        self.send_json(self.request(Method::GET, ["samples", &id.to_string()]))
+            .await
+    }
+
+    /// Gets the sample with a barcode.
+    pub async fn sample_by_barcode(&self, barcode: &str) -> Result<SampleResponse, ClientError> {
+        self.send_json(self.request(Method::GET, ["samples", "barcode", barcode]))
+            .await
+    }
+
+    /// Lists one page of a project's samples.
+    pub async fn list_project_samples(
+        &self,
+        project_id: i32,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<SampleSummary>, ClientError> {
+        self.send_json(
+            self.request(Method::GET, ["samples", "project", &project_id.to_string()])
+                .query(&[("limit", limit), ("offset", offset)]),
+        )
+        .await
+    }
+
+    /// Streams every sample of a project, fetching `page_size` at a time.
+    pub fn all_project_samples(
+        &self,
+        project_id: i32,
+        page_size: u64,
+    ) -> impl Stream<Item = Result<SampleSummary, ClientError>> + '_ {
+        paginate(page_size, move |limit, offset| {
+            self.list_project_samples(project_id, limit, offset)
+        })
+    }
+
+    /// Creates a sample.
+    pub async fn create_sample(
+        &self,
+        request: &CreatePlainSampleRequest,
+    ) -> Result<SampleResponse, ClientError> {
+        self.send_json(self.request(Method::POST, ["samples"]).json(request))
+            .await
+    }
+
+    /// Updates a sample.
+    pub async fn update_sample(
+        &self,
+        id: i32,
+        request: &UpdateSampleRequest,
+    ) -> Result<SampleResponse, ClientError> {
+        self.send_json(
+            self.request(Method::PUT, ["samples", &id.to_string()])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Deletes a sample.
+    pub async fn delete_sample(&self, id: i32) -> Result<(), ClientError> {
+        self.send(self.request(Method::DELETE, ["samples", &id.to_string()]))
+            .await?;
+        Ok(())
+    }
+
+    /// Updates many samples at once.
+    ///
+    /// A batch that wasn't applied because some rows failed isn't an
+    /// error: check `applied` and the per-row results.
+    pub async fn bulk_update_samples(
+        &self,
+        request: &BulkUpdateSamplesRequest,
+    ) -> Result<BulkUpdateSamplesResponse, ClientError> {
+        let builder = self
+            .request(Method::PATCH, ["samples", "bulk"])
+            .json(request);
+        let response = self.execute(builder.build()?).await?;
+
+        // A rejected batch comes back as 409 with the row results
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(response.json().await?);
+        }
+        Ok(check(response).await?.json().await?)
+    }
+}
+
+/// Sequencing runs
+impl MisoClient {
+    /// Gets a run's lanes, pools and QC state.
+    pub async fn run_overview(&self, id: i32) -> Result<RunOverviewResponse, ClientError> {
+        self.send_json(self.request(Method::GET, ["runs", &id.to_string(), "overview"]))
+            .await
+    }
+
+    /// Passes or fails a run's QC.
+    pub async fn sign_off_run_qc(
+        &self,
+        id: i32,
+        request: &SignOffRunQcRequest,
+    ) -> Result<RunOverviewResponse, ClientError> {
+        self.send_json(
+            self.request(Method::PUT, ["runs", &id.to_string(), "qc"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Gets the per-library metrics recorded for a run.
+    pub async fn run_metrics(&self, id: i32) -> Result<RunMetricsResponse, ClientError> {
+        self.send_json(self.request(Method::GET, ["runs", &id.to_string(), "metrics"]))
+            .await
+    }
+
+    /// Records per-library metrics for a run.
+    pub async fn submit_run_metrics(
+        &self,
+        id: i32,
+        request: &SubmitRunMetricsRequest,
+    ) -> Result<RunMetricsResponse, ClientError> {
+        self.send_json(
+            self.request(Method::POST, ["runs", &id.to_string(), "metrics"])
+                .json(request),
+        )
+        .await
+    }
+}
+
+/// Storage, search and attributes
+impl MisoClient {
+    /// Lists freezers, shelves and racks with their boxes.
+    pub async fn freezers(&self) -> Result<Vec<StorageNode>, ClientError> {
+        self.send_json(self.request(Method::GET, ["storage", "freezers"]))
+            .await
+    }
+
+    /// Gets a box and what's in it.
+    pub async fn box_contents(&self, id: i32) -> Result<BoxContents, ClientError> {
+        self.send_json(self.request(Method::GET, ["storage", "boxes", &id.to_string()]))
+            .await
+    }
+
+    /// Finds where the item with a barcode is stored.
+    pub async fn locate(&self, barcode: &str) -> Result<ItemLocation, ClientError> {
+        self.send_json(
+            self.request(Method::GET, ["storage", "locate"])
+                .query(&[("barcode", barcode)]),
+        )
+        .await
+    }
+
+    /// Moves an item to another box position.
+    pub async fn move_item(&self, request: &MoveItemRequest) -> Result<ItemMoved, ClientError> {
+        self.send_json(
+            self.request(Method::POST, ["storage", "moves"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Searches projects and samples.
+    pub async fn search(&self, query: &str, limit: u64) -> Result<SearchResponse, ClientError> {
+        self.send_json(
+            self.request(Method::GET, ["search"])
+                .query(&[("q", query), ("limit", &limit.to_string())]),
+        )
+        .await
+    }
+
+    /// Gets the custom attributes a form should show for `target`
+    /// ("sample" or "library"), including a project's own if given.
+    pub async fn attribute_schema(
+        &self,
+        target: &str,
+        project_id: Option<i32>,
+    ) -> Result<AttributeSchemaResponse, ClientError> {
+        let mut builder = self.request(Method::GET, ["attributes", "schema", target]);
+        if let Some(project_id) = project_id {
+            builder = builder.query(&[("project_id", project_id)]);
+        }
+        self.send_json(builder).await
+    }
+}
@@ -0,0 +1,48 @@
+//! Client errors.
+
+use thiserror::Error;
+
+/// Error returned by a client call.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The request couldn't be sent, or its response couldn't be read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The server rejected the request
+    #[error("{message} ({status})")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error type, e.g. "not_found" or "validation_error"
+        error: String,
+        /// The server's explanation
+        message: String,
+    },
+
+    /// The base URL or a path couldn't be made into a URL
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl ClientError {
+    /// The HTTP status the server answered with, if it answered.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Http(e) => e.status().map(|s| s.as_u16()),
+            Self::Api { status, .. } => Some(*status),
+            Self::InvalidUrl(_) => None,
+        }
+    }
+
+    /// Returns true if the requested entity doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// Returns true if the request conflicted with the entity's current
+    /// state, e.g. a duplicate barcode or a stale version.
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(409)
+    }
+}
@@ -0,0 +1,40 @@
+//! # MISO Client
+//!
+//! A typed client for the MISO LIMS REST API, for pipelines and scripts
+//! written in Rust. Requests and responses are the server's own DTOs from
+//! miso-dto, re-exported here as [`dto`].
+//!
+//! ```no_run
+//! use futures_util::TryStreamExt;
+//! use miso_client::MisoClient;
+//!
+//! # async fn run() -> Result<(), miso_client::ClientError> {
+//! let client = MisoClient::builder("https://miso.example.org")
+//!     .bearer_token("eyJhbGciOi...")
+//!     .build()?;
+//!
+//! let sample = client.sample_by_barcode("SAM0042").await?;
+//! let samples: Vec<_> = client
+//!     .all_project_samples(sample.project_id, 200)
+//!     .try_collect()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests that are safe to repeat (GET, PUT and DELETE) are retried
+//! with backoff when the server can't be reached or answers 429, 502, 503
+//! or 504; see [`RetryPolicy`]. POSTs are sent once.
+
+mod client;
+mod endpoints;
+mod error;
+mod pages;
+mod retry;
+
+pub use client::{MisoClient, MisoClientBuilder};
+pub use error::ClientError;
+pub use retry::RetryPolicy;
+
+/// Request and response types.
+pub use miso_dto as dto;
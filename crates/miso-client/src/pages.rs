@@ -0,0 +1,100 @@
+//! Walking list endpoints a page at a time.
+
+use std::future::Future;
+
+use futures_util::stream::{self, Stream, TryStreamExt};
+
+use crate::error::ClientError;
+
+/// Streams every item of a list endpoint, fetching `page_size` items at a
+/// time with `fetch(limit, offset)` as the stream is read. The stream
+/// ends after the first short page.
+pub(crate) fn paginate<T, F, Fut>(
+    page_size: u64,
+    fetch: F,
+) -> impl Stream<Item = Result<T, ClientError>>
+where
+    F: FnMut(u64, u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, ClientError>>,
+{
+    let page_size = page_size.max(1);
+
+    stream::try_unfold((Some(0), fetch), move |(offset, mut fetch)| async move {
+        let Some(offset) = offset else {
+            return Ok(None);
+        };
+        let page: Vec<T> = fetch(page_size, offset).await?;
+        if page.is_empty() {
+            return Ok(None);
+        }
+        let next = (page.len() as u64 >= page_size).then_some(offset + page_size);
+        Ok::<_, ClientError>(Some((page, (next, fetch))))
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_pages_until_a_short_one() {
+        let requests = Mutex::new(Vec::new());
+        let items: Vec<u64> = (0..7).collect();
+
+        let all: Vec<u64> = paginate(3, |limit, offset| {
+            requests.lock().unwrap().push((limit, offset));
+            let page = items
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .copied()
+                .collect();
+            async move { Ok(page) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(all, items);
+        assert_eq!(*requests.lock().unwrap(), vec![(3, 0), (3, 3), (3, 6)]);
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_an_empty_page() {
+        let mut calls = 0;
+        let all: Vec<u64> = paginate(2, |_, offset| {
+            calls += 1;
+            let page = if offset < 4 {
+                vec![offset, offset + 1]
+            } else {
+                vec![]
+            };
+            async move { Ok(page) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(all, vec![0, 1, 2, 3]);
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_the_first_error() {
+        let result: Result<Vec<u64>, _> = paginate(1, |_, offset| async move {
+            if offset == 0 {
+                Ok(vec![0])
+            } else {
+                Err(ClientError::InvalidUrl("boom".to_string()))
+            }
+        })
+        .try_collect()
+        .await;
+
+        assert!(result.is_err());
+    }
+}
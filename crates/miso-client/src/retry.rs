@@ -0,0 +1,122 @@
+//! Retrying requests that failed for reasons that may pass.
+
+use std::time::Duration;
+
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Method, Response, StatusCode};
+
+/// When and how long to wait before repeating a request.
+///
+/// Only requests that are safe to repeat are retried, and only after a
+/// connection failure, a timeout or a 429, 502, 503 or 504 response. The
+/// wait doubles with each attempt, up to `max_backoff`, unless the server
+/// says how long to wait in a `Retry-After` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; zero disables retrying
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before retry number `attempt` (0 for the first retry).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+
+    /// Wait before retrying after `response`, or `None` if it shouldn't be
+    /// retried.
+    pub(crate) fn after_response(&self, response: &Response, attempt: u32) -> Option<Duration> {
+        if !retries_status(response.status()) {
+            return None;
+        }
+        let requested = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        Some(
+            requested
+                .unwrap_or_else(|| self.backoff(attempt))
+                .min(self.max_backoff),
+        )
+    }
+
+    /// Wait before retrying after `error`, or `None` if it shouldn't be
+    /// retried.
+    pub(crate) fn after_error(&self, error: &reqwest::Error, attempt: u32) -> Option<Duration> {
+        (error.is_connect() || error.is_timeout()).then(|| self.backoff(attempt))
+    }
+}
+
+/// Returns true if a request with `method` can be sent again without
+/// repeating its effect.
+pub(crate) fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Returns true if a response with `status` may succeed when repeated.
+fn retries_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_limit() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(40), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_only_repeatable_methods_are_retried() {
+        assert!(is_idempotent(&Method::GET));
+        assert!(is_idempotent(&Method::PUT));
+        assert!(!is_idempotent(&Method::POST));
+        assert!(!is_idempotent(&Method::PATCH));
+        assert!(retries_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!retries_status(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
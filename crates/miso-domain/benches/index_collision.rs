@@ -0,0 +1,94 @@
+//! Benchmarks for index collision checking.
+//!
+//! Collision checking compares every pair of indices, so cost grows
+//! quadratically with pool size. Large NovaSeq pools of several hundred
+//! libraries are checked on every pool edit.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use miso_domain::entities::{Library, LibraryDesign, LibraryType};
+use miso_domain::services::IndexCollisionChecker;
+use miso_domain::value_objects::{Barcode, DnaIndex, IndexFamily};
+
+/// Deterministic pseudo-random 10bp sequence.
+fn sequence(seed: u64) -> String {
+    let mut state = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (0..10)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ['A', 'C', 'G', 'T'][(state >> 62) as usize]
+        })
+        .collect()
+}
+
+fn libraries(count: usize) -> Vec<Library> {
+    (0..count)
+        .map(|i| {
+            let id = i as i32 + 1;
+            let mut library = Library::new(
+                id,
+                format!("LIB{:05}", id),
+                Barcode::new(format!("LIB-{:05}", id)).unwrap(),
+                1,
+                1,
+                LibraryDesign::Wgs,
+                LibraryType::PairedEnd,
+                "Illumina".to_string(),
+                "bench".to_string(),
+            );
+            library.set_index(
+                DnaIndex::dual(
+                    format!("UDP{:04}", id),
+                    sequence(i as u64 * 2),
+                    sequence(i as u64 * 2 + 1),
+                    IndexFamily::IdtUdi,
+                )
+                .unwrap(),
+            );
+            library
+        })
+        .collect()
+}
+
+fn bench_check_libraries(c: &mut Criterion) {
+    let checker = IndexCollisionChecker::new();
+    let mut group = c.benchmark_group("check_libraries");
+
+    for size in [96, 384, 1536] {
+        let pool = libraries(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &pool, |b, pool| {
+            b.iter(|| checker.check_libraries(black_box(pool)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_can_add_index(c: &mut Criterion) {
+    let checker = IndexCollisionChecker::new();
+    let pool = libraries(384);
+    let existing: Vec<(String, DnaIndex)> = pool
+        .iter()
+        .filter_map(|l| l.index.clone().map(|idx| (l.name.clone(), idx)))
+        .collect();
+    let candidate = DnaIndex::dual(
+        "NEW",
+        sequence(9_999),
+        sequence(10_000),
+        IndexFamily::IdtUdi,
+    )
+    .unwrap();
+
+    c.bench_function("can_add_index/384", |b| {
+        b.iter(|| {
+            checker
+                .can_add_index(black_box(&existing), "NEW", black_box(&candidate))
+                .is_ok()
+        })
+    });
+}
+
+criterion_group!(benches, bench_check_libraries, bench_can_add_index);
+criterion_main!(benches);
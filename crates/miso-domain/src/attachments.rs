@@ -0,0 +1,30 @@
+//! Attachment Storage Traits - interfaces for storing uploaded files.
+//!
+//! Like repositories, attachment stores are implemented in the
+//! infrastructure layer (local filesystem, S3) so that application code can
+//! keep the files attached to samples, libraries and runs without knowing
+//! where they end up. Their metadata is kept separately, by an
+//! [`AttachmentRepository`](crate::repositories::AttachmentRepository).
+
+use async_trait::async_trait;
+
+use crate::errors::DomainError;
+
+/// Stores the contents of attachments by key.
+///
+/// Keys are relative, `/`-separated paths such as
+/// "sample/42/6f1c...". A store may place them under a directory or prefix
+/// of its own.
+#[async_trait]
+pub trait AttachmentStore: Send + Sync {
+    /// Stores contents under a key, replacing anything already there.
+    async fn put(&self, key: &str, content_type: &str, contents: &[u8])
+        -> Result<(), DomainError>;
+
+    /// Reads the contents stored under a key.
+    async fn get(&self, key: &str) -> Result<Vec<u8>, DomainError>;
+
+    /// Removes the contents stored under a key. Removing a missing key is
+    /// not an error.
+    async fn delete(&self, key: &str) -> Result<(), DomainError>;
+}
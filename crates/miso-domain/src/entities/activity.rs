@@ -0,0 +1,123 @@
+//! Project activity feed items.
+//!
+//! The activity feed merges events from several tables into one timeline.
+//! Items are ordered newest first by (time, kind, source ID), which is
+//! total across sources, so a cursor taken from the last item of a page
+//! identifies exactly where the next page starts.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// What kind of event an activity item records.
+///
+/// The declaration order breaks ties between items recorded at the same
+/// instant and must not be changed without invalidating cursors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// A sample was created
+    SampleCreated,
+    /// A change log entry was recorded
+    Change,
+}
+
+impl ActivityKind {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::SampleCreated => "sample_created",
+            Self::Change => "change",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "sample_created" => Some(Self::SampleCreated),
+            "change" => Some(Self::Change),
+            _ => None,
+        }
+    }
+}
+
+/// One event in a project's activity feed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityItem {
+    /// What happened
+    pub kind: ActivityKind,
+    /// ID of the row the item was read from, unique within its kind
+    pub source_id: EntityId,
+    /// When it happened
+    pub occurred_at: DateTime<Utc>,
+    /// Type of the entity concerned, e.g. "Sample"
+    pub entity_type: String,
+    /// ID of the entity concerned
+    pub entity_id: EntityId,
+    /// Human-readable description
+    pub summary: String,
+    /// Supporting detail, such as the reason for a change
+    pub detail: Option<String>,
+    /// Who did it
+    pub actor: String,
+}
+
+impl ActivityItem {
+    /// Returns the cursor positioned at this item.
+    pub fn cursor(&self) -> ActivityCursor {
+        ActivityCursor {
+            occurred_at: self.occurred_at,
+            kind: self.kind,
+            source_id: self.source_id,
+        }
+    }
+}
+
+/// Position in an activity feed.
+///
+/// Cursors order like the items they were taken from, oldest first; a
+/// page "before" a cursor holds the items that sort below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ActivityCursor {
+    /// Time of the item
+    pub occurred_at: DateTime<Utc>,
+    /// Kind of the item
+    pub kind: ActivityKind,
+    /// Source row of the item
+    pub source_id: EntityId,
+}
+
+impl ActivityCursor {
+    /// Encodes the cursor as an opaque, URL-safe token.
+    pub fn encode(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.occurred_at.timestamp_millis(),
+            self.kind.code(),
+            self.source_id
+        )
+    }
+
+    /// Decodes a token produced by [`encode`](Self::encode).
+    pub fn decode(token: &str) -> Result<Self, DomainError> {
+        let invalid = || DomainError::Validation(format!("Invalid activity cursor: {}", token));
+
+        let mut parts = token.splitn(3, '.');
+        let (Some(millis), Some(kind), Some(source_id)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            occurred_at: millis
+                .parse()
+                .ok()
+                .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                .ok_or_else(invalid)?,
+            kind: ActivityKind::from_code(kind).ok_or_else(invalid)?,
+            source_id: source_id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+//! API key entity - credentials for instruments and pipeline scripts.
+//!
+//! Scripts can't log in interactively, so an administrator issues them a
+//! key instead. A key acts for the user who created it, with a role of its
+//! own that is never more than that user's: deactivating the user or
+//! lowering their role takes effect on their keys too. Only a hash of the
+//! key is kept; the key itself is shown once, when it is created.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::{EntityId, Role};
+
+/// An API key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Unique identifier
+    pub id: EntityId,
+    /// What the key is for (e.g. "novaseq-01"); unique
+    pub name: String,
+    /// The start of the key, by which people can tell keys apart
+    pub prefix: String,
+    /// SHA-256 hash of the key, hex encoded
+    pub key_hash: String,
+    /// Most the key may do
+    pub role: Role,
+    /// The user the key acts for, who created it
+    pub user_id: EntityId,
+    /// When the key was created
+    pub created_at: DateTime<Utc>,
+    /// When the key stops working, if it expires
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the key was last used
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// When the key was revoked
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Creates a key for a user.
+    pub fn new(
+        name: String,
+        prefix: String,
+        key_hash: String,
+        role: Role,
+        user_id: EntityId,
+    ) -> Result<Self, DomainError> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(DomainError::Validation(
+                "API key name must not be empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            name,
+            prefix,
+            key_hash,
+            role,
+            user_id,
+            created_at: Utc::now(),
+            expires_at: None,
+            last_used_at: None,
+            revoked_at: None,
+        })
+    }
+
+    /// Returns true if the key works at `now`: it is neither revoked nor
+    /// expired.
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+
+    /// Returns the role the key has for a user of `user_role`: its own, or
+    /// the user's if that is lower.
+    pub fn effective_role(&self, user_role: Role) -> Role {
+        if user_role.has_at_least(&self.role) {
+            self.role
+        } else {
+            user_role
+        }
+    }
+
+    /// Revokes the key.
+    pub fn revoke(&mut self) -> Result<(), DomainError> {
+        if self.revoked_at.is_some() {
+            return Err(DomainError::Validation(format!(
+                "API key {} is already revoked",
+                self.name
+            )));
+        }
+        self.revoked_at = Some(Utc::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_revoked_and_expired_keys_stop_working() {
+        let mut key = ApiKey::new(
+            " novaseq-01 ".to_string(),
+            "miso_3f2a".to_string(),
+            "hash".to_string(),
+            Role::Technician,
+            1,
+        )
+        .unwrap();
+        let now = Utc::now();
+        assert_eq!(key.name, "novaseq-01");
+        assert!(key.is_active_at(now));
+
+        key.expires_at = Some(now + Duration::days(1));
+        assert!(key.is_active_at(now));
+        assert!(!key.is_active_at(now + Duration::days(2)));
+
+        key.revoke().unwrap();
+        assert!(!key.is_active_at(now));
+        assert!(key.revoke().is_err());
+
+        assert_eq!(key.effective_role(Role::Admin), Role::Technician);
+        assert_eq!(key.effective_role(Role::Viewer), Role::Viewer);
+    }
+}
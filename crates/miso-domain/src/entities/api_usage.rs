@@ -0,0 +1,127 @@
+//! API usage entity - how often one caller used one endpoint in an hour,
+//! and how long the calls took.
+//!
+//! Only a sample of calls is timed and recorded; each sampled call stands
+//! for the calls skipped since the last one, so call and error counts are
+//! estimates while latencies are those of the sampled calls.
+
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::EntityId;
+
+/// Calls one user, or one of their API keys, made to one endpoint within an
+/// hour.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiUsage {
+    /// Start of the hour
+    pub hour: DateTime<Utc>,
+    pub user_id: EntityId,
+    /// Key the calls were made with; unset for calls made after logging in
+    pub api_key_id: Option<EntityId>,
+    /// HTTP method, e.g. "GET"
+    pub method: String,
+    /// Route template, e.g. "/api/v1/samples/{id}"
+    pub route: String,
+    /// Estimated calls
+    pub calls: i64,
+    /// Estimated calls answered with a 4xx or 5xx status
+    pub errors: i64,
+    /// Calls timed
+    pub samples: i64,
+    /// Total time of the timed calls, in milliseconds
+    pub total_ms: i64,
+    /// Slowest timed call, in milliseconds
+    pub max_ms: i64,
+}
+
+impl ApiUsage {
+    /// Starts counting a caller's calls to an endpoint in the hour `at`
+    /// falls in.
+    pub fn new(
+        at: DateTime<Utc>,
+        user_id: EntityId,
+        api_key_id: Option<EntityId>,
+        method: &str,
+        route: &str,
+    ) -> Self {
+        Self {
+            hour: hour_of(at),
+            user_id,
+            api_key_id,
+            method: method.to_string(),
+            route: route.to_string(),
+            calls: 0,
+            errors: 0,
+            samples: 0,
+            total_ms: 0,
+            max_ms: 0,
+        }
+    }
+
+    /// Returns true if both count the same caller, endpoint and hour.
+    pub fn same_bucket(&self, other: &ApiUsage) -> bool {
+        self.hour == other.hour
+            && self.user_id == other.user_id
+            && self.api_key_id == other.api_key_id
+            && self.method == other.method
+            && self.route == other.route
+    }
+
+    /// Records a timed call standing for `weight` calls.
+    pub fn record(&mut self, weight: i64, error: bool, elapsed_ms: i64) {
+        self.calls += weight;
+        if error {
+            self.errors += weight;
+        }
+        self.samples += 1;
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+    }
+
+    /// Adds another count of the same kind of call to this one.
+    pub fn merge(&mut self, other: &ApiUsage) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.samples += other.samples;
+        self.total_ms += other.total_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+    }
+
+    /// Mean time of the timed calls, in milliseconds.
+    pub fn mean_ms(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.total_ms as f64 / self.samples as f64)
+    }
+}
+
+/// Returns the start of the hour `at` falls in.
+fn hour_of(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(TimeDelta::hours(1)).unwrap_or(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_sampled_calls_are_weighted() {
+        let at = Utc.with_ymd_and_hms(2024, 12, 15, 9, 41, 7).unwrap();
+        let mut usage = ApiUsage::new(at, 3, Some(8), "GET", "/api/v1/samples/{id}");
+        assert_eq!(usage.hour, Utc.with_ymd_and_hms(2024, 12, 15, 9, 0, 0).unwrap());
+        assert_eq!(usage.mean_ms(), None);
+
+        usage.record(10, false, 30);
+        usage.record(10, true, 90);
+        assert_eq!((usage.calls, usage.errors, usage.samples), (20, 10, 2));
+        assert_eq!(usage.mean_ms(), Some(60.0));
+
+        let route = "/api/v1/samples/{id}";
+        let later = ApiUsage::new(at + TimeDelta::minutes(5), 3, Some(8), "GET", route);
+        assert!(usage.same_bucket(&later));
+        assert!(!usage.same_bucket(&ApiUsage::new(at, 3, None, "GET", route)));
+        usage.merge(&later);
+        assert_eq!(usage.max_ms, 90);
+    }
+}
@@ -0,0 +1,277 @@
+//! Archive rule entities - automatic archiving of libraries and samples
+//! once they are done with.
+//!
+//! A rule such as "archive libraries 90 days after their last run
+//! completed" is evaluated by the scheduler. Lab managers are told which
+//! items a rule is about to archive `notice_days` ahead, and an item is only
+//! archived by an evaluation after the one that announced it. Every item a
+//! rule archives is recorded, so it can be restored; a restored item is
+//! left alone by that rule from then on.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// What a rule archives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveTarget {
+    /// Libraries, by the last completed run they were sequenced on
+    Library,
+    /// Samples, by the last completed run any of their libraries were
+    /// sequenced on
+    Sample,
+}
+
+impl ArchiveTarget {
+    /// Returns the code stored for the target.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Library => "library",
+            Self::Sample => "sample",
+        }
+    }
+}
+
+impl fmt::Display for ArchiveTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ArchiveTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "library" => Ok(Self::Library),
+            "sample" => Ok(Self::Sample),
+            other => Err(format!("Unknown archive target: {}", other)),
+        }
+    }
+}
+
+/// A rule archiving items a number of days after their last run completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveRule {
+    pub id: EntityId,
+    /// Name lab managers know the rule by
+    pub name: String,
+    pub target: ArchiveTarget,
+    /// Days after the last completed run that items are archived
+    pub after_days: u32,
+    /// Days ahead of archiving that lab managers are told; at least one
+    pub notice_days: u32,
+    /// Inactive rules are kept but not evaluated
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// When the scheduler last evaluated the rule; items announced by then
+    /// may be archived once due
+    pub last_evaluated_at: Option<DateTime<Utc>>,
+}
+
+impl ArchiveRule {
+    /// Creates an active rule.
+    pub fn new(
+        name: String,
+        target: ArchiveTarget,
+        after_days: u32,
+        notice_days: u32,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let rule = Self {
+            id: 0,
+            name: name.trim().to_string(),
+            target,
+            after_days,
+            notice_days,
+            active: true,
+            created_by,
+            created_at: Utc::now(),
+            last_evaluated_at: None,
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    /// Checks that the rule is well formed.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.is_empty() {
+            return Err(DomainError::Validation(
+                "Archive rule name is required".to_string(),
+            ));
+        }
+        if self.after_days == 0 {
+            return Err(DomainError::Validation(
+                "Items must be kept at least a day after their last run".to_string(),
+            ));
+        }
+        if self.notice_days == 0 || self.notice_days > self.after_days {
+            return Err(DomainError::Validation(format!(
+                "Notice must be between 1 and {} days",
+                self.after_days
+            )));
+        }
+        Ok(())
+    }
+
+    /// Changes how long items are kept or the notice given. Items are
+    /// announced again before the changed rule archives anything.
+    pub fn reschedule(&mut self, after_days: u32, notice_days: u32) -> Result<(), DomainError> {
+        let mut changed = self.clone();
+        changed.after_days = after_days;
+        changed.notice_days = notice_days;
+        changed.validate()?;
+
+        if (after_days, notice_days) != (self.after_days, self.notice_days) {
+            self.after_days = after_days;
+            self.notice_days = notice_days;
+            self.last_evaluated_at = None;
+        }
+        Ok(())
+    }
+
+    /// Returns when an item whose last run completed at `last_run` is due
+    /// to be archived.
+    pub fn due_at(&self, last_run: DateTime<Utc>) -> DateTime<Utc> {
+        last_run + Duration::days(i64::from(self.after_days))
+    }
+
+    /// Returns when lab managers are told an item is to be archived.
+    pub fn notice_at(&self, last_run: DateTime<Utc>) -> DateTime<Utc> {
+        self.due_at(last_run) - Duration::days(i64::from(self.notice_days))
+    }
+
+    /// Returns true if an evaluation at `now` archives the item: it is due
+    /// and was announced by an earlier evaluation.
+    pub fn archives(&self, last_run: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.due_at(last_run) <= now
+            && self
+                .last_evaluated_at
+                .is_some_and(|evaluated| self.notice_at(last_run) <= evaluated)
+    }
+
+    /// Returns true if an evaluation at `now` announces the item: its notice
+    /// is due and no earlier evaluation announced it.
+    pub fn announces(&self, last_run: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let notice_at = self.notice_at(last_run);
+        notice_at <= now
+            && self
+                .last_evaluated_at
+                .is_none_or(|evaluated| notice_at > evaluated)
+    }
+}
+
+/// An item archived by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoArchival {
+    pub id: EntityId,
+    pub rule_id: EntityId,
+    pub target: ArchiveTarget,
+    /// The archived library or sample
+    pub entity_id: EntityId,
+    pub entity_name: String,
+    /// When the run the item was last sequenced on completed
+    pub last_run_at: DateTime<Utc>,
+    pub archived_at: DateTime<Utc>,
+    pub restored_by: Option<String>,
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+impl AutoArchival {
+    /// Records that a rule archived an item.
+    pub fn new(
+        rule: &ArchiveRule,
+        entity_id: EntityId,
+        entity_name: String,
+        last_run_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: 0,
+            rule_id: rule.id,
+            target: rule.target,
+            entity_id,
+            entity_name,
+            last_run_at,
+            archived_at: Utc::now(),
+            restored_by: None,
+            restored_at: None,
+        }
+    }
+
+    /// Returns true if the item has been restored.
+    pub fn is_restored(&self) -> bool {
+        self.restored_at.is_some()
+    }
+
+    /// Records that the item was taken out of the archive.
+    pub fn restore(&mut self, restored_by: String, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if self.is_restored() {
+            return Err(DomainError::Validation(format!(
+                "{} {} was already restored",
+                self.target, self.entity_name
+            )));
+        }
+        self.restored_by = Some(restored_by);
+        self.restored_at = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_items_are_announced_before_they_are_archived() {
+        let mut rule = ArchiveRule::new(
+            "Old libraries".to_string(),
+            ArchiveTarget::Library,
+            90,
+            7,
+            "lm".to_string(),
+        )
+        .unwrap();
+        let last_run = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let day = |d| last_run + Duration::days(d);
+
+        assert!(!rule.announces(last_run, day(82)));
+        assert!(rule.announces(last_run, day(83)));
+        // A new rule archives nothing, however overdue
+        assert!(!rule.archives(last_run, day(200)));
+
+        rule.last_evaluated_at = Some(day(85));
+        assert!(!rule.announces(last_run, day(86)));
+        assert!(!rule.archives(last_run, day(89)));
+        assert!(rule.archives(last_run, day(90)));
+
+        // Changing the rule announces items again
+        rule.reschedule(120, 14).unwrap();
+        assert_eq!(rule.last_evaluated_at, None);
+        assert!(rule.announces(last_run, day(106)));
+        assert!(rule.reschedule(30, 31).is_err());
+        assert!(rule.reschedule(30, 0).is_err());
+        assert_eq!(rule.after_days, 120);
+    }
+
+    #[test]
+    fn test_archival_is_restored_once() {
+        let rule =
+            ArchiveRule::new("Old samples".to_string(), ArchiveTarget::Sample, 30, 3, "lm".to_string())
+                .unwrap();
+        let mut archival = AutoArchival::new(&rule, 5, "SAM5".to_string(), Utc::now());
+        archival.restore("tech".to_string(), Utc::now()).unwrap();
+        assert!(archival.is_restored());
+        assert!(archival.restore("tech".to_string(), Utc::now()).is_err());
+        assert_eq!("Library".parse::<ArchiveTarget>().unwrap(), ArchiveTarget::Library);
+    }
+}
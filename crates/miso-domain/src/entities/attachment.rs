@@ -0,0 +1,150 @@
+//! Attachment entity - a file uploaded against a sample, library or run.
+//!
+//! Labs keep the paperwork and instrument output that goes with their
+//! material: consent forms and pathology reports as PDFs, gel images,
+//! Bioanalyzer traces. The file itself is kept by an
+//! [`AttachmentStore`](crate::attachments::AttachmentStore) under the
+//! attachment's storage key; the entity records what it is and who
+//! uploaded it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// Kind of entity a file is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentTarget {
+    Sample,
+    Library,
+    Run,
+}
+
+impl AttachmentTarget {
+    /// Returns the code stored for the target.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sample => "sample",
+            Self::Library => "library",
+            Self::Run => "run",
+        }
+    }
+}
+
+impl fmt::Display for AttachmentTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AttachmentTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sample" => Ok(Self::Sample),
+            "library" => Ok(Self::Library),
+            "run" => Ok(Self::Run),
+            other => Err(format!("Unknown attachment target: {}", other)),
+        }
+    }
+}
+
+/// A file attached to a sample, library or run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: EntityId,
+    pub target: AttachmentTarget,
+    pub target_id: EntityId,
+    /// Name of the file as uploaded, without any directory
+    pub filename: String,
+    /// MIME type, e.g. "application/pdf"
+    pub content_type: String,
+    /// Size in bytes
+    pub size: i64,
+    /// Hex-encoded SHA-256 of the contents
+    pub checksum: String,
+    /// Where the attachment store keeps the contents
+    pub storage_key: String,
+    pub uploaded_by: String,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    /// Describes a file being attached, giving it a storage key of its own.
+    ///
+    /// Any directory in the uploaded file name is dropped.
+    pub fn new(
+        target: AttachmentTarget,
+        target_id: EntityId,
+        filename: &str,
+        content_type: &str,
+        size: i64,
+        checksum: String,
+        uploaded_by: String,
+    ) -> Result<Self, DomainError> {
+        let filename = filename
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        if filename.is_empty() || filename == "." || filename == ".." {
+            return Err(DomainError::Validation(
+                "An attachment needs a file name".to_string(),
+            ));
+        }
+        let content_type = match content_type.trim() {
+            "" => "application/octet-stream",
+            content_type => content_type,
+        };
+
+        Ok(Self {
+            id: 0,
+            target,
+            target_id,
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            size,
+            checksum,
+            storage_key: format!("{}/{}/{}", target, target_id, uuid::Uuid::new_v4().simple()),
+            uploaded_by,
+            uploaded_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attach(filename: &str) -> Result<Attachment, DomainError> {
+        Attachment::new(
+            AttachmentTarget::Sample,
+            42,
+            filename,
+            "",
+            10,
+            "ab".to_string(),
+            "tech".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_file_name_drops_directories() {
+        let attachment = attach("C:\\scans\\consent form.pdf").unwrap();
+        assert_eq!(attachment.filename, "consent form.pdf");
+        assert_eq!(attachment.content_type, "application/octet-stream");
+        assert!(attachment.storage_key.starts_with("sample/42/"));
+        assert_ne!(attach("trace.png").unwrap().storage_key, attachment.storage_key);
+
+        assert_eq!(attach("../../etc/passwd").unwrap().filename, "passwd");
+        assert!(attach("reports/").is_err());
+        assert!(attach("..").is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! Custom attribute definitions.
+//!
+//! Admins define extra fields recorded on samples or libraries without a
+//! schema change. A definition either applies everywhere or only to one
+//! project, which is how projects require fields of their own. Values are
+//! stored as text and checked against the definition's type and limits.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::EntityId;
+use crate::errors::DomainError;
+
+/// The kind of entity an attribute is recorded on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeTarget {
+    Sample,
+    Library,
+}
+
+impl std::fmt::Display for AttributeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sample => write!(f, "sample"),
+            Self::Library => write!(f, "library"),
+        }
+    }
+}
+
+/// The type of an attribute's values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    Text,
+    Integer,
+    Decimal,
+    /// "true" or "false"
+    Boolean,
+    /// An ISO 8601 date, e.g. "2024-03-09"
+    Date,
+    /// One of the definition's options
+    Choice,
+}
+
+/// An admin-defined attribute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Entity the attribute is recorded on
+    pub target: AttributeTarget,
+    /// Project the attribute is limited to, or `None` for every project
+    pub project_id: Option<EntityId>,
+    /// Name values are stored under, e.g. "donor_id"
+    pub key: String,
+    /// Name shown on forms
+    pub label: String,
+    /// Type of the values
+    pub value_type: AttributeType,
+    /// Whether every entity must have a value
+    pub required: bool,
+    /// Allowed values of a choice
+    pub options: Vec<String>,
+    /// Smallest allowed number
+    pub min: Option<f64>,
+    /// Largest allowed number
+    pub max: Option<f64>,
+    /// Longest allowed text, in characters
+    pub max_length: Option<u32>,
+    /// Guidance shown with the field
+    pub help_text: Option<String>,
+    /// Order on forms, lowest first
+    pub position: i32,
+}
+
+impl AttributeDefinition {
+    /// Creates an optional attribute available in every project.
+    pub fn new(
+        target: AttributeTarget,
+        key: String,
+        label: String,
+        value_type: AttributeType,
+    ) -> Self {
+        Self {
+            id: 0,
+            target,
+            project_id: None,
+            key,
+            label,
+            value_type,
+            required: false,
+            options: Vec::new(),
+            min: None,
+            max: None,
+            max_length: None,
+            help_text: None,
+            position: 0,
+        }
+    }
+
+    /// Checks that the definition is well formed.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        let key_valid = self
+            .key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase())
+            && self
+                .key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !key_valid {
+            return Err(DomainError::Validation(format!(
+                "Attribute key '{}' must start with a lowercase letter and contain only \
+                 lowercase letters, digits and underscores",
+                self.key
+            )));
+        }
+        if self.label.trim().is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Attribute {} must have a label",
+                self.key
+            )));
+        }
+
+        let numeric = matches!(self.value_type, AttributeType::Integer | AttributeType::Decimal);
+        if !numeric && (self.min.is_some() || self.max.is_some()) {
+            return Err(DomainError::Validation(format!(
+                "Attribute {} is not numeric and cannot have a minimum or maximum",
+                self.key
+            )));
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(DomainError::Validation(format!(
+                    "Attribute {} has a minimum above its maximum",
+                    self.key
+                )));
+            }
+        }
+        if self.max_length.is_some() && self.value_type != AttributeType::Text {
+            return Err(DomainError::Validation(format!(
+                "Attribute {} is not text and cannot have a maximum length",
+                self.key
+            )));
+        }
+
+        match self.value_type {
+            AttributeType::Choice if self.options.is_empty() => {
+                Err(DomainError::Validation(format!(
+                    "Choice attribute {} must have at least one option",
+                    self.key
+                )))
+            }
+            AttributeType::Choice if self.options.iter().any(|o| o.trim().is_empty()) => {
+                Err(DomainError::Validation(format!(
+                    "Choice attribute {} has a blank option",
+                    self.key
+                )))
+            }
+            AttributeType::Choice => Ok(()),
+            _ if !self.options.is_empty() => Err(DomainError::Validation(format!(
+                "Attribute {} is not a choice and cannot have options",
+                self.key
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if the attribute applies to entities in the project.
+    pub fn applies_to(&self, project_id: EntityId) -> bool {
+        self.project_id.is_none_or(|p| p == project_id)
+    }
+
+    /// Checks a value against the definition's type and limits, returning
+    /// what is wrong with it.
+    pub fn check_value(&self, value: &str) -> Result<(), String> {
+        let number = match self.value_type {
+            AttributeType::Text => {
+                if let Some(max) = self.max_length {
+                    if value.chars().count() > max as usize {
+                        return Err(format!("must be at most {} characters", max));
+                    }
+                }
+                None
+            }
+            AttributeType::Integer => Some(
+                value
+                    .parse::<i64>()
+                    .map_err(|_| "must be a whole number".to_string())? as f64,
+            ),
+            AttributeType::Decimal => Some(
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| "must be a number".to_string())?,
+            ),
+            AttributeType::Boolean => {
+                if value != "true" && value != "false" {
+                    return Err("must be true or false".to_string());
+                }
+                None
+            }
+            AttributeType::Date => {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| "must be a date (YYYY-MM-DD)".to_string())?;
+                None
+            }
+            AttributeType::Choice => {
+                if !self.options.iter().any(|o| o == value) {
+                    return Err(format!("must be one of {}", self.options.join(", ")));
+                }
+                None
+            }
+        };
+
+        if let Some(number) = number {
+            if let Some(min) = self.min.filter(|min| number < *min) {
+                return Err(format!("must be at least {}", min));
+            }
+            if let Some(max) = self.max.filter(|max| number > *max) {
+                return Err(format!("must be at most {}", max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks an entity's attribute values against the definitions that apply
+/// to it.
+///
+/// Every required attribute must have a non-blank value, every value must
+/// suit its definition, and values may only be given for defined
+/// attributes. All problems are reported together.
+pub fn check_attributes(
+    definitions: &[AttributeDefinition],
+    values: &BTreeMap<String, String>,
+) -> Result<(), DomainError> {
+    let mut problems = Vec::new();
+
+    for definition in definitions {
+        match values.get(&definition.key).map(|v| v.trim()) {
+            None | Some("") if definition.required => {
+                problems.push(format!("{} is required", definition.label))
+            }
+            None | Some("") => {}
+            Some(value) => {
+                if let Err(problem) = definition.check_value(value) {
+                    problems.push(format!("{} {}", definition.label, problem));
+                }
+            }
+        }
+    }
+    for key in values.keys() {
+        if !definitions.iter().any(|d| &d.key == key) {
+            problems.push(format!("{} is not a defined attribute", key));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(DomainError::Validation(problems.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(key: &str, value_type: AttributeType) -> AttributeDefinition {
+        AttributeDefinition::new(
+            AttributeTarget::Sample,
+            key.to_string(),
+            key.replace('_', " "),
+            value_type,
+        )
+    }
+
+    #[test]
+    fn test_definition_validation() {
+        assert!(definition("donor_id", AttributeType::Text).validate().is_ok());
+        assert!(definition("Donor", AttributeType::Text).validate().is_err());
+        assert!(definition("2nd", AttributeType::Text).validate().is_err());
+        assert!(definition("tissue", AttributeType::Choice).validate().is_err());
+
+        let mut age = definition("age", AttributeType::Integer);
+        age.min = Some(18.0);
+        age.max = Some(5.0);
+        assert!(age.validate().is_err());
+
+        let mut note = definition("note", AttributeType::Text);
+        note.min = Some(1.0);
+        assert!(note.validate().is_err());
+    }
+
+    #[test]
+    fn test_values_are_checked_against_type_and_limits() {
+        let mut age = definition("age", AttributeType::Integer);
+        age.min = Some(0.0);
+        age.max = Some(120.0);
+        assert!(age.check_value("42").is_ok());
+        assert_eq!(age.check_value("4.2").unwrap_err(), "must be a whole number");
+        assert_eq!(age.check_value("130").unwrap_err(), "must be at most 120");
+
+        let mut tissue = definition("tissue", AttributeType::Choice);
+        tissue.options = vec!["Blood".to_string(), "Saliva".to_string()];
+        assert!(tissue.check_value("Saliva").is_ok());
+        assert!(tissue.check_value("saliva").is_err());
+
+        let collected = definition("collected", AttributeType::Date);
+        assert!(collected.check_value("2024-02-29").is_ok());
+        assert!(collected.check_value("2023-02-29").is_err());
+
+        let consent = definition("consent", AttributeType::Boolean);
+        assert!(consent.check_value("true").is_ok());
+        assert!(consent.check_value("yes").is_err());
+    }
+
+    #[test]
+    fn test_check_attributes_reports_every_problem() {
+        let mut donor = definition("donor_id", AttributeType::Text);
+        donor.required = true;
+        let age = definition("age", AttributeType::Integer);
+        let definitions = vec![donor, age];
+
+        let values = BTreeMap::from([
+            ("age".to_string(), "old".to_string()),
+            ("colour".to_string(), "blue".to_string()),
+        ]);
+        let error = check_attributes(&definitions, &values).unwrap_err().to_string();
+        assert!(error.contains("donor id is required"));
+        assert!(error.contains("age must be a whole number"));
+        assert!(error.contains("colour is not a defined attribute"));
+
+        let values = BTreeMap::from([("donor_id".to_string(), "D-12".to_string())]);
+        assert!(check_attributes(&definitions, &values).is_ok());
+    }
+
+    #[test]
+    fn test_project_attributes_apply_only_to_their_project() {
+        let mut consent = definition("consent", AttributeType::Boolean);
+        assert!(consent.applies_to(7));
+        consent.project_id = Some(3);
+        assert!(consent.applies_to(3));
+        assert!(!consent.applies_to(7));
+    }
+}
@@ -0,0 +1,228 @@
+//! Audit log entity.
+//!
+//! Every create, update and delete of a lab record is kept in the audit
+//! log: who made it, when, and the value of each field before and after.
+//! Unlike the change log, which records notable changes with their
+//! reasons, the audit log is complete and has no reasons. Like the change
+//! log, entries are append-only, and outlive the records they describe,
+//! and are hash-chained as they are saved.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+use crate::services::sha256_hex;
+
+use super::{EntityId, ERASED};
+
+/// What was done to an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// The entity was created
+    Create,
+    /// The entity was changed
+    Update,
+    /// The entity was deleted
+    Delete,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create => write!(f, "Create"),
+            Self::Update => write!(f, "Update"),
+            Self::Delete => write!(f, "Delete"),
+        }
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            other => Err(DomainError::Validation(format!(
+                "Unknown audit action: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// A field's value before and after a change.
+///
+/// Values are JSON. Nested fields are named by their path, e.g.
+/// "details.external_name"; a field that didn't exist before or after is
+/// `None` on that side. An erased value keeps the hash of what it was, so
+/// the entry can still be checked against its chain hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// The field's name or path
+    pub field: String,
+    /// JSON value before the change
+    pub old: Option<String>,
+    /// JSON value after the change
+    pub new: Option<String>,
+    /// Hash of the value before, once it has been erased
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_hash: Option<String>,
+    /// Hash of the value after, once it has been erased
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_hash: Option<String>,
+}
+
+impl FieldChange {
+    /// Creates a change of a field from one JSON value to another.
+    pub fn new(field: impl Into<String>, old: Option<String>, new: Option<String>) -> Self {
+        Self {
+            field: field.into(),
+            old,
+            new,
+            old_hash: None,
+            new_hash: None,
+        }
+    }
+}
+
+/// The hash standing in for one side of a change in an entry's hash
+/// input: the value's own hash, or for an erased value the hash it kept.
+fn value_hash(value: &Option<String>, kept: &Option<String>) -> String {
+    let erased = format!("\"{}\"", ERASED);
+    match value {
+        Some(value) if *value == erased => kept.clone().unwrap_or_default(),
+        Some(value) => sha256_hex(value),
+        None => String::new(),
+    }
+}
+
+/// One recorded create, update or delete.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Type of the entity, e.g. "Sample"
+    pub entity_type: String,
+    /// ID of the entity
+    pub entity_id: EntityId,
+    /// What was done
+    pub action: AuditAction,
+    /// Fields that changed; every field for creates and deletes
+    pub changes: Vec<FieldChange>,
+    /// Who did it
+    pub changed_by: String,
+    /// When it was done
+    pub changed_at: DateTime<Utc>,
+    /// Hash of the entry before; empty for the first entry
+    pub previous_hash: String,
+    /// Hash of this entry's contents and the previous hash; empty until
+    /// the entry is saved
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Creates a new, unsaved entry timestamped now.
+    pub fn new(
+        entity_type: impl Into<String>,
+        entity_id: EntityId,
+        action: AuditAction,
+        changes: Vec<FieldChange>,
+        changed_by: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: 0,
+            entity_type: entity_type.into(),
+            entity_id,
+            action,
+            changes,
+            changed_by: changed_by.into(),
+            changed_at: Utc::now(),
+            previous_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    /// The text an entry's hash is computed from: the previous hash, each
+    /// of the entry's fields, and its changes by field name, one per line.
+    /// Changes contribute the hashes of their values rather than the
+    /// values, so that erasing a value leaves the input unchanged.
+    pub fn hash_input(&self) -> String {
+        let mut changes: Vec<&FieldChange> = self.changes.iter().collect();
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        let mut input = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            self.previous_hash,
+            self.entity_type,
+            self.entity_id,
+            self.action,
+            self.changed_by,
+            self.changed_at.to_rfc3339(),
+        );
+        for change in changes {
+            input.push_str(&format!(
+                "\n{}\t{}\t{}",
+                change.field,
+                value_hash(&change.old, &change.old_hash),
+                value_hash(&change.new, &change.new_hash),
+            ));
+        }
+        input
+    }
+
+    /// Chains the entry onto the one whose hash is given.
+    pub fn chain(&mut self, previous_hash: String) {
+        // Whole seconds, as stored, so the hash is the same once read back
+        self.changed_at = self.changed_at.trunc_subsecs(0);
+        self.previous_hash = previous_hash;
+        self.hash = sha256_hex(&self.hash_input());
+    }
+
+    /// Returns the hash the entry's contents give. Erased values are
+    /// stood in for by the hashes they kept, so the rest of the entry is
+    /// still checked.
+    pub fn expected_hash(&self) -> String {
+        sha256_hex(&self.hash_input())
+    }
+
+    /// Replaces the values of the given fields, keeping the record that
+    /// they changed and the hashes of the values. Returns true if any of
+    /// them were recorded.
+    pub fn erase_fields(&mut self, fields: &[&str]) -> bool {
+        // Values are JSON, so the replacement is a JSON string
+        let erased = format!("\"{}\"", ERASED);
+        let mut found = false;
+        for change in &mut self.changes {
+            if fields.contains(&change.field.as_str()) {
+                for (side, kept) in [
+                    (&mut change.old, &mut change.old_hash),
+                    (&mut change.new, &mut change.new_hash),
+                ] {
+                    if side.is_some() {
+                        *kept = Some(value_hash(side, kept));
+                        *side = Some(erased.clone());
+                    }
+                }
+                found = true;
+            }
+        }
+        found
+    }
+}
+
+/// Filter for reading the audit log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditQuery {
+    /// Only entries for this type of entity
+    pub entity_type: Option<String>,
+    /// Only entries for the entity with this ID
+    pub entity_id: Option<EntityId>,
+    /// Only entries by this user
+    pub changed_by: Option<String>,
+    /// Maximum number of entries
+    pub limit: Option<u64>,
+    /// Number of entries to skip
+    pub offset: Option<u64>,
+}
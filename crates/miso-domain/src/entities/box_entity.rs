@@ -135,6 +135,9 @@ pub struct StorageBox {
     pub dimension: Dimension,
     /// Location in the storage hierarchy
     pub location: StorageLocation,
+    /// Rack the box is assigned to; its location then names the rack's
+    /// freezer, shelf and rack
+    pub rack_id: Option<EntityId>,
     /// The type of items this box can hold
     pub storable_type: StorableType,
     /// Map of position -> item
@@ -162,6 +165,7 @@ impl StorageBox {
             barcode: None,
             dimension,
             location: StorageLocation::new(),
+            rack_id: None,
             storable_type,
             contents: HashMap::new(),
             description: None,
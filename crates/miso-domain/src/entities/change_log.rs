@@ -0,0 +1,138 @@
+//! Change log entity.
+//!
+//! A change log entry records a notable change to an entity: who made it,
+//! when, and why. Entries are append-only, and are hash-chained as they are
+//! saved.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::services::sha256_hex;
+
+use super::{EntityId, ERASED};
+
+/// A single recorded change to an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLogEntry {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Type of the changed entity, e.g. "Sample"
+    pub entity_type: String,
+    /// ID of the changed entity
+    pub entity_id: EntityId,
+    /// What changed
+    pub summary: String,
+    /// Why it changed, if a reason was given
+    pub reason: Option<String>,
+    /// Hash of the summary as it was, once details are erased from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_hash: Option<String>,
+    /// Hash of the reason as it was, once details are erased from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_hash: Option<String>,
+    /// Who made the change
+    pub changed_by: String,
+    /// When the change was made
+    pub changed_at: DateTime<Utc>,
+    /// Hash of the entry before; empty for the first entry
+    pub previous_hash: String,
+    /// Hash of this entry's contents and the previous hash; empty until
+    /// the entry is saved
+    pub hash: String,
+}
+
+impl ChangeLogEntry {
+    /// Creates a new, unsaved entry timestamped now.
+    pub fn new(
+        entity_type: impl Into<String>,
+        entity_id: EntityId,
+        summary: impl Into<String>,
+        changed_by: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: 0,
+            entity_type: entity_type.into(),
+            entity_id,
+            summary: summary.into(),
+            reason: None,
+            summary_hash: None,
+            reason_hash: None,
+            changed_by: changed_by.into(),
+            changed_at: Utc::now(),
+            previous_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    /// Attaches the reason for the change.
+    pub fn with_reason(mut self, reason: Option<String>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    /// Replaces `text` wherever it appears in the summary or reason,
+    /// keeping the hash of what was there first. Returns true if it
+    /// appeared.
+    pub fn redact(&mut self, text: &str) -> bool {
+        if text.is_empty() {
+            return false;
+        }
+        let mut redacted = false;
+        let sides = [
+            (Some(&mut self.summary), &mut self.summary_hash),
+            (self.reason.as_mut(), &mut self.reason_hash),
+        ];
+        for (field, kept) in sides {
+            if let Some(field) = field.filter(|f| f.contains(text)) {
+                if kept.is_none() {
+                    *kept = Some(sha256_hex(field));
+                }
+                *field = field.replace(text, ERASED);
+                redacted = true;
+            }
+        }
+        redacted
+    }
+
+    /// The text an entry's hash is computed from: the previous hash, each
+    /// of the entry's fields, and the hashes of its summary and reason, one
+    /// per line. Redacted text is stood in for by the hash it kept, so
+    /// that redacting leaves the input unchanged.
+    pub fn hash_input(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.previous_hash,
+            self.entity_type,
+            self.entity_id,
+            self.changed_by,
+            self.changed_at.to_rfc3339(),
+            text_hash(Some(&self.summary), &self.summary_hash),
+            text_hash(self.reason.as_deref(), &self.reason_hash),
+        )
+    }
+
+    /// Chains the entry onto the one whose hash is given.
+    pub fn chain(&mut self, previous_hash: String) {
+        // Whole seconds, as stored, so the hash is the same once read back
+        self.changed_at = self.changed_at.trunc_subsecs(0);
+        self.previous_hash = previous_hash;
+        self.hash = sha256_hex(&self.hash_input());
+    }
+
+    /// Returns the hash the entry's contents give. Redacted text is stood
+    /// in for by the hash it kept, so the rest of the entry is still
+    /// checked.
+    pub fn expected_hash(&self) -> String {
+        sha256_hex(&self.hash_input())
+    }
+}
+
+/// The hash standing in for a summary or reason in an entry's hash input:
+/// the text's own hash, or for redacted text the hash it kept.
+fn text_hash(text: Option<&str>, kept: &Option<String>) -> String {
+    match (text, kept) {
+        (Some(text), Some(kept)) if text.contains(ERASED) => kept.clone(),
+        (Some(text), _) => sha256_hex(text),
+        (None, _) => String::new(),
+    }
+}
@@ -0,0 +1,151 @@
+//! Consent entity - what a participant agreed may be shared of their
+//! samples.
+//!
+//! Consent is recorded against an identity and covers every sample derived
+//! from it. Samples without recorded consent, including plain samples, are
+//! shared as they are.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::{EntityId, Sample, SampleDetails};
+
+/// How much of a participant's samples may leave the LIMS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentSharing {
+    /// Everything may be shared
+    Full,
+    /// The samples may be shared without details that could identify the
+    /// participant
+    Deidentified,
+    /// Nothing may be shared
+    None,
+}
+
+impl std::fmt::Display for ConsentSharing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Deidentified => write!(f, "deidentified"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConsentSharing {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "deidentified" => Ok(Self::Deidentified),
+            "none" => Ok(Self::None),
+            other => Err(DomainError::Validation(format!(
+                "Unknown consent sharing: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// The consent recorded for an identity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consent {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The identity consent was given for
+    pub identity_id: EntityId,
+    /// What may be shared
+    pub sharing: ConsentSharing,
+    /// Reference to the signed form, or other notes
+    pub notes: Option<String>,
+    /// Who recorded it
+    pub recorded_by: String,
+    /// When it was last recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl Consent {
+    /// Records an identity's consent, timestamped now.
+    pub fn new(
+        identity_id: EntityId,
+        sharing: ConsentSharing,
+        notes: Option<String>,
+        recorded_by: String,
+    ) -> Self {
+        Self {
+            id: 0,
+            identity_id,
+            sharing,
+            notes,
+            recorded_by,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Restricts a sample derived from this consent's identity to what may
+    /// be shared: all of it, it without its external name, description and
+    /// custom attributes, or nothing.
+    pub fn restrict(&self, mut sample: Sample) -> Option<Sample> {
+        match self.sharing {
+            ConsentSharing::Full => Some(sample),
+            ConsentSharing::Deidentified => {
+                if let SampleDetails::Detailed(details) = &mut sample.details {
+                    details.external_name = None;
+                }
+                sample.description = None;
+                sample.attributes.clear();
+                Some(sample)
+            }
+            ConsentSharing::None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::Barcode;
+
+    fn identity() -> Sample {
+        let mut sample = Sample::new_identity(
+            1,
+            "PAT_1".to_string(),
+            Barcode::new_unchecked("SAM1".to_string()),
+            1,
+            "MRN-1234".to_string(),
+            "tech".to_string(),
+        );
+        sample.description = Some("Jane's second biopsy".to_string());
+        sample
+            .attributes
+            .insert("donor_id".to_string(), "D-77".to_string());
+        sample
+    }
+
+    #[test]
+    fn test_consent_restricts_what_is_shared() {
+        let consent = |sharing| Consent::new(1, sharing, None, "manager".to_string());
+
+        let sample = identity();
+        assert_eq!(
+            consent(ConsentSharing::Full).restrict(sample.clone()),
+            Some(sample)
+        );
+        assert_eq!(consent(ConsentSharing::None).restrict(identity()), None);
+
+        let shared = consent(ConsentSharing::Deidentified)
+            .restrict(identity())
+            .unwrap();
+        assert_eq!(shared.name, "PAT_1");
+        assert!(shared.description.is_none());
+        assert!(shared.attributes.is_empty());
+        assert!(matches!(
+            shared.details,
+            SampleDetails::Detailed(ref d) if d.external_name.is_none()
+        ));
+    }
+}
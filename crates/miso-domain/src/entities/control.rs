@@ -0,0 +1,72 @@
+//! Control designations for samples and libraries.
+//!
+//! Prep batches and runs carry controls alongside the real material: a
+//! positive control of known content, a negative control that should show
+//! nothing, and a no-template control (NTC) of reagents alone. Controls are
+//! tracked like any other sample or library, but don't count towards a
+//! project's samples.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of control a sample or library is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlType {
+    /// Material of known content, expected to give a result
+    Positive,
+    /// Material expected to give no result
+    Negative,
+    /// Reagents with no template, showing contamination
+    Ntc,
+}
+
+impl ControlType {
+    /// Every control type, in display order.
+    pub const ALL: [ControlType; 3] = [Self::Positive, Self::Negative, Self::Ntc];
+
+    /// Returns the code stored for the type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Negative => "negative",
+            Self::Ntc => "ntc",
+        }
+    }
+}
+
+impl fmt::Display for ControlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ControlType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "positive" => Ok(Self::Positive),
+            "negative" => Ok(Self::Negative),
+            "ntc" | "no_template" => Ok(Self::Ntc),
+            other => Err(format!("Unknown control type: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_type_codes_round_trip() {
+        for control in ControlType::ALL {
+            assert_eq!(control.as_str().parse::<ControlType>(), Ok(control));
+        }
+        assert_eq!(" NTC ".parse::<ControlType>(), Ok(ControlType::Ntc));
+        assert_eq!("no_template".parse::<ControlType>(), Ok(ControlType::Ntc));
+        assert!("blank".parse::<ControlType>().is_err());
+    }
+}
@@ -0,0 +1,321 @@
+//! Data location entities - where a run's output is stored.
+//!
+//! Pipelines register each copy of a run's data (or one lane's data) as a
+//! filesystem path or S3 URI, with a reference to its checksum manifest
+//! and a retention class. The registry answers "where does this run's data
+//! live?" and tells operations what is due for purging.
+//!
+//! Purging is two-step: once the retention date has passed, one person
+//! requests the purge, and a different person confirms it after the data
+//! has actually been deleted.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::RunError;
+
+use super::{EntityId, Platform};
+
+/// How long stored data must be kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionClass {
+    /// Intermediate output, kept for 30 days
+    Scratch,
+    /// Routine output, kept for a year
+    #[default]
+    Standard,
+    /// Clinical or archival output, kept for ten years
+    LongTerm,
+    /// Never purged
+    Permanent,
+}
+
+impl RetentionClass {
+    /// Returns how long data of this class is kept, or `None` if forever.
+    pub fn retention_period(&self) -> Option<Duration> {
+        match self {
+            Self::Scratch => Some(Duration::days(30)),
+            Self::Standard => Some(Duration::days(365)),
+            Self::LongTerm => Some(Duration::days(3650)),
+            Self::Permanent => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RetentionClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scratch => write!(f, "scratch"),
+            Self::Standard => write!(f, "standard"),
+            Self::LongTerm => write!(f, "long_term"),
+            Self::Permanent => write!(f, "permanent"),
+        }
+    }
+}
+
+impl std::str::FromStr for RetentionClass {
+    type Err = RunError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scratch" => Ok(Self::Scratch),
+            "standard" => Ok(Self::Standard),
+            "long_term" => Ok(Self::LongTerm),
+            "permanent" => Ok(Self::Permanent),
+            other => Err(RunError::InvalidParameters(format!(
+                "Unknown retention class: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// A stored copy of a run's data, or of one lane's data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataLocation {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The run the data belongs to
+    pub run_id: EntityId,
+    /// The lane, if the data covers a single lane
+    pub lane: Option<u8>,
+    /// Absolute filesystem path or `s3://bucket/key` URI
+    pub uri: String,
+    /// Location of the checksum manifest for the data
+    pub checksum_manifest: Option<String>,
+    /// How long the data must be kept
+    pub retention_class: RetentionClass,
+    /// Size of the data in bytes, if known
+    pub size_bytes: Option<u64>,
+    /// Platform the run was sequenced on, for usage reporting
+    pub platform: Option<Platform>,
+    /// Projects with data at this location, for usage reporting
+    pub project_ids: Vec<EntityId>,
+    /// Who registered the location
+    pub registered_by: String,
+    /// When the location was registered
+    pub registered_at: DateTime<Utc>,
+    /// Who requested that the data be purged
+    pub purge_requested_by: Option<String>,
+    /// When the purge was requested
+    pub purge_requested_at: Option<DateTime<Utc>>,
+    /// Who confirmed that the data was purged
+    pub purged_by: Option<String>,
+    /// When the purge was confirmed
+    pub purged_at: Option<DateTime<Utc>>,
+}
+
+impl DataLocation {
+    /// Creates a new data location.
+    ///
+    /// The URI must be an absolute path or an S3 URI naming a bucket.
+    pub fn new(
+        run_id: EntityId,
+        lane: Option<u8>,
+        uri: String,
+        retention_class: RetentionClass,
+        registered_by: String,
+    ) -> Result<Self, RunError> {
+        let uri = uri.trim().to_string();
+        let valid = match uri.strip_prefix("s3://") {
+            Some(rest) => rest.split('/').next().is_some_and(|b| !b.is_empty()),
+            None => uri.starts_with('/'),
+        };
+        if !valid {
+            return Err(RunError::InvalidParameters(format!(
+                "Data location must be an absolute path or s3:// URI: {}",
+                uri
+            )));
+        }
+        if lane == Some(0) {
+            return Err(RunError::InvalidParameters(
+                "Lanes are numbered from 1".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            run_id,
+            lane,
+            uri,
+            checksum_manifest: None,
+            retention_class,
+            size_bytes: None,
+            platform: None,
+            project_ids: Vec::new(),
+            registered_by,
+            registered_at: Utc::now(),
+            purge_requested_by: None,
+            purge_requested_at: None,
+            purged_by: None,
+            purged_at: None,
+        })
+    }
+
+    /// Returns true if the data is stored in S3.
+    pub fn is_s3(&self) -> bool {
+        self.uri.starts_with("s3://")
+    }
+
+    /// Returns the date after which the data may be purged, or `None` if
+    /// it must be kept forever.
+    pub fn retain_until(&self) -> Option<DateTime<Utc>> {
+        self.retention_class
+            .retention_period()
+            .map(|period| self.registered_at + period)
+    }
+
+    /// Returns true if the data has been purged.
+    pub fn is_purged(&self) -> bool {
+        self.purged_at.is_some()
+    }
+
+    /// Returns true if the data is past its retention date and not yet
+    /// purged.
+    pub fn is_due_for_purge(&self, now: DateTime<Utc>) -> bool {
+        !self.is_purged() && self.retain_until().is_some_and(|until| until <= now)
+    }
+
+    /// Requests that the data be purged.
+    pub fn request_purge(
+        &mut self,
+        requested_by: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), RunError> {
+        if !self.is_due_for_purge(now) {
+            return Err(RunError::InvalidParameters(format!(
+                "{} is not due for purging",
+                self.uri
+            )));
+        }
+
+        self.purge_requested_by = Some(requested_by);
+        self.purge_requested_at = Some(now);
+        Ok(())
+    }
+
+    /// Withdraws a pending purge request.
+    pub fn cancel_purge(&mut self) -> Result<(), RunError> {
+        if self.is_purged() {
+            return Err(RunError::InvalidParameters(format!(
+                "{} has already been purged",
+                self.uri
+            )));
+        }
+
+        self.purge_requested_by = None;
+        self.purge_requested_at = None;
+        Ok(())
+    }
+
+    /// Confirms that the data has been deleted.
+    ///
+    /// The purge must have been requested, by someone other than the
+    /// confirming user, and the data must still be past its retention
+    /// date (it may have been reclassified since the request).
+    pub fn confirm_purge(
+        &mut self,
+        confirmed_by: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), RunError> {
+        let Some(requested_by) = &self.purge_requested_by else {
+            return Err(RunError::InvalidParameters(format!(
+                "No purge has been requested for {}",
+                self.uri
+            )));
+        };
+        if *requested_by == confirmed_by {
+            return Err(RunError::InvalidParameters(format!(
+                "The purge of {} must be confirmed by someone other than {}",
+                self.uri, requested_by
+            )));
+        }
+        if !self.is_due_for_purge(now) {
+            return Err(RunError::InvalidParameters(format!(
+                "{} is not due for purging",
+                self.uri
+            )));
+        }
+
+        self.purged_by = Some(confirmed_by);
+        self.purged_at = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_uri_validation() {
+        let new = |uri: &str| {
+            DataLocation::new(
+                1,
+                None,
+                uri.to_string(),
+                RetentionClass::Standard,
+                "pipeline".to_string(),
+            )
+        };
+
+        assert!(new("/data/runs/RUN42").is_ok());
+        assert!(new(" s3://seq-archive/runs/RUN42 ").unwrap().is_s3());
+        assert!(new("s3:///runs/RUN42").is_err());
+        assert!(new("runs/RUN42").is_err());
+        assert!(new("https://example.org/RUN42").is_err());
+    }
+
+    #[test]
+    fn test_retention_dates() {
+        let mut location = DataLocation::new(
+            1,
+            Some(2),
+            "/data/runs/RUN42/L002".to_string(),
+            RetentionClass::Scratch,
+            "pipeline".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            location.retain_until(),
+            Some(location.registered_at + Duration::days(30))
+        );
+
+        location.retention_class = RetentionClass::Permanent;
+        assert_eq!(location.retain_until(), None);
+        assert_eq!(
+            "long_term".parse::<RetentionClass>().unwrap(),
+            RetentionClass::LongTerm
+        );
+    }
+
+    #[test]
+    fn test_purge_workflow() {
+        let mut location = DataLocation::new(
+            1,
+            None,
+            "s3://seq-archive/runs/RUN42".to_string(),
+            RetentionClass::Scratch,
+            "pipeline".to_string(),
+        )
+        .unwrap();
+        let now = location.registered_at + Duration::days(10);
+        assert!(location.request_purge("ops".to_string(), now).is_err());
+
+        let now = location.registered_at + Duration::days(31);
+        assert!(location.is_due_for_purge(now));
+        assert!(location.confirm_purge("manager".to_string(), now).is_err());
+        location.request_purge("ops".to_string(), now).unwrap();
+        assert!(location.confirm_purge("ops".to_string(), now).is_err());
+
+        location.retention_class = RetentionClass::LongTerm;
+        assert!(location.confirm_purge("manager".to_string(), now).is_err());
+        location.retention_class = RetentionClass::Scratch;
+
+        location.confirm_purge("manager".to_string(), now).unwrap();
+        assert!(location.is_purged());
+        assert!(!location.is_due_for_purge(now));
+        assert!(location.cancel_purge().is_err());
+    }
+}
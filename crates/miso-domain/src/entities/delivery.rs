@@ -0,0 +1,332 @@
+//! Data delivery entities - where a project's data is released to, and
+//! which runs' data have been delivered there.
+//!
+//! A project registers one or more delivery targets: an S3 bucket and
+//! prefix, or a directory on an SFTP host. The data-release workflow copies
+//! a run's data to a target, records the delivery, then checks the copy
+//! against the run's checksum manifest and records whether it matched.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// Where delivered data is copied to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+pub enum DeliveryDestination {
+    /// An S3 bucket, under a key prefix
+    S3 { bucket: String, prefix: String },
+    /// A directory on an SFTP host
+    Sftp {
+        host: String,
+        #[serde(default = "default_sftp_port")]
+        port: u16,
+        path: String,
+    },
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+impl DeliveryDestination {
+    /// Returns the protocol code stored for the destination.
+    pub fn protocol(&self) -> &'static str {
+        match self {
+            Self::S3 { .. } => "s3",
+            Self::Sftp { .. } => "sftp",
+        }
+    }
+
+    /// Checks the destination and tidies it: names are trimmed and S3
+    /// prefixes lose their leading and trailing slashes.
+    pub fn normalized(self) -> Result<Self, DomainError> {
+        match self {
+            Self::S3 { bucket, prefix } => {
+                let bucket = bucket.trim().to_string();
+                let valid = (3..=63).contains(&bucket.len())
+                    && bucket
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-')
+                    && bucket.starts_with(|c: char| c.is_ascii_alphanumeric())
+                    && bucket.ends_with(|c: char| c.is_ascii_alphanumeric());
+                if !valid {
+                    return Err(DomainError::Validation(format!(
+                        "Not a valid S3 bucket name: {}",
+                        bucket
+                    )));
+                }
+                Ok(Self::S3 {
+                    bucket,
+                    prefix: prefix.trim().trim_matches('/').to_string(),
+                })
+            }
+            Self::Sftp { host, port, path } => {
+                let host = host.trim().to_string();
+                if host.is_empty() || host.contains(|c: char| c.is_whitespace() || c == '/') {
+                    return Err(DomainError::Validation(format!(
+                        "Not a valid SFTP host: {}",
+                        host
+                    )));
+                }
+                if port == 0 {
+                    return Err(DomainError::Validation(
+                        "SFTP port must be between 1 and 65535".to_string(),
+                    ));
+                }
+                let path = path.trim().trim_end_matches('/').to_string();
+                if !path.starts_with('/') && !path.is_empty() {
+                    return Err(DomainError::Validation(format!(
+                        "SFTP path must be absolute: {}",
+                        path
+                    )));
+                }
+                Ok(Self::Sftp { host, port, path })
+            }
+        }
+    }
+
+    /// Returns the URI of a directory under the destination.
+    pub fn uri_of(&self, directory: &str) -> String {
+        let directory = directory.trim_matches('/');
+        let base = self.to_string();
+        if directory.is_empty() {
+            base
+        } else {
+            format!("{}/{}", base.trim_end_matches('/'), directory)
+        }
+    }
+}
+
+impl fmt::Display for DeliveryDestination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::S3 { bucket, prefix } if prefix.is_empty() => write!(f, "s3://{}", bucket),
+            Self::S3 { bucket, prefix } => write!(f, "s3://{}/{}", bucket, prefix),
+            Self::Sftp { host, port: 22, path } => write!(f, "sftp://{}{}", host, path),
+            Self::Sftp { host, port, path } => write!(f, "sftp://{}:{}{}", host, port, path),
+        }
+    }
+}
+
+/// A place a project's data is released to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryTarget {
+    pub id: EntityId,
+    pub project_id: EntityId,
+    /// Name the project knows the target by, e.g. "Collaborator bucket"
+    pub name: String,
+    pub destination: DeliveryDestination,
+    /// Retired targets keep their deliveries but take no more
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeliveryTarget {
+    /// Registers a delivery target for a project.
+    pub fn new(
+        project_id: EntityId,
+        name: String,
+        destination: DeliveryDestination,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(DomainError::Validation(
+                "Delivery target name is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            project_id,
+            name,
+            destination: destination.normalized()?,
+            active: true,
+            created_by,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// How far a delivery has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Copied, not yet checked
+    Delivered,
+    /// Checked against the checksum manifest and found complete
+    Verified,
+    /// Checked and found missing or corrupt files
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// Returns the code stored for the status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delivered => "delivered",
+            Self::Verified => "verified",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "delivered" => Ok(Self::Delivered),
+            "verified" => Ok(Self::Verified),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("Unknown delivery status: {}", other)),
+        }
+    }
+}
+
+/// A copy of a run's data released to a project's delivery target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delivery {
+    pub id: EntityId,
+    pub target_id: EntityId,
+    pub project_id: EntityId,
+    pub run_id: EntityId,
+    /// Where the data was copied to
+    pub uri: String,
+    /// Manifest the copy is checked against
+    pub checksum_manifest: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub status: DeliveryStatus,
+    pub delivered_by: String,
+    pub delivered_at: DateTime<Utc>,
+    pub verified_by: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    /// What the check found, e.g. the files that didn't match
+    pub note: Option<String>,
+}
+
+impl Delivery {
+    /// Records that a run's data was copied to a target.
+    pub fn new(target: &DeliveryTarget, run_id: EntityId, uri: String, delivered_by: String) -> Self {
+        Self {
+            id: 0,
+            target_id: target.id,
+            project_id: target.project_id,
+            run_id,
+            uri,
+            checksum_manifest: None,
+            size_bytes: None,
+            status: DeliveryStatus::Delivered,
+            delivered_by,
+            delivered_at: Utc::now(),
+            verified_by: None,
+            verified_at: None,
+            note: None,
+        }
+    }
+
+    /// Records the outcome of checking the copy against its checksum
+    /// manifest. Each delivery is checked once; a failed delivery is
+    /// repeated as a new one.
+    pub fn verify(
+        &mut self,
+        complete: bool,
+        note: Option<String>,
+        verified_by: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        if self.status != DeliveryStatus::Delivered {
+            return Err(DomainError::Validation(format!(
+                "Delivery to {} was already checked and {}",
+                self.uri, self.status
+            )));
+        }
+        if complete && self.checksum_manifest.is_none() {
+            return Err(DomainError::Validation(format!(
+                "Delivery to {} has no checksum manifest to verify it against",
+                self.uri
+            )));
+        }
+
+        self.status = if complete {
+            DeliveryStatus::Verified
+        } else {
+            DeliveryStatus::Failed
+        };
+        self.note = note;
+        self.verified_by = Some(verified_by);
+        self.verified_at = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s3(bucket: &str, prefix: &str) -> DeliveryDestination {
+        DeliveryDestination::S3 {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_destinations_are_checked_and_named() {
+        let bucket = s3(" collab-data ", "/releases/PROJ1/").normalized().unwrap();
+        assert_eq!(bucket.to_string(), "s3://collab-data/releases/PROJ1");
+        assert_eq!(bucket.uri_of("RUN42"), "s3://collab-data/releases/PROJ1/RUN42");
+        assert_eq!(s3("collab-data", "").normalized().unwrap().uri_of("RUN42"), "s3://collab-data/RUN42");
+        assert!(s3("Collab_Data", "").normalized().is_err());
+        assert!(s3("ab", "").normalized().is_err());
+
+        let sftp = |host: &str, port: u16, path: &str| DeliveryDestination::Sftp {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        };
+        assert_eq!(
+            sftp("sftp.example.org", 22, "/incoming/").normalized().unwrap().uri_of("RUN42"),
+            "sftp://sftp.example.org/incoming/RUN42"
+        );
+        assert_eq!(
+            sftp("sftp.example.org", 2222, "/incoming").normalized().unwrap().to_string(),
+            "sftp://sftp.example.org:2222/incoming"
+        );
+        assert!(sftp("sftp.example.org", 22, "incoming").normalized().is_err());
+        assert!(sftp("sftp.example.org", 0, "/incoming").normalized().is_err());
+        assert!(sftp(" ", 22, "/incoming").normalized().is_err());
+    }
+
+    #[test]
+    fn test_delivery_is_checked_once() {
+        let target =
+            DeliveryTarget::new(1, "Collaborator".to_string(), s3("collab-data", ""), "lm".to_string())
+                .unwrap();
+        let mut delivery = Delivery::new(&target, 42, target.destination.uri_of("RUN42"), "pipeline".to_string());
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert!(delivery.verify(true, None, "pipeline".to_string(), Utc::now()).is_err());
+
+        delivery.checksum_manifest = Some("/data/runs/RUN42/md5sums.txt".to_string());
+        delivery
+            .verify(false, Some("3 files missing".to_string()), "pipeline".to_string(), Utc::now())
+            .unwrap();
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert!(delivery.verify(true, None, "pipeline".to_string(), Utc::now()).is_err());
+        assert_eq!("verified".parse::<DeliveryStatus>().unwrap(), DeliveryStatus::Verified);
+    }
+}
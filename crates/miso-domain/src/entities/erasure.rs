@@ -0,0 +1,83 @@
+//! Erasure entity - a withdrawn participant's identifying details removed.
+//!
+//! When a participant withdraws consent, their identity's external name
+//! and free-text descriptions are scrubbed from the identity, every sample
+//! derived from it, and the change and audit logs. The samples themselves
+//! stay, so counts, QC and downstream libraries still add up.
+//!
+//! Only hashes of the erased external names are kept, so that the same
+//! participant can't be entered again without anyone learning who they
+//! were.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// What erased text is replaced with.
+pub const ERASED: &str = "[erased]";
+
+/// A recorded erasure of an identity's identifying details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Erasure {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The identity erased
+    pub identity_id: EntityId,
+    /// SHA-256 hashes of the identity's normalized external names, hex
+    /// encoded
+    pub name_hashes: Vec<String>,
+    /// Samples scrubbed, the identity included
+    pub samples_erased: u32,
+    /// Why the identity was erased, e.g. the withdrawal request reference
+    pub reason: String,
+    /// Who erased it
+    pub erased_by: String,
+    /// When it was erased
+    pub erased_at: DateTime<Utc>,
+}
+
+impl Erasure {
+    /// Records the erasure of an identity, timestamped now.
+    pub fn new(
+        identity_id: EntityId,
+        name_hashes: Vec<String>,
+        samples_erased: u32,
+        reason: String,
+        erased_by: String,
+    ) -> Result<Self, DomainError> {
+        let reason = reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(DomainError::Validation(
+                "An erasure needs a reason".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            identity_id,
+            name_hashes,
+            samples_erased,
+            reason,
+            erased_by,
+            erased_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erasure_needs_a_reason() {
+        assert!(Erasure::new(1, Vec::new(), 1, "  ".to_string(), "dpo".to_string()).is_err());
+
+        let erasure =
+            Erasure::new(1, Vec::new(), 3, " REQ-42 ".to_string(), "dpo".to_string()).unwrap();
+        assert_eq!(erasure.reason, "REQ-42");
+        assert_eq!(erasure.id, 0);
+    }
+}
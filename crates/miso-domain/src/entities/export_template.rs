@@ -0,0 +1,207 @@
+//! Export template entity - admin-defined delimited export layouts.
+//!
+//! Collaborators each want their own column set. A template names the
+//! entity type to export, the ordered columns (as field paths into the
+//! entity's API representation, e.g. `project_id` or `attributes.donor`)
+//! with their header names, and the delimiter to use.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// The entity types that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportEntityType {
+    Project,
+    Sample,
+}
+
+impl std::fmt::Display for ExportEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Project => write!(f, "project"),
+            Self::Sample => write!(f, "sample"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportEntityType {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "project" => Ok(Self::Project),
+            "sample" => Ok(Self::Sample),
+            other => Err(DomainError::Validation(format!(
+                "Unsupported export entity type: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Field delimiter for exported files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl Delimiter {
+    /// Returns the delimiter character.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Comma => ',',
+            Self::Tab => '\t',
+            Self::Semicolon => ';',
+            Self::Pipe => '|',
+        }
+    }
+
+    /// Returns the conventional file extension for this delimiter.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Comma => "csv",
+            Self::Tab => "tsv",
+            Self::Semicolon | Self::Pipe => "txt",
+        }
+    }
+}
+
+/// A single column in an export template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportColumn {
+    /// Dotted path to the value (e.g. "barcode", "attributes.donor_id")
+    pub field: String,
+    /// Header text; defaults to the field path
+    pub header: Option<String>,
+}
+
+impl ExportColumn {
+    /// Returns the header text to write for this column.
+    pub fn header_text(&self) -> &str {
+        self.header.as_deref().unwrap_or(&self.field)
+    }
+}
+
+/// An admin-configured export layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportTemplate {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Unique template name
+    pub name: String,
+    /// What this template exports
+    pub entity_type: ExportEntityType,
+    /// Ordered columns
+    pub columns: Vec<ExportColumn>,
+    /// Field delimiter
+    pub delimiter: Delimiter,
+    /// Whether to write a header row
+    pub include_header: bool,
+    /// Who created this template
+    pub created_by: String,
+    /// When this template was created
+    pub created_at: DateTime<Utc>,
+    /// When this template was last modified
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExportTemplate {
+    /// Creates a new template, validating its columns.
+    pub fn new(
+        id: EntityId,
+        name: String,
+        entity_type: ExportEntityType,
+        columns: Vec<ExportColumn>,
+        delimiter: Delimiter,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        Self::validate_columns(&columns)?;
+
+        let now = Utc::now();
+        Ok(Self {
+            id,
+            name,
+            entity_type,
+            columns,
+            delimiter,
+            include_header: true,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Replaces the column set.
+    pub fn set_columns(&mut self, columns: Vec<ExportColumn>) -> Result<(), DomainError> {
+        Self::validate_columns(&columns)?;
+        self.columns = columns;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    fn validate_columns(columns: &[ExportColumn]) -> Result<(), DomainError> {
+        if columns.is_empty() {
+            return Err(DomainError::Validation(
+                "An export template needs at least one column".to_string(),
+            ));
+        }
+
+        if let Some(col) = columns.iter().find(|c| c.field.trim().is_empty()) {
+            return Err(DomainError::Validation(format!(
+                "Column '{}' has an empty field path",
+                col.header_text()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(field: &str, header: Option<&str>) -> ExportColumn {
+        ExportColumn {
+            field: field.to_string(),
+            header: header.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_template_validation() {
+        assert!(ExportTemplate::new(
+            0,
+            "Empty".to_string(),
+            ExportEntityType::Sample,
+            vec![],
+            Delimiter::Comma,
+            "admin".to_string(),
+        )
+        .is_err());
+
+        let template = ExportTemplate::new(
+            0,
+            "Collaborator A".to_string(),
+            ExportEntityType::Sample,
+            vec![column("barcode", Some("Tube ID")), column("name", None)],
+            Delimiter::Tab,
+            "admin".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(template.columns[0].header_text(), "Tube ID");
+        assert_eq!(template.columns[1].header_text(), "name");
+        assert_eq!(template.delimiter.file_extension(), "tsv");
+    }
+}
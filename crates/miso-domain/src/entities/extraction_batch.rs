@@ -0,0 +1,139 @@
+//! Extraction batch entity - samples extracted together.
+//!
+//! An extraction batch records one extraction run: the instrument and kit
+//! lot used, who ran it, the Stock samples it produced and the reagent
+//! blanks taken through it alongside them. Comparing batches' yields shows
+//! when a kit lot starts to fail, and a blank with a yield shows a
+//! contaminated one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// Samples extracted together, with the instrument and reagents used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionBatch {
+    pub id: EntityId,
+    /// Unique name, e.g. "EXT-2024-031"
+    pub name: String,
+    /// Extraction instrument, e.g. "QIAcube Connect 2"; none for manual
+    /// extractions
+    pub instrument: Option<String>,
+    /// Extraction kit, e.g. "DNeasy Blood & Tissue"
+    pub kit: String,
+    /// Lot number of the kit
+    pub kit_lot: String,
+    /// Who ran the extraction
+    pub operator: String,
+    /// When the extraction was run
+    pub extracted_at: DateTime<Utc>,
+    /// Stock samples the extraction produced, in the order added
+    pub sample_ids: Vec<EntityId>,
+    /// Reagent blanks taken through the extraction, in the order added
+    pub blank_ids: Vec<EntityId>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExtractionBatch {
+    /// Creates a batch with no samples. The name, kit, kit lot and operator
+    /// are required.
+    pub fn new(
+        name: &str,
+        kit: &str,
+        kit_lot: &str,
+        operator: &str,
+        extracted_at: DateTime<Utc>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let [name, kit, kit_lot, operator] = [name, kit, kit_lot, operator].map(str::trim);
+        if [name, kit, kit_lot, operator].iter().any(|f| f.is_empty()) {
+            return Err(DomainError::Validation(
+                "An extraction batch needs a name, kit, kit lot and operator".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        Ok(Self {
+            id: 0,
+            name: name.to_string(),
+            instrument: None,
+            kit: kit.to_string(),
+            kit_lot: kit_lot.to_string(),
+            operator: operator.to_string(),
+            extracted_at,
+            sample_ids: Vec::new(),
+            blank_ids: Vec::new(),
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Returns true if the sample is in the batch, as a stock or a blank.
+    pub fn contains(&self, sample_id: EntityId) -> bool {
+        self.sample_ids.contains(&sample_id) || self.blank_ids.contains(&sample_id)
+    }
+
+    /// Adds a Stock sample, returning false if it was already in the batch.
+    pub fn add_sample(&mut self, sample_id: EntityId, now: DateTime<Utc>) -> bool {
+        if self.contains(sample_id) {
+            return false;
+        }
+        self.sample_ids.push(sample_id);
+        self.updated_at = now;
+        true
+    }
+
+    /// Adds a reagent blank, returning false if it was already in the
+    /// batch.
+    pub fn add_blank(&mut self, sample_id: EntityId, now: DateTime<Utc>) -> bool {
+        if self.contains(sample_id) {
+            return false;
+        }
+        self.blank_ids.push(sample_id);
+        self.updated_at = now;
+        true
+    }
+
+    /// Removes a sample or blank, returning false if it wasn't in the
+    /// batch.
+    pub fn remove(&mut self, sample_id: EntityId, now: DateTime<Utc>) -> bool {
+        if !self.contains(sample_id) {
+            return false;
+        }
+        self.sample_ids.retain(|&id| id != sample_id);
+        self.blank_ids.retain(|&id| id != sample_id);
+        self.updated_at = now;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_and_blanks_are_added_once() {
+        let now = Utc::now();
+        assert!(ExtractionBatch::new("EXT-1", "DNeasy", " ", "jo", now, "tech".to_string()).is_err());
+
+        let mut batch =
+            ExtractionBatch::new(" EXT-1 ", "DNeasy", "L123", "jo", now, "tech".to_string())
+                .unwrap();
+        assert_eq!(batch.name, "EXT-1");
+        assert!(batch.add_sample(4, now));
+        assert!(batch.add_blank(9, now));
+        assert!(!batch.add_sample(9, now));
+        assert!(!batch.add_blank(4, now));
+        assert_eq!((batch.sample_ids.clone(), batch.blank_ids.clone()), (vec![4], vec![9]));
+
+        assert!(batch.remove(9, now));
+        assert!(!batch.remove(9, now));
+        assert!(batch.blank_ids.is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! Index set entity - the catalog of index kits libraries are indexed from.
+//!
+//! Index kits are bought as named sets (e.g. "IDT UDI Plate 1") whose
+//! indices are known by name, so libraries are given indices by name from
+//! the catalog rather than by typing in sequences.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+use crate::value_objects::{DnaIndex, IndexFamily};
+
+use super::EntityId;
+
+/// A named set of indices, such as an index kit's plate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSet {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Kit or plate name; unique
+    pub name: String,
+    /// Family every index in the set belongs to
+    pub family: IndexFamily,
+    /// The indices, in the order the kit lists them
+    pub indices: Vec<DnaIndex>,
+    /// Who registered the set
+    pub created_by: String,
+    /// When the set was registered
+    pub created_at: DateTime<Utc>,
+}
+
+impl IndexSet {
+    /// Registers a new index set.
+    pub fn new(
+        name: String,
+        family: IndexFamily,
+        indices: Vec<DnaIndex>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let set = Self {
+            id: 0,
+            name: name.trim().to_string(),
+            family,
+            indices,
+            created_by,
+            created_at: Utc::now(),
+        };
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Checks that the set is named, holds indices of its own family, and
+    /// that no two of them share a name.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.is_empty() {
+            return Err(DomainError::Validation(
+                "Index set name must not be empty".to_string(),
+            ));
+        }
+        if self.indices.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Index set {} has no indices",
+                self.name
+            )));
+        }
+        for (i, index) in self.indices.iter().enumerate() {
+            if index.family() != self.family {
+                return Err(DomainError::Validation(format!(
+                    "Index {} is {}, not {} like its set",
+                    index.name(),
+                    index.family(),
+                    self.family
+                )));
+            }
+            if self.indices[..i]
+                .iter()
+                .any(|other| other.name().eq_ignore_ascii_case(index.name()))
+            {
+                return Err(DomainError::Validation(format!(
+                    "Index set {} lists {} more than once",
+                    self.name,
+                    index.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the index at a 1-based position in the kit's order, e.g. 5
+    /// for the fifth well of a plate.
+    pub fn index_at(&self, position: usize) -> Option<&DnaIndex> {
+        position.checked_sub(1).and_then(|i| self.indices.get(i))
+    }
+
+    /// Finds an index in the set by name, ignoring case.
+    pub fn find_index(&self, name: &str) -> Option<&DnaIndex> {
+        self.indices
+            .iter()
+            .find(|index| index.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(name: &str, i7: &str) -> DnaIndex {
+        DnaIndex::dual(name, i7, "AGGCTATA", IndexFamily::IdtUdi).unwrap()
+    }
+
+    #[test]
+    fn test_index_set_validation_and_lookup() {
+        let set = IndexSet::new(
+            " IDT UDI Plate 1 ".to_string(),
+            IndexFamily::IdtUdi,
+            vec![index("UDP0001", "GAACTGAGCG"), index("UDP0002", "AGGTCAGATA")],
+            "admin".to_string(),
+        )
+        .unwrap();
+        assert_eq!(set.name, "IDT UDI Plate 1");
+        assert_eq!(set.find_index("udp0002").map(DnaIndex::i7), Some("AGGTCAGATA"));
+        assert!(set.find_index("UDP0003").is_none());
+        assert_eq!(set.index_at(1).map(DnaIndex::name), Some("UDP0001"));
+        assert!(set.index_at(0).is_none());
+        assert!(set.index_at(3).is_none());
+
+        let duplicated = IndexSet::new(
+            "Plate".to_string(),
+            IndexFamily::IdtUdi,
+            vec![index("UDP0001", "GAACTGAGCG"), index("udp0001", "AGGTCAGATA")],
+            "admin".to_string(),
+        );
+        assert!(duplicated.is_err());
+
+        let mixed = IndexSet::new(
+            "Plate".to_string(),
+            IndexFamily::TruSeq,
+            vec![index("UDP0001", "GAACTGAGCG")],
+            "admin".to_string(),
+        );
+        assert!(mixed.is_err());
+        assert!(IndexSet::new("Plate".to_string(), IndexFamily::TruSeq, Vec::new(), "admin".to_string()).is_err());
+    }
+}
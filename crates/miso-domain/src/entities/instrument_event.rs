@@ -0,0 +1,152 @@
+//! Instrument events - errors and warnings an instrument reported during a
+//! run.
+//!
+//! Events are read from the run folder (Illumina control software logs,
+//! Oxford Nanopore run reports) and stored against the run so that QC
+//! reviewers see hardware issues alongside the run's metrics. Each
+//! ingestion replaces the events previously read from the same file.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::RunError;
+
+use super::EntityId;
+
+/// How serious an instrument event is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSeverity {
+    /// The instrument warned about a condition but carried on
+    Warning,
+    /// The instrument reported an error
+    Error,
+}
+
+impl std::fmt::Display for EventSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::str::FromStr for EventSeverity {
+    type Err = RunError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            other => Err(RunError::InvalidParameters(format!(
+                "Unknown event severity: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// An error or warning reported by the instrument during a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstrumentEvent {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The run the event was reported for
+    pub run_id: EntityId,
+    /// Severity
+    pub severity: EventSeverity,
+    /// Name of the file the event was read from
+    pub source: String,
+    /// Instrument-specific event code, if reported
+    pub code: Option<String>,
+    /// Message text
+    pub message: String,
+    /// When the instrument reported the event, if the file records it
+    pub occurred_at: Option<DateTime<Utc>>,
+    /// Who ingested the file
+    pub ingested_by: String,
+    /// When the file was ingested
+    pub ingested_at: DateTime<Utc>,
+}
+
+impl InstrumentEvent {
+    /// Creates a new event. The source file and message are required.
+    pub fn new(
+        run_id: EntityId,
+        severity: EventSeverity,
+        source: String,
+        message: String,
+        ingested_by: String,
+    ) -> Result<Self, RunError> {
+        if source.trim().is_empty() {
+            return Err(RunError::InvalidParameters(
+                "An instrument event requires a source file".to_string(),
+            ));
+        }
+        let message = message.trim().to_string();
+        if message.is_empty() {
+            return Err(RunError::InvalidParameters(
+                "An instrument event requires a message".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            run_id,
+            severity,
+            source,
+            code: None,
+            message,
+            occurred_at: None,
+            ingested_by,
+            ingested_at: Utc::now(),
+        })
+    }
+
+    /// Returns true if the event is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == EventSeverity::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_requires_source_and_message() {
+        assert!(InstrumentEvent::new(
+            1,
+            EventSeverity::Error,
+            " ".to_string(),
+            "Fluidics error".to_string(),
+            "pipeline".to_string()
+        )
+        .is_err());
+        assert!(InstrumentEvent::new(
+            1,
+            EventSeverity::Error,
+            "Logs/NovaSeq.log".to_string(),
+            "  ".to_string(),
+            "pipeline".to_string()
+        )
+        .is_err());
+
+        let event = InstrumentEvent::new(
+            1,
+            EventSeverity::Warning,
+            "Logs/NovaSeq.log".to_string(),
+            " Chiller temperature out of range ".to_string(),
+            "pipeline".to_string(),
+        )
+        .unwrap();
+        assert_eq!(event.message, "Chiller temperature out of range");
+        assert!(!event.is_error());
+        assert_eq!(
+            "error".parse::<EventSeverity>().unwrap(),
+            EventSeverity::Error
+        );
+        assert!("fatal".parse::<EventSeverity>().is_err());
+    }
+}
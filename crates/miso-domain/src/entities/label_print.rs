@@ -0,0 +1,179 @@
+//! Label print entity - a record of one label printed for an entity.
+//!
+//! Some accreditation schemes require exactly one label per specimen, so
+//! that no two tubes can carry the same identity. Templates printed in
+//! unique mode are printed once per entity; a second print needs a lab
+//! manager to override, giving a reason, and the override is recorded with
+//! the print.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+use crate::labels::{Label, PrintedJob};
+
+use super::{EntityId, Role};
+
+/// One label printed for an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelPrint {
+    pub id: EntityId,
+    /// Type of the entity labelled, e.g. "Sample"
+    pub entity_type: String,
+    pub entity_id: EntityId,
+    /// Name of the label template
+    pub template: String,
+    /// Which print of the entity with this template it is, from 1
+    pub copy: u32,
+    pub printed_by: String,
+    pub printed_at: DateTime<Utc>,
+    /// Why a manager allowed a reprint of a unique label
+    pub override_reason: Option<String>,
+}
+
+impl LabelPrint {
+    /// Records the next print of a label, after the last print of the same
+    /// entity and template if there was one.
+    ///
+    /// Reprints of a `unique` template are refused unless a lab manager or
+    /// above gives a reason to override.
+    pub fn next(
+        label: &Label,
+        last: Option<&LabelPrint>,
+        unique: bool,
+        printed_by: impl Into<String>,
+        role: Role,
+        override_reason: Option<&str>,
+    ) -> Result<Self, DomainError> {
+        let override_reason = override_reason
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty());
+        if let (Some(last), true) = (last, unique) {
+            let Some(reason) = override_reason else {
+                return Err(DomainError::Duplicate {
+                    entity_type: "LabelPrint".to_string(),
+                    field: format!("{} {} {} label", label.entity_type, label.entity_id, label.template),
+                    value: format!("printed by {} at {}", last.printed_by, last.printed_at),
+                });
+            };
+            if !role.has_at_least(&Role::LabManager) {
+                return Err(DomainError::PermissionDenied(format!(
+                    "Only a lab manager can reprint a {} label",
+                    label.template
+                )));
+            }
+            return Ok(Self::new(label, last.copy + 1, printed_by, Some(reason)));
+        }
+
+        // A reason given for a print that needs no override isn't kept
+        let copy = last.map_or(1, |last| last.copy + 1);
+        Ok(Self::new(label, copy, printed_by, None))
+    }
+
+    fn new(label: &Label, copy: u32, printed_by: impl Into<String>, reason: Option<&str>) -> Self {
+        Self {
+            id: 0,
+            entity_type: label.entity_type.clone(),
+            entity_id: label.entity_id,
+            template: label.template.clone(),
+            copy,
+            printed_by: printed_by.into(),
+            printed_at: Utc::now(),
+            override_reason: reason.map(str::to_string),
+        }
+    }
+}
+
+/// The labels a print job used, for tracking label stock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelUsage {
+    pub id: EntityId,
+    /// Printer the job was sent to
+    pub printer: String,
+    /// Template of the job's labels, which decides the stock used
+    pub template: String,
+    /// Labels sent to the printer
+    pub labels: u32,
+    /// The printer's lifetime label count as the job started, if read
+    pub odometer: Option<u64>,
+    pub printed_by: String,
+    pub printed_at: DateTime<Utc>,
+}
+
+impl LabelUsage {
+    /// Records the labels a job of `template` labels used.
+    pub fn of_job(
+        printer: impl Into<String>,
+        template: impl Into<String>,
+        job: PrintedJob,
+        printed_by: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: 0,
+            printer: printer.into(),
+            template: template.into(),
+            labels: job.labels,
+            odometer: job.odometer,
+            printed_by: printed_by.into(),
+            printed_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label() -> Label {
+        Label {
+            template: "sample".to_string(),
+            entity_type: "Sample".to_string(),
+            entity_id: 7,
+            barcode: "SAM-7".to_string(),
+            lines: vec!["S7".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_unique_label_reprint_needs_manager_override() {
+        let label = label();
+        let first = LabelPrint::next(&label, None, true, "tech", Role::Technician, None).unwrap();
+        assert_eq!((first.copy, first.override_reason.as_deref()), (1, None));
+
+        let refused = LabelPrint::next(&label, Some(&first), true, "tech", Role::Technician, None);
+        assert!(matches!(refused, Err(DomainError::Duplicate { .. })));
+        let refused = LabelPrint::next(
+            &label,
+            Some(&first),
+            true,
+            "tech",
+            Role::Technician,
+            Some("Smudged"),
+        );
+        assert!(matches!(refused, Err(DomainError::PermissionDenied(_))));
+        assert!(
+            LabelPrint::next(&label, Some(&first), true, "boss", Role::LabManager, Some(" ")).is_err()
+        );
+
+        let second = LabelPrint::next(
+            &label,
+            Some(&first),
+            true,
+            "boss",
+            Role::LabManager,
+            Some("Smudged"),
+        )
+        .unwrap();
+        assert_eq!((second.copy, second.override_reason.as_deref()), (2, Some("Smudged")));
+    }
+
+    #[test]
+    fn test_other_labels_reprint_freely() {
+        let label = label();
+        let first = LabelPrint::next(&label, None, false, "tech", Role::Technician, None).unwrap();
+        let second =
+            LabelPrint::next(&label, Some(&first), false, "tech", Role::Technician, Some("x"))
+                .unwrap();
+        assert_eq!((second.copy, second.override_reason), (2, None));
+    }
+}
@@ -3,11 +3,11 @@
 //! A Library represents the DNA/RNA after it has been prepared with
 //! adapters and indices for sequencing on a specific platform.
 
-use crate::value_objects::{Barcode, Concentration, DnaIndex, QcStatus, Volume};
+use crate::value_objects::{Barcode, Concentration, DnaIndex, Mass, QcStatus, Volume};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::EntityId;
+use super::{ControlType, EntityId};
 
 /// The design of the library (what the sequencing is targeting).
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -52,6 +52,25 @@ impl std::fmt::Display for LibraryDesign {
     }
 }
 
+impl LibraryDesign {
+    /// Parses a design code, e.g. "rna_seq"; anything unrecognised names a
+    /// custom design.
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "wgs" => Self::Wgs,
+            "wes" => Self::Wes,
+            "rna_seq" => Self::RnaSeq,
+            "targeted_panel" => Self::TargetedPanel,
+            "chip_seq" => Self::ChipSeq,
+            "atac_seq" => Self::AtacSeq,
+            "methylation" => Self::Methylation,
+            "single_cell_rna" => Self::SingleCellRna,
+            "single_cell_atac" => Self::SingleCellAtac,
+            custom => Self::Custom(custom.to_string()),
+        }
+    }
+}
+
 /// The type of library (based on preparation method).
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -100,12 +119,18 @@ pub struct Library {
     pub platform: String,
     /// The preparation kit used
     pub kit_name: Option<String>,
+    /// The targeted panel version captured, for targeted panel designs
+    pub panel_id: Option<EntityId>,
+    /// Reference genome for this library's assay, if not its project's
+    pub reference_genome_id: Option<EntityId>,
     /// The DNA index (barcode) for multiplexing
     pub index: Option<DnaIndex>,
     /// Insert size (fragment length) in base pairs
     pub insert_size: Option<u32>,
     /// Current volume
     pub volume: Option<Volume>,
+    /// Current mass of DNA
+    pub mass: Option<Mass>,
     /// Current concentration
     pub concentration: Option<Concentration>,
     /// QC status
@@ -122,6 +147,9 @@ pub struct Library {
     pub updated_at: DateTime<Utc>,
     /// Is this library archived/discarded?
     pub archived: bool,
+    /// Kind of control, if the library is one rather than project material
+    #[serde(default)]
+    pub control_type: Option<ControlType>,
 }
 
 impl Library {
@@ -150,9 +178,12 @@ impl Library {
             library_type,
             platform,
             kit_name: None,
+            panel_id: None,
+            reference_genome_id: None,
             index: None,
             insert_size: None,
             volume: None,
+            mass: None,
             concentration: None,
             qc_status: QcStatus::NotReady,
             pcr_cycles: None,
@@ -161,9 +192,16 @@ impl Library {
             created_at: now,
             updated_at: now,
             archived: false,
+            control_type: None,
         }
     }
 
+    /// Returns true if the library is a control rather than project
+    /// material.
+    pub fn is_control(&self) -> bool {
+        self.control_type.is_some()
+    }
+
     /// Sets the DNA index for this library.
     pub fn set_index(&mut self, index: DnaIndex) {
         self.index = Some(index);
@@ -199,11 +237,37 @@ impl Library {
         self.updated_at = Utc::now();
     }
 
+    /// Takes this library back out of the archive.
+    pub fn unarchive(&mut self) {
+        self.archived = false;
+        self.updated_at = Utc::now();
+    }
+
     /// Sets the QC status.
     pub fn set_qc_status(&mut self, status: QcStatus) {
         self.qc_status = status;
         self.updated_at = Utc::now();
     }
+
+    /// Withdraws volume from this library. Any tracked mass goes down in
+    /// proportion, as it is withdrawn at the library's concentration.
+    ///
+    /// Returns `Ok(())` if successful, or an error if insufficient volume.
+    pub fn withdraw_volume(&mut self, amount: Volume) -> Result<(), &'static str> {
+        let volume = self.volume.ok_or("Library has no tracked volume")?;
+        let remaining = volume.subtract(amount).ok_or("Insufficient volume")?;
+        if let Some(mass) = &mut self.mass {
+            let left = if volume.is_zero() {
+                1.0
+            } else {
+                remaining.as_microliters() / volume.as_microliters()
+            };
+            *mass = Mass::new(mass.value() * left, mass.unit());
+        }
+        self.volume = Some(remaining);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
 }
 
 /// A library aliquot - a portion of a library used for pooling.
@@ -299,6 +363,32 @@ mod tests {
         assert!(!lib.can_pool());
     }
 
+    #[test]
+    fn test_withdraw_volume_takes_its_mass() {
+        let mut lib = Library::new(
+            1,
+            "LIB001".to_string(),
+            Barcode::new("LIB-001").unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        assert_eq!(
+            lib.withdraw_volume(Volume::microliters(5.0)),
+            Err("Library has no tracked volume")
+        );
+
+        lib.volume = Some(Volume::microliters(20.0));
+        lib.mass = Some(Mass::nanograms(100.0));
+        lib.withdraw_volume(Volume::microliters(5.0)).unwrap();
+        assert_eq!(lib.volume.unwrap().as_microliters(), 15.0);
+        assert_eq!(lib.mass.unwrap().as_nanograms(), 75.0);
+        assert_eq!(lib.withdraw_volume(Volume::microliters(16.0)), Err("Insufficient volume"));
+    }
+
     #[test]
     fn test_index_distance() {
         let mut lib1 = Library::new(
@@ -0,0 +1,101 @@
+//! Maintenance window entity - time set aside for servicing a sequencer or
+//! the whole facility.
+//!
+//! Windows are announced ahead so collaborators can plan around them; they
+//! are published on the public status page, so the reason should say what
+//! is happening rather than anything confidential.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// Planned downtime of one sequencer, or of the facility as a whole.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub id: EntityId,
+    /// Sequencer being serviced; the whole facility if unset
+    pub sequencer_id: Option<EntityId>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// What is being done, e.g. "Annual preventive maintenance"
+    pub reason: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    /// Plans a maintenance window. It must end after it starts and say
+    /// why.
+    pub fn new(
+        sequencer_id: Option<EntityId>,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        reason: &str,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(DomainError::Validation(
+                "A maintenance window needs a reason".to_string(),
+            ));
+        }
+        if ends_at <= starts_at {
+            return Err(DomainError::Validation(
+                "A maintenance window must end after it starts".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            sequencer_id,
+            starts_at,
+            ends_at,
+            reason: reason.to_string(),
+            created_by,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Returns true if the window covers the whole facility.
+    pub fn is_facility_wide(&self) -> bool {
+        self.sequencer_id.is_none()
+    }
+
+    /// Returns true if the window has started and not yet ended.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// Returns true if the window applies to the sequencer, either directly
+    /// or because the whole facility is down.
+    pub fn covers(&self, sequencer_id: EntityId) -> bool {
+        self.sequencer_id.is_none_or(|id| id == sequencer_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_window_bounds() {
+        let now = Utc::now();
+        let later = now + Duration::hours(4);
+        assert!(MaintenanceWindow::new(None, later, now, "Service", "lm".to_string()).is_err());
+        assert!(MaintenanceWindow::new(None, now, later, "  ", "lm".to_string()).is_err());
+
+        let window =
+            MaintenanceWindow::new(Some(3), now, later, " PM visit ", "lm".to_string()).unwrap();
+        assert_eq!(window.reason, "PM visit");
+        assert!(window.is_active(now));
+        assert!(!window.is_active(later));
+        assert!(window.covers(3));
+        assert!(!window.covers(4));
+        assert!(!window.is_facility_wide());
+    }
+}
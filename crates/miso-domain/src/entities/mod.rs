@@ -3,23 +3,107 @@
 //! Entities are distinguished by their identity (ID), not their attributes.
 //! Two samples with identical attributes but different IDs are different entities.
 
+mod activity;
+mod api_key;
+mod api_usage;
+mod archive_rule;
+mod attachment;
+mod attribute_definition;
+mod audit;
 mod box_entity;
+mod change_log;
+mod consent;
+mod control;
+mod data_location;
+mod delivery;
+mod erasure;
+mod export_template;
+mod extraction_batch;
+mod index_set;
+mod instrument_event;
+mod label_print;
 mod library;
+mod log_archive;
+mod maintenance;
+mod panel;
 mod pool;
+mod possible_duplicate;
 mod project;
+mod project_member;
+mod qc;
+mod qc_report;
+mod reagent_lot;
+mod reconciliation;
+mod reference_genome;
+mod requisition;
 mod run;
+mod run_metrics;
 mod sample;
+mod sample_composition;
+mod sample_list;
 mod sequencer;
+mod storage_audit;
+mod storage_unit;
+mod stored_event;
+mod subproject;
+mod transfer;
 mod user;
+mod workset;
 
-pub use box_entity::{StorageBox, StorageLocation};
+pub use activity::{ActivityCursor, ActivityItem, ActivityKind};
+pub use api_key::ApiKey;
+pub use api_usage::ApiUsage;
+pub use archive_rule::{ArchiveRule, ArchiveTarget, AutoArchival};
+pub use attachment::{Attachment, AttachmentTarget};
+pub use attribute_definition::{
+    check_attributes, AttributeDefinition, AttributeTarget, AttributeType,
+};
+pub use audit::{AuditAction, AuditEntry, AuditQuery, FieldChange};
+pub use box_entity::{StorableItem, StorableType, StorageBox, StorageLocation};
+pub use change_log::ChangeLogEntry;
+pub use consent::{Consent, ConsentSharing};
+pub use control::ControlType;
+pub use data_location::{DataLocation, RetentionClass};
+pub use delivery::{Delivery, DeliveryDestination, DeliveryStatus, DeliveryTarget};
+pub use erasure::{Erasure, ERASED};
+pub use export_template::{Delimiter, ExportColumn, ExportEntityType, ExportTemplate};
+pub use extraction_batch::ExtractionBatch;
+pub use index_set::IndexSet;
+pub use instrument_event::{EventSeverity, InstrumentEvent};
+pub use label_print::{LabelPrint, LabelUsage};
 pub use library::{Library, LibraryAliquot, LibraryDesign, LibraryType};
-pub use pool::Pool;
-pub use project::Project;
-pub use run::{Run, RunPartition, RunStatus};
+pub use log_archive::{ArchivedLog, ArchivedRow, LogArchive, LogTableSize};
+pub use maintenance::MaintenanceWindow;
+pub use panel::{BedFile, Panel};
+pub use pool::{Pool, PoolElement};
+pub use possible_duplicate::{DuplicateResolution, PossibleDuplicate};
+pub use project::{Project, ProjectLock, ProjectStatus};
+pub use project_member::ProjectMember;
+pub use qc::{QcRecord, QcTarget};
+pub use qc_report::{RunQcReport, SampleQcSummary};
+pub use reagent_lot::ReagentLot;
+pub use reconciliation::{
+    DiscrepancyKind, LocationDiscrepancy, ReconciliationReport, RecordedLocation,
+};
+pub use reference_genome::ReferenceGenome;
+pub use requisition::Requisition;
+pub use run::{LoadScan, Run, RunInterruption, RunPartition, RunQcSignOff, RunStatus};
+pub use run_metrics::LibraryRunMetrics;
 pub use sample::{DetailedSampleData, PlainSampleData, Sample, SampleClass, SampleDetails};
-pub use sequencer::{ContainerModel, InstrumentModel, Platform, Sequencer};
+pub use sample_composition::SampleComposition;
+pub use sample_list::{
+    ListedLocation, ListedQc, SampleListCount, SampleListFilter, SampleListRow,
+};
+pub use sequencer::{ContainerModel, InstrumentModel, Platform, Sequencer, SequencerStatus};
+pub use storage_audit::{
+    ScanDiscrepancy, ScanDiscrepancyKind, ScannedTube, StorageAudit, StorageAuditStatus,
+};
+pub use storage_unit::{StorageUnit, StorageUnitKind};
+pub use stored_event::{EntitySnapshot, StoredEvent};
+pub use subproject::Subproject;
+pub use transfer::{ShipmentStatus, Transfer, TransferItem, TransferItemType, TransferReceipt};
 pub use user::{Role, User};
+pub use workset::{Workset, WorksetItem, WorksetItemType};
 
 /// Type alias for entity IDs.
 pub type EntityId = i32;
@@ -0,0 +1,227 @@
+//! Targeted panel entity - the regions a targeted library captures.
+//!
+//! A panel is versioned: its target regions, attached as a BED file, never
+//! change once recorded. Revising a panel records a new version under the
+//! same name, so libraries keep referring to the regions they were actually
+//! captured with.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// A BED (interval) file listing a panel's target regions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BedFile {
+    /// Name of the file as uploaded
+    pub file_name: String,
+    /// The file's contents
+    pub contents: String,
+    /// Number of intervals
+    pub intervals: u32,
+    /// Total bases covered by the intervals, counting overlaps twice
+    pub target_bases: u64,
+}
+
+impl BedFile {
+    /// Checks a BED file and counts its intervals.
+    ///
+    /// Blank lines, comments and `track`/`browser` lines are skipped. Every
+    /// other line needs a chromosome and a 0-based start before its end;
+    /// columns after the third are not checked.
+    pub fn parse(file_name: &str, contents: &str) -> Result<Self, DomainError> {
+        let file_name = file_name.trim();
+        if file_name.is_empty() {
+            return Err(DomainError::Validation(
+                "BED file name must not be empty".to_string(),
+            ));
+        }
+
+        let mut intervals = 0u32;
+        let mut target_bases = 0u64;
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim_end();
+            if line.trim().is_empty()
+                || line.starts_with('#')
+                || line.starts_with("track")
+                || line.starts_with("browser")
+            {
+                continue;
+            }
+
+            let invalid = |reason: &str| {
+                DomainError::Validation(format!("{} line {}: {}", file_name, i + 1, reason))
+            };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 3 {
+                return Err(invalid("expected chromosome, start and end"));
+            }
+            let start: u64 = fields[1]
+                .parse()
+                .map_err(|_| invalid("start is not a position"))?;
+            let end: u64 = fields[2]
+                .parse()
+                .map_err(|_| invalid("end is not a position"))?;
+            if end <= start {
+                return Err(invalid("end must be after start"));
+            }
+
+            intervals += 1;
+            target_bases += end - start;
+        }
+
+        if intervals == 0 {
+            return Err(DomainError::Validation(format!(
+                "{} has no intervals",
+                file_name
+            )));
+        }
+
+        Ok(Self {
+            file_name: file_name.to_string(),
+            contents: contents.to_string(),
+            intervals,
+            target_bases,
+        })
+    }
+}
+
+/// One version of a targeted sequencing panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Panel {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Panel name, shared by all its versions
+    pub name: String,
+    /// Version number, starting from 1
+    pub version: u32,
+    /// Genome build the intervals are on (e.g. "GRCh38")
+    pub genome_build: String,
+    /// Panel description
+    pub description: Option<String>,
+    /// The target regions
+    pub bed_file: BedFile,
+    /// Who created this version
+    pub created_by: String,
+    /// When this version was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Panel {
+    /// Creates the first version of a panel.
+    pub fn new(
+        name: String,
+        genome_build: String,
+        bed_file: BedFile,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let panel = Self {
+            id: 0,
+            name,
+            version: 1,
+            genome_build,
+            description: None,
+            bed_file,
+            created_by,
+            created_at: Utc::now(),
+        };
+        panel.validate()?;
+        Ok(panel)
+    }
+
+    /// Creates the version after this one, with new target regions.
+    pub fn next_version(&self, bed_file: BedFile, created_by: String) -> Self {
+        Self {
+            id: 0,
+            name: self.name.clone(),
+            version: self.version + 1,
+            genome_build: self.genome_build.clone(),
+            description: self.description.clone(),
+            bed_file,
+            created_by,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Checks that the panel is well formed.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.trim().is_empty() {
+            return Err(DomainError::Validation(
+                "Panel name must not be empty".to_string(),
+            ));
+        }
+        if self.genome_build.trim().is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Panel {} must name a genome build",
+                self.name
+            )));
+        }
+        if self.version == 0 {
+            return Err(DomainError::Validation(format!(
+                "Panel {} versions start from 1",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the identifier pipelines know this version by, e.g.
+    /// "CancerHotspot_v2".
+    pub fn identifier(&self) -> String {
+        format!("{}_v{}", self.name, self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BED: &str = "track name=hotspots\n\
+        # GRCh38\n\
+        chr1\t100\t200\tTP53_ex1\n\
+        \n\
+        chr17 7668401 7668421\n";
+
+    #[test]
+    fn test_bed_file_counts_intervals() {
+        let bed = BedFile::parse("hotspots.bed", BED).unwrap();
+        assert_eq!(bed.intervals, 2);
+        assert_eq!(bed.target_bases, 120);
+
+        for contents in [
+            "chr1\t100\n",
+            "chr1\tx\t200\n",
+            "chr1\t200\t200\n",
+            "# empty\n",
+        ] {
+            assert!(BedFile::parse("bad.bed", contents).is_err(), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn test_versions_share_a_name() {
+        let bed = BedFile::parse("hotspots.bed", BED).unwrap();
+        let v1 = Panel::new(
+            "CancerHotspot".to_string(),
+            "GRCh38".to_string(),
+            bed.clone(),
+            "admin".to_string(),
+        )
+        .unwrap();
+        assert_eq!(v1.identifier(), "CancerHotspot_v1");
+
+        let v2 = v1.next_version(bed.clone(), "admin".to_string());
+        assert_eq!(v2.identifier(), "CancerHotspot_v2");
+        assert_eq!(v2.genome_build, "GRCh38");
+
+        assert!(Panel::new(
+            "CancerHotspot".to_string(),
+            " ".to_string(),
+            bed,
+            "admin".to_string()
+        )
+        .is_err());
+    }
+}
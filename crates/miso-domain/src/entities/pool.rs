@@ -4,6 +4,7 @@
 //! flow cell lane, with computational demultiplexing afterward.
 
 use crate::errors::PoolError;
+use crate::services::{IndexHoppingAssessment, IndexHoppingRiskAssessor};
 use crate::value_objects::{Barcode, Concentration, QcStatus, Volume};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -47,6 +48,11 @@ pub struct Pool {
     pub qc_status: QcStatus,
     /// Platform this pool is designed for
     pub platform: String,
+    /// Container model (flow cell type) this pool is designed for
+    pub container_model: Option<String>,
+    /// Most library aliquots the pool may hold, from the site's plexity
+    /// limits; not stored, and unlimited if unset
+    pub max_size: Option<usize>,
     /// Has this pool been sequenced?
     pub sequenced: bool,
     /// Who created this record
@@ -77,6 +83,8 @@ impl Pool {
             volume: None,
             qc_status: QcStatus::NotReady,
             platform,
+            container_model: None,
+            max_size: None,
             sequenced: false,
             created_by,
             created_at: now,
@@ -96,6 +104,8 @@ impl Pool {
 
     /// Adds a library aliquot to the pool.
     ///
+    /// Fails if the pool is already at its `max_size`.
+    ///
     /// Note: Index collision checking should be done before calling this.
     pub fn add_element(&mut self, element: PoolElement) -> Result<(), PoolError> {
         // Check for duplicates
@@ -103,6 +113,12 @@ impl Pool {
             return Err(PoolError::DuplicateLibrary(element.library_id.to_string()));
         }
 
+        if let Some(max_size) = self.max_size {
+            if self.elements.len() >= max_size {
+                return Err(PoolError::CapacityExceeded(self.name.clone(), max_size));
+            }
+        }
+
         self.elements.push(element);
         self.updated_at = Utc::now();
         Ok(())
@@ -166,6 +182,25 @@ impl Pool {
         errors
     }
 
+    /// Assesses index hopping risk for the libraries in this pool.
+    ///
+    /// The result carries warnings rather than errors: non-UDI pools can
+    /// still be sequenced, but users should know the reads may be affected.
+    pub fn assess_index_hopping(
+        &self,
+        libraries: &[Library],
+        patterned: bool,
+    ) -> IndexHoppingAssessment {
+        let ids = self.library_ids();
+        let members: Vec<Library> = libraries
+            .iter()
+            .filter(|l| ids.contains(&l.id))
+            .cloned()
+            .collect();
+
+        IndexHoppingRiskAssessor::new().assess(&members, patterned)
+    }
+
     /// Returns true if this pool can be sequenced.
     pub fn can_sequence(&self) -> bool {
         !self.is_empty() && self.qc_status.allows_progression() && !self.sequenced
@@ -274,6 +309,30 @@ mod tests {
         assert!(matches!(result, Err(PoolError::DuplicateLibrary(_))));
     }
 
+    #[test]
+    fn test_pool_capacity() {
+        let mut pool = Pool::new(
+            1,
+            "POOL001".to_string(),
+            Barcode::new("POOL-001").unwrap(),
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        pool.max_size = Some(1);
+
+        let element = |id| PoolElement {
+            library_aliquot_id: id,
+            library_id: id,
+            volume: None,
+            proportion: None,
+        };
+        pool.add_element(element(1)).unwrap();
+        let result = pool.add_element(element(2));
+
+        assert!(matches!(result, Err(PoolError::CapacityExceeded(_, 1))));
+        assert_eq!(pool.size(), 1);
+    }
+
     #[test]
     fn test_index_collision_detection() {
         let mut pool = Pool::new(
@@ -339,5 +398,38 @@ mod tests {
         let errors = pool.validate_indices(&[lib1, lib2], 3);
         assert!(errors.is_empty());
     }
-}
 
+    #[test]
+    fn test_index_hopping_only_considers_pool_members() {
+        let mut pool = Pool::new(
+            1,
+            "POOL001".to_string(),
+            Barcode::new("POOL-001").unwrap(),
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+
+        pool.add_element(PoolElement {
+            library_aliquot_id: 1,
+            library_id: 1,
+            volume: None,
+            proportion: None,
+        }).unwrap();
+
+        pool.add_element(PoolElement {
+            library_aliquot_id: 2,
+            library_id: 2,
+            volume: None,
+            proportion: None,
+        }).unwrap();
+
+        // Single-indexed libraries give no protection against hopping
+        let lib1 = create_test_library(1, "ATCACG");
+        let lib2 = create_test_library(2, "TTAGGC");
+        let outsider = create_test_library(3, "CGATGT");
+
+        let assessment = pool.assess_index_hopping(&[lib1, lib2, outsider], true);
+        assert!(assessment.has_warning());
+        assert_eq!(assessment.non_udi_libraries.len(), 2);
+    }
+}
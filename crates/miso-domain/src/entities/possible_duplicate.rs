@@ -0,0 +1,159 @@
+//! Possible duplicate entity - identities created despite looking like
+//! existing ones.
+//!
+//! When an identity's external name is close to an existing identity's, it
+//! is only created once the user confirms it is a different person. Each
+//! such confirmation is kept for a lab manager to review later, and either
+//! accept the two as distinct or mark them for merging.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::{EntityId, ERASED};
+
+/// A reviewer's decision on a possible duplicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateResolution {
+    /// The identities are different people
+    Distinct,
+    /// The identities are the same person and should be merged
+    Duplicate,
+}
+
+impl std::fmt::Display for DuplicateResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Distinct => write!(f, "Distinct"),
+            Self::Duplicate => write!(f, "Duplicate"),
+        }
+    }
+}
+
+impl std::str::FromStr for DuplicateResolution {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "distinct" => Ok(Self::Distinct),
+            "duplicate" => Ok(Self::Duplicate),
+            other => Err(DomainError::Validation(format!(
+                "Unknown duplicate resolution: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// An identity created although it looked like an existing one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PossibleDuplicate {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The identity created
+    pub identity_id: EntityId,
+    /// The existing identity it looked like
+    pub match_id: EntityId,
+    /// The external name given for the new identity
+    pub external_name: String,
+    /// The existing identity's external name that matched
+    pub matched_name: String,
+    /// Edits between the normalized names
+    pub distance: u32,
+    /// Who confirmed the new identity was a different person
+    pub confirmed_by: String,
+    /// When they confirmed it
+    pub confirmed_at: DateTime<Utc>,
+    /// The reviewer's decision, once reviewed
+    pub resolution: Option<DuplicateResolution>,
+    /// Who reviewed it
+    pub resolved_by: Option<String>,
+    /// When it was reviewed
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl PossibleDuplicate {
+    /// Records that `identity_id` was created although it looked like
+    /// `match_id`.
+    pub fn new(
+        identity_id: EntityId,
+        match_id: EntityId,
+        external_name: String,
+        matched_name: String,
+        distance: u32,
+        confirmed_by: String,
+    ) -> Self {
+        Self {
+            id: 0,
+            identity_id,
+            match_id,
+            external_name,
+            matched_name,
+            distance,
+            confirmed_by,
+            confirmed_at: Utc::now(),
+            resolution: None,
+            resolved_by: None,
+            resolved_at: None,
+        }
+    }
+
+    /// Records a reviewer's decision.
+    pub fn resolve(
+        &mut self,
+        resolution: DuplicateResolution,
+        resolved_by: String,
+    ) -> Result<(), DomainError> {
+        if let Some(previous) = self.resolution {
+            return Err(DomainError::Validation(format!(
+                "Possible duplicate {} was already reviewed as {}",
+                self.id, previous
+            )));
+        }
+        self.resolution = Some(resolution);
+        self.resolved_by = Some(resolved_by);
+        self.resolved_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Removes an erased identity's external name, on whichever side it
+    /// appears.
+    pub fn erase_names_of(&mut self, identity_id: EntityId) {
+        if self.identity_id == identity_id {
+            self.external_name = ERASED.to_string();
+        }
+        if self.match_id == identity_id {
+            self.matched_name = ERASED.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_possible_duplicates_are_reviewed_once() {
+        let mut duplicate = PossibleDuplicate::new(
+            7,
+            3,
+            "MRN-0012346".to_string(),
+            "MRN-0012345".to_string(),
+            1,
+            "alice".to_string(),
+        );
+        assert_eq!(duplicate.resolution, None);
+
+        duplicate
+            .resolve("distinct".parse().unwrap(), "bob".to_string())
+            .unwrap();
+        assert_eq!(duplicate.resolution, Some(DuplicateResolution::Distinct));
+        assert_eq!(duplicate.resolved_by.as_deref(), Some("bob"));
+        assert!(duplicate
+            .resolve(DuplicateResolution::Duplicate, "bob".to_string())
+            .is_err());
+        assert!("merged".parse::<DuplicateResolution>().is_err());
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! Every sample, library, and pool belongs to a project. Projects
 //! provide the primary boundary for access control and organization.
+//!
+//! Before a review, a manager can lock a project, freezing its samples
+//! and libraries: only admins can change them until it is unlocked, by
+//! hand or at the time set when it was locked.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::EntityId;
+use crate::errors::DomainError;
+
+use super::{EntityId, Role};
 
 /// The status of a project.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -49,6 +55,26 @@ impl std::fmt::Display for ProjectStatus {
     }
 }
 
+/// Why and until when a project is locked against data entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectLock {
+    /// Why the project is locked, e.g. "PI review"
+    pub reason: String,
+    /// Who locked it
+    pub locked_by: String,
+    /// When it was locked
+    pub locked_at: DateTime<Utc>,
+    /// When the lock lapses; it stays until unlocked if `None`
+    pub unlock_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectLock {
+    /// Returns true if the lock still holds at `now`.
+    pub fn holds_at(&self, now: DateTime<Utc>) -> bool {
+        self.unlock_at.is_none_or(|unlock_at| now < unlock_at)
+    }
+}
+
 /// A project in the LIMS.
 ///
 /// Projects are the administrative root and access control boundary.
@@ -73,6 +99,8 @@ pub struct Project {
     pub reference_number: Option<String>,
     /// Target number of samples
     pub target_sample_count: Option<u32>,
+    /// Reference genome the project's data is analysed against
+    pub reference_genome_id: Option<EntityId>,
     /// Actual number of samples received
     pub sample_count: u32,
     /// When the project was created
@@ -83,6 +111,8 @@ pub struct Project {
     pub updated_at: DateTime<Utc>,
     /// When the project is due/expected to complete
     pub due_date: Option<DateTime<Utc>>,
+    /// Data entry lock, if the project has been locked
+    pub lock: Option<ProjectLock>,
 }
 
 impl Project {
@@ -104,11 +134,13 @@ impl Project {
             pi_email: None,
             reference_number: None,
             target_sample_count: None,
+            reference_genome_id: None,
             sample_count: 0,
             created_at: now,
             created_by,
             updated_at: now,
             due_date: None,
+            lock: None,
         }
     }
 
@@ -157,6 +189,41 @@ impl Project {
     pub fn can_add_samples(&self) -> bool {
         self.status.accepts_samples()
     }
+
+    /// Locks the project against data entry, until `unlock_at` if given.
+    pub fn lock(&mut self, reason: String, locked_by: String, unlock_at: Option<DateTime<Utc>>) {
+        self.lock = Some(ProjectLock {
+            reason,
+            locked_by,
+            locked_at: Utc::now(),
+            unlock_at,
+        });
+        self.updated_at = Utc::now();
+    }
+
+    /// Lifts the project's lock.
+    pub fn unlock(&mut self) {
+        self.lock = None;
+        self.updated_at = Utc::now();
+    }
+
+    /// Returns the project's lock if it still holds.
+    pub fn active_lock(&self) -> Option<&ProjectLock> {
+        self.lock.as_ref().filter(|lock| lock.holds_at(Utc::now()))
+    }
+
+    /// Checks that someone with `role` may change the project's data: any
+    /// editor while it is unlocked, but only admins while it is locked.
+    pub fn check_unlocked(&self, role: Role) -> Result<(), DomainError> {
+        match self.active_lock() {
+            Some(lock) if !role.has_at_least(&Role::Admin) => Err(DomainError::ProjectLocked {
+                project: self.code.clone(),
+                reason: lock.reason.clone(),
+                unlock_at: lock.unlock_at,
+            }),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -220,5 +287,32 @@ mod tests {
         let progress = project.progress_percent().unwrap();
         assert!((progress - 50.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_lock_allows_only_admins_until_it_lapses() {
+        let mut project = Project::new(
+            1,
+            "PROJ001".to_string(),
+            "Test Project".to_string(),
+            "admin".to_string(),
+        );
+        assert!(project.check_unlocked(Role::Technician).is_ok());
+
+        project.lock("PI review".to_string(), "manager".to_string(), None);
+        assert!(matches!(
+            project.check_unlocked(Role::LabManager),
+            Err(DomainError::ProjectLocked { ref reason, .. }) if reason == "PI review"
+        ));
+        assert!(project.check_unlocked(Role::Admin).is_ok());
+
+        let lapsed = Utc::now() - chrono::Duration::minutes(1);
+        project.lock("PI review".to_string(), "manager".to_string(), Some(lapsed));
+        assert!(project.active_lock().is_none());
+        assert!(project.check_unlocked(Role::Technician).is_ok());
+
+        project.lock("PI review".to_string(), "manager".to_string(), None);
+        project.unlock();
+        assert!(project.check_unlocked(Role::Technician).is_ok());
+    }
 }
 
@@ -0,0 +1,32 @@
+//! Project member entity - a user allowed to see and change a project's
+//! samples, libraries and pools.
+//!
+//! Projects are the access-control boundary: where membership is enforced,
+//! users only reach the data of projects they belong to. Admins reach every
+//! project without being members.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::EntityId;
+
+/// A user's membership of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectMember {
+    pub project_id: EntityId,
+    pub user_id: EntityId,
+    pub added_by: String,
+    pub added_at: DateTime<Utc>,
+}
+
+impl ProjectMember {
+    /// Makes a user a member of a project.
+    pub fn new(project_id: EntityId, user_id: EntityId, added_by: String) -> Self {
+        Self {
+            project_id,
+            user_id,
+            added_by,
+            added_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! QC record entity - a QC measurement kept against a sample, library or
+//! pool.
+//!
+//! An entity may be measured many times, with different tests; each
+//! measurement is kept as it was taken, newest last.
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+use crate::value_objects::QcResult;
+
+use super::EntityId;
+
+/// The kind of entity a QC measurement was taken of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QcTarget {
+    Sample,
+    Library,
+    Pool,
+}
+
+impl std::fmt::Display for QcTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sample => write!(f, "sample"),
+            Self::Library => write!(f, "library"),
+            Self::Pool => write!(f, "pool"),
+        }
+    }
+}
+
+impl std::str::FromStr for QcTarget {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sample" => Ok(Self::Sample),
+            "library" => Ok(Self::Library),
+            "pool" => Ok(Self::Pool),
+            other => Err(DomainError::Validation(format!(
+                "Unknown QC target: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// A stored QC measurement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QcRecord {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Kind of entity measured
+    pub target: QcTarget,
+    /// ID of the entity measured
+    pub entity_id: EntityId,
+    /// The measurement
+    pub result: QcResult,
+}
+
+impl QcRecord {
+    /// Creates a new, unsaved record of a measurement.
+    pub fn new(target: QcTarget, entity_id: EntityId, result: QcResult) -> Self {
+        Self {
+            id: 0,
+            target,
+            entity_id,
+            result,
+        }
+    }
+}
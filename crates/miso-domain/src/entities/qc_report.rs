@@ -0,0 +1,124 @@
+//! QC report entities - external QC reports attached to runs.
+//!
+//! MultiQC aggregates the output of FastQC, alignment and duplication tools
+//! into a single report. The LIMS keeps a link to the report and a copy of
+//! the key per-sample figures so QC review can happen in one place.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::RunError;
+
+use super::EntityId;
+
+/// Key per-sample metrics extracted from a MultiQC summary.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SampleQcSummary {
+    /// Sample name as reported by MultiQC
+    pub sample_name: String,
+    /// Total number of sequences
+    pub total_sequences: Option<f64>,
+    /// Percentage GC content
+    pub percent_gc: Option<f64>,
+    /// Percentage of duplicate reads
+    pub percent_duplicates: Option<f64>,
+    /// Average sequence length
+    pub avg_sequence_length: Option<f64>,
+    /// Percentage of reads aligned to the reference
+    pub percent_aligned: Option<f64>,
+}
+
+impl SampleQcSummary {
+    /// Creates an empty summary for a sample.
+    pub fn new(sample_name: String) -> Self {
+        Self {
+            sample_name,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if no metrics were extracted for this sample.
+    pub fn is_empty(&self) -> bool {
+        self.total_sequences.is_none()
+            && self.percent_gc.is_none()
+            && self.percent_duplicates.is_none()
+            && self.avg_sequence_length.is_none()
+            && self.percent_aligned.is_none()
+    }
+}
+
+/// A MultiQC report attached to a sequencing run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunQcReport {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The run the report belongs to
+    pub run_id: EntityId,
+    /// URL where the HTML report can be viewed
+    pub report_url: Option<String>,
+    /// Path or name of an uploaded report file
+    pub file_name: Option<String>,
+    /// MultiQC version that generated the report, if known
+    pub multiqc_version: Option<String>,
+    /// Per-sample metrics extracted from the summary
+    pub samples: Vec<SampleQcSummary>,
+    /// Who attached the report
+    pub attached_by: String,
+    /// When the report was attached
+    pub attached_at: DateTime<Utc>,
+}
+
+impl RunQcReport {
+    /// Creates a new report link. At least one of URL or file is required.
+    pub fn new(
+        run_id: EntityId,
+        report_url: Option<String>,
+        file_name: Option<String>,
+        attached_by: String,
+    ) -> Result<Self, RunError> {
+        let report_url = report_url.filter(|u| !u.trim().is_empty());
+        let file_name = file_name.filter(|f| !f.trim().is_empty());
+
+        if report_url.is_none() && file_name.is_none() {
+            return Err(RunError::InvalidParameters(
+                "A QC report requires a URL or a file".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: 0,
+            run_id,
+            report_url,
+            file_name,
+            multiqc_version: None,
+            samples: Vec::new(),
+            attached_by,
+            attached_at: Utc::now(),
+        })
+    }
+
+    /// Finds the summary for a sample by name.
+    pub fn sample(&self, sample_name: &str) -> Option<&SampleQcSummary> {
+        self.samples.iter().find(|s| s.sample_name == sample_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_requires_location() {
+        assert!(RunQcReport::new(1, None, Some("  ".to_string()), "admin".to_string()).is_err());
+
+        let report = RunQcReport::new(
+            1,
+            Some("https://qc.example.org/run1/multiqc_report.html".to_string()),
+            None,
+            "admin".to_string(),
+        )
+        .unwrap();
+        assert_eq!(report.run_id, 1);
+        assert!(report.samples.is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+//! Reagent lot entity - kit inventory.
+//!
+//! Each lot of a kit the lab holds is recorded with its expiry date and how
+//! many units (preps or reactions) are left, so lots can be used before
+//! they expire and kits reordered before they run out.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// A lot of a kit held in stock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReagentLot {
+    pub id: EntityId,
+    /// Kit name, e.g. "DNeasy Blood & Tissue"
+    pub kit: String,
+    /// Manufacturer's lot number, unique per kit
+    pub lot_number: String,
+    /// Last day the lot may be used
+    pub expires_on: NaiveDate,
+    /// Units left
+    pub quantity: i32,
+    /// Units at or below which the kit should be reordered
+    pub reorder_level: i32,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReagentLot {
+    /// Records a lot received into stock.
+    pub fn new(
+        kit: &str,
+        lot_number: &str,
+        expires_on: NaiveDate,
+        quantity: i32,
+        reorder_level: i32,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let (kit, lot_number) = (kit.trim(), lot_number.trim());
+        if kit.is_empty() || lot_number.is_empty() {
+            return Err(DomainError::Validation(
+                "A reagent lot needs a kit and a lot number".to_string(),
+            ));
+        }
+        if quantity < 0 || reorder_level < 0 {
+            return Err(DomainError::Validation(
+                "Reagent quantities can't be negative".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        Ok(Self {
+            id: 0,
+            kit: kit.to_string(),
+            lot_number: lot_number.to_string(),
+            expires_on,
+            quantity,
+            reorder_level,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Returns true if the lot is past its expiry date on `today`.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires_on < today
+    }
+
+    /// Returns the days left before the lot expires, negative once it has.
+    pub fn days_left(&self, today: NaiveDate) -> i64 {
+        (self.expires_on - today).num_days()
+    }
+
+    /// Returns true if any of the lot is left.
+    pub fn in_stock(&self) -> bool {
+        self.quantity > 0
+    }
+
+    /// Sets the units left.
+    pub fn set_quantity(&mut self, quantity: i32, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if quantity < 0 {
+            return Err(DomainError::Validation(
+                "Reagent quantities can't be negative".to_string(),
+            ));
+        }
+        self.quantity = quantity;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_is_inclusive_of_last_day() {
+        let expires_on = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        assert!(ReagentLot::new("DNeasy", " ", expires_on, 10, 2, "tech".to_string()).is_err());
+        assert!(ReagentLot::new("DNeasy", "L1", expires_on, -1, 2, "tech".to_string()).is_err());
+
+        let mut lot = ReagentLot::new(" DNeasy ", "L1", expires_on, 10, 2, "tech".to_string())
+            .unwrap();
+        assert_eq!(lot.kit, "DNeasy");
+        assert!(!lot.is_expired(expires_on));
+        assert!(lot.is_expired(expires_on.succ_opt().unwrap()));
+        assert_eq!(lot.days_left(NaiveDate::from_ymd_opt(2024, 6, 20).unwrap()), 10);
+
+        assert!(lot.set_quantity(-3, Utc::now()).is_err());
+        lot.set_quantity(0, Utc::now()).unwrap();
+        assert!(!lot.in_stock());
+    }
+}
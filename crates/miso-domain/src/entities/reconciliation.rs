@@ -0,0 +1,101 @@
+//! Location reconciliation report entity.
+//!
+//! A box records what sits in each of its positions, and each storable
+//! entity records where it believes it lives. The two should always agree,
+//! but manual database edits and failed transactions let them drift. A
+//! reconciliation report captures every disagreement found in one pass.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::value_objects::BoxPosition;
+
+use super::{EntityId, StorableItem};
+
+/// Where a storable entity records itself as being stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedLocation {
+    /// The item
+    pub item: StorableItem,
+    /// The box the item records, if any
+    pub box_id: Option<EntityId>,
+    /// The position the item records within that box
+    pub position: Option<BoxPosition>,
+}
+
+/// The kind of disagreement found between a box and an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// The item records a box, but that box doesn't hold it
+    MissingFromBox,
+    /// A box holds the item, but the item records no location
+    UnrecordedLocation,
+    /// A box holds the item, but the item records a different box
+    WrongBox,
+    /// The item is in the recorded box, but at a different position
+    WrongPosition,
+    /// The item occupies more than one position
+    MultiplePlacements,
+    /// A box holds an item that doesn't exist
+    UnknownItem,
+}
+
+impl std::fmt::Display for DiscrepancyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFromBox => write!(f, "missing from box"),
+            Self::UnrecordedLocation => write!(f, "location not recorded"),
+            Self::WrongBox => write!(f, "wrong box"),
+            Self::WrongPosition => write!(f, "wrong position"),
+            Self::MultiplePlacements => write!(f, "multiple placements"),
+            Self::UnknownItem => write!(f, "unknown item"),
+        }
+    }
+}
+
+/// A single mismatch between box contents and an item's recorded location.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocationDiscrepancy {
+    /// The item concerned
+    pub item: StorableItem,
+    /// What is wrong
+    pub kind: DiscrepancyKind,
+    /// Box actually holding the item, if any
+    pub box_id: Option<EntityId>,
+    /// Position the box holds the item at, e.g. "A1"
+    pub position: Option<String>,
+    /// Box the item records
+    pub recorded_box_id: Option<EntityId>,
+    /// Position the item records
+    pub recorded_position: Option<String>,
+}
+
+/// The outcome of one reconciliation pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Number of boxes inspected
+    pub boxes_checked: u32,
+    /// Number of item locations inspected
+    pub items_checked: u32,
+    /// Mismatches found
+    pub discrepancies: Vec<LocationDiscrepancy>,
+    /// When the pass started
+    pub started_at: DateTime<Utc>,
+    /// When the pass finished
+    pub completed_at: DateTime<Utc>,
+}
+
+impl ReconciliationReport {
+    /// Returns true if no discrepancies were found.
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    /// Counts discrepancies of the given kind.
+    pub fn count_of(&self, kind: DiscrepancyKind) -> usize {
+        self.discrepancies.iter().filter(|d| d.kind == kind).count()
+    }
+}
@@ -0,0 +1,125 @@
+//! Reference genome entity - the registry of genome builds data is aligned
+//! to.
+//!
+//! Projects name the reference genome their data is analysed against, and a
+//! library may name a different one for its own assay (a mouse spike-in in
+//! a human project, say). Pipelines ask MISO which build to use rather than
+//! guessing from a free-text field.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// A registered reference genome build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceGenome {
+    /// Unique identifier
+    pub id: EntityId,
+    /// Build name pipelines know it by (e.g. "GRCh38", "mm39"); unique
+    pub name: String,
+    /// Species (e.g. "Homo sapiens")
+    pub species: String,
+    /// Where the FASTA is, for custom builds (e.g. "s3://refs/custom.fa")
+    pub fasta_uri: Option<String>,
+    /// Description
+    pub description: Option<String>,
+    /// Who registered the genome
+    pub created_by: String,
+    /// When the genome was registered
+    pub created_at: DateTime<Utc>,
+    /// When the genome was last modified
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReferenceGenome {
+    /// Registers a new reference genome.
+    pub fn new(
+        name: String,
+        species: String,
+        fasta_uri: Option<String>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        let genome = Self {
+            id: 0,
+            name: name.trim().to_string(),
+            species: species.trim().to_string(),
+            fasta_uri,
+            description: None,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        };
+        genome.validate()?;
+        Ok(genome)
+    }
+
+    /// Checks that the genome is well formed.
+    ///
+    /// A FASTA location must be a URI with a scheme, such as `s3://`,
+    /// `https://` or `file://`, so pipelines can tell how to fetch it.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.is_empty() {
+            return Err(DomainError::Validation(
+                "Reference genome name must not be empty".to_string(),
+            ));
+        }
+        if self.name.contains(char::is_whitespace) {
+            return Err(DomainError::Validation(format!(
+                "Reference genome name '{}' must not contain spaces",
+                self.name
+            )));
+        }
+        if self.species.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Reference genome {} must name a species",
+                self.name
+            )));
+        }
+        if let Some(uri) = &self.fasta_uri {
+            let has_scheme = uri.split_once("://").is_some_and(|(scheme, rest)| {
+                !scheme.is_empty()
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                    && !rest.is_empty()
+            });
+            if !has_scheme {
+                return Err(DomainError::Validation(format!(
+                    "FASTA location '{}' of reference genome {} is not a URI",
+                    uri, self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genome(name: &str, fasta_uri: Option<&str>) -> Result<ReferenceGenome, DomainError> {
+        ReferenceGenome::new(
+            name.to_string(),
+            "Homo sapiens".to_string(),
+            fasta_uri.map(str::to_string),
+            "admin".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_reference_genome_validation() {
+        assert_eq!(genome(" GRCh38 ", None).unwrap().name, "GRCh38");
+        assert!(genome("hg38-custom", Some("s3://refs/hg38-custom.fa")).is_ok());
+        assert!(genome("hg38-custom", Some("file:///refs/hg38.fa")).is_ok());
+
+        assert!(genome("", None).is_err());
+        assert!(genome("GRCh38 primary", None).is_err());
+        assert!(genome("hg38-custom", Some("/refs/hg38.fa")).is_err());
+        assert!(genome("hg38-custom", Some("s3://")).is_err());
+    }
+}
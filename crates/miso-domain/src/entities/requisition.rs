@@ -0,0 +1,212 @@
+//! Requisition entity - an external order for work on samples.
+//!
+//! A requisition records the order a clinic or collaborator placed, under
+//! their own order ID, and the assay they asked for. Samples received for
+//! the order are attached to it. Work can be paused while the order is on
+//! hold, or stopped for good if it is cancelled.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// An external order and the samples received for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Requisition {
+    pub id: EntityId,
+    /// The order's ID in the ordering system; unique
+    pub alias: String,
+    /// Assay the order asks for, e.g. "WGS 30X"
+    pub assay: String,
+    /// Samples attached to the order, in the order attached
+    pub sample_ids: Vec<EntityId>,
+    /// Whether work on the order has been stopped for good
+    pub stopped: bool,
+    /// Why work was stopped; set when stopped
+    pub stop_reason: Option<String>,
+    /// Whether work on the order is on hold
+    pub paused: bool,
+    /// Why work is on hold; set while paused
+    pub pause_reason: Option<String>,
+    /// When work was put on hold; set while paused
+    pub paused_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Requisition {
+    /// Creates a requisition with no samples. The order ID and assay are
+    /// required.
+    pub fn new(alias: &str, assay: &str, created_by: String) -> Result<Self, DomainError> {
+        let alias = alias.trim();
+        let assay = assay.trim();
+        if alias.is_empty() || assay.is_empty() {
+            return Err(DomainError::Validation(
+                "A requisition needs an order ID and an assay".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        Ok(Self {
+            id: 0,
+            alias: alias.to_string(),
+            assay: assay.to_string(),
+            sample_ids: Vec::new(),
+            stopped: false,
+            stop_reason: None,
+            paused: false,
+            pause_reason: None,
+            paused_at: None,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Returns true if work on the order can go ahead.
+    pub fn is_active(&self) -> bool {
+        !self.stopped && !self.paused
+    }
+
+    /// Attaches a sample, returning false if it was already attached.
+    /// Samples can't be attached to a stopped requisition.
+    pub fn attach(&mut self, sample_id: EntityId, now: DateTime<Utc>) -> Result<bool, DomainError> {
+        if self.stopped {
+            return Err(DomainError::Validation(format!(
+                "Requisition {} has been stopped",
+                self.alias
+            )));
+        }
+        if self.sample_ids.contains(&sample_id) {
+            return Ok(false);
+        }
+        self.sample_ids.push(sample_id);
+        self.updated_at = now;
+        Ok(true)
+    }
+
+    /// Detaches a sample, returning false if it wasn't attached.
+    pub fn detach(&mut self, sample_id: EntityId, now: DateTime<Utc>) -> bool {
+        let count = self.sample_ids.len();
+        self.sample_ids.retain(|&id| id != sample_id);
+        if self.sample_ids.len() == count {
+            return false;
+        }
+        self.updated_at = now;
+        true
+    }
+
+    /// Puts work on the order on hold.
+    pub fn pause(&mut self, reason: &str, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.check_not_stopped()?;
+        if self.paused {
+            return Err(DomainError::Validation(format!(
+                "Requisition {} is already paused",
+                self.alias
+            )));
+        }
+        self.pause_reason = Some(required_reason(reason, "pausing")?);
+        self.paused = true;
+        self.paused_at = Some(now);
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Takes work on the order off hold.
+    pub fn resume(&mut self, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.check_not_stopped()?;
+        if !self.paused {
+            return Err(DomainError::Validation(format!(
+                "Requisition {} is not paused",
+                self.alias
+            )));
+        }
+        self.paused = false;
+        self.pause_reason = None;
+        self.paused_at = None;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Stops work on the order for good, ending any pause.
+    pub fn stop(&mut self, reason: &str, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.check_not_stopped()?;
+        self.stop_reason = Some(required_reason(reason, "stopping")?);
+        self.stopped = true;
+        self.paused = false;
+        self.pause_reason = None;
+        self.paused_at = None;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    fn check_not_stopped(&self) -> Result<(), DomainError> {
+        if self.stopped {
+            return Err(DomainError::Validation(format!(
+                "Requisition {} has been stopped",
+                self.alias
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn required_reason(reason: &str, action: &str) -> Result<String, DomainError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(DomainError::Validation(format!(
+            "A reason is required for {} a requisition",
+            action
+        )));
+    }
+    Ok(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_are_attached_once() {
+        let now = Utc::now();
+        assert!(Requisition::new("ORD-1", " ", "tech".to_string()).is_err());
+
+        let mut requisition = Requisition::new(" ORD-1 ", "WGS 30X", "tech".to_string()).unwrap();
+        assert_eq!(requisition.alias, "ORD-1");
+        assert!(requisition.attach(4, now).unwrap());
+        assert!(requisition.attach(2, now).unwrap());
+        assert!(!requisition.attach(4, now).unwrap());
+        assert_eq!(requisition.sample_ids, vec![4, 2]);
+
+        assert!(requisition.detach(4, now));
+        assert!(!requisition.detach(4, now));
+        assert_eq!(requisition.sample_ids, vec![2]);
+    }
+
+    #[test]
+    fn test_pause_resume_and_stop() {
+        let now = Utc::now();
+        let mut requisition = Requisition::new("ORD-1", "WGS 30X", "tech".to_string()).unwrap();
+
+        assert!(requisition.resume(now).is_err());
+        assert!(requisition.pause(" ", now).is_err());
+        requisition.pause("Awaiting consent", now).unwrap();
+        assert!(!requisition.is_active());
+        assert!(requisition.pause("Again", now).is_err());
+        requisition.resume(now).unwrap();
+        assert!(requisition.is_active());
+        assert!(requisition.paused_at.is_none());
+
+        requisition.pause("Awaiting consent", now).unwrap();
+        assert!(requisition.stop("", now).is_err());
+        requisition.stop("Order cancelled", now).unwrap();
+        assert!(requisition.stopped);
+        assert!(!requisition.paused);
+        assert!(requisition.stop("Again", now).is_err());
+        assert!(requisition.pause("Hold", now).is_err());
+        assert!(requisition.attach(1, now).is_err());
+    }
+}
@@ -3,10 +3,13 @@
 //! A Run represents the execution of sequencing on a specific instrument,
 //! linking pools to the generated data.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::EntityId;
+use crate::errors::RunError;
+use crate::run_import::ExternalRun;
+
+use super::{EntityId, InstrumentModel};
 
 /// The status of a sequencing run.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -17,6 +20,8 @@ pub enum RunStatus {
     Unknown,
     /// Run is in progress
     Running,
+    /// Run was interrupted and is waiting to resume
+    Paused,
     /// Run completed successfully
     Completed,
     /// Run failed
@@ -34,7 +39,7 @@ pub enum RunStatus {
 impl RunStatus {
     /// Returns true if this run is still in progress.
     pub fn is_active(&self) -> bool {
-        matches!(self, Self::Running | Self::QcInProgress)
+        matches!(self, Self::Running | Self::Paused | Self::QcInProgress)
     }
 
     /// Returns true if this run is complete (regardless of pass/fail).
@@ -56,6 +61,7 @@ impl std::fmt::Display for RunStatus {
         match self {
             Self::Unknown => write!(f, "Unknown"),
             Self::Running => write!(f, "Running"),
+            Self::Paused => write!(f, "Paused"),
             Self::Completed => write!(f, "Completed"),
             Self::Failed => write!(f, "Failed"),
             Self::Stopped => write!(f, "Stopped"),
@@ -81,6 +87,8 @@ pub struct RunPartition {
     pub pass_filter_percent: Option<f64>,
     /// Q30 score (percentage of bases with Q >= 30)
     pub q30_percent: Option<f64>,
+    /// Bases passing filter, in gigabases
+    pub yield_gbp: Option<f64>,
     /// Notes about this partition
     pub notes: Option<String>,
 }
@@ -95,6 +103,7 @@ impl RunPartition {
             cluster_density: None,
             pass_filter_percent: None,
             q30_percent: None,
+            yield_gbp: None,
             notes: None,
         }
     }
@@ -118,6 +127,53 @@ impl RunPartition {
     }
 }
 
+/// A pool tube scanned while loading a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadScan {
+    /// Barcode as scanned
+    pub barcode: String,
+    /// Pool with the barcode, if there is one
+    pub pool_id: Option<EntityId>,
+    /// Lane the tube is being loaded on, if the instrument loads by lane
+    pub partition: Option<u8>,
+}
+
+/// A period during which a run was paused, e.g. by a power blip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInterruption {
+    /// Why the run was paused
+    pub cause: String,
+    /// When the run was paused
+    pub started_at: DateTime<Utc>,
+    /// When the run resumed; `None` while it is still paused
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Lanes affected by the interruption that need extra QC review
+    pub flagged_lanes: Vec<u8>,
+    /// Who recorded the interruption
+    pub recorded_by: String,
+}
+
+impl RunInterruption {
+    /// Returns how long the run was paused, counting an interruption that
+    /// hasn't ended up to `now`.
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.ended_at.unwrap_or(now) - self.started_at
+    }
+}
+
+/// A reviewer's QC decision on a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunQcSignOff {
+    /// Whether the run passed QC
+    pub passed: bool,
+    /// Who made the decision
+    pub reviewer: String,
+    /// Reviewer's comments; required when the run fails
+    pub note: Option<String>,
+    /// When the decision was recorded
+    pub signed_off_at: DateTime<Utc>,
+}
+
 /// A sequencing run.
 ///
 /// Runs are the execution of sequencing on a specific instrument,
@@ -134,6 +190,9 @@ pub struct Run {
     pub sequencer_id: EntityId,
     /// The container (flow cell) used
     pub container_barcode: Option<String>,
+    /// The model of that container, when it was assigned against the
+    /// instrument model's catalogue
+    pub container_model: Option<String>,
     /// Current status
     pub status: RunStatus,
     /// The partitions (lanes/cells) of this run
@@ -146,6 +205,19 @@ pub struct Run {
     pub started_at: Option<DateTime<Utc>>,
     /// When the run completed
     pub completed_at: Option<DateTime<Utc>>,
+    /// When the run was flagged as running longer than expected
+    pub overdue_since: Option<DateTime<Utc>>,
+    /// When the run is expected to finish, estimated when it starts and
+    /// refined as cycles complete
+    pub estimated_completion: Option<DateTime<Utc>>,
+    /// Sequencing cycles completed so far, where the instrument reports them
+    pub cycles_completed: Option<u32>,
+    /// When the run was last marked ready to load on its instrument
+    pub ready_to_load_at: Option<DateTime<Utc>>,
+    /// The latest QC decision on the run, if it has been reviewed
+    pub qc_sign_off: Option<RunQcSignOff>,
+    /// Times the run was paused, oldest first
+    pub interruptions: Vec<RunInterruption>,
     /// Number of read cycles (e.g., "2x150" for 150bp paired-end)
     pub read_length: Option<String>,
     /// Run description/notes
@@ -178,12 +250,19 @@ impl Run {
             alias: None,
             sequencer_id,
             container_barcode: None,
+            container_model: None,
             status: RunStatus::Unknown,
             partitions,
             data_path: None,
             output_path: None,
             started_at: None,
             completed_at: None,
+            overdue_since: None,
+            estimated_completion: None,
+            cycles_completed: None,
+            ready_to_load_at: None,
+            qc_sign_off: None,
+            interruptions: Vec::new(),
             read_length: None,
             description: None,
             created_by,
@@ -198,6 +277,164 @@ impl Run {
         self.updated_at = Utc::now();
     }
 
+    /// Assigns a container (flow cell) of the given model to the run,
+    /// provided the run's instrument model accepts it.
+    pub fn assign_container(
+        &mut self,
+        barcode: String,
+        container_model: &str,
+        instrument: &InstrumentModel,
+    ) -> Result<(), RunError> {
+        instrument.check_container(container_model)?;
+        self.set_container(barcode);
+        self.container_model = Some(container_model.to_string());
+        Ok(())
+    }
+
+    /// Marks the run as ready to load on its instrument.
+    ///
+    /// Only runs that haven't started can be loaded, and only once they
+    /// have a flow cell and at least one pool. Marking again, e.g. after
+    /// changing a pool, moves the time on.
+    pub fn mark_ready_to_load(&mut self, now: DateTime<Utc>) -> Result<(), RunError> {
+        if self.status != RunStatus::Unknown {
+            return Err(RunError::NotLoadable(
+                self.name.clone(),
+                self.status.to_string(),
+            ));
+        }
+        if self.container_barcode.is_none() {
+            return Err(RunError::InvalidParameters(
+                "a flow cell must be assigned before loading".to_string(),
+            ));
+        }
+        if self.partitions.iter().all(|p| p.pool_id.is_none()) {
+            return Err(RunError::InvalidParameters(
+                "a pool must be assigned before loading".to_string(),
+            ));
+        }
+
+        self.ready_to_load_at = Some(now);
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Checks the flow cell and pool tubes scanned at the instrument against
+    /// the run's plan before it is loaded.
+    ///
+    /// The flow cell must be the one registered to the run, every tube must
+    /// hold a pool planned for the run (for its lane, if one is given), and
+    /// every planned pool must be scanned. All mismatches are reported
+    /// together so they can be fixed in one go.
+    pub fn check_load(&self, container_barcode: &str, scans: &[LoadScan]) -> Result<(), RunError> {
+        if self.status != RunStatus::Unknown {
+            return Err(RunError::NotLoadable(
+                self.name.clone(),
+                self.status.to_string(),
+            ));
+        }
+
+        let mut mismatches = Vec::new();
+        let container_barcode = container_barcode.trim();
+        match &self.container_barcode {
+            None => mismatches.push("no flow cell is registered to the run".to_string()),
+            Some(registered) if registered != container_barcode => mismatches.push(format!(
+                "flow cell {} is not the registered {}",
+                container_barcode, registered
+            )),
+            Some(_) => {}
+        }
+
+        for scan in scans {
+            let Some(pool_id) = scan.pool_id else {
+                mismatches.push(format!("tube {} is not a known pool", scan.barcode));
+                continue;
+            };
+            match scan.partition {
+                Some(number) => match self.get_partition(number) {
+                    None => mismatches.push(format!("the run has no lane {}", number)),
+                    Some(partition) if partition.pool_id != Some(pool_id) => mismatches.push(
+                        format!("pool {} is not planned for lane {}", scan.barcode, number),
+                    ),
+                    Some(_) => {}
+                },
+                None if !self.pool_ids().contains(&pool_id) => mismatches.push(format!(
+                    "pool {} is not planned for the run",
+                    scan.barcode
+                )),
+                None => {}
+            }
+        }
+
+        let planned: Vec<_> = self
+            .partitions
+            .iter()
+            .filter_map(|p| p.pool_id.map(|pool_id| (p.partition_number, pool_id)))
+            .collect();
+        if planned.is_empty() {
+            mismatches.push("no pool is planned for the run".to_string());
+        }
+        for (number, pool_id) in planned {
+            let scanned = scans.iter().any(|scan| {
+                scan.pool_id == Some(pool_id) && scan.partition.is_none_or(|n| n == number)
+            });
+            if !scanned {
+                mismatches.push(format!("the pool planned for lane {} was not scanned", number));
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(RunError::LoadMismatch {
+                run: self.name.clone(),
+                mismatches,
+            })
+        }
+    }
+
+    /// Records what an instrument's cloud service reported about the run,
+    /// returning whether anything changed.
+    ///
+    /// Details already recorded are kept, and a finished run keeps its
+    /// status so that a reviewed run isn't reopened. Reported lane metrics
+    /// replace those recorded.
+    pub fn record_external(&mut self, external: &ExternalRun) -> bool {
+        let before = self.clone();
+
+        if self.container_barcode.is_none() {
+            self.container_barcode = external.flow_cell_barcode.clone();
+        }
+        if self.read_length.is_none() {
+            self.read_length = external.read_length.clone();
+        }
+        if self.started_at.is_none() {
+            self.started_at = external.started_at;
+        }
+        if !self.status.is_terminal() && external.status.is_terminal() {
+            self.status = external.status;
+            self.completed_at = external.completed_at.or(self.completed_at);
+            self.overdue_since = None;
+        }
+
+        for lane in &external.lanes {
+            let Some(partition) = self.get_partition_mut(lane.lane) else {
+                continue;
+            };
+            partition.cluster_density = lane.cluster_density.or(partition.cluster_density);
+            partition.pass_filter_percent =
+                lane.pass_filter_percent.or(partition.pass_filter_percent);
+            partition.q30_percent = lane.q30_percent.or(partition.q30_percent);
+            partition.yield_gbp = lane.yield_gbp.or(partition.yield_gbp);
+        }
+
+        if *self == before {
+            return false;
+        }
+        self.updated_at = Utc::now();
+        true
+    }
+
     /// Starts the run.
     pub fn start(&mut self) {
         self.status = RunStatus::Running;
@@ -209,16 +446,261 @@ impl Run {
     pub fn complete(&mut self) {
         self.status = RunStatus::Completed;
         self.completed_at = Some(Utc::now());
+        self.overdue_since = None;
         self.updated_at = Utc::now();
     }
 
-    /// Fails the run.
+    /// Fails the run. A paused run's interruption ends when it fails.
     pub fn fail(&mut self) {
+        let now = Utc::now();
+        if let Some(interruption) = self.open_interruption_mut() {
+            interruption.ended_at = Some(now);
+        }
         self.status = RunStatus::Failed;
-        self.completed_at = Some(Utc::now());
+        self.completed_at = Some(now);
+        self.overdue_since = None;
         self.updated_at = Utc::now();
     }
 
+    /// Returns true if the run has been flagged as overdue.
+    pub fn is_overdue(&self) -> bool {
+        self.overdue_since.is_some()
+    }
+
+    /// Returns true if the run has been sequencing for longer than
+    /// `expected` as of `now`.
+    ///
+    /// Only runs in the Running state can be overdue; a run with no start
+    /// time never is.
+    pub fn exceeds_duration(&self, expected: Duration, now: DateTime<Utc>) -> bool {
+        self.status == RunStatus::Running
+            && self
+                .started_at
+                .is_some_and(|started| now - started - self.downtime(now) > expected)
+    }
+
+    /// Flags the run as overdue. Returns false if it was already flagged.
+    pub fn flag_overdue(&mut self, now: DateTime<Utc>) -> bool {
+        if self.overdue_since.is_some() {
+            return false;
+        }
+        self.overdue_since = Some(now);
+        self.updated_at = now;
+        true
+    }
+
+    /// Pauses a running run, recording why and any lanes already known to
+    /// be affected.
+    pub fn pause(
+        &mut self,
+        cause: &str,
+        flagged_lanes: &[u8],
+        recorded_by: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), RunError> {
+        if self.status != RunStatus::Running {
+            return Err(RunError::InvalidParameters(format!(
+                "only a running run can be paused, and run {} is {}",
+                self.name, self.status
+            )));
+        }
+        let cause = cause.trim();
+        if cause.is_empty() {
+            return Err(RunError::InvalidParameters(
+                "an interruption needs a cause".to_string(),
+            ));
+        }
+        let flagged_lanes = self.checked_lanes(flagged_lanes)?;
+
+        self.interruptions.push(RunInterruption {
+            cause: cause.to_string(),
+            started_at: now,
+            ended_at: None,
+            flagged_lanes,
+            recorded_by,
+        });
+        self.status = RunStatus::Paused;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Resumes a paused run, ending its interruption. Lanes found to be
+    /// affected on resuming are flagged on the interruption too.
+    pub fn resume(&mut self, flagged_lanes: &[u8], now: DateTime<Utc>) -> Result<(), RunError> {
+        if self.status != RunStatus::Paused {
+            return Err(RunError::InvalidParameters(format!(
+                "only a paused run can be resumed, and run {} is {}",
+                self.name, self.status
+            )));
+        }
+        let flagged_lanes = self.checked_lanes(flagged_lanes)?;
+
+        if let Some(interruption) = self.open_interruption_mut() {
+            interruption.ended_at = Some(now);
+            interruption.flagged_lanes.extend(flagged_lanes);
+            interruption.flagged_lanes.sort_unstable();
+            interruption.flagged_lanes.dedup();
+        }
+        if let Some(eta) = self.estimated_completion.as_mut() {
+            if let Some(interruption) = self.interruptions.last() {
+                *eta += interruption.duration(now);
+            }
+        }
+        self.status = RunStatus::Running;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Returns the number of sequencing cycles in the run's read
+    /// configuration, e.g. 300 for "2x150", 150 for "150" or 252 for
+    /// "151+101".
+    pub fn total_cycles(&self) -> Option<u32> {
+        let mut total: u32 = 0;
+        for read in self.read_length.as_deref()?.split('+') {
+            let (reads, length) = match read.split_once('x') {
+                Some((reads, length)) => (reads.trim().parse::<u32>().ok()?, length),
+                None => (1, read),
+            };
+            let cycles = reads.checked_mul(length.trim().parse::<u32>().ok()?)?;
+            total = total.checked_add(cycles)?;
+        }
+        (total > 0).then_some(total)
+    }
+
+    /// Estimates when the run will finish from its start time and the
+    /// instrument model's throughput. Leaves the estimate unset if the
+    /// model gives no way to estimate it.
+    pub fn estimate_completion(&mut self, model: &InstrumentModel) {
+        let duration = model.estimate_run_duration(self.total_cycles());
+        self.estimated_completion = self
+            .started_at
+            .zip(duration)
+            .map(|(started, duration)| started + duration);
+    }
+
+    /// Records the number of cycles the instrument has completed and
+    /// re-estimates completion from the rate so far, not counting time
+    /// spent paused.
+    pub fn record_progress(&mut self, cycles: u32, now: DateTime<Utc>) -> Result<(), RunError> {
+        if self.status != RunStatus::Running {
+            return Err(RunError::InvalidParameters(format!(
+                "progress can only be recorded on a running run, and run {} is {}",
+                self.name, self.status
+            )));
+        }
+        if let Some(total) = self.total_cycles().filter(|&total| cycles > total) {
+            return Err(RunError::InvalidParameters(format!(
+                "run {} has only {} cycles, not {}",
+                self.name, total, cycles
+            )));
+        }
+        if let Some(previous) = self.cycles_completed.filter(|&previous| cycles < previous) {
+            return Err(RunError::InvalidParameters(format!(
+                "run {} has already completed {} cycles",
+                self.name, previous
+            )));
+        }
+
+        if let (Some(started), Some(total)) = (self.started_at, self.total_cycles()) {
+            let elapsed = now - started - self.downtime(now);
+            if cycles > 0 && elapsed > Duration::zero() {
+                let remaining = i32::try_from(total - cycles).unwrap_or(i32::MAX);
+                let per_cycle = elapsed / i32::try_from(cycles).unwrap_or(i32::MAX);
+                self.estimated_completion = Some(now + per_cycle * remaining);
+            }
+        }
+        self.cycles_completed = Some(cycles);
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Returns the total time the run has spent paused as of `now`.
+    pub fn downtime(&self, now: DateTime<Utc>) -> Duration {
+        self.interruptions
+            .iter()
+            .map(|i| i.duration(now))
+            .fold(Duration::zero(), |total, d| total + d)
+    }
+
+    /// Returns the lanes flagged by any interruption for extra QC review.
+    pub fn lanes_for_review(&self) -> Vec<u8> {
+        let mut lanes: Vec<u8> = self
+            .interruptions
+            .iter()
+            .flat_map(|i| i.flagged_lanes.iter().copied())
+            .collect();
+        lanes.sort_unstable();
+        lanes.dedup();
+        lanes
+    }
+
+    fn open_interruption_mut(&mut self) -> Option<&mut RunInterruption> {
+        self.interruptions
+            .last_mut()
+            .filter(|i| i.ended_at.is_none())
+    }
+
+    /// Checks that every lane exists on the run, returning them sorted.
+    fn checked_lanes(&self, lanes: &[u8]) -> Result<Vec<u8>, RunError> {
+        if let Some(missing) = lanes.iter().find(|&&l| self.get_partition(l).is_none()) {
+            return Err(RunError::InvalidParameters(format!(
+                "run {} has no lane {}",
+                self.name, missing
+            )));
+        }
+        let mut lanes = lanes.to_vec();
+        lanes.sort_unstable();
+        lanes.dedup();
+        Ok(lanes)
+    }
+
+    /// Records a reviewer's QC decision.
+    ///
+    /// Only completed runs, or runs already in QC, can be signed off.
+    /// Failing a run needs a note saying why. Signing off again replaces
+    /// the earlier decision.
+    pub fn sign_off_qc(
+        &mut self,
+        passed: bool,
+        reviewer: String,
+        note: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<(), RunError> {
+        if !matches!(
+            self.status,
+            RunStatus::Completed
+                | RunStatus::QcInProgress
+                | RunStatus::QcPassed
+                | RunStatus::QcFailed
+        ) {
+            return Err(RunError::NotReadyForQc(
+                self.name.clone(),
+                self.status.to_string(),
+            ));
+        }
+
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if !passed && note.is_none() {
+            return Err(RunError::InvalidParameters(
+                "a note is required when failing QC".to_string(),
+            ));
+        }
+
+        self.status = if passed {
+            RunStatus::QcPassed
+        } else {
+            RunStatus::QcFailed
+        };
+        self.qc_sign_off = Some(RunQcSignOff {
+            passed,
+            reviewer,
+            note,
+            signed_off_at: now,
+        });
+        self.updated_at = now;
+        Ok(())
+    }
+
     /// Gets a partition by number.
     pub fn get_partition(&self, number: u8) -> Option<&RunPartition> {
         self.partitions.iter().find(|p| p.partition_number == number)
@@ -261,6 +743,7 @@ impl Run {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::Platform;
 
     #[test]
     fn test_run_creation() {
@@ -287,6 +770,253 @@ mod tests {
         assert!(run.completed_at.is_some());
     }
 
+    #[test]
+    fn test_load_check_against_plan() {
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 2, "admin".to_string());
+        run.set_container("FC123".to_string());
+        run.get_partition_mut(1).unwrap().set_pool(10, 180.0);
+        run.get_partition_mut(2).unwrap().set_pool(20, 180.0);
+        let scan = |barcode: &str, pool_id, partition| LoadScan {
+            barcode: barcode.to_string(),
+            pool_id,
+            partition,
+        };
+
+        assert!(run
+            .check_load("FC123", &[scan("P10", Some(10), Some(1)), scan("P20", Some(20), None)])
+            .is_ok());
+
+        let Err(RunError::LoadMismatch { mismatches, .. }) = run.check_load(
+            "FC999",
+            &[scan("P20", Some(20), Some(1)), scan("X", None, None)],
+        ) else {
+            panic!("expected a mismatch");
+        };
+        assert_eq!(
+            mismatches,
+            vec![
+                "flow cell FC999 is not the registered FC123",
+                "pool P20 is not planned for lane 1",
+                "tube X is not a known pool",
+                "the pool planned for lane 1 was not scanned",
+                "the pool planned for lane 2 was not scanned",
+            ]
+        );
+
+        run.start();
+        assert!(matches!(
+            run.check_load("FC123", &[scan("P10", Some(10), None)]),
+            Err(RunError::NotLoadable(..))
+        ));
+    }
+
+    #[test]
+    fn test_overdue_detection() {
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
+        let expected = Duration::hours(44);
+        let now = Utc::now();
+
+        assert!(!run.exceeds_duration(expected, now));
+
+        run.start();
+        run.started_at = Some(now - Duration::hours(40));
+        assert!(!run.exceeds_duration(expected, now));
+
+        run.started_at = Some(now - Duration::hours(48));
+        assert!(run.exceeds_duration(expected, now));
+
+        assert!(run.flag_overdue(now));
+        assert!(!run.flag_overdue(now + Duration::hours(1)));
+        assert_eq!(run.overdue_since, Some(now));
+
+        run.complete();
+        assert!(!run.is_overdue());
+        assert!(!run.exceeds_duration(expected, now));
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let now = Utc::now();
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
+
+        assert!(run.pause("Power cut", &[], "tech".to_string(), now).is_err());
+
+        run.start();
+        run.started_at = Some(now - Duration::hours(46));
+        assert!(run.pause(" ", &[], "tech".to_string(), now).is_err());
+        assert!(run.pause("Power cut", &[5], "tech".to_string(), now).is_err());
+
+        run.pause("Power cut", &[2], "tech".to_string(), now - Duration::hours(3))
+            .unwrap();
+        assert_eq!(run.status, RunStatus::Paused);
+        assert!(run.status.is_active());
+        assert_eq!(run.downtime(now), Duration::hours(3));
+        assert!(run.resume(&[9], now).is_err());
+
+        run.resume(&[1, 2], now).unwrap();
+        assert_eq!(run.status, RunStatus::Running);
+        assert!(run.resume(&[], now).is_err());
+        assert_eq!(run.interruptions[0].ended_at, Some(now));
+        assert_eq!(run.lanes_for_review(), vec![1, 2]);
+
+        // Time spent paused doesn't count towards the expected duration
+        assert!(!run.exceeds_duration(Duration::hours(44), now));
+        assert_eq!(run.downtime(now + Duration::hours(1)), Duration::hours(3));
+
+        run.pause("Chiller fault", &[], "tech".to_string(), now).unwrap();
+        run.fail();
+        assert_eq!(run.status, RunStatus::Failed);
+        assert!(run.interruptions[1].ended_at.is_some());
+    }
+
+    #[test]
+    fn test_estimated_completion() {
+        let now = Utc::now();
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq 6000".to_string(), 4);
+        model.expected_run_hours = Some(44);
+
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
+        run.read_length = Some("2x150".to_string());
+        assert_eq!(run.total_cycles(), Some(300));
+        assert!(run.record_progress(10, now).is_err());
+
+        run.start();
+        run.started_at = Some(now - Duration::hours(10));
+        run.estimate_completion(&model);
+        assert_eq!(run.estimated_completion, Some(now + Duration::hours(34)));
+
+        // A per-cycle time takes precedence over the expected duration
+        model.seconds_per_cycle = Some(360);
+        run.estimate_completion(&model);
+        assert_eq!(run.estimated_completion, Some(now + Duration::hours(20)));
+
+        // Progress re-estimates from the observed rate: 2 minutes a cycle
+        assert!(run.record_progress(301, now).is_err());
+        run.record_progress(300 / 2, now - Duration::hours(5)).unwrap();
+        assert_eq!(run.estimated_completion, Some(now));
+        assert!(run.record_progress(100, now).is_err());
+
+        // Time spent paused pushes the estimate back
+        run.pause("Chiller fault", &[], "tech".to_string(), now).unwrap();
+        run.resume(&[], now + Duration::hours(2)).unwrap();
+        assert_eq!(run.estimated_completion, Some(now + Duration::hours(2)));
+
+        run.read_length = Some("151+101".to_string());
+        assert_eq!(run.total_cycles(), Some(252));
+        run.read_length = Some("long".to_string());
+        assert_eq!(run.total_cycles(), None);
+    }
+
+    #[test]
+    fn test_qc_sign_off() {
+        let now = Utc::now();
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
+
+        run.start();
+        assert!(matches!(
+            run.sign_off_qc(true, "reviewer".to_string(), None, now),
+            Err(RunError::NotReadyForQc(..))
+        ));
+
+        run.complete();
+        assert!(matches!(
+            run.sign_off_qc(false, "reviewer".to_string(), Some(" ".to_string()), now),
+            Err(RunError::InvalidParameters(_))
+        ));
+        assert!(run.sign_off_qc(true, "reviewer".to_string(), None, now).is_ok());
+        assert_eq!(run.status, RunStatus::QcPassed);
+
+        run.sign_off_qc(
+            false,
+            "manager".to_string(),
+            Some("Low Q30".to_string()),
+            now,
+        )
+        .unwrap();
+        assert_eq!(run.status, RunStatus::QcFailed);
+        let sign_off = run.qc_sign_off.as_ref().unwrap();
+        assert_eq!(sign_off.reviewer, "manager");
+        assert_eq!(sign_off.note.as_deref(), Some("Low Q30"));
+    }
+
+    #[test]
+    fn test_record_external() {
+        use crate::run_import::ExternalLaneMetrics;
+
+        let now = Utc::now();
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 2, "admin".to_string());
+        run.set_container("FC001".to_string());
+        let external = ExternalRun {
+            external_id: "123".to_string(),
+            name: "RUN001".to_string(),
+            instrument_serial: None,
+            instrument_name: None,
+            flow_cell_barcode: Some("FC999".to_string()),
+            status: RunStatus::Completed,
+            read_length: Some("2x151".to_string()),
+            started_at: Some(now - Duration::hours(40)),
+            completed_at: Some(now),
+            lanes: vec![
+                ExternalLaneMetrics {
+                    lane: 2,
+                    q30_percent: Some(91.5),
+                    yield_gbp: Some(120.0),
+                    ..Default::default()
+                },
+                ExternalLaneMetrics {
+                    lane: 3,
+                    q30_percent: Some(50.0),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        assert!(run.record_external(&external));
+        assert_eq!(run.container_barcode.as_deref(), Some("FC001"));
+        assert_eq!(run.read_length.as_deref(), Some("2x151"));
+        assert_eq!(run.status, RunStatus::Completed);
+        assert_eq!(run.completed_at, Some(now));
+        let lane = run.get_partition(2).unwrap();
+        assert_eq!(lane.q30_percent, Some(91.5));
+        assert_eq!(lane.yield_gbp, Some(120.0));
+        assert!(!run.record_external(&external));
+
+        run.sign_off_qc(true, "reviewer".to_string(), None, now)
+            .unwrap();
+        let failed = ExternalRun {
+            status: RunStatus::Failed,
+            ..external
+        };
+        assert!(!run.record_external(&failed));
+        assert_eq!(run.status, RunStatus::QcPassed);
+    }
+
+    #[test]
+    fn test_mark_ready_to_load() {
+        let now = Utc::now();
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 2, "admin".to_string());
+
+        assert!(matches!(
+            run.mark_ready_to_load(now),
+            Err(RunError::InvalidParameters(_))
+        ));
+        run.set_container("FC001".to_string());
+        assert!(matches!(
+            run.mark_ready_to_load(now),
+            Err(RunError::InvalidParameters(_))
+        ));
+
+        run.get_partition_mut(2).unwrap().set_pool(1, 250.0);
+        run.mark_ready_to_load(now).unwrap();
+        assert_eq!(run.ready_to_load_at, Some(now));
+
+        run.start();
+        assert!(matches!(
+            run.mark_ready_to_load(now),
+            Err(RunError::NotLoadable(..))
+        ));
+    }
+
     #[test]
     fn test_partition_metrics() {
         let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
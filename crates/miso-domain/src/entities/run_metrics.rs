@@ -0,0 +1,123 @@
+//! Run metrics - per-lane, per-library demultiplexing results.
+//!
+//! Demultiplexing pipelines run outside the LIMS and report back how many
+//! reads each library received on each partition of a run, along with
+//! quality figures used to decide whether the requested sequencing is done.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::RunError;
+
+use super::EntityId;
+
+/// Demultiplexing metrics for one library on one partition (lane) of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryRunMetrics {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The run these metrics belong to
+    pub run_id: EntityId,
+    /// Partition number (1-based lane/cell)
+    pub partition_number: u8,
+    /// The library the reads were assigned to
+    pub library_id: EntityId,
+    /// Number of reads (clusters passing filter) assigned to the library
+    pub reads: u64,
+    /// Total yield in bases
+    pub yield_bases: u64,
+    /// Percentage of bases with Q >= 30
+    pub q30_percent: f64,
+    /// Fraction of reads attributed to unexpected index combinations (0-100)
+    pub index_hopping_percent: Option<f64>,
+    /// Who (or which pipeline) submitted the metrics
+    pub submitted_by: String,
+    /// When the metrics were submitted
+    pub submitted_at: DateTime<Utc>,
+}
+
+impl LibraryRunMetrics {
+    /// Creates a new metrics record, validating the reported values.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        run_id: EntityId,
+        partition_number: u8,
+        library_id: EntityId,
+        reads: u64,
+        yield_bases: u64,
+        q30_percent: f64,
+        index_hopping_percent: Option<f64>,
+        submitted_by: String,
+    ) -> Result<Self, RunError> {
+        if partition_number == 0 {
+            return Err(RunError::InvalidParameters(
+                "Partition numbers start at 1".to_string(),
+            ));
+        }
+        Self::validate_percent("q30_percent", q30_percent)?;
+        if let Some(hopping) = index_hopping_percent {
+            Self::validate_percent("index_hopping_percent", hopping)?;
+        }
+
+        Ok(Self {
+            id: 0,
+            run_id,
+            partition_number,
+            library_id,
+            reads,
+            yield_bases,
+            q30_percent,
+            index_hopping_percent,
+            submitted_by,
+            submitted_at: Utc::now(),
+        })
+    }
+
+    fn validate_percent(field: &str, value: f64) -> Result<(), RunError> {
+        if value.is_nan() || !(0.0..=100.0).contains(&value) {
+            return Err(RunError::InvalidParameters(format!(
+                "{} must be between 0 and 100, got {}",
+                field, value
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns true if this record describes the same run/lane/library slot.
+    pub fn same_slot(&self, other: &Self) -> bool {
+        self.run_id == other.run_id
+            && self.partition_number == other.partition_number
+            && self.library_id == other.library_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_creation() {
+        let metrics = LibraryRunMetrics::new(
+            1,
+            2,
+            10,
+            1_000_000,
+            300_000_000,
+            92.5,
+            Some(0.4),
+            "demux".to_string(),
+        )
+        .unwrap();
+        assert_eq!(metrics.partition_number, 2);
+        assert_eq!(metrics.reads, 1_000_000);
+    }
+
+    #[test]
+    fn test_metrics_validation() {
+        assert!(LibraryRunMetrics::new(1, 0, 10, 1, 1, 90.0, None, "demux".to_string()).is_err());
+        assert!(LibraryRunMetrics::new(1, 1, 10, 1, 1, 101.0, None, "demux".to_string()).is_err());
+        assert!(
+            LibraryRunMetrics::new(1, 1, 10, 1, 1, 90.0, Some(-1.0), "demux".to_string()).is_err()
+        );
+    }
+}
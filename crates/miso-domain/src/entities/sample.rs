@@ -4,11 +4,13 @@
 //! - **Plain Sample Mode**: Flat hierarchy (Sample -> Library -> Pool)
 //! - **Detailed Sample Mode**: Deep hierarchy (Identity -> Tissue -> Stock -> Aliquot)
 
-use crate::value_objects::{Barcode, Concentration, QcStatus, Volume};
+use std::collections::BTreeMap;
+
+use crate::value_objects::{Barcode, Concentration, Mass, QcStatus, Volume};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::EntityId;
+use super::{ControlType, EntityId};
 
 /// The class/type of a sample in the hierarchy.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -112,6 +114,12 @@ pub struct DetailedSampleData {
     pub analyte_type: Option<String>,
     /// Purpose of the sample
     pub purpose: Option<String>,
+    /// Whether the sample was made by pooling several identities; it then
+    /// has no parent, and its identities are its [`SampleComposition`]
+    ///
+    /// [`SampleComposition`]: super::SampleComposition
+    #[serde(default)]
+    pub pooled: bool,
 }
 
 /// Polymorphic container for sample-type-specific data.
@@ -168,6 +176,8 @@ pub struct Sample {
     pub details: SampleDetails,
     /// Current volume (if applicable)
     pub volume: Option<Volume>,
+    /// Current mass of nucleic acid (if applicable)
+    pub mass: Option<Mass>,
     /// Current concentration (if applicable)
     pub concentration: Option<Concentration>,
     /// QC status
@@ -182,6 +192,16 @@ pub struct Sample {
     pub updated_at: DateTime<Utc>,
     /// Is this sample archived/discarded?
     pub archived: bool,
+    /// Optimistic concurrency version, incremented on every update
+    pub version: i32,
+    /// Values of custom attributes, by attribute key
+    pub attributes: BTreeMap<String, String>,
+    /// Kind of control, if the sample is one rather than project material
+    #[serde(default)]
+    pub control_type: Option<ControlType>,
+    /// The subproject of its project the sample is assigned to, if any
+    #[serde(default)]
+    pub subproject_id: Option<EntityId>,
 }
 
 impl Sample {
@@ -206,6 +226,7 @@ impl Sample {
                 sample_type: None,
             }),
             volume: None,
+            mass: None,
             concentration: None,
             qc_status: QcStatus::NotReady,
             received_at: Some(now),
@@ -213,9 +234,70 @@ impl Sample {
             created_at: now,
             updated_at: now,
             archived: false,
+            version: 1,
+            attributes: BTreeMap::new(),
+            control_type: None,
+            subproject_id: None,
         }
     }
 
+    /// Creates a new identity, the root of a detailed sample hierarchy.
+    pub fn new_identity(
+        id: EntityId,
+        name: String,
+        barcode: Barcode,
+        project_id: EntityId,
+        external_name: String,
+        created_by: String,
+    ) -> Self {
+        let mut sample = Self::new_plain(id, name, barcode, project_id, String::new(), created_by);
+        sample.details = SampleDetails::Detailed(DetailedSampleData {
+            parent_id: None,
+            sample_class: SampleClass::Identity,
+            external_name: Some(external_name),
+            tissue_origin: None,
+            tissue_type: None,
+            time_point: None,
+            group_id: None,
+            group_description: None,
+            passage: None,
+            analyte_type: None,
+            purpose: None,
+            pooled: false,
+        });
+        sample
+    }
+
+    /// Creates a tissue made by pooling several identities, such as an
+    /// environmental or pooled screening specimen. It has no parent; its
+    /// identities are recorded as a [`SampleComposition`].
+    ///
+    /// [`SampleComposition`]: super::SampleComposition
+    pub fn new_pooled(
+        id: EntityId,
+        name: String,
+        barcode: Barcode,
+        project_id: EntityId,
+        created_by: String,
+    ) -> Self {
+        let mut sample = Self::new_plain(id, name, barcode, project_id, String::new(), created_by);
+        sample.details = SampleDetails::Detailed(DetailedSampleData {
+            parent_id: None,
+            sample_class: SampleClass::Tissue,
+            external_name: None,
+            tissue_origin: None,
+            tissue_type: None,
+            time_point: None,
+            group_id: None,
+            group_description: None,
+            passage: None,
+            analyte_type: None,
+            purpose: None,
+            pooled: true,
+        });
+        sample
+    }
+
     /// Returns the sample class.
     pub fn sample_class(&self) -> SampleClass {
         self.details.sample_class()
@@ -236,18 +318,54 @@ impl Sample {
         self.details.parent_id()
     }
 
+    /// Returns true if the sample is a control rather than project material.
+    pub fn is_control(&self) -> bool {
+        self.control_type.is_some()
+    }
+
+    /// Returns the nucleic acid the sample holds, in ng: its mass, or else
+    /// its mass concentration times its volume.
+    pub fn yield_ng(&self) -> Option<f64> {
+        if let Some(mass) = self.mass {
+            return Some(mass.as_nanograms());
+        }
+        let ng_per_ul = self.concentration?.as_ng_per_ul()?;
+        Some(ng_per_ul * self.volume?.as_microliters())
+    }
+
+    /// Returns true if the sample was made by pooling several identities.
+    pub fn is_pooled(&self) -> bool {
+        matches!(&self.details, SampleDetails::Detailed(d) if d.pooled)
+    }
+
     /// Archives this sample (marks as discarded/unavailable).
     pub fn archive(&mut self) {
         self.archived = true;
         self.updated_at = Utc::now();
     }
 
+    /// Takes this sample back out of the archive.
+    pub fn unarchive(&mut self) {
+        self.archived = false;
+        self.updated_at = Utc::now();
+    }
+
     /// Updates the QC status.
     pub fn set_qc_status(&mut self, status: QcStatus) {
         self.qc_status = status;
         self.updated_at = Utc::now();
     }
 
+    /// Removes details that could identify the participant the sample came
+    /// from: its external name and description.
+    pub fn erase_identifying_details(&mut self) {
+        if let SampleDetails::Detailed(details) = &mut self.details {
+            details.external_name = None;
+        }
+        self.description = None;
+        self.updated_at = Utc::now();
+    }
+
     /// Withdraws volume from this sample.
     ///
     /// Returns `Ok(())` if successful, or an error if insufficient volume.
@@ -265,6 +383,46 @@ impl Sample {
             None => Err("Sample has no tracked volume"),
         }
     }
+
+    /// Withdraws a mass from this sample, along with the volume holding it
+    /// at the current concentration.
+    ///
+    /// Returns `Ok(())` if successful, or an error if there isn't enough
+    /// mass or volume left; nothing is withdrawn then.
+    pub fn withdraw_mass(&mut self, amount: Mass) -> Result<(), &'static str> {
+        let ng_per_ul = self.concentration.and_then(|c| c.as_ng_per_ul());
+        withdraw_by_mass(&mut self.mass, &mut self.volume, ng_per_ul, amount)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+/// Withdraws a mass, and the volume holding it at `ng_per_ul`, from
+/// whichever of the two are tracked.
+fn withdraw_by_mass(
+    mass: &mut Option<Mass>,
+    volume: &mut Option<Volume>,
+    ng_per_ul: Option<f64>,
+    amount: Mass,
+) -> Result<(), &'static str> {
+    if mass.is_none() && volume.is_none() {
+        return Err("No tracked mass or volume");
+    }
+    let withdrawn = amount
+        .volume_at(ng_per_ul.ok_or("No mass concentration to withdraw by")?)
+        .ok_or("Concentration is zero")?;
+
+    let remaining_mass = match mass {
+        Some(m) => Some(m.subtract(amount).ok_or("Insufficient mass")?),
+        None => None,
+    };
+    let remaining_volume = match volume {
+        Some(v) => Some(v.subtract(withdrawn).ok_or("Insufficient volume")?),
+        None => None,
+    };
+    *mass = remaining_mass;
+    *volume = remaining_volume;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -316,5 +474,75 @@ mod tests {
         sample.archive();
         assert!(!sample.can_create_library());
     }
-}
 
+    #[test]
+    fn test_erase_identifying_details() {
+        let mut identity = Sample::new_identity(
+            1,
+            "PAT_0001".to_string(),
+            Barcode::new("SAM-001").unwrap(),
+            1,
+            "MRN-1".to_string(),
+            "admin".to_string(),
+        );
+        identity.description = Some("Referred by Dr Smith".to_string());
+
+        identity.erase_identifying_details();
+
+        assert_eq!(identity.description, None);
+        let SampleDetails::Detailed(details) = &identity.details else {
+            panic!("identity is not detailed");
+        };
+        assert_eq!(details.external_name, None);
+        assert_eq!(identity.name, "PAT_0001");
+    }
+
+    #[test]
+    fn test_withdraw_mass_takes_its_volume() {
+        let mut sample = Sample::new_plain(
+            1,
+            "SAM001".to_string(),
+            Barcode::new("SAM-001").unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        );
+        sample.volume = Some(Volume::microliters(40.0));
+        sample.mass = Some(Mass::micrograms(1.0));
+        assert_eq!(
+            sample.withdraw_mass(Mass::nanograms(200.0)),
+            Err("No mass concentration to withdraw by")
+        );
+
+        // 200 ng at 25 ng/µL is 8 µL
+        sample.concentration = Some(Concentration::ng_per_ul(25.0));
+        sample.withdraw_mass(Mass::nanograms(200.0)).unwrap();
+        assert_eq!(sample.mass.unwrap().as_nanograms(), 800.0);
+        assert_eq!(sample.volume.unwrap().as_microliters(), 32.0);
+
+        // Nothing is withdrawn if either runs short
+        assert_eq!(sample.withdraw_mass(Mass::nanograms(900.0)), Err("Insufficient mass"));
+        sample.mass = None;
+        assert_eq!(sample.withdraw_mass(Mass::nanograms(900.0)), Err("Insufficient volume"));
+        assert_eq!(sample.volume.unwrap().as_microliters(), 32.0);
+    }
+
+    #[test]
+    fn test_yield_is_mass_or_concentration_times_volume() {
+        let mut sample = Sample::new_plain(
+            1,
+            "SAM001".to_string(),
+            Barcode::new("SAM-001").unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        );
+        sample.concentration = Some(Concentration::ng_per_ul(25.0));
+        assert_eq!(sample.yield_ng(), None);
+
+        sample.volume = Some(Volume::microliters(40.0));
+        assert_eq!(sample.yield_ng(), Some(1000.0));
+        sample.mass = Some(Mass::micrograms(1.2));
+        assert_eq!(sample.yield_ng(), Some(1200.0));
+    }
+}
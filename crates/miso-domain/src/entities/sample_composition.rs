@@ -0,0 +1,110 @@
+//! Sample composition - the identities pooled into one physical sample.
+//!
+//! Some assays, such as environmental or pooled screening, pool several
+//! identities into one tissue before library prep. Such a sample has no
+//! single parent; the identities it was made from are its composition.
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::{EntityId, Sample, SampleClass};
+
+/// The identities a pooled sample was made from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleComposition {
+    /// The pooled sample
+    pub sample_id: EntityId,
+    /// Identities pooled into it, in the order given
+    pub identity_ids: Vec<EntityId>,
+}
+
+impl SampleComposition {
+    /// Records the identities `pooled` was made from.
+    ///
+    /// The sample must be pooled and not linked to a single identity, and
+    /// at least two distinct identities are needed. Identities listed twice
+    /// are kept once.
+    pub fn new(pooled: &Sample, identities: &[Sample]) -> Result<Self, DomainError> {
+        if !pooled.is_pooled() {
+            return Err(DomainError::Validation(format!(
+                "Sample {} is not a pooled sample",
+                pooled.name
+            )));
+        }
+        if let Some(parent_id) = pooled.parent_id() {
+            return Err(DomainError::Validation(format!(
+                "Pooled sample {} can't be linked to a single identity, but has parent {}",
+                pooled.name, parent_id
+            )));
+        }
+
+        let mut identity_ids: Vec<EntityId> = Vec::with_capacity(identities.len());
+        for identity in identities {
+            if identity.sample_class() != SampleClass::Identity {
+                return Err(DomainError::Validation(format!(
+                    "Sample {} is a {}, not an identity",
+                    identity.name,
+                    identity.sample_class()
+                )));
+            }
+            if !identity_ids.contains(&identity.id) {
+                identity_ids.push(identity.id);
+            }
+        }
+        if identity_ids.len() < 2 {
+            return Err(DomainError::Validation(format!(
+                "Pooled sample {} needs at least two identities",
+                pooled.name
+            )));
+        }
+
+        Ok(Self {
+            sample_id: pooled.id,
+            identity_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::SampleDetails;
+    use crate::value_objects::Barcode;
+
+    fn identity(id: EntityId) -> Sample {
+        Sample::new_identity(
+            id,
+            format!("ID{}", id),
+            Barcode::new_unchecked(format!("SAM{}", id)),
+            1,
+            format!("PT-{}", id),
+            "tech".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_pooled_sample_needs_several_identities() {
+        let mut pooled = Sample::new_pooled(
+            10,
+            "POOL1".to_string(),
+            Barcode::new_unchecked("SAM10".to_string()),
+            1,
+            "tech".to_string(),
+        );
+        assert!(pooled.is_pooled());
+
+        assert!(SampleComposition::new(&pooled, &[identity(1), identity(1)]).is_err());
+        assert!(SampleComposition::new(&pooled, &[identity(1), pooled.clone()]).is_err());
+        assert!(SampleComposition::new(&identity(3), &[identity(1), identity(2)]).is_err());
+
+        let composition =
+            SampleComposition::new(&pooled, &[identity(2), identity(1), identity(2)]).unwrap();
+        assert_eq!(composition.identity_ids, vec![2, 1]);
+
+        if let SampleDetails::Detailed(details) = &mut pooled.details {
+            details.parent_id = Some(1);
+        }
+        assert!(SampleComposition::new(&pooled, &[identity(1), identity(2)]).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Sample list read model - one denormalized row per sample.
+//!
+//! The sample list shows each sample with its project, latest QC
+//! measurement and box position, which live in four different tables.
+//! Rather than join them for every page, a row per sample is kept up to
+//! date as samples, QC measurements and boxes change, along with counts of
+//! samples by project and QC status. The rows can always be rebuilt from
+//! the tables they are drawn from.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::value_objects::QcStatus;
+
+use super::{ControlType, EntityId, Project, QcRecord, Sample};
+
+/// A sample's latest QC measurement, as listed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListedQc {
+    /// ID of the QC record
+    pub qc_id: EntityId,
+    /// Name of the test, e.g. "Qubit"
+    pub test_type: String,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub status: QcStatus,
+    pub performed_at: DateTime<Utc>,
+}
+
+impl From<&QcRecord> for ListedQc {
+    fn from(record: &QcRecord) -> Self {
+        Self {
+            qc_id: record.id,
+            test_type: record.result.test_type.to_string(),
+            value: record.result.value,
+            unit: record.result.unit.clone(),
+            status: record.result.status,
+            performed_at: record.result.performed_at,
+        }
+    }
+}
+
+/// Where a sample is stored, as listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListedLocation {
+    pub box_id: EntityId,
+    pub box_name: String,
+    /// Position in the box, e.g. "B12"
+    pub position: String,
+}
+
+/// A sample as shown in the sample list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleListRow {
+    pub sample_id: EntityId,
+    pub name: String,
+    pub barcode: String,
+    pub project_id: EntityId,
+    pub project_code: String,
+    pub project_name: String,
+    /// Display name of the sample's class, e.g. "Stock"
+    pub sample_class: String,
+    pub qc_status: QcStatus,
+    pub latest_qc: Option<ListedQc>,
+    pub location: Option<ListedLocation>,
+    pub archived: bool,
+    pub received_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    /// Kind of control, if the sample is one; controls aren't counted
+    pub control_type: Option<ControlType>,
+}
+
+impl SampleListRow {
+    /// Creates the row for a sample of a project, without QC or location.
+    pub fn new(sample: &Sample, project: &Project) -> Self {
+        Self {
+            sample_id: sample.id,
+            name: sample.name.clone(),
+            barcode: sample.barcode.to_string(),
+            project_id: project.id,
+            project_code: project.code.clone(),
+            project_name: project.name.clone(),
+            sample_class: sample.sample_class().to_string(),
+            qc_status: sample.qc_status,
+            latest_qc: None,
+            location: None,
+            archived: sample.archived,
+            received_at: sample.received_at,
+            updated_at: sample.updated_at,
+            control_type: sample.control_type,
+        }
+    }
+
+    /// Returns true if the row counts towards its project's samples:
+    /// unarchived project material, not a control.
+    pub fn is_counted(&self) -> bool {
+        !self.archived && self.control_type.is_none()
+    }
+
+    /// Takes the sample's own fields from a changed sample, keeping its
+    /// latest QC and location.
+    pub fn refresh(&mut self, sample: &Sample, project: &Project) {
+        let latest_qc = self.latest_qc.take();
+        let location = self.location.take();
+        *self = Self {
+            latest_qc,
+            location,
+            ..Self::new(sample, project)
+        };
+    }
+
+    /// Lists a QC measurement as the latest, unless a later one is already
+    /// listed. Returns true if the row changed.
+    pub fn record_qc(&mut self, record: &QcRecord) -> bool {
+        let later = self.latest_qc.as_ref().is_none_or(|latest| {
+            (latest.performed_at, latest.qc_id) <= (record.result.performed_at, record.id)
+        });
+        if later {
+            self.latest_qc = Some(record.into());
+        }
+        later
+    }
+}
+
+/// What to list from the sample list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleListFilter {
+    pub project_id: Option<EntityId>,
+    /// Only samples of these projects, if given
+    pub project_ids: Option<Vec<EntityId>>,
+    pub qc_status: Option<QcStatus>,
+    /// Text the name or barcode contains
+    pub search: Option<String>,
+    /// Whether archived samples are listed
+    pub include_archived: bool,
+}
+
+/// How many samples of a project have a QC status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleListCount {
+    pub project_id: EntityId,
+    pub qc_status: QcStatus,
+    pub count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::entities::QcTarget;
+    use crate::value_objects::{Barcode, QcResult, QcTestType};
+
+    fn record(id: EntityId, minutes_ago: i64) -> QcRecord {
+        let mut result = QcResult::passed(QcTestType::Qubit, Some(12.5), None, "tech");
+        result.performed_at = Utc::now() - Duration::minutes(minutes_ago);
+        let mut record = QcRecord::new(QcTarget::Sample, 4, result);
+        record.id = id;
+        record
+    }
+
+    #[test]
+    fn test_row_keeps_latest_qc_and_location_on_refresh() {
+        let project = Project::new(2, "PROJ2".to_string(), "Two".to_string(), "admin".to_string());
+        let mut sample = Sample::new_plain(
+            4,
+            "SAM4".to_string(),
+            Barcode::new("SAM-4").unwrap(),
+            2,
+            "Homo sapiens".to_string(),
+            "tech".to_string(),
+        );
+        let mut row = SampleListRow::new(&sample, &project);
+        assert_eq!(row.project_code, "PROJ2");
+        assert_eq!(row.sample_class, "Plain Sample");
+
+        assert!(row.record_qc(&record(7, 10)));
+        assert!(!row.record_qc(&record(6, 20)));
+        assert!(row.record_qc(&record(8, 5)));
+        assert_eq!(row.latest_qc.as_ref().map(|qc| qc.qc_id), Some(8));
+
+        row.location = Some(ListedLocation {
+            box_id: 1,
+            box_name: "Box 1".to_string(),
+            position: "A01".to_string(),
+        });
+        sample.name = "SAM4b".to_string();
+        row.refresh(&sample, &project);
+        assert_eq!(row.name, "SAM4b");
+        assert_eq!(row.latest_qc.as_ref().map(|qc| qc.qc_id), Some(8));
+        assert!(row.location.is_some());
+    }
+}
@@ -6,7 +6,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::EntityId;
+use crate::errors::{DomainError, RunError};
+
+use super::{EntityId, Run};
 
 /// The sequencing platform/manufacturer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -46,8 +48,14 @@ impl std::fmt::Display for Platform {
 }
 
 /// The instrument model.
+///
+/// Instrument models are catalogued data managed by administrators rather
+/// than constants: each records the partitions a run has, the container
+/// models the instrument accepts, and the chemistry it can run.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct InstrumentModel {
+    /// Unique identifier
+    pub id: EntityId,
     /// Platform
     pub platform: Platform,
     /// Model name (e.g., "NovaSeq 6000", "PromethION 48")
@@ -56,42 +64,201 @@ pub struct InstrumentModel {
     pub partitions: u8,
     /// Description of the model's capabilities
     pub description: Option<String>,
+    /// Names of the container models the instrument accepts
+    pub container_models: Vec<String>,
+    /// Names of the accepted container models that are patterned flow
+    /// cells, whose runs are prone to index hopping
+    pub patterned_containers: Vec<String>,
+    /// Chemistry versions the instrument supports; empty if unconstrained
+    pub chemistries: Vec<String>,
+    /// Longest read, in cycles, the instrument supports
+    pub max_read_length: Option<u16>,
+    /// How long a run normally takes, in hours; runs going on longer are
+    /// flagged as overdue
+    pub expected_run_hours: Option<u32>,
+    /// How long the instrument takes per sequencing cycle, in seconds, used
+    /// to estimate when runs finish from their read configuration
+    pub seconds_per_cycle: Option<u32>,
 }
 
 impl InstrumentModel {
     /// Creates a new instrument model.
     pub fn new(platform: Platform, name: String, partitions: u8) -> Self {
         Self {
+            id: 0,
             platform,
             name,
             partitions,
             description: None,
+            container_models: Vec::new(),
+            patterned_containers: Vec::new(),
+            chemistries: Vec::new(),
+            max_read_length: None,
+            expected_run_hours: None,
+            seconds_per_cycle: None,
+        }
+    }
+
+    /// Checks that the model is well formed.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.trim().is_empty() {
+            return Err(DomainError::Validation(
+                "Instrument model name must not be empty".to_string(),
+            ));
+        }
+        if self.partitions == 0 {
+            return Err(DomainError::Validation(format!(
+                "Instrument model {} must have at least one partition",
+                self.name
+            )));
+        }
+        if self.max_read_length == Some(0) {
+            return Err(DomainError::Validation(format!(
+                "Instrument model {} must allow reads of at least one cycle",
+                self.name
+            )));
+        }
+        if self.seconds_per_cycle == Some(0) {
+            return Err(DomainError::Validation(format!(
+                "Instrument model {} must take at least a second per cycle",
+                self.name
+            )));
+        }
+        if self.expected_run_hours == Some(0) {
+            return Err(DomainError::Validation(format!(
+                "Instrument model {} must expect runs to take at least an hour",
+                self.name
+            )));
+        }
+        for value in self.container_models.iter().chain(&self.chemistries) {
+            if value.trim().is_empty() {
+                return Err(DomainError::Validation(format!(
+                    "Instrument model {} lists a blank container model or chemistry",
+                    self.name
+                )));
+            }
+        }
+        if let Some(unknown) = self
+            .patterned_containers
+            .iter()
+            .find(|c| !self.supports_container(c))
+        {
+            return Err(DomainError::Validation(format!(
+                "Instrument model {} doesn't accept patterned container model {}",
+                self.name, unknown
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the instrument accepts the named container model.
+    pub fn supports_container(&self, container_model: &str) -> bool {
+        self.container_models.iter().any(|c| c == container_model)
+    }
+
+    /// Returns true if the named container model is a patterned flow cell
+    /// on this instrument.
+    pub fn is_patterned(&self, container_model: &str) -> bool {
+        self.patterned_containers.iter().any(|c| c == container_model)
+    }
+
+    /// Checks that the instrument accepts the named container model.
+    pub fn check_container(&self, container_model: &str) -> Result<(), RunError> {
+        if self.supports_container(container_model) {
+            Ok(())
+        } else {
+            Err(RunError::IncompatibleContainer(
+                container_model.to_string(),
+                self.name.clone(),
+            ))
         }
     }
 
-    /// Common Illumina NovaSeq 6000 model.
-    pub fn novaseq_6000() -> Self {
-        Self::new(Platform::Illumina, "NovaSeq 6000".to_string(), 4)
+    /// Returns how long a run normally takes, if known.
+    pub fn expected_run_duration(&self) -> Option<chrono::Duration> {
+        self.expected_run_hours.map(|hours| chrono::Duration::hours(i64::from(hours)))
     }
 
-    /// Common Illumina NovaSeq X model.
-    pub fn novaseq_x() -> Self {
-        Self::new(Platform::Illumina, "NovaSeq X".to_string(), 2)
+    /// Estimates how long a run of `total_cycles` cycles takes.
+    ///
+    /// Uses the model's time per cycle when both it and the cycles are
+    /// known, and otherwise its expected run duration.
+    pub fn estimate_run_duration(&self, total_cycles: Option<u32>) -> Option<chrono::Duration> {
+        match (self.seconds_per_cycle, total_cycles) {
+            (Some(seconds), Some(cycles)) => Some(chrono::Duration::seconds(
+                i64::from(seconds) * i64::from(cycles),
+            )),
+            _ => self.expected_run_duration(),
+        }
     }
 
-    /// Common Illumina MiSeq model.
-    pub fn miseq() -> Self {
-        Self::new(Platform::Illumina, "MiSeq".to_string(), 1)
+    /// Returns true if the instrument can run the named chemistry.
+    pub fn supports_chemistry(&self, chemistry: &str) -> bool {
+        self.chemistries.is_empty() || self.chemistries.iter().any(|c| c == chemistry)
     }
 
-    /// Common Illumina NextSeq 2000 model.
-    pub fn nextseq_2000() -> Self {
-        Self::new(Platform::Illumina, "NextSeq 2000".to_string(), 1)
+    /// Returns true if the instrument reads i5 as the reverse complement of
+    /// the adapter's sequence, as Illumina's newer instruments do.
+    ///
+    /// Illumina's forward-strand workflow instruments (MiSeq, HiSeq 2500
+    /// and the NovaSeq 6000 on v1.0 reagents) read i5 as written; the
+    /// NovaSeq 6000 is assumed to be on v1.5 reagents.
+    pub fn reads_i5_reverse_complement(&self) -> bool {
+        const REVERSE_COMPLEMENT_WORKFLOW: &[&str] = &[
+            "iSeq",
+            "MiniSeq",
+            "NextSeq",
+            "HiSeq 3000",
+            "HiSeq 4000",
+            "HiSeq X",
+            "NovaSeq",
+        ];
+        self.platform == Platform::Illumina
+            && REVERSE_COMPLEMENT_WORKFLOW
+                .iter()
+                .any(|model| self.name.starts_with(model))
     }
 
-    /// Common Oxford Nanopore PromethION model.
-    pub fn promethion() -> Self {
-        Self::new(Platform::OxfordNanopore, "PromethION 48".to_string(), 48)
+    /// Checks that a planned run fits this instrument.
+    ///
+    /// The run's read length is given as e.g. "2x150" or "150"; the
+    /// longest read must not exceed the model's maximum.
+    pub fn check_run(&self, run: &Run, chemistry: Option<&str>) -> Result<(), RunError> {
+        if run.partitions.len() != self.partitions as usize {
+            return Err(RunError::InvalidParameters(format!(
+                "{} runs have {} partitions, not {}",
+                self.name,
+                self.partitions,
+                run.partitions.len()
+            )));
+        }
+
+        if let Some(chemistry) = chemistry {
+            if !self.supports_chemistry(chemistry) {
+                return Err(RunError::InvalidParameters(format!(
+                    "{} does not support {} chemistry",
+                    self.name, chemistry
+                )));
+            }
+        }
+
+        if let (Some(max), Some(read_length)) = (self.max_read_length, &run.read_length) {
+            let cycles = read_length
+                .rsplit('x')
+                .next()
+                .and_then(|c| c.trim().parse::<u16>().ok())
+                .ok_or_else(|| {
+                    RunError::InvalidParameters(format!("Invalid read length: {}", read_length))
+                })?;
+            if cycles > max {
+                return Err(RunError::InvalidParameters(format!(
+                    "{} supports reads of at most {} cycles, not {}",
+                    self.name, max, cycles
+                )));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -241,9 +408,12 @@ impl Sequencer {
         self.updated_at = Utc::now();
     }
 
-    /// Sets the sequencer as available.
+    /// Sets the sequencer as available once its run ends, unless it was
+    /// taken out of service during the run.
     pub fn complete_run(&mut self) {
-        self.status = SequencerStatus::Available;
+        if self.status == SequencerStatus::Running {
+            self.status = SequencerStatus::Available;
+        }
         self.updated_at = Utc::now();
     }
 
@@ -253,6 +423,37 @@ impl Sequencer {
         self.updated_at = Utc::now();
     }
 
+    /// Moves the sequencer to a status set by staff rather than by runs.
+    ///
+    /// A retired sequencer stays retired. A running sequencer can only be
+    /// taken out of service; maintenance and retirement wait for the run
+    /// to end. Returning from maintenance records a service.
+    pub fn change_status(&mut self, status: SequencerStatus) -> Result<(), DomainError> {
+        let refuse = |reason: &str| {
+            Err(DomainError::Validation(format!(
+                "Sequencer {} {}",
+                self.name, reason
+            )))
+        };
+        match (self.status, status) {
+            (SequencerStatus::Retired, _) => return refuse("is retired"),
+            (_, SequencerStatus::Running) => {
+                return refuse("starts running when a run is started on it")
+            }
+            (SequencerStatus::Running, SequencerStatus::Available) => {
+                return refuse("becomes available when its run ends")
+            }
+            (SequencerStatus::Running, SequencerStatus::Maintenance | SequencerStatus::Retired) => {
+                return refuse("is running; wait for the run to end")
+            }
+            (SequencerStatus::Maintenance, SequencerStatus::Available) => self.record_service(),
+            _ => {}
+        }
+        self.status = status;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Records a service date.
     pub fn record_service(&mut self) {
         self.last_service_date = Some(Utc::now());
@@ -264,13 +465,17 @@ impl Sequencer {
 mod tests {
     use super::*;
 
+    fn novaseq_6000() -> InstrumentModel {
+        let mut model = InstrumentModel::new(Platform::Illumina, "NovaSeq 6000".to_string(), 4);
+        model.container_models = vec!["S4 Flow Cell".to_string()];
+        model.chemistries = vec!["v1.5".to_string()];
+        model.max_read_length = Some(250);
+        model
+    }
+
     #[test]
     fn test_sequencer_creation() {
-        let seq = Sequencer::new(
-            1,
-            "NovaSeq01".to_string(),
-            InstrumentModel::novaseq_6000(),
-        );
+        let seq = Sequencer::new(1, "NovaSeq01".to_string(), novaseq_6000());
         assert!(seq.can_run());
         assert_eq!(seq.platform(), Platform::Illumina);
         assert_eq!(seq.num_partitions(), 4);
@@ -278,11 +483,7 @@ mod tests {
 
     #[test]
     fn test_sequencer_lifecycle() {
-        let mut seq = Sequencer::new(
-            1,
-            "NovaSeq01".to_string(),
-            InstrumentModel::novaseq_6000(),
-        );
+        let mut seq = Sequencer::new(1, "NovaSeq01".to_string(), novaseq_6000());
 
         assert!(seq.can_run());
 
@@ -296,5 +497,88 @@ mod tests {
         seq.start_maintenance();
         assert!(!seq.can_run());
     }
-}
 
+    #[test]
+    fn test_status_changes() {
+        let mut seq = Sequencer::new(1, "NovaSeq01".to_string(), novaseq_6000());
+
+        seq.start_run();
+        assert!(seq.change_status(SequencerStatus::Maintenance).is_err());
+        assert!(seq.change_status(SequencerStatus::Available).is_err());
+        seq.change_status(SequencerStatus::OutOfService).unwrap();
+        // The run ending doesn't put a broken instrument back in service
+        seq.complete_run();
+        assert_eq!(seq.status, SequencerStatus::OutOfService);
+
+        seq.change_status(SequencerStatus::Maintenance).unwrap();
+        assert!(seq.last_service_date.is_none());
+        seq.change_status(SequencerStatus::Available).unwrap();
+        assert!(seq.last_service_date.is_some());
+        assert!(seq.change_status(SequencerStatus::Running).is_err());
+
+        seq.change_status(SequencerStatus::Retired).unwrap();
+        assert!(seq.change_status(SequencerStatus::Available).is_err());
+        assert!(!seq.can_run());
+    }
+
+    #[test]
+    fn test_i5_orientation_by_model() {
+        let model = |platform, name: &str| InstrumentModel::new(platform, name.to_string(), 1);
+
+        assert!(novaseq_6000().reads_i5_reverse_complement());
+        assert!(model(Platform::Illumina, "NextSeq 2000").reads_i5_reverse_complement());
+        assert!(!model(Platform::Illumina, "MiSeq").reads_i5_reverse_complement());
+        assert!(!model(Platform::Illumina, "HiSeq 2500").reads_i5_reverse_complement());
+    }
+
+    #[test]
+    fn test_check_run_against_model() {
+        let model = novaseq_6000();
+        assert!(model.validate().is_ok());
+        assert!(model.supports_container("S4 Flow Cell"));
+        assert!(!model.supports_container("Flongle"));
+
+        let mut run = Run::new(1, "RUN1".to_string(), 1, 4, "tech".to_string());
+        run.read_length = Some("2x150".to_string());
+        assert!(model.check_run(&run, Some("v1.5")).is_ok());
+        assert!(matches!(
+            model.check_run(&run, Some("v1.0")),
+            Err(RunError::InvalidParameters(_))
+        ));
+
+        run.read_length = Some("2x300".to_string());
+        assert!(model.check_run(&run, None).is_err());
+
+        let miseq_sized = Run::new(2, "RUN2".to_string(), 1, 1, "tech".to_string());
+        assert!(model.check_run(&miseq_sized, None).is_err());
+
+        let mut run = Run::new(3, "RUN3".to_string(), 1, 4, "tech".to_string());
+        assert!(run
+            .assign_container("HXXXXXDSX2".to_string(), "S4 Flow Cell", &model)
+            .is_ok());
+        assert_eq!(run.container_barcode.as_deref(), Some("HXXXXXDSX2"));
+        assert_eq!(run.container_model.as_deref(), Some("S4 Flow Cell"));
+        match run.assign_container("FLO-MIN106".to_string(), "Flongle", &model) {
+            Err(RunError::IncompatibleContainer(container, instrument)) => {
+                assert_eq!(container, "Flongle");
+                assert_eq!(instrument, "NovaSeq 6000");
+            }
+            other => panic!("expected incompatible container, got {:?}", other),
+        }
+        assert_eq!(run.container_barcode.as_deref(), Some("HXXXXXDSX2"));
+
+        let mut patterned = novaseq_6000();
+        patterned.patterned_containers = vec!["S4 Flow Cell".to_string()];
+        assert!(patterned.validate().is_ok());
+        assert!(patterned.is_patterned("S4 Flow Cell"));
+        assert!(!novaseq_6000().is_patterned("S4 Flow Cell"));
+        patterned.patterned_containers.push("Flongle".to_string());
+        assert!(patterned.validate().is_err());
+
+        let mut blank = InstrumentModel::new(Platform::Illumina, " ".to_string(), 1);
+        assert!(blank.validate().is_err());
+        blank.name = "MiSeq".to_string();
+        blank.partitions = 0;
+        assert!(blank.validate().is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! Storage audit entity - a guided physical inventory of a freezer.
+//!
+//! An audit walks a freezer's boxes one at a time. Each box is scanned and
+//! the scan compared with what the box records; the disagreements
+//! accumulate on the audit until a manager decides which records to
+//! correct. Unlike a [`ReconciliationReport`](super::ReconciliationReport),
+//! which compares records with each other, an audit compares records with
+//! what is actually on the shelf.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+use crate::value_objects::BoxPosition;
+
+use super::{EntityId, StorableItem, StorageBox};
+
+/// Where a storage audit is up to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageAuditStatus {
+    /// Boxes are still to be scanned
+    Scanning,
+    /// Every box has been scanned; the discrepancies await a manager
+    Review,
+    /// A manager has accepted the corrections they agree with
+    Closed,
+}
+
+impl std::fmt::Display for StorageAuditStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scanning => write!(f, "scanning"),
+            Self::Review => write!(f, "review"),
+            Self::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+impl std::str::FromStr for StorageAuditStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scanning" => Ok(Self::Scanning),
+            "review" => Ok(Self::Review),
+            "closed" => Ok(Self::Closed),
+            other => Err(DomainError::Validation(format!(
+                "Unknown storage audit status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// The kind of disagreement between a scan and a box's records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanDiscrepancyKind {
+    /// The box records an item the scan didn't find
+    Missing,
+    /// The scan found an item where the box records none
+    Unexpected,
+    /// The scan found a different item from the one recorded
+    Mismatched,
+    /// The scan found a barcode no item has
+    UnknownBarcode,
+}
+
+impl std::fmt::Display for ScanDiscrepancyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing"),
+            Self::Unexpected => write!(f, "unexpected"),
+            Self::Mismatched => write!(f, "mismatched"),
+            Self::UnknownBarcode => write!(f, "unknown barcode"),
+        }
+    }
+}
+
+/// A tube read at a position by a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedTube {
+    /// Where the tube was read
+    pub position: BoxPosition,
+    /// The barcode read
+    pub barcode: String,
+    /// The item with that barcode, if any
+    pub item: Option<StorableItem>,
+}
+
+/// A position at which a scan disagreed with the box's records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanDiscrepancy {
+    /// The box scanned
+    pub box_id: EntityId,
+    /// The position, e.g. "A1"
+    pub position: String,
+    /// What is wrong
+    pub kind: ScanDiscrepancyKind,
+    /// The item the box records there
+    pub recorded: Option<StorableItem>,
+    /// The barcode the scan read there
+    pub scanned_barcode: Option<String>,
+    /// The item with the scanned barcode
+    pub scanned: Option<StorableItem>,
+    /// True once a manager has had the records corrected to the scan
+    pub accepted: bool,
+}
+
+impl ScanDiscrepancy {
+    /// Returns true if the records can be corrected to match the scan; a
+    /// barcode no item has can't be put in a box.
+    pub fn is_correctable(&self) -> bool {
+        self.kind != ScanDiscrepancyKind::UnknownBarcode
+    }
+}
+
+/// A physical inventory of one freezer's boxes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageAudit {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The freezer audited
+    pub freezer: String,
+    /// The freezer's boxes, in the order they are to be scanned
+    pub box_ids: Vec<EntityId>,
+    /// The boxes scanned so far
+    pub scanned_box_ids: Vec<EntityId>,
+    /// Disagreements found, in scan order
+    pub discrepancies: Vec<ScanDiscrepancy>,
+    /// Where the audit is up to
+    pub status: StorageAuditStatus,
+    /// Who started the audit
+    pub started_by: String,
+    /// When the audit started
+    pub started_at: DateTime<Utc>,
+    /// Who reviewed the discrepancies
+    pub closed_by: Option<String>,
+    /// When the discrepancies were reviewed
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl StorageAudit {
+    /// Starts an audit of a freezer's boxes.
+    pub fn new(
+        freezer: String,
+        box_ids: Vec<EntityId>,
+        started_by: String,
+    ) -> Result<Self, DomainError> {
+        if box_ids.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "Freezer {} holds no boxes to audit",
+                freezer
+            )));
+        }
+
+        Ok(Self {
+            id: 0,
+            freezer,
+            box_ids,
+            scanned_box_ids: Vec::new(),
+            discrepancies: Vec::new(),
+            status: StorageAuditStatus::Scanning,
+            started_by,
+            started_at: Utc::now(),
+            closed_by: None,
+            closed_at: None,
+        })
+    }
+
+    /// Returns the next box to scan, if any are left.
+    pub fn next_box(&self) -> Option<EntityId> {
+        self.box_ids
+            .iter()
+            .copied()
+            .find(|id| !self.scanned_box_ids.contains(id))
+    }
+
+    /// Compares a scan of one of the audited boxes with its records.
+    ///
+    /// Scanning a box again replaces what its earlier scan found. Once
+    /// every box is scanned the audit moves to review; boxes can still be
+    /// rescanned until it is closed.
+    pub fn record_scan(
+        &mut self,
+        storage_box: &StorageBox,
+        scanned: &[ScannedTube],
+    ) -> Result<(), DomainError> {
+        if self.status == StorageAuditStatus::Closed {
+            return Err(DomainError::Validation(format!(
+                "The audit of {} is closed",
+                self.freezer
+            )));
+        }
+        if !self.box_ids.contains(&storage_box.id) {
+            return Err(DomainError::Validation(format!(
+                "Box {} is not part of the audit of {}",
+                storage_box.name, self.freezer
+            )));
+        }
+
+        self.discrepancies.retain(|d| d.box_id != storage_box.id);
+        self.discrepancies.extend(compare(storage_box, scanned));
+        if !self.scanned_box_ids.contains(&storage_box.id) {
+            self.scanned_box_ids.push(storage_box.id);
+        }
+        if self.next_box().is_none() {
+            self.status = StorageAuditStatus::Review;
+        }
+        Ok(())
+    }
+
+    /// Accepts the discrepancies at the given indexes, closing the audit.
+    ///
+    /// Returns the accepted discrepancies, whose records are to be
+    /// corrected to match the scan. Those not accepted stay on the audit
+    /// as found.
+    pub fn accept(
+        &mut self,
+        indexes: &[usize],
+        by: String,
+    ) -> Result<Vec<ScanDiscrepancy>, DomainError> {
+        if self.status != StorageAuditStatus::Review {
+            return Err(DomainError::Validation(format!(
+                "The audit of {} is {}, not awaiting review",
+                self.freezer, self.status
+            )));
+        }
+
+        let indexes: BTreeSet<usize> = indexes.iter().copied().collect();
+        for &index in &indexes {
+            let discrepancy = self.discrepancies.get(index).ok_or_else(|| {
+                DomainError::Validation(format!("The audit has no discrepancy {}", index))
+            })?;
+            if !discrepancy.is_correctable() {
+                return Err(DomainError::Validation(format!(
+                    "Discrepancy {} is a barcode no item has, and can't be corrected",
+                    index
+                )));
+            }
+        }
+
+        let mut accepted = Vec::new();
+        for index in indexes {
+            let discrepancy = &mut self.discrepancies[index];
+            discrepancy.accepted = true;
+            accepted.push(discrepancy.clone());
+        }
+        self.status = StorageAuditStatus::Closed;
+        self.closed_by = Some(by);
+        self.closed_at = Some(Utc::now());
+        Ok(accepted)
+    }
+}
+
+/// Finds the positions at which a scan disagrees with a box's records.
+fn compare(storage_box: &StorageBox, scanned: &[ScannedTube]) -> Vec<ScanDiscrepancy> {
+    let scanned: HashMap<&BoxPosition, &ScannedTube> =
+        scanned.iter().map(|t| (&t.position, t)).collect();
+    let positions: BTreeSet<&BoxPosition> = storage_box
+        .all_contents()
+        .into_iter()
+        .map(|(position, _)| position)
+        .chain(scanned.keys().copied())
+        .collect();
+
+    let mut discrepancies = Vec::new();
+    for position in positions {
+        let recorded = storage_box.get_item(position);
+        let tube = scanned.get(position);
+        let kind = match (recorded, tube.map(|t| &t.item)) {
+            (None, None) => continue,
+            (Some(r), Some(Some(s))) if r == s => continue,
+            (Some(_), None) => ScanDiscrepancyKind::Missing,
+            (_, Some(None)) => ScanDiscrepancyKind::UnknownBarcode,
+            (None, Some(Some(_))) => ScanDiscrepancyKind::Unexpected,
+            (Some(_), Some(Some(_))) => ScanDiscrepancyKind::Mismatched,
+        };
+        discrepancies.push(ScanDiscrepancy {
+            box_id: storage_box.id,
+            position: position.to_string(),
+            kind,
+            recorded: recorded.cloned(),
+            scanned_barcode: tube.map(|t| t.barcode.clone()),
+            scanned: tube.and_then(|t| t.item.clone()),
+            accepted: false,
+        });
+    }
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::StorableType;
+    use crate::value_objects::Dimension;
+
+    fn stored_box(id: EntityId) -> StorageBox {
+        let mut storage_box = StorageBox::new(
+            id,
+            format!("Box {}", id),
+            Dimension::CRYOBOX_9X9,
+            StorableType::Sample,
+        );
+        for (position, sample) in [("A1", 1), ("A2", 2), ("A3", 3)] {
+            let position = BoxPosition::parse(position, &storage_box.dimension).unwrap();
+            storage_box
+                .place_item(position, StorableItem::sample(sample))
+                .unwrap();
+        }
+        storage_box
+    }
+
+    fn tube(position: &str, barcode: &str, sample: Option<EntityId>) -> ScannedTube {
+        ScannedTube {
+            position: BoxPosition::parse(position, &Dimension::CRYOBOX_9X9).unwrap(),
+            barcode: barcode.to_string(),
+            item: sample.map(StorableItem::sample),
+        }
+    }
+
+    #[test]
+    fn test_scan_is_compared_with_the_box() {
+        let mut audit = StorageAudit::new("Freezer 1".to_string(), vec![7], "tech".to_string())
+            .unwrap();
+        assert_eq!(audit.next_box(), Some(7));
+
+        // A1 as recorded, A2 empty, A3 holds sample 4, B1 sample 5, B2 junk
+        let scan = [
+            tube("A1", "SAM1", Some(1)),
+            tube("A3", "SAM4", Some(4)),
+            tube("B1", "SAM5", Some(5)),
+            tube("B2", "XYZ", None),
+        ];
+        audit.record_scan(&stored_box(7), &scan).unwrap();
+
+        let found: Vec<(&str, ScanDiscrepancyKind)> = audit
+            .discrepancies
+            .iter()
+            .map(|d| (d.position.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("A2", ScanDiscrepancyKind::Missing),
+                ("A3", ScanDiscrepancyKind::Mismatched),
+                ("B1", ScanDiscrepancyKind::Unexpected),
+                ("B2", ScanDiscrepancyKind::UnknownBarcode),
+            ]
+        );
+        assert_eq!(audit.status, StorageAuditStatus::Review);
+        assert_eq!(audit.next_box(), None);
+
+        // A rescan replaces what the first one found
+        audit.record_scan(&stored_box(7), &scan[..1]).unwrap();
+        assert_eq!(audit.discrepancies.len(), 2);
+
+        let err = audit.record_scan(&stored_box(8), &scan).unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+    }
+
+    #[test]
+    fn test_accepting_corrections_closes_the_audit() {
+        let mut audit =
+            StorageAudit::new("Freezer 1".to_string(), vec![7, 8], "tech".to_string()).unwrap();
+        audit
+            .record_scan(&stored_box(7), &[tube("A1", "XYZ", None)])
+            .unwrap();
+        assert_eq!(audit.status, StorageAuditStatus::Scanning);
+        assert_eq!(audit.next_box(), Some(8));
+        assert!(audit.accept(&[], "manager".to_string()).is_err());
+
+        audit.record_scan(&stored_box(8), &[]).unwrap();
+        // 0: A1 unknown barcode, 1-2: A2, A3 missing from box 7, 3-5: box 8
+        assert_eq!(audit.discrepancies.len(), 6);
+        assert!(audit.accept(&[0], "manager".to_string()).is_err());
+        assert!(audit.accept(&[9], "manager".to_string()).is_err());
+
+        let accepted = audit.accept(&[1, 2], "manager".to_string()).unwrap();
+        assert_eq!(accepted.len(), 2);
+        assert_eq!(audit.status, StorageAuditStatus::Closed);
+        assert_eq!(audit.closed_by.as_deref(), Some("manager"));
+        assert_eq!(
+            audit.discrepancies.iter().filter(|d| d.accepted).count(),
+            2
+        );
+        assert!(audit.record_scan(&stored_box(7), &[]).is_err());
+    }
+}
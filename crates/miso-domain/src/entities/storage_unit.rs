@@ -0,0 +1,219 @@
+//! Storage unit entity - the freezers, shelves and racks boxes are kept in.
+//!
+//! Units form a Freezer → Shelf → Rack hierarchy. Each unit may limit how
+//! many units (or, for racks, boxes) it holds, and may record its own
+//! temperature; boxes take the temperature of the nearest unit above them
+//! that records one.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::{EntityId, StorageLocation};
+
+/// Level of the storage hierarchy a unit sits at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageUnitKind {
+    Freezer,
+    Shelf,
+    Rack,
+}
+
+impl StorageUnitKind {
+    /// Returns the code stored for the kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Freezer => "freezer",
+            Self::Shelf => "shelf",
+            Self::Rack => "rack",
+        }
+    }
+
+    /// Returns the kind of unit this kind sits in; freezers sit in none.
+    pub fn parent(&self) -> Option<Self> {
+        match self {
+            Self::Freezer => None,
+            Self::Shelf => Some(Self::Freezer),
+            Self::Rack => Some(Self::Shelf),
+        }
+    }
+}
+
+impl fmt::Display for StorageUnitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for StorageUnitKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "freezer" => Ok(Self::Freezer),
+            "shelf" => Ok(Self::Shelf),
+            "rack" => Ok(Self::Rack),
+            other => Err(format!("Unknown storage unit kind: {}", other)),
+        }
+    }
+}
+
+/// A freezer, a shelf in a freezer, or a rack on a shelf.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUnit {
+    pub id: EntityId,
+    pub kind: StorageUnitKind,
+    /// Name, unique among the units in the same parent
+    pub name: String,
+    /// Freezer a shelf is in or shelf a rack is on; `None` for freezers
+    pub parent_id: Option<EntityId>,
+    /// Barcode on the unit itself; unique
+    pub barcode: Option<String>,
+    /// Most shelves a freezer, racks a shelf or boxes a rack holds;
+    /// `None` if unlimited
+    pub capacity: Option<u32>,
+    /// Temperature (°C), if the unit records its own
+    pub temperature: Option<i8>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StorageUnit {
+    /// Creates a unit of `kind` in `parent`.
+    ///
+    /// Freezers have no parent, shelves go in freezers and racks go on
+    /// shelves.
+    pub fn new(
+        kind: StorageUnitKind,
+        name: &str,
+        parent: Option<&StorageUnit>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        match (kind.parent(), parent) {
+            (None, None) => {}
+            (Some(expected), Some(parent)) if parent.kind == expected => {}
+            (None, Some(_)) => {
+                return Err(DomainError::Validation(format!(
+                    "A {} cannot be placed in another unit",
+                    kind
+                )))
+            }
+            (Some(expected), _) => {
+                return Err(DomainError::Validation(format!(
+                    "A {} must be placed in a {}",
+                    kind, expected
+                )))
+            }
+        }
+
+        let now = Utc::now();
+        let unit = Self {
+            id: 0,
+            kind,
+            name: name.trim().to_string(),
+            parent_id: parent.map(|p| p.id),
+            barcode: None,
+            capacity: None,
+            temperature: None,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        };
+        unit.validate()?;
+        Ok(unit)
+    }
+
+    /// Checks the unit has a name and, if limited, room for something.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.name.trim().is_empty() {
+            return Err(DomainError::Validation(format!(
+                "A {} needs a name",
+                self.kind
+            )));
+        }
+        if self.capacity == Some(0) {
+            return Err(DomainError::Validation(format!(
+                "The capacity of {} {} must be at least 1",
+                self.kind, self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fails if the unit already holds as many units or boxes as its
+    /// capacity allows.
+    pub fn check_room(&self, held: usize) -> Result<(), DomainError> {
+        match self.capacity {
+            Some(capacity) if held >= capacity as usize => Err(DomainError::Validation(format!(
+                "{} {} is full ({} of {})",
+                self.kind, self.name, held, capacity
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl StorageLocation {
+    /// Returns the location of a box in `rack`, on `shelf`, in `freezer`.
+    /// The box takes the temperature of the nearest of them that records
+    /// one.
+    pub fn in_rack(freezer: &StorageUnit, shelf: &StorageUnit, rack: &StorageUnit) -> Self {
+        Self {
+            temperature: rack
+                .temperature
+                .or(shelf.temperature)
+                .or(freezer.temperature),
+            ..Self::with_path(&freezer.name, &shelf.name, &rack.name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(kind: StorageUnitKind, name: &str, parent: Option<&StorageUnit>) -> StorageUnit {
+        StorageUnit::new(kind, name, parent, "tech".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_units_nest_freezer_shelf_rack() {
+        let mut freezer = unit(StorageUnitKind::Freezer, " Freezer 2 ", None);
+        freezer.id = 1;
+        freezer.temperature = Some(-80);
+        assert_eq!(freezer.name, "Freezer 2");
+
+        let new = |kind, parent| StorageUnit::new(kind, "X", parent, "tech".to_string());
+        assert!(new(StorageUnitKind::Shelf, None).is_err());
+        assert!(new(StorageUnitKind::Rack, Some(&freezer)).is_err());
+        assert!(new(StorageUnitKind::Freezer, Some(&freezer)).is_err());
+        assert!(StorageUnit::new(StorageUnitKind::Freezer, " ", None, "tech".to_string()).is_err());
+
+        let mut shelf = unit(StorageUnitKind::Shelf, "Shelf 1", Some(&freezer));
+        shelf.id = 2;
+        assert_eq!(shelf.parent_id, Some(1));
+        let rack = unit(StorageUnitKind::Rack, "Rack C", Some(&shelf));
+
+        let location = StorageLocation::in_rack(&freezer, &shelf, &rack);
+        assert_eq!(location.path(), "Freezer 2 / Shelf 1 / Rack C");
+        assert_eq!(location.temperature, Some(-80));
+    }
+
+    #[test]
+    fn test_capacity_limits_contents() {
+        let mut rack = unit(StorageUnitKind::Freezer, "Freezer 1", None);
+        assert!(rack.check_room(100).is_ok());
+
+        rack.capacity = Some(0);
+        assert!(rack.validate().is_err());
+        rack.capacity = Some(2);
+        assert!(rack.check_room(1).is_ok());
+        assert!(rack.check_room(2).is_err());
+    }
+}
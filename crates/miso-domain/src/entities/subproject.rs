@@ -0,0 +1,86 @@
+//! Subproject entity - a named partition of a large project's samples,
+//! such as a cohort or collection site.
+//!
+//! Samples belong to a project and may additionally be assigned to one of
+//! its subprojects. Access and locking stay with the project; subprojects
+//! only group samples for listing and filtering.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// A partition of a project's samples.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subproject {
+    /// Unique identifier
+    pub id: EntityId,
+    /// The project the subproject partitions
+    pub project_id: EntityId,
+    /// Short name, unique within the project (e.g. "COHORT-A")
+    pub alias: String,
+    /// Description
+    pub description: Option<String>,
+    /// Who created the subproject
+    pub created_by: String,
+    /// When the subproject was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subproject {
+    /// Creates a subproject of a project.
+    pub fn new(
+        project_id: EntityId,
+        alias: String,
+        description: Option<String>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let mut subproject = Self {
+            id: 0,
+            project_id,
+            alias: String::new(),
+            description: None,
+            created_by,
+            created_at: Utc::now(),
+        };
+        subproject.rename(alias)?;
+        subproject.description = description.filter(|d| !d.trim().is_empty());
+        Ok(subproject)
+    }
+
+    /// Changes the subproject's alias.
+    pub fn rename(&mut self, alias: String) -> Result<(), DomainError> {
+        let alias = alias.trim().to_string();
+        if alias.is_empty() {
+            return Err(DomainError::Validation(
+                "Subproject alias is required".to_string(),
+            ));
+        }
+        self.alias = alias;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_is_trimmed_and_required() {
+        let mut subproject = Subproject::new(
+            1,
+            " COHORT-A ".to_string(),
+            Some("  ".to_string()),
+            "lm".to_string(),
+        )
+        .unwrap();
+        assert_eq!(subproject.alias, "COHORT-A");
+        assert_eq!(subproject.description, None);
+
+        assert!(subproject.rename(" ".to_string()).is_err());
+        assert_eq!(subproject.alias, "COHORT-A");
+        assert!(Subproject::new(1, String::new(), None, "lm".to_string()).is_err());
+    }
+}
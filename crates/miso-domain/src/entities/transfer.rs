@@ -0,0 +1,450 @@
+//! Transfer entity - samples and libraries handed from one lab or person to
+//! another.
+//!
+//! Each transfer records who sent what to whom and when, and whether each
+//! item arrived and passed the recipient's QC, so that the chain of custody
+//! of any sample or library can be traced. Transfers sent by courier carry
+//! the courier's tracking number and the shipment's last known status.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DomainError;
+
+use super::EntityId;
+
+/// Kind of item in a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferItemType {
+    Sample,
+    Library,
+}
+
+impl TransferItemType {
+    /// Returns the code stored for the type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sample => "sample",
+            Self::Library => "library",
+        }
+    }
+}
+
+impl fmt::Display for TransferItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransferItemType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sample" => Ok(Self::Sample),
+            "library" => Ok(Self::Library),
+            other => Err(format!("Unknown transfer item type: {}", other)),
+        }
+    }
+}
+
+/// Where a couriered shipment has got to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShipmentStatus {
+    /// The courier has been told about the shipment but not collected it
+    Pending,
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    /// Delayed, returned or lost
+    Exception,
+}
+
+impl ShipmentStatus {
+    /// Returns the code stored for the status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InTransit => "in_transit",
+            Self::OutForDelivery => "out_for_delivery",
+            Self::Delivered => "delivered",
+            Self::Exception => "exception",
+        }
+    }
+}
+
+impl fmt::Display for ShipmentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ShipmentStatus {
+    type Err = String;
+
+    /// Parses a status code, accepting "out-for-delivery" and
+    /// "Out For Delivery" as well as "out_for_delivery".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', ' '], "_").as_str() {
+            "pending" => Ok(Self::Pending),
+            "in_transit" => Ok(Self::InTransit),
+            "out_for_delivery" => Ok(Self::OutForDelivery),
+            "delivered" => Ok(Self::Delivered),
+            "exception" => Ok(Self::Exception),
+            _ => Err(format!("Unknown shipment status: {}", s)),
+        }
+    }
+}
+
+/// A sample or library in a transfer and what the recipient found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferItem {
+    pub item_type: TransferItemType,
+    pub entity_id: EntityId,
+    /// Whether the item arrived; `None` until the recipient checks
+    pub received: Option<bool>,
+    /// Whether the item passed the recipient's QC; `None` if not checked
+    pub qc_passed: Option<bool>,
+    /// Recipient's QC comments; required when the item fails
+    pub qc_note: Option<String>,
+}
+
+/// What the recipient found for one item of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferReceipt {
+    pub item_type: TransferItemType,
+    pub entity_id: EntityId,
+    pub received: bool,
+    pub qc_passed: Option<bool>,
+    pub qc_note: Option<String>,
+}
+
+/// A handover of samples and libraries between labs or people.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub id: EntityId,
+    /// Lab or user the items came from
+    pub sender: String,
+    /// Lab or user the items went to
+    pub recipient: String,
+    /// Items sent, in the order listed
+    pub items: Vec<TransferItem>,
+    pub notes: Option<String>,
+    /// Courier carrying the items, e.g. "FedEx"
+    pub courier: Option<String>,
+    /// Courier's tracking number; the shipment is only tracked with one
+    pub tracking_number: Option<String>,
+    /// Address to tell when the shipment is out for delivery
+    pub recipient_email: Option<String>,
+    /// Last status reported by the courier
+    pub shipment_status: Option<ShipmentStatus>,
+    /// When the courier last reported a change of status
+    pub shipment_updated_at: Option<DateTime<Utc>>,
+    /// When the items were sent
+    pub sent_at: DateTime<Utc>,
+    /// Who checked every item in; `None` until then
+    pub received_by: Option<String>,
+    /// When every item had been checked in
+    pub received_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Transfer {
+    /// Creates a transfer of `items` from `sender` to `recipient`.
+    ///
+    /// The sender and recipient must differ, and at least one item must be
+    /// sent. Items listed twice are sent once.
+    pub fn new(
+        sender: &str,
+        recipient: &str,
+        items: &[(TransferItemType, EntityId)],
+        sent_at: DateTime<Utc>,
+        created_by: String,
+    ) -> Result<Self, DomainError> {
+        let sender = sender.trim();
+        let recipient = recipient.trim();
+        if sender.is_empty() || recipient.is_empty() {
+            return Err(DomainError::Validation(
+                "A transfer needs a sender and a recipient".to_string(),
+            ));
+        }
+        if sender.eq_ignore_ascii_case(recipient) {
+            return Err(DomainError::Validation(format!(
+                "{} cannot transfer items to itself",
+                sender
+            )));
+        }
+        if items.is_empty() {
+            return Err(DomainError::Validation(
+                "A transfer needs at least one item".to_string(),
+            ));
+        }
+
+        let mut transfer_items: Vec<TransferItem> = Vec::with_capacity(items.len());
+        for &(item_type, entity_id) in items {
+            if !transfer_items
+                .iter()
+                .any(|i| i.item_type == item_type && i.entity_id == entity_id)
+            {
+                transfer_items.push(TransferItem {
+                    item_type,
+                    entity_id,
+                    received: None,
+                    qc_passed: None,
+                    qc_note: None,
+                });
+            }
+        }
+
+        let now = Utc::now();
+        Ok(Self {
+            id: 0,
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            items: transfer_items,
+            notes: None,
+            courier: None,
+            tracking_number: None,
+            recipient_email: None,
+            shipment_status: None,
+            shipment_updated_at: None,
+            sent_at,
+            received_by: None,
+            received_at: None,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Returns the item for an entity, if it was sent.
+    pub fn item(&self, item_type: TransferItemType, entity_id: EntityId) -> Option<&TransferItem> {
+        self.items
+            .iter()
+            .find(|i| i.item_type == item_type && i.entity_id == entity_id)
+    }
+
+    /// Returns the IDs of the items of a type, in the order listed.
+    pub fn ids(&self, item_type: TransferItemType) -> Vec<EntityId> {
+        self.items
+            .iter()
+            .filter(|i| i.item_type == item_type)
+            .map(|i| i.entity_id)
+            .collect()
+    }
+
+    /// Returns true once every item has been checked in.
+    pub fn is_received(&self) -> bool {
+        self.items.iter().all(|i| i.received.is_some())
+    }
+
+    /// Records the courier carrying the items and its tracking number.
+    ///
+    /// A tracking number needs a courier. Changing the tracking number
+    /// forgets the status reported for the old one.
+    pub fn set_shipment(
+        &mut self,
+        courier: Option<String>,
+        tracking_number: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        let courier = trimmed(&courier);
+        let tracking_number = trimmed(&tracking_number);
+        if tracking_number.is_some() && courier.is_none() {
+            return Err(DomainError::Validation(
+                "A tracking number needs a courier".to_string(),
+            ));
+        }
+
+        if tracking_number != self.tracking_number {
+            self.shipment_status = None;
+            self.shipment_updated_at = None;
+        }
+        self.courier = courier;
+        self.tracking_number = tracking_number;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Returns true while the courier should still be asked where the
+    /// shipment is: it has a tracking number and has neither been
+    /// delivered nor checked in.
+    pub fn is_in_transit(&self) -> bool {
+        self.tracking_number.is_some()
+            && self.shipment_status != Some(ShipmentStatus::Delivered)
+            && self.received_at.is_none()
+    }
+
+    /// Records a status reported by the courier. Returns true if the
+    /// status changed.
+    pub fn track(&mut self, status: ShipmentStatus, now: DateTime<Utc>) -> Result<bool, DomainError> {
+        if self.tracking_number.is_none() {
+            return Err(DomainError::Validation(format!(
+                "Transfer {} has no tracking number",
+                self.id
+            )));
+        }
+        if self.shipment_status == Some(status) {
+            return Ok(false);
+        }
+
+        self.shipment_status = Some(status);
+        self.shipment_updated_at = Some(now);
+        self.updated_at = now;
+        Ok(true)
+    }
+
+    /// Records what the recipient found for some of the items.
+    ///
+    /// Every receipt must be for an item sent, QC can only be recorded for
+    /// items that arrived, and failing QC needs a note saying why. Nothing
+    /// is recorded if any receipt is refused. Receipts can be recorded
+    /// again to correct them. Once every item is checked in, the transfer
+    /// is marked received by `received_by`.
+    pub fn receive(
+        &mut self,
+        receipts: &[TransferReceipt],
+        received_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        for receipt in receipts {
+            if self.item(receipt.item_type, receipt.entity_id).is_none() {
+                return Err(DomainError::Validation(format!(
+                    "{} {} is not part of this transfer",
+                    receipt.item_type, receipt.entity_id
+                )));
+            }
+            if !receipt.received && receipt.qc_passed.is_some() {
+                return Err(DomainError::Validation(format!(
+                    "{} {} did not arrive and cannot be QC'd",
+                    receipt.item_type, receipt.entity_id
+                )));
+            }
+            if receipt.qc_passed == Some(false) && trimmed(&receipt.qc_note).is_none() {
+                return Err(DomainError::Validation(format!(
+                    "A note is required when {} {} fails QC",
+                    receipt.item_type, receipt.entity_id
+                )));
+            }
+        }
+
+        for receipt in receipts {
+            if let Some(item) = self
+                .items
+                .iter_mut()
+                .find(|i| i.item_type == receipt.item_type && i.entity_id == receipt.entity_id)
+            {
+                item.received = Some(receipt.received);
+                item.qc_passed = receipt.qc_passed;
+                item.qc_note = trimmed(&receipt.qc_note);
+            }
+        }
+
+        if self.received_at.is_none() && self.is_received() {
+            self.received_by = Some(received_by.to_string());
+            self.received_at = Some(now);
+        }
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+fn trimmed(note: &Option<String>) -> Option<String> {
+    note.as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(entity_id: EntityId, received: bool, qc_passed: Option<bool>) -> TransferReceipt {
+        TransferReceipt {
+            item_type: TransferItemType::Sample,
+            entity_id,
+            received,
+            qc_passed,
+            qc_note: None,
+        }
+    }
+
+    #[test]
+    fn test_new_transfer_checks_parties_and_items() {
+        let now = Utc::now();
+        let items = [(TransferItemType::Sample, 1), (TransferItemType::Sample, 1)];
+
+        assert!(Transfer::new("Lab A", " lab a ", &items, now, "tech".to_string()).is_err());
+        assert!(Transfer::new("Lab A", "Lab B", &[], now, "tech".to_string()).is_err());
+
+        let transfer = Transfer::new(" Lab A", "Lab B", &items, now, "tech".to_string()).unwrap();
+        assert_eq!(transfer.sender, "Lab A");
+        assert_eq!(transfer.items.len(), 1);
+        assert!(!transfer.is_received());
+    }
+
+    #[test]
+    fn test_receipt_marks_transfer_received_once_all_items_checked() {
+        let now = Utc::now();
+        let items = [(TransferItemType::Sample, 1), (TransferItemType::Sample, 2)];
+        let mut transfer = Transfer::new("Lab A", "Lab B", &items, now, "tech".to_string()).unwrap();
+
+        assert!(transfer.receive(&[receipt(3, true, None)], "recv", now).is_err());
+        assert!(transfer.receive(&[receipt(1, false, Some(true))], "recv", now).is_err());
+        assert!(transfer
+            .receive(&[receipt(1, true, None), receipt(2, true, Some(false))], "recv", now)
+            .is_err());
+        assert_eq!(transfer.items[0].received, None);
+
+        transfer.receive(&[receipt(1, true, Some(true))], "recv", now).unwrap();
+        assert!(transfer.received_at.is_none());
+
+        let mut failed = receipt(2, true, Some(false));
+        failed.qc_note = Some("Tube cracked".to_string());
+        transfer.receive(&[failed], "recv", now).unwrap();
+        assert!(transfer.is_received());
+        assert_eq!(transfer.received_by.as_deref(), Some("recv"));
+        assert_eq!(transfer.items[1].qc_note.as_deref(), Some("Tube cracked"));
+    }
+
+    #[test]
+    fn test_tracking_follows_tracking_number() {
+        let now = Utc::now();
+        let items = [(TransferItemType::Sample, 1)];
+        let mut transfer = Transfer::new("Lab A", "Lab B", &items, now, "tech".to_string()).unwrap();
+
+        assert!(transfer.track(ShipmentStatus::InTransit, now).is_err());
+        assert!(transfer
+            .set_shipment(None, Some("1Z999".to_string()), now)
+            .is_err());
+
+        transfer
+            .set_shipment(Some(" UPS ".to_string()), Some("1Z999".to_string()), now)
+            .unwrap();
+        assert_eq!(transfer.courier.as_deref(), Some("UPS"));
+        assert!(transfer.is_in_transit());
+        assert!(transfer.track(ShipmentStatus::OutForDelivery, now).unwrap());
+        assert!(!transfer.track(ShipmentStatus::OutForDelivery, now).unwrap());
+
+        // A new tracking number starts over
+        transfer
+            .set_shipment(Some("UPS".to_string()), Some("1Z998".to_string()), now)
+            .unwrap();
+        assert_eq!(transfer.shipment_status, None);
+        assert!(transfer.track(ShipmentStatus::Delivered, now).unwrap());
+        assert!(!transfer.is_in_transit());
+
+        assert_eq!(
+            "Out For Delivery".parse::<ShipmentStatus>(),
+            Ok(ShipmentStatus::OutForDelivery)
+        );
+    }
+}
@@ -71,6 +71,24 @@ impl std::fmt::Display for Role {
     }
 }
 
+impl std::str::FromStr for Role {
+    type Err = crate::errors::DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(Self::Viewer),
+            "technician" => Ok(Self::Technician),
+            "lab_manager" => Ok(Self::LabManager),
+            "admin" => Ok(Self::Admin),
+            "super_admin" => Ok(Self::SuperAdmin),
+            other => Err(crate::errors::DomainError::Validation(format!(
+                "Unknown role: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// A user in the system.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct User {
@@ -86,7 +104,7 @@ pub struct User {
     pub role: Role,
     /// Is the user account active?
     pub active: bool,
-    /// Is this an internal (local) or external (LDAP) user?
+    /// Is this an internal (local) or external (LDAP or single sign-on) user?
     pub internal: bool,
     /// When the user was created
     pub created_at: DateTime<Utc>,
@@ -127,6 +145,18 @@ impl User {
         display_name: String,
         email: String,
         role: Role,
+    ) -> Self {
+        Self::new_external(id, username, display_name, email, role)
+    }
+
+    /// Creates a new external user, whose logins are checked by a directory
+    /// or single sign-on provider rather than a password kept here.
+    pub fn new_external(
+        id: EntityId,
+        username: String,
+        display_name: String,
+        email: String,
+        role: Role,
     ) -> Self {
         let mut user = Self::new_internal(id, username, display_name, email, role);
         user.internal = false;
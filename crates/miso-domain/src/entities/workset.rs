@@ -0,0 +1,159 @@
+//! Workset entity - a batch of samples and libraries taken through a
+//! workflow step together.
+//!
+//! Technicians build a workset at the bench, usually from a rack scan, and
+//! then act on all its members at once instead of one tube at a time.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::EntityId;
+
+/// Kind of item in a workset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorksetItemType {
+    Sample,
+    Library,
+}
+
+impl WorksetItemType {
+    /// Returns the code stored for the type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sample => "sample",
+            Self::Library => "library",
+        }
+    }
+}
+
+impl fmt::Display for WorksetItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WorksetItemType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sample" => Ok(Self::Sample),
+            "library" => Ok(Self::Library),
+            other => Err(format!("Unknown workset item type: {}", other)),
+        }
+    }
+}
+
+/// A sample or library in a workset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorksetItem {
+    pub item_type: WorksetItemType,
+    pub entity_id: EntityId,
+    pub added_at: DateTime<Utc>,
+}
+
+/// A named batch of samples and libraries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workset {
+    pub id: EntityId,
+    /// Unique name, e.g. "Extraction 2024-12-16"
+    pub name: String,
+    pub description: Option<String>,
+    /// Members, in the order they were added
+    pub items: Vec<WorksetItem>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Workset {
+    /// Creates an empty workset.
+    pub fn new(name: String, description: Option<String>, created_by: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            name,
+            description,
+            items: Vec::new(),
+            created_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Returns true if an entity is a member.
+    pub fn contains(&self, item_type: WorksetItemType, entity_id: EntityId) -> bool {
+        self.items
+            .iter()
+            .any(|i| i.item_type == item_type && i.entity_id == entity_id)
+    }
+
+    /// Adds an entity, returning false if it was already a member.
+    pub fn add(&mut self, item_type: WorksetItemType, entity_id: EntityId, now: DateTime<Utc>) -> bool {
+        if self.contains(item_type, entity_id) {
+            return false;
+        }
+        self.items.push(WorksetItem {
+            item_type,
+            entity_id,
+            added_at: now,
+        });
+        self.updated_at = now;
+        true
+    }
+
+    /// Removes an entity, returning false if it wasn't a member.
+    pub fn remove(&mut self, item_type: WorksetItemType, entity_id: EntityId, now: DateTime<Utc>) -> bool {
+        let count = self.items.len();
+        self.items
+            .retain(|i| i.item_type != item_type || i.entity_id != entity_id);
+        if self.items.len() == count {
+            return false;
+        }
+        self.updated_at = now;
+        true
+    }
+
+    /// Returns the IDs of the members of a type, in the order added.
+    pub fn ids(&self, item_type: WorksetItemType) -> Vec<EntityId> {
+        self.items
+            .iter()
+            .filter(|i| i.item_type == item_type)
+            .map(|i| i.entity_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_members_are_added_once() {
+        let now = Utc::now();
+        let mut workset = Workset::new("Bench 1".to_string(), None, "tech".to_string());
+
+        assert!(workset.add(WorksetItemType::Sample, 3, now));
+        assert!(workset.add(WorksetItemType::Library, 3, now));
+        assert!(workset.add(WorksetItemType::Sample, 1, now));
+        assert!(!workset.add(WorksetItemType::Sample, 3, now));
+        assert_eq!(workset.ids(WorksetItemType::Sample), vec![3, 1]);
+
+        assert!(workset.remove(WorksetItemType::Sample, 3, now));
+        assert!(!workset.remove(WorksetItemType::Sample, 3, now));
+        assert!(workset.contains(WorksetItemType::Library, 3));
+        assert_eq!(workset.items.len(), 2);
+    }
+
+    #[test]
+    fn test_item_type_codes_round_trip() {
+        for item_type in [WorksetItemType::Sample, WorksetItemType::Library] {
+            assert_eq!(item_type.as_str().parse::<WorksetItemType>(), Ok(item_type));
+        }
+        assert!("pool".parse::<WorksetItemType>().is_err());
+    }
+}
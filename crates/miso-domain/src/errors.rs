@@ -5,6 +5,9 @@
 
 use thiserror::Error;
 
+use crate::entities::LibraryDesign;
+use crate::services::KitCombination;
+
 /// The root error type for all domain operations.
 #[derive(Debug, Error)]
 pub enum DomainError {
@@ -39,6 +42,19 @@ pub enum DomainError {
         value: String,
     },
 
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Concurrent modification: {entity_type} with id {id} was changed by another user")]
+    ConcurrentModification { entity_type: String, id: String },
+
+    #[error("Project {project} is locked: {reason}")]
+    ProjectLocked {
+        project: String,
+        reason: String,
+        unlock_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+
     #[error("Invalid state transition: cannot transition {entity} from {from} to {to}")]
     InvalidStateTransition {
         entity: String,
@@ -90,13 +106,29 @@ pub enum LibraryError {
     #[error("Library {0} has already been exhausted (no volume remaining)")]
     Exhausted(String),
 
-    #[error("Invalid kit type: {0}")]
-    InvalidKitType(String),
+    #[error(
+        "Kit {kit} cannot prepare {design} libraries for {platform}; it can prepare {}",
+        list(allowed)
+    )]
+    InvalidKitType {
+        kit: String,
+        design: LibraryDesign,
+        platform: String,
+        allowed: Vec<KitCombination>,
+    },
 
     #[error("Library {0} is already in pool {1}")]
     AlreadyPooled(String, String),
 }
 
+fn list(combinations: &[KitCombination]) -> String {
+    combinations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Errors specific to Pool operations.
 #[derive(Debug, Error)]
 pub enum PoolError {
@@ -144,6 +176,15 @@ pub enum RunError {
 
     #[error("Run {0} is missing required QC metrics")]
     MissingQcMetrics(String),
+
+    #[error("Run {0} is {1} and cannot be signed off for QC")]
+    NotReadyForQc(String, String),
+
+    #[error("Run {0} is {1} and cannot be loaded")]
+    NotLoadable(String, String),
+
+    #[error("Run {run} is not being loaded as planned: {}", .mismatches.join("; "))]
+    LoadMismatch { run: String, mismatches: Vec<String> },
 }
 
 /// Errors specific to Box/Storage operations.
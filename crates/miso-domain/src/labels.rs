@@ -0,0 +1,74 @@
+//! Label Printing Traits - interfaces for printing specimen labels.
+//!
+//! Like run planners, label printers are implemented in the infrastructure
+//! layer (Zebra ZPL printers) so that application code can print a label
+//! and keep track of the prints without knowing how they are made.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::EntityId;
+use crate::errors::DomainError;
+
+/// Name of the sample tube label template.
+pub const SAMPLE_LABEL: &str = "sample";
+
+/// A label to print for an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    /// Name of the template the label is laid out with
+    pub template: String,
+    /// Type of the entity labelled, e.g. "Sample"
+    pub entity_type: String,
+    pub entity_id: EntityId,
+    /// Barcode printed on the label
+    pub barcode: String,
+    /// Lines of text printed above the barcode, in order
+    pub lines: Vec<String>,
+}
+
+impl Label {
+    /// Creates a sample tube label, with the sample's name and project.
+    pub fn sample(id: EntityId, barcode: &str, name: &str, project_code: &str) -> Self {
+        Self {
+            template: SAMPLE_LABEL.to_string(),
+            entity_type: "Sample".to_string(),
+            entity_id: id,
+            barcode: barcode.to_string(),
+            lines: vec![name.to_string(), project_code.to_string()],
+        }
+    }
+}
+
+/// Labels used by a print job, for consumable tracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrintedJob {
+    /// Labels sent to the printer
+    pub labels: u32,
+    /// The printer's lifetime label count as the job started, if read
+    pub odometer: Option<u64>,
+}
+
+/// Prints labels.
+#[async_trait]
+pub trait LabelPrinter: Send + Sync {
+    /// Names the printer, e.g. by its address, in records of its jobs.
+    fn name(&self) -> String;
+
+    /// Prints one copy of a label.
+    async fn print(&self, label: &Label) -> Result<(), DomainError>;
+
+    /// Prints one copy of each label as a single job.
+    ///
+    /// Printers that can check what stock is loaded should refuse a job it
+    /// doesn't fit rather than waste it.
+    async fn print_job(&self, labels: &[Label]) -> Result<PrintedJob, DomainError> {
+        for label in labels {
+            self.print(label).await?;
+        }
+        Ok(PrintedJob {
+            labels: labels.len() as u32,
+            odometer: None,
+        })
+    }
+}
@@ -11,12 +11,26 @@
 //! - **Value Objects**: Immutable objects defined by their attributes (Barcode, Concentration)
 //! - **Repository Traits**: Interfaces for data persistence (implemented in infrastructure)
 //! - **Domain Services**: Business logic that doesn't belong to a single entity
+//! - **Notification Traits**: Interfaces for alerting lab staff (implemented in infrastructure)
+//! - **Plugin Traits**: Extension points for site-specific rules (implemented by site crates)
+//! - **Run Planning Traits**: Interfaces for sending sample sheets to sequencers (implemented in infrastructure)
+//! - **Label Printing Traits**: Interfaces for printing specimen labels (implemented in infrastructure)
+//! - **Run Import Traits**: Interfaces for pulling finished runs from instrument cloud services (implemented in infrastructure)
+//! - **Attachment Storage Traits**: Interfaces for storing files uploaded against samples, libraries and runs (implemented in infrastructure)
+//! - **Shipment Tracking Traits**: Interfaces for following couriered transfers (implemented in infrastructure)
 //! - **Domain Errors**: Semantic errors representing domain rule violations
 
+pub mod attachments;
 pub mod entities;
 pub mod errors;
+pub mod labels;
+pub mod notifications;
+pub mod plugins;
 pub mod repositories;
+pub mod run_import;
+pub mod run_planning;
 pub mod services;
+pub mod shipments;
 pub mod value_objects;
 
 // Re-export commonly used types
@@ -0,0 +1,57 @@
+//! Notification Traits - interfaces for alerting lab staff.
+//!
+//! Like repositories, notifiers are implemented in the infrastructure layer
+//! (logging, email, chat) so that domain and application code can raise
+//! alerts without knowing how they are delivered.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::Role;
+use crate::errors::DomainError;
+
+/// A message for every user holding a given role, and for any other
+/// addresses it names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Role whose holders should receive the message
+    pub audience: Role,
+    /// Further email addresses to send the message to, e.g. a transfer's
+    /// recipient
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// One-line summary
+    pub subject: String,
+    /// Full message text
+    pub body: String,
+}
+
+impl Notification {
+    /// Creates a notification for holders of `audience`.
+    pub fn new(audience: Role, subject: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            audience,
+            addresses: Vec::new(),
+            subject: subject.into(),
+            body: body.into(),
+        }
+    }
+
+    /// Creates a notification for lab managers.
+    pub fn lab_managers(subject: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::new(Role::LabManager, subject, body)
+    }
+
+    /// Also sends the notification to an email address.
+    pub fn with_address(mut self, address: impl Into<String>) -> Self {
+        self.addresses.push(address.into());
+        self
+    }
+}
+
+/// Delivers notifications to users.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Sends a notification.
+    async fn notify(&self, notification: &Notification) -> Result<(), DomainError>;
+}
@@ -0,0 +1,153 @@
+//! Plugin Traits - extension points for site-specific rules.
+//!
+//! Every institute has rules of its own: a naming convention, a field that
+//! must be filled for one department, a LIMS or billing system to tell when
+//! something changes. Site crates implement these traits and register them
+//! at startup, and the application services call them, so such rules need
+//! no changes to the core services.
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::entities::{EntityId, Library, Project, QcRecord, Sample, StorableItem};
+use crate::errors::DomainError;
+
+/// Whether an entity is being created or changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// The entity is new and has no ID yet
+    Create,
+    /// The entity exists and is being changed
+    Update,
+}
+
+/// Checks entities against site-specific rules before they are saved.
+///
+/// Each method receives the entity as it will be saved. Returning an error
+/// rejects the save; `DomainError::Validation` is reported to the client as
+/// a bad request. Unimplemented methods accept everything.
+#[async_trait]
+pub trait ValidationHook: Send + Sync {
+    /// Checks a project.
+    async fn validate_project(
+        &self,
+        _operation: Operation,
+        _project: &Project,
+    ) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    /// Checks a sample.
+    async fn validate_sample(
+        &self,
+        _operation: Operation,
+        _sample: &Sample,
+    ) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    /// Checks a library.
+    async fn validate_library(
+        &self,
+        _operation: Operation,
+        _library: &Library,
+    ) -> Result<(), DomainError> {
+        Ok(())
+    }
+}
+
+/// Names new entities by a site's convention.
+///
+/// Each method receives the entity with the name the user asked for and
+/// returns the name to save it under, or `None` to keep the requested one.
+/// Library names are still checked for uniqueness afterwards.
+#[async_trait]
+pub trait NamingHook: Send + Sync {
+    /// Names a new sample.
+    async fn sample_name(&self, _sample: &Sample) -> Result<Option<String>, DomainError> {
+        Ok(None)
+    }
+
+    /// Names a new library made from `sample`.
+    async fn library_name(
+        &self,
+        _library: &Library,
+        _sample: &Sample,
+    ) -> Result<Option<String>, DomainError> {
+        Ok(None)
+    }
+}
+
+/// Something that happened to an entity, after it was saved.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", content = "entity", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// A project was created
+    ProjectCreated(Project),
+    /// A project was changed
+    ProjectUpdated(Project),
+    /// The project with this ID was deleted
+    ProjectDeleted(EntityId),
+    /// A sample was created
+    SampleCreated(Sample),
+    /// A sample was changed, including by bulk update
+    SampleUpdated(Sample),
+    /// The sample with this ID was deleted
+    SampleDeleted(EntityId),
+    /// A library was created
+    LibraryCreated(Library),
+    /// A library was changed, indexed or archived
+    LibraryUpdated(Library),
+    /// A QC measurement of a sample, library or pool was recorded
+    QcRecorded(QcRecord),
+    /// An item was placed or moved into a box
+    ItemStored {
+        item: StorableItem,
+        box_id: EntityId,
+        box_name: String,
+        position: String,
+    },
+    /// An item was taken out of its box
+    ItemRemoved(StorableItem),
+}
+
+impl DomainEvent {
+    /// The event's name, e.g. `sample_created`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ProjectCreated(_) => "project_created",
+            Self::ProjectUpdated(_) => "project_updated",
+            Self::ProjectDeleted(_) => "project_deleted",
+            Self::SampleCreated(_) => "sample_created",
+            Self::SampleUpdated(_) => "sample_updated",
+            Self::SampleDeleted(_) => "sample_deleted",
+            Self::LibraryCreated(_) => "library_created",
+            Self::LibraryUpdated(_) => "library_updated",
+            Self::QcRecorded(_) => "qc_recorded",
+            Self::ItemStored { .. } => "item_stored",
+            Self::ItemRemoved(_) => "item_removed",
+        }
+    }
+
+    /// Returns false for events about control samples and libraries, which
+    /// billing subscribers shouldn't charge projects for.
+    pub fn is_billable(&self) -> bool {
+        match self {
+            Self::SampleCreated(sample) | Self::SampleUpdated(sample) => !sample.is_control(),
+            Self::LibraryCreated(library) | Self::LibraryUpdated(library) => !library.is_control(),
+            _ => true,
+        }
+    }
+}
+
+/// Reacts to entities being saved.
+///
+/// Events are delivered after the change is committed, so a subscriber
+/// cannot undo it; an error is logged and the remaining subscribers still
+/// run.
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    /// Handles an event.
+    async fn on_event(&self, event: &DomainEvent) -> Result<(), DomainError>;
+}
@@ -8,6 +8,7 @@
 
 use crate::entities::*;
 use crate::errors::DomainError;
+use crate::value_objects::QcStatus;
 use async_trait::async_trait;
 
 /// Common query options for listing entities.
@@ -60,6 +61,27 @@ impl QueryOptions {
     }
 }
 
+/// A versioned write rejected because the stored row has moved on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    /// ID of the entity
+    pub id: EntityId,
+    /// Version the writer expected
+    pub expected_version: i32,
+    /// Version currently stored, or `None` if the entity no longer exists
+    pub current_version: Option<i32>,
+}
+
+/// A sample to insert as part of a batch.
+#[derive(Debug, Clone)]
+pub struct NewSample {
+    /// The sample; its ID is assigned on insert
+    pub sample: Sample,
+    /// Position in the batch of an earlier sample that is this one's
+    /// parent, for parents inserted alongside their children
+    pub parent_index: Option<usize>,
+}
+
 /// Repository for Project entities.
 #[async_trait]
 pub trait ProjectRepository: Send + Sync {
@@ -72,6 +94,9 @@ pub trait ProjectRepository: Send + Sync {
     /// Lists all projects.
     async fn list(&self, options: QueryOptions) -> Result<Vec<Project>, DomainError>;
 
+    /// Finds up to `limit` projects whose code or name contains `term`.
+    async fn search(&self, term: &str, limit: u64) -> Result<Vec<Project>, DomainError>;
+
     /// Saves a project (insert or update).
     async fn save(&self, project: &Project) -> Result<EntityId, DomainError>;
 
@@ -82,6 +107,41 @@ pub trait ProjectRepository: Send + Sync {
     async fn count(&self) -> Result<u64, DomainError>;
 }
 
+/// Repository for project memberships.
+#[async_trait]
+pub trait ProjectMemberRepository: Send + Sync {
+    /// Lists a project's members, oldest first.
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<ProjectMember>, DomainError>;
+
+    /// Lists the IDs of the projects a user belongs to.
+    async fn find_projects(&self, user_id: EntityId) -> Result<Vec<EntityId>, DomainError>;
+
+    /// Adds a member; fails with [`DomainError::Duplicate`] if the user
+    /// already belongs to the project.
+    async fn add(&self, member: &ProjectMember) -> Result<(), DomainError>;
+
+    /// Removes a member.
+    async fn remove(&self, project_id: EntityId, user_id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for the subprojects that partition projects' samples.
+#[async_trait]
+pub trait SubprojectRepository: Send + Sync {
+    /// Finds a subproject by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Subproject>, DomainError>;
+
+    /// Lists a project's subprojects by alias.
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Subproject>, DomainError>;
+
+    /// Saves a subproject (insert or update); fails with
+    /// [`DomainError::Duplicate`] if the project has another subproject
+    /// with the same alias.
+    async fn save(&self, subproject: &Subproject) -> Result<EntityId, DomainError>;
+
+    /// Deletes a subproject. Its samples stay in the project, unassigned.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
 /// Repository for Sample entities.
 #[async_trait]
 pub trait SampleRepository: Send + Sync {
@@ -91,6 +151,9 @@ pub trait SampleRepository: Send + Sync {
     /// Finds a sample by barcode.
     async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Sample>, DomainError>;
 
+    /// Finds samples by barcodes (batch lookup).
+    async fn find_by_barcodes(&self, barcodes: &[String]) -> Result<Vec<Sample>, DomainError>;
+
     /// Finds samples by project.
     async fn find_by_project(
         &self,
@@ -98,20 +161,133 @@ pub trait SampleRepository: Send + Sync {
         options: QueryOptions,
     ) -> Result<Vec<Sample>, DomainError>;
 
+    /// Finds the samples assigned to a subproject.
+    async fn find_by_subproject(
+        &self,
+        subproject_id: EntityId,
+        options: QueryOptions,
+    ) -> Result<Vec<Sample>, DomainError>;
+
     /// Finds samples by parent (for detailed hierarchy).
     async fn find_by_parent(&self, parent_id: EntityId) -> Result<Vec<Sample>, DomainError>;
 
+    /// Finds every sample of a class, archived ones included.
+    async fn find_by_class(&self, class: &SampleClass) -> Result<Vec<Sample>, DomainError>;
+
     /// Lists samples with optional filtering.
     async fn list(&self, options: QueryOptions) -> Result<Vec<Sample>, DomainError>;
 
+    /// Finds up to `limit` samples whose name, barcode or external name
+    /// contains `term`.
+    async fn search(&self, term: &str, limit: u64) -> Result<Vec<Sample>, DomainError>;
+
+    /// Finds samples by IDs (batch load).
+    async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError>;
+
     /// Saves a sample (insert or update).
     async fn save(&self, sample: &Sample) -> Result<EntityId, DomainError>;
 
+    /// Inserts new samples in one transaction, in order, returning their
+    /// IDs.
+    ///
+    /// A sample whose parent is earlier in the batch is given that
+    /// sample's new ID as its parent. If any insert fails, nothing is
+    /// written.
+    async fn insert_all(&self, samples: &[NewSample]) -> Result<Vec<EntityId>, DomainError>;
+
+    /// Updates samples in one transaction, guarded by each sample's version.
+    ///
+    /// Each row is written only if its stored version still equals
+    /// `sample.version`, and the stored version is then incremented. If any
+    /// row fails that check, nothing is written and the conflicts are
+    /// returned.
+    async fn update_all_versioned(
+        &self,
+        samples: &[Sample],
+    ) -> Result<Vec<VersionConflict>, DomainError>;
+
     /// Deletes a sample.
     async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 
-    /// Counts samples in a project.
+    /// Counts samples in a project, leaving out controls.
     async fn count_by_project(&self, project_id: EntityId) -> Result<u64, DomainError>;
+
+    /// Finds samples whose QC has failed and that were last modified at or
+    /// after `since`.
+    async fn find_qc_failed_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Sample>, DomainError>;
+
+    /// Counts samples received on each day from `since` onwards, oldest
+    /// first. Days with no samples are omitted.
+    ///
+    /// Samples without a receipt time are counted on the day they were
+    /// created.
+    async fn count_received_by_day(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, u64)>, DomainError>;
+
+    /// Counts unarchived samples by QC status. Statuses with no samples
+    /// are omitted.
+    async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError>;
+}
+
+/// Repository for the sample list read model and its counts.
+#[async_trait]
+pub trait SampleListRepository: Send + Sync {
+    /// Finds a sample's row.
+    async fn find(&self, sample_id: EntityId) -> Result<Option<SampleListRow>, DomainError>;
+
+    /// Saves a sample's row (insert or replace), adjusting the counts to
+    /// match in the same transaction.
+    async fn save(&self, row: &SampleListRow) -> Result<(), DomainError>;
+
+    /// Removes a sample's row, adjusting the counts to match.
+    async fn delete(&self, sample_id: EntityId) -> Result<(), DomainError>;
+
+    /// Updates the project code and name on every row of a project.
+    async fn rename_project(
+        &self,
+        project_id: EntityId,
+        code: &str,
+        name: &str,
+    ) -> Result<(), DomainError>;
+
+    /// Lists rows matching a filter.
+    async fn list(
+        &self,
+        filter: &SampleListFilter,
+        options: QueryOptions,
+    ) -> Result<Vec<SampleListRow>, DomainError>;
+
+    /// Returns the counts of unarchived samples by QC status, of one
+    /// project or all.
+    async fn counts(
+        &self,
+        project_id: Option<EntityId>,
+    ) -> Result<Vec<SampleListCount>, DomainError>;
+
+    /// Rebuilds every row and count from the samples, projects, QC
+    /// results and boxes, returning the number of rows.
+    async fn rebuild(&self) -> Result<u64, DomainError>;
+}
+
+/// Repository for the index set catalog.
+#[async_trait]
+pub trait IndexSetRepository: Send + Sync {
+    /// Finds an index set by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<IndexSet>, DomainError>;
+
+    /// Finds an index set by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<IndexSet>, DomainError>;
+
+    /// Lists all index sets by name.
+    async fn list(&self) -> Result<Vec<IndexSet>, DomainError>;
+
+    /// Saves an index set (insert or update).
+    async fn save(&self, set: &IndexSet) -> Result<EntityId, DomainError>;
 }
 
 /// Repository for Library entities.
@@ -123,6 +299,9 @@ pub trait LibraryRepository: Send + Sync {
     /// Finds a library by barcode.
     async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Library>, DomainError>;
 
+    /// Finds a library by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<Library>, DomainError>;
+
     /// Finds libraries by sample.
     async fn find_by_sample(&self, sample_id: EntityId) -> Result<Vec<Library>, DomainError>;
 
@@ -139,10 +318,81 @@ pub trait LibraryRepository: Send + Sync {
     /// Saves a library (insert or update).
     async fn save(&self, library: &Library) -> Result<EntityId, DomainError>;
 
+    /// Inserts new libraries in one transaction, returning their IDs. If
+    /// any insert fails, nothing is written.
+    async fn insert_all(&self, libraries: &[Library]) -> Result<Vec<EntityId>, DomainError>;
+
+    /// Inserts a library and saves the sample its input was withdrawn from
+    /// in one transaction, returning the library's ID. The sample is saved
+    /// only if its version is unchanged; otherwise nothing is written.
+    async fn create_from_sample(
+        &self,
+        library: &Library,
+        sample: &Sample,
+    ) -> Result<EntityId, DomainError>;
+
     /// Deletes a library.
     async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 }
 
+/// Repository for library aliquots.
+#[async_trait]
+pub trait LibraryAliquotRepository: Send + Sync {
+    /// Finds an aliquot by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<LibraryAliquot>, DomainError>;
+
+    /// Finds an aliquot by barcode.
+    async fn find_by_barcode(&self, barcode: &str)
+        -> Result<Option<LibraryAliquot>, DomainError>;
+
+    /// Finds the aliquots of a library, oldest first.
+    async fn find_by_library(&self, library_id: EntityId)
+        -> Result<Vec<LibraryAliquot>, DomainError>;
+
+    /// Inserts an aliquot and saves the library it was withdrawn from in
+    /// one transaction, returning the aliquot's ID.
+    async fn create(
+        &self,
+        aliquot: &LibraryAliquot,
+        library: &Library,
+    ) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for targeted panels, each version stored separately.
+#[async_trait]
+pub trait PanelRepository: Send + Sync {
+    /// Finds a panel version by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Panel>, DomainError>;
+
+    /// Finds panel versions by IDs (batch load).
+    async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Panel>, DomainError>;
+
+    /// Finds the latest version of a panel by name.
+    async fn find_latest(&self, name: &str) -> Result<Option<Panel>, DomainError>;
+
+    /// Lists all versions of all panels, by name then version.
+    async fn list(&self) -> Result<Vec<Panel>, DomainError>;
+
+    /// Saves a panel version (insert or update).
+    async fn save(&self, panel: &Panel) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for the reference genome registry.
+#[async_trait]
+pub trait ReferenceGenomeRepository: Send + Sync {
+    /// Finds a reference genome by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ReferenceGenome>, DomainError>;
+
+    /// Finds a reference genome by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<ReferenceGenome>, DomainError>;
+
+    /// Lists all reference genomes by name.
+    async fn list(&self) -> Result<Vec<ReferenceGenome>, DomainError>;
+
+    /// Saves a reference genome (insert or update).
+    async fn save(&self, genome: &ReferenceGenome) -> Result<EntityId, DomainError>;
+}
+
 /// Repository for Pool entities.
 #[async_trait]
 pub trait PoolRepository: Send + Sync {
@@ -180,6 +430,9 @@ pub trait RunRepository: Send + Sync {
     /// Finds runs by status.
     async fn find_by_status(&self, status: RunStatus) -> Result<Vec<Run>, DomainError>;
 
+    /// Finds runs with the pool loaded on any of their partitions.
+    async fn find_by_pool(&self, pool_id: EntityId) -> Result<Vec<Run>, DomainError>;
+
     /// Lists runs with optional filtering.
     async fn list(&self, options: QueryOptions) -> Result<Vec<Run>, DomainError>;
 
@@ -190,6 +443,117 @@ pub trait RunRepository: Send + Sync {
     async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 }
 
+/// Repository for demultiplexing metrics reported against runs.
+#[async_trait]
+pub trait RunMetricsRepository: Send + Sync {
+    /// Finds all metrics for a run.
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<LibraryRunMetrics>, DomainError>;
+
+    /// Finds all metrics for a library across runs.
+    async fn find_by_library(
+        &self,
+        library_id: EntityId,
+    ) -> Result<Vec<LibraryRunMetrics>, DomainError>;
+
+    /// Saves a batch of metrics atomically.
+    ///
+    /// Existing metrics for the same run/partition/library are replaced, so
+    /// pipelines can safely resubmit after a re-demultiplex.
+    async fn save_all(&self, metrics: &[LibraryRunMetrics]) -> Result<(), DomainError>;
+}
+
+/// Repository for QC reports attached to runs.
+#[async_trait]
+pub trait QcReportRepository: Send + Sync {
+    /// Finds the QC report attached to a run.
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Option<RunQcReport>, DomainError>;
+
+    /// Saves a run's QC report, replacing any previously attached report.
+    async fn save(&self, report: &RunQcReport) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for the run data location registry.
+#[async_trait]
+pub trait DataLocationRepository: Send + Sync {
+    /// Finds a data location by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<DataLocation>, DomainError>;
+
+    /// Finds the data locations of a run, lane-level ones included.
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<DataLocation>, DomainError>;
+
+    /// Lists all data locations that have not been purged.
+    async fn list_unpurged(&self) -> Result<Vec<DataLocation>, DomainError>;
+
+    /// Saves a data location (insert or update).
+    async fn save(&self, location: &DataLocation) -> Result<EntityId, DomainError>;
+
+    /// Deletes a data location.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for the places projects release their data to.
+#[async_trait]
+pub trait DeliveryTargetRepository: Send + Sync {
+    /// Finds a delivery target by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<DeliveryTarget>, DomainError>;
+
+    /// Lists a project's delivery targets, oldest first.
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<DeliveryTarget>, DomainError>;
+
+    /// Saves a delivery target (insert or update).
+    async fn save(&self, target: &DeliveryTarget) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for the record of runs' data delivered to projects.
+#[async_trait]
+pub trait DeliveryRepository: Send + Sync {
+    /// Finds a delivery by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Delivery>, DomainError>;
+
+    /// Lists a project's deliveries, newest first.
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Delivery>, DomainError>;
+
+    /// Lists the deliveries of a run's data, newest first.
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<Delivery>, DomainError>;
+
+    /// Saves a delivery (insert or update).
+    async fn save(&self, delivery: &Delivery) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for instrument-reported run events.
+#[async_trait]
+pub trait InstrumentEventRepository: Send + Sync {
+    /// Finds the events reported for a run, in the order they occurred.
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<InstrumentEvent>, DomainError>;
+
+    /// Replaces the events read from one source file of a run.
+    async fn replace_for_source(
+        &self,
+        run_id: EntityId,
+        source: &str,
+        events: &[InstrumentEvent],
+    ) -> Result<(), DomainError>;
+}
+
+/// Repository for export templates.
+#[async_trait]
+pub trait ExportTemplateRepository: Send + Sync {
+    /// Finds a template by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ExportTemplate>, DomainError>;
+
+    /// Finds a template by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<ExportTemplate>, DomainError>;
+
+    /// Lists all templates.
+    async fn list(&self) -> Result<Vec<ExportTemplate>, DomainError>;
+
+    /// Saves a template (insert or update).
+    async fn save(&self, template: &ExportTemplate) -> Result<EntityId, DomainError>;
+
+    /// Deletes a template.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
 /// Repository for Sequencer entities.
 #[async_trait]
 pub trait SequencerRepository: Send + Sync {
@@ -205,8 +569,80 @@ pub trait SequencerRepository: Send + Sync {
     /// Finds available sequencers.
     async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError>;
 
-    /// Saves a sequencer (insert or update).
+    /// Saves a sequencer (insert or update); fails with
+    /// [`DomainError::Duplicate`] if another sequencer has the same name.
     async fn save(&self, sequencer: &Sequencer) -> Result<EntityId, DomainError>;
+
+    /// Deletes a sequencer and its planned maintenance.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for planned sequencer and facility maintenance.
+#[async_trait]
+pub trait MaintenanceWindowRepository: Send + Sync {
+    /// Finds a maintenance window by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<MaintenanceWindow>, DomainError>;
+
+    /// Lists the windows that haven't ended by `now`, soonest first.
+    async fn find_upcoming(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<MaintenanceWindow>, DomainError>;
+
+    /// Saves a maintenance window (insert or update).
+    async fn save(&self, window: &MaintenanceWindow) -> Result<EntityId, DomainError>;
+
+    /// Deletes a maintenance window.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for the instrument model catalog.
+#[async_trait]
+pub trait InstrumentModelRepository: Send + Sync {
+    /// Finds an instrument model by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<InstrumentModel>, DomainError>;
+
+    /// Finds an instrument model by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<InstrumentModel>, DomainError>;
+
+    /// Lists all instrument models.
+    async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError>;
+
+    /// Saves an instrument model (insert or update).
+    async fn save(&self, model: &InstrumentModel) -> Result<EntityId, DomainError>;
+
+    /// Deletes an instrument model.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for custom attribute definitions.
+#[async_trait]
+pub trait AttributeDefinitionRepository: Send + Sync {
+    /// Finds an attribute definition by ID.
+    async fn find_by_id(&self, id: EntityId)
+        -> Result<Option<AttributeDefinition>, DomainError>;
+
+    /// Lists the definitions for a kind of entity, or for every kind,
+    /// ordered by position.
+    async fn list(
+        &self,
+        target: Option<AttributeTarget>,
+    ) -> Result<Vec<AttributeDefinition>, DomainError>;
+
+    /// Lists the definitions that apply to a kind of entity in a project:
+    /// those for every project and those for that project, ordered by
+    /// position.
+    async fn find_for_project(
+        &self,
+        target: AttributeTarget,
+        project_id: EntityId,
+    ) -> Result<Vec<AttributeDefinition>, DomainError>;
+
+    /// Saves an attribute definition (insert or update).
+    async fn save(&self, definition: &AttributeDefinition) -> Result<EntityId, DomainError>;
+
+    /// Deletes an attribute definition.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 }
 
 /// Repository for StorageBox entities.
@@ -221,13 +657,16 @@ pub trait StorageBoxRepository: Send + Sync {
     /// Finds boxes by location.
     async fn find_by_location(&self, freezer: &str) -> Result<Vec<StorageBox>, DomainError>;
 
+    /// Finds the boxes assigned to a rack.
+    async fn find_by_rack(&self, rack_id: EntityId) -> Result<Vec<StorageBox>, DomainError>;
+
     /// Lists all boxes.
     async fn list(&self, options: QueryOptions) -> Result<Vec<StorageBox>, DomainError>;
 
     /// Finds the box containing a specific item.
     async fn find_by_item(
         &self,
-        item_type: crate::entities::box_entity::StorableType,
+        item_type: crate::entities::StorableType,
         item_id: EntityId,
     ) -> Result<Option<(StorageBox, crate::value_objects::BoxPosition)>, DomainError>;
 
@@ -238,6 +677,334 @@ pub trait StorageBoxRepository: Send + Sync {
     async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 }
 
+/// Repository for the freezers, shelves and racks boxes are kept in.
+#[async_trait]
+pub trait StorageUnitRepository: Send + Sync {
+    /// Finds a unit by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageUnit>, DomainError>;
+
+    /// Finds a unit by barcode.
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<StorageUnit>, DomainError>;
+
+    /// Finds the units directly in a unit, by name.
+    async fn find_children(&self, parent_id: EntityId) -> Result<Vec<StorageUnit>, DomainError>;
+
+    /// Lists freezers, by name.
+    async fn list_freezers(&self) -> Result<Vec<StorageUnit>, DomainError>;
+
+    /// Saves a unit (insert or update).
+    async fn save(&self, unit: &StorageUnit) -> Result<EntityId, DomainError>;
+
+    /// Deletes a unit. Units holding units or boxes can't be deleted.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Source of the locations storable items record for themselves.
+#[async_trait]
+pub trait ItemLocationRepository: Send + Sync {
+    /// Lists the recorded location of every item of the given type,
+    /// including items that aren't stored anywhere.
+    async fn list_recorded(
+        &self,
+        item_type: StorableType,
+    ) -> Result<Vec<RecordedLocation>, DomainError>;
+}
+
+/// Repository for location reconciliation reports.
+#[async_trait]
+pub trait ReconciliationReportRepository: Send + Sync {
+    /// Finds a report by ID.
+    async fn find_by_id(&self, id: EntityId)
+        -> Result<Option<ReconciliationReport>, DomainError>;
+
+    /// Finds the most recent report.
+    async fn find_latest(&self) -> Result<Option<ReconciliationReport>, DomainError>;
+
+    /// Lists reports, newest first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ReconciliationReport>, DomainError>;
+
+    /// Saves a report.
+    async fn save(&self, report: &ReconciliationReport) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for storage audits.
+#[async_trait]
+pub trait StorageAuditRepository: Send + Sync {
+    /// Finds an audit by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageAudit>, DomainError>;
+
+    /// Lists audits, newest first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<StorageAudit>, DomainError>;
+
+    /// Saves an audit (insert or update).
+    async fn save(&self, audit: &StorageAudit) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for QC measurements.
+#[async_trait]
+pub trait QcRecordRepository: Send + Sync {
+    /// Finds the measurements of an entity, oldest first.
+    async fn find_by_entity(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+    ) -> Result<Vec<QcRecord>, DomainError>;
+
+    /// Saves a new measurement.
+    async fn save(&self, record: &QcRecord) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for change log entries.
+#[async_trait]
+pub trait ChangeLogRepository: Send + Sync {
+    /// Finds the entries for an entity, oldest first.
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<ChangeLogEntry>, DomainError>;
+
+    /// Appends entries, chaining each onto the newest stored entry.
+    async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError>;
+
+    /// Lists up to `limit` entries after an ID, oldest first.
+    async fn list_after(
+        &self,
+        id: EntityId,
+        limit: u64,
+    ) -> Result<Vec<ChangeLogEntry>, DomainError>;
+
+    /// Rewrites existing entries. Entries are otherwise append-only; this
+    /// is only for erasing a withdrawn participant's details.
+    async fn update_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError>;
+}
+
+/// Repository for the per-project activity feed.
+#[async_trait]
+pub trait ProjectActivityRepository: Send + Sync {
+    /// Lists a project's activity, newest first.
+    ///
+    /// Returns at most `limit` items, all strictly before `before` if given.
+    async fn list_for_project(
+        &self,
+        project_id: EntityId,
+        before: Option<ActivityCursor>,
+        limit: u64,
+    ) -> Result<Vec<ActivityItem>, DomainError>;
+}
+
+/// Repository for API keys.
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Finds an API key by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ApiKey>, DomainError>;
+
+    /// Finds an API key by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<ApiKey>, DomainError>;
+
+    /// Finds an API key by the hash of the key.
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError>;
+
+    /// Lists all API keys, newest first.
+    async fn list(&self) -> Result<Vec<ApiKey>, DomainError>;
+
+    /// Saves an API key (insert or update).
+    async fn save(&self, key: &ApiKey) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for hourly API usage counts.
+#[async_trait]
+pub trait ApiUsageRepository: Send + Sync {
+    /// Adds counts to those already stored for the same caller, endpoint
+    /// and hour.
+    async fn add(&self, usage: &[ApiUsage]) -> Result<(), DomainError>;
+
+    /// Lists the counts for hours starting in `[since, until)`.
+    async fn list(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ApiUsage>, DomainError>;
+}
+
+/// Repository for the audit log.
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    /// Finds entries matching a query, newest first.
+    async fn find(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError>;
+
+    /// Saves new entries, chaining each onto the newest stored entry.
+    async fn save_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError>;
+
+    /// Lists up to `limit` entries after an ID, oldest first.
+    async fn list_after(&self, id: EntityId, limit: u64) -> Result<Vec<AuditEntry>, DomainError>;
+
+    /// Finds every entry for an entity, oldest first.
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<AuditEntry>, DomainError>;
+
+    /// Rewrites existing entries. Entries are otherwise append-only; this
+    /// is only for erasing a withdrawn participant's details.
+    async fn update_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError>;
+}
+
+/// Repository for the record of label prints.
+#[async_trait]
+pub trait LabelPrintRepository: Send + Sync {
+    /// Finds the latest print of an entity with a template.
+    async fn find_last(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+        template: &str,
+    ) -> Result<Option<LabelPrint>, DomainError>;
+
+    /// Lists the prints of an entity, oldest first.
+    async fn list_for_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<LabelPrint>, DomainError>;
+
+    /// Records a print. Fails with a duplicate error if the same copy of
+    /// the label was recorded meanwhile.
+    async fn save(&self, print: &LabelPrint) -> Result<EntityId, DomainError>;
+
+    /// Deletes the record of a print that failed.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+
+    /// Records the labels a print job used.
+    async fn save_usage(&self, usage: &LabelUsage) -> Result<EntityId, DomainError>;
+
+    /// Lists the print jobs since a time, oldest first.
+    async fn list_usage(&self, since: chrono::DateTime<chrono::Utc>) -> Result<Vec<LabelUsage>, DomainError>;
+}
+
+/// Repository for archiving old log rows.
+#[async_trait]
+pub trait LogArchiveRepository: Send + Sync {
+    /// Lists up to `limit` archivable rows of a log written before
+    /// `before`, oldest first.
+    ///
+    /// For chained logs these are the oldest rows, up to the first written
+    /// since `before`; for storage audits they are audits closed before it.
+    async fn list_expired(
+        &self,
+        log: ArchivedLog,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: u64,
+    ) -> Result<Vec<ArchivedRow>, DomainError>;
+
+    /// Records an archive and deletes the rows it holds, together.
+    async fn archive(&self, archive: &LogArchive, ids: &[EntityId])
+        -> Result<EntityId, DomainError>;
+
+    /// Puts an archive's rows back and marks it restored, together.
+    /// `rows` are the rows as read for archiving.
+    async fn restore(&self, archive: &LogArchive, rows: &[String]) -> Result<(), DomainError>;
+
+    /// Finds an archive by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<LogArchive>, DomainError>;
+
+    /// Lists the archives of a log, newest first.
+    async fn list(&self, log: ArchivedLog) -> Result<Vec<LogArchive>, DomainError>;
+
+    /// Returns how big each log has grown, and how much has been archived.
+    async fn sizes(&self) -> Result<Vec<LogTableSize>, DomainError>;
+}
+
+/// Repository for the hash-chained event store and its read model.
+#[async_trait]
+pub trait EventStoreRepository: Send + Sync {
+    /// Returns the newest event, if any.
+    async fn last(&self) -> Result<Option<StoredEvent>, DomainError>;
+
+    /// Appends chained events in one transaction, updating the read model
+    /// to match.
+    ///
+    /// Fails with `ConcurrentModification` unless the first event's
+    /// previous hash is the newest stored event's hash, so that two
+    /// writers cannot fork the chain.
+    async fn append(&self, events: &[StoredEvent]) -> Result<(), DomainError>;
+
+    /// Lists up to `limit` events after a sequence number, oldest first.
+    async fn list_after(&self, sequence: i64, limit: u64) -> Result<Vec<StoredEvent>, DomainError>;
+
+    /// Finds every event for an entity, oldest first.
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<StoredEvent>, DomainError>;
+
+    /// Rewrites the states of existing events in one transaction, along
+    /// with any state in the read model that came from them. Events are
+    /// otherwise never changed; this is only for erasing a withdrawn
+    /// participant's details.
+    async fn update_all(&self, events: &[StoredEvent]) -> Result<(), DomainError>;
+
+    /// Finds an entity's state in the read model.
+    async fn find_snapshot(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Option<EntitySnapshot>, DomainError>;
+
+    /// Replaces the whole read model in one transaction.
+    async fn replace_snapshots(&self, snapshots: &[EntitySnapshot]) -> Result<(), DomainError>;
+}
+
+/// Repository for identities created despite looking like existing ones.
+#[async_trait]
+pub trait PossibleDuplicateRepository: Send + Sync {
+    /// Finds a possible duplicate by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<PossibleDuplicate>, DomainError>;
+
+    /// Lists possible duplicates not yet reviewed, oldest first.
+    async fn list_unresolved(&self) -> Result<Vec<PossibleDuplicate>, DomainError>;
+
+    /// Finds the possible duplicates an identity is on either side of.
+    async fn find_by_identity(
+        &self,
+        identity_id: EntityId,
+    ) -> Result<Vec<PossibleDuplicate>, DomainError>;
+
+    /// Saves a possible duplicate (insert or update).
+    async fn save(&self, duplicate: &PossibleDuplicate) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for erasures of withdrawn participants' details.
+#[async_trait]
+pub trait ErasureRepository: Send + Sync {
+    /// Lists all erasures, newest first.
+    async fn list(&self) -> Result<Vec<Erasure>, DomainError>;
+
+    /// Returns true if any of the external name hashes was erased.
+    async fn is_erased(&self, name_hashes: &[String]) -> Result<bool, DomainError>;
+
+    /// Saves a new erasure.
+    async fn save(&self, erasure: &Erasure) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for participants' consent, one per identity.
+#[async_trait]
+pub trait ConsentRepository: Send + Sync {
+    /// Finds the consent recorded for an identity.
+    async fn find_by_identity(&self, identity_id: EntityId) -> Result<Option<Consent>, DomainError>;
+
+    /// Finds the consent recorded for any of the identities (batch load).
+    async fn find_by_identities(
+        &self,
+        identity_ids: &[EntityId],
+    ) -> Result<Vec<Consent>, DomainError>;
+
+    /// Saves an identity's consent (insert or update).
+    async fn save(&self, consent: &Consent) -> Result<EntityId, DomainError>;
+}
+
 /// Repository for User entities.
 #[async_trait]
 pub trait UserRepository: Send + Sync {
@@ -253,10 +1020,208 @@ pub trait UserRepository: Send + Sync {
     /// Lists all users.
     async fn list(&self, options: QueryOptions) -> Result<Vec<User>, DomainError>;
 
-    /// Saves a user (insert or update).
+    /// Saves a user (insert or update). The user's password is left as it
+    /// is.
     async fn save(&self, user: &User) -> Result<EntityId, DomainError>;
 
+    /// Finds the password hash of a user, or `None` if no password is set
+    /// (as for LDAP users).
+    async fn find_password_hash(&self, id: EntityId) -> Result<Option<String>, DomainError>;
+
+    /// Sets the password hash of a user.
+    async fn set_password_hash(&self, id: EntityId, password_hash: &str)
+        -> Result<(), DomainError>;
+
     /// Deletes a user.
     async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
 }
 
+/// Repository for the identities pooled samples were made from.
+#[async_trait]
+pub trait SampleCompositionRepository: Send + Sync {
+    /// Finds the compositions of the given pooled samples.
+    async fn find_by_samples(
+        &self,
+        sample_ids: &[EntityId],
+    ) -> Result<Vec<SampleComposition>, DomainError>;
+
+    /// Finds the compositions an identity was pooled into.
+    async fn find_by_identity(
+        &self,
+        identity_id: EntityId,
+    ) -> Result<Vec<SampleComposition>, DomainError>;
+
+    /// Saves a pooled sample's composition, replacing any it had.
+    async fn save(&self, composition: &SampleComposition) -> Result<(), DomainError>;
+}
+
+/// Repository for extraction batches.
+#[async_trait]
+pub trait ExtractionBatchRepository: Send + Sync {
+    /// Finds a batch by ID, with its samples and blanks.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ExtractionBatch>, DomainError>;
+
+    /// Finds a batch by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<ExtractionBatch>, DomainError>;
+
+    /// Finds the batch a sample or blank was extracted in.
+    async fn find_by_sample(
+        &self,
+        sample_id: EntityId,
+    ) -> Result<Option<ExtractionBatch>, DomainError>;
+
+    /// Lists batches, most recently extracted first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ExtractionBatch>, DomainError>;
+
+    /// Saves a batch and replaces its samples and blanks (insert or update).
+    async fn save(&self, batch: &ExtractionBatch) -> Result<EntityId, DomainError>;
+
+    /// Deletes a batch. Its samples are not affected.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for reagent lots in stock.
+#[async_trait]
+pub trait ReagentLotRepository: Send + Sync {
+    /// Finds a lot by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ReagentLot>, DomainError>;
+
+    /// Finds a kit's lot by lot number.
+    async fn find_by_lot(
+        &self,
+        kit: &str,
+        lot_number: &str,
+    ) -> Result<Option<ReagentLot>, DomainError>;
+
+    /// Lists lots by kit, soonest expiring first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ReagentLot>, DomainError>;
+
+    /// Lists every lot with units left, by kit, soonest expiring first.
+    async fn find_in_stock(&self) -> Result<Vec<ReagentLot>, DomainError>;
+
+    /// Saves a lot (insert or update).
+    async fn save(&self, lot: &ReagentLot) -> Result<EntityId, DomainError>;
+
+    /// Deletes a lot.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for attachment metadata. The files themselves are kept by an
+/// [`AttachmentStore`](crate::attachments::AttachmentStore).
+#[async_trait]
+pub trait AttachmentRepository: Send + Sync {
+    /// Finds an attachment by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Attachment>, DomainError>;
+
+    /// Lists the attachments of an entity, newest first.
+    async fn find_by_target(
+        &self,
+        target: AttachmentTarget,
+        target_id: EntityId,
+    ) -> Result<Vec<Attachment>, DomainError>;
+
+    /// Saves an attachment (insert or update).
+    async fn save(&self, attachment: &Attachment) -> Result<EntityId, DomainError>;
+
+    /// Deletes an attachment.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for requisitions.
+#[async_trait]
+pub trait RequisitionRepository: Send + Sync {
+    /// Finds a requisition by ID, with its samples.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Requisition>, DomainError>;
+
+    /// Finds a requisition by its order ID.
+    async fn find_by_alias(&self, alias: &str) -> Result<Option<Requisition>, DomainError>;
+
+    /// Finds the requisition a sample is attached to.
+    async fn find_by_sample(&self, sample_id: EntityId)
+        -> Result<Option<Requisition>, DomainError>;
+
+    /// Lists requisitions, newest first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Requisition>, DomainError>;
+
+    /// Saves a requisition and replaces its samples (insert or update).
+    async fn save(&self, requisition: &Requisition) -> Result<EntityId, DomainError>;
+
+    /// Deletes a requisition. Its samples are not affected.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for transfers between labs or people.
+#[async_trait]
+pub trait TransferRepository: Send + Sync {
+    /// Finds a transfer by ID, with its items.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Transfer>, DomainError>;
+
+    /// Lists transfers, most recently sent first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Transfer>, DomainError>;
+
+    /// Finds the transfers of a sample or library, in the order sent.
+    async fn find_by_item(
+        &self,
+        item_type: TransferItemType,
+        entity_id: EntityId,
+    ) -> Result<Vec<Transfer>, DomainError>;
+
+    /// Finds the transfers sent under a courier's tracking number.
+    async fn find_by_tracking_number(
+        &self,
+        tracking_number: &str,
+    ) -> Result<Vec<Transfer>, DomainError>;
+
+    /// Finds transfers with a tracking number that have been neither
+    /// delivered nor checked in, oldest first.
+    async fn find_in_transit(&self) -> Result<Vec<Transfer>, DomainError>;
+
+    /// Saves a transfer and replaces its items (insert or update).
+    async fn save(&self, transfer: &Transfer) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for worksets.
+#[async_trait]
+pub trait WorksetRepository: Send + Sync {
+    /// Finds a workset by ID, with its items.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Workset>, DomainError>;
+
+    /// Finds a workset by name.
+    async fn find_by_name(&self, name: &str) -> Result<Option<Workset>, DomainError>;
+
+    /// Lists worksets, newest first.
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Workset>, DomainError>;
+
+    /// Saves a workset and replaces its items (insert or update).
+    async fn save(&self, workset: &Workset) -> Result<EntityId, DomainError>;
+
+    /// Deletes a workset. Its members are not affected.
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError>;
+}
+
+/// Repository for automatic archiving rules.
+#[async_trait]
+pub trait ArchiveRuleRepository: Send + Sync {
+    /// Finds a rule by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ArchiveRule>, DomainError>;
+
+    /// Lists all rules by name.
+    async fn list(&self) -> Result<Vec<ArchiveRule>, DomainError>;
+
+    /// Saves a rule (insert or update); fails with
+    /// [`DomainError::Duplicate`] if another rule has the same name.
+    async fn save(&self, rule: &ArchiveRule) -> Result<EntityId, DomainError>;
+}
+
+/// Repository for the record of items archived by rules.
+#[async_trait]
+pub trait AutoArchivalRepository: Send + Sync {
+    /// Finds an archival by ID.
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<AutoArchival>, DomainError>;
+
+    /// Lists the items a rule archived, most recently archived first.
+    async fn find_by_rule(&self, rule_id: EntityId) -> Result<Vec<AutoArchival>, DomainError>;
+
+    /// Saves an archival (insert or update).
+    async fn save(&self, archival: &AutoArchival) -> Result<EntityId, DomainError>;
+}
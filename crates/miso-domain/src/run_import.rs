@@ -0,0 +1,58 @@
+//! Run Import Traits - interfaces for pulling finished runs from the cloud
+//! services instruments upload to.
+//!
+//! Like run planners, run sources are implemented in the infrastructure
+//! layer (BaseSpace Sequence Hub) so that a scheduled job can record runs
+//! and their metrics without knowing which service they came from.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::entities::RunStatus;
+use crate::errors::DomainError;
+
+/// A run as an instrument's cloud service reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalRun {
+    /// The service's identifier for the run
+    pub external_id: String,
+    /// Run name, e.g. "240105_A01234_0042_BHXXXXDSX7"
+    pub name: String,
+    /// Serial number of the instrument it ran on
+    pub instrument_serial: Option<String>,
+    /// Name the instrument is registered under with the service
+    pub instrument_name: Option<String>,
+    pub flow_cell_barcode: Option<String>,
+    pub status: RunStatus,
+    /// Read structure, e.g. "2x151"
+    pub read_length: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub lanes: Vec<ExternalLaneMetrics>,
+}
+
+/// Instrument metrics for one lane of an external run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExternalLaneMetrics {
+    pub lane: u8,
+    /// Cluster density (K/mm²)
+    pub cluster_density: Option<f64>,
+    pub pass_filter_percent: Option<f64>,
+    pub q30_percent: Option<f64>,
+    /// Bases passing filter, in gigabases
+    pub yield_gbp: Option<f64>,
+}
+
+/// Lists runs from an instrument cloud service.
+#[async_trait]
+pub trait RunSource: Send + Sync {
+    /// Short name of the service, recorded as the creator of imported runs.
+    fn name(&self) -> &str;
+
+    /// Lists the runs that finished, successfully or not, since the given
+    /// time, or all finished runs if none is given.
+    async fn finished_runs(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ExternalRun>, DomainError>;
+}
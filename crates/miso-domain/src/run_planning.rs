@@ -0,0 +1,23 @@
+//! Run Planning Traits - interfaces for sending run plans to sequencers.
+//!
+//! Like notifiers, run planners are implemented in the infrastructure layer
+//! (Illumina Local Run Manager, BaseSpace run planning) so that application
+//! code can hand a run's sample sheet to its instrument without knowing how
+//! it gets there.
+
+use async_trait::async_trait;
+
+use crate::entities::Sequencer;
+use crate::errors::DomainError;
+
+/// Delivers sample sheets to sequencers.
+#[async_trait]
+pub trait RunPlanner: Send + Sync {
+    /// Sends a run's sample sheet to the sequencer it will run on.
+    async fn push_sample_sheet(
+        &self,
+        sequencer: &Sequencer,
+        file_name: &str,
+        contents: &str,
+    ) -> Result<(), DomainError>;
+}
@@ -0,0 +1,90 @@
+//! Activity feed assembly.
+//!
+//! Each source returns its own newest-first page; the feed is the newest
+//! items across all of them.
+
+use crate::entities::{ActivityCursor, ActivityItem};
+
+/// Service merging per-source activity into one page.
+pub struct ActivityFeed;
+
+impl ActivityFeed {
+    /// Merges pages from several sources into the newest `limit` items
+    /// strictly before `before`, newest first.
+    ///
+    /// Each source must already hold its own newest `limit` items before
+    /// the cursor; items from further back can never make the page.
+    pub fn merge(
+        sources: Vec<Vec<ActivityItem>>,
+        before: Option<ActivityCursor>,
+        limit: usize,
+    ) -> Vec<ActivityItem> {
+        let mut items: Vec<ActivityItem> = sources
+            .into_iter()
+            .flatten()
+            .filter(|item| before.is_none_or(|cursor| item.cursor() < cursor))
+            .collect();
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.cursor()));
+        items.truncate(limit);
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::entities::ActivityKind;
+
+    fn item(kind: ActivityKind, source_id: i32, second: u32) -> ActivityItem {
+        ActivityItem {
+            kind,
+            source_id,
+            occurred_at: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, second).unwrap(),
+            entity_type: "Sample".to_string(),
+            entity_id: 1,
+            summary: String::new(),
+            detail: None,
+            actor: "admin".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_pages_through_ties_without_gaps() {
+        let created = vec![
+            item(ActivityKind::SampleCreated, 2, 30),
+            item(ActivityKind::SampleCreated, 1, 10),
+        ];
+        let changes = vec![
+            item(ActivityKind::Change, 7, 30),
+            item(ActivityKind::Change, 6, 30),
+            item(ActivityKind::Change, 5, 20),
+        ];
+
+        let first = ActivityFeed::merge(vec![created.clone(), changes.clone()], None, 2);
+        let ids: Vec<_> = first.iter().map(|i| (i.kind, i.source_id)).collect();
+        assert_eq!(
+            ids,
+            vec![(ActivityKind::Change, 7), (ActivityKind::Change, 6)]
+        );
+
+        let cursor = ActivityCursor::decode(&first[1].cursor().encode()).unwrap();
+        assert_eq!(cursor, first[1].cursor());
+
+        let second = ActivityFeed::merge(vec![created, changes], Some(cursor), 2);
+        let ids: Vec<_> = second.iter().map(|i| (i.kind, i.source_id)).collect();
+        assert_eq!(
+            ids,
+            vec![(ActivityKind::SampleCreated, 2), (ActivityKind::Change, 5)]
+        );
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        assert!(ActivityCursor::decode("").is_err());
+        assert!(ActivityCursor::decode("123.comment.4").is_err());
+        assert!(ActivityCursor::decode("abc.change.4").is_err());
+    }
+}
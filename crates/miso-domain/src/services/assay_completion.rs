@@ -0,0 +1,229 @@
+//! Assay completion evaluation service.
+//!
+//! Aggregates demultiplexing metrics across all lanes a library was
+//! sequenced on and decides whether the assay's sequencing requirements
+//! have been met.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EntityId, LibraryRunMetrics};
+
+/// Sequencing requirements an assay places on each library.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssayRequirements {
+    /// Minimum total reads across all lanes
+    pub min_reads: u64,
+    /// Minimum read-weighted %Q30
+    pub min_q30_percent: f64,
+    /// Maximum tolerated index hopping rate (percent)
+    pub max_index_hopping_percent: Option<f64>,
+}
+
+impl Default for AssayRequirements {
+    fn default() -> Self {
+        Self {
+            min_reads: 10_000_000,
+            min_q30_percent: 75.0,
+            max_index_hopping_percent: Some(2.0),
+        }
+    }
+}
+
+/// The outcome of evaluating a library against its requirements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionStatus {
+    /// All requirements met
+    Complete,
+    /// Not enough reads yet - more sequencing needed
+    InsufficientReads,
+    /// Enough reads, but quality is below threshold
+    LowQuality,
+    /// Index hopping above tolerance - data may be contaminated
+    ExcessiveIndexHopping,
+}
+
+impl CompletionStatus {
+    /// Returns true if the library needs no further sequencing.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Complete)
+    }
+}
+
+impl std::fmt::Display for CompletionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Complete => write!(f, "Complete"),
+            Self::InsufficientReads => write!(f, "Insufficient Reads"),
+            Self::LowQuality => write!(f, "Low Quality"),
+            Self::ExcessiveIndexHopping => write!(f, "Excessive Index Hopping"),
+        }
+    }
+}
+
+/// Aggregated metrics and completion status for a single library.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryCompletion {
+    /// The library evaluated
+    pub library_id: EntityId,
+    /// Total reads across all lanes
+    pub total_reads: u64,
+    /// Total yield in bases across all lanes
+    pub total_yield_bases: u64,
+    /// Read-weighted %Q30 across all lanes
+    pub q30_percent: f64,
+    /// Highest index hopping rate reported on any lane
+    pub max_index_hopping_percent: Option<f64>,
+    /// Number of lanes contributing
+    pub lane_count: usize,
+    /// Evaluation outcome
+    pub status: CompletionStatus,
+}
+
+/// Service for evaluating assay completion from run metrics.
+#[derive(Debug, Clone, Default)]
+pub struct AssayCompletionEvaluator {
+    requirements: AssayRequirements,
+}
+
+impl AssayCompletionEvaluator {
+    /// Creates a new evaluator with default requirements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new evaluator with custom requirements.
+    pub fn with_requirements(requirements: AssayRequirements) -> Self {
+        Self { requirements }
+    }
+
+    /// Returns the requirements.
+    pub fn requirements(&self) -> &AssayRequirements {
+        &self.requirements
+    }
+
+    /// Evaluates every library present in the metrics.
+    ///
+    /// Results are ordered by library ID.
+    pub fn evaluate(&self, metrics: &[LibraryRunMetrics]) -> Vec<LibraryCompletion> {
+        let mut by_library: BTreeMap<EntityId, Vec<&LibraryRunMetrics>> = BTreeMap::new();
+        for m in metrics {
+            by_library.entry(m.library_id).or_default().push(m);
+        }
+
+        by_library
+            .into_iter()
+            .map(|(library_id, lanes)| self.evaluate_library(library_id, &lanes))
+            .collect()
+    }
+
+    fn evaluate_library(
+        &self,
+        library_id: EntityId,
+        lanes: &[&LibraryRunMetrics],
+    ) -> LibraryCompletion {
+        let total_reads: u64 = lanes.iter().map(|m| m.reads).sum();
+        let total_yield_bases: u64 = lanes.iter().map(|m| m.yield_bases).sum();
+
+        // Weight Q30 by reads so a nearly-empty lane doesn't skew the result
+        let q30_percent = if total_reads == 0 {
+            0.0
+        } else {
+            lanes
+                .iter()
+                .map(|m| m.q30_percent * m.reads as f64)
+                .sum::<f64>()
+                / total_reads as f64
+        };
+
+        let max_index_hopping_percent = lanes
+            .iter()
+            .filter_map(|m| m.index_hopping_percent)
+            .fold(None, |max: Option<f64>, v| {
+                Some(max.map_or(v, |m| m.max(v)))
+            });
+
+        let status = if total_reads < self.requirements.min_reads {
+            CompletionStatus::InsufficientReads
+        } else if q30_percent < self.requirements.min_q30_percent {
+            CompletionStatus::LowQuality
+        } else if matches!(
+            (max_index_hopping_percent, self.requirements.max_index_hopping_percent),
+            (Some(observed), Some(limit)) if observed > limit
+        ) {
+            CompletionStatus::ExcessiveIndexHopping
+        } else {
+            CompletionStatus::Complete
+        };
+
+        LibraryCompletion {
+            library_id,
+            total_reads,
+            total_yield_bases,
+            q30_percent,
+            max_index_hopping_percent,
+            lane_count: lanes.len(),
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(partition: u8, library_id: EntityId, reads: u64, q30: f64) -> LibraryRunMetrics {
+        LibraryRunMetrics::new(
+            1,
+            partition,
+            library_id,
+            reads,
+            reads * 150,
+            q30,
+            None,
+            "demux".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_aggregates_across_lanes() {
+        let evaluator = AssayCompletionEvaluator::new();
+        let results = evaluator.evaluate(&[
+            metrics(1, 1, 6_000_000, 90.0),
+            metrics(2, 1, 6_000_000, 80.0),
+        ]);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].total_reads, 12_000_000);
+        assert_eq!(results[0].lane_count, 2);
+        assert!((results[0].q30_percent - 85.0).abs() < 0.01);
+        assert!(results[0].status.is_complete());
+    }
+
+    #[test]
+    fn test_insufficient_reads() {
+        let evaluator = AssayCompletionEvaluator::new();
+        let results = evaluator.evaluate(&[metrics(1, 1, 1_000, 95.0)]);
+        assert_eq!(results[0].status, CompletionStatus::InsufficientReads);
+    }
+
+    #[test]
+    fn test_low_quality_and_hopping() {
+        let evaluator = AssayCompletionEvaluator::with_requirements(AssayRequirements {
+            min_reads: 100,
+            min_q30_percent: 80.0,
+            max_index_hopping_percent: Some(1.0),
+        });
+
+        let low_q = evaluator.evaluate(&[metrics(1, 1, 1_000, 60.0)]);
+        assert_eq!(low_q[0].status, CompletionStatus::LowQuality);
+
+        let mut hopping = metrics(1, 2, 1_000, 95.0);
+        hopping.index_hopping_percent = Some(3.5);
+        let results = evaluator.evaluate(&[hopping]);
+        assert_eq!(results[0].status, CompletionStatus::ExcessiveIndexHopping);
+    }
+}
@@ -0,0 +1,190 @@
+//! Index carry-over checking.
+//!
+//! Library left behind in an instrument's fluidics can turn up in the next
+//! lane or run through it. Its reads are then credited to whichever
+//! library there has the same index pair, so the same pair in two lanes of
+//! one run, or in back-to-back runs on one sequencer, is flagged while the
+//! run is being planned.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::entities::Library;
+
+/// The libraries a pool puts in one lane of a run.
+#[derive(Debug, Clone)]
+pub struct PlannedLane {
+    /// Name of the run
+    pub run: String,
+    /// Lane number, from 1
+    pub lane: u8,
+    /// Libraries sequenced in the lane
+    pub libraries: Vec<Library>,
+}
+
+/// A library whose index pair was sequenced earlier, in another lane of
+/// its run or in the sequencer's previous run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarryoverRisk {
+    /// The index pair, as `i7` or `i7+i5`
+    pub index: String,
+    /// Lane the library is in
+    pub lane: u8,
+    /// Name of the library
+    pub library: String,
+    /// Run the pair was sequenced in before
+    pub earlier_run: String,
+    /// Lane the pair was sequenced in before
+    pub earlier_lane: u8,
+    /// Library that had the pair before
+    pub earlier_library: String,
+}
+
+impl fmt::Display for CarryoverRisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Index {} of {} in lane {} was also sequenced in lane {} of {} ({}); reads carried over may be assigned to it",
+            self.index,
+            self.library,
+            self.lane,
+            self.earlier_lane,
+            self.earlier_run,
+            self.earlier_library
+        )
+    }
+}
+
+/// Service finding index pairs sequenced where earlier material with the
+/// same pair could carry over.
+pub struct CarryoverChecker;
+
+impl CarryoverChecker {
+    /// Returns the libraries in `lanes` whose index pair was sequenced in
+    /// an earlier lane of the same run or in `previous`, the lanes of the
+    /// sequencer's previous run.
+    ///
+    /// Each library is reported once, against the nearest earlier lane
+    /// with its pair. Repeats within one lane are index collisions and are
+    /// left to the pool's validation; unindexed libraries are skipped.
+    pub fn check(lanes: &[PlannedLane], previous: &[PlannedLane]) -> Vec<CarryoverRisk> {
+        let mut ordered: Vec<&PlannedLane> = lanes.iter().collect();
+        ordered.sort_by_key(|lane| lane.lane);
+
+        let mut seen: HashMap<(String, Option<String>), (&PlannedLane, &Library)> = HashMap::new();
+        for lane in previous {
+            for library in &lane.libraries {
+                if let Some(key) = index_pair(library) {
+                    seen.insert(key, (lane, library));
+                }
+            }
+        }
+
+        let mut risks = Vec::new();
+        for lane in ordered {
+            for library in &lane.libraries {
+                let Some(key) = index_pair(library) else {
+                    continue;
+                };
+                if let Some((earlier, earlier_library)) = seen.get(&key) {
+                    let same_lane = earlier.run == lane.run && earlier.lane == lane.lane;
+                    if !same_lane {
+                        risks.push(CarryoverRisk {
+                            index: match &key.1 {
+                                Some(i5) => format!("{}+{}", key.0, i5),
+                                None => key.0.clone(),
+                            },
+                            lane: lane.lane,
+                            library: library.name.clone(),
+                            earlier_run: earlier.run.clone(),
+                            earlier_lane: earlier.lane,
+                            earlier_library: earlier_library.name.clone(),
+                        });
+                    }
+                }
+                seen.insert(key, (lane, library));
+            }
+        }
+        risks
+    }
+}
+
+fn index_pair(library: &Library) -> Option<(String, Option<String>)> {
+    let index = library.index.as_ref()?;
+    Some((index.i7().to_string(), index.i5().map(str::to_string)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{LibraryDesign, LibraryType};
+    use crate::value_objects::{Barcode, DnaIndex, IndexFamily};
+
+    fn library(id: i32, i7: &str, i5: &str) -> Library {
+        let mut library = Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        library.set_index(DnaIndex::dual("UDI", i7, i5, IndexFamily::Nextera).unwrap());
+        library
+    }
+
+    fn lane(run: &str, lane: u8, libraries: Vec<Library>) -> PlannedLane {
+        PlannedLane {
+            run: run.to_string(),
+            lane,
+            libraries,
+        }
+    }
+
+    #[test]
+    fn test_flags_pairs_repeated_across_lanes_and_runs() {
+        let previous = [lane("RUN1", 1, vec![library(1, "ATCACG", "GATCGA")])];
+        let lanes = [
+            lane(
+                "RUN2",
+                2,
+                vec![
+                    library(3, "ATCACG", "TTAGGC"),
+                    library(4, "CGATGT", "AGCTAG"),
+                ],
+            ),
+            lane(
+                "RUN2",
+                1,
+                vec![
+                    library(2, "ATCACG", "GATCGA"),
+                    library(5, "CGATGT", "AGCTAG"),
+                ],
+            ),
+        ];
+
+        let risks = CarryoverChecker::check(&lanes, &previous);
+        let found: Vec<_> = risks
+            .iter()
+            .map(|r| (r.library.as_str(), r.earlier_run.as_str(), r.earlier_lane))
+            .collect();
+        assert_eq!(found, vec![("LIB2", "RUN1", 1), ("LIB4", "RUN2", 1)]);
+        assert_eq!(risks[0].index, "ATCACG+GATCGA");
+        assert!(risks[1]
+            .to_string()
+            .starts_with("Index CGATGT+AGCTAG of LIB4 in lane 2"));
+
+        let collision = [lane(
+            "RUN3",
+            1,
+            vec![
+                library(6, "ATCACG", "GATCGA"),
+                library(7, "ATCACG", "GATCGA"),
+            ],
+        )];
+        assert!(CarryoverChecker::check(&collision, &[]).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+//! Required controls for prep batches and runs.
+//!
+//! A site may insist that every prep batch carries, say, a positive and a
+//! negative control, and that every run has a no-template control. Sites
+//! configure the control types each needs; with none configured, nothing
+//! is required.
+
+use std::collections::BTreeSet;
+
+use crate::entities::ControlType;
+use crate::errors::DomainError;
+
+/// The controls prep batches and runs must include.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlPolicy {
+    batches: BTreeSet<ControlType>,
+    runs: BTreeSet<ControlType>,
+}
+
+impl ControlPolicy {
+    /// Creates a policy that requires no controls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires a control of `control_type` in every prep batch.
+    pub fn with_batch_control(mut self, control_type: ControlType) -> Self {
+        self.batches.insert(control_type);
+        self
+    }
+
+    /// Requires a control of `control_type` on every run.
+    pub fn with_run_control(mut self, control_type: ControlType) -> Self {
+        self.runs.insert(control_type);
+        self
+    }
+
+    /// Returns the control types a prep batch holding `present` lacks.
+    pub fn missing_from_batch(&self, present: &[ControlType]) -> Vec<ControlType> {
+        missing(&self.batches, present)
+    }
+
+    /// Returns the control types a run sequencing `present` lacks.
+    pub fn missing_from_run(&self, present: &[ControlType]) -> Vec<ControlType> {
+        missing(&self.runs, present)
+    }
+
+    /// Checks that the prep batch `name` holds the controls it needs.
+    pub fn check_batch(&self, name: &str, present: &[ControlType]) -> Result<(), DomainError> {
+        refuse("Batch", name, self.missing_from_batch(present))
+    }
+
+    /// Checks that the run `name` sequences the controls it needs.
+    pub fn check_run(&self, name: &str, present: &[ControlType]) -> Result<(), DomainError> {
+        refuse("Run", name, self.missing_from_run(present))
+    }
+}
+
+fn missing(required: &BTreeSet<ControlType>, present: &[ControlType]) -> Vec<ControlType> {
+    required
+        .iter()
+        .filter(|control| !present.contains(control))
+        .copied()
+        .collect()
+}
+
+fn refuse(kind: &str, name: &str, missing: Vec<ControlType>) -> Result<(), DomainError> {
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let missing: Vec<&str> = missing.iter().map(ControlType::as_str).collect();
+    Err(DomainError::Validation(format!(
+        "{} {} is missing required controls: {}",
+        kind,
+        name,
+        missing.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_and_runs_need_their_configured_controls() {
+        let policy = ControlPolicy::new()
+            .with_batch_control(ControlType::Negative)
+            .with_batch_control(ControlType::Positive)
+            .with_run_control(ControlType::Ntc);
+
+        assert_eq!(
+            policy.missing_from_batch(&[ControlType::Ntc]),
+            vec![ControlType::Positive, ControlType::Negative]
+        );
+        assert!(policy
+            .check_batch("Extraction 1", &[ControlType::Negative, ControlType::Positive])
+            .is_ok());
+        match policy.check_run("RUN1", &[ControlType::Positive]) {
+            Err(DomainError::Validation(message)) => {
+                assert_eq!(message, "Run RUN1 is missing required controls: ntc")
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        assert!(ControlPolicy::new().check_run("RUN2", &[]).is_ok());
+    }
+}
@@ -0,0 +1,173 @@
+//! Duplicate identity detection.
+//!
+//! An identity's external name is the patient or donor ID it came in under,
+//! and a mistyped one quietly creates a second identity for the same
+//! person. Before an identity is created, its external names are compared
+//! with those of existing identities, ignoring case, spacing and
+//! punctuation, and allowing for a typo or two.
+//!
+//! An identity may have several external names, separated by commas.
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EntityId, Sample, SampleDetails};
+
+/// An existing identity an external name may be a duplicate of.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityMatch {
+    /// The existing identity
+    pub identity_id: EntityId,
+    /// Its name
+    pub name: String,
+    /// Its external name that matched
+    pub external_name: String,
+    /// Edits between the normalized names; 0 if they are the same
+    pub distance: u32,
+}
+
+/// Finds existing identities with external names like a new one's.
+pub struct IdentityMatcher;
+
+impl IdentityMatcher {
+    /// Returns the identities whose external names match any of
+    /// `external_name`'s, closest first.
+    ///
+    /// Names match if they are the same once normalized, or within one
+    /// edit for names of 5 to 8 characters and two edits for longer ones.
+    /// Short names must match exactly, as most short IDs are one edit from
+    /// another.
+    pub fn find_matches(external_name: &str, identities: &[Sample]) -> Vec<IdentityMatch> {
+        let wanted: Vec<String> = split_external_names(external_name)
+            .map(normalize_external_name)
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        let mut matches: Vec<IdentityMatch> = identities
+            .iter()
+            .filter_map(|identity| {
+                let SampleDetails::Detailed(details) = &identity.details else {
+                    return None;
+                };
+                split_external_names(details.external_name.as_deref()?)
+                    .filter_map(|existing| {
+                        let normalized = normalize_external_name(existing);
+                        wanted
+                            .iter()
+                            .filter_map(|name| {
+                                let distance = edit_distance(name, &normalized);
+                                (distance <= max_distance(name, &normalized)).then_some(distance)
+                            })
+                            .min()
+                            .map(|distance| (existing, distance))
+                    })
+                    .min_by_key(|(_, distance)| *distance)
+                    .map(|(existing, distance)| IdentityMatch {
+                        identity_id: identity.id,
+                        name: identity.name.clone(),
+                        external_name: existing.to_string(),
+                        distance,
+                    })
+            })
+            .collect();
+
+        matches.sort_by_key(|m| (m.distance, m.identity_id));
+        matches
+    }
+}
+
+/// Normalizes an external name for comparison: lower case, letters and
+/// digits only.
+pub fn normalize_external_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn split_external_names(names: &str) -> impl Iterator<Item = &str> {
+    names.split(',').map(str::trim).filter(|name| !name.is_empty())
+}
+
+/// Edits allowed between two normalized names.
+fn max_distance(a: &str, b: &str) -> u32 {
+    match a.chars().count().min(b.chars().count()) {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Returns the number of insertions, deletions, substitutions and swaps of
+/// adjacent characters that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> u32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Three rows of the distance table: two back, the last and this one
+    let mut before: Vec<u32> = vec![0; b.len() + 1];
+    let mut last: Vec<u32> = (0..=b.len() as u32).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i as u32; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = u32::from(a[i - 1] != b[j - 1]);
+            row[j] = (last[j] + 1).min(row[j - 1] + 1).min(last[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut last, row);
+    }
+    last[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::Barcode;
+
+    fn identity(id: EntityId, external_name: &str) -> Sample {
+        Sample::new_identity(
+            id,
+            format!("PAT_{:04}", id),
+            Barcode::new_unchecked(format!("SAM{}", id)),
+            1,
+            external_name.to_string(),
+            "admin".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_edit_distance_counts_swaps_once() {
+        assert_eq!(edit_distance("mrn1234", "mrn1234"), 0);
+        assert_eq!(edit_distance("mrn1234", "mrn1243"), 1);
+        assert_eq!(edit_distance("mrn1234", "mrn123"), 1);
+        assert_eq!(edit_distance("mrn1234", "mrn9934"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(normalize_external_name(" MRN-12.34 "), "mrn1234");
+    }
+
+    #[test]
+    fn test_typos_match_closest_first() {
+        let identities = vec![
+            identity(1, "MRN-00123456"),
+            identity(2, "DONOR 77, mrn00123465"),
+            identity(3, "MRN-00999999"),
+            identity(4, "AB12"),
+        ];
+
+        let matches = IdentityMatcher::find_matches("mrn 00123456", &identities);
+        let found: Vec<(EntityId, u32)> =
+            matches.iter().map(|m| (m.identity_id, m.distance)).collect();
+        assert_eq!(found, vec![(1, 0), (2, 1)]);
+        assert_eq!(matches[1].external_name, "mrn00123465");
+
+        // Any of several names may match
+        let matches = IdentityMatcher::find_matches("X, donor-77", &identities);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].identity_id, 2);
+
+        // Short names must match exactly
+        assert!(IdentityMatcher::find_matches("AB13", &identities).is_empty());
+        assert_eq!(IdentityMatcher::find_matches("ab-12", &identities).len(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+//! Automatic index assignment.
+//!
+//! Suggests indices from the catalog for libraries about to be pooled, so
+//! that every pair of indices in the pool, including those the libraries
+//! already have, is at least the minimum Hamming distance apart. Small
+//! sets are searched exhaustively, so an assignment is found whenever one
+//! exists; larger ones are assigned greedily. Either way candidates are
+//! tried in catalog order, so a kit's plate is used up well by well.
+
+use crate::value_objects::DnaIndex;
+
+use super::CollisionCheckConfig;
+
+/// Largest number of libraries searched for exhaustively.
+pub const EXHAUSTIVE_LIMIT: usize = 12;
+
+/// Candidates tried before an exhaustive search gives up.
+const SEARCH_BUDGET: usize = 200_000;
+
+/// How an assignment was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentMethod {
+    /// Each library took the first index that fit
+    Greedy,
+    /// Every combination of indices was searched
+    Exhaustive,
+}
+
+/// Indices suggested for the libraries that need them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexAssignment {
+    /// For each library, the position of its index in the candidates
+    pub candidates: Vec<usize>,
+    pub method: AssignmentMethod,
+}
+
+/// Suggests collision-free indices for libraries being pooled.
+#[derive(Debug, Clone, Default)]
+pub struct IndexAssigner {
+    config: CollisionCheckConfig,
+}
+
+impl IndexAssigner {
+    /// Creates an assigner with the given collision settings.
+    pub fn new(config: CollisionCheckConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &CollisionCheckConfig {
+        &self.config
+    }
+
+    /// Picks indices for `needed` libraries from `candidates`, far enough
+    /// from each other and from the `fixed` indices already in the pool.
+    ///
+    /// Returns `None` if no assignment was found: for small sets, because
+    /// none exists (or the search ran too long); for larger ones, because
+    /// the greedy pass ran out of candidates.
+    pub fn assign(
+        &self,
+        fixed: &[DnaIndex],
+        needed: usize,
+        candidates: &[DnaIndex],
+    ) -> Option<IndexAssignment> {
+        // Only candidates that fit alongside the fixed indices are usable
+        let usable: Vec<usize> = (0..candidates.len())
+            .filter(|&i| fixed.iter().all(|f| self.fits(f, &candidates[i])))
+            .collect();
+        if usable.len() < needed {
+            return None;
+        }
+
+        if needed <= EXHAUSTIVE_LIMIT {
+            let mut chosen = Vec::with_capacity(needed);
+            let mut budget = SEARCH_BUDGET;
+            self.search(candidates, &usable, 0, needed, &mut chosen, &mut budget)
+                .then_some(IndexAssignment {
+                    candidates: chosen,
+                    method: AssignmentMethod::Exhaustive,
+                })
+        } else {
+            let mut chosen: Vec<usize> = Vec::with_capacity(needed);
+            for &i in &usable {
+                if chosen.len() == needed {
+                    break;
+                }
+                if chosen.iter().all(|&c| self.fits(&candidates[c], &candidates[i])) {
+                    chosen.push(i);
+                }
+            }
+            (chosen.len() == needed).then_some(IndexAssignment {
+                candidates: chosen,
+                method: AssignmentMethod::Greedy,
+            })
+        }
+    }
+
+    /// Extends `chosen` with usable candidates from `from` on until it
+    /// holds `needed`, backtracking when a choice leads nowhere.
+    fn search(
+        &self,
+        candidates: &[DnaIndex],
+        usable: &[usize],
+        from: usize,
+        needed: usize,
+        chosen: &mut Vec<usize>,
+        budget: &mut usize,
+    ) -> bool {
+        if chosen.len() == needed {
+            return true;
+        }
+        for next in from..usable.len() {
+            // Too few candidates left to finish from here
+            if usable.len() - next < needed - chosen.len() || *budget == 0 {
+                return false;
+            }
+            *budget -= 1;
+
+            let i = usable[next];
+            if chosen.iter().all(|&c| self.fits(&candidates[c], &candidates[i])) {
+                chosen.push(i);
+                if self.search(candidates, usable, next + 1, needed, chosen, budget) {
+                    return true;
+                }
+                chosen.pop();
+            }
+        }
+        false
+    }
+
+    fn fits(&self, a: &DnaIndex, b: &DnaIndex) -> bool {
+        a.hamming_distance(b) >= self.config.min_distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::IndexFamily;
+
+    fn index(name: &str, i7: &str) -> DnaIndex {
+        DnaIndex::single(name, i7, IndexFamily::Custom).unwrap()
+    }
+
+    #[test]
+    fn test_exhaustive_search_backtracks() {
+        // Taking AAAAAA first leaves nothing that fits it; the other two
+        // fit each other. GGGGGT is too close to the pool's GGGGGG.
+        let candidates = [
+            index("A", "AAAAAA"),
+            index("D", "GGGGGT"),
+            index("B", "AACCAA"),
+            index("C", "CCAAAA"),
+        ];
+        let fixed = [index("F", "GGGGGG")];
+        let assigner = IndexAssigner::new(CollisionCheckConfig::strict());
+
+        let assignment = assigner.assign(&fixed, 2, &candidates).unwrap();
+        assert_eq!(assignment.method, AssignmentMethod::Exhaustive);
+        assert_eq!(assignment.candidates, vec![2, 3]);
+
+        assert!(assigner.assign(&fixed, 3, &candidates).is_none());
+        assert!(assigner.assign(&[], 0, &candidates).unwrap().candidates.is_empty());
+    }
+
+    #[test]
+    fn test_greedy_assignment_for_large_sets() {
+        let bases = ['A', 'C', 'G', 'T'];
+        let candidates: Vec<DnaIndex> = (0..64)
+            .map(|n| {
+                let seq: String = [n / 16, n / 4 % 4, n % 4]
+                    .iter()
+                    .flat_map(|&b| std::iter::repeat_n(bases[b], 3))
+                    .collect();
+                index(&format!("I{}", n), &seq)
+            })
+            .collect();
+        let assigner = IndexAssigner::new(CollisionCheckConfig::strict());
+
+        let assignment = assigner.assign(&[], 20, &candidates).unwrap();
+        assert_eq!(assignment.method, AssignmentMethod::Greedy);
+        assert_eq!(assignment.candidates.len(), 20);
+        for (n, &a) in assignment.candidates.iter().enumerate() {
+            for &b in &assignment.candidates[n + 1..] {
+                assert!(candidates[a].hamming_distance(&candidates[b]) >= 3);
+            }
+        }
+    }
+}
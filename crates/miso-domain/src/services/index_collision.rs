@@ -155,6 +155,7 @@ impl IndexCollisionChecker {
     }
 
     /// Checks if a new index can be added to an existing set without collision.
+    #[allow(clippy::result_large_err)]
     pub fn can_add_index(
         &self,
         existing: &[(String, DnaIndex)],
@@ -307,7 +308,7 @@ mod tests {
 
         let indices = vec![
             ("LIB1".to_string(), DnaIndex::single("A01", "ATCACG", IndexFamily::TruSeq).unwrap()),
-            ("LIB2".to_string(), DnaIndex::single("A02", "ATCACC", IndexFamily::TruSeq).unwrap()), // 2 bases different
+            ("LIB2".to_string(), DnaIndex::single("A02", "ATCAGC", IndexFamily::TruSeq).unwrap()), // 2 bases different
         ];
 
         let collisions = checker.check_indices(&indices);
@@ -0,0 +1,246 @@
+//! Index hopping risk assessment service.
+//!
+//! On patterned flow cells (ExAmp chemistry) free adapters can swap index
+//! reads between molecules, so a read from one library may be assigned to
+//! another. Unique dual indexes (UDIs) make hopped reads detectable because
+//! the hopped i7/i5 combination is never expected. Combinatorial or single
+//! indexing gives no such protection, so pools using them are flagged.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::Library;
+use crate::value_objects::IndexFamily;
+
+/// Typical index hopping rate (percent of reads) on patterned flow cells.
+const PATTERNED_HOP_RATE_PERCENT: f64 = 2.0;
+/// Typical index hopping rate (percent of reads) on non-patterned flow cells.
+const NON_PATTERNED_HOP_RATE_PERCENT: f64 = 0.1;
+
+/// Qualitative index hopping risk for a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HopRisk {
+    /// All libraries are uniquely dual indexed
+    None,
+    /// Some exposure, but unlikely to matter
+    Low,
+    /// Misassigned reads are likely to be noticeable
+    Moderate,
+    /// A substantial share of reads may be misassigned undetectably
+    High,
+}
+
+impl std::fmt::Display for HopRisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Low => write!(f, "Low"),
+            Self::Moderate => write!(f, "Moderate"),
+            Self::High => write!(f, "High"),
+        }
+    }
+}
+
+/// The result of assessing a pool for index hopping risk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexHoppingAssessment {
+    /// Overall risk
+    pub risk: HopRisk,
+    /// Names of libraries not protected by a unique dual index
+    pub non_udi_libraries: Vec<String>,
+    /// Whether UDI and non-UDI index sets are mixed in the pool
+    pub mixes_index_sets: bool,
+    /// Estimated percent of reads that could be silently misassigned
+    pub estimated_misassigned_percent: f64,
+}
+
+impl IndexHoppingAssessment {
+    /// Returns true if the assessment should be shown to the user.
+    pub fn has_warning(&self) -> bool {
+        self.risk > HopRisk::None
+    }
+
+    /// Returns human-readable warnings for display alongside validation.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if !self.has_warning() {
+            return warnings;
+        }
+
+        warnings.push(format!(
+            "{} index hopping risk: {} librar{} without unique dual indexes ({}), ~{:.2}% of reads may be misassigned",
+            self.risk,
+            self.non_udi_libraries.len(),
+            if self.non_udi_libraries.len() == 1 { "y" } else { "ies" },
+            self.non_udi_libraries.join(", "),
+            self.estimated_misassigned_percent
+        ));
+
+        if self.mixes_index_sets {
+            warnings
+                .push("Pool mixes unique dual index sets with non-unique index sets".to_string());
+        }
+
+        warnings
+    }
+}
+
+/// Service for estimating index hopping risk in pools.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IndexHoppingRiskAssessor;
+
+impl IndexHoppingRiskAssessor {
+    /// Creates a new assessor.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Assesses the given libraries as a single pool.
+    ///
+    /// Libraries without an index are ignored; they are caught by pooling
+    /// validation instead.
+    pub fn assess(&self, libraries: &[Library], patterned: bool) -> IndexHoppingAssessment {
+        let indexed: Vec<_> = libraries
+            .iter()
+            .filter_map(|lib| lib.index.as_ref().map(|idx| (lib, idx)))
+            .collect();
+
+        let mut i7_counts: HashMap<&str, usize> = HashMap::new();
+        let mut i5_counts: HashMap<&str, usize> = HashMap::new();
+        for (_, idx) in &indexed {
+            *i7_counts.entry(idx.i7()).or_default() += 1;
+            if let Some(i5) = idx.i5() {
+                *i5_counts.entry(i5).or_default() += 1;
+            }
+        }
+
+        // A library is protected only if both its i7 and i5 are unique in the pool
+        let non_udi_libraries: Vec<String> = indexed
+            .iter()
+            .filter(|(_, idx)| {
+                !idx.is_dual()
+                    || i7_counts[idx.i7()] > 1
+                    || idx.i5().is_some_and(|i5| i5_counts[i5] > 1)
+            })
+            .map(|(lib, _)| lib.name.clone())
+            .collect();
+
+        let has_udi_set = indexed
+            .iter()
+            .any(|(_, idx)| idx.family() == IndexFamily::IdtUdi);
+        let mixes_index_sets = has_udi_set && !non_udi_libraries.is_empty();
+
+        let non_udi_fraction = if indexed.is_empty() {
+            0.0
+        } else {
+            non_udi_libraries.len() as f64 / indexed.len() as f64
+        };
+
+        let base_rate = if patterned {
+            PATTERNED_HOP_RATE_PERCENT
+        } else {
+            NON_PATTERNED_HOP_RATE_PERCENT
+        };
+        let estimated_misassigned_percent = base_rate * non_udi_fraction;
+
+        // A single library can't receive reads from anything else
+        let risk = if non_udi_libraries.is_empty() || indexed.len() < 2 {
+            HopRisk::None
+        } else if !patterned {
+            HopRisk::Low
+        } else if non_udi_fraction < 0.25 {
+            HopRisk::Moderate
+        } else {
+            HopRisk::High
+        };
+
+        IndexHoppingAssessment {
+            risk,
+            non_udi_libraries,
+            mixes_index_sets,
+            estimated_misassigned_percent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{LibraryDesign, LibraryType};
+    use crate::value_objects::{Barcode, DnaIndex};
+
+    fn library(id: i32, index: DnaIndex) -> Library {
+        let mut lib = Library::new(
+            id,
+            format!("LIB{:03}", id),
+            Barcode::new(format!("LIB-{:03}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        lib.set_index(index);
+        lib
+    }
+
+    fn udi(id: i32, i7: &str, i5: &str) -> Library {
+        library(
+            id,
+            DnaIndex::dual(format!("UDP{:04}", id), i7, i5, IndexFamily::IdtUdi).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_udi_pool_has_no_risk() {
+        let libs = vec![
+            udi(1, "ATCACGAT", "AGATCTCG"),
+            udi(2, "CGATGTTT", "CTCTCTAT"),
+        ];
+        let assessment = IndexHoppingRiskAssessor::new().assess(&libs, true);
+
+        assert_eq!(assessment.risk, HopRisk::None);
+        assert!(assessment.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_combinatorial_on_patterned_is_high() {
+        // Two libraries sharing an i5 - combinatorial dual indexing
+        let libs = vec![
+            udi(1, "ATCACGAT", "AGATCTCG"),
+            udi(2, "CGATGTTT", "AGATCTCG"),
+        ];
+        let assessment = IndexHoppingRiskAssessor::new().assess(&libs, true);
+
+        assert_eq!(assessment.risk, HopRisk::High);
+        assert_eq!(assessment.non_udi_libraries.len(), 2);
+        assert!(assessment.mixes_index_sets);
+        assert!((assessment.estimated_misassigned_percent - 2.0).abs() < 1e-9);
+
+        let relaxed = IndexHoppingRiskAssessor::new().assess(&libs, false);
+        assert_eq!(relaxed.risk, HopRisk::Low);
+    }
+
+    #[test]
+    fn test_single_index_library_flagged() {
+        let mut libs = vec![
+            udi(1, "ATCACGAA", "AGATCTAA"),
+            udi(2, "ATCACGCC", "AGATCTCC"),
+            udi(3, "ATCACGGG", "AGATCTGG"),
+            udi(4, "ATCACGTT", "AGATCTTT"),
+        ];
+        libs.push(library(
+            5,
+            DnaIndex::single("A05", "TTAGGCAT", IndexFamily::TruSeq).unwrap(),
+        ));
+
+        let assessment = IndexHoppingRiskAssessor::new().assess(&libs, true);
+        assert_eq!(assessment.non_udi_libraries, vec!["LIB005".to_string()]);
+        assert_eq!(assessment.risk, HopRisk::Moderate);
+        assert_eq!(assessment.warnings().len(), 2);
+    }
+}
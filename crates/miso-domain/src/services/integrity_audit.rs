@@ -0,0 +1,345 @@
+//! Data integrity audit service.
+//!
+//! Checks cross-entity invariants that the database schema can't enforce
+//! on its own. Each check is a pure function over already-loaded entities
+//! and returns the violations it found.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EntityId, Library, Pool, Sample, StorageBox};
+
+/// The invariant a violation breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    /// Every sample belongs to an existing project
+    SampleProject,
+    /// Detailed samples have a parent of the expected class
+    SampleHierarchy,
+    /// Unsequenced pools only contain existing, active libraries
+    PoolElements,
+    /// Boxes only hold items of their storable type
+    BoxContents,
+}
+
+impl std::fmt::Display for IntegrityCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SampleProject => write!(f, "sample_project"),
+            Self::SampleHierarchy => write!(f, "sample_hierarchy"),
+            Self::PoolElements => write!(f, "pool_elements"),
+            Self::BoxContents => write!(f, "box_contents"),
+        }
+    }
+}
+
+/// A single broken invariant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityViolation {
+    /// Which check found it
+    pub check: IntegrityCheck,
+    /// Type of the offending entity
+    pub entity_type: String,
+    /// ID of the offending entity
+    pub entity_id: EntityId,
+    /// What is wrong
+    pub message: String,
+}
+
+impl IntegrityViolation {
+    fn new(check: IntegrityCheck, entity_type: &str, entity_id: EntityId, message: String) -> Self {
+        Self {
+            check,
+            entity_type: entity_type.to_string(),
+            entity_id,
+            message,
+        }
+    }
+}
+
+/// Service for auditing cross-entity invariants.
+pub struct IntegrityAuditor;
+
+impl IntegrityAuditor {
+    /// Finds samples whose project doesn't exist.
+    pub fn check_sample_projects(
+        samples: &[Sample],
+        project_ids: &HashSet<EntityId>,
+    ) -> Vec<IntegrityViolation> {
+        samples
+            .iter()
+            .filter(|s| !project_ids.contains(&s.project_id))
+            .map(|s| {
+                IntegrityViolation::new(
+                    IntegrityCheck::SampleProject,
+                    "Sample",
+                    s.id,
+                    format!("{} references missing project {}", s.name, s.project_id),
+                )
+            })
+            .collect()
+    }
+
+    /// Finds samples whose parent is missing or of the wrong class, and
+    /// pooled samples linked to a single parent.
+    pub fn check_sample_hierarchy(samples: &[Sample]) -> Vec<IntegrityViolation> {
+        let by_id: HashMap<EntityId, &Sample> = samples.iter().map(|s| (s.id, s)).collect();
+        let violation = |sample: &Sample, message: String| {
+            IntegrityViolation::new(
+                IntegrityCheck::SampleHierarchy,
+                "Sample",
+                sample.id,
+                message,
+            )
+        };
+
+        samples
+            .iter()
+            .filter_map(|sample| {
+                let class = sample.sample_class();
+                if sample.is_pooled() {
+                    return sample.parent_id().map(|parent_id| {
+                        violation(
+                            sample,
+                            format!(
+                                "{} is pooled from several identities but has parent {}",
+                                sample.name, parent_id
+                            ),
+                        )
+                    });
+                }
+                match (class.expected_parent(), sample.parent_id()) {
+                    (None, None) => None,
+                    (None, Some(parent_id)) => Some(violation(
+                        sample,
+                        format!(
+                            "{} is a {:?} sample but has parent {}",
+                            sample.name, class, parent_id
+                        ),
+                    )),
+                    (Some(expected), None) => Some(violation(
+                        sample,
+                        format!(
+                            "{} is a {:?} sample with no parent (expected {:?})",
+                            sample.name, class, expected
+                        ),
+                    )),
+                    (Some(expected), Some(parent_id)) => match by_id.get(&parent_id) {
+                        None => Some(violation(
+                            sample,
+                            format!("{} references missing parent {}", sample.name, parent_id),
+                        )),
+                        Some(parent) if parent.sample_class() != expected => Some(violation(
+                            sample,
+                            format!(
+                                "{} is a {:?} sample but its parent {} is {:?} (expected {:?})",
+                                sample.name,
+                                class,
+                                parent.name,
+                                parent.sample_class(),
+                                expected
+                            ),
+                        )),
+                        Some(_) => None,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Finds unsequenced pools referencing missing or archived libraries.
+    ///
+    /// Sequenced pools are skipped: libraries are routinely archived once
+    /// used up, after the pool has run.
+    pub fn check_pool_elements(pools: &[Pool], libraries: &[Library]) -> Vec<IntegrityViolation> {
+        let by_id: HashMap<EntityId, &Library> = libraries.iter().map(|l| (l.id, l)).collect();
+
+        pools
+            .iter()
+            .filter(|pool| !pool.sequenced)
+            .flat_map(|pool| {
+                pool.elements.iter().filter_map(|element| {
+                    let message = match by_id.get(&element.library_id) {
+                        None => format!(
+                            "{} contains missing library {}",
+                            pool.name, element.library_id
+                        ),
+                        Some(library) if library.archived => {
+                            format!("{} contains archived library {}", pool.name, library.name)
+                        }
+                        Some(_) => return None,
+                    };
+                    Some(IntegrityViolation::new(
+                        IntegrityCheck::PoolElements,
+                        "Pool",
+                        pool.id,
+                        message,
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Finds box positions holding an item of the wrong storable type.
+    pub fn check_box_contents(boxes: &[StorageBox]) -> Vec<IntegrityViolation> {
+        boxes
+            .iter()
+            .flat_map(|storage_box| {
+                let mut contents = storage_box.all_contents();
+                contents.sort_by_key(|(pos, _)| **pos);
+                contents
+                    .into_iter()
+                    .filter(|(_, item)| item.item_type != storage_box.storable_type)
+                    .map(|(pos, item)| {
+                        IntegrityViolation::new(
+                            IntegrityCheck::BoxContents,
+                            "StorageBox",
+                            storage_box.id,
+                            format!(
+                                "{} holds {} {} at {} but stores {} items",
+                                storage_box.name,
+                                item.item_type,
+                                item.item_id,
+                                pos,
+                                storage_box.storable_type
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{
+        DetailedSampleData, LibraryDesign, LibraryType, PoolElement, SampleClass, SampleDetails,
+    };
+    use crate::value_objects::Barcode;
+
+    fn sample(id: EntityId, class: SampleClass, parent_id: Option<EntityId>) -> Sample {
+        let mut sample = Sample::new_plain(
+            id,
+            format!("SAM{}", id),
+            Barcode::new(format!("SAM-{}", id)).unwrap(),
+            1,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        );
+        if class != SampleClass::Plain {
+            sample.details = SampleDetails::Detailed(DetailedSampleData {
+                parent_id,
+                sample_class: class,
+                external_name: None,
+                tissue_origin: None,
+                tissue_type: None,
+                time_point: None,
+                group_id: None,
+                group_description: None,
+                passage: None,
+                analyte_type: None,
+                purpose: None,
+                pooled: false,
+            });
+        }
+        sample
+    }
+
+    #[test]
+    fn test_sample_checks() {
+        let pooled = Sample::new_pooled(
+            6,
+            "POOL6".to_string(),
+            Barcode::new("SAM-6").unwrap(),
+            1,
+            "admin".to_string(),
+        );
+        let mut samples = vec![
+            sample(1, SampleClass::Identity, None),
+            sample(2, SampleClass::Tissue, Some(1)),
+            sample(3, SampleClass::Stock, Some(2)),
+            sample(4, SampleClass::Aliquot, Some(99)),
+            sample(5, SampleClass::Tissue, None),
+            pooled,
+            sample(7, SampleClass::Tissue, Some(1)),
+        ];
+        // Pooled, but linked to a single identity
+        if let SampleDetails::Detailed(details) = &mut samples[6].details {
+            details.pooled = true;
+        }
+
+        let violations = IntegrityAuditor::check_sample_hierarchy(&samples);
+        let ids: Vec<_> = violations.iter().map(|v| v.entity_id).collect();
+        assert_eq!(ids, vec![3, 4, 5, 7]);
+
+        let projects = HashSet::from([2]);
+        assert_eq!(
+            IntegrityAuditor::check_sample_projects(&samples, &projects).len(),
+            7
+        );
+    }
+
+    fn library(id: EntityId, archived: bool) -> Library {
+        let mut library = Library::new(
+            id,
+            format!("LIB{}", id),
+            Barcode::new(format!("LIB-{}", id)).unwrap(),
+            1,
+            1,
+            LibraryDesign::Wgs,
+            LibraryType::PairedEnd,
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        library.archived = archived;
+        library
+    }
+
+    fn pool(id: EntityId, library_ids: &[EntityId], sequenced: bool) -> Pool {
+        let mut pool = Pool::new(
+            id,
+            format!("IPO{}", id),
+            Barcode::new(format!("IPO-{}", id)).unwrap(),
+            "Illumina".to_string(),
+            "admin".to_string(),
+        );
+        for &library_id in library_ids {
+            pool.add_element(PoolElement {
+                library_aliquot_id: library_id,
+                library_id,
+                volume: None,
+                proportion: None,
+            })
+            .unwrap();
+        }
+        pool.sequenced = sequenced;
+        pool
+    }
+
+    #[test]
+    fn test_pool_elements_ignore_sequenced_pools() {
+        let libraries = vec![library(1, false), library(2, true)];
+        let pools = vec![
+            pool(10, &[1, 2], false),
+            pool(11, &[1, 3], false),
+            pool(12, &[2, 3], true),
+        ];
+
+        let violations = IntegrityAuditor::check_pool_elements(&pools, &libraries);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.entity_id, v.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (10, "IPO10 contains archived library LIB2"),
+                (11, "IPO11 contains missing library 3"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Library preparation kit compatibility.
+//!
+//! A kit makes libraries of particular designs for particular platforms:
+//! an mRNA kit is no use for WGS, and an Illumina kit is no use on a
+//! PacBio. Sites list the combinations each kit supports; kits they don't
+//! list aren't checked.
+
+use std::collections::HashMap;
+
+use crate::entities::LibraryDesign;
+use crate::errors::LibraryError;
+
+/// A library design and platform a kit can prepare libraries for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KitCombination {
+    pub design: LibraryDesign,
+    pub platform: String,
+}
+
+impl std::fmt::Display for KitCombination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} on {}", self.design, self.platform)
+    }
+}
+
+/// The combinations of design and platform each kit supports.
+///
+/// Kit and platform names are matched case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KitCompatibility {
+    kits: HashMap<String, Vec<KitCombination>>,
+}
+
+impl KitCompatibility {
+    /// Creates a table that allows every kit for everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows `kit` to prepare `design` libraries for `platform`.
+    pub fn with_combination(mut self, kit: &str, design: LibraryDesign, platform: &str) -> Self {
+        self.kits.entry(key(kit)).or_default().push(KitCombination {
+            design,
+            platform: platform.trim().to_string(),
+        });
+        self
+    }
+
+    /// Returns the combinations a kit supports, if it is listed.
+    pub fn allowed(&self, kit: &str) -> Option<&[KitCombination]> {
+        self.kits.get(&key(kit)).map(Vec::as_slice)
+    }
+
+    /// Checks that `kit` can prepare a `design` library for `platform`.
+    pub fn check(
+        &self,
+        kit: &str,
+        design: &LibraryDesign,
+        platform: &str,
+    ) -> Result<(), LibraryError> {
+        let Some(allowed) = self.allowed(kit) else {
+            return Ok(());
+        };
+
+        if allowed
+            .iter()
+            .any(|c| &c.design == design && key(&c.platform) == key(platform))
+        {
+            return Ok(());
+        }
+
+        Err(LibraryError::InvalidKitType {
+            kit: kit.to_string(),
+            design: design.clone(),
+            platform: platform.to_string(),
+            allowed: allowed.to_vec(),
+        })
+    }
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listed_kits_are_checked() {
+        let kits = KitCompatibility::new()
+            .with_combination("TruSeq Stranded mRNA", LibraryDesign::RnaSeq, "illumina")
+            .with_combination("SMRTbell Prep Kit 3.0", LibraryDesign::Wgs, "pac_bio")
+            .with_combination("SMRTbell Prep Kit 3.0", LibraryDesign::RnaSeq, "pac_bio");
+
+        assert!(kits
+            .check("truseq stranded mrna", &LibraryDesign::RnaSeq, "Illumina")
+            .is_ok());
+        assert!(kits
+            .check("SMRTbell Prep Kit 3.0", &LibraryDesign::RnaSeq, "pac_bio")
+            .is_ok());
+        assert!(kits
+            .check("Unlisted Kit", &LibraryDesign::Wgs, "pac_bio")
+            .is_ok());
+
+        match kits.check("SMRTbell Prep Kit 3.0", &LibraryDesign::Wes, "illumina") {
+            Err(LibraryError::InvalidKitType { allowed, .. }) => {
+                assert_eq!(allowed.len(), 2);
+                assert_eq!(allowed[0].to_string(), "WGS on pac_bio");
+            }
+            other => panic!("expected an invalid kit, got {:?}", other),
+        }
+    }
+}
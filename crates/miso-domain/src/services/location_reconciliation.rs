@@ -0,0 +1,190 @@
+//! Location reconciliation service.
+//!
+//! Cross-checks what boxes say they contain against where each storable
+//! item records itself as stored. The recorded locations are expected to
+//! cover every existing item of the checked types, including items that are
+//! not stored anywhere, so that box contents referring to no known item can
+//! be reported too.
+
+use std::collections::HashMap;
+
+use crate::entities::{
+    DiscrepancyKind, EntityId, LocationDiscrepancy, RecordedLocation, StorableItem, StorageBox,
+};
+use crate::value_objects::BoxPosition;
+
+/// Service comparing box contents against recorded item locations.
+pub struct LocationReconciler;
+
+impl LocationReconciler {
+    /// Returns every disagreement between the boxes and the recorded locations.
+    ///
+    /// Discrepancies are ordered by box and position, followed by items
+    /// missing from the box they record.
+    pub fn reconcile(
+        boxes: &[StorageBox],
+        recorded: &[RecordedLocation],
+    ) -> Vec<LocationDiscrepancy> {
+        let recorded_by_item: HashMap<&StorableItem, &RecordedLocation> =
+            recorded.iter().map(|r| (&r.item, r)).collect();
+
+        let mut placements: HashMap<&StorableItem, Vec<(EntityId, BoxPosition)>> = HashMap::new();
+        let mut order = Vec::new();
+        for storage_box in boxes {
+            let mut contents = storage_box.all_contents();
+            contents.sort_by_key(|(pos, _)| **pos);
+            for (pos, item) in contents {
+                let entry = placements.entry(item).or_default();
+                if entry.is_empty() {
+                    order.push(item);
+                }
+                entry.push((storage_box.id, *pos));
+            }
+        }
+
+        let mut discrepancies = Vec::new();
+
+        for item in order {
+            let found = &placements[item];
+            let recorded = recorded_by_item.get(item).copied();
+            let discrepancy = |kind, (box_id, pos): (EntityId, BoxPosition)| LocationDiscrepancy {
+                item: item.clone(),
+                kind,
+                box_id: Some(box_id),
+                position: Some(pos.to_string()),
+                recorded_box_id: recorded.and_then(|r| r.box_id),
+                recorded_position: recorded.and_then(|r| r.position).map(|p| p.to_string()),
+            };
+
+            if found.len() > 1 {
+                discrepancies.extend(
+                    found.iter().map(|&placement| {
+                        discrepancy(DiscrepancyKind::MultiplePlacements, placement)
+                    }),
+                );
+                continue;
+            }
+
+            let (box_id, pos) = found[0];
+            let kind = match recorded {
+                None => Some(DiscrepancyKind::UnknownItem),
+                Some(RecordedLocation { box_id: None, .. }) => {
+                    Some(DiscrepancyKind::UnrecordedLocation)
+                }
+                Some(r) if r.box_id != Some(box_id) => Some(DiscrepancyKind::WrongBox),
+                Some(r) if r.position != Some(pos) => Some(DiscrepancyKind::WrongPosition),
+                Some(_) => None,
+            };
+            if let Some(kind) = kind {
+                discrepancies.push(discrepancy(kind, (box_id, pos)));
+            }
+        }
+
+        for r in recorded {
+            if r.box_id.is_some() && !placements.contains_key(&r.item) {
+                discrepancies.push(LocationDiscrepancy {
+                    item: r.item.clone(),
+                    kind: DiscrepancyKind::MissingFromBox,
+                    box_id: None,
+                    position: None,
+                    recorded_box_id: r.box_id,
+                    recorded_position: r.position.map(|p| p.to_string()),
+                });
+            }
+        }
+
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(
+        item: StorableItem,
+        box_id: Option<EntityId>,
+        pos: Option<&str>,
+    ) -> RecordedLocation {
+        RecordedLocation {
+            item,
+            box_id,
+            position: pos.map(|p| {
+                BoxPosition::new_unchecked(p.as_bytes()[0] as char, p[1..].parse().unwrap())
+            }),
+        }
+    }
+
+    #[test]
+    fn test_consistent_locations_have_no_discrepancies() {
+        let mut storage_box = StorageBox::sample_box_9x9(1, "BOX001".to_string());
+        storage_box
+            .place_item(BoxPosition::new_unchecked('A', 1), StorableItem::sample(10))
+            .unwrap();
+
+        let discrepancies = LocationReconciler::reconcile(
+            &[storage_box],
+            &[
+                recorded(StorableItem::sample(10), Some(1), Some("A1")),
+                recorded(StorableItem::sample(11), None, None),
+            ],
+        );
+
+        assert!(discrepancies.is_empty());
+    }
+
+    #[test]
+    fn test_detects_each_kind_of_drift() {
+        let mut box_a = StorageBox::sample_box_9x9(1, "BOX001".to_string());
+        let mut box_b = StorageBox::sample_box_9x9(2, "BOX002".to_string());
+        box_a
+            .place_item(BoxPosition::new_unchecked('A', 1), StorableItem::sample(10))
+            .unwrap();
+        box_a
+            .place_item(BoxPosition::new_unchecked('A', 2), StorableItem::sample(11))
+            .unwrap();
+        box_a
+            .place_item(BoxPosition::new_unchecked('A', 3), StorableItem::sample(12))
+            .unwrap();
+        box_a
+            .place_item(BoxPosition::new_unchecked('A', 4), StorableItem::sample(13))
+            .unwrap();
+        box_b
+            .place_item(BoxPosition::new_unchecked('B', 1), StorableItem::sample(13))
+            .unwrap();
+        box_b
+            .place_item(BoxPosition::new_unchecked('B', 2), StorableItem::sample(99))
+            .unwrap();
+
+        let discrepancies = LocationReconciler::reconcile(
+            &[box_a, box_b],
+            &[
+                recorded(StorableItem::sample(10), Some(1), Some("C5")),
+                recorded(StorableItem::sample(11), Some(2), Some("A2")),
+                recorded(StorableItem::sample(12), None, None),
+                recorded(StorableItem::sample(13), Some(1), Some("A4")),
+                recorded(StorableItem::sample(14), Some(1), Some("A5")),
+            ],
+        );
+
+        let kinds: Vec<_> = discrepancies
+            .iter()
+            .map(|d| (d.item.item_id, d.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (10, DiscrepancyKind::WrongPosition),
+                (11, DiscrepancyKind::WrongBox),
+                (12, DiscrepancyKind::UnrecordedLocation),
+                (13, DiscrepancyKind::MultiplePlacements),
+                (13, DiscrepancyKind::MultiplePlacements),
+                (99, DiscrepancyKind::UnknownItem),
+                (14, DiscrepancyKind::MissingFromBox),
+            ]
+        );
+        assert_eq!(discrepancies[0].position.as_deref(), Some("A1"));
+        assert_eq!(discrepancies[0].recorded_position.as_deref(), Some("C5"));
+        assert_eq!(discrepancies[4].box_id, Some(2));
+    }
+}
@@ -3,9 +3,51 @@
 //! These services contain pure domain logic that doesn't belong to a single
 //! entity. They are dependency-free and can be tested in isolation.
 
+mod activity_feed;
+mod assay_completion;
 mod barcode_validation;
+mod carryover;
+mod control_policy;
+mod identity_matching;
+mod index_assignment;
 mod index_collision;
+mod index_hopping;
+mod integrity_audit;
+mod kit_compatibility;
+mod location_reconciliation;
+mod log_chain;
+mod normalization;
+mod plexity;
+mod pool_balancing;
+mod pool_dilution;
+mod qc_evaluator;
+mod qc_transition;
+mod storage_usage;
+mod yield_statistics;
 
+pub use activity_feed::ActivityFeed;
+pub use assay_completion::{
+    AssayCompletionEvaluator, AssayRequirements, CompletionStatus, LibraryCompletion,
+};
 pub use barcode_validation::BarcodeValidator;
-pub use index_collision::IndexCollisionChecker;
+pub use carryover::{CarryoverChecker, CarryoverRisk, PlannedLane};
+pub use control_policy::ControlPolicy;
+pub use identity_matching::{normalize_external_name, IdentityMatch, IdentityMatcher};
+pub use index_assignment::{AssignmentMethod, IndexAssigner, IndexAssignment, EXHAUSTIVE_LIMIT};
+pub use index_collision::{CollisionCheckConfig, IndexCollision, IndexCollisionChecker};
+pub use index_hopping::{HopRisk, IndexHoppingAssessment, IndexHoppingRiskAssessor};
+pub use integrity_audit::{IntegrityAuditor, IntegrityCheck, IntegrityViolation};
+pub use kit_compatibility::{KitCombination, KitCompatibility};
+pub use location_reconciliation::LocationReconciler;
+pub use log_chain::{sha256_hex, ChainBreak, LogChainVerifier};
+pub use normalization::{Dilution, Normalizer};
+pub use plexity::PlexityLimits;
+pub use pool_balancing::{PoolBalance, PoolBalancer, PoolMember, PoolShare, YieldTargets};
+pub use pool_dilution::{
+    EquimolarPooler, PipettingStep, PoolingLibrary, PoolingWorksheet, DEFAULT_MIN_PIPETTE_UL,
+};
+pub use qc_evaluator::{QcEvaluator, QcRollup, QcThreshold};
+pub use qc_transition::QcTransitionPolicy;
+pub use storage_usage::{StorageTotal, StorageUsage};
+pub use yield_statistics::YieldStatistics;
 
@@ -0,0 +1,97 @@
+//! Library normalization.
+//!
+//! Works out how to dilute a library to a target molarity before pooling:
+//! C1·V1 = C2·V2, with mass concentrations converted to molarity from the
+//! library's fragment size.
+
+use serde::{Deserialize, Serialize};
+
+use crate::value_objects::{Concentration, Volume};
+
+/// Volumes to combine to normalize a library.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Dilution {
+    /// Volume of library to take
+    pub library: Volume,
+    /// Volume of diluent to add
+    pub diluent: Volume,
+}
+
+/// Computes the dilution that brings a library to a target molarity.
+pub struct Normalizer;
+
+impl Normalizer {
+    /// Returns the library molarity in nM, or `None` if a mass
+    /// concentration is given without a fragment size.
+    pub fn molarity_nm(concentration: Concentration, fragment_size_bp: Option<u32>) -> Option<f64> {
+        concentration
+            .to_molarity(fragment_size_bp)
+            .map(|m| m.as_nanomolar())
+    }
+
+    /// Returns how much library and diluent make `final_volume` at
+    /// `target`.
+    ///
+    /// Returns `None` if the stock can't be converted to molarity or is
+    /// already more dilute than the target.
+    pub fn dilution(
+        stock: Concentration,
+        fragment_size_bp: Option<u32>,
+        target: Concentration,
+        final_volume: Volume,
+    ) -> Option<Dilution> {
+        let stock_nm = Self::molarity_nm(stock, fragment_size_bp)?;
+        let target_nm = Self::molarity_nm(target, fragment_size_bp)?;
+        if stock_nm <= 0.0 || stock_nm < target_nm {
+            return None;
+        }
+
+        let total_ul = final_volume.as_microliters();
+        let library_ul = total_ul * target_nm / stock_nm;
+
+        Some(Dilution {
+            library: Volume::microliters(library_ul),
+            diluent: Volume::microliters(total_ul - library_ul),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_molarity_from_mass_needs_fragment_size() {
+        let conc = Concentration::ng_per_ul(3.3);
+
+        assert_eq!(Normalizer::molarity_nm(conc, None), None);
+        let nm = Normalizer::molarity_nm(conc, Some(500)).unwrap();
+        assert!((nm - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dilution_to_target() {
+        let dilution = Normalizer::dilution(
+            Concentration::nanomolar(20.0),
+            None,
+            Concentration::nanomolar(4.0),
+            Volume::microliters(50.0),
+        )
+        .unwrap();
+
+        assert!((dilution.library.as_microliters() - 10.0).abs() < 1e-9);
+        assert!((dilution.diluent.as_microliters() - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dilute_stock_cannot_reach_target() {
+        let dilution = Normalizer::dilution(
+            Concentration::picomolar(500.0),
+            None,
+            Concentration::nanomolar(2.0),
+            Volume::microliters(20.0),
+        );
+
+        assert!(dilution.is_none());
+    }
+}
@@ -0,0 +1,79 @@
+//! Pool plexity limits.
+//!
+//! How many libraries can share a run depends on the platform and, more
+//! tightly, on the flow cell: a site may allow 384 libraries in an Illumina
+//! pool but only 12 on a Flongle. Sites configure both, and a container's
+//! limit overrides its platform's.
+
+use std::collections::HashMap;
+
+/// The most library aliquots a pool may hold, by platform and container
+/// model.
+///
+/// Names are matched case-insensitively. Pools for a platform and
+/// container with no limit are unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlexityLimits {
+    platforms: HashMap<String, usize>,
+    containers: HashMap<String, usize>,
+}
+
+impl PlexityLimits {
+    /// Creates limits that allow pools of any size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits pools for a platform, e.g. "illumina".
+    pub fn with_platform(mut self, platform: &str, max_size: usize) -> Self {
+        self.platforms.insert(key(platform), max_size);
+        self
+    }
+
+    /// Limits pools for a container model, e.g. "Flongle Flow Cell".
+    pub fn with_container(mut self, container_model: &str, max_size: usize) -> Self {
+        self.containers.insert(key(container_model), max_size);
+        self
+    }
+
+    /// Returns the most library aliquots a pool for `platform`, made for
+    /// `container_model` if known, may hold.
+    pub fn max_size(&self, platform: &str, container_model: Option<&str>) -> Option<usize> {
+        container_model
+            .and_then(|container| self.containers.get(&key(container)))
+            .or_else(|| self.platforms.get(&key(platform)))
+            .copied()
+    }
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_limit_overrides_platform_limit() {
+        let limits = PlexityLimits::new()
+            .with_platform("illumina", 384)
+            .with_container("S4 Flow Cell", 1536)
+            .with_container("Flongle Flow Cell", 12);
+
+        assert_eq!(limits.max_size("ILLUMINA", None), Some(384));
+        assert_eq!(
+            limits.max_size("Illumina", Some("MiSeq v2 Flow Cell")),
+            Some(384)
+        );
+        assert_eq!(
+            limits.max_size("illumina", Some("s4 flow cell")),
+            Some(1536)
+        );
+        assert_eq!(
+            limits.max_size("oxford_nanopore", Some("Flongle Flow Cell")),
+            Some(12)
+        );
+        assert_eq!(limits.max_size("pac_bio", None), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Pool balancing.
+//!
+//! Libraries sequenced together share their lanes' reads in proportion to
+//! how many molecules of each go into the pool. Given the reads each
+//! library's assay needs and the reads a lane is expected to give, this
+//! proposes each library's share of the pool, how many lanes the pool
+//! needs, and, for libraries of known molarity, how much of each to pool.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EntityId, LibraryDesign};
+use crate::errors::DomainError;
+use crate::value_objects::{Concentration, Volume};
+
+/// Reads each library design needs, and reads each lane gives, by
+/// platform and container model.
+///
+/// Platform and container names are matched case-insensitively; a
+/// container's lane output overrides its platform's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YieldTargets {
+    designs: HashMap<LibraryDesign, u64>,
+    platforms: HashMap<String, u64>,
+    containers: HashMap<String, u64>,
+}
+
+impl YieldTargets {
+    /// Creates targets with no reads configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the reads a library of a design needs.
+    pub fn with_design(mut self, design: LibraryDesign, reads: u64) -> Self {
+        self.designs.insert(design, reads);
+        self
+    }
+
+    /// Sets the reads a lane gives on a platform, e.g. "illumina".
+    pub fn with_platform(mut self, platform: &str, lane_reads: u64) -> Self {
+        self.platforms.insert(key(platform), lane_reads);
+        self
+    }
+
+    /// Sets the reads a lane gives on a container model, e.g. "S4 Flow
+    /// Cell".
+    pub fn with_container(mut self, container_model: &str, lane_reads: u64) -> Self {
+        self.containers.insert(key(container_model), lane_reads);
+        self
+    }
+
+    /// Returns the reads a library of `design` needs, if configured.
+    pub fn required_reads(&self, design: &LibraryDesign) -> Option<u64> {
+        self.designs.get(design).copied()
+    }
+
+    /// Returns the reads a lane gives on `platform`, in `container_model`
+    /// if known.
+    pub fn lane_reads(&self, platform: &str, container_model: Option<&str>) -> Option<u64> {
+        container_model
+            .and_then(|container| self.containers.get(&key(container)))
+            .or_else(|| self.platforms.get(&key(platform)))
+            .copied()
+    }
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// A library aliquot to balance in a pool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolMember {
+    /// The aliquot pooled
+    pub library_aliquot_id: EntityId,
+    /// Its library
+    pub library_id: EntityId,
+    /// Reads the library needs
+    pub required_reads: u64,
+    /// The aliquot's molarity in nM, if known
+    pub molarity_nm: Option<f64>,
+}
+
+/// A library aliquot's proposed share of a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PoolShare {
+    /// The aliquot pooled
+    pub library_aliquot_id: EntityId,
+    /// Its library
+    pub library_id: EntityId,
+    /// Reads the library needs
+    pub required_reads: u64,
+    /// Share of the pool, from 0 to 1
+    pub proportion: f64,
+    /// Reads the library is expected to get across the pool's lanes
+    pub expected_reads: u64,
+    /// Volume of the aliquot to pool, if a dilution was asked for and its
+    /// molarity is known
+    pub volume: Option<Volume>,
+}
+
+/// Proposed proportions for a pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolBalance {
+    /// Each aliquot's share
+    pub shares: Vec<PoolShare>,
+    /// Reads a lane is expected to give
+    pub lane_reads: u64,
+    /// Reads the pool's libraries need in total
+    pub required_reads: u64,
+    /// Lanes needed to give every library its reads
+    pub lanes: u32,
+    /// Diluent to make up the pool's volume, if every aliquot's volume is
+    /// known
+    pub diluent: Option<Volume>,
+}
+
+/// Proposes pool proportions from read targets.
+pub struct PoolBalancer;
+
+impl PoolBalancer {
+    /// Shares a pool between its members in proportion to the reads each
+    /// needs.
+    ///
+    /// With a `dilution` of target molarity and final volume, the volume
+    /// of each member is worked out too: a member making up `p` of the pool
+    /// takes `p · target · volume / molarity`. This fails if the members
+    /// are too dilute to make the pool at that molarity.
+    pub fn balance(
+        members: &[PoolMember],
+        lane_reads: u64,
+        dilution: Option<(Concentration, Volume)>,
+    ) -> Result<PoolBalance, DomainError> {
+        if members.is_empty() {
+            return Err(DomainError::Validation(
+                "A pool needs libraries to be balanced".to_string(),
+            ));
+        }
+        if lane_reads == 0 {
+            return Err(DomainError::Validation(
+                "Lane output must be more than zero reads".to_string(),
+            ));
+        }
+        if let Some(member) = members.iter().find(|m| m.required_reads == 0) {
+            return Err(DomainError::Validation(format!(
+                "Library {} must need more than zero reads",
+                member.library_id
+            )));
+        }
+
+        let required_reads: u64 = members.iter().map(|m| m.required_reads).sum();
+        let lanes = required_reads.div_ceil(lane_reads).max(1) as u32;
+        let target = match dilution {
+            Some((target, volume)) => Some((
+                target
+                    .to_nanomolar(None)
+                    .map(|c| c.value())
+                    .ok_or_else(|| {
+                        DomainError::Validation("Pool target must be a molarity".to_string())
+                    })?,
+                volume.as_microliters(),
+            )),
+            None => None,
+        };
+
+        let shares: Vec<PoolShare> = members
+            .iter()
+            .map(|member| {
+                let proportion = member.required_reads as f64 / required_reads as f64;
+                let volume = target.zip(member.molarity_nm.filter(|nm| *nm > 0.0)).map(
+                    |((target_nm, total_ul), molarity_nm)| {
+                        Volume::microliters(proportion * target_nm * total_ul / molarity_nm)
+                    },
+                );
+                PoolShare {
+                    library_aliquot_id: member.library_aliquot_id,
+                    library_id: member.library_id,
+                    required_reads: member.required_reads,
+                    proportion,
+                    expected_reads: (proportion * (lane_reads * lanes as u64) as f64).round()
+                        as u64,
+                    volume,
+                }
+            })
+            .collect();
+
+        let diluent = match target {
+            Some((_, total_ul)) if shares.iter().all(|s| s.volume.is_some()) => {
+                let pooled_ul: f64 = shares
+                    .iter()
+                    .filter_map(|s| s.volume)
+                    .map(|v| v.as_microliters())
+                    .sum();
+                if pooled_ul > total_ul {
+                    return Err(DomainError::Validation(format!(
+                        "The libraries are too dilute to make {} µL at the target; \
+                         they need {:.1} µL",
+                        total_ul, pooled_ul
+                    )));
+                }
+                Some(Volume::microliters(total_ul - pooled_ul))
+            }
+            _ => None,
+        };
+
+        Ok(PoolBalance {
+            shares,
+            lane_reads,
+            required_reads,
+            lanes,
+            diluent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(id: EntityId, required_reads: u64, molarity_nm: Option<f64>) -> PoolMember {
+        PoolMember {
+            library_aliquot_id: id,
+            library_id: id,
+            required_reads,
+            molarity_nm,
+        }
+    }
+
+    #[test]
+    fn test_container_lane_reads_override_platform() {
+        let targets = YieldTargets::new()
+            .with_design(LibraryDesign::RnaSeq, 30_000_000)
+            .with_platform("illumina", 400_000_000)
+            .with_container("S4 Flow Cell", 2_500_000_000);
+
+        assert_eq!(
+            targets.required_reads(&LibraryDesign::RnaSeq),
+            Some(30_000_000)
+        );
+        assert_eq!(targets.required_reads(&LibraryDesign::Wgs), None);
+        assert_eq!(targets.lane_reads("ILLUMINA", None), Some(400_000_000));
+        assert_eq!(
+            targets.lane_reads("illumina", Some("s4 flow cell")),
+            Some(2_500_000_000)
+        );
+        assert_eq!(targets.lane_reads("pac_bio", Some("SMRT Cell 8M")), None);
+    }
+
+    #[test]
+    fn test_shares_follow_required_reads() {
+        let balance = PoolBalancer::balance(
+            &[member(1, 300_000_000, None), member(2, 100_000_000, None)],
+            300_000_000,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(balance.required_reads, 400_000_000);
+        assert_eq!(balance.lanes, 2);
+        assert!((balance.shares[0].proportion - 0.75).abs() < 1e-9);
+        assert_eq!(balance.shares[0].expected_reads, 450_000_000);
+        assert_eq!(balance.shares[1].expected_reads, 150_000_000);
+        assert_eq!(balance.diluent, None);
+
+        assert!(PoolBalancer::balance(&[], 300_000_000, None).is_err());
+        assert!(PoolBalancer::balance(&[member(1, 1, None)], 0, None).is_err());
+    }
+
+    #[test]
+    fn test_volumes_make_the_pool_at_target() {
+        let dilution = Some((Concentration::nanomolar(2.0), Volume::microliters(50.0)));
+        let balance = PoolBalancer::balance(
+            &[member(1, 30, Some(10.0)), member(2, 10, Some(5.0))],
+            100,
+            dilution,
+        )
+        .unwrap();
+
+        // 0.75 · 2 nM · 50 µL / 10 nM and 0.25 · 2 nM · 50 µL / 5 nM
+        let volumes: Vec<f64> = balance
+            .shares
+            .iter()
+            .map(|s| s.volume.unwrap().as_microliters())
+            .collect();
+        assert!((volumes[0] - 7.5).abs() < 1e-9);
+        assert!((volumes[1] - 5.0).abs() < 1e-9);
+        assert!((balance.diluent.unwrap().as_microliters() - 37.5).abs() < 1e-9);
+
+        let unknown = PoolBalancer::balance(&[member(1, 30, None)], 100, dilution).unwrap();
+        assert_eq!(unknown.shares[0].volume, None);
+        assert_eq!(unknown.diluent, None);
+
+        assert!(PoolBalancer::balance(&[member(1, 30, Some(1.0))], 100, dilution).is_err());
+    }
+}
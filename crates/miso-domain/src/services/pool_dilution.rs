@@ -0,0 +1,252 @@
+//! Equimolar pool dilution.
+//!
+//! Works out how much of each library to pipette so that a pool of a given
+//! volume holds every library at the same molarity, adding up to a target
+//! pool molarity. A library at `m` nM in a pool of `n` libraries at
+//! `target` nM and volume `V` takes `target · V / (n · m)`; the rest of the
+//! pool is diluent.
+//!
+//! Volumes too small to pipette accurately are made up from a predilution
+//! of the library instead, diluted so that exactly the smallest volume is
+//! taken from it.
+
+use serde::{Deserialize, Serialize};
+
+use super::normalization::{Dilution, Normalizer};
+use crate::entities::EntityId;
+use crate::errors::DomainError;
+use crate::value_objects::{Concentration, Volume};
+
+/// Smallest volume pipetted when none is given, in µL.
+pub const DEFAULT_MIN_PIPETTE_UL: f64 = 0.5;
+
+/// A library to pool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolingLibrary {
+    pub library_id: EntityId,
+    /// The library's concentration, by mass or molarity
+    pub concentration: Concentration,
+    /// Fragment size, needed to convert a mass concentration to molarity
+    pub fragment_size_bp: Option<u32>,
+}
+
+/// How much of a library goes into the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PipettingStep {
+    pub library_id: EntityId,
+    /// The library's molarity in nM
+    pub molarity_nm: f64,
+    /// Library and diluent to combine first, if the library is too
+    /// concentrated to pipette straight into the pool
+    pub predilution: Option<Dilution>,
+    /// Volume to add to the pool, of the library or of its predilution
+    pub volume: Volume,
+}
+
+/// Volumes to combine to make an equimolar pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolingWorksheet {
+    /// Molarity of the pool in nM
+    pub target_nm: f64,
+    /// Volume of the pool
+    pub final_volume: Volume,
+    /// Molarity of each library in the pool, in nM
+    pub per_library_nm: f64,
+    /// One step per library, in the order given
+    pub steps: Vec<PipettingStep>,
+    /// Diluent to make up the pool's volume
+    pub diluent: Volume,
+}
+
+/// Computes equimolar pooling volumes.
+pub struct EquimolarPooler;
+
+impl EquimolarPooler {
+    /// Returns the volumes that pool `libraries` equimolar at `target` in
+    /// `final_volume`, pipetting no less than `min_pipette`.
+    ///
+    /// Fails if the target isn't a positive molarity, if any library's
+    /// molarity can't be worked out, or if the libraries are too dilute to
+    /// make the pool.
+    pub fn worksheet(
+        libraries: &[PoolingLibrary],
+        target: Concentration,
+        final_volume: Volume,
+        min_pipette: Volume,
+    ) -> Result<PoolingWorksheet, DomainError> {
+        if libraries.is_empty() {
+            return Err(DomainError::Validation(
+                "A pool needs libraries to be worked out".to_string(),
+            ));
+        }
+        let target_nm = Normalizer::molarity_nm(target, None)
+            .filter(|nm| *nm > 0.0)
+            .ok_or_else(|| {
+                DomainError::Validation("Pool target must be a molarity above zero".to_string())
+            })?;
+        let total_ul = final_volume.as_microliters();
+        if total_ul <= 0.0 {
+            return Err(DomainError::Validation(
+                "Pool volume must be more than zero".to_string(),
+            ));
+        }
+
+        let unknown: Vec<String> = libraries
+            .iter()
+            .filter(|l| {
+                Normalizer::molarity_nm(l.concentration, l.fragment_size_bp)
+                    .is_none_or(|nm| nm <= 0.0)
+            })
+            .map(|l| l.library_id.to_string())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(DomainError::Validation(format!(
+                "The molarity of libraries {} is unknown; give a molarity, or a \
+                 concentration and fragment size",
+                unknown.join(", ")
+            )));
+        }
+
+        let per_library_nm = target_nm / libraries.len() as f64;
+        let min_ul = min_pipette.as_microliters();
+        let steps: Vec<PipettingStep> = libraries
+            .iter()
+            .map(|library| {
+                let molarity_nm =
+                    Normalizer::molarity_nm(library.concentration, library.fragment_size_bp)
+                        .expect("molarities are checked above");
+                let volume_ul = per_library_nm * total_ul / molarity_nm;
+                if volume_ul >= min_ul || min_ul <= 0.0 {
+                    return PipettingStep {
+                        library_id: library.library_id,
+                        molarity_nm,
+                        predilution: None,
+                        volume: Volume::microliters(volume_ul),
+                    };
+                }
+
+                // Dilute so that the smallest volume carries the library's
+                // share, taking the smallest volume of library to do so
+                let working_nm = per_library_nm * total_ul / min_ul;
+                let predilution = Normalizer::dilution(
+                    Concentration::nanomolar(molarity_nm),
+                    None,
+                    Concentration::nanomolar(working_nm),
+                    Volume::microliters(min_ul * molarity_nm / working_nm),
+                );
+                PipettingStep {
+                    library_id: library.library_id,
+                    molarity_nm,
+                    predilution,
+                    volume: min_pipette,
+                }
+            })
+            .collect();
+
+        let pooled_ul: f64 = steps.iter().map(|s| s.volume.as_microliters()).sum();
+        if pooled_ul > total_ul {
+            return Err(DomainError::Validation(format!(
+                "The libraries are too dilute to make {} µL at {} nM; they need {:.1} µL",
+                total_ul, target_nm, pooled_ul
+            )));
+        }
+
+        Ok(PoolingWorksheet {
+            target_nm,
+            final_volume,
+            per_library_nm,
+            steps,
+            diluent: Volume::microliters(total_ul - pooled_ul),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(id: EntityId, concentration: Concentration, size: Option<u32>) -> PoolingLibrary {
+        PoolingLibrary {
+            library_id: id,
+            concentration,
+            fragment_size_bp: size,
+        }
+    }
+
+    fn worksheet(libraries: &[PoolingLibrary], target_nm: f64, volume_ul: f64) -> PoolingWorksheet {
+        EquimolarPooler::worksheet(
+            libraries,
+            Concentration::nanomolar(target_nm),
+            Volume::microliters(volume_ul),
+            Volume::microliters(DEFAULT_MIN_PIPETTE_UL),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_libraries_pool_equimolar() {
+        // 3.3 ng/µL of 500 bp is 10 nM
+        let sheet = worksheet(
+            &[
+                library(1, Concentration::nanomolar(20.0), None),
+                library(2, Concentration::ng_per_ul(3.3), Some(500)),
+            ],
+            4.0,
+            50.0,
+        );
+
+        // Each library is 2 nM of the pool: 2 · 50 / 20 and 2 · 50 / 10
+        assert!((sheet.per_library_nm - 2.0).abs() < 1e-9);
+        assert!((sheet.steps[0].volume.as_microliters() - 5.0).abs() < 1e-9);
+        assert!((sheet.steps[1].volume.as_microliters() - 10.0).abs() < 1e-9);
+        assert!((sheet.steps[1].molarity_nm - 10.0).abs() < 1e-9);
+        assert!((sheet.diluent.as_microliters() - 35.0).abs() < 1e-9);
+        assert!(sheet.steps.iter().all(|s| s.predilution.is_none()));
+    }
+
+    #[test]
+    fn test_concentrated_library_is_prediluted() {
+        let sheet = worksheet(
+            &[
+                library(1, Concentration::nanomolar(400.0), None),
+                library(2, Concentration::nanomolar(10.0), None),
+            ],
+            2.0,
+            20.0,
+        );
+
+        // 1 nM · 20 µL / 400 nM is 0.05 µL, so 0.5 µL of a 40 nM
+        // predilution goes in instead: 0.5 µL of library in 5 µL
+        let step = sheet.steps[0];
+        assert!((step.volume.as_microliters() - 0.5).abs() < 1e-9);
+        let predilution = step.predilution.unwrap();
+        assert!((predilution.library.as_microliters() - 0.5).abs() < 1e-9);
+        assert!((predilution.diluent.as_microliters() - 4.5).abs() < 1e-9);
+        assert!((sheet.diluent.as_microliters() - 17.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unknown_or_dilute_libraries_fail() {
+        let target = Concentration::nanomolar(4.0);
+        let volume = Volume::microliters(20.0);
+        let min = Volume::microliters(DEFAULT_MIN_PIPETTE_UL);
+
+        let no_size = [library(7, Concentration::ng_per_ul(3.3), None)];
+        let error = EquimolarPooler::worksheet(&no_size, target, volume, min).unwrap_err();
+        assert!(error.to_string().contains("libraries 7"));
+
+        let dilute = [
+            library(1, Concentration::nanomolar(2.0), None),
+            library(2, Concentration::nanomolar(2.0), None),
+        ];
+        assert!(EquimolarPooler::worksheet(&dilute, target, volume, min).is_err());
+        assert!(EquimolarPooler::worksheet(&[], target, volume, min).is_err());
+        assert!(EquimolarPooler::worksheet(
+            &dilute,
+            Concentration::ng_per_ul(4.0),
+            volume,
+            min
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! QC status rollup from QC measurements.
+//!
+//! An entity's QC status follows from the measurements taken of it: the
+//! latest measurement of each test counts, and the entity fails if any of
+//! them failed. Sites set thresholds for tests, by kind of entity (e.g. a
+//! library needs at least 2 ng/µL on the Qubit), and a measured value is
+//! judged against them rather than trusted as recorded. A final Passed or
+//! Failed status is never overturned by measurements; if they disagree with
+//! it, it is kept and flagged for review.
+
+use std::collections::HashMap;
+
+use crate::entities::QcTarget;
+use crate::value_objects::{QcResult, QcStatus, QcTestType};
+
+/// The range a test's value must fall in, inclusive. Either end may be
+/// open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QcThreshold {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl QcThreshold {
+    /// Returns true if `value` is within the range.
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// What rolling an entity's measurements up does to its QC status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QcRollup {
+    /// The measurements agree with the current status
+    Unchanged,
+    /// The entity takes the rolled-up status
+    Changed(QcStatus),
+    /// The measurements roll up to this status, but the entity's final
+    /// status is kept until someone reviews it
+    NeedsReview(QcStatus),
+}
+
+/// Service working out QC status from measurements and per-entity-type
+/// thresholds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcEvaluator {
+    thresholds: HashMap<(QcTarget, String), QcThreshold>,
+}
+
+impl QcEvaluator {
+    /// Creates an evaluator without thresholds, which takes measurements'
+    /// statuses as recorded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the range a test's value must fall in for one kind of entity.
+    pub fn with_threshold(
+        mut self,
+        target: QcTarget,
+        test_type: &QcTestType,
+        threshold: QcThreshold,
+    ) -> Self {
+        self.thresholds.insert((target, key(test_type)), threshold);
+        self
+    }
+
+    /// Returns the threshold for a test on a kind of entity, if any.
+    pub fn threshold(&self, target: QcTarget, test_type: &QcTestType) -> Option<QcThreshold> {
+        self.thresholds.get(&(target, key(test_type))).copied()
+    }
+
+    /// Judges one measurement: against its test's threshold if there is
+    /// one, else as recorded. A measurement without a value can't pass a
+    /// threshold and needs review.
+    pub fn evaluate(&self, target: QcTarget, result: &QcResult) -> QcStatus {
+        match (self.threshold(target, &result.test_type), result.value) {
+            (None, _) => result.status,
+            (Some(_), None) => QcStatus::NeedsReview,
+            (Some(threshold), Some(value)) if threshold.contains(value) => QcStatus::Passed,
+            (Some(_), Some(_)) => QcStatus::Failed,
+        }
+    }
+
+    /// Works out an entity's QC status from all its measurements.
+    ///
+    /// Only the latest measurement of each test counts. The entity fails
+    /// if any of those failed, needs review if any needs review, and
+    /// passes if all passed. Without measurements it isn't ready.
+    pub fn rollup(&self, target: QcTarget, results: &[QcResult]) -> QcStatus {
+        let mut latest: HashMap<String, &QcResult> = HashMap::new();
+        for result in results {
+            let entry = latest.entry(key(&result.test_type)).or_insert(result);
+            if result.performed_at >= entry.performed_at {
+                *entry = result;
+            }
+        }
+        if latest.is_empty() {
+            return QcStatus::NotReady;
+        }
+
+        let statuses: Vec<QcStatus> = latest
+            .values()
+            .map(|result| self.evaluate(target, result))
+            .collect();
+        if statuses.contains(&QcStatus::Failed) {
+            QcStatus::Failed
+        } else if statuses.contains(&QcStatus::NeedsReview) {
+            QcStatus::NeedsReview
+        } else if statuses.iter().all(|s| *s == QcStatus::Passed) {
+            QcStatus::Passed
+        } else {
+            QcStatus::Ready
+        }
+    }
+
+    /// Works out what the measurements do to an entity now at `current`.
+    /// Statuses that aren't final follow the measurements; a final Passed
+    /// or Failed status only changes through a reviewed QC status change.
+    pub fn settle(&self, current: QcStatus, target: QcTarget, results: &[QcResult]) -> QcRollup {
+        let status = self.rollup(target, results);
+        if status == current {
+            QcRollup::Unchanged
+        } else if current.is_complete() {
+            QcRollup::NeedsReview(status)
+        } else {
+            QcRollup::Changed(status)
+        }
+    }
+}
+
+/// Tests are matched by name, ignoring case.
+fn key(test_type: &QcTestType) -> String {
+    test_type.to_string().trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    fn measured(test_type: QcTestType, value: Option<f64>, minutes_ago: i64) -> QcResult {
+        let mut result = QcResult::passed(test_type, value, None, "tech");
+        result.status = QcStatus::Ready;
+        result.performed_at = Utc::now() - Duration::minutes(minutes_ago);
+        result
+    }
+
+    fn evaluator() -> QcEvaluator {
+        QcEvaluator::new()
+            .with_threshold(
+                QcTarget::Library,
+                &QcTestType::Qubit,
+                QcThreshold {
+                    min: Some(2.0),
+                    max: None,
+                },
+            )
+            .with_threshold(
+                QcTarget::Library,
+                &QcTestType::TapeStation,
+                QcThreshold {
+                    min: Some(200.0),
+                    max: Some(800.0),
+                },
+            )
+    }
+
+    #[test]
+    fn test_thresholds_apply_to_their_entity_type() {
+        let evaluator = evaluator();
+        let low = measured(QcTestType::Qubit, Some(1.5), 0);
+
+        assert_eq!(evaluator.evaluate(QcTarget::Library, &low), QcStatus::Failed);
+        assert_eq!(
+            evaluator.evaluate(QcTarget::Library, &measured(QcTestType::Qubit, Some(2.0), 0)),
+            QcStatus::Passed
+        );
+        assert_eq!(
+            evaluator.evaluate(QcTarget::Library, &measured(QcTestType::Qubit, None, 0)),
+            QcStatus::NeedsReview
+        );
+        // Samples have no Qubit threshold, so the recorded status stands
+        assert_eq!(evaluator.evaluate(QcTarget::Sample, &low), QcStatus::Ready);
+    }
+
+    #[test]
+    fn test_rollup_counts_the_latest_measurement_of_each_test() {
+        let evaluator = evaluator();
+        let results = vec![
+            measured(QcTestType::Qubit, Some(1.0), 30),
+            measured(QcTestType::Qubit, Some(4.2), 10),
+            measured(QcTestType::TapeStation, Some(350.0), 20),
+        ];
+        assert_eq!(
+            evaluator.rollup(QcTarget::Library, &results),
+            QcStatus::Passed
+        );
+
+        let mut retested = results.clone();
+        retested.push(measured(QcTestType::TapeStation, Some(950.0), 0));
+        assert_eq!(
+            evaluator.rollup(QcTarget::Library, &retested),
+            QcStatus::Failed
+        );
+
+        assert_eq!(evaluator.rollup(QcTarget::Library, &[]), QcStatus::NotReady);
+        assert_eq!(
+            evaluator.rollup(QcTarget::Sample, &results),
+            QcStatus::Ready
+        );
+    }
+
+    #[test]
+    fn test_final_statuses_are_kept_for_review() {
+        let evaluator = evaluator();
+        let passing = vec![measured(QcTestType::Qubit, Some(4.2), 10)];
+        let mut failing = passing.clone();
+        failing.push(measured(QcTestType::Qubit, Some(1.0), 0));
+
+        assert_eq!(
+            evaluator.settle(QcStatus::Ready, QcTarget::Library, &passing),
+            QcRollup::Changed(QcStatus::Passed)
+        );
+        assert_eq!(
+            evaluator.settle(QcStatus::Passed, QcTarget::Library, &passing),
+            QcRollup::Unchanged
+        );
+        assert_eq!(
+            evaluator.settle(QcStatus::Passed, QcTarget::Library, &failing),
+            QcRollup::NeedsReview(QcStatus::Failed)
+        );
+        assert_eq!(
+            evaluator.settle(QcStatus::Failed, QcTarget::Library, &passing),
+            QcRollup::NeedsReview(QcStatus::Passed)
+        );
+    }
+}
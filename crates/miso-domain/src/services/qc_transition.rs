@@ -0,0 +1,122 @@
+//! QC status transition rules.
+//!
+//! QC is recorded as Not Ready → Ready → Passed/Failed, with Needs Review
+//! for results that need a second look. Once a result is final (Passed or
+//! Failed), overturning it is a lab manager's call and must be justified.
+//! Any failure must say why.
+
+use crate::entities::Role;
+use crate::errors::DomainError;
+use crate::value_objects::QcStatus;
+
+/// Service deciding whether a QC status change is allowed.
+pub struct QcTransitionPolicy;
+
+impl QcTransitionPolicy {
+    /// Checks a change from `from` to `to` by a user with `role`.
+    ///
+    /// Changing to the current status is always allowed and is not a
+    /// transition.
+    pub fn check(
+        from: QcStatus,
+        to: QcStatus,
+        role: Role,
+        reason: Option<&str>,
+    ) -> Result<(), DomainError> {
+        if from == to {
+            return Ok(());
+        }
+
+        if from.is_complete() && matches!(to, QcStatus::NotReady | QcStatus::Ready) {
+            return Err(Self::invalid(from, to));
+        }
+
+        let has_reason = reason.is_some_and(|r| !r.trim().is_empty());
+
+        if from.is_complete() {
+            if !role.has_at_least(&Role::LabManager) {
+                return Err(DomainError::PermissionDenied(format!(
+                    "changing QC status from {} to {} requires the {} role",
+                    from,
+                    to,
+                    Role::LabManager
+                )));
+            }
+            if !has_reason {
+                return Err(Self::reason_required(from, to));
+            }
+        }
+
+        if to == QcStatus::Failed && !has_reason {
+            return Err(Self::reason_required(from, to));
+        }
+
+        Ok(())
+    }
+
+    fn invalid(from: QcStatus, to: QcStatus) -> DomainError {
+        DomainError::InvalidStateTransition {
+            entity: "QC status".to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    fn reason_required(from: QcStatus, to: QcStatus) -> DomainError {
+        DomainError::Validation(format!(
+            "A reason is required to change QC status from {} to {}",
+            from, to
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_transitions() {
+        use QcStatus::*;
+
+        assert!(QcTransitionPolicy::check(NotReady, Ready, Role::Technician, None).is_ok());
+        assert!(QcTransitionPolicy::check(Ready, Passed, Role::Technician, None).is_ok());
+        assert!(QcTransitionPolicy::check(NeedsReview, Passed, Role::Technician, None).is_ok());
+        assert!(QcTransitionPolicy::check(Passed, Passed, Role::Viewer, None).is_ok());
+
+        assert!(matches!(
+            QcTransitionPolicy::check(Ready, Failed, Role::Technician, None),
+            Err(DomainError::Validation(_))
+        ));
+        assert!(matches!(
+            QcTransitionPolicy::check(Ready, Failed, Role::Technician, Some("  ")),
+            Err(DomainError::Validation(_))
+        ));
+        assert!(
+            QcTransitionPolicy::check(Ready, Failed, Role::Technician, Some("Degraded RIN"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_overturning_final_results() {
+        use QcStatus::*;
+
+        assert!(matches!(
+            QcTransitionPolicy::check(Passed, Failed, Role::Technician, Some("Contaminated")),
+            Err(DomainError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            QcTransitionPolicy::check(Failed, Passed, Role::LabManager, None),
+            Err(DomainError::Validation(_))
+        ));
+        assert!(
+            QcTransitionPolicy::check(Passed, Failed, Role::LabManager, Some("Contaminated"))
+                .is_ok()
+        );
+        assert!(QcTransitionPolicy::check(Failed, NeedsReview, Role::Admin, Some("Rerun")).is_ok());
+        assert!(matches!(
+            QcTransitionPolicy::check(Passed, Ready, Role::SuperAdmin, Some("Retest")),
+            Err(DomainError::InvalidStateTransition { .. })
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+//! Storage usage accounting for sequencing output.
+//!
+//! Totals the bytes held at registered data locations per project and per
+//! platform. Purged locations no longer count. A location shared by
+//! several projects (a lane holding pools from different groups, say) has
+//! its bytes split evenly between them, so project totals add up to the
+//! bytes actually stored.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{DataLocation, EntityId, Platform};
+
+/// Storage held for one project or platform.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageTotal {
+    /// Bytes stored
+    pub bytes: u64,
+    /// Number of locations contributing
+    pub locations: usize,
+}
+
+impl StorageTotal {
+    fn add(&mut self, bytes: u64) {
+        self.bytes += bytes;
+        self.locations += 1;
+    }
+}
+
+/// Storage usage across all unpurged data locations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Bytes stored in total
+    pub total_bytes: u64,
+    /// Usage per project
+    pub by_project: BTreeMap<EntityId, StorageTotal>,
+    /// Usage per platform
+    pub by_platform: Vec<(Platform, StorageTotal)>,
+    /// Locations not attributed to any project
+    pub unattributed: StorageTotal,
+    /// Locations whose size is not known, and so are not counted
+    pub unsized_locations: usize,
+}
+
+impl StorageUsage {
+    /// Totals usage over a set of data locations.
+    pub fn summarize(locations: &[DataLocation]) -> Self {
+        let mut usage = Self::default();
+
+        for location in locations.iter().filter(|l| !l.is_purged()) {
+            let Some(bytes) = location.size_bytes else {
+                usage.unsized_locations += 1;
+                continue;
+            };
+            usage.total_bytes += bytes;
+
+            if let Some(platform) = location.platform {
+                match usage.by_platform.iter_mut().find(|(p, _)| *p == platform) {
+                    Some((_, total)) => total.add(bytes),
+                    None => {
+                        let mut total = StorageTotal::default();
+                        total.add(bytes);
+                        usage.by_platform.push((platform, total));
+                    }
+                }
+            }
+
+            let mut projects = location.project_ids.clone();
+            projects.sort_unstable();
+            projects.dedup();
+            if projects.is_empty() {
+                usage.unattributed.add(bytes);
+                continue;
+            }
+
+            let share = bytes / projects.len() as u64;
+            let remainder = bytes % projects.len() as u64;
+            for (i, project_id) in projects.into_iter().enumerate() {
+                let extra = if i == 0 { remainder } else { 0 };
+                usage
+                    .by_project
+                    .entry(project_id)
+                    .or_default()
+                    .add(share + extra);
+            }
+        }
+
+        usage
+            .by_platform
+            .sort_by_key(|(_, total)| std::cmp::Reverse(total.bytes));
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::entities::RetentionClass;
+
+    fn location(size: Option<u64>, platform: Platform, projects: &[EntityId]) -> DataLocation {
+        let mut location = DataLocation::new(
+            1,
+            None,
+            "/data/runs/RUN1".to_string(),
+            RetentionClass::Standard,
+            "pipeline".to_string(),
+        )
+        .unwrap();
+        location.size_bytes = size;
+        location.platform = Some(platform);
+        location.project_ids = projects.to_vec();
+        location
+    }
+
+    #[test]
+    fn test_summarize_splits_shared_locations() {
+        let mut purged = location(Some(500), Platform::Illumina, &[1]);
+        purged.purged_at = Some(Utc::now());
+
+        let usage = StorageUsage::summarize(&[
+            location(Some(1001), Platform::Illumina, &[1, 2]),
+            location(Some(300), Platform::OxfordNanopore, &[2]),
+            location(Some(50), Platform::Illumina, &[]),
+            location(None, Platform::Illumina, &[1]),
+            purged,
+        ]);
+
+        assert_eq!(usage.total_bytes, 1351);
+        assert_eq!(usage.by_project[&1].bytes, 501);
+        assert_eq!(usage.by_project[&2].bytes, 800);
+        assert_eq!(usage.by_project[&2].locations, 2);
+        assert_eq!(usage.unattributed.bytes, 50);
+        assert_eq!(usage.unsized_locations, 1);
+        assert_eq!(usage.by_platform[0].0, Platform::Illumina);
+        assert_eq!(usage.by_platform[0].1.bytes, 1051);
+    }
+}
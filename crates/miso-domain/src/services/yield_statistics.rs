@@ -0,0 +1,67 @@
+//! Yield statistics for extraction batches.
+//!
+//! A kit lot going bad shows as batches whose yields sit below the rest,
+//! so each batch's yields are summarized as a distribution that can be
+//! compared across batches and lots.
+
+use serde::{Deserialize, Serialize};
+
+/// Distribution of the yields of a set of samples, in ng.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YieldStatistics {
+    /// Number of yields summarized
+    pub count: usize,
+    pub min: f64,
+    /// Middle yield, or the mean of the middle two
+    pub median: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl YieldStatistics {
+    /// Summarizes yields, ignoring any that aren't finite. Returns `None`
+    /// if there are none.
+    pub fn of(yields: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = yields.iter().copied().filter(|y| y.is_finite()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+
+        let count = sorted.len();
+        // The same element twice when the count is odd
+        let median = (sorted[(count - 1) / 2] + sorted[count / 2]) / 2.0;
+
+        Some(Self {
+            count,
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<f64>() / count as f64,
+            max: sorted[count - 1],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarizes_yields() {
+        assert_eq!(YieldStatistics::of(&[]), None);
+        assert_eq!(YieldStatistics::of(&[f64::NAN]), None);
+
+        let stats = YieldStatistics::of(&[900.0, 300.0, f64::NAN, 600.0, 1000.0]).unwrap();
+        assert_eq!(
+            stats,
+            YieldStatistics {
+                count: 4,
+                min: 300.0,
+                median: 750.0,
+                mean: 700.0,
+                max: 1000.0,
+            }
+        );
+        assert_eq!(YieldStatistics::of(&[5.0]).unwrap().median, 5.0);
+    }
+}
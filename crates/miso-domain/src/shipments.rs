@@ -0,0 +1,27 @@
+//! Shipment Tracking Traits - interfaces for asking couriers where a
+//! shipment has got to.
+//!
+//! Like run sources, shipment trackers are implemented in the
+//! infrastructure layer (a courier's API or a tracking aggregator) so that
+//! a scheduled job can follow transfers sent by courier without knowing
+//! which service answers.
+
+use async_trait::async_trait;
+
+use crate::entities::ShipmentStatus;
+use crate::errors::DomainError;
+
+/// Reports the status of couriered shipments.
+#[async_trait]
+pub trait ShipmentTracker: Send + Sync {
+    /// Name of the service, for logging, e.g. "aftership".
+    fn name(&self) -> &str;
+
+    /// Returns the status of the shipment with a courier's tracking
+    /// number, or `None` if the courier doesn't know of it yet.
+    async fn status(
+        &self,
+        courier: &str,
+        tracking_number: &str,
+    ) -> Result<Option<ShipmentStatus>, DomainError>;
+}
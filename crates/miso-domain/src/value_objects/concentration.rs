@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use super::{Molarity, MolarityUnit};
+
 /// Units of concentration measurement.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -73,25 +75,57 @@ impl Concentration {
         self.unit
     }
 
-    /// Converts to nanomolar (if applicable).
+    /// Returns true if this is a molar concentration.
+    pub fn is_molar(&self) -> bool {
+        matches!(
+            self.unit,
+            ConcentrationUnit::Nanomolar | ConcentrationUnit::Picomolar
+        )
+    }
+
+    /// Returns the concentration in ng/µL, if it is a mass concentration.
+    pub fn as_ng_per_ul(&self) -> Option<f64> {
+        match self.unit {
+            // 1 µg/mL is 1 ng/µL
+            ConcentrationUnit::NgPerUl | ConcentrationUnit::UgPerMl => Some(self.value),
+            ConcentrationUnit::Nanomolar | ConcentrationUnit::Picomolar => None,
+        }
+    }
+
+    /// Converts to a molarity.
     ///
-    /// Note: This requires additional information (like fragment size) for
-    /// accurate ng/µL to nM conversion. This is a simplified version.
-    pub fn to_nanomolar(&self, fragment_size_bp: Option<u32>) -> Option<Self> {
+    /// Mass concentrations need the fragment size; `None` is returned for
+    /// them without one.
+    pub fn to_molarity(&self, fragment_size_bp: Option<u32>) -> Option<Molarity> {
         match self.unit {
-            ConcentrationUnit::Nanomolar => Some(*self),
-            ConcentrationUnit::Picomolar => Some(Self::nanomolar(self.value / 1000.0)),
-            ConcentrationUnit::NgPerUl => {
-                // Formula: nM = (ng/µL * 1,000,000) / (660 * fragment_size)
-                fragment_size_bp.map(|size| {
-                    let nm = (self.value * 1_000_000.0) / (660.0 * size as f64);
-                    Self::nanomolar(nm)
-                })
+            ConcentrationUnit::Nanomolar => Some(Molarity::nanomolar(self.value)),
+            ConcentrationUnit::Picomolar => Some(Molarity::picomolar(self.value)),
+            ConcentrationUnit::NgPerUl | ConcentrationUnit::UgPerMl => {
+                Molarity::from_ng_per_ul(self.value, fragment_size_bp?)
             }
-            ConcentrationUnit::UgPerMl => None, // Would need molecular weight
         }
     }
 
+    /// Converts to a mass concentration in ng/µL.
+    ///
+    /// Molar concentrations need the fragment size; `None` is returned for
+    /// them without one.
+    pub fn to_ng_per_ul(&self, fragment_size_bp: Option<u32>) -> Option<Self> {
+        match self.as_ng_per_ul() {
+            Some(ng_per_ul) => Some(Self::ng_per_ul(ng_per_ul)),
+            None => {
+                let molarity = self.to_molarity(None)?;
+                Some(Self::ng_per_ul(molarity.as_ng_per_ul(fragment_size_bp?)))
+            }
+        }
+    }
+
+    /// Converts to nanomolar; mass concentrations need the fragment size.
+    pub fn to_nanomolar(&self, fragment_size_bp: Option<u32>) -> Option<Self> {
+        self.to_molarity(fragment_size_bp)
+            .map(|molarity| Self::nanomolar(molarity.as_nanomolar()))
+    }
+
     /// Checks if this concentration meets a minimum threshold.
     pub fn meets_threshold(&self, threshold: f64, unit: ConcentrationUnit) -> bool {
         if self.unit == unit {
@@ -103,6 +137,15 @@ impl Concentration {
     }
 }
 
+impl From<Molarity> for Concentration {
+    fn from(molarity: Molarity) -> Self {
+        match molarity.unit() {
+            MolarityUnit::Nanomolar => Self::nanomolar(molarity.value()),
+            MolarityUnit::Picomolar => Self::picomolar(molarity.value()),
+        }
+    }
+}
+
 impl fmt::Display for Concentration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:.2} {}", self.value, self.unit)
@@ -134,6 +177,23 @@ mod tests {
         assert_eq!(nm.unit(), ConcentrationUnit::Nanomolar);
     }
 
+    #[test]
+    fn test_mass_and_molar_conversions() {
+        let mass = Concentration::ng_per_ul(3.3);
+        assert!(mass.to_molarity(None).is_none());
+        let molarity = mass.to_molarity(Some(500)).unwrap();
+        assert!((molarity.as_nanomolar() - 10.0).abs() < 1e-9);
+
+        // µg/mL is the same as ng/µL
+        let same = Concentration::new(3.3, ConcentrationUnit::UgPerMl);
+        assert_eq!(same.to_molarity(Some(500)), Some(molarity));
+
+        let back = Concentration::from(molarity).to_ng_per_ul(Some(500)).unwrap();
+        assert!((back.value() - 3.3).abs() < 1e-9);
+        assert!(Concentration::picomolar(750.0).to_ng_per_ul(None).is_none());
+        assert!(Concentration::picomolar(750.0).is_molar());
+    }
+
     #[test]
     #[should_panic]
     fn test_negative_concentration() {
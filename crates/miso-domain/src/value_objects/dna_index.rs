@@ -138,6 +138,39 @@ impl DnaIndex {
         self.i7_sequence.len() + self.i5_sequence.as_ref().map(|s| s.len()).unwrap_or(0)
     }
 
+    /// Returns true if the given sequences identify this index.
+    ///
+    /// Instruments differ in whether i5 is read on the forward strand or as
+    /// its reverse complement, so either orientation is accepted for i5.
+    pub fn matches_sequences(&self, i7: &str, i5: Option<&str>) -> bool {
+        if !self.i7_sequence.eq_ignore_ascii_case(i7) {
+            return false;
+        }
+
+        match (&self.i5_sequence, i5) {
+            (Some(own), Some(other)) => {
+                let other = other.to_uppercase();
+                *own == other || Self::reverse_complement(own) == other
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the reverse complement of a sequence.
+    pub fn reverse_complement(seq: &str) -> String {
+        seq.chars()
+            .rev()
+            .map(|c| match c {
+                'A' => 'T',
+                'T' => 'A',
+                'C' => 'G',
+                'G' => 'C',
+                other => other,
+            })
+            .collect()
+    }
+
     /// Calculates the Hamming distance between this index and another.
     ///
     /// This is critical for detecting potential barcode collisions in pools.
@@ -230,7 +263,18 @@ mod tests {
         let idx3 = DnaIndex::single("A03", "TTAGGC", IndexFamily::TruSeq).unwrap();
 
         assert_eq!(idx1.hamming_distance(&idx2), 0); // Same sequence
-        assert_eq!(idx1.hamming_distance(&idx3), 6); // Completely different
+        assert_eq!(idx1.hamming_distance(&idx3), 5); // Five of six bases differ
+    }
+
+    #[test]
+    fn test_matches_sequences_either_i5_orientation() {
+        let idx = DnaIndex::dual("UDP0001", "GAACTGAGCG", "TCGTGGAGCG", IndexFamily::IdtUdi)
+            .unwrap();
+
+        assert!(idx.matches_sequences("gaactgagcg", Some("TCGTGGAGCG")));
+        assert!(idx.matches_sequences("GAACTGAGCG", Some("CGCTCCACGA")));
+        assert!(!idx.matches_sequences("GAACTGAGCG", None));
+        assert!(!idx.matches_sequences("GAACTGAGCT", Some("TCGTGGAGCG")));
     }
 
     #[test]
@@ -0,0 +1,195 @@
+//! Mass value object for tracking DNA and RNA input amounts.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, Sub};
+
+use super::Volume;
+
+/// Units of mass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MassUnit {
+    /// Nanograms (ng) - library prep inputs
+    Nanograms,
+    /// Micrograms (µg) - extraction yields
+    Micrograms,
+}
+
+impl MassUnit {
+    /// Conversion factor to nanograms.
+    fn to_ng_factor(self) -> f64 {
+        match self {
+            Self::Nanograms => 1.0,
+            Self::Micrograms => 1000.0,
+        }
+    }
+}
+
+impl fmt::Display for MassUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nanograms => write!(f, "ng"),
+            Self::Micrograms => write!(f, "µg"),
+        }
+    }
+}
+
+/// A mass of nucleic acid with its unit.
+///
+/// Library preps call for an input mass rather than a volume, so the mass
+/// left in a tube matters as much as its volume.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Mass {
+    /// Value stored internally in nanograms for consistency
+    value_ng: f64,
+    /// Original unit for display purposes
+    display_unit: MassUnit,
+}
+
+impl Mass {
+    /// Creates a new mass.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is negative or NaN.
+    pub fn new(value: f64, unit: MassUnit) -> Self {
+        assert!(value >= 0.0 && !value.is_nan(), "Mass must be non-negative");
+        Self {
+            value_ng: value * unit.to_ng_factor(),
+            display_unit: unit,
+        }
+    }
+
+    /// Creates a mass in nanograms.
+    pub fn nanograms(value: f64) -> Self {
+        Self::new(value, MassUnit::Nanograms)
+    }
+
+    /// Creates a mass in micrograms.
+    pub fn micrograms(value: f64) -> Self {
+        Self::new(value, MassUnit::Micrograms)
+    }
+
+    /// Creates a zero mass.
+    pub fn zero() -> Self {
+        Self {
+            value_ng: 0.0,
+            display_unit: MassUnit::Nanograms,
+        }
+    }
+
+    /// Returns the mass in `volume` at a concentration in ng/µL.
+    pub fn in_volume(volume: Volume, ng_per_ul: f64) -> Self {
+        Self::nanograms(volume.as_microliters() * ng_per_ul)
+    }
+
+    /// Returns the value in nanograms.
+    pub fn as_nanograms(&self) -> f64 {
+        self.value_ng
+    }
+
+    /// Returns the value in micrograms.
+    pub fn as_micrograms(&self) -> f64 {
+        self.value_ng / 1000.0
+    }
+
+    /// Returns the display unit.
+    pub fn unit(&self) -> MassUnit {
+        self.display_unit
+    }
+
+    /// Returns the value in the display unit.
+    pub fn value(&self) -> f64 {
+        self.value_ng / self.display_unit.to_ng_factor()
+    }
+
+    /// Returns true if this mass is zero.
+    pub fn is_zero(&self) -> bool {
+        self.value_ng == 0.0
+    }
+
+    /// Returns the volume holding this mass at a concentration in ng/µL, or
+    /// `None` if the concentration is zero.
+    pub fn volume_at(&self, ng_per_ul: f64) -> Option<Volume> {
+        (ng_per_ul > 0.0).then(|| Volume::microliters(self.value_ng / ng_per_ul))
+    }
+
+    /// Subtracts a mass, returning the remaining mass.
+    ///
+    /// Returns None if the subtraction would result in negative mass.
+    pub fn subtract(&self, amount: Mass) -> Option<Self> {
+        let remaining = self.value_ng - amount.value_ng;
+        (remaining >= 0.0).then_some(Self {
+            value_ng: remaining,
+            display_unit: self.display_unit,
+        })
+    }
+}
+
+impl fmt::Display for Mass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.value(), self.display_unit)
+    }
+}
+
+impl Add for Mass {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            value_ng: self.value_ng + rhs.value_ng,
+            display_unit: self.display_unit,
+        }
+    }
+}
+
+impl Sub for Mass {
+    type Output = Option<Self>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.subtract(rhs)
+    }
+}
+
+impl PartialOrd for Mass {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value_ng.partial_cmp(&other.value_ng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mass_units() {
+        let mass = Mass::micrograms(1.5);
+        assert_eq!(mass.as_nanograms(), 1500.0);
+        assert_eq!(mass.to_string(), "1.50 µg");
+        assert!(Mass::nanograms(2000.0) > mass);
+        assert_eq!((mass + Mass::nanograms(500.0)).as_micrograms(), 2.0);
+    }
+
+    #[test]
+    fn test_mass_subtraction() {
+        let mass = Mass::nanograms(100.0);
+        assert_eq!(mass.subtract(Mass::nanograms(30.0)).unwrap().as_nanograms(), 70.0);
+        assert!((mass - Mass::nanograms(101.0)).is_none());
+    }
+
+    #[test]
+    fn test_mass_and_volume_at_concentration() {
+        // 200 ng at 25 ng/µL is 8 µL
+        let volume = Mass::nanograms(200.0).volume_at(25.0).unwrap();
+        assert_eq!(volume.as_microliters(), 8.0);
+        assert_eq!(Mass::in_volume(volume, 25.0).as_nanograms(), 200.0);
+        assert!(Mass::nanograms(200.0).volume_at(0.0).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_mass() {
+        Mass::nanograms(-1.0);
+    }
+}
@@ -6,14 +6,18 @@
 mod barcode;
 mod concentration;
 mod dna_index;
+mod mass;
+mod molarity;
 mod position;
 mod qc_status;
 mod volume;
 
 pub use barcode::Barcode;
-pub use concentration::Concentration;
+pub use concentration::{Concentration, ConcentrationUnit};
 pub use dna_index::{DnaIndex, IndexFamily};
+pub use mass::{Mass, MassUnit};
+pub use molarity::{Molarity, MolarityUnit, BASE_PAIR_MASS_G_PER_MOL};
 pub use position::{BoxPosition, Dimension};
-pub use qc_status::{QcResult, QcStatus};
+pub use qc_status::{QcResult, QcStatus, QcTestType};
 pub use volume::Volume;
 
@@ -0,0 +1,186 @@
+//! Molarity value object for library loading calculations.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::Volume;
+
+/// Average mass of a double-stranded DNA base pair, in g/mol.
+pub const BASE_PAIR_MASS_G_PER_MOL: f64 = 660.0;
+
+/// Units of molarity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MolarityUnit {
+    /// Nanomolar (nM) - libraries and pools
+    Nanomolar,
+    /// Picomolar (pM) - Illumina loading concentrations
+    Picomolar,
+}
+
+impl MolarityUnit {
+    /// Conversion factor to nanomolar.
+    fn to_nm_factor(self) -> f64 {
+        match self {
+            Self::Nanomolar => 1.0,
+            Self::Picomolar => 0.001,
+        }
+    }
+}
+
+impl fmt::Display for MolarityUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nanomolar => write!(f, "nM"),
+            Self::Picomolar => write!(f, "pM"),
+        }
+    }
+}
+
+/// A molar concentration of DNA molecules with its unit.
+///
+/// Sequencers are loaded by molarity (Illumina, in pM) or by amount
+/// (Oxford Nanopore, in fmol), while fluorometers measure mass
+/// concentration; converting between them needs the fragment length.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Molarity {
+    /// Value stored internally in nanomolar for consistency
+    value_nm: f64,
+    /// Original unit for display purposes
+    display_unit: MolarityUnit,
+}
+
+impl Molarity {
+    /// Creates a new molarity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if value is negative or NaN.
+    pub fn new(value: f64, unit: MolarityUnit) -> Self {
+        assert!(value >= 0.0 && !value.is_nan(), "Molarity must be non-negative");
+        Self {
+            value_nm: value * unit.to_nm_factor(),
+            display_unit: unit,
+        }
+    }
+
+    /// Creates a molarity in nM.
+    pub fn nanomolar(value: f64) -> Self {
+        Self::new(value, MolarityUnit::Nanomolar)
+    }
+
+    /// Creates a molarity in pM.
+    pub fn picomolar(value: f64) -> Self {
+        Self::new(value, MolarityUnit::Picomolar)
+    }
+
+    /// Returns the molarity of a mass concentration in ng/µL of fragments
+    /// `fragment_size_bp` long, or `None` for fragments of no length.
+    ///
+    /// nM = ng/µL × 10⁶ / (660 × bp)
+    pub fn from_ng_per_ul(ng_per_ul: f64, fragment_size_bp: u32) -> Option<Self> {
+        (fragment_size_bp > 0).then(|| {
+            Self::nanomolar(
+                ng_per_ul * 1_000_000.0 / (BASE_PAIR_MASS_G_PER_MOL * fragment_size_bp as f64),
+            )
+        })
+    }
+
+    /// Returns the mass concentration in ng/µL of fragments
+    /// `fragment_size_bp` long at this molarity.
+    pub fn as_ng_per_ul(&self, fragment_size_bp: u32) -> f64 {
+        self.value_nm * BASE_PAIR_MASS_G_PER_MOL * fragment_size_bp as f64 / 1_000_000.0
+    }
+
+    /// Returns the value in nM.
+    pub fn as_nanomolar(&self) -> f64 {
+        self.value_nm
+    }
+
+    /// Returns the value in pM.
+    pub fn as_picomolar(&self) -> f64 {
+        self.value_nm * 1000.0
+    }
+
+    /// Returns the display unit.
+    pub fn unit(&self) -> MolarityUnit {
+        self.display_unit
+    }
+
+    /// Returns the value in the display unit.
+    pub fn value(&self) -> f64 {
+        self.value_nm / self.display_unit.to_nm_factor()
+    }
+
+    /// Returns the same molarity displayed in another unit.
+    pub fn in_unit(&self, unit: MolarityUnit) -> Self {
+        Self {
+            value_nm: self.value_nm,
+            display_unit: unit,
+        }
+    }
+
+    /// Returns the amount of DNA in `volume` at this molarity, in fmol
+    /// (1 nM × 1 µL is 1 fmol).
+    pub fn fmol_in(&self, volume: Volume) -> f64 {
+        self.value_nm * volume.as_microliters()
+    }
+
+    /// Returns the volume holding `fmol` at this molarity, or `None` if
+    /// the molarity is zero.
+    pub fn volume_for_fmol(&self, fmol: f64) -> Option<Volume> {
+        (self.value_nm > 0.0 && fmol >= 0.0).then(|| Volume::microliters(fmol / self.value_nm))
+    }
+}
+
+impl fmt::Display for Molarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.value(), self.display_unit)
+    }
+}
+
+impl PartialOrd for Molarity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value_nm.partial_cmp(&other.value_nm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_molarity_units() {
+        let m = Molarity::picomolar(1500.0);
+        assert!((m.as_nanomolar() - 1.5).abs() < 1e-9);
+        assert_eq!(m.unit(), MolarityUnit::Picomolar);
+        assert_eq!(m.to_string(), "1500.00 pM");
+        assert_eq!(m.in_unit(MolarityUnit::Nanomolar).to_string(), "1.50 nM");
+        assert!(Molarity::nanomolar(2.0) > m);
+    }
+
+    #[test]
+    fn test_mass_conversion_round_trips() {
+        // 3.3 ng/µL of 500 bp fragments is 10 nM
+        let m = Molarity::from_ng_per_ul(3.3, 500).unwrap();
+        assert!((m.as_nanomolar() - 10.0).abs() < 1e-9);
+        assert!((m.as_ng_per_ul(500) - 3.3).abs() < 1e-9);
+        assert!(Molarity::from_ng_per_ul(3.3, 0).is_none());
+    }
+
+    #[test]
+    fn test_amounts_for_loading() {
+        // 50 fmol of a 20 nM library for a nanopore flow cell is 2.5 µL
+        let m = Molarity::nanomolar(20.0);
+        let volume = m.volume_for_fmol(50.0).unwrap();
+        assert!((volume.as_microliters() - 2.5).abs() < 1e-9);
+        assert!((m.fmol_in(volume) - 50.0).abs() < 1e-9);
+        assert!(Molarity::nanomolar(0.0).volume_for_fmol(50.0).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_molarity() {
+        Molarity::nanomolar(-1.0);
+    }
+}
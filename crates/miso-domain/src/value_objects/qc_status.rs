@@ -84,6 +84,24 @@ impl fmt::Display for QcTestType {
     }
 }
 
+impl std::str::FromStr for QcTestType {
+    type Err = std::convert::Infallible;
+
+    /// Parses a test name, ignoring case; unknown names are custom tests.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ok(match s.to_lowercase().as_str() {
+            "qubit" => Self::Qubit,
+            "nanodrop" | "nano_drop" => Self::NanoDrop,
+            "tapestation" | "tape_station" => Self::TapeStation,
+            "bioanalyzer" => Self::Bioanalyzer,
+            "qpcr" => Self::Qpcr,
+            "visual" => Self::Visual,
+            _ => Self::Custom(s.to_string()),
+        })
+    }
+}
+
 /// A single QC test result.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QcResult {
@@ -183,6 +201,20 @@ mod tests {
         assert!(!result.meets_threshold(30.0, 50.0));
     }
 
+    #[test]
+    fn test_qc_test_type_round_trips_through_its_name() {
+        for test_type in [
+            QcTestType::Qubit,
+            QcTestType::NanoDrop,
+            QcTestType::TapeStation,
+            QcTestType::Qpcr,
+            QcTestType::Custom("Femto Pulse".to_string()),
+        ] {
+            assert_eq!(test_type.to_string().parse(), Ok(test_type));
+        }
+        assert_eq!("tape_station".parse(), Ok(QcTestType::TapeStation));
+    }
+
     #[test]
     fn test_qc_result_display() {
         let result = QcResult::passed(
@@ -18,7 +18,7 @@ pub enum VolumeUnit {
 
 impl VolumeUnit {
     /// Conversion factor to microliters.
-    fn to_ul_factor(&self) -> f64 {
+    fn to_ul_factor(self) -> f64 {
         match self {
             Self::Microliters => 1.0,
             Self::Milliliters => 1000.0,
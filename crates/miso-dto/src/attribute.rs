@@ -0,0 +1,56 @@
+//! Custom attribute Data Transfer Objects.
+
+use serde::{Deserialize, Serialize};
+
+/// Response containing a custom attribute definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDefinitionResponse {
+    pub id: i32,
+    /// Target code: "sample" or "library"
+    pub target: String,
+    pub project_id: Option<i32>,
+    pub key: String,
+    pub label: String,
+    /// Type code: "text", "integer", "decimal", "boolean", "date" or
+    /// "choice"
+    pub value_type: String,
+    pub required: bool,
+    pub options: Vec<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub max_length: Option<u32>,
+    pub help_text: Option<String>,
+    pub position: i32,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::AttributeDefinition> for AttributeDefinitionResponse {
+    fn from(definition: miso_domain::entities::AttributeDefinition) -> Self {
+        Self {
+            id: definition.id,
+            target: crate::codes::attribute_target(definition.target).to_string(),
+            project_id: definition.project_id,
+            key: definition.key,
+            label: definition.label,
+            value_type: crate::codes::attribute_type(definition.value_type).to_string(),
+            required: definition.required,
+            options: definition.options,
+            min: definition.min,
+            max: definition.max,
+            max_length: definition.max_length,
+            help_text: definition.help_text,
+            position: definition.position,
+        }
+    }
+}
+
+/// The custom attributes a form should show for an entity, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeSchemaResponse {
+    /// Target code: "sample" or "library"
+    pub target: String,
+    /// The project the schema was resolved for, or `None` for the
+    /// attributes shared by every project
+    pub project_id: Option<i32>,
+    pub fields: Vec<AttributeDefinitionResponse>,
+}
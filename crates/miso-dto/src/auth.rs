@@ -0,0 +1,58 @@
+//! Authentication Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Request to log in with a username and password.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct LoginRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub username: String,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 1024)))]
+    pub password: String,
+}
+
+/// The user a token was issued to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentUser {
+    pub id: i32,
+    pub username: String,
+    pub display_name: String,
+    pub email: String,
+    /// Role code, e.g. "lab_manager"
+    pub role: String,
+}
+
+#[cfg(feature = "server")]
+impl From<&miso_domain::entities::User> for CurrentUser {
+    fn from(user: &miso_domain::entities::User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            email: user.email.clone(),
+            role: crate::codes::role(user.role).to_string(),
+        }
+    }
+}
+
+/// A bearer token, issued on login or refresh.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub token: String,
+    /// Always "Bearer"
+    pub token_type: String,
+    pub expires_at: DateTime<Utc>,
+    pub user: CurrentUser,
+}
+
+/// The `details` of the 403 returned when a user's role is too low.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleRequiredResponse {
+    /// Role code of the least role allowed, e.g. "lab_manager"
+    pub required_role: String,
+}
@@ -0,0 +1,256 @@
+//! Wire codes of domain enumerations.
+//!
+//! The codes are the ones the domain types serialize to, so a DTO field
+//! holding a code reads the same as one that held the enumeration.
+
+use miso_domain::entities::{
+    AttributeTarget, AttributeType, DuplicateResolution, LibraryDesign, LibraryType, Platform,
+    Role, RunStatus, StorableType,
+};
+use miso_domain::services::{AssignmentMethod, HopRisk};
+use miso_domain::value_objects::IndexFamily;
+
+/// Code of a sequencing platform, e.g. "oxford_nanopore".
+pub fn platform(platform: Platform) -> &'static str {
+    match platform {
+        Platform::Illumina => "illumina",
+        Platform::OxfordNanopore => "oxford_nanopore",
+        Platform::PacBio => "pac_bio",
+        Platform::IonTorrent => "ion_torrent",
+        Platform::Element => "element",
+        Platform::Mgi => "mgi",
+        Platform::Ultima => "ultima",
+        Platform::Other => "other",
+    }
+}
+
+/// Code of a storable type, e.g. "library_aliquot".
+pub fn storable_type(storable_type: StorableType) -> &'static str {
+    match storable_type {
+        StorableType::Sample => "sample",
+        StorableType::Library => "library",
+        StorableType::LibraryAliquot => "library_aliquot",
+        StorableType::Pool => "pool",
+    }
+}
+
+/// Code of the entity an attribute is recorded on.
+pub fn attribute_target(target: AttributeTarget) -> &'static str {
+    match target {
+        AttributeTarget::Sample => "sample",
+        AttributeTarget::Library => "library",
+    }
+}
+
+/// Code of an attribute's value type, e.g. "decimal".
+pub fn attribute_type(value_type: AttributeType) -> &'static str {
+    match value_type {
+        AttributeType::Text => "text",
+        AttributeType::Integer => "integer",
+        AttributeType::Decimal => "decimal",
+        AttributeType::Boolean => "boolean",
+        AttributeType::Date => "date",
+        AttributeType::Choice => "choice",
+    }
+}
+
+/// Code of a library design, e.g. "rna_seq"; "custom" for every custom
+/// design.
+pub fn library_design(design: &LibraryDesign) -> &'static str {
+    match design {
+        LibraryDesign::Wgs => "wgs",
+        LibraryDesign::Wes => "wes",
+        LibraryDesign::RnaSeq => "rna_seq",
+        LibraryDesign::TargetedPanel => "targeted_panel",
+        LibraryDesign::ChipSeq => "chip_seq",
+        LibraryDesign::AtacSeq => "atac_seq",
+        LibraryDesign::Methylation => "methylation",
+        LibraryDesign::SingleCellRna => "single_cell_rna",
+        LibraryDesign::SingleCellAtac => "single_cell_atac",
+        LibraryDesign::Custom(_) => "custom",
+    }
+}
+
+/// Code of a library type, e.g. "paired_end".
+pub fn library_type(library_type: &LibraryType) -> &'static str {
+    match library_type {
+        LibraryType::PairedEnd => "paired_end",
+        LibraryType::SingleEnd => "single_end",
+        LibraryType::MatePair => "mate_pair",
+    }
+}
+
+/// Code of an index family, e.g. "tru_seq".
+pub fn index_family(family: IndexFamily) -> &'static str {
+    match family {
+        IndexFamily::TruSeq => "tru_seq",
+        IndexFamily::Nextera => "nextera",
+        IndexFamily::IdtUdi => "idt_udi",
+        IndexFamily::TenX => "ten_x",
+        IndexFamily::Custom => "custom",
+    }
+}
+
+/// Code of an index hopping risk, e.g. "moderate".
+pub fn hop_risk(risk: HopRisk) -> &'static str {
+    match risk {
+        HopRisk::None => "none",
+        HopRisk::Low => "low",
+        HopRisk::Moderate => "moderate",
+        HopRisk::High => "high",
+    }
+}
+
+/// Code of how an index assignment was found, e.g. "greedy".
+pub fn assignment_method(method: AssignmentMethod) -> &'static str {
+    match method {
+        AssignmentMethod::Greedy => "greedy",
+        AssignmentMethod::Exhaustive => "exhaustive",
+    }
+}
+
+/// Code of a run status, e.g. "qc_in_progress".
+pub fn run_status(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Unknown => "unknown",
+        RunStatus::Running => "running",
+        RunStatus::Paused => "paused",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::Stopped => "stopped",
+        RunStatus::QcInProgress => "qc_in_progress",
+        RunStatus::QcPassed => "qc_passed",
+        RunStatus::QcFailed => "qc_failed",
+    }
+}
+
+/// Code of a user role, e.g. "lab_manager".
+pub fn role(role: Role) -> &'static str {
+    match role {
+        Role::Viewer => "viewer",
+        Role::Technician => "technician",
+        Role::LabManager => "lab_manager",
+        Role::Admin => "admin",
+        Role::SuperAdmin => "super_admin",
+    }
+}
+
+/// Code of a possible duplicate's resolution, e.g. "distinct".
+pub fn duplicate_resolution(resolution: DuplicateResolution) -> &'static str {
+    match resolution {
+        DuplicateResolution::Distinct => "distinct",
+        DuplicateResolution::Duplicate => "duplicate",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialized<T: serde::Serialize>(value: T) -> String {
+        serde_json::to_value(value)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_codes_match_serialized_values() {
+        for p in [
+            Platform::Illumina,
+            Platform::OxfordNanopore,
+            Platform::PacBio,
+            Platform::IonTorrent,
+            Platform::Element,
+            Platform::Mgi,
+            Platform::Ultima,
+            Platform::Other,
+        ] {
+            assert_eq!(platform(p), serialized(p));
+        }
+        for t in [
+            StorableType::Sample,
+            StorableType::Library,
+            StorableType::LibraryAliquot,
+            StorableType::Pool,
+        ] {
+            assert_eq!(storable_type(t), serialized(t));
+        }
+        for t in [AttributeTarget::Sample, AttributeTarget::Library] {
+            assert_eq!(attribute_target(t), serialized(t));
+        }
+        for t in [
+            AttributeType::Text,
+            AttributeType::Integer,
+            AttributeType::Decimal,
+            AttributeType::Boolean,
+            AttributeType::Date,
+            AttributeType::Choice,
+        ] {
+            assert_eq!(attribute_type(t), serialized(t));
+        }
+        for d in [
+            LibraryDesign::Wgs,
+            LibraryDesign::Wes,
+            LibraryDesign::RnaSeq,
+            LibraryDesign::TargetedPanel,
+            LibraryDesign::ChipSeq,
+            LibraryDesign::AtacSeq,
+            LibraryDesign::Methylation,
+            LibraryDesign::SingleCellRna,
+            LibraryDesign::SingleCellAtac,
+        ] {
+            assert_eq!(library_design(&d), serialized(d.clone()));
+        }
+        for t in [
+            LibraryType::PairedEnd,
+            LibraryType::SingleEnd,
+            LibraryType::MatePair,
+        ] {
+            assert_eq!(library_type(&t), serialized(t));
+        }
+        for f in [
+            IndexFamily::TruSeq,
+            IndexFamily::Nextera,
+            IndexFamily::IdtUdi,
+            IndexFamily::TenX,
+            IndexFamily::Custom,
+        ] {
+            assert_eq!(index_family(f), serialized(f));
+        }
+        for r in [
+            HopRisk::None,
+            HopRisk::Low,
+            HopRisk::Moderate,
+            HopRisk::High,
+        ] {
+            assert_eq!(hop_risk(r), serialized(r));
+        }
+        for s in [
+            RunStatus::Unknown,
+            RunStatus::Running,
+            RunStatus::Paused,
+            RunStatus::Completed,
+            RunStatus::Failed,
+            RunStatus::Stopped,
+            RunStatus::QcInProgress,
+            RunStatus::QcPassed,
+            RunStatus::QcFailed,
+        ] {
+            assert_eq!(run_status(s), serialized(s));
+        }
+        for r in [
+            Role::Viewer,
+            Role::Technician,
+            Role::LabManager,
+            Role::Admin,
+            Role::SuperAdmin,
+        ] {
+            assert_eq!(role(r), serialized(r));
+        }
+        for r in [DuplicateResolution::Distinct, DuplicateResolution::Duplicate] {
+            assert_eq!(duplicate_resolution(r), serialized(r));
+        }
+    }
+}
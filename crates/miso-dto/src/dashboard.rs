@@ -0,0 +1,211 @@
+//! Dashboard statistics Data Transfer Objects.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of lab activity shown on the dashboard.
+///
+/// Sections whose data isn't available are `None` rather than empty, so
+/// that "no runs" and "runs not tracked" can be told apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardStats {
+    pub generated_at: DateTime<Utc>,
+    /// Number of days covered by the intake and run sections
+    pub days: u32,
+    /// Samples received per day, oldest first, one entry per day
+    pub sample_intake: Vec<DailyIntake>,
+    pub qc: QcBreakdown,
+    pub runs_per_platform: Option<Vec<PlatformRuns>>,
+    /// Runs sequencing or paused now, soonest to finish first
+    pub active_runs: Option<Vec<ActiveRun>>,
+    pub freezers: Option<Vec<FreezerCapacity>>,
+}
+
+/// Number of samples received on one day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyIntake {
+    pub date: NaiveDate,
+    pub samples: u64,
+}
+
+impl DailyIntake {
+    /// Expands sparse per-day counts into one entry for each of the `days`
+    /// days ending on `last`, filling gaps with zero.
+    pub fn fill(counts: &[(NaiveDate, u64)], last: NaiveDate, days: u32) -> Vec<Self> {
+        (0..days as i64)
+            .rev()
+            .map(|back| {
+                let date = last - Duration::days(back);
+                let samples = counts
+                    .iter()
+                    .filter(|(d, _)| *d == date)
+                    .map(|(_, n)| n)
+                    .sum();
+                Self { date, samples }
+            })
+            .collect()
+    }
+}
+
+/// Unarchived samples by QC status.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QcBreakdown {
+    pub not_ready: u64,
+    pub ready: u64,
+    pub passed: u64,
+    pub failed: u64,
+    pub needs_review: u64,
+    /// Percentage of completed QC that passed; `None` if none has completed
+    pub pass_rate: Option<f64>,
+}
+
+impl QcBreakdown {
+    /// Builds the breakdown from per-status counts.
+    #[cfg(feature = "server")]
+    pub fn from_counts(counts: &[(miso_domain::value_objects::QcStatus, u64)]) -> Self {
+        use miso_domain::value_objects::QcStatus;
+
+        let mut breakdown = Self::default();
+        for &(status, count) in counts {
+            let slot = match status {
+                QcStatus::NotReady => &mut breakdown.not_ready,
+                QcStatus::Ready => &mut breakdown.ready,
+                QcStatus::Passed => &mut breakdown.passed,
+                QcStatus::Failed => &mut breakdown.failed,
+                QcStatus::NeedsReview => &mut breakdown.needs_review,
+            };
+            *slot += count;
+        }
+
+        let completed = breakdown.passed + breakdown.failed;
+        breakdown.pass_rate =
+            (completed > 0).then(|| breakdown.passed as f64 * 100.0 / completed as f64);
+        breakdown
+    }
+
+    /// Returns (status code, label, count) for each status, in workflow
+    /// order.
+    pub fn statuses(&self) -> [(&'static str, &'static str, u64); 5] {
+        [
+            ("not_ready", "Not Ready", self.not_ready),
+            ("ready", "Ready", self.ready),
+            ("needs_review", "Needs Review", self.needs_review),
+            ("passed", "Passed", self.passed),
+            ("failed", "Failed", self.failed),
+        ]
+    }
+}
+
+/// Runs started on one platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformRuns {
+    /// Platform code, e.g. "illumina"
+    pub platform: String,
+    /// Runs started within the period
+    pub runs: u64,
+    /// Runs in progress now, whenever they started
+    pub running: u64,
+}
+
+/// A run in progress and when it is expected to finish.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveRun {
+    pub id: i32,
+    pub name: String,
+    /// Platform code, e.g. "illumina"
+    pub platform: String,
+    /// Status code, "running" or "paused"
+    pub status: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// `None` if the instrument model gives no way to estimate it
+    pub estimated_completion: Option<DateTime<Utc>>,
+    pub cycles_completed: Option<u32>,
+    pub total_cycles: Option<u32>,
+}
+
+impl ActiveRun {
+    /// Returns the percentage of cycles completed, if known.
+    pub fn percent_complete(&self) -> Option<f64> {
+        match (self.cycles_completed, self.total_cycles) {
+            (Some(done), Some(total)) if total > 0 => Some(done as f64 * 100.0 / total as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Box occupancy of one freezer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreezerCapacity {
+    /// Freezer name; boxes with no location are grouped as "Unassigned"
+    pub freezer: String,
+    pub boxes: usize,
+    /// Total positions across the freezer's boxes
+    pub capacity: usize,
+    /// Occupied positions across the freezer's boxes
+    pub occupied: usize,
+    pub fill_percent: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intake_fills_missing_days() {
+        let last = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let counts = vec![
+            (NaiveDate::from_ymd_opt(2024, 3, 8).unwrap(), 4),
+            (last, 2),
+            // Outside the window
+            (NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), 9),
+        ];
+
+        let intake = DailyIntake::fill(&counts, last, 3);
+
+        assert_eq!(
+            intake.iter().map(|d| d.samples).collect::<Vec<_>>(),
+            vec![4, 0, 2]
+        );
+        assert_eq!(intake[0].date, NaiveDate::from_ymd_opt(2024, 3, 8).unwrap());
+        assert_eq!(intake[2].date, last);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_qc_pass_rate() {
+        use miso_domain::value_objects::QcStatus;
+
+        let qc = QcBreakdown::from_counts(&[
+            (QcStatus::Passed, 9),
+            (QcStatus::Failed, 3),
+            (QcStatus::Ready, 5),
+        ]);
+        assert_eq!(qc.ready, 5);
+        assert_eq!(qc.pass_rate, Some(75.0));
+
+        let qc = QcBreakdown::from_counts(&[(QcStatus::NotReady, 2)]);
+        assert_eq!(qc.pass_rate, None);
+    }
+
+    #[test]
+    fn test_parses_stats_without_optional_sections() {
+        let json = r#"{
+            "generated_at": "2024-03-10T12:00:00Z",
+            "days": 2,
+            "sample_intake": [
+                {"date": "2024-03-09", "samples": 4},
+                {"date": "2024-03-10", "samples": 0}
+            ],
+            "qc": {"not_ready": 1, "ready": 2, "passed": 3, "failed": 1,
+                   "needs_review": 0, "pass_rate": 75.0},
+            "runs_per_platform": null,
+            "freezers": null
+        }"#;
+
+        let stats: DashboardStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.sample_intake.len(), 2);
+        assert_eq!(stats.qc.pass_rate, Some(75.0));
+        assert!(stats.runs_per_platform.is_none());
+        assert!(stats.active_runs.is_none());
+    }
+}
@@ -0,0 +1,45 @@
+//! # MISO Data Transfer Objects
+//!
+//! Requests and responses exchanged between the API and its Rust clients.
+//! The server builds and validates them (miso-application, miso-api) and
+//! the frontend and client library read and send them (miso-frontend,
+//! miso-client), all from these definitions, so the two sides can't drift
+//! apart.
+//!
+//! The crate needs only serde and chrono and builds for WASM. The `server`
+//! feature adds what only the server needs: conversions from domain
+//! entities and request validation.
+//!
+//! Enumerations defined by the domain, such as platforms and storable
+//! types, are carried as their wire codes (see [`codes`] on the server),
+//! since the frontend doesn't depend on the domain. Enumerations defined
+//! here are typed.
+
+#[cfg(feature = "server")]
+pub mod codes;
+
+mod attribute;
+mod auth;
+mod dashboard;
+mod library;
+mod pool;
+mod project;
+mod run;
+mod run_metrics;
+mod run_monitor;
+mod sample;
+mod search;
+mod storage_browser;
+
+pub use attribute::*;
+pub use auth::*;
+pub use dashboard::*;
+pub use library::*;
+pub use pool::*;
+pub use project::*;
+pub use run::*;
+pub use run_metrics::*;
+pub use run_monitor::*;
+pub use sample::*;
+pub use search::*;
+pub use storage_browser::*;
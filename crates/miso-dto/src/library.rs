@@ -0,0 +1,352 @@
+//! Library Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Request to prepare a new library from a sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreateLibraryRequest {
+    /// The sample the library is prepared from; the library joins its
+    /// project
+    pub sample_id: i32,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    /// Design code: "wgs", "wes", "rna_seq", "targeted_panel", "chip_seq",
+    /// "atac_seq", "methylation", "single_cell_rna" or "single_cell_atac";
+    /// anything else is taken as the name of a custom design
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub design: String,
+
+    /// Type code: "paired_end", "single_end" or "mate_pair"
+    pub library_type: String,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub platform: String,
+
+    pub description: Option<String>,
+
+    #[cfg_attr(feature = "server", validate(length(max = 255)))]
+    pub kit_name: Option<String>,
+
+    /// Version of the targeted panel captured; only for "targeted_panel"
+    /// designs
+    pub panel_id: Option<i32>,
+
+    /// Reference genome for this library's assay, if not its project's
+    pub reference_genome_id: Option<i32>,
+
+    pub insert_size: Option<u32>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub volume_ul: Option<f64>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub concentration_ng_ul: Option<f64>,
+
+    pub pcr_cycles: Option<u8>,
+
+    /// "positive", "negative" or "ntc" if the library is a control;
+    /// libraries made from a control sample are controls of its type
+    /// unless given
+    #[serde(default)]
+    pub control_type: Option<String>,
+
+    /// Mass of the sample put into the library prep; it is withdrawn from
+    /// the sample, along with the volume holding it
+    #[serde(default)]
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub input_ng: Option<f64>,
+}
+
+/// Request to update an existing library.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct UpdateLibraryRequest {
+    pub description: Option<String>,
+
+    #[cfg_attr(feature = "server", validate(length(max = 255)))]
+    pub kit_name: Option<String>,
+
+    /// Version of the targeted panel captured
+    pub panel_id: Option<i32>,
+
+    /// Reference genome for this library's assay, if not its project's
+    pub reference_genome_id: Option<i32>,
+
+    pub insert_size: Option<u32>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub volume_ul: Option<f64>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub mass_ng: Option<f64>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub concentration_ng_ul: Option<f64>,
+
+    pub pcr_cycles: Option<u8>,
+
+    pub qc_status: Option<String>,
+
+    /// Why the QC status is changing; required for failures and for
+    /// overturning a final result
+    #[cfg_attr(feature = "server", validate(length(max = 2000)))]
+    pub qc_reason: Option<String>,
+
+    pub low_quality: Option<bool>,
+
+    /// "positive", "negative" or "ntc" to mark the library a control, or
+    /// "none" to make it project material again
+    #[serde(default)]
+    pub control_type: Option<String>,
+}
+
+/// Request to assign a library's index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct SetLibraryIndexRequest {
+    /// Index name, e.g. "A01" or "UDP0001"
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 100)))]
+    pub name: String,
+
+    /// Family code: "tru_seq", "nextera", "idt_udi", "ten_x" or "custom"
+    pub family: String,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub i7: String,
+
+    /// Second index, for dual-indexed libraries
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub i5: Option<String>,
+}
+
+/// A library's index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryIndexResponse {
+    pub name: String,
+    /// Family code, e.g. "idt_udi"
+    pub family: String,
+    pub i7: String,
+    pub i5: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl From<&miso_domain::value_objects::DnaIndex> for LibraryIndexResponse {
+    fn from(index: &miso_domain::value_objects::DnaIndex) -> Self {
+        Self {
+            name: index.name().to_string(),
+            family: crate::codes::index_family(index.family()).to_string(),
+            i7: index.i7().to_string(),
+            i5: index.i5().map(str::to_string),
+        }
+    }
+}
+
+/// Response containing library details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryResponse {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub sample_id: i32,
+    pub project_id: i32,
+    pub description: Option<String>,
+    /// Design code, e.g. "rna_seq", or "custom"
+    pub design: String,
+    /// Name of a custom design
+    pub custom_design: Option<String>,
+    /// Type code, e.g. "paired_end"
+    pub library_type: String,
+    pub platform: String,
+    pub kit_name: Option<String>,
+    /// Targeted panel version captured
+    pub panel_id: Option<i32>,
+    /// Reference genome for this library's assay, if not its project's
+    pub reference_genome_id: Option<i32>,
+    pub index: Option<LibraryIndexResponse>,
+    pub insert_size: Option<u32>,
+    pub volume_ul: Option<f64>,
+    pub mass_ng: Option<f64>,
+    pub concentration_ng_ul: Option<f64>,
+    pub qc_status: String,
+    pub pcr_cycles: Option<u8>,
+    pub low_quality: bool,
+    pub can_pool: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived: bool,
+    /// "positive", "negative" or "ntc" if the library is a control
+    #[serde(default)]
+    pub control_type: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Library> for LibraryResponse {
+    fn from(library: miso_domain::entities::Library) -> Self {
+        use miso_domain::entities::LibraryDesign;
+
+        let can_pool = library.can_pool();
+        let custom_design = match &library.design {
+            LibraryDesign::Custom(name) => Some(name.clone()),
+            _ => None,
+        };
+
+        Self {
+            id: library.id,
+            name: library.name,
+            barcode: library.barcode.to_string(),
+            sample_id: library.sample_id,
+            project_id: library.project_id,
+            description: library.description,
+            design: crate::codes::library_design(&library.design).to_string(),
+            custom_design,
+            library_type: crate::codes::library_type(&library.library_type).to_string(),
+            platform: library.platform,
+            kit_name: library.kit_name,
+            panel_id: library.panel_id,
+            reference_genome_id: library.reference_genome_id,
+            index: library.index.as_ref().map(LibraryIndexResponse::from),
+            insert_size: library.insert_size,
+            volume_ul: library.volume.map(|v| v.as_microliters()),
+            mass_ng: library.mass.map(|m| m.as_nanograms()),
+            concentration_ng_ul: library.concentration.map(|c| c.value()),
+            qc_status: library.qc_status.to_string(),
+            pcr_cycles: library.pcr_cycles,
+            low_quality: library.low_quality,
+            can_pool,
+            created_by: library.created_by,
+            created_at: library.created_at,
+            updated_at: library.updated_at,
+            archived: library.archived,
+            control_type: library.control_type.map(|c| c.to_string()),
+        }
+    }
+}
+
+/// Summary of a library (for list views).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibrarySummary {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub sample_id: i32,
+    /// Design code, e.g. "rna_seq", or "custom"
+    pub design: String,
+    pub index_name: Option<String>,
+    pub qc_status: String,
+    pub can_pool: bool,
+    pub archived: bool,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Library> for LibrarySummary {
+    fn from(library: miso_domain::entities::Library) -> Self {
+        Self {
+            id: library.id,
+            name: library.name.clone(),
+            barcode: library.barcode.to_string(),
+            sample_id: library.sample_id,
+            design: crate::codes::library_design(&library.design).to_string(),
+            index_name: library.index.as_ref().map(|i| i.name().to_string()),
+            qc_status: library.qc_status.to_string(),
+            can_pool: library.can_pool(),
+            archived: library.archived,
+        }
+    }
+}
+
+/// A design and platform a kit can prepare libraries for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KitCombinationResponse {
+    /// Design code, or the name of a custom design
+    pub design: String,
+    pub platform: String,
+}
+
+/// The `details` of the 422 returned when a library's kit can't prepare
+/// its design for its platform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KitCompatibilityResponse {
+    pub kit: String,
+    /// Design code, or the name of a custom design
+    pub design: String,
+    pub platform: String,
+    /// What the kit can prepare
+    pub allowed: Vec<KitCombinationResponse>,
+}
+
+#[cfg(feature = "server")]
+impl KitCompatibilityResponse {
+    /// Describes a kit that can't prepare a design for a platform.
+    pub fn new(
+        kit: &str,
+        design: &miso_domain::entities::LibraryDesign,
+        platform: &str,
+        allowed: &[miso_domain::services::KitCombination],
+    ) -> Self {
+        Self {
+            kit: kit.to_string(),
+            design: design_code(design),
+            platform: platform.to_string(),
+            allowed: allowed
+                .iter()
+                .map(|combination| KitCombinationResponse {
+                    design: design_code(&combination.design),
+                    platform: combination.platform.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Code of a design as a create request gives it: custom designs by name.
+#[cfg(feature = "server")]
+fn design_code(design: &miso_domain::entities::LibraryDesign) -> String {
+    match design {
+        miso_domain::entities::LibraryDesign::Custom(name) => name.clone(),
+        design => crate::codes::library_design(design).to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{Library, LibraryDesign, LibraryType};
+    use miso_domain::value_objects::{Barcode, DnaIndex, IndexFamily};
+
+    #[test]
+    fn test_response_carries_codes_and_index() {
+        let mut library = Library::new(
+            3,
+            "LIB_0003".to_string(),
+            Barcode::new("LIB-0003").unwrap(),
+            7,
+            1,
+            LibraryDesign::Custom("Hi-C".to_string()),
+            LibraryType::SingleEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        library.set_index(
+            DnaIndex::dual("UDP0001", "GAACTGAGCG", "TCGTGGAGCG", IndexFamily::IdtUdi).unwrap(),
+        );
+
+        let response = LibraryResponse::from(library.clone());
+        assert_eq!(response.design, "custom");
+        assert_eq!(response.custom_design.as_deref(), Some("Hi-C"));
+        assert_eq!(response.library_type, "single_end");
+        let index = response.index.unwrap();
+        assert_eq!(index.family, "idt_udi");
+        assert_eq!(index.i5.as_deref(), Some("TCGTGGAGCG"));
+
+        let summary = LibrarySummary::from(library);
+        assert_eq!(summary.index_name.as_deref(), Some("UDP0001"));
+        assert!(!summary.can_pool);
+    }
+}
@@ -0,0 +1,378 @@
+//! Pool Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Request to create an empty pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreatePoolRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub platform: String,
+
+    /// Container model (flow cell type) the pool is made for; its plexity
+    /// limit, if the site sets one, overrides the platform's
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub container_model: Option<String>,
+
+    pub description: Option<String>,
+
+    pub volume_ul: Option<f64>,
+}
+
+/// Request to add a library aliquot to a pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct AddPoolElementRequest {
+    pub library_id: i32,
+
+    /// The aliquot of the library to add; defaults to the library's ID
+    pub library_aliquot_id: Option<i32>,
+
+    pub volume_ul: Option<f64>,
+
+    /// Share of the pool, from 0 to 1
+    #[cfg_attr(feature = "server", validate(range(min = 0.0, max = 1.0)))]
+    pub proportion: Option<f64>,
+}
+
+/// A library aliquot in a pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolElementResponse {
+    pub library_aliquot_id: i32,
+    pub library_id: i32,
+    pub volume_ul: Option<f64>,
+    pub proportion: Option<f64>,
+}
+
+#[cfg(feature = "server")]
+impl From<&miso_domain::entities::PoolElement> for PoolElementResponse {
+    fn from(element: &miso_domain::entities::PoolElement) -> Self {
+        Self {
+            library_aliquot_id: element.library_aliquot_id,
+            library_id: element.library_id,
+            volume_ul: element.volume.map(|v| v.as_microliters()),
+            proportion: element.proportion,
+        }
+    }
+}
+
+/// Response containing pool details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolResponse {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub description: Option<String>,
+    pub platform: String,
+    pub container_model: Option<String>,
+    pub elements: Vec<PoolElementResponse>,
+    /// Most library aliquots the pool may hold; unlimited if `None`
+    pub max_size: Option<usize>,
+    pub volume_ul: Option<f64>,
+    pub qc_status: String,
+    pub sequenced: bool,
+    pub can_sequence: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Pool> for PoolResponse {
+    fn from(pool: miso_domain::entities::Pool) -> Self {
+        Self {
+            can_sequence: pool.can_sequence(),
+            elements: pool
+                .elements
+                .iter()
+                .map(PoolElementResponse::from)
+                .collect(),
+            id: pool.id,
+            name: pool.name,
+            barcode: pool.barcode.to_string(),
+            description: pool.description,
+            platform: pool.platform,
+            container_model: pool.container_model,
+            max_size: pool.max_size,
+            volume_ul: pool.volume.map(|v| v.as_microliters()),
+            qc_status: pool.qc_status.to_string(),
+            sequenced: pool.sequenced,
+            created_by: pool.created_by,
+            created_at: pool.created_at,
+            updated_at: pool.updated_at,
+        }
+    }
+}
+
+/// Summary of a pool (for list views).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolSummary {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub platform: String,
+    pub size: usize,
+    pub qc_status: String,
+    pub sequenced: bool,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Pool> for PoolSummary {
+    fn from(pool: miso_domain::entities::Pool) -> Self {
+        Self {
+            size: pool.size(),
+            id: pool.id,
+            name: pool.name,
+            barcode: pool.barcode.to_string(),
+            platform: pool.platform,
+            qc_status: pool.qc_status.to_string(),
+            sequenced: pool.sequenced,
+        }
+    }
+}
+
+/// Two libraries whose indices are too similar to demultiplex.
+///
+/// Also the `details` of the 409 returned when adding a library would
+/// cause one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexCollisionResponse {
+    pub library1: String,
+    pub library2: String,
+    /// Hamming distance between the two indices
+    pub distance: u32,
+    pub required_distance: u32,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::services::IndexCollision> for IndexCollisionResponse {
+    fn from(collision: miso_domain::services::IndexCollision) -> Self {
+        Self {
+            library1: collision.library1,
+            library2: collision.library2,
+            distance: collision.distance,
+            required_distance: collision.required_distance,
+        }
+    }
+}
+
+/// The `details` of the 409 returned when a pool is already at its
+/// plexity limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolCapacityResponse {
+    pub pool: String,
+    pub max_size: usize,
+}
+
+/// Whether a pool's libraries can be sequenced together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolValidationResponse {
+    /// True if the pool has libraries and none collide or are unpoolable
+    pub valid: bool,
+    pub collisions: Vec<IndexCollisionResponse>,
+    /// Libraries that can't be pooled: unindexed, failed QC, low quality
+    /// or archived
+    pub unpoolable_libraries: Vec<String>,
+    /// Index hopping risk code: "none", "low", "moderate" or "high"
+    pub hopping_risk: String,
+    /// Problems that don't stop the pool being sequenced
+    pub warnings: Vec<String>,
+}
+
+/// How to balance a pool: as query parameters to suggest proportions, or
+/// as the body to apply them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct BalancePoolRequest {
+    /// Reads a lane gives; defaults to the configured output of the pool's
+    /// container model or platform
+    #[cfg_attr(feature = "server", validate(range(min = 1)))]
+    pub lane_reads: Option<u64>,
+
+    /// Molarity to make the pool at, in nM; volumes are only proposed
+    /// with one
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub target_nm: Option<f64>,
+
+    /// Volume of pool to make, in µL; defaults to the pool's volume
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub volume_ul: Option<f64>,
+}
+
+/// A library aliquot's proposed share of a pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolShareResponse {
+    pub library_aliquot_id: i32,
+    pub library_id: i32,
+    /// Reads the library's design needs
+    pub required_reads: u64,
+    /// Share of the pool, from 0 to 1
+    pub proportion: f64,
+    /// Reads the library is expected to get across the pool's lanes
+    pub expected_reads: u64,
+    /// Volume to pool, if a target molarity was given and the library's
+    /// molarity is known
+    pub volume_ul: Option<f64>,
+}
+
+#[cfg(feature = "server")]
+impl From<&miso_domain::services::PoolShare> for PoolShareResponse {
+    fn from(share: &miso_domain::services::PoolShare) -> Self {
+        Self {
+            library_aliquot_id: share.library_aliquot_id,
+            library_id: share.library_id,
+            required_reads: share.required_reads,
+            proportion: share.proportion,
+            expected_reads: share.expected_reads,
+            volume_ul: share.volume.map(|v| v.as_microliters()),
+        }
+    }
+}
+
+/// Proposed proportions for a pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolBalanceResponse {
+    pub pool_id: i32,
+    pub shares: Vec<PoolShareResponse>,
+    /// Reads a lane is expected to give
+    pub lane_reads: u64,
+    /// Reads the pool's libraries need in total
+    pub required_reads: u64,
+    /// Lanes needed to give every library its reads
+    pub lanes: u32,
+    /// Diluent to make up the pool's volume, if every library's volume is
+    /// known
+    pub diluent_ul: Option<f64>,
+}
+
+#[cfg(feature = "server")]
+impl PoolBalanceResponse {
+    /// Creates the response for a pool's balance.
+    pub fn new(pool_id: i32, balance: &miso_domain::services::PoolBalance) -> Self {
+        Self {
+            pool_id,
+            shares: balance.shares.iter().map(PoolShareResponse::from).collect(),
+            lane_reads: balance.lane_reads,
+            required_reads: balance.required_reads,
+            lanes: balance.lanes,
+            diluent_ul: balance.diluent.map(|v| v.as_microliters()),
+        }
+    }
+}
+
+/// Request for a worksheet to pool libraries equimolar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct PoolingWorksheetRequest {
+    /// Molarity to make the pool at, in nM
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub target_nm: f64,
+
+    /// Volume of pool to make, in µL
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub volume_ul: f64,
+
+    /// Smallest volume to pipette, in µL; libraries that would need less
+    /// are prediluted. Defaults to 0.5 µL
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub min_pipette_ul: Option<f64>,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 384), nested))]
+    pub libraries: Vec<PoolingLibraryRequest>,
+}
+
+/// A library to pool, with measurements to use instead of its recorded
+/// ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct PoolingLibraryRequest {
+    pub library_id: i32,
+
+    /// Molarity measured by qPCR, in nM; used instead of the concentration
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub molarity_nm: Option<f64>,
+
+    /// Defaults to the library's recorded concentration
+    #[cfg_attr(feature = "server", validate(range(exclusive_min = 0.0)))]
+    pub concentration_ng_ul: Option<f64>,
+
+    /// Fragment size in bp; defaults to the library's insert size
+    #[cfg_attr(feature = "server", validate(range(min = 1)))]
+    pub fragment_size: Option<u32>,
+}
+
+/// A step of a pooling worksheet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipettingStepResponse {
+    pub library_id: i32,
+    pub library_name: String,
+    pub barcode: String,
+    /// The library's molarity, in nM
+    pub molarity_nm: f64,
+    /// Library to predilute, if too concentrated to pipette straight into
+    /// the pool, in µL
+    pub predilution_library_ul: Option<f64>,
+    /// Diluent to predilute it with, in µL
+    pub predilution_diluent_ul: Option<f64>,
+    /// Volume to add to the pool, of the library or of its predilution,
+    /// in µL
+    pub volume_ul: f64,
+}
+
+/// Volumes to combine to pool libraries equimolar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolingWorksheetResponse {
+    pub target_nm: f64,
+    pub volume_ul: f64,
+    /// Molarity of each library in the pool, in nM
+    pub per_library_nm: f64,
+    /// One step per library, in the order asked for
+    pub steps: Vec<PipettingStepResponse>,
+    /// Diluent to make up the pool's volume, in µL
+    pub diluent_ul: f64,
+}
+
+/// Request for catalog indices for libraries about to be pooled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct SuggestIndicesRequest {
+    /// Libraries to pool; those with indices keep them
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 384)))]
+    pub library_ids: Vec<i32>,
+
+    /// Catalog sets to pick indices from, in order of preference
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 20)))]
+    pub index_set_ids: Vec<i32>,
+}
+
+/// A library's index in a suggested assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSuggestionResponse {
+    pub library_id: i32,
+    pub library_name: String,
+    /// True if the library keeps the index it has
+    pub kept: bool,
+    /// Set and 1-based position of a suggested index, as taken by
+    /// `PUT /libraries/{id}/index/catalog`; absent for kept indices
+    pub index_set_id: Option<i32>,
+    pub position: Option<usize>,
+    pub index_name: String,
+    pub i7: String,
+    pub i5: Option<String>,
+}
+
+/// Suggested indices for libraries about to be pooled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexAssignmentResponse {
+    /// Smallest Hamming distance between any two indices
+    pub min_distance: u32,
+    /// "exhaustive" or "greedy"; absent if every library keeps its index
+    pub method: Option<String>,
+    pub libraries: Vec<IndexSuggestionResponse>,
+}
@@ -2,20 +2,22 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
 use validator::Validate;
 
 /// Request to create a new project.
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
 pub struct CreateProjectRequest {
-    #[validate(length(min = 2, max = 50))]
+    #[cfg_attr(feature = "server", validate(length(min = 2, max = 50)))]
     pub code: String,
 
-    #[validate(length(min = 1, max = 255))]
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
     pub name: String,
 
     pub description: Option<String>,
 
-    #[validate(email)]
+    #[cfg_attr(feature = "server", validate(email))]
     pub pi_email: Option<String>,
 
     pub pi_name: Option<String>,
@@ -23,17 +25,21 @@ pub struct CreateProjectRequest {
     pub reference_number: Option<String>,
 
     pub target_sample_count: Option<u32>,
+
+    /// Reference genome the project's data is analysed against
+    pub reference_genome_id: Option<i32>,
 }
 
 /// Request to update an existing project.
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
 pub struct UpdateProjectRequest {
-    #[validate(length(min = 1, max = 255))]
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
     pub name: Option<String>,
 
     pub description: Option<String>,
 
-    #[validate(email)]
+    #[cfg_attr(feature = "server", validate(email))]
     pub pi_email: Option<String>,
 
     pub pi_name: Option<String>,
@@ -42,11 +48,14 @@ pub struct UpdateProjectRequest {
 
     pub target_sample_count: Option<u32>,
 
+    /// Reference genome the project's data is analysed against
+    pub reference_genome_id: Option<i32>,
+
     pub status: Option<String>,
 }
 
 /// Response containing project details.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectResponse {
     pub id: i32,
     pub code: String,
@@ -57,15 +66,20 @@ pub struct ProjectResponse {
     pub pi_email: Option<String>,
     pub reference_number: Option<String>,
     pub target_sample_count: Option<u32>,
+    pub reference_genome_id: Option<i32>,
     pub sample_count: u32,
     pub created_at: DateTime<Utc>,
     pub created_by: String,
     pub updated_at: DateTime<Utc>,
     pub due_date: Option<DateTime<Utc>>,
+    /// The project's data entry lock, while it holds
+    pub lock: Option<ProjectLockResponse>,
 }
 
+#[cfg(feature = "server")]
 impl From<miso_domain::entities::Project> for ProjectResponse {
     fn from(project: miso_domain::entities::Project) -> Self {
+        let lock = project.active_lock().cloned().map(Into::into);
         Self {
             id: project.id,
             code: project.code,
@@ -76,17 +90,19 @@ impl From<miso_domain::entities::Project> for ProjectResponse {
             pi_email: project.pi_email,
             reference_number: project.reference_number,
             target_sample_count: project.target_sample_count,
+            reference_genome_id: project.reference_genome_id,
             sample_count: project.sample_count,
             created_at: project.created_at,
             created_by: project.created_by,
             updated_at: project.updated_at,
             due_date: project.due_date,
+            lock,
         }
     }
 }
 
 /// Summary of a project (for list views).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectSummary {
     pub id: i32,
     pub code: String,
@@ -94,8 +110,11 @@ pub struct ProjectSummary {
     pub status: String,
     pub sample_count: u32,
     pub progress_percent: Option<f64>,
+    /// True while the project is locked against data entry
+    pub locked: bool,
 }
 
+#[cfg(feature = "server")]
 impl From<miso_domain::entities::Project> for ProjectSummary {
     fn from(project: miso_domain::entities::Project) -> Self {
         Self {
@@ -105,7 +124,48 @@ impl From<miso_domain::entities::Project> for ProjectSummary {
             status: project.status.to_string(),
             sample_count: project.sample_count,
             progress_percent: project.progress_percent(),
+            locked: project.active_lock().is_some(),
         }
     }
 }
 
+/// Request to lock a project against data entry, e.g. for a PI review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct LockProjectRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub reason: String,
+
+    /// When the lock lapses by itself; it stays until unlocked if absent
+    pub unlock_at: Option<DateTime<Utc>>,
+}
+
+/// A project's data entry lock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectLockResponse {
+    pub reason: String,
+    pub locked_by: String,
+    pub locked_at: DateTime<Utc>,
+    pub unlock_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::ProjectLock> for ProjectLockResponse {
+    fn from(lock: miso_domain::entities::ProjectLock) -> Self {
+        Self {
+            reason: lock.reason,
+            locked_by: lock.locked_by,
+            locked_at: lock.locked_at,
+            unlock_at: lock.unlock_at,
+        }
+    }
+}
+
+/// The `details` of the 423 returned when a change is refused because its
+/// project is locked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectLockedResponse {
+    pub project: String,
+    pub reason: String,
+    pub unlock_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,257 @@
+//! Run lifecycle Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+use crate::{LaneOverview, RunInterruptionResponse};
+
+/// Request to set up a run on a sequencer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreateRunRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    #[cfg_attr(feature = "server", validate(length(max = 255)))]
+    pub alias: Option<String>,
+
+    pub sequencer_id: i32,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub container_barcode: Option<String>,
+
+    /// Container model of the flow cell, checked against the sequencer's
+    /// instrument model
+    pub container_model: Option<String>,
+
+    /// Chemistry version, checked against the sequencer's instrument model
+    pub chemistry: Option<String>,
+
+    /// e.g. "2x150"
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub read_length: Option<String>,
+
+    pub description: Option<String>,
+}
+
+/// Request to load a pool on one of a run's partitions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct AssignPoolRequest {
+    pub pool_id: i32,
+
+    /// Loading concentration in pM
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub loading_concentration: f64,
+}
+
+/// What was scanned at the instrument while loading a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct LoadCheckRequest {
+    /// Serial of the flow cell being loaded
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub container_barcode: String,
+
+    /// Pool tubes being loaded
+    #[cfg_attr(feature = "server", validate(length(min = 1)))]
+    pub pools: Vec<LoadCheckScan>,
+}
+
+/// A pool tube scanned while loading a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadCheckScan {
+    pub pool_barcode: String,
+    /// Lane the tube is loaded on, for instruments loaded lane by lane
+    pub partition: Option<u8>,
+}
+
+/// A load check that found the run loaded as planned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadCheckResponse {
+    pub run_id: i32,
+    pub container_barcode: String,
+    /// Pools checked, by ID
+    pub pool_ids: Vec<i32>,
+    pub checked_by: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// The `details` of the 409 returned when what is scanned at the
+/// instrument doesn't match the run's plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadMismatchResponse {
+    pub run: String,
+    pub mismatches: Vec<String>,
+}
+
+/// Request to move a run to a new status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateRunStatusRequest {
+    /// "running", "completed" or "failed"
+    pub status: String,
+}
+
+/// Request to pause a running run, e.g. after a power blip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct PauseRunRequest {
+    /// What interrupted the run
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 2000)))]
+    pub cause: String,
+
+    /// Lanes affected by the interruption that need extra QC review
+    #[serde(default)]
+    pub flagged_lanes: Vec<u8>,
+}
+
+/// Request to resume a paused run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResumeRunRequest {
+    /// Lanes found to be affected on resuming, flagged for extra QC review
+    #[serde(default)]
+    pub flagged_lanes: Vec<u8>,
+}
+
+/// Request to record the sequencing cycles a run has completed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunProgressRequest {
+    pub cycles_completed: u32,
+}
+
+/// Response containing run details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunResponse {
+    pub id: i32,
+    pub name: String,
+    pub alias: Option<String>,
+    pub sequencer_id: i32,
+    pub container_barcode: Option<String>,
+    /// Model of the container, e.g. "S4 Flow Cell"
+    pub container_model: Option<String>,
+    /// Status code, e.g. "running"
+    pub status: String,
+    pub read_length: Option<String>,
+    pub description: Option<String>,
+    pub lanes: Vec<LaneOverview>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// When the run was last marked ready to load on its instrument
+    pub ready_to_load_at: Option<DateTime<Utc>>,
+    /// Times the run was paused, oldest first
+    pub interruptions: Vec<RunInterruptionResponse>,
+    /// Total time paused, in minutes
+    pub downtime_minutes: i64,
+    /// When the run is expected to finish
+    pub estimated_completion: Option<DateTime<Utc>>,
+    pub cycles_completed: Option<u32>,
+    /// Cycles in the run's read configuration, e.g. 300 for "2x150"
+    pub total_cycles: Option<u32>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Run> for RunResponse {
+    fn from(run: miso_domain::entities::Run) -> Self {
+        let now = Utc::now();
+        Self {
+            status: crate::codes::run_status(run.status).to_string(),
+            lanes: run.partitions.iter().map(LaneOverview::from).collect(),
+            interruptions: RunInterruptionResponse::list(&run, now),
+            downtime_minutes: run.downtime(now).num_minutes(),
+            total_cycles: run.total_cycles(),
+            id: run.id,
+            name: run.name,
+            alias: run.alias,
+            sequencer_id: run.sequencer_id,
+            container_barcode: run.container_barcode,
+            container_model: run.container_model,
+            read_length: run.read_length,
+            description: run.description,
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+            ready_to_load_at: run.ready_to_load_at,
+            estimated_completion: run.estimated_completion,
+            cycles_completed: run.cycles_completed,
+            created_by: run.created_by,
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+        }
+    }
+}
+
+/// Summary of a run (for list views).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub id: i32,
+    pub name: String,
+    pub sequencer_id: i32,
+    /// Status code, e.g. "running"
+    pub status: String,
+    /// Lanes with a pool loaded
+    pub loaded_lanes: usize,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Run> for RunSummary {
+    fn from(run: miso_domain::entities::Run) -> Self {
+        Self {
+            status: crate::codes::run_status(run.status).to_string(),
+            loaded_lanes: run
+                .partitions
+                .iter()
+                .filter(|p| p.pool_id.is_some())
+                .count(),
+            id: run.id,
+            name: run.name,
+            sequencer_id: run.sequencer_id,
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+        }
+    }
+}
+
+/// A library whose index pair was sequenced in an earlier lane of its run
+/// or in the previous run on its sequencer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CarryoverWarningResponse {
+    /// The index pair, as `i7` or `i7+i5`
+    pub index: String,
+    pub lane: u8,
+    pub library: String,
+    pub earlier_run: String,
+    pub earlier_lane: u8,
+    pub earlier_library: String,
+    pub message: String,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::services::CarryoverRisk> for CarryoverWarningResponse {
+    fn from(risk: miso_domain::services::CarryoverRisk) -> Self {
+        Self {
+            message: risk.to_string(),
+            index: risk.index,
+            lane: risk.lane,
+            library: risk.library,
+            earlier_run: risk.earlier_run,
+            earlier_lane: risk.earlier_lane,
+            earlier_library: risk.earlier_library,
+        }
+    }
+}
+
+/// Index pairs a run repeats from earlier lanes, where material carried
+/// over could be assigned to the wrong library.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunCarryoverResponse {
+    pub run: String,
+    /// The run before it on the same sequencer, if any
+    pub previous_run: Option<String>,
+    pub warnings: Vec<CarryoverWarningResponse>,
+}
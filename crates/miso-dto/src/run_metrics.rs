@@ -0,0 +1,168 @@
+//! Run metrics Data Transfer Objects.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Metrics for one library on one lane, as reported by a demux pipeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct LibraryMetricsInput {
+    #[cfg_attr(feature = "server", validate(range(min = 1)))]
+    pub partition_number: u8,
+
+    pub library_id: i32,
+
+    pub reads: u64,
+
+    pub yield_bases: u64,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0, max = 100.0)))]
+    pub q30_percent: f64,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0, max = 100.0)))]
+    pub index_hopping_percent: Option<f64>,
+}
+
+/// Request to submit demultiplexing metrics for a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct SubmitRunMetricsRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1), nested))]
+    pub metrics: Vec<LibraryMetricsInput>,
+}
+
+/// Response containing stored metrics for one library on one lane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryMetricsResponse {
+    pub partition_number: u8,
+    pub library_id: i32,
+    pub reads: u64,
+    pub yield_bases: u64,
+    pub q30_percent: f64,
+    pub index_hopping_percent: Option<f64>,
+    pub submitted_by: String,
+    pub submitted_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::LibraryRunMetrics> for LibraryMetricsResponse {
+    fn from(metrics: miso_domain::entities::LibraryRunMetrics) -> Self {
+        Self {
+            partition_number: metrics.partition_number,
+            library_id: metrics.library_id,
+            reads: metrics.reads,
+            yield_bases: metrics.yield_bases,
+            q30_percent: metrics.q30_percent,
+            index_hopping_percent: metrics.index_hopping_percent,
+            submitted_by: metrics.submitted_by,
+            submitted_at: metrics.submitted_at,
+        }
+    }
+}
+
+/// Assay completion status for a library, aggregated across lanes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryCompletionResponse {
+    pub library_id: i32,
+    pub total_reads: u64,
+    pub total_yield_bases: u64,
+    pub q30_percent: f64,
+    pub max_index_hopping_percent: Option<f64>,
+    pub lane_count: usize,
+    pub status: String,
+    pub complete: bool,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::services::LibraryCompletion> for LibraryCompletionResponse {
+    fn from(completion: miso_domain::services::LibraryCompletion) -> Self {
+        Self {
+            library_id: completion.library_id,
+            total_reads: completion.total_reads,
+            total_yield_bases: completion.total_yield_bases,
+            q30_percent: completion.q30_percent,
+            max_index_hopping_percent: completion.max_index_hopping_percent,
+            lane_count: completion.lane_count,
+            status: completion.status.to_string(),
+            complete: completion.status.is_complete(),
+        }
+    }
+}
+
+/// Response containing all metrics for a run and the resulting completion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetricsResponse {
+    pub run_id: i32,
+    pub metrics: Vec<LibraryMetricsResponse>,
+    pub completion: Vec<LibraryCompletionResponse>,
+}
+
+/// Demultiplexed output of one lane, summed over its libraries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaneYield {
+    pub partition_number: u8,
+    pub reads: u64,
+    pub yield_bases: u64,
+    /// Read-weighted Q30 across the lane's libraries
+    pub q30_percent: Option<f64>,
+}
+
+impl RunMetricsResponse {
+    /// Sums library metrics per lane, in lane order.
+    pub fn lane_yields(&self) -> Vec<LaneYield> {
+        let mut lanes: BTreeMap<u8, (u64, u64, f64)> = Default::default();
+        for m in &self.metrics {
+            let lane = lanes.entry(m.partition_number).or_default();
+            lane.0 += m.reads;
+            lane.1 += m.yield_bases;
+            lane.2 += m.q30_percent * m.reads as f64;
+        }
+        lanes
+            .into_iter()
+            .map(
+                |(partition_number, (reads, yield_bases, weighted_q30))| LaneYield {
+                    partition_number,
+                    reads,
+                    yield_bases,
+                    q30_percent: (reads > 0).then(|| weighted_q30 / reads as f64),
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lane_yields_weight_q30_by_reads() {
+        let json = r#"{
+            "run_id": 1,
+            "metrics": [
+                {"partition_number": 2, "library_id": 1, "reads": 300, "yield_bases": 45000,
+                 "q30_percent": 90.0, "index_hopping_percent": null,
+                 "submitted_by": "demux", "submitted_at": "2024-03-10T12:00:00Z"},
+                {"partition_number": 1, "library_id": 1, "reads": 100, "yield_bases": 15000,
+                 "q30_percent": 80.0, "index_hopping_percent": 0.1,
+                 "submitted_by": "demux", "submitted_at": "2024-03-10T12:00:00Z"},
+                {"partition_number": 2, "library_id": 2, "reads": 100, "yield_bases": 15000,
+                 "q30_percent": 70.0, "index_hopping_percent": null,
+                 "submitted_by": "demux", "submitted_at": "2024-03-10T12:00:00Z"}
+            ],
+            "completion": []
+        }"#;
+        let metrics: RunMetricsResponse = serde_json::from_str(json).unwrap();
+
+        let lanes = metrics.lane_yields();
+        assert_eq!(lanes.len(), 2);
+        assert_eq!(lanes[0].partition_number, 1);
+        assert_eq!(lanes[1].reads, 400);
+        assert_eq!(lanes[1].yield_bases, 60000);
+        assert_eq!(lanes[1].q30_percent, Some(85.0));
+    }
+}
@@ -0,0 +1,273 @@
+//! Run monitoring Data Transfer Objects.
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
+use miso_domain::entities::{Library, Pool, Run, RunInterruption, RunPartition, RunQcSignOff};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Instrument-reported metrics for one lane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaneOverview {
+    pub partition_number: u8,
+    pub pool_id: Option<i32>,
+    pub loading_concentration: Option<f64>,
+    pub cluster_density: Option<f64>,
+    pub pass_filter_percent: Option<f64>,
+    pub q30_percent: Option<f64>,
+    /// Bases passing filter, in gigabases
+    #[serde(default)]
+    pub yield_gbp: Option<f64>,
+}
+
+#[cfg(feature = "server")]
+impl From<&RunPartition> for LaneOverview {
+    fn from(partition: &RunPartition) -> Self {
+        Self {
+            partition_number: partition.partition_number,
+            pool_id: partition.pool_id,
+            loading_concentration: partition.loading_concentration,
+            cluster_density: partition.cluster_density,
+            pass_filter_percent: partition.pass_filter_percent,
+            q30_percent: partition.q30_percent,
+            yield_gbp: partition.yield_gbp,
+        }
+    }
+}
+
+/// A reviewer's QC decision on a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunQcSignOffResponse {
+    pub passed: bool,
+    pub reviewer: String,
+    pub note: Option<String>,
+    pub signed_off_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl From<&RunQcSignOff> for RunQcSignOffResponse {
+    fn from(sign_off: &RunQcSignOff) -> Self {
+        Self {
+            passed: sign_off.passed,
+            reviewer: sign_off.reviewer.clone(),
+            note: sign_off.note.clone(),
+            signed_off_at: sign_off.signed_off_at,
+        }
+    }
+}
+
+/// A time the run was paused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInterruptionResponse {
+    pub cause: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the run is still paused
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Time paused so far, in minutes
+    pub duration_minutes: i64,
+    /// Lanes flagged for extra QC review
+    pub flagged_lanes: Vec<u8>,
+    pub recorded_by: String,
+}
+
+#[cfg(feature = "server")]
+impl RunInterruptionResponse {
+    /// Builds the response for `interruption` as of `now`.
+    pub fn new(interruption: &RunInterruption, now: DateTime<Utc>) -> Self {
+        Self {
+            cause: interruption.cause.clone(),
+            started_at: interruption.started_at,
+            ended_at: interruption.ended_at,
+            duration_minutes: interruption.duration(now).num_minutes(),
+            flagged_lanes: interruption.flagged_lanes.clone(),
+            recorded_by: interruption.recorded_by.clone(),
+        }
+    }
+
+    /// Builds the responses for all of `run`'s interruptions as of `now`.
+    pub fn list(run: &Run, now: DateTime<Utc>) -> Vec<Self> {
+        run.interruptions
+            .iter()
+            .map(|i| Self::new(i, now))
+            .collect()
+    }
+}
+
+/// A library in a pool loaded on the run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolLibrary {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    /// Index name, if the library is indexed
+    pub index: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl From<&Library> for PoolLibrary {
+    fn from(library: &Library) -> Self {
+        Self {
+            id: library.id,
+            name: library.name.clone(),
+            barcode: library.barcode.to_string(),
+            index: library.index.as_ref().map(|i| i.name().to_string()),
+        }
+    }
+}
+
+/// A pool loaded on the run and the libraries it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolOverview {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    /// Lanes the pool is loaded on
+    pub lanes: Vec<u8>,
+    pub libraries: Vec<PoolLibrary>,
+}
+
+#[cfg(feature = "server")]
+impl PoolOverview {
+    /// Builds the overview of `pool` on `run`, listing the libraries found
+    /// among `libraries`.
+    pub fn new(pool: &Pool, run: &Run, libraries: &[Library]) -> Self {
+        let lanes = run
+            .partitions
+            .iter()
+            .filter(|p| p.pool_id == Some(pool.id))
+            .map(|p| p.partition_number)
+            .collect();
+        let libraries = pool
+            .elements
+            .iter()
+            .filter_map(|e| libraries.iter().find(|l| l.id == e.library_id))
+            .map(PoolLibrary::from)
+            .collect();
+
+        Self {
+            id: pool.id,
+            name: pool.name.clone(),
+            barcode: pool.barcode.to_string(),
+            lanes,
+            libraries,
+        }
+    }
+}
+
+/// A run with its lanes, loaded pools and QC decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOverviewResponse {
+    pub id: i32,
+    pub name: String,
+    pub alias: Option<String>,
+    pub sequencer_id: i32,
+    pub container_barcode: Option<String>,
+    pub status: String,
+    pub read_length: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub overdue_since: Option<DateTime<Utc>>,
+    /// When the run is expected to finish
+    #[serde(default)]
+    pub estimated_completion: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cycles_completed: Option<u32>,
+    /// Cycles in the run's read configuration, e.g. 300 for "2x150"
+    #[serde(default)]
+    pub total_cycles: Option<u32>,
+    pub lanes: Vec<LaneOverview>,
+    pub qc_sign_off: Option<RunQcSignOffResponse>,
+    /// Times the run was paused, oldest first
+    #[serde(default)]
+    pub interruptions: Vec<RunInterruptionResponse>,
+    /// Total time paused, in minutes
+    #[serde(default)]
+    pub downtime_minutes: i64,
+    /// Lanes flagged by an interruption for extra QC review
+    #[serde(default)]
+    pub lanes_for_review: Vec<u8>,
+    /// Pools on the run; `None` when pools are not available
+    pub pools: Option<Vec<PoolOverview>>,
+}
+
+impl RunOverviewResponse {
+    /// Builds the overview of `run`; pools are filled in separately.
+    #[cfg(feature = "server")]
+    pub fn new(run: &Run) -> Self {
+        let now = Utc::now();
+        Self {
+            id: run.id,
+            name: run.name.clone(),
+            alias: run.alias.clone(),
+            sequencer_id: run.sequencer_id,
+            container_barcode: run.container_barcode.clone(),
+            status: run.status.to_string(),
+            read_length: run.read_length.clone(),
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+            overdue_since: run.overdue_since,
+            estimated_completion: run.estimated_completion,
+            cycles_completed: run.cycles_completed,
+            total_cycles: run.total_cycles(),
+            lanes: run.partitions.iter().map(LaneOverview::from).collect(),
+            qc_sign_off: run.qc_sign_off.as_ref().map(RunQcSignOffResponse::from),
+            interruptions: RunInterruptionResponse::list(run, now),
+            downtime_minutes: run.downtime(now).num_minutes(),
+            lanes_for_review: run.lanes_for_review(),
+            pools: None,
+        }
+    }
+
+    /// Whether a reviewer can sign the run off: it has completed and is not
+    /// still sequencing, stopped or failed on the instrument.
+    pub fn awaiting_review(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "Completed" | "QC In Progress" | "QC Passed" | "QC Failed"
+        )
+    }
+}
+
+/// Request to record a QC decision on a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct SignOffRunQcRequest {
+    pub passed: bool,
+
+    /// Required when failing the run
+    #[cfg_attr(feature = "server", validate(length(max = 2000)))]
+    pub note: Option<String>,
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use miso_domain::entities::PoolElement;
+    use miso_domain::value_objects::Barcode;
+
+    #[test]
+    fn test_pool_overview_lists_lanes_and_libraries() {
+        let mut run = Run::new(1, "RUN001".to_string(), 1, 4, "admin".to_string());
+        run.partitions[0].set_pool(7, 1.5);
+        run.partitions[2].set_pool(7, 1.5);
+
+        let mut pool = Pool::new(
+            7,
+            "POOL7".to_string(),
+            Barcode::new("POOL7").unwrap(),
+            "ILLUMINA".to_string(),
+            "admin".to_string(),
+        );
+        pool.elements.push(PoolElement {
+            library_aliquot_id: 11,
+            library_id: 3,
+            volume: None,
+            proportion: None,
+        });
+
+        let overview = PoolOverview::new(&pool, &run, &[]);
+        assert_eq!(overview.lanes, vec![1, 3]);
+        assert!(overview.libraries.is_empty());
+    }
+}
@@ -0,0 +1,452 @@
+//! Sample Data Transfer Objects.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+/// Request to create a new plain sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreatePlainSampleRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    pub project_id: i32,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub scientific_name: String,
+
+    pub description: Option<String>,
+
+    pub sample_type: Option<String>,
+
+    /// Custom attribute values, keyed by attribute key
+    #[serde(default)]
+    pub attributes: Option<BTreeMap<String, String>>,
+
+    /// "positive", "negative" or "ntc" if the sample is a control
+    #[serde(default)]
+    pub control_type: Option<String>,
+
+    /// Subproject of the project to assign the sample to
+    #[serde(default)]
+    pub subproject_id: Option<i32>,
+}
+
+/// Request to create a detailed sample (with hierarchy).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreateDetailedSampleRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    pub project_id: i32,
+
+    pub parent_id: Option<i32>,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 50)))]
+    pub sample_class: String,
+
+    pub external_name: Option<String>,
+
+    pub tissue_origin: Option<String>,
+
+    pub tissue_type: Option<String>,
+
+    pub analyte_type: Option<String>,
+
+    pub description: Option<String>,
+}
+
+/// Request to create an identity, the patient or donor other detailed
+/// samples come from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreateIdentityRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    pub project_id: i32,
+
+    /// Patient or donor IDs, separated by commas
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub external_name: String,
+
+    pub description: Option<String>,
+
+    /// Create the identity even though existing identities have similar
+    /// external names
+    #[serde(default)]
+    pub create_anyway: bool,
+
+    /// Subproject of the project to assign the identity to
+    #[serde(default)]
+    pub subproject_id: Option<i32>,
+}
+
+/// Request to create a tissue pooled from several identities, such as an
+/// environmental or pooled screening specimen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreatePooledSampleRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    pub project_id: i32,
+
+    /// Identities pooled into the sample; at least two
+    #[cfg_attr(feature = "server", validate(length(min = 2, max = 1000)))]
+    pub identity_ids: Vec<i32>,
+
+    pub tissue_origin: Option<String>,
+
+    pub tissue_type: Option<String>,
+
+    pub description: Option<String>,
+}
+
+/// An existing identity a new one may be a duplicate of.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityMatchResponse {
+    pub identity_id: i32,
+    pub name: String,
+    pub external_name: String,
+    /// Typos between the external names; 0 if they differ only in case,
+    /// spacing or punctuation
+    pub distance: u32,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::services::IdentityMatch> for IdentityMatchResponse {
+    fn from(m: miso_domain::services::IdentityMatch) -> Self {
+        Self {
+            identity_id: m.identity_id,
+            name: m.name,
+            external_name: m.external_name,
+            distance: m.distance,
+        }
+    }
+}
+
+/// Response to a request to create an identity.
+///
+/// If existing identities have similar external names and the request
+/// didn't say to create it anyway, `identity` is empty and `matches` lists
+/// them; otherwise `matches` lists those the user chose to ignore.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateIdentityResponse {
+    pub identity: Option<SampleResponse>,
+    pub matches: Vec<IdentityMatchResponse>,
+}
+
+/// An identity created although it looked like an existing one, awaiting
+/// review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PossibleDuplicateResponse {
+    pub id: i32,
+    pub identity_id: i32,
+    pub match_id: i32,
+    pub external_name: String,
+    pub matched_name: String,
+    pub distance: u32,
+    pub confirmed_by: String,
+    pub confirmed_at: DateTime<Utc>,
+    /// "distinct" or "duplicate", once reviewed
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::PossibleDuplicate> for PossibleDuplicateResponse {
+    fn from(duplicate: miso_domain::entities::PossibleDuplicate) -> Self {
+        Self {
+            id: duplicate.id,
+            identity_id: duplicate.identity_id,
+            match_id: duplicate.match_id,
+            external_name: duplicate.external_name,
+            matched_name: duplicate.matched_name,
+            distance: duplicate.distance,
+            confirmed_by: duplicate.confirmed_by,
+            confirmed_at: duplicate.confirmed_at,
+            resolution: duplicate
+                .resolution
+                .map(|r| crate::codes::duplicate_resolution(r).to_string()),
+            resolved_by: duplicate.resolved_by,
+            resolved_at: duplicate.resolved_at,
+        }
+    }
+}
+
+/// Request to record the review of a possible duplicate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct ResolveDuplicateRequest {
+    /// "distinct" if the identities are different people, "duplicate" if
+    /// they should be merged
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 20)))]
+    pub resolution: String,
+}
+
+/// Request to update an existing sample.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct UpdateSampleRequest {
+    pub description: Option<String>,
+
+    pub volume_ul: Option<f64>,
+
+    #[cfg_attr(feature = "server", validate(range(min = 0.0)))]
+    pub mass_ng: Option<f64>,
+
+    pub concentration_ng_ul: Option<f64>,
+
+    pub qc_status: Option<String>,
+
+    /// Why the QC status is changing; required for failures and for
+    /// overturning a final result
+    #[cfg_attr(feature = "server", validate(length(max = 2000)))]
+    pub qc_reason: Option<String>,
+
+    /// Custom attribute values; replaces every stored value when given
+    #[serde(default)]
+    pub attributes: Option<BTreeMap<String, String>>,
+
+    /// "positive", "negative" or "ntc" to mark the sample a control, or
+    /// "none" to make it project material again
+    #[serde(default)]
+    pub control_type: Option<String>,
+
+    /// Subproject of the sample's project to move the sample to
+    #[serde(default)]
+    pub subproject_id: Option<i32>,
+}
+
+/// One row of a bulk sample update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct BulkSampleUpdate {
+    pub id: i32,
+
+    /// Version the client last read; the row is rejected if it has changed
+    pub version: i32,
+
+    #[cfg_attr(feature = "server", validate(nested))]
+    pub changes: UpdateSampleRequest,
+}
+
+/// Request to update many samples at once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct BulkUpdateSamplesRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 1000), nested))]
+    pub updates: Vec<BulkSampleUpdate>,
+}
+
+/// Outcome of one row of a bulk sample update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkUpdateRowStatus {
+    /// The row was written
+    Updated,
+    /// The row was valid but not written because another row failed
+    Skipped,
+    /// The sample changed since the client read it
+    Conflict,
+    /// The sample doesn't exist
+    NotFound,
+    /// The changes were rejected
+    Invalid,
+}
+
+/// Result for one row of a bulk sample update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkUpdateRowResult {
+    pub id: i32,
+    pub status: BulkUpdateRowStatus,
+    /// Stored version after the update, or the conflicting version
+    pub current_version: Option<i32>,
+    pub message: Option<String>,
+}
+
+/// Response to a bulk sample update.
+///
+/// Updates are all-or-nothing: if any row fails, `applied` is false and
+/// no row has been written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkUpdateSamplesResponse {
+    pub applied: bool,
+    pub results: Vec<BulkUpdateRowResult>,
+    /// The updated samples, empty unless applied
+    pub samples: Vec<SampleResponse>,
+}
+
+/// Response containing sample details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleResponse {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub project_id: i32,
+    pub description: Option<String>,
+    pub sample_mode: String,
+    pub sample_class: String,
+    pub parent_id: Option<i32>,
+    pub external_name: Option<String>,
+    pub volume_ul: Option<f64>,
+    pub mass_ng: Option<f64>,
+    pub concentration_ng_ul: Option<f64>,
+    pub qc_status: String,
+    pub received_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived: bool,
+    pub version: i32,
+    pub attributes: BTreeMap<String, String>,
+    /// Pooled from several identities rather than derived from one
+    #[serde(default)]
+    pub pooled: bool,
+    /// "positive", "negative" or "ntc" if the sample is a control
+    #[serde(default)]
+    pub control_type: Option<String>,
+    /// Subproject of the project the sample is assigned to
+    #[serde(default)]
+    pub subproject_id: Option<i32>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Sample> for SampleResponse {
+    fn from(sample: miso_domain::entities::Sample) -> Self {
+        use miso_domain::entities::SampleDetails;
+
+        let (sample_mode, sample_class, external_name) = match &sample.details {
+            SampleDetails::Plain(_) => ("plain".to_string(), "plain".to_string(), None),
+            SampleDetails::Detailed(d) => (
+                "detailed".to_string(),
+                d.sample_class.to_string(),
+                d.external_name.clone(),
+            ),
+        };
+        let parent_id = sample.parent_id();
+        let pooled = sample.is_pooled();
+
+        Self {
+            id: sample.id,
+            name: sample.name,
+            barcode: sample.barcode.to_string(),
+            project_id: sample.project_id,
+            description: sample.description,
+            sample_mode,
+            sample_class,
+            parent_id,
+            external_name,
+            volume_ul: sample.volume.map(|v| v.as_microliters()),
+            mass_ng: sample.mass.map(|m| m.as_nanograms()),
+            concentration_ng_ul: sample.concentration.map(|c| c.value()),
+            qc_status: sample.qc_status.to_string(),
+            received_at: sample.received_at,
+            created_by: sample.created_by,
+            created_at: sample.created_at,
+            updated_at: sample.updated_at,
+            archived: sample.archived,
+            version: sample.version,
+            attributes: sample.attributes,
+            pooled,
+            control_type: sample.control_type.map(|c| c.to_string()),
+            subproject_id: sample.subproject_id,
+        }
+    }
+}
+
+/// A change log entry for a sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeLogEntryResponse {
+    pub id: i32,
+    pub summary: String,
+    pub reason: Option<String>,
+    pub changed_by: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::ChangeLogEntry> for ChangeLogEntryResponse {
+    fn from(entry: miso_domain::entities::ChangeLogEntry) -> Self {
+        Self {
+            id: entry.id,
+            summary: entry.summary,
+            reason: entry.reason,
+            changed_by: entry.changed_by,
+            changed_at: entry.changed_at,
+        }
+    }
+}
+
+/// Summary of a sample (for list views).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleSummary {
+    pub id: i32,
+    pub name: String,
+    pub barcode: String,
+    pub sample_class: String,
+    pub qc_status: String,
+    pub can_create_library: bool,
+    pub version: i32,
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Sample> for SampleSummary {
+    fn from(sample: miso_domain::entities::Sample) -> Self {
+        Self {
+            id: sample.id,
+            name: sample.name.clone(),
+            barcode: sample.barcode.to_string(),
+            sample_class: sample.sample_class().to_string(),
+            qc_status: sample.qc_status.to_string(),
+            can_create_library: sample.can_create_library(),
+            version: sample.version,
+        }
+    }
+}
+
+impl SampleSummary {
+    /// Whether the sample still needs a QC result.
+    pub fn awaiting_qc(&self) -> bool {
+        !matches!(self.qc_status.as_str(), "Passed" | "Failed")
+    }
+}
+
+/// Where a sample comes from and what was made from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleLineageResponse {
+    pub sample: SampleResponse,
+    /// Parents of the sample, nearest first
+    pub ancestors: Vec<SampleSummary>,
+    /// Identities the sample comes from: the one at the top of its
+    /// hierarchy, or each identity pooled into it
+    pub identities: Vec<SampleSummary>,
+    /// Samples derived from the sample, including samples an identity was
+    /// pooled into, nearest first
+    pub descendants: Vec<SampleSummary>,
+}
+
+/// Scan result from VisionMate scanner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RackScanResult {
+    pub rack_barcode: Option<String>,
+    pub tubes: Vec<TubeScanResult>,
+    pub empty_count: usize,
+    pub error_count: usize,
+}
+
+/// Individual tube from a rack scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TubeScanResult {
+    pub position: String,
+    pub barcode: String,
+    pub sample_id: Option<i32>,
+    pub sample_name: Option<String>,
+}
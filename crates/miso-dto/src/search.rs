@@ -0,0 +1,150 @@
+//! Quick search DTOs.
+
+use serde::{Deserialize, Serialize};
+
+use crate::SampleResponse;
+
+/// Kind of entity a search hit refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchHitKind {
+    Project,
+    Sample,
+}
+
+/// An entity matching a search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub id: i32,
+    /// Project code or sample name
+    pub label: String,
+    /// Project name or sample barcode
+    pub detail: Option<String>,
+    /// The project itself, or the project the sample belongs to
+    pub project_id: i32,
+    pub barcode: Option<String>,
+}
+
+impl SearchHit {
+    /// Whether the hit is a project.
+    pub fn is_project(&self) -> bool {
+        self.kind == SearchHitKind::Project
+    }
+}
+
+impl From<SampleResponse> for SearchHit {
+    fn from(sample: SampleResponse) -> Self {
+        Self {
+            kind: SearchHitKind::Sample,
+            id: sample.id,
+            label: sample.name,
+            detail: Some(sample.barcode.clone()),
+            project_id: sample.project_id,
+            barcode: Some(sample.barcode),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Project> for SearchHit {
+    fn from(project: miso_domain::entities::Project) -> Self {
+        Self {
+            kind: SearchHitKind::Project,
+            id: project.id,
+            label: project.code,
+            detail: Some(project.name),
+            project_id: project.id,
+            barcode: None,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<miso_domain::entities::Sample> for SearchHit {
+    fn from(sample: miso_domain::entities::Sample) -> Self {
+        let barcode = sample.barcode.to_string();
+        Self {
+            kind: SearchHitKind::Sample,
+            id: sample.id,
+            label: sample.name,
+            detail: Some(barcode.clone()),
+            project_id: sample.project_id,
+            barcode: Some(barcode),
+        }
+    }
+}
+
+/// Results of a quick search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+}
+
+impl SearchResponse {
+    /// Collects the hits for `query`, projects before samples, with exact
+    /// matches on code, name or barcode first and duplicates dropped.
+    pub fn new(query: &str, hits: impl IntoIterator<Item = SearchHit>) -> Self {
+        let mut unique: Vec<SearchHit> = Vec::new();
+        for hit in hits {
+            if !unique.iter().any(|h| h.kind == hit.kind && h.id == hit.id) {
+                unique.push(hit);
+            }
+        }
+
+        let exact = |hit: &SearchHit| {
+            hit.label.eq_ignore_ascii_case(query)
+                || hit
+                    .barcode
+                    .as_deref()
+                    .is_some_and(|b| b.eq_ignore_ascii_case(query))
+        };
+        // Stable, so each group keeps the repositories' order
+        unique.sort_by_key(|hit| (!exact(hit), hit.kind == SearchHitKind::Sample));
+
+        Self {
+            query: query.to_string(),
+            hits: unique,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(kind: SearchHitKind, id: i32, label: &str, barcode: Option<&str>) -> SearchHit {
+        SearchHit {
+            kind,
+            id,
+            label: label.to_string(),
+            detail: None,
+            project_id: 1,
+            barcode: barcode.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_exact_matches_rank_first() {
+        let response = SearchResponse::new(
+            "sam-12",
+            vec![
+                hit(SearchHitKind::Sample, 3, "liver", Some("SAM-123")),
+                hit(SearchHitKind::Sample, 2, "kidney", Some("SAM-12")),
+                hit(SearchHitKind::Project, 1, "SAMPLING", None),
+                hit(SearchHitKind::Sample, 2, "kidney", Some("SAM-12")),
+            ],
+        );
+
+        let order: Vec<_> = response.hits.iter().map(|h| (h.kind, h.id)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (SearchHitKind::Sample, 2),
+                (SearchHitKind::Project, 1),
+                (SearchHitKind::Sample, 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,531 @@
+//! Box storage browsing Data Transfer Objects.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use validator::Validate;
+
+#[cfg(feature = "server")]
+use miso_domain::entities::StorageBox;
+
+/// Name used for a level of the hierarchy a box's location leaves unset.
+pub const UNASSIGNED: &str = "Unassigned";
+
+/// Level of the storage hierarchy a node represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLevel {
+    Freezer,
+    Shelf,
+    Rack,
+}
+
+impl StorageLevel {
+    fn child(self) -> Option<Self> {
+        match self {
+            Self::Freezer => Some(Self::Shelf),
+            Self::Shelf => Some(Self::Rack),
+            Self::Rack => None,
+        }
+    }
+}
+
+impl std::fmt::Display for StorageLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Freezer => write!(f, "freezer"),
+            Self::Shelf => write!(f, "shelf"),
+            Self::Rack => write!(f, "rack"),
+        }
+    }
+}
+
+/// A freezer, shelf or rack with the fill level of the boxes under it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageNode {
+    pub level: StorageLevel,
+    pub name: String,
+    /// Total positions across the boxes under this node
+    pub capacity: usize,
+    /// Occupied positions across the boxes under this node
+    pub occupied: usize,
+    pub fill_percent: f64,
+    /// Shelves of a freezer or racks of a shelf; empty for racks
+    pub children: Vec<StorageNode>,
+    /// Boxes in a rack; empty for freezers and shelves
+    pub boxes: Vec<BoxSummary>,
+}
+
+impl StorageNode {
+    /// Arranges boxes into a Freezer → Shelf → Rack tree, sorted by name
+    /// at each level.
+    #[cfg(feature = "server")]
+    pub fn build(boxes: &[StorageBox]) -> Vec<Self> {
+        let summaries: Vec<BoxSummary> = boxes.iter().map(BoxSummary::from).collect();
+        Self::group(StorageLevel::Freezer, summaries)
+    }
+
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    fn group(level: StorageLevel, boxes: Vec<BoxSummary>) -> Vec<Self> {
+        let mut groups: BTreeMap<String, Vec<BoxSummary>> = BTreeMap::new();
+        for summary in boxes {
+            let name = match level {
+                StorageLevel::Freezer => summary.freezer.clone(),
+                StorageLevel::Shelf => summary.shelf.clone(),
+                StorageLevel::Rack => summary.rack.clone(),
+            };
+            groups
+                .entry(name.unwrap_or_else(|| UNASSIGNED.to_string()))
+                .or_default()
+                .push(summary);
+        }
+
+        groups
+            .into_iter()
+            .map(|(name, mut boxes)| {
+                let capacity = boxes.iter().map(|b| b.capacity).sum();
+                let occupied = boxes.iter().map(|b| b.occupied).sum();
+                let (children, boxes) = match level.child() {
+                    Some(child) => (Self::group(child, boxes), Vec::new()),
+                    None => {
+                        boxes.sort_by(|a, b| a.name.cmp(&b.name));
+                        (Vec::new(), boxes)
+                    }
+                };
+                Self {
+                    level,
+                    name,
+                    capacity,
+                    occupied,
+                    fill_percent: fill_percent(occupied, capacity),
+                    children,
+                    boxes,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Finds a box anywhere in a storage tree.
+pub fn find_box(nodes: &[StorageNode], id: i32) -> Option<&BoxSummary> {
+    nodes.iter().find_map(|node| {
+        node.boxes
+            .iter()
+            .find(|b| b.id == id)
+            .or_else(|| find_box(&node.children, id))
+    })
+}
+
+/// Lists every box in a storage tree, in tree order.
+pub fn all_boxes(nodes: &[StorageNode]) -> Vec<&BoxSummary> {
+    nodes
+        .iter()
+        .flat_map(|node| {
+            let mut boxes: Vec<&BoxSummary> = node.boxes.iter().collect();
+            boxes.extend(all_boxes(&node.children));
+            boxes
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "server"), allow(dead_code))]
+fn fill_percent(occupied: usize, capacity: usize) -> f64 {
+    if capacity == 0 {
+        0.0
+    } else {
+        occupied as f64 * 100.0 / capacity as f64
+    }
+}
+
+/// A box, its place in the hierarchy and how full it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoxSummary {
+    pub id: i32,
+    pub name: String,
+    pub barcode: Option<String>,
+    /// Storable type code, e.g. "sample"
+    pub storable_type: String,
+    pub rows: u8,
+    pub cols: u8,
+    pub freezer: Option<String>,
+    pub shelf: Option<String>,
+    pub rack: Option<String>,
+    /// Rack the box is assigned to, if any
+    #[serde(default)]
+    pub rack_id: Option<i32>,
+    pub capacity: usize,
+    pub occupied: usize,
+    pub fill_percent: f64,
+}
+
+impl BoxSummary {
+    /// Names of the freezer, shelf and rack holding the box, as they
+    /// appear in the storage tree.
+    pub fn tree_path(&self) -> [String; 3] {
+        [&self.freezer, &self.shelf, &self.rack]
+            .map(|level| level.clone().unwrap_or_else(|| UNASSIGNED.to_string()))
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<&StorageBox> for BoxSummary {
+    fn from(storage_box: &StorageBox) -> Self {
+        let capacity = storage_box.capacity();
+        let occupied = storage_box.item_count();
+        Self {
+            id: storage_box.id,
+            name: storage_box.name.clone(),
+            barcode: storage_box.barcode.clone(),
+            storable_type: crate::codes::storable_type(storage_box.storable_type).to_string(),
+            rows: storage_box.dimension.rows(),
+            cols: storage_box.dimension.cols(),
+            freezer: storage_box.location.freezer.clone(),
+            shelf: storage_box.location.shelf.clone(),
+            rack: storage_box.location.rack.clone(),
+            rack_id: storage_box.rack_id,
+            capacity,
+            occupied,
+            fill_percent: fill_percent(occupied, capacity),
+        }
+    }
+}
+
+/// An item at a position in a box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredItem {
+    /// Position label, e.g. "B7"
+    pub position: String,
+    /// 0-based row index
+    pub row: u8,
+    /// 1-based column number
+    pub col: u8,
+    /// Storable type code, e.g. "sample"
+    pub item_type: String,
+    pub item_id: i32,
+}
+
+/// A box with everything in it, in position order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoxContents {
+    pub storage_box: BoxSummary,
+    pub items: Vec<StoredItem>,
+}
+
+#[cfg(feature = "server")]
+impl From<&StorageBox> for BoxContents {
+    fn from(storage_box: &StorageBox) -> Self {
+        let mut contents = storage_box.all_contents();
+        contents.sort_by_key(|(position, _)| **position);
+        Self {
+            storage_box: BoxSummary::from(storage_box),
+            items: contents
+                .into_iter()
+                .map(|(position, item)| StoredItem {
+                    position: position.to_string(),
+                    row: position.row_index(),
+                    col: position.col(),
+                    item_type: crate::codes::storable_type(item.item_type).to_string(),
+                    item_id: item.item_id,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Where a searched-for item is stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemLocation {
+    /// Storable type code, e.g. "sample"
+    pub item_type: String,
+    pub item_id: i32,
+    pub barcode: String,
+    pub name: String,
+    pub storage_box: BoxSummary,
+    pub position: String,
+}
+
+/// Request to move an item to another position, in the same box or
+/// another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct MoveItemRequest {
+    pub from_box_id: i32,
+
+    /// Position label, e.g. "A1"
+    #[cfg_attr(feature = "server", validate(length(min = 2, max = 4)))]
+    pub from_position: String,
+
+    pub to_box_id: i32,
+
+    #[cfg_attr(feature = "server", validate(length(min = 2, max = 4)))]
+    pub to_position: String,
+}
+
+/// An item that has been moved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemMoved {
+    /// Storable type code, e.g. "sample"
+    pub item_type: String,
+    pub item_id: i32,
+    pub from_box_id: i32,
+    pub from_position: String,
+    pub to_box_id: i32,
+    pub to_position: String,
+}
+
+/// Request to create an empty box.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct CreateBoxRequest {
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub name: String,
+
+    #[cfg_attr(feature = "server", validate(length(min = 1, max = 255)))]
+    pub barcode: Option<String>,
+
+    /// Rows, lettered A onwards
+    #[cfg_attr(feature = "server", validate(range(min = 1, max = 26)))]
+    pub rows: u8,
+
+    #[cfg_attr(feature = "server", validate(range(min = 1, max = 48)))]
+    pub cols: u8,
+
+    /// Storable type code, e.g. "sample"
+    pub storable_type: String,
+
+    pub freezer: Option<String>,
+    pub shelf: Option<String>,
+    pub rack: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Request to place an item at a position in a box.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaceItemRequest {
+    /// Storable type code, e.g. "sample"; must match the box's
+    pub item_type: String,
+    pub item_id: i32,
+}
+
+/// Request to move the item at a box position elsewhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(Validate))]
+pub struct MoveToRequest {
+    /// Box to move the item to; the same box if unset
+    pub to_box_id: Option<i32>,
+
+    #[cfg_attr(feature = "server", validate(length(min = 2, max = 4)))]
+    pub to_position: String,
+}
+
+/// What a plate map shows for an occupied position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlateWell {
+    /// Position label, e.g. "B7"
+    pub position: String,
+    /// Storable type code, e.g. "sample"
+    pub item_type: String,
+    pub item_id: i32,
+    /// Name of the item, where known
+    pub name: Option<String>,
+    pub barcode: Option<String>,
+}
+
+/// A box laid out as a dense grid for plate views.
+///
+/// `wells[r][c]` is the position in row `row_labels[r]` and column
+/// `c + 1`; empty positions are `null`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlateMap {
+    pub storage_box: BoxSummary,
+    /// Row letters, e.g. ["A", "B", ...]
+    pub row_labels: Vec<String>,
+    /// Column numbers, e.g. [1, 2, ...]
+    pub col_labels: Vec<u8>,
+    pub wells: Vec<Vec<Option<PlateWell>>>,
+}
+
+impl PlateMap {
+    /// Returns the well at a 0-based row and 1-based column.
+    pub fn well(&self, row: usize, col: u8) -> Option<&PlateWell> {
+        self.wells
+            .get(row)?
+            .get(usize::from(col).checked_sub(1)?)?
+            .as_ref()
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<&StorageBox> for PlateMap {
+    fn from(storage_box: &StorageBox) -> Self {
+        let rows = storage_box.dimension.rows();
+        let cols = storage_box.dimension.cols();
+        let mut wells = vec![vec![None; usize::from(cols)]; usize::from(rows)];
+        for (position, item) in storage_box.all_contents() {
+            wells[usize::from(position.row_index())][usize::from(position.col() - 1)] =
+                Some(PlateWell {
+                    position: position.to_string(),
+                    item_type: crate::codes::storable_type(item.item_type).to_string(),
+                    item_id: item.item_id,
+                    name: None,
+                    barcode: None,
+                });
+        }
+
+        Self {
+            storage_box: BoxSummary::from(storage_box),
+            row_labels: (0..rows)
+                .map(|r| char::from(b'A' + r).to_string())
+                .collect(),
+            col_labels: (1..=cols).collect(),
+            wells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_boxes_in_tree() {
+        let json = r#"[{
+            "level": "freezer", "name": "F1", "capacity": 162, "occupied": 10,
+            "fill_percent": 6.2, "boxes": [],
+            "children": [{
+                "level": "shelf", "name": "S1", "capacity": 162, "occupied": 10,
+                "fill_percent": 6.2, "boxes": [],
+                "children": [{
+                    "level": "rack", "name": "Unassigned", "capacity": 162,
+                    "occupied": 10, "fill_percent": 6.2, "children": [],
+                    "boxes": [
+                        {"id": 4, "name": "B1", "barcode": null, "storable_type": "sample",
+                         "rows": 9, "cols": 9, "freezer": "F1", "shelf": "S1", "rack": null,
+                         "capacity": 81, "occupied": 10, "fill_percent": 12.3},
+                        {"id": 7, "name": "B2", "barcode": null, "storable_type": "sample",
+                         "rows": 9, "cols": 9, "freezer": "F1", "shelf": "S1", "rack": null,
+                         "capacity": 81, "occupied": 0, "fill_percent": 0.0}
+                    ]
+                }]
+            }]
+        }]"#;
+        let tree: Vec<StorageNode> = serde_json::from_str(json).unwrap();
+
+        let found = find_box(&tree, 7).unwrap();
+        assert_eq!(found.name, "B2");
+        assert_eq!(
+            found.tree_path(),
+            ["F1", "S1", UNASSIGNED].map(String::from)
+        );
+        assert!(find_box(&tree, 5).is_none());
+        assert_eq!(all_boxes(&tree).len(), 2);
+    }
+
+    #[cfg(feature = "server")]
+    mod server {
+        use super::super::*;
+        use miso_domain::entities::{EntityId, StorableItem, StorableType, StorageLocation};
+        use miso_domain::value_objects::BoxPosition;
+
+        fn storage_box(
+            id: EntityId,
+            name: &str,
+            location: StorageLocation,
+            items: usize,
+        ) -> StorageBox {
+            let mut storage_box = StorageBox::sample_box_9x9(id, name.to_string());
+            storage_box.location = location;
+            for i in 0..items {
+                let position = storage_box.dimension.index_to_position(i).unwrap();
+                storage_box
+                    .place_item(position, StorableItem::sample(id * 100 + i as EntityId))
+                    .unwrap();
+            }
+            storage_box
+        }
+
+        #[test]
+        fn test_hierarchy_rolls_up_fill_levels() {
+            let boxes = vec![
+                storage_box(1, "B2", StorageLocation::with_path("F1", "S1", "R1"), 81),
+                storage_box(2, "B1", StorageLocation::with_path("F1", "S1", "R1"), 0),
+                storage_box(3, "B3", StorageLocation::with_path("F1", "S2", "R1"), 27),
+                storage_box(4, "Loose", StorageLocation::new(), 9),
+            ];
+
+            let tree = StorageNode::build(&boxes);
+
+            assert_eq!(
+                tree.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(),
+                vec!["F1", UNASSIGNED]
+            );
+            let freezer = &tree[0];
+            assert_eq!((freezer.occupied, freezer.capacity), (108, 243));
+            assert_eq!(freezer.children.len(), 2);
+
+            let rack = &freezer.children[0].children[0];
+            assert_eq!(rack.level, StorageLevel::Rack);
+            assert_eq!(rack.fill_percent, 50.0);
+            assert_eq!(
+                rack.boxes
+                    .iter()
+                    .map(|b| b.name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["B1", "B2"]
+            );
+
+            let unassigned = &tree[1].children[0].children[0];
+            assert_eq!(unassigned.name, UNASSIGNED);
+            assert_eq!(unassigned.boxes[0].id, 4);
+        }
+
+        #[test]
+        fn test_contents_in_position_order() {
+            let mut storage_box = StorageBox::sample_box_9x9(1, "B1".to_string());
+            for (label, id) in [("C2", 3), ("A9", 2), ("A1", 1)] {
+                let position = BoxPosition::parse(label, &storage_box.dimension).unwrap();
+                storage_box
+                    .place_item(position, StorableItem::sample(id))
+                    .unwrap();
+            }
+
+            let contents = BoxContents::from(&storage_box);
+
+            assert_eq!(
+                contents
+                    .items
+                    .iter()
+                    .map(|i| i.position.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["A1", "A9", "C2"]
+            );
+            assert_eq!((contents.items[2].row, contents.items[2].col), (2, 2));
+            assert_eq!(contents.items[0].item_type, "sample");
+        }
+
+        #[test]
+        fn test_plate_map_is_dense() {
+            let mut storage_box = StorageBox::plate_96(1, "P1".to_string(), StorableType::Sample);
+            for (label, id) in [("A1", 1), ("H12", 2), ("C5", 3)] {
+                let position = BoxPosition::parse(label, &storage_box.dimension).unwrap();
+                storage_box
+                    .place_item(position, StorableItem::sample(id))
+                    .unwrap();
+            }
+
+            let map = PlateMap::from(&storage_box);
+
+            assert_eq!(map.wells.len(), 8);
+            assert!(map.wells.iter().all(|row| row.len() == 12));
+            assert_eq!(map.row_labels.first().map(String::as_str), Some("A"));
+            assert_eq!(map.row_labels.last().map(String::as_str), Some("H"));
+            assert_eq!(map.col_labels.last(), Some(&12));
+            assert_eq!(map.well(7, 12).unwrap().item_id, 2);
+            assert_eq!(map.well(2, 5).unwrap().position, "C5");
+            assert!(map.well(0, 2).is_none());
+            assert!(map.well(0, 0).is_none());
+            assert_eq!(map.wells.iter().flatten().flatten().count(), 3);
+        }
+    }
+}
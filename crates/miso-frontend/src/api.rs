@@ -0,0 +1,283 @@
+//! API client: paths, requests and list view links.
+//!
+//! Requests and responses are the server's own DTOs from miso-dto, so a
+//! change to one side fails to build rather than to parse.
+
+use gloo_net::http::{Request, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+pub use miso_dto::{
+    all_boxes, find_box, AttributeDefinitionResponse, AttributeSchemaResponse, BoxContents,
+    BoxSummary, CreatePlainSampleRequest, DashboardStats, ItemLocation, ItemMoved, LaneOverview,
+    LaneYield, MoveItemRequest, PoolOverview, ProjectLockResponse, ProjectResponse,
+    RunMetricsResponse, RunOverviewResponse, SampleResponse, SampleSummary, SearchHit,
+    SearchHitKind, SearchResponse, SignOffRunQcRequest, StorageNode, UpdateSampleRequest,
+    UNASSIGNED,
+};
+
+/// Dashboard statistics endpoint.
+const DASHBOARD_STATS: &str = "/api/v1/dashboard/stats";
+
+/// Server-sent event stream of dashboard statistics.
+pub const DASHBOARD_STREAM: &str = "/api/v1/dashboard/stream";
+
+/// Box storage endpoints.
+const STORAGE: &str = "/api/v1/storage";
+
+/// Project endpoints.
+const PROJECTS: &str = "/api/v1/projects";
+
+/// Sample endpoints.
+const SAMPLES: &str = "/api/v1/samples";
+
+/// Sequencing run endpoints.
+const RUNS: &str = "/api/v1/runs";
+
+/// Project and sample search endpoint.
+const SEARCH: &str = "/api/v1/search";
+
+/// Custom attribute endpoints.
+const ATTRIBUTES: &str = "/api/v1/attributes";
+
+/// Local storage key holding the bearer token sent with requests.
+const TOKEN_KEY: &str = "miso_token";
+
+/// Fetches the current dashboard statistics.
+pub async fn fetch_dashboard_stats() -> Result<DashboardStats, String> {
+    get_json(DASHBOARD_STATS).await
+}
+
+/// Fetches a project.
+pub async fn fetch_project(id: i32) -> Result<ProjectResponse, String> {
+    get_json(&format!("{}/{}", PROJECTS, id)).await
+}
+
+/// Fetches the samples in a project.
+pub async fn fetch_project_samples(project_id: i32) -> Result<Vec<SampleSummary>, String> {
+    get_json(&format!("{}/project/{}", SAMPLES, project_id)).await
+}
+
+/// Creates a plain sample.
+pub async fn create_sample(request: &CreatePlainSampleRequest) -> Result<SampleResponse, String> {
+    let request = authorized(Request::post(SAMPLES))
+        .json(request)
+        .map_err(|e| e.to_string())?;
+    read_json(request.send().await.map_err(|e| e.to_string())?).await
+}
+
+/// Fetches the custom attributes of forms for `target` ("sample" or
+/// "library") in a project.
+pub async fn fetch_attribute_schema(
+    target: &str,
+    project_id: i32,
+) -> Result<AttributeSchemaResponse, String> {
+    get_json(&format!(
+        "{}/schema/{}?project_id={}",
+        ATTRIBUTES, target, project_id
+    ))
+    .await
+}
+
+/// Updates a sample.
+pub async fn update_sample(
+    id: i32,
+    request: &UpdateSampleRequest,
+) -> Result<SampleResponse, String> {
+    let request = authorized(Request::put(&format!("{}/{}", SAMPLES, id)))
+        .json(request)
+        .map_err(|e| e.to_string())?;
+    read_json(request.send().await.map_err(|e| e.to_string())?).await
+}
+
+/// Prints a sample's barcode label.
+pub async fn print_sample_label(id: i32) -> Result<(), String> {
+    let response = authorized(Request::post(&format!("{}/{}/label", SAMPLES, id)))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.ok() {
+        Ok(())
+    } else {
+        read_json::<()>(response).await
+    }
+}
+
+/// Fetches a run's overview.
+pub async fn fetch_run(id: i32) -> Result<RunOverviewResponse, String> {
+    get_json(&format!("{}/{}/overview", RUNS, id)).await
+}
+
+/// Fetches a run's demultiplexing metrics.
+pub async fn fetch_run_metrics(id: i32) -> Result<RunMetricsResponse, String> {
+    get_json(&format!("{}/{}/metrics", RUNS, id)).await
+}
+
+/// Records a QC decision on a run.
+pub async fn sign_off_run(
+    id: i32,
+    request: &SignOffRunQcRequest,
+) -> Result<RunOverviewResponse, String> {
+    let request = authorized(Request::put(&format!("{}/{}/qc", RUNS, id)))
+        .json(request)
+        .map_err(|e| e.to_string())?;
+    read_json(request.send().await.map_err(|e| e.to_string())?).await
+}
+
+/// Finds projects and samples matching `query`.
+pub async fn search(query: &str) -> Result<SearchResponse, String> {
+    get_json(&format!("{}?q={}", SEARCH, encode(query))).await
+}
+
+/// Fetches the Freezer → Shelf → Rack tree with its boxes.
+pub async fn fetch_freezers() -> Result<Vec<StorageNode>, String> {
+    get_json(&format!("{}/freezers", STORAGE)).await
+}
+
+/// Fetches a box and its contents.
+pub async fn fetch_box(id: i32) -> Result<BoxContents, String> {
+    get_json(&format!("{}/boxes/{}", STORAGE, id)).await
+}
+
+/// Finds where the item with `barcode` is stored.
+pub async fn locate(barcode: &str) -> Result<ItemLocation, String> {
+    get_json(&format!("{}/locate?barcode={}", STORAGE, encode(barcode))).await
+}
+
+/// Moves an item to another position.
+pub async fn move_item(request: &MoveItemRequest) -> Result<ItemMoved, String> {
+    let request = authorized(Request::post(&format!("{}/moves", STORAGE)))
+        .json(request)
+        .map_err(|e| e.to_string())?;
+    read_json(request.send().await.map_err(|e| e.to_string())?).await
+}
+
+async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, String> {
+    let response = authorized(Request::get(url))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_json(response).await
+}
+
+/// Adds the stored bearer token, if any, to a request.
+fn authorized(request: RequestBuilder) -> RequestBuilder {
+    let token = web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|s| s.get_item(TOKEN_KEY).ok().flatten());
+    match token {
+        Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+/// Reads a JSON body, or the server's error message for a failed request.
+async fn read_json<T: DeserializeOwned>(response: Response) -> Result<T, String> {
+    if response.ok() {
+        return response.json().await.map_err(|e| e.to_string());
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        message: String,
+    }
+    match response.json::<ErrorBody>().await {
+        Ok(body) => Err(body.message),
+        Err(_) => Err(format!("Request failed: {}", response.status_text())),
+    }
+}
+
+/// Returns the display name of a platform code.
+pub fn platform_label(code: &str) -> String {
+    match code {
+        "illumina" => "Illumina".to_string(),
+        "oxford_nanopore" => "Oxford Nanopore".to_string(),
+        "pac_bio" => "PacBio".to_string(),
+        "ion_torrent" => "Ion Torrent".to_string(),
+        "element" => "Element".to_string(),
+        "mgi" => "MGI".to_string(),
+        "ultima" => "Ultima".to_string(),
+        "other" => "Other".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Links to pages, including the list views the dashboard drills into.
+pub mod links {
+    use super::{encode, SearchHit, SearchHitKind};
+
+    /// Samples received on `date`.
+    pub fn samples_received_on(date: &str) -> String {
+        format!("/samples?received_on={}", encode(date))
+    }
+
+    /// Samples with the given QC status code.
+    pub fn samples_with_qc_status(status: &str) -> String {
+        format!("/samples?qc_status={}", encode(status))
+    }
+
+    /// Runs on the given platform code.
+    pub fn runs_on_platform(platform: &str) -> String {
+        format!("/runs?platform={}", encode(platform))
+    }
+
+    /// A run's detail page.
+    pub fn run(id: i32) -> String {
+        format!("/runs/{}", id)
+    }
+
+    /// Boxes stored in the named freezer.
+    pub fn boxes_in_freezer(freezer: &str) -> String {
+        format!("/boxes?freezer={}", encode(freezer))
+    }
+
+    /// The box holding the item with `barcode`.
+    pub fn locate(barcode: &str) -> String {
+        format!("/boxes?barcode={}", encode(barcode))
+    }
+
+    /// A project's bench worksheet.
+    pub fn worksheet(project_id: i32) -> String {
+        format!("/worksheet?project={}", project_id)
+    }
+
+    /// Page a search hit opens: a project's worksheet, or where a sample
+    /// is stored.
+    pub fn search_hit(hit: &SearchHit) -> String {
+        match (&hit.kind, &hit.barcode) {
+            (SearchHitKind::Sample, Some(barcode)) => locate(barcode),
+            _ => worksheet(hit.project_id),
+        }
+    }
+}
+
+/// Percent-encodes a query parameter value.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_encode_values() {
+        assert_eq!(
+            links::boxes_in_freezer("Freezer 2/B"),
+            "/boxes?freezer=Freezer%202%2FB"
+        );
+        assert_eq!(
+            links::samples_received_on("2024-03-09"),
+            "/samples?received_on=2024-03-09"
+        );
+    }
+}
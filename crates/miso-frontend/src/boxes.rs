@@ -0,0 +1,440 @@
+//! Box browser page.
+//!
+//! Freezers open into shelves, racks and boxes, each with its fill level.
+//! Selecting a box lays out its positions. Searching for a barcode, or
+//! arriving with `?barcode=`, opens the box holding that item and
+//! highlights its position. Clicking an occupied position starts a move
+//! to any free position, in the same box or another.
+
+use std::collections::HashMap;
+
+use leptos::ev::SubmitEvent;
+use leptos::html::Input;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_query_map;
+
+use crate::api::{self, BoxContents, MoveItemRequest, StorageNode};
+use crate::charts::FillBar;
+use crate::page_data;
+
+/// Outcome of the last search or move, shown above the box.
+#[derive(Debug, Clone, PartialEq)]
+struct Notice {
+    text: String,
+    is_error: bool,
+}
+
+impl Notice {
+    fn info(text: String) -> Option<Self> {
+        Some(Self {
+            text,
+            is_error: false,
+        })
+    }
+
+    fn error(text: String) -> Option<Self> {
+        Some(Self {
+            text,
+            is_error: true,
+        })
+    }
+}
+
+/// A position picked as the item to move.
+#[derive(Debug, Clone, PartialEq)]
+struct MoveSource {
+    box_id: i32,
+    position: String,
+    item: String,
+}
+
+/// Returns the letter of a 0-based row index.
+pub fn row_label(row: u8) -> char {
+    (b'A' + row) as char
+}
+
+/// Freezer → Shelf → Rack → Box browser.
+#[component]
+pub fn BoxBrowser() -> impl IntoView {
+    let query = use_query_map();
+
+    // Bumped after a move so the tree and box are fetched again.
+    let (version, set_version) = signal(0u32);
+    let (selected, set_selected) = signal(None::<i32>);
+    let (highlight, set_highlight) = signal(None::<String>);
+    let (notice, set_notice) = signal(None::<Notice>);
+    let (barcode, set_barcode) = signal(String::new());
+    let (move_from, set_move_from) = signal(None::<MoveSource>);
+    let (move_to_box, set_move_to_box) = signal(None::<i32>);
+    let (move_to_position, set_move_to_position) = signal(String::new());
+    let search_input = NodeRef::<Input>::new();
+
+    let freezers = page_data::freezers(version);
+    let contents = LocalResource::new(move || {
+        version.track();
+        let id = selected.get();
+        async move {
+            match id {
+                Some(id) => Some(api::fetch_box(id).await),
+                None => None,
+            }
+        }
+    });
+
+    let select_box = move |id: i32| {
+        set_selected.set(Some(id));
+        set_highlight.set(None);
+    };
+
+    let locate = move |code: String| {
+        spawn_local(async move {
+            match api::locate(&code).await {
+                Ok(found) => {
+                    set_notice.set(Notice::info(format!(
+                        "{} ({}) is in {} at {}",
+                        found.name, found.barcode, found.storage_box.name, found.position
+                    )));
+                    set_selected.set(Some(found.storage_box.id));
+                    set_highlight.set(Some(found.position));
+                }
+                Err(e) => set_notice.set(Notice::error(e)),
+            }
+        });
+    };
+
+    let search = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let code = barcode.get_untracked().trim().to_string();
+        if !code.is_empty() {
+            locate(code);
+        }
+    };
+
+    // Find the item named in the URL, e.g. a sample opened from the
+    // command palette, and otherwise ready the search for a scanner.
+    Effect::new(move || match query.read().get("barcode") {
+        Some(code) if !code.trim().is_empty() => {
+            set_barcode.set(code.trim().to_string());
+            locate(code.trim().to_string());
+        }
+        _ => {
+            if let Some(input) = search_input.get() {
+                let _ = input.focus();
+            }
+        }
+    });
+
+    let pick_position = move |box_id: i32, position: String, item: Option<String>| match (
+        item,
+        move_from.get_untracked(),
+    ) {
+        (Some(item), _) => {
+            set_move_from.set(Some(MoveSource {
+                box_id,
+                position,
+                item,
+            }));
+            set_move_to_box.set(Some(box_id));
+            set_move_to_position.set(String::new());
+        }
+        (None, Some(_)) => {
+            set_move_to_box.set(Some(box_id));
+            set_move_to_position.set(position);
+        }
+        (None, None) => {}
+    };
+
+    let submit_move = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let (Some(from), Some(to_box_id)) =
+            (move_from.get_untracked(), move_to_box.get_untracked())
+        else {
+            return;
+        };
+        let request = MoveItemRequest {
+            from_box_id: from.box_id,
+            from_position: from.position,
+            to_box_id,
+            to_position: move_to_position.get_untracked().trim().to_uppercase(),
+        };
+        spawn_local(async move {
+            match api::move_item(&request).await {
+                Ok(moved) => {
+                    set_notice.set(Notice::info(format!(
+                        "Moved {} from {} to {}",
+                        from.item, moved.from_position, moved.to_position
+                    )));
+                    set_move_from.set(None);
+                    set_selected.set(Some(moved.to_box_id));
+                    set_highlight.set(Some(moved.to_position));
+                    set_version.update(|v| *v += 1);
+                }
+                Err(e) => set_notice.set(Notice::error(e)),
+            }
+        });
+    };
+
+    view! {
+        <main class="box-browser">
+            <h1>"Boxes"</h1>
+            <form class="item-search" on:submit=search>
+                <input
+                    type="search"
+                    node_ref=search_input
+                    placeholder="Item barcode"
+                    prop:value=barcode
+                    on:input=move |ev| set_barcode.set(event_target_value(&ev))
+                />
+                <button type="submit">"Find"</button>
+            </form>
+            {move || {
+                notice
+                    .get()
+                    .map(|n| {
+                        view! {
+                            <p class=if n.is_error { "notice error" } else { "notice" }>{n.text}</p>
+                        }
+                    })
+            }}
+            <nav class="storage-tree">
+                <Suspense fallback=|| view! { <p class="loading">"Loading freezers…"</p> }>
+                    {move || Suspend::new(async move {
+                        match freezers.await {
+                            Ok(tree) => {
+                                let open = open_path(&tree, selected.get(), query.read().get("freezer"));
+                                let nodes = tree
+                                    .into_iter()
+                                    .map(|node| tree_node(node, &open, 0, selected, select_box))
+                                    .collect_view();
+                                view! { <div>{nodes}</div> }.into_any()
+                            }
+                            Err(e) => view! { <p class="notice error">{e}</p> }.into_any(),
+                        }
+                    })}
+                </Suspense>
+            </nav>
+            <section class="box-detail">
+                <Suspense fallback=|| view! { <p class="loading">"Loading box…"</p> }>
+                    {move || Suspend::new(async move {
+                        match contents.await {
+                            Some(Ok(contents)) => {
+                                box_grid(contents, highlight.get(), move_from.get(), pick_position)
+                            }
+                            Some(Err(e)) => view! { <p class="notice error">{e}</p> }.into_any(),
+                            None => {
+                                view! { <p class="empty">"Select a box or search for an item."</p> }
+                                    .into_any()
+                            }
+                        }
+                    })}
+                </Suspense>
+                {move || {
+                    move_from
+                        .get()
+                        .map(|from| {
+                            let boxes: Vec<(i32, String)> = freezers
+                                .get()
+                                .and_then(|tree| tree.ok())
+                                .map(|tree| {
+                                    api::all_boxes(&tree)
+                                        .into_iter()
+                                        .map(|b| (b.id, b.name.clone()))
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            view! {
+                                <form class="move-form" on:submit=submit_move>
+                                    <span>{format!("Move {} from {} to", from.item, from.position)}</span>
+                                    <select on:change=move |ev| {
+                                        set_move_to_box.set(event_target_value(&ev).parse().ok())
+                                    }>
+                                        {boxes
+                                            .into_iter()
+                                            .map(|(id, name)| {
+                                                let is_target = move_to_box.get_untracked() == Some(id);
+                                                view! {
+                                                    <option value=id.to_string() selected=is_target>
+                                                        {name}
+                                                    </option>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </select>
+                                    <input
+                                        type="text"
+                                        size="4"
+                                        placeholder="A1"
+                                        prop:value=move_to_position
+                                        on:input=move |ev| set_move_to_position.set(event_target_value(&ev))
+                                    />
+                                    <button type="submit">"Move"</button>
+                                    <button type="button" on:click=move |_| set_move_from.set(None)>
+                                        "Cancel"
+                                    </button>
+                                </form>
+                            }
+                        })
+                }}
+            </section>
+        </main>
+    }
+}
+
+/// Names of the freezer, shelf and rack to show open: those holding the
+/// selected box, or else the freezer named in the URL.
+fn open_path(tree: &[StorageNode], selected: Option<i32>, freezer: Option<String>) -> Vec<String> {
+    match selected.and_then(|id| api::find_box(tree, id)) {
+        Some(storage_box) => storage_box.tree_path().to_vec(),
+        None => freezer.into_iter().collect(),
+    }
+}
+
+/// Renders a freezer, shelf or rack and everything under it.
+fn tree_node(
+    node: StorageNode,
+    open: &[String],
+    depth: usize,
+    selected: ReadSignal<Option<i32>>,
+    select_box: impl Fn(i32) + Copy + 'static,
+) -> AnyView {
+    let is_open = open.get(depth) == Some(&node.name);
+    let on_path = is_open && open.len() > depth + 1;
+    let children = node
+        .children
+        .into_iter()
+        .map(|child| {
+            let open: &[String] = if on_path { open } else { &[] };
+            tree_node(child, open, depth + 1, selected, select_box)
+        })
+        .collect_view();
+    let boxes = node
+        .boxes
+        .into_iter()
+        .map(|b| {
+            let id = b.id;
+            view! {
+                <li>
+                    <button
+                        class=move || if selected.get() == Some(id) { "box selected" } else { "box" }
+                        on:click=move |_| select_box(id)
+                    >
+                        <span class="node-name">{b.name}</span>
+                        <FillBar percent=b.fill_percent occupied=b.occupied capacity=b.capacity />
+                    </button>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <details class=format!("storage-node {}", node.level) open=is_open>
+            <summary>
+                <span class="node-name">{node.name}</span>
+                <FillBar percent=node.fill_percent occupied=node.occupied capacity=node.capacity />
+            </summary>
+            {children}
+            <ul class="boxes">{boxes}</ul>
+        </details>
+    }
+    .into_any()
+}
+
+/// Lays out a box's positions, highlighting the searched-for item and the
+/// item being moved.
+fn box_grid(
+    contents: BoxContents,
+    highlight: Option<String>,
+    move_from: Option<MoveSource>,
+    pick: impl Fn(i32, String, Option<String>) + Copy + 'static,
+) -> AnyView {
+    let summary = contents.storage_box;
+    let box_id = summary.id;
+    let items: HashMap<String, String> = contents
+        .items
+        .into_iter()
+        .map(|i| (i.position, format!("{} {}", i.item_type, i.item_id)))
+        .collect();
+    let moving = move_from.filter(|m| m.box_id == box_id).map(|m| m.position);
+
+    let header = (1..=summary.cols)
+        .map(|col| view! { <th>{col}</th> })
+        .collect_view();
+    let rows = (0..summary.rows)
+        .map(|row| {
+            let label = row_label(row);
+            let cells = (1..=summary.cols)
+                .map(|col| {
+                    let position = format!("{}{}", label, col);
+                    let item = items.get(&position).cloned();
+                    let mut class = String::from(if item.is_some() { "occupied" } else { "empty" });
+                    if highlight.as_ref() == Some(&position) {
+                        class.push_str(" highlight");
+                    }
+                    if moving.as_ref() == Some(&position) {
+                        class.push_str(" move-source");
+                    }
+                    let title = match &item {
+                        Some(item) => format!("{}: {}", position, item),
+                        None => position.clone(),
+                    };
+                    view! {
+                        <td
+                            class=class
+                            title=title
+                            on:click=move |_| pick(box_id, position.clone(), item.clone())
+                        ></td>
+                    }
+                })
+                .collect_view();
+            view! {
+                <tr>
+                    <th>{label.to_string()}</th>
+                    {cells}
+                </tr>
+            }
+        })
+        .collect_view();
+
+    let meta = format!(
+        "{} · {}×{} {} box · {} of {} positions used",
+        summary.tree_path().join(" / "),
+        summary.rows,
+        summary.cols,
+        summary.storable_type,
+        summary.occupied,
+        summary.capacity,
+    );
+
+    view! {
+        <h2>{summary.name}</h2>
+        <p class="box-meta">{meta}</p>
+        <table class="box-grid">
+            <thead>
+                <tr>
+                    <th></th>
+                    {header}
+                </tr>
+            </thead>
+            <tbody>{rows}</tbody>
+        </table>
+    }
+    .into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_labels() {
+        assert_eq!(row_label(0), 'A');
+        assert_eq!(row_label(7), 'H');
+    }
+
+    #[test]
+    fn test_open_path_falls_back_to_freezer() {
+        assert_eq!(open_path(&[], Some(3), Some("F2".to_string())), vec!["F2"]);
+        assert!(open_path(&[], None, None).is_empty());
+    }
+}
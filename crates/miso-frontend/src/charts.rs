@@ -0,0 +1,189 @@
+//! Chart components.
+//!
+//! Bar charts are laid out with CSS so that each bar can be an ordinary
+//! link; gauges are drawn as SVG arcs.
+
+use std::f64::consts::PI;
+
+use leptos::prelude::*;
+
+/// One bar of a [`BarChart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    pub label: String,
+    pub value: u64,
+    /// List view the bar links to
+    pub href: String,
+    /// Extra CSS class, e.g. to colour a QC status
+    pub class: &'static str,
+}
+
+/// Height of a bar as a percentage of the tallest.
+pub fn bar_percent(value: u64, max: u64) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        value as f64 * 100.0 / max as f64
+    }
+}
+
+/// Vertical bar chart whose bars link to filtered list views.
+#[component]
+pub fn BarChart(bars: Vec<Bar>, #[prop(optional)] show_labels: bool) -> impl IntoView {
+    let max = bars.iter().map(|b| b.value).max().unwrap_or(0);
+
+    view! {
+        <div class="bar-chart">
+            {bars
+                .into_iter()
+                .map(|bar| {
+                    let title = format!("{}: {}", bar.label, bar.value);
+                    let height = format!("{:.1}%", bar_percent(bar.value, max));
+                    view! {
+                        <a class=format!("bar {}", bar.class) href=bar.href title=title>
+                            <span class="bar-value">{bar.value}</span>
+                            <span class="bar-fill" style:height=height></span>
+                            {show_labels.then(|| view! { <span class="bar-label">{bar.label}</span> })}
+                        </a>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+/// Height of a bar as a percentage of `max`, for fractional values.
+pub fn scale_percent(value: f64, max: f64) -> f64 {
+    if max <= 0.0 {
+        0.0
+    } else {
+        (value * 100.0 / max).clamp(0.0, 100.0)
+    }
+}
+
+/// Per-lane bar chart of one metric. Lanes without a value are left
+/// empty; `max` fixes the scale, which otherwise fits the tallest bar.
+#[component]
+pub fn LaneChart(
+    lanes: Vec<(u8, Option<f64>)>,
+    format: fn(f64) -> String,
+    #[prop(optional)] max: Option<f64>,
+) -> impl IntoView {
+    let max = max.unwrap_or_else(|| {
+        lanes
+            .iter()
+            .filter_map(|(_, v)| *v)
+            .fold(0.0, f64::max)
+    });
+
+    view! {
+        <div class="bar-chart lanes">
+            {lanes
+                .into_iter()
+                .map(|(lane, value)| {
+                    let label = format!("Lane {}", lane);
+                    let text = value.map(format).unwrap_or_else(|| "–".to_string());
+                    let height = format!("{:.1}%", scale_percent(value.unwrap_or(0.0), max));
+                    let title = format!("{}: {}", label, text);
+                    view! {
+                        <div class="bar" title=title>
+                            <span class="bar-value">{text}</span>
+                            <span class="bar-fill" style:height=height></span>
+                            <span class="bar-label">{label}</span>
+                        </div>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+/// Centre and radius of the gauge arc within its 120×70 view box.
+const GAUGE_CX: f64 = 60.0;
+const GAUGE_CY: f64 = 60.0;
+const GAUGE_R: f64 = 50.0;
+
+/// SVG path of a semicircular gauge arc filled to `percent`, sweeping
+/// left to right.
+pub fn gauge_arc(percent: f64) -> String {
+    let angle = PI * (1.0 - percent.clamp(0.0, 100.0) / 100.0);
+    let x = GAUGE_CX + GAUGE_R * angle.cos();
+    let y = GAUGE_CY - GAUGE_R * angle.sin();
+    format!(
+        "M {:.2} {:.2} A {} {} 0 0 1 {:.2} {:.2}",
+        GAUGE_CX - GAUGE_R,
+        GAUGE_CY,
+        GAUGE_R,
+        GAUGE_R,
+        x,
+        y
+    )
+}
+
+/// CSS class for a fill level: "critical" from 90%, "warning" from 75%.
+pub fn fill_level(percent: f64) -> &'static str {
+    if percent >= 90.0 {
+        "critical"
+    } else if percent >= 75.0 {
+        "warning"
+    } else {
+        "ok"
+    }
+}
+
+/// Semicircular fill gauge linking to a list view.
+#[component]
+pub fn Gauge(label: String, percent: f64, detail: String, href: String) -> impl IntoView {
+    let percent = percent.clamp(0.0, 100.0);
+
+    view! {
+        <a class=format!("gauge {}", fill_level(percent)) href=href>
+            <svg viewBox="0 0 120 70" width="120" height="70">
+                <path class="gauge-track" d=gauge_arc(100.0) fill="none" stroke-width="10" />
+                <path class="gauge-fill" d=gauge_arc(percent) fill="none" stroke-width="10" />
+                <text x="60" y="58" text-anchor="middle">{format!("{:.0}%", percent)}</text>
+            </svg>
+            <span class="gauge-label">{label}</span>
+            <span class="gauge-detail">{detail}</span>
+        </a>
+    }
+}
+
+/// Horizontal fill bar with an occupied/capacity caption.
+#[component]
+pub fn FillBar(percent: f64, occupied: usize, capacity: usize) -> impl IntoView {
+    let percent = percent.clamp(0.0, 100.0);
+
+    view! {
+        <span class=format!("fill-bar {}", fill_level(percent))>
+            <span class="fill-bar-level" style:width=format!("{:.1}%", percent)></span>
+        </span>
+        <span class="fill-text">{format!("{}/{}", occupied, capacity)}</span>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_percent() {
+        assert_eq!(bar_percent(5, 20), 25.0);
+        assert_eq!(bar_percent(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_scale_percent() {
+        assert_eq!(scale_percent(45.0, 90.0), 50.0);
+        assert_eq!(scale_percent(120.0, 100.0), 100.0);
+        assert_eq!(scale_percent(3.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_gauge_arc_endpoints() {
+        assert_eq!(gauge_arc(0.0), "M 10.00 60.00 A 50 50 0 0 1 10.00 60.00");
+        assert_eq!(gauge_arc(50.0), "M 10.00 60.00 A 50 50 0 0 1 60.00 10.00");
+        assert_eq!(gauge_arc(100.0), "M 10.00 60.00 A 50 50 0 0 1 110.00 60.00");
+        assert_eq!(gauge_arc(150.0), gauge_arc(100.0));
+    }
+}
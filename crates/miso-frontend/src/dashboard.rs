@@ -0,0 +1,240 @@
+//! Dashboard page.
+//!
+//! The page opens with the statistics as of loading it, rendered in by the
+//! server where it renders pages. Updates then arrive over the server's
+//! event stream: one on connection and then at the server's refresh
+//! interval. The browser reconnects on its own if the stream drops.
+
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{EventSource, MessageEvent};
+
+use crate::api::{self, links, DashboardStats};
+use crate::charts::{Bar, BarChart, Gauge};
+use crate::page_data;
+
+/// Live lab overview.
+#[component]
+pub fn Dashboard() -> impl IntoView {
+    let initial = page_data::dashboard();
+    let (stats, set_stats) = signal(None::<DashboardStats>);
+    let (error, set_error) = signal(None::<String>);
+    // Effects only run in the browser, so rendering doesn't open a stream.
+    Effect::new(move |_| subscribe(set_stats, set_error));
+
+    view! {
+        <main class="dashboard">
+            <h1>"Dashboard"</h1>
+            {move || error.get().map(|e| view! { <p class="dashboard-error">{e}</p> })}
+            <Transition fallback=|| view! { <p class="loading">"Loading statistics…"</p> }>
+                {move || match (stats.get(), initial.get()) {
+                    (Some(stats), _) | (None, Some(Ok(stats))) => {
+                        view! { <Panels stats=stats /> }.into_any()
+                    }
+                    (None, Some(Err(e))) => view! { <p class="dashboard-error">{e}</p> }.into_any(),
+                    (None, None) => view! { <p class="loading">"Loading statistics…"</p> }.into_any(),
+                }}
+            </Transition>
+        </main>
+    }
+}
+
+/// Opens the statistics stream, closing it when the page is unmounted.
+fn subscribe(
+    set_stats: WriteSignal<Option<DashboardStats>>,
+    set_error: WriteSignal<Option<String>>,
+) {
+    let source = match EventSource::new(api::DASHBOARD_STREAM) {
+        Ok(source) => source,
+        Err(_) => {
+            set_error.set(Some("Live updates are not available".to_string()));
+            return;
+        }
+    };
+
+    let on_stats = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let Some(data) = event.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<DashboardStats>(&data) {
+            Ok(stats) => {
+                set_stats.set(Some(stats));
+                set_error.set(None);
+            }
+            Err(e) => set_error.set(Some(format!("Unreadable statistics: {}", e))),
+        }
+    });
+
+    // Fired both for `error` events sent by the server, which carry a
+    // message, and for dropped connections, which don't.
+    let on_error = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+        let message = event
+            .dyn_ref::<MessageEvent>()
+            .and_then(|e| e.data().as_string())
+            .unwrap_or_else(|| "Connection lost; reconnecting…".to_string());
+        set_error.set(Some(message));
+    });
+
+    let _ = source.add_event_listener_with_callback("stats", on_stats.as_ref().unchecked_ref());
+    let _ = source.add_event_listener_with_callback("error", on_error.as_ref().unchecked_ref());
+
+    // The closures must outlive the listeners, so they are dropped only
+    // once the stream is closed.
+    let subscription = SendWrapper::new((source, on_stats, on_error));
+    on_cleanup(move || subscription.0.close());
+}
+
+/// The dashboard's panels for one set of statistics.
+#[component]
+fn Panels(stats: DashboardStats) -> impl IntoView {
+    let intake_total: u64 = stats.sample_intake.iter().map(|d| d.samples).sum();
+    let intake: Vec<Bar> = stats
+        .sample_intake
+        .iter()
+        .map(|day| Bar {
+            label: day.date.to_string(),
+            value: day.samples,
+            href: links::samples_received_on(&day.date.to_string()),
+            class: "",
+        })
+        .collect();
+
+    let qc: Vec<Bar> = stats
+        .qc
+        .statuses()
+        .into_iter()
+        .map(|(code, label, count)| Bar {
+            label: label.to_string(),
+            value: count,
+            href: links::samples_with_qc_status(code),
+            class: code,
+        })
+        .collect();
+    let pass_rate = match stats.qc.pass_rate {
+        Some(rate) => format!("{:.1}%", rate),
+        None => "–".to_string(),
+    };
+
+    let runs = stats.runs_per_platform.map(|platforms| {
+        platforms
+            .into_iter()
+            .map(|p| Bar {
+                label: format!(
+                    "{} ({} running)",
+                    api::platform_label(&p.platform),
+                    p.running
+                ),
+                value: p.runs,
+                href: links::runs_on_platform(&p.platform),
+                class: "",
+            })
+            .collect::<Vec<_>>()
+    });
+
+    view! {
+        <section class="panel intake">
+            <h2>{format!("Sample intake, last {} days", stats.days)}</h2>
+            <p class="headline">{format!("{} samples", intake_total)}</p>
+            <BarChart bars=intake />
+        </section>
+        <section class="panel qc">
+            <h2>"QC"</h2>
+            <p class="headline">{pass_rate}" pass rate"</p>
+            <BarChart bars=qc show_labels=true />
+        </section>
+        <section class="panel runs">
+            <h2>{format!("Runs per platform, last {} days", stats.days)}</h2>
+            {match runs {
+                Some(bars) if !bars.is_empty() => {
+                    view! { <BarChart bars=bars show_labels=true /> }.into_any()
+                }
+                Some(_) => view! { <p class="empty">"No runs"</p> }.into_any(),
+                None => view! { <p class="empty">"Not available"</p> }.into_any(),
+            }}
+        </section>
+        <section class="panel active-runs">
+            <h2>"Runs in progress"</h2>
+            {match stats.active_runs {
+                Some(runs) if !runs.is_empty() => {
+                    view! {
+                        <table>
+                            <thead>
+                                <tr>
+                                    <th>"Run"</th>
+                                    <th>"Platform"</th>
+                                    <th>"Progress"</th>
+                                    <th>"Expected to finish"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {runs
+                                    .into_iter()
+                                    .map(|run| {
+                                        let progress = match run.percent_complete() {
+                                            Some(percent) => format!("{:.0}%", percent),
+                                            None => "–".to_string(),
+                                        };
+                                        let eta = match run.estimated_completion {
+                                            Some(t) => t.format("%Y-%m-%d %H:%M UTC").to_string(),
+                                            None => "Unknown".to_string(),
+                                        };
+                                        view! {
+                                            <tr class=run.status.clone()>
+                                                <td>
+                                                    <a href=links::run(run.id)>{run.name}</a>
+                                                </td>
+                                                <td>{api::platform_label(&run.platform)}</td>
+                                                <td>{progress}</td>
+                                                <td>{eta}</td>
+                                            </tr>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                        </table>
+                    }
+                        .into_any()
+                }
+                Some(_) => view! { <p class="empty">"No runs in progress"</p> }.into_any(),
+                None => view! { <p class="empty">"Not available"</p> }.into_any(),
+            }}
+        </section>
+        <section class="panel freezers">
+            <h2>"Freezer capacity"</h2>
+            {match stats.freezers {
+                Some(freezers) if !freezers.is_empty() => {
+                    view! {
+                        <div class="gauges">
+                            {freezers
+                                .into_iter()
+                                .map(|f| {
+                                    let detail = format!(
+                                        "{} of {} positions in {} boxes",
+                                        f.occupied,
+                                        f.capacity,
+                                        f.boxes,
+                                    );
+                                    let href = links::boxes_in_freezer(&f.freezer);
+                                    view! {
+                                        <Gauge
+                                            label=f.freezer
+                                            percent=f.fill_percent
+                                            detail=detail
+                                            href=href
+                                        />
+                                    }
+                                })
+                                .collect_view()}
+                        </div>
+                    }
+                        .into_any()
+                }
+                Some(_) => view! { <p class="empty">"No boxes"</p> }.into_any(),
+                None => view! { <p class="empty">"Not available"</p> }.into_any(),
+            }}
+        </section>
+        <p class="updated">"Updated "{stats.generated_at.format("%Y-%m-%d %H:%M UTC").to_string()}</p>
+    }
+}
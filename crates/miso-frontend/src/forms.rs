@@ -0,0 +1,298 @@
+//! Forms generated from custom attribute schemas.
+//!
+//! Admins define extra sample and library fields on the server, so forms
+//! render a control per field of the schema endpoint rather than hard
+//! coding them. Values are checked here with the same rules the server
+//! applies, so mistakes show against the field before anything is sent;
+//! the server still has the final say.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use leptos::prelude::*;
+
+use crate::api::AttributeDefinitionResponse;
+
+/// Attribute values or problems, keyed by attribute key.
+pub type FieldMap = BTreeMap<String, String>;
+
+/// Checks a non-blank value against a field's type and limits, returning
+/// what is wrong with it.
+pub fn check_value(field: &AttributeDefinitionResponse, value: &str) -> Result<(), String> {
+    let number = match field.value_type.as_str() {
+        "integer" => Some(
+            value
+                .parse::<i64>()
+                .map_err(|_| "must be a whole number".to_string())? as f64,
+        ),
+        "decimal" => Some(
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| "must be a number".to_string())?,
+        ),
+        "boolean" if value != "true" && value != "false" => {
+            return Err("must be true or false".to_string())
+        }
+        "date" => {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| "must be a date (YYYY-MM-DD)".to_string())?;
+            None
+        }
+        "choice" if !field.options.iter().any(|o| o == value) => {
+            return Err(format!("must be one of {}", field.options.join(", ")))
+        }
+        "text" => {
+            if let Some(max) = field.max_length {
+                if value.chars().count() > max as usize {
+                    return Err(format!("must be at most {} characters", max));
+                }
+            }
+            None
+        }
+        // Left to the server: a type added there shouldn't block the form
+        _ => None,
+    };
+
+    if let Some(number) = number {
+        if let Some(min) = field.min.filter(|min| number < *min) {
+            return Err(format!("must be at least {}", min));
+        }
+        if let Some(max) = field.max.filter(|max| number > *max) {
+            return Err(format!("must be at most {}", max));
+        }
+    }
+    Ok(())
+}
+
+/// Checks one field's value, returning the message to show by it.
+pub fn check_field(field: &AttributeDefinitionResponse, value: Option<&str>) -> Option<String> {
+    match value.map(str::trim) {
+        None | Some("") if field.required => Some(format!("{} is required", field.label)),
+        None | Some("") => None,
+        Some(value) => check_value(field, value)
+            .err()
+            .map(|problem| format!("{} {}", field.label, problem)),
+    }
+}
+
+/// The values to submit for a form: trimmed, without blanks, and with
+/// unticked checkboxes as "false".
+pub fn form_values(fields: &[AttributeDefinitionResponse], values: &FieldMap) -> FieldMap {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let value = match values.get(&field.key).map(|v| v.trim()) {
+                None | Some("") if field.value_type == "boolean" => "false",
+                None | Some("") => return None,
+                Some(value) => value,
+            };
+            Some((field.key.clone(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Checks every field of a form, returning the problems by key.
+pub fn check_form(fields: &[AttributeDefinitionResponse], values: &FieldMap) -> FieldMap {
+    fields
+        .iter()
+        .filter_map(|field| {
+            check_field(field, values.get(&field.key).map(String::as_str))
+                .map(|problem| (field.key.clone(), problem))
+        })
+        .collect()
+}
+
+/// Controls for a schema's fields, in order.
+///
+/// Each field is rechecked as it is edited; `errors` also shows the
+/// problems found when the form is submitted.
+#[component]
+pub fn AttributeFields(
+    fields: Vec<AttributeDefinitionResponse>,
+    values: RwSignal<FieldMap>,
+    errors: RwSignal<FieldMap>,
+) -> impl IntoView {
+    fields
+        .into_iter()
+        .map(|field| {
+            let key = field.key.clone();
+            let label = if field.required {
+                format!("{} *", field.label)
+            } else {
+                field.label.clone()
+            };
+            let help = field
+                .help_text
+                .clone()
+                .map(|text| view! { <small class="field-help">{text}</small> });
+            let error = {
+                let key = key.clone();
+                move || {
+                    errors
+                        .with(|e| e.get(&key).cloned())
+                        .map(|text| view! { <small class="field-error">{text}</small> })
+                }
+            };
+            view! {
+                <label class="attribute-field">
+                    <span>{label}</span>
+                    {control(field, values, errors)}
+                    {help}
+                    {error}
+                </label>
+            }
+        })
+        .collect_view()
+}
+
+/// The input for one field, suited to its type.
+fn control(
+    field: AttributeDefinitionResponse,
+    values: RwSignal<FieldMap>,
+    errors: RwSignal<FieldMap>,
+) -> AnyView {
+    let value_type = field.value_type.clone();
+    let options = field.options.clone();
+    let (min, max, max_length) = (field.min, field.max, field.max_length);
+    let value = {
+        let key = field.key.clone();
+        move || values.with(|v| v.get(&key).cloned().unwrap_or_default())
+    };
+    let set = move |value: String| {
+        let problem = check_field(&field, Some(&value));
+        errors.update(|e| match problem {
+            Some(problem) => {
+                e.insert(field.key.clone(), problem);
+            }
+            None => {
+                e.remove(&field.key);
+            }
+        });
+        values.update(|v| {
+            v.insert(field.key.clone(), value);
+        });
+    };
+
+    match value_type.as_str() {
+        "boolean" => view! {
+            <input
+                type="checkbox"
+                prop:checked=move || value() == "true"
+                on:change=move |ev| set(event_target_checked(&ev).to_string())
+            />
+        }
+        .into_any(),
+        "choice" => {
+            let options = options
+                .iter()
+                .map(|option| view! { <option value=option.clone()>{option.clone()}</option> })
+                .collect_view();
+            view! {
+                <select prop:value=value on:change=move |ev| set(event_target_value(&ev))>
+                    <option value="">"—"</option>
+                    {options}
+                </select>
+            }
+            .into_any()
+        }
+        "integer" | "decimal" => {
+            let step = if value_type == "integer" { "1" } else { "any" };
+            view! {
+                <input
+                    type="number"
+                    step=step
+                    min=min.map(|m| m.to_string())
+                    max=max.map(|m| m.to_string())
+                    prop:value=value
+                    on:input=move |ev| set(event_target_value(&ev))
+                />
+            }
+            .into_any()
+        }
+        "date" => view! {
+            <input type="date" prop:value=value on:input=move |ev| set(event_target_value(&ev)) />
+        }
+        .into_any(),
+        _ => view! {
+            <input
+                type="text"
+                maxlength=max_length.map(|m| m.to_string())
+                prop:value=value
+                on:input=move |ev| set(event_target_value(&ev))
+            />
+        }
+        .into_any(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(key: &str, value_type: &str) -> AttributeDefinitionResponse {
+        AttributeDefinitionResponse {
+            id: 0,
+            target: "sample".to_string(),
+            project_id: None,
+            key: key.to_string(),
+            label: key.to_string(),
+            value_type: value_type.to_string(),
+            required: false,
+            options: Vec::new(),
+            min: None,
+            max: None,
+            max_length: None,
+            help_text: None,
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn test_values_are_checked_like_the_server() {
+        let mut age = field("age", "integer");
+        age.min = Some(0.0);
+        age.max = Some(120.0);
+        assert!(check_value(&age, "42").is_ok());
+        assert_eq!(
+            check_value(&age, "4.2").unwrap_err(),
+            "must be a whole number"
+        );
+        assert_eq!(check_value(&age, "130").unwrap_err(), "must be at most 120");
+
+        let mut tissue = field("tissue", "choice");
+        tissue.options = vec!["Blood".to_string(), "Saliva".to_string()];
+        assert!(check_value(&tissue, "Blood").is_ok());
+        assert_eq!(
+            check_value(&tissue, "Urine").unwrap_err(),
+            "must be one of Blood, Saliva"
+        );
+
+        assert!(check_value(&field("collected", "date"), "2023-02-29").is_err());
+        assert!(check_value(&field("barcode_scheme", "uuid"), "anything").is_ok());
+    }
+
+    #[test]
+    fn test_form_values_and_problems() {
+        let mut donor = field("donor_id", "text");
+        donor.required = true;
+        let fields = vec![donor, field("consent", "boolean"), field("note", "text")];
+        let values = FieldMap::from([
+            ("donor_id".to_string(), "  ".to_string()),
+            ("note".to_string(), " ok ".to_string()),
+        ]);
+
+        let submitted = form_values(&fields, &values);
+        assert_eq!(
+            submitted,
+            FieldMap::from([
+                ("consent".to_string(), "false".to_string()),
+                ("note".to_string(), "ok".to_string()),
+            ])
+        );
+        let problems = check_form(&fields, &submitted);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems["donor_id"], "donor_id is required");
+    }
+}
@@ -2,20 +2,97 @@
 //!
 //! Leptos-based WASM frontend for MISO LIMS.
 //!
-//! ## Features
+//! ## Pages
 //!
-//! - Reactive plate map visualization
-//! - Drag-and-drop sample management
-//! - Real-time scanner integration
-//! - Server functions for seamless API calls
+//! - Dashboard (`/`): sample intake, QC pass rates, runs per platform and
+//!   freezer capacity, kept current by the server's event stream
+//! - Boxes (`/boxes`): the Freezer → Shelf → Rack → Box hierarchy with
+//!   item search and moves
+//! - Run (`/runs/:id`): per-lane metrics, loaded pools and libraries, and
+//!   QC sign-off
+//! - Worksheet (`/worksheet?project=`): a tablet view stepping through a
+//!   project's samples awaiting QC at the bench
 //!
-//! ## Note
+//! The command palette (Ctrl+K) is available on every page.
 //!
-//! This crate requires Leptos which needs additional setup.
-//! See the README for installation instructions.
+//! ## Building
+//!
+//! The frontend is built and served with [Trunk](https://trunkrs.dev),
+//! which proxies `/api/` to a running `miso-server`. Alternatively,
+//! [cargo-leptos](https://github.com/leptos-rs/cargo-leptos) builds
+//! `miso-server` with its `ssr` feature, which renders the pages on the
+//! server, and this crate with `hydrate`, which takes them over in the
+//! browser. See the README.
+
+pub mod api;
+pub mod boxes;
+pub mod charts;
+pub mod dashboard;
+pub mod forms;
+pub mod page_data;
+pub mod palette;
+pub mod runs;
+pub mod worksheet;
+
+use leptos::prelude::*;
+use leptos_router::components::{Route, Router, Routes, A};
+use leptos_router::path;
+
+pub use boxes::BoxBrowser;
+pub use dashboard::Dashboard;
+pub use palette::CommandPalette;
+pub use runs::RunMonitor;
+pub use worksheet::Worksheet;
 
-// Placeholder until Leptos is properly configured
-pub fn hello() -> &'static str {
-    "MISO Frontend"
+/// Root component of the application.
+#[component]
+pub fn App() -> impl IntoView {
+    view! {
+        <Router>
+            <nav class="site-nav">
+                <span class="brand">"MISO"</span>
+                <A href="/">"Dashboard"</A>
+                <A href="/boxes">"Boxes"</A>
+                <A href="/worksheet">"Worksheet"</A>
+                <CommandPalette />
+            </nav>
+            <Routes fallback=|| view! { <main><p>"Page not found."</p></main> }>
+                <Route path=path!("/") view=Dashboard />
+                <Route path=path!("/boxes") view=BoxBrowser />
+                <Route path=path!("/runs/:id") view=RunMonitor />
+                <Route path=path!("/worksheet") view=Worksheet />
+            </Routes>
+        </Router>
+    }
 }
 
+/// The HTML document pages are rendered into by the server.
+#[cfg(feature = "ssr")]
+pub fn shell(options: LeptosOptions) -> impl IntoView {
+    use leptos::hydration::{AutoReload, HydrationScripts};
+
+    let stylesheet = format!("/{}/{}.css", options.site_pkg_dir, options.output_name);
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8" />
+                <title>"MISO LIMS"</title>
+                <link rel="stylesheet" href=stylesheet />
+                <AutoReload options=options.clone() />
+                <HydrationScripts options />
+            </head>
+            <body>
+                <App />
+            </body>
+        </html>
+    }
+}
+
+/// Browser entry point of the server-rendered build: takes over the page
+/// the server rendered.
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn hydrate() {
+    leptos::mount::hydrate_body(App);
+}
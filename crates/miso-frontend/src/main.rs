@@ -0,0 +1,5 @@
+//! Browser entry point for the MISO frontend.
+
+fn main() {
+    leptos::mount::mount_to_body(miso_frontend::App);
+}
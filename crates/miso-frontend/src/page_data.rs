@@ -0,0 +1,164 @@
+//! The data pages show first, and where it comes from.
+//!
+//! In the server-rendered build (`ssr` on miso-server, `hydrate` in the
+//! browser) these are server functions. They resolve while the server
+//! renders the page, so the HTML arrives with the dashboard, box tree or
+//! run already in it, and the browser picks the data up from the page
+//! rather than calling the API once the WASM has loaded. The browser-only
+//! build (`csr`) has no server to render on, so its resources fetch from
+//! the REST API as before.
+
+use leptos::prelude::*;
+
+use crate::api::{DashboardStats, RunOverviewResponse, StorageNode};
+
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+pub use rendered::{
+    dashboard, freezers, get_dashboard_stats, get_run_overview, get_storage_tree, run,
+};
+
+#[cfg(feature = "ssr")]
+pub use rendered::PageServices;
+
+#[cfg(not(any(feature = "ssr", feature = "hydrate")))]
+pub use fetched::{dashboard, freezers, run};
+
+/// Server functions, and resources resolved while the server renders the
+/// page.
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+mod rendered {
+    use super::*;
+
+    /// Days of history on the dashboard; the statistics stream's default.
+    #[cfg(feature = "ssr")]
+    const DASHBOARD_DAYS: u32 = 30;
+
+    /// Services the server functions answer from, provided as context by
+    /// miso-server.
+    #[cfg(feature = "ssr")]
+    #[derive(Clone)]
+    pub struct PageServices {
+        pub dashboard: std::sync::Arc<miso_application::DashboardService>,
+        /// Box storage browsing, if boxes are persisted
+        pub storage: Option<
+            std::sync::Arc<
+                miso_application::StorageBrowserService<
+                    dyn miso_domain::repositories::StorageBoxRepository,
+                    dyn miso_domain::repositories::SampleRepository,
+                >,
+            >,
+        >,
+        /// Run monitoring, if runs are persisted
+        pub runs: Option<std::sync::Arc<miso_application::RunMonitorService>>,
+    }
+
+    #[cfg(feature = "ssr")]
+    fn services() -> Result<PageServices, ServerFnError> {
+        use_context::<PageServices>().ok_or_else(|| ServerFnError::new("Page services are missing"))
+    }
+
+    /// Current dashboard statistics.
+    #[server]
+    pub async fn get_dashboard_stats() -> Result<DashboardStats, ServerFnError> {
+        services()?
+            .dashboard
+            .stats(chrono::Utc::now(), DASHBOARD_DAYS)
+            .await
+            .map_err(ServerFnError::new)
+    }
+
+    /// The Freezer → Shelf → Rack → Box tree.
+    #[server]
+    pub async fn get_storage_tree() -> Result<Vec<StorageNode>, ServerFnError> {
+        let storage = services()?
+            .storage
+            .ok_or_else(|| ServerFnError::new("Box storage is not available"))?;
+        storage.hierarchy().await.map_err(ServerFnError::new)
+    }
+
+    /// A run with its lanes, pools and QC decision.
+    #[server]
+    pub async fn get_run_overview(id: i32) -> Result<RunOverviewResponse, ServerFnError> {
+        let runs = services()?
+            .runs
+            .ok_or_else(|| ServerFnError::new("Run monitoring is not available"))?;
+        runs.overview(id).await.map_err(ServerFnError::new)
+    }
+
+    /// The message of a failed server function, as the page shows it.
+    fn message(error: ServerFnError) -> String {
+        match error {
+            ServerFnError::ServerError(message) => message,
+            other => other.to_string(),
+        }
+    }
+
+    /// Dashboard statistics as of the page load.
+    pub fn dashboard() -> Resource<Result<DashboardStats, String>> {
+        Resource::new(
+            || (),
+            |_| async { get_dashboard_stats().await.map_err(message) },
+        )
+    }
+
+    /// The storage tree, fetched again when `version` changes.
+    pub fn freezers(version: ReadSignal<u32>) -> Resource<Result<Vec<StorageNode>, String>> {
+        Resource::new(
+            move || version.get(),
+            |_| async { get_storage_tree().await.map_err(message) },
+        )
+    }
+
+    /// The run `run_id` names, fetched again when `version` changes.
+    pub fn run(
+        version: ReadSignal<u32>,
+        run_id: impl Fn() -> Option<i32> + Send + Sync + 'static,
+    ) -> Resource<Result<RunOverviewResponse, String>> {
+        Resource::new(
+            move || (version.get(), run_id()),
+            |(_, id)| async move {
+                match id {
+                    Some(id) => get_run_overview(id).await.map_err(message),
+                    None => Err("Not a run ID".to_string()),
+                }
+            },
+        )
+    }
+}
+
+/// Resources fetched from the REST API in the browser.
+#[cfg(not(any(feature = "ssr", feature = "hydrate")))]
+mod fetched {
+    use super::*;
+    use crate::api;
+
+    /// Dashboard statistics as of the page load.
+    pub fn dashboard() -> LocalResource<Result<DashboardStats, String>> {
+        LocalResource::new(api::fetch_dashboard_stats)
+    }
+
+    /// The storage tree, fetched again when `version` changes.
+    pub fn freezers(version: ReadSignal<u32>) -> LocalResource<Result<Vec<StorageNode>, String>> {
+        LocalResource::new(move || {
+            version.track();
+            api::fetch_freezers()
+        })
+    }
+
+    /// The run `run_id` names, fetched again when `version` changes.
+    pub fn run(
+        version: ReadSignal<u32>,
+        run_id: impl Fn() -> Option<i32> + 'static,
+    ) -> LocalResource<Result<RunOverviewResponse, String>> {
+        LocalResource::new(move || {
+            version.track();
+            let id = run_id();
+            async move {
+                match id {
+                    Some(id) => api::fetch_run(id).await,
+                    None => Err("Not a run ID".to_string()),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,596 @@
+//! Command palette.
+//!
+//! Ctrl+K (⌘K on a Mac) opens a box that finds actions, recently opened
+//! projects and samples, and anything the search endpoint matches, so
+//! that "scan box" or "new sample in PROJ42" is a few keystrokes away.
+//! Actions and recent entities are matched fuzzily on the client: the
+//! typed characters must appear in order, with runs of consecutive
+//! characters and word starts ranking higher.
+
+use leptos::ev;
+use leptos::ev::SubmitEvent;
+use leptos::html::Input;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+use leptos_router::NavigateOptions;
+
+use crate::api::{self, CreatePlainSampleRequest, SearchHit};
+use crate::forms::{check_form, form_values, AttributeFields, FieldMap};
+
+/// Local storage key holding recently opened entities.
+const RECENT_KEY: &str = "miso_recent";
+
+/// Most recent entities remembered.
+const MAX_RECENT: usize = 8;
+
+/// Most entries listed at once.
+const MAX_ENTRIES: usize = 12;
+
+/// Shortest text sent to the search endpoint.
+const MIN_SEARCH_LEN: usize = 2;
+
+/// Pages the palette can always open.
+const PAGES: [(&str, &str); 4] = [
+    ("Scan box", "/boxes"),
+    ("Go to dashboard", "/"),
+    ("Browse boxes", "/boxes"),
+    ("Open worksheet", "/worksheet"),
+];
+
+/// Actions taking a project, written before its code.
+const NEW_SAMPLE_IN: &str = "New sample in ";
+const WORKSHEET_FOR: &str = "Worksheet for ";
+
+/// Something the palette can do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Opens a page
+    Navigate { label: String, href: String },
+    /// Opens a project or sample
+    Open(SearchHit),
+    /// Asks for the details of a new sample in a project
+    NewSample { project_id: i32, code: String },
+}
+
+impl Command {
+    /// Text the command is listed and matched by.
+    pub fn label(&self) -> String {
+        match self {
+            Command::Navigate { label, .. } => label.clone(),
+            Command::Open(hit) => hit.label.clone(),
+            Command::NewSample { code, .. } => format!("{}{}", NEW_SAMPLE_IN, code),
+        }
+    }
+
+    fn detail(&self) -> Option<String> {
+        match self {
+            Command::Navigate { .. } | Command::NewSample { .. } => None,
+            Command::Open(hit) => {
+                let kind = if hit.is_project() {
+                    "Project"
+                } else {
+                    "Sample"
+                };
+                Some(match &hit.detail {
+                    Some(detail) => format!("{} · {}", kind, detail),
+                    None => kind.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Scores how well `query` matches `text`, or `None` if the characters of
+/// `query` (ignoring case and whitespace) don't all appear in `text` in
+/// order.
+///
+/// Each matched character scores a point, plus three more if it follows
+/// the previous match directly and two more if it starts a word.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut wanted = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for c in text.chars() {
+        let Some(&next) = wanted.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(next));
+        if matched {
+            wanted.next();
+            score += 1;
+            if previous_matched {
+                score += 3;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 2;
+            }
+        }
+        previous_matched = matched;
+        previous = Some(c);
+    }
+
+    wanted.peek().is_none().then_some(score)
+}
+
+/// The text to send to the search endpoint: whatever follows an action
+/// taking a project, e.g. "PROJ42" in "new sample in PROJ42", or else the
+/// whole query.
+pub fn search_term(query: &str) -> &str {
+    let query = query.trim();
+    [NEW_SAMPLE_IN, WORKSHEET_FOR]
+        .iter()
+        .find_map(|prefix| {
+            query
+                .get(..prefix.len())
+                .filter(|start| start.eq_ignore_ascii_case(prefix))
+                .map(|_| query[prefix.len()..].trim())
+        })
+        .unwrap_or(query)
+}
+
+/// Lists what the palette offers for `query`, best match first.
+///
+/// Pages, recent entities and actions on projects are matched against
+/// the query. Search hits have already been matched by the server and
+/// are kept even when the query doesn't fuzzily match their label.
+pub fn commands(query: &str, recent: &[SearchHit], hits: &[SearchHit]) -> Vec<Command> {
+    let projects = recent.iter().chain(hits).filter(|h| h.is_project());
+    let candidates: Vec<(Command, bool)> = PAGES
+        .iter()
+        .map(|(label, href)| {
+            let command = Command::Navigate {
+                label: label.to_string(),
+                href: href.to_string(),
+            };
+            (command, false)
+        })
+        .chain(projects.flat_map(|p| {
+            [
+                Command::NewSample {
+                    project_id: p.project_id,
+                    code: p.label.clone(),
+                },
+                Command::Navigate {
+                    label: format!("{}{}", WORKSHEET_FOR, p.label),
+                    href: api::links::worksheet(p.project_id),
+                },
+            ]
+            .map(|command| (command, false))
+        }))
+        .chain(recent.iter().map(|h| (Command::Open(h.clone()), false)))
+        .chain(hits.iter().map(|h| (Command::Open(h.clone()), true)))
+        .collect();
+    // A recent entity the server found again keeps the server's match
+    let mut unique: Vec<(Command, bool)> = Vec::new();
+    for (command, from_server) in candidates {
+        match unique
+            .iter_mut()
+            .find(|(c, _)| c.label() == command.label())
+        {
+            Some((_, found)) => *found |= from_server,
+            None => unique.push((command, from_server)),
+        }
+    }
+    let mut candidates = unique;
+
+    if query.trim().is_empty() {
+        // Recent entities first, then pages
+        candidates.sort_by_key(|(command, _)| !matches!(command, Command::Open(_)));
+        return candidates
+            .into_iter()
+            .filter(|(command, _)| !matches!(command, Command::NewSample { .. }))
+            .map(|(command, _)| command)
+            .take(MAX_ENTRIES)
+            .collect();
+    }
+
+    let term = search_term(query);
+    let mut scored: Vec<(u32, Command)> = candidates
+        .into_iter()
+        .filter_map(|(command, from_server)| {
+            let score = fuzzy_score(query, &command.label());
+            match (score, from_server) {
+                (Some(score), _) => Some((score, command)),
+                (None, true) => Some((fuzzy_score(term, &command.label()).unwrap_or(0), command)),
+                (None, false) => None,
+            }
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .map(|(_, command)| command)
+        .take(MAX_ENTRIES)
+        .collect()
+}
+
+/// Puts `hit` at the front of the recent entities.
+pub fn push_recent(mut recent: Vec<SearchHit>, hit: SearchHit) -> Vec<SearchHit> {
+    recent.retain(|h| !(h.kind == hit.kind && h.id == hit.id));
+    recent.insert(0, hit);
+    recent.truncate(MAX_RECENT);
+    recent
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+fn load_recent() -> Vec<SearchHit> {
+    local_storage()
+        .and_then(|s| s.get_item(RECENT_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_recent(recent: &[SearchHit]) {
+    if let (Some(storage), Ok(json)) = (local_storage(), serde_json::to_string(recent)) {
+        let _ = storage.set_item(RECENT_KEY, &json);
+    }
+}
+
+/// What the palette is asking for.
+#[derive(Debug, Clone, PartialEq)]
+enum Mode {
+    /// A command to run
+    Find,
+    /// The name and species of a new sample
+    NewSample { project_id: i32, code: String },
+}
+
+/// Ctrl+K command palette, with a button in the navigation bar to open it.
+#[component]
+pub fn CommandPalette() -> impl IntoView {
+    // Held in the arena so the closures below stay `Copy`
+    let navigate = StoredValue::new_local(use_navigate());
+    let (open, set_open) = signal(false);
+    let (mode, set_mode) = signal(Mode::Find);
+    let (query, set_query) = signal(String::new());
+    let (selected, set_selected) = signal(0usize);
+    let (recent, set_recent) = signal(load_recent());
+    let (notice, set_notice) = signal(None::<(String, bool)>);
+    let (sample_name, set_sample_name) = signal(String::new());
+    let (scientific_name, set_scientific_name) = signal(String::new());
+    let attributes = RwSignal::new(FieldMap::new());
+    let attribute_errors = RwSignal::new(FieldMap::new());
+    let input = NodeRef::<Input>::new();
+
+    let hits = LocalResource::new(move || {
+        let term = search_term(&query.get()).to_string();
+        async move {
+            if term.chars().count() < MIN_SEARCH_LEN {
+                return Vec::new();
+            }
+            api::search(&term).await.map(|r| r.hits).unwrap_or_default()
+        }
+    });
+    // Custom fields of the project a sample is being created in
+    let schema = LocalResource::new(move || {
+        let project_id = match mode.get() {
+            Mode::NewSample { project_id, .. } => Some(project_id),
+            Mode::Find => None,
+        };
+        async move {
+            match project_id {
+                Some(id) => api::fetch_attribute_schema("sample", id)
+                    .await
+                    .map(|s| s.fields),
+                None => Ok(Vec::new()),
+            }
+        }
+    });
+    let entries = Memo::new(move |_| {
+        let hits = hits.get().unwrap_or_default();
+        commands(&query.get(), &recent.get(), &hits)
+    });
+
+    let show = move |visible: bool| {
+        set_open.set(visible);
+        set_mode.set(Mode::Find);
+        set_query.set(String::new());
+        set_selected.set(0);
+        set_notice.set(None);
+    };
+
+    let _toggle = window_event_listener(ev::keydown, move |ev| {
+        if (ev.ctrl_key() || ev.meta_key()) && ev.key().eq_ignore_ascii_case("k") {
+            ev.prevent_default();
+            show(!open.get_untracked());
+        }
+    });
+
+    // Focus the input whenever the palette opens or changes mode.
+    Effect::new(move || {
+        mode.track();
+        if open.get() {
+            if let Some(input) = input.get() {
+                let _ = input.focus();
+            }
+        }
+    });
+
+    let remember = move |hit: SearchHit| {
+        let updated = push_recent(recent.get_untracked(), hit);
+        save_recent(&updated);
+        set_recent.set(updated);
+    };
+
+    let run = move |command: Command| match command {
+        Command::Navigate { href, .. } => {
+            show(false);
+            navigate.with_value(|navigate| navigate(&href, NavigateOptions::default()));
+        }
+        Command::Open(hit) => {
+            let href = api::links::search_hit(&hit);
+            remember(hit);
+            show(false);
+            navigate.with_value(|navigate| navigate(&href, NavigateOptions::default()));
+        }
+        Command::NewSample { project_id, code } => {
+            set_sample_name.set(String::new());
+            attributes.set(FieldMap::new());
+            attribute_errors.set(FieldMap::new());
+            set_notice.set(None);
+            set_mode.set(Mode::NewSample { project_id, code });
+        }
+    };
+
+    let keydown = move |ev: ev::KeyboardEvent| {
+        let count = entries.with_untracked(Vec::len);
+        match ev.key().as_str() {
+            "Escape" => show(false),
+            "ArrowDown" if count > 0 => {
+                ev.prevent_default();
+                set_selected.update(|i| *i = (*i + 1) % count);
+            }
+            "ArrowUp" if count > 0 => {
+                ev.prevent_default();
+                set_selected.update(|i| *i = (*i + count - 1) % count);
+            }
+            "Enter" => {
+                ev.prevent_default();
+                let chosen = entries.with_untracked(|e| e.get(selected.get_untracked()).cloned());
+                if let Some(command) = chosen {
+                    run(command);
+                }
+            }
+            _ => {}
+        }
+    };
+
+    let create = move |ev: SubmitEvent, project_id: i32| {
+        ev.prevent_default();
+        let fields = schema.get_untracked().and_then(Result::ok).unwrap_or_default();
+        let values = attributes.with_untracked(|values| form_values(&fields, values));
+        let problems = check_form(&fields, &values);
+        let request = CreatePlainSampleRequest {
+            name: sample_name.get_untracked().trim().to_string(),
+            project_id,
+            scientific_name: scientific_name.get_untracked().trim().to_string(),
+            description: None,
+            sample_type: None,
+            attributes: Some(values),
+            control_type: None,
+            subproject_id: None,
+        };
+        if request.name.is_empty() || request.scientific_name.is_empty() {
+            set_notice.set(Some((
+                "Name and scientific name are required".to_string(),
+                true,
+            )));
+            return;
+        }
+        if !problems.is_empty() {
+            attribute_errors.set(problems);
+            set_notice.set(Some(("Check the highlighted fields".to_string(), true)));
+            return;
+        }
+        spawn_local(async move {
+            match api::create_sample(&request).await {
+                Ok(sample) => {
+                    set_notice.set(Some((
+                        format!("Created {} ({})", sample.name, sample.barcode),
+                        false,
+                    )));
+                    set_sample_name.set(String::new());
+                    attributes.set(FieldMap::new());
+                    attribute_errors.set(FieldMap::new());
+                    remember(sample.into());
+                    // Stay in the form: the next sample is often in the
+                    // same project.
+                    if let Some(input) = input.get_untracked() {
+                        let _ = input.focus();
+                    }
+                }
+                Err(e) => set_notice.set(Some((e, true))),
+            }
+        });
+    };
+
+    let find = move || {
+        view! {
+            <input
+                type="text"
+                class="palette-input"
+                node_ref=input
+                placeholder="Type a command, project or sample…"
+                prop:value=query
+                on:input=move |ev| {
+                    set_query.set(event_target_value(&ev));
+                    set_selected.set(0);
+                }
+                on:keydown=keydown
+            />
+            <ul class="palette-entries">
+                {move || {
+                    entries
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, command)| {
+                            let detail = command.detail();
+                            let label = command.label();
+                            view! {
+                                <li
+                                    class=move || if selected.get() == i { "selected" } else { "" }
+                                    on:mouseenter=move |_| set_selected.set(i)
+                                    on:mousedown=move |ev| {
+                                        ev.prevent_default();
+                                        run(command.clone());
+                                    }
+                                >
+                                    <span class="palette-label">{label}</span>
+                                    {detail.map(|d| view! { <span class="palette-detail">{d}</span> })}
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ul>
+        }
+    };
+
+    let new_sample = move |project_id: i32, code: String| {
+        view! {
+            <form class="palette-form" on:submit=move |ev| create(ev, project_id)>
+                <h2>{format!("{}{}", NEW_SAMPLE_IN, code)}</h2>
+                <input
+                    type="text"
+                    class="palette-input"
+                    node_ref=input
+                    placeholder="Sample name"
+                    prop:value=sample_name
+                    on:input=move |ev| set_sample_name.set(event_target_value(&ev))
+                    on:keydown=move |ev: ev::KeyboardEvent| {
+                        if ev.key() == "Escape" {
+                            show(false);
+                        }
+                    }
+                />
+                <input
+                    type="text"
+                    placeholder="Scientific name, e.g. Homo sapiens"
+                    prop:value=scientific_name
+                    on:input=move |ev| set_scientific_name.set(event_target_value(&ev))
+                />
+                {move || {
+                    schema
+                        .get()
+                        .map(|fields| match fields {
+                            Ok(fields) => {
+                                view! {
+                                    <AttributeFields
+                                        fields=fields
+                                        values=attributes
+                                        errors=attribute_errors
+                                    />
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => {
+                                view! {
+                                    <p class="notice error">
+                                        {format!("Custom fields could not be loaded: {}", e)}
+                                    </p>
+                                }
+                                    .into_any()
+                            }
+                        })
+                }}
+                <button type="submit">"Create"</button>
+            </form>
+        }
+    };
+
+    view! {
+        <button class="palette-trigger" on:click=move |_| show(true)>
+            "Search "
+            <kbd>"Ctrl+K"</kbd>
+        </button>
+        <Show when=move || open.get()>
+            <div class="palette-backdrop" on:click=move |_| show(false)></div>
+            <div class="palette" role="dialog" aria-label="Command palette">
+                {move || match mode.get() {
+                    Mode::Find => find().into_any(),
+                    Mode::NewSample { project_id, code } => new_sample(project_id, code).into_any(),
+                }}
+                {move || {
+                    notice
+                        .get()
+                        .map(|(text, is_error)| {
+                            view! { <p class=if is_error { "notice error" } else { "notice" }>{text}</p> }
+                        })
+                }}
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::SearchHitKind;
+
+    fn project(id: i32, code: &str) -> SearchHit {
+        SearchHit {
+            kind: SearchHitKind::Project,
+            id,
+            label: code.to_string(),
+            detail: None,
+            project_id: id,
+            barcode: None,
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_consecutive_word_starts() {
+        assert_eq!(fuzzy_score("sb", "Scan box"), Some(6));
+        assert!(fuzzy_score("scan", "Scan box") > fuzzy_score("scan", "Some clean animal"));
+        assert!(fuzzy_score("BOX", "Browse boxes").is_some());
+        assert_eq!(fuzzy_score("xb", "Scan box"), None);
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_search_term_follows_project_actions() {
+        assert_eq!(search_term("new sample in PROJ42"), "PROJ42");
+        assert_eq!(search_term(" Worksheet for  PROJ7 "), "PROJ7");
+        assert_eq!(search_term("SAM-0042"), "SAM-0042");
+        assert_eq!(search_term("new"), "new");
+    }
+
+    #[test]
+    fn test_commands_offer_actions_on_found_projects() {
+        let hits = vec![project(42, "PROJ42")];
+        let found = commands("new sample in PROJ42", &[], &hits);
+
+        assert_eq!(
+            found[0],
+            Command::NewSample {
+                project_id: 42,
+                code: "PROJ42".to_string()
+            }
+        );
+        assert!(found.contains(&Command::Open(hits[0].clone())));
+    }
+
+    #[test]
+    fn test_recent_entities_are_deduplicated_and_capped() {
+        let mut recent = Vec::new();
+        for id in 0..10 {
+            recent = push_recent(recent, project(id, &format!("P{}", id)));
+        }
+        recent = push_recent(recent, project(5, "P5"));
+
+        assert_eq!(recent.len(), MAX_RECENT);
+        assert_eq!(recent[0].id, 5);
+        assert_eq!(recent.iter().filter(|h| h.id == 5).count(), 1);
+    }
+}
@@ -0,0 +1,380 @@
+//! Run monitoring page.
+//!
+//! Shows a run's per-lane metrics: Q30, cluster density and pass filter
+//! as reported by the instrument, and reads and yield from the
+//! demultiplexing pipeline. Below them are the pools loaded on each lane
+//! with their libraries, and the QC sign-off controls for reviewers.
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_params_map;
+
+use crate::api::{
+    self, LaneOverview, LaneYield, PoolOverview, RunOverviewResponse, SignOffRunQcRequest,
+};
+use crate::charts::LaneChart;
+use crate::page_data;
+
+/// Formats a base count with a decimal unit, e.g. "1.25 Gb".
+pub fn format_bases(bases: u64) -> String {
+    const UNITS: [(f64, &str); 4] = [(1e12, "Tb"), (1e9, "Gb"), (1e6, "Mb"), (1e3, "kb")];
+    let value = bases as f64;
+    UNITS
+        .iter()
+        .find(|(scale, _)| value >= *scale)
+        .map(|(scale, unit)| format!("{:.2} {}", value / scale, unit))
+        .unwrap_or_else(|| format!("{} b", bases))
+}
+
+/// Formats a read count in millions.
+pub fn format_reads(reads: f64) -> String {
+    format!("{:.1} M", reads / 1e6)
+}
+
+fn format_percent(value: f64) -> String {
+    format!("{:.1}%", value)
+}
+
+fn format_density(value: f64) -> String {
+    format!("{:.0} K/mm²", value)
+}
+
+/// CSS class for a run status label.
+fn status_class(status: &str) -> &'static str {
+    match status {
+        "QC Passed" => "status passed",
+        "QC Failed" | "Failed" | "Stopped" => "status failed",
+        "Completed" | "QC In Progress" => "status needs_review",
+        _ => "status",
+    }
+}
+
+/// Detail page for one run.
+#[component]
+pub fn RunMonitor() -> impl IntoView {
+    let params = use_params_map();
+    let run_id = move || {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i32>().ok())
+    };
+
+    // Bumped after a sign-off so the run is fetched again.
+    let (version, set_version) = signal(0u32);
+    let (note, set_note) = signal(String::new());
+    let (notice, set_notice) = signal(None::<(String, bool)>);
+
+    let overview = page_data::run(version, run_id);
+    let metrics = LocalResource::new(move || {
+        let id = run_id();
+        async move {
+            match id {
+                Some(id) => api::fetch_run_metrics(id).await.map(|m| m.lane_yields()),
+                None => Ok(Vec::new()),
+            }
+        }
+    });
+
+    let sign_off = move |passed: bool| {
+        let Some(id) = run_id() else {
+            return;
+        };
+        let note = note.get_untracked().trim().to_string();
+        let request = SignOffRunQcRequest {
+            passed,
+            note: (!note.is_empty()).then_some(note),
+        };
+        spawn_local(async move {
+            match api::sign_off_run(id, &request).await {
+                Ok(run) => {
+                    set_notice.set(Some((format!("{} marked {}", run.name, run.status), false)));
+                    set_note.set(String::new());
+                    set_version.update(|v| *v += 1);
+                }
+                Err(e) => set_notice.set(Some((e, true))),
+            }
+        });
+    };
+
+    view! {
+        <main class="run-monitor">
+            <Suspense fallback=|| view! { <p class="loading">"Loading run…"</p> }>
+                {move || Suspend::new(async move {
+                    match overview.await {
+                        Ok(run) => {
+                            let can_review = run.awaiting_review();
+                            let lanes = run.lanes.clone();
+                            let pools = run.pools.clone();
+                            view! {
+                                {run_header(&run)}
+                                <section class="panel">
+                                    <h2>"Instrument metrics"</h2>
+                                    {instrument_charts(lanes)}
+                                </section>
+                                <section class="panel">
+                                    <h2>"Demultiplexed output"</h2>
+                                    <Suspense fallback=|| view! { <p class="loading">"Loading metrics…"</p> }>
+                                        {move || Suspend::new(async move {
+                                            match metrics.await {
+                                                Ok(lanes) => demux_charts(lanes),
+                                                Err(e) => view! { <p class="notice error">{e}</p> }.into_any(),
+                                            }
+                                        })}
+                                    </Suspense>
+                                    <p class="unavailable">
+                                        "Yield per cycle is not available: per-cycle InterOp metrics are not ingested."
+                                    </p>
+                                </section>
+                                <section class="panel">
+                                    <h2>"Pools"</h2>
+                                    {pools_section(pools)}
+                                </section>
+                                <section class="panel qc-sign-off">
+                                    <h2>"QC sign-off"</h2>
+                                    {sign_off_summary(&run)}
+                                    <textarea
+                                        placeholder="Note (required to fail the run)"
+                                        prop:value=note
+                                        disabled=!can_review
+                                        on:input=move |ev| set_note.set(event_target_value(&ev))
+                                    ></textarea>
+                                    <div class="actions">
+                                        <button
+                                            class="pass"
+                                            disabled=!can_review
+                                            on:click=move |_| sign_off(true)
+                                        >
+                                            "Pass"
+                                        </button>
+                                        <button
+                                            class="fail"
+                                            disabled=move || !can_review || note.get().trim().is_empty()
+                                            on:click=move |_| sign_off(false)
+                                        >
+                                            "Fail"
+                                        </button>
+                                    </div>
+                                </section>
+                            }
+                            .into_any()
+                        }
+                        Err(e) => view! { <p class="notice error">{e}</p> }.into_any(),
+                    }
+                })}
+            </Suspense>
+            {move || {
+                notice
+                    .get()
+                    .map(|(text, is_error)| {
+                        view! { <p class=if is_error { "notice error" } else { "notice" }>{text}</p> }
+                    })
+            }}
+        </main>
+    }
+}
+
+/// Run name, status and instrument details.
+fn run_header(run: &RunOverviewResponse) -> AnyView {
+    let mut details = vec![format!("Sequencer {}", run.sequencer_id)];
+    details.extend(
+        run.container_barcode
+            .as_ref()
+            .map(|c| format!("Container {}", c)),
+    );
+    details.extend(run.read_length.as_ref().map(|r| format!("Reads {}", r)));
+    details.extend(run.started_at.as_ref().map(|t| format!("Started {}", t)));
+    details.extend(
+        run.completed_at
+            .as_ref()
+            .map(|t| format!("Completed {}", t)),
+    );
+    if run.completed_at.is_none() {
+        details.extend(
+            run.cycles_completed
+                .zip(run.total_cycles)
+                .map(|(done, total)| format!("Cycle {} of {}", done, total)),
+        );
+        details.extend(
+            run.estimated_completion
+                .as_ref()
+                .map(|t| format!("Expected to finish {}", t.format("%Y-%m-%d %H:%M UTC"))),
+        );
+    }
+    let overdue = run
+        .overdue_since
+        .as_ref()
+        .map(|t| view! { <p class="notice error">{format!("Overdue since {}", t)}</p> });
+
+    view! {
+        <h1>
+            {run.name.clone()}
+            {run.alias.clone().map(|alias| view! { <small>{alias}</small> })}
+        </h1>
+        <p class="run-meta">
+            <span class=status_class(&run.status)>{run.status.clone()}</span>
+            {format!(" · {}", details.join(" · "))}
+        </p>
+        {overdue}
+    }
+    .into_any()
+}
+
+/// Q30, cluster density and pass filter per lane, from the instrument.
+fn instrument_charts(lanes: Vec<LaneOverview>) -> AnyView {
+    if lanes
+        .iter()
+        .all(|l| l.q30_percent.is_none() && l.cluster_density.is_none())
+    {
+        return view! { <p class="empty">"The instrument has not reported lane metrics yet."</p> }
+            .into_any();
+    }
+    let series = |metric: fn(&LaneOverview) -> Option<f64>| -> Vec<(u8, Option<f64>)> {
+        lanes
+            .iter()
+            .map(|l| (l.partition_number, metric(l)))
+            .collect()
+    };
+
+    view! {
+        <div class="lane-charts">
+            <figure>
+                <figcaption>"% ≥ Q30"</figcaption>
+                <LaneChart lanes=series(|l| l.q30_percent) format=format_percent max=100.0 />
+            </figure>
+            <figure>
+                <figcaption>"Cluster density"</figcaption>
+                <LaneChart lanes=series(|l| l.cluster_density) format=format_density />
+            </figure>
+            <figure>
+                <figcaption>"% pass filter"</figcaption>
+                <LaneChart lanes=series(|l| l.pass_filter_percent) format=format_percent max=100.0 />
+            </figure>
+        </div>
+    }
+    .into_any()
+}
+
+/// Reads, yield and Q30 per lane, from the demultiplexing pipeline.
+fn demux_charts(lanes: Vec<LaneYield>) -> AnyView {
+    if lanes.is_empty() {
+        return view! { <p class="empty">"No demultiplexing metrics have been submitted."</p> }
+            .into_any();
+    }
+    let reads = lanes
+        .iter()
+        .map(|l| (l.partition_number, Some(l.reads as f64)))
+        .collect::<Vec<_>>();
+    let yields = lanes
+        .iter()
+        .map(|l| (l.partition_number, Some(l.yield_bases as f64)))
+        .collect::<Vec<_>>();
+    let q30 = lanes
+        .iter()
+        .map(|l| (l.partition_number, l.q30_percent))
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="lane-charts">
+            <figure>
+                <figcaption>"Reads"</figcaption>
+                <LaneChart lanes=reads format=format_reads />
+            </figure>
+            <figure>
+                <figcaption>"Yield"</figcaption>
+                <LaneChart lanes=yields format=|v| format_bases(v as u64) />
+            </figure>
+            <figure>
+                <figcaption>"% ≥ Q30 (demultiplexed)"</figcaption>
+                <LaneChart lanes=q30 format=format_percent max=100.0 />
+            </figure>
+        </div>
+    }
+    .into_any()
+}
+
+/// The pools on each lane and their libraries.
+fn pools_section(pools: Option<Vec<PoolOverview>>) -> AnyView {
+    let Some(pools) = pools else {
+        return view! { <p class="unavailable">"Pools are not available."</p> }.into_any();
+    };
+    if pools.is_empty() {
+        return view! { <p class="empty">"No pools are loaded on this run."</p> }.into_any();
+    }
+
+    pools
+        .into_iter()
+        .map(|pool| {
+            let lanes = pool
+                .lanes
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let libraries = pool
+                .libraries
+                .into_iter()
+                .map(|library| {
+                    view! {
+                        <tr>
+                            <td>{library.name}</td>
+                            <td>{library.barcode}</td>
+                            <td>{library.index.unwrap_or_default()}</td>
+                        </tr>
+                    }
+                })
+                .collect_view();
+            view! {
+                <details class="pool" open=true>
+                    <summary>{format!("{} ({}) · lanes {}", pool.name, pool.barcode, lanes)}</summary>
+                    <table class="libraries">
+                        <thead>
+                            <tr>
+                                <th>"Library"</th>
+                                <th>"Barcode"</th>
+                                <th>"Index"</th>
+                            </tr>
+                        </thead>
+                        <tbody>{libraries}</tbody>
+                    </table>
+                </details>
+            }
+        })
+        .collect_view()
+        .into_any()
+}
+
+/// The current QC decision, or why the run cannot be signed off yet.
+fn sign_off_summary(run: &RunOverviewResponse) -> AnyView {
+    match (&run.qc_sign_off, run.awaiting_review()) {
+        (Some(sign_off), _) => {
+            let verdict = if sign_off.passed { "Passed" } else { "Failed" };
+            let text = format!(
+                "{} by {} on {}",
+                verdict, sign_off.reviewer, sign_off.signed_off_at
+            );
+            view! {
+                <p class=if sign_off.passed { "verdict passed" } else { "verdict failed" }>{text}</p>
+                {sign_off.note.clone().map(|note| view! { <blockquote>{note}</blockquote> })}
+            }
+            .into_any()
+        }
+        (None, true) => view! { <p>"Awaiting review."</p> }.into_any(),
+        (None, false) => {
+            view! { <p class="unavailable">"The run can be signed off once it completes."</p> }
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bases() {
+        assert_eq!(format_bases(950), "950 b");
+        assert_eq!(format_bases(45_000), "45.00 kb");
+        assert_eq!(format_bases(1_250_000_000), "1.25 Gb");
+    }
+}
@@ -0,0 +1,461 @@
+//! Bench worksheet page.
+//!
+//! A tablet view for working through a project's samples awaiting QC one
+//! at a time: scan the tube to confirm it is the right sample, key in
+//! volume and concentration on the numeric pad, pass or fail it and print
+//! its label. Every control is a large touch target for gloved hands, and
+//! failing a sample picks a reason from buttons rather than typing one.
+//!
+//! A banner warns when the project is locked for review, as the server
+//! will then refuse QC results from anyone but an admin.
+
+use std::collections::HashMap;
+
+use leptos::ev::SubmitEvent;
+use leptos::html::Input;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::NavigateOptions;
+
+use crate::api::{self, ProjectLockResponse, SampleSummary, UpdateSampleRequest};
+
+/// Longest value the numeric pad accepts.
+const MAX_ENTRY_LEN: usize = 8;
+
+/// Reasons offered for failing a sample.
+const FAIL_REASONS: [&str; 3] = ["Low concentration", "Low volume", "Degraded"];
+
+/// A key on the numeric pad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadKey {
+    Digit(u8),
+    Point,
+    Backspace,
+    Clear,
+}
+
+impl PadKey {
+    /// Keys in the order they are laid out, three to a row.
+    const LAYOUT: [PadKey; 12] = [
+        PadKey::Digit(7),
+        PadKey::Digit(8),
+        PadKey::Digit(9),
+        PadKey::Digit(4),
+        PadKey::Digit(5),
+        PadKey::Digit(6),
+        PadKey::Digit(1),
+        PadKey::Digit(2),
+        PadKey::Digit(3),
+        PadKey::Point,
+        PadKey::Digit(0),
+        PadKey::Backspace,
+    ];
+
+    fn label(self) -> String {
+        match self {
+            PadKey::Digit(d) => d.to_string(),
+            PadKey::Point => ".".to_string(),
+            PadKey::Backspace => "⌫".to_string(),
+            PadKey::Clear => "C".to_string(),
+        }
+    }
+}
+
+/// Applies a numeric pad key to the value being entered.
+///
+/// Keeps the value a valid decimal: at most one point, no leading zeros
+/// and no more than [`MAX_ENTRY_LEN`] characters.
+pub fn press(value: &str, key: PadKey) -> String {
+    match key {
+        PadKey::Clear => String::new(),
+        PadKey::Backspace => {
+            let mut value = value.to_string();
+            value.pop();
+            value
+        }
+        _ if value.len() >= MAX_ENTRY_LEN => value.to_string(),
+        PadKey::Point if value.contains('.') => value.to_string(),
+        PadKey::Point if value.is_empty() => "0.".to_string(),
+        PadKey::Point => format!("{}.", value),
+        PadKey::Digit(d) if value == "0" => d.to_string(),
+        PadKey::Digit(d) => format!("{}{}", value, d),
+    }
+}
+
+/// Whether a scanned barcode identifies the expected sample.
+pub fn barcode_matches(expected: &str, scanned: &str) -> bool {
+    expected.trim().eq_ignore_ascii_case(scanned.trim())
+}
+
+/// Text of the banner shown while a project is locked.
+pub fn lock_banner(lock: &ProjectLockResponse) -> String {
+    let until = match lock.unlock_at {
+        Some(at) => format!("until {}", at.format("%Y-%m-%d %H:%M UTC")),
+        None => "until it is unlocked".to_string(),
+    };
+    format!(
+        "Locked by {} {}: {}. Only admins can record changes.",
+        lock.locked_by, until, lock.reason
+    )
+}
+
+/// The value the numeric pad is entering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Volume,
+    Concentration,
+}
+
+/// Step-by-step QC worksheet for a project's samples.
+#[component]
+pub fn Worksheet() -> impl IntoView {
+    let query = use_query_map();
+    let project = move || {
+        query
+            .read()
+            .get("project")
+            .and_then(|p| p.trim().parse::<i32>().ok())
+    };
+
+    let samples = LocalResource::new(move || {
+        let project = project();
+        async move {
+            match project {
+                Some(id) => api::fetch_project_samples(id)
+                    .await
+                    .map(|samples| samples.into_iter().filter(|s| s.awaiting_qc()).collect()),
+                None => Ok(Vec::<SampleSummary>::new()),
+            }
+        }
+    });
+
+    let lock = LocalResource::new(move || {
+        let project = project();
+        async move {
+            match project {
+                Some(id) => api::fetch_project(id).await.ok().and_then(|p| p.lock),
+                None => None,
+            }
+        }
+    });
+
+    let (index, set_index) = signal(0usize);
+    let (scanned, set_scanned) = signal(String::new());
+    let (confirmed, set_confirmed) = signal(false);
+    let (field, set_field) = signal(Field::Volume);
+    let (volume, set_volume) = signal(String::new());
+    let (concentration, set_concentration) = signal(String::new());
+    let (notice, set_notice) = signal(None::<(String, bool)>);
+    let (results, set_results) = signal(HashMap::<i32, &'static str>::new());
+    let scan_input = NodeRef::<Input>::new();
+
+    // Keep the scan box focused so a handheld scanner can type into it.
+    Effect::new(move || {
+        index.track();
+        if let Some(input) = scan_input.get() {
+            let _ = input.focus();
+        }
+    });
+
+    let go_to = move |i: usize| {
+        set_index.set(i);
+        set_scanned.set(String::new());
+        set_confirmed.set(false);
+        set_field.set(Field::Volume);
+        set_volume.set(String::new());
+        set_concentration.set(String::new());
+    };
+
+    let scan = move |ev: SubmitEvent, sample: SampleSummary| {
+        ev.prevent_default();
+        let code = scanned.get_untracked();
+        if barcode_matches(&sample.barcode, &code) {
+            set_confirmed.set(true);
+            set_notice.set(None);
+        } else {
+            set_notice.set(Some((
+                format!("Scanned {}, expected {}", code.trim(), sample.barcode),
+                true,
+            )));
+        }
+        set_scanned.set(String::new());
+    };
+
+    let key = move |key: PadKey| {
+        let target = match field.get_untracked() {
+            Field::Volume => set_volume,
+            Field::Concentration => set_concentration,
+        };
+        target.update(|value| *value = press(value, key));
+    };
+
+    let record = move |sample: SampleSummary, reason: Option<&'static str>| {
+        let request = UpdateSampleRequest {
+            volume_ul: volume.get_untracked().parse().ok(),
+            concentration_ng_ul: concentration.get_untracked().parse().ok(),
+            qc_status: Some(if reason.is_some() { "failed" } else { "passed" }.to_string()),
+            qc_reason: reason.map(str::to_string),
+            ..Default::default()
+        };
+        let next = index.get_untracked() + 1;
+        spawn_local(async move {
+            match api::update_sample(sample.id, &request).await {
+                Ok(updated) => {
+                    set_notice.set(Some((
+                        format!("{} marked {}", updated.name, updated.qc_status),
+                        false,
+                    )));
+                    set_results.update(|r| {
+                        r.insert(
+                            sample.id,
+                            if reason.is_some() { "Failed" } else { "Passed" },
+                        );
+                    });
+                    go_to(next);
+                }
+                Err(e) => set_notice.set(Some((e, true))),
+            }
+        });
+    };
+
+    let print = move |sample: SampleSummary| {
+        spawn_local(async move {
+            match api::print_sample_label(sample.id).await {
+                Ok(()) => {
+                    set_notice.set(Some((format!("Printed label for {}", sample.name), false)))
+                }
+                Err(e) => set_notice.set(Some((e, true))),
+            }
+        });
+    };
+
+    let step = move |sample: SampleSummary, position: usize, total: usize| {
+        let progress = format!("{:.1}%", position as f64 * 100.0 / total as f64);
+        let scanning = sample.clone();
+        let passing = sample.clone();
+        let failing = sample.clone();
+        let printing = sample.clone();
+
+        view! {
+            <header class="step-header">
+                <span>{format!("Sample {} of {}", position + 1, total)}</span>
+                <span class="progress">
+                    <span class="progress-level" style:width=progress></span>
+                </span>
+            </header>
+            <section class="sample-card">
+                <h1>{sample.name.clone()}</h1>
+                <p class="barcode">{sample.barcode.clone()}</p>
+                <p>{format!("{} · {}", sample.sample_class, sample.qc_status)}</p>
+            </section>
+            <Show
+                when=move || confirmed.get()
+                fallback=move || {
+                    let sample = scanning.clone();
+                    view! {
+                        <form class="scan" on:submit=move |ev| scan(ev, sample.clone())>
+                            <input
+                                type="text"
+                                node_ref=scan_input
+                                placeholder="Scan tube to confirm"
+                                prop:value=scanned
+                                on:input=move |ev| set_scanned.set(event_target_value(&ev))
+                            />
+                            <button type="submit">"Confirm"</button>
+                        </form>
+                    }
+                }
+            >
+                <div class="entry">
+                    <button
+                        class=move || if field.get() == Field::Volume { "field active" } else { "field" }
+                        on:click=move |_| set_field.set(Field::Volume)
+                    >
+                        <span class="field-label">"Volume (µL)"</span>
+                        <span class="field-value">{move || volume.get()}</span>
+                    </button>
+                    <button
+                        class=move || {
+                            if field.get() == Field::Concentration { "field active" } else { "field" }
+                        }
+                        on:click=move |_| set_field.set(Field::Concentration)
+                    >
+                        <span class="field-label">"Concentration (ng/µL)"</span>
+                        <span class="field-value">{move || concentration.get()}</span>
+                    </button>
+                </div>
+                <div class="numpad">
+                    {PadKey::LAYOUT
+                        .into_iter()
+                        .map(|k| view! { <button on:click=move |_| key(k)>{k.label()}</button> })
+                        .collect_view()}
+                    <button class="clear" on:click=move |_| key(PadKey::Clear)>
+                        {PadKey::Clear.label()}
+                    </button>
+                </div>
+                <div class="verdicts">
+                    {
+                        let sample = passing.clone();
+                        view! {
+                            <button class="pass" on:click=move |_| record(sample.clone(), None)>
+                                "Pass"
+                            </button>
+                        }
+                    }
+                    {FAIL_REASONS
+                        .into_iter()
+                        .map(|reason| {
+                            let sample = failing.clone();
+                            view! {
+                                <button class="fail" on:click=move |_| record(sample.clone(), Some(reason))>
+                                    {format!("Fail: {}", reason)}
+                                </button>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </Show>
+            <footer class="step-nav">
+                <button disabled=position == 0 on:click=move |_| go_to(position.saturating_sub(1))>
+                    "Previous"
+                </button>
+                <button on:click=move |_| print(printing.clone())>"Print label"</button>
+                <button on:click=move |_| go_to(position + 1)>"Skip"</button>
+            </footer>
+        }
+    };
+
+    let body = move || match samples.get() {
+        None => view! { <p class="loading">"Loading samples…"</p> }.into_any(),
+        Some(Err(e)) => view! { <p class="notice error">{e}</p> }.into_any(),
+        Some(Ok(list)) if list.is_empty() => {
+            view! { <p class="empty">"No samples in this project are awaiting QC."</p> }.into_any()
+        }
+        Some(Ok(list)) => {
+            let total = list.len();
+            match list.get(index.get()).cloned() {
+                Some(sample) => step(sample, index.get(), total).into_any(),
+                None => {
+                    let done = results.get();
+                    let passed = done.values().filter(|r| **r == "Passed").count();
+                    let failed = done.values().filter(|r| **r == "Failed").count();
+                    view! {
+                        <section class="sample-card">
+                            <h1>"Worksheet complete"</h1>
+                            <p>
+                                {format!(
+                                    "{} passed, {} failed, {} skipped",
+                                    passed,
+                                    failed,
+                                    total - passed - failed,
+                                )}
+                            </p>
+                        </section>
+                        <footer class="step-nav">
+                            <button on:click=move |_| go_to(0)>"Start over"</button>
+                        </footer>
+                    }
+                    .into_any()
+                }
+            }
+        }
+    };
+
+    view! {
+        <main class="worksheet">
+            {move || {
+                lock.get().flatten().map(|lock| {
+                    view! { <p class="notice locked" role="alert">{lock_banner(&lock)}</p> }
+                })
+            }}
+            {move || {
+                notice
+                    .get()
+                    .map(|(text, is_error)| {
+                        view! { <p class=if is_error { "notice error" } else { "notice" }>{text}</p> }
+                    })
+            }}
+            {move || match project() {
+                Some(_) => body().into_any(),
+                None => view! { <ProjectPicker /> }.into_any(),
+            }}
+        </main>
+    }
+}
+
+/// Asks which project's samples to work through.
+#[component]
+fn ProjectPicker() -> impl IntoView {
+    let navigate = use_navigate();
+    let (project, set_project) = signal(String::new());
+
+    let open = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        if let Ok(id) = project.get_untracked().trim().parse::<i32>() {
+            navigate(
+                &format!("/worksheet?project={}", id),
+                NavigateOptions::default(),
+            );
+        }
+    };
+
+    view! {
+        <form class="scan" on:submit=open>
+            <input
+                type="text"
+                inputmode="numeric"
+                placeholder="Project ID"
+                prop:value=project
+                on:input=move |ev| set_project.set(event_target_value(&ev))
+            />
+            <button type="submit">"Start"</button>
+        </form>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numpad_keeps_value_decimal() {
+        let mut value = String::new();
+        for key in [
+            PadKey::Point,
+            PadKey::Digit(5),
+            PadKey::Point,
+            PadKey::Digit(2),
+        ] {
+            value = press(&value, key);
+        }
+        assert_eq!(value, "0.52");
+        assert_eq!(press("0", PadKey::Digit(7)), "7");
+        assert_eq!(press("12.5", PadKey::Backspace), "12.");
+        assert_eq!(press("12.5", PadKey::Clear), "");
+        assert_eq!(press("12345678", PadKey::Digit(9)), "12345678");
+    }
+
+    #[test]
+    fn test_lock_banner_says_until_when() {
+        let mut lock = ProjectLockResponse {
+            reason: "PI review".to_string(),
+            locked_by: "manager".to_string(),
+            locked_at: chrono::Utc::now(),
+            unlock_at: None,
+        };
+        assert_eq!(
+            lock_banner(&lock),
+            "Locked by manager until it is unlocked: PI review. Only admins can record changes."
+        );
+
+        lock.unlock_at = "2024-06-01T09:00:00Z".parse().ok();
+        assert!(lock_banner(&lock).contains("until 2024-06-01 09:00 UTC"));
+    }
+
+    #[test]
+    fn test_barcode_match_ignores_case_and_whitespace() {
+        assert!(barcode_matches("SAM-0042", " sam-0042\n"));
+        assert!(!barcode_matches("SAM-0042", "SAM-0043"));
+    }
+}
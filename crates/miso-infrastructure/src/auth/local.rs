@@ -0,0 +1,203 @@
+//! Login for internal users, against the password hashes kept in the
+//! user table.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use miso_domain::entities::User;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::UserRepository;
+use tracing::{debug, instrument};
+
+use super::password::verify_password;
+use super::AuthProvider;
+
+/// Provider logging internal users in with [`authenticate`].
+#[derive(Clone)]
+pub struct LocalAuthProvider {
+    users: Arc<dyn UserRepository>,
+}
+
+impl LocalAuthProvider {
+    /// Creates a provider checking passwords against the given users.
+    pub fn new(users: Arc<dyn UserRepository>) -> Self {
+        Self { users }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LocalAuthProvider {
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, DomainError> {
+        authenticate(self.users.as_ref(), username, password).await
+    }
+}
+
+/// Checks an internal user's password and records the login.
+///
+/// Returns `None` for an unknown username, an inactive or LDAP user, or a
+/// wrong password alike, so callers can't reveal which usernames exist.
+#[instrument(skip(users, password))]
+pub async fn authenticate(
+    users: &dyn UserRepository,
+    username: &str,
+    password: &str,
+) -> Result<Option<User>, DomainError> {
+    let Some(mut user) = users.find_by_username(username).await? else {
+        debug!("Login for unknown user {}", username);
+        return Ok(None);
+    };
+
+    if !user.active || !user.internal {
+        debug!("Login for inactive or external user {}", username);
+        return Ok(None);
+    }
+
+    let matches = users
+        .find_password_hash(user.id)
+        .await?
+        .is_some_and(|hash| verify_password(password, &hash));
+    if !matches {
+        debug!("Wrong password for {}", username);
+        return Ok(None);
+    }
+
+    user.record_login();
+    users.save(&user).await?;
+
+    Ok(Some(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::{EntityId, Role};
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+    use crate::auth::password::hash_password;
+
+    #[derive(Default)]
+    struct InMemoryUsers {
+        users: Mutex<HashMap<EntityId, User>>,
+        hashes: Mutex<HashMap<EntityId, String>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for InMemoryUsers {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<User>, DomainError> {
+            Ok(self.users.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+            Ok(self
+                .users
+                .lock()
+                .unwrap()
+                .values()
+                .find(|u| u.username == username)
+                .cloned())
+        }
+
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+            Ok(self
+                .users
+                .lock()
+                .unwrap()
+                .values()
+                .find(|u| u.email == email)
+                .cloned())
+        }
+
+        async fn list(&self, _options: QueryOptions) -> Result<Vec<User>, DomainError> {
+            Ok(self.users.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn save(&self, user: &User) -> Result<EntityId, DomainError> {
+            self.users.lock().unwrap().insert(user.id, user.clone());
+            Ok(user.id)
+        }
+
+        async fn find_password_hash(&self, id: EntityId) -> Result<Option<String>, DomainError> {
+            Ok(self.hashes.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn set_password_hash(
+            &self,
+            id: EntityId,
+            password_hash: &str,
+        ) -> Result<(), DomainError> {
+            self.hashes
+                .lock()
+                .unwrap()
+                .insert(id, password_hash.to_string());
+            Ok(())
+        }
+
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.users.lock().unwrap().remove(&id);
+            Ok(())
+        }
+    }
+
+    async fn users() -> InMemoryUsers {
+        let users = InMemoryUsers::default();
+        let alice = User::new_internal(
+            1,
+            "alice".to_string(),
+            "Alice".to_string(),
+            "alice@example.org".to_string(),
+            Role::Technician,
+        );
+        let mut bob = alice.clone();
+        bob.id = 2;
+        bob.username = "bob".to_string();
+        bob.deactivate();
+        let mut carol = alice.clone();
+        carol.id = 3;
+        carol.username = "carol".to_string();
+        carol.internal = false;
+
+        for user in [alice, bob, carol] {
+            users.save(&user).await.unwrap();
+            users
+                .set_password_hash(user.id, &hash_password("secret").unwrap())
+                .await
+                .unwrap();
+        }
+        users
+    }
+
+    #[tokio::test]
+    async fn test_only_active_internal_users_with_the_right_password_log_in() {
+        let users = users().await;
+
+        let alice = authenticate(&users, "alice", "secret").await.unwrap();
+        assert_eq!(alice.map(|u| u.id), Some(1));
+        assert!(users
+            .find_by_id(1)
+            .await
+            .unwrap()
+            .unwrap()
+            .last_login_at
+            .is_some());
+
+        for (username, password) in [
+            ("alice", "wrong"),
+            ("bob", "secret"),
+            ("carol", "secret"),
+            ("dave", "secret"),
+        ] {
+            assert!(authenticate(&users, username, password)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+}
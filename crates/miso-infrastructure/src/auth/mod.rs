@@ -0,0 +1,32 @@
+//! Authentication support.
+//!
+//! Logins are checked by [`AuthProvider`]s: [`local::LocalAuthProvider`]
+//! for internal users, and [`LdapAuthProvider`] for directory users if a
+//! directory is configured. Single sign-on users log in at their identity
+//! provider instead, through [`OidcAuthProvider`]; either way external users
+//! are recorded by [`provision::sync_external_user`].
+//!
+//! [`LdapAuthProvider`]: crate::external::ldap::LdapAuthProvider
+//! [`OidcAuthProvider`]: crate::external::oidc::OidcAuthProvider
+
+pub mod local;
+pub mod password;
+pub mod provision;
+
+use async_trait::async_trait;
+use miso_domain::entities::User;
+use miso_domain::errors::DomainError;
+
+/// A way of checking a user's login.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Checks a username and password and returns the user they log in.
+    ///
+    /// Returns `None` if the provider doesn't know the user or the password
+    /// is wrong, so that the next provider can be tried.
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, DomainError>;
+}
@@ -0,0 +1,49 @@
+//! Password hashing for internal users.
+//!
+//! Passwords are hashed with Argon2id and a random salt. The hash is kept
+//! in PHC string format, which records the algorithm, parameters and salt
+//! alongside it, so hashes stay verifiable if the defaults change.
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use miso_domain::errors::DomainError;
+
+/// Hashes a password for storage.
+pub fn hash_password(password: &str) -> Result<String, DomainError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| DomainError::Validation(format!("Failed to hash password: {}", e)))
+}
+
+/// Returns true if `password` matches a stored hash. A hash that can't be
+/// read matches nothing.
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passwords_verify_against_their_hash() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("battery staple", &hash));
+        assert!(!verify_password("correct horse", "not a hash"));
+
+        // Salted, so the same password never hashes the same way twice
+        assert_ne!(hash_password("correct horse").unwrap(), hash);
+    }
+}
@@ -0,0 +1,168 @@
+//! Records of external users, kept up to date from the directory or
+//! identity provider that logs them in.
+
+use tracing::{debug, info, warn};
+
+use miso_domain::entities::{Role, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::UserRepository;
+
+/// A user an external provider has vouched for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalIdentity {
+    /// Username the user is known by here
+    pub username: String,
+    /// Display name, if the provider gives one
+    pub display_name: Option<String>,
+    /// Email address, if the provider gives one
+    pub email: Option<String>,
+    /// Role mapped from the user's groups
+    pub role: Role,
+}
+
+/// Creates or updates the record of an external user who has logged in.
+///
+/// Returns `None` if the username belongs to an internal user, whose
+/// account an external provider mustn't take over, or to a deactivated
+/// user.
+pub async fn sync_external_user(
+    users: &dyn UserRepository,
+    identity: ExternalIdentity,
+) -> Result<Option<User>, DomainError> {
+    let ExternalIdentity {
+        username,
+        display_name,
+        email,
+        role,
+    } = identity;
+    let display_name = display_name.unwrap_or_else(|| username.clone());
+    let email = email.unwrap_or_default();
+
+    let mut user = match users.find_by_username(&username).await? {
+        Some(user) if user.internal => {
+            warn!("External login for internal user {} refused", username);
+            return Ok(None);
+        }
+        Some(user) if !user.active => {
+            debug!("External login for inactive user {}", username);
+            return Ok(None);
+        }
+        Some(mut user) => {
+            user.display_name = display_name;
+            user.email = email;
+            if user.role != role {
+                info!("External groups changed {}'s role to {}", username, role);
+                user.set_role(role);
+            }
+            user
+        }
+        None => {
+            info!("Creating external user {} as {}", username, role);
+            User::new_external(0, username, display_name, email, role)
+        }
+    };
+
+    user.record_login();
+    user.id = users.save(&user).await?;
+
+    Ok(Some(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use miso_domain::entities::EntityId;
+    use miso_domain::repositories::QueryOptions;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct InMemoryUsers {
+        users: Mutex<Vec<User>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for InMemoryUsers {
+        async fn find_by_id(&self, id: EntityId) -> Result<Option<User>, DomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.id == id).cloned())
+        }
+        async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.username == username).cloned())
+        }
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.email == email).cloned())
+        }
+        async fn list(&self, _: QueryOptions) -> Result<Vec<User>, DomainError> {
+            Ok(self.users.lock().unwrap().clone())
+        }
+        async fn save(&self, user: &User) -> Result<EntityId, DomainError> {
+            let mut users = self.users.lock().unwrap();
+            let mut user = user.clone();
+            if user.id == 0 {
+                user.id = users.len() as EntityId + 1;
+            }
+            users.retain(|u| u.id != user.id);
+            users.push(user.clone());
+            Ok(user.id)
+        }
+        async fn find_password_hash(&self, _: EntityId) -> Result<Option<String>, DomainError> {
+            Ok(None)
+        }
+        async fn set_password_hash(&self, _: EntityId, _: &str) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+            self.users.lock().unwrap().retain(|u| u.id != id);
+            Ok(())
+        }
+    }
+
+    fn identity(username: &str, name: &str, role: Role) -> ExternalIdentity {
+        ExternalIdentity {
+            username: username.to_string(),
+            display_name: Some(name.to_string()),
+            email: Some(format!("{}@example.org", username)),
+            role,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_external_users_are_created_then_kept_up_to_date() {
+        let users = InMemoryUsers::default();
+
+        let created = sync_external_user(&users, identity("alice", "Alice", Role::Technician))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!created.internal);
+        assert!(created.last_login_at.is_some());
+
+        let updated = sync_external_user(&users, identity("alice", "Alice Smith", Role::Admin))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.display_name, "Alice Smith");
+        assert_eq!(updated.role, Role::Admin);
+
+        let bob = User::new_internal(
+            0,
+            "bob".to_string(),
+            "Bob".to_string(),
+            "bob@example.org".to_string(),
+            Role::Viewer,
+        );
+        users.save(&bob).await.unwrap();
+        assert!(
+            sync_external_user(&users, identity("bob", "Bob", Role::Admin))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
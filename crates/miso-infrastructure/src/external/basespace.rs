@@ -0,0 +1,375 @@
+//! Run source that reads finished runs from BaseSpace Sequence Hub.
+//!
+//! Runs are listed from `/v2/runs`, most recently modified first, paging
+//! until runs older than the last import are reached. Each finished run's
+//! `/v2/runs/{id}/sequencingstats` gives its read structure and per-lane
+//! cluster density, passing filter, Q30 and yield.
+//!
+//! Requests carry the configured access token. If BaseSpace turns it down
+//! as expired and app credentials with a refresh token are configured, a
+//! new access token is requested and the request is tried again once; the
+//! refreshed tokens are kept for later requests.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::{debug, info, instrument};
+
+use miso_domain::entities::RunStatus;
+use miso_domain::errors::DomainError;
+use miso_domain::run_import::{ExternalLaneMetrics, ExternalRun, RunSource};
+
+/// How long to wait for BaseSpace to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs requested per page.
+const PAGE_SIZE: usize = 100;
+
+/// BaseSpace Sequence Hub settings.
+#[derive(Debug, Clone)]
+pub struct BaseSpaceConfig {
+    /// API server, e.g. "https://api.basespace.illumina.com"
+    pub api_url: String,
+    /// Access token to start with
+    pub access_token: String,
+    /// App client ID, to refresh the access token with
+    pub client_id: Option<String>,
+    /// App client secret, to refresh the access token with
+    pub client_secret: Option<String>,
+    /// Refresh token; the access token is never refreshed without one
+    pub refresh_token: Option<String>,
+}
+
+/// Tokens currently in use, replaced when refreshed.
+struct Tokens {
+    access: String,
+    refresh: Option<String>,
+}
+
+/// Client that lists finished runs from BaseSpace.
+pub struct BaseSpaceClient {
+    config: BaseSpaceConfig,
+    http: reqwest::Client,
+    tokens: Mutex<Tokens>,
+}
+
+impl BaseSpaceClient {
+    /// Creates a client for the given account. No request is made until
+    /// runs are first listed.
+    pub fn new(config: BaseSpaceConfig) -> Self {
+        let tokens = Tokens {
+            access: config.access_token.clone(),
+            refresh: config.refresh_token.clone(),
+        };
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            tokens: Mutex::new(tokens),
+        }
+    }
+
+    /// Fetches a JSON document, refreshing the access token and trying
+    /// again once if it has expired.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, DomainError> {
+        let url = format!("{}{}", self.config.api_url.trim_end_matches('/'), path);
+
+        let mut refreshed = false;
+        loop {
+            let token = self.tokens().access.clone();
+            let response = self
+                .http
+                .get(&url)
+                .timeout(REQUEST_TIMEOUT)
+                .bearer_auth(token)
+                .send()
+                .await
+                .map_err(failed)?;
+
+            if response.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                self.refresh().await?;
+                refreshed = true;
+                continue;
+            }
+
+            return response
+                .error_for_status()
+                .map_err(failed)?
+                .json()
+                .await
+                .map_err(failed);
+        }
+    }
+
+    /// Exchanges the refresh token for a new access token.
+    async fn refresh(&self) -> Result<(), DomainError> {
+        let refresh_token = self.tokens().refresh.clone();
+        let (Some(client_id), Some(client_secret), Some(refresh_token)) = (
+            &self.config.client_id,
+            &self.config.client_secret,
+            refresh_token,
+        ) else {
+            return Err(failed("the access token was refused and can't be refreshed"));
+        };
+
+        let response: TokenResponse = self
+            .http
+            .post(format!(
+                "{}/v1pre3/oauthv2/token",
+                self.config.api_url.trim_end_matches('/')
+            ))
+            .timeout(REQUEST_TIMEOUT)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token.as_str()),
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(failed)?
+            .json()
+            .await
+            .map_err(failed)?;
+
+        let mut tokens = self.tokens();
+        tokens.access = response.access_token;
+        if let Some(refresh) = response.refresh_token {
+            tokens.refresh = Some(refresh);
+        }
+        info!("Refreshed BaseSpace access token");
+        Ok(())
+    }
+
+    fn tokens(&self) -> std::sync::MutexGuard<'_, Tokens> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl RunSource for BaseSpaceClient {
+    fn name(&self) -> &str {
+        "basespace"
+    }
+
+    #[instrument(skip(self))]
+    async fn finished_runs(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ExternalRun>, DomainError> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page: RunPage = self
+                .get(&format!(
+                    "/v2/runs?sortby=DateModified&sortdir=Desc&offset={}&limit={}",
+                    offset, PAGE_SIZE
+                ))
+                .await?;
+            let count = page.items.len();
+            let mut reached_since = false;
+            for item in page.items {
+                if since.is_some_and(|since| item.date_modified.is_some_and(|m| m < since)) {
+                    reached_since = true;
+                    break;
+                }
+                items.push(item);
+            }
+
+            offset += count;
+            if reached_since || count < PAGE_SIZE || page.paging.is_some_and(|p| offset >= p.total_count) {
+                break;
+            }
+        }
+
+        let mut runs = Vec::new();
+        for item in items {
+            if finished_status(&item.status).is_none() {
+                continue;
+            }
+            let stats: SequencingStats = self
+                .get(&format!("/v2/runs/{}/sequencingstats", item.id))
+                .await?;
+            if let Some(run) = external_run(item, stats) {
+                runs.push(run);
+            }
+        }
+
+        debug!("BaseSpace reported {} finished runs", runs.len());
+        Ok(runs)
+    }
+}
+
+/// A page of `/v2/runs`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RunPage {
+    #[serde(default)]
+    items: Vec<RunItem>,
+    paging: Option<Paging>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Paging {
+    total_count: usize,
+}
+
+/// A run as `/v2/runs` lists it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RunItem {
+    id: String,
+    name: String,
+    status: String,
+    date_created: Option<DateTime<Utc>>,
+    date_modified: Option<DateTime<Utc>>,
+    flowcell_barcode: Option<String>,
+    instrument: Option<Instrument>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Instrument {
+    name: Option<String>,
+    serial_number: Option<String>,
+}
+
+/// A run's `/v2/runs/{id}/sequencingstats`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SequencingStats {
+    num_cycles_read1: Option<u32>,
+    num_cycles_read2: Option<u32>,
+    #[serde(default)]
+    lane_stats: Vec<LaneStats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LaneStats {
+    lane_number: u8,
+    cluster_density: Option<f64>,
+    percent_pf: Option<f64>,
+    percent_gt_q30: Option<f64>,
+    /// Gigabases
+    yield_total: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+/// Maps a BaseSpace run status to the status a finished run ends in.
+fn finished_status(status: &str) -> Option<RunStatus> {
+    match status {
+        "Complete" => Some(RunStatus::Completed),
+        "Failed" | "Needs Attention" => Some(RunStatus::Failed),
+        "Stopped" => Some(RunStatus::Stopped),
+        _ => None,
+    }
+}
+
+/// Maps a finished BaseSpace run and its stats to an external run.
+fn external_run(item: RunItem, stats: SequencingStats) -> Option<ExternalRun> {
+    let status = finished_status(&item.status)?;
+    let read_length = match (stats.num_cycles_read1, stats.num_cycles_read2) {
+        (Some(read1), Some(read2)) if read1 == read2 => Some(format!("2x{}", read1)),
+        (Some(read1), Some(read2)) if read2 > 0 => Some(format!("{}+{}", read1, read2)),
+        (Some(read1), _) if read1 > 0 => Some(format!("1x{}", read1)),
+        _ => None,
+    };
+    let (instrument_name, instrument_serial) = item
+        .instrument
+        .map_or((None, None), |i| (i.name, i.serial_number));
+
+    Some(ExternalRun {
+        external_id: item.id,
+        name: item.name,
+        instrument_serial,
+        instrument_name,
+        flow_cell_barcode: item.flowcell_barcode,
+        status,
+        read_length,
+        started_at: item.date_created,
+        completed_at: item.date_modified,
+        lanes: stats
+            .lane_stats
+            .into_iter()
+            .map(|lane| ExternalLaneMetrics {
+                lane: lane.lane_number,
+                cluster_density: lane.cluster_density,
+                pass_filter_percent: lane.percent_pf,
+                q30_percent: lane.percent_gt_q30,
+                yield_gbp: lane.yield_total,
+            })
+            .collect(),
+    })
+}
+
+fn failed(e: impl std::fmt::Display) -> DomainError {
+    DomainError::Validation(format!("BaseSpace request failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maps_finished_run() {
+        let page: RunPage = serde_json::from_str(
+            r#"{
+                "Items": [
+                    {
+                        "Id": "273123",
+                        "Name": "240105_A01234_0042_BHXXXXDSX7",
+                        "ExperimentName": "Exome batch 12",
+                        "Status": "Complete",
+                        "DateCreated": "2024-01-05T10:00:00.0000000Z",
+                        "DateModified": "2024-01-07T02:30:00.0000000Z",
+                        "FlowcellBarcode": "HXXXXDSX7",
+                        "Instrument": {"Name": "NovaSeq A", "SerialNumber": "A01234"}
+                    },
+                    {
+                        "Id": "273124",
+                        "Name": "240106_A01234_0043_AHYYYYDSX7",
+                        "Status": "Running"
+                    }
+                ],
+                "Paging": {"DisplayedCount": 2, "TotalCount": 2, "Offset": 0, "Limit": 100}
+            }"#,
+        )
+        .unwrap();
+        let stats: SequencingStats = serde_json::from_str(
+            r#"{
+                "NumCyclesRead1": 151,
+                "NumCyclesRead2": 151,
+                "NumCyclesIndex1": 10,
+                "LaneStats": [
+                    {"LaneNumber": 1, "ClusterDensity": 2850.5, "PercentPf": 78.2,
+                     "PercentGtQ30": 92.1, "YieldTotal": 410.3}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut items = page.items.into_iter();
+        let run = external_run(items.next().unwrap(), stats).unwrap();
+        assert_eq!(run.name, "240105_A01234_0042_BHXXXXDSX7");
+        assert_eq!(run.instrument_serial.as_deref(), Some("A01234"));
+        assert_eq!(run.flow_cell_barcode.as_deref(), Some("HXXXXDSX7"));
+        assert_eq!(run.status, RunStatus::Completed);
+        assert_eq!(run.read_length.as_deref(), Some("2x151"));
+        assert_eq!(run.lanes[0].q30_percent, Some(92.1));
+        assert_eq!(run.lanes[0].yield_gbp, Some(410.3));
+
+        assert!(external_run(items.next().unwrap(), SequencingStats::default()).is_none());
+    }
+}
@@ -0,0 +1,273 @@
+//! Login for LDAP users against a directory server.
+//!
+//! A login searches the directory for the user's entry, binding first with
+//! the service account if one is configured, then binds as that entry with
+//! the password given. The user's role comes from the directory groups
+//! they belong to, mapped in configuration; users in none of the mapped
+//! groups can't log in. A user record is created on first login and kept
+//! up to date with the directory on each later one.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ldap3::{ldap_escape, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{Role, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::UserRepository;
+
+use crate::auth::provision::{sync_external_user, ExternalIdentity};
+use crate::auth::AuthProvider;
+
+/// Attributes read from a user's entry.
+const ATTRIBUTES: &[&str] = &["cn", "displayName", "mail", "memberOf"];
+
+/// How long to wait for the directory server to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Directory server settings.
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    /// Server URL, e.g. "ldaps://ldap.example.org"
+    pub url: String,
+    /// Whether to upgrade an `ldap://` connection with StartTLS
+    pub starttls: bool,
+    /// Service account DN and password to search with; the search is
+    /// anonymous if unset
+    pub bind_credentials: Option<(String, String)>,
+    /// Where to search for users, e.g. "ou=people,dc=example,dc=org"
+    pub base_dn: String,
+    /// Search filter, in which `{username}` is replaced by the escaped
+    /// username, e.g. "(uid={username})"
+    pub user_filter: String,
+    /// Groups whose members get each role, by common name
+    pub group_roles: HashMap<Role, Vec<String>>,
+}
+
+impl LdapConfig {
+    /// Returns the highest role any of the groups is mapped to.
+    ///
+    /// Groups are given as DNs, as listed in `memberOf`, and matched on
+    /// their common name, ignoring case.
+    pub fn role_for(&self, groups: &[String]) -> Option<Role> {
+        let names: Vec<String> = groups.iter().map(|dn| group_name(dn)).collect();
+        self.group_roles
+            .iter()
+            .filter(|(_, mapped)| {
+                mapped
+                    .iter()
+                    .any(|group| names.iter().any(|name| name.eq_ignore_ascii_case(group)))
+            })
+            .map(|(role, _)| *role)
+            .max_by_key(Role::level)
+    }
+}
+
+/// A user's directory entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryUser {
+    /// The entry's DN
+    pub dn: String,
+    /// Display name, if the entry has one
+    pub display_name: Option<String>,
+    /// Email address, if the entry has one
+    pub email: Option<String>,
+    /// DNs of the groups the user belongs to
+    pub groups: Vec<String>,
+}
+
+impl From<SearchEntry> for DirectoryUser {
+    fn from(mut entry: SearchEntry) -> Self {
+        let mut first = |attribute: &str| {
+            entry
+                .attrs
+                .remove(attribute)
+                .and_then(|values| values.into_iter().next())
+        };
+        let display_name = first("displayName").or_else(|| first("cn"));
+        let email = first("mail");
+
+        Self {
+            groups: entry.attrs.remove("memberOf").unwrap_or_default(),
+            dn: entry.dn,
+            display_name,
+            email,
+        }
+    }
+}
+
+/// Client binding and searching against a directory server.
+#[derive(Debug, Clone)]
+pub struct LdapClient {
+    config: LdapConfig,
+}
+
+impl LdapClient {
+    /// Creates a client for the given server. No connection is made until
+    /// the first login.
+    pub fn new(config: LdapConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the client's settings.
+    pub fn config(&self) -> &LdapConfig {
+        &self.config
+    }
+
+    /// Finds a user's entry and checks their password by binding as it.
+    ///
+    /// Returns `None` if no single entry matches or the password is wrong.
+    #[instrument(skip(self, password))]
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, DomainError> {
+        // An empty password would make an unauthenticated bind, which
+        // servers accept
+        if password.is_empty() {
+            return Ok(None);
+        }
+
+        self.bind_as(username, password)
+            .await
+            .map_err(|e| DomainError::Validation(format!("LDAP login failed: {}", e)))
+    }
+
+    async fn bind_as(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, ldap3::LdapError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(CONNECT_TIMEOUT)
+            .set_starttls(self.config.starttls);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.config.url).await?;
+        ldap3::drive!(conn);
+
+        if let Some((dn, bind_password)) = &self.config.bind_credentials {
+            ldap.simple_bind(dn, bind_password).await?.success()?;
+        }
+
+        let filter = self
+            .config
+            .user_filter
+            .replace("{username}", &ldap_escape(username));
+        let (mut entries, _) = ldap
+            .search(&self.config.base_dn, Scope::Subtree, &filter, ATTRIBUTES)
+            .await?
+            .success()?;
+        if entries.len() != 1 {
+            debug!("{} directory entries match {}", entries.len(), username);
+            ldap.unbind().await?;
+            return Ok(None);
+        }
+        let entry = SearchEntry::construct(entries.remove(0));
+
+        let bound = ldap.simple_bind(&entry.dn, password).await?.rc == 0;
+        ldap.unbind().await?;
+        if !bound {
+            debug!("Wrong directory password for {}", username);
+            return Ok(None);
+        }
+
+        Ok(Some(entry.into()))
+    }
+}
+
+/// Provider logging LDAP users in against the directory.
+#[derive(Clone)]
+pub struct LdapAuthProvider {
+    client: LdapClient,
+    users: Arc<dyn UserRepository>,
+}
+
+impl LdapAuthProvider {
+    /// Creates a provider for the given directory, recording its users in
+    /// the given repository.
+    pub fn new(client: LdapClient, users: Arc<dyn UserRepository>) -> Self {
+        Self { client, users }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LdapAuthProvider {
+    #[instrument(skip(self, password))]
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, DomainError> {
+        let Some(entry) = self.client.authenticate(username, password).await? else {
+            return Ok(None);
+        };
+        let Some(role) = self.client.config().role_for(&entry.groups) else {
+            debug!("Directory user {} is in no mapped group", username);
+            return Ok(None);
+        };
+
+        let identity = ExternalIdentity {
+            username: username.to_string(),
+            display_name: entry.display_name,
+            email: entry.email,
+            role,
+        };
+        sync_external_user(self.users.as_ref(), identity).await
+    }
+}
+
+/// Returns the common name of a group DN, e.g. "lims-admins" for
+/// "cn=lims-admins,ou=groups,dc=example,dc=org", or the DN itself if it
+/// doesn't start with one.
+fn group_name(dn: &str) -> String {
+    dn.split(',')
+        .next()
+        .and_then(|rdn| rdn.split_once('='))
+        .filter(|(attribute, _)| attribute.trim().eq_ignore_ascii_case("cn"))
+        .map(|(_, name)| name.trim().to_string())
+        .unwrap_or_else(|| dn.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LdapConfig {
+        LdapConfig {
+            url: "ldap://localhost".to_string(),
+            starttls: false,
+            bind_credentials: None,
+            base_dn: "ou=people,dc=example,dc=org".to_string(),
+            user_filter: "(uid={username})".to_string(),
+            group_roles: HashMap::from([
+                (Role::Technician, vec!["lab".to_string()]),
+                (Role::Admin, vec!["LIMS-Admins".to_string()]),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_highest_mapped_group_gives_the_role() {
+        let config = config();
+        let groups = |dns: &[&str]| dns.iter().map(|dn| dn.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            config.role_for(&groups(&["cn=lab,ou=groups,dc=example,dc=org"])),
+            Some(Role::Technician)
+        );
+        assert_eq!(
+            config.role_for(&groups(&[
+                "cn=lab,ou=groups,dc=example,dc=org",
+                "CN=lims-admins,ou=groups,dc=example,dc=org",
+            ])),
+            Some(Role::Admin)
+        );
+        assert_eq!(
+            config.role_for(&groups(&["cn=finance,ou=groups,dc=example,dc=org"])),
+            None
+        );
+    }
+}
@@ -0,0 +1,14 @@
+//! Clients for external services.
+//!
+//! Provides:
+//! - BaseSpace Sequence Hub run import
+//! - LDAP directory login
+//! - OpenID Connect single sign-on
+//! - Sample sheet uploads to sequencers
+//! - Courier shipment tracking
+
+pub mod basespace;
+pub mod ldap;
+pub mod oidc;
+pub mod run_planning;
+pub mod shipment_tracking;
@@ -0,0 +1,480 @@
+//! Single sign-on through an OpenID Connect provider.
+//!
+//! Logins use the authorization code flow. The user is sent to the
+//! provider's authorization endpoint with a random `state` and `nonce`,
+//! and comes back with a code, which is exchanged at the token endpoint for
+//! an ID token. The ID token's signature is checked against the provider's
+//! published keys, along with its issuer, audience, expiry and nonce.
+//!
+//! The user's role comes from a claim listing their groups, mapped in
+//! configuration; users in none of the mapped groups can't log in. As with
+//! LDAP, a user record is created on first login and kept up to date with
+//! the provider's claims on each later one.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{Role, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::UserRepository;
+
+use crate::auth::provision::{sync_external_user, ExternalIdentity};
+
+/// How long to wait for the provider to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a user has to log in at the provider and come back.
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// OpenID Connect provider settings.
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL, under which `/.well-known/openid-configuration` is
+    /// published, e.g. "https://login.example.org/realms/lab"
+    pub issuer_url: String,
+    /// Client ID registered with the provider
+    pub client_id: String,
+    /// Client secret registered with the provider
+    pub client_secret: String,
+    /// Where the provider sends users back to, i.e. this server's
+    /// `/api/v1/auth/oidc/callback`
+    pub redirect_url: String,
+    /// Scopes to request; "openid" is always requested
+    pub scopes: Vec<String>,
+    /// Claim holding the username, e.g. "preferred_username"
+    pub username_claim: String,
+    /// Claim listing the user's groups, e.g. "groups"
+    pub groups_claim: String,
+    /// Groups whose members get each role
+    pub group_roles: HashMap<Role, Vec<String>>,
+}
+
+impl OidcConfig {
+    /// Returns the highest role any of the groups is mapped to, matching
+    /// group names ignoring case.
+    pub fn role_for(&self, groups: &[String]) -> Option<Role> {
+        self.group_roles
+            .iter()
+            .filter(|(_, mapped)| {
+                mapped
+                    .iter()
+                    .any(|group| groups.iter().any(|g| g.eq_ignore_ascii_case(group)))
+            })
+            .map(|(role, _)| *role)
+            .max_by_key(Role::level)
+    }
+}
+
+/// The parts of the provider's discovery document used here.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+/// Token endpoint response.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Claims of a validated ID token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    /// The provider's identifier for the user
+    pub sub: String,
+    /// Nonce sent with the authorization request
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// Full name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Email address
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Every other claim
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+impl IdTokenClaims {
+    /// Returns a claim's value if it is a string.
+    pub fn string(&self, claim: &str) -> Option<String> {
+        match claim {
+            "sub" => Some(self.sub.clone()),
+            "name" => self.name.clone(),
+            "email" => self.email.clone(),
+            _ => self.other.get(claim)?.as_str().map(str::to_string),
+        }
+    }
+
+    /// Returns a claim's values if it is a list of strings, or its value
+    /// if it is a single string.
+    pub fn strings(&self, claim: &str) -> Vec<String> {
+        match self.other.get(claim) {
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            Some(serde_json::Value::String(value)) => vec![value.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Client for an OpenID Connect provider.
+///
+/// The provider's discovery document is fetched on first use, and its
+/// signing keys then and whenever a token is signed with a key not yet
+/// seen, so that the provider can rotate keys.
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    metadata: OnceCell<ProviderMetadata>,
+    keys: RwLock<JwkSet>,
+}
+
+impl OidcClient {
+    /// Creates a client for the given provider. No request is made until
+    /// the first login.
+    pub fn new(config: OidcConfig) -> Result<Self, DomainError> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(failed)?;
+
+        Ok(Self {
+            config,
+            http,
+            metadata: OnceCell::new(),
+            keys: RwLock::new(JwkSet { keys: Vec::new() }),
+        })
+    }
+
+    /// Returns the client's settings.
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Returns the URL to send a user to for them to log in at the
+    /// provider.
+    pub async fn authorization_url(&self, state: &str, nonce: &str) -> Result<String, DomainError> {
+        let metadata = self.metadata().await?;
+
+        let mut scopes = vec!["openid"];
+        scopes.extend(
+            self.config
+                .scopes
+                .iter()
+                .map(String::as_str)
+                .filter(|s| *s != "openid"),
+        );
+        let url = reqwest::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", &self.config.client_id),
+                ("redirect_uri", &self.config.redirect_url),
+                ("scope", &scopes.join(" ")),
+                ("state", state),
+                ("nonce", nonce),
+            ],
+        )
+        .map_err(failed)?;
+
+        Ok(url.into())
+    }
+
+    /// Exchanges an authorization code for the user's validated ID token
+    /// claims.
+    #[instrument(skip(self, code, nonce))]
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, DomainError> {
+        let metadata = self.metadata().await?;
+
+        let response: TokenResponse = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_url),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(failed)?
+            .json()
+            .await
+            .map_err(failed)?;
+
+        self.validate_id_token(&response.id_token, nonce).await
+    }
+
+    /// Checks an ID token's signature, issuer, audience, expiry and nonce.
+    async fn validate_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, DomainError> {
+        let metadata = self.metadata().await?;
+        let header = decode_header(id_token).map_err(failed)?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(failed("ID tokens must be signed with a published key"));
+        }
+        let kid = header
+            .kid
+            .ok_or_else(|| failed("ID token names no signing key"))?;
+
+        let mut key = self.decoding_key(&kid)?;
+        if key.is_none() {
+            self.refresh_keys(&metadata.jwks_uri).await?;
+            key = self.decoding_key(&kid)?;
+        }
+        let key = key.ok_or_else(|| failed(format!("unknown signing key {}", kid)))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(failed)?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(failed("ID token nonce doesn't match the login"));
+        }
+        Ok(claims)
+    }
+
+    fn decoding_key(&self, kid: &str) -> Result<Option<DecodingKey>, DomainError> {
+        let keys = self.keys.read().unwrap();
+        keys.find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(failed)
+    }
+
+    async fn refresh_keys(&self, jwks_uri: &str) -> Result<(), DomainError> {
+        debug!("Fetching signing keys from {}", jwks_uri);
+        let keys: JwkSet = self.get_json(jwks_uri).await?;
+        *self.keys.write().unwrap() = keys;
+        Ok(())
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, DomainError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer_url.trim_end_matches('/')
+                );
+                debug!("Fetching provider metadata from {}", url);
+                self.get_json(&url).await
+            })
+            .await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, DomainError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(failed)?
+            .json()
+            .await
+            .map_err(failed)
+    }
+}
+
+/// Logins started at the provider and not yet finished, by state.
+///
+/// Like revoked tokens, pending logins are held in memory: a login must
+/// come back to the server that started it, before it restarts.
+#[derive(Debug, Default)]
+struct PendingLogins {
+    logins: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl PendingLogins {
+    /// Records a login's nonce under its state, forgetting logins that
+    /// have timed out.
+    fn insert(&self, state: String, nonce: String) {
+        let now = Instant::now();
+        let mut logins = self.logins.lock().unwrap();
+        logins.retain(|_, (_, expires_at)| *expires_at > now);
+        logins.insert(state, (nonce, now + LOGIN_TIMEOUT));
+    }
+
+    /// Returns the nonce of a login that hasn't timed out, which can then
+    /// not be finished again.
+    fn take(&self, state: &str) -> Option<String> {
+        let (nonce, expires_at) = self.logins.lock().unwrap().remove(state)?;
+        (expires_at > Instant::now()).then_some(nonce)
+    }
+}
+
+/// Provider logging users in through an OpenID Connect provider.
+pub struct OidcAuthProvider {
+    client: OidcClient,
+    users: Arc<dyn UserRepository>,
+    pending: PendingLogins,
+}
+
+impl OidcAuthProvider {
+    /// Creates a provider for the given client, recording its users in
+    /// the given repository.
+    pub fn new(client: OidcClient, users: Arc<dyn UserRepository>) -> Self {
+        Self {
+            client,
+            users,
+            pending: PendingLogins::default(),
+        }
+    }
+
+    /// Starts a login, returning the URL to send the user to.
+    pub async fn start_login(&self) -> Result<String, DomainError> {
+        let state = uuid::Uuid::new_v4().simple().to_string();
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let url = self.client.authorization_url(&state, &nonce).await?;
+
+        self.pending.insert(state, nonce);
+        Ok(url)
+    }
+
+    /// Finishes a login the provider has sent the user back from.
+    ///
+    /// Returns `None` if the login is unknown or timed out, or the user is
+    /// in no mapped group, an internal user or deactivated.
+    #[instrument(skip(self, code, state))]
+    pub async fn finish_login(&self, code: &str, state: &str) -> Result<Option<User>, DomainError> {
+        let Some(nonce) = self.pending.take(state) else {
+            debug!("Unknown or timed out single sign-on login");
+            return Ok(None);
+        };
+        let claims = self.client.exchange_code(code, &nonce).await?;
+
+        let config = self.client.config();
+        let Some(username) = claims.string(&config.username_claim) else {
+            return Err(failed(format!(
+                "ID token has no {} claim",
+                config.username_claim
+            )));
+        };
+        let Some(role) = config.role_for(&claims.strings(&config.groups_claim)) else {
+            debug!("Single sign-on user {} is in no mapped group", username);
+            return Ok(None);
+        };
+
+        let identity = ExternalIdentity {
+            username,
+            display_name: claims.name,
+            email: claims.email,
+            role,
+        };
+        sync_external_user(self.users.as_ref(), identity).await
+    }
+}
+
+fn failed(e: impl std::fmt::Display) -> DomainError {
+    DomainError::Validation(format!("Single sign-on failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> OidcConfig {
+        OidcConfig {
+            issuer_url: "https://login.example.org/realms/lab".to_string(),
+            client_id: "miso".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_url: "https://miso.example.org/api/v1/auth/oidc/callback".to_string(),
+            scopes: vec!["profile".to_string(), "email".to_string()],
+            username_claim: "preferred_username".to_string(),
+            groups_claim: "groups".to_string(),
+            group_roles: HashMap::from([
+                (Role::Technician, vec!["lab".to_string()]),
+                (Role::Admin, vec!["LIMS-Admins".to_string()]),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_claims_give_the_username_and_role() {
+        let config = config();
+        let claims: IdTokenClaims = serde_json::from_value(serde_json::json!({
+            "sub": "f4c1",
+            "nonce": "n",
+            "email": "alice@example.org",
+            "preferred_username": "alice",
+            "groups": ["lab", "lims-admins"],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            claims.string("preferred_username").as_deref(),
+            Some("alice")
+        );
+        assert_eq!(claims.string("email").as_deref(), Some("alice@example.org"));
+        assert_eq!(
+            config.role_for(&claims.strings("groups")),
+            Some(Role::Admin)
+        );
+        assert!(claims.strings("roles").is_empty());
+        assert_eq!(config.role_for(&["finance".to_string()]), None);
+    }
+
+    #[tokio::test]
+    async fn test_authorization_url_carries_the_login() {
+        let client = OidcClient {
+            metadata: OnceCell::new_with(Some(ProviderMetadata {
+                issuer: "https://login.example.org/realms/lab".to_string(),
+                authorization_endpoint: "https://login.example.org/auth".to_string(),
+                token_endpoint: "https://login.example.org/token".to_string(),
+                jwks_uri: "https://login.example.org/certs".to_string(),
+            })),
+            ..OidcClient::new(config()).unwrap()
+        };
+
+        let url = client.authorization_url("s1", "n1").await.unwrap();
+        let url = reqwest::Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], "miso");
+        assert_eq!(params["scope"], "openid profile email");
+        assert_eq!(params["state"], "s1");
+        assert_eq!(params["nonce"], "n1");
+    }
+
+    #[test]
+    fn test_pending_logins_finish_once() {
+        let pending = PendingLogins::default();
+        pending.insert("s1".to_string(), "n1".to_string());
+        assert_eq!(pending.take("s1").as_deref(), Some("n1"));
+        assert_eq!(pending.take("s1"), None);
+
+        pending.logins.lock().unwrap().insert(
+            "s2".to_string(),
+            ("n2".to_string(), Instant::now() - Duration::from_secs(1)),
+        );
+        assert_eq!(pending.take("s2"), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Run planner that uploads sample sheets to instruments over HTTP.
+//!
+//! Illumina's Local Run Manager runs on each instrument's control computer,
+//! and BaseSpace run planning is a single cloud endpoint, so the upload URL
+//! is a template filled in per sequencer: `{ip_address}`, `{serial_number}`
+//! and `{name}` are replaced with the sequencer's own. The sample sheet is
+//! posted as the CSV body, with its file name in the Content-Disposition
+//! header, and an access token if one is configured.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header;
+use tracing::{info, instrument};
+
+use miso_domain::entities::Sequencer;
+use miso_domain::errors::DomainError;
+use miso_domain::run_planning::RunPlanner;
+
+/// How long to wait for the instrument to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run planning endpoint settings.
+#[derive(Debug, Clone)]
+pub struct RunPlanningConfig {
+    /// Upload URL template, e.g.
+    /// "http://{ip_address}/api/v1/samplesheets"
+    pub url: String,
+    /// Bearer token sent with each upload, e.g. a BaseSpace access token
+    pub token: Option<String>,
+}
+
+impl RunPlanningConfig {
+    /// Fills in the upload URL for a sequencer.
+    ///
+    /// Fails if the template names a detail the sequencer has no record
+    /// of.
+    pub fn url_for(&self, sequencer: &Sequencer) -> Result<String, DomainError> {
+        let mut url = self.url.clone();
+        for (placeholder, value) in [
+            ("{ip_address}", sequencer.ip_address.as_deref()),
+            ("{serial_number}", sequencer.serial_number.as_deref()),
+            ("{name}", Some(sequencer.name.as_str())),
+        ] {
+            if !url.contains(placeholder) {
+                continue;
+            }
+            let value = value.ok_or_else(|| {
+                DomainError::Validation(format!(
+                    "Sequencer {} has no {} to send its sample sheet to",
+                    sequencer.name,
+                    placeholder.trim_matches(['{', '}']).replace('_', " ")
+                ))
+            })?;
+            url = url.replace(placeholder, value);
+        }
+        Ok(url)
+    }
+}
+
+/// Run planner that posts sample sheets to a configured endpoint.
+#[derive(Debug, Clone)]
+pub struct HttpRunPlanner {
+    config: RunPlanningConfig,
+    http: reqwest::Client,
+}
+
+impl HttpRunPlanner {
+    /// Creates a planner for the given endpoint. No request is made until
+    /// the first sample sheet is pushed.
+    pub fn new(config: RunPlanningConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl RunPlanner for HttpRunPlanner {
+    #[instrument(skip(self, sequencer, contents), fields(sequencer = %sequencer.name))]
+    async fn push_sample_sheet(
+        &self,
+        sequencer: &Sequencer,
+        file_name: &str,
+        contents: &str,
+    ) -> Result<(), DomainError> {
+        let url = self.config.url_for(sequencer)?;
+
+        let mut request = self
+            .http
+            .post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .header(header::CONTENT_TYPE, "text/csv")
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            )
+            .body(contents.to_string());
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+
+        request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| {
+                DomainError::Validation(format!(
+                    "Sending the sample sheet to {} failed: {}",
+                    sequencer.name, e
+                ))
+            })?;
+
+        info!("Sent sample sheet {} to {}", file_name, url);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{InstrumentModel, Platform};
+
+    fn sequencer() -> Sequencer {
+        let model = InstrumentModel::new(Platform::Illumina, "NovaSeq 6000".to_string(), 4);
+        let mut sequencer = Sequencer::new(1, "NOVA1".to_string(), model);
+        sequencer.ip_address = Some("10.0.0.12".to_string());
+        sequencer
+    }
+
+    #[test]
+    fn test_url_for_fills_in_sequencer() {
+        let config = RunPlanningConfig {
+            url: "http://{ip_address}/api/{name}/samplesheets".to_string(),
+            token: None,
+        };
+        assert_eq!(
+            config.url_for(&sequencer()).unwrap(),
+            "http://10.0.0.12/api/NOVA1/samplesheets"
+        );
+
+        let config = RunPlanningConfig {
+            url: "https://example.org/runs?instrument={serial_number}".to_string(),
+            token: None,
+        };
+        let error = config.url_for(&sequencer()).unwrap_err();
+        assert!(error.to_string().contains("serial number"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Shipment tracker that asks a courier tracking service over HTTP.
+//!
+//! Couriers and tracking aggregators each have their own API, so the
+//! lookup URL is a template: `{courier}` and `{tracking_number}` are
+//! replaced with the transfer's own. The service is expected to answer
+//! with a JSON object whose `status` is one of "pending", "in_transit",
+//! "out_for_delivery", "delivered" or "exception", and with 404 for a
+//! shipment it doesn't know yet.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tracing::{debug, instrument, warn};
+
+use miso_domain::entities::ShipmentStatus;
+use miso_domain::errors::DomainError;
+use miso_domain::shipments::ShipmentTracker;
+
+/// How long to wait for the tracking service to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracking service settings.
+#[derive(Debug, Clone)]
+pub struct ShipmentTrackingConfig {
+    /// Lookup URL template, e.g.
+    /// "https://tracking.example.org/v1/{courier}/{tracking_number}"
+    pub url: String,
+    /// Bearer token sent with each lookup
+    pub token: Option<String>,
+}
+
+impl ShipmentTrackingConfig {
+    /// Fills in the lookup URL for a shipment.
+    pub fn url_for(&self, courier: &str, tracking_number: &str) -> String {
+        self.url
+            .replace("{courier}", &encode(&courier.to_lowercase()))
+            .replace("{tracking_number}", &encode(tracking_number))
+    }
+}
+
+/// Shipment tracker that looks shipments up at a configured endpoint.
+#[derive(Debug, Clone)]
+pub struct HttpShipmentTracker {
+    config: ShipmentTrackingConfig,
+    http: reqwest::Client,
+}
+
+impl HttpShipmentTracker {
+    /// Creates a tracker for the given endpoint. No request is made until
+    /// the first shipment is looked up.
+    pub fn new(config: ShipmentTrackingConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+/// The part of the tracking service's answer that is used.
+#[derive(Debug, Deserialize)]
+struct TrackingResponse {
+    status: Option<String>,
+}
+
+#[async_trait]
+impl ShipmentTracker for HttpShipmentTracker {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    #[instrument(skip(self))]
+    async fn status(
+        &self,
+        courier: &str,
+        tracking_number: &str,
+    ) -> Result<Option<ShipmentStatus>, DomainError> {
+        let url = self.config.url_for(courier, tracking_number);
+
+        let mut request = self.http.get(&url).timeout(REQUEST_TIMEOUT);
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(failed)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            debug!("{} doesn't know {} yet", courier, tracking_number);
+            return Ok(None);
+        }
+
+        let tracking: TrackingResponse = response
+            .error_for_status()
+            .map_err(failed)?
+            .json()
+            .await
+            .map_err(failed)?;
+
+        Ok(tracking.status.and_then(|status| match status.parse() {
+            Ok(status) => Some(status),
+            Err(e) => {
+                warn!("Ignoring status of {} {}: {}", courier, tracking_number, e);
+                None
+            }
+        }))
+    }
+}
+
+/// Percent-encodes everything but unreserved URL characters.
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn failed(e: impl std::fmt::Display) -> DomainError {
+    DomainError::Validation(format!("Shipment tracking request failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_for_fills_in_shipment() {
+        let config = ShipmentTrackingConfig {
+            url: "https://tracking.example.org/v1/{courier}/{tracking_number}".to_string(),
+            token: None,
+        };
+        assert_eq!(
+            config.url_for("Royal Mail", "AB 123/4"),
+            "https://tracking.example.org/v1/royal%20mail/AB%20123%2F4"
+        );
+    }
+}
@@ -3,9 +3,12 @@
 //! Async TCP client for Zebra printers using ZPL (Zebra Programming Language).
 //! Supports printing labels for samples, libraries, pools, and boxes.
 
+use async_trait::async_trait;
+use miso_domain::errors::DomainError;
+use miso_domain::labels::{Label, LabelPrinter, PrintedJob};
 use std::time::Duration;
 use thiserror::Error;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 use tracing::{debug, error, info};
@@ -28,6 +31,50 @@ pub enum PrinterError {
 
     #[error("Invalid label template: {0}")]
     InvalidTemplate(String),
+
+    #[error("Printer gave no usable answer for {setting}: {response:?}")]
+    InvalidResponse { setting: String, response: String },
+
+    #[error("Loaded labels are {loaded} but the template needs {expected}; load the right stock")]
+    MediaMismatch { expected: MediaSize, loaded: MediaSize },
+}
+
+/// Difference in dots allowed between loaded media and a template, to allow
+/// for calibration (8 dots is 1 mm at 203 DPI).
+const MEDIA_TOLERANCE_DOTS: u32 = 8;
+
+/// Size of a label, in dots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaSize {
+    pub width_dots: u32,
+    pub length_dots: u32,
+}
+
+impl MediaSize {
+    /// Returns true if labels of this size can take a template of `other`.
+    pub fn fits(&self, other: &MediaSize) -> bool {
+        self.width_dots.abs_diff(other.width_dots) <= MEDIA_TOLERANCE_DOTS
+            && self.length_dots.abs_diff(other.length_dots) <= MEDIA_TOLERANCE_DOTS
+    }
+}
+
+impl std::fmt::Display for MediaSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{} dots", self.width_dots, self.length_dots)
+    }
+}
+
+/// Labels used by a print job.
+///
+/// The printer answers queries while it is still printing, so the odometer
+/// is only read as a job starts; the difference between the readings of
+/// two jobs, less the labels the first sent, is the stock wasted between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobUsage {
+    /// Labels sent to the printer
+    pub labels: u32,
+    /// Odometer as the job started, if it was read
+    pub odometer: Option<u64>,
 }
 
 /// Configuration for the Zebra printer client.
@@ -47,6 +94,11 @@ pub struct PrinterConfig {
     pub darkness: u8,
     /// Print speed (1-14, default: 6)
     pub speed: u8,
+    /// Jobs of at least this many labels check the loaded media and read
+    /// the odometer first (default: 10)
+    pub large_job_labels: u32,
+    /// Seconds to wait for the printer to answer a query (default: 5)
+    pub read_timeout_secs: u64,
 }
 
 impl Default for PrinterConfig {
@@ -59,6 +111,8 @@ impl Default for PrinterConfig {
             label_height_dots: 203, // ~1 inch at 203 DPI
             darkness: 15,
             speed: 6,
+            large_job_labels: 10,
+            read_timeout_secs: 5,
         }
     }
 }
@@ -84,6 +138,26 @@ impl PrinterConfig {
         self.label_height_dots = height;
         self
     }
+
+    /// Sets the size of job checked before printing.
+    pub fn large_job_labels(mut self, labels: u32) -> Self {
+        self.large_job_labels = labels;
+        self
+    }
+
+    /// Returns the label size templates are laid out for.
+    pub fn media(&self) -> MediaSize {
+        MediaSize {
+            width_dots: self.label_width_dots,
+            length_dots: self.label_height_dots,
+        }
+    }
+}
+
+/// Reads the value out of a Set-Get-Do answer, which comes in quotes.
+fn parse_sgd_value(response: &str) -> Option<&str> {
+    let value = response.trim().trim_end_matches('\0').trim();
+    value.strip_prefix('"')?.strip_suffix('"')
 }
 
 /// Barcode types supported by ZPL.
@@ -505,6 +579,97 @@ impl ZebraPrinter {
         self.print_label(&label_with_copies).await
     }
 
+    /// Reads a printer setting with a Set-Get-Do query.
+    pub async fn get_setting(&self, setting: &str) -> Result<String, PrinterError> {
+        let mut stream = self.connect().await?;
+        stream
+            .write_all(format!("! U1 getvar \"{}\"\r\n", setting).as_bytes())
+            .await?;
+        stream.flush().await?;
+
+        // The answer is a single quoted value; read until its closing quote
+        let timeout_secs = self.config.read_timeout_secs;
+        let mut response = Vec::new();
+        let mut buf = [0u8; 256];
+        while response.iter().filter(|b| **b == b'"').count() < 2 {
+            let read = timeout(Duration::from_secs(timeout_secs), stream.read(&mut buf))
+                .await
+                .map_err(|_| PrinterError::ConnectionTimeout { timeout_secs })??;
+            if read == 0 {
+                break;
+            }
+            response.extend_from_slice(&buf[..read]);
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        debug!("Printer {} is {}", setting, response.trim());
+        parse_sgd_value(&response)
+            .map(str::to_string)
+            .ok_or_else(|| PrinterError::InvalidResponse {
+                setting: setting.to_string(),
+                response: response.to_string(),
+            })
+    }
+
+    /// Reads a numeric printer setting.
+    async fn get_number<T: std::str::FromStr>(&self, setting: &str) -> Result<T, PrinterError> {
+        let value = self.get_setting(setting).await?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| PrinterError::InvalidResponse {
+                setting: setting.to_string(),
+                response: value,
+            })
+    }
+
+    /// Reads the printer's odometer: the labels it has printed in its life.
+    pub async fn label_count(&self) -> Result<u64, PrinterError> {
+        self.get_number("odometer.total_label_count").await
+    }
+
+    /// Reads the size of the labels loaded, as the printer calibrated it.
+    pub async fn loaded_media(&self) -> Result<MediaSize, PrinterError> {
+        Ok(MediaSize {
+            width_dots: self.get_number("ezpl.print_width").await?,
+            length_dots: self.get_number("zpl.label_length").await?,
+        })
+    }
+
+    /// Checks the labels loaded fit the templates, which are laid out for
+    /// the configured label size.
+    pub async fn check_media(&self) -> Result<(), PrinterError> {
+        let expected = self.config.media();
+        let loaded = self.loaded_media().await?;
+        if !loaded.fits(&expected) {
+            return Err(PrinterError::MediaMismatch { expected, loaded });
+        }
+        Ok(())
+    }
+
+    /// Prints a job of labels and reports the labels it used.
+    ///
+    /// Before a large job the loaded media is checked, so a whole batch
+    /// isn't wasted on the wrong stock, and the odometer is read for
+    /// consumable tracking.
+    pub async fn print_job(&self, labels: &[LabelBuilder]) -> Result<JobUsage, PrinterError> {
+        let odometer = if labels.len() >= self.config.large_job_labels as usize {
+            self.check_media().await?;
+            Some(self.label_count().await?)
+        } else {
+            None
+        };
+
+        let zpl: String = labels.iter().map(LabelBuilder::build).collect();
+        self.print_raw(&zpl).await?;
+
+        info!("Printed job of {} labels", labels.len());
+        Ok(JobUsage {
+            labels: labels.len() as u32,
+            odometer,
+        })
+    }
+
     /// Tests printer connectivity.
     pub async fn ping(&self) -> bool {
         match self.connect().await {
@@ -520,6 +685,40 @@ impl ZebraPrinter {
     }
 }
 
+/// Lays a label out as text lines above a Code128 barcode, the first line
+/// larger, as on the sample label.
+fn layout(builder: LabelBuilder, label: &Label) -> LabelBuilder {
+    let builder = label.lines.iter().enumerate().fold(builder, |builder, (i, line)| {
+        let height = if i == 0 { 25 } else { 20 };
+        builder.text(10, 10 + 30 * i as u32, line, '0', height)
+    });
+    builder.code128(10, 10 + 30 * label.lines.len() as u32, &label.barcode, 50)
+}
+
+#[async_trait]
+impl LabelPrinter for ZebraPrinter {
+    fn name(&self) -> String {
+        format!("{}:{}", self.config.host, self.config.port)
+    }
+
+    async fn print(&self, label: &Label) -> Result<(), DomainError> {
+        self.print_label(&layout(self.label(), label))
+            .await
+            .map_err(|e| DomainError::Validation(format!("Print failed: {}", e)))
+    }
+
+    async fn print_job(&self, labels: &[Label]) -> Result<PrintedJob, DomainError> {
+        let builders: Vec<_> = labels.iter().map(|l| layout(self.label(), l)).collect();
+        let usage = ZebraPrinter::print_job(self, &builders)
+            .await
+            .map_err(|e| DomainError::Validation(format!("Print failed: {}", e)))?;
+        Ok(PrintedJob {
+            labels: usage.labels,
+            odometer: usage.odometer,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -557,6 +756,105 @@ mod tests {
         assert!(label.contains("SAM-001"));
     }
 
+    #[test]
+    fn test_label_layout_matches_sample_label() {
+        let label = Label {
+            template: "sample".to_string(),
+            entity_type: "Sample".to_string(),
+            entity_id: 1,
+            barcode: "SAM-001".to_string(),
+            lines: vec!["S1".to_string(), "PROJ".to_string()],
+        };
+        let zpl = layout(LabelBuilder::new(400, 200), &label).build();
+
+        assert!(zpl.contains("^FO10,10^A0,25,25^FDS1^FS"));
+        assert!(zpl.contains("^FO10,40^A0,20,20^FDPROJ^FS"));
+        assert!(zpl.contains("^FO10,70^BCN,50,Y^FDSAM-001^FS"));
+    }
+
+    #[test]
+    fn test_parse_sgd_value() {
+        assert_eq!(parse_sgd_value("\"12345\"\r\n"), Some("12345"));
+        assert_eq!(parse_sgd_value("\"\""), Some(""));
+        assert_eq!(parse_sgd_value("?"), None);
+    }
+
+    #[test]
+    fn test_media_fits_within_tolerance() {
+        let template = MediaSize { width_dots: 406, length_dots: 203 };
+        assert!(MediaSize { width_dots: 400, length_dots: 210 }.fits(&template));
+        assert!(!MediaSize { width_dots: 812, length_dots: 406 }.fits(&template));
+    }
+
+    /// Starts a printer on a local port that answers queries from
+    /// `settings` and sends everything printed down the channel.
+    async fn mock_printer(
+        settings: &'static [(&'static str, &'static str)],
+    ) -> (PrinterConfig, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut received = Vec::new();
+                let mut buf = [0u8; 1024];
+                while let Ok(read) = stream.read(&mut buf).await {
+                    if read == 0 {
+                        break;
+                    }
+                    received.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&received);
+                    if let Some((_, value)) = settings
+                        .iter()
+                        .find(|(setting, _)| text.starts_with("! U1 getvar") && text.contains(setting))
+                    {
+                        let _ = stream.write_all(format!("\"{}\"", value).as_bytes()).await;
+                        break;
+                    }
+                }
+                if !received.starts_with(b"! U1") {
+                    let _ = tx.send(String::from_utf8_lossy(&received).to_string());
+                }
+            }
+        });
+        (PrinterConfig::new("127.0.0.1").port(port).large_job_labels(2), rx)
+    }
+
+    #[tokio::test]
+    async fn test_large_job_reads_odometer_on_matching_stock() {
+        let (config, mut printed) = mock_printer(&[
+            ("odometer.total_label_count", "1042"),
+            ("ezpl.print_width", "406"),
+            ("zpl.label_length", "203"),
+        ])
+        .await;
+        let printer = ZebraPrinter::new(config);
+
+        let labels = vec![printer.label().text(10, 10, "A", '0', 20); 3];
+        let usage = printer.print_job(&labels).await.unwrap();
+        assert_eq!(usage, JobUsage { labels: 3, odometer: Some(1042) });
+        assert_eq!(printed.recv().await.unwrap().matches("^XA").count(), 3);
+
+        let usage = printer.print_job(&labels[..1]).await.unwrap();
+        assert_eq!(usage, JobUsage { labels: 1, odometer: None });
+    }
+
+    #[tokio::test]
+    async fn test_large_job_aborts_on_mismatched_stock() {
+        let (config, mut printed) = mock_printer(&[
+            ("odometer.total_label_count", "1042"),
+            ("ezpl.print_width", "812"),
+            ("zpl.label_length", "203"),
+        ])
+        .await;
+        let printer = ZebraPrinter::new(config);
+
+        let labels = vec![printer.label(); 2];
+        let result = printer.print_job(&labels).await;
+        assert!(matches!(result, Err(PrinterError::MediaMismatch { .. })));
+        assert!(printed.try_recv().is_err());
+    }
+
     #[test]
     fn test_config_builder() {
         let config = PrinterConfig::new("192.168.1.50")
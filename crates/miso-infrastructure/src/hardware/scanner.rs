@@ -151,6 +151,7 @@ mod commands {
 }
 
 /// Response prefixes from the scanner.
+#[allow(dead_code)]
 mod responses {
     pub const OK_SCAN: &str = "OKS";
     pub const OK_STATUS: &str = "OKG";
@@ -3,13 +3,21 @@
 //! This crate provides concrete implementations of the domain interfaces:
 //! - **Persistence**: SeaORM-based repository implementations
 //! - **Hardware**: Async clients for lab equipment (VisionMate scanners, printers)
+//! - **Notifications**: Delivery of alerts to lab staff
+//! - **Auth**: Password hashing and login for internal users
+//! - **Storage**: Where uploaded attachments are kept (local directory, S3)
 //! - **External Services**: LDAP authentication, etc.
 
+pub mod auth;
+pub mod external;
 pub mod hardware;
+pub mod notifications;
 pub mod persistence;
+pub mod storage;
 
 // Re-export commonly used types
 pub use hardware::scanner::VisionMateClient;
 pub use hardware::printer::ZebraPrinter;
+pub use notifications::log::LogNotifier;
 pub use persistence::database::Database;
 
@@ -0,0 +1,165 @@
+//! Notifier that sends email through an SMTP relay.
+//!
+//! Recipients are configured per role rather than looked up from user
+//! accounts, so notifications can go to shared mailing lists. Addresses a
+//! notification names itself are sent a copy too.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::{debug, warn};
+
+use miso_domain::entities::Role;
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+
+/// SMTP relay and recipient settings.
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    /// Relay host name
+    pub host: String,
+    /// Relay port
+    pub port: u16,
+    /// Whether to upgrade the connection with STARTTLS
+    pub starttls: bool,
+    /// Login, if the relay requires authentication
+    pub credentials: Option<(String, String)>,
+    /// Sender address, e.g. "MISO <miso@example.org>"
+    pub from: String,
+    /// Addresses to notify for each role
+    pub recipients: HashMap<Role, Vec<String>>,
+}
+
+/// Notifier that emails each notification to the addresses configured for
+/// its audience.
+#[derive(Clone)]
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    recipients: HashMap<Role, Vec<Mailbox>>,
+}
+
+impl EmailNotifier {
+    /// Creates a notifier from the given settings.
+    ///
+    /// Fails if an address is malformed or the relay host is invalid. No
+    /// connection is made until the first notification is sent.
+    pub fn new(config: EmailConfig) -> Result<Self, DomainError> {
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| DomainError::Validation(format!("Invalid SMTP relay: {}", e)))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        }
+        .port(config.port);
+        if let Some((username, password)) = config.credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let recipients = config
+            .recipients
+            .into_iter()
+            .map(|(role, addresses)| {
+                let mailboxes = addresses
+                    .iter()
+                    .map(|a| parse_mailbox(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((role, mailboxes))
+            })
+            .collect::<Result<HashMap<_, _>, DomainError>>()?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from: parse_mailbox(&config.from)?,
+            recipients,
+        })
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, DomainError> {
+    address
+        .trim()
+        .parse()
+        .map_err(|e| DomainError::Validation(format!("Invalid email address {}: {}", address, e)))
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), DomainError> {
+        let mut recipients: Vec<Mailbox> = self
+            .recipients
+            .get(&notification.audience)
+            .cloned()
+            .unwrap_or_default();
+        for address in &notification.addresses {
+            recipients.push(parse_mailbox(address)?);
+        }
+        if recipients.is_empty() {
+            warn!(
+                audience = ?notification.audience,
+                "No email recipients configured; dropping notification: {}",
+                notification.subject
+            );
+            return Ok(());
+        }
+
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(&notification.subject);
+        for recipient in &recipients {
+            builder = builder.to(recipient.clone());
+        }
+        let message = builder
+            .body(notification.body.clone())
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| DomainError::Validation(format!("Failed to send email: {}", e)))?;
+
+        debug!(
+            "Emailed {} recipients: {}",
+            recipients.len(),
+            notification.subject
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EmailConfig {
+        EmailConfig {
+            host: "localhost".to_string(),
+            port: 25,
+            starttls: false,
+            credentials: None,
+            from: "MISO <miso@example.org>".to_string(),
+            recipients: HashMap::from([(
+                Role::LabManager,
+                vec!["lab-managers@example.org".to_string()],
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_addresses() {
+        assert!(EmailNotifier::new(config()).is_ok());
+
+        let mut bad_from = config();
+        bad_from.from = "not an address".to_string();
+        assert!(EmailNotifier::new(bad_from).is_err());
+
+        let mut bad_recipient = config();
+        bad_recipient
+            .recipients
+            .insert(Role::Admin, vec!["admins@".to_string()]);
+        assert!(EmailNotifier::new(bad_recipient).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Notifier that writes to the application log.
+//!
+//! Useful during development and as a fallback where no mail relay is
+//! configured; operators can still pick alerts out of the server logs.
+
+use async_trait::async_trait;
+use tracing::warn;
+
+use miso_domain::errors::DomainError;
+use miso_domain::notifications::{Notification, Notifier};
+
+/// Notifier that logs each notification at warn level.
+#[derive(Debug, Clone, Default)]
+pub struct LogNotifier;
+
+impl LogNotifier {
+    /// Creates a new log notifier.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), DomainError> {
+        warn!(
+            audience = ?notification.audience,
+            addresses = ?notification.addresses,
+            "{}\n{}",
+            notification.subject,
+            notification.body
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+//! Notifier implementations.
+//!
+//! Provides delivery channels for staff notifications:
+//! - Application log
+//! - Email via an SMTP relay
+
+pub mod email;
+pub mod log;
@@ -3,9 +3,12 @@
 //! Handles connection pooling and configuration for MySQL via SeaORM.
 
 use sea_orm::{ConnectOptions, DatabaseConnection, DbErr};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::info;
 
+use super::metrics::{PoolStatus, QueryStats, QueryStatsSnapshot};
+
 /// Database configuration options.
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
@@ -23,6 +26,8 @@ pub struct DatabaseConfig {
     pub max_lifetime_secs: u64,
     /// Whether to log SQL queries
     pub sqlx_logging: bool,
+    /// Queries taking at least this long are logged as slow
+    pub slow_query_threshold: Option<Duration>,
 }
 
 impl Default for DatabaseConfig {
@@ -35,6 +40,7 @@ impl Default for DatabaseConfig {
             idle_timeout_secs: 300,
             max_lifetime_secs: 3600,
             sqlx_logging: false,
+            slow_query_threshold: Some(Duration::from_millis(500)),
         }
     }
 }
@@ -60,6 +66,12 @@ impl DatabaseConfig {
         self
     }
 
+    /// Sets the slow-query threshold; `None` disables slow-query logging.
+    pub fn slow_query_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.slow_query_threshold = threshold;
+        self
+    }
+
     /// Creates a configuration from environment variables.
     pub fn from_env() -> Self {
         let url = std::env::var("DATABASE_URL")
@@ -70,10 +82,21 @@ impl DatabaseConfig {
             .and_then(|s| s.parse().ok())
             .unwrap_or(10);
 
+        let defaults = Self::default();
+        let slow_query_threshold = match std::env::var("DATABASE_SLOW_QUERY_MS") {
+            Ok(ms) => ms
+                .parse()
+                .ok()
+                .filter(|&ms| ms > 0)
+                .map(Duration::from_millis),
+            Err(_) => defaults.slow_query_threshold,
+        };
+
         Self {
             url,
             max_connections,
-            ..Default::default()
+            slow_query_threshold,
+            ..defaults
         }
     }
 }
@@ -84,6 +107,8 @@ impl DatabaseConfig {
 #[derive(Debug, Clone)]
 pub struct Database {
     connection: DatabaseConnection,
+    max_connections: u32,
+    stats: Arc<QueryStats>,
 }
 
 impl Database {
@@ -99,11 +124,27 @@ impl Database {
             .max_lifetime(Duration::from_secs(config.max_lifetime_secs))
             .sqlx_logging(config.sqlx_logging);
 
-        let connection = sea_orm::Database::connect(opts).await?;
+        let mut connection = sea_orm::Database::connect(opts).await?;
+
+        let stats = Arc::new(QueryStats::default());
+        let callback_stats = stats.clone();
+        let slow_query_threshold = config.slow_query_threshold;
+        connection.set_metric_callback(move |info| {
+            callback_stats.record(
+                &info.statement.sql,
+                info.elapsed,
+                info.failed,
+                slow_query_threshold,
+            );
+        });
 
         info!("Database connected successfully");
 
-        Ok(Self { connection })
+        Ok(Self {
+            connection,
+            max_connections: config.max_connections,
+            stats,
+        })
     }
 
     /// Creates a connection from environment variables.
@@ -126,6 +167,43 @@ impl Database {
             .map(|_| ())
     }
 
+    /// Returns query totals since the pool was created.
+    pub fn query_stats(&self) -> QueryStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    /// Returns current pool usage.
+    ///
+    /// Checks out (and immediately returns) a connection to measure how long
+    /// callers are currently waiting for one.
+    pub async fn pool_status(&self) -> Result<PoolStatus, DbErr> {
+        if !matches!(
+            self.connection,
+            DatabaseConnection::SqlxMySqlPoolConnection(_)
+        ) {
+            return Err(DbErr::Custom("Not connected to MySQL".to_string()));
+        }
+
+        let pool = self.connection.get_mysql_connection_pool();
+        let size = pool.size();
+        let idle = pool.num_idle() as u32;
+
+        let started = Instant::now();
+        drop(
+            pool.acquire()
+                .await
+                .map_err(|e| DbErr::Custom(e.to_string()))?,
+        );
+
+        Ok(PoolStatus {
+            max_connections: self.max_connections,
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            acquire_wait: started.elapsed(),
+        })
+    }
+
     /// Closes the database connection pool.
     pub async fn close(self) -> Result<(), DbErr> {
         self.connection.close().await
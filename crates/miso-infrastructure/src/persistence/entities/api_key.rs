@@ -0,0 +1,100 @@
+//! SeaORM entity for the api_key table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::user::role_code;
+
+/// API key database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_key")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub prefix: String,
+
+    /// SHA-256 hash of the key, hex encoded
+    #[serde(skip_serializing)]
+    #[sea_orm(column_type = "String(StringLen::N(64))", unique)]
+    pub key_hash: String,
+
+    /// Role code, e.g. "technician"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub role: String,
+
+    pub user_id: i32,
+
+    pub created_at: DateTimeUtc,
+
+    pub expires_at: Option<DateTimeUtc>,
+
+    pub last_used_at: Option<DateTimeUtc>,
+
+    pub revoked_at: Option<DateTimeUtc>,
+}
+
+/// Database relations for ApiKey.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::ApiKey {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            prefix: model.prefix,
+            key_hash: model.key_hash,
+            role: model.role.parse()?,
+            user_id: model.user_id,
+            created_at: model.created_at,
+            expires_at: model.expires_at,
+            last_used_at: model.last_used_at,
+            revoked_at: model.revoked_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::ApiKey> for ActiveModel {
+    fn from(key: &miso_domain::entities::ApiKey) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if key.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(key.id)
+            },
+            name: ActiveValue::Set(key.name.clone()),
+            prefix: ActiveValue::Set(key.prefix.clone()),
+            key_hash: ActiveValue::Set(key.key_hash.clone()),
+            role: ActiveValue::Set(role_code(key.role).to_string()),
+            user_id: ActiveValue::Set(key.user_id),
+            created_at: ActiveValue::Set(key.created_at),
+            expires_at: ActiveValue::Set(key.expires_at),
+            last_used_at: ActiveValue::Set(key.last_used_at),
+            revoked_at: ActiveValue::Set(key.revoked_at),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! SeaORM entity for the api_usage table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Hourly API usage database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_usage")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub hour: DateTimeUtc,
+
+    pub user_id: i32,
+
+    pub api_key_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(10))")]
+    pub method: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub route: String,
+
+    pub calls: i64,
+
+    pub errors: i64,
+
+    pub samples: i64,
+
+    pub total_ms: i64,
+
+    pub max_ms: i64,
+}
+
+/// Database relations for ApiUsage.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::ApiUsage {
+    fn from(model: Model) -> Self {
+        Self {
+            hour: model.hour,
+            user_id: model.user_id,
+            api_key_id: model.api_key_id,
+            method: model.method,
+            route: model.route,
+            calls: model.calls,
+            errors: model.errors,
+            samples: model.samples,
+            total_ms: model.total_ms,
+            max_ms: model.max_ms,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ApiUsage> for ActiveModel {
+    fn from(usage: &miso_domain::entities::ApiUsage) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: ActiveValue::NotSet,
+            hour: ActiveValue::Set(usage.hour),
+            user_id: ActiveValue::Set(usage.user_id),
+            api_key_id: ActiveValue::Set(usage.api_key_id),
+            method: ActiveValue::Set(usage.method.clone()),
+            route: ActiveValue::Set(usage.route.clone()),
+            calls: ActiveValue::Set(usage.calls),
+            errors: ActiveValue::Set(usage.errors),
+            samples: ActiveValue::Set(usage.samples),
+            total_ms: ActiveValue::Set(usage.total_ms),
+            max_ms: ActiveValue::Set(usage.max_ms),
+        }
+    }
+}
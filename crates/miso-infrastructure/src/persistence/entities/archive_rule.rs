@@ -0,0 +1,90 @@
+//! SeaORM entity for the archive_rule table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Automatic archiving rule database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "archive_rule")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub target: String,
+
+    pub after_days: i32,
+
+    pub notice_days: i32,
+
+    pub active: bool,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    #[sea_orm(nullable)]
+    pub last_evaluated_at: Option<DateTimeUtc>,
+}
+
+/// Database relations for ArchiveRule.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::auto_archival::Entity")]
+    AutoArchival,
+}
+
+impl Related<super::auto_archival::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AutoArchival.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::ArchiveRule {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            target: model
+                .target
+                .parse()
+                .map_err(miso_domain::errors::DomainError::Validation)?,
+            after_days: model.after_days.max(0) as u32,
+            notice_days: model.notice_days.max(0) as u32,
+            active: model.active,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            last_evaluated_at: model.last_evaluated_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::ArchiveRule> for ActiveModel {
+    fn from(rule: &miso_domain::entities::ArchiveRule) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if rule.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(rule.id)
+            },
+            name: ActiveValue::Set(rule.name.clone()),
+            target: ActiveValue::Set(rule.target.as_str().to_string()),
+            after_days: ActiveValue::Set(rule.after_days as i32),
+            notice_days: ActiveValue::Set(rule.notice_days as i32),
+            active: ActiveValue::Set(rule.active),
+            created_by: ActiveValue::Set(rule.created_by.clone()),
+            created_at: ActiveValue::Set(rule.created_at),
+            last_evaluated_at: ActiveValue::Set(rule.last_evaluated_at),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! SeaORM entity for the attachment table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Attachment database entity. The file contents are kept by an
+/// attachment store under the storage key.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub target: String,
+
+    pub target_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub filename: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub content_type: String,
+
+    pub size: i64,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub checksum: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(512))", unique)]
+    pub storage_key: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub uploaded_by: String,
+
+    pub uploaded_at: DateTimeUtc,
+}
+
+/// Database relations for Attachment.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::Attachment {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            target: model
+                .target
+                .parse()
+                .map_err(miso_domain::errors::DomainError::Validation)?,
+            target_id: model.target_id,
+            filename: model.filename,
+            content_type: model.content_type,
+            size: model.size,
+            checksum: model.checksum,
+            storage_key: model.storage_key,
+            uploaded_by: model.uploaded_by,
+            uploaded_at: model.uploaded_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::Attachment> for ActiveModel {
+    fn from(attachment: &miso_domain::entities::Attachment) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if attachment.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(attachment.id)
+            },
+            target: ActiveValue::Set(attachment.target.as_str().to_string()),
+            target_id: ActiveValue::Set(attachment.target_id),
+            filename: ActiveValue::Set(attachment.filename.clone()),
+            content_type: ActiveValue::Set(attachment.content_type.clone()),
+            size: ActiveValue::Set(attachment.size),
+            checksum: ActiveValue::Set(attachment.checksum.clone()),
+            storage_key: ActiveValue::Set(attachment.storage_key.clone()),
+            uploaded_by: ActiveValue::Set(attachment.uploaded_by.clone()),
+            uploaded_at: ActiveValue::Set(attachment.uploaded_at),
+        }
+    }
+}
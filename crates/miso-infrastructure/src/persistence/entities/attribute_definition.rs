@@ -0,0 +1,199 @@
+//! SeaORM entity for the attribute_definition table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Custom attribute definition database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "attribute_definition")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// "sample" or "library"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub target: String,
+
+    /// Project the attribute is limited to; null for every project
+    pub project_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub attribute_key: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub label: String,
+
+    /// "text", "integer", "decimal", "boolean", "date" or "choice"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub value_type: String,
+
+    #[sea_orm(default_value = "false")]
+    pub required: bool,
+
+    /// Allowed values of a choice
+    pub options: Json,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub min_value: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub max_value: Option<f64>,
+
+    pub max_length: Option<i32>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub help_text: Option<String>,
+
+    #[sea_orm(default_value = 0)]
+    pub position: i32,
+}
+
+/// Database relations for AttributeDefinition.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::AttributeDefinition {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::entities::AttributeType;
+        use miso_domain::errors::DomainError;
+
+        let value_type = match model.value_type.as_str() {
+            "text" => AttributeType::Text,
+            "integer" => AttributeType::Integer,
+            "decimal" => AttributeType::Decimal,
+            "boolean" => AttributeType::Boolean,
+            "date" => AttributeType::Date,
+            "choice" => AttributeType::Choice,
+            other => {
+                return Err(DomainError::Validation(format!(
+                    "Unknown attribute type: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            id: model.id,
+            target: target_from_code(&model.target)?,
+            project_id: model.project_id,
+            key: model.attribute_key,
+            label: model.label,
+            value_type,
+            required: model.required,
+            options: serde_json::from_value(model.options)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            min: model.min_value,
+            max: model.max_value,
+            max_length: model
+                .max_length
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            help_text: model.help_text,
+            position: model.position,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::AttributeDefinition> for ActiveModel {
+    fn from(definition: &miso_domain::entities::AttributeDefinition) -> Self {
+        use miso_domain::entities::AttributeType;
+        use sea_orm::ActiveValue;
+
+        let value_type = match definition.value_type {
+            AttributeType::Text => "text",
+            AttributeType::Integer => "integer",
+            AttributeType::Decimal => "decimal",
+            AttributeType::Boolean => "boolean",
+            AttributeType::Date => "date",
+            AttributeType::Choice => "choice",
+        };
+
+        Self {
+            id: if definition.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(definition.id)
+            },
+            target: ActiveValue::Set(definition.target.to_string()),
+            project_id: ActiveValue::Set(definition.project_id),
+            attribute_key: ActiveValue::Set(definition.key.clone()),
+            label: ActiveValue::Set(definition.label.clone()),
+            value_type: ActiveValue::Set(value_type.to_string()),
+            required: ActiveValue::Set(definition.required),
+            options: ActiveValue::Set(
+                serde_json::to_value(&definition.options).unwrap_or_default(),
+            ),
+            min_value: ActiveValue::Set(definition.min),
+            max_value: ActiveValue::Set(definition.max),
+            max_length: ActiveValue::Set(
+                definition
+                    .max_length
+                    .map(|len| i32::try_from(len).unwrap_or(i32::MAX)),
+            ),
+            help_text: ActiveValue::Set(definition.help_text.clone()),
+            position: ActiveValue::Set(definition.position),
+        }
+    }
+}
+
+/// Parses a stored attribute target.
+fn target_from_code(
+    code: &str,
+) -> Result<miso_domain::entities::AttributeTarget, miso_domain::errors::DomainError> {
+    use miso_domain::entities::AttributeTarget;
+
+    match code {
+        "sample" => Ok(AttributeTarget::Sample),
+        "library" => Ok(AttributeTarget::Library),
+        other => Err(miso_domain::errors::DomainError::Validation(format!(
+            "Unknown attribute target: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{AttributeDefinition, AttributeTarget, AttributeType};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_attribute_definition_round_trips_through_model() {
+        let mut definition = AttributeDefinition::new(
+            AttributeTarget::Library,
+            "tissue".to_string(),
+            "Tissue".to_string(),
+            AttributeType::Choice,
+        );
+        definition.id = 4;
+        definition.project_id = Some(2);
+        definition.required = true;
+        definition.options = vec!["Blood".to_string(), "Saliva".to_string()];
+
+        let model = ActiveModel::from(&definition).try_into_model().unwrap();
+        assert_eq!(model.target, "library");
+        assert_eq!(model.value_type, "choice");
+
+        let restored = AttributeDefinition::try_from(model).unwrap();
+        assert_eq!(restored, definition);
+    }
+}
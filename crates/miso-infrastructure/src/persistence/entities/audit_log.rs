@@ -0,0 +1,134 @@
+//! SeaORM entity for the audit_log table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Audit log database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    pub entity_id: i32,
+
+    /// "create", "update" or "delete"
+    #[sea_orm(column_type = "String(StringLen::N(10))")]
+    pub action: String,
+
+    /// Old and new values of each changed field, by field path
+    pub changes: Json,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub changed_by: String,
+
+    pub changed_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub previous_hash: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::AuditEntry {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::entities::FieldChange;
+
+        let value = |change: &Json, side: &str| change.get(side).map(|v| v.to_string());
+        let hash = |change: &Json, side: &str| {
+            change.get(side).and_then(Json::as_str).map(str::to_string)
+        };
+        let changes = match model.changes {
+            Json::Object(fields) => fields
+                .into_iter()
+                .map(|(field, change)| FieldChange {
+                    old: value(&change, "old"),
+                    new: value(&change, "new"),
+                    old_hash: hash(&change, "old_hash"),
+                    new_hash: hash(&change, "new_hash"),
+                    field,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            id: model.id,
+            entity_type: model.entity_type,
+            entity_id: model.entity_id,
+            action: model.action.parse()?,
+            changes,
+            changed_by: model.changed_by,
+            changed_at: model.changed_at,
+            previous_hash: model.previous_hash,
+            hash: model.hash,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::AuditEntry> for ActiveModel {
+    fn from(entry: &miso_domain::entities::AuditEntry) -> Self {
+        use miso_domain::entities::AuditAction;
+        use sea_orm::ActiveValue;
+
+        let action = match entry.action {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        };
+
+        // Values are JSON already; store them as such rather than as text
+        let value = |json: &Option<String>| {
+            json.as_deref()
+                .and_then(|json| serde_json::from_str::<Json>(json).ok())
+        };
+        let changes: serde_json::Map<String, Json> = entry
+            .changes
+            .iter()
+            .map(|change| {
+                let mut sides = serde_json::Map::new();
+                if let Some(old) = value(&change.old) {
+                    sides.insert("old".to_string(), old);
+                }
+                if let Some(new) = value(&change.new) {
+                    sides.insert("new".to_string(), new);
+                }
+                // Erased values keep their hashes for checking the chain
+                if let Some(old_hash) = &change.old_hash {
+                    sides.insert("old_hash".to_string(), Json::String(old_hash.clone()));
+                }
+                if let Some(new_hash) = &change.new_hash {
+                    sides.insert("new_hash".to_string(), Json::String(new_hash.clone()));
+                }
+                (change.field.clone(), Json::Object(sides))
+            })
+            .collect();
+
+        Self {
+            id: if entry.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(entry.id)
+            },
+            entity_type: ActiveValue::Set(entry.entity_type.clone()),
+            entity_id: ActiveValue::Set(entry.entity_id),
+            action: ActiveValue::Set(action.to_string()),
+            changes: ActiveValue::Set(Json::Object(changes)),
+            changed_by: ActiveValue::Set(entry.changed_by.clone()),
+            changed_at: ActiveValue::Set(entry.changed_at),
+            previous_hash: ActiveValue::Set(entry.previous_hash.clone()),
+            hash: ActiveValue::Set(entry.hash.clone()),
+        }
+    }
+}
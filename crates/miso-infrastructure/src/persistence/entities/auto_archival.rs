@@ -0,0 +1,95 @@
+//! SeaORM entity for the auto_archival table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Database entity for an item archived by a rule. The entity ID points at
+/// a library or a sample depending on the target.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "auto_archival")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub rule_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub target: String,
+
+    pub entity_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub entity_name: String,
+
+    pub last_run_at: DateTimeUtc,
+
+    pub archived_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub restored_by: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub restored_at: Option<DateTimeUtc>,
+}
+
+/// Database relations for AutoArchival.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::archive_rule::Entity",
+        from = "Column::RuleId",
+        to = "super::archive_rule::Column::Id"
+    )]
+    ArchiveRule,
+}
+
+impl Related<super::archive_rule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ArchiveRule.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::AutoArchival {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            rule_id: model.rule_id,
+            target: model
+                .target
+                .parse()
+                .map_err(miso_domain::errors::DomainError::Validation)?,
+            entity_id: model.entity_id,
+            entity_name: model.entity_name,
+            last_run_at: model.last_run_at,
+            archived_at: model.archived_at,
+            restored_by: model.restored_by,
+            restored_at: model.restored_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::AutoArchival> for ActiveModel {
+    fn from(archival: &miso_domain::entities::AutoArchival) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if archival.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(archival.id)
+            },
+            rule_id: ActiveValue::Set(archival.rule_id),
+            target: ActiveValue::Set(archival.target.as_str().to_string()),
+            entity_id: ActiveValue::Set(archival.entity_id),
+            entity_name: ActiveValue::Set(archival.entity_name.clone()),
+            last_run_at: ActiveValue::Set(archival.last_run_at),
+            archived_at: ActiveValue::Set(archival.archived_at),
+            restored_by: ActiveValue::Set(archival.restored_by.clone()),
+            restored_at: ActiveValue::Set(archival.restored_at),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! SeaORM entity for the box_position table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An item stored at a position of a box.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "box_position")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub box_id: i32,
+
+    /// Position in the box, e.g. "B12"
+    #[sea_orm(
+        primary_key,
+        auto_increment = false,
+        column_type = "String(StringLen::N(4))"
+    )]
+    pub position: String,
+
+    /// Code of the item's type, e.g. "sample"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub item_type: String,
+
+    pub item_id: i32,
+}
+
+/// Database relations for BoxPosition.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::storage_box::Entity",
+        from = "Column::BoxId",
+        to = "super::storage_box::Column::Id"
+    )]
+    StorageBox,
+}
+
+impl Related<super::storage_box::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StorageBox.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for an item at a position of the box with `box_id`.
+    pub fn new(
+        box_id: i32,
+        position: &miso_domain::value_objects::BoxPosition,
+        item: &miso_domain::entities::StorableItem,
+    ) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            box_id: ActiveValue::Set(box_id),
+            position: ActiveValue::Set(position.to_string()),
+            item_type: ActiveValue::Set(
+                super::storage_box::storable_type_code(item.item_type).to_string(),
+            ),
+            item_id: ActiveValue::Set(item.item_id),
+        }
+    }
+}
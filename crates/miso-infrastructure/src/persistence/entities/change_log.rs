@@ -0,0 +1,89 @@
+//! SeaORM entity for the change_log table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Change log database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "change_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    pub entity_id: i32,
+
+    #[sea_orm(column_type = "Text")]
+    pub summary: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub reason: Option<String>,
+
+    /// Hash the summary had before details were erased from it
+    #[sea_orm(column_type = "String(StringLen::N(64))", nullable)]
+    pub summary_hash: Option<String>,
+
+    /// Hash the reason had before details were erased from it
+    #[sea_orm(column_type = "String(StringLen::N(64))", nullable)]
+    pub reason_hash: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub changed_by: String,
+
+    pub changed_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub previous_hash: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::ChangeLogEntry {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            entity_type: model.entity_type,
+            entity_id: model.entity_id,
+            summary: model.summary,
+            reason: model.reason,
+            summary_hash: model.summary_hash,
+            reason_hash: model.reason_hash,
+            changed_by: model.changed_by,
+            changed_at: model.changed_at,
+            previous_hash: model.previous_hash,
+            hash: model.hash,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ChangeLogEntry> for ActiveModel {
+    fn from(entry: &miso_domain::entities::ChangeLogEntry) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if entry.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(entry.id)
+            },
+            entity_type: ActiveValue::Set(entry.entity_type.clone()),
+            entity_id: ActiveValue::Set(entry.entity_id),
+            summary: ActiveValue::Set(entry.summary.clone()),
+            reason: ActiveValue::Set(entry.reason.clone()),
+            summary_hash: ActiveValue::Set(entry.summary_hash.clone()),
+            reason_hash: ActiveValue::Set(entry.reason_hash.clone()),
+            changed_by: ActiveValue::Set(entry.changed_by.clone()),
+            changed_at: ActiveValue::Set(entry.changed_at),
+            previous_hash: ActiveValue::Set(entry.previous_hash.clone()),
+            hash: ActiveValue::Set(entry.hash.clone()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! SeaORM entity for the consent table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Consent database entity, one per identity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "consent")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub identity_id: i32,
+
+    /// "full", "deidentified" or "none"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub sharing: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub recorded_by: String,
+
+    pub recorded_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::Consent {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            identity_id: model.identity_id,
+            sharing: model.sharing.parse()?,
+            notes: model.notes,
+            recorded_by: model.recorded_by,
+            recorded_at: model.recorded_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::Consent> for ActiveModel {
+    fn from(consent: &miso_domain::entities::Consent) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if consent.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(consent.id)
+            },
+            identity_id: ActiveValue::Set(consent.identity_id),
+            sharing: ActiveValue::Set(consent.sharing.to_string()),
+            notes: ActiveValue::Set(consent.notes.clone()),
+            recorded_by: ActiveValue::Set(consent.recorded_by.clone()),
+            recorded_at: ActiveValue::Set(consent.recorded_at),
+        }
+    }
+}
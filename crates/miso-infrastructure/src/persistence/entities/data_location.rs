@@ -0,0 +1,133 @@
+//! SeaORM entity for the data_location table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Run data location database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "data_location")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub run_id: i32,
+
+    pub lane: Option<i32>,
+
+    #[sea_orm(column_type = "Text")]
+    pub uri: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub checksum_manifest: Option<String>,
+
+    /// "scratch", "standard", "long_term" or "permanent"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub retention_class: String,
+
+    pub size_bytes: Option<i64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub platform: Option<String>,
+
+    /// IDs of the projects with data at this location
+    pub project_ids: Option<Json>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub registered_by: String,
+
+    pub registered_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub purge_requested_by: Option<String>,
+
+    pub purge_requested_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub purged_by: Option<String>,
+
+    pub purged_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::DataLocation {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        use super::instrument_model::platform_from_code;
+
+        let project_ids = model
+            .project_ids
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .unwrap_or_default();
+
+        Ok(Self {
+            id: model.id,
+            run_id: model.run_id,
+            lane: model
+                .lane
+                .map(u8::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            uri: model.uri,
+            checksum_manifest: model.checksum_manifest,
+            retention_class: model.retention_class.parse()?,
+            size_bytes: model
+                .size_bytes
+                .map(u64::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            platform: model.platform.as_deref().map(platform_from_code),
+            project_ids,
+            registered_by: model.registered_by,
+            registered_at: model.registered_at,
+            purge_requested_by: model.purge_requested_by,
+            purge_requested_at: model.purge_requested_at,
+            purged_by: model.purged_by,
+            purged_at: model.purged_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::DataLocation> for ActiveModel {
+    fn from(location: &miso_domain::entities::DataLocation) -> Self {
+        use sea_orm::ActiveValue;
+
+        use super::instrument_model::platform_code;
+
+        Self {
+            id: if location.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(location.id)
+            },
+            run_id: ActiveValue::Set(location.run_id),
+            lane: ActiveValue::Set(location.lane.map(i32::from)),
+            uri: ActiveValue::Set(location.uri.clone()),
+            checksum_manifest: ActiveValue::Set(location.checksum_manifest.clone()),
+            retention_class: ActiveValue::Set(location.retention_class.to_string()),
+            size_bytes: ActiveValue::Set(
+                location
+                    .size_bytes
+                    .map(|b| i64::try_from(b).unwrap_or(i64::MAX)),
+            ),
+            platform: ActiveValue::Set(location.platform.map(|p| platform_code(p).to_string())),
+            project_ids: ActiveValue::Set(Some(
+                serde_json::to_value(&location.project_ids).unwrap_or_default(),
+            )),
+            registered_by: ActiveValue::Set(location.registered_by.clone()),
+            registered_at: ActiveValue::Set(location.registered_at),
+            purge_requested_by: ActiveValue::Set(location.purge_requested_by.clone()),
+            purge_requested_at: ActiveValue::Set(location.purge_requested_at),
+            purged_by: ActiveValue::Set(location.purged_by.clone()),
+            purged_at: ActiveValue::Set(location.purged_at),
+        }
+    }
+}
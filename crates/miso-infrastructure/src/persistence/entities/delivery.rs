@@ -0,0 +1,131 @@
+//! SeaORM entity for the delivery table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use miso_domain::errors::DomainError;
+
+/// Delivery database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "delivery")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub target_id: i32,
+
+    pub project_id: i32,
+
+    pub run_id: i32,
+
+    #[sea_orm(column_type = "Text")]
+    pub uri: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub checksum_manifest: Option<String>,
+
+    pub size_bytes: Option<i64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub status: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub delivered_by: String,
+
+    pub delivered_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub verified_by: Option<String>,
+
+    pub verified_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub note: Option<String>,
+}
+
+/// Database relations for Delivery.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::delivery_target::Entity",
+        from = "Column::TargetId",
+        to = "super::delivery_target::Column::Id"
+    )]
+    DeliveryTarget,
+    #[sea_orm(
+        belongs_to = "super::run::Entity",
+        from = "Column::RunId",
+        to = "super::run::Column::Id"
+    )]
+    Run,
+}
+
+impl Related<super::delivery_target::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliveryTarget.def()
+    }
+}
+
+impl Related<super::run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Run.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::Delivery {
+    type Error = DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            target_id: model.target_id,
+            project_id: model.project_id,
+            run_id: model.run_id,
+            uri: model.uri,
+            checksum_manifest: model.checksum_manifest,
+            size_bytes: model
+                .size_bytes
+                .map(u64::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            status: model.status.parse().map_err(DomainError::Validation)?,
+            delivered_by: model.delivered_by,
+            delivered_at: model.delivered_at,
+            verified_by: model.verified_by,
+            verified_at: model.verified_at,
+            note: model.note,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::Delivery> for ActiveModel {
+    fn from(delivery: &miso_domain::entities::Delivery) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if delivery.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(delivery.id)
+            },
+            target_id: ActiveValue::Set(delivery.target_id),
+            project_id: ActiveValue::Set(delivery.project_id),
+            run_id: ActiveValue::Set(delivery.run_id),
+            uri: ActiveValue::Set(delivery.uri.clone()),
+            checksum_manifest: ActiveValue::Set(delivery.checksum_manifest.clone()),
+            size_bytes: ActiveValue::Set(
+                delivery
+                    .size_bytes
+                    .map(|b| i64::try_from(b).unwrap_or(i64::MAX)),
+            ),
+            status: ActiveValue::Set(delivery.status.as_str().to_string()),
+            delivered_by: ActiveValue::Set(delivery.delivered_by.clone()),
+            delivered_at: ActiveValue::Set(delivery.delivered_at),
+            verified_by: ActiveValue::Set(delivery.verified_by.clone()),
+            verified_at: ActiveValue::Set(delivery.verified_at),
+            note: ActiveValue::Set(delivery.note.clone()),
+        }
+    }
+}
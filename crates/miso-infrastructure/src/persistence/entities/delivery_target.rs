@@ -0,0 +1,127 @@
+//! SeaORM entity for the delivery_target table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use miso_domain::entities::DeliveryDestination;
+use miso_domain::errors::DomainError;
+
+/// Delivery target database entity. S3 targets keep their bucket in `host`
+/// and key prefix in `path`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "delivery_target")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub project_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(10))")]
+    pub protocol: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub host: String,
+
+    pub port: Option<i32>,
+
+    #[sea_orm(column_type = "Text")]
+    pub path: String,
+
+    pub active: bool,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+/// Database relations for DeliveryTarget.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::DeliveryTarget {
+    type Error = DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        let destination = match model.protocol.as_str() {
+            "s3" => DeliveryDestination::S3 {
+                bucket: model.host,
+                prefix: model.path,
+            },
+            "sftp" => DeliveryDestination::Sftp {
+                host: model.host,
+                port: model
+                    .port
+                    .map(u16::try_from)
+                    .transpose()
+                    .map_err(|e| DomainError::Validation(e.to_string()))?
+                    .unwrap_or(22),
+                path: model.path,
+            },
+            other => {
+                return Err(DomainError::Validation(format!(
+                    "Unknown delivery protocol: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            id: model.id,
+            project_id: model.project_id,
+            name: model.name,
+            destination,
+            active: model.active,
+            created_by: model.created_by,
+            created_at: model.created_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::DeliveryTarget> for ActiveModel {
+    fn from(target: &miso_domain::entities::DeliveryTarget) -> Self {
+        use sea_orm::ActiveValue;
+
+        let (host, port, path) = match &target.destination {
+            DeliveryDestination::S3 { bucket, prefix } => (bucket.clone(), None, prefix.clone()),
+            DeliveryDestination::Sftp { host, port, path } => {
+                (host.clone(), Some(i32::from(*port)), path.clone())
+            }
+        };
+
+        Self {
+            id: if target.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(target.id)
+            },
+            project_id: ActiveValue::Set(target.project_id),
+            name: ActiveValue::Set(target.name.clone()),
+            protocol: ActiveValue::Set(target.destination.protocol().to_string()),
+            host: ActiveValue::Set(host),
+            port: ActiveValue::Set(port),
+            path: ActiveValue::Set(path),
+            active: ActiveValue::Set(target.active),
+            created_by: ActiveValue::Set(target.created_by.clone()),
+            created_at: ActiveValue::Set(target.created_at),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! SeaORM entity for the entity_snapshot table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Event store read model database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "entity_snapshot")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entity_id: i32,
+
+    /// The entity as JSON text
+    #[sea_orm(column_type = "Text")]
+    pub state: String,
+
+    /// Sequence number of the event that gave this state
+    pub sequence: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::EntitySnapshot {
+    fn from(model: Model) -> Self {
+        Self {
+            entity_type: model.entity_type,
+            entity_id: model.entity_id,
+            state: model.state,
+            sequence: model.sequence,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::EntitySnapshot> for ActiveModel {
+    fn from(snapshot: &miso_domain::entities::EntitySnapshot) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            entity_type: ActiveValue::Set(snapshot.entity_type.clone()),
+            entity_id: ActiveValue::Set(snapshot.entity_id),
+            state: ActiveValue::Set(snapshot.state.clone()),
+            sequence: ActiveValue::Set(snapshot.sequence),
+        }
+    }
+}
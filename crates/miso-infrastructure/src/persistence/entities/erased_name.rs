@@ -0,0 +1,36 @@
+//! SeaORM entity for the erased_name table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Hash of an erased identity's external name.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "erased_name")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub erasure_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))", unique)]
+    pub name_hash: String,
+}
+
+/// Database relations for ErasedName.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::erasure::Entity",
+        from = "Column::ErasureId",
+        to = "super::erasure::Column::Id"
+    )]
+    Erasure,
+}
+
+impl Related<super::erasure::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Erasure.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,76 @@
+//! SeaORM entity for the erasure table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Erasure database entity. Its name hashes are in erased_name.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "erasure")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub identity_id: i32,
+
+    pub samples_erased: i32,
+
+    #[sea_orm(column_type = "Text")]
+    pub reason: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub erased_by: String,
+
+    pub erased_at: DateTimeUtc,
+}
+
+/// Database relations for Erasure.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::erased_name::Entity")]
+    ErasedNames,
+}
+
+impl Related<super::erased_name::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ErasedNames.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts to the domain entity, with its erased names' hashes.
+    pub fn into_domain(
+        self,
+        names: Vec<super::erased_name::Model>,
+    ) -> miso_domain::entities::Erasure {
+        miso_domain::entities::Erasure {
+            id: self.id,
+            identity_id: self.identity_id,
+            name_hashes: names.into_iter().map(|name| name.name_hash).collect(),
+            samples_erased: self.samples_erased.max(0) as u32,
+            reason: self.reason,
+            erased_by: self.erased_by,
+            erased_at: self.erased_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Erasure> for ActiveModel {
+    fn from(erasure: &miso_domain::entities::Erasure) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if erasure.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(erasure.id)
+            },
+            identity_id: ActiveValue::Set(erasure.identity_id),
+            samples_erased: ActiveValue::Set(erasure.samples_erased as i32),
+            reason: ActiveValue::Set(erasure.reason.clone()),
+            erased_by: ActiveValue::Set(erasure.erased_by.clone()),
+            erased_at: ActiveValue::Set(erasure.erased_at),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! SeaORM entity for the export_template table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Export template database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "export_template")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    /// "project" or "sample"
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    /// Ordered column definitions
+    pub columns: Json,
+
+    /// "comma", "tab", "semicolon" or "pipe"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub delimiter: String,
+
+    pub include_header: bool,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::ExportTemplate {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::entities::{Delimiter, ExportColumn};
+        use miso_domain::errors::DomainError;
+
+        let delimiter = match model.delimiter.as_str() {
+            "tab" => Delimiter::Tab,
+            "semicolon" => Delimiter::Semicolon,
+            "pipe" => Delimiter::Pipe,
+            _ => Delimiter::Comma,
+        };
+
+        let columns: Vec<ExportColumn> = serde_json::from_value(model.columns)
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            entity_type: model.entity_type.parse()?,
+            columns,
+            delimiter,
+            include_header: model.include_header,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::ExportTemplate> for ActiveModel {
+    fn from(template: &miso_domain::entities::ExportTemplate) -> Self {
+        use miso_domain::entities::Delimiter;
+        use sea_orm::ActiveValue;
+
+        let delimiter = match template.delimiter {
+            Delimiter::Comma => "comma",
+            Delimiter::Tab => "tab",
+            Delimiter::Semicolon => "semicolon",
+            Delimiter::Pipe => "pipe",
+        };
+
+        Self {
+            id: if template.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(template.id)
+            },
+            name: ActiveValue::Set(template.name.clone()),
+            entity_type: ActiveValue::Set(template.entity_type.to_string()),
+            columns: ActiveValue::Set(
+                serde_json::to_value(&template.columns).unwrap_or_default(),
+            ),
+            delimiter: ActiveValue::Set(delimiter.to_string()),
+            include_header: ActiveValue::Set(template.include_header),
+            created_by: ActiveValue::Set(template.created_by.clone()),
+            created_at: ActiveValue::Set(template.created_at),
+            updated_at: ActiveValue::Set(template.updated_at),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! SeaORM entity for the extraction_batch table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::extraction_batch_sample;
+
+/// Extraction batch database entity. The batch's samples and blanks are
+/// held in [`extraction_batch_sample`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "extraction_batch")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub instrument: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub kit: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub kit_lot: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub operator: String,
+
+    pub extracted_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for ExtractionBatch.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::extraction_batch_sample::Entity")]
+    ExtractionBatchSample,
+}
+
+impl Related<super::extraction_batch_sample::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExtractionBatchSample.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored batch and its samples to the domain entity.
+    pub fn into_domain(
+        self,
+        mut samples: Vec<extraction_batch_sample::Model>,
+    ) -> miso_domain::entities::ExtractionBatch {
+        samples.sort_by_key(|s| s.position);
+        let (blanks, stocks): (Vec<_>, Vec<_>) = samples.into_iter().partition(|s| s.blank);
+
+        miso_domain::entities::ExtractionBatch {
+            id: self.id,
+            name: self.name,
+            instrument: self.instrument,
+            kit: self.kit,
+            kit_lot: self.kit_lot,
+            operator: self.operator,
+            extracted_at: self.extracted_at,
+            sample_ids: stocks.into_iter().map(|s| s.sample_id).collect(),
+            blank_ids: blanks.into_iter().map(|s| s.sample_id).collect(),
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ExtractionBatch> for ActiveModel {
+    fn from(batch: &miso_domain::entities::ExtractionBatch) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if batch.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(batch.id)
+            },
+            name: ActiveValue::Set(batch.name.clone()),
+            instrument: ActiveValue::Set(batch.instrument.clone()),
+            kit: ActiveValue::Set(batch.kit.clone()),
+            kit_lot: ActiveValue::Set(batch.kit_lot.clone()),
+            operator: ActiveValue::Set(batch.operator.clone()),
+            extracted_at: ActiveValue::Set(batch.extracted_at),
+            created_by: ActiveValue::Set(batch.created_by.clone()),
+            created_at: ActiveValue::Set(batch.created_at),
+            updated_at: ActiveValue::Set(batch.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use miso_domain::entities::ExtractionBatch;
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_extraction_batch_round_trips_through_models() {
+        let now = Utc::now();
+        let mut batch =
+            ExtractionBatch::new("EXT-1", "DNeasy", "L123", "jo", now, "tech".to_string())
+                .unwrap();
+        batch.id = 3;
+        batch.instrument = Some("QIAcube 2".to_string());
+        batch.add_sample(9, now);
+        batch.add_blank(5, now);
+        batch.add_sample(4, now);
+
+        let model = ActiveModel::from(&batch).try_into_model().unwrap();
+        let samples = extraction_batch_sample::ActiveModel::for_batch(3, &batch)
+            .into_iter()
+            .rev()
+            .map(|row| row.try_into_model().unwrap())
+            .collect();
+
+        assert_eq!(model.into_domain(samples), batch);
+    }
+}
@@ -0,0 +1,65 @@
+//! SeaORM entity for the extraction_batch_sample table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A Stock sample or reagent blank of an extraction batch. A sample is in
+/// at most one batch.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "extraction_batch_sample")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sample_id: i32,
+
+    pub batch_id: i32,
+
+    /// Whether the sample is a reagent blank
+    pub blank: bool,
+
+    /// Order the sample was added in among the batch's samples or its
+    /// blanks, from 0
+    pub position: i32,
+}
+
+/// Database relations for ExtractionBatchSample.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::extraction_batch::Entity",
+        from = "Column::BatchId",
+        to = "super::extraction_batch::Column::Id"
+    )]
+    ExtractionBatch,
+}
+
+impl Related<super::extraction_batch::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExtractionBatch.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the rows for a batch's samples and blanks, stored under
+    /// `batch_id`.
+    pub fn for_batch(batch_id: i32, batch: &miso_domain::entities::ExtractionBatch) -> Vec<Self> {
+        use sea_orm::ActiveValue;
+
+        let row = |blank: bool| {
+            move |(position, &sample_id): (usize, &i32)| Self {
+                sample_id: ActiveValue::Set(sample_id),
+                batch_id: ActiveValue::Set(batch_id),
+                blank: ActiveValue::Set(blank),
+                position: ActiveValue::Set(i32::try_from(position).unwrap_or(i32::MAX)),
+            }
+        };
+        batch
+            .sample_ids
+            .iter()
+            .enumerate()
+            .map(row(false))
+            .chain(batch.blank_ids.iter().enumerate().map(row(true)))
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+//! SeaORM entity for the index_set table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::{index_family_code, index_family_from_code};
+
+/// Index set database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "index_set")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub family: String,
+
+    /// Index list, in kit order
+    pub indices: Json,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::IndexSet {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            family: index_family_from_code(Some(&model.family)),
+            indices: serde_json::from_value(model.indices)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            created_by: model.created_by,
+            created_at: model.created_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::IndexSet> for ActiveModel {
+    fn from(set: &miso_domain::entities::IndexSet) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if set.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(set.id)
+            },
+            name: ActiveValue::Set(set.name.clone()),
+            family: ActiveValue::Set(index_family_code(set.family).to_string()),
+            indices: ActiveValue::Set(serde_json::to_value(&set.indices).unwrap_or_default()),
+            created_by: ActiveValue::Set(set.created_by.clone()),
+            created_at: ActiveValue::Set(set.created_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miso_domain::entities::IndexSet;
+    use miso_domain::value_objects::{DnaIndex, IndexFamily};
+    use sea_orm::TryIntoModel;
+
+    use super::*;
+
+    #[test]
+    fn test_index_set_round_trips_through_model() {
+        let mut set = IndexSet::new(
+            "IDT UDI Plate 1".to_string(),
+            IndexFamily::IdtUdi,
+            vec![
+                DnaIndex::dual("UDP0001", "GAACTGAGCG", "TCGTGGAGCG", IndexFamily::IdtUdi).unwrap(),
+                DnaIndex::dual("UDP0002", "AGGTCAGATA", "CTACAAGATA", IndexFamily::IdtUdi).unwrap(),
+            ],
+            "admin".to_string(),
+        )
+        .unwrap();
+        set.id = 4;
+
+        let model = ActiveModel::from(&set).try_into_model().unwrap();
+        assert_eq!(model.family, "idt_udi");
+        let restored = IndexSet::try_from(model).unwrap();
+        assert_eq!(restored, set);
+    }
+}
@@ -0,0 +1,80 @@
+//! SeaORM entity for the instrument_event table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Instrument-reported run event database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "instrument_event")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub run_id: i32,
+
+    /// "warning" or "error"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub severity: String,
+
+    /// Run folder file the event was read from
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub source: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub code: Option<String>,
+
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+
+    pub occurred_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub ingested_by: String,
+
+    pub ingested_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::InstrumentEvent {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            run_id: model.run_id,
+            severity: model.severity.parse()?,
+            source: model.source,
+            code: model.code,
+            message: model.message,
+            occurred_at: model.occurred_at,
+            ingested_by: model.ingested_by,
+            ingested_at: model.ingested_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::InstrumentEvent> for ActiveModel {
+    fn from(event: &miso_domain::entities::InstrumentEvent) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if event.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(event.id)
+            },
+            run_id: ActiveValue::Set(event.run_id),
+            severity: ActiveValue::Set(event.severity.to_string()),
+            source: ActiveValue::Set(event.source.clone()),
+            code: ActiveValue::Set(event.code.clone()),
+            message: ActiveValue::Set(event.message.clone()),
+            occurred_at: ActiveValue::Set(event.occurred_at),
+            ingested_by: ActiveValue::Set(event.ingested_by.clone()),
+            ingested_at: ActiveValue::Set(event.ingested_at),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! SeaORM entity for the instrument_model table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Instrument model catalog database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "instrument_model")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// "illumina", "oxford_nanopore", "pac_bio", ...
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub platform: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    pub partitions: i32,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    /// Names of the supported container models
+    pub container_models: Json,
+
+    /// Names of the supported container models that are patterned flow cells
+    pub patterned_containers: Option<Json>,
+
+    /// Supported chemistry versions
+    pub chemistries: Json,
+
+    pub max_read_length: Option<i32>,
+
+    pub expected_run_hours: Option<i32>,
+
+    pub seconds_per_cycle: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::InstrumentModel {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        let list = |value: Json| -> Result<Vec<String>, DomainError> {
+            serde_json::from_value(value).map_err(|e| DomainError::Validation(e.to_string()))
+        };
+
+        Ok(Self {
+            id: model.id,
+            platform: platform_from_code(&model.platform),
+            name: model.name,
+            partitions: u8::try_from(model.partitions)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            description: model.description,
+            container_models: list(model.container_models)?,
+            patterned_containers: model
+                .patterned_containers
+                .map(list)
+                .transpose()?
+                .unwrap_or_default(),
+            chemistries: list(model.chemistries)?,
+            max_read_length: model
+                .max_read_length
+                .map(u16::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            expected_run_hours: model
+                .expected_run_hours
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            seconds_per_cycle: model
+                .seconds_per_cycle
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::InstrumentModel> for ActiveModel {
+    fn from(model: &miso_domain::entities::InstrumentModel) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if model.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(model.id)
+            },
+            platform: ActiveValue::Set(platform_code(model.platform).to_string()),
+            name: ActiveValue::Set(model.name.clone()),
+            partitions: ActiveValue::Set(model.partitions as i32),
+            description: ActiveValue::Set(model.description.clone()),
+            container_models: ActiveValue::Set(
+                serde_json::to_value(&model.container_models).unwrap_or_default(),
+            ),
+            patterned_containers: ActiveValue::Set(
+                (!model.patterned_containers.is_empty()).then(|| {
+                    serde_json::to_value(&model.patterned_containers).unwrap_or_default()
+                }),
+            ),
+            chemistries: ActiveValue::Set(
+                serde_json::to_value(&model.chemistries).unwrap_or_default(),
+            ),
+            max_read_length: ActiveValue::Set(model.max_read_length.map(i32::from)),
+            expected_run_hours: ActiveValue::Set(
+                model
+                    .expected_run_hours
+                    .map(|hours| i32::try_from(hours).unwrap_or(i32::MAX)),
+            ),
+            seconds_per_cycle: ActiveValue::Set(
+                model
+                    .seconds_per_cycle
+                    .map(|seconds| i32::try_from(seconds).unwrap_or(i32::MAX)),
+            ),
+        }
+    }
+}
+
+/// Returns the stored code for a platform.
+pub(crate) fn platform_code(platform: miso_domain::entities::Platform) -> &'static str {
+    use miso_domain::entities::Platform;
+
+    match platform {
+        Platform::Illumina => "illumina",
+        Platform::OxfordNanopore => "oxford_nanopore",
+        Platform::PacBio => "pac_bio",
+        Platform::IonTorrent => "ion_torrent",
+        Platform::Element => "element",
+        Platform::Mgi => "mgi",
+        Platform::Ultima => "ultima",
+        Platform::Other => "other",
+    }
+}
+
+/// Parses a stored platform code; unknown codes read as `Other`.
+pub(crate) fn platform_from_code(code: &str) -> miso_domain::entities::Platform {
+    use miso_domain::entities::Platform;
+
+    match code {
+        "illumina" => Platform::Illumina,
+        "oxford_nanopore" => Platform::OxfordNanopore,
+        "pac_bio" => Platform::PacBio,
+        "ion_torrent" => Platform::IonTorrent,
+        "element" => Platform::Element,
+        "mgi" => Platform::Mgi,
+        "ultima" => Platform::Ultima,
+        _ => Platform::Other,
+    }
+}
@@ -0,0 +1,104 @@
+//! SeaORM entity for the item_location table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where a storable item was last put away. Kept separately from
+/// [`box_position`](super::box_position) so the two can be reconciled.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "item_location")]
+pub struct Model {
+    /// Code of the item's type, e.g. "sample"
+    #[sea_orm(
+        primary_key,
+        auto_increment = false,
+        column_type = "String(StringLen::N(20))"
+    )]
+    pub item_type: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_id: i32,
+
+    pub box_id: i32,
+
+    /// Position in the box, e.g. "B12"
+    #[sea_orm(column_type = "String(StringLen::N(4))")]
+    pub position: String,
+}
+
+/// Database relations for ItemLocation.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::storage_box::Entity",
+        from = "Column::BoxId",
+        to = "super::storage_box::Column::Id"
+    )]
+    StorageBox,
+}
+
+impl Related<super::storage_box::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StorageBox.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Returns the recorded position. The box may since have been resized,
+    /// so the position isn't checked against its dimensions.
+    pub fn box_position(&self) -> Option<miso_domain::value_objects::BoxPosition> {
+        let mut chars = self.position.chars();
+        let row = chars.next().filter(char::is_ascii_alphabetic)?;
+        let col = chars.as_str().parse().ok()?;
+
+        Some(miso_domain::value_objects::BoxPosition::new_unchecked(
+            row, col,
+        ))
+    }
+}
+
+impl ActiveModel {
+    /// Builds the row recording an item at a position of the box with
+    /// `box_id`.
+    pub fn new(
+        box_id: i32,
+        position: &miso_domain::value_objects::BoxPosition,
+        item: &miso_domain::entities::StorableItem,
+    ) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            item_type: ActiveValue::Set(
+                super::storage_box::storable_type_code(item.item_type).to_string(),
+            ),
+            item_id: ActiveValue::Set(item.item_id),
+            box_id: ActiveValue::Set(box_id),
+            position: ActiveValue::Set(position.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(position: &str) -> Model {
+        Model {
+            item_type: "sample".to_string(),
+            item_id: 1,
+            box_id: 1,
+            position: position.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_box_position() {
+        let position = at("B12").box_position().unwrap();
+        assert_eq!(position.row(), 'B');
+        assert_eq!(position.col(), 12);
+        assert!(at("12").box_position().is_none());
+        assert!(at("B").box_position().is_none());
+    }
+}
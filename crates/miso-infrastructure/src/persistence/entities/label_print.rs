@@ -0,0 +1,71 @@
+//! SeaORM entity for the label_print table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Label print database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "label_print")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    pub entity_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub template: String,
+
+    pub copy: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub printed_by: String,
+
+    pub printed_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub override_reason: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::LabelPrint {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            entity_type: model.entity_type,
+            entity_id: model.entity_id,
+            template: model.template,
+            copy: model.copy as u32,
+            printed_by: model.printed_by,
+            printed_at: model.printed_at,
+            override_reason: model.override_reason,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::LabelPrint> for ActiveModel {
+    fn from(print: &miso_domain::entities::LabelPrint) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if print.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(print.id)
+            },
+            entity_type: ActiveValue::Set(print.entity_type.clone()),
+            entity_id: ActiveValue::Set(print.entity_id),
+            template: ActiveValue::Set(print.template.clone()),
+            copy: ActiveValue::Set(print.copy as i32),
+            printed_by: ActiveValue::Set(print.printed_by.clone()),
+            printed_at: ActiveValue::Set(print.printed_at),
+            override_reason: ActiveValue::Set(print.override_reason.clone()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! SeaORM entity for the label_usage table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Label usage database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "label_usage")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub printer: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub template: String,
+
+    pub labels: i32,
+
+    pub odometer: Option<i64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub printed_by: String,
+
+    pub printed_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::LabelUsage {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            printer: model.printer,
+            template: model.template,
+            labels: model.labels as u32,
+            odometer: model.odometer.map(|count| count as u64),
+            printed_by: model.printed_by,
+            printed_at: model.printed_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::LabelUsage> for ActiveModel {
+    fn from(usage: &miso_domain::entities::LabelUsage) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if usage.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(usage.id)
+            },
+            printer: ActiveValue::Set(usage.printer.clone()),
+            template: ActiveValue::Set(usage.template.clone()),
+            labels: ActiveValue::Set(usage.labels as i32),
+            odometer: ActiveValue::Set(usage.odometer.map(|count| count as i64)),
+            printed_by: ActiveValue::Set(usage.printed_by.clone()),
+            printed_at: ActiveValue::Set(usage.printed_at),
+        }
+    }
+}
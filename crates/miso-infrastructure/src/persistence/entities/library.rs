@@ -0,0 +1,413 @@
+//! SeaORM entity for the library table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Library database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "library")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", unique)]
+    pub barcode: String,
+
+    pub sample_id: i32,
+
+    pub project_id: i32,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    /// "wgs", "wes", "rna_seq", ... or "custom"
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub design: String,
+
+    /// Name of a custom design
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub custom_design: Option<String>,
+
+    /// "paired_end", "single_end" or "mate_pair"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub library_type: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub platform: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub kit_name: Option<String>,
+
+    /// Targeted panel version, for targeted panel designs
+    pub panel_id: Option<i32>,
+
+    /// Reference genome for the library's assay, if not its project's
+    pub reference_genome_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub index_name: Option<String>,
+
+    /// "truseq", "nextera", "idt_udi", "ten_x" or "custom"
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub index_family: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub i7_sequence: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub i5_sequence: Option<String>,
+
+    pub insert_size: Option<i32>,
+
+    /// Volume in microliters
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub volume: Option<Decimal>,
+
+    /// Concentration in ng/µL
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub concentration: Option<Decimal>,
+
+    /// Mass in ng
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub mass: Option<Decimal>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub qc_status: String,
+
+    pub pcr_cycles: Option<i32>,
+
+    #[sea_orm(default_value = "false")]
+    pub low_quality: bool,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+
+    #[sea_orm(default_value = "false")]
+    pub archived: bool,
+
+    /// "positive", "negative" or "ntc" if the library is a control
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub control_type: Option<String>,
+}
+
+/// Database relations for Library.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sample::Entity",
+        from = "Column::SampleId",
+        to = "super::sample::Column::Id"
+    )]
+    Sample,
+
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+
+    #[sea_orm(
+        belongs_to = "super::panel::Entity",
+        from = "Column::PanelId",
+        to = "super::panel::Column::Id"
+    )]
+    Panel,
+
+    #[sea_orm(
+        belongs_to = "super::reference_genome::Entity",
+        from = "Column::ReferenceGenomeId",
+        to = "super::reference_genome::Column::Id"
+    )]
+    ReferenceGenome,
+}
+
+impl Related<super::sample::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sample.def()
+    }
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::panel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Panel.def()
+    }
+}
+
+impl Related<super::reference_genome::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReferenceGenome.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::Library {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::entities::{LibraryDesign, LibraryType};
+        use miso_domain::errors::DomainError;
+        use miso_domain::value_objects::{Barcode, Concentration, DnaIndex, Mass, Volume};
+
+        let design = match model.design.as_str() {
+            "wgs" => LibraryDesign::Wgs,
+            "wes" => LibraryDesign::Wes,
+            "rna_seq" => LibraryDesign::RnaSeq,
+            "targeted_panel" => LibraryDesign::TargetedPanel,
+            "chip_seq" => LibraryDesign::ChipSeq,
+            "atac_seq" => LibraryDesign::AtacSeq,
+            "methylation" => LibraryDesign::Methylation,
+            "single_cell_rna" => LibraryDesign::SingleCellRna,
+            "single_cell_atac" => LibraryDesign::SingleCellAtac,
+            other => {
+                LibraryDesign::Custom(model.custom_design.unwrap_or_else(|| other.to_string()))
+            }
+        };
+
+        let library_type = match model.library_type.as_str() {
+            "single_end" => LibraryType::SingleEnd,
+            "mate_pair" => LibraryType::MatePair,
+            _ => LibraryType::PairedEnd,
+        };
+
+        let index = match model.i7_sequence {
+            Some(i7) => {
+                let name = model.index_name.unwrap_or_default();
+                let family = index_family_from_code(model.index_family.as_deref());
+                let index = match model.i5_sequence {
+                    Some(i5) => DnaIndex::dual(name, i7, i5, family),
+                    None => DnaIndex::single(name, i7, family),
+                };
+                Some(index?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            barcode: Barcode::new_unchecked(model.barcode),
+            sample_id: model.sample_id,
+            project_id: model.project_id,
+            description: model.description,
+            design,
+            library_type,
+            platform: model.platform,
+            kit_name: model.kit_name,
+            panel_id: model.panel_id,
+            reference_genome_id: model.reference_genome_id,
+            index,
+            insert_size: model
+                .insert_size
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            volume: model.volume.map(|v| Volume::microliters(decimal_to_f64(v))),
+            mass: model.mass.map(|m| Mass::nanograms(decimal_to_f64(m))),
+            concentration: model
+                .concentration
+                .map(|c| Concentration::ng_per_ul(decimal_to_f64(c))),
+            qc_status: qc_status_from_code(&model.qc_status),
+            pcr_cycles: model
+                .pcr_cycles
+                .map(u8::try_from)
+                .transpose()
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            low_quality: model.low_quality,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            archived: model.archived,
+            control_type: model
+                .control_type
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(DomainError::Validation)?,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::Library> for ActiveModel {
+    fn from(library: &miso_domain::entities::Library) -> Self {
+        use miso_domain::entities::{LibraryDesign, LibraryType};
+        use sea_orm::ActiveValue;
+
+        let (design, custom_design) = match &library.design {
+            LibraryDesign::Wgs => ("wgs", None),
+            LibraryDesign::Wes => ("wes", None),
+            LibraryDesign::RnaSeq => ("rna_seq", None),
+            LibraryDesign::TargetedPanel => ("targeted_panel", None),
+            LibraryDesign::ChipSeq => ("chip_seq", None),
+            LibraryDesign::AtacSeq => ("atac_seq", None),
+            LibraryDesign::Methylation => ("methylation", None),
+            LibraryDesign::SingleCellRna => ("single_cell_rna", None),
+            LibraryDesign::SingleCellAtac => ("single_cell_atac", None),
+            LibraryDesign::Custom(name) => ("custom", Some(name.clone())),
+        };
+
+        let library_type = match library.library_type {
+            LibraryType::PairedEnd => "paired_end",
+            LibraryType::SingleEnd => "single_end",
+            LibraryType::MatePair => "mate_pair",
+        };
+
+        let index = library.index.as_ref();
+
+        Self {
+            id: if library.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(library.id)
+            },
+            name: ActiveValue::Set(library.name.clone()),
+            barcode: ActiveValue::Set(library.barcode.to_string()),
+            sample_id: ActiveValue::Set(library.sample_id),
+            project_id: ActiveValue::Set(library.project_id),
+            description: ActiveValue::Set(library.description.clone()),
+            design: ActiveValue::Set(design.to_string()),
+            custom_design: ActiveValue::Set(custom_design),
+            library_type: ActiveValue::Set(library_type.to_string()),
+            platform: ActiveValue::Set(library.platform.clone()),
+            kit_name: ActiveValue::Set(library.kit_name.clone()),
+            panel_id: ActiveValue::Set(library.panel_id),
+            reference_genome_id: ActiveValue::Set(library.reference_genome_id),
+            index_name: ActiveValue::Set(index.map(|i| i.name().to_string())),
+            index_family: ActiveValue::Set(
+                index.map(|i| index_family_code(i.family()).to_string()),
+            ),
+            i7_sequence: ActiveValue::Set(index.map(|i| i.i7().to_string())),
+            i5_sequence: ActiveValue::Set(index.and_then(|i| i.i5()).map(str::to_string)),
+            insert_size: ActiveValue::Set(
+                library
+                    .insert_size
+                    .map(|size| i32::try_from(size).unwrap_or(i32::MAX)),
+            ),
+            volume: ActiveValue::Set(library.volume.and_then(|v| to_decimal(v.as_microliters()))),
+            mass: ActiveValue::Set(library.mass.and_then(|m| to_decimal(m.as_nanograms()))),
+            concentration: ActiveValue::Set(
+                library.concentration.and_then(|c| to_decimal(c.value())),
+            ),
+            qc_status: ActiveValue::Set(qc_status_code(library.qc_status).to_string()),
+            pcr_cycles: ActiveValue::Set(library.pcr_cycles.map(i32::from)),
+            low_quality: ActiveValue::Set(library.low_quality),
+            created_by: ActiveValue::Set(library.created_by.clone()),
+            created_at: ActiveValue::Set(library.created_at),
+            updated_at: ActiveValue::Set(library.updated_at),
+            archived: ActiveValue::Set(library.archived),
+            control_type: ActiveValue::Set(library.control_type.map(|c| c.as_str().to_string())),
+        }
+    }
+}
+
+/// Returns the stored code for a QC status.
+pub(crate) fn qc_status_code(status: miso_domain::value_objects::QcStatus) -> &'static str {
+    use miso_domain::value_objects::QcStatus;
+
+    match status {
+        QcStatus::NotReady => "not_ready",
+        QcStatus::Ready => "ready",
+        QcStatus::Passed => "passed",
+        QcStatus::Failed => "failed",
+        QcStatus::NeedsReview => "needs_review",
+    }
+}
+
+/// Parses a stored QC status code; unknown codes read as not ready.
+pub(crate) fn qc_status_from_code(code: &str) -> miso_domain::value_objects::QcStatus {
+    use miso_domain::value_objects::QcStatus;
+
+    match code {
+        "ready" => QcStatus::Ready,
+        "passed" => QcStatus::Passed,
+        "failed" => QcStatus::Failed,
+        "needs_review" => QcStatus::NeedsReview,
+        _ => QcStatus::NotReady,
+    }
+}
+
+/// Rounds a quantity to the two decimal places the columns hold.
+pub(crate) fn to_decimal(value: f64) -> Option<Decimal> {
+    Decimal::from_f64_retain(value).map(|d| d.round_dp(2))
+}
+
+/// Reads a stored quantity back as a float.
+pub(crate) fn decimal_to_f64(value: Decimal) -> f64 {
+    use std::str::FromStr;
+
+    f64::from_str(&value.to_string()).unwrap_or(0.0)
+}
+
+pub(crate) fn index_family_code(family: miso_domain::value_objects::IndexFamily) -> &'static str {
+    use miso_domain::value_objects::IndexFamily;
+
+    match family {
+        IndexFamily::TruSeq => "truseq",
+        IndexFamily::Nextera => "nextera",
+        IndexFamily::IdtUdi => "idt_udi",
+        IndexFamily::TenX => "ten_x",
+        IndexFamily::Custom => "custom",
+    }
+}
+
+pub(crate) fn index_family_from_code(code: Option<&str>) -> miso_domain::value_objects::IndexFamily {
+    use miso_domain::value_objects::IndexFamily;
+
+    match code {
+        Some("truseq") => IndexFamily::TruSeq,
+        Some("nextera") => IndexFamily::Nextera,
+        Some("idt_udi") => IndexFamily::IdtUdi,
+        Some("ten_x") => IndexFamily::TenX,
+        _ => IndexFamily::Custom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{Library, LibraryDesign, LibraryType};
+    use miso_domain::value_objects::{Barcode, DnaIndex, IndexFamily};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_library_round_trips_through_model() {
+        let mut library = Library::new(
+            5,
+            "LIB_0005".to_string(),
+            Barcode::new("LIB-0005").unwrap(),
+            2,
+            1,
+            LibraryDesign::Custom("Hi-C".to_string()),
+            LibraryType::PairedEnd,
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        library.set_index(
+            DnaIndex::dual("UDP0001", "GAACTGAGCG", "TCGTGGAGCG", IndexFamily::IdtUdi).unwrap(),
+        );
+        library.pcr_cycles = Some(8);
+
+        let model = ActiveModel::from(&library).try_into_model().unwrap();
+        assert_eq!(model.design, "custom");
+        assert_eq!(model.index_family.as_deref(), Some("idt_udi"));
+
+        let restored = Library::try_from(model).unwrap();
+        assert_eq!(restored.design, library.design);
+        assert_eq!(restored.index, library.index);
+        assert_eq!(restored.pcr_cycles, Some(8));
+    }
+}
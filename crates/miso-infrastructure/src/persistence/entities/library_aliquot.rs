@@ -0,0 +1,77 @@
+//! SeaORM entity for the library_aliquot table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::{decimal_to_f64, to_decimal};
+
+/// Library aliquot database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "library_aliquot")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub library_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable, unique)]
+    pub barcode: Option<String>,
+
+    /// Volume in microliters
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub volume: Option<Decimal>,
+
+    /// Concentration in ng/µL
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub concentration: Option<Decimal>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::LibraryAliquot {
+    fn from(model: Model) -> Self {
+        use miso_domain::value_objects::{Barcode, Concentration, Volume};
+
+        Self {
+            id: model.id,
+            library_id: model.library_id,
+            barcode: model.barcode.map(Barcode::new_unchecked),
+            volume: model.volume.map(|v| Volume::microliters(decimal_to_f64(v))),
+            concentration: model
+                .concentration
+                .map(|c| Concentration::ng_per_ul(decimal_to_f64(c))),
+            created_at: model.created_at,
+            created_by: model.created_by,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::LibraryAliquot> for ActiveModel {
+    fn from(aliquot: &miso_domain::entities::LibraryAliquot) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if aliquot.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(aliquot.id)
+            },
+            library_id: ActiveValue::Set(aliquot.library_id),
+            barcode: ActiveValue::Set(aliquot.barcode.as_ref().map(|b| b.to_string())),
+            volume: ActiveValue::Set(aliquot.volume.and_then(|v| to_decimal(v.as_microliters()))),
+            concentration: ActiveValue::Set(
+                aliquot.concentration.and_then(|c| to_decimal(c.value())),
+            ),
+            created_by: ActiveValue::Set(aliquot.created_by.clone()),
+            created_at: ActiveValue::Set(aliquot.created_at),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! SeaORM entity for the log_archive table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Log archive database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "log_archive")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Name of the table the rows came from
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub log: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(1024))")]
+    pub file: String,
+
+    pub first_id: i32,
+
+    pub last_id: i32,
+
+    pub row_count: i64,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub last_hash: String,
+
+    pub archived_at: DateTimeUtc,
+
+    pub restored_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::LogArchive {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            log: model.log.parse()?,
+            file: model.file,
+            first_id: model.first_id,
+            last_id: model.last_id,
+            rows: model.row_count as u64,
+            last_hash: model.last_hash,
+            archived_at: model.archived_at,
+            restored_at: model.restored_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::LogArchive> for ActiveModel {
+    fn from(archive: &miso_domain::entities::LogArchive) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if archive.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(archive.id)
+            },
+            log: ActiveValue::Set(archive.log.as_str().to_string()),
+            file: ActiveValue::Set(archive.file.clone()),
+            first_id: ActiveValue::Set(archive.first_id),
+            last_id: ActiveValue::Set(archive.last_id),
+            row_count: ActiveValue::Set(archive.rows as i64),
+            last_hash: ActiveValue::Set(archive.last_hash.clone()),
+            archived_at: ActiveValue::Set(archive.archived_at),
+            restored_at: ActiveValue::Set(archive.restored_at),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! SeaORM entity for the maintenance_window table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Maintenance window database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "maintenance_window")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Null for facility-wide maintenance
+    pub sequencer_id: Option<i32>,
+
+    pub starts_at: DateTimeUtc,
+
+    pub ends_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(500))")]
+    pub reason: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+/// Database relations for MaintenanceWindow.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::MaintenanceWindow {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            sequencer_id: model.sequencer_id,
+            starts_at: model.starts_at,
+            ends_at: model.ends_at,
+            reason: model.reason,
+            created_by: model.created_by,
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::MaintenanceWindow> for ActiveModel {
+    fn from(window: &miso_domain::entities::MaintenanceWindow) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if window.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(window.id)
+            },
+            sequencer_id: ActiveValue::Set(window.sequencer_id),
+            starts_at: ActiveValue::Set(window.starts_at),
+            ends_at: ActiveValue::Set(window.ends_at),
+            reason: ActiveValue::Set(window.reason.clone()),
+            created_by: ActiveValue::Set(window.created_by.clone()),
+            created_at: ActiveValue::Set(window.created_at),
+        }
+    }
+}
@@ -3,10 +3,130 @@
 //! These entities map directly to the MySQL database tables.
 //! They are generated/maintained to match the legacy MISO schema.
 
+pub mod api_key;
+pub mod api_usage;
+pub mod archive_rule;
+pub mod attachment;
+pub mod attribute_definition;
+pub mod audit_log;
+pub mod auto_archival;
+pub mod box_position;
+pub mod change_log;
+pub mod consent;
+pub mod data_location;
+pub mod delivery;
+pub mod delivery_target;
+pub mod entity_snapshot;
+pub mod erased_name;
+pub mod erasure;
+pub mod export_template;
+pub mod extraction_batch;
+pub mod extraction_batch_sample;
+pub mod index_set;
+pub mod instrument_event;
+pub mod instrument_model;
+pub mod item_location;
+pub mod label_print;
+pub mod label_usage;
+pub mod library;
+pub mod library_aliquot;
+pub mod log_archive;
+pub mod maintenance_window;
+pub mod panel;
+pub mod pool;
+pub mod pool_element;
+pub mod possible_duplicate;
 pub mod project;
+pub mod project_member;
+pub mod qc_result;
+pub mod reconciliation_report;
+pub mod reagent_lot;
+pub mod reference_genome;
+pub mod requisition;
+pub mod requisition_sample;
+pub mod run;
+pub mod run_interruption;
+pub mod run_library_metrics;
+pub mod run_partition;
+pub mod run_qc_report;
+pub mod run_qc_sample_metrics;
 pub mod sample;
+pub mod sample_component;
+pub mod sample_list;
+pub mod sample_list_count;
+pub mod sequencer;
+pub mod storage_audit;
+pub mod storage_box;
+pub mod storage_unit;
+pub mod stored_event;
+pub mod subproject;
+pub mod transfer;
+pub mod transfer_item;
+pub mod user;
+pub mod workset;
+pub mod workset_item;
 
 // Re-export entity types
+pub use api_key::Entity as ApiKeyEntity;
+pub use api_usage::Entity as ApiUsageEntity;
+pub use archive_rule::Entity as ArchiveRuleEntity;
+pub use attachment::Entity as AttachmentEntity;
+pub use attribute_definition::Entity as AttributeDefinitionEntity;
+pub use audit_log::Entity as AuditLogEntity;
+pub use auto_archival::Entity as AutoArchivalEntity;
+pub use box_position::Entity as BoxPositionEntity;
+pub use change_log::Entity as ChangeLogEntity;
+pub use consent::Entity as ConsentEntity;
+pub use data_location::Entity as DataLocationEntity;
+pub use delivery::Entity as DeliveryEntity;
+pub use delivery_target::Entity as DeliveryTargetEntity;
+pub use entity_snapshot::Entity as EntitySnapshotEntity;
+pub use erased_name::Entity as ErasedNameEntity;
+pub use erasure::Entity as ErasureEntity;
+pub use export_template::Entity as ExportTemplateEntity;
+pub use extraction_batch::Entity as ExtractionBatchEntity;
+pub use extraction_batch_sample::Entity as ExtractionBatchSampleEntity;
+pub use index_set::Entity as IndexSetEntity;
+pub use instrument_event::Entity as InstrumentEventEntity;
+pub use instrument_model::Entity as InstrumentModelEntity;
+pub use item_location::Entity as ItemLocationEntity;
+pub use label_print::Entity as LabelPrintEntity;
+pub use label_usage::Entity as LabelUsageEntity;
+pub use library::Entity as LibraryEntity;
+pub use library_aliquot::Entity as LibraryAliquotEntity;
+pub use log_archive::Entity as LogArchiveEntity;
+pub use maintenance_window::Entity as MaintenanceWindowEntity;
+pub use panel::Entity as PanelEntity;
+pub use pool::Entity as PoolEntity;
+pub use pool_element::Entity as PoolElementEntity;
+pub use possible_duplicate::Entity as PossibleDuplicateEntity;
 pub use project::Entity as ProjectEntity;
+pub use project_member::Entity as ProjectMemberEntity;
+pub use qc_result::Entity as QcResultEntity;
+pub use reconciliation_report::Entity as ReconciliationReportEntity;
+pub use reagent_lot::Entity as ReagentLotEntity;
+pub use reference_genome::Entity as ReferenceGenomeEntity;
+pub use requisition::Entity as RequisitionEntity;
+pub use requisition_sample::Entity as RequisitionSampleEntity;
+pub use run::Entity as RunEntity;
+pub use run_interruption::Entity as RunInterruptionEntity;
+pub use run_library_metrics::Entity as RunLibraryMetricsEntity;
+pub use run_partition::Entity as RunPartitionEntity;
+pub use run_qc_report::Entity as RunQcReportEntity;
+pub use run_qc_sample_metrics::Entity as RunQcSampleMetricsEntity;
 pub use sample::Entity as SampleEntity;
+pub use sample_component::Entity as SampleComponentEntity;
+pub use sample_list::Entity as SampleListEntity;
+pub use sample_list_count::Entity as SampleListCountEntity;
+pub use sequencer::Entity as SequencerEntity;
+pub use storage_audit::Entity as StorageAuditEntity;
+pub use storage_box::Entity as StorageBoxEntity;
+pub use storage_unit::Entity as StorageUnitEntity;
+pub use stored_event::Entity as StoredEventEntity;
+pub use subproject::Entity as SubprojectEntity;
+pub use transfer::Entity as TransferEntity;
+pub use transfer_item::Entity as TransferItemEntity;
+pub use user::Entity as UserEntity;
+pub use workset::Entity as WorksetEntity;
+pub use workset_item::Entity as WorksetItemEntity;
 
@@ -0,0 +1,108 @@
+//! SeaORM entity for the panel table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Targeted panel version database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "panel")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    pub version: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub genome_build: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub bed_file_name: String,
+
+    #[sea_orm(column_type = "Text")]
+    pub bed_contents: String,
+
+    pub interval_count: i32,
+
+    pub target_bases: i64,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+/// Database relations for Panel.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::library::Entity")]
+    Library,
+}
+
+impl Related<super::library::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Library.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::Panel {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::entities::BedFile;
+        use miso_domain::errors::DomainError;
+
+        let invalid = |e: std::num::TryFromIntError| DomainError::Validation(e.to_string());
+
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            version: u32::try_from(model.version).map_err(invalid)?,
+            genome_build: model.genome_build,
+            description: model.description,
+            bed_file: BedFile {
+                file_name: model.bed_file_name,
+                contents: model.bed_contents,
+                intervals: u32::try_from(model.interval_count).map_err(invalid)?,
+                target_bases: u64::try_from(model.target_bases).map_err(invalid)?,
+            },
+            created_by: model.created_by,
+            created_at: model.created_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::Panel> for ActiveModel {
+    fn from(panel: &miso_domain::entities::Panel) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if panel.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(panel.id)
+            },
+            name: ActiveValue::Set(panel.name.clone()),
+            version: ActiveValue::Set(i32::try_from(panel.version).unwrap_or(i32::MAX)),
+            genome_build: ActiveValue::Set(panel.genome_build.clone()),
+            description: ActiveValue::Set(panel.description.clone()),
+            bed_file_name: ActiveValue::Set(panel.bed_file.file_name.clone()),
+            bed_contents: ActiveValue::Set(panel.bed_file.contents.clone()),
+            interval_count: ActiveValue::Set(
+                i32::try_from(panel.bed_file.intervals).unwrap_or(i32::MAX),
+            ),
+            target_bases: ActiveValue::Set(
+                i64::try_from(panel.bed_file.target_bases).unwrap_or(i64::MAX),
+            ),
+            created_by: ActiveValue::Set(panel.created_by.clone()),
+            created_at: ActiveValue::Set(panel.created_at),
+        }
+    }
+}
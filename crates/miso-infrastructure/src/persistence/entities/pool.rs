@@ -0,0 +1,196 @@
+//! SeaORM entity for the pool table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::{decimal_to_f64, qc_status_code, qc_status_from_code, to_decimal};
+use super::pool_element;
+
+/// Pool database entity. The pool's library aliquots are held in
+/// [`pool_element`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "pool")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", unique)]
+    pub barcode: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub concentration: Option<Decimal>,
+
+    /// "ng_per_ul", "nanomolar", "picomolar" or "ug_per_ml"
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub concentration_unit: Option<String>,
+
+    /// Volume in microliters
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub volume: Option<Decimal>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub qc_status: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub platform: String,
+
+    /// Container model the pool is made for, e.g. "Flongle Flow Cell"
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub container_model: Option<String>,
+
+    #[sea_orm(default_value = "false")]
+    pub sequenced: bool,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Pool.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::pool_element::Entity")]
+    PoolElement,
+}
+
+impl Related<super::pool_element::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PoolElement.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored pool and its elements to the domain entity.
+    pub fn into_domain(self, elements: Vec<pool_element::Model>) -> miso_domain::entities::Pool {
+        use miso_domain::entities::{Pool, PoolElement};
+        use miso_domain::value_objects::{Barcode, Concentration, ConcentrationUnit, Volume};
+
+        let unit = match self.concentration_unit.as_deref() {
+            Some("nanomolar") => ConcentrationUnit::Nanomolar,
+            Some("picomolar") => ConcentrationUnit::Picomolar,
+            Some("ug_per_ml") => ConcentrationUnit::UgPerMl,
+            _ => ConcentrationUnit::NgPerUl,
+        };
+
+        Pool {
+            id: self.id,
+            name: self.name,
+            barcode: Barcode::new_unchecked(self.barcode),
+            description: self.description,
+            elements: elements
+                .into_iter()
+                .map(|e| PoolElement {
+                    library_aliquot_id: e.library_aliquot_id,
+                    library_id: e.library_id,
+                    volume: e.volume.map(|v| Volume::microliters(decimal_to_f64(v))),
+                    proportion: e.proportion,
+                })
+                .collect(),
+            concentration: self
+                .concentration
+                .map(|c| Concentration::new(decimal_to_f64(c), unit)),
+            volume: self.volume.map(|v| Volume::microliters(decimal_to_f64(v))),
+            qc_status: qc_status_from_code(&self.qc_status),
+            platform: self.platform,
+            container_model: self.container_model,
+            max_size: None,
+            sequenced: self.sequenced,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Pool> for ActiveModel {
+    fn from(pool: &miso_domain::entities::Pool) -> Self {
+        use miso_domain::value_objects::ConcentrationUnit;
+        use sea_orm::ActiveValue;
+
+        let unit = pool.concentration.map(|c| match c.unit() {
+            ConcentrationUnit::NgPerUl => "ng_per_ul",
+            ConcentrationUnit::Nanomolar => "nanomolar",
+            ConcentrationUnit::Picomolar => "picomolar",
+            ConcentrationUnit::UgPerMl => "ug_per_ml",
+        });
+
+        Self {
+            id: if pool.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(pool.id)
+            },
+            name: ActiveValue::Set(pool.name.clone()),
+            barcode: ActiveValue::Set(pool.barcode.to_string()),
+            description: ActiveValue::Set(pool.description.clone()),
+            concentration: ActiveValue::Set(pool.concentration.and_then(|c| to_decimal(c.value()))),
+            concentration_unit: ActiveValue::Set(unit.map(str::to_string)),
+            volume: ActiveValue::Set(pool.volume.and_then(|v| to_decimal(v.as_microliters()))),
+            qc_status: ActiveValue::Set(qc_status_code(pool.qc_status).to_string()),
+            platform: ActiveValue::Set(pool.platform.clone()),
+            container_model: ActiveValue::Set(pool.container_model.clone()),
+            sequenced: ActiveValue::Set(pool.sequenced),
+            created_by: ActiveValue::Set(pool.created_by.clone()),
+            created_at: ActiveValue::Set(pool.created_at),
+            updated_at: ActiveValue::Set(pool.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{Pool, PoolElement};
+    use miso_domain::value_objects::{Barcode, Concentration, ConcentrationUnit, Volume};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_pool_round_trips_through_models() {
+        let mut pool = Pool::new(
+            3,
+            "POOL_0003".to_string(),
+            Barcode::new("POOL-0003").unwrap(),
+            "ILLUMINA".to_string(),
+            "tech".to_string(),
+        );
+        pool.concentration = Some(Concentration::new(4.5, ConcentrationUnit::Nanomolar));
+        pool.container_model = Some("S4 Flow Cell".to_string());
+        pool.add_element(PoolElement {
+            library_aliquot_id: 11,
+            library_id: 7,
+            volume: Some(Volume::microliters(2.5)),
+            proportion: Some(0.5),
+        })
+        .unwrap();
+
+        let model = ActiveModel::from(&pool).try_into_model().unwrap();
+        assert_eq!(model.concentration_unit.as_deref(), Some("nanomolar"));
+        let elements = pool
+            .elements
+            .iter()
+            .map(|e| {
+                pool_element::ActiveModel::new(pool.id, e)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+
+        let restored = model.into_domain(elements);
+        assert_eq!(restored.concentration, pool.concentration);
+        assert_eq!(restored.container_model, pool.container_model);
+        assert_eq!(restored.elements.len(), 1);
+        assert_eq!(restored.elements[0].library_id, 7);
+        assert_eq!(restored.elements[0].volume, Some(Volume::microliters(2.5)));
+    }
+}
@@ -0,0 +1,74 @@
+//! SeaORM entity for the pool_element table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::to_decimal;
+
+/// A library aliquot in a pool.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "pool_element")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub pool_id: i32,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub library_aliquot_id: i32,
+
+    pub library_id: i32,
+
+    /// Volume contributed to the pool, in microliters
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
+    pub volume: Option<Decimal>,
+
+    /// Share of the pool, from 0 to 1
+    #[sea_orm(column_type = "Double", nullable)]
+    pub proportion: Option<f64>,
+}
+
+/// Database relations for PoolElement.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::pool::Entity",
+        from = "Column::PoolId",
+        to = "super::pool::Column::Id"
+    )]
+    Pool,
+
+    #[sea_orm(
+        belongs_to = "super::library::Entity",
+        from = "Column::LibraryId",
+        to = "super::library::Column::Id"
+    )]
+    Library,
+}
+
+impl Related<super::pool::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Pool.def()
+    }
+}
+
+impl Related<super::library::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Library.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for an element of the pool with `pool_id`.
+    pub fn new(pool_id: i32, element: &miso_domain::entities::PoolElement) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            pool_id: ActiveValue::Set(pool_id),
+            library_aliquot_id: ActiveValue::Set(element.library_aliquot_id),
+            library_id: ActiveValue::Set(element.library_id),
+            volume: ActiveValue::Set(element.volume.and_then(|v| to_decimal(v.as_microliters()))),
+            proportion: ActiveValue::Set(element.proportion),
+        }
+    }
+}
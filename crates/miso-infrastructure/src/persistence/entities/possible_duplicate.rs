@@ -0,0 +1,108 @@
+//! SeaORM entity for the possible_duplicate table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Possible duplicate identity database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "possible_duplicate")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub identity_id: i32,
+
+    pub match_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub external_name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub matched_name: String,
+
+    pub distance: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub confirmed_by: String,
+
+    pub confirmed_at: DateTimeUtc,
+
+    /// "distinct" or "duplicate", once reviewed
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub resolution: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub resolved_by: Option<String>,
+
+    pub resolved_at: Option<DateTimeUtc>,
+}
+
+/// Database relations for PossibleDuplicate.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sample::Entity",
+        from = "Column::IdentityId",
+        to = "super::sample::Column::Id"
+    )]
+    Identity,
+
+    #[sea_orm(
+        belongs_to = "super::sample::Entity",
+        from = "Column::MatchId",
+        to = "super::sample::Column::Id"
+    )]
+    Match,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::PossibleDuplicate {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            identity_id: model.identity_id,
+            match_id: model.match_id,
+            external_name: model.external_name,
+            matched_name: model.matched_name,
+            distance: model.distance.max(0) as u32,
+            confirmed_by: model.confirmed_by,
+            confirmed_at: model.confirmed_at,
+            resolution: model.resolution.as_deref().map(str::parse).transpose()?,
+            resolved_by: model.resolved_by,
+            resolved_at: model.resolved_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::PossibleDuplicate> for ActiveModel {
+    fn from(duplicate: &miso_domain::entities::PossibleDuplicate) -> Self {
+        use miso_domain::entities::DuplicateResolution;
+        use sea_orm::ActiveValue;
+
+        let resolution = duplicate.resolution.map(|resolution| match resolution {
+            DuplicateResolution::Distinct => "distinct".to_string(),
+            DuplicateResolution::Duplicate => "duplicate".to_string(),
+        });
+
+        Self {
+            id: if duplicate.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(duplicate.id)
+            },
+            identity_id: ActiveValue::Set(duplicate.identity_id),
+            match_id: ActiveValue::Set(duplicate.match_id),
+            external_name: ActiveValue::Set(duplicate.external_name.clone()),
+            matched_name: ActiveValue::Set(duplicate.matched_name.clone()),
+            distance: ActiveValue::Set(duplicate.distance as i32),
+            confirmed_by: ActiveValue::Set(duplicate.confirmed_by.clone()),
+            confirmed_at: ActiveValue::Set(duplicate.confirmed_at),
+            resolution: ActiveValue::Set(resolution),
+            resolved_by: ActiveValue::Set(duplicate.resolved_by.clone()),
+            resolved_at: ActiveValue::Set(duplicate.resolved_at),
+        }
+    }
+}
@@ -10,42 +10,57 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
 
-    #[sea_orm(column_type = "String(Some(50))", unique)]
+    #[sea_orm(column_type = "String(StringLen::N(50))", unique)]
     pub code: String,
 
-    #[sea_orm(column_type = "String(Some(255))")]
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
     pub name: String,
 
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(20))")]
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
     pub status: String,
 
-    #[sea_orm(column_type = "String(Some(255))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
     pub pi_name: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(255))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
     pub pi_email: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(100))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
     pub reference_number: Option<String>,
 
     #[sea_orm(nullable)]
     pub target_sample_count: Option<i32>,
 
+    /// Reference genome the project's data is analysed against
+    pub reference_genome_id: Option<i32>,
+
     #[sea_orm(default_value = "0")]
     pub sample_count: i32,
 
     pub created_at: DateTimeUtc,
 
-    #[sea_orm(column_type = "String(Some(255))")]
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
     pub created_by: String,
 
     pub updated_at: DateTimeUtc,
 
     #[sea_orm(nullable)]
     pub due_date: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub lock_reason: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub locked_by: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub locked_at: Option<DateTimeUtc>,
+
+    #[sea_orm(nullable)]
+    pub unlock_at: Option<DateTimeUtc>,
 }
 
 /// Database relations for Project.
@@ -53,6 +68,13 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::sample::Entity")]
     Sample,
+
+    #[sea_orm(
+        belongs_to = "super::reference_genome::Entity",
+        from = "Column::ReferenceGenomeId",
+        to = "super::reference_genome::Column::Id"
+    )]
+    ReferenceGenome,
 }
 
 impl Related<super::sample::Entity> for Entity {
@@ -61,11 +83,17 @@ impl Related<super::sample::Entity> for Entity {
     }
 }
 
+impl Related<super::reference_genome::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReferenceGenome.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 impl From<Model> for miso_domain::entities::Project {
     fn from(model: Model) -> Self {
-        use miso_domain::entities::ProjectStatus;
+        use miso_domain::entities::{ProjectLock, ProjectStatus};
 
         let status = match model.status.as_str() {
             "pending" => ProjectStatus::Pending,
@@ -75,6 +103,12 @@ impl From<Model> for miso_domain::entities::Project {
             "cancelled" => ProjectStatus::Cancelled,
             _ => ProjectStatus::Pending,
         };
+        let lock = model.lock_reason.map(|reason| ProjectLock {
+            reason,
+            locked_by: model.locked_by.unwrap_or_default(),
+            locked_at: model.locked_at.unwrap_or(model.updated_at),
+            unlock_at: model.unlock_at,
+        });
 
         Self {
             id: model.id,
@@ -86,11 +120,13 @@ impl From<Model> for miso_domain::entities::Project {
             pi_email: model.pi_email,
             reference_number: model.reference_number,
             target_sample_count: model.target_sample_count.map(|v| v as u32),
+            reference_genome_id: model.reference_genome_id,
             sample_count: model.sample_count as u32,
             created_at: model.created_at,
             created_by: model.created_by,
             updated_at: model.updated_at,
             due_date: model.due_date,
+            lock,
         }
     }
 }
@@ -107,6 +143,7 @@ impl From<&miso_domain::entities::Project> for ActiveModel {
             ProjectStatus::Completed => "completed",
             ProjectStatus::Cancelled => "cancelled",
         };
+        let lock = project.lock.as_ref();
 
         Self {
             id: ActiveValue::Set(project.id),
@@ -118,11 +155,16 @@ impl From<&miso_domain::entities::Project> for ActiveModel {
             pi_email: ActiveValue::Set(project.pi_email.clone()),
             reference_number: ActiveValue::Set(project.reference_number.clone()),
             target_sample_count: ActiveValue::Set(project.target_sample_count.map(|v| v as i32)),
+            reference_genome_id: ActiveValue::Set(project.reference_genome_id),
             sample_count: ActiveValue::Set(project.sample_count as i32),
             created_at: ActiveValue::Set(project.created_at),
             created_by: ActiveValue::Set(project.created_by.clone()),
             updated_at: ActiveValue::Set(project.updated_at),
             due_date: ActiveValue::Set(project.due_date),
+            lock_reason: ActiveValue::Set(lock.map(|l| l.reason.clone())),
+            locked_by: ActiveValue::Set(lock.map(|l| l.locked_by.clone())),
+            locked_at: ActiveValue::Set(lock.map(|l| l.locked_at)),
+            unlock_at: ActiveValue::Set(lock.and_then(|l| l.unlock_at)),
         }
     }
 }
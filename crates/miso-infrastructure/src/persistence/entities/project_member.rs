@@ -0,0 +1,75 @@
+//! SeaORM entity for the project_member table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Project member database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_member")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub project_id: i32,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub added_by: String,
+
+    pub added_at: DateTimeUtc,
+}
+
+/// Database relations for ProjectMember.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::ProjectMember {
+    fn from(model: Model) -> Self {
+        Self {
+            project_id: model.project_id,
+            user_id: model.user_id,
+            added_by: model.added_by,
+            added_at: model.added_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ProjectMember> for ActiveModel {
+    fn from(member: &miso_domain::entities::ProjectMember) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            project_id: ActiveValue::Set(member.project_id),
+            user_id: ActiveValue::Set(member.user_id),
+            added_by: ActiveValue::Set(member.added_by.clone()),
+            added_at: ActiveValue::Set(member.added_at),
+        }
+    }
+}
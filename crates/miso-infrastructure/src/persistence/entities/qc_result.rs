@@ -0,0 +1,126 @@
+//! SeaORM entity for the qc_result table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::{qc_status_code, qc_status_from_code};
+
+/// QC measurement database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "qc_result")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// "sample", "library" or "pool"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub entity_type: String,
+
+    pub entity_id: i32,
+
+    /// Test name, e.g. "Qubit"
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub test_type: String,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub value: Option<f64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub unit: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub status: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub performed_by: String,
+
+    pub performed_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::QcRecord {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::value_objects::QcResult;
+
+        Ok(Self {
+            id: model.id,
+            target: model.entity_type.parse()?,
+            entity_id: model.entity_id,
+            result: QcResult {
+                test_type: model
+                    .test_type
+                    .parse()
+                    .unwrap_or_else(|never| match never {}),
+                value: model.value,
+                unit: model.unit,
+                status: qc_status_from_code(&model.status),
+                notes: model.notes,
+                performed_at: model.performed_at,
+                performed_by: model.performed_by,
+            },
+        })
+    }
+}
+
+impl From<&miso_domain::entities::QcRecord> for ActiveModel {
+    fn from(record: &miso_domain::entities::QcRecord) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if record.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(record.id)
+            },
+            entity_type: ActiveValue::Set(record.target.to_string()),
+            entity_id: ActiveValue::Set(record.entity_id),
+            test_type: ActiveValue::Set(record.result.test_type.to_string()),
+            value: ActiveValue::Set(record.result.value),
+            unit: ActiveValue::Set(record.result.unit.clone()),
+            status: ActiveValue::Set(qc_status_code(record.result.status).to_string()),
+            notes: ActiveValue::Set(record.result.notes.clone()),
+            performed_by: ActiveValue::Set(record.result.performed_by.clone()),
+            performed_at: ActiveValue::Set(record.result.performed_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{QcRecord, QcTarget};
+    use miso_domain::value_objects::{QcResult, QcTestType};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_qc_record_round_trips_through_model() {
+        let mut record = QcRecord::new(
+            QcTarget::Library,
+            7,
+            QcResult::failed(
+                QcTestType::TapeStation,
+                Some(180.0),
+                Some("bp".to_string()),
+                "tech",
+                "Fragments too short",
+            ),
+        );
+        record.id = 3;
+
+        let model = ActiveModel::from(&record).try_into_model().unwrap();
+        assert_eq!(model.entity_type, "library");
+        assert_eq!(model.status, "failed");
+
+        let restored = QcRecord::try_from(model).unwrap();
+        assert_eq!(restored, record);
+    }
+}
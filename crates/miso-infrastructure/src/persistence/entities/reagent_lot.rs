@@ -0,0 +1,75 @@
+//! SeaORM entity for the reagent_lot table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Reagent lot database entity. Kit and lot number are unique together.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "reagent_lot")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub kit: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub lot_number: String,
+
+    pub expires_on: Date,
+
+    pub quantity: i32,
+
+    pub reorder_level: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for ReagentLot.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::ReagentLot {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            kit: model.kit,
+            lot_number: model.lot_number,
+            expires_on: model.expires_on,
+            quantity: model.quantity,
+            reorder_level: model.reorder_level,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ReagentLot> for ActiveModel {
+    fn from(lot: &miso_domain::entities::ReagentLot) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if lot.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(lot.id)
+            },
+            kit: ActiveValue::Set(lot.kit.clone()),
+            lot_number: ActiveValue::Set(lot.lot_number.clone()),
+            expires_on: ActiveValue::Set(lot.expires_on),
+            quantity: ActiveValue::Set(lot.quantity),
+            reorder_level: ActiveValue::Set(lot.reorder_level),
+            created_by: ActiveValue::Set(lot.created_by.clone()),
+            created_at: ActiveValue::Set(lot.created_at),
+            updated_at: ActiveValue::Set(lot.updated_at),
+        }
+    }
+}
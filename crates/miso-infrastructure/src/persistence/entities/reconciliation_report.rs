@@ -0,0 +1,71 @@
+//! SeaORM entity for the reconciliation_report table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Location reconciliation report database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "reconciliation_report")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub boxes_checked: i32,
+
+    pub items_checked: i32,
+
+    /// Denormalized for listing without parsing the JSON
+    pub discrepancy_count: i32,
+
+    /// Discrepancy list
+    pub discrepancies: Json,
+
+    pub started_at: DateTimeUtc,
+
+    pub completed_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::ReconciliationReport {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        Ok(Self {
+            id: model.id,
+            boxes_checked: model.boxes_checked as u32,
+            items_checked: model.items_checked as u32,
+            discrepancies: serde_json::from_value(model.discrepancies)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            started_at: model.started_at,
+            completed_at: model.completed_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::ReconciliationReport> for ActiveModel {
+    fn from(report: &miso_domain::entities::ReconciliationReport) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if report.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(report.id)
+            },
+            boxes_checked: ActiveValue::Set(report.boxes_checked as i32),
+            items_checked: ActiveValue::Set(report.items_checked as i32),
+            discrepancy_count: ActiveValue::Set(report.discrepancies.len() as i32),
+            discrepancies: ActiveValue::Set(
+                serde_json::to_value(&report.discrepancies).unwrap_or_default(),
+            ),
+            started_at: ActiveValue::Set(report.started_at),
+            completed_at: ActiveValue::Set(report.completed_at),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! SeaORM entity for the reference_genome table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Reference genome database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "reference_genome")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub species: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(1024))", nullable)]
+    pub fasta_uri: Option<String>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for ReferenceGenome.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::project::Entity")]
+    Project,
+
+    #[sea_orm(has_many = "super::library::Entity")]
+    Library,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::library::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Library.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::ReferenceGenome {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            species: model.species,
+            fasta_uri: model.fasta_uri,
+            description: model.description,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::ReferenceGenome> for ActiveModel {
+    fn from(genome: &miso_domain::entities::ReferenceGenome) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if genome.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(genome.id)
+            },
+            name: ActiveValue::Set(genome.name.clone()),
+            species: ActiveValue::Set(genome.species.clone()),
+            fasta_uri: ActiveValue::Set(genome.fasta_uri.clone()),
+            description: ActiveValue::Set(genome.description.clone()),
+            created_by: ActiveValue::Set(genome.created_by.clone()),
+            created_at: ActiveValue::Set(genome.created_at),
+            updated_at: ActiveValue::Set(genome.updated_at),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! SeaORM entity for the requisition table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::requisition_sample;
+
+/// Requisition database entity. The samples attached to the requisition
+/// are held in [`requisition_sample`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "requisition")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// The order's ID in the ordering system
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub alias: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub assay: String,
+
+    pub stopped: bool,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stop_reason: Option<String>,
+
+    pub paused: bool,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub pause_reason: Option<String>,
+
+    pub paused_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Requisition.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::requisition_sample::Entity")]
+    RequisitionSample,
+}
+
+impl Related<super::requisition_sample::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RequisitionSample.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored requisition and its samples to the domain
+    /// entity.
+    pub fn into_domain(
+        self,
+        mut samples: Vec<requisition_sample::Model>,
+    ) -> miso_domain::entities::Requisition {
+        samples.sort_by_key(|s| s.position);
+
+        miso_domain::entities::Requisition {
+            id: self.id,
+            alias: self.alias,
+            assay: self.assay,
+            sample_ids: samples.into_iter().map(|s| s.sample_id).collect(),
+            stopped: self.stopped,
+            stop_reason: self.stop_reason,
+            paused: self.paused,
+            pause_reason: self.pause_reason,
+            paused_at: self.paused_at,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Requisition> for ActiveModel {
+    fn from(requisition: &miso_domain::entities::Requisition) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if requisition.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(requisition.id)
+            },
+            alias: ActiveValue::Set(requisition.alias.clone()),
+            assay: ActiveValue::Set(requisition.assay.clone()),
+            stopped: ActiveValue::Set(requisition.stopped),
+            stop_reason: ActiveValue::Set(requisition.stop_reason.clone()),
+            paused: ActiveValue::Set(requisition.paused),
+            pause_reason: ActiveValue::Set(requisition.pause_reason.clone()),
+            paused_at: ActiveValue::Set(requisition.paused_at),
+            created_by: ActiveValue::Set(requisition.created_by.clone()),
+            created_at: ActiveValue::Set(requisition.created_at),
+            updated_at: ActiveValue::Set(requisition.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use miso_domain::entities::Requisition;
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_requisition_round_trips_through_models() {
+        let now = Utc::now();
+        let mut requisition =
+            Requisition::new("ORD-1", "WGS 30X", "tech".to_string()).unwrap();
+        requisition.id = 3;
+        requisition.attach(9, now).unwrap();
+        requisition.attach(4, now).unwrap();
+        requisition.pause("Awaiting consent", now).unwrap();
+
+        let model = ActiveModel::from(&requisition).try_into_model().unwrap();
+        let samples = requisition
+            .sample_ids
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, &sample_id)| {
+                requisition_sample::ActiveModel::new(3, i, sample_id)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(model.into_domain(samples), requisition);
+    }
+}
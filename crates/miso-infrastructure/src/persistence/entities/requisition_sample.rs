@@ -0,0 +1,50 @@
+//! SeaORM entity for the requisition_sample table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A sample attached to a requisition. A sample is attached to at most one.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "requisition_sample")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sample_id: i32,
+
+    pub requisition_id: i32,
+
+    /// Order the sample was attached in, from 0
+    pub position: i32,
+}
+
+/// Database relations for RequisitionSample.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::requisition::Entity",
+        from = "Column::RequisitionId",
+        to = "super::requisition::Column::Id"
+    )]
+    Requisition,
+}
+
+impl Related<super::requisition::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Requisition.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for the `position`th sample of the requisition with
+    /// `requisition_id`.
+    pub fn new(requisition_id: i32, position: usize, sample_id: i32) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            sample_id: ActiveValue::Set(sample_id),
+            requisition_id: ActiveValue::Set(requisition_id),
+            position: ActiveValue::Set(i32::try_from(position).unwrap_or(i32::MAX)),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! SeaORM entity for the run table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{run_interruption, run_partition};
+
+/// Sequencing run database entity. The run's lanes are held in
+/// [`run_partition`] and the times it was paused in [`run_interruption`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub alias: Option<String>,
+
+    pub sequencer_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub container_barcode: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub container_model: Option<String>,
+
+    /// Status code, e.g. "qc_in_progress"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub status: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub data_path: Option<String>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub output_path: Option<String>,
+
+    pub started_at: Option<DateTimeUtc>,
+
+    pub completed_at: Option<DateTimeUtc>,
+
+    pub overdue_since: Option<DateTimeUtc>,
+
+    pub estimated_completion: Option<DateTimeUtc>,
+
+    pub cycles_completed: Option<i32>,
+
+    pub ready_to_load_at: Option<DateTimeUtc>,
+
+    /// QC decision; null until the run is signed off
+    pub qc_passed: Option<bool>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub qc_reviewer: Option<String>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub qc_note: Option<String>,
+
+    pub qc_signed_off_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub read_length: Option<String>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Run.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::run_partition::Entity")]
+    RunPartition,
+
+    #[sea_orm(has_many = "super::run_interruption::Entity")]
+    RunInterruption,
+}
+
+impl Related<super::run_partition::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RunPartition.def()
+    }
+}
+
+impl Related<super::run_interruption::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RunInterruption.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored run, its partitions and its interruptions to the
+    /// domain entity.
+    pub fn into_domain(
+        self,
+        partitions: Vec<run_partition::Model>,
+        interruptions: Vec<run_interruption::Model>,
+    ) -> miso_domain::entities::Run {
+        use miso_domain::entities::{Run, RunPartition, RunQcSignOff};
+
+        let qc_sign_off = match (self.qc_passed, self.qc_reviewer, self.qc_signed_off_at) {
+            (Some(passed), Some(reviewer), Some(signed_off_at)) => Some(RunQcSignOff {
+                passed,
+                reviewer,
+                note: self.qc_note,
+                signed_off_at,
+            }),
+            _ => None,
+        };
+
+        Run {
+            id: self.id,
+            name: self.name,
+            alias: self.alias,
+            sequencer_id: self.sequencer_id,
+            container_barcode: self.container_barcode,
+            container_model: self.container_model,
+            status: run_status_from_code(&self.status),
+            partitions: partitions
+                .into_iter()
+                .map(|p| RunPartition {
+                    partition_number: u8::try_from(p.partition_number).unwrap_or_default(),
+                    pool_id: p.pool_id,
+                    loading_concentration: p.loading_concentration,
+                    cluster_density: p.cluster_density,
+                    pass_filter_percent: p.pass_filter_percent,
+                    q30_percent: p.q30_percent,
+                    yield_gbp: p.yield_gbp,
+                    notes: p.notes,
+                })
+                .collect(),
+            data_path: self.data_path,
+            output_path: self.output_path,
+            started_at: self.started_at,
+            completed_at: self.completed_at,
+            overdue_since: self.overdue_since,
+            estimated_completion: self.estimated_completion,
+            cycles_completed: self
+                .cycles_completed
+                .and_then(|c| u32::try_from(c).ok()),
+            ready_to_load_at: self.ready_to_load_at,
+            qc_sign_off,
+            interruptions: interruptions.into_iter().map(Into::into).collect(),
+            read_length: self.read_length,
+            description: self.description,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Run> for ActiveModel {
+    fn from(run: &miso_domain::entities::Run) -> Self {
+        use sea_orm::ActiveValue;
+
+        let sign_off = run.qc_sign_off.as_ref();
+
+        Self {
+            id: if run.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(run.id)
+            },
+            name: ActiveValue::Set(run.name.clone()),
+            alias: ActiveValue::Set(run.alias.clone()),
+            sequencer_id: ActiveValue::Set(run.sequencer_id),
+            container_barcode: ActiveValue::Set(run.container_barcode.clone()),
+            container_model: ActiveValue::Set(run.container_model.clone()),
+            status: ActiveValue::Set(run_status_code(run.status).to_string()),
+            data_path: ActiveValue::Set(run.data_path.clone()),
+            output_path: ActiveValue::Set(run.output_path.clone()),
+            started_at: ActiveValue::Set(run.started_at),
+            completed_at: ActiveValue::Set(run.completed_at),
+            overdue_since: ActiveValue::Set(run.overdue_since),
+            estimated_completion: ActiveValue::Set(run.estimated_completion),
+            cycles_completed: ActiveValue::Set(
+                run.cycles_completed
+                    .map(|cycles| i32::try_from(cycles).unwrap_or(i32::MAX)),
+            ),
+            ready_to_load_at: ActiveValue::Set(run.ready_to_load_at),
+            qc_passed: ActiveValue::Set(sign_off.map(|s| s.passed)),
+            qc_reviewer: ActiveValue::Set(sign_off.map(|s| s.reviewer.clone())),
+            qc_note: ActiveValue::Set(sign_off.and_then(|s| s.note.clone())),
+            qc_signed_off_at: ActiveValue::Set(sign_off.map(|s| s.signed_off_at)),
+            read_length: ActiveValue::Set(run.read_length.clone()),
+            description: ActiveValue::Set(run.description.clone()),
+            created_by: ActiveValue::Set(run.created_by.clone()),
+            created_at: ActiveValue::Set(run.created_at),
+            updated_at: ActiveValue::Set(run.updated_at),
+        }
+    }
+}
+
+/// Returns the stored code of a run status.
+pub(crate) fn run_status_code(status: miso_domain::entities::RunStatus) -> &'static str {
+    use miso_domain::entities::RunStatus;
+
+    match status {
+        RunStatus::Unknown => "unknown",
+        RunStatus::Running => "running",
+        RunStatus::Paused => "paused",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::Stopped => "stopped",
+        RunStatus::QcInProgress => "qc_in_progress",
+        RunStatus::QcPassed => "qc_passed",
+        RunStatus::QcFailed => "qc_failed",
+    }
+}
+
+/// Parses a stored run status code; unknown codes read as unknown.
+fn run_status_from_code(code: &str) -> miso_domain::entities::RunStatus {
+    use miso_domain::entities::RunStatus;
+
+    match code {
+        "running" => RunStatus::Running,
+        "paused" => RunStatus::Paused,
+        "completed" => RunStatus::Completed,
+        "failed" => RunStatus::Failed,
+        "stopped" => RunStatus::Stopped,
+        "qc_in_progress" => RunStatus::QcInProgress,
+        "qc_passed" => RunStatus::QcPassed,
+        "qc_failed" => RunStatus::QcFailed,
+        _ => RunStatus::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use miso_domain::entities::{Run, RunInterruption, RunQcSignOff, RunStatus};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_run_round_trips_through_models() {
+        let mut run = Run::new(5, "RUN_0005".to_string(), 2, 2, "tech".to_string());
+        run.status = RunStatus::QcFailed;
+        run.partitions[1].set_pool(9, 1.8);
+        run.partitions[1].set_metrics(230.5, 91.2, 88.4);
+        run.qc_sign_off = Some(RunQcSignOff {
+            passed: false,
+            reviewer: "manager".to_string(),
+            note: Some("Low yield".to_string()),
+            signed_off_at: Utc::now(),
+        });
+        run.interruptions.push(RunInterruption {
+            cause: "Power cut".to_string(),
+            started_at: Utc::now(),
+            ended_at: None,
+            flagged_lanes: vec![2],
+            recorded_by: "tech".to_string(),
+        });
+        run.estimated_completion = Some(Utc::now());
+        run.cycles_completed = Some(118);
+
+        let model = ActiveModel::from(&run).try_into_model().unwrap();
+        assert_eq!(model.status, "qc_failed");
+        let partitions = run
+            .partitions
+            .iter()
+            .map(|p| {
+                run_partition::ActiveModel::new(run.id, p)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+        let interruptions = run
+            .interruptions
+            .iter()
+            .enumerate()
+            .map(|(i, interruption)| {
+                run_interruption::ActiveModel::new(run.id, i, interruption)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+
+        let restored = model.into_domain(partitions, interruptions);
+        assert_eq!(restored, run);
+    }
+}
@@ -0,0 +1,84 @@
+//! SeaORM entity for the run_interruption table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A time a run was paused.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_interruption")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub run_id: i32,
+
+    /// Order of the interruption on the run, from 0
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub position: i16,
+
+    #[sea_orm(column_type = "Text")]
+    pub cause: String,
+
+    pub started_at: DateTimeUtc,
+
+    pub ended_at: Option<DateTimeUtc>,
+
+    /// Lane numbers flagged for extra QC review
+    pub flagged_lanes: Json,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub recorded_by: String,
+}
+
+/// Database relations for RunInterruption.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::run::Entity",
+        from = "Column::RunId",
+        to = "super::run::Column::Id"
+    )]
+    Run,
+}
+
+impl Related<super::run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Run.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for the `position`th interruption of the run with
+    /// `run_id`.
+    pub fn new(
+        run_id: i32,
+        position: usize,
+        interruption: &miso_domain::entities::RunInterruption,
+    ) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            run_id: ActiveValue::Set(run_id),
+            position: ActiveValue::Set(i16::try_from(position).unwrap_or(i16::MAX)),
+            cause: ActiveValue::Set(interruption.cause.clone()),
+            started_at: ActiveValue::Set(interruption.started_at),
+            ended_at: ActiveValue::Set(interruption.ended_at),
+            flagged_lanes: ActiveValue::Set(
+                serde_json::to_value(&interruption.flagged_lanes).unwrap_or_default(),
+            ),
+            recorded_by: ActiveValue::Set(interruption.recorded_by.clone()),
+        }
+    }
+}
+
+impl From<Model> for miso_domain::entities::RunInterruption {
+    fn from(model: Model) -> Self {
+        Self {
+            cause: model.cause,
+            started_at: model.started_at,
+            ended_at: model.ended_at,
+            flagged_lanes: serde_json::from_value(model.flagged_lanes).unwrap_or_default(),
+            recorded_by: model.recorded_by,
+        }
+    }
+}
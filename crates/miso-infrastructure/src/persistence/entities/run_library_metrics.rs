@@ -0,0 +1,78 @@
+//! SeaORM entity for the run_library_metrics table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Per-lane, per-library demultiplexing metrics.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_library_metrics")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub run_id: i32,
+
+    pub partition_number: i16,
+
+    pub library_id: i32,
+
+    pub reads: i64,
+
+    pub yield_bases: i64,
+
+    #[sea_orm(column_type = "Double")]
+    pub q30_percent: f64,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub index_hopping_percent: Option<f64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub submitted_by: String,
+
+    pub submitted_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::LibraryRunMetrics {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            run_id: model.run_id,
+            partition_number: model.partition_number as u8,
+            library_id: model.library_id,
+            reads: model.reads as u64,
+            yield_bases: model.yield_bases as u64,
+            q30_percent: model.q30_percent,
+            index_hopping_percent: model.index_hopping_percent,
+            submitted_by: model.submitted_by,
+            submitted_at: model.submitted_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::LibraryRunMetrics> for ActiveModel {
+    fn from(metrics: &miso_domain::entities::LibraryRunMetrics) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if metrics.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(metrics.id)
+            },
+            run_id: ActiveValue::Set(metrics.run_id),
+            partition_number: ActiveValue::Set(metrics.partition_number as i16),
+            library_id: ActiveValue::Set(metrics.library_id),
+            reads: ActiveValue::Set(metrics.reads as i64),
+            yield_bases: ActiveValue::Set(metrics.yield_bases as i64),
+            q30_percent: ActiveValue::Set(metrics.q30_percent),
+            index_hopping_percent: ActiveValue::Set(metrics.index_hopping_percent),
+            submitted_by: ActiveValue::Set(metrics.submitted_by.clone()),
+            submitted_at: ActiveValue::Set(metrics.submitted_at),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! SeaORM entity for the run_partition table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A lane (or cell) of a run and the pool loaded on it.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_partition")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub run_id: i32,
+
+    /// Partition number, from 1
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub partition_number: i16,
+
+    pub pool_id: Option<i32>,
+
+    /// Loading concentration, in pM
+    #[sea_orm(column_type = "Double", nullable)]
+    pub loading_concentration: Option<f64>,
+
+    /// Cluster density, in K/mm²
+    #[sea_orm(column_type = "Double", nullable)]
+    pub cluster_density: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub pass_filter_percent: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub q30_percent: Option<f64>,
+
+    /// Bases passing filter, in gigabases
+    #[sea_orm(column_type = "Double", nullable)]
+    pub yield_gbp: Option<f64>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+}
+
+/// Database relations for RunPartition.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::run::Entity",
+        from = "Column::RunId",
+        to = "super::run::Column::Id"
+    )]
+    Run,
+
+    #[sea_orm(
+        belongs_to = "super::pool::Entity",
+        from = "Column::PoolId",
+        to = "super::pool::Column::Id"
+    )]
+    Pool,
+}
+
+impl Related<super::run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Run.def()
+    }
+}
+
+impl Related<super::pool::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Pool.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for a partition of the run with `run_id`.
+    pub fn new(run_id: i32, partition: &miso_domain::entities::RunPartition) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            run_id: ActiveValue::Set(run_id),
+            partition_number: ActiveValue::Set(i16::from(partition.partition_number)),
+            pool_id: ActiveValue::Set(partition.pool_id),
+            loading_concentration: ActiveValue::Set(partition.loading_concentration),
+            cluster_density: ActiveValue::Set(partition.cluster_density),
+            pass_filter_percent: ActiveValue::Set(partition.pass_filter_percent),
+            q30_percent: ActiveValue::Set(partition.q30_percent),
+            yield_gbp: ActiveValue::Set(partition.yield_gbp),
+            notes: ActiveValue::Set(partition.notes.clone()),
+        }
+    }
+}
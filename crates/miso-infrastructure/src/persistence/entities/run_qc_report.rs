@@ -0,0 +1,60 @@
+//! SeaORM entity for the run_qc_report table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A MultiQC report attached to a run.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_qc_report")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub run_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(1024))", nullable)]
+    pub report_url: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub file_name: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub multiqc_version: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub attached_by: String,
+
+    pub attached_at: DateTimeUtc,
+}
+
+/// Database relations for RunQcReport.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::run_qc_sample_metrics::Entity")]
+    SampleMetrics,
+}
+
+impl Related<super::run_qc_sample_metrics::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SampleMetrics.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&miso_domain::entities::RunQcReport> for ActiveModel {
+    fn from(report: &miso_domain::entities::RunQcReport) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: ActiveValue::NotSet,
+            run_id: ActiveValue::Set(report.run_id),
+            report_url: ActiveValue::Set(report.report_url.clone()),
+            file_name: ActiveValue::Set(report.file_name.clone()),
+            multiqc_version: ActiveValue::Set(report.multiqc_version.clone()),
+            attached_by: ActiveValue::Set(report.attached_by.clone()),
+            attached_at: ActiveValue::Set(report.attached_at),
+        }
+    }
+}
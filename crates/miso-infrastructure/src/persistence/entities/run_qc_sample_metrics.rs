@@ -0,0 +1,82 @@
+//! SeaORM entity for the run_qc_sample_metrics table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Per-sample metrics extracted from a run's MultiQC report.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_qc_sample_metrics")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub report_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub sample_name: String,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub total_sequences: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub percent_gc: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub percent_duplicates: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub avg_sequence_length: Option<f64>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub percent_aligned: Option<f64>,
+}
+
+/// Database relations for RunQcSampleMetrics.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::run_qc_report::Entity",
+        from = "Column::ReportId",
+        to = "super::run_qc_report::Column::Id"
+    )]
+    Report,
+}
+
+impl Related<super::run_qc_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Report.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::SampleQcSummary {
+    fn from(model: Model) -> Self {
+        Self {
+            sample_name: model.sample_name,
+            total_sequences: model.total_sequences,
+            percent_gc: model.percent_gc,
+            percent_duplicates: model.percent_duplicates,
+            avg_sequence_length: model.avg_sequence_length,
+            percent_aligned: model.percent_aligned,
+        }
+    }
+}
+
+impl ActiveModel {
+    /// Builds an insertable row for a sample summary under a report.
+    pub fn from_summary(report_id: i32, summary: &miso_domain::entities::SampleQcSummary) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: ActiveValue::NotSet,
+            report_id: ActiveValue::Set(report_id),
+            sample_name: ActiveValue::Set(summary.sample_name.clone()),
+            total_sequences: ActiveValue::Set(summary.total_sequences),
+            percent_gc: ActiveValue::Set(summary.percent_gc),
+            percent_duplicates: ActiveValue::Set(summary.percent_duplicates),
+            avg_sequence_length: ActiveValue::Set(summary.avg_sequence_length),
+            percent_aligned: ActiveValue::Set(summary.percent_aligned),
+        }
+    }
+}
@@ -10,10 +10,10 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
 
-    #[sea_orm(column_type = "String(Some(255))")]
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
     pub name: String,
 
-    #[sea_orm(column_type = "String(Some(50))", unique)]
+    #[sea_orm(column_type = "String(StringLen::N(50))", unique)]
     pub barcode: String,
 
     pub project_id: i32,
@@ -22,11 +22,11 @@ pub struct Model {
     pub description: Option<String>,
 
     /// "plain" or "detailed"
-    #[sea_orm(column_type = "String(Some(20))")]
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
     pub sample_mode: String,
 
     /// Sample class (for detailed mode)
-    #[sea_orm(column_type = "String(Some(50))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
     pub sample_class: Option<String>,
 
     /// Parent sample ID (for detailed hierarchy)
@@ -34,9 +34,13 @@ pub struct Model {
     pub parent_id: Option<i32>,
 
     /// Scientific name (for plain mode)
-    #[sea_orm(column_type = "String(Some(255))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
     pub scientific_name: Option<String>,
 
+    /// Sample type (for plain mode)
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub sample_type: Option<String>,
+
     /// Volume in microliters
     #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
     pub volume: Option<Decimal>,
@@ -45,14 +49,18 @@ pub struct Model {
     #[sea_orm(column_type = "Decimal(Some((10, 2)))", nullable)]
     pub concentration: Option<Decimal>,
 
+    /// Mass in ng
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub mass: Option<Decimal>,
+
     /// QC status
-    #[sea_orm(column_type = "String(Some(20))")]
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
     pub qc_status: String,
 
     #[sea_orm(nullable)]
     pub received_at: Option<DateTimeUtc>,
 
-    #[sea_orm(column_type = "String(Some(255))")]
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
     pub created_by: String,
 
     pub created_at: DateTimeUtc,
@@ -63,17 +71,50 @@ pub struct Model {
     pub archived: bool,
 
     // Detailed sample fields
-    #[sea_orm(column_type = "String(Some(255))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
     pub external_name: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(100))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
     pub tissue_origin: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(100))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
     pub tissue_type: Option<String>,
 
-    #[sea_orm(column_type = "String(Some(50))", nullable)]
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
     pub analyte_type: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub time_point: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub group_id: Option<String>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub group_description: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub passage: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub purpose: Option<String>,
+
+    /// Pooled from several identities (see sample_component)
+    #[sea_orm(default_value = "false")]
+    pub pooled: bool,
+
+    /// Optimistic concurrency version
+    #[sea_orm(default_value = 1)]
+    pub version: i32,
+
+    /// Custom attribute values, keyed by attribute key
+    pub attributes: Option<Json>,
+
+    /// "positive", "negative" or "ntc" if the sample is a control
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub control_type: Option<String>,
+
+    /// The subproject of the project the sample is assigned to
+    pub subproject_id: Option<i32>,
 }
 
 /// Database relations for Sample.
@@ -92,9 +133,6 @@ pub enum Relation {
         to = "Column::Id"
     )]
     Parent,
-
-    #[sea_orm(has_many = "Entity")]
-    Children,
 }
 
 impl Related<super::project::Entity> for Entity {
@@ -105,3 +143,241 @@ impl Related<super::project::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl From<&miso_domain::entities::Sample> for ActiveModel {
+    fn from(sample: &miso_domain::entities::Sample) -> Self {
+        use miso_domain::entities::SampleDetails;
+        use miso_domain::value_objects::QcStatus;
+        use sea_orm::ActiveValue;
+
+        let to_decimal = |value: f64| Decimal::from_f64_retain(value).map(|d| d.round_dp(2));
+
+        let (sample_mode, plain, detailed) = match &sample.details {
+            SampleDetails::Plain(plain) => ("plain", Some(plain), None),
+            SampleDetails::Detailed(detailed) => ("detailed", None, Some(detailed)),
+        };
+
+        let sample_class = detailed.map(|d| sample_class_code(&d.sample_class).to_string());
+
+        let qc_status = match sample.qc_status {
+            QcStatus::NotReady => "not_ready",
+            QcStatus::Ready => "ready",
+            QcStatus::Passed => "passed",
+            QcStatus::Failed => "failed",
+            QcStatus::NeedsReview => "needs_review",
+        };
+
+        Self {
+            id: if sample.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(sample.id)
+            },
+            name: ActiveValue::Set(sample.name.clone()),
+            barcode: ActiveValue::Set(sample.barcode.to_string()),
+            project_id: ActiveValue::Set(sample.project_id),
+            description: ActiveValue::Set(sample.description.clone()),
+            sample_mode: ActiveValue::Set(sample_mode.to_string()),
+            sample_class: ActiveValue::Set(sample_class),
+            parent_id: ActiveValue::Set(detailed.and_then(|d| d.parent_id)),
+            scientific_name: ActiveValue::Set(plain.map(|p| p.scientific_name.clone())),
+            sample_type: ActiveValue::Set(plain.and_then(|p| p.sample_type.clone())),
+            volume: ActiveValue::Set(sample.volume.and_then(|v| to_decimal(v.as_microliters()))),
+            mass: ActiveValue::Set(sample.mass.and_then(|m| to_decimal(m.as_nanograms()))),
+            concentration: ActiveValue::Set(
+                sample.concentration.and_then(|c| to_decimal(c.value())),
+            ),
+            qc_status: ActiveValue::Set(qc_status.to_string()),
+            received_at: ActiveValue::Set(sample.received_at),
+            created_by: ActiveValue::Set(sample.created_by.clone()),
+            created_at: ActiveValue::Set(sample.created_at),
+            updated_at: ActiveValue::Set(sample.updated_at),
+            archived: ActiveValue::Set(sample.archived),
+            external_name: ActiveValue::Set(detailed.and_then(|d| d.external_name.clone())),
+            tissue_origin: ActiveValue::Set(detailed.and_then(|d| d.tissue_origin.clone())),
+            tissue_type: ActiveValue::Set(detailed.and_then(|d| d.tissue_type.clone())),
+            analyte_type: ActiveValue::Set(detailed.and_then(|d| d.analyte_type.clone())),
+            time_point: ActiveValue::Set(detailed.and_then(|d| d.time_point.clone())),
+            group_id: ActiveValue::Set(detailed.and_then(|d| d.group_id.clone())),
+            group_description: ActiveValue::Set(detailed.and_then(|d| d.group_description.clone())),
+            passage: ActiveValue::Set(detailed.and_then(|d| d.passage)),
+            purpose: ActiveValue::Set(detailed.and_then(|d| d.purpose.clone())),
+            pooled: ActiveValue::Set(detailed.is_some_and(|d| d.pooled)),
+            version: ActiveValue::Set(sample.version),
+            attributes: ActiveValue::Set(
+                (!sample.attributes.is_empty())
+                    .then(|| serde_json::to_value(&sample.attributes).unwrap_or_default()),
+            ),
+            control_type: ActiveValue::Set(sample.control_type.map(|c| c.as_str().to_string())),
+            subproject_id: ActiveValue::Set(sample.subproject_id),
+        }
+    }
+}
+
+impl From<Model> for miso_domain::entities::Sample {
+    fn from(model: Model) -> Self {
+        use miso_domain::entities::{DetailedSampleData, PlainSampleData, SampleDetails};
+        use miso_domain::value_objects::{Barcode, Concentration, Mass, Volume};
+
+        let details = if model.sample_mode == "detailed" {
+            SampleDetails::Detailed(DetailedSampleData {
+                parent_id: model.parent_id,
+                sample_class: sample_class_from_code(model.sample_class.as_deref()),
+                external_name: model.external_name,
+                tissue_origin: model.tissue_origin,
+                tissue_type: model.tissue_type,
+                time_point: model.time_point,
+                group_id: model.group_id,
+                group_description: model.group_description,
+                passage: model.passage,
+                analyte_type: model.analyte_type,
+                purpose: model.purpose,
+                pooled: model.pooled,
+            })
+        } else {
+            SampleDetails::Plain(PlainSampleData {
+                scientific_name: model.scientific_name.unwrap_or_default(),
+                sample_type: model.sample_type,
+            })
+        };
+
+        let to_f64 = |value: Decimal| value.to_string().parse::<f64>().unwrap_or(0.0);
+
+        Self {
+            id: model.id,
+            name: model.name,
+            barcode: Barcode::new_unchecked(model.barcode),
+            project_id: model.project_id,
+            description: model.description,
+            details,
+            volume: model.volume.map(|v| Volume::microliters(to_f64(v))),
+            mass: model.mass.map(|m| Mass::nanograms(to_f64(m))),
+            concentration: model
+                .concentration
+                .map(|c| Concentration::ng_per_ul(to_f64(c))),
+            qc_status: qc_status_from_code(&model.qc_status),
+            received_at: model.received_at,
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            archived: model.archived,
+            version: model.version,
+            attributes: model
+                .attributes
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default(),
+            control_type: model.control_type.as_deref().and_then(|c| c.parse().ok()),
+            subproject_id: model.subproject_id,
+        }
+    }
+}
+
+/// Returns the stored code of a sample class.
+pub(crate) fn sample_class_code(class: &miso_domain::entities::SampleClass) -> &'static str {
+    use miso_domain::entities::SampleClass;
+
+    match class {
+        SampleClass::Plain => "plain",
+        SampleClass::Identity => "identity",
+        SampleClass::Tissue => "tissue",
+        SampleClass::TissueProcessing => "tissue_processing",
+        SampleClass::Stock => "stock",
+        SampleClass::Aliquot => "aliquot",
+        SampleClass::SingleCell => "single_cell",
+        SampleClass::WholeTranscriptome => "whole_transcriptome",
+    }
+}
+
+/// Parses a stored sample class code, treating unknown codes as plain.
+fn sample_class_from_code(code: Option<&str>) -> miso_domain::entities::SampleClass {
+    use miso_domain::entities::SampleClass;
+
+    match code {
+        Some("identity") => SampleClass::Identity,
+        Some("tissue") => SampleClass::Tissue,
+        Some("tissue_processing") => SampleClass::TissueProcessing,
+        Some("stock") => SampleClass::Stock,
+        Some("aliquot") => SampleClass::Aliquot,
+        Some("single_cell") => SampleClass::SingleCell,
+        Some("whole_transcriptome") => SampleClass::WholeTranscriptome,
+        _ => SampleClass::Plain,
+    }
+}
+
+/// Parses a stored QC status code, treating unknown codes as not ready.
+pub(crate) fn qc_status_from_code(code: &str) -> miso_domain::value_objects::QcStatus {
+    use miso_domain::value_objects::QcStatus;
+
+    match code {
+        "not_ready" => QcStatus::NotReady,
+        "ready" => QcStatus::Ready,
+        "passed" => QcStatus::Passed,
+        "failed" => QcStatus::Failed,
+        "needs_review" => QcStatus::NeedsReview,
+        _ => QcStatus::NotReady,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{DetailedSampleData, Sample, SampleClass, SampleDetails};
+    use miso_domain::value_objects::{Barcode, Mass, QcStatus, Volume};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_plain_sample_round_trips_through_model() {
+        let mut sample = Sample::new_plain(
+            12,
+            "LIVER_01".to_string(),
+            Barcode::new("SAM0012").unwrap(),
+            3,
+            "Homo sapiens".to_string(),
+            "admin".to_string(),
+        );
+        if let SampleDetails::Plain(plain) = &mut sample.details {
+            plain.sample_type = Some("Tissue".to_string());
+        }
+        sample.volume = Some(Volume::microliters(12.5));
+        sample.mass = Some(Mass::nanograms(850.0));
+        sample.qc_status = QcStatus::NeedsReview;
+
+        let model = ActiveModel::from(&sample).try_into_model().unwrap();
+        assert_eq!(model.sample_mode, "plain");
+        assert_eq!(model.sample_class, None);
+
+        assert_eq!(Sample::from(model), sample);
+    }
+
+    #[test]
+    fn test_detailed_sample_keeps_every_field() {
+        let details = DetailedSampleData {
+            parent_id: Some(4),
+            sample_class: SampleClass::TissueProcessing,
+            external_name: Some("PT-88".to_string()),
+            tissue_origin: Some("Liver".to_string()),
+            tissue_type: Some("Primary Tumor".to_string()),
+            time_point: Some("Week 6".to_string()),
+            group_id: Some("G2".to_string()),
+            group_description: Some("Treatment arm".to_string()),
+            passage: Some(3),
+            analyte_type: None,
+            purpose: Some("Sequencing".to_string()),
+            pooled: false,
+        };
+        let mut sample = Sample::new_plain(
+            13,
+            "LIVER_01_SL".to_string(),
+            Barcode::new("SAM0013").unwrap(),
+            3,
+            String::new(),
+            "admin".to_string(),
+        );
+        sample.details = SampleDetails::Detailed(details);
+
+        let model = ActiveModel::from(&sample).try_into_model().unwrap();
+        assert_eq!(model.sample_class.as_deref(), Some("tissue_processing"));
+        assert_eq!(model.scientific_name, None);
+
+        assert_eq!(Sample::from(model), sample);
+    }
+}
@@ -0,0 +1,52 @@
+//! SeaORM entity for the sample_component table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An identity a pooled sample was made from.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sample_component")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sample_id: i32,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub identity_id: i32,
+
+    /// Order the identity was listed in, from 0
+    pub position: i32,
+}
+
+/// Database relations for SampleComponent.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sample::Entity",
+        from = "Column::SampleId",
+        to = "super::sample::Column::Id"
+    )]
+    Sample,
+
+    #[sea_orm(
+        belongs_to = "super::sample::Entity",
+        from = "Column::IdentityId",
+        to = "super::sample::Column::Id"
+    )]
+    Identity,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for the `position`th identity of the pooled sample
+    /// with `sample_id`.
+    pub fn new(sample_id: i32, position: usize, identity_id: i32) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            sample_id: ActiveValue::Set(sample_id),
+            identity_id: ActiveValue::Set(identity_id),
+            position: ActiveValue::Set(i32::try_from(position).unwrap_or(i32::MAX)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! SeaORM entity for the sample_list table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::{qc_status_code, qc_status_from_code};
+
+/// Sample list read model database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sample_list")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sample_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub barcode: String,
+
+    pub project_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub project_code: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub project_name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub sample_class: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub qc_status: String,
+
+    pub latest_qc_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub latest_qc_test: Option<String>,
+
+    #[sea_orm(column_type = "Double", nullable)]
+    pub latest_qc_value: Option<f64>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable)]
+    pub latest_qc_unit: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub latest_qc_status: Option<String>,
+
+    pub latest_qc_at: Option<DateTimeUtc>,
+
+    pub box_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub box_name: Option<String>,
+
+    /// Position in the box, e.g. "B12"
+    #[sea_orm(column_type = "String(StringLen::N(4))", nullable)]
+    pub box_position: Option<String>,
+
+    pub archived: bool,
+
+    pub received_at: Option<DateTimeUtc>,
+
+    pub updated_at: DateTimeUtc,
+
+    /// Controls are listed but not counted
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub control_type: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::SampleListRow {
+    fn from(model: Model) -> Self {
+        use miso_domain::entities::{ListedLocation, ListedQc};
+
+        let latest_qc = match (model.latest_qc_id, model.latest_qc_at) {
+            (Some(qc_id), Some(performed_at)) => Some(ListedQc {
+                qc_id,
+                test_type: model.latest_qc_test.unwrap_or_default(),
+                value: model.latest_qc_value,
+                unit: model.latest_qc_unit,
+                status: qc_status_from_code(model.latest_qc_status.as_deref().unwrap_or_default()),
+                performed_at,
+            }),
+            _ => None,
+        };
+        let location = match (model.box_id, model.box_position) {
+            (Some(box_id), Some(position)) => Some(ListedLocation {
+                box_id,
+                box_name: model.box_name.unwrap_or_default(),
+                position,
+            }),
+            _ => None,
+        };
+
+        Self {
+            sample_id: model.sample_id,
+            name: model.name,
+            barcode: model.barcode,
+            project_id: model.project_id,
+            project_code: model.project_code,
+            project_name: model.project_name,
+            sample_class: model.sample_class,
+            qc_status: qc_status_from_code(&model.qc_status),
+            latest_qc,
+            location,
+            archived: model.archived,
+            received_at: model.received_at,
+            updated_at: model.updated_at,
+            control_type: model.control_type.as_deref().and_then(|c| c.parse().ok()),
+        }
+    }
+}
+
+impl From<&miso_domain::entities::SampleListRow> for ActiveModel {
+    fn from(row: &miso_domain::entities::SampleListRow) -> Self {
+        use sea_orm::ActiveValue;
+
+        let qc = row.latest_qc.as_ref();
+        let location = row.location.as_ref();
+
+        Self {
+            sample_id: ActiveValue::Set(row.sample_id),
+            name: ActiveValue::Set(row.name.clone()),
+            barcode: ActiveValue::Set(row.barcode.clone()),
+            project_id: ActiveValue::Set(row.project_id),
+            project_code: ActiveValue::Set(row.project_code.clone()),
+            project_name: ActiveValue::Set(row.project_name.clone()),
+            sample_class: ActiveValue::Set(row.sample_class.clone()),
+            qc_status: ActiveValue::Set(qc_status_code(row.qc_status).to_string()),
+            latest_qc_id: ActiveValue::Set(qc.map(|qc| qc.qc_id)),
+            latest_qc_test: ActiveValue::Set(qc.map(|qc| qc.test_type.clone())),
+            latest_qc_value: ActiveValue::Set(qc.and_then(|qc| qc.value)),
+            latest_qc_unit: ActiveValue::Set(qc.and_then(|qc| qc.unit.clone())),
+            latest_qc_status: ActiveValue::Set(
+                qc.map(|qc| qc_status_code(qc.status).to_string()),
+            ),
+            latest_qc_at: ActiveValue::Set(qc.map(|qc| qc.performed_at)),
+            box_id: ActiveValue::Set(location.map(|l| l.box_id)),
+            box_name: ActiveValue::Set(location.map(|l| l.box_name.clone())),
+            box_position: ActiveValue::Set(location.map(|l| l.position.clone())),
+            archived: ActiveValue::Set(row.archived),
+            received_at: ActiveValue::Set(row.received_at),
+            updated_at: ActiveValue::Set(row.updated_at),
+            control_type: ActiveValue::Set(row.control_type.map(|c| c.as_str().to_string())),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! SeaORM entity for the sample_list_count table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::library::qc_status_from_code;
+
+/// Materialized count of a project's samples with a QC status.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sample_list_count")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub project_id: i32,
+
+    #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(20))")]
+    pub qc_status: String,
+
+    pub sample_count: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::SampleListCount {
+    fn from(model: Model) -> Self {
+        Self {
+            project_id: model.project_id,
+            qc_status: qc_status_from_code(&model.qc_status),
+            count: model.sample_count.max(0) as u64,
+        }
+    }
+}
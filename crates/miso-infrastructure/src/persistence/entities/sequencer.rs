@@ -0,0 +1,155 @@
+//! SeaORM entity for the sequencer table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Sequencing instrument database entity. The instrument's model is held
+/// in [`super::instrument_model`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sequencer")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub serial_number: Option<String>,
+
+    pub instrument_model_id: i32,
+
+    /// Status code, e.g. "out_of_service"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub status: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub location: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub ip_address: Option<String>,
+
+    pub date_commissioned: Option<DateTimeUtc>,
+
+    pub last_service_date: Option<DateTimeUtc>,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Sequencer.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::instrument_model::Entity",
+        from = "Column::InstrumentModelId",
+        to = "super::instrument_model::Column::Id"
+    )]
+    InstrumentModel,
+}
+
+impl Related<super::instrument_model::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::InstrumentModel.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored sequencer and its model to the domain entity.
+    pub fn into_domain(
+        self,
+        model: miso_domain::entities::InstrumentModel,
+    ) -> miso_domain::entities::Sequencer {
+        miso_domain::entities::Sequencer {
+            id: self.id,
+            name: self.name,
+            serial_number: self.serial_number,
+            model,
+            status: sequencer_status_from_code(&self.status),
+            location: self.location,
+            ip_address: self.ip_address,
+            date_commissioned: self.date_commissioned,
+            last_service_date: self.last_service_date,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Sequencer> for ActiveModel {
+    fn from(sequencer: &miso_domain::entities::Sequencer) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if sequencer.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(sequencer.id)
+            },
+            name: ActiveValue::Set(sequencer.name.clone()),
+            serial_number: ActiveValue::Set(sequencer.serial_number.clone()),
+            instrument_model_id: ActiveValue::Set(sequencer.model.id),
+            status: ActiveValue::Set(sequencer_status_code(sequencer.status).to_string()),
+            location: ActiveValue::Set(sequencer.location.clone()),
+            ip_address: ActiveValue::Set(sequencer.ip_address.clone()),
+            date_commissioned: ActiveValue::Set(sequencer.date_commissioned),
+            last_service_date: ActiveValue::Set(sequencer.last_service_date),
+            created_at: ActiveValue::Set(sequencer.created_at),
+            updated_at: ActiveValue::Set(sequencer.updated_at),
+        }
+    }
+}
+
+/// Returns the stored code of a sequencer status.
+pub(crate) fn sequencer_status_code(
+    status: miso_domain::entities::SequencerStatus,
+) -> &'static str {
+    use miso_domain::entities::SequencerStatus;
+
+    match status {
+        SequencerStatus::Available => "available",
+        SequencerStatus::Running => "running",
+        SequencerStatus::Maintenance => "maintenance",
+        SequencerStatus::OutOfService => "out_of_service",
+        SequencerStatus::Retired => "retired",
+    }
+}
+
+/// Parses a stored sequencer status code; unknown codes read as out of
+/// service, so the instrument is never offered a run by mistake.
+fn sequencer_status_from_code(code: &str) -> miso_domain::entities::SequencerStatus {
+    use miso_domain::entities::SequencerStatus;
+
+    match code {
+        "available" => SequencerStatus::Available,
+        "running" => SequencerStatus::Running,
+        "maintenance" => SequencerStatus::Maintenance,
+        "retired" => SequencerStatus::Retired,
+        _ => SequencerStatus::OutOfService,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{InstrumentModel, Platform, Sequencer, SequencerStatus};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_sequencer_round_trips_through_models() {
+        let mut instrument = InstrumentModel::new(Platform::Illumina, "MiSeq".to_string(), 1);
+        instrument.id = 3;
+        let mut sequencer = Sequencer::new(7, "MiSeq01".to_string(), instrument.clone());
+        sequencer.status = SequencerStatus::OutOfService;
+        sequencer.serial_number = Some("M00123".to_string());
+
+        let model = ActiveModel::from(&sequencer).try_into_model().unwrap();
+        assert_eq!(model.status, "out_of_service");
+        assert_eq!(model.instrument_model_id, 3);
+
+        assert_eq!(model.into_domain(instrument), sequencer);
+    }
+}
@@ -0,0 +1,128 @@
+//! SeaORM entity for the storage_audit table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Storage audit database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "storage_audit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub freezer: String,
+
+    /// Boxes to scan, in order
+    pub box_ids: Json,
+
+    /// Boxes scanned so far
+    pub scanned_box_ids: Json,
+
+    /// Discrepancy list
+    pub discrepancies: Json,
+
+    /// "scanning", "review" or "closed"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub status: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub started_by: String,
+
+    pub started_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub closed_by: Option<String>,
+
+    pub closed_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::StorageAudit {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        Ok(Self {
+            id: model.id,
+            freezer: model.freezer,
+            box_ids: serde_json::from_value(model.box_ids)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            scanned_box_ids: serde_json::from_value(model.scanned_box_ids)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            discrepancies: serde_json::from_value(model.discrepancies)
+                .map_err(|e| DomainError::Validation(e.to_string()))?,
+            status: model.status.parse()?,
+            started_by: model.started_by,
+            started_at: model.started_at,
+            closed_by: model.closed_by,
+            closed_at: model.closed_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::StorageAudit> for ActiveModel {
+    fn from(audit: &miso_domain::entities::StorageAudit) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if audit.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(audit.id)
+            },
+            freezer: ActiveValue::Set(audit.freezer.clone()),
+            box_ids: ActiveValue::Set(serde_json::to_value(&audit.box_ids).unwrap_or_default()),
+            scanned_box_ids: ActiveValue::Set(
+                serde_json::to_value(&audit.scanned_box_ids).unwrap_or_default(),
+            ),
+            discrepancies: ActiveValue::Set(
+                serde_json::to_value(&audit.discrepancies).unwrap_or_default(),
+            ),
+            status: ActiveValue::Set(audit.status.to_string()),
+            started_by: ActiveValue::Set(audit.started_by.clone()),
+            started_at: ActiveValue::Set(audit.started_at),
+            closed_by: ActiveValue::Set(audit.closed_by.clone()),
+            closed_at: ActiveValue::Set(audit.closed_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miso_domain::entities::{
+        ScanDiscrepancy, ScanDiscrepancyKind, StorableItem, StorageAudit,
+    };
+
+    use sea_orm::TryIntoModel;
+
+    use super::*;
+
+    #[test]
+    fn test_storage_audit_round_trips_through_model() {
+        let mut audit =
+            StorageAudit::new("Freezer 1".to_string(), vec![3, 4], "tech".to_string()).unwrap();
+        audit.id = 2;
+        audit.scanned_box_ids = vec![3];
+        audit.discrepancies.push(ScanDiscrepancy {
+            box_id: 3,
+            position: "B4".to_string(),
+            kind: ScanDiscrepancyKind::Mismatched,
+            recorded: Some(StorableItem::sample(10)),
+            scanned_barcode: Some("SAM11".to_string()),
+            scanned: Some(StorableItem::sample(11)),
+            accepted: false,
+        });
+
+        let model = ActiveModel::from(&audit).try_into_model().unwrap();
+        assert_eq!(model.status, "scanning");
+
+        let restored = StorageAudit::try_from(model).unwrap();
+        assert_eq!(restored, audit);
+    }
+}
@@ -0,0 +1,242 @@
+//! SeaORM entity for the storage_box table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use miso_domain::errors::DomainError;
+
+use super::box_position;
+
+/// Storage box database entity. What the box holds is kept in
+/// [`box_position`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "storage_box")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable, unique)]
+    pub barcode: Option<String>,
+
+    pub rows: i16,
+
+    pub columns: i16,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub freezer: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub shelf: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub rack: Option<String>,
+
+    /// Storage temperature, in °C
+    pub temperature: Option<i16>,
+
+    /// Rack the box is assigned to
+    pub rack_id: Option<i32>,
+
+    /// Code of the type of item the box holds, e.g. "library_aliquot"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub storable_type: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for StorageBox.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::box_position::Entity")]
+    BoxPosition,
+
+    #[sea_orm(
+        belongs_to = "super::storage_unit::Entity",
+        from = "Column::RackId",
+        to = "super::storage_unit::Column::Id"
+    )]
+    Rack,
+}
+
+impl Related<super::box_position::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BoxPosition.def()
+    }
+}
+
+impl Related<super::storage_unit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Rack.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored box and its positions to the domain entity.
+    ///
+    /// Contents are placed through the domain so a row the box couldn't
+    /// hold is reported rather than loaded.
+    pub fn into_domain(
+        self,
+        positions: Vec<box_position::Model>,
+    ) -> Result<miso_domain::entities::StorageBox, DomainError> {
+        use miso_domain::entities::{StorableItem, StorageBox, StorageLocation};
+        use miso_domain::value_objects::{BoxPosition, Dimension};
+
+        let invalid =
+            |what: String| DomainError::Validation(format!("Stored box {} has {}", self.id, what));
+
+        let dimension = match (u8::try_from(self.rows), u8::try_from(self.columns)) {
+            (Ok(rows @ 1..=26), Ok(cols @ 1..)) => Dimension::new(rows, cols),
+            _ => {
+                return Err(invalid(format!(
+                    "invalid dimensions {}x{}",
+                    self.rows, self.columns
+                )))
+            }
+        };
+        let storable_type = storable_type_from_code(&self.storable_type)
+            .ok_or_else(|| invalid(format!("unknown item type '{}'", self.storable_type)))?;
+
+        let mut storage_box = StorageBox::new(self.id, self.name, dimension, storable_type);
+        storage_box.barcode = self.barcode;
+        storage_box.location = StorageLocation {
+            freezer: self.freezer,
+            shelf: self.shelf,
+            rack: self.rack,
+            temperature: self.temperature.and_then(|t| i8::try_from(t).ok()),
+        };
+        storage_box.rack_id = self.rack_id;
+        storage_box.description = self.description;
+
+        for position in positions {
+            let item_type = storable_type_from_code(&position.item_type)
+                .ok_or_else(|| invalid(format!("unknown item type '{}'", position.item_type)))?;
+            let at = BoxPosition::parse(&position.position, &dimension)?;
+            storage_box.place_item(at, StorableItem::new(item_type, position.item_id))?;
+        }
+
+        // Placing the contents touched the box; restore the stored times.
+        storage_box.created_at = self.created_at;
+        storage_box.updated_at = self.updated_at;
+        Ok(storage_box)
+    }
+}
+
+impl From<&miso_domain::entities::StorageBox> for ActiveModel {
+    fn from(storage_box: &miso_domain::entities::StorageBox) -> Self {
+        use sea_orm::ActiveValue;
+
+        let location = &storage_box.location;
+
+        Self {
+            id: if storage_box.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(storage_box.id)
+            },
+            name: ActiveValue::Set(storage_box.name.clone()),
+            barcode: ActiveValue::Set(storage_box.barcode.clone()),
+            rows: ActiveValue::Set(i16::from(storage_box.dimension.rows())),
+            columns: ActiveValue::Set(i16::from(storage_box.dimension.cols())),
+            freezer: ActiveValue::Set(location.freezer.clone()),
+            shelf: ActiveValue::Set(location.shelf.clone()),
+            rack: ActiveValue::Set(location.rack.clone()),
+            temperature: ActiveValue::Set(location.temperature.map(i16::from)),
+            rack_id: ActiveValue::Set(storage_box.rack_id),
+            storable_type: ActiveValue::Set(
+                storable_type_code(storage_box.storable_type).to_string(),
+            ),
+            description: ActiveValue::Set(storage_box.description.clone()),
+            created_at: ActiveValue::Set(storage_box.created_at),
+            updated_at: ActiveValue::Set(storage_box.updated_at),
+        }
+    }
+}
+
+/// Returns the stored code of a storable item type.
+pub(crate) fn storable_type_code(item_type: miso_domain::entities::StorableType) -> &'static str {
+    use miso_domain::entities::StorableType;
+
+    match item_type {
+        StorableType::Sample => "sample",
+        StorableType::Library => "library",
+        StorableType::LibraryAliquot => "library_aliquot",
+        StorableType::Pool => "pool",
+    }
+}
+
+/// Parses a stored storable item type code.
+fn storable_type_from_code(code: &str) -> Option<miso_domain::entities::StorableType> {
+    use miso_domain::entities::StorableType;
+
+    match code {
+        "sample" => Some(StorableType::Sample),
+        "library" => Some(StorableType::Library),
+        "library_aliquot" => Some(StorableType::LibraryAliquot),
+        "pool" => Some(StorableType::Pool),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{StorableItem, StorableType, StorageBox, StorageLocation};
+    use miso_domain::value_objects::{BoxPosition, Dimension};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_storage_box_round_trips_through_models() {
+        let mut storage_box = StorageBox::new(
+            4,
+            "LA-07".to_string(),
+            Dimension::new(8, 12),
+            StorableType::LibraryAliquot,
+        );
+        storage_box.barcode = Some("BX0007".to_string());
+        storage_box.location = StorageLocation {
+            temperature: Some(-80),
+            ..StorageLocation::with_path("Freezer 2", "Shelf 1", "Rack C")
+        };
+        storage_box.rack_id = Some(12);
+        for (position, id) in [("A1", 31), ("H12", 32)] {
+            let position = BoxPosition::parse(position, &storage_box.dimension).unwrap();
+            let item = StorableItem::new(StorableType::LibraryAliquot, id);
+            storage_box.place_item(position, item).unwrap();
+        }
+
+        let model = ActiveModel::from(&storage_box).try_into_model().unwrap();
+        assert_eq!(model.storable_type, "library_aliquot");
+        let positions: Vec<_> = storage_box
+            .all_contents()
+            .into_iter()
+            .map(|(position, item)| {
+                box_position::ActiveModel::new(storage_box.id, position, item)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+        assert!(positions.iter().any(|p| p.position == "H12"));
+
+        let restored = model.clone().into_domain(positions).unwrap();
+        assert_eq!(restored, storage_box);
+
+        let stray = box_position::Model {
+            box_id: 4,
+            position: "J1".to_string(),
+            item_type: "library_aliquot".to_string(),
+            item_id: 33,
+        };
+        assert!(model.into_domain(vec![stray]).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! SeaORM entity for the storage_unit table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Storage unit database entity: a freezer, shelf or rack.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "storage_unit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Kind code: "freezer", "shelf" or "rack"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub kind: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub name: String,
+
+    pub parent_id: Option<i32>,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))", nullable, unique)]
+    pub barcode: Option<String>,
+
+    pub capacity: Option<i32>,
+
+    /// Temperature, in °C
+    pub temperature: Option<i16>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for StorageUnit.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::storage_box::Entity")]
+    StorageBox,
+}
+
+impl Related<super::storage_box::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StorageBox.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::StorageUnit {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        use miso_domain::errors::DomainError;
+
+        Ok(Self {
+            id: model.id,
+            kind: model.kind.parse().map_err(DomainError::Validation)?,
+            name: model.name,
+            parent_id: model.parent_id,
+            barcode: model.barcode,
+            capacity: model.capacity.and_then(|c| u32::try_from(c).ok()),
+            temperature: model.temperature.and_then(|t| i8::try_from(t).ok()),
+            created_by: model.created_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+impl From<&miso_domain::entities::StorageUnit> for ActiveModel {
+    fn from(unit: &miso_domain::entities::StorageUnit) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if unit.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(unit.id)
+            },
+            kind: ActiveValue::Set(unit.kind.as_str().to_string()),
+            name: ActiveValue::Set(unit.name.clone()),
+            parent_id: ActiveValue::Set(unit.parent_id),
+            barcode: ActiveValue::Set(unit.barcode.clone()),
+            capacity: ActiveValue::Set(unit.capacity.map(|c| c.min(i32::MAX as u32) as i32)),
+            temperature: ActiveValue::Set(unit.temperature.map(i16::from)),
+            created_by: ActiveValue::Set(unit.created_by.clone()),
+            created_at: ActiveValue::Set(unit.created_at),
+            updated_at: ActiveValue::Set(unit.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{StorageUnit, StorageUnitKind};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_storage_unit_round_trips_through_model() {
+        let mut freezer =
+            StorageUnit::new(StorageUnitKind::Freezer, "Freezer 2", None, "tech".to_string())
+                .unwrap();
+        freezer.id = 1;
+        let mut shelf =
+            StorageUnit::new(StorageUnitKind::Shelf, "Shelf 1", Some(&freezer), "tech".to_string())
+                .unwrap();
+        shelf.id = 2;
+        shelf.barcode = Some("SH0001".to_string());
+        shelf.capacity = Some(6);
+        shelf.temperature = Some(-80);
+
+        let model = ActiveModel::from(&shelf).try_into_model().unwrap();
+        assert_eq!(model.kind, "shelf");
+        assert_eq!(StorageUnit::try_from(model.clone()).unwrap(), shelf);
+
+        let unknown = Model {
+            kind: "drawer".to_string(),
+            ..model
+        };
+        assert!(StorageUnit::try_from(unknown).is_err());
+    }
+}
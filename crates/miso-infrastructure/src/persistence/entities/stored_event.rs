@@ -0,0 +1,99 @@
+//! SeaORM entity for the stored_event table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Stored event database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "stored_event")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub sequence: i64,
+
+    #[sea_orm(column_type = "String(StringLen::N(50))")]
+    pub entity_type: String,
+
+    pub entity_id: i32,
+
+    /// "create", "update" or "delete"
+    #[sea_orm(column_type = "String(StringLen::N(10))")]
+    pub action: String,
+
+    /// The entity as JSON text after the change; null for deletes
+    #[sea_orm(column_type = "Text", nullable)]
+    pub state: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub recorded_by: String,
+
+    pub recorded_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub previous_hash: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(64))", unique)]
+    pub hash: String,
+
+    /// Hashes of the values erased from the state, by field path
+    pub erased_hashes: Option<Json>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::StoredEvent {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence: model.sequence,
+            entity_type: model.entity_type,
+            entity_id: model.entity_id,
+            action: model.action.parse()?,
+            state: model.state,
+            recorded_by: model.recorded_by,
+            recorded_at: model.recorded_at,
+            previous_hash: model.previous_hash,
+            hash: model.hash,
+            erased_hashes: model
+                .erased_hashes
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl From<&miso_domain::entities::StoredEvent> for ActiveModel {
+    fn from(event: &miso_domain::entities::StoredEvent) -> Self {
+        use miso_domain::entities::AuditAction;
+        use sea_orm::ActiveValue;
+
+        let action = match event.action {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        };
+
+        Self {
+            sequence: if event.sequence == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(event.sequence)
+            },
+            entity_type: ActiveValue::Set(event.entity_type.clone()),
+            entity_id: ActiveValue::Set(event.entity_id),
+            action: ActiveValue::Set(action.to_string()),
+            state: ActiveValue::Set(event.state.clone()),
+            recorded_by: ActiveValue::Set(event.recorded_by.clone()),
+            recorded_at: ActiveValue::Set(event.recorded_at),
+            previous_hash: ActiveValue::Set(event.previous_hash.clone()),
+            hash: ActiveValue::Set(event.hash.clone()),
+            erased_hashes: ActiveValue::Set(
+                (!event.erased_hashes.is_empty())
+                    .then(|| serde_json::to_value(&event.erased_hashes).unwrap_or_default()),
+            ),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! SeaORM entity for the subproject table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Subproject database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "subproject")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    pub project_id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))")]
+    pub alias: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+/// Database relations for Subproject.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for miso_domain::entities::Subproject {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            project_id: model.project_id,
+            alias: model.alias,
+            description: model.description,
+            created_by: model.created_by,
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Subproject> for ActiveModel {
+    fn from(subproject: &miso_domain::entities::Subproject) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if subproject.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(subproject.id)
+            },
+            project_id: ActiveValue::Set(subproject.project_id),
+            alias: ActiveValue::Set(subproject.alias.clone()),
+            description: ActiveValue::Set(subproject.description.clone()),
+            created_by: ActiveValue::Set(subproject.created_by.clone()),
+            created_at: ActiveValue::Set(subproject.created_at),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! SeaORM entity for the transfer table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::transfer_item;
+
+/// Transfer database entity. The items handed over are held in
+/// [`transfer_item`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "transfer")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub sender: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub recipient: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub courier: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", nullable)]
+    pub tracking_number: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub recipient_email: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(20))", nullable)]
+    pub shipment_status: Option<String>,
+
+    pub shipment_updated_at: Option<DateTimeUtc>,
+
+    pub sent_at: DateTimeUtc,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub received_by: Option<String>,
+
+    pub received_at: Option<DateTimeUtc>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Transfer.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::transfer_item::Entity")]
+    TransferItem,
+}
+
+impl Related<super::transfer_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransferItem.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored transfer and its items to the domain entity.
+    /// Items of an unknown type and unknown shipment statuses are skipped.
+    pub fn into_domain(self, items: Vec<transfer_item::Model>) -> miso_domain::entities::Transfer {
+        use miso_domain::entities::{Transfer, TransferItem};
+
+        Transfer {
+            id: self.id,
+            sender: self.sender,
+            recipient: self.recipient,
+            items: items
+                .into_iter()
+                .filter_map(|i| {
+                    Some(TransferItem {
+                        item_type: i.item_type.parse().ok()?,
+                        entity_id: i.entity_id,
+                        received: i.received,
+                        qc_passed: i.qc_passed,
+                        qc_note: i.qc_note,
+                    })
+                })
+                .collect(),
+            notes: self.notes,
+            courier: self.courier,
+            tracking_number: self.tracking_number,
+            recipient_email: self.recipient_email,
+            shipment_status: self.shipment_status.and_then(|s| s.parse().ok()),
+            shipment_updated_at: self.shipment_updated_at,
+            sent_at: self.sent_at,
+            received_by: self.received_by,
+            received_at: self.received_at,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Transfer> for ActiveModel {
+    fn from(transfer: &miso_domain::entities::Transfer) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if transfer.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(transfer.id)
+            },
+            sender: ActiveValue::Set(transfer.sender.clone()),
+            recipient: ActiveValue::Set(transfer.recipient.clone()),
+            notes: ActiveValue::Set(transfer.notes.clone()),
+            courier: ActiveValue::Set(transfer.courier.clone()),
+            tracking_number: ActiveValue::Set(transfer.tracking_number.clone()),
+            recipient_email: ActiveValue::Set(transfer.recipient_email.clone()),
+            shipment_status: ActiveValue::Set(
+                transfer.shipment_status.map(|s| s.as_str().to_string()),
+            ),
+            shipment_updated_at: ActiveValue::Set(transfer.shipment_updated_at),
+            sent_at: ActiveValue::Set(transfer.sent_at),
+            received_by: ActiveValue::Set(transfer.received_by.clone()),
+            received_at: ActiveValue::Set(transfer.received_at),
+            created_by: ActiveValue::Set(transfer.created_by.clone()),
+            created_at: ActiveValue::Set(transfer.created_at),
+            updated_at: ActiveValue::Set(transfer.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use miso_domain::entities::{ShipmentStatus, Transfer, TransferItemType, TransferReceipt};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_transfer_round_trips_through_models() {
+        let now = Utc::now();
+        let items = [(TransferItemType::Library, 7), (TransferItemType::Sample, 7)];
+        let mut transfer = Transfer::new("Lab A", "Lab B", &items, now, "tech".to_string()).unwrap();
+        transfer.id = 4;
+        transfer
+            .set_shipment(Some("DHL".to_string()), Some("JD0142".to_string()), now)
+            .unwrap();
+        transfer.track(ShipmentStatus::InTransit, now).unwrap();
+        transfer
+            .receive(
+                &[TransferReceipt {
+                    item_type: TransferItemType::Sample,
+                    entity_id: 7,
+                    received: true,
+                    qc_passed: Some(false),
+                    qc_note: Some("Thawed".to_string()),
+                }],
+                "recv",
+                now,
+            )
+            .unwrap();
+
+        let model = ActiveModel::from(&transfer).try_into_model().unwrap();
+        let items = transfer
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                transfer_item::ActiveModel::new(4, i, item)
+                    .try_into_model()
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(model.into_domain(items), transfer);
+    }
+}
@@ -0,0 +1,70 @@
+//! SeaORM entity for the transfer_item table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A sample or library in a transfer and what the recipient found.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "transfer_item")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub transfer_id: i32,
+
+    /// "sample" or "library"
+    #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(20))")]
+    pub item_type: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entity_id: i32,
+
+    /// Order of the item in the transfer, from 0
+    pub position: i16,
+
+    pub received: Option<bool>,
+
+    pub qc_passed: Option<bool>,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub qc_note: Option<String>,
+}
+
+/// Database relations for TransferItem.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transfer::Entity",
+        from = "Column::TransferId",
+        to = "super::transfer::Column::Id"
+    )]
+    Transfer,
+}
+
+impl Related<super::transfer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transfer.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for the `position`th item of the transfer with
+    /// `transfer_id`.
+    pub fn new(
+        transfer_id: i32,
+        position: usize,
+        item: &miso_domain::entities::TransferItem,
+    ) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            transfer_id: ActiveValue::Set(transfer_id),
+            item_type: ActiveValue::Set(item.item_type.as_str().to_string()),
+            entity_id: ActiveValue::Set(item.entity_id),
+            position: ActiveValue::Set(i16::try_from(position).unwrap_or(i16::MAX)),
+            received: ActiveValue::Set(item.received),
+            qc_passed: ActiveValue::Set(item.qc_passed),
+            qc_note: ActiveValue::Set(item.qc_note.clone()),
+        }
+    }
+}
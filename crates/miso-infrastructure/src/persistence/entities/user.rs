@@ -0,0 +1,140 @@
+//! SeaORM entity for the user table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// User database entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(100))", unique)]
+    pub username: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub display_name: String,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub email: String,
+
+    /// Argon2 hash in PHC string format; null for LDAP users
+    #[serde(skip_serializing)]
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub password_hash: Option<String>,
+
+    /// Role code, e.g. "lab_manager"
+    #[sea_orm(column_type = "String(StringLen::N(20))")]
+    pub role: String,
+
+    pub active: bool,
+
+    pub internal: bool,
+
+    pub created_at: DateTimeUtc,
+
+    pub last_login_at: Option<DateTimeUtc>,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for User.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::api_key::Entity")]
+    ApiKey,
+}
+
+impl Related<super::api_key::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKey.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryFrom<Model> for miso_domain::entities::User {
+    type Error = miso_domain::errors::DomainError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            username: model.username,
+            display_name: model.display_name,
+            email: model.email,
+            role: model.role.parse()?,
+            active: model.active,
+            internal: model.internal,
+            created_at: model.created_at,
+            last_login_at: model.last_login_at,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+/// Builds the row for a user. The password hash is not set, so saving a
+/// user leaves the stored password alone.
+impl From<&miso_domain::entities::User> for ActiveModel {
+    fn from(user: &miso_domain::entities::User) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if user.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(user.id)
+            },
+            username: ActiveValue::Set(user.username.clone()),
+            display_name: ActiveValue::Set(user.display_name.clone()),
+            email: ActiveValue::Set(user.email.clone()),
+            password_hash: ActiveValue::NotSet,
+            role: ActiveValue::Set(role_code(user.role).to_string()),
+            active: ActiveValue::Set(user.active),
+            internal: ActiveValue::Set(user.internal),
+            created_at: ActiveValue::Set(user.created_at),
+            last_login_at: ActiveValue::Set(user.last_login_at),
+            updated_at: ActiveValue::Set(user.updated_at),
+        }
+    }
+}
+
+/// Returns the stored code of a role, as parsed by `Role::from_str`.
+pub(crate) fn role_code(role: miso_domain::entities::Role) -> &'static str {
+    use miso_domain::entities::Role;
+
+    match role {
+        Role::Viewer => "viewer",
+        Role::Technician => "technician",
+        Role::LabManager => "lab_manager",
+        Role::Admin => "admin",
+        Role::SuperAdmin => "super_admin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miso_domain::entities::{Role, User};
+    use sea_orm::{ActiveValue, TryIntoModel};
+
+    #[test]
+    fn test_user_round_trips_through_model() {
+        let mut user = User::new_ldap(
+            3,
+            "asmith".to_string(),
+            "A. Smith".to_string(),
+            "asmith@example.org".to_string(),
+            Role::LabManager,
+        );
+        user.record_login();
+
+        let mut active_model = ActiveModel::from(&user);
+        assert_eq!(active_model.password_hash, ActiveValue::NotSet);
+        active_model.password_hash = ActiveValue::Set(None);
+
+        let model = active_model.try_into_model().unwrap();
+        assert_eq!(model.role, "lab_manager");
+        assert_eq!(User::try_from(model).unwrap(), user);
+    }
+}
@@ -0,0 +1,115 @@
+//! SeaORM entity for the workset table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::workset_item;
+
+/// Workset database entity. The workset's members are held in
+/// [`workset_item`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "workset")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))", unique)]
+    pub name: String,
+
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub created_by: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: DateTimeUtc,
+}
+
+/// Database relations for Workset.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::workset_item::Entity")]
+    WorksetItem,
+}
+
+impl Related<super::workset_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WorksetItem.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Converts the stored workset and its items to the domain entity.
+    /// Items of an unknown type are skipped.
+    pub fn into_domain(self, items: Vec<workset_item::Model>) -> miso_domain::entities::Workset {
+        use miso_domain::entities::{Workset, WorksetItem};
+
+        Workset {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            items: items
+                .into_iter()
+                .filter_map(|i| {
+                    Some(WorksetItem {
+                        item_type: i.item_type.parse().ok()?,
+                        entity_id: i.entity_id,
+                        added_at: i.added_at,
+                    })
+                })
+                .collect(),
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<&miso_domain::entities::Workset> for ActiveModel {
+    fn from(workset: &miso_domain::entities::Workset) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            id: if workset.id == 0 {
+                ActiveValue::NotSet
+            } else {
+                ActiveValue::Set(workset.id)
+            },
+            name: ActiveValue::Set(workset.name.clone()),
+            description: ActiveValue::Set(workset.description.clone()),
+            created_by: ActiveValue::Set(workset.created_by.clone()),
+            created_at: ActiveValue::Set(workset.created_at),
+            updated_at: ActiveValue::Set(workset.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use miso_domain::entities::{Workset, WorksetItemType};
+    use sea_orm::TryIntoModel;
+
+    #[test]
+    fn test_workset_round_trips_through_models() {
+        let now = Utc::now();
+        let mut workset = Workset::new("Bench 1".to_string(), None, "tech".to_string());
+        workset.id = 4;
+        workset.add(WorksetItemType::Sample, 7, now);
+        workset.add(WorksetItemType::Library, 7, now);
+
+        let model = ActiveModel::from(&workset).try_into_model().unwrap();
+        let items = workset
+            .items
+            .iter()
+            .map(|i| workset_item::ActiveModel::new(4, i).try_into_model().unwrap())
+            .collect();
+
+        assert_eq!(model.into_domain(items), workset);
+    }
+}
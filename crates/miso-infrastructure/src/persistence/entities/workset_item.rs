@@ -0,0 +1,54 @@
+//! SeaORM entity for the workset_item table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A sample or library in a workset.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "workset_item")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub workset_id: i32,
+
+    /// "sample" or "library"
+    #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(20))")]
+    pub item_type: String,
+
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entity_id: i32,
+
+    pub added_at: DateTimeUtc,
+}
+
+/// Database relations for WorksetItem.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workset::Entity",
+        from = "Column::WorksetId",
+        to = "super::workset::Column::Id"
+    )]
+    Workset,
+}
+
+impl Related<super::workset::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workset.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Builds the row for an item of the workset with `workset_id`.
+    pub fn new(workset_id: i32, item: &miso_domain::entities::WorksetItem) -> Self {
+        use sea_orm::ActiveValue;
+
+        Self {
+            workset_id: ActiveValue::Set(workset_id),
+            item_type: ActiveValue::Set(item.item_type.as_str().to_string()),
+            entity_id: ActiveValue::Set(item.entity_id),
+            added_at: ActiveValue::Set(item.added_at),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Connection pool and query instrumentation.
+//!
+//! Query counts and durations are collected from SeaORM's metric callback,
+//! which runs on the task that issued the query. Slow queries are logged
+//! from there too, so they carry the fields of the enclosing tracing span
+//! (such as the HTTP request ID).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tracing::warn;
+
+/// Running totals for queries executed through a connection.
+#[derive(Debug, Default)]
+pub struct QueryStats {
+    queries: AtomicU64,
+    failures: AtomicU64,
+    slow: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+/// Point-in-time copy of [`QueryStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryStatsSnapshot {
+    /// Queries executed
+    pub queries: u64,
+    /// Queries that returned an error
+    pub failures: u64,
+    /// Queries at or above the slow-query threshold
+    pub slow: u64,
+    /// Total execution time
+    pub total_time: Duration,
+}
+
+impl QueryStats {
+    /// Records one executed query.
+    ///
+    /// Queries taking at least `slow_threshold` are counted as slow and
+    /// logged at warn level with their SQL (without bound values).
+    pub fn record(
+        &self,
+        sql: &str,
+        elapsed: Duration,
+        failed: bool,
+        slow_threshold: Option<Duration>,
+    ) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.total_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        if failed {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if slow_threshold.is_some_and(|threshold| elapsed >= threshold) {
+            self.slow.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                elapsed_ms = elapsed.as_millis() as u64,
+                failed, sql, "Slow query"
+            );
+        }
+    }
+
+    /// Returns the current totals.
+    pub fn snapshot(&self) -> QueryStatsSnapshot {
+        QueryStatsSnapshot {
+            queries: self.queries.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            slow: self.slow.load(Ordering::Relaxed),
+            total_time: Duration::from_micros(self.total_micros.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Connection pool usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// Configured maximum pool size
+    pub max_connections: u32,
+    /// Connections currently open
+    pub size: u32,
+    /// Open connections not checked out
+    pub idle: u32,
+    /// Open connections checked out by a query or transaction
+    pub in_use: u32,
+    /// Time taken to check out a connection when the status was taken
+    pub acquire_wait: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_stats() {
+        let stats = QueryStats::default();
+        let threshold = Some(Duration::from_millis(100));
+
+        stats.record("SELECT 1", Duration::from_millis(5), false, threshold);
+        stats.record("SELECT 2", Duration::from_millis(150), false, threshold);
+        stats.record("SELECT 3", Duration::from_millis(20), true, threshold);
+        stats.record("SELECT 4", Duration::from_millis(500), false, None);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.queries, 4);
+        assert_eq!(snapshot.failures, 1);
+        assert_eq!(snapshot.slow, 1);
+        assert_eq!(snapshot.total_time, Duration::from_millis(675));
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod database;
 pub mod entities;
+pub mod metrics;
 pub mod repositories;
 
 pub use database::Database;
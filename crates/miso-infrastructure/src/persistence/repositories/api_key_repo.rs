@@ -0,0 +1,94 @@
+//! SeaORM implementation of ApiKeyRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{ApiKey, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ApiKeyRepository;
+
+use crate::persistence::entities::api_key::{self, Entity as ApiKeyEntity};
+
+/// SeaORM-based API key repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmApiKeyRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmApiKeyRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SeaOrmApiKeyRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ApiKey>, DomainError> {
+        debug!("Finding API key by ID: {}", id);
+
+        let result = ApiKeyEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<ApiKey>, DomainError> {
+        debug!("Finding API key by name: {}", name);
+
+        let result = ApiKeyEntity::find()
+            .filter(api_key::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self, key_hash))]
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError> {
+        let result = ApiKeyEntity::find()
+            .filter(api_key::Column::KeyHash.eq(key_hash))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<ApiKey>, DomainError> {
+        debug!("Listing API keys");
+
+        let results = ApiKeyEntity::find()
+            .order_by_desc(api_key::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, key))]
+    async fn save(&self, key: &ApiKey) -> Result<EntityId, DomainError> {
+        debug!("Saving API key: {}", key.name);
+
+        let active_model: api_key::ActiveModel = key.into();
+
+        let model = if key.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,104 @@
+//! SeaORM implementation of ApiUsageRepository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::ApiUsage;
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ApiUsageRepository;
+
+use crate::persistence::entities::api_usage::{self, Entity as ApiUsageEntity};
+
+/// SeaORM-based API usage repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmApiUsageRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmApiUsageRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiUsageRepository for SeaOrmApiUsageRepository {
+    #[instrument(skip(self, usage), fields(count = usage.len()))]
+    async fn add(&self, usage: &[ApiUsage]) -> Result<(), DomainError> {
+        debug!("Adding {} API usage counts", usage.len());
+
+        if usage.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for usage in usage {
+            // Locked so servers flushing at the same time don't overwrite
+            // each other's additions. Two first inserts of a bucket may
+            // both succeed; reports add rows up, so that does no harm.
+            let key = match usage.api_key_id {
+                Some(id) => api_usage::Column::ApiKeyId.eq(id),
+                None => api_usage::Column::ApiKeyId.is_null(),
+            };
+            let existing = ApiUsageEntity::find()
+                .filter(api_usage::Column::Hour.eq(usage.hour))
+                .filter(api_usage::Column::UserId.eq(usage.user_id))
+                .filter(key)
+                .filter(api_usage::Column::Method.eq(usage.method.as_str()))
+                .filter(api_usage::Column::Route.eq(usage.route.as_str()))
+                .lock_exclusive()
+                .one(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+            match existing {
+                Some(model) => {
+                    let mut total: ApiUsage = model.clone().into();
+                    total.merge(usage);
+                    let mut active_model: api_usage::ActiveModel = (&total).into();
+                    active_model.id = ActiveValue::Unchanged(model.id);
+                    active_model.update(&txn).await
+                }
+                None => api_usage::ActiveModel::from(usage).insert(&txn).await,
+            }
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<ApiUsage>, DomainError> {
+        debug!("Listing API usage from {} to {}", since, until);
+
+        let results = ApiUsageEntity::find()
+            .filter(api_usage::Column::Hour.gte(since))
+            .filter(api_usage::Column::Hour.lt(until))
+            .order_by_asc(api_usage::Column::Hour)
+            .order_by_asc(api_usage::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,75 @@
+//! SeaORM implementation of ArchiveRuleRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, SqlErr};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{ArchiveRule, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ArchiveRuleRepository;
+
+use crate::persistence::entities::archive_rule::{self, Entity as ArchiveRuleEntity};
+
+/// SeaORM-based archiving rule repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmArchiveRuleRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmArchiveRuleRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ArchiveRuleRepository for SeaOrmArchiveRuleRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ArchiveRule>, DomainError> {
+        debug!("Finding archive rule by ID: {}", id);
+
+        let result = ArchiveRuleEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<ArchiveRule>, DomainError> {
+        debug!("Listing archive rules");
+
+        let results = ArchiveRuleEntity::find()
+            .order_by_asc(archive_rule::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, rule), fields(name = %rule.name))]
+    async fn save(&self, rule: &ArchiveRule) -> Result<EntityId, DomainError> {
+        debug!("Saving archive rule {}", rule.name);
+
+        let active_model: archive_rule::ActiveModel = rule.into();
+
+        let model = if rule.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "ArchiveRule".to_string(),
+                field: "name".to_string(),
+                value: rule.name.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(model.id)
+    }
+}
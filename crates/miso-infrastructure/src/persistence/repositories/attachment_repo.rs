@@ -0,0 +1,89 @@
+//! SeaORM implementation of AttachmentRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{Attachment, AttachmentTarget, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::AttachmentRepository;
+
+use crate::persistence::entities::attachment::{self, Entity as AttachmentEntity};
+
+/// SeaORM-based attachment repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmAttachmentRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmAttachmentRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AttachmentRepository for SeaOrmAttachmentRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Attachment>, DomainError> {
+        debug!("Finding attachment by ID: {}", id);
+
+        let result = AttachmentEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_target(
+        &self,
+        target: AttachmentTarget,
+        target_id: EntityId,
+    ) -> Result<Vec<Attachment>, DomainError> {
+        debug!("Finding attachments of {} {}", target, target_id);
+
+        let results = AttachmentEntity::find()
+            .filter(attachment::Column::Target.eq(target.as_str()))
+            .filter(attachment::Column::TargetId.eq(target_id))
+            .order_by_desc(attachment::Column::UploadedAt)
+            .order_by_desc(attachment::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, attachment))]
+    async fn save(&self, attachment: &Attachment) -> Result<EntityId, DomainError> {
+        debug!("Saving attachment: {}", attachment.filename);
+
+        let active_model: attachment::ActiveModel = attachment.into();
+
+        let model = if attachment.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting attachment: {}", id);
+
+        AttachmentEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! SeaORM implementation of AttributeDefinitionRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{AttributeDefinition, AttributeTarget, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::AttributeDefinitionRepository;
+
+use crate::persistence::entities::attribute_definition::{
+    self, Entity as AttributeDefinitionEntity,
+};
+
+/// SeaORM-based attribute definition repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmAttributeDefinitionRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmAttributeDefinitionRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AttributeDefinitionRepository for SeaOrmAttributeDefinitionRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<AttributeDefinition>, DomainError> {
+        debug!("Finding attribute definition by ID: {}", id);
+
+        let result = AttributeDefinitionEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(
+        &self,
+        target: Option<AttributeTarget>,
+    ) -> Result<Vec<AttributeDefinition>, DomainError> {
+        debug!("Listing attribute definitions");
+
+        let mut query = AttributeDefinitionEntity::find();
+        if let Some(target) = target {
+            query = query.filter(attribute_definition::Column::Target.eq(target.to_string()));
+        }
+
+        let results = query
+            .order_by_asc(attribute_definition::Column::Position)
+            .order_by_asc(attribute_definition::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_for_project(
+        &self,
+        target: AttributeTarget,
+        project_id: EntityId,
+    ) -> Result<Vec<AttributeDefinition>, DomainError> {
+        debug!(
+            "Finding {} attribute definitions for project {}",
+            target, project_id
+        );
+
+        let results = AttributeDefinitionEntity::find()
+            .filter(attribute_definition::Column::Target.eq(target.to_string()))
+            .filter(
+                Condition::any()
+                    .add(attribute_definition::Column::ProjectId.is_null())
+                    .add(attribute_definition::Column::ProjectId.eq(project_id)),
+            )
+            .order_by_asc(attribute_definition::Column::Position)
+            .order_by_asc(attribute_definition::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, definition))]
+    async fn save(&self, definition: &AttributeDefinition) -> Result<EntityId, DomainError> {
+        debug!("Saving attribute definition: {}", definition.key);
+
+        let active_model: attribute_definition::ActiveModel = definition.into();
+
+        let saved = if definition.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting attribute definition: {}", id);
+
+        AttributeDefinitionEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! SeaORM implementation of AuditLogRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{AuditEntry, AuditQuery, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::AuditLogRepository;
+
+use crate::persistence::entities::audit_log::{self, Entity as AuditLogEntity};
+
+/// Entries returned when a query sets no limit.
+const DEFAULT_LIMIT: u64 = 100;
+
+/// SeaORM-based audit log repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmAuditLogRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmAuditLogRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for SeaOrmAuditLogRepository {
+    #[instrument(skip(self))]
+    async fn find(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, DomainError> {
+        debug!("Finding audit log entries: {:?}", query);
+
+        let mut select = AuditLogEntity::find();
+        if let Some(entity_type) = &query.entity_type {
+            select = select.filter(audit_log::Column::EntityType.eq(entity_type.as_str()));
+        }
+        if let Some(entity_id) = query.entity_id {
+            select = select.filter(audit_log::Column::EntityId.eq(entity_id));
+        }
+        if let Some(changed_by) = &query.changed_by {
+            select = select.filter(audit_log::Column::ChangedBy.eq(changed_by.as_str()));
+        }
+
+        let results = select
+            .order_by_desc(audit_log::Column::ChangedAt)
+            .order_by_desc(audit_log::Column::Id)
+            .limit(query.limit.unwrap_or(DEFAULT_LIMIT))
+            .offset(query.offset.unwrap_or(0))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, entries), fields(count = entries.len()))]
+    async fn save_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+        debug!("Saving {} audit log entries", entries.len());
+
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Locking the newest entry holds back other writers until this
+        // transaction ends, so they chain onto these entries instead
+        let last = AuditLogEntity::find()
+            .order_by_desc(audit_log::Column::Id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        let mut previous_hash = last.map(|e| e.hash).unwrap_or_default();
+
+        let mut models = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut entry = entry.clone();
+            entry.chain(previous_hash);
+            previous_hash = entry.hash.clone();
+            models.push(audit_log::ActiveModel::from(&entry));
+        }
+
+        AuditLogEntity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_after(&self, id: EntityId, limit: u64) -> Result<Vec<AuditEntry>, DomainError> {
+        debug!("Listing audit log entries after {}", id);
+
+        let results = AuditLogEntity::find()
+            .filter(audit_log::Column::Id.gt(id))
+            .order_by_asc(audit_log::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<AuditEntry>, DomainError> {
+        debug!("Finding audit log for {} {}", entity_type, entity_id);
+
+        let results = AuditLogEntity::find()
+            .filter(audit_log::Column::EntityType.eq(entity_type))
+            .filter(audit_log::Column::EntityId.eq(entity_id))
+            .order_by_asc(audit_log::Column::ChangedAt)
+            .order_by_asc(audit_log::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, entries), fields(count = entries.len()))]
+    async fn update_all(&self, entries: &[AuditEntry]) -> Result<(), DomainError> {
+        debug!("Rewriting {} audit log entries", entries.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for entry in entries {
+            audit_log::ActiveModel::from(entry)
+                .update(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
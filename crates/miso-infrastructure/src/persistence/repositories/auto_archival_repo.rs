@@ -0,0 +1,72 @@
+//! SeaORM implementation of AutoArchivalRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{AutoArchival, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::AutoArchivalRepository;
+
+use crate::persistence::entities::auto_archival::{self, Entity as AutoArchivalEntity};
+
+/// SeaORM-based repository for items archived by rules.
+#[derive(Debug, Clone)]
+pub struct SeaOrmAutoArchivalRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmAutoArchivalRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AutoArchivalRepository for SeaOrmAutoArchivalRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<AutoArchival>, DomainError> {
+        debug!("Finding auto archival by ID: {}", id);
+
+        let result = AutoArchivalEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_rule(&self, rule_id: EntityId) -> Result<Vec<AutoArchival>, DomainError> {
+        debug!("Finding items archived by rule {}", rule_id);
+
+        let results = AutoArchivalEntity::find()
+            .filter(auto_archival::Column::RuleId.eq(rule_id))
+            .order_by_desc(auto_archival::Column::ArchivedAt)
+            .order_by_desc(auto_archival::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, archival), fields(rule_id = archival.rule_id))]
+    async fn save(&self, archival: &AutoArchival) -> Result<EntityId, DomainError> {
+        debug!("Saving auto archival of {} {}", archival.target, archival.entity_name);
+
+        let active_model: auto_archival::ActiveModel = archival.into();
+
+        let model = if archival.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,133 @@
+//! SeaORM implementation of ChangeLogRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{ChangeLogEntry, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ChangeLogRepository;
+
+use crate::persistence::entities::change_log::{self, Entity as ChangeLogEntity};
+
+/// SeaORM-based change log repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmChangeLogRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmChangeLogRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ChangeLogRepository for SeaOrmChangeLogRepository {
+    #[instrument(skip(self))]
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<ChangeLogEntry>, DomainError> {
+        debug!("Finding change log for {} {}", entity_type, entity_id);
+
+        let results = ChangeLogEntity::find()
+            .filter(change_log::Column::EntityType.eq(entity_type))
+            .filter(change_log::Column::EntityId.eq(entity_id))
+            .order_by_asc(change_log::Column::ChangedAt)
+            .order_by_asc(change_log::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, entries), fields(count = entries.len()))]
+    async fn save_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+        debug!("Saving {} change log entries", entries.len());
+
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Locking the newest entry holds back other writers until this
+        // transaction ends, so they chain onto these entries instead
+        let last = ChangeLogEntity::find()
+            .order_by_desc(change_log::Column::Id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        let mut previous_hash = last.map(|e| e.hash).unwrap_or_default();
+
+        let mut models = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut entry = entry.clone();
+            entry.chain(previous_hash);
+            previous_hash = entry.hash.clone();
+            models.push(change_log::ActiveModel::from(&entry));
+        }
+
+        ChangeLogEntity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_after(&self, id: EntityId, limit: u64) -> Result<Vec<ChangeLogEntry>, DomainError> {
+        debug!("Listing change log entries after {}", id);
+
+        let results = ChangeLogEntity::find()
+            .filter(change_log::Column::Id.gt(id))
+            .order_by_asc(change_log::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, entries), fields(count = entries.len()))]
+    async fn update_all(&self, entries: &[ChangeLogEntry]) -> Result<(), DomainError> {
+        debug!("Rewriting {} change log entries", entries.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for entry in entries {
+            change_log::ActiveModel::from(entry)
+                .update(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
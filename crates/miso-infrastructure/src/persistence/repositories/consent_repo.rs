@@ -0,0 +1,74 @@
+//! SeaORM implementation of ConsentRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{Consent, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ConsentRepository;
+
+use crate::persistence::entities::consent::{self, Entity as ConsentEntity};
+
+/// SeaORM-based consent repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmConsentRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmConsentRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ConsentRepository for SeaOrmConsentRepository {
+    #[instrument(skip(self))]
+    async fn find_by_identity(&self, identity_id: EntityId) -> Result<Option<Consent>, DomainError> {
+        debug!("Finding consent for identity {}", identity_id);
+
+        let result = ConsentEntity::find()
+            .filter(consent::Column::IdentityId.eq(identity_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self, identity_ids), fields(count = identity_ids.len()))]
+    async fn find_by_identities(
+        &self,
+        identity_ids: &[EntityId],
+    ) -> Result<Vec<Consent>, DomainError> {
+        if identity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let results = ConsentEntity::find()
+            .filter(consent::Column::IdentityId.is_in(identity_ids.iter().copied()))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, consent), fields(identity_id = consent.identity_id))]
+    async fn save(&self, consent: &Consent) -> Result<EntityId, DomainError> {
+        debug!("Saving consent for identity {}", consent.identity_id);
+
+        let active_model: consent::ActiveModel = consent.into();
+
+        let saved = if consent.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+}
@@ -0,0 +1,101 @@
+//! SeaORM implementation of DataLocationRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{DataLocation, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::DataLocationRepository;
+
+use crate::persistence::entities::data_location::{self, Entity as DataLocationEntity};
+
+/// SeaORM-based data location repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmDataLocationRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmDataLocationRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl DataLocationRepository for SeaOrmDataLocationRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<DataLocation>, DomainError> {
+        debug!("Finding data location by ID: {}", id);
+
+        let result = DataLocationEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<DataLocation>, DomainError> {
+        debug!("Finding data locations for run: {}", run_id);
+
+        let results = DataLocationEntity::find()
+            .filter(data_location::Column::RunId.eq(run_id))
+            .order_by_asc(data_location::Column::Lane)
+            .order_by_asc(data_location::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn list_unpurged(&self) -> Result<Vec<DataLocation>, DomainError> {
+        debug!("Listing unpurged data locations");
+
+        let results = DataLocationEntity::find()
+            .filter(data_location::Column::PurgedAt.is_null())
+            .order_by_asc(data_location::Column::RegisteredAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, location))]
+    async fn save(&self, location: &DataLocation) -> Result<EntityId, DomainError> {
+        debug!(
+            "Saving data location for run {}: {}",
+            location.run_id, location.uri
+        );
+
+        let active_model: data_location::ActiveModel = location.into();
+
+        let saved = if location.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting data location: {}", id);
+
+        DataLocationEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
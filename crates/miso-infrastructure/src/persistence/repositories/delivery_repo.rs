@@ -0,0 +1,83 @@
+//! SeaORM implementation of DeliveryRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{Delivery, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::DeliveryRepository;
+
+use crate::persistence::entities::delivery::{self, Entity as DeliveryEntity};
+
+/// SeaORM-based delivery repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmDeliveryRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmDeliveryRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Deliveries matching a condition, newest first.
+    async fn find_where(
+        &self,
+        condition: sea_orm::sea_query::SimpleExpr,
+    ) -> Result<Vec<Delivery>, DomainError> {
+        let results = DeliveryEntity::find()
+            .filter(condition)
+            .order_by_desc(delivery::Column::DeliveredAt)
+            .order_by_desc(delivery::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+#[async_trait]
+impl DeliveryRepository for SeaOrmDeliveryRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Delivery>, DomainError> {
+        debug!("Finding delivery by ID: {}", id);
+
+        let result = DeliveryEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Delivery>, DomainError> {
+        debug!("Finding deliveries of project {}", project_id);
+        self.find_where(delivery::Column::ProjectId.eq(project_id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<Delivery>, DomainError> {
+        debug!("Finding deliveries of run {}", run_id);
+        self.find_where(delivery::Column::RunId.eq(run_id)).await
+    }
+
+    #[instrument(skip(self, delivery), fields(run_id = delivery.run_id))]
+    async fn save(&self, delivery: &Delivery) -> Result<EntityId, DomainError> {
+        debug!("Saving delivery to {}", delivery.uri);
+
+        let active_model: delivery::ActiveModel = delivery.into();
+
+        let model = if delivery.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
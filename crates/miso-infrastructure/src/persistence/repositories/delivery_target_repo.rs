@@ -0,0 +1,69 @@
+//! SeaORM implementation of DeliveryTargetRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{DeliveryTarget, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::DeliveryTargetRepository;
+
+use crate::persistence::entities::delivery_target::{self, Entity as DeliveryTargetEntity};
+
+/// SeaORM-based delivery target repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmDeliveryTargetRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmDeliveryTargetRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl DeliveryTargetRepository for SeaOrmDeliveryTargetRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<DeliveryTarget>, DomainError> {
+        debug!("Finding delivery target by ID: {}", id);
+
+        let result = DeliveryTargetEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<DeliveryTarget>, DomainError> {
+        debug!("Finding delivery targets of project {}", project_id);
+
+        let results = DeliveryTargetEntity::find()
+            .filter(delivery_target::Column::ProjectId.eq(project_id))
+            .order_by_asc(delivery_target::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, target), fields(project_id = target.project_id))]
+    async fn save(&self, target: &DeliveryTarget) -> Result<EntityId, DomainError> {
+        debug!("Saving delivery target {}", target.name);
+
+        let active_model: delivery_target::ActiveModel = target.into();
+
+        let model = if target.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,129 @@
+//! SeaORM implementation of ErasureRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Erasure};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ErasureRepository;
+
+use crate::persistence::entities::erased_name::{self, Entity as ErasedNameEntity};
+use crate::persistence::entities::erasure::{self, Entity as ErasureEntity};
+
+/// SeaORM-based erasure repository.
+///
+/// An erasure's name hashes live in the erased_name table and are written
+/// together with it.
+#[derive(Debug, Clone)]
+pub struct SeaOrmErasureRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmErasureRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ErasureRepository for SeaOrmErasureRepository {
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<Erasure>, DomainError> {
+        debug!("Listing erasures");
+
+        let models = ErasureEntity::find()
+            .order_by_desc(erasure::Column::ErasedAt)
+            .order_by_desc(erasure::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut names: HashMap<i32, Vec<erased_name::Model>> = HashMap::new();
+        for name in ErasedNameEntity::find()
+            .filter(erased_name::Column::ErasureId.is_in(ids))
+            .order_by_asc(erased_name::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            names.entry(name.erasure_id).or_default().push(name);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let erased_names = names.remove(&m.id).unwrap_or_default();
+                m.into_domain(erased_names)
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self, name_hashes), fields(count = name_hashes.len()))]
+    async fn is_erased(&self, name_hashes: &[String]) -> Result<bool, DomainError> {
+        if name_hashes.is_empty() {
+            return Ok(false);
+        }
+
+        let count = ErasedNameEntity::find()
+            .filter(erased_name::Column::NameHash.is_in(name_hashes.iter().cloned()))
+            .count(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(count > 0)
+    }
+
+    #[instrument(skip(self, erasure), fields(identity_id = erasure.identity_id))]
+    async fn save(&self, erasure: &Erasure) -> Result<EntityId, DomainError> {
+        debug!("Saving erasure of identity {}", erasure.identity_id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let saved = erasure::ActiveModel::from(erasure)
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for hash in &erasure.name_hashes {
+            // A name erased before, under another identity, stays erased
+            let exists = ErasedNameEntity::find()
+                .filter(erased_name::Column::NameHash.eq(hash.as_str()))
+                .count(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?
+                > 0;
+            if exists {
+                continue;
+            }
+            erased_name::ActiveModel {
+                erasure_id: sea_orm::ActiveValue::Set(saved.id),
+                name_hash: sea_orm::ActiveValue::Set(hash.clone()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+}
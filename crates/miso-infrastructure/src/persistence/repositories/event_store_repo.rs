@@ -0,0 +1,236 @@
+//! SeaORM implementation of EventStoreRepository.
+
+use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, EntitySnapshot, StoredEvent};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::EventStoreRepository;
+
+use crate::persistence::entities::entity_snapshot::{self, Entity as EntitySnapshotEntity};
+use crate::persistence::entities::stored_event::{self, Entity as StoredEventEntity};
+
+/// SeaORM-based event store repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmEventStoreRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmEventStoreRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl EventStoreRepository for SeaOrmEventStoreRepository {
+    #[instrument(skip(self))]
+    async fn last(&self) -> Result<Option<StoredEvent>, DomainError> {
+        debug!("Finding newest stored event");
+
+        let result = StoredEventEntity::find()
+            .order_by_desc(stored_event::Column::Sequence)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self, events), fields(count = events.len()))]
+    async fn append(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+        debug!("Appending {} events", events.len());
+
+        let Some(first) = events.first() else {
+            return Ok(());
+        };
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Locking the newest event holds back other writers until this
+        // transaction ends, so they chain onto these events instead
+        let last = StoredEventEntity::find()
+            .order_by_desc(stored_event::Column::Sequence)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        let last_hash = last.map(|e| e.hash).unwrap_or_default();
+        if first.previous_hash != last_hash {
+            return Err(DomainError::ConcurrentModification {
+                entity_type: "StoredEvent".to_string(),
+                id: last_hash,
+            });
+        }
+
+        for event in events {
+            let saved = stored_event::ActiveModel::from(event)
+                .insert(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+            // Deleted entities leave the read model; others take the new
+            // state
+            delete_snapshot(&txn, &event.entity_type, event.entity_id).await?;
+            if let Some(state) = &event.state {
+                let snapshot = EntitySnapshot {
+                    entity_type: event.entity_type.clone(),
+                    entity_id: event.entity_id,
+                    state: state.clone(),
+                    sequence: saved.sequence,
+                };
+                entity_snapshot::ActiveModel::from(&snapshot)
+                    .insert(&txn)
+                    .await
+                    .map_err(|e| DomainError::Validation(e.to_string()))?;
+            }
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_after(&self, sequence: i64, limit: u64) -> Result<Vec<StoredEvent>, DomainError> {
+        debug!("Listing up to {} events after {}", limit, sequence);
+
+        let results = StoredEventEntity::find()
+            .filter(stored_event::Column::Sequence.gt(sequence))
+            .order_by_asc(stored_event::Column::Sequence)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<StoredEvent>, DomainError> {
+        debug!("Finding events of {} {}", entity_type, entity_id);
+
+        let results = StoredEventEntity::find()
+            .filter(stored_event::Column::EntityType.eq(entity_type))
+            .filter(stored_event::Column::EntityId.eq(entity_id))
+            .order_by_asc(stored_event::Column::Sequence)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, events), fields(count = events.len()))]
+    async fn update_all(&self, events: &[StoredEvent]) -> Result<(), DomainError> {
+        debug!("Rewriting {} stored events", events.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Only the state and the hashes of its erased values are
+        // rewritten; the event's hash stays as it was chained
+        for event in events {
+            let erased = stored_event::ActiveModel::from(event).erased_hashes;
+            stored_event::ActiveModel {
+                sequence: ActiveValue::Unchanged(event.sequence),
+                state: ActiveValue::Set(event.state.clone()),
+                erased_hashes: erased,
+                ..Default::default()
+            }
+            .update(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+            if let Some(state) = &event.state {
+                EntitySnapshotEntity::update_many()
+                    .col_expr(entity_snapshot::Column::State, Expr::value(state.clone()))
+                    .filter(entity_snapshot::Column::Sequence.eq(event.sequence))
+                    .exec(&txn)
+                    .await
+                    .map_err(|e| DomainError::Validation(e.to_string()))?;
+            }
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_snapshot(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Option<EntitySnapshot>, DomainError> {
+        debug!("Finding snapshot of {} {}", entity_type, entity_id);
+
+        let result = EntitySnapshotEntity::find_by_id((entity_type.to_string(), entity_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self, snapshots), fields(count = snapshots.len()))]
+    async fn replace_snapshots(&self, snapshots: &[EntitySnapshot]) -> Result<(), DomainError> {
+        debug!("Replacing read model with {} snapshots", snapshots.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        EntitySnapshotEntity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        // Insert in chunks to stay under statement size limits
+        for chunk in snapshots.chunks(500) {
+            EntitySnapshotEntity::insert_many(chunk.iter().map(entity_snapshot::ActiveModel::from))
+                .exec(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+async fn delete_snapshot(
+    txn: &DatabaseTransaction,
+    entity_type: &str,
+    entity_id: EntityId,
+) -> Result<(), DomainError> {
+    EntitySnapshotEntity::delete_by_id((entity_type.to_string(), entity_id))
+        .exec(txn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! SeaORM implementation of ExportTemplateRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ExportTemplate};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ExportTemplateRepository;
+
+use crate::persistence::entities::export_template::{self, Entity as ExportTemplateEntity};
+
+/// SeaORM-based export template repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmExportTemplateRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmExportTemplateRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ExportTemplateRepository for SeaOrmExportTemplateRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ExportTemplate>, DomainError> {
+        debug!("Finding export template by ID: {}", id);
+
+        let result = ExportTemplateEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<ExportTemplate>, DomainError> {
+        debug!("Finding export template by name: {}", name);
+
+        let result = ExportTemplateEntity::find()
+            .filter(export_template::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<ExportTemplate>, DomainError> {
+        debug!("Listing export templates");
+
+        let results = ExportTemplateEntity::find()
+            .order_by_asc(export_template::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, template))]
+    async fn save(&self, template: &ExportTemplate) -> Result<EntityId, DomainError> {
+        debug!("Saving export template: {}", template.name);
+
+        let active_model: export_template::ActiveModel = template.into();
+
+        let model = if template.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting export template: {}", id);
+
+        ExportTemplateEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
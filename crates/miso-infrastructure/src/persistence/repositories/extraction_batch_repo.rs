@@ -0,0 +1,219 @@
+//! SeaORM implementation of ExtractionBatchRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, SqlErr, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ExtractionBatch};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{ExtractionBatchRepository, QueryOptions};
+
+use crate::persistence::entities::extraction_batch::{self, Entity as ExtractionBatchEntity};
+use crate::persistence::entities::extraction_batch_sample::{
+    self, Entity as ExtractionBatchSampleEntity,
+};
+
+/// SeaORM-based extraction batch repository.
+///
+/// A batch's samples and blanks live in the extraction_batch_sample table
+/// and are always read and written together with the batch.
+#[derive(Debug, Clone)]
+pub struct SeaOrmExtractionBatchRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmExtractionBatchRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the samples of `models` in one query and assembles the
+    /// batches.
+    async fn with_samples(
+        &self,
+        models: Vec<extraction_batch::Model>,
+    ) -> Result<Vec<ExtractionBatch>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut samples: HashMap<i32, Vec<extraction_batch_sample::Model>> = HashMap::new();
+        for sample in ExtractionBatchSampleEntity::find()
+            .filter(extraction_batch_sample::Column::BatchId.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            samples.entry(sample.batch_id).or_default().push(sample);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let batch_samples = samples.remove(&m.id).unwrap_or_default();
+                m.into_domain(batch_samples)
+            })
+            .collect())
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<ExtractionBatchEntity>,
+    ) -> Result<Option<ExtractionBatch>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self.with_samples(model.into_iter().collect()).await?.pop())
+    }
+
+    /// Replaces the stored samples and blanks of the batch with `batch_id`.
+    async fn replace_samples<C: ConnectionTrait>(
+        conn: &C,
+        batch_id: EntityId,
+        batch: &ExtractionBatch,
+    ) -> Result<(), DomainError> {
+        ExtractionBatchSampleEntity::delete_many()
+            .filter(extraction_batch_sample::Column::BatchId.eq(batch_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let rows = extraction_batch_sample::ActiveModel::for_batch(batch_id, batch);
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        ExtractionBatchSampleEntity::insert_many(rows)
+            .exec(conn)
+            .await
+            .map_err(|e| match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Validation(
+                    "A sample is already in another extraction batch".to_string(),
+                ),
+                _ => DomainError::Validation(e.to_string()),
+            })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExtractionBatchRepository for SeaOrmExtractionBatchRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ExtractionBatch>, DomainError> {
+        debug!("Finding extraction batch by ID: {}", id);
+
+        self.find_one(ExtractionBatchEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<ExtractionBatch>, DomainError> {
+        debug!("Finding extraction batch by name: {}", name);
+
+        self.find_one(
+            ExtractionBatchEntity::find().filter(extraction_batch::Column::Name.eq(name)),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_sample(
+        &self,
+        sample_id: EntityId,
+    ) -> Result<Option<ExtractionBatch>, DomainError> {
+        debug!("Finding extraction batch of sample: {}", sample_id);
+
+        let member = ExtractionBatchSampleEntity::find_by_id(sample_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        match member {
+            Some(member) => self.find_by_id(member.batch_id).await,
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ExtractionBatch>, DomainError> {
+        debug!("Listing extraction batches");
+
+        let mut query = ExtractionBatchEntity::find()
+            .order_by_desc(extraction_batch::Column::ExtractedAt)
+            .order_by_desc(extraction_batch::Column::Id);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_samples(models).await
+    }
+
+    #[instrument(
+        skip(self, batch),
+        fields(samples = batch.sample_ids.len(), blanks = batch.blank_ids.len())
+    )]
+    async fn save(&self, batch: &ExtractionBatch) -> Result<EntityId, DomainError> {
+        debug!("Saving extraction batch: {}", batch.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: extraction_batch::ActiveModel = batch.into();
+        let saved = if batch.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "ExtractionBatch".to_string(),
+                field: "name".to_string(),
+                value: batch.name.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Self::replace_samples(&txn, saved.id, batch).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting extraction batch: {}", id);
+
+        // Samples leave the batch with it by the foreign key cascade.
+        ExtractionBatchEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! SeaORM implementation of IndexSetRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, IndexSet};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::IndexSetRepository;
+
+use crate::persistence::entities::index_set::{self, Entity as IndexSetEntity};
+
+/// SeaORM-based index set catalog repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmIndexSetRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmIndexSetRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl IndexSetRepository for SeaOrmIndexSetRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<IndexSet>, DomainError> {
+        debug!("Finding index set by ID: {}", id);
+
+        let result = IndexSetEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<IndexSet>, DomainError> {
+        debug!("Finding index set by name: {}", name);
+
+        let result = IndexSetEntity::find()
+            .filter(index_set::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<IndexSet>, DomainError> {
+        debug!("Listing index sets");
+
+        let results = IndexSetEntity::find()
+            .order_by_asc(index_set::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, set))]
+    async fn save(&self, set: &IndexSet) -> Result<EntityId, DomainError> {
+        debug!("Saving index set: {}", set.name);
+
+        let active_model: index_set::ActiveModel = set.into();
+
+        let model = if set.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,87 @@
+//! SeaORM implementation of InstrumentEventRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, InstrumentEvent};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::InstrumentEventRepository;
+
+use crate::persistence::entities::instrument_event::{self, Entity as InstrumentEventEntity};
+
+/// SeaORM-based instrument event repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmInstrumentEventRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmInstrumentEventRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl InstrumentEventRepository for SeaOrmInstrumentEventRepository {
+    #[instrument(skip(self))]
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<InstrumentEvent>, DomainError> {
+        debug!("Finding instrument events for run: {}", run_id);
+
+        let results = InstrumentEventEntity::find()
+            .filter(instrument_event::Column::RunId.eq(run_id))
+            .order_by_asc(instrument_event::Column::OccurredAt)
+            .order_by_asc(instrument_event::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, events))]
+    async fn replace_for_source(
+        &self,
+        run_id: EntityId,
+        source: &str,
+        events: &[InstrumentEvent],
+    ) -> Result<(), DomainError> {
+        debug!(
+            "Replacing instrument events for run {} from {}: {} events",
+            run_id,
+            source,
+            events.len()
+        );
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        InstrumentEventEntity::delete_many()
+            .filter(instrument_event::Column::RunId.eq(run_id))
+            .filter(instrument_event::Column::Source.eq(source))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for event in events {
+            let active_model: instrument_event::ActiveModel = event.into();
+            active_model
+                .insert(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! SeaORM implementation of InstrumentModelRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, InstrumentModel};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::InstrumentModelRepository;
+
+use crate::persistence::entities::instrument_model::{self, Entity as InstrumentModelEntity};
+
+/// SeaORM-based instrument model repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmInstrumentModelRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmInstrumentModelRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl InstrumentModelRepository for SeaOrmInstrumentModelRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<InstrumentModel>, DomainError> {
+        debug!("Finding instrument model by ID: {}", id);
+
+        let result = InstrumentModelEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<InstrumentModel>, DomainError> {
+        debug!("Finding instrument model by name: {}", name);
+
+        let result = InstrumentModelEntity::find()
+            .filter(instrument_model::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<InstrumentModel>, DomainError> {
+        debug!("Listing instrument models");
+
+        let results = InstrumentModelEntity::find()
+            .order_by_asc(instrument_model::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, model))]
+    async fn save(&self, model: &InstrumentModel) -> Result<EntityId, DomainError> {
+        debug!("Saving instrument model: {}", model.name);
+
+        let active_model: instrument_model::ActiveModel = model.into();
+
+        let saved = if model.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting instrument model: {}", id);
+
+        InstrumentModelEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+//! SeaORM implementation of ItemLocationRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, RecordedLocation, StorableItem, StorableType};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ItemLocationRepository;
+
+use crate::persistence::entities::item_location::{self, Entity as ItemLocationEntity};
+use crate::persistence::entities::storage_box::storable_type_code;
+use crate::persistence::entities::{library, library_aliquot, pool, sample};
+
+/// SeaORM-based source of the locations items record, read from the
+/// item_location table that saving a box keeps up to date.
+#[derive(Debug, Clone)]
+pub struct SeaOrmItemLocationRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmItemLocationRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Lists the IDs of every item of the given type.
+    async fn item_ids(&self, item_type: StorableType) -> Result<Vec<EntityId>, DomainError> {
+        let ids = match item_type {
+            StorableType::Sample => {
+                sample::Entity::find()
+                    .select_only()
+                    .column(sample::Column::Id)
+                    .order_by_asc(sample::Column::Id)
+                    .into_tuple()
+                    .all(&self.db)
+                    .await
+            }
+            StorableType::Library => {
+                library::Entity::find()
+                    .select_only()
+                    .column(library::Column::Id)
+                    .order_by_asc(library::Column::Id)
+                    .into_tuple()
+                    .all(&self.db)
+                    .await
+            }
+            StorableType::LibraryAliquot => {
+                library_aliquot::Entity::find()
+                    .select_only()
+                    .column(library_aliquot::Column::Id)
+                    .order_by_asc(library_aliquot::Column::Id)
+                    .into_tuple()
+                    .all(&self.db)
+                    .await
+            }
+            StorableType::Pool => {
+                pool::Entity::find()
+                    .select_only()
+                    .column(pool::Column::Id)
+                    .order_by_asc(pool::Column::Id)
+                    .into_tuple()
+                    .all(&self.db)
+                    .await
+            }
+        };
+
+        ids.map_err(|e| DomainError::Validation(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl ItemLocationRepository for SeaOrmItemLocationRepository {
+    #[instrument(skip(self))]
+    async fn list_recorded(
+        &self,
+        item_type: StorableType,
+    ) -> Result<Vec<RecordedLocation>, DomainError> {
+        debug!("Listing recorded locations of {} items", item_type);
+
+        let mut locations: HashMap<EntityId, item_location::Model> = ItemLocationEntity::find()
+            .filter(item_location::Column::ItemType.eq(storable_type_code(item_type)))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .into_iter()
+            .map(|location| (location.item_id, location))
+            .collect();
+
+        Ok(self
+            .item_ids(item_type)
+            .await?
+            .into_iter()
+            .map(|id| {
+                let location = locations.remove(&id);
+                RecordedLocation {
+                    item: StorableItem::new(item_type, id),
+                    box_id: location.as_ref().map(|l| l.box_id),
+                    position: location
+                        .as_ref()
+                        .and_then(item_location::Model::box_position),
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,129 @@
+//! SeaORM implementation of LabelPrintRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    SqlErr,
+};
+use tracing::{debug, instrument};
+
+use chrono::{DateTime, Utc};
+use miso_domain::entities::{EntityId, LabelPrint, LabelUsage};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::LabelPrintRepository;
+
+use crate::persistence::entities::label_print::{self, Entity as LabelPrintEntity};
+use crate::persistence::entities::label_usage::{self, Entity as LabelUsageEntity};
+
+/// SeaORM-based label print repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmLabelPrintRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmLabelPrintRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl LabelPrintRepository for SeaOrmLabelPrintRepository {
+    #[instrument(skip(self))]
+    async fn find_last(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+        template: &str,
+    ) -> Result<Option<LabelPrint>, DomainError> {
+        debug!("Finding last {} label of {} {}", template, entity_type, entity_id);
+
+        let result = LabelPrintEntity::find()
+            .filter(label_print::Column::EntityType.eq(entity_type))
+            .filter(label_print::Column::EntityId.eq(entity_id))
+            .filter(label_print::Column::Template.eq(template))
+            .order_by_desc(label_print::Column::Copy)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn list_for_entity(
+        &self,
+        entity_type: &str,
+        entity_id: EntityId,
+    ) -> Result<Vec<LabelPrint>, DomainError> {
+        debug!("Listing labels of {} {}", entity_type, entity_id);
+
+        let results = LabelPrintEntity::find()
+            .filter(label_print::Column::EntityType.eq(entity_type))
+            .filter(label_print::Column::EntityId.eq(entity_id))
+            .order_by_asc(label_print::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, print), fields(template = %print.template, copy = print.copy))]
+    async fn save(&self, print: &LabelPrint) -> Result<EntityId, DomainError> {
+        debug!("Recording label of {} {}", print.entity_type, print.entity_id);
+
+        let saved = label_print::ActiveModel::from(print)
+            .insert(&self.db)
+            .await
+            .map_err(|e| match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                    entity_type: "LabelPrint".to_string(),
+                    field: format!("{} {} {} label", print.entity_type, print.entity_id, print.template),
+                    value: format!("copy {}", print.copy),
+                },
+                _ => DomainError::Validation(e.to_string()),
+            })?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting label print: {}", id);
+
+        LabelPrintEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, usage), fields(printer = %usage.printer, labels = usage.labels))]
+    async fn save_usage(&self, usage: &LabelUsage) -> Result<EntityId, DomainError> {
+        debug!("Recording {} labels used", usage.template);
+
+        let saved = label_usage::ActiveModel::from(usage)
+            .insert(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_usage(&self, since: DateTime<Utc>) -> Result<Vec<LabelUsage>, DomainError> {
+        debug!("Listing label usage since {}", since);
+
+        let results = LabelUsageEntity::find()
+            .filter(label_usage::Column::PrintedAt.gte(since))
+            .order_by_asc(label_usage::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+}
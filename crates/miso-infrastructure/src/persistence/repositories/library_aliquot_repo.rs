@@ -0,0 +1,108 @@
+//! SeaORM implementation of LibraryAliquotRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Library, LibraryAliquot};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::LibraryAliquotRepository;
+
+use crate::persistence::entities::library;
+use crate::persistence::entities::library_aliquot::{self, Entity as LibraryAliquotEntity};
+
+/// SeaORM-based library aliquot repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmLibraryAliquotRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmLibraryAliquotRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl LibraryAliquotRepository for SeaOrmLibraryAliquotRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<LibraryAliquot>, DomainError> {
+        debug!("Finding library aliquot by ID: {}", id);
+
+        let result = LibraryAliquotEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_barcode(
+        &self,
+        barcode: &str,
+    ) -> Result<Option<LibraryAliquot>, DomainError> {
+        debug!("Finding library aliquot by barcode: {}", barcode);
+
+        let result = LibraryAliquotEntity::find()
+            .filter(library_aliquot::Column::Barcode.eq(barcode))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_library(
+        &self,
+        library_id: EntityId,
+    ) -> Result<Vec<LibraryAliquot>, DomainError> {
+        debug!("Finding aliquots of library: {}", library_id);
+
+        let results = LibraryAliquotEntity::find()
+            .filter(library_aliquot::Column::LibraryId.eq(library_id))
+            .order_by_asc(library_aliquot::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, aliquot, library), fields(library = library.id))]
+    async fn create(
+        &self,
+        aliquot: &LibraryAliquot,
+        library: &Library,
+    ) -> Result<EntityId, DomainError> {
+        debug!("Creating aliquot of library: {}", library.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Dropping the transaction on error rolls it back
+        let saved = library_aliquot::ActiveModel::from(aliquot)
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        library::ActiveModel::from(library)
+            .update(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+}
@@ -0,0 +1,245 @@
+//! SeaORM implementation of LibraryRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Library, Sample};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{LibraryRepository, QueryOptions};
+
+use crate::persistence::entities::library::{self, Entity as LibraryEntity};
+use crate::persistence::entities::sample::{self, Entity as SampleEntity};
+
+/// SeaORM-based library repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmLibraryRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmLibraryRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<LibraryEntity>,
+    ) -> Result<Option<Library>, DomainError> {
+        let result = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    async fn find_all(
+        &self,
+        query: sea_orm::Select<LibraryEntity>,
+    ) -> Result<Vec<Library>, DomainError> {
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+#[async_trait]
+impl LibraryRepository for SeaOrmLibraryRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Library>, DomainError> {
+        debug!("Finding library by ID: {}", id);
+
+        self.find_one(LibraryEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Library>, DomainError> {
+        debug!("Finding library by barcode: {}", barcode);
+
+        self.find_one(LibraryEntity::find().filter(library::Column::Barcode.eq(barcode)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<Library>, DomainError> {
+        debug!("Finding library by name: {}", name);
+
+        self.find_one(LibraryEntity::find().filter(library::Column::Name.eq(name)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_sample(&self, sample_id: EntityId) -> Result<Vec<Library>, DomainError> {
+        debug!("Finding libraries by sample: {}", sample_id);
+
+        self.find_all(
+            LibraryEntity::find()
+                .filter(library::Column::SampleId.eq(sample_id))
+                .order_by_asc(library::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_project(
+        &self,
+        project_id: EntityId,
+        options: QueryOptions,
+    ) -> Result<Vec<Library>, DomainError> {
+        debug!("Finding libraries by project: {}", project_id);
+
+        let mut query = LibraryEntity::find().filter(library::Column::ProjectId.eq(project_id));
+
+        if let Some(sort_by) = &options.sort_by {
+            let order = if options.ascending.unwrap_or(true) {
+                sea_orm::Order::Asc
+            } else {
+                sea_orm::Order::Desc
+            };
+
+            query = match sort_by.as_str() {
+                "name" => query.order_by(library::Column::Name, order),
+                "barcode" => query.order_by(library::Column::Barcode, order),
+                "created_at" => query.order_by(library::Column::CreatedAt, order),
+                _ => query.order_by(library::Column::Id, order),
+            };
+        }
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        self.find_all(query).await
+    }
+
+    #[instrument(skip(self, ids), fields(count = ids.len()))]
+    async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Library>, DomainError> {
+        debug!("Finding {} libraries by ID", ids.len());
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.find_all(LibraryEntity::find().filter(library::Column::Id.is_in(ids.iter().copied())))
+            .await
+    }
+
+    #[instrument(skip(self, library))]
+    async fn save(&self, library: &Library) -> Result<EntityId, DomainError> {
+        debug!("Saving library: {}", library.name);
+
+        let active_model: library::ActiveModel = library.into();
+
+        let saved = if library.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self, libraries), fields(count = libraries.len()))]
+    async fn insert_all(&self, libraries: &[Library]) -> Result<Vec<EntityId>, DomainError> {
+        debug!("Inserting {} libraries", libraries.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut ids = Vec::with_capacity(libraries.len());
+        for library in libraries {
+            let mut active_model: library::ActiveModel = library.into();
+            active_model.id = sea_orm::ActiveValue::NotSet;
+
+            // Dropping the transaction on error rolls it back
+            let saved = active_model
+                .insert(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+            ids.push(saved.id);
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(ids)
+    }
+
+    #[instrument(skip(self, library, sample))]
+    async fn create_from_sample(
+        &self,
+        library: &Library,
+        sample: &Sample,
+    ) -> Result<EntityId, DomainError> {
+        debug!("Creating library {} from sample: {}", library.name, sample.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut sample_model: sample::ActiveModel = sample.into();
+        sample_model.id = ActiveValue::NotSet;
+        sample_model.created_by = ActiveValue::NotSet;
+        sample_model.created_at = ActiveValue::NotSet;
+        sample_model.version = ActiveValue::Set(sample.version + 1);
+
+        // Dropping the transaction on error rolls it back
+        let result = SampleEntity::update_many()
+            .set(sample_model)
+            .filter(sample::Column::Id.eq(sample.id))
+            .filter(sample::Column::Version.eq(sample.version))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if result.rows_affected == 0 {
+            return Err(DomainError::ConcurrentModification {
+                entity_type: "Sample".to_string(),
+                id: sample.id.to_string(),
+            });
+        }
+
+        let mut library_model: library::ActiveModel = library.into();
+        library_model.id = ActiveValue::NotSet;
+        let saved = library_model
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting library: {}", id);
+
+        LibraryEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
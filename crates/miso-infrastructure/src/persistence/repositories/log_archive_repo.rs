@@ -0,0 +1,274 @@
+//! SeaORM implementation of LogArchiveRepository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{ArchivedLog, ArchivedRow, EntityId, LogArchive, LogTableSize};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::LogArchiveRepository;
+
+use crate::persistence::entities::audit_log::{self, Entity as AuditLogEntity};
+use crate::persistence::entities::change_log::{self, Entity as ChangeLogEntity};
+use crate::persistence::entities::log_archive::{self, Entity as LogArchiveEntity};
+use crate::persistence::entities::storage_audit::{self, Entity as StorageAuditEntity};
+
+/// SeaORM-based log archive repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmLogArchiveRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmLogArchiveRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Counts the rows of a log's table.
+    async fn count_rows(&self, log: ArchivedLog) -> Result<u64, DomainError> {
+        match log {
+            ArchivedLog::AuditLog => AuditLogEntity::find().count(&self.db).await,
+            ArchivedLog::ChangeLog => ChangeLogEntity::find().count(&self.db).await,
+            ArchivedLog::StorageAudit => StorageAuditEntity::find().count(&self.db).await,
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))
+    }
+}
+
+/// Serializes a row as written to an archive file.
+fn archived_row<M: serde::Serialize>(
+    id: EntityId,
+    hash: String,
+    model: &M,
+) -> Result<ArchivedRow, DomainError> {
+    let json = serde_json::to_string(model).map_err(|e| DomainError::Validation(e.to_string()))?;
+    Ok(ArchivedRow { id, hash, json })
+}
+
+/// Parses archived rows back into models of their table.
+fn parse_rows<M: DeserializeOwned>(rows: &[String]) -> Result<Vec<M>, DomainError> {
+    rows.iter()
+        .map(|row| {
+            serde_json::from_str(row)
+                .map_err(|e| DomainError::Validation(format!("Unreadable archived row: {}", e)))
+        })
+        .collect()
+}
+
+/// Puts archived rows back into a log's table, with their original IDs.
+async fn insert_rows<C: ConnectionTrait>(
+    db: &C,
+    log: ArchivedLog,
+    rows: &[String],
+) -> Result<(), DomainError> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    match log {
+        ArchivedLog::AuditLog => {
+            let models: Vec<audit_log::Model> = parse_rows(rows)?;
+            AuditLogEntity::insert_many(models.into_iter().map(IntoActiveModel::into_active_model))
+                .exec(db)
+                .await
+                .map(|_| ())
+        }
+        ArchivedLog::ChangeLog => {
+            let models: Vec<change_log::Model> = parse_rows(rows)?;
+            ChangeLogEntity::insert_many(models.into_iter().map(IntoActiveModel::into_active_model))
+                .exec(db)
+                .await
+                .map(|_| ())
+        }
+        ArchivedLog::StorageAudit => {
+            let models: Vec<storage_audit::Model> = parse_rows(rows)?;
+            StorageAuditEntity::insert_many(
+                models.into_iter().map(IntoActiveModel::into_active_model),
+            )
+            .exec(db)
+            .await
+            .map(|_| ())
+        }
+    }
+    .map_err(|e| DomainError::Validation(e.to_string()))
+}
+
+#[async_trait]
+impl LogArchiveRepository for SeaOrmLogArchiveRepository {
+    #[instrument(skip(self))]
+    async fn list_expired(
+        &self,
+        log: ArchivedLog,
+        before: DateTime<Utc>,
+        limit: u64,
+    ) -> Result<Vec<ArchivedRow>, DomainError> {
+        debug!("Listing {} rows from before {}", log, before);
+
+        // Chained logs are archived oldest first with no gaps, so stop at
+        // the first row still within its retention period
+        match log {
+            ArchivedLog::AuditLog => AuditLogEntity::find()
+                .order_by_asc(audit_log::Column::Id)
+                .limit(limit)
+                .all(&self.db)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?
+                .into_iter()
+                .take_while(|row| row.changed_at < before)
+                .map(|row| archived_row(row.id, row.hash.clone(), &row))
+                .collect(),
+            ArchivedLog::ChangeLog => ChangeLogEntity::find()
+                .order_by_asc(change_log::Column::Id)
+                .limit(limit)
+                .all(&self.db)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?
+                .into_iter()
+                .take_while(|row| row.changed_at < before)
+                .map(|row| archived_row(row.id, row.hash.clone(), &row))
+                .collect(),
+            ArchivedLog::StorageAudit => StorageAuditEntity::find()
+                .filter(storage_audit::Column::Status.eq("closed"))
+                .filter(storage_audit::Column::ClosedAt.lt(before))
+                .order_by_asc(storage_audit::Column::Id)
+                .limit(limit)
+                .all(&self.db)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?
+                .into_iter()
+                .map(|row| archived_row(row.id, String::new(), &row))
+                .collect(),
+        }
+    }
+
+    #[instrument(skip(self, archive, ids), fields(log = %archive.log, count = ids.len()))]
+    async fn archive(
+        &self,
+        archive: &LogArchive,
+        ids: &[EntityId],
+    ) -> Result<EntityId, DomainError> {
+        debug!("Archiving {} {} rows to {}", ids.len(), archive.log, archive.file);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let saved = log_archive::ActiveModel::from(archive)
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let ids = ids.to_vec();
+        match archive.log {
+            ArchivedLog::AuditLog => {
+                AuditLogEntity::delete_many()
+                    .filter(audit_log::Column::Id.is_in(ids))
+                    .exec(&txn)
+                    .await
+            }
+            ArchivedLog::ChangeLog => {
+                ChangeLogEntity::delete_many()
+                    .filter(change_log::Column::Id.is_in(ids))
+                    .exec(&txn)
+                    .await
+            }
+            ArchivedLog::StorageAudit => {
+                StorageAuditEntity::delete_many()
+                    .filter(storage_audit::Column::Id.is_in(ids))
+                    .exec(&txn)
+                    .await
+            }
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self, archive, rows), fields(archive = archive.id, count = rows.len()))]
+    async fn restore(&self, archive: &LogArchive, rows: &[String]) -> Result<(), DomainError> {
+        debug!("Restoring {} rows from archive {}", rows.len(), archive.id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        insert_rows(&txn, archive.log, rows).await?;
+
+        log_archive::ActiveModel {
+            id: ActiveValue::Unchanged(archive.id),
+            restored_at: ActiveValue::Set(Some(Utc::now())),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<LogArchive>, DomainError> {
+        debug!("Finding log archive by ID: {}", id);
+
+        let result = LogArchiveEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, log: ArchivedLog) -> Result<Vec<LogArchive>, DomainError> {
+        debug!("Listing {} archives", log);
+
+        let results = LogArchiveEntity::find()
+            .filter(log_archive::Column::Log.eq(log.as_str()))
+            .order_by_desc(log_archive::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn sizes(&self) -> Result<Vec<LogTableSize>, DomainError> {
+        debug!("Measuring log tables");
+
+        let archives = LogArchiveEntity::find()
+            .filter(log_archive::Column::RestoredAt.is_null())
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut sizes = Vec::with_capacity(ArchivedLog::ALL.len());
+        for log in ArchivedLog::ALL {
+            let archived = archives.iter().filter(|a| a.log == log.as_str());
+            sizes.push(LogTableSize {
+                log,
+                rows: self.count_rows(log).await?,
+                archived_rows: archived.clone().map(|a| a.row_count as u64).sum(),
+                archives: archived.count() as u64,
+            });
+        }
+        Ok(sizes)
+    }
+}
@@ -0,0 +1,90 @@
+//! SeaORM implementation of MaintenanceWindowRepository.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, MaintenanceWindow};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::MaintenanceWindowRepository;
+
+use crate::persistence::entities::maintenance_window::{
+    self, Entity as MaintenanceWindowEntity,
+};
+
+/// SeaORM-based maintenance window repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmMaintenanceWindowRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmMaintenanceWindowRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MaintenanceWindowRepository for SeaOrmMaintenanceWindowRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<MaintenanceWindow>, DomainError> {
+        debug!("Finding maintenance window by ID: {}", id);
+
+        let result = MaintenanceWindowEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_upcoming(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<MaintenanceWindow>, DomainError> {
+        debug!("Finding maintenance windows ending after {}", now);
+
+        let results = MaintenanceWindowEntity::find()
+            .filter(maintenance_window::Column::EndsAt.gt(now))
+            .order_by_asc(maintenance_window::Column::StartsAt)
+            .order_by_asc(maintenance_window::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, window))]
+    async fn save(&self, window: &MaintenanceWindow) -> Result<EntityId, DomainError> {
+        debug!("Saving maintenance window: {}", window.reason);
+
+        let active_model: maintenance_window::ActiveModel = window.into();
+
+        let model = if window.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting maintenance window: {}", id);
+
+        MaintenanceWindowEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
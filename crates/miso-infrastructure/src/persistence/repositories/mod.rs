@@ -2,9 +2,105 @@
 //!
 //! These implement the domain repository traits defined in miso-domain.
 
+mod api_key_repo;
+mod api_usage_repo;
+mod archive_rule_repo;
+mod attachment_repo;
+mod attribute_definition_repo;
+mod audit_log_repo;
+mod auto_archival_repo;
+mod change_log_repo;
+mod consent_repo;
+mod data_location_repo;
+mod delivery_repo;
+mod delivery_target_repo;
+mod erasure_repo;
+mod event_store_repo;
+mod export_template_repo;
+mod extraction_batch_repo;
+mod index_set_repo;
+mod instrument_event_repo;
+mod instrument_model_repo;
+mod item_location_repo;
+mod label_print_repo;
+mod library_aliquot_repo;
+mod library_repo;
+mod log_archive_repo;
+mod maintenance_window_repo;
+mod panel_repo;
+mod pool_repo;
+mod possible_duplicate_repo;
+mod project_activity_repo;
+mod project_member_repo;
 mod project_repo;
+mod qc_report_repo;
+mod qc_result_repo;
+mod reconciliation_report_repo;
+mod reagent_lot_repo;
+mod reference_genome_repo;
+mod requisition_repo;
+mod run_metrics_repo;
+mod run_repo;
+mod sample_composition_repo;
+mod sample_list_repo;
 mod sample_repo;
+mod sequencer_repo;
+mod storage_audit_repo;
+mod storage_box_repo;
+mod storage_unit_repo;
+mod subproject_repo;
+mod transfer_repo;
+mod user_repo;
+mod workset_repo;
 
+pub use api_key_repo::SeaOrmApiKeyRepository;
+pub use api_usage_repo::SeaOrmApiUsageRepository;
+pub use archive_rule_repo::SeaOrmArchiveRuleRepository;
+pub use attachment_repo::SeaOrmAttachmentRepository;
+pub use attribute_definition_repo::SeaOrmAttributeDefinitionRepository;
+pub use audit_log_repo::SeaOrmAuditLogRepository;
+pub use auto_archival_repo::SeaOrmAutoArchivalRepository;
+pub use change_log_repo::SeaOrmChangeLogRepository;
+pub use consent_repo::SeaOrmConsentRepository;
+pub use data_location_repo::SeaOrmDataLocationRepository;
+pub use delivery_repo::SeaOrmDeliveryRepository;
+pub use delivery_target_repo::SeaOrmDeliveryTargetRepository;
+pub use erasure_repo::SeaOrmErasureRepository;
+pub use event_store_repo::SeaOrmEventStoreRepository;
+pub use export_template_repo::SeaOrmExportTemplateRepository;
+pub use extraction_batch_repo::SeaOrmExtractionBatchRepository;
+pub use index_set_repo::SeaOrmIndexSetRepository;
+pub use instrument_event_repo::SeaOrmInstrumentEventRepository;
+pub use instrument_model_repo::SeaOrmInstrumentModelRepository;
+pub use item_location_repo::SeaOrmItemLocationRepository;
+pub use label_print_repo::SeaOrmLabelPrintRepository;
+pub use library_aliquot_repo::SeaOrmLibraryAliquotRepository;
+pub use library_repo::SeaOrmLibraryRepository;
+pub use log_archive_repo::SeaOrmLogArchiveRepository;
+pub use maintenance_window_repo::SeaOrmMaintenanceWindowRepository;
+pub use panel_repo::SeaOrmPanelRepository;
+pub use pool_repo::SeaOrmPoolRepository;
+pub use possible_duplicate_repo::SeaOrmPossibleDuplicateRepository;
+pub use project_activity_repo::SeaOrmProjectActivityRepository;
+pub use project_member_repo::SeaOrmProjectMemberRepository;
 pub use project_repo::SeaOrmProjectRepository;
+pub use qc_report_repo::SeaOrmQcReportRepository;
+pub use qc_result_repo::SeaOrmQcRecordRepository;
+pub use reconciliation_report_repo::SeaOrmReconciliationReportRepository;
+pub use reagent_lot_repo::SeaOrmReagentLotRepository;
+pub use reference_genome_repo::SeaOrmReferenceGenomeRepository;
+pub use requisition_repo::SeaOrmRequisitionRepository;
+pub use run_metrics_repo::SeaOrmRunMetricsRepository;
+pub use run_repo::SeaOrmRunRepository;
+pub use sample_composition_repo::SeaOrmSampleCompositionRepository;
+pub use sample_list_repo::SeaOrmSampleListRepository;
 pub use sample_repo::SeaOrmSampleRepository;
+pub use sequencer_repo::SeaOrmSequencerRepository;
+pub use storage_audit_repo::SeaOrmStorageAuditRepository;
+pub use storage_box_repo::SeaOrmStorageBoxRepository;
+pub use storage_unit_repo::SeaOrmStorageUnitRepository;
+pub use subproject_repo::SeaOrmSubprojectRepository;
+pub use transfer_repo::SeaOrmTransferRepository;
+pub use user_repo::SeaOrmUserRepository;
+pub use workset_repo::SeaOrmWorksetRepository;
 
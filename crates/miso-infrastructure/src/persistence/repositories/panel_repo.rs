@@ -0,0 +1,104 @@
+//! SeaORM implementation of PanelRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Panel};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::PanelRepository;
+
+use crate::persistence::entities::panel::{self, Entity as PanelEntity};
+
+/// SeaORM-based targeted panel repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmPanelRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmPanelRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn find_all(
+        &self,
+        query: sea_orm::Select<PanelEntity>,
+    ) -> Result<Vec<Panel>, DomainError> {
+        let results = query
+            .order_by_asc(panel::Column::Name)
+            .order_by_asc(panel::Column::Version)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+#[async_trait]
+impl PanelRepository for SeaOrmPanelRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Panel>, DomainError> {
+        debug!("Finding panel by ID: {}", id);
+
+        let result = PanelEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Panel>, DomainError> {
+        debug!("Finding {} panels by ID", ids.len());
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.find_all(PanelEntity::find().filter(panel::Column::Id.is_in(ids.iter().copied())))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_latest(&self, name: &str) -> Result<Option<Panel>, DomainError> {
+        debug!("Finding latest version of panel: {}", name);
+
+        let result = PanelEntity::find()
+            .filter(panel::Column::Name.eq(name))
+            .order_by_desc(panel::Column::Version)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<Panel>, DomainError> {
+        debug!("Listing panels");
+
+        self.find_all(PanelEntity::find()).await
+    }
+
+    #[instrument(skip(self, panel))]
+    async fn save(&self, panel: &Panel) -> Result<EntityId, DomainError> {
+        debug!("Saving panel: {}", panel.identifier());
+
+        let active_model: panel::ActiveModel = panel.into();
+
+        let model = if panel.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,223 @@
+//! SeaORM implementation of PoolRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Pool};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{PoolRepository, QueryOptions};
+
+use crate::persistence::entities::pool::{self, Entity as PoolEntity};
+use crate::persistence::entities::pool_element::{self, Entity as PoolElementEntity};
+
+/// SeaORM-based pool repository.
+///
+/// A pool's elements live in the pool_element table and are always read
+/// and written together with the pool.
+#[derive(Debug, Clone)]
+pub struct SeaOrmPoolRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmPoolRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the elements of `models` in one query and assembles the pools.
+    async fn with_elements(&self, models: Vec<pool::Model>) -> Result<Vec<Pool>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut elements: HashMap<i32, Vec<pool_element::Model>> = HashMap::new();
+        for element in PoolElementEntity::find()
+            .filter(pool_element::Column::PoolId.is_in(ids))
+            .order_by_asc(pool_element::Column::LibraryAliquotId)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            elements.entry(element.pool_id).or_default().push(element);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let pool_elements = elements.remove(&m.id).unwrap_or_default();
+                m.into_domain(pool_elements)
+            })
+            .collect())
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<PoolEntity>,
+    ) -> Result<Option<Pool>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self.with_elements(model.into_iter().collect()).await?.pop())
+    }
+
+    /// Replaces the stored elements of the pool with `pool_id`.
+    async fn replace_elements<C: ConnectionTrait>(
+        conn: &C,
+        pool_id: EntityId,
+        pool: &Pool,
+    ) -> Result<(), DomainError> {
+        PoolElementEntity::delete_many()
+            .filter(pool_element::Column::PoolId.eq(pool_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if pool.elements.is_empty() {
+            return Ok(());
+        }
+
+        PoolElementEntity::insert_many(
+            pool.elements
+                .iter()
+                .map(|e| pool_element::ActiveModel::new(pool_id, e)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PoolRepository for SeaOrmPoolRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Pool>, DomainError> {
+        debug!("Finding pool by ID: {}", id);
+
+        self.find_one(PoolEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Pool>, DomainError> {
+        debug!("Finding pool by barcode: {}", barcode);
+
+        self.find_one(PoolEntity::find().filter(pool::Column::Barcode.eq(barcode)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Pool>, DomainError> {
+        debug!("Listing pools");
+
+        let mut query = PoolEntity::find();
+
+        if let Some(sort_by) = &options.sort_by {
+            let order = if options.ascending.unwrap_or(true) {
+                sea_orm::Order::Asc
+            } else {
+                sea_orm::Order::Desc
+            };
+
+            query = match sort_by.as_str() {
+                "name" => query.order_by(pool::Column::Name, order),
+                "barcode" => query.order_by(pool::Column::Barcode, order),
+                "created_at" => query.order_by(pool::Column::CreatedAt, order),
+                _ => query.order_by(pool::Column::Id, order),
+            };
+        }
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_elements(models).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_library(&self, library_id: EntityId) -> Result<Vec<Pool>, DomainError> {
+        debug!("Finding pools containing library: {}", library_id);
+
+        let pool_ids: Vec<i32> = PoolElementEntity::find()
+            .select_only()
+            .column(pool_element::Column::PoolId)
+            .distinct()
+            .filter(pool_element::Column::LibraryId.eq(library_id))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if pool_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let models = PoolEntity::find()
+            .filter(pool::Column::Id.is_in(pool_ids))
+            .order_by_asc(pool::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_elements(models).await
+    }
+
+    #[instrument(skip(self, pool), fields(elements = pool.elements.len()))]
+    async fn save(&self, pool: &Pool) -> Result<EntityId, DomainError> {
+        debug!("Saving pool: {}", pool.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: pool::ActiveModel = pool.into();
+        let saved = if pool.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Self::replace_elements(&txn, saved.id, pool).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting pool: {}", id);
+
+        // Elements are removed with the pool by the foreign key cascade.
+        PoolEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! SeaORM implementation of PossibleDuplicateRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, PossibleDuplicate};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::PossibleDuplicateRepository;
+
+use crate::persistence::entities::possible_duplicate::{self, Entity as PossibleDuplicateEntity};
+
+/// SeaORM-based possible duplicate repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmPossibleDuplicateRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmPossibleDuplicateRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PossibleDuplicateRepository for SeaOrmPossibleDuplicateRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<PossibleDuplicate>, DomainError> {
+        debug!("Finding possible duplicate by ID: {}", id);
+
+        let result = PossibleDuplicateEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list_unresolved(&self) -> Result<Vec<PossibleDuplicate>, DomainError> {
+        debug!("Listing unresolved possible duplicates");
+
+        let results = PossibleDuplicateEntity::find()
+            .filter(possible_duplicate::Column::Resolution.is_null())
+            .order_by_asc(possible_duplicate::Column::ConfirmedAt)
+            .order_by_asc(possible_duplicate::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_identity(
+        &self,
+        identity_id: EntityId,
+    ) -> Result<Vec<PossibleDuplicate>, DomainError> {
+        debug!("Finding possible duplicates involving identity {}", identity_id);
+
+        let results = PossibleDuplicateEntity::find()
+            .filter(
+                Condition::any()
+                    .add(possible_duplicate::Column::IdentityId.eq(identity_id))
+                    .add(possible_duplicate::Column::MatchId.eq(identity_id)),
+            )
+            .order_by_asc(possible_duplicate::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, duplicate))]
+    async fn save(&self, duplicate: &PossibleDuplicate) -> Result<EntityId, DomainError> {
+        debug!(
+            "Saving possible duplicate of {} for {}",
+            duplicate.match_id, duplicate.identity_id
+        );
+
+        let active_model: possible_duplicate::ActiveModel = duplicate.into();
+
+        let model = if duplicate.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,182 @@
+//! SeaORM implementation of ProjectActivityRepository.
+//!
+//! Activity is read from the sample table (creations) and the change log
+//! (changes to the project's samples), each source paged with the same
+//! cursor and then merged.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{ActivityCursor, ActivityItem, ActivityKind, EntityId};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ProjectActivityRepository;
+use miso_domain::services::ActivityFeed;
+
+use crate::persistence::entities::change_log::{self, Entity as ChangeLogEntity};
+use crate::persistence::entities::sample::{self, Entity as SampleEntity};
+
+/// SeaORM-based project activity repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmProjectActivityRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmProjectActivityRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn sample_creations(
+        &self,
+        project_id: EntityId,
+        before: Option<ActivityCursor>,
+        limit: u64,
+    ) -> Result<Vec<ActivityItem>, DomainError> {
+        let mut query = SampleEntity::find().filter(sample::Column::ProjectId.eq(project_id));
+        if let Some(cursor) = before {
+            query = query.filter(before_condition(
+                sample::Column::CreatedAt,
+                sample::Column::Id,
+                ActivityKind::SampleCreated,
+                cursor,
+            ));
+        }
+
+        let samples = query
+            .order_by_desc(sample::Column::CreatedAt)
+            .order_by_desc(sample::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(samples
+            .into_iter()
+            .map(|s| ActivityItem {
+                kind: ActivityKind::SampleCreated,
+                source_id: s.id,
+                occurred_at: s.created_at,
+                entity_type: "Sample".to_string(),
+                entity_id: s.id,
+                summary: format!("{} created", s.name),
+                detail: None,
+                actor: s.created_by,
+            })
+            .collect())
+    }
+
+    async fn sample_changes(
+        &self,
+        project_id: EntityId,
+        before: Option<ActivityCursor>,
+        limit: u64,
+    ) -> Result<Vec<ActivityItem>, DomainError> {
+        let project_samples = Query::select()
+            .column(sample::Column::Id)
+            .from(SampleEntity)
+            .and_where(Expr::col(sample::Column::ProjectId).eq(project_id))
+            .to_owned();
+
+        let mut query = ChangeLogEntity::find()
+            .filter(change_log::Column::EntityType.eq("Sample"))
+            .filter(change_log::Column::EntityId.in_subquery(project_samples));
+        if let Some(cursor) = before {
+            query = query.filter(before_condition(
+                change_log::Column::ChangedAt,
+                change_log::Column::Id,
+                ActivityKind::Change,
+                cursor,
+            ));
+        }
+
+        let entries = query
+            .order_by_desc(change_log::Column::ChangedAt)
+            .order_by_desc(change_log::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut sample_ids: Vec<EntityId> = entries.iter().map(|e| e.entity_id).collect();
+        sample_ids.sort_unstable();
+        sample_ids.dedup();
+        let names: HashMap<EntityId, String> = SampleEntity::find()
+            .filter(sample::Column::Id.is_in(sample_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .into_iter()
+            .map(|s| (s.id, s.name))
+            .collect();
+
+        Ok(entries
+            .into_iter()
+            .map(|e| ActivityItem {
+                kind: ActivityKind::Change,
+                source_id: e.id,
+                occurred_at: e.changed_at,
+                entity_type: e.entity_type,
+                entity_id: e.entity_id,
+                summary: match names.get(&e.entity_id) {
+                    Some(name) => format!("{}: {}", name, e.summary),
+                    None => e.summary,
+                },
+                detail: e.reason,
+                actor: e.changed_by,
+            })
+            .collect())
+    }
+}
+
+/// Selects a source's rows that sort strictly before `cursor`.
+///
+/// Rows at the cursor's instant fall before it if their kind sorts lower,
+/// or if they share its kind and have a lower ID.
+fn before_condition<C: ColumnTrait>(
+    time: C,
+    id: C,
+    kind: ActivityKind,
+    cursor: ActivityCursor,
+) -> Condition {
+    let at = cursor.occurred_at;
+    let same_instant = if kind < cursor.kind {
+        Some(Condition::all().add(time.eq(at)))
+    } else if kind == cursor.kind {
+        Some(Condition::all().add(time.eq(at)).add(id.lt(cursor.source_id)))
+    } else {
+        None
+    };
+
+    let mut condition = Condition::any().add(time.lt(at));
+    if let Some(same_instant) = same_instant {
+        condition = condition.add(same_instant);
+    }
+    condition
+}
+
+#[async_trait]
+impl ProjectActivityRepository for SeaOrmProjectActivityRepository {
+    #[instrument(skip(self))]
+    async fn list_for_project(
+        &self,
+        project_id: EntityId,
+        before: Option<ActivityCursor>,
+        limit: u64,
+    ) -> Result<Vec<ActivityItem>, DomainError> {
+        debug!("Listing activity for project: {}", project_id);
+
+        let sources = vec![
+            self.sample_creations(project_id, before, limit).await?,
+            self.sample_changes(project_id, before, limit).await?,
+        ];
+
+        Ok(ActivityFeed::merge(sources, before, limit as usize))
+    }
+}
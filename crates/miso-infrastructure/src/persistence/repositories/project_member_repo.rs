@@ -0,0 +1,94 @@
+//! SeaORM implementation of ProjectMemberRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ProjectMember};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ProjectMemberRepository;
+
+use crate::persistence::entities::project_member::{self, Entity as ProjectMemberEntity};
+
+/// SeaORM-based project member repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmProjectMemberRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmProjectMemberRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ProjectMemberRepository for SeaOrmProjectMemberRepository {
+    #[instrument(skip(self))]
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<ProjectMember>, DomainError> {
+        debug!("Finding members of project {}", project_id);
+
+        let models = ProjectMemberEntity::find()
+            .filter(project_member::Column::ProjectId.eq(project_id))
+            .order_by_asc(project_member::Column::AddedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_projects(&self, user_id: EntityId) -> Result<Vec<EntityId>, DomainError> {
+        debug!("Finding projects of user {}", user_id);
+
+        ProjectMemberEntity::find()
+            .select_only()
+            .column(project_member::Column::ProjectId)
+            .filter(project_member::Column::UserId.eq(user_id))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))
+    }
+
+    #[instrument(skip(self, member), fields(project_id = member.project_id, user_id = member.user_id))]
+    async fn add(&self, member: &ProjectMember) -> Result<(), DomainError> {
+        debug!("Adding user {} to project {}", member.user_id, member.project_id);
+
+        let existing = ProjectMemberEntity::find_by_id((member.project_id, member.user_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if existing.is_some() {
+            return Err(DomainError::Duplicate {
+                entity_type: "ProjectMember".to_string(),
+                field: "user_id".to_string(),
+                value: member.user_id.to_string(),
+            });
+        }
+
+        project_member::ActiveModel::from(member)
+            .insert(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn remove(&self, project_id: EntityId, user_id: EntityId) -> Result<(), DomainError> {
+        debug!("Removing user {} from project {}", user_id, project_id);
+
+        ProjectMemberEntity::delete_by_id((project_id, user_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -2,8 +2,9 @@
 
 use async_trait::async_trait;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
     QueryOrder,
+    QuerySelect,
 };
 use tracing::{debug, instrument};
 
@@ -92,6 +93,25 @@ impl ProjectRepository for SeaOrmProjectRepository {
         Ok(results.into_iter().map(|m| m.into()).collect())
     }
 
+    #[instrument(skip(self))]
+    async fn search(&self, term: &str, limit: u64) -> Result<Vec<Project>, DomainError> {
+        debug!("Searching projects for: {}", term);
+
+        let results = ProjectEntity::find()
+            .filter(
+                Condition::any()
+                    .add(project::Column::Code.contains(term))
+                    .add(project::Column::Name.contains(term)),
+            )
+            .order_by_asc(project::Column::Code)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(|m| m.into()).collect())
+    }
+
     #[instrument(skip(self))]
     async fn save(&self, project: &Project) -> Result<EntityId, DomainError> {
         debug!("Saving project: {}", project.code);
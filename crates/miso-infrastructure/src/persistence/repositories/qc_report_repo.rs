@@ -0,0 +1,107 @@
+//! SeaORM implementation of QcReportRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, RunQcReport};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::QcReportRepository;
+
+use crate::persistence::entities::run_qc_report::{self, Entity as RunQcReportEntity};
+use crate::persistence::entities::run_qc_sample_metrics::{
+    self, Entity as RunQcSampleMetricsEntity,
+};
+
+/// SeaORM-based QC report repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmQcReportRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmQcReportRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl QcReportRepository for SeaOrmQcReportRepository {
+    #[instrument(skip(self))]
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Option<RunQcReport>, DomainError> {
+        debug!("Finding QC report for run: {}", run_id);
+
+        let Some(model) = RunQcReportEntity::find()
+            .filter(run_qc_report::Column::RunId.eq(run_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let samples = model
+            .find_related(RunQcSampleMetricsEntity)
+            .order_by_asc(run_qc_sample_metrics::Column::SampleName)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(Some(RunQcReport {
+            id: model.id,
+            run_id: model.run_id,
+            report_url: model.report_url,
+            file_name: model.file_name,
+            multiqc_version: model.multiqc_version,
+            samples: samples.into_iter().map(Into::into).collect(),
+            attached_by: model.attached_by,
+            attached_at: model.attached_at,
+        }))
+    }
+
+    #[instrument(skip(self, report))]
+    async fn save(&self, report: &RunQcReport) -> Result<EntityId, DomainError> {
+        debug!("Saving QC report for run: {}", report.run_id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Sample metrics cascade with the report
+        RunQcReportEntity::delete_many()
+            .filter(run_qc_report::Column::RunId.eq(report.run_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: run_qc_report::ActiveModel = report.into();
+        let model = active_model
+            .insert(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if !report.samples.is_empty() {
+            RunQcSampleMetricsEntity::insert_many(
+                report
+                    .samples
+                    .iter()
+                    .map(|s| run_qc_sample_metrics::ActiveModel::from_summary(model.id, s)),
+            )
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,61 @@
+//! SeaORM implementation of QcRecordRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, QcRecord, QcTarget};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::QcRecordRepository;
+
+use crate::persistence::entities::qc_result::{self, Entity as QcResultEntity};
+
+/// SeaORM-based QC measurement repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmQcRecordRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmQcRecordRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl QcRecordRepository for SeaOrmQcRecordRepository {
+    #[instrument(skip(self))]
+    async fn find_by_entity(
+        &self,
+        target: QcTarget,
+        entity_id: EntityId,
+    ) -> Result<Vec<QcRecord>, DomainError> {
+        debug!("Finding QCs of {} {}", target, entity_id);
+
+        let results = QcResultEntity::find()
+            .filter(qc_result::Column::EntityType.eq(target.to_string()))
+            .filter(qc_result::Column::EntityId.eq(entity_id))
+            .order_by_asc(qc_result::Column::PerformedAt)
+            .order_by_asc(qc_result::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, record))]
+    async fn save(&self, record: &QcRecord) -> Result<EntityId, DomainError> {
+        debug!("Saving {} QC of {} {}", record.result.test_type, record.target, record.entity_id);
+
+        let model = qc_result::ActiveModel::from(record)
+            .insert(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
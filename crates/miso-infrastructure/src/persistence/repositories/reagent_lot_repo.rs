@@ -0,0 +1,138 @@
+//! SeaORM implementation of ReagentLotRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, SqlErr,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ReagentLot};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, ReagentLotRepository};
+
+use crate::persistence::entities::reagent_lot::{self, Entity as ReagentLotEntity};
+
+/// SeaORM-based reagent lot repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmReagentLotRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmReagentLotRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Lots ordered by kit, then soonest expiring first.
+    fn ordered() -> sea_orm::Select<ReagentLotEntity> {
+        ReagentLotEntity::find()
+            .order_by_asc(reagent_lot::Column::Kit)
+            .order_by_asc(reagent_lot::Column::ExpiresOn)
+            .order_by_asc(reagent_lot::Column::Id)
+    }
+}
+
+#[async_trait]
+impl ReagentLotRepository for SeaOrmReagentLotRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ReagentLot>, DomainError> {
+        debug!("Finding reagent lot by ID: {}", id);
+
+        let result = ReagentLotEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_lot(
+        &self,
+        kit: &str,
+        lot_number: &str,
+    ) -> Result<Option<ReagentLot>, DomainError> {
+        debug!("Finding lot {} of {}", lot_number, kit);
+
+        let result = ReagentLotEntity::find()
+            .filter(reagent_lot::Column::Kit.eq(kit))
+            .filter(reagent_lot::Column::LotNumber.eq(lot_number))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ReagentLot>, DomainError> {
+        debug!("Listing reagent lots");
+
+        let mut query = Self::ordered();
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_in_stock(&self) -> Result<Vec<ReagentLot>, DomainError> {
+        debug!("Finding reagent lots in stock");
+
+        let results = Self::ordered()
+            .filter(reagent_lot::Column::Quantity.gt(0))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, lot))]
+    async fn save(&self, lot: &ReagentLot) -> Result<EntityId, DomainError> {
+        debug!("Saving lot {} of {}", lot.lot_number, lot.kit);
+
+        let active_model: reagent_lot::ActiveModel = lot.into();
+
+        let model = if lot.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "ReagentLot".to_string(),
+                field: "lot_number".to_string(),
+                value: lot.lot_number.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting reagent lot: {}", id);
+
+        ReagentLotEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
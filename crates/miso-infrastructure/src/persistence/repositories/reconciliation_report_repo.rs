@@ -0,0 +1,95 @@
+//! SeaORM implementation of ReconciliationReportRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, QuerySelect};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ReconciliationReport};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, ReconciliationReportRepository};
+
+use crate::persistence::entities::reconciliation_report::{
+    self, Entity as ReconciliationReportEntity,
+};
+
+/// SeaORM-based reconciliation report repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmReconciliationReportRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmReconciliationReportRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ReconciliationReportRepository for SeaOrmReconciliationReportRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ReconciliationReport>, DomainError> {
+        debug!("Finding reconciliation report by ID: {}", id);
+
+        let result = ReconciliationReportEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_latest(&self) -> Result<Option<ReconciliationReport>, DomainError> {
+        debug!("Finding latest reconciliation report");
+
+        let result = ReconciliationReportEntity::find()
+            .order_by_desc(reconciliation_report::Column::CompletedAt)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<ReconciliationReport>, DomainError> {
+        debug!("Listing reconciliation reports with options: {:?}", options);
+
+        let mut query = ReconciliationReportEntity::find()
+            .order_by_desc(reconciliation_report::Column::CompletedAt);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, report))]
+    async fn save(&self, report: &ReconciliationReport) -> Result<EntityId, DomainError> {
+        debug!(
+            "Saving reconciliation report with {} discrepancies",
+            report.discrepancies.len()
+        );
+
+        let active_model: reconciliation_report::ActiveModel = report.into();
+
+        let model = if report.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,83 @@
+//! SeaORM implementation of ReferenceGenomeRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ReferenceGenome};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::ReferenceGenomeRepository;
+
+use crate::persistence::entities::reference_genome::{self, Entity as ReferenceGenomeEntity};
+
+/// SeaORM-based reference genome repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmReferenceGenomeRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmReferenceGenomeRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ReferenceGenomeRepository for SeaOrmReferenceGenomeRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<ReferenceGenome>, DomainError> {
+        debug!("Finding reference genome by ID: {}", id);
+
+        let result = ReferenceGenomeEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<ReferenceGenome>, DomainError> {
+        debug!("Finding reference genome by name: {}", name);
+
+        let result = ReferenceGenomeEntity::find()
+            .filter(reference_genome::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<ReferenceGenome>, DomainError> {
+        debug!("Listing reference genomes");
+
+        let results = ReferenceGenomeEntity::find()
+            .order_by_asc(reference_genome::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, genome))]
+    async fn save(&self, genome: &ReferenceGenome) -> Result<EntityId, DomainError> {
+        debug!("Saving reference genome: {}", genome.name);
+
+        let active_model: reference_genome::ActiveModel = genome.into();
+
+        let model = if genome.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,221 @@
+//! SeaORM implementation of RequisitionRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, SqlErr, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Requisition};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, RequisitionRepository};
+
+use crate::persistence::entities::requisition::{self, Entity as RequisitionEntity};
+use crate::persistence::entities::requisition_sample::{
+    self, Entity as RequisitionSampleEntity,
+};
+
+/// SeaORM-based requisition repository.
+///
+/// A requisition's samples live in the requisition_sample table and are
+/// always read and written together with the requisition.
+#[derive(Debug, Clone)]
+pub struct SeaOrmRequisitionRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmRequisitionRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the samples of `models` in one query and assembles the
+    /// requisitions.
+    async fn with_samples(
+        &self,
+        models: Vec<requisition::Model>,
+    ) -> Result<Vec<Requisition>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut samples: HashMap<i32, Vec<requisition_sample::Model>> = HashMap::new();
+        for sample in RequisitionSampleEntity::find()
+            .filter(requisition_sample::Column::RequisitionId.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            samples.entry(sample.requisition_id).or_default().push(sample);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let requisition_samples = samples.remove(&m.id).unwrap_or_default();
+                m.into_domain(requisition_samples)
+            })
+            .collect())
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<RequisitionEntity>,
+    ) -> Result<Option<Requisition>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self.with_samples(model.into_iter().collect()).await?.pop())
+    }
+
+    /// Replaces the stored samples of the requisition with
+    /// `requisition_id`.
+    async fn replace_samples<C: ConnectionTrait>(
+        conn: &C,
+        requisition_id: EntityId,
+        requisition: &Requisition,
+    ) -> Result<(), DomainError> {
+        RequisitionSampleEntity::delete_many()
+            .filter(requisition_sample::Column::RequisitionId.eq(requisition_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if requisition.sample_ids.is_empty() {
+            return Ok(());
+        }
+
+        RequisitionSampleEntity::insert_many(
+            requisition
+                .sample_ids
+                .iter()
+                .enumerate()
+                .map(|(i, &sample_id)| {
+                    requisition_sample::ActiveModel::new(requisition_id, i, sample_id)
+                }),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Validation(
+                "A sample is already attached to another requisition".to_string(),
+            ),
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RequisitionRepository for SeaOrmRequisitionRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Requisition>, DomainError> {
+        debug!("Finding requisition by ID: {}", id);
+
+        self.find_one(RequisitionEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_alias(&self, alias: &str) -> Result<Option<Requisition>, DomainError> {
+        debug!("Finding requisition by alias: {}", alias);
+
+        self.find_one(RequisitionEntity::find().filter(requisition::Column::Alias.eq(alias)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_sample(
+        &self,
+        sample_id: EntityId,
+    ) -> Result<Option<Requisition>, DomainError> {
+        debug!("Finding requisition of sample: {}", sample_id);
+
+        let attached = RequisitionSampleEntity::find_by_id(sample_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        match attached {
+            Some(attached) => self.find_by_id(attached.requisition_id).await,
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Requisition>, DomainError> {
+        debug!("Listing requisitions");
+
+        let mut query = RequisitionEntity::find().order_by_desc(requisition::Column::Id);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_samples(models).await
+    }
+
+    #[instrument(skip(self, requisition), fields(samples = requisition.sample_ids.len()))]
+    async fn save(&self, requisition: &Requisition) -> Result<EntityId, DomainError> {
+        debug!("Saving requisition: {}", requisition.alias);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: requisition::ActiveModel = requisition.into();
+        let saved = if requisition.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "Requisition".to_string(),
+                field: "alias".to_string(),
+                value: requisition.alias.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Self::replace_samples(&txn, saved.id, requisition).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting requisition: {}", id);
+
+        // Samples are detached with the requisition by the foreign key
+        // cascade.
+        RequisitionEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
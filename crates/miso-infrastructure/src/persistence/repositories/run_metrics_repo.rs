@@ -0,0 +1,97 @@
+//! SeaORM implementation of RunMetricsRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, LibraryRunMetrics};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::RunMetricsRepository;
+
+use crate::persistence::entities::run_library_metrics::{self, Entity as RunLibraryMetricsEntity};
+
+/// SeaORM-based run metrics repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmRunMetricsRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmRunMetricsRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RunMetricsRepository for SeaOrmRunMetricsRepository {
+    #[instrument(skip(self))]
+    async fn find_by_run(&self, run_id: EntityId) -> Result<Vec<LibraryRunMetrics>, DomainError> {
+        debug!("Finding metrics for run: {}", run_id);
+
+        let results = RunLibraryMetricsEntity::find()
+            .filter(run_library_metrics::Column::RunId.eq(run_id))
+            .order_by_asc(run_library_metrics::Column::PartitionNumber)
+            .order_by_asc(run_library_metrics::Column::LibraryId)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_library(
+        &self,
+        library_id: EntityId,
+    ) -> Result<Vec<LibraryRunMetrics>, DomainError> {
+        debug!("Finding metrics for library: {}", library_id);
+
+        let results = RunLibraryMetricsEntity::find()
+            .filter(run_library_metrics::Column::LibraryId.eq(library_id))
+            .order_by_asc(run_library_metrics::Column::RunId)
+            .order_by_asc(run_library_metrics::Column::PartitionNumber)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self, metrics))]
+    async fn save_all(&self, metrics: &[LibraryRunMetrics]) -> Result<(), DomainError> {
+        debug!("Saving {} metrics records", metrics.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        for m in metrics {
+            // Replace any earlier submission for the same slot
+            RunLibraryMetricsEntity::delete_many()
+                .filter(run_library_metrics::Column::RunId.eq(m.run_id))
+                .filter(run_library_metrics::Column::PartitionNumber.eq(m.partition_number as i16))
+                .filter(run_library_metrics::Column::LibraryId.eq(m.library_id))
+                .exec(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+            let active_model: run_library_metrics::ActiveModel = m.into();
+            active_model
+                .insert(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
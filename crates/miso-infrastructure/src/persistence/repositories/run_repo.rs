@@ -0,0 +1,305 @@
+//! SeaORM implementation of RunRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Run, RunStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, RunRepository};
+
+use crate::persistence::entities::run::{self, run_status_code, Entity as RunEntity};
+use crate::persistence::entities::run_interruption::{self, Entity as RunInterruptionEntity};
+use crate::persistence::entities::run_partition::{self, Entity as RunPartitionEntity};
+
+/// SeaORM-based run repository.
+///
+/// A run's partitions, and the pools assigned to them, live in the
+/// run_partition table and the times it was paused in run_interruption.
+/// Both are always read and written together with the run.
+#[derive(Debug, Clone)]
+pub struct SeaOrmRunRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmRunRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the partitions and interruptions of `models`, one query each,
+    /// and assembles the runs.
+    async fn with_partitions(&self, models: Vec<run::Model>) -> Result<Vec<Run>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut partitions: HashMap<i32, Vec<run_partition::Model>> = HashMap::new();
+        for partition in RunPartitionEntity::find()
+            .filter(run_partition::Column::RunId.is_in(ids.clone()))
+            .order_by_asc(run_partition::Column::PartitionNumber)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            partitions
+                .entry(partition.run_id)
+                .or_default()
+                .push(partition);
+        }
+
+        let mut interruptions: HashMap<i32, Vec<run_interruption::Model>> = HashMap::new();
+        for interruption in RunInterruptionEntity::find()
+            .filter(run_interruption::Column::RunId.is_in(ids))
+            .order_by_asc(run_interruption::Column::Position)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            interruptions
+                .entry(interruption.run_id)
+                .or_default()
+                .push(interruption);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let run_partitions = partitions.remove(&m.id).unwrap_or_default();
+                let run_interruptions = interruptions.remove(&m.id).unwrap_or_default();
+                m.into_domain(run_partitions, run_interruptions)
+            })
+            .collect())
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<RunEntity>,
+    ) -> Result<Option<Run>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self
+            .with_partitions(model.into_iter().collect())
+            .await?
+            .pop())
+    }
+
+    async fn find_many(&self, query: sea_orm::Select<RunEntity>) -> Result<Vec<Run>, DomainError> {
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_partitions(models).await
+    }
+
+    /// Replaces the stored partitions of the run with `run_id`.
+    async fn replace_partitions<C: ConnectionTrait>(
+        conn: &C,
+        run_id: EntityId,
+        run: &Run,
+    ) -> Result<(), DomainError> {
+        RunPartitionEntity::delete_many()
+            .filter(run_partition::Column::RunId.eq(run_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if run.partitions.is_empty() {
+            return Ok(());
+        }
+
+        RunPartitionEntity::insert_many(
+            run.partitions
+                .iter()
+                .map(|p| run_partition::ActiveModel::new(run_id, p)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Replaces the stored interruptions of the run with `run_id`.
+    async fn replace_interruptions<C: ConnectionTrait>(
+        conn: &C,
+        run_id: EntityId,
+        run: &Run,
+    ) -> Result<(), DomainError> {
+        RunInterruptionEntity::delete_many()
+            .filter(run_interruption::Column::RunId.eq(run_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if run.interruptions.is_empty() {
+            return Ok(());
+        }
+
+        RunInterruptionEntity::insert_many(
+            run.interruptions
+                .iter()
+                .enumerate()
+                .map(|(i, interruption)| run_interruption::ActiveModel::new(run_id, i, interruption)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RunRepository for SeaOrmRunRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Run>, DomainError> {
+        debug!("Finding run by ID: {}", id);
+
+        self.find_one(RunEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<Run>, DomainError> {
+        debug!("Finding run by name: {}", name);
+
+        self.find_one(RunEntity::find().filter(run::Column::Name.eq(name)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_sequencer(&self, sequencer_id: EntityId) -> Result<Vec<Run>, DomainError> {
+        debug!("Finding runs on sequencer: {}", sequencer_id);
+
+        self.find_many(
+            RunEntity::find()
+                .filter(run::Column::SequencerId.eq(sequencer_id))
+                .order_by_asc(run::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_status(&self, status: RunStatus) -> Result<Vec<Run>, DomainError> {
+        debug!("Finding runs with status: {}", status);
+
+        self.find_many(
+            RunEntity::find()
+                .filter(run::Column::Status.eq(run_status_code(status)))
+                .order_by_asc(run::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_pool(&self, pool_id: EntityId) -> Result<Vec<Run>, DomainError> {
+        debug!("Finding runs loaded with pool: {}", pool_id);
+
+        let run_ids: Vec<i32> = RunPartitionEntity::find()
+            .select_only()
+            .column(run_partition::Column::RunId)
+            .distinct()
+            .filter(run_partition::Column::PoolId.eq(pool_id))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if run_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.find_many(
+            RunEntity::find()
+                .filter(run::Column::Id.is_in(run_ids))
+                .order_by_asc(run::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Run>, DomainError> {
+        debug!("Listing runs");
+
+        let mut query = RunEntity::find();
+
+        if let Some(sort_by) = &options.sort_by {
+            let order = if options.ascending.unwrap_or(true) {
+                sea_orm::Order::Asc
+            } else {
+                sea_orm::Order::Desc
+            };
+
+            query = match sort_by.as_str() {
+                "name" => query.order_by(run::Column::Name, order),
+                "started_at" => query.order_by(run::Column::StartedAt, order),
+                "completed_at" => query.order_by(run::Column::CompletedAt, order),
+                "created_at" => query.order_by(run::Column::CreatedAt, order),
+                _ => query.order_by(run::Column::Id, order),
+            };
+        }
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        self.find_many(query).await
+    }
+
+    #[instrument(skip(self, run), fields(partitions = run.partitions.len()))]
+    async fn save(&self, run: &Run) -> Result<EntityId, DomainError> {
+        debug!("Saving run: {}", run.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: run::ActiveModel = run.into();
+        let saved = if run.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Self::replace_partitions(&txn, saved.id, run).await?;
+        Self::replace_interruptions(&txn, saved.id, run).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting run: {}", id);
+
+        // Partitions and interruptions are removed with the run by the
+        // foreign key cascade.
+        RunEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! SeaORM implementation of SampleCompositionRepository.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, SampleComposition};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::SampleCompositionRepository;
+
+use crate::persistence::entities::sample_component::{self, Entity as SampleComponentEntity};
+
+/// SeaORM-based sample composition repository.
+///
+/// Each identity in a composition is a row of the sample_component table.
+#[derive(Debug, Clone)]
+pub struct SeaOrmSampleCompositionRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmSampleCompositionRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SampleCompositionRepository for SeaOrmSampleCompositionRepository {
+    #[instrument(skip(self, sample_ids), fields(samples = sample_ids.len()))]
+    async fn find_by_samples(
+        &self,
+        sample_ids: &[EntityId],
+    ) -> Result<Vec<SampleComposition>, DomainError> {
+        debug!("Finding compositions of {} samples", sample_ids.len());
+
+        if sample_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = SampleComponentEntity::find()
+            .filter(sample_component::Column::SampleId.is_in(sample_ids.iter().copied()))
+            .order_by_asc(sample_component::Column::SampleId)
+            .order_by_asc(sample_component::Column::Position)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut compositions: BTreeMap<EntityId, Vec<EntityId>> = BTreeMap::new();
+        for row in rows {
+            compositions
+                .entry(row.sample_id)
+                .or_default()
+                .push(row.identity_id);
+        }
+
+        Ok(compositions
+            .into_iter()
+            .map(|(sample_id, identity_ids)| SampleComposition {
+                sample_id,
+                identity_ids,
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_identity(
+        &self,
+        identity_id: EntityId,
+    ) -> Result<Vec<SampleComposition>, DomainError> {
+        debug!("Finding pooled samples made from identity: {}", identity_id);
+
+        let sample_ids: Vec<EntityId> = SampleComponentEntity::find()
+            .filter(sample_component::Column::IdentityId.eq(identity_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .into_iter()
+            .map(|row| row.sample_id)
+            .collect();
+
+        self.find_by_samples(&sample_ids).await
+    }
+
+    #[instrument(skip(self, composition), fields(identities = composition.identity_ids.len()))]
+    async fn save(&self, composition: &SampleComposition) -> Result<(), DomainError> {
+        debug!("Saving composition of sample: {}", composition.sample_id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        SampleComponentEntity::delete_many()
+            .filter(sample_component::Column::SampleId.eq(composition.sample_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if !composition.identity_ids.is_empty() {
+            SampleComponentEntity::insert_many(composition.identity_ids.iter().enumerate().map(
+                |(i, &identity_id)| {
+                    sample_component::ActiveModel::new(composition.sample_id, i, identity_id)
+                },
+            ))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
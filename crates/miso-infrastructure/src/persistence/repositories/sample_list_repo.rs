@@ -0,0 +1,431 @@
+//! SeaORM implementation of SampleListRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveValue, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{
+    EntityId, ListedLocation, ListedQc, Project, QcRecord, Sample, SampleListCount,
+    SampleListFilter, SampleListRow, StorableType,
+};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, SampleListRepository};
+
+use crate::persistence::entities::box_position::{self, Entity as BoxPositionEntity};
+use crate::persistence::entities::library::qc_status_code;
+use crate::persistence::entities::project::{self, Entity as ProjectEntity};
+use crate::persistence::entities::qc_result::{self, Entity as QcResultEntity};
+use crate::persistence::entities::sample::{self, Entity as SampleEntity};
+use crate::persistence::entities::sample_list::{self, Entity as SampleListEntity};
+use crate::persistence::entities::sample_list_count::{self, Entity as SampleListCountEntity};
+use crate::persistence::entities::storage_box::{storable_type_code, Entity as StorageBoxEntity};
+
+/// Samples read per page while rebuilding.
+const REBUILD_PAGE: u64 = 1000;
+
+/// SeaORM-based sample list repository.
+///
+/// Rows live in the sample_list table and the counts of unarchived samples
+/// by project and QC status, leaving out controls, in sample_list_count. Every write of a row
+/// moves its sample between counts in the same transaction, so the counts
+/// never drift from the rows.
+#[derive(Debug, Clone)]
+pub struct SeaOrmSampleListRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmSampleListRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SampleListRepository for SeaOrmSampleListRepository {
+    #[instrument(skip(self))]
+    async fn find(&self, sample_id: EntityId) -> Result<Option<SampleListRow>, DomainError> {
+        debug!("Finding sample list row: {}", sample_id);
+
+        let result = SampleListEntity::find_by_id(sample_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Into::into))
+    }
+
+    #[instrument(skip(self, row), fields(sample_id = row.sample_id))]
+    async fn save(&self, row: &SampleListRow) -> Result<(), DomainError> {
+        debug!("Saving sample list row: {}", row.sample_id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let old = SampleListEntity::find_by_id(row.sample_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if let Some(old) = &old {
+            if counted(old) {
+                adjust_count(&txn, old.project_id, &old.qc_status, -1).await?;
+            }
+            SampleListEntity::delete_by_id(row.sample_id)
+                .exec(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+        SampleListEntity::insert(sample_list::ActiveModel::from(row))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if row.is_counted() {
+            adjust_count(&txn, row.project_id, qc_status_code(row.qc_status), 1).await?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, sample_id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting sample list row: {}", sample_id);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let old = SampleListEntity::find_by_id(sample_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        if let Some(old) = old {
+            if counted(&old) {
+                adjust_count(&txn, old.project_id, &old.qc_status, -1).await?;
+            }
+            SampleListEntity::delete_by_id(sample_id)
+                .exec(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn rename_project(
+        &self,
+        project_id: EntityId,
+        code: &str,
+        name: &str,
+    ) -> Result<(), DomainError> {
+        debug!("Renaming project {} in the sample list", project_id);
+
+        SampleListEntity::update_many()
+            .col_expr(sample_list::Column::ProjectCode, Expr::value(code))
+            .col_expr(sample_list::Column::ProjectName, Expr::value(name))
+            .filter(sample_list::Column::ProjectId.eq(project_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list(
+        &self,
+        filter: &SampleListFilter,
+        options: QueryOptions,
+    ) -> Result<Vec<SampleListRow>, DomainError> {
+        debug!("Listing sample list rows");
+
+        let mut query = SampleListEntity::find();
+        if let Some(project_id) = filter.project_id {
+            query = query.filter(sample_list::Column::ProjectId.eq(project_id));
+        }
+        if let Some(project_ids) = &filter.project_ids {
+            query = query.filter(sample_list::Column::ProjectId.is_in(project_ids.iter().copied()));
+        }
+        if let Some(status) = filter.qc_status {
+            query = query.filter(sample_list::Column::QcStatus.eq(qc_status_code(status)));
+        }
+        if let Some(term) = filter.search.as_deref().filter(|t| !t.is_empty()) {
+            query = query.filter(
+                Condition::any()
+                    .add(sample_list::Column::Name.contains(term))
+                    .add(sample_list::Column::Barcode.contains(term)),
+            );
+        }
+        if !filter.include_archived {
+            query = query.filter(sample_list::Column::Archived.eq(false));
+        }
+
+        let order = if options.ascending.unwrap_or(true) {
+            sea_orm::Order::Asc
+        } else {
+            sea_orm::Order::Desc
+        };
+        query = match options.sort_by.as_deref() {
+            Some("barcode") => query.order_by(sample_list::Column::Barcode, order.clone()),
+            Some("project") => query.order_by(sample_list::Column::ProjectCode, order.clone()),
+            Some("qc_status") => query.order_by(sample_list::Column::QcStatus, order.clone()),
+            Some("updated_at") => query.order_by(sample_list::Column::UpdatedAt, order.clone()),
+            _ => query.order_by(sample_list::Column::Name, order.clone()),
+        };
+        // Ties fall back to the sample ID so pages never overlap
+        query = query.order_by(sample_list::Column::SampleId, order);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn counts(
+        &self,
+        project_id: Option<EntityId>,
+    ) -> Result<Vec<SampleListCount>, DomainError> {
+        debug!("Listing sample list counts");
+
+        let mut query = SampleListCountEntity::find()
+            .filter(sample_list_count::Column::SampleCount.gt(0))
+            .order_by_asc(sample_list_count::Column::ProjectId)
+            .order_by_asc(sample_list_count::Column::QcStatus);
+        if let Some(project_id) = project_id {
+            query = query.filter(sample_list_count::Column::ProjectId.eq(project_id));
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn rebuild(&self) -> Result<u64, DomainError> {
+        debug!("Rebuilding the sample list");
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        SampleListEntity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        SampleListCountEntity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Page through the samples by ID, so each page is an index range
+        // rather than an ever-growing offset
+        let mut projects: HashMap<EntityId, Project> = HashMap::new();
+        let mut after = 0;
+        let mut total = 0;
+        loop {
+            let samples: Vec<Sample> = SampleEntity::find()
+                .filter(sample::Column::Id.gt(after))
+                .order_by_asc(sample::Column::Id)
+                .limit(REBUILD_PAGE)
+                .all(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?
+                .into_iter()
+                .map(Sample::from)
+                .collect();
+            let Some(last) = samples.last() else {
+                break;
+            };
+            after = last.id;
+
+            let missing: Vec<EntityId> = samples
+                .iter()
+                .map(|s| s.project_id)
+                .filter(|id| !projects.contains_key(id))
+                .collect();
+            if !missing.is_empty() {
+                let found = ProjectEntity::find()
+                    .filter(project::Column::Id.is_in(missing))
+                    .all(&txn)
+                    .await
+                    .map_err(|e| DomainError::Validation(e.to_string()))?;
+                projects.extend(found.into_iter().map(|p| (p.id, Project::from(p))));
+            }
+
+            let ids: Vec<EntityId> = samples.iter().map(|s| s.id).collect();
+            let mut latest = latest_qc(&txn, &ids).await?;
+            let mut locations = locations(&txn, &ids).await?;
+
+            let rows: Vec<SampleListRow> = samples
+                .iter()
+                .filter_map(|sample| {
+                    let project = projects.get(&sample.project_id)?;
+                    let mut row = SampleListRow::new(sample, project);
+                    row.latest_qc = latest.remove(&sample.id);
+                    row.location = locations.remove(&sample.id);
+                    Some(row)
+                })
+                .collect();
+            if !rows.is_empty() {
+                total += rows.len() as u64;
+                SampleListEntity::insert_many(rows.iter().map(sample_list::ActiveModel::from))
+                    .exec(&txn)
+                    .await
+                    .map_err(|e| DomainError::Validation(e.to_string()))?;
+            }
+        }
+
+        let counts: Vec<(i32, String, i64)> = SampleListEntity::find()
+            .select_only()
+            .column(sample_list::Column::ProjectId)
+            .column(sample_list::Column::QcStatus)
+            .column_as(sample_list::Column::SampleId.count(), "samples")
+            .filter(sample_list::Column::Archived.eq(false))
+            .filter(sample_list::Column::ControlType.is_null())
+            .group_by(sample_list::Column::ProjectId)
+            .group_by(sample_list::Column::QcStatus)
+            .into_tuple()
+            .all(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        for chunk in counts.chunks(500) {
+            SampleListCountEntity::insert_many(chunk.iter().map(|(project_id, status, count)| {
+                sample_list_count::ActiveModel {
+                    project_id: ActiveValue::Set(*project_id),
+                    qc_status: ActiveValue::Set(status.clone()),
+                    sample_count: ActiveValue::Set(*count),
+                }
+            }))
+            .exec(&txn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(total)
+    }
+}
+
+/// Returns true if a stored row is in its project's counts.
+fn counted(row: &sample_list::Model) -> bool {
+    !row.archived && row.control_type.is_none()
+}
+
+/// Adds `delta` to the count of a project's samples with a QC status code.
+async fn adjust_count<C: ConnectionTrait>(
+    db: &C,
+    project_id: EntityId,
+    qc_status: &str,
+    delta: i64,
+) -> Result<(), DomainError> {
+    let count = sample_list_count::ActiveModel {
+        project_id: ActiveValue::Set(project_id),
+        qc_status: ActiveValue::Set(qc_status.to_string()),
+        sample_count: ActiveValue::Set(delta.max(0)),
+    };
+    SampleListCountEntity::insert(count)
+        .on_conflict(
+            OnConflict::columns([
+                sample_list_count::Column::ProjectId,
+                sample_list_count::Column::QcStatus,
+            ])
+            .value(
+                sample_list_count::Column::SampleCount,
+                Expr::col(sample_list_count::Column::SampleCount).add(delta),
+            )
+            .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+    Ok(())
+}
+
+/// Finds the latest QC measurement of each of some samples.
+async fn latest_qc<C: ConnectionTrait>(
+    db: &C,
+    sample_ids: &[EntityId],
+) -> Result<HashMap<EntityId, ListedQc>, DomainError> {
+    let results = QcResultEntity::find()
+        .filter(qc_result::Column::EntityType.eq("sample"))
+        .filter(qc_result::Column::EntityId.is_in(sample_ids.iter().copied()))
+        .order_by_asc(qc_result::Column::PerformedAt)
+        .order_by_asc(qc_result::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+    // In ascending order, the last measurement of each sample wins
+    let mut latest = HashMap::new();
+    for model in results {
+        let record = QcRecord::try_from(model)?;
+        latest.insert(record.entity_id, ListedQc::from(&record));
+    }
+    Ok(latest)
+}
+
+/// Finds the box position of each of some samples.
+async fn locations<C: ConnectionTrait>(
+    db: &C,
+    sample_ids: &[EntityId],
+) -> Result<HashMap<EntityId, ListedLocation>, DomainError> {
+    let results = BoxPositionEntity::find()
+        .find_also_related(StorageBoxEntity)
+        .filter(box_position::Column::ItemType.eq(storable_type_code(StorableType::Sample)))
+        .filter(box_position::Column::ItemId.is_in(sample_ids.iter().copied()))
+        .all(db)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+    Ok(results
+        .into_iter()
+        .map(|(position, storage_box)| {
+            let location = ListedLocation {
+                box_id: position.box_id,
+                box_name: storage_box.map(|b| b.name).unwrap_or_default(),
+                position: position.position,
+            };
+            (position.item_id, location)
+        })
+        .collect())
+}
+
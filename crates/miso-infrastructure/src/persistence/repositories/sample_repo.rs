@@ -1,16 +1,26 @@
 //! SeaORM implementation of SampleRepository.
 
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
 };
 use tracing::{debug, instrument};
 
-use miso_domain::entities::{EntityId, Sample};
+use miso_domain::entities::{EntityId, Sample, SampleClass};
 use miso_domain::errors::DomainError;
-use miso_domain::repositories::{QueryOptions, SampleRepository};
+use miso_domain::repositories::{NewSample, QueryOptions, SampleRepository, VersionConflict};
+use miso_domain::value_objects::QcStatus;
 
-use crate::persistence::entities::sample::{self, Entity as SampleEntity};
+use crate::persistence::entities::sample::{
+    self, qc_status_from_code, sample_class_code, Entity as SampleEntity,
+};
+
+/// Day a sample was taken in: when it was received, or failing that when
+/// its record was created.
+const INTAKE_DAY: &str = "DATE(COALESCE(received_at, created_at))";
 
 /// SeaORM-based sample repository.
 #[derive(Debug, Clone)]
@@ -23,87 +33,6 @@ impl SeaOrmSampleRepository {
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
     }
-
-    /// Converts a database model to a domain Sample.
-    ///
-    /// This handles the complexity of Plain vs Detailed sample modes.
-    fn model_to_domain(&self, model: sample::Model) -> Sample {
-        use miso_domain::entities::{
-            DetailedSampleData, PlainSampleData, SampleClass, SampleDetails,
-        };
-        use miso_domain::value_objects::{Barcode, Concentration, QcStatus, Volume};
-
-        let details = if model.sample_mode == "detailed" {
-            let sample_class = match model.sample_class.as_deref() {
-                Some("identity") => SampleClass::Identity,
-                Some("tissue") => SampleClass::Tissue,
-                Some("tissue_processing") => SampleClass::TissueProcessing,
-                Some("stock") => SampleClass::Stock,
-                Some("aliquot") => SampleClass::Aliquot,
-                Some("single_cell") => SampleClass::SingleCell,
-                Some("whole_transcriptome") => SampleClass::WholeTranscriptome,
-                _ => SampleClass::Plain,
-            };
-
-            SampleDetails::Detailed(DetailedSampleData {
-                parent_id: model.parent_id,
-                sample_class,
-                external_name: model.external_name.clone(),
-                tissue_origin: model.tissue_origin.clone(),
-                tissue_type: model.tissue_type.clone(),
-                time_point: None,
-                group_id: None,
-                group_description: None,
-                passage: None,
-                analyte_type: model.analyte_type.clone(),
-                purpose: None,
-            })
-        } else {
-            SampleDetails::Plain(PlainSampleData {
-                scientific_name: model.scientific_name.clone().unwrap_or_default(),
-                sample_type: None,
-            })
-        };
-
-        let qc_status = match model.qc_status.as_str() {
-            "not_ready" => QcStatus::NotReady,
-            "ready" => QcStatus::Ready,
-            "passed" => QcStatus::Passed,
-            "failed" => QcStatus::Failed,
-            "needs_review" => QcStatus::NeedsReview,
-            _ => QcStatus::NotReady,
-        };
-
-        let volume = model.volume.map(|v| {
-            // Convert Decimal to f64
-            use std::str::FromStr;
-            let val = f64::from_str(&v.to_string()).unwrap_or(0.0);
-            Volume::microliters(val)
-        });
-
-        let concentration = model.concentration.map(|c| {
-            use std::str::FromStr;
-            let val = f64::from_str(&c.to_string()).unwrap_or(0.0);
-            Concentration::ng_per_ul(val)
-        });
-
-        Sample {
-            id: model.id,
-            name: model.name,
-            barcode: Barcode::new_unchecked(model.barcode),
-            project_id: model.project_id,
-            description: model.description,
-            details,
-            volume,
-            concentration,
-            qc_status,
-            received_at: model.received_at,
-            created_by: model.created_by,
-            created_at: model.created_at,
-            updated_at: model.updated_at,
-            archived: model.archived,
-        }
-    }
 }
 
 #[async_trait]
@@ -117,7 +46,7 @@ impl SampleRepository for SeaOrmSampleRepository {
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
-        Ok(result.map(|m| self.model_to_domain(m)))
+        Ok(result.map(Sample::from))
     }
 
     #[instrument(skip(self))]
@@ -130,7 +59,24 @@ impl SampleRepository for SeaOrmSampleRepository {
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
-        Ok(result.map(|m| self.model_to_domain(m)))
+        Ok(result.map(Sample::from))
+    }
+
+    #[instrument(skip(self, barcodes), fields(count = barcodes.len()))]
+    async fn find_by_barcodes(&self, barcodes: &[String]) -> Result<Vec<Sample>, DomainError> {
+        debug!("Finding {} samples by barcode", barcodes.len());
+
+        if barcodes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let results = SampleEntity::find()
+            .filter(sample::Column::Barcode.is_in(barcodes.iter().cloned()))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
     }
 
     #[instrument(skip(self))]
@@ -173,7 +119,35 @@ impl SampleRepository for SeaOrmSampleRepository {
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
-        Ok(results.into_iter().map(|m| self.model_to_domain(m)).collect())
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_subproject(
+        &self,
+        subproject_id: EntityId,
+        options: QueryOptions,
+    ) -> Result<Vec<Sample>, DomainError> {
+        debug!("Finding samples by subproject: {}", subproject_id);
+
+        let mut query = SampleEntity::find()
+            .filter(sample::Column::SubprojectId.eq(subproject_id))
+            .order_by_asc(sample::Column::Id);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
     }
 
     #[instrument(skip(self))]
@@ -186,7 +160,25 @@ impl SampleRepository for SeaOrmSampleRepository {
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
-        Ok(results.into_iter().map(|m| self.model_to_domain(m)).collect())
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_class(&self, class: &SampleClass) -> Result<Vec<Sample>, DomainError> {
+        debug!("Finding samples by class: {}", class);
+
+        let condition = match class {
+            SampleClass::Plain => sample::Column::SampleMode.eq("plain"),
+            _ => sample::Column::SampleClass.eq(sample_class_code(class)),
+        };
+        let results = SampleEntity::find()
+            .filter(condition)
+            .order_by_asc(sample::Column::Id)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
     }
 
     #[instrument(skip(self))]
@@ -209,13 +201,160 @@ impl SampleRepository for SeaOrmSampleRepository {
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
-        Ok(results.into_iter().map(|m| self.model_to_domain(m)).collect())
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn search(&self, term: &str, limit: u64) -> Result<Vec<Sample>, DomainError> {
+        debug!("Searching samples for: {}", term);
+
+        let results = SampleEntity::find()
+            .filter(
+                Condition::any()
+                    .add(sample::Column::Name.contains(term))
+                    .add(sample::Column::Barcode.contains(term))
+                    .add(sample::Column::ExternalName.contains(term)),
+            )
+            .order_by_desc(sample::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self, ids), fields(count = ids.len()))]
+    async fn find_by_ids(&self, ids: &[EntityId]) -> Result<Vec<Sample>, DomainError> {
+        debug!("Finding {} samples by ID", ids.len());
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let results = SampleEntity::find()
+            .filter(sample::Column::Id.is_in(ids.iter().copied()))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self, sample))]
+    async fn save(&self, sample: &Sample) -> Result<EntityId, DomainError> {
+        debug!("Saving sample: {}", sample.name);
+
+        let active_model: sample::ActiveModel = sample.into();
+
+        // A new sample has no ID yet; any other is updated in place
+        let model = if sample.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e {
+            DbErr::RecordNotUpdated => DomainError::NotFound {
+                entity_type: "Sample".to_string(),
+                id: sample.id.to_string(),
+            },
+            e => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self, samples), fields(count = samples.len()))]
+    async fn insert_all(&self, samples: &[NewSample]) -> Result<Vec<EntityId>, DomainError> {
+        debug!("Inserting {} samples", samples.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut ids: Vec<EntityId> = Vec::with_capacity(samples.len());
+        for new in samples {
+            let mut active_model: sample::ActiveModel = (&new.sample).into();
+            active_model.id = ActiveValue::NotSet;
+            if let Some(index) = new.parent_index {
+                let parent_id = ids.get(index).copied().ok_or_else(|| {
+                    DomainError::Validation(format!(
+                        "Sample {} names a parent that comes after it",
+                        new.sample.name
+                    ))
+                })?;
+                active_model.parent_id = ActiveValue::Set(Some(parent_id));
+            }
+
+            // Dropping the transaction on error rolls it back
+            let model = active_model
+                .insert(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+            ids.push(model.id);
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(ids)
     }
 
-    #[instrument(skip(self, _sample))]
-    async fn save(&self, _sample: &Sample) -> Result<EntityId, DomainError> {
-        // TODO: Implement save with proper ActiveModel conversion
-        Err(DomainError::Validation("Not yet implemented".to_string()))
+    #[instrument(skip(self, samples), fields(count = samples.len()))]
+    async fn update_all_versioned(
+        &self,
+        samples: &[Sample],
+    ) -> Result<Vec<VersionConflict>, DomainError> {
+        debug!("Updating {} samples with version checks", samples.len());
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let mut conflicts = Vec::new();
+        for sample in samples {
+            let mut active_model: sample::ActiveModel = sample.into();
+            active_model.id = ActiveValue::NotSet;
+            active_model.created_by = ActiveValue::NotSet;
+            active_model.created_at = ActiveValue::NotSet;
+            active_model.version = ActiveValue::Set(sample.version + 1);
+
+            // The version bump guarantees a changed row, so MySQL's
+            // changed-rows count is zero only when the guard didn't match.
+            let result = SampleEntity::update_many()
+                .set(active_model)
+                .filter(sample::Column::Id.eq(sample.id))
+                .filter(sample::Column::Version.eq(sample.version))
+                .exec(&txn)
+                .await
+                .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+            if result.rows_affected == 0 {
+                let current = SampleEntity::find_by_id(sample.id)
+                    .one(&txn)
+                    .await
+                    .map_err(|e| DomainError::Validation(e.to_string()))?;
+                conflicts.push(VersionConflict {
+                    id: sample.id,
+                    expected_version: sample.version,
+                    current_version: current.map(|m| m.version),
+                });
+            }
+        }
+
+        if conflicts.is_empty() {
+            txn.commit().await
+        } else {
+            txn.rollback().await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(conflicts)
     }
 
     #[instrument(skip(self))]
@@ -234,11 +373,90 @@ impl SampleRepository for SeaOrmSampleRepository {
     async fn count_by_project(&self, project_id: EntityId) -> Result<u64, DomainError> {
         let count = SampleEntity::find()
             .filter(sample::Column::ProjectId.eq(project_id))
+            .filter(sample::Column::ControlType.is_null())
             .count(&self.db)
             .await
             .map_err(|e| DomainError::Validation(e.to_string()))?;
 
         Ok(count)
     }
-}
 
+    #[instrument(skip(self))]
+    async fn find_qc_failed_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Sample>, DomainError> {
+        debug!("Finding samples failing QC since {}", since);
+
+        let results = SampleEntity::find()
+            .filter(sample::Column::QcStatus.eq("failed"))
+            .filter(sample::Column::UpdatedAt.gte(since))
+            .order_by_asc(sample::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Sample::from).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn count_received_by_day(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(NaiveDate, u64)>, DomainError> {
+        debug!("Counting samples received per day since {}", since);
+
+        let received_since = Condition::any()
+            .add(sample::Column::ReceivedAt.gte(since))
+            .add(
+                Condition::all()
+                    .add(sample::Column::ReceivedAt.is_null())
+                    .add(sample::Column::CreatedAt.gte(since)),
+            );
+
+        let rows: Vec<(NaiveDate, i64)> = SampleEntity::find()
+            .select_only()
+            .column_as(Expr::cust(INTAKE_DAY), "day")
+            .column_as(sample::Column::Id.count(), "samples")
+            .filter(received_since)
+            .group_by(Expr::cust(INTAKE_DAY))
+            .order_by_asc(Expr::cust(INTAKE_DAY))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(day, count)| (day, count.max(0) as u64))
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn count_by_qc_status(&self) -> Result<Vec<(QcStatus, u64)>, DomainError> {
+        debug!("Counting samples by QC status");
+
+        let rows: Vec<(String, i64)> = SampleEntity::find()
+            .select_only()
+            .column(sample::Column::QcStatus)
+            .column_as(sample::Column::Id.count(), "samples")
+            .filter(sample::Column::Archived.eq(false))
+            .group_by(sample::Column::QcStatus)
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // Unknown codes read as Not Ready, so fold them into its count.
+        let mut counts: Vec<(QcStatus, u64)> = Vec::new();
+        for (code, count) in rows {
+            let status = qc_status_from_code(&code);
+            let count = count.max(0) as u64;
+            match counts.iter_mut().find(|(s, _)| *s == status) {
+                Some((_, total)) => *total += count,
+                None => counts.push((status, count)),
+            }
+        }
+        Ok(counts)
+    }
+}
@@ -0,0 +1,147 @@
+//! SeaORM implementation of SequencerRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    SqlErr,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Sequencer, SequencerStatus};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::SequencerRepository;
+
+use crate::persistence::entities::instrument_model::{self, Entity as InstrumentModelEntity};
+use crate::persistence::entities::sequencer::{
+    self, sequencer_status_code, Entity as SequencerEntity,
+};
+
+/// SeaORM-based sequencer repository.
+///
+/// Sequencers are always read together with their instrument model.
+#[derive(Debug, Clone)]
+pub struct SeaOrmSequencerRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmSequencerRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn find_many(
+        &self,
+        query: sea_orm::Select<SequencerEntity>,
+    ) -> Result<Vec<Sequencer>, DomainError> {
+        query
+            .find_also_related(InstrumentModelEntity)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .into_iter()
+            .map(into_domain)
+            .collect()
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<SequencerEntity>,
+    ) -> Result<Option<Sequencer>, DomainError> {
+        query
+            .find_also_related(InstrumentModelEntity)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .map(into_domain)
+            .transpose()
+    }
+}
+
+/// Assembles a sequencer from its row and its model's row.
+fn into_domain(
+    (sequencer, model): (sequencer::Model, Option<instrument_model::Model>),
+) -> Result<Sequencer, DomainError> {
+    let model = model.ok_or_else(|| DomainError::NotFound {
+        entity_type: "InstrumentModel".to_string(),
+        id: sequencer.instrument_model_id.to_string(),
+    })?;
+
+    Ok(sequencer.into_domain(model.try_into()?))
+}
+
+#[async_trait]
+impl SequencerRepository for SeaOrmSequencerRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Sequencer>, DomainError> {
+        debug!("Finding sequencer by ID: {}", id);
+
+        self.find_one(SequencerEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<Sequencer>, DomainError> {
+        debug!("Finding sequencer by name: {}", name);
+
+        self.find_one(SequencerEntity::find().filter(sequencer::Column::Name.eq(name)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<Sequencer>, DomainError> {
+        debug!("Listing sequencers");
+
+        self.find_many(SequencerEntity::find().order_by_asc(sequencer::Column::Name))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_available(&self) -> Result<Vec<Sequencer>, DomainError> {
+        debug!("Finding available sequencers");
+
+        self.find_many(
+            SequencerEntity::find()
+                .filter(
+                    sequencer::Column::Status.eq(sequencer_status_code(SequencerStatus::Available)),
+                )
+                .order_by_asc(sequencer::Column::Name),
+        )
+        .await
+    }
+
+    #[instrument(skip(self, sequencer))]
+    async fn save(&self, sequencer: &Sequencer) -> Result<EntityId, DomainError> {
+        debug!("Saving sequencer: {}", sequencer.name);
+
+        let active_model: sequencer::ActiveModel = sequencer.into();
+
+        let saved = if sequencer.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "Sequencer".to_string(),
+                field: "name".to_string(),
+                value: sequencer.name.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting sequencer: {}", id);
+
+        // The foreign key deletes the sequencer's maintenance windows.
+        SequencerEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
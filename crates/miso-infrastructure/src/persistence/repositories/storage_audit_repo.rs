@@ -0,0 +1,81 @@
+//! SeaORM implementation of StorageAuditRepository.
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, QuerySelect};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, StorageAudit};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, StorageAuditRepository};
+
+use crate::persistence::entities::storage_audit::{self, Entity as StorageAuditEntity};
+
+/// SeaORM-based storage audit repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmStorageAuditRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmStorageAuditRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl StorageAuditRepository for SeaOrmStorageAuditRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageAudit>, DomainError> {
+        debug!("Finding storage audit by ID: {}", id);
+
+        let result = StorageAuditEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<StorageAudit>, DomainError> {
+        debug!("Listing storage audits with options: {:?}", options);
+
+        let mut query =
+            StorageAuditEntity::find().order_by_desc(storage_audit::Column::StartedAt);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, audit), fields(freezer = %audit.freezer))]
+    async fn save(&self, audit: &StorageAudit) -> Result<EntityId, DomainError> {
+        debug!(
+            "Saving storage audit of {} with {} discrepancies",
+            audit.freezer,
+            audit.discrepancies.len()
+        );
+
+        let active_model: storage_audit::ActiveModel = audit.into();
+
+        let model = if audit.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(model.id)
+    }
+}
@@ -0,0 +1,312 @@
+//! SeaORM implementation of StorageBoxRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, StorableType, StorageBox};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, StorageBoxRepository};
+use miso_domain::value_objects::BoxPosition;
+
+use crate::persistence::entities::box_position::{self, Entity as BoxPositionEntity};
+use crate::persistence::entities::item_location::{self, Entity as ItemLocationEntity};
+use crate::persistence::entities::storage_box::{
+    self, storable_type_code, Entity as StorageBoxEntity,
+};
+
+/// SeaORM-based storage box repository.
+///
+/// A box's contents live in the box_position table, one row per occupied
+/// position, and are always read and written together with the box. The
+/// table is indexed on (item_type, item_id) so an item's box can be found
+/// without loading every box. Saving a box also records it as the location
+/// of its contents in the item_location table, which the nightly
+/// reconciliation checks box contents against.
+#[derive(Debug, Clone)]
+pub struct SeaOrmStorageBoxRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmStorageBoxRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the contents of `models` in one query and assembles the boxes.
+    async fn with_contents(
+        &self,
+        models: Vec<storage_box::Model>,
+    ) -> Result<Vec<StorageBox>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut positions: HashMap<i32, Vec<box_position::Model>> = HashMap::new();
+        for position in BoxPositionEntity::find()
+            .filter(box_position::Column::BoxId.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            positions.entry(position.box_id).or_default().push(position);
+        }
+
+        models
+            .into_iter()
+            .map(|m| {
+                let box_positions = positions.remove(&m.id).unwrap_or_default();
+                m.into_domain(box_positions)
+            })
+            .collect()
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<StorageBoxEntity>,
+    ) -> Result<Option<StorageBox>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self.with_contents(model.into_iter().collect()).await?.pop())
+    }
+
+    async fn find_many(
+        &self,
+        query: sea_orm::Select<StorageBoxEntity>,
+    ) -> Result<Vec<StorageBox>, DomainError> {
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_contents(models).await
+    }
+
+    /// Replaces the stored contents of the box with `box_id`.
+    async fn replace_contents<C: ConnectionTrait>(
+        conn: &C,
+        box_id: EntityId,
+        storage_box: &StorageBox,
+    ) -> Result<(), DomainError> {
+        BoxPositionEntity::delete_many()
+            .filter(box_position::Column::BoxId.eq(box_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if storage_box.is_empty() {
+            return Ok(());
+        }
+
+        BoxPositionEntity::insert_many(
+            storage_box
+                .all_contents()
+                .into_iter()
+                .map(|(position, item)| box_position::ActiveModel::new(box_id, position, item)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Records the box with `box_id` as the location of everything it
+    /// holds. Items that have left the box and weren't put away elsewhere
+    /// no longer record a location.
+    async fn record_locations<C: ConnectionTrait>(
+        conn: &C,
+        box_id: EntityId,
+        storage_box: &StorageBox,
+    ) -> Result<(), DomainError> {
+        ItemLocationEntity::delete_many()
+            .filter(item_location::Column::BoxId.eq(box_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        // One row per item, even if the box holds it twice
+        let mut locations = HashMap::new();
+        for (position, item) in storage_box.all_contents() {
+            locations
+                .entry(item.clone())
+                .or_insert_with(|| item_location::ActiveModel::new(box_id, position, item));
+        }
+        if locations.is_empty() {
+            return Ok(());
+        }
+
+        ItemLocationEntity::insert_many(locations.into_values())
+            .on_conflict(
+                OnConflict::columns([
+                    item_location::Column::ItemType,
+                    item_location::Column::ItemId,
+                ])
+                .update_columns([
+                    item_location::Column::BoxId,
+                    item_location::Column::Position,
+                ])
+                .to_owned(),
+            )
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageBoxRepository for SeaOrmStorageBoxRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageBox>, DomainError> {
+        debug!("Finding storage box by ID: {}", id);
+
+        self.find_one(StorageBoxEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<StorageBox>, DomainError> {
+        debug!("Finding storage box by barcode: {}", barcode);
+
+        self.find_one(StorageBoxEntity::find().filter(storage_box::Column::Barcode.eq(barcode)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_location(&self, freezer: &str) -> Result<Vec<StorageBox>, DomainError> {
+        debug!("Finding storage boxes in freezer: {}", freezer);
+
+        self.find_many(
+            StorageBoxEntity::find()
+                .filter(storage_box::Column::Freezer.eq(freezer))
+                .order_by_asc(storage_box::Column::Name),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_rack(&self, rack_id: EntityId) -> Result<Vec<StorageBox>, DomainError> {
+        debug!("Finding storage boxes in rack: {}", rack_id);
+
+        self.find_many(
+            StorageBoxEntity::find()
+                .filter(storage_box::Column::RackId.eq(rack_id))
+                .order_by_asc(storage_box::Column::Name),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<StorageBox>, DomainError> {
+        debug!("Listing storage boxes");
+
+        let mut query = StorageBoxEntity::find();
+
+        if let Some(sort_by) = &options.sort_by {
+            let order = if options.ascending.unwrap_or(true) {
+                sea_orm::Order::Asc
+            } else {
+                sea_orm::Order::Desc
+            };
+
+            query = match sort_by.as_str() {
+                "name" => query.order_by(storage_box::Column::Name, order),
+                "barcode" => query.order_by(storage_box::Column::Barcode, order),
+                "freezer" => query.order_by(storage_box::Column::Freezer, order),
+                "created_at" => query.order_by(storage_box::Column::CreatedAt, order),
+                _ => query.order_by(storage_box::Column::Id, order),
+            };
+        }
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        self.find_many(query).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_item(
+        &self,
+        item_type: StorableType,
+        item_id: EntityId,
+    ) -> Result<Option<(StorageBox, BoxPosition)>, DomainError> {
+        debug!("Finding storage box holding {} {}", item_type, item_id);
+
+        // An item can briefly be recorded in two boxes while it is moved;
+        // the lowest box ID is returned until the move completes.
+        let Some(row) = BoxPositionEntity::find()
+            .filter(box_position::Column::ItemType.eq(storable_type_code(item_type)))
+            .filter(box_position::Column::ItemId.eq(item_id))
+            .order_by_asc(box_position::Column::BoxId)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let Some(storage_box) = self.find_by_id(row.box_id).await? else {
+            return Ok(None);
+        };
+        let position = BoxPosition::parse(&row.position, &storage_box.dimension)?;
+        Ok(Some((storage_box, position)))
+    }
+
+    #[instrument(skip(self, storage_box), fields(items = storage_box.item_count()))]
+    async fn save(&self, storage_box: &StorageBox) -> Result<EntityId, DomainError> {
+        debug!("Saving storage box: {}", storage_box.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: storage_box::ActiveModel = storage_box.into();
+        let saved = if storage_box.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Self::replace_contents(&txn, saved.id, storage_box).await?;
+        Self::record_locations(&txn, saved.id, storage_box).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting storage box: {}", id);
+
+        // Positions and the locations recorded in the box are removed with
+        // the box by the foreign key cascade.
+        StorageBoxEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
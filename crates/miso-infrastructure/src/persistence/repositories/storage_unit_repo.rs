@@ -0,0 +1,128 @@
+//! SeaORM implementation of StorageUnitRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    SqlErr,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, StorageUnit};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::StorageUnitRepository;
+
+use crate::persistence::entities::storage_unit::{self, Entity as StorageUnitEntity};
+
+/// SeaORM-based storage unit repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmStorageUnitRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmStorageUnitRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn find_many(
+        &self,
+        query: sea_orm::Select<StorageUnitEntity>,
+    ) -> Result<Vec<StorageUnit>, DomainError> {
+        query
+            .order_by_asc(storage_unit::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl StorageUnitRepository for SeaOrmStorageUnitRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<StorageUnit>, DomainError> {
+        debug!("Finding storage unit by ID: {}", id);
+
+        let result = StorageUnitEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<StorageUnit>, DomainError> {
+        debug!("Finding storage unit by barcode: {}", barcode);
+
+        let result = StorageUnitEntity::find()
+            .filter(storage_unit::Column::Barcode.eq(barcode))
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_children(&self, parent_id: EntityId) -> Result<Vec<StorageUnit>, DomainError> {
+        debug!("Finding storage units in unit: {}", parent_id);
+
+        self.find_many(
+            StorageUnitEntity::find().filter(storage_unit::Column::ParentId.eq(parent_id)),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_freezers(&self) -> Result<Vec<StorageUnit>, DomainError> {
+        debug!("Listing freezers");
+
+        self.find_many(StorageUnitEntity::find().filter(storage_unit::Column::ParentId.is_null()))
+            .await
+    }
+
+    #[instrument(skip(self, unit))]
+    async fn save(&self, unit: &StorageUnit) -> Result<EntityId, DomainError> {
+        debug!("Saving {}: {}", unit.kind, unit.name);
+
+        let active_model: storage_unit::ActiveModel = unit.into();
+
+        let model = if unit.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "StorageUnit".to_string(),
+                field: "barcode".to_string(),
+                value: unit.barcode.clone().unwrap_or_default(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting storage unit: {}", id);
+
+        // Units still holding units or boxes are kept by the foreign keys.
+        StorageUnitEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| match e.sql_err() {
+                Some(SqlErr::ForeignKeyConstraintViolation(_)) => DomainError::Validation(
+                    "The storage unit still holds units or boxes".to_string(),
+                ),
+                _ => DomainError::Validation(e.to_string()),
+            })?;
+
+        Ok(())
+    }
+}
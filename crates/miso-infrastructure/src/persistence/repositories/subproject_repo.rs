@@ -0,0 +1,92 @@
+//! SeaORM implementation of SubprojectRepository.
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    SqlErr,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Subproject};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::SubprojectRepository;
+
+use crate::persistence::entities::subproject::{self, Entity as SubprojectEntity};
+
+/// SeaORM-based subproject repository.
+#[derive(Debug, Clone)]
+pub struct SeaOrmSubprojectRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmSubprojectRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SubprojectRepository for SeaOrmSubprojectRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Subproject>, DomainError> {
+        debug!("Finding subproject by ID: {}", id);
+
+        let result = SubprojectEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(result.map(Subproject::from))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_project(&self, project_id: EntityId) -> Result<Vec<Subproject>, DomainError> {
+        debug!("Finding subprojects of project {}", project_id);
+
+        let results = SubprojectEntity::find()
+            .filter(subproject::Column::ProjectId.eq(project_id))
+            .order_by_asc(subproject::Column::Alias)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(results.into_iter().map(Subproject::from).collect())
+    }
+
+    #[instrument(skip(self, subproject), fields(project_id = subproject.project_id))]
+    async fn save(&self, subproject: &Subproject) -> Result<EntityId, DomainError> {
+        debug!("Saving subproject {}", subproject.alias);
+
+        let active_model: subproject::ActiveModel = subproject.into();
+
+        let model = if subproject.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "Subproject".to_string(),
+                field: "alias".to_string(),
+                value: subproject.alias.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Ok(model.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting subproject: {}", id);
+
+        // The foreign key unassigns the subproject's samples.
+        SubprojectEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
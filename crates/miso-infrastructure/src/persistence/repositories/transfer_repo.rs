@@ -0,0 +1,232 @@
+//! SeaORM implementation of TransferRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, ShipmentStatus, Transfer, TransferItemType};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, TransferRepository};
+
+use crate::persistence::entities::transfer::{self, Entity as TransferEntity};
+use crate::persistence::entities::transfer_item::{self, Entity as TransferItemEntity};
+
+/// SeaORM-based transfer repository.
+///
+/// A transfer's items live in the transfer_item table and are always read
+/// and written together with the transfer.
+#[derive(Debug, Clone)]
+pub struct SeaOrmTransferRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmTransferRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the items of `models` in one query and assembles the transfers.
+    async fn with_items(&self, models: Vec<transfer::Model>) -> Result<Vec<Transfer>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut items: HashMap<i32, Vec<transfer_item::Model>> = HashMap::new();
+        for item in TransferItemEntity::find()
+            .filter(transfer_item::Column::TransferId.is_in(ids))
+            .order_by_asc(transfer_item::Column::Position)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            items.entry(item.transfer_id).or_default().push(item);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let transfer_items = items.remove(&m.id).unwrap_or_default();
+                m.into_domain(transfer_items)
+            })
+            .collect())
+    }
+
+    async fn find_many(
+        &self,
+        query: sea_orm::Select<TransferEntity>,
+    ) -> Result<Vec<Transfer>, DomainError> {
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_items(models).await
+    }
+
+    /// Replaces the stored items of the transfer with `transfer_id`.
+    async fn replace_items<C: ConnectionTrait>(
+        conn: &C,
+        transfer_id: EntityId,
+        transfer: &Transfer,
+    ) -> Result<(), DomainError> {
+        TransferItemEntity::delete_many()
+            .filter(transfer_item::Column::TransferId.eq(transfer_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if transfer.items.is_empty() {
+            return Ok(());
+        }
+
+        TransferItemEntity::insert_many(
+            transfer
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| transfer_item::ActiveModel::new(transfer_id, i, item)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransferRepository for SeaOrmTransferRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Transfer>, DomainError> {
+        debug!("Finding transfer by ID: {}", id);
+
+        Ok(self
+            .find_many(TransferEntity::find_by_id(id))
+            .await?
+            .pop())
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Transfer>, DomainError> {
+        debug!("Listing transfers");
+
+        let mut query = TransferEntity::find()
+            .order_by_desc(transfer::Column::SentAt)
+            .order_by_desc(transfer::Column::Id);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        self.find_many(query).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_item(
+        &self,
+        item_type: TransferItemType,
+        entity_id: EntityId,
+    ) -> Result<Vec<Transfer>, DomainError> {
+        debug!("Finding transfers of {} {}", item_type, entity_id);
+
+        let transfer_ids: Vec<i32> = TransferItemEntity::find()
+            .select_only()
+            .column(transfer_item::Column::TransferId)
+            .filter(transfer_item::Column::ItemType.eq(item_type.as_str()))
+            .filter(transfer_item::Column::EntityId.eq(entity_id))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if transfer_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.find_many(
+            TransferEntity::find()
+                .filter(transfer::Column::Id.is_in(transfer_ids))
+                .order_by_asc(transfer::Column::SentAt)
+                .order_by_asc(transfer::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_tracking_number(
+        &self,
+        tracking_number: &str,
+    ) -> Result<Vec<Transfer>, DomainError> {
+        debug!("Finding transfers with tracking number {}", tracking_number);
+
+        self.find_many(
+            TransferEntity::find()
+                .filter(transfer::Column::TrackingNumber.eq(tracking_number))
+                .order_by_asc(transfer::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_in_transit(&self) -> Result<Vec<Transfer>, DomainError> {
+        debug!("Finding transfers in transit");
+
+        self.find_many(
+            TransferEntity::find()
+                .filter(transfer::Column::TrackingNumber.is_not_null())
+                .filter(transfer::Column::ReceivedAt.is_null())
+                .filter(
+                    Condition::any()
+                        .add(transfer::Column::ShipmentStatus.is_null())
+                        .add(
+                            transfer::Column::ShipmentStatus
+                                .ne(ShipmentStatus::Delivered.as_str()),
+                        ),
+                )
+                .order_by_asc(transfer::Column::SentAt)
+                .order_by_asc(transfer::Column::Id),
+        )
+        .await
+    }
+
+    #[instrument(skip(self, transfer), fields(items = transfer.items.len()))]
+    async fn save(&self, transfer: &Transfer) -> Result<EntityId, DomainError> {
+        debug!(
+            "Saving transfer from {} to {}",
+            transfer.sender, transfer.recipient
+        );
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: transfer::ActiveModel = transfer.into();
+        let saved = if transfer.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Self::replace_items(&txn, saved.id, transfer).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+}
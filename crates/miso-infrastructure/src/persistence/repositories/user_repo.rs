@@ -0,0 +1,176 @@
+//! SeaORM implementation of UserRepository.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, User};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, UserRepository};
+
+use crate::persistence::entities::user::{self, Entity as UserEntity};
+
+/// SeaORM-based user repository.
+///
+/// Password hashes are stored with the user but kept out of [`User`], so
+/// they are only read and written through the password methods.
+#[derive(Debug, Clone)]
+pub struct SeaOrmUserRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmUserRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<UserEntity>,
+    ) -> Result<Option<User>, DomainError> {
+        let result = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        result.map(TryInto::try_into).transpose()
+    }
+}
+
+#[async_trait]
+impl UserRepository for SeaOrmUserRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<User>, DomainError> {
+        debug!("Finding user by ID: {}", id);
+
+        self.find_one(UserEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        debug!("Finding user by username: {}", username);
+
+        self.find_one(UserEntity::find().filter(user::Column::Username.eq(username)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+        debug!("Finding user by email: {}", email);
+
+        self.find_one(UserEntity::find().filter(user::Column::Email.eq(email)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<User>, DomainError> {
+        debug!("Listing users");
+
+        let mut query = UserEntity::find();
+
+        if let Some(sort_by) = &options.sort_by {
+            let order = if options.ascending.unwrap_or(true) {
+                sea_orm::Order::Asc
+            } else {
+                sea_orm::Order::Desc
+            };
+
+            query = match sort_by.as_str() {
+                "username" => query.order_by(user::Column::Username, order),
+                "display_name" => query.order_by(user::Column::DisplayName, order),
+                "last_login_at" => query.order_by(user::Column::LastLoginAt, order),
+                "created_at" => query.order_by(user::Column::CreatedAt, order),
+                _ => query.order_by(user::Column::Id, order),
+            };
+        }
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let results = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        results.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(skip(self, user), fields(username = %user.username))]
+    async fn save(&self, user: &User) -> Result<EntityId, DomainError> {
+        debug!("Saving user: {}", user.username);
+
+        let active_model: user::ActiveModel = user.into();
+        let saved = if user.id == 0 {
+            active_model.insert(&self.db).await
+        } else {
+            active_model.update(&self.db).await
+        }
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn find_password_hash(&self, id: EntityId) -> Result<Option<String>, DomainError> {
+        debug!("Finding password hash of user: {}", id);
+
+        let hash: Option<Option<String>> = UserEntity::find_by_id(id)
+            .select_only()
+            .column(user::Column::PasswordHash)
+            .into_tuple()
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(hash.flatten())
+    }
+
+    #[instrument(skip(self, password_hash))]
+    async fn set_password_hash(
+        &self,
+        id: EntityId,
+        password_hash: &str,
+    ) -> Result<(), DomainError> {
+        debug!("Setting password of user: {}", id);
+
+        let result = UserEntity::update_many()
+            .col_expr(user::Column::PasswordHash, Expr::value(password_hash))
+            .col_expr(user::Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(user::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(DomainError::NotFound {
+                entity_type: "User".to_string(),
+                id: id.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting user: {}", id);
+
+        UserEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! SeaORM implementation of WorksetRepository.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, SqlErr, TransactionTrait,
+};
+use tracing::{debug, instrument};
+
+use miso_domain::entities::{EntityId, Workset};
+use miso_domain::errors::DomainError;
+use miso_domain::repositories::{QueryOptions, WorksetRepository};
+
+use crate::persistence::entities::workset::{self, Entity as WorksetEntity};
+use crate::persistence::entities::workset_item::{self, Entity as WorksetItemEntity};
+
+/// SeaORM-based workset repository.
+///
+/// A workset's items live in the workset_item table and are always read
+/// and written together with the workset.
+#[derive(Debug, Clone)]
+pub struct SeaOrmWorksetRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmWorksetRepository {
+    /// Creates a new repository with the given database connection.
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Loads the items of `models` in one query and assembles the worksets.
+    async fn with_items(&self, models: Vec<workset::Model>) -> Result<Vec<Workset>, DomainError> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = models.iter().map(|m| m.id).collect();
+        let mut items: HashMap<i32, Vec<workset_item::Model>> = HashMap::new();
+        for item in WorksetItemEntity::find()
+            .filter(workset_item::Column::WorksetId.is_in(ids))
+            .order_by_asc(workset_item::Column::AddedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?
+        {
+            items.entry(item.workset_id).or_default().push(item);
+        }
+
+        Ok(models
+            .into_iter()
+            .map(|m| {
+                let workset_items = items.remove(&m.id).unwrap_or_default();
+                m.into_domain(workset_items)
+            })
+            .collect())
+    }
+
+    async fn find_one(
+        &self,
+        query: sea_orm::Select<WorksetEntity>,
+    ) -> Result<Option<Workset>, DomainError> {
+        let model = query
+            .one(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(self.with_items(model.into_iter().collect()).await?.pop())
+    }
+
+    /// Replaces the stored items of the workset with `workset_id`.
+    async fn replace_items<C: ConnectionTrait>(
+        conn: &C,
+        workset_id: EntityId,
+        workset: &Workset,
+    ) -> Result<(), DomainError> {
+        WorksetItemEntity::delete_many()
+            .filter(workset_item::Column::WorksetId.eq(workset_id))
+            .exec(conn)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        if workset.items.is_empty() {
+            return Ok(());
+        }
+
+        WorksetItemEntity::insert_many(
+            workset
+                .items
+                .iter()
+                .map(|i| workset_item::ActiveModel::new(workset_id, i)),
+        )
+        .exec(conn)
+        .await
+        .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WorksetRepository for SeaOrmWorksetRepository {
+    #[instrument(skip(self))]
+    async fn find_by_id(&self, id: EntityId) -> Result<Option<Workset>, DomainError> {
+        debug!("Finding workset by ID: {}", id);
+
+        self.find_one(WorksetEntity::find_by_id(id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_name(&self, name: &str) -> Result<Option<Workset>, DomainError> {
+        debug!("Finding workset by name: {}", name);
+
+        self.find_one(WorksetEntity::find().filter(workset::Column::Name.eq(name)))
+            .await
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self, options: QueryOptions) -> Result<Vec<Workset>, DomainError> {
+        debug!("Listing worksets");
+
+        let mut query = WorksetEntity::find().order_by_desc(workset::Column::Id);
+
+        if let Some(offset) = options.offset {
+            query = query.offset(offset);
+        }
+
+        if let Some(limit) = options.limit {
+            query = query.limit(limit);
+        }
+
+        let models = query
+            .all(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        self.with_items(models).await
+    }
+
+    #[instrument(skip(self, workset), fields(items = workset.items.len()))]
+    async fn save(&self, workset: &Workset) -> Result<EntityId, DomainError> {
+        debug!("Saving workset: {}", workset.name);
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        let active_model: workset::ActiveModel = workset.into();
+        let saved = if workset.id == 0 {
+            active_model.insert(&txn).await
+        } else {
+            active_model.update(&txn).await
+        }
+        .map_err(|e| match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => DomainError::Duplicate {
+                entity_type: "Workset".to_string(),
+                field: "name".to_string(),
+                value: workset.name.clone(),
+            },
+            _ => DomainError::Validation(e.to_string()),
+        })?;
+
+        Self::replace_items(&txn, saved.id, workset).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(saved.id)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: EntityId) -> Result<(), DomainError> {
+        debug!("Deleting workset: {}", id);
+
+        // Items are removed with the workset by the foreign key cascade.
+        WorksetEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DomainError::Validation(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! Attachment store that keeps files in a local directory.
+//!
+//! Each key becomes a path under the directory, with intermediate
+//! directories created as needed. Keys that could step outside the
+//! directory are turned down.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tracing::{debug, instrument};
+
+use miso_domain::attachments::AttachmentStore;
+use miso_domain::errors::DomainError;
+
+/// Keeps attachments under a directory on the local filesystem.
+#[derive(Debug, Clone)]
+pub struct LocalAttachmentStore {
+    root: PathBuf,
+}
+
+impl LocalAttachmentStore {
+    /// Creates a store rooted at the given directory. The directory is
+    /// created when the first file is stored.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolves a key to a path under the root directory.
+    fn path(&self, key: &str) -> Result<PathBuf, DomainError> {
+        let mut path = self.root.clone();
+        for segment in key.split('/') {
+            if segment.is_empty() || segment == "." || segment == ".." || segment.contains('\\') {
+                return Err(DomainError::Validation(format!(
+                    "Invalid attachment key: {}",
+                    key
+                )));
+            }
+            path.push(segment);
+        }
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl AttachmentStore for LocalAttachmentStore {
+    #[instrument(skip(self, contents))]
+    async fn put(&self, key: &str, _content_type: &str, contents: &[u8]) -> Result<(), DomainError> {
+        let path = self.path(key)?;
+        debug!("Writing {} bytes to {}", contents.len(), path.display());
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(failed)?;
+        }
+        tokio::fs::write(&path, contents).await.map_err(failed)
+    }
+
+    #[instrument(skip(self))]
+    async fn get(&self, key: &str) -> Result<Vec<u8>, DomainError> {
+        let path = self.path(key)?;
+
+        tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+            ErrorKind::NotFound => DomainError::NotFound {
+                entity_type: "Attachment".to_string(),
+                id: key.to_string(),
+            },
+            _ => failed(e),
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        let path = self.path(key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(failed(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn failed(e: std::io::Error) -> DomainError {
+    DomainError::Validation(format!("Attachment storage failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip_and_key_checks() {
+        let root = std::env::temp_dir().join(format!("miso-attachments-{}", uuid::Uuid::new_v4()));
+        let store = LocalAttachmentStore::new(&root);
+
+        store.put("sample/1/abc", "text/plain", b"hello").await.unwrap();
+        assert_eq!(store.get("sample/1/abc").await.unwrap(), b"hello");
+
+        store.delete("sample/1/abc").await.unwrap();
+        store.delete("sample/1/abc").await.unwrap();
+        assert!(matches!(
+            store.get("sample/1/abc").await,
+            Err(DomainError::NotFound { .. })
+        ));
+
+        assert!(store.put("../escape", "", b"x").await.is_err());
+        assert!(store.put("/etc/passwd", "", b"x").await.is_err());
+        assert!(store.get("sample//abc").await.is_err());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
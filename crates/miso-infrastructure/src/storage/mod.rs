@@ -0,0 +1,8 @@
+//! Attachment store implementations.
+//!
+//! Provides places to keep uploaded files:
+//! - A directory on the local filesystem
+//! - An S3 bucket, or any service speaking the S3 API
+
+pub mod local;
+pub mod s3;
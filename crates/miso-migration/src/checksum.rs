@@ -0,0 +1,447 @@
+//! Checksum verification of applied migrations.
+//!
+//! When a migration is applied through `miso-migrate up`, a SHA-256 of its
+//! source file is recorded alongside SeaORM's own bookkeeping table. `verify`
+//! recomputes the checksums from the compiled-in sources and reports any
+//! applied migration whose source has since been edited — a sign that the
+//! database schema no longer matches what the code describes — or that has
+//! no checksum. Checksums for migrations applied by other tools are only
+//! recorded through `record`, once their sources have been reviewed.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr};
+use sea_orm_migration::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::Migrator;
+
+/// Source text of every migration, keyed by migration name.
+///
+/// New migrations must be added here as well as to [`Migrator`].
+const SOURCES: &[(&str, &str)] = &[
+    (
+        "m20241215_000001_create_project",
+        include_str!("m20241215_000001_create_project.rs"),
+    ),
+    (
+        "m20241215_000002_create_sample",
+        include_str!("m20241215_000002_create_sample.rs"),
+    ),
+    (
+        "m20241215_000003_create_run_library_metrics",
+        include_str!("m20241215_000003_create_run_library_metrics.rs"),
+    ),
+    (
+        "m20241215_000004_create_run_qc_report",
+        include_str!("m20241215_000004_create_run_qc_report.rs"),
+    ),
+    (
+        "m20241215_000005_create_export_template",
+        include_str!("m20241215_000005_create_export_template.rs"),
+    ),
+    (
+        "m20241215_000006_create_reconciliation_report",
+        include_str!("m20241215_000006_create_reconciliation_report.rs"),
+    ),
+    (
+        "m20241215_000007_add_sample_version",
+        include_str!("m20241215_000007_add_sample_version.rs"),
+    ),
+    (
+        "m20241215_000008_create_change_log",
+        include_str!("m20241215_000008_create_change_log.rs"),
+    ),
+    (
+        "m20241215_000009_create_instrument_model",
+        include_str!("m20241215_000009_create_instrument_model.rs"),
+    ),
+    (
+        "m20241215_000010_create_data_location",
+        include_str!("m20241215_000010_create_data_location.rs"),
+    ),
+    (
+        "m20241215_000011_add_data_location_retention",
+        include_str!("m20241215_000011_add_data_location_retention.rs"),
+    ),
+    (
+        "m20241215_000012_add_instrument_model_run_duration",
+        include_str!("m20241215_000012_add_instrument_model_run_duration.rs"),
+    ),
+    (
+        "m20241215_000013_create_instrument_event",
+        include_str!("m20241215_000013_create_instrument_event.rs"),
+    ),
+    (
+        "m20241215_000014_create_library",
+        include_str!("m20241215_000014_create_library.rs"),
+    ),
+    (
+        "m20241215_000015_create_pool",
+        include_str!("m20241215_000015_create_pool.rs"),
+    ),
+    (
+        "m20241215_000016_create_attribute_definition",
+        include_str!("m20241215_000016_create_attribute_definition.rs"),
+    ),
+    (
+        "m20241215_000017_add_sample_attributes",
+        include_str!("m20241215_000017_add_sample_attributes.rs"),
+    ),
+    (
+        "m20241215_000018_create_run",
+        include_str!("m20241215_000018_create_run.rs"),
+    ),
+    (
+        "m20241215_000019_create_storage_box",
+        include_str!("m20241215_000019_create_storage_box.rs"),
+    ),
+    (
+        "m20241215_000020_create_user",
+        include_str!("m20241215_000020_create_user.rs"),
+    ),
+    (
+        "m20241215_000021_add_sample_details",
+        include_str!("m20241215_000021_add_sample_details.rs"),
+    ),
+    (
+        "m20241215_000022_create_sequencer",
+        include_str!("m20241215_000022_create_sequencer.rs"),
+    ),
+    (
+        "m20241215_000023_add_pool_container_model",
+        include_str!("m20241215_000023_add_pool_container_model.rs"),
+    ),
+    (
+        "m20241215_000024_create_panel",
+        include_str!("m20241215_000024_create_panel.rs"),
+    ),
+    (
+        "m20241215_000025_create_reference_genome",
+        include_str!("m20241215_000025_create_reference_genome.rs"),
+    ),
+    (
+        "m20241215_000026_create_api_key",
+        include_str!("m20241215_000026_create_api_key.rs"),
+    ),
+    (
+        "m20241215_000027_create_possible_duplicate",
+        include_str!("m20241215_000027_create_possible_duplicate.rs"),
+    ),
+    (
+        "m20241215_000028_create_audit_log",
+        include_str!("m20241215_000028_create_audit_log.rs"),
+    ),
+    (
+        "m20241215_000029_create_erasure",
+        include_str!("m20241215_000029_create_erasure.rs"),
+    ),
+    (
+        "m20241215_000030_create_qc_result",
+        include_str!("m20241215_000030_create_qc_result.rs"),
+    ),
+    (
+        "m20241215_000031_create_consent",
+        include_str!("m20241215_000031_create_consent.rs"),
+    ),
+    (
+        "m20241215_000032_create_storage_audit",
+        include_str!("m20241215_000032_create_storage_audit.rs"),
+    ),
+    (
+        "m20241215_000033_add_run_ready_to_load",
+        include_str!("m20241215_000033_add_run_ready_to_load.rs"),
+    ),
+    (
+        "m20241215_000034_add_run_partition_yield",
+        include_str!("m20241215_000034_add_run_partition_yield.rs"),
+    ),
+    (
+        "m20241215_000035_create_index_set",
+        include_str!("m20241215_000035_create_index_set.rs"),
+    ),
+    (
+        "m20241215_000036_create_event_store",
+        include_str!("m20241215_000036_create_event_store.rs"),
+    ),
+    (
+        "m20241215_000037_add_log_hash_chain",
+        include_str!("m20241215_000037_add_log_hash_chain.rs"),
+    ),
+    (
+        "m20241215_000038_add_project_lock",
+        include_str!("m20241215_000038_add_project_lock.rs"),
+    ),
+    (
+        "m20241215_000039_create_sample_list",
+        include_str!("m20241215_000039_create_sample_list.rs"),
+    ),
+    (
+        "m20241215_000040_create_log_archive",
+        include_str!("m20241215_000040_create_log_archive.rs"),
+    ),
+    (
+        "m20241215_000041_create_label_print",
+        include_str!("m20241215_000041_create_label_print.rs"),
+    ),
+    (
+        "m20241215_000042_add_sample_library_mass",
+        include_str!("m20241215_000042_add_sample_library_mass.rs"),
+    ),
+    (
+        "m20241215_000043_create_library_aliquot",
+        include_str!("m20241215_000043_create_library_aliquot.rs"),
+    ),
+    (
+        "m20241215_000044_create_label_usage",
+        include_str!("m20241215_000044_create_label_usage.rs"),
+    ),
+    (
+        "m20241215_000045_create_workset",
+        include_str!("m20241215_000045_create_workset.rs"),
+    ),
+    (
+        "m20241215_000046_create_run_interruption",
+        include_str!("m20241215_000046_create_run_interruption.rs"),
+    ),
+    (
+        "m20241215_000047_create_transfer",
+        include_str!("m20241215_000047_create_transfer.rs"),
+    ),
+    (
+        "m20241215_000048_add_run_estimated_completion",
+        include_str!("m20241215_000048_add_run_estimated_completion.rs"),
+    ),
+    (
+        "m20241215_000049_create_requisition",
+        include_str!("m20241215_000049_create_requisition.rs"),
+    ),
+    (
+        "m20241215_000050_create_storage_unit",
+        include_str!("m20241215_000050_create_storage_unit.rs"),
+    ),
+    (
+        "m20241215_000051_create_sample_component",
+        include_str!("m20241215_000051_create_sample_component.rs"),
+    ),
+    (
+        "m20241215_000052_add_control_type",
+        include_str!("m20241215_000052_add_control_type.rs"),
+    ),
+    (
+        "m20241215_000053_create_extraction_batch",
+        include_str!("m20241215_000053_create_extraction_batch.rs"),
+    ),
+    (
+        "m20241215_000054_create_reagent_lot",
+        include_str!("m20241215_000054_create_reagent_lot.rs"),
+    ),
+    (
+        "m20241215_000055_create_attachment",
+        include_str!("m20241215_000055_create_attachment.rs"),
+    ),
+    (
+        "m20241215_000056_add_transfer_shipment",
+        include_str!("m20241215_000056_add_transfer_shipment.rs"),
+    ),
+    (
+        "m20241215_000057_create_maintenance_window",
+        include_str!("m20241215_000057_create_maintenance_window.rs"),
+    ),
+    (
+        "m20241215_000058_create_api_usage",
+        include_str!("m20241215_000058_create_api_usage.rs"),
+    ),
+    (
+        "m20241215_000059_create_project_member",
+        include_str!("m20241215_000059_create_project_member.rs"),
+    ),
+    (
+        "m20241215_000060_create_delivery",
+        include_str!("m20241215_000060_create_delivery.rs"),
+    ),
+    (
+        "m20241215_000061_create_subproject",
+        include_str!("m20241215_000061_create_subproject.rs"),
+    ),
+    (
+        "m20241215_000062_create_archive_rule",
+        include_str!("m20241215_000062_create_archive_rule.rs"),
+    ),
+    (
+        "m20241215_000063_create_item_location",
+        include_str!("m20241215_000063_create_item_location.rs"),
+    ),
+    (
+        "m20241215_000064_add_change_log_kept_hashes",
+        include_str!("m20241215_000064_add_change_log_kept_hashes.rs"),
+    ),
+    (
+        "m20241215_000065_add_stored_event_erased_hashes",
+        include_str!("m20241215_000065_add_stored_event_erased_hashes.rs"),
+    ),
+    (
+        "m20241215_000066_add_patterned_containers",
+        include_str!("m20241215_000066_add_patterned_containers.rs"),
+    ),
+];
+
+/// Returns the hex SHA-256 of a migration's source, if it is known.
+pub fn source_checksum(name: &str) -> Option<String> {
+    SOURCES.iter().find(|(n, _)| *n == name).map(|(_, source)| {
+        Sha256::digest(source.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    })
+}
+
+/// Verification outcome for one applied migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// Recorded checksum matches the source
+    Ok,
+    /// Source was edited after the migration was applied
+    Modified { recorded: String, current: String },
+    /// Applied without a checksum being recorded (e.g. by another tool)
+    Unrecorded,
+}
+
+/// Ensures the checksum table exists.
+pub async fn install<C: ConnectionTrait>(db: &C) -> Result<(), DbErr> {
+    let stmt = Table::create()
+        .table(MigrationChecksum::Table)
+        .if_not_exists()
+        .col(
+            ColumnDef::new(MigrationChecksum::Version)
+                .string_len(255)
+                .not_null()
+                .primary_key(),
+        )
+        .col(
+            ColumnDef::new(MigrationChecksum::Checksum)
+                .string_len(64)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(MigrationChecksum::RecordedAt)
+                .timestamp()
+                .not_null()
+                .default(Expr::current_timestamp()),
+        )
+        .to_owned();
+
+    db.execute(db.get_database_backend().build(&stmt)).await?;
+    Ok(())
+}
+
+/// Records checksums for the given migrations' current sources, skipping
+/// any that already have one.
+pub async fn record<C: ConnectionTrait>(db: &C, versions: &[String]) -> Result<(), DbErr> {
+    if versions.is_empty() {
+        return Ok(());
+    }
+    install(db).await?;
+    let recorded = recorded(db).await?;
+
+    for name in versions {
+        if recorded.iter().any(|(v, _)| v == name) {
+            continue;
+        }
+        let Some(checksum) = source_checksum(name) else {
+            continue;
+        };
+
+        let stmt = Query::insert()
+            .into_table(MigrationChecksum::Table)
+            .columns([MigrationChecksum::Version, MigrationChecksum::Checksum])
+            .values_panic([name.as_str().into(), checksum.into()])
+            .to_owned();
+        db.execute(db.get_database_backend().build(&stmt)).await?;
+    }
+
+    Ok(())
+}
+
+/// Removes recorded checksums, e.g. after migrations are rolled back.
+pub async fn forget<C: ConnectionTrait>(db: &C, versions: &[String]) -> Result<(), DbErr> {
+    if versions.is_empty() {
+        return Ok(());
+    }
+    install(db).await?;
+
+    let stmt = Query::delete()
+        .from_table(MigrationChecksum::Table)
+        .and_where(Expr::col(MigrationChecksum::Version).is_in(versions.iter().cloned()))
+        .to_owned();
+    db.execute(db.get_database_backend().build(&stmt)).await?;
+
+    Ok(())
+}
+
+/// Verifies every applied migration against its source without recording
+/// anything.
+pub async fn verify(db: &DatabaseConnection) -> Result<Vec<(String, ChecksumStatus)>, DbErr> {
+    let installed = SchemaManager::new(db)
+        .has_table(MigrationChecksum::Table.to_string())
+        .await?;
+    let recorded = if installed {
+        recorded(db).await?
+    } else {
+        Vec::new()
+    };
+
+    let mut results = Vec::new();
+    for migration in Migrator::get_applied_migrations(db).await? {
+        let name = migration.name().to_string();
+        let current = source_checksum(&name)
+            .ok_or_else(|| DbErr::Custom(format!("No source registered for '{}'", name)))?;
+
+        let status = match recorded.iter().find(|(v, _)| *v == name) {
+            None => ChecksumStatus::Unrecorded,
+            Some((_, checksum)) if *checksum == current => ChecksumStatus::Ok,
+            Some((_, checksum)) => ChecksumStatus::Modified {
+                recorded: checksum.clone(),
+                current,
+            },
+        };
+        results.push((name, status));
+    }
+
+    Ok(results)
+}
+
+async fn recorded<C: ConnectionTrait>(db: &C) -> Result<Vec<(String, String)>, DbErr> {
+    let stmt = Query::select()
+        .columns([MigrationChecksum::Version, MigrationChecksum::Checksum])
+        .from(MigrationChecksum::Table)
+        .to_owned();
+
+    db.query_all(db.get_database_backend().build(&stmt))
+        .await?
+        .iter()
+        .map(|row| Ok((row.try_get("", "version")?, row.try_get("", "checksum")?)))
+        .collect()
+}
+
+#[derive(Iden)]
+enum MigrationChecksum {
+    #[iden = "seaql_migration_checksums"]
+    Table,
+    Version,
+    Checksum,
+    RecordedAt,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_migration_has_source() {
+        for migration in Migrator::migrations() {
+            let checksum = source_checksum(migration.name())
+                .unwrap_or_else(|| panic!("No source for {}", migration.name()));
+            assert_eq!(checksum.len(), 64);
+        }
+        assert_eq!(SOURCES.len(), Migrator::migrations().len());
+    }
+}
@@ -1,11 +1,82 @@
 //! MISO LIMS Database Migrations
 //!
 //! Uses SeaORM migration framework to manage database schema.
+//!
+//! Besides the migrations themselves, this crate provides dry-run planning
+//! ([`plan`]) and checksum verification of applied migrations
+//! ([`checksum`]) for the `miso-migrate` CLI.
 
 pub use sea_orm_migration::prelude::*;
 
+pub mod checksum;
+pub mod plan;
+
 mod m20241215_000001_create_project;
 mod m20241215_000002_create_sample;
+mod m20241215_000003_create_run_library_metrics;
+mod m20241215_000004_create_run_qc_report;
+mod m20241215_000005_create_export_template;
+mod m20241215_000006_create_reconciliation_report;
+mod m20241215_000007_add_sample_version;
+mod m20241215_000008_create_change_log;
+mod m20241215_000009_create_instrument_model;
+mod m20241215_000010_create_data_location;
+mod m20241215_000011_add_data_location_retention;
+mod m20241215_000012_add_instrument_model_run_duration;
+mod m20241215_000013_create_instrument_event;
+mod m20241215_000014_create_library;
+mod m20241215_000015_create_pool;
+mod m20241215_000016_create_attribute_definition;
+mod m20241215_000017_add_sample_attributes;
+mod m20241215_000018_create_run;
+mod m20241215_000019_create_storage_box;
+mod m20241215_000020_create_user;
+mod m20241215_000021_add_sample_details;
+mod m20241215_000022_create_sequencer;
+mod m20241215_000023_add_pool_container_model;
+mod m20241215_000024_create_panel;
+mod m20241215_000025_create_reference_genome;
+mod m20241215_000026_create_api_key;
+mod m20241215_000027_create_possible_duplicate;
+mod m20241215_000028_create_audit_log;
+mod m20241215_000029_create_erasure;
+mod m20241215_000030_create_qc_result;
+mod m20241215_000031_create_consent;
+mod m20241215_000032_create_storage_audit;
+mod m20241215_000033_add_run_ready_to_load;
+mod m20241215_000034_add_run_partition_yield;
+mod m20241215_000035_create_index_set;
+mod m20241215_000036_create_event_store;
+mod m20241215_000037_add_log_hash_chain;
+mod m20241215_000038_add_project_lock;
+mod m20241215_000039_create_sample_list;
+mod m20241215_000040_create_log_archive;
+mod m20241215_000041_create_label_print;
+mod m20241215_000042_add_sample_library_mass;
+mod m20241215_000043_create_library_aliquot;
+mod m20241215_000044_create_label_usage;
+mod m20241215_000045_create_workset;
+mod m20241215_000046_create_run_interruption;
+mod m20241215_000047_create_transfer;
+mod m20241215_000048_add_run_estimated_completion;
+mod m20241215_000049_create_requisition;
+mod m20241215_000050_create_storage_unit;
+mod m20241215_000051_create_sample_component;
+mod m20241215_000052_add_control_type;
+mod m20241215_000053_create_extraction_batch;
+mod m20241215_000054_create_reagent_lot;
+mod m20241215_000055_create_attachment;
+mod m20241215_000056_add_transfer_shipment;
+mod m20241215_000057_create_maintenance_window;
+mod m20241215_000058_create_api_usage;
+mod m20241215_000059_create_project_member;
+mod m20241215_000060_create_delivery;
+mod m20241215_000061_create_subproject;
+mod m20241215_000062_create_archive_rule;
+mod m20241215_000063_create_item_location;
+mod m20241215_000064_add_change_log_kept_hashes;
+mod m20241215_000065_add_stored_event_erased_hashes;
+mod m20241215_000066_add_patterned_containers;
 
 pub struct Migrator;
 
@@ -15,6 +86,70 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20241215_000001_create_project::Migration),
             Box::new(m20241215_000002_create_sample::Migration),
+            Box::new(m20241215_000003_create_run_library_metrics::Migration),
+            Box::new(m20241215_000004_create_run_qc_report::Migration),
+            Box::new(m20241215_000005_create_export_template::Migration),
+            Box::new(m20241215_000006_create_reconciliation_report::Migration),
+            Box::new(m20241215_000007_add_sample_version::Migration),
+            Box::new(m20241215_000008_create_change_log::Migration),
+            Box::new(m20241215_000009_create_instrument_model::Migration),
+            Box::new(m20241215_000010_create_data_location::Migration),
+            Box::new(m20241215_000011_add_data_location_retention::Migration),
+            Box::new(m20241215_000012_add_instrument_model_run_duration::Migration),
+            Box::new(m20241215_000013_create_instrument_event::Migration),
+            Box::new(m20241215_000014_create_library::Migration),
+            Box::new(m20241215_000015_create_pool::Migration),
+            Box::new(m20241215_000016_create_attribute_definition::Migration),
+            Box::new(m20241215_000017_add_sample_attributes::Migration),
+            Box::new(m20241215_000018_create_run::Migration),
+            Box::new(m20241215_000019_create_storage_box::Migration),
+            Box::new(m20241215_000020_create_user::Migration),
+            Box::new(m20241215_000021_add_sample_details::Migration),
+            Box::new(m20241215_000022_create_sequencer::Migration),
+            Box::new(m20241215_000023_add_pool_container_model::Migration),
+            Box::new(m20241215_000024_create_panel::Migration),
+            Box::new(m20241215_000025_create_reference_genome::Migration),
+            Box::new(m20241215_000026_create_api_key::Migration),
+            Box::new(m20241215_000027_create_possible_duplicate::Migration),
+            Box::new(m20241215_000028_create_audit_log::Migration),
+            Box::new(m20241215_000029_create_erasure::Migration),
+            Box::new(m20241215_000030_create_qc_result::Migration),
+            Box::new(m20241215_000031_create_consent::Migration),
+            Box::new(m20241215_000032_create_storage_audit::Migration),
+            Box::new(m20241215_000033_add_run_ready_to_load::Migration),
+            Box::new(m20241215_000034_add_run_partition_yield::Migration),
+            Box::new(m20241215_000035_create_index_set::Migration),
+            Box::new(m20241215_000036_create_event_store::Migration),
+            Box::new(m20241215_000037_add_log_hash_chain::Migration),
+            Box::new(m20241215_000038_add_project_lock::Migration),
+            Box::new(m20241215_000039_create_sample_list::Migration),
+            Box::new(m20241215_000040_create_log_archive::Migration),
+            Box::new(m20241215_000041_create_label_print::Migration),
+            Box::new(m20241215_000042_add_sample_library_mass::Migration),
+            Box::new(m20241215_000043_create_library_aliquot::Migration),
+            Box::new(m20241215_000044_create_label_usage::Migration),
+            Box::new(m20241215_000045_create_workset::Migration),
+            Box::new(m20241215_000046_create_run_interruption::Migration),
+            Box::new(m20241215_000047_create_transfer::Migration),
+            Box::new(m20241215_000048_add_run_estimated_completion::Migration),
+            Box::new(m20241215_000049_create_requisition::Migration),
+            Box::new(m20241215_000050_create_storage_unit::Migration),
+            Box::new(m20241215_000051_create_sample_component::Migration),
+            Box::new(m20241215_000052_add_control_type::Migration),
+            Box::new(m20241215_000053_create_extraction_batch::Migration),
+            Box::new(m20241215_000054_create_reagent_lot::Migration),
+            Box::new(m20241215_000055_create_attachment::Migration),
+            Box::new(m20241215_000056_add_transfer_shipment::Migration),
+            Box::new(m20241215_000057_create_maintenance_window::Migration),
+            Box::new(m20241215_000058_create_api_usage::Migration),
+            Box::new(m20241215_000059_create_project_member::Migration),
+            Box::new(m20241215_000060_create_delivery::Migration),
+            Box::new(m20241215_000061_create_subproject::Migration),
+            Box::new(m20241215_000062_create_archive_rule::Migration),
+            Box::new(m20241215_000063_create_item_location::Migration),
+            Box::new(m20241215_000064_add_change_log_kept_hashes::Migration),
+            Box::new(m20241215_000065_add_stored_event_erased_hashes::Migration),
+            Box::new(m20241215_000066_add_patterned_containers::Migration),
         ]
     }
 }
@@ -0,0 +1,114 @@
+//! Create the run_library_metrics table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunLibraryMetrics::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::RunId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::PartitionNumber)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::LibraryId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::Reads)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::YieldBases)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::Q30Percent)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RunLibraryMetrics::IndexHoppingPercent).double())
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::SubmittedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunLibraryMetrics::SubmittedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One row per library per lane; resubmissions replace the row
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_library_metrics_slot")
+                    .table(RunLibraryMetrics::Table)
+                    .col(RunLibraryMetrics::RunId)
+                    .col(RunLibraryMetrics::PartitionNumber)
+                    .col(RunLibraryMetrics::LibraryId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_library_metrics_library")
+                    .table(RunLibraryMetrics::Table)
+                    .col(RunLibraryMetrics::LibraryId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RunLibraryMetrics::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum RunLibraryMetrics {
+    Table,
+    Id,
+    RunId,
+    PartitionNumber,
+    LibraryId,
+    Reads,
+    YieldBases,
+    Q30Percent,
+    IndexHoppingPercent,
+    SubmittedBy,
+    SubmittedAt,
+}
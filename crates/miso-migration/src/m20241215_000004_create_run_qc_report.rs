@@ -0,0 +1,130 @@
+//! Create the run_qc_report and run_qc_sample_metrics tables.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunQcReport::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RunQcReport::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RunQcReport::RunId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(RunQcReport::ReportUrl).string_len(1024))
+                    .col(ColumnDef::new(RunQcReport::FileName).string_len(255))
+                    .col(ColumnDef::new(RunQcReport::MultiqcVersion).string_len(50))
+                    .col(
+                        ColumnDef::new(RunQcReport::AttachedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunQcReport::AttachedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunQcSampleMetrics::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RunQcSampleMetrics::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RunQcSampleMetrics::ReportId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RunQcSampleMetrics::SampleName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RunQcSampleMetrics::TotalSequences).double())
+                    .col(ColumnDef::new(RunQcSampleMetrics::PercentGc).double())
+                    .col(ColumnDef::new(RunQcSampleMetrics::PercentDuplicates).double())
+                    .col(ColumnDef::new(RunQcSampleMetrics::AvgSequenceLength).double())
+                    .col(ColumnDef::new(RunQcSampleMetrics::PercentAligned).double())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_run_qc_sample_metrics_report")
+                            .from(RunQcSampleMetrics::Table, RunQcSampleMetrics::ReportId)
+                            .to(RunQcReport::Table, RunQcReport::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_qc_sample_metrics_report")
+                    .table(RunQcSampleMetrics::Table)
+                    .col(RunQcSampleMetrics::ReportId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RunQcSampleMetrics::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RunQcReport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum RunQcReport {
+    Table,
+    Id,
+    RunId,
+    ReportUrl,
+    FileName,
+    MultiqcVersion,
+    AttachedBy,
+    AttachedAt,
+}
+
+#[derive(Iden)]
+pub enum RunQcSampleMetrics {
+    Table,
+    Id,
+    ReportId,
+    SampleName,
+    TotalSequences,
+    PercentGc,
+    PercentDuplicates,
+    AvgSequenceLength,
+    PercentAligned,
+}
@@ -0,0 +1,88 @@
+//! Create the export_template table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExportTemplate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExportTemplate::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::EntityType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExportTemplate::Columns).json().not_null())
+                    .col(
+                        ColumnDef::new(ExportTemplate::Delimiter)
+                            .string_len(20)
+                            .not_null()
+                            .default("comma"),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::IncludeHeader)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::CreatedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ExportTemplate::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExportTemplate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ExportTemplate {
+    Table,
+    Id,
+    Name,
+    EntityType,
+    Columns,
+    Delimiter,
+    IncludeHeader,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
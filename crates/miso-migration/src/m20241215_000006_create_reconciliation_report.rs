@@ -0,0 +1,85 @@
+//! Create the reconciliation_report table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReconciliationReport::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReconciliationReport::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::BoxesChecked)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::ItemsChecked)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::DiscrepancyCount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::Discrepancies)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::StartedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::CompletedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reconciliation_report_completed_at")
+                    .table(ReconciliationReport::Table)
+                    .col(ReconciliationReport::CompletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReconciliationReport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ReconciliationReport {
+    Table,
+    Id,
+    BoxesChecked,
+    ItemsChecked,
+    DiscrepancyCount,
+    Discrepancies,
+    StartedAt,
+    CompletedAt,
+}
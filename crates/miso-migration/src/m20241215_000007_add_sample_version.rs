@@ -0,0 +1,43 @@
+//! Add an optimistic concurrency version to the sample table.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(
+                        ColumnDef::new(SampleVersion::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .drop_column(SampleVersion::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SampleVersion {
+    Version,
+}
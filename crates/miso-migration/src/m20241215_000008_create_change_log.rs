@@ -0,0 +1,70 @@
+//! Create the change_log table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChangeLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChangeLog::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChangeLog::EntityType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChangeLog::EntityId).integer().not_null())
+                    .col(ColumnDef::new(ChangeLog::Summary).text().not_null())
+                    .col(ColumnDef::new(ChangeLog::Reason).text())
+                    .col(
+                        ColumnDef::new(ChangeLog::ChangedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChangeLog::ChangedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_change_log_entity")
+                    .table(ChangeLog::Table)
+                    .col(ChangeLog::EntityType)
+                    .col(ChangeLog::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChangeLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ChangeLog {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Summary,
+    Reason,
+    ChangedBy,
+    ChangedAt,
+}
@@ -0,0 +1,138 @@
+//! Create the instrument_model table and seed it with the models that were
+//! previously hard-coded.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// (platform, name, partitions, container models, max read length)
+const SEED_MODELS: &[(&str, &str, i32, &str, Option<i32>)] = &[
+    (
+        "illumina",
+        "NovaSeq 6000",
+        4,
+        r#"["SP Flow Cell","S1 Flow Cell","S2 Flow Cell","S4 Flow Cell"]"#,
+        Some(250),
+    ),
+    (
+        "illumina",
+        "NovaSeq X",
+        2,
+        r#"["1.5B Flow Cell","10B Flow Cell","25B Flow Cell"]"#,
+        Some(150),
+    ),
+    (
+        "illumina",
+        "MiSeq",
+        1,
+        r#"["MiSeq v2 Flow Cell","MiSeq v3 Flow Cell"]"#,
+        Some(300),
+    ),
+    (
+        "illumina",
+        "NextSeq 2000",
+        1,
+        r#"["P1 Flow Cell","P2 Flow Cell","P3 Flow Cell"]"#,
+        Some(300),
+    ),
+    (
+        "oxford_nanopore",
+        "PromethION 48",
+        48,
+        r#"["PromethION Flow Cell"]"#,
+        None,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InstrumentModel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(InstrumentModel::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentModel::Platform)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentModel::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentModel::Partitions)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InstrumentModel::Description).text())
+                    .col(
+                        ColumnDef::new(InstrumentModel::ContainerModels)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentModel::Chemistries)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InstrumentModel::MaxReadLength).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut seed = Query::insert()
+            .into_table(InstrumentModel::Table)
+            .columns([
+                InstrumentModel::Platform,
+                InstrumentModel::Name,
+                InstrumentModel::Partitions,
+                InstrumentModel::ContainerModels,
+                InstrumentModel::Chemistries,
+                InstrumentModel::MaxReadLength,
+            ])
+            .to_owned();
+        for (platform, name, partitions, containers, max_read_length) in SEED_MODELS {
+            seed.values_panic([
+                (*platform).into(),
+                (*name).into(),
+                (*partitions).into(),
+                (*containers).into(),
+                "[]".into(),
+                (*max_read_length).into(),
+            ]);
+        }
+
+        manager.exec_stmt(seed).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(InstrumentModel::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum InstrumentModel {
+    Table,
+    Id,
+    Platform,
+    Name,
+    Partitions,
+    Description,
+    ContainerModels,
+    Chemistries,
+    MaxReadLength,
+}
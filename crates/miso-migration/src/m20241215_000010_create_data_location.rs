@@ -0,0 +1,78 @@
+//! Create the data_location table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataLocation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DataLocation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DataLocation::RunId).integer().not_null())
+                    .col(ColumnDef::new(DataLocation::Lane).integer())
+                    .col(ColumnDef::new(DataLocation::Uri).text().not_null())
+                    .col(ColumnDef::new(DataLocation::ChecksumManifest).text())
+                    .col(
+                        ColumnDef::new(DataLocation::RetentionClass)
+                            .string_len(20)
+                            .not_null()
+                            .default("standard"),
+                    )
+                    .col(ColumnDef::new(DataLocation::SizeBytes).big_integer())
+                    .col(
+                        ColumnDef::new(DataLocation::RegisteredBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DataLocation::RegisteredAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_data_location_run")
+                    .table(DataLocation::Table)
+                    .col(DataLocation::RunId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DataLocation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum DataLocation {
+    Table,
+    Id,
+    RunId,
+    Lane,
+    Uri,
+    ChecksumManifest,
+    RetentionClass,
+    SizeBytes,
+    RegisteredBy,
+    RegisteredAt,
+}
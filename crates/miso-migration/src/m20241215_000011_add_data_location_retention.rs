@@ -0,0 +1,55 @@
+//! Add usage attribution and purge tracking to the data_location table.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000010_create_data_location::DataLocation;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataLocation::Table)
+                    .add_column(ColumnDef::new(DataLocationRetention::Platform).string_len(50))
+                    .add_column(ColumnDef::new(DataLocationRetention::ProjectIds).json())
+                    .add_column(
+                        ColumnDef::new(DataLocationRetention::PurgeRequestedBy).string_len(255),
+                    )
+                    .add_column(ColumnDef::new(DataLocationRetention::PurgeRequestedAt).timestamp())
+                    .add_column(ColumnDef::new(DataLocationRetention::PurgedBy).string_len(255))
+                    .add_column(ColumnDef::new(DataLocationRetention::PurgedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataLocation::Table)
+                    .drop_column(DataLocationRetention::Platform)
+                    .drop_column(DataLocationRetention::ProjectIds)
+                    .drop_column(DataLocationRetention::PurgeRequestedBy)
+                    .drop_column(DataLocationRetention::PurgeRequestedAt)
+                    .drop_column(DataLocationRetention::PurgedBy)
+                    .drop_column(DataLocationRetention::PurgedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DataLocationRetention {
+    Platform,
+    ProjectIds,
+    PurgeRequestedBy,
+    PurgeRequestedAt,
+    PurgedBy,
+    PurgedAt,
+}
@@ -0,0 +1,65 @@
+//! Add expected run durations to the instrument_model table, used to flag
+//! runs that are taking too long.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000009_create_instrument_model::InstrumentModel;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// (name, expected run hours) for the seeded models, from the longest
+/// standard run each supports.
+const SEED_DURATIONS: &[(&str, i32)] = &[
+    ("NovaSeq 6000", 44),
+    ("NovaSeq X", 48),
+    ("MiSeq", 56),
+    ("NextSeq 2000", 48),
+    ("PromethION 48", 72),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .add_column(
+                        ColumnDef::new(InstrumentModelRunDuration::ExpectedRunHours).integer(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, hours) in SEED_DURATIONS {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(InstrumentModel::Table)
+                        .value(InstrumentModelRunDuration::ExpectedRunHours, *hours)
+                        .and_where(Expr::col(InstrumentModel::Name).eq(*name))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .drop_column(InstrumentModelRunDuration::ExpectedRunHours)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum InstrumentModelRunDuration {
+    ExpectedRunHours,
+}
@@ -0,0 +1,82 @@
+//! Create the instrument_event table.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InstrumentEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(InstrumentEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(InstrumentEvent::RunId).integer().not_null())
+                    .col(
+                        ColumnDef::new(InstrumentEvent::Severity)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentEvent::Source)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InstrumentEvent::Code).string_len(255))
+                    .col(ColumnDef::new(InstrumentEvent::Message).text().not_null())
+                    .col(ColumnDef::new(InstrumentEvent::OccurredAt).timestamp())
+                    .col(
+                        ColumnDef::new(InstrumentEvent::IngestedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InstrumentEvent::IngestedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_instrument_event_run_source")
+                    .table(InstrumentEvent::Table)
+                    .col(InstrumentEvent::RunId)
+                    .col(InstrumentEvent::Source)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(InstrumentEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum InstrumentEvent {
+    Table,
+    Id,
+    RunId,
+    Severity,
+    Source,
+    Code,
+    Message,
+    OccurredAt,
+    IngestedBy,
+    IngestedAt,
+}
@@ -0,0 +1,163 @@
+//! Create the library table.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Library::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Library::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Library::Name).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Library::Barcode)
+                            .string_len(50)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Library::SampleId).integer().not_null())
+                    .col(ColumnDef::new(Library::ProjectId).integer().not_null())
+                    .col(ColumnDef::new(Library::Description).text())
+                    .col(ColumnDef::new(Library::Design).string_len(50).not_null())
+                    .col(ColumnDef::new(Library::CustomDesign).string_len(255))
+                    .col(
+                        ColumnDef::new(Library::LibraryType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Library::Platform).string_len(50).not_null())
+                    .col(ColumnDef::new(Library::KitName).string_len(255))
+                    .col(ColumnDef::new(Library::IndexName).string_len(100))
+                    .col(ColumnDef::new(Library::IndexFamily).string_len(20))
+                    .col(ColumnDef::new(Library::I7Sequence).string_len(50))
+                    .col(ColumnDef::new(Library::I5Sequence).string_len(50))
+                    .col(ColumnDef::new(Library::InsertSize).integer())
+                    .col(ColumnDef::new(Library::Volume).decimal_len(10, 2))
+                    .col(ColumnDef::new(Library::Concentration).decimal_len(10, 2))
+                    .col(
+                        ColumnDef::new(Library::QcStatus)
+                            .string_len(20)
+                            .not_null()
+                            .default("not_ready"),
+                    )
+                    .col(ColumnDef::new(Library::PcrCycles).integer())
+                    .col(
+                        ColumnDef::new(Library::LowQuality)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(Library::CreatedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Library::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Library::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Library::Archived)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_library_sample")
+                            .from(Library::Table, Library::SampleId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_library_project")
+                            .from(Library::Table, Library::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_library_sample")
+                    .table(Library::Table)
+                    .col(Library::SampleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_library_project")
+                    .table(Library::Table)
+                    .col(Library::ProjectId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Library::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+#[allow(clippy::enum_variant_names)]
+pub enum Library {
+    Table,
+    Id,
+    Name,
+    Barcode,
+    SampleId,
+    ProjectId,
+    Description,
+    Design,
+    CustomDesign,
+    LibraryType,
+    Platform,
+    KitName,
+    IndexName,
+    IndexFamily,
+    I7Sequence,
+    I5Sequence,
+    InsertSize,
+    Volume,
+    Concentration,
+    QcStatus,
+    PcrCycles,
+    LowQuality,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+    Archived,
+}
@@ -0,0 +1,152 @@
+//! Create the pool table and the pool_element table listing the library
+//! aliquots in each pool.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Pool::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Pool::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Pool::Name).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Pool::Barcode)
+                            .string_len(50)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Pool::Description).text())
+                    .col(ColumnDef::new(Pool::Concentration).decimal_len(10, 2))
+                    .col(ColumnDef::new(Pool::ConcentrationUnit).string_len(20))
+                    .col(ColumnDef::new(Pool::Volume).decimal_len(10, 2))
+                    .col(
+                        ColumnDef::new(Pool::QcStatus)
+                            .string_len(20)
+                            .not_null()
+                            .default("not_ready"),
+                    )
+                    .col(ColumnDef::new(Pool::Platform).string_len(50).not_null())
+                    .col(
+                        ColumnDef::new(Pool::Sequenced)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Pool::CreatedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Pool::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Pool::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PoolElement::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PoolElement::PoolId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PoolElement::LibraryAliquotId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PoolElement::LibraryId).integer().not_null())
+                    .col(ColumnDef::new(PoolElement::Volume).decimal_len(10, 2))
+                    .col(ColumnDef::new(PoolElement::Proportion).double())
+                    .primary_key(
+                        Index::create()
+                            .col(PoolElement::PoolId)
+                            .col(PoolElement::LibraryAliquotId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_pool_element_pool")
+                            .from(PoolElement::Table, PoolElement::PoolId)
+                            .to(Pool::Table, Pool::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_pool_element_library")
+                            .from(PoolElement::Table, PoolElement::LibraryId)
+                            .to(Library::Table, Library::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_pool_element_library")
+                    .table(PoolElement::Table)
+                    .col(PoolElement::LibraryId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PoolElement::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Pool::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Pool {
+    Table,
+    Id,
+    Name,
+    Barcode,
+    Description,
+    Concentration,
+    ConcentrationUnit,
+    Volume,
+    QcStatus,
+    Platform,
+    Sequenced,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum PoolElement {
+    Table,
+    PoolId,
+    LibraryAliquotId,
+    LibraryId,
+    Volume,
+    Proportion,
+}
@@ -0,0 +1,114 @@
+//! Create the attribute_definition table for admin-defined custom
+//! attributes.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AttributeDefinition::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Target)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AttributeDefinition::ProjectId).integer())
+                    .col(
+                        ColumnDef::new(AttributeDefinition::AttributeKey)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Label)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttributeDefinition::ValueType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Required)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Options)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AttributeDefinition::MinValue).double())
+                    .col(ColumnDef::new(AttributeDefinition::MaxValue).double())
+                    .col(ColumnDef::new(AttributeDefinition::MaxLength).integer())
+                    .col(ColumnDef::new(AttributeDefinition::HelpText).text())
+                    .col(
+                        ColumnDef::new(AttributeDefinition::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attribute_definition_project")
+                            .from(AttributeDefinition::Table, AttributeDefinition::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attribute_definition_target_project")
+                    .table(AttributeDefinition::Table)
+                    .col(AttributeDefinition::Target)
+                    .col(AttributeDefinition::ProjectId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AttributeDefinition::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum AttributeDefinition {
+    Table,
+    Id,
+    Target,
+    ProjectId,
+    AttributeKey,
+    Label,
+    ValueType,
+    Required,
+    Options,
+    MinValue,
+    MaxValue,
+    MaxLength,
+    HelpText,
+    Position,
+}
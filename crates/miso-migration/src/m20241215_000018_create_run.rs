@@ -0,0 +1,187 @@
+//! Create the run table and the run_partition table recording each lane's
+//! pool and instrument metrics.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000015_create_pool::Pool;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Run::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Run::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Run::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Run::Alias).string_len(255))
+                    .col(ColumnDef::new(Run::SequencerId).integer().not_null())
+                    .col(ColumnDef::new(Run::ContainerBarcode).string_len(255))
+                    .col(
+                        ColumnDef::new(Run::Status)
+                            .string_len(20)
+                            .not_null()
+                            .default("unknown"),
+                    )
+                    .col(ColumnDef::new(Run::DataPath).text())
+                    .col(ColumnDef::new(Run::OutputPath).text())
+                    .col(ColumnDef::new(Run::StartedAt).timestamp())
+                    .col(ColumnDef::new(Run::CompletedAt).timestamp())
+                    .col(ColumnDef::new(Run::OverdueSince).timestamp())
+                    .col(ColumnDef::new(Run::QcPassed).boolean())
+                    .col(ColumnDef::new(Run::QcReviewer).string_len(255))
+                    .col(ColumnDef::new(Run::QcNote).text())
+                    .col(ColumnDef::new(Run::QcSignedOffAt).timestamp())
+                    .col(ColumnDef::new(Run::ReadLength).string_len(50))
+                    .col(ColumnDef::new(Run::Description).text())
+                    .col(ColumnDef::new(Run::CreatedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Run::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Run::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_status")
+                    .table(Run::Table)
+                    .col(Run::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_sequencer")
+                    .table(Run::Table)
+                    .col(Run::SequencerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunPartition::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RunPartition::RunId).integer().not_null())
+                    .col(
+                        ColumnDef::new(RunPartition::PartitionNumber)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RunPartition::PoolId).integer())
+                    .col(ColumnDef::new(RunPartition::LoadingConcentration).double())
+                    .col(ColumnDef::new(RunPartition::ClusterDensity).double())
+                    .col(ColumnDef::new(RunPartition::PassFilterPercent).double())
+                    .col(ColumnDef::new(RunPartition::Q30Percent).double())
+                    .col(ColumnDef::new(RunPartition::Notes).text())
+                    .primary_key(
+                        Index::create()
+                            .col(RunPartition::RunId)
+                            .col(RunPartition::PartitionNumber),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_run_partition_run")
+                            .from(RunPartition::Table, RunPartition::RunId)
+                            .to(Run::Table, Run::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_run_partition_pool")
+                            .from(RunPartition::Table, RunPartition::PoolId)
+                            .to(Pool::Table, Pool::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_run_partition_pool")
+                    .table(RunPartition::Table)
+                    .col(RunPartition::PoolId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RunPartition::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Run::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Run {
+    Table,
+    Id,
+    Name,
+    Alias,
+    SequencerId,
+    ContainerBarcode,
+    Status,
+    DataPath,
+    OutputPath,
+    StartedAt,
+    CompletedAt,
+    OverdueSince,
+    QcPassed,
+    QcReviewer,
+    QcNote,
+    QcSignedOffAt,
+    ReadLength,
+    Description,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum RunPartition {
+    Table,
+    RunId,
+    PartitionNumber,
+    PoolId,
+    LoadingConcentration,
+    ClusterDensity,
+    PassFilterPercent,
+    Q30Percent,
+    Notes,
+}
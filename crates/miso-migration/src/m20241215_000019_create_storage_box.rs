@@ -0,0 +1,155 @@
+//! Create the storage_box table and the box_position table recording what
+//! is stored where.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StorageBox::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StorageBox::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StorageBox::Name).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(StorageBox::Barcode)
+                            .string_len(50)
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(StorageBox::Rows).small_integer().not_null())
+                    .col(
+                        ColumnDef::new(StorageBox::Columns)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StorageBox::Freezer).string_len(255))
+                    .col(ColumnDef::new(StorageBox::Shelf).string_len(255))
+                    .col(ColumnDef::new(StorageBox::Rack).string_len(255))
+                    .col(ColumnDef::new(StorageBox::Temperature).small_integer())
+                    .col(
+                        ColumnDef::new(StorageBox::StorableType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StorageBox::Description).text())
+                    .col(
+                        ColumnDef::new(StorageBox::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(StorageBox::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_storage_box_freezer")
+                    .table(StorageBox::Table)
+                    .col(StorageBox::Freezer)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BoxPosition::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BoxPosition::BoxId).integer().not_null())
+                    .col(
+                        ColumnDef::new(BoxPosition::Position)
+                            .string_len(4)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BoxPosition::ItemType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(BoxPosition::ItemId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(BoxPosition::BoxId)
+                            .col(BoxPosition::Position),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_box_position_box")
+                            .from(BoxPosition::Table, BoxPosition::BoxId)
+                            .to(StorageBox::Table, StorageBox::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Not unique: moving an item between boxes saves the target box
+        // first, so the item is briefly recorded in both.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_box_position_item")
+                    .table(BoxPosition::Table)
+                    .col(BoxPosition::ItemType)
+                    .col(BoxPosition::ItemId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BoxPosition::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(StorageBox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum StorageBox {
+    Table,
+    Id,
+    Name,
+    Barcode,
+    Rows,
+    Columns,
+    Freezer,
+    Shelf,
+    Rack,
+    Temperature,
+    StorableType,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum BoxPosition {
+    Table,
+    BoxId,
+    Position,
+    ItemType,
+    ItemId,
+}
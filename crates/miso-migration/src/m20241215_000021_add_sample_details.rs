@@ -0,0 +1,54 @@
+//! Add the plain sample type and remaining detailed sample fields to the
+//! sample table.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(ColumnDef::new(SampleDetails::SampleType).string_len(100))
+                    .add_column(ColumnDef::new(SampleDetails::TimePoint).string_len(100))
+                    .add_column(ColumnDef::new(SampleDetails::GroupId).string_len(100))
+                    .add_column(ColumnDef::new(SampleDetails::GroupDescription).text())
+                    .add_column(ColumnDef::new(SampleDetails::Passage).integer())
+                    .add_column(ColumnDef::new(SampleDetails::Purpose).string_len(100))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .drop_column(SampleDetails::SampleType)
+                    .drop_column(SampleDetails::TimePoint)
+                    .drop_column(SampleDetails::GroupId)
+                    .drop_column(SampleDetails::GroupDescription)
+                    .drop_column(SampleDetails::Passage)
+                    .drop_column(SampleDetails::Purpose)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SampleDetails {
+    SampleType,
+    TimePoint,
+    GroupId,
+    GroupDescription,
+    Passage,
+    Purpose,
+}
@@ -0,0 +1,103 @@
+//! Create the sequencer table recording each instrument, its model and
+//! whether it can take a run.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000009_create_instrument_model::InstrumentModel;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Sequencer::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Sequencer::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Sequencer::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Sequencer::SerialNumber).string_len(255))
+                    .col(
+                        ColumnDef::new(Sequencer::InstrumentModelId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Sequencer::Status)
+                            .string_len(20)
+                            .not_null()
+                            .default("available"),
+                    )
+                    .col(ColumnDef::new(Sequencer::Location).string_len(255))
+                    .col(ColumnDef::new(Sequencer::IpAddress).string_len(255))
+                    .col(ColumnDef::new(Sequencer::DateCommissioned).timestamp())
+                    .col(ColumnDef::new(Sequencer::LastServiceDate).timestamp())
+                    .col(
+                        ColumnDef::new(Sequencer::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Sequencer::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sequencer_instrument_model")
+                            .from(Sequencer::Table, Sequencer::InstrumentModelId)
+                            .to(InstrumentModel::Table, InstrumentModel::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sequencer_status")
+                    .table(Sequencer::Table)
+                    .col(Sequencer::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Sequencer::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Sequencer {
+    Table,
+    Id,
+    Name,
+    SerialNumber,
+    InstrumentModelId,
+    Status,
+    Location,
+    IpAddress,
+    DateCommissioned,
+    LastServiceDate,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,39 @@
+//! Add the container model a pool is made for to the pool table, so pools
+//! can be held to that container's plexity limit.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000015_create_pool::Pool;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Pool::Table)
+                    .add_column(ColumnDef::new(PoolContainer::ContainerModel).string_len(255))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Pool::Table)
+                    .drop_column(PoolContainer::ContainerModel)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PoolContainer {
+    ContainerModel,
+}
@@ -0,0 +1,126 @@
+//! Create the panel table, one row per version of a targeted panel with its
+//! BED file, and let libraries reference the panel version they captured.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Panel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Panel::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Panel::Name).string_len(255).not_null())
+                    .col(ColumnDef::new(Panel::Version).integer().not_null())
+                    .col(ColumnDef::new(Panel::GenomeBuild).string_len(50).not_null())
+                    .col(ColumnDef::new(Panel::Description).text())
+                    .col(
+                        ColumnDef::new(Panel::BedFileName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Panel::BedContents).text().not_null())
+                    .col(ColumnDef::new(Panel::IntervalCount).integer().not_null())
+                    .col(ColumnDef::new(Panel::TargetBases).big_integer().not_null())
+                    .col(ColumnDef::new(Panel::CreatedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Panel::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_panel_name_version")
+                    .table(Panel::Table)
+                    .col(Panel::Name)
+                    .col(Panel::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column(ColumnDef::new(LibraryPanel::PanelId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_library_panel")
+                    .from(Library::Table, LibraryPanel::PanelId)
+                    .to(Panel::Table, Panel::Id)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_library_panel")
+                    .table(Library::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(LibraryPanel::PanelId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Panel::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Panel {
+    Table,
+    Id,
+    Name,
+    Version,
+    GenomeBuild,
+    Description,
+    BedFileName,
+    BedContents,
+    IntervalCount,
+    TargetBases,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum LibraryPanel {
+    PanelId,
+}
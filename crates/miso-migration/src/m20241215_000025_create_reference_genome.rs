@@ -0,0 +1,161 @@
+//! Create the reference genome registry, and let projects and libraries
+//! name the genome their data is analysed against.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReferenceGenome::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReferenceGenome::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReferenceGenome::Name)
+                            .string_len(50)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReferenceGenome::Species)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReferenceGenome::FastaUri).string_len(1024))
+                    .col(ColumnDef::new(ReferenceGenome::Description).text())
+                    .col(
+                        ColumnDef::new(ReferenceGenome::CreatedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReferenceGenome::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ReferenceGenome::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .add_column(ColumnDef::new(GenomeReference::ReferenceGenomeId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_project_reference_genome")
+                    .from(Project::Table, GenomeReference::ReferenceGenomeId)
+                    .to(ReferenceGenome::Table, ReferenceGenome::Id)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column(ColumnDef::new(GenomeReference::ReferenceGenomeId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_library_reference_genome")
+                    .from(Library::Table, GenomeReference::ReferenceGenomeId)
+                    .to(ReferenceGenome::Table, ReferenceGenome::Id)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_library_reference_genome")
+                    .table(Library::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(GenomeReference::ReferenceGenomeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_project_reference_genome")
+                    .table(Project::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .drop_column(GenomeReference::ReferenceGenomeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ReferenceGenome::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ReferenceGenome {
+    Table,
+    Id,
+    Name,
+    Species,
+    FastaUri,
+    Description,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum GenomeReference {
+    ReferenceGenomeId,
+}
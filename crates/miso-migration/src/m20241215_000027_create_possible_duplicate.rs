@@ -0,0 +1,117 @@
+//! Create the possible_duplicate table.
+//!
+//! Rows record identities created although their external names looked
+//! like existing identities', until a reviewer resolves them.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PossibleDuplicate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::IdentityId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::MatchId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::ExternalName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::MatchedName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::Distance)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::ConfirmedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PossibleDuplicate::ConfirmedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(PossibleDuplicate::Resolution).string_len(20))
+                    .col(ColumnDef::new(PossibleDuplicate::ResolvedBy).string_len(255))
+                    .col(ColumnDef::new(PossibleDuplicate::ResolvedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_possible_duplicate_identity")
+                            .from(PossibleDuplicate::Table, PossibleDuplicate::IdentityId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_possible_duplicate_match")
+                            .from(PossibleDuplicate::Table, PossibleDuplicate::MatchId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_possible_duplicate_resolution")
+                    .table(PossibleDuplicate::Table)
+                    .col(PossibleDuplicate::Resolution)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PossibleDuplicate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PossibleDuplicate {
+    Table,
+    Id,
+    IdentityId,
+    MatchId,
+    ExternalName,
+    MatchedName,
+    Distance,
+    ConfirmedBy,
+    ConfirmedAt,
+    Resolution,
+    ResolvedBy,
+    ResolvedAt,
+}
@@ -0,0 +1,84 @@
+//! Create the audit_log table.
+//!
+//! `changes` is a JSON object of each changed field's old and new values,
+//! keyed by the field's path. Entries keep no foreign key to the entity,
+//! as they outlive it.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AuditLog::EntityType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditLog::EntityId).integer().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string_len(10).not_null())
+                    .col(ColumnDef::new(AuditLog::Changes).json().not_null())
+                    .col(
+                        ColumnDef::new(AuditLog::ChangedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditLog::ChangedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_entity")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::EntityType)
+                    .col(AuditLog::EntityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_changed_by")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::ChangedBy)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Action,
+    Changes,
+    ChangedBy,
+    ChangedAt,
+}
@@ -0,0 +1,99 @@
+//! Create the erasure and erased_name tables.
+//!
+//! An erasure records that a withdrawn participant's identifying details
+//! were removed. Its erased names are hashes of the identity's external
+//! names, unique so that a lookup finds at most one.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Erasure::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Erasure::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Erasure::IdentityId).integer().not_null())
+                    .col(ColumnDef::new(Erasure::SamplesErased).integer().not_null())
+                    .col(ColumnDef::new(Erasure::Reason).text().not_null())
+                    .col(ColumnDef::new(Erasure::ErasedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Erasure::ErasedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ErasedName::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ErasedName::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ErasedName::ErasureId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ErasedName::NameHash)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_erased_name_erasure")
+                            .from(ErasedName::Table, ErasedName::ErasureId)
+                            .to(Erasure::Table, Erasure::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ErasedName::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Erasure::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Erasure {
+    Table,
+    Id,
+    IdentityId,
+    SamplesErased,
+    Reason,
+    ErasedBy,
+    ErasedAt,
+}
+
+#[derive(Iden)]
+enum ErasedName {
+    Table,
+    Id,
+    ErasureId,
+    NameHash,
+}
@@ -0,0 +1,80 @@
+//! Create the qc_result table.
+//!
+//! Rows are QC measurements of a sample, library or pool, named by
+//! `entity_type` and `entity_id`. There is no foreign key, as the entity
+//! may be in any of three tables.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(QcResult::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QcResult::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(QcResult::EntityType).string_len(20).not_null())
+                    .col(ColumnDef::new(QcResult::EntityId).integer().not_null())
+                    .col(ColumnDef::new(QcResult::TestType).string_len(100).not_null())
+                    .col(ColumnDef::new(QcResult::Value).double())
+                    .col(ColumnDef::new(QcResult::Unit).string_len(50))
+                    .col(ColumnDef::new(QcResult::Status).string_len(20).not_null())
+                    .col(ColumnDef::new(QcResult::Notes).text())
+                    .col(
+                        ColumnDef::new(QcResult::PerformedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QcResult::PerformedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_qc_result_entity")
+                    .table(QcResult::Table)
+                    .col(QcResult::EntityType)
+                    .col(QcResult::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QcResult::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum QcResult {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    TestType,
+    Value,
+    Unit,
+    Status,
+    Notes,
+    PerformedBy,
+    PerformedAt,
+}
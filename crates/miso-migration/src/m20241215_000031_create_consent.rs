@@ -0,0 +1,62 @@
+//! Create the consent table.
+//!
+//! One row per identity, saying how much of the participant's samples may
+//! be shared.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Consent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Consent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Consent::IdentityId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Consent::Sharing).string_len(20).not_null())
+                    .col(ColumnDef::new(Consent::Notes).text())
+                    .col(ColumnDef::new(Consent::RecordedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Consent::RecordedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Consent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Consent {
+    Table,
+    Id,
+    IdentityId,
+    Sharing,
+    Notes,
+    RecordedBy,
+    RecordedAt,
+}
@@ -0,0 +1,75 @@
+//! Create the storage_audit table.
+//!
+//! One row per physical inventory of a freezer. The boxes to scan, those
+//! scanned and the discrepancies found are kept as JSON on the row.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StorageAudit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StorageAudit::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StorageAudit::Freezer).string_len(255).not_null())
+                    .col(ColumnDef::new(StorageAudit::BoxIds).json().not_null())
+                    .col(ColumnDef::new(StorageAudit::ScannedBoxIds).json().not_null())
+                    .col(ColumnDef::new(StorageAudit::Discrepancies).json().not_null())
+                    .col(ColumnDef::new(StorageAudit::Status).string_len(20).not_null())
+                    .col(ColumnDef::new(StorageAudit::StartedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(StorageAudit::StartedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(StorageAudit::ClosedBy).string_len(255))
+                    .col(ColumnDef::new(StorageAudit::ClosedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_storage_audit_started_at")
+                    .table(StorageAudit::Table)
+                    .col(StorageAudit::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StorageAudit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum StorageAudit {
+    Table,
+    Id,
+    Freezer,
+    BoxIds,
+    ScannedBoxIds,
+    Discrepancies,
+    Status,
+    StartedBy,
+    StartedAt,
+    ClosedBy,
+    ClosedAt,
+}
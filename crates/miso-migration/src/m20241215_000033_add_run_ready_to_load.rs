@@ -0,0 +1,41 @@
+//! Add when a run was marked ready to load to the run table, which is when
+//! its sample sheet is sent to the instrument.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000018_create_run::Run;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .add_column(
+                        ColumnDef::new(RunReadyToLoad::ReadyToLoadAt).timestamp(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .drop_column(RunReadyToLoad::ReadyToLoadAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RunReadyToLoad {
+    ReadyToLoadAt,
+}
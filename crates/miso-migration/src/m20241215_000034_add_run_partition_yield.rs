@@ -0,0 +1,39 @@
+//! Add the yield each lane gave to the run_partition table, as reported by
+//! the instrument's cloud service.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000018_create_run::RunPartition;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RunPartition::Table)
+                    .add_column(ColumnDef::new(RunPartitionYield::YieldGbp).double())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RunPartition::Table)
+                    .drop_column(RunPartitionYield::YieldGbp)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RunPartitionYield {
+    YieldGbp,
+}
@@ -0,0 +1,62 @@
+//! Create the index_set table.
+//!
+//! One row per index kit or plate in the catalog. Its indices, in the
+//! order the kit lists them, are kept as JSON on the row.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IndexSet::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IndexSet::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(IndexSet::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(IndexSet::Family).string_len(20).not_null())
+                    .col(ColumnDef::new(IndexSet::Indices).json().not_null())
+                    .col(ColumnDef::new(IndexSet::CreatedBy).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(IndexSet::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IndexSet::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum IndexSet {
+    Table,
+    Id,
+    Name,
+    Family,
+    Indices,
+    CreatedBy,
+    CreatedAt,
+}
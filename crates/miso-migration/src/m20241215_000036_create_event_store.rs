@@ -0,0 +1,116 @@
+//! Create the stored_event and entity_snapshot tables.
+//!
+//! `stored_event` is the append-only, hash-chained event store; each
+//! event's `hash` covers its contents and `previous_hash`. States are kept
+//! as text rather than JSON so that they read back exactly as they were
+//! hashed. `entity_snapshot` is the read model rebuilt from the events,
+//! one row per entity.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StoredEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StoredEvent::Sequence)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StoredEvent::EntityType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StoredEvent::EntityId).integer().not_null())
+                    .col(ColumnDef::new(StoredEvent::Action).string_len(10).not_null())
+                    .col(ColumnDef::new(StoredEvent::State).text())
+                    .col(
+                        ColumnDef::new(StoredEvent::RecordedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StoredEvent::RecordedAt).timestamp().not_null())
+                    .col(
+                        ColumnDef::new(StoredEvent::PreviousHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StoredEvent::Hash)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(EntitySnapshot::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EntitySnapshot::EntityType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EntitySnapshot::EntityId).integer().not_null())
+                    .col(ColumnDef::new(EntitySnapshot::State).text().not_null())
+                    .col(
+                        ColumnDef::new(EntitySnapshot::Sequence)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(EntitySnapshot::EntityType)
+                            .col(EntitySnapshot::EntityId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EntitySnapshot::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(StoredEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum StoredEvent {
+    Table,
+    Sequence,
+    EntityType,
+    EntityId,
+    Action,
+    State,
+    RecordedBy,
+    RecordedAt,
+    PreviousHash,
+    Hash,
+}
+
+#[derive(Iden)]
+enum EntitySnapshot {
+    Table,
+    EntityType,
+    EntityId,
+    State,
+    Sequence,
+}
@@ -0,0 +1,78 @@
+//! Add hash chaining to the audit_log and change_log tables.
+//!
+//! Each entry's `hash` covers its contents and `previous_hash`, the hash of
+//! the entry before it. Entries written before this migration are left
+//! with empty hashes.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000008_create_change_log::ChangeLog;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuditLog::Table)
+                    .add_column(hash_column(LogHashChain::PreviousHash))
+                    .add_column(hash_column(LogHashChain::Hash))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChangeLog::Table)
+                    .add_column(hash_column(LogHashChain::PreviousHash))
+                    .add_column(hash_column(LogHashChain::Hash))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChangeLog::Table)
+                    .drop_column(LogHashChain::PreviousHash)
+                    .drop_column(LogHashChain::Hash)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuditLog::Table)
+                    .drop_column(LogHashChain::PreviousHash)
+                    .drop_column(LogHashChain::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+fn hash_column(column: LogHashChain) -> ColumnDef {
+    ColumnDef::new(column)
+        .string_len(64)
+        .not_null()
+        .default("")
+        .to_owned()
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+}
+
+#[derive(Iden)]
+enum LogHashChain {
+    PreviousHash,
+    Hash,
+}
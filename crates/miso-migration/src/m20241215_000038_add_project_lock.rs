@@ -0,0 +1,50 @@
+//! Add data entry locks to the project table.
+//!
+//! A locked project has a `lock_reason`; the other columns say who locked
+//! it, when, and when the lock lapses, if it does.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .add_column(ColumnDef::new(ProjectLock::LockReason).string_len(255))
+                    .add_column(ColumnDef::new(ProjectLock::LockedBy).string_len(255))
+                    .add_column(ColumnDef::new(ProjectLock::LockedAt).timestamp())
+                    .add_column(ColumnDef::new(ProjectLock::UnlockAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Project::Table)
+                    .drop_column(ProjectLock::LockReason)
+                    .drop_column(ProjectLock::LockedBy)
+                    .drop_column(ProjectLock::LockedAt)
+                    .drop_column(ProjectLock::UnlockAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ProjectLock {
+    LockReason,
+    LockedBy,
+    LockedAt,
+    UnlockAt,
+}
@@ -0,0 +1,168 @@
+//! Create the sample_list and sample_list_count tables.
+//!
+//! `sample_list` is the sample list read model: one row per sample with
+//! its project, latest QC result and box position copied in, so the list
+//! needs no joins. `sample_list_count` holds the number of rows of each
+//! project and QC status. Both are kept up to date as samples, QC results
+//! and boxes change, and can be rebuilt from those tables.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SampleList::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SampleList::SampleId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SampleList::Name).string_len(255).not_null())
+                    .col(ColumnDef::new(SampleList::Barcode).string_len(50).not_null())
+                    .col(ColumnDef::new(SampleList::ProjectId).integer().not_null())
+                    .col(
+                        ColumnDef::new(SampleList::ProjectCode)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SampleList::ProjectName)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SampleList::SampleClass)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SampleList::QcStatus).string_len(20).not_null())
+                    .col(ColumnDef::new(SampleList::LatestQcId).integer())
+                    .col(ColumnDef::new(SampleList::LatestQcTest).string_len(100))
+                    .col(ColumnDef::new(SampleList::LatestQcValue).double())
+                    .col(ColumnDef::new(SampleList::LatestQcUnit).string_len(50))
+                    .col(ColumnDef::new(SampleList::LatestQcStatus).string_len(20))
+                    .col(ColumnDef::new(SampleList::LatestQcAt).timestamp())
+                    .col(ColumnDef::new(SampleList::BoxId).integer())
+                    .col(ColumnDef::new(SampleList::BoxName).string_len(255))
+                    .col(ColumnDef::new(SampleList::BoxPosition).string_len(4))
+                    .col(
+                        ColumnDef::new(SampleList::Archived)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(SampleList::ReceivedAt).timestamp())
+                    .col(ColumnDef::new(SampleList::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sample_list_project_name")
+                    .table(SampleList::Table)
+                    .col(SampleList::ProjectId)
+                    .col(SampleList::Name)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sample_list_qc_status_name")
+                    .table(SampleList::Table)
+                    .col(SampleList::QcStatus)
+                    .col(SampleList::Name)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sample_list_barcode")
+                    .table(SampleList::Table)
+                    .col(SampleList::Barcode)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SampleListCount::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SampleListCount::ProjectId).integer().not_null())
+                    .col(
+                        ColumnDef::new(SampleListCount::QcStatus)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SampleListCount::SampleCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SampleListCount::ProjectId)
+                            .col(SampleListCount::QcStatus),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SampleListCount::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(SampleList::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SampleList {
+    Table,
+    SampleId,
+    Name,
+    Barcode,
+    ProjectId,
+    ProjectCode,
+    ProjectName,
+    SampleClass,
+    QcStatus,
+    LatestQcId,
+    LatestQcTest,
+    LatestQcValue,
+    LatestQcUnit,
+    LatestQcStatus,
+    LatestQcAt,
+    BoxId,
+    BoxName,
+    BoxPosition,
+    Archived,
+    ReceivedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum SampleListCount {
+    Table,
+    ProjectId,
+    QcStatus,
+    SampleCount,
+}
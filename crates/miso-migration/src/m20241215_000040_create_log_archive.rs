@@ -0,0 +1,70 @@
+//! Create the log_archive table.
+//!
+//! Each row records a compressed file of old rows moved out of the audit
+//! log, change log or storage audit table, so the rows can be restored and
+//! the hash chains of the logs checked from where their archives end.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogArchive::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogArchive::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LogArchive::Log).string_len(50).not_null())
+                    .col(ColumnDef::new(LogArchive::File).string_len(1024).not_null())
+                    .col(ColumnDef::new(LogArchive::FirstId).integer().not_null())
+                    .col(ColumnDef::new(LogArchive::LastId).integer().not_null())
+                    .col(ColumnDef::new(LogArchive::RowCount).big_integer().not_null())
+                    .col(ColumnDef::new(LogArchive::LastHash).string_len(64).not_null())
+                    .col(ColumnDef::new(LogArchive::ArchivedAt).timestamp().not_null())
+                    .col(ColumnDef::new(LogArchive::RestoredAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_log_archive_log_last_id")
+                    .table(LogArchive::Table)
+                    .col(LogArchive::Log)
+                    .col(LogArchive::LastId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LogArchive::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum LogArchive {
+    Table,
+    Id,
+    Log,
+    File,
+    FirstId,
+    LastId,
+    RowCount,
+    LastHash,
+    ArchivedAt,
+    RestoredAt,
+}
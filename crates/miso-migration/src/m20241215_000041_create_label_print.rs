@@ -0,0 +1,71 @@
+//! Create the label_print table.
+//!
+//! Each row records one label printed for an entity. The unique index on
+//! the copy number stops two concurrent prints from both being recorded as
+//! the first print of a unique label.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LabelPrint::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LabelPrint::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LabelPrint::EntityType).string_len(50).not_null())
+                    .col(ColumnDef::new(LabelPrint::EntityId).integer().not_null())
+                    .col(ColumnDef::new(LabelPrint::Template).string_len(100).not_null())
+                    .col(ColumnDef::new(LabelPrint::Copy).integer().not_null())
+                    .col(ColumnDef::new(LabelPrint::PrintedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(LabelPrint::PrintedAt).timestamp().not_null())
+                    .col(ColumnDef::new(LabelPrint::OverrideReason).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_label_print_entity_template_copy")
+                    .table(LabelPrint::Table)
+                    .col(LabelPrint::EntityType)
+                    .col(LabelPrint::EntityId)
+                    .col(LabelPrint::Template)
+                    .col(LabelPrint::Copy)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LabelPrint::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum LabelPrint {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Template,
+    Copy,
+    PrintedBy,
+    PrintedAt,
+    OverrideReason,
+}
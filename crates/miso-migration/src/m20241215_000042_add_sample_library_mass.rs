@@ -0,0 +1,58 @@
+//! Add the mass of nucleic acid left, in ng, to the sample and library
+//! tables, tracked alongside their volumes.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(ColumnDef::new(Mass::Mass).decimal_len(12, 2))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column(ColumnDef::new(Mass::Mass).decimal_len(12, 2))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .drop_column(Mass::Mass)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .drop_column(Mass::Mass)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Mass {
+    Mass,
+}
@@ -0,0 +1,86 @@
+//! Create the library_aliquot table.
+//!
+//! An aliquot is a portion of a library taken out for pooling; the volume
+//! it holds is withdrawn from its library.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LibraryAliquot::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LibraryAliquot::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LibraryAliquot::LibraryId).integer().not_null())
+                    .col(
+                        ColumnDef::new(LibraryAliquot::Barcode)
+                            .string_len(50)
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(LibraryAliquot::Volume).decimal_len(10, 2))
+                    .col(ColumnDef::new(LibraryAliquot::Concentration).decimal_len(10, 2))
+                    .col(
+                        ColumnDef::new(LibraryAliquot::CreatedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LibraryAliquot::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_library_aliquot_library")
+                            .from(LibraryAliquot::Table, LibraryAliquot::LibraryId)
+                            .to(Library::Table, Library::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_library_aliquot_library")
+                    .table(LibraryAliquot::Table)
+                    .col(LibraryAliquot::LibraryId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LibraryAliquot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum LibraryAliquot {
+    Table,
+    Id,
+    LibraryId,
+    Barcode,
+    Volume,
+    Concentration,
+    CreatedBy,
+    CreatedAt,
+}
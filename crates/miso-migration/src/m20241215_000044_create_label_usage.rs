@@ -0,0 +1,64 @@
+//! Create the label_usage table.
+//!
+//! Each row records the labels one print job used, with the printer's
+//! odometer when it was read, so label stock can be tracked and reordered.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LabelUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LabelUsage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LabelUsage::Printer).string_len(255).not_null())
+                    .col(ColumnDef::new(LabelUsage::Template).string_len(100).not_null())
+                    .col(ColumnDef::new(LabelUsage::Labels).integer().not_null())
+                    .col(ColumnDef::new(LabelUsage::Odometer).big_integer())
+                    .col(ColumnDef::new(LabelUsage::PrintedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(LabelUsage::PrintedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_label_usage_printed_at")
+                    .table(LabelUsage::Table)
+                    .col(LabelUsage::PrintedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LabelUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum LabelUsage {
+    Table,
+    Id,
+    Printer,
+    Template,
+    Labels,
+    Odometer,
+    PrintedBy,
+    PrintedAt,
+}
@@ -0,0 +1,107 @@
+//! Create the workset table and the workset_item table listing the samples
+//! and libraries in each workset.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Workset::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Workset::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Workset::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Workset::Description).text())
+                    .col(ColumnDef::new(Workset::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Workset::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Workset::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Items point at samples or libraries, so there's no foreign key to
+        // either; members are checked when they are added
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorksetItem::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WorksetItem::WorksetId).integer().not_null())
+                    .col(ColumnDef::new(WorksetItem::ItemType).string_len(20).not_null())
+                    .col(ColumnDef::new(WorksetItem::EntityId).integer().not_null())
+                    .col(ColumnDef::new(WorksetItem::AddedAt).timestamp().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(WorksetItem::WorksetId)
+                            .col(WorksetItem::ItemType)
+                            .col(WorksetItem::EntityId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workset_item_workset")
+                            .from(WorksetItem::Table, WorksetItem::WorksetId)
+                            .to(Workset::Table, Workset::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workset_item_entity")
+                    .table(WorksetItem::Table)
+                    .col(WorksetItem::ItemType)
+                    .col(WorksetItem::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WorksetItem::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Workset::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Workset {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WorksetItem {
+    Table,
+    WorksetId,
+    ItemType,
+    EntityId,
+    AddedAt,
+}
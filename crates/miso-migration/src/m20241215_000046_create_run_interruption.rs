@@ -0,0 +1,75 @@
+//! Create the run_interruption table recording when runs were paused.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunInterruption::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RunInterruption::RunId).integer().not_null())
+                    .col(
+                        ColumnDef::new(RunInterruption::Position)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RunInterruption::Cause).text().not_null())
+                    .col(
+                        ColumnDef::new(RunInterruption::StartedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RunInterruption::EndedAt).timestamp())
+                    .col(ColumnDef::new(RunInterruption::FlaggedLanes).json().not_null())
+                    .col(
+                        ColumnDef::new(RunInterruption::RecordedBy)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(RunInterruption::RunId)
+                            .col(RunInterruption::Position),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_run_interruption_run")
+                            .from(RunInterruption::Table, RunInterruption::RunId)
+                            .to(Run::Table, Run::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RunInterruption::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Run {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum RunInterruption {
+    Table,
+    RunId,
+    Position,
+    Cause,
+    StartedAt,
+    EndedAt,
+    FlaggedLanes,
+    RecordedBy,
+}
@@ -0,0 +1,116 @@
+//! Create the transfer table and the transfer_item table listing the
+//! samples and libraries handed over in each transfer.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Transfer::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Transfer::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Transfer::Sender).string_len(255).not_null())
+                    .col(ColumnDef::new(Transfer::Recipient).string_len(255).not_null())
+                    .col(ColumnDef::new(Transfer::Notes).text())
+                    .col(ColumnDef::new(Transfer::SentAt).timestamp().not_null())
+                    .col(ColumnDef::new(Transfer::ReceivedBy).string_len(255))
+                    .col(ColumnDef::new(Transfer::ReceivedAt).timestamp())
+                    .col(ColumnDef::new(Transfer::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Transfer::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Transfer::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Items point at samples or libraries, so there's no foreign key to
+        // either; they are checked when the transfer is recorded
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransferItem::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TransferItem::TransferId).integer().not_null())
+                    .col(ColumnDef::new(TransferItem::ItemType).string_len(20).not_null())
+                    .col(ColumnDef::new(TransferItem::EntityId).integer().not_null())
+                    .col(ColumnDef::new(TransferItem::Position).small_integer().not_null())
+                    .col(ColumnDef::new(TransferItem::Received).boolean())
+                    .col(ColumnDef::new(TransferItem::QcPassed).boolean())
+                    .col(ColumnDef::new(TransferItem::QcNote).text())
+                    .primary_key(
+                        Index::create()
+                            .col(TransferItem::TransferId)
+                            .col(TransferItem::ItemType)
+                            .col(TransferItem::EntityId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_transfer_item_transfer")
+                            .from(TransferItem::Table, TransferItem::TransferId)
+                            .to(Transfer::Table, Transfer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transfer_item_entity")
+                    .table(TransferItem::Table)
+                    .col(TransferItem::ItemType)
+                    .col(TransferItem::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransferItem::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Transfer::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Transfer {
+    Table,
+    Id,
+    Sender,
+    Recipient,
+    Notes,
+    SentAt,
+    ReceivedBy,
+    ReceivedAt,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum TransferItem {
+    Table,
+    TransferId,
+    ItemType,
+    EntityId,
+    Position,
+    Received,
+    QcPassed,
+    QcNote,
+}
@@ -0,0 +1,85 @@
+//! Add completion estimates to the run table and per-cycle sequencing times
+//! to the instrument_model table they are estimated from.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000009_create_instrument_model::InstrumentModel;
+use super::m20241215_000018_create_run::Run;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// (name, seconds per cycle) for the seeded short-read models, from their
+/// expected run hours over a 2x150 run with dual 10bp indexes.
+const SEED_CYCLE_TIMES: &[(&str, i32)] = &[
+    ("NovaSeq 6000", 500),
+    ("NovaSeq X", 540),
+    ("MiSeq", 330),
+    ("NextSeq 2000", 540),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .add_column(ColumnDef::new(RunEstimate::EstimatedCompletion).timestamp())
+                    .add_column(ColumnDef::new(RunEstimate::CyclesCompleted).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .add_column(ColumnDef::new(RunEstimate::SecondsPerCycle).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, seconds) in SEED_CYCLE_TIMES {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(InstrumentModel::Table)
+                        .value(RunEstimate::SecondsPerCycle, *seconds)
+                        .and_where(Expr::col(InstrumentModel::Name).eq(*name))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .drop_column(RunEstimate::SecondsPerCycle)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .drop_column(RunEstimate::EstimatedCompletion)
+                    .drop_column(RunEstimate::CyclesCompleted)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RunEstimate {
+    EstimatedCompletion,
+    CyclesCompleted,
+    SecondsPerCycle,
+}
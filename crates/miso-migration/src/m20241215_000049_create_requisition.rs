@@ -0,0 +1,140 @@
+//! Create the requisition table and the requisition_sample table listing
+//! the samples attached to each requisition.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Requisition::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Requisition::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Requisition::Alias)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Requisition::Assay).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(Requisition::Stopped)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Requisition::StopReason).text())
+                    .col(
+                        ColumnDef::new(Requisition::Paused)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Requisition::PauseReason).text())
+                    .col(ColumnDef::new(Requisition::PausedAt).timestamp())
+                    .col(ColumnDef::new(Requisition::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Requisition::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Requisition::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // A sample belongs to at most one requisition, so sample_id alone is
+        // the key
+        manager
+            .create_table(
+                Table::create()
+                    .table(RequisitionSample::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RequisitionSample::SampleId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RequisitionSample::RequisitionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RequisitionSample::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_requisition_sample_requisition")
+                            .from(RequisitionSample::Table, RequisitionSample::RequisitionId)
+                            .to(Requisition::Table, Requisition::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_requisition_sample_sample")
+                            .from(RequisitionSample::Table, RequisitionSample::SampleId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_requisition_sample_requisition")
+                    .table(RequisitionSample::Table)
+                    .col(RequisitionSample::RequisitionId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RequisitionSample::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Requisition::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Requisition {
+    Table,
+    Id,
+    Alias,
+    Assay,
+    Stopped,
+    StopReason,
+    Paused,
+    PauseReason,
+    PausedAt,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum RequisitionSample {
+    Table,
+    SampleId,
+    RequisitionId,
+    Position,
+}
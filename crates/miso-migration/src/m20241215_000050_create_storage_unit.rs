@@ -0,0 +1,143 @@
+//! Create the storage_unit table holding the freezers, shelves and racks
+//! boxes are kept in, and let a box be assigned to a rack.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000019_create_storage_box::StorageBox;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StorageUnit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StorageUnit::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StorageUnit::Kind).string_len(20).not_null())
+                    .col(ColumnDef::new(StorageUnit::Name).string_len(255).not_null())
+                    .col(ColumnDef::new(StorageUnit::ParentId).integer())
+                    .col(
+                        ColumnDef::new(StorageUnit::Barcode)
+                            .string_len(50)
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(StorageUnit::Capacity).integer())
+                    .col(ColumnDef::new(StorageUnit::Temperature).small_integer())
+                    .col(ColumnDef::new(StorageUnit::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(StorageUnit::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(StorageUnit::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_storage_unit_parent")
+                            .from(StorageUnit::Table, StorageUnit::ParentId)
+                            .to(StorageUnit::Table, StorageUnit::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_storage_unit_parent")
+                    .table(StorageUnit::Table)
+                    .col(StorageUnit::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StorageBox::Table)
+                    .add_column(ColumnDef::new(StorageBoxRack::RackId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_storage_box_rack")
+                    .from(StorageBox::Table, StorageBoxRack::RackId)
+                    .to(StorageUnit::Table, StorageUnit::Id)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_storage_box_rack")
+                    .table(StorageBox::Table)
+                    .col(StorageBoxRack::RackId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_storage_box_rack")
+                    .table(StorageBox::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_storage_box_rack")
+                    .table(StorageBox::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StorageBox::Table)
+                    .drop_column(StorageBoxRack::RackId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(StorageUnit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum StorageUnit {
+    Table,
+    Id,
+    Kind,
+    Name,
+    ParentId,
+    Barcode,
+    Capacity,
+    Temperature,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum StorageBoxRack {
+    RackId,
+}
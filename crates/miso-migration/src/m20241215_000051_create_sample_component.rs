@@ -0,0 +1,102 @@
+//! Mark samples pooled from several identities, and create the
+//! sample_component table listing the identities each was made from.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(
+                        ColumnDef::new(Pooled::Pooled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An identity can't be deleted while a pooled sample is made from it
+        manager
+            .create_table(
+                Table::create()
+                    .table(SampleComponent::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SampleComponent::SampleId).integer().not_null())
+                    .col(
+                        ColumnDef::new(SampleComponent::IdentityId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SampleComponent::Position).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(SampleComponent::SampleId)
+                            .col(SampleComponent::IdentityId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sample_component_sample")
+                            .from(SampleComponent::Table, SampleComponent::SampleId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sample_component_identity")
+                            .from(SampleComponent::Table, SampleComponent::IdentityId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sample_component_identity")
+                    .table(SampleComponent::Table)
+                    .col(SampleComponent::IdentityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SampleComponent::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .drop_column(Pooled::Pooled)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SampleComponent {
+    Table,
+    SampleId,
+    IdentityId,
+    Position,
+}
+
+#[derive(Iden)]
+enum Pooled {
+    Pooled,
+}
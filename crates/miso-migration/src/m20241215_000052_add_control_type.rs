@@ -0,0 +1,71 @@
+//! Add control designations ("positive", "negative" or "ntc") to the
+//! sample and library tables, and to the sample list so its counts can
+//! leave controls out.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+use super::m20241215_000014_create_library::Library;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(ColumnDef::new(Control::ControlType).string_len(20))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Library::Table)
+                    .add_column(ColumnDef::new(Control::ControlType).string_len(20))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SampleList::Table)
+                    .add_column(ColumnDef::new(Control::ControlType).string_len(20))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            SampleList::Table.into_iden(),
+            Library::Table.into_iden(),
+            Sample::Table.into_iden(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Control::ControlType)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum SampleList {
+    Table,
+}
+
+#[derive(Iden)]
+enum Control {
+    ControlType,
+}
@@ -0,0 +1,150 @@
+//! Create the extraction_batch table and the extraction_batch_sample table
+//! listing the Stock samples and reagent blanks of each batch.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExtractionBatch::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExtractionBatch::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExtractionBatch::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ExtractionBatch::Instrument).string_len(255))
+                    .col(ColumnDef::new(ExtractionBatch::Kit).string_len(255).not_null())
+                    .col(ColumnDef::new(ExtractionBatch::KitLot).string_len(100).not_null())
+                    .col(ColumnDef::new(ExtractionBatch::Operator).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(ExtractionBatch::ExtractedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExtractionBatch::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(ExtractionBatch::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ExtractionBatch::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_extraction_batch_kit_lot")
+                    .table(ExtractionBatch::Table)
+                    .col(ExtractionBatch::Kit)
+                    .col(ExtractionBatch::KitLot)
+                    .to_owned(),
+            )
+            .await?;
+
+        // A sample is extracted in at most one batch, so sample_id alone is
+        // the key
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExtractionBatchSample::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExtractionBatchSample::SampleId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExtractionBatchSample::BatchId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExtractionBatchSample::Blank)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ExtractionBatchSample::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_extraction_batch_sample_batch")
+                            .from(ExtractionBatchSample::Table, ExtractionBatchSample::BatchId)
+                            .to(ExtractionBatch::Table, ExtractionBatch::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_extraction_batch_sample_sample")
+                            .from(ExtractionBatchSample::Table, ExtractionBatchSample::SampleId)
+                            .to(Sample::Table, Sample::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_extraction_batch_sample_batch")
+                    .table(ExtractionBatchSample::Table)
+                    .col(ExtractionBatchSample::BatchId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExtractionBatchSample::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ExtractionBatch::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExtractionBatch {
+    Table,
+    Id,
+    Name,
+    Instrument,
+    Kit,
+    KitLot,
+    Operator,
+    ExtractedAt,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum ExtractionBatchSample {
+    Table,
+    SampleId,
+    BatchId,
+    Blank,
+    Position,
+}
@@ -0,0 +1,77 @@
+//! Create the reagent_lot table recording the kit lots held in stock.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReagentLot::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReagentLot::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReagentLot::Kit).string_len(255).not_null())
+                    .col(ColumnDef::new(ReagentLot::LotNumber).string_len(100).not_null())
+                    .col(ColumnDef::new(ReagentLot::ExpiresOn).date().not_null())
+                    .col(
+                        ColumnDef::new(ReagentLot::Quantity)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ReagentLot::ReorderLevel)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ReagentLot::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(ReagentLot::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ReagentLot::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reagent_lot_kit_lot_number")
+                    .table(ReagentLot::Table)
+                    .col(ReagentLot::Kit)
+                    .col(ReagentLot::LotNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReagentLot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ReagentLot {
+    Table,
+    Id,
+    Kit,
+    LotNumber,
+    ExpiresOn,
+    Quantity,
+    ReorderLevel,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
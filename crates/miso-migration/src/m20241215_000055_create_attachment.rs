@@ -0,0 +1,75 @@
+//! Create the attachment table recording files uploaded against samples,
+//! libraries and runs.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Attachment::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // Points at a sample, library or run, so no foreign key
+                    .col(ColumnDef::new(Attachment::Target).string_len(20).not_null())
+                    .col(ColumnDef::new(Attachment::TargetId).integer().not_null())
+                    .col(ColumnDef::new(Attachment::Filename).string_len(255).not_null())
+                    .col(ColumnDef::new(Attachment::ContentType).string_len(255).not_null())
+                    .col(ColumnDef::new(Attachment::Size).big_integer().not_null())
+                    .col(ColumnDef::new(Attachment::Checksum).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(Attachment::StorageKey)
+                            .string_len(512)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Attachment::UploadedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Attachment::UploadedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attachment_target")
+                    .table(Attachment::Table)
+                    .col(Attachment::Target)
+                    .col(Attachment::TargetId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Attachment {
+    Table,
+    Id,
+    Target,
+    TargetId,
+    Filename,
+    ContentType,
+    Size,
+    Checksum,
+    StorageKey,
+    UploadedBy,
+    UploadedAt,
+}
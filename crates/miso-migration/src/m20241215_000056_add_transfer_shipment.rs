@@ -0,0 +1,70 @@
+//! Add courier tracking to the transfer table: the courier, its tracking
+//! number, who to tell when the shipment is out for delivery and the
+//! shipment's last known status.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transfer::Table)
+                    .add_column(ColumnDef::new(Transfer::Courier).string_len(100))
+                    .add_column(ColumnDef::new(Transfer::TrackingNumber).string_len(100))
+                    .add_column(ColumnDef::new(Transfer::RecipientEmail).string_len(255))
+                    .add_column(ColumnDef::new(Transfer::ShipmentStatus).string_len(20))
+                    .add_column(ColumnDef::new(Transfer::ShipmentUpdatedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transfer_tracking_number")
+                    .table(Transfer::Table)
+                    .col(Transfer::TrackingNumber)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transfer_tracking_number")
+                    .table(Transfer::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transfer::Table)
+                    .drop_column(Transfer::Courier)
+                    .drop_column(Transfer::TrackingNumber)
+                    .drop_column(Transfer::RecipientEmail)
+                    .drop_column(Transfer::ShipmentStatus)
+                    .drop_column(Transfer::ShipmentUpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Transfer {
+    Table,
+    Courier,
+    TrackingNumber,
+    RecipientEmail,
+    ShipmentStatus,
+    ShipmentUpdatedAt,
+}
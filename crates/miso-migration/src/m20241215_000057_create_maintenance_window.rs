@@ -0,0 +1,72 @@
+//! Create the maintenance_window table recording planned downtime of
+//! sequencers and of the facility as a whole.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000022_create_sequencer::Sequencer;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MaintenanceWindow::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MaintenanceWindow::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // Null for facility-wide maintenance
+                    .col(ColumnDef::new(MaintenanceWindow::SequencerId).integer().null())
+                    .col(ColumnDef::new(MaintenanceWindow::StartsAt).timestamp().not_null())
+                    .col(ColumnDef::new(MaintenanceWindow::EndsAt).timestamp().not_null())
+                    .col(ColumnDef::new(MaintenanceWindow::Reason).string_len(500).not_null())
+                    .col(ColumnDef::new(MaintenanceWindow::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(MaintenanceWindow::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_maintenance_window_sequencer")
+                            .from(MaintenanceWindow::Table, MaintenanceWindow::SequencerId)
+                            .to(Sequencer::Table, Sequencer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_maintenance_window_ends_at")
+                    .table(MaintenanceWindow::Table)
+                    .col(MaintenanceWindow::EndsAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MaintenanceWindow::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MaintenanceWindow {
+    Table,
+    Id,
+    SequencerId,
+    StartsAt,
+    EndsAt,
+    Reason,
+    CreatedBy,
+    CreatedAt,
+}
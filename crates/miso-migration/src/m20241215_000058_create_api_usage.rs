@@ -0,0 +1,72 @@
+//! Create the api_usage table of hourly call counts and latencies per
+//! caller and endpoint.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiUsage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiUsage::Hour).timestamp().not_null())
+                    // Counts outlive users and keys, so no foreign keys
+                    .col(ColumnDef::new(ApiUsage::UserId).integer().not_null())
+                    .col(ColumnDef::new(ApiUsage::ApiKeyId).integer().null())
+                    .col(ColumnDef::new(ApiUsage::Method).string_len(10).not_null())
+                    .col(ColumnDef::new(ApiUsage::Route).string_len(255).not_null())
+                    .col(ColumnDef::new(ApiUsage::Calls).big_integer().not_null())
+                    .col(ColumnDef::new(ApiUsage::Errors).big_integer().not_null())
+                    .col(ColumnDef::new(ApiUsage::Samples).big_integer().not_null())
+                    .col(ColumnDef::new(ApiUsage::TotalMs).big_integer().not_null())
+                    .col(ColumnDef::new(ApiUsage::MaxMs).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_api_usage_hour_user")
+                    .table(ApiUsage::Table)
+                    .col(ApiUsage::Hour)
+                    .col(ApiUsage::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ApiUsage {
+    Table,
+    Id,
+    Hour,
+    UserId,
+    ApiKeyId,
+    Method,
+    Route,
+    Calls,
+    Errors,
+    Samples,
+    TotalMs,
+    MaxMs,
+}
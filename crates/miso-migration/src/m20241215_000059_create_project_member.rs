@@ -0,0 +1,73 @@
+//! Create the project_member table recording which users belong to which
+//! projects.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+use super::m20241215_000020_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectMember::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ProjectMember::ProjectId).integer().not_null())
+                    .col(ColumnDef::new(ProjectMember::UserId).integer().not_null())
+                    .col(ColumnDef::new(ProjectMember::AddedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(ProjectMember::AddedAt).timestamp().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ProjectMember::ProjectId)
+                            .col(ProjectMember::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_member_project")
+                            .from(ProjectMember::Table, ProjectMember::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_member_user")
+                            .from(ProjectMember::Table, ProjectMember::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Looked up by user on every check
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_member_user")
+                    .table(ProjectMember::Table)
+                    .col(ProjectMember::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectMember::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ProjectMember {
+    Table,
+    ProjectId,
+    UserId,
+    AddedBy,
+    AddedAt,
+}
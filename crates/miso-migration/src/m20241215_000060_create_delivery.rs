@@ -0,0 +1,159 @@
+//! Create the delivery_target table of the places projects release their
+//! data to, and the delivery table recording which runs' data were
+//! delivered there.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+use super::m20241215_000018_create_run::Run;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeliveryTarget::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeliveryTarget::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DeliveryTarget::ProjectId).integer().not_null())
+                    .col(ColumnDef::new(DeliveryTarget::Name).string_len(255).not_null())
+                    .col(ColumnDef::new(DeliveryTarget::Protocol).string_len(10).not_null())
+                    // S3 bucket or SFTP host
+                    .col(ColumnDef::new(DeliveryTarget::Host).string_len(255).not_null())
+                    .col(ColumnDef::new(DeliveryTarget::Port).integer())
+                    // S3 key prefix or SFTP directory
+                    .col(ColumnDef::new(DeliveryTarget::Path).text().not_null())
+                    .col(
+                        ColumnDef::new(DeliveryTarget::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(DeliveryTarget::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(DeliveryTarget::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_delivery_target_project")
+                            .from(DeliveryTarget::Table, DeliveryTarget::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Delivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Delivery::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Delivery::TargetId).integer().not_null())
+                    .col(ColumnDef::new(Delivery::ProjectId).integer().not_null())
+                    .col(ColumnDef::new(Delivery::RunId).integer().not_null())
+                    .col(ColumnDef::new(Delivery::Uri).text().not_null())
+                    .col(ColumnDef::new(Delivery::ChecksumManifest).text())
+                    .col(ColumnDef::new(Delivery::SizeBytes).big_integer())
+                    .col(ColumnDef::new(Delivery::Status).string_len(20).not_null())
+                    .col(ColumnDef::new(Delivery::DeliveredBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Delivery::DeliveredAt).timestamp().not_null())
+                    .col(ColumnDef::new(Delivery::VerifiedBy).string_len(255))
+                    .col(ColumnDef::new(Delivery::VerifiedAt).timestamp())
+                    .col(ColumnDef::new(Delivery::Note).text())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_delivery_target")
+                            .from(Delivery::Table, Delivery::TargetId)
+                            .to(DeliveryTarget::Table, DeliveryTarget::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_delivery_run")
+                            .from(Delivery::Table, Delivery::RunId)
+                            .to(Run::Table, Run::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_delivery_project")
+                    .table(Delivery::Table)
+                    .col(Delivery::ProjectId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_delivery_run")
+                    .table(Delivery::Table)
+                    .col(Delivery::RunId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Delivery::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DeliveryTarget::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DeliveryTarget {
+    Table,
+    Id,
+    ProjectId,
+    Name,
+    Protocol,
+    Host,
+    Port,
+    Path,
+    Active,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Delivery {
+    Table,
+    Id,
+    TargetId,
+    ProjectId,
+    RunId,
+    Uri,
+    ChecksumManifest,
+    SizeBytes,
+    Status,
+    DeliveredBy,
+    DeliveredAt,
+    VerifiedBy,
+    VerifiedAt,
+    Note,
+}
@@ -0,0 +1,136 @@
+//! Create the subproject table of named partitions of a project's samples,
+//! and let samples be assigned to one.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000001_create_project::Project;
+use super::m20241215_000002_create_sample::Sample;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Subproject::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Subproject::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Subproject::ProjectId).integer().not_null())
+                    .col(ColumnDef::new(Subproject::Alias).string_len(100).not_null())
+                    .col(ColumnDef::new(Subproject::Description).text())
+                    .col(ColumnDef::new(Subproject::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(Subproject::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_subproject_project")
+                            .from(Subproject::Table, Subproject::ProjectId)
+                            .to(Project::Table, Project::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Aliases are unique within a project
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_subproject_alias")
+                    .table(Subproject::Table)
+                    .col(Subproject::ProjectId)
+                    .col(Subproject::Alias)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .add_column(ColumnDef::new(SampleSubproject::SubprojectId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Deleting a subproject leaves its samples in the project
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_sample_subproject")
+                    .from(Sample::Table, SampleSubproject::SubprojectId)
+                    .to(Subproject::Table, Subproject::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sample_subproject")
+                    .table(Sample::Table)
+                    .col(SampleSubproject::SubprojectId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_sample_subproject")
+                    .table(Sample::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_sample_subproject")
+                    .table(Sample::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sample::Table)
+                    .drop_column(SampleSubproject::SubprojectId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Subproject::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Subproject {
+    Table,
+    Id,
+    ProjectId,
+    Alias,
+    Description,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum SampleSubproject {
+    SubprojectId,
+}
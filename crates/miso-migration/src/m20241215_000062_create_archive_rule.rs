@@ -0,0 +1,128 @@
+//! Create the archive_rule table of automatic archiving rules, and the
+//! auto_archival table recording the items they archived.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchiveRule::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArchiveRule::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ArchiveRule::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ArchiveRule::Target).string_len(20).not_null())
+                    .col(ColumnDef::new(ArchiveRule::AfterDays).integer().not_null())
+                    .col(ColumnDef::new(ArchiveRule::NoticeDays).integer().not_null())
+                    .col(
+                        ColumnDef::new(ArchiveRule::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(ArchiveRule::CreatedBy).string_len(255).not_null())
+                    .col(ColumnDef::new(ArchiveRule::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ArchiveRule::LastEvaluatedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Entity IDs point at libraries or samples depending on the target,
+        // so they carry no foreign key
+        manager
+            .create_table(
+                Table::create()
+                    .table(AutoArchival::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AutoArchival::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AutoArchival::RuleId).integer().not_null())
+                    .col(ColumnDef::new(AutoArchival::Target).string_len(20).not_null())
+                    .col(ColumnDef::new(AutoArchival::EntityId).integer().not_null())
+                    .col(ColumnDef::new(AutoArchival::EntityName).string_len(255).not_null())
+                    .col(ColumnDef::new(AutoArchival::LastRunAt).timestamp().not_null())
+                    .col(ColumnDef::new(AutoArchival::ArchivedAt).timestamp().not_null())
+                    .col(ColumnDef::new(AutoArchival::RestoredBy).string_len(255))
+                    .col(ColumnDef::new(AutoArchival::RestoredAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_auto_archival_rule")
+                            .from(AutoArchival::Table, AutoArchival::RuleId)
+                            .to(ArchiveRule::Table, ArchiveRule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_auto_archival_rule")
+                    .table(AutoArchival::Table)
+                    .col(AutoArchival::RuleId)
+                    .col(AutoArchival::ArchivedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AutoArchival::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ArchiveRule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ArchiveRule {
+    Table,
+    Id,
+    Name,
+    Target,
+    AfterDays,
+    NoticeDays,
+    Active,
+    CreatedBy,
+    CreatedAt,
+    LastEvaluatedAt,
+}
+
+#[derive(Iden)]
+enum AutoArchival {
+    Table,
+    Id,
+    RuleId,
+    Target,
+    EntityId,
+    EntityName,
+    LastRunAt,
+    ArchivedAt,
+    RestoredBy,
+    RestoredAt,
+}
@@ -0,0 +1,96 @@
+//! Create the item_location table recording where each storable item was
+//! last put away, for the nightly reconciliation against box contents.
+//! Existing box contents are copied in as the starting locations.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000019_create_storage_box::{BoxPosition, StorageBox};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemLocation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ItemLocation::ItemType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ItemLocation::ItemId).integer().not_null())
+                    .col(ColumnDef::new(ItemLocation::BoxId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ItemLocation::Position)
+                            .string_len(4)
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ItemLocation::ItemType)
+                            .col(ItemLocation::ItemId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_item_location_box")
+                            .from(ItemLocation::Table, ItemLocation::BoxId)
+                            .to(StorageBox::Table, StorageBox::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An item found in more than one box keeps the first placement
+        // copied; the reconciliation reports the others
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(ItemLocation::Table)
+                    .columns([
+                        ItemLocation::ItemType,
+                        ItemLocation::ItemId,
+                        ItemLocation::BoxId,
+                        ItemLocation::Position,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .columns([
+                                BoxPosition::ItemType,
+                                BoxPosition::ItemId,
+                                BoxPosition::BoxId,
+                                BoxPosition::Position,
+                            ])
+                            .from(BoxPosition::Table)
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .on_conflict(
+                        OnConflict::columns([ItemLocation::ItemType, ItemLocation::ItemId])
+                            .do_nothing_on([ItemLocation::ItemType])
+                            .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ItemLocation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ItemLocation {
+    Table,
+    ItemType,
+    ItemId,
+    BoxId,
+    Position,
+}
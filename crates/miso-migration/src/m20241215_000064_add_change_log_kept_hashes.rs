@@ -0,0 +1,43 @@
+//! Add the hashes an erased change log entry's summary and reason had to
+//! the change_log table, so the entry can still be checked against its
+//! chain hash once a withdrawn participant's details are erased from it.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000008_create_change_log::ChangeLog;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChangeLog::Table)
+                    .add_column(ColumnDef::new(KeptHash::SummaryHash).string_len(64))
+                    .add_column(ColumnDef::new(KeptHash::ReasonHash).string_len(64))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChangeLog::Table)
+                    .drop_column(KeptHash::SummaryHash)
+                    .drop_column(KeptHash::ReasonHash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum KeptHash {
+    SummaryHash,
+    ReasonHash,
+}
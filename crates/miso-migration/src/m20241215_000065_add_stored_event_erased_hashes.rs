@@ -0,0 +1,39 @@
+//! Add the hashes of values erased from a stored event's state to the
+//! stored_event table, so the event can still be checked against its chain
+//! hash once a withdrawn participant's details are erased from it.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StoredEvent::Table)
+                    .add_column(ColumnDef::new(StoredEvent::ErasedHashes).json())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StoredEvent::Table)
+                    .drop_column(StoredEvent::ErasedHashes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum StoredEvent {
+    Table,
+    ErasedHashes,
+}
@@ -0,0 +1,88 @@
+//! Add the patterned container models to the instrument_model table, and the
+//! model of the container a run was assigned to the run table, so index
+//! hopping can be assessed against the flow cell a run actually uses.
+
+use sea_orm_migration::prelude::*;
+
+use super::m20241215_000009_create_instrument_model::InstrumentModel;
+use super::m20241215_000018_create_run::Run;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// (name, patterned container models) for the seeded models.
+const SEED_PATTERNED: &[(&str, &str)] = &[
+    (
+        "NovaSeq 6000",
+        r#"["SP Flow Cell","S1 Flow Cell","S2 Flow Cell","S4 Flow Cell"]"#,
+    ),
+    (
+        "NovaSeq X",
+        r#"["1.5B Flow Cell","10B Flow Cell","25B Flow Cell"]"#,
+    ),
+    (
+        "NextSeq 2000",
+        r#"["P1 Flow Cell","P2 Flow Cell","P3 Flow Cell"]"#,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .add_column(ColumnDef::new(Patterned::PatternedContainers).json())
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, containers) in SEED_PATTERNED {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(InstrumentModel::Table)
+                        .value(Patterned::PatternedContainers, *containers)
+                        .and_where(Expr::col(InstrumentModel::Name).eq(*name))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .add_column(ColumnDef::new(Patterned::ContainerModel).string_len(255))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Run::Table)
+                    .drop_column(Patterned::ContainerModel)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InstrumentModel::Table)
+                    .drop_column(Patterned::PatternedContainers)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Patterned {
+    PatternedContainers,
+    ContainerModel,
+}
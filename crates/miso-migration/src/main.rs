@@ -1,14 +1,26 @@
 //! MISO LIMS Database Migration CLI
 //!
 //! Usage:
-//!   miso-migrate up      - Apply all pending migrations
-//!   miso-migrate down    - Rollback last migration
-//!   miso-migrate status  - Show migration status
+//!   miso-migrate up [-n N]                    - Apply pending migrations and record their checksums
+//!   miso-migrate down                         - Rollback last migration
+//!   miso-migrate status                       - Show migration status
+//!   miso-migrate plan                         - Show pending migrations and their SQL without applying
+//!   miso-migrate verify                       - Check applied migrations against source checksums
+//!   miso-migrate record                       - Record checksums for applied migrations that have none
+//!   miso-migrate down-to <version> [--dry-run] - Roll back every migration applied after <version>
+//!
+//! Other commands are handled by the SeaORM migration CLI.
+
+use std::process::ExitCode;
 
+use miso_migration::checksum::{self, ChecksumStatus};
+use miso_migration::plan::{self, Direction};
+use miso_migration::Migrator;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr};
 use sea_orm_migration::prelude::*;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     // Load .env file if present
     let _ = dotenvy::dotenv();
 
@@ -17,6 +29,214 @@ async fn main() {
         .with_env_filter("info")
         .init();
 
-    cli::run_cli(miso_migration::Migrator).await;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("up") => up(&args[1..]).await,
+        Some("plan") => plan_pending().await,
+        Some("verify") => verify().await,
+        Some("record") => record().await,
+        Some("down-to") => down_to(&args[1..]).await,
+        _ => {
+            cli::run_cli(Migrator).await;
+            return ExitCode::SUCCESS;
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn connect() -> Result<DatabaseConnection, DbErr> {
+    let url = std::env::var("DATABASE_URL")
+        .map_err(|_| DbErr::Custom("DATABASE_URL must be set".to_string()))?;
+    Database::connect(url).await
+}
+
+/// Applies pending migrations, refusing if applied ones have been edited.
+async fn up(args: &[String]) -> Result<ExitCode, DbErr> {
+    let steps = match args {
+        [] => None,
+        [flag, n] if flag == "-n" || flag == "--num" => Some(
+            n.parse()
+                .map_err(|_| DbErr::Custom(format!("Invalid step count: {}", n)))?,
+        ),
+        _ => return Err(DbErr::Custom("Usage: miso-migrate up [-n N]".to_string())),
+    };
+
+    let db = connect().await?;
+    if report_modified(&checksum::verify(&db).await?) {
+        eprintln!("Refusing to apply migrations until the modified sources are reverted");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let before = applied(&db).await?;
+    Migrator::up(&db, steps).await?;
+    let newly_applied: Vec<String> = applied(&db)
+        .await?
+        .into_iter()
+        .filter(|name| !before.contains(name))
+        .collect();
+    checksum::record(&db, &newly_applied).await?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints the SQL pending migrations would run.
+async fn plan_pending() -> Result<ExitCode, DbErr> {
+    let db = connect().await?;
+    let pending: Vec<String> = Migrator::get_pending_migrations(&db)
+        .await?
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect();
+
+    if pending.is_empty() {
+        println!("No pending migrations");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    println!("{} pending migration(s):", pending.len());
+    print_plan(&plan::plan(db.get_database_backend(), &pending, Direction::Up).await)
+}
+
+/// Checks applied migrations against their sources. Migrations without a
+/// recorded checksum fail the check; nothing is written.
+async fn verify() -> Result<ExitCode, DbErr> {
+    let db = connect().await?;
+    let results = checksum::verify(&db).await?;
+
+    let mut unrecorded = 0;
+    for (name, status) in &results {
+        match status {
+            ChecksumStatus::Ok => println!("  ok          {}", name),
+            ChecksumStatus::Unrecorded => {
+                unrecorded += 1;
+                eprintln!("  UNRECORDED  {}", name);
+            }
+            ChecksumStatus::Modified { .. } => {}
+        }
+    }
+
+    let modified = report_modified(&results);
+    if unrecorded > 0 {
+        eprintln!(
+            "{} applied migration(s) have no recorded checksum; review their sources and run `miso-migrate record`",
+            unrecorded
+        );
+    }
+    if modified || unrecorded > 0 {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    println!("{} applied migration(s) match their source", results.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Records checksums of the current sources for applied migrations that
+/// have none, e.g. ones applied by another tool.
+async fn record() -> Result<ExitCode, DbErr> {
+    let db = connect().await?;
+    let unrecorded: Vec<String> = checksum::verify(&db)
+        .await?
+        .into_iter()
+        .filter(|(_, status)| *status == ChecksumStatus::Unrecorded)
+        .map(|(name, _)| name)
+        .collect();
+
+    checksum::record(&db, &unrecorded).await?;
+    for name in &unrecorded {
+        println!("  recorded    {}", name);
+    }
+    println!("Recorded {} checksum(s)", unrecorded.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn applied(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    Ok(Migrator::get_applied_migrations(db)
+        .await?
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect())
+}
+
+/// Rolls back every migration applied after the given version.
+async fn down_to(args: &[String]) -> Result<ExitCode, DbErr> {
+    let (version, dry_run) = match args {
+        [version] => (version, false),
+        [version, flag] if flag == "--dry-run" => (version, true),
+        _ => {
+            return Err(DbErr::Custom(
+                "Usage: miso-migrate down-to <version> [--dry-run]".to_string(),
+            ))
+        }
+    };
+
+    let db = connect().await?;
+    let applied = applied(&db).await?;
+
+    let index = applied
+        .iter()
+        .position(|name| name == version)
+        .ok_or_else(|| DbErr::Custom(format!("'{}' is not an applied migration", version)))?;
+    let to_roll_back: Vec<String> = applied[index + 1..].iter().rev().cloned().collect();
+
+    if to_roll_back.is_empty() {
+        println!("'{}' is the latest applied migration; nothing to roll back", version);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if dry_run {
+        println!("Would roll back {} migration(s):", to_roll_back.len());
+        return print_plan(
+            &plan::plan(db.get_database_backend(), &to_roll_back, Direction::Down).await,
+        );
+    }
+
+    Migrator::down(&db, Some(to_roll_back.len() as u32)).await?;
+    checksum::forget(&db, &to_roll_back).await?;
+    println!("Rolled back to {}", version);
+
+    Ok(ExitCode::SUCCESS)
 }
 
+fn print_plan(planned: &[plan::PlannedMigration]) -> Result<ExitCode, DbErr> {
+    let mut ok = true;
+    for migration in planned {
+        println!("\n-- {}", migration.name);
+        match &migration.sql {
+            Ok(statements) => {
+                for sql in statements {
+                    println!("{};", sql);
+                }
+            }
+            Err(e) => {
+                ok = false;
+                println!("-- SQL unavailable: {}", e);
+            }
+        }
+    }
+
+    Ok(if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// Prints modified migrations; returns true if there were any.
+fn report_modified(results: &[(String, ChecksumStatus)]) -> bool {
+    let mut modified = false;
+    for (name, status) in results {
+        if let ChecksumStatus::Modified { recorded, current } = status {
+            modified = true;
+            eprintln!(
+                "  MODIFIED    {} (recorded {}, source {})",
+                name,
+                &recorded[..12],
+                &current[..12]
+            );
+        }
+    }
+    modified
+}
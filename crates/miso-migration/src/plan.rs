@@ -0,0 +1,118 @@
+//! Dry-run planning of migrations.
+//!
+//! Migrations are run against a proxy connection that records statements
+//! instead of executing them, so the SQL that `up` or `down` would issue can
+//! be reviewed before touching a real database. Migrations that inspect the
+//! live schema (e.g. `has_column`) can't be planned this way and report an
+//! error in place of their SQL.
+
+use std::sync::{Arc, Mutex};
+
+use sea_orm::{
+    Database, DatabaseBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Statement,
+};
+use sea_orm_migration::prelude::*;
+
+use crate::Migrator;
+
+/// Proxy backend that records statements rather than running them.
+#[derive(Debug, Default)]
+struct StatementRecorder {
+    statements: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait::async_trait]
+impl ProxyDatabaseTrait for StatementRecorder {
+    async fn query(&self, _statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        Err(DbErr::Custom(
+            "Migration queries the live schema and can't be planned".to_string(),
+        ))
+    }
+
+    async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        self.statements.lock().unwrap().push(statement.to_string());
+        Ok(ProxyExecResult::default())
+    }
+}
+
+/// The SQL a migration would run.
+#[derive(Debug)]
+pub struct PlannedMigration {
+    /// Migration name
+    pub name: String,
+    /// Statements in execution order, or why they couldn't be generated
+    pub sql: Result<Vec<String>, DbErr>,
+}
+
+/// Direction to plan in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Plans the named migrations, in the order given.
+pub async fn plan(
+    backend: DatabaseBackend,
+    names: &[String],
+    direction: Direction,
+) -> Vec<PlannedMigration> {
+    let mut migrations = Migrator::migrations();
+    let mut planned = Vec::new();
+
+    for name in names {
+        let Some(index) = migrations.iter().position(|m| m.name() == name) else {
+            planned.push(PlannedMigration {
+                name: name.clone(),
+                sql: Err(DbErr::Custom("Migration not found in source".to_string())),
+            });
+            continue;
+        };
+        let migration = migrations.remove(index);
+
+        let statements = Arc::new(Mutex::new(Vec::new()));
+        let recorder = StatementRecorder {
+            statements: statements.clone(),
+        };
+        let sql = match Database::connect_proxy(backend, Arc::new(Box::new(recorder))).await {
+            Ok(conn) => {
+                let manager = SchemaManager::new(&conn);
+                match direction {
+                    Direction::Up => migration.up(&manager).await,
+                    Direction::Down => migration.down(&manager).await,
+                }
+                .map(|_| std::mem::take(&mut *statements.lock().unwrap()))
+            }
+            Err(e) => Err(e),
+        };
+
+        planned.push(PlannedMigration {
+            name: name.clone(),
+            sql,
+        });
+    }
+
+    planned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_plan_generates_sql() {
+        let names: Vec<String> = Migrator::migrations()
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect();
+
+        let planned = plan(DatabaseBackend::MySql, &names, Direction::Up).await;
+        assert_eq!(planned.len(), names.len());
+
+        let first = planned[0].sql.as_ref().unwrap();
+        assert!(first[0].starts_with("CREATE TABLE IF NOT EXISTS `project`"));
+
+        let down = plan(DatabaseBackend::MySql, &names[..1], Direction::Down).await;
+        assert_eq!(down[0].sql.as_ref().unwrap(), &["DROP TABLE `project`"]);
+    }
+}
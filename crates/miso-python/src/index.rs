@@ -0,0 +1,206 @@
+//! Index sequences and collision checks.
+
+use miso_domain::services::{CollisionCheckConfig, IndexCollision, IndexCollisionChecker};
+use miso_domain::value_objects::{DnaIndex, IndexFamily};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// A library index: an i7 sequence and, for dual indexing, an i5.
+#[pyclass(name = "DnaIndex", module = "miso_lims", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct PyDnaIndex {
+    inner: DnaIndex,
+}
+
+#[pymethods]
+impl PyDnaIndex {
+    /// Sequences are upper-cased and may only contain A, C, G, T and N.
+    /// `family` is "tru_seq", "nextera", "idt_udi", "ten_x" or "custom".
+    #[new]
+    #[pyo3(signature = (name, i7, i5=None, family="custom"))]
+    fn new(name: String, i7: String, i5: Option<String>, family: &str) -> PyResult<Self> {
+        let family = parse_family(family)?;
+        let inner = match i5 {
+            Some(i5) => DnaIndex::dual(name, i7, i5, family),
+            None => DnaIndex::single(name, i7, family),
+        }
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    #[getter]
+    fn i7(&self) -> &str {
+        self.inner.i7()
+    }
+
+    #[getter]
+    fn i5(&self) -> Option<&str> {
+        self.inner.i5()
+    }
+
+    #[getter]
+    fn family(&self) -> &'static str {
+        family_code(self.inner.family())
+    }
+
+    /// Mismatches between the two indices, i7 and i5 together.
+    fn hamming_distance(&self, other: &Self) -> u32 {
+        self.inner.hamming_distance(&other.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.inner.i5() {
+            Some(i5) => format!(
+                "DnaIndex({:?}, {:?}, {:?})",
+                self.inner.name(),
+                self.inner.i7(),
+                i5
+            ),
+            None => format!("DnaIndex({:?}, {:?})", self.inner.name(), self.inner.i7()),
+        }
+    }
+}
+
+/// Two indices too similar to be told apart after sequencing.
+#[pyclass(name = "IndexCollision", module = "miso_lims", frozen, get_all)]
+pub struct PyIndexCollision {
+    /// Name of the first library
+    library1: String,
+    /// Name of the second library
+    library2: String,
+    index1: PyDnaIndex,
+    index2: PyDnaIndex,
+    /// Mismatches between the two indices
+    distance: u32,
+    /// Mismatches the checker requires
+    required_distance: u32,
+}
+
+#[pymethods]
+impl PyIndexCollision {
+    fn __repr__(&self) -> String {
+        format!(
+            "IndexCollision({:?}, {:?}, distance={}, required_distance={})",
+            self.library1, self.library2, self.distance, self.required_distance
+        )
+    }
+}
+
+impl From<IndexCollision> for PyIndexCollision {
+    fn from(collision: IndexCollision) -> Self {
+        Self {
+            library1: collision.library1,
+            library2: collision.library2,
+            index1: PyDnaIndex {
+                inner: collision.index1,
+            },
+            index2: PyDnaIndex {
+                inner: collision.index2,
+            },
+            distance: collision.distance,
+            required_distance: collision.required_distance,
+        }
+    }
+}
+
+/// Finds indices in a pool that are too similar to demultiplex.
+#[pyclass(name = "IndexCollisionChecker", module = "miso_lims", frozen)]
+pub struct PyIndexCollisionChecker {
+    inner: IndexCollisionChecker,
+}
+
+#[pymethods]
+impl PyIndexCollisionChecker {
+    #[new]
+    #[pyo3(signature = (min_distance=3, check_dual_index=true))]
+    fn new(min_distance: u32, check_dual_index: bool) -> Self {
+        Self {
+            inner: IndexCollisionChecker::with_config(CollisionCheckConfig {
+                min_distance,
+                check_dual_index,
+            }),
+        }
+    }
+
+    #[getter]
+    fn min_distance(&self) -> u32 {
+        self.inner.config().min_distance
+    }
+
+    /// Every pair of `(name, index)` entries closer than the minimum
+    /// distance.
+    fn check(&self, indices: Vec<(String, PyDnaIndex)>) -> Vec<PyIndexCollision> {
+        self.inner
+            .check_indices(&unwrap_named(indices))
+            .into_iter()
+            .map(PyIndexCollision::from)
+            .collect()
+    }
+
+    /// The first collision adding `index` to `existing` would cause, or
+    /// None if it can be added.
+    fn can_add(
+        &self,
+        existing: Vec<(String, PyDnaIndex)>,
+        name: &str,
+        index: &PyDnaIndex,
+    ) -> Option<PyIndexCollision> {
+        self.inner
+            .can_add_index(&unwrap_named(existing), name, &index.inner)
+            .err()
+            .map(PyIndexCollision::from)
+    }
+
+    /// Distances between every pair of indices, as a list of rows.
+    fn distance_matrix(&self, indices: Vec<PyDnaIndex>) -> Vec<Vec<u32>> {
+        self.inner.distance_matrix(&unwrap(indices))
+    }
+
+    /// The smallest distance between any two indices, or None for fewer
+    /// than two.
+    fn smallest_distance(&self, indices: Vec<PyDnaIndex>) -> Option<u32> {
+        self.inner.min_distance(&unwrap(indices))
+    }
+}
+
+fn unwrap(indices: Vec<PyDnaIndex>) -> Vec<DnaIndex> {
+    indices.into_iter().map(|i| i.inner).collect()
+}
+
+fn unwrap_named(indices: Vec<(String, PyDnaIndex)>) -> Vec<(String, DnaIndex)> {
+    indices
+        .into_iter()
+        .map(|(name, index)| (name, index.inner))
+        .collect()
+}
+
+/// Parses an index family code, as the domain serializes it.
+fn parse_family(code: &str) -> PyResult<IndexFamily> {
+    match code {
+        "tru_seq" => Ok(IndexFamily::TruSeq),
+        "nextera" => Ok(IndexFamily::Nextera),
+        "idt_udi" => Ok(IndexFamily::IdtUdi),
+        "ten_x" => Ok(IndexFamily::TenX),
+        "custom" => Ok(IndexFamily::Custom),
+        _ => Err(PyValueError::new_err(format!(
+            "Invalid index family: {}",
+            code
+        ))),
+    }
+}
+
+fn family_code(family: IndexFamily) -> &'static str {
+    match family {
+        IndexFamily::TruSeq => "tru_seq",
+        IndexFamily::Nextera => "nextera",
+        IndexFamily::IdtUdi => "idt_udi",
+        IndexFamily::TenX => "ten_x",
+        IndexFamily::Custom => "custom",
+    }
+}
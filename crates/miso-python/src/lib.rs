@@ -0,0 +1,40 @@
+//! # MISO Python Bindings
+//!
+//! The domain's calculations for notebooks and pipeline scripts, as the
+//! `miso_lims` Python package: index collision checks, Illumina sample
+//! sheets, and library molarity and normalization. The results are the
+//! ones the server computes, since both run the same code.
+//!
+//! Build the package with maturin from this directory:
+//!
+//! ```text
+//! pip install maturin
+//! maturin develop --release
+//! ```
+
+use pyo3::prelude::*;
+
+mod index;
+mod normalization;
+mod sample_sheet;
+
+/// The `miso_lims` module.
+#[pymodule]
+fn miso_lims(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+
+    m.add_class::<index::PyDnaIndex>()?;
+    m.add_class::<index::PyIndexCollision>()?;
+    m.add_class::<index::PyIndexCollisionChecker>()?;
+
+    m.add_class::<sample_sheet::PySampleSheet>()?;
+    m.add_class::<sample_sheet::PySampleSheetRow>()?;
+    m.add_function(wrap_pyfunction!(sample_sheet::parse_sample_sheet, m)?)?;
+    m.add_function(wrap_pyfunction!(sample_sheet::render_sample_sheet, m)?)?;
+
+    m.add_class::<normalization::PyDilution>()?;
+    m.add_function(wrap_pyfunction!(normalization::molarity_nm, m)?)?;
+    m.add_function(wrap_pyfunction!(normalization::dilution, m)?)?;
+
+    Ok(())
+}
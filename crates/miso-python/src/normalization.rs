@@ -0,0 +1,96 @@
+//! Library molarity and normalization.
+
+use miso_domain::services::{Dilution, Normalizer};
+use miso_domain::value_objects::{Concentration, ConcentrationUnit, Volume};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Volumes to combine to normalize a library, in µL.
+#[pyclass(name = "Dilution", module = "miso_lims", frozen, get_all)]
+pub struct PyDilution {
+    /// Library to take
+    library_ul: f64,
+    /// Diluent to add
+    diluent_ul: f64,
+}
+
+#[pymethods]
+impl PyDilution {
+    fn __repr__(&self) -> String {
+        format!(
+            "Dilution(library_ul={:.2}, diluent_ul={:.2})",
+            self.library_ul, self.diluent_ul
+        )
+    }
+}
+
+impl From<Dilution> for PyDilution {
+    fn from(dilution: Dilution) -> Self {
+        Self {
+            library_ul: dilution.library.as_microliters(),
+            diluent_ul: dilution.diluent.as_microliters(),
+        }
+    }
+}
+
+/// A library's molarity in nM.
+///
+/// `unit` is "ng_per_ul", "ug_per_ml", "nanomolar" or "picomolar". Mass
+/// concentrations need the mean fragment size.
+#[pyfunction]
+#[pyo3(signature = (value, unit, fragment_size_bp=None))]
+pub fn molarity_nm(value: f64, unit: &str, fragment_size_bp: Option<u32>) -> PyResult<f64> {
+    let concentration = concentration(value, unit)?;
+
+    Normalizer::molarity_nm(concentration, fragment_size_bp)
+        .ok_or_else(|| PyValueError::new_err(format!("Can't convert {} to nM", concentration)))
+}
+
+/// How much library and diluent make `final_volume_ul` at `target_nm`.
+///
+/// Returns None if the library is already more dilute than the target.
+#[pyfunction]
+#[pyo3(signature = (value, unit, target_nm, final_volume_ul, fragment_size_bp=None))]
+pub fn dilution(
+    value: f64,
+    unit: &str,
+    target_nm: f64,
+    final_volume_ul: f64,
+    fragment_size_bp: Option<u32>,
+) -> PyResult<Option<PyDilution>> {
+    let stock = concentration(value, unit)?;
+    let target = concentration(target_nm, "nanomolar")?;
+    if final_volume_ul.is_nan() || final_volume_ul < 0.0 {
+        return Err(PyValueError::new_err("Volume must be non-negative"));
+    }
+    // Fail on an unconvertible stock rather than reporting no dilution
+    molarity_nm(value, unit, fragment_size_bp)?;
+
+    Ok(Normalizer::dilution(
+        stock,
+        fragment_size_bp,
+        target,
+        Volume::microliters(final_volume_ul),
+    )
+    .map(PyDilution::from))
+}
+
+fn concentration(value: f64, unit: &str) -> PyResult<Concentration> {
+    let unit = match unit {
+        "ng_per_ul" => ConcentrationUnit::NgPerUl,
+        "nanomolar" => ConcentrationUnit::Nanomolar,
+        "picomolar" => ConcentrationUnit::Picomolar,
+        "ug_per_ml" => ConcentrationUnit::UgPerMl,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Invalid concentration unit: {}",
+                unit
+            )))
+        }
+    };
+    if value.is_nan() || value < 0.0 {
+        return Err(PyValueError::new_err("Concentration must be non-negative"));
+    }
+
+    Ok(Concentration::new(value, unit))
+}
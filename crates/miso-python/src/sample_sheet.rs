@@ -0,0 +1,159 @@
+//! Illumina sample sheets.
+
+use std::collections::BTreeMap;
+
+use miso_application::exporters;
+use miso_application::importers::{self, SampleSheet, SampleSheetRow};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// One row of a sample sheet's `[Data]` section.
+#[pyclass(name = "SampleSheetRow", module = "miso_lims", get_all, set_all)]
+#[derive(Clone)]
+pub struct PySampleSheetRow {
+    sample_id: String,
+    sample_name: Option<String>,
+    /// i7 index sequence
+    index: Option<String>,
+    /// i5 index sequence
+    index2: Option<String>,
+    project: Option<String>,
+    lane: Option<u8>,
+    /// Description, e.g. the targeted panel a library captured
+    description: Option<String>,
+    /// Genome build the library is aligned to, e.g. "GRCh38"
+    reference_genome: Option<String>,
+}
+
+#[pymethods]
+impl PySampleSheetRow {
+    #[new]
+    #[pyo3(signature = (sample_id, index=None, index2=None, sample_name=None, project=None, lane=None, description=None, reference_genome=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        sample_id: String,
+        index: Option<String>,
+        index2: Option<String>,
+        sample_name: Option<String>,
+        project: Option<String>,
+        lane: Option<u8>,
+        description: Option<String>,
+        reference_genome: Option<String>,
+    ) -> Self {
+        Self {
+            sample_id,
+            sample_name,
+            index,
+            index2,
+            project,
+            lane,
+            description,
+            reference_genome,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SampleSheetRow({:?})", self.sample_id)
+    }
+}
+
+impl From<SampleSheetRow> for PySampleSheetRow {
+    fn from(row: SampleSheetRow) -> Self {
+        Self {
+            sample_id: row.sample_id,
+            sample_name: row.sample_name,
+            index: row.index,
+            index2: row.index2,
+            project: row.project,
+            lane: row.lane,
+            description: row.description,
+            reference_genome: row.reference_genome,
+        }
+    }
+}
+
+impl PySampleSheetRow {
+    fn to_row(&self, line: usize) -> SampleSheetRow {
+        SampleSheetRow {
+            line,
+            lane: self.lane,
+            sample_id: self.sample_id.clone(),
+            sample_name: self.sample_name.clone(),
+            index: self.index.clone(),
+            index2: self.index2.clone(),
+            project: self.project.clone(),
+            description: self.description.clone(),
+            reference_genome: self.reference_genome.clone(),
+        }
+    }
+}
+
+/// A parsed sample sheet.
+#[pyclass(name = "SampleSheet", module = "miso_lims", frozen, get_all)]
+pub struct PySampleSheet {
+    /// Key/value pairs of the `[Header]` section
+    header: BTreeMap<String, String>,
+    /// Read lengths of the `[Reads]` section
+    reads: Vec<u32>,
+    rows: Vec<PySampleSheetRow>,
+}
+
+#[pymethods]
+impl PySampleSheet {
+    /// The read structure as MISO stores it on runs, e.g. "2x151".
+    fn read_length(&self) -> Option<String> {
+        self.to_sheet().read_length()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SampleSheet(rows={})", self.rows.len())
+    }
+}
+
+impl PySampleSheet {
+    fn to_sheet(&self) -> SampleSheet {
+        SampleSheet {
+            header: self.header.clone(),
+            reads: self.reads.clone(),
+            rows: Vec::new(),
+        }
+    }
+}
+
+/// Parses the text of an Illumina (v1) sample sheet.
+#[pyfunction]
+pub fn parse_sample_sheet(text: &str) -> PyResult<PySampleSheet> {
+    let sheet =
+        importers::parse_sample_sheet(text).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    Ok(PySampleSheet {
+        header: sheet.header,
+        reads: sheet.reads,
+        rows: sheet.rows.into_iter().map(PySampleSheetRow::from).collect(),
+    })
+}
+
+/// Renders rows as an Illumina (v1) sample sheet.
+///
+/// `read_length` is a run's read structure, e.g. "2x151" or "151+8+151".
+#[pyfunction]
+#[pyo3(signature = (rows, header=None, read_length=None))]
+pub fn render_sample_sheet(
+    rows: Vec<PySampleSheetRow>,
+    header: Option<BTreeMap<String, String>>,
+    read_length: Option<&str>,
+) -> String {
+    let sheet = SampleSheet {
+        header: header.unwrap_or_default(),
+        reads: read_length
+            .map(exporters::sample_sheet_reads)
+            .unwrap_or_default(),
+        rows: rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row.to_row(i + 1))
+            .collect(),
+    };
+
+    exporters::render_sample_sheet(&sheet)
+}